@@ -0,0 +1,87 @@
+// WezTerm Multi-Process Development Framework - Dashboard Encoding Benchmark
+// Frame size and encode time of a full metrics update in each stream format
+//
+// Run with `cargo bench --bench dashboard_encoding`.
+
+use std::time::Instant;
+use wezterm_parallel::dashboard::{
+    DashboardMessage, Encoding, MetricsUpdate, StreamFormat, WebSocketMessage,
+};
+use wezterm_parallel::metrics::{FrameworkMetrics, ProcessMetrics, WorkspaceMetrics};
+
+const ITERATIONS: u32 = 200;
+
+const FORMATS: [StreamFormat; 4] = [
+    StreamFormat::JSON,
+    StreamFormat {
+        encoding: Encoding::MessagePack,
+        deflate: false,
+    },
+    StreamFormat {
+        encoding: Encoding::Json,
+        deflate: true,
+    },
+    StreamFormat {
+        encoding: Encoding::MessagePack,
+        deflate: true,
+    },
+];
+
+/// Full update of `workspaces` workspaces running `processes` processes each
+fn full_update(workspaces: usize, processes: usize) -> WebSocketMessage {
+    let mut framework = FrameworkMetrics::new();
+    let workspaces = (0..workspaces)
+        .map(|w| {
+            let name = format!("workspace-{w}");
+            let mut workspace = WorkspaceMetrics::new(name.clone());
+            workspace.update_from_processes(
+                (0..processes)
+                    .map(|p| {
+                        let mut process =
+                            ProcessMetrics::new(format!("{name}-claude-{p}"), name.clone());
+                        process.cpu_usage = 3.5 + (w * processes + p) as f64 * 0.7;
+                        process.memory_usage = 180_000_000 + (p as u64) * 12_345_678;
+                        process
+                    })
+                    .collect(),
+            );
+            workspace
+        })
+        .collect();
+    framework.update_from_workspaces(workspaces);
+    WebSocketMessage {
+        id: None,
+        seq: Some(1),
+        payload: DashboardMessage::MetricsUpdate(Box::new(MetricsUpdate::full(framework))),
+    }
+}
+
+fn bench(label: &str, message: &WebSocketMessage) {
+    println!("{label}");
+    println!(
+        "  {:<34} {:>9} {:>7} {:>11}",
+        "format", "bytes", "ratio", "encode"
+    );
+    let json_len = StreamFormat::JSON.encode(message).unwrap().len();
+    for format in FORMATS {
+        let len = format.encode(message).unwrap().len();
+        let started = Instant::now();
+        for _ in 0..ITERATIONS {
+            std::hint::black_box(format.encode(message).unwrap());
+        }
+        let per_message = started.elapsed() / ITERATIONS;
+        println!(
+            "  {:<34} {:>9} {:>6.1}% {:>9.1}µs",
+            format.to_string(),
+            len,
+            len as f64 * 100.0 / json_len as f64,
+            per_message.as_secs_f64() * 1e6
+        );
+    }
+    println!();
+}
+
+fn main() {
+    bench("4 workspaces x 4 processes", &full_update(4, 4));
+    bench("16 workspaces x 8 processes", &full_update(16, 8));
+}
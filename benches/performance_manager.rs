@@ -0,0 +1,84 @@
+// WezTerm Multi-Process Development Framework - Performance Manager Benchmark
+// Throughput of hot-path updates from concurrent clients, lock-free versus
+// behind the `std::sync::Mutex` the daemon used to share it with
+//
+// Run with `cargo bench --bench performance_manager`.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use wezterm_parallel::performance::{PerformanceConfig, PerformanceManager};
+
+const OPERATIONS_PER_CLIENT: usize = 20_000;
+const CLIENTS: [usize; 4] = [1, 4, 16, 64];
+
+/// What one IPC request does to the manager: a CPU sample and a cache lookup
+fn request(manager: &PerformanceManager, client: usize, i: usize) {
+    manager.update_cpu_usage((i % 100) as f64);
+    let key = format!("{client}-{}", i % 32);
+    if manager.get_cached(&key).is_none() {
+        manager.cache_data(key, vec![0; 64]);
+    }
+}
+
+async fn run<F>(clients: usize, op: F) -> Duration
+where
+    F: Fn(usize, usize) + Send + Sync + 'static,
+{
+    let op = Arc::new(op);
+    let started = Instant::now();
+    let handles: Vec<_> = (0..clients)
+        .map(|client| {
+            let op = Arc::clone(&op);
+            tokio::spawn(async move {
+                for i in 0..OPERATIONS_PER_CLIENT {
+                    op(client, i);
+                    if i % 256 == 0 {
+                        tokio::task::yield_now().await;
+                    }
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.await.unwrap();
+    }
+    started.elapsed()
+}
+
+fn per_second(clients: usize, elapsed: Duration) -> f64 {
+    (clients * OPERATIONS_PER_CLIENT) as f64 / elapsed.as_secs_f64()
+}
+
+fn main() {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    println!(
+        "  {:>7} {:>16} {:>16} {:>8}",
+        "clients", "lock-free ops/s", "mutex ops/s", "speedup"
+    );
+    for clients in CLIENTS {
+        let shared = Arc::new(PerformanceManager::new(PerformanceConfig::default()));
+        let lock_free =
+            runtime.block_on(run(clients, move |client, i| request(&shared, client, i)));
+
+        let locked = Arc::new(Mutex::new(PerformanceManager::new(
+            PerformanceConfig::default(),
+        )));
+        let mutex = runtime.block_on(run(clients, move |client, i| {
+            request(&locked.lock().unwrap(), client, i)
+        }));
+
+        let lock_free = per_second(clients, lock_free);
+        let mutex = per_second(clients, mutex);
+        println!(
+            "  {:>7} {:>16.0} {:>16.0} {:>7.1}x",
+            clients,
+            lock_free,
+            mutex,
+            lock_free / mutex
+        );
+    }
+}
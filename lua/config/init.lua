@@ -7,12 +7,13 @@ local ui_manager = require 'ui.manager'
 local dashboard = require 'ui.dashboard'
 local pane_manager = require 'ui.pane_manager'
 local keybindings = require 'config.keybindings'
+local socket_client = require 'utils.socket_client'
 
 local config = wezterm.config_builder()
 
 -- Framework configuration
 local framework_config = {
-  socket_path = "/tmp/wezterm-parallel.sock",
+  socket_path = socket_client.default_socket_path(),
   auto_start_backend = true,
   max_workspaces = 8,
   max_processes_per_workspace = 16,
@@ -8,7 +8,7 @@ local TemplateBridge = {}
 
 -- 設定
 local config = {
-  socket_path = "/tmp/wezterm-parallel.sock",
+  socket_path = socket_client.default_socket_path(),
   timeout = 5000,
   retry_attempts = 3,
   cache_enabled = true,
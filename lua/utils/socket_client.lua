@@ -13,9 +13,28 @@ local json = require 'utils.json'
 
 local socket_client = {}
 
+-- Default IPC socket of the platform, as chosen by the server
+-- (src/ipc/transport.rs): the XDG runtime directory on Linux,
+-- ~/Library/Application Support on macOS, /tmp otherwise
+function socket_client.default_socket_path()
+  local triple = wezterm.target_triple or ""
+  if triple:find("darwin") then
+    local home = os.getenv("HOME")
+    if home then
+      return home .. "/Library/Application Support/wezterm-parallel/wezterm-parallel.sock"
+    end
+  else
+    local runtime_dir = os.getenv("XDG_RUNTIME_DIR")
+    if runtime_dir and runtime_dir:sub(1, 1) == "/" then
+      return runtime_dir .. "/wezterm-parallel.sock"
+    end
+  end
+  return "/tmp/wezterm-parallel.sock"
+end
+
 -- Configuration
 local config = {
-  socket_path = "/tmp/wezterm-parallel.sock",
+  socket_path = socket_client.default_socket_path(),
   timeout = 5000, -- milliseconds
   retry_attempts = 3,
   retry_delay = 1000, -- milliseconds
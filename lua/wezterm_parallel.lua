@@ -0,0 +1,252 @@
+-- WezTerm Multi-Process Development Framework - Client Library
+-- Talks to the wezterm-parallel IPC socket and provides keybindings for
+-- workspace create/switch, task queueing, focus mode and a dashboard status pane.
+--
+-- Regenerate with `wezterm-parallel generate-lua-config`, then in wezterm.lua:
+--
+--   local wezterm_parallel = require 'wezterm_parallel'
+--   wezterm_parallel.apply_to_config(config)
+
+local wezterm = require 'wezterm'
+local act = wezterm.action
+
+local M = {}
+
+-- BEGIN GENERATED DEFAULTS
+M.defaults = {
+  socket_path = "/tmp/wezterm-parallel.sock",
+  dashboard_url = "http://127.0.0.1:9999",
+  mods = "CTRL|SHIFT",
+  default_template = "basic",
+  task_priority = 5,
+}
+-- END GENERATED DEFAULTS
+
+local options = {}
+for k, v in pairs(M.defaults) do
+  options[k] = v
+end
+
+-- The bundled defaults name the fallback socket; like the server, prefer
+-- the XDG runtime directory on Linux and ~/Library on macOS
+-- (`wezterm-parallel generate-lua-config` bakes in the platform's socket)
+if options.socket_path == '/tmp/wezterm-parallel.sock' then
+  local runtime_dir = os.getenv('XDG_RUNTIME_DIR')
+  local home = os.getenv('HOME')
+  if wezterm.target_triple:find('darwin') then
+    if home then
+      options.socket_path = home .. '/Library/Application Support/wezterm-parallel/wezterm-parallel.sock'
+    end
+  elseif runtime_dir and runtime_dir:sub(1, 1) == '/' then
+    options.socket_path = runtime_dir .. '/wezterm-parallel.sock'
+  end
+end
+
+-- Quote a string for use as a single POSIX shell word
+local function shell_quote(value)
+  return "'" .. tostring(value):gsub("'", "'\\''") .. "'"
+end
+
+-- Send one IPC message and return the decoded response
+--
+-- Messages use the framework's serde encoding: unit variants are plain
+-- strings, everything else is `{ Variant = { fields } }`.
+function M.request(variant, fields)
+  local message
+  if fields == nil then
+    message = variant
+  else
+    message = { [variant] = fields }
+  end
+
+  local payload = wezterm.json_encode(message)
+  local command = "printf '%s' " .. shell_quote(payload)
+    .. " | nc -N -U " .. shell_quote(options.socket_path)
+  local success, stdout, stderr = wezterm.run_child_process({ 'sh', '-c', command })
+
+  if not success or stdout == nil or stdout == '' then
+    wezterm.log_error('wezterm-parallel: ' .. variant .. ' failed: ' .. (stderr or 'no response'))
+    return nil
+  end
+
+  local ok, response = pcall(wezterm.json_parse, stdout)
+  if not ok then
+    wezterm.log_error('wezterm-parallel: invalid response to ' .. variant)
+    return nil
+  end
+  return response
+end
+
+-- Human readable text for a StatusUpdate response
+local function status_text(response)
+  if response and response.StatusUpdate then
+    return response.StatusUpdate.status
+  end
+  return 'No response from wezterm-parallel (is it running?)'
+end
+
+local function notify(window, text)
+  window:toast_notification('wezterm-parallel', text, nil, 4000)
+end
+
+-- Local directory of the pane, when WezTerm knows it
+local function pane_dir(pane)
+  local cwd = pane:get_current_working_dir()
+  if not cwd then
+    return nil
+  end
+  -- Newer WezTerm returns a Url object, older versions a file:// string
+  if type(cwd) ~= 'string' then
+    return cwd.file_path
+  end
+  return (cwd:gsub('^file://[^/]*', ''))
+end
+
+-- Create a framework workspace rooted at the pane's directory and switch to it
+function M.create_workspace(window, pane)
+  window:perform_action(act.PromptInputLine {
+    description = 'New workspace name',
+    action = wezterm.action_callback(function(inner_window, inner_pane, name)
+      if not name or name == '' then
+        return
+      end
+      local response = M.request('WorkspaceCreate', {
+        name = name,
+        template = options.default_template,
+        project_dir = pane_dir(inner_pane),
+      })
+      notify(inner_window, status_text(response))
+      inner_window:perform_action(act.SwitchToWorkspace { name = name }, inner_pane)
+    end),
+  }, pane)
+end
+
+-- Pick a framework workspace and switch to it
+function M.switch_workspace(window, pane)
+  local response = M.request('WorkspaceList')
+  if not (response and response.WorkspaceListResponse) then
+    notify(window, status_text(response))
+    return
+  end
+
+  local choices = {}
+  for _, workspace in ipairs(response.WorkspaceListResponse.workspaces) do
+    local marker = workspace.is_active and '* ' or '  '
+    table.insert(choices, {
+      id = workspace.name,
+      label = string.format('%s%s (%d processes, %d tasks)', marker, workspace.name,
+        workspace.process_count, workspace.task_count),
+    })
+  end
+
+  window:perform_action(act.InputSelector {
+    title = 'Switch workspace',
+    choices = choices,
+    action = wezterm.action_callback(function(inner_window, inner_pane, id)
+      if not id then
+        return
+      end
+      local switched = M.request('WorkspaceSwitch', { name = id })
+      notify(inner_window, status_text(switched))
+      inner_window:perform_action(act.SwitchToWorkspace { name = id }, inner_pane)
+    end),
+  }, pane)
+end
+
+-- Queue a shell command as a task
+function M.queue_task(window, pane)
+  window:perform_action(act.PromptInputLine {
+    description = 'Command to queue as a task',
+    action = wezterm.action_callback(function(inner_window, _, command)
+      if not command or command == '' then
+        return
+      end
+      local response = M.request('TaskQueue', {
+        id = string.format('lua-%d-%d', os.time(), math.random(1000, 9999)),
+        priority = options.task_priority,
+        command = command,
+      })
+      notify(inner_window, status_text(response))
+    end),
+  }, pane)
+end
+
+-- Jot a note on the tracked task, or the active workspace's scratchpad
+function M.quick_note(window, pane)
+  window:perform_action(act.PromptInputLine {
+    description = 'Note',
+    action = wezterm.action_callback(function(inner_window, _, text)
+      if not text or text == '' then
+        return
+      end
+      local response = M.request('NoteAdd', { text = text })
+      if response and response.NoteAddResponse then
+        local added = response.NoteAddResponse
+        notify(inner_window, added.task_id and ('Note added to task ' .. added.task_id)
+          or ('Note added to workspace ' .. added.workspace))
+      else
+        notify(inner_window, status_text(response))
+      end
+    end),
+  }, pane)
+end
+
+-- Toggle focus mode: silence non-critical alerts and defer low-priority tasks
+function M.toggle_focus(window, _)
+  local current = M.request('FocusStatus')
+  if not (current and current.FocusStatusResponse) then
+    notify(window, status_text(current))
+    return
+  end
+
+  local enable = not current.FocusStatusResponse.status.enabled
+  local response = M.request('FocusSet', { enabled = enable })
+  if response and response.FocusStatusResponse then
+    notify(window, enable and 'Focus mode on' or 'Focus mode off')
+  else
+    notify(window, status_text(response))
+  end
+end
+
+-- Open a pane showing the dashboard status endpoint, refreshed every 2 seconds
+function M.dashboard_popup(_, pane)
+  local url = options.dashboard_url .. '/status'
+  pane:split {
+    direction = 'Right',
+    size = 0.35,
+    args = {
+      'sh', '-c',
+      'while true; do clear; curl -s ' .. shell_quote(url) .. '; echo; sleep 2; done',
+    },
+  }
+end
+
+-- Add keybindings to a WezTerm config
+--
+-- `opts` overrides any of `M.defaults` for this config.
+function M.apply_to_config(config, opts)
+  for k, v in pairs(opts or {}) do
+    options[k] = v
+  end
+
+  config.keys = config.keys or {}
+  local bindings = {
+    { key = 'N', fn = M.create_workspace },
+    { key = 'W', fn = M.switch_workspace },
+    { key = 'Q', fn = M.queue_task },
+    { key = 'D', fn = M.dashboard_popup },
+    { key = 'F', fn = M.toggle_focus },
+    { key = 'J', fn = M.quick_note },
+  }
+  for _, binding in ipairs(bindings) do
+    table.insert(config.keys, {
+      key = binding.key,
+      mods = options.mods,
+      action = wezterm.action_callback(binding.fn),
+    })
+  end
+
+  return config
+end
+
+return M
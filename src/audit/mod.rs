@@ -0,0 +1,408 @@
+// WezTerm Multi-Process Development Framework - Audit Trail
+// Append-only journal of state-changing IPC requests and dashboard actions,
+// with the client that made them
+//
+// Entries are JSON Lines in `audit.jsonl`. Once the file reaches
+// `max_file_size_mb` it is renamed to `audit.jsonl.1` (older files shift to
+// `.2`, `.3`, ...) and a new file is started; files beyond `max_files` are
+// deleted.
+
+use crate::dashboard::DashboardAction;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Entries returned by a query unless it asks for fewer
+pub const DEFAULT_QUERY_LIMIT: usize = 100;
+
+/// Most entries a query returns
+pub const MAX_QUERY_LIMIT: usize = 1000;
+
+/// Longest string argument kept in an entry; longer ones are cut
+const MAX_DETAIL_CHARS: usize = 200;
+
+/// Audit journal settings
+///
+/// ```yaml
+/// audit:
+///   enabled: true
+///   path: /var/log/wezterm-parallel/audit.jsonl
+///   max_file_size_mb: 10
+///   max_files: 5
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditConfig {
+    pub enabled: bool,
+
+    /// Journal file; defaults to `audit.jsonl` in the config directory
+    pub path: Option<String>,
+
+    /// Size at which the journal is rotated
+    pub max_file_size_mb: u64,
+
+    /// Rotated files kept besides the current one
+    pub max_files: usize,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            path: None,
+            max_file_size_mb: 10,
+            max_files: 5,
+        }
+    }
+}
+
+/// Where an audited operation came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditSource {
+    Ipc,
+    Dashboard,
+}
+
+/// One audited operation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: u64,
+    pub source: AuditSource,
+
+    /// Name of the authenticated client (`local`, a token name, `anonymous`)
+    pub client: String,
+
+    /// User ID of the IPC socket peer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peer_uid: Option<u32>,
+
+    /// IPC connection number or dashboard client ID
+    pub connection: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+
+    /// Message or action name (e.g. `WorkspaceCreate`, `KillProcess`)
+    pub operation: String,
+
+    /// Arguments, with secrets redacted and long strings cut
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+    pub success: bool,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// Which entries an `AuditQuery` returns; all fields are optional
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditFilter {
+    pub client: Option<String>,
+
+    /// Operation name, case-insensitive
+    pub operation: Option<String>,
+    pub source: Option<AuditSource>,
+
+    /// Entries at or after this time (seconds since the epoch)
+    pub since: Option<u64>,
+
+    /// Entries before this time
+    pub until: Option<u64>,
+
+    /// Failed and rejected operations only
+    pub failed_only: bool,
+    pub limit: Option<usize>,
+}
+
+impl AuditFilter {
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        self.client.as_ref().map_or(true, |c| *c == entry.client)
+            && self
+                .operation
+                .as_ref()
+                .map_or(true, |op| op.eq_ignore_ascii_case(&entry.operation))
+            && self.source.map_or(true, |source| source == entry.source)
+            && self.since.map_or(true, |since| entry.timestamp >= since)
+            && self.until.map_or(true, |until| entry.timestamp < until)
+            && !(self.failed_only && entry.success)
+    }
+
+    fn limit(&self) -> usize {
+        self.limit
+            .unwrap_or(DEFAULT_QUERY_LIMIT)
+            .min(MAX_QUERY_LIMIT)
+    }
+}
+
+/// Append-only journal with size-based rotation
+#[derive(Debug)]
+pub struct AuditJournal {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    file: Mutex<Option<File>>,
+}
+
+impl AuditJournal {
+    pub fn open(path: impl AsRef<Path>, max_bytes: u64, max_files: usize) -> std::io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = open_append(&path)?;
+        Ok(Self {
+            path,
+            max_bytes: max_bytes.max(1),
+            max_files,
+            file: Mutex::new(Some(file)),
+        })
+    }
+
+    /// Journal described by the configuration, `None` when auditing is off
+    pub fn from_config(config: &AuditConfig) -> std::io::Result<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+        let path = config
+            .path
+            .as_ref()
+            .map(PathBuf::from)
+            .unwrap_or_else(Self::default_path);
+        Self::open(
+            path,
+            config.max_file_size_mb * 1024 * 1024,
+            config.max_files,
+        )
+        .map(Some)
+    }
+
+    /// Default journal location
+    pub fn default_path() -> PathBuf {
+        let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+        path.push("wezterm-parallel");
+        path.push("audit.jsonl");
+        path
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append an entry, rotating the journal once it is full
+    pub fn record(&self, entry: &AuditEntry) {
+        let mut line = match serde_json::to_vec(entry) {
+            Ok(line) => line,
+            Err(e) => {
+                tracing::warn!("Failed to encode audit entry {}: {}", entry.operation, e);
+                return;
+            }
+        };
+        line.push(b'\n');
+
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        if file.is_none() {
+            // Reopened after a failed rotation
+            *file = open_append(&self.path).ok();
+        }
+        let Some(current) = file.as_mut() else {
+            tracing::warn!("Audit journal {} is not writable", self.path.display());
+            return;
+        };
+        if let Err(e) = current.write_all(&line) {
+            tracing::warn!("Failed to write to {}: {}", self.path.display(), e);
+            return;
+        }
+        let full = current
+            .metadata()
+            .is_ok_and(|metadata| metadata.len() >= self.max_bytes);
+        if full {
+            *file = None;
+            if let Err(e) = self.rotate() {
+                tracing::warn!("Failed to rotate {}: {}", self.path.display(), e);
+            }
+            *file = open_append(&self.path).ok();
+        }
+    }
+
+    /// Entries matching the filter, newest first
+    pub fn query(&self, filter: &AuditFilter) -> Vec<AuditEntry> {
+        // Held so a rotation cannot move files while they are read
+        let _file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        let mut entries: Vec<AuditEntry> = (1..=self.max_files)
+            .rev()
+            .map(|index| self.rotated_path(index))
+            .chain(std::iter::once(self.path.clone()))
+            .filter_map(|path| std::fs::read_to_string(path).ok())
+            .flat_map(|content| {
+                content
+                    .lines()
+                    .filter_map(|line| serde_json::from_str::<AuditEntry>(line).ok())
+                    .collect::<Vec<_>>()
+            })
+            .filter(|entry| filter.matches(entry))
+            .collect();
+        entries.reverse();
+        entries.truncate(filter.limit());
+        entries
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut name = self.path.as_os_str().to_os_string();
+        name.push(format!(".{index}"));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&self) -> std::io::Result<()> {
+        if self.max_files == 0 {
+            return std::fs::remove_file(&self.path);
+        }
+        let _ = std::fs::remove_file(self.rotated_path(self.max_files));
+        for index in (1..self.max_files).rev() {
+            let from = self.rotated_path(index);
+            if from.exists() {
+                std::fs::rename(from, self.rotated_path(index + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, self.rotated_path(1))
+    }
+}
+
+fn open_append(path: &Path) -> std::io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)
+}
+
+/// Arguments of a message or action for an entry: the fields of the variant,
+/// with long strings cut; `None` for variants without fields
+pub fn details(value: &impl Serialize) -> Option<serde_json::Value> {
+    // `{"Variant": {..}}` for messages, `{"action": .., "params": {..}}` for actions
+    let fields = match serde_json::to_value(value).ok()? {
+        serde_json::Value::Object(mut map) if map.contains_key("action") => map.remove("params")?,
+        serde_json::Value::Object(map) if map.len() == 1 => map.into_iter().next()?.1,
+        value => value,
+    };
+    match fields {
+        serde_json::Value::Null | serde_json::Value::String(_) => None,
+        fields => Some(shorten(fields)),
+    }
+}
+
+fn shorten(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::String(text) if text.chars().count() > MAX_DETAIL_CHARS => {
+            let cut: String = text.chars().take(MAX_DETAIL_CHARS).collect();
+            serde_json::Value::String(format!("{cut}... ({} bytes)", text.len()))
+        }
+        serde_json::Value::Array(items) => items.into_iter().map(shorten).collect(),
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.into_iter()
+                .map(|(key, value)| (key, shorten(value)))
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+/// Whether a dashboard action changes state and is journaled
+pub fn is_audited_action(action: &DashboardAction) -> bool {
+    !matches!(
+        action,
+        DashboardAction::ListSyncConflicts
+            | DashboardAction::GetSyncDiff { .. }
+            | DashboardAction::QueryLogs { .. }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(timestamp: u64, client: &str, operation: &str, success: bool) -> AuditEntry {
+        AuditEntry {
+            timestamp,
+            source: AuditSource::Ipc,
+            client: client.to_string(),
+            peer_uid: None,
+            connection: "1".to_string(),
+            request_id: None,
+            operation: operation.to_string(),
+            details: None,
+            success,
+            error: None,
+            duration_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_query_filters_newest_first() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let journal = AuditJournal::open(dir.path().join("audit.jsonl"), 1 << 20, 2).unwrap();
+        journal.record(&entry(10, "local", "WorkspaceCreate", true));
+        journal.record(&entry(20, "ci", "ProcessKill", false));
+        journal.record(&entry(30, "local", "WorkspaceDelete", true));
+
+        let all = journal.query(&AuditFilter::default());
+        let times: Vec<u64> = all.iter().map(|e| e.timestamp).collect();
+        assert_eq!(times, [30, 20, 10]);
+
+        let local = journal.query(&AuditFilter {
+            client: Some("local".to_string()),
+            since: Some(15),
+            ..Default::default()
+        });
+        assert_eq!(local.len(), 1);
+        assert_eq!(local[0].operation, "WorkspaceDelete");
+
+        let failed = journal.query(&AuditFilter {
+            operation: Some("processkill".to_string()),
+            failed_only: true,
+            ..Default::default()
+        });
+        assert_eq!(failed.len(), 1);
+    }
+
+    #[test]
+    fn test_rotation_keeps_max_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        // Every entry fills a file
+        let journal = AuditJournal::open(&path, 1, 2).unwrap();
+        for timestamp in 1..=5 {
+            journal.record(&entry(timestamp, "local", "TaskQueue", true));
+        }
+
+        assert!(dir.path().join("audit.jsonl.1").exists());
+        assert!(dir.path().join("audit.jsonl.2").exists());
+        assert!(!dir.path().join("audit.jsonl.3").exists());
+        let times: Vec<u64> = journal
+            .query(&AuditFilter::default())
+            .iter()
+            .map(|e| e.timestamp)
+            .collect();
+        assert_eq!(times, [5, 4]);
+    }
+
+    #[test]
+    fn test_details_cut_long_strings() {
+        let action = DashboardAction::CreateWorkspace {
+            name: "x".repeat(300),
+            template: "basic".to_string(),
+        };
+        let fields = details(&action).unwrap();
+        assert_eq!(fields["template"], "basic");
+        assert!(fields["name"].as_str().unwrap().ends_with("(300 bytes)"));
+        assert!(details(&DashboardAction::TriggerGC).is_none());
+        assert!(!is_audited_action(&DashboardAction::ListSyncConflicts));
+    }
+}
@@ -0,0 +1,270 @@
+// WezTerm Multi-Process Development Framework - Confirmation Tokens
+// Two-step confirmation of destructive operations: the first request returns a
+// single-use token with an impact summary, the client re-sends it to proceed
+
+use super::{ClientIdentity, Scope};
+use crate::dashboard::DashboardAction;
+use crate::process::ProcessManager;
+use crate::room::WorkspaceManager;
+use crate::Message;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Default lifetime of a confirmation token
+pub const DEFAULT_CONFIRM_TTL_SECS: u64 = 60;
+
+/// Which clients have to confirm destructive operations
+///
+/// ```yaml
+/// auth:
+///   confirm:
+///     scopes: [control, admin]
+///     ttl_secs: 30
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfirmConfig {
+    /// Scopes whose clients must confirm; empty disables the two-step flow
+    pub scopes: Vec<Scope>,
+
+    /// Seconds a confirmation token stays valid
+    pub ttl_secs: u64,
+}
+
+impl Default for ConfirmConfig {
+    fn default() -> Self {
+        Self {
+            scopes: Vec::new(),
+            ttl_secs: DEFAULT_CONFIRM_TTL_SECS,
+        }
+    }
+}
+
+/// Returned instead of executing an operation that needs confirmation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfirmationChallenge {
+    /// Token to re-send with the identical request
+    pub token: String,
+
+    /// Variant name of the operation (e.g. `WorkspaceDelete`)
+    pub operation: String,
+
+    /// What the operation would destroy
+    pub impact: String,
+
+    /// Unix time after which the token is no longer accepted
+    pub expires_at: u64,
+}
+
+#[derive(Debug)]
+struct PendingConfirmation {
+    client: String,
+    fingerprint: String,
+    expires_at: Instant,
+}
+
+/// Issues and redeems confirmation tokens
+///
+/// A token is bound to the client it was issued to and to the exact request
+/// (operation and arguments), and is consumed by its first use. Clones share
+/// the issued tokens.
+#[derive(Debug, Clone, Default)]
+pub struct ConfirmationGuard {
+    config: ConfirmConfig,
+    pending: Arc<Mutex<HashMap<String, PendingConfirmation>>>,
+}
+
+impl ConfirmationGuard {
+    pub fn new(config: ConfirmConfig) -> Self {
+        Self {
+            config,
+            pending: Arc::default(),
+        }
+    }
+
+    /// Whether a client has to confirm destructive operations
+    pub fn required(&self, identity: &ClientIdentity) -> bool {
+        self.config.scopes.contains(&identity.scope)
+    }
+
+    /// Whether a request may go ahead: the client needs no confirmation, or
+    /// `token` was issued to it for this very request and has not expired
+    pub fn confirmed(
+        &self,
+        identity: &ClientIdentity,
+        fingerprint: &str,
+        token: Option<&str>,
+    ) -> bool {
+        if !self.required(identity) {
+            return true;
+        }
+        let Some(token) = token else {
+            return false;
+        };
+        let mut pending = self.lock();
+        match pending.remove(token) {
+            Some(entry) => {
+                entry.client == identity.name
+                    && entry.fingerprint == fingerprint
+                    && entry.expires_at > Instant::now()
+            }
+            None => false,
+        }
+    }
+
+    /// Issue a token for a request that has to be confirmed
+    pub fn issue(
+        &self,
+        identity: &ClientIdentity,
+        operation: &str,
+        fingerprint: String,
+        impact: String,
+    ) -> ConfirmationChallenge {
+        let ttl = Duration::from_secs(self.config.ttl_secs.max(1));
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        let now = Instant::now();
+
+        let mut pending = self.lock();
+        pending.retain(|_, entry| entry.expires_at > now);
+        pending.insert(
+            token.clone(),
+            PendingConfirmation {
+                client: identity.name.clone(),
+                fingerprint,
+                expires_at: now + ttl,
+            },
+        );
+
+        let expires_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+            + ttl.as_secs();
+        ConfirmationChallenge {
+            token,
+            operation: operation.to_string(),
+            impact,
+            expires_at,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, PendingConfirmation>> {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Whether an IPC message destroys state and so may need confirmation
+pub fn is_destructive_message(message: &Message) -> bool {
+    matches!(message, Message::WorkspaceDelete { .. })
+}
+
+/// Whether a dashboard action destroys state and so may need confirmation
+pub fn is_destructive_action(action: &DashboardAction) -> bool {
+    matches!(
+        action,
+        DashboardAction::KillProcess { .. }
+            | DashboardAction::DeleteWorkspace { .. }
+            | DashboardAction::ResetMetrics { .. }
+    )
+}
+
+/// Identifies a request by its operation and arguments
+pub fn fingerprint(operation: &impl Serialize) -> String {
+    serde_json::to_string(operation).unwrap_or_default()
+}
+
+/// What deleting a workspace would stop and discard
+pub async fn workspace_delete_impact(workspace_manager: &WorkspaceManager, name: &str) -> String {
+    let Some(workspace) = workspace_manager.get_workspace_info(name).await else {
+        return format!("Workspace '{name}' does not exist");
+    };
+    let processes = workspace_manager.attached_processes(name).await;
+    let mut impact = format!(
+        "Deletes workspace '{name}', stopping {} process(es)",
+        processes.len()
+    );
+    if !processes.is_empty() {
+        impact.push_str(&format!(" ({})", processes.join(", ")));
+    }
+    impact.push_str(&format!(
+        " and discarding {} active task(s) and {} note(s)",
+        workspace.active_tasks.len(),
+        workspace.notes.len()
+    ));
+    impact
+}
+
+/// What killing a process would stop
+pub async fn process_kill_impact(process_manager: &ProcessManager, process_id: &str) -> String {
+    match process_manager.get_process_info(process_id).await {
+        Some(process) => format!(
+            "Kills process '{process_id}' ({}{}) in workspace '{}'",
+            process.command,
+            process
+                .pid
+                .map(|pid| format!(", pid {pid}"))
+                .unwrap_or_default(),
+            process.workspace
+        ),
+        None => format!("Process '{process_id}' is not running"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guard() -> ConfirmationGuard {
+        let config: ConfirmConfig = serde_yaml::from_str("scopes: [control]").unwrap();
+        assert_eq!(config.ttl_secs, DEFAULT_CONFIRM_TTL_SECS);
+        ConfirmationGuard::new(config)
+    }
+
+    #[test]
+    fn test_token_is_single_use_and_bound_to_request() {
+        let guard = guard();
+        let ci = ClientIdentity::new("ci", Scope::Control);
+        let delete = fingerprint(&Message::WorkspaceDelete {
+            name: "docs".to_string(),
+        });
+        assert!(!guard.confirmed(&ci, &delete, None));
+
+        let challenge = guard.issue(&ci, "WorkspaceDelete", delete.clone(), "impact".to_string());
+        assert_eq!(challenge.operation, "WorkspaceDelete");
+        assert!(guard.confirmed(&ci, &delete, Some(&challenge.token)));
+        assert!(!guard.confirmed(&ci, &delete, Some(&challenge.token)));
+
+        // Another target or another client cannot use the token
+        let challenge = guard.issue(&ci, "WorkspaceDelete", delete.clone(), "impact".to_string());
+        let other = fingerprint(&Message::WorkspaceDelete {
+            name: "main".to_string(),
+        });
+        assert!(!guard.confirmed(&ci, &other, Some(&challenge.token)));
+        let challenge = guard.issue(&ci, "WorkspaceDelete", delete.clone(), "impact".to_string());
+        let bot = ClientIdentity::new("bot", Scope::Control);
+        assert!(!guard.confirmed(&bot, &delete, Some(&challenge.token)));
+    }
+
+    #[test]
+    fn test_confirmation_is_per_scope() {
+        let guard = guard();
+        let admin = ClientIdentity::anonymous();
+        assert!(guard.confirmed(&admin, "anything", None));
+        assert!(ConfirmationGuard::default().confirmed(
+            &ClientIdentity::new("ci", Scope::Control),
+            "anything",
+            None
+        ));
+
+        assert!(is_destructive_message(&Message::WorkspaceDelete {
+            name: "docs".to_string()
+        }));
+        assert!(!is_destructive_message(&Message::WorkspaceList));
+        assert!(is_destructive_action(&DashboardAction::ResetMetrics {
+            metric_type: None
+        }));
+        assert!(!is_destructive_action(&DashboardAction::TriggerGC));
+    }
+}
@@ -0,0 +1,512 @@
+// WezTerm Multi-Process Development Framework - Authentication
+// API tokens, local peer identification and capability scopes for IPC and dashboard clients
+
+pub mod confirm;
+
+pub use confirm::{ConfirmConfig, ConfirmationChallenge, ConfirmationGuard};
+
+use crate::dashboard::DashboardAction;
+use crate::ipc::{ErrorCode, IpcError};
+use crate::Message;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Default permission bits of the IPC socket: owner read/write only
+pub const DEFAULT_SOCKET_MODE: u32 = 0o600;
+
+/// Environment variable the CLI reads its API token from
+pub const TOKEN_ENV_VAR: &str = "WEZTERM_PARALLEL_TOKEN";
+
+/// What a client is allowed to do, ordered from least to most privileged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scope {
+    /// Queries and subscriptions only
+    Read,
+
+    /// Workspace, process, task and template changes (e.g. `KillProcess`)
+    Control,
+
+    /// Maintenance mode, upgrades and metric exports
+    Admin,
+}
+
+impl Scope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::Read => "read",
+            Scope::Control => "control",
+            Scope::Admin => "admin",
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// API token issued to a client
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClientToken {
+    /// Client name shown in logs
+    pub name: String,
+    pub token: String,
+    pub scope: Scope,
+}
+
+/// Authentication configuration
+///
+/// ```yaml
+/// auth:
+///   enabled: true
+///   socket_mode: 0o660
+///   local_scope: admin
+///   tokens:
+///     - name: statusbar
+///       token: "3f6c..."
+///       scope: read
+///     - name: ci
+///       token: "9a1e..."
+///       scope: control
+///   confirm:
+///     scopes: [control]
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuthConfig {
+    /// Require credentials; while false every client has full access
+    pub enabled: bool,
+
+    /// Tokens accepted on the IPC socket and the dashboard port
+    pub tokens: Vec<ClientToken>,
+
+    /// Scope of socket clients running as the framework's own user, without a
+    /// token (`null` requires a token from them too)
+    pub local_scope: Option<Scope>,
+
+    /// Permission bits applied to the IPC socket file
+    pub socket_mode: u32,
+
+    /// Roles that must confirm destructive operations with a token
+    pub confirm: ConfirmConfig,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            tokens: Vec::new(),
+            local_scope: Some(Scope::Admin),
+            socket_mode: DEFAULT_SOCKET_MODE,
+            confirm: ConfirmConfig::default(),
+        }
+    }
+}
+
+/// An authenticated client
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientIdentity {
+    pub name: String,
+    pub scope: Scope,
+}
+
+impl ClientIdentity {
+    pub fn new(name: impl Into<String>, scope: Scope) -> Self {
+        Self {
+            name: name.into(),
+            scope,
+        }
+    }
+
+    /// Identity used for every client while authentication is disabled
+    pub fn anonymous() -> Self {
+        Self::new("anonymous", Scope::Admin)
+    }
+
+    pub fn allows(&self, required: Scope) -> bool {
+        self.scope >= required
+    }
+}
+
+/// Checks client credentials and scopes
+#[derive(Debug, Clone, Default)]
+pub struct Authenticator {
+    config: AuthConfig,
+    owner_uid: Option<u32>,
+    confirmations: ConfirmationGuard,
+}
+
+impl Authenticator {
+    pub fn new(config: AuthConfig) -> Self {
+        Self {
+            confirmations: ConfirmationGuard::new(config.confirm.clone()),
+            config,
+            owner_uid: None,
+        }
+    }
+
+    /// Build from the dashboard's own settings, where `auth_token` grants full access
+    pub fn from_dashboard(config: &crate::dashboard::DashboardConfig) -> Self {
+        Self::new(AuthConfig {
+            enabled: config.auth_enabled,
+            tokens: config
+                .auth_token
+                .iter()
+                .map(|token| ClientToken {
+                    name: "dashboard".to_string(),
+                    token: token.clone(),
+                    scope: Scope::Admin,
+                })
+                .collect(),
+            local_scope: None,
+            ..Default::default()
+        })
+    }
+
+    /// Set the user ID the framework runs as, so local socket peers can be recognised
+    pub fn with_owner_uid(mut self, uid: u32) -> Self {
+        self.owner_uid = Some(uid);
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    pub fn socket_mode(&self) -> u32 {
+        self.config.socket_mode
+    }
+
+    /// Confirmation tokens of destructive operations, shared by clones
+    pub fn confirmations(&self) -> &ConfirmationGuard {
+        &self.confirmations
+    }
+
+    /// Identity for a token, if it is one of the configured tokens
+    pub fn authenticate_token(&self, token: &str) -> Option<ClientIdentity> {
+        self.config
+            .tokens
+            .iter()
+            .find(|client| constant_time_eq(client.token.as_bytes(), token.as_bytes()))
+            .map(|client| ClientIdentity::new(client.name.clone(), client.scope))
+    }
+
+    /// Identity granted to a socket peer by its user ID alone
+    pub fn authenticate_peer(&self, uid: Option<u32>) -> Option<ClientIdentity> {
+        if !self.config.enabled {
+            return Some(ClientIdentity::anonymous());
+        }
+        match (uid, self.owner_uid, self.config.local_scope) {
+            (Some(uid), Some(owner), Some(scope)) if uid == owner => {
+                Some(ClientIdentity::new(format!("uid:{uid}"), scope))
+            }
+            _ => None,
+        }
+    }
+
+    /// Resolve the identity of a request from the peer identity and an optional token
+    ///
+    /// A token, when given, must be valid even if the peer is already known.
+    pub fn identify(
+        &self,
+        peer: Option<&ClientIdentity>,
+        token: Option<&str>,
+    ) -> Result<ClientIdentity, IpcError> {
+        if !self.config.enabled {
+            return Ok(ClientIdentity::anonymous());
+        }
+        match (token, peer) {
+            (Some(token), _) => self
+                .authenticate_token(token)
+                .ok_or_else(|| IpcError::new(ErrorCode::Unauthorized, "auth", "Invalid API token")),
+            (None, Some(peer)) => Ok(peer.clone()),
+            (None, None) => Err(IpcError::new(
+                ErrorCode::Unauthorized,
+                "auth",
+                "Authentication required: provide an API token",
+            )),
+        }
+    }
+
+    /// Check that an identity may perform an operation needing `required`
+    pub fn authorize(
+        &self,
+        identity: &ClientIdentity,
+        required: Scope,
+        operation: &str,
+    ) -> Result<(), IpcError> {
+        if identity.allows(required) {
+            return Ok(());
+        }
+        Err(IpcError::new(
+            ErrorCode::Forbidden,
+            "auth",
+            format!(
+                "Client '{}' has {} access, {} requires {}",
+                identity.name, identity.scope, operation, required
+            ),
+        ))
+    }
+}
+
+/// Scope needed to send an IPC message
+///
+/// Every variant is listed, so a new message does not build until it is
+/// given a scope.
+pub fn message_scope(message: &Message) -> Scope {
+    match message {
+        Message::MaintenanceSet { .. }
+        | Message::LogLevelSet { .. }
+        | Message::Upgrade { .. }
+        | Message::Takeover { .. }
+        | Message::AuditQuery { .. }
+        | Message::PeerRegister { .. }
+        | Message::PeerRemove { .. } => Scope::Admin,
+        // Writes a file wherever the daemon may
+        Message::AnalyticsExport { path: Some(_), .. } => Scope::Admin,
+        // Carries the tasks' environment variables, secrets included
+        Message::SessionSnapshot => Scope::Admin,
+        // The local client needs what the peer would demand of it
+        Message::PeerForward { message, .. } => message_scope(message),
+        Message::FocusSet { .. } => Scope::Control,
+        Message::WorkspaceCreate { .. }
+        | Message::WorkspaceDelete { .. }
+        | Message::WorkspaceRename { .. }
+        | Message::WorkspaceSwitch { .. }
+        | Message::WorkspaceDrain { .. }
+        | Message::WorkspaceUndrain { .. }
+        | Message::ProcessSpawn { .. }
+        | Message::ProcessEnvSet { .. }
+        | Message::ProcessCircuitReset { .. }
+        | Message::ProcessInput { .. }
+        | Message::ProcessStdinWrite { .. }
+        | Message::ProcessResize { .. }
+        | Message::TaskQueue { .. }
+        | Message::TaskQueueBatch { .. }
+        | Message::TaskCancel { .. }
+        | Message::TaskPause { .. }
+        | Message::TaskResume { .. }
+        | Message::DeadLetterRequeue { .. }
+        | Message::DeadLetterPurge { .. }
+        | Message::GithubSync
+        | Message::SessionTag { .. }
+        | Message::SessionUntag { .. }
+        | Message::SessionAnnotate { .. }
+        | Message::HistoryImport { .. }
+        | Message::BoardImport { .. }
+        | Message::SessionRestore { .. }
+        | Message::NoteAdd { .. }
+        | Message::TemplateCreate { .. }
+        | Message::TemplateDelete { .. }
+        | Message::SyncResolve { .. }
+        | Message::SyncWatchSet { .. } => Scope::Control,
+        Message::WorkspaceList
+        | Message::WorkspaceDrainStatus
+        | Message::GitStatus { .. }
+        | Message::ProcessList { .. }
+        | Message::ProcessEnv { .. }
+        | Message::ProcessLogsGet { .. }
+        | Message::ProcessLogTail { .. }
+        | Message::ProcessQuarantineList
+        | Message::SystemStatus
+        | Message::DeadLetterList
+        | Message::TaskTrace { .. }
+        | Message::TaskLatencyReport { .. }
+        | Message::TaskResultGet { .. }
+        | Message::SessionExport { .. }
+        | Message::ProductivityExport { .. }
+        | Message::BoardExport { .. }
+        | Message::TemplateList
+        | Message::TemplateGet { .. }
+        | Message::MaintenanceStatus
+        | Message::LogLevelList
+        | Message::LogQuery { .. }
+        | Message::SheddingReport { .. }
+        | Message::AnalyticsExport { path: None, .. }
+        | Message::PeerList
+        | Message::FocusStatus
+        | Message::SyncConflictList
+        | Message::SyncDiff { .. }
+        | Message::SyncWatchList
+        | Message::TelemetryPreview
+        | Message::Ping => Scope::Read,
+        // Replies; a client sending one gets an error from the pipeline
+        Message::WorkspaceListResponse { .. }
+        | Message::WorkspaceDrainResponse { .. }
+        | Message::GitStatusResponse { .. }
+        | Message::ProcessListResponse { .. }
+        | Message::ProcessEnvResponse { .. }
+        | Message::ProcessLogsResponse { .. }
+        | Message::ProcessLogTailResponse { .. }
+        | Message::ProcessQuarantineResponse { .. }
+        | Message::ProcessInputResponse { .. }
+        | Message::ProcessResizeResponse { .. }
+        | Message::SystemStatusResponse { .. }
+        | Message::StatusUpdate { .. }
+        | Message::TaskQueueBatchResponse { .. }
+        | Message::DeadLetterListResponse { .. }
+        | Message::TaskTraceResponse { .. }
+        | Message::TaskLatencyReportResponse { .. }
+        | Message::TaskResultResponse { .. }
+        | Message::GithubSyncResponse { .. }
+        | Message::SessionExportResponse { .. }
+        | Message::ProductivityExportResponse { .. }
+        | Message::HistoryImportResponse { .. }
+        | Message::BoardExportResponse { .. }
+        | Message::BoardImportResponse { .. }
+        | Message::SessionSnapshotResponse { .. }
+        | Message::SessionRestoreResponse { .. }
+        | Message::NoteAddResponse { .. }
+        | Message::TemplateListResponse { .. }
+        | Message::TemplateGetResponse { .. }
+        | Message::TemplateCreateResponse { .. }
+        | Message::TemplateDeleteResponse { .. }
+        | Message::MaintenanceStatusResponse { .. }
+        | Message::LogLevelResponse { .. }
+        | Message::LogQueryResponse { .. }
+        | Message::AuditQueryResponse { .. }
+        | Message::SheddingReportResponse { .. }
+        | Message::AnalyticsExportResponse { .. }
+        | Message::PeerListResponse { .. }
+        | Message::FocusStatusResponse { .. }
+        | Message::SyncConflictListResponse { .. }
+        | Message::SyncResolveResponse { .. }
+        | Message::SyncDiffResponse { .. }
+        | Message::SyncWatchListResponse { .. }
+        | Message::TelemetryPreviewResponse { .. }
+        | Message::UpgradeResponse { .. }
+        | Message::TakeoverResponse { .. }
+        | Message::Pong => Scope::Read,
+    }
+}
+
+/// Scope needed to execute a dashboard action
+pub fn action_scope(action: &DashboardAction) -> Scope {
+    match action {
+        DashboardAction::ResetMetrics { .. }
+        | DashboardAction::TriggerGC
+        | DashboardAction::ExportMetrics { .. }
+        | DashboardAction::SetLogLevel { .. } => Scope::Admin,
+        _ => Scope::Control,
+    }
+}
+
+/// Variant name of a message or action (e.g. `KillProcess`) for errors and logs
+pub fn operation_name(operation: &impl fmt::Debug) -> String {
+    format!("{operation:?}")
+        .split(|c: char| !c.is_alphanumeric())
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Token from an `Authorization: Bearer <token>` header value
+pub fn bearer_token(header: &str) -> Option<&str> {
+    let (scheme, token) = header.trim().split_once(' ')?;
+    if scheme.eq_ignore_ascii_case("bearer") && !token.trim().is_empty() {
+        Some(token.trim())
+    } else {
+        None
+    }
+}
+
+/// Compare secrets without returning early on the first differing byte
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn authenticator() -> Authenticator {
+        let config: AuthConfig = serde_yaml::from_str(
+            "enabled: true\nsocket_mode: 0o660\ntokens:\n  - name: statusbar\n    token: read-secret\n    scope: read\n  - name: ci\n    token: control-secret\n    scope: control\n",
+        )
+        .unwrap();
+        assert_eq!(config.socket_mode, 0o660);
+        assert_eq!(config.local_scope, Some(Scope::Admin));
+        Authenticator::new(config).with_owner_uid(1000)
+    }
+
+    #[test]
+    fn test_identify_by_token_and_peer() {
+        let auth = authenticator();
+        assert_eq!(
+            auth.identify(None, Some("control-secret")).unwrap(),
+            ClientIdentity::new("ci", Scope::Control)
+        );
+        assert_eq!(
+            auth.identify(None, Some("control-secre")).unwrap_err().code,
+            ErrorCode::Unauthorized
+        );
+        assert_eq!(
+            auth.identify(None, None).unwrap_err().code,
+            ErrorCode::Unauthorized
+        );
+
+        // Same user as the framework: local scope, other users need a token
+        let owner = auth.authenticate_peer(Some(1000)).unwrap();
+        assert_eq!(owner.scope, Scope::Admin);
+        assert_eq!(auth.identify(Some(&owner), None).unwrap(), owner);
+        assert_eq!(auth.authenticate_peer(Some(1001)), None);
+
+        let disabled = Authenticator::default();
+        assert_eq!(
+            disabled.identify(None, None).unwrap(),
+            ClientIdentity::anonymous()
+        );
+    }
+
+    #[test]
+    fn test_scopes() {
+        let auth = authenticator();
+        let reader = auth.authenticate_token("read-secret").unwrap();
+        assert!(auth
+            .authorize(&reader, message_scope(&Message::SystemStatus), "status")
+            .is_ok());
+
+        let kill = DashboardAction::KillProcess {
+            process_id: "p1".to_string(),
+        };
+        let error = auth
+            .authorize(&reader, action_scope(&kill), "KillProcess")
+            .unwrap_err();
+        assert_eq!(error.code, ErrorCode::Forbidden);
+        // Typing into an agent is as good as controlling it
+        let stdin = Message::ProcessStdinWrite {
+            process_id: "p1".to_string(),
+            data: "y\n".to_string(),
+            close: false,
+        };
+        assert!(!reader.allows(message_scope(&stdin)));
+
+        let ci = auth.authenticate_token("control-secret").unwrap();
+        assert!(ci.allows(action_scope(&kill)));
+        assert!(ci.allows(message_scope(&stdin)));
+        assert!(!ci.allows(message_scope(&Message::Upgrade {
+            binary: "/tmp/new".to_string(),
+            sha256: None,
+        })));
+        assert!(!ci.allows(message_scope(&Message::Takeover { pid: 1 })));
+        // Exporting to a file on the daemon's machine needs admin
+        let export = |path: Option<&str>| Message::AnalyticsExport {
+            format: "html".to_string(),
+            path: path.map(str::to_string),
+            range: None,
+        };
+        assert!(reader.allows(message_scope(&export(None))));
+        assert!(!ci.allows(message_scope(&export(Some("/tmp/report.html")))));
+        assert!(!ci.allows(message_scope(&Message::SessionSnapshot)));
+        assert!(!ci.allows(action_scope(&DashboardAction::SetLogLevel {
+            module: "sync".to_string(),
+            level: "debug".to_string(),
+        })));
+
+        assert_eq!(operation_name(&kill), "KillProcess");
+        assert_eq!(bearer_token("Bearer abc"), Some("abc"));
+        assert_eq!(bearer_token("Basic abc"), None);
+    }
+}
@@ -0,0 +1,520 @@
+// WezTerm Multi-Process Development Framework - Event Bridge
+// Posts selected workspace events to external automation webhooks (n8n, Zapier, ...)
+
+use crate::dashboard::{DashboardMessage, StatusChange};
+use crate::hooks::HookRunner;
+use crate::logging::LogContext;
+use crate::{log_debug, log_warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{broadcast, RwLock};
+use uuid::Uuid;
+
+/// Workspace key whose webhooks receive the events of every workspace
+pub const ALL_WORKSPACES: &str = "*";
+
+/// Deliveries kept for the dashboard
+pub const MAX_DELIVERIES: usize = 200;
+
+/// Delay before the first retry; doubles with each attempt
+pub const RETRY_BACKOFF: Duration = Duration::from_secs(2);
+
+/// Time allowed for one delivery attempt
+pub const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Outbound webhooks by workspace
+///
+/// ```yaml
+/// bridge:
+///   workspaces:
+///     frontend:
+///       - url: https://n8n.example.com/webhook/frontend
+///         events: [task_completed]
+///         payload: '{"text": "{{data.title}} finished in {{workspace}}"}'
+///     "*":
+///       - url: https://hooks.zapier.com/hooks/catch/123/abc
+///         events: [workspace_created, agent_failed]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BridgeConfig {
+    /// Webhooks by workspace name; `"*"` receives every workspace's events
+    pub workspaces: BTreeMap<String, Vec<WebhookConfig>>,
+}
+
+/// One outbound webhook
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+
+    /// Events posted to this webhook; all when empty
+    #[serde(default)]
+    pub events: Vec<BridgeEventKind>,
+
+    /// JSON body with `{{event}}`, `{{workspace}}`, `{{timestamp}}` and
+    /// `{{data.<field>}}` placeholders; the event itself when unset
+    #[serde(default)]
+    pub payload: Option<String>,
+
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+
+    /// Attempts before a delivery is given up
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+}
+
+fn default_max_attempts() -> u32 {
+    5
+}
+
+impl WebhookConfig {
+    pub fn accepts(&self, kind: BridgeEventKind) -> bool {
+        self.events.is_empty() || self.events.contains(&kind)
+    }
+}
+
+/// Events that can be bridged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BridgeEventKind {
+    WorkspaceCreated,
+    AgentFailed,
+    TaskCompleted,
+}
+
+impl BridgeEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::WorkspaceCreated => "workspace_created",
+            Self::AgentFailed => "agent_failed",
+            Self::TaskCompleted => "task_completed",
+        }
+    }
+}
+
+/// A workspace event as posted when no payload template is configured
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BridgeEvent {
+    pub event: BridgeEventKind,
+    pub workspace: String,
+    pub timestamp: u64,
+    pub data: serde_json::Map<String, serde_json::Value>,
+}
+
+impl BridgeEvent {
+    pub fn new(event: BridgeEventKind, workspace: &str) -> Self {
+        Self {
+            event,
+            workspace: workspace.to_string(),
+            timestamp: now_secs(),
+            data: serde_json::Map::new(),
+        }
+    }
+
+    pub fn with(mut self, key: &str, value: impl Serialize) -> Self {
+        self.data.insert(
+            key.to_string(),
+            serde_json::to_value(value).unwrap_or_default(),
+        );
+        self
+    }
+
+    pub fn workspace_created(workspace: &str, template: &str) -> Self {
+        Self::new(BridgeEventKind::WorkspaceCreated, workspace).with("template", template)
+    }
+
+    pub fn agent_failed(
+        workspace: &str,
+        process_id: &str,
+        exit_code: Option<i32>,
+        error: &str,
+    ) -> Self {
+        Self::new(BridgeEventKind::AgentFailed, workspace)
+            .with("process_id", process_id)
+            .with("exit_code", exit_code)
+            .with("error", error)
+    }
+
+    pub fn task_completed(workspace: &str, task_id: &str, title: &str) -> Self {
+        Self::new(BridgeEventKind::TaskCompleted, workspace)
+            .with("task_id", task_id)
+            .with("title", title)
+    }
+
+    /// Value of a template placeholder
+    pub(crate) fn field(&self, name: &str) -> Option<String> {
+        let value = match name {
+            "event" => return Some(self.event.as_str().to_string()),
+            "workspace" => return Some(self.workspace.clone()),
+            "timestamp" => return Some(self.timestamp.to_string()),
+            _ => self.data.get(name.strip_prefix("data.")?)?,
+        };
+        Some(match value {
+            serde_json::Value::String(text) => text.clone(),
+            serde_json::Value::Null => String::new(),
+            other => other.to_string(),
+        })
+    }
+
+    /// Request body for a webhook
+    ///
+    /// Placeholder values are JSON-escaped, so they belong inside string
+    /// literals of the template. Unknown placeholders render empty.
+    pub fn render(&self, template: Option<&str>) -> Result<serde_json::Value, String> {
+        let Some(template) = template else {
+            return serde_json::to_value(self).map_err(|e| e.to_string());
+        };
+
+        let mut body = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find("{{") {
+            body.push_str(&rest[..start]);
+            let Some(end) = rest[start..].find("}}") else {
+                return Err("unterminated placeholder".to_string());
+            };
+            let name = rest[start + 2..start + end].trim();
+            let value = self.field(name).unwrap_or_default();
+            let escaped = serde_json::to_string(&value).map_err(|e| e.to_string())?;
+            body.push_str(&escaped[1..escaped.len() - 1]);
+            rest = &rest[start + end + 2..];
+        }
+        body.push_str(rest);
+
+        serde_json::from_str(&body).map_err(|e| format!("payload is not valid JSON: {e}"))
+    }
+}
+
+/// Delivery state of a bridged event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    Pending,
+    /// Waiting for the next attempt after a failure
+    Retrying,
+    Delivered,
+    /// All attempts failed
+    Failed,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Retrying => "retrying",
+            Self::Delivered => "delivered",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+/// One event posted to one webhook
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Delivery {
+    pub id: String,
+    pub workspace: String,
+    pub event: BridgeEventKind,
+    pub url: String,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+/// Posts workspace events to the webhooks configured for them
+///
+/// Deliveries run in the background and are retried with exponential
+/// backoff. Their state is kept for the dashboard (`/status/webhooks`) and
+/// each change is announced as a `StatusChange` for `webhook:<id>`.
+#[derive(Debug)]
+pub struct EventBridge {
+    config: BridgeConfig,
+    client: reqwest::Client,
+    deliveries: RwLock<VecDeque<Delivery>>,
+    dashboard_tx: Option<broadcast::Sender<DashboardMessage>>,
+    retry_backoff: Duration,
+
+    /// Local scripts run for the same events
+    hooks: Option<Arc<HookRunner>>,
+}
+
+impl EventBridge {
+    pub fn new(config: BridgeConfig) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::builder()
+                .timeout(DELIVERY_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            deliveries: RwLock::new(VecDeque::new()),
+            dashboard_tx: None,
+            retry_backoff: RETRY_BACKOFF,
+            hooks: None,
+        }
+    }
+
+    /// Announce delivery status changes to dashboard clients
+    pub fn with_dashboard_broadcaster(mut self, tx: broadcast::Sender<DashboardMessage>) -> Self {
+        self.dashboard_tx = Some(tx);
+        self
+    }
+
+    pub fn with_retry_backoff(mut self, backoff: Duration) -> Self {
+        self.retry_backoff = backoff;
+        self
+    }
+
+    /// Run the configured hooks for each published event
+    pub fn with_hooks(mut self, hooks: Arc<HookRunner>) -> Self {
+        self.hooks = Some(hooks);
+        self
+    }
+
+    /// Runner of the hooks, for hooks set per workspace
+    pub fn hooks(&self) -> Option<&Arc<HookRunner>> {
+        self.hooks.as_ref()
+    }
+
+    /// Whether any webhook is configured
+    pub fn is_enabled(&self) -> bool {
+        self.config
+            .workspaces
+            .values()
+            .any(|hooks| !hooks.is_empty())
+    }
+
+    /// Post `event` to every matching webhook, and run its hooks, in the background
+    pub fn publish(self: &Arc<Self>, event: BridgeEvent) {
+        if let Some(ref hooks) = self.hooks {
+            hooks.spawn(&event);
+        }
+
+        let webhooks: Vec<WebhookConfig> = [event.workspace.as_str(), ALL_WORKSPACES]
+            .iter()
+            .filter_map(|workspace| self.config.workspaces.get(*workspace))
+            .flatten()
+            .filter(|webhook| webhook.accepts(event.event))
+            .cloned()
+            .collect();
+
+        for webhook in webhooks {
+            let bridge = Arc::clone(self);
+            let event = event.clone();
+            tokio::spawn(async move { bridge.deliver(webhook, event).await });
+        }
+    }
+
+    /// Recent deliveries, newest first
+    pub async fn deliveries(&self) -> Vec<Delivery> {
+        self.deliveries.read().await.iter().rev().cloned().collect()
+    }
+
+    async fn deliver(&self, webhook: WebhookConfig, event: BridgeEvent) {
+        let now = now_secs();
+        let mut delivery = Delivery {
+            id: Uuid::new_v4().to_string(),
+            workspace: event.workspace.clone(),
+            event: event.event,
+            url: webhook.url.clone(),
+            status: DeliveryStatus::Pending,
+            attempts: 0,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+        };
+        {
+            let mut deliveries = self.deliveries.write().await;
+            deliveries.push_back(delivery.clone());
+            while deliveries.len() > MAX_DELIVERIES {
+                deliveries.pop_front();
+            }
+        }
+
+        let body = match event.render(webhook.payload.as_deref()) {
+            Ok(body) => body,
+            Err(e) => {
+                delivery.last_error = Some(e);
+                self.update(&mut delivery, DeliveryStatus::Failed).await;
+                return;
+            }
+        };
+
+        let max_attempts = webhook.max_attempts.max(1);
+        loop {
+            delivery.attempts += 1;
+            match self.post(&webhook, &body).await {
+                Ok(()) => {
+                    delivery.last_error = None;
+                    self.update(&mut delivery, DeliveryStatus::Delivered).await;
+                    return;
+                }
+                Err(e) => delivery.last_error = Some(e),
+            }
+
+            if delivery.attempts >= max_attempts {
+                self.update(&mut delivery, DeliveryStatus::Failed).await;
+                return;
+            }
+            self.update(&mut delivery, DeliveryStatus::Retrying).await;
+            let backoff = self
+                .retry_backoff
+                .saturating_mul(2u32.saturating_pow(delivery.attempts - 1));
+            tokio::time::sleep(backoff).await;
+        }
+    }
+
+    async fn post(&self, webhook: &WebhookConfig, body: &serde_json::Value) -> Result<(), String> {
+        let mut request = self.client.post(&webhook.url).json(body);
+        for (name, value) in &webhook.headers {
+            request = request.header(name, value);
+        }
+        let response = request.send().await.map_err(|e| e.to_string())?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("HTTP {}", response.status()))
+        }
+    }
+
+    /// Store a status change and announce it
+    async fn update(&self, delivery: &mut Delivery, status: DeliveryStatus) {
+        let previous = delivery.status;
+        delivery.status = status;
+        delivery.updated_at = now_secs();
+        {
+            let mut deliveries = self.deliveries.write().await;
+            if let Some(stored) = deliveries.iter_mut().find(|d| d.id == delivery.id) {
+                *stored = delivery.clone();
+            }
+        }
+
+        let context = LogContext::new("bridge", "webhook_delivery")
+            .with_entity_id(&delivery.id)
+            .with_metadata("workspace", serde_json::json!(delivery.workspace))
+            .with_metadata("event", serde_json::json!(delivery.event))
+            .with_metadata("url", serde_json::json!(delivery.url))
+            .with_metadata("attempts", serde_json::json!(delivery.attempts));
+        if matches!(status, DeliveryStatus::Retrying | DeliveryStatus::Failed) {
+            log_warn!(
+                context,
+                "Webhook delivery {} to {}: {}",
+                status.as_str(),
+                delivery.url,
+                delivery.last_error.as_deref().unwrap_or("unknown error")
+            );
+        } else {
+            log_debug!(
+                context,
+                "Webhook delivery {} to {}",
+                status.as_str(),
+                delivery.url
+            );
+        }
+
+        if let Some(ref tx) = self.dashboard_tx {
+            let _ = tx.send(DashboardMessage::StatusChange(StatusChange {
+                component: format!("webhook:{}", delivery.id),
+                previous_status: previous.as_str().to_string(),
+                new_status: status.as_str().to_string(),
+                reason: delivery.last_error.clone(),
+                timestamp: delivery.updated_at,
+            }));
+        }
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[test]
+    fn test_payload_template() {
+        let event = BridgeEvent::task_completed("frontend", "task-1", "Fix \"login\" bug");
+
+        let body = event
+            .render(Some(
+                r#"{"text": "{{data.title}} done in {{workspace}}", "kind": "{{event}}", "missing": "{{data.nope}}"}"#,
+            ))
+            .unwrap();
+        assert_eq!(body["text"], "Fix \"login\" bug done in frontend");
+        assert_eq!(body["kind"], "task_completed");
+        assert_eq!(body["missing"], "");
+
+        let body = event.render(None).unwrap();
+        assert_eq!(body["event"], "task_completed");
+        assert_eq!(body["data"]["task_id"], "task-1");
+
+        assert!(event.render(Some("{\"text\": {{workspace}}}")).is_err());
+        assert!(event.render(Some("{\"text\": \"{{workspace\"}")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_delivery_retries_until_delivered() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let config: BridgeConfig = serde_yaml::from_str(&format!(
+            "workspaces:\n  frontend:\n    - url: {url}\n      events: [agent_failed]\n  \"*\":\n    - url: {url}\n      events: [task_completed]\n      payload: '{{\"task\": \"{{{{data.title}}}}\"}}'\n"
+        ))
+        .unwrap();
+        let (tx, mut rx) = broadcast::channel(16);
+        let bridge = Arc::new(
+            EventBridge::new(config)
+                .with_dashboard_broadcaster(tx)
+                .with_retry_backoff(Duration::from_millis(10)),
+        );
+
+        // Filtered out: the frontend webhook only takes agent failures
+        bridge.publish(BridgeEvent::workspace_created("frontend", "basic"));
+        bridge.publish(BridgeEvent::task_completed("backend", "t-1", "Ship it"));
+
+        // First attempt is refused, the retry succeeds
+        let mut bodies = Vec::new();
+        for reply in ["500 Internal Server Error", "200 OK"] {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut chunk = [0; 4096];
+            while !request.ends_with(b"}") {
+                let n = stream.read(&mut chunk).await.unwrap();
+                assert!(n > 0);
+                request.extend_from_slice(&chunk[..n]);
+            }
+            bodies.push(String::from_utf8_lossy(&request).into_owned());
+            stream
+                .write_all(format!("HTTP/1.1 {reply}\r\ncontent-length: 0\r\n\r\n").as_bytes())
+                .await
+                .unwrap();
+        }
+        assert!(bodies
+            .iter()
+            .all(|body| body.ends_with(r#"{"task":"Ship it"}"#)));
+
+        let mut statuses = Vec::new();
+        while statuses.last().map(String::as_str) != Some("delivered") {
+            match tokio::time::timeout(Duration::from_secs(5), rx.recv()).await {
+                Ok(Ok(DashboardMessage::StatusChange(change))) => {
+                    assert!(change.component.starts_with("webhook:"));
+                    statuses.push(change.new_status);
+                }
+                other => panic!("unexpected message: {other:?}"),
+            }
+        }
+        assert_eq!(statuses, vec!["retrying", "delivered"]);
+
+        let deliveries = bridge.deliveries().await;
+        assert_eq!(deliveries.len(), 1);
+        assert_eq!(deliveries[0].workspace, "backend");
+        assert_eq!(deliveries[0].status, DeliveryStatus::Delivered);
+        assert_eq!(deliveries[0].attempts, 2);
+    }
+}
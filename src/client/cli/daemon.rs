@@ -0,0 +1,565 @@
+// WezTerm Multi-Process Development Framework - Daemon Commands
+// Starting, stopping, upgrading and installing the daemon
+
+use super::{format_uptime, print_status_response};
+use crate::client::IpcClient;
+use crate::config::loader::ConfigLoader;
+use crate::fsck::{self, StatePaths};
+use crate::instance::{self, InstanceError, InstanceLock, InstanceState, PidFile};
+use crate::lua_config::{self, LuaConfigOptions};
+use crate::service::{self, ServiceManager, ServiceOptions};
+use crate::upgrade::{self, HandoffState, UpgradeHandoff};
+use crate::Message;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Time `--daemon` waits for the detached daemon to answer on the socket
+const DAEMON_START_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Time `stop` waits for the daemon to exit without `--timeout`
+const DEFAULT_STOP_TIMEOUT_SECS: u64 = 30;
+
+/// Value of `--pidfile PATH`, if given
+pub fn pidfile_arg(args: &[String]) -> Result<Option<PathBuf>, Box<dyn std::error::Error>> {
+    match args.iter().position(|arg| arg == "--pidfile") {
+        Some(i) => Ok(Some(PathBuf::from(
+            args.get(i + 1).ok_or("--pidfile requires a path")?,
+        ))),
+        None => Ok(None),
+    }
+}
+
+/// `wezterm-parallel --daemon [--pidfile PATH] [--takeover]`
+///
+/// Starts the daemon in its own session with its output appended to
+/// `logging.file_path`, and returns once it answers on the IPC socket.
+pub async fn run_daemonized(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let lock_path = InstanceLock::default_path();
+    let takeover = args.iter().any(|arg| arg == "--takeover");
+    if let InstanceState::Running(holder) = instance::probe(&lock_path)? {
+        if !takeover {
+            return Err(InstanceError::AlreadyRunning {
+                path: lock_path,
+                holder,
+            }
+            .to_string()
+            .into());
+        }
+    }
+
+    // The daemon keeps this working directory, but the pidfile must be found
+    // from anywhere
+    let pidfile =
+        env::current_dir()?.join(pidfile_arg(args)?.unwrap_or_else(PidFile::default_path));
+    let mut daemon_args = Vec::new();
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--daemon" => {}
+            "--pidfile" => {
+                rest.next();
+            }
+            _ => daemon_args.push(arg.clone()),
+        }
+    }
+    daemon_args.push("--pidfile".to_string());
+    daemon_args.push(pidfile.to_string_lossy().to_string());
+
+    let config = ConfigLoader::new().load().unwrap_or_default();
+    let log_file = config.logging.log_file_path();
+    let mut child =
+        instance::spawn_detached(&env::current_exe()?, &daemon_args, log_file.as_deref())?;
+    let log_target = log_file
+        .as_ref()
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|| "nowhere (logging.file_path is not set)".to_string());
+
+    // Ready once the daemon holds the lock and serves IPC; while taking over,
+    // the previous instance still answers on the socket
+    let deadline = Instant::now() + DAEMON_START_TIMEOUT;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Err(
+                format!("Daemon exited during startup ({status}); see {log_target}").into(),
+            );
+        }
+        let holds_lock = matches!(
+            instance::probe(&lock_path)?,
+            InstanceState::Running(Some(ref holder)) if holder.pid == child.id()
+        );
+        if holds_lock
+            && matches!(
+                IpcClient::default().request(&Message::Ping).await,
+                Ok(Message::Pong)
+            )
+        {
+            break;
+        }
+        if Instant::now() >= deadline {
+            return Err(format!(
+                "Daemon (pid {}) did not come up within {}s; see {log_target}",
+                child.id(),
+                DAEMON_START_TIMEOUT.as_secs()
+            )
+            .into());
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    }
+
+    println!("wezterm-parallel started (pid {})", child.id());
+    println!("  pidfile: {}", pidfile.display());
+    println!("  logs:    {log_target}");
+    Ok(())
+}
+
+/// `wezterm-parallel stop [--timeout SECS]`
+pub async fn run_stop_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let timeout = stop_timeout(args)?;
+    let lock_path = InstanceLock::default_path();
+    let holder = match instance::probe(&lock_path)? {
+        InstanceState::NotRunning => {
+            println!("wezterm-parallel is not running");
+            return Ok(());
+        }
+        InstanceState::Running(holder) => holder.ok_or_else(|| {
+            format!(
+                "A daemon holds {} but its PID is not recorded yet",
+                lock_path.display()
+            )
+        })?,
+    };
+
+    // SIGTERM takes the daemon through its normal shutdown, saving task state
+    upgrade::terminate(holder.pid)?;
+    println!("Stopping wezterm-parallel (pid {})...", holder.pid);
+    if !instance::wait_until_stopped(&lock_path, timeout).await? {
+        return Err(format!(
+            "Daemon (pid {}) is still running after {}s; kill it with `kill -9 {}`",
+            holder.pid,
+            timeout.as_secs(),
+            holder.pid
+        )
+        .into());
+    }
+    println!("Stopped");
+    Ok(())
+}
+
+/// `wezterm-parallel restart [--timeout SECS]`: stop, then start detached
+/// with the options the daemon was started with
+pub async fn run_restart_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let daemon_args = match instance::probe(&InstanceLock::default_path())? {
+        InstanceState::Running(Some(holder)) => holder.restart_args(),
+        _ => vec!["--daemon".to_string()],
+    };
+    run_stop_command(args).await?;
+    run_daemonized(&daemon_args).await
+}
+
+fn stop_timeout(args: &[String]) -> Result<std::time::Duration, Box<dyn std::error::Error>> {
+    let secs = match args.iter().position(|arg| arg == "--timeout") {
+        Some(i) => args
+            .get(i + 1)
+            .and_then(|v| v.parse().ok())
+            .ok_or("--timeout requires a number of seconds")?,
+        None => DEFAULT_STOP_TIMEOUT_SECS,
+    };
+    Ok(std::time::Duration::from_secs(secs))
+}
+
+/// `wezterm-parallel status`
+///
+/// Exits with status 3 when no daemon is running, like LSB init scripts.
+pub async fn run_status_command() -> Result<(), Box<dyn std::error::Error>> {
+    let lock_path = InstanceLock::default_path();
+    let holder = match instance::probe(&lock_path)? {
+        InstanceState::Running(holder) => holder,
+        InstanceState::NotRunning => {
+            println!("wezterm-parallel is not running");
+            std::process::exit(3);
+        }
+    };
+    if let Some(ref holder) = holder {
+        println!(
+            "Daemon pid {} (socket {})",
+            holder.pid,
+            holder.socket.display()
+        );
+    }
+
+    let response = IpcClient::default()
+        .call(Message::SystemStatus)
+        .await
+        .map_err(|e| {
+            format!(
+                "A daemon holds {} but does not answer: {e}",
+                lock_path.display()
+            )
+        })?;
+    match response {
+        Message::SystemStatusResponse { status } => {
+            println!(
+                "wezterm-parallel {} (up {})",
+                status.version,
+                format_uptime(status.uptime_secs)
+            );
+            println!(
+                "Active workspace: {}",
+                status.active_workspace.as_deref().unwrap_or("-")
+            );
+            println!("Workspaces:       {}", status.workspace_count);
+            println!("Processes:        {}", status.process_count);
+            println!(
+                "Tasks:            {} running, {} queued, {} completed, {} failed",
+                status.active_tasks,
+                status.queued_tasks,
+                status.completed_tasks,
+                status.failed_tasks
+            );
+            println!(
+                "Maintenance mode: {}",
+                if status.maintenance { "on" } else { "off" }
+            );
+            Ok(())
+        }
+        other => print_status_response(other),
+    }
+}
+
+/// `wezterm-parallel self-upgrade <PATH|https://URL> [--sha256 HEX] [--drain-timeout SECS]`
+pub async fn run_upgrade_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let source = args.first().ok_or(
+        "Usage: wezterm-parallel self-upgrade <PATH|https://URL> [--sha256 HEX] [--drain-timeout SECS]",
+    )?;
+    let sha256 = match args.iter().position(|a| a == "--sha256") {
+        Some(i) => Some(args.get(i + 1).ok_or("--sha256 requires a hex digest")?),
+        None => None,
+    };
+    let drain_timeout = match args.iter().position(|a| a == "--drain-timeout") {
+        Some(i) => args
+            .get(i + 1)
+            .and_then(|v| v.parse().ok())
+            .ok_or("--drain-timeout requires a number of seconds")?,
+        None => 300,
+    };
+
+    let client = IpcClient::default();
+    let current_exe = env::current_exe()?;
+    let staged = current_exe.with_file_name("wezterm-parallel.new");
+
+    // 1. Stage and validate the new binary before touching the running instance
+    println!("Staging {source}...");
+    upgrade::stage_binary(source, &staged, sha256.map(String::as_str)).await?;
+    // The instance checks the file it runs against what was validated here
+    let staged_sha256 = upgrade::sha256_file(&staged)?;
+    let target_version = match upgrade::probe_version(&staged) {
+        Ok(version) => version,
+        Err(e) => {
+            let _ = std::fs::remove_file(&staged);
+            return Err(e.into());
+        }
+    };
+    println!("Validated wezterm-parallel {target_version}");
+
+    // 2. Drain
+    client
+        .request(&Message::MaintenanceSet {
+            enabled: true,
+            reason: Some(format!("upgrade to {target_version}")),
+        })
+        .await?;
+    println!("Draining (timeout {drain_timeout}s)...");
+
+    let deadline = Instant::now() + std::time::Duration::from_secs(drain_timeout);
+    loop {
+        if let Message::MaintenanceStatusResponse { status } =
+            client.request(&Message::MaintenanceStatus).await?
+        {
+            if status.drained {
+                break;
+            }
+            println!(
+                "  {} running, {} queued",
+                status.running_tasks, status.queued_tasks
+            );
+        }
+        if Instant::now() >= deadline {
+            let _ = client
+                .request(&Message::MaintenanceSet {
+                    enabled: false,
+                    reason: None,
+                })
+                .await;
+            let _ = std::fs::remove_file(&staged);
+            return Err("Timed out waiting for in-flight work to drain".into());
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    }
+
+    // 3. Hand off
+    let handoff_path = UpgradeHandoff::default_path();
+    let _ = std::fs::remove_file(&handoff_path);
+    let response = client
+        .request(&Message::Upgrade {
+            binary: staged.to_string_lossy().to_string(),
+            sha256: Some(staged_sha256),
+        })
+        .await?;
+    match response {
+        Message::UpgradeResponse { success: true, .. } => {}
+        Message::UpgradeResponse { error, .. } => {
+            let _ = client
+                .request(&Message::MaintenanceSet {
+                    enabled: false,
+                    reason: None,
+                })
+                .await;
+            return Err(error
+                .unwrap_or_else(|| "Upgrade rejected".to_string())
+                .into());
+        }
+        other => return Err(format!("Unexpected response: {other:?}").into()),
+    }
+
+    // 4. The previous instance health checks the successor before it exits,
+    //    and rolls back if it is not serving
+    let mut handoff =
+        match upgrade::wait_for_handoff(&handoff_path, std::time::Duration::from_secs(35)).await {
+            Some(handoff) if handoff.state == HandoffState::Ready => handoff,
+            Some(handoff) => {
+                return Err(format!(
+                    "Upgrade failed, previous instance kept running: {}",
+                    handoff.error.unwrap_or_default()
+                )
+                .into());
+            }
+            None => return Err("Timed out waiting for the new instance".into()),
+        };
+    let deadline = Instant::now() + std::time::Duration::from_secs(20);
+    while upgrade::is_running(handoff.previous_pid) {
+        if let Ok(latest) = UpgradeHandoff::load(&handoff_path) {
+            if latest.state != HandoffState::Ready {
+                return Err(format!(
+                    "Upgrade rolled back, previous instance kept running: {}",
+                    latest.error.unwrap_or_default()
+                )
+                .into());
+            }
+        }
+        if Instant::now() >= deadline {
+            return Err("Timed out waiting for the previous instance to exit".into());
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    }
+
+    let mut healthy = false;
+    for _ in 0..10 {
+        if let Ok(Message::Pong) = client.request(&Message::Ping).await {
+            healthy = true;
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    }
+
+    if healthy {
+        println!(
+            "Upgraded {} -> {} (pid {})",
+            handoff.previous_version,
+            handoff.target_version,
+            handoff.successor_pid.unwrap_or_default()
+        );
+        return Ok(());
+    }
+
+    println!("New instance failed health check, rolling back...");
+    if let Some(pid) = handoff.successor_pid {
+        let _ = upgrade::terminate(pid);
+    }
+    let backup = handoff
+        .previous_binary
+        .clone()
+        .ok_or("No previous binary recorded, cannot roll back")?;
+    upgrade::restore_binary(&backup, &handoff.binary)?;
+    handoff.transition(
+        &handoff_path,
+        HandoffState::RolledBack,
+        Some("Health check failed".to_string()),
+    )?;
+    let pid = upgrade::spawn_successor(&handoff.binary, &handoff_path)?;
+    Err(format!("Rolled back to {} (pid {pid})", handoff.previous_version).into())
+}
+
+/// `wezterm-parallel install-service [--manager systemd|launchd] [--socket-activation] [--output DIR] [--print]`
+///
+/// Writes the units to the per-user unit directory and prints the commands
+/// that enable them; it does not run them.
+pub fn run_install_service(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut manager = ServiceManager::native();
+    let mut output: Option<PathBuf> = None;
+    let mut print_only = false;
+    let mut socket_activation = false;
+
+    let mut iter = args.iter();
+    while let Some(flag) = iter.next() {
+        match flag.as_str() {
+            "--socket-activation" => socket_activation = true,
+            "--print" => print_only = true,
+            "--manager" | "--output" | "-o" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| format!("Missing value for {flag}"))?;
+                if flag == "--manager" {
+                    manager = ServiceManager::parse(value)
+                        .ok_or_else(|| format!("Unknown service manager: {value}"))?;
+                } else {
+                    output = Some(PathBuf::from(value));
+                }
+            }
+            other => return Err(format!("Unknown option: {other}").into()),
+        }
+    }
+
+    let config = ConfigLoader::new().load().unwrap_or_default();
+    let options = ServiceOptions {
+        binary: env::current_exe()?,
+        socket_activation,
+        socket_mode: config.auth.socket_mode,
+        log_file: config.logging.log_file_path(),
+    };
+    let units = service::render(manager, &options)?;
+
+    if print_only {
+        for (i, unit) in units.iter().enumerate() {
+            if i > 0 {
+                println!();
+            }
+            println!("# {}", unit.file_name);
+            print!("{}", unit.content);
+        }
+        return Ok(());
+    }
+
+    let dir = match output {
+        Some(dir) => dir,
+        None => manager
+            .user_unit_dir()
+            .ok_or("Cannot determine the unit directory; pass --output DIR")?,
+    };
+    std::fs::create_dir_all(&dir)?;
+    for unit in &units {
+        let path = dir.join(&unit.file_name);
+        std::fs::write(&path, &unit.content)?;
+        println!("Wrote {}", path.display());
+    }
+    println!("Enable it with:");
+    for command in service::enable_commands(manager, &options, &dir) {
+        println!("  {command}");
+    }
+    Ok(())
+}
+
+/// `wezterm-parallel generate-lua-config [--output PATH] [--socket PATH] [--dashboard-url URL] [--mods MODS]`
+pub fn run_generate_lua_config(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut options = LuaConfigOptions::default();
+    let mut output: Option<String> = None;
+
+    let mut iter = args.iter();
+    while let Some(flag) = iter.next() {
+        let value = iter
+            .next()
+            .ok_or_else(|| format!("Missing value for {flag}"))?
+            .clone();
+        match flag.as_str() {
+            "--output" | "-o" => output = Some(value),
+            "--socket" => options.socket_path = value,
+            "--dashboard-url" => options.dashboard_url = value,
+            "--mods" => options.mods = value,
+            "--template" => options.default_template = value,
+            other => return Err(format!("Unknown option: {other}").into()),
+        }
+    }
+
+    let lua = lua_config::generate(&options)?;
+    match output {
+        Some(path) => {
+            let path = Path::new(&path);
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, lua)?;
+            println!("Wrote {}", path.display());
+            println!("Add to wezterm.lua:");
+            println!("  local wezterm_parallel = require 'wezterm_parallel'");
+            println!("  wezterm_parallel.apply_to_config(config)");
+        }
+        None => print!("{lua}"),
+    }
+    Ok(())
+}
+
+/// `wezterm-parallel fsck [--fix] [--json]`
+///
+/// Works on the state files directly; `--fix` refuses to run while a server
+/// is up, since it would overwrite the repaired files on its next save.
+pub async fn run_fsck_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut fix = false;
+    let mut json = false;
+    for arg in args {
+        match arg.as_str() {
+            "--fix" => fix = true,
+            "--json" => json = true,
+            other => return Err(format!("Unknown fsck option: {other}").into()),
+        }
+    }
+
+    let paths = StatePaths::default();
+    let report = if fix {
+        if IpcClient::default().call(Message::Ping).await.is_ok() {
+            return Err("A server is running; stop it before running fsck --fix".into());
+        }
+        fsck::repair(&paths)?
+    } else {
+        fsck::check(&paths)
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!(
+            "Checked {} workspaces ({}) and {} tasks ({})",
+            report.workspaces_checked,
+            paths.workspaces.display(),
+            report.tasks_checked,
+            paths.tasks.display()
+        );
+        for issue in &report.issues {
+            let marker = if issue.fixable { "" } else { " (manual)" };
+            println!(
+                "  {:<28} {}: {}{marker}",
+                format!("{:?}", issue.kind),
+                issue.entity,
+                issue.description
+            );
+        }
+        for file in &report.repaired_files {
+            println!(
+                "Repaired {} (previous copy kept as .json.bak)",
+                file.display()
+            );
+        }
+        if report.is_clean() {
+            println!("No problems found");
+        } else if !fix && report.fixable_count() > 0 {
+            println!(
+                "Run `wezterm-parallel fsck --fix` to repair {} issues",
+                report.fixable_count()
+            );
+        }
+    }
+
+    if !fix && !report.is_clean() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
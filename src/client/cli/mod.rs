@@ -0,0 +1,95 @@
+// WezTerm Multi-Process Development Framework - Command Line
+// The `wezterm-parallel` subcommands, which talk to a running daemon
+//
+// Every `run_*` function takes the arguments after its subcommand name and
+// prints its result; the daemon binary dispatches to them.
+
+mod daemon;
+mod process;
+mod sync;
+mod system;
+mod task;
+mod workspace;
+
+pub use daemon::{
+    pidfile_arg, run_daemonized, run_fsck_command, run_generate_lua_config, run_install_service,
+    run_restart_command, run_status_command, run_stop_command, run_upgrade_command,
+};
+pub use process::{run_attach_command, run_env_command, run_output_command, run_ps_command};
+pub use sync::run_sync_command;
+pub use system::{
+    run_audit_command, run_focus_command, run_log_level_command, run_logs_command,
+    run_maintenance_command, run_peer_command, run_replay_command, run_report_command,
+    run_shedding_command, run_telemetry_command,
+};
+pub use task::{run_import_command, run_note_command, run_task_command, run_timeline_command};
+pub use workspace::{
+    run_board_command, run_project_command, run_snapshot_command, run_template_command,
+    run_workspace_command,
+};
+
+use super::IpcClient;
+use crate::ipc::IpcError;
+use crate::Message;
+
+/// Send a request, confirming it when the server holds it back as destructive
+///
+/// The impact summary is shown and the user asked to type `yes`, unless
+/// `assume_yes` is set. Without a terminal to ask on, the request is aborted.
+async fn call_with_confirmation(
+    client: &IpcClient,
+    message: Message,
+    assume_yes: bool,
+) -> Result<Message, Box<dyn std::error::Error>> {
+    let challenge = match client.call(message.clone()).await {
+        Ok(response) => return Ok(response),
+        Err(IpcError {
+            confirmation: Some(challenge),
+            ..
+        }) => challenge,
+        Err(e) => return Err(e.into()),
+    };
+
+    println!("{}", challenge.impact);
+    if !assume_yes {
+        use std::io::{BufRead, IsTerminal, Write};
+        if !std::io::stdin().is_terminal() {
+            return Err(format!(
+                "{} needs confirmation: re-run with --yes",
+                challenge.operation
+            )
+            .into());
+        }
+        print!("Type 'yes' to continue: ");
+        std::io::stdout().flush()?;
+        let mut answer = String::new();
+        std::io::stdin().lock().read_line(&mut answer)?;
+        if answer.trim() != "yes" {
+            return Err("Aborted".into());
+        }
+    }
+    Ok(client.call_confirmed(message, challenge.token).await?)
+}
+
+/// Print a `StatusUpdate` reply, treating anything else as unexpected
+fn print_status_response(response: Message) -> Result<(), Box<dyn std::error::Error>> {
+    match response {
+        Message::StatusUpdate { status, .. } => {
+            println!("{status}");
+            Ok(())
+        }
+        other => Err(format!("Unexpected response: {other:?}").into()),
+    }
+}
+
+/// Format seconds as `1d 2h 3m` / `4m 5s`
+fn format_uptime(secs: u64) -> String {
+    let (days, hours, minutes) = (secs / 86_400, secs / 3600 % 24, secs / 60 % 60);
+    if days > 0 {
+        format!("{days}d {hours}h {minutes}m")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        format!("{minutes}m {}s", secs % 60)
+    }
+}
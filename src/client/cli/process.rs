@@ -0,0 +1,256 @@
+// WezTerm Multi-Process Development Framework - Process Commands
+// Listing managed processes, their environment and output
+
+use super::print_status_response;
+use crate::client::IpcClient;
+use crate::process::{tail, OutputStream};
+use crate::Message;
+
+/// `wezterm-parallel ps [--workspace NAME]`
+pub async fn run_ps_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let workspace = match args {
+        [] => None,
+        [flag, name] if flag == "--workspace" || flag == "-w" => Some(name.clone()),
+        _ => return Err("Usage: ps [--workspace NAME]".into()),
+    };
+
+    match IpcClient::default()
+        .call(Message::ProcessList { workspace })
+        .await?
+    {
+        Message::ProcessListResponse { processes } => {
+            if processes.is_empty() {
+                println!("No processes running");
+                return Ok(());
+            }
+            println!(
+                "{:<24} {:<16} {:<10} {:>8} {:>8} {:<9}  COMMAND",
+                "ID", "WORKSPACE", "STATUS", "PID", "RESTARTS", "CONTEXT"
+            );
+            for process in processes {
+                // Short context version, marked when the file changed since
+                let context = match process.context_version {
+                    Some(ref version) => format!(
+                        "{}{}",
+                        &version[..version.len().min(8)],
+                        if process.context_outdated { "*" } else { "" }
+                    ),
+                    None => "-".to_string(),
+                };
+                println!(
+                    "{:<24} {:<16} {:<10} {:>8} {:>8} {:<9}  {}",
+                    process.id,
+                    process.workspace,
+                    process.status,
+                    process
+                        .pid
+                        .map(|pid| pid.to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                    process.restart_count,
+                    context,
+                    process.command
+                );
+            }
+            Ok(())
+        }
+        other => print_status_response(other),
+    }
+}
+
+/// `wezterm-parallel env ID [--set NAME=VALUE]... [--unset NAME]... [--reset]`
+pub async fn run_env_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    const USAGE: &str = "Usage: env ID [--set NAME=VALUE]... [--unset NAME]... [--reset]";
+    let Some((process_id, mut rest)) = args.split_first() else {
+        return Err(USAGE.into());
+    };
+
+    let mut set = std::collections::BTreeMap::new();
+    let mut unset = Vec::new();
+    let mut reset = false;
+    while let Some((flag, tail)) = rest.split_first() {
+        rest = tail;
+        match flag.as_str() {
+            "--reset" => reset = true,
+            "--set" | "--unset" => {
+                let Some((value, tail)) = rest.split_first() else {
+                    return Err(USAGE.into());
+                };
+                rest = tail;
+                if flag == "--unset" {
+                    unset.push(value.clone());
+                } else {
+                    let (name, value) = value.split_once('=').ok_or(USAGE)?;
+                    set.insert(name.to_string(), value.to_string());
+                }
+            }
+            _ => return Err(USAGE.into()),
+        }
+    }
+
+    let request = if set.is_empty() && unset.is_empty() && !reset {
+        Message::ProcessEnv {
+            process_id: process_id.clone(),
+        }
+    } else {
+        Message::ProcessEnvSet {
+            process_id: process_id.clone(),
+            set,
+            unset,
+            reset,
+        }
+    };
+
+    match IpcClient::default().call(request).await? {
+        Message::ProcessEnvResponse { environment } => {
+            for variable in &environment.variables {
+                println!(
+                    "{:<10} {}={}",
+                    format!("{:?}", variable.source).to_lowercase(),
+                    variable.name,
+                    variable.value
+                );
+            }
+            if !environment.overrides.is_empty() {
+                println!();
+                println!("Overrides for the next restart:");
+                for (name, value) in environment.overrides.iter() {
+                    match value {
+                        Some(value) => println!("  {name}={value}"),
+                        None => println!("  unset {name}"),
+                    }
+                }
+            }
+            if environment.restart_required {
+                println!();
+                println!("Restart the process to apply the changed overrides");
+            }
+            Ok(())
+        }
+        other => print_status_response(other),
+    }
+}
+
+/// `wezterm-parallel output ID [-n N] [-f]`
+///
+/// Stdout lines go to stdout and stderr lines to stderr, so the output can be
+/// followed in a WezTerm pane as if the process ran there.
+pub async fn run_output_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    const USAGE: &str = "Usage: output ID [-n N] [-f]";
+    let Some((process_id, rest)) = args.split_first() else {
+        return Err(USAGE.into());
+    };
+
+    let mut limit = None;
+    let mut follow = false;
+    let mut iter = rest.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-n" | "--lines" => {
+                limit = Some(
+                    iter.next()
+                        .and_then(|value| value.parse().ok())
+                        .ok_or("-n requires a number of lines")?,
+                )
+            }
+            "--follow" | "-f" => follow = true,
+            _ => return Err(USAGE.into()),
+        }
+    }
+
+    let client = IpcClient::default();
+    let mut since = None;
+    loop {
+        let output = match client
+            .call(Message::ProcessLogsGet {
+                process_id: process_id.clone(),
+                since,
+                limit,
+            })
+            .await?
+        {
+            Message::ProcessLogsResponse { output, .. } => output,
+            other => return print_status_response(other),
+        };
+
+        if output.dropped > 0 {
+            eprintln!("... {} lines dropped", output.dropped);
+        }
+        for line in &output.lines {
+            match line.stream {
+                OutputStream::Stdout => println!("{}", line.line),
+                OutputStream::Stderr => eprintln!("{}", line.line),
+            }
+        }
+
+        if !follow {
+            return Ok(());
+        }
+        since = Some(output.cursor);
+        if output.lines.is_empty() {
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        }
+    }
+}
+
+/// `wezterm-parallel attach ID [--level L] [--grep TEXT] [-n N] [--no-follow]`
+pub async fn run_attach_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    const USAGE: &str = "Usage: attach ID [--level L] [--grep TEXT] [-n N] [--no-follow]";
+    let Some((process_id, rest)) = args.split_first() else {
+        return Err(USAGE.into());
+    };
+
+    let mut filter = tail::TailFilter::default();
+    let mut limit = None;
+    let mut follow = true;
+    let mut iter = rest.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = || iter.next().cloned().ok_or(USAGE);
+        match arg.as_str() {
+            "--level" | "-l" => filter.level = Some(value()?),
+            "--grep" | "-g" => filter.pattern = Some(value()?),
+            "-n" | "--lines" => {
+                limit = Some(
+                    value()?
+                        .parse()
+                        .map_err(|_| "-n requires a number of lines")?,
+                )
+            }
+            "--no-follow" => follow = false,
+            _ => return Err(USAGE.into()),
+        }
+    }
+
+    let client = IpcClient::default();
+    let mut cursor = None;
+    loop {
+        let chunk = match client
+            .call(Message::ProcessLogTail {
+                process_id: process_id.clone(),
+                filter: filter.clone(),
+                cursor,
+                limit,
+            })
+            .await?
+        {
+            Message::ProcessLogTailResponse { chunk, .. } => chunk,
+            other => return print_status_response(other),
+        };
+
+        if chunk.lost {
+            eprintln!("--- rotated log removed before it was read to the end ---");
+        } else if chunk.rotated {
+            eprintln!("--- log rotated ---");
+        }
+        for line in &chunk.lines {
+            println!("{line}");
+        }
+
+        if !follow {
+            return Ok(());
+        }
+        cursor = Some(chunk.cursor);
+        if chunk.lines.is_empty() {
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        }
+    }
+}
@@ -0,0 +1,138 @@
+// WezTerm Multi-Process Development Framework - Sync Commands
+// File sync conflicts and watching
+
+use crate::client::IpcClient;
+use crate::sync::{DiffTarget, ResolutionStrategy};
+use crate::Message;
+
+/// `wezterm-parallel sync <conflicts|resolve ID <ours|theirs|merge|manual FILE>|diff <OLD NEW|process ID|conflict ID>|watch [WORKSPACE <on|off>]>`
+pub async fn run_sync_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    const USAGE: &str =
+        "Usage: sync <conflicts|resolve ID <ours|theirs|merge|manual FILE>|diff <OLD NEW|process ID|conflict ID>|watch [WORKSPACE <on|off>]>";
+    let request = match (
+        args.first().map(|s| s.as_str()),
+        args.get(1..).unwrap_or_default(),
+    ) {
+        (Some("conflicts") | None, []) => Message::SyncConflictList,
+        (Some("diff"), [kind, id]) if kind == "process" || kind == "conflict" => {
+            let target = if kind == "process" {
+                DiffTarget::Process {
+                    process_id: id.clone(),
+                }
+            } else {
+                DiffTarget::Conflict {
+                    conflict_id: id.clone(),
+                }
+            };
+            Message::SyncDiff {
+                target,
+                options: Default::default(),
+            }
+        }
+        (Some("diff"), [old_path, new_path]) => {
+            // The server may run in another directory
+            let current_dir = std::env::current_dir()?;
+            Message::SyncDiff {
+                target: DiffTarget::Files {
+                    old_path: current_dir.join(old_path),
+                    new_path: current_dir.join(new_path),
+                },
+                options: Default::default(),
+            }
+        }
+        (Some("watch"), []) => Message::SyncWatchList,
+        (Some("watch"), [workspace, state]) => Message::SyncWatchSet {
+            workspace: workspace.clone(),
+            enabled: match state.as_str() {
+                "on" => true,
+                "off" => false,
+                _ => return Err(USAGE.into()),
+            },
+        },
+        (Some("resolve"), [id, strategy, rest @ ..]) => {
+            let strategy: ResolutionStrategy = strategy.parse()?;
+            let content = match (strategy, rest) {
+                (ResolutionStrategy::Manual, [file]) => Some(std::fs::read_to_string(file)?),
+                (ResolutionStrategy::Manual, _) => {
+                    return Err(
+                        "Manual resolution requires a FILE with the resolved content".into(),
+                    )
+                }
+                (_, []) => None,
+                _ => return Err(USAGE.into()),
+            };
+            Message::SyncResolve {
+                conflict_id: id.clone(),
+                strategy,
+                content,
+            }
+        }
+        _ => return Err(USAGE.into()),
+    };
+
+    match IpcClient::default().call(request).await? {
+        Message::SyncConflictListResponse { conflicts } => {
+            if conflicts.is_empty() {
+                println!("No unresolved sync conflicts");
+                return Ok(());
+            }
+            println!("{:<36}  {:<10}  FILE", "ID", "DETECTED");
+            for conflict in conflicts {
+                let detected = chrono::DateTime::from_timestamp(conflict.detected_at as i64, 0)
+                    .map(|at| at.format("%H:%M:%S").to_string())
+                    .unwrap_or_default();
+                println!(
+                    "{:<36}  {:<10}  {}",
+                    conflict.id,
+                    detected,
+                    conflict.file_path.display()
+                );
+            }
+            Ok(())
+        }
+        Message::SyncWatchListResponse { workspaces } => {
+            if workspaces.is_empty() {
+                println!("File watching is disabled");
+                return Ok(());
+            }
+            println!("{:<16}  {:<8}  ROOTS", "WORKSPACE", "STATE");
+            for status in workspaces {
+                let roots: Vec<String> = status
+                    .roots
+                    .iter()
+                    .map(|root| root.display().to_string())
+                    .collect();
+                println!(
+                    "{:<16}  {:<8}  {}",
+                    status.workspace,
+                    if status.enabled { "watching" } else { "paused" },
+                    roots.join(", ")
+                );
+            }
+            Ok(())
+        }
+        Message::SyncDiffResponse { diffs } => {
+            for diff in diffs {
+                if diff.binary || diff.too_large {
+                    let reason = if diff.binary { "binary" } else { "too large" };
+                    println!("{}: {reason}, not shown", diff.new_path.display());
+                    continue;
+                }
+                print!("{}", diff.unified);
+                if diff.truncated {
+                    println!("... (truncated)");
+                }
+            }
+            Ok(())
+        }
+        Message::SyncResolveResponse { resolution } => {
+            println!(
+                "Resolved {} with {}",
+                resolution.file_path.display(),
+                resolution.strategy.as_str()
+            );
+            Ok(())
+        }
+        other => Err(format!("Unexpected response: {other:?}").into()),
+    }
+}
@@ -0,0 +1,630 @@
+// WezTerm Multi-Process Development Framework - System Commands
+// Maintenance, focus, logging, audit, telemetry, analytics and federation commands
+
+use super::{call_with_confirmation, print_status_response};
+use crate::audit::AuditFilter;
+use crate::client::IpcClient;
+use crate::config::Timezone;
+use crate::federation::PeerConfig;
+use crate::ipc::{self, IpcError};
+use crate::logging::buffer as log_buffer;
+use crate::monitoring::report;
+use crate::Message;
+
+/// `wezterm-parallel maintenance <on [REASON]|off|status>`
+pub async fn run_maintenance_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let request = match args.first().map(|s| s.as_str()) {
+        Some("on") => Message::MaintenanceSet {
+            enabled: true,
+            reason: (args.len() > 1).then(|| args[1..].join(" ")),
+        },
+        Some("off") => Message::MaintenanceSet {
+            enabled: false,
+            reason: None,
+        },
+        Some("status") | None => Message::MaintenanceStatus,
+        Some(other) => {
+            return Err(format!(
+                "Unknown maintenance command: {other} (expected on, off or status)"
+            )
+            .into());
+        }
+    };
+
+    match IpcClient::default().call(request).await? {
+        Message::MaintenanceStatusResponse { status } => {
+            let state = if !status.enabled {
+                "off"
+            } else if status.drained {
+                "on (drained)"
+            } else {
+                "on (draining)"
+            };
+            println!("Maintenance mode: {state}");
+            if let Some(reason) = status.reason {
+                println!("Reason:           {reason}");
+            }
+            println!("Running tasks:    {}", status.running_tasks);
+            println!("Queued tasks:     {}", status.queued_tasks);
+            println!("Processes:        {}", status.active_processes);
+            Ok(())
+        }
+        other => Err(format!("Unexpected response: {other:?}").into()),
+    }
+}
+
+/// `wezterm-parallel focus <on [LABEL]|off|toggle [LABEL]|status>`
+pub async fn run_focus_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let client = IpcClient::default();
+    let label = (args.len() > 1).then(|| args[1..].join(" "));
+    let request = match args.first().map(|s| s.as_str()) {
+        Some("on") => Message::FocusSet {
+            enabled: true,
+            label,
+        },
+        Some("off") => Message::FocusSet {
+            enabled: false,
+            label: None,
+        },
+        Some("toggle") => match client.call(Message::FocusStatus).await? {
+            Message::FocusStatusResponse { status } => Message::FocusSet {
+                enabled: !status.enabled,
+                label,
+            },
+            other => return Err(format!("Unexpected response: {other:?}").into()),
+        },
+        Some("status") | None => Message::FocusStatus,
+        Some(other) => {
+            return Err(format!(
+                "Unknown focus command: {other} (expected on, off, toggle or status)"
+            )
+            .into());
+        }
+    };
+
+    match client.call(request).await? {
+        Message::FocusStatusResponse { status } => {
+            println!(
+                "Focus mode:       {}",
+                if status.enabled { "on" } else { "off" }
+            );
+            if let Some(label) = status.label {
+                println!("Focusing on:      {label}");
+            }
+            if let Some(since) = status.since {
+                println!(
+                    "Since:            {}",
+                    Timezone::Local.format(since, "%Y-%m-%d %H:%M:%S")
+                );
+            }
+            if let Some(priority) = status.defer_below {
+                println!(
+                    "Deferred tasks:   {} (below {priority})",
+                    status.deferred_tasks
+                );
+            }
+            if !status.paused_processes.is_empty() {
+                println!("Paused processes: {}", status.paused_processes.join(", "));
+            }
+            println!("Suppressed:       {}", status.suppressed_notifications);
+            Ok(())
+        }
+        other => Err(format!("Unexpected response: {other:?}").into()),
+    }
+}
+
+/// `wezterm-parallel log-level [MODULE LEVEL]`
+pub async fn run_log_level_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let request = match args {
+        [] => Message::LogLevelList,
+        [module, level] => Message::LogLevelSet {
+            module: module.clone(),
+            level: level.clone(),
+        },
+        _ => return Err("Usage: log-level [MODULE <trace|debug|info|warn|error|reset>]".into()),
+    };
+
+    match IpcClient::default().call(request).await? {
+        Message::LogLevelResponse { levels } => {
+            println!("{:<20} {}", "default", levels.default);
+            for (module, level) in &levels.modules {
+                let marker = if levels.overridden.contains(module) {
+                    " *"
+                } else {
+                    ""
+                };
+                println!("{module:<20} {level}{marker}");
+            }
+            println!();
+            println!("Tracing filter: {}", levels.filter);
+            Ok(())
+        }
+        other => print_status_response(other),
+    }
+}
+
+/// `wezterm-parallel logs [--component C] [--process ID] [--level L] [--grep TEXT] [-n N] [-f]`
+pub async fn run_logs_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut query = log_buffer::LogQuery::default();
+    let mut follow = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |flag: &str| {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("{flag} requires a value"))
+        };
+        match arg.as_str() {
+            "--component" | "-c" => query.component = Some(value(arg)?),
+            "--process" | "-p" => query.entity = Some(value(arg)?),
+            "--level" | "-l" => query.level = Some(value(arg)?),
+            "--grep" | "-g" => query.search = Some(value(arg)?),
+            "-n" | "--limit" => {
+                query.limit = Some(
+                    value(arg)?
+                        .parse()
+                        .map_err(|_| "-n requires a number of entries")?,
+                )
+            }
+            "--follow" | "-f" => follow = true,
+            _ => return Err(
+                "Usage: logs [--component C] [--process ID] [--level L] [--grep TEXT] [-n N] [-f]"
+                    .into(),
+            ),
+        }
+    }
+
+    let client = IpcClient::default();
+    loop {
+        let result = match client
+            .call(Message::LogQuery {
+                query: query.clone(),
+            })
+            .await?
+        {
+            Message::LogQueryResponse { result } => result,
+            other => return print_status_response(other),
+        };
+
+        if result.truncated > 0 {
+            println!("... {} earlier entries omitted", result.truncated);
+        }
+        for buffered in &result.entries {
+            let entry = &buffered.entry;
+            let source = match entry.context.entity_id {
+                Some(ref entity) => format!("{}:{}", entry.context.component, entity),
+                None => entry.context.component.clone(),
+            };
+            match entry.error {
+                Some(ref error) => println!(
+                    "{} {:<5} [{}] {}: {}",
+                    entry.timestamp,
+                    entry.level.as_str(),
+                    source,
+                    entry.message,
+                    error
+                ),
+                None => println!(
+                    "{} {:<5} [{}] {}",
+                    entry.timestamp,
+                    entry.level.as_str(),
+                    source,
+                    entry.message
+                ),
+            }
+        }
+
+        if !follow {
+            return Ok(());
+        }
+        query.since = Some(result.cursor);
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    }
+}
+
+/// Incidents listed by `wezterm-parallel shedding`
+const SHEDDING_INCIDENTS_SHOWN: usize = 10;
+
+pub async fn run_shedding_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut since_hours = None;
+    let mut json = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--since" => {
+                since_hours = Some(
+                    iter.next()
+                        .and_then(|hours| hours.parse().ok())
+                        .ok_or("--since requires a number of hours")?,
+                )
+            }
+            "--json" => json = true,
+            _ => return Err("Usage: shedding [--since HOURS] [--json]".into()),
+        }
+    }
+
+    let report = match IpcClient::default()
+        .call(Message::SheddingReport { since_hours })
+        .await?
+    {
+        Message::SheddingReportResponse { report } => report,
+        other => return print_status_response(other),
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+    if report.summaries.is_empty() {
+        println!("No load shedding recorded");
+        return Ok(());
+    }
+
+    println!(
+        "{:<18} {:<10} {:>9} {:>11} {:>9} {:>9}  PEAK",
+        "SOURCE", "ACTION", "INCIDENTS", "OCCURRENCES", "TOTAL", "LONGEST"
+    );
+    for summary in &report.summaries {
+        println!(
+            "{:<18} {:<10} {:>9} {:>11} {:>8}s {:>8}s  {} {} / {}",
+            summary.source,
+            summary.action.as_str(),
+            summary.incidents,
+            summary.occurrences,
+            summary.total_secs,
+            summary.longest_secs,
+            summary.peak.metric,
+            summary.peak.value,
+            summary.peak.limit
+        );
+    }
+
+    println!();
+    println!("Recent incidents:");
+    for incident in report.incidents.iter().take(SHEDDING_INCIDENTS_SHOWN) {
+        println!(
+            "  {} {:>6}s {} {} x{}: {}",
+            Timezone::Local.format(incident.started_at, "%Y-%m-%d %H:%M:%S"),
+            incident.duration_secs(),
+            incident.source,
+            incident.action.as_str(),
+            incident.occurrences,
+            incident.items.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// `wezterm-parallel shedding [--since HOURS] [--json]`
+/// `wezterm-parallel audit [--client NAME] [--operation OP] [--source ipc|dashboard] [--since HOURS] [--failed] [-n N] [--json]`
+pub async fn run_audit_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    const USAGE: &str = "Usage: audit [--client NAME] [--operation OP] [--source ipc|dashboard] [--since HOURS] [--failed] [-n N] [--json]";
+    let mut filter = AuditFilter::default();
+    let mut json = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--client" => filter.client = Some(iter.next().ok_or(USAGE)?.clone()),
+            "--operation" => filter.operation = Some(iter.next().ok_or(USAGE)?.clone()),
+            "--source" => {
+                filter.source = Some(
+                    iter.next()
+                        .and_then(|source| serde_json::from_value(serde_json::json!(source)).ok())
+                        .ok_or("--source requires ipc or dashboard")?,
+                )
+            }
+            "--since" => {
+                let hours: u64 = iter
+                    .next()
+                    .and_then(|hours| hours.parse().ok())
+                    .ok_or("--since requires a number of hours")?;
+                filter.since = Some(crate::clock::unix_secs().saturating_sub(hours * 3600));
+            }
+            "--failed" => filter.failed_only = true,
+            "-n" => {
+                filter.limit = Some(
+                    iter.next()
+                        .and_then(|n| n.parse().ok())
+                        .ok_or("-n requires a number")?,
+                )
+            }
+            "--json" => json = true,
+            _ => return Err(USAGE.into()),
+        }
+    }
+
+    let entries = match IpcClient::default()
+        .call(Message::AuditQuery { filter })
+        .await?
+    {
+        Message::AuditQueryResponse { entries } => entries,
+        other => return print_status_response(other),
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+    if entries.is_empty() {
+        println!("No audit entries");
+        return Ok(());
+    }
+
+    println!(
+        "{:<19} {:<9} {:<12} {:<24} {:<6} DETAILS",
+        "TIME", "SOURCE", "CLIENT", "OPERATION", "RESULT"
+    );
+    // Oldest first, like a log
+    for entry in entries.iter().rev() {
+        let source = match entry.source {
+            crate::audit::AuditSource::Ipc => "ipc",
+            crate::audit::AuditSource::Dashboard => "dashboard",
+        };
+        let details = match (&entry.error, &entry.details) {
+            (Some(error), _) => error.clone(),
+            (None, Some(details)) => details.to_string(),
+            (None, None) => String::new(),
+        };
+        println!(
+            "{:<19} {:<9} {:<12} {:<24} {:<6} {}",
+            Timezone::Local.format(entry.timestamp, "%Y-%m-%d %H:%M:%S"),
+            source,
+            entry.client,
+            entry.operation,
+            if entry.success { "ok" } else { "failed" },
+            details
+        );
+    }
+    Ok(())
+}
+
+/// `wezterm-parallel replay FILE [--realtime]`
+///
+/// Requests go to the running instance in recorded order; with `--realtime`
+/// the recorded gaps between them are kept. Destructive operations that were
+/// confirmed in the recording are confirmed again.
+pub async fn run_replay_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let (path, realtime) = match args {
+        [path] => (path, false),
+        [path, flag] | [flag, path] if flag == "--realtime" => (path, true),
+        _ => return Err("Usage: replay FILE [--realtime]".into()),
+    };
+    let session = ipc::recorder::read_session(path)?;
+    let requests = ipc::recorder::recorded_requests(&session);
+    if requests.is_empty() {
+        println!("No requests recorded in {path}");
+        return Ok(());
+    }
+
+    let client = IpcClient::default();
+    let mut previous_ms = None;
+    let mut mismatches = 0;
+    for (index, request) in requests.iter().enumerate() {
+        let Some(message) = request.request.message.clone() else {
+            continue;
+        };
+        if let (true, Some(previous_ms)) = (realtime, previous_ms) {
+            let gap = request.request.timestamp_ms.saturating_sub(previous_ms);
+            tokio::time::sleep(std::time::Duration::from_millis(gap)).await;
+        }
+        previous_ms = Some(request.request.timestamp_ms);
+
+        let mut result = client.call(message.clone()).await;
+        let recorded_success = request
+            .recorded_outcome
+            .as_ref()
+            .is_some_and(|outcome| !outcome.starts_with("error:"));
+        if let Err(IpcError {
+            confirmation: Some(ref challenge),
+            ..
+        }) = result
+        {
+            if recorded_success {
+                let token = challenge.token.clone();
+                result = client.call_confirmed(message, token).await;
+            }
+        }
+
+        let step = ipc::recorder::ReplayStep::new(request, &result);
+        if !step.matches() {
+            mismatches += 1;
+        }
+        println!(
+            "{:>4}  {:<24} {:<28} {}",
+            index + 1,
+            step.operation,
+            step.replayed,
+            match step.recorded {
+                Some(ref recorded) if !step.matches() => format!("DIFF (recorded {recorded})"),
+                Some(_) => "ok".to_string(),
+                None => "(no recorded reply)".to_string(),
+            }
+        );
+    }
+
+    println!();
+    println!(
+        "Replayed {} requests, {} differed from the recording",
+        requests.len(),
+        mismatches
+    );
+    Ok(())
+}
+
+/// `wezterm-parallel telemetry preview`
+pub async fn run_telemetry_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    match args.first().map(|s| s.as_str()) {
+        Some("preview") | None => {}
+        Some(other) => return Err(format!("Unknown telemetry command: {other}").into()),
+    }
+
+    match IpcClient::default().call(Message::TelemetryPreview).await? {
+        Message::TelemetryPreviewResponse {
+            enabled,
+            endpoint,
+            payload,
+        } => {
+            if enabled {
+                println!(
+                    "Telemetry: enabled (endpoint: {})",
+                    endpoint.unwrap_or_default()
+                );
+            } else {
+                println!("Telemetry: disabled (set telemetry.enabled in config.yaml to opt in)");
+            }
+            println!("Payload:");
+            println!("{}", serde_json::to_string_pretty(&payload)?);
+            Ok(())
+        }
+        other => Err(format!("Unexpected response: {other:?}").into()),
+    }
+}
+
+/// `report [daily|weekly] [--range R] [--format markdown|html|json] [--output FILE]`
+pub async fn run_report_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    const USAGE: &str =
+        "Usage: report [daily|weekly] [--range 24h|7d|2w] [--format markdown|html|json] [--output FILE]";
+    let mut range = None;
+    let mut format = "markdown".to_string();
+    let mut output = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("{name} requires a value"))
+        };
+        match arg.as_str() {
+            "daily" if range.is_none() => range = Some("24h".to_string()),
+            "weekly" if range.is_none() => range = Some("7d".to_string()),
+            "--range" => range = Some(value("--range")?),
+            "--format" => format = value("--format")?,
+            "--output" | "-o" => output = Some(value("--output")?),
+            _ => return Err(USAGE.into()),
+        }
+    }
+
+    let request = Message::AnalyticsExport {
+        format,
+        path: None,
+        range,
+    };
+    match IpcClient::default().call(request).await? {
+        Message::AnalyticsExportResponse {
+            content: Some(content),
+            ..
+        } => {
+            match output {
+                Some(path) => {
+                    report::write_report(std::path::Path::new(&path), &content)?;
+                    println!("Report written to {path}");
+                }
+                None if content.ends_with('\n') => print!("{content}"),
+                None => println!("{content}"),
+            }
+            Ok(())
+        }
+        other => print_status_response(other),
+    }
+}
+
+/// `wezterm-parallel peer <list|add|remove|call>`
+///
+/// `call` sends a workspace, task or process message (as JSON) to the peer
+/// and prints its reply, asking for confirmation when the peer wants it.
+pub async fn run_peer_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    const USAGE: &str =
+        "Usage: peer <list|add NAME ADDRESS [--token TOKEN]|remove NAME|call NAME JSON [--yes]>";
+    let client = IpcClient::default();
+
+    let response = match args.first().map(String::as_str) {
+        Some("list") | None => client.call(Message::PeerList).await?,
+        Some("add") => {
+            let (Some(name), Some(address)) = (args.get(1), args.get(2)) else {
+                return Err(USAGE.into());
+            };
+            let mut peer = PeerConfig::new(name, address);
+            let mut iter = args[3..].iter();
+            while let Some(arg) = iter.next() {
+                match arg.as_str() {
+                    "--token" => {
+                        peer = peer.with_token(iter.next().ok_or("--token requires a value")?)
+                    }
+                    _ => return Err(USAGE.into()),
+                }
+            }
+            client.call(Message::PeerRegister { peer }).await?
+        }
+        Some("remove") => {
+            let name = args.get(1).ok_or(USAGE)?;
+            client
+                .call(Message::PeerRemove { name: name.clone() })
+                .await?
+        }
+        Some("call") => {
+            let (Some(peer), Some(json)) = (args.get(1), args.get(2)) else {
+                return Err(USAGE.into());
+            };
+            let assume_yes = args[3..].iter().any(|arg| arg == "--yes");
+            let message: Message =
+                serde_json::from_str(json).map_err(|e| format!("Invalid message: {e}"))?;
+            let forward = Message::PeerForward {
+                peer: peer.clone(),
+                message: Box::new(message),
+            };
+            let reply = call_with_confirmation(&client, forward, assume_yes).await?;
+            println!("{}", serde_json::to_string_pretty(&reply)?);
+            return Ok(());
+        }
+        Some(_) => return Err(USAGE.into()),
+    };
+
+    let peers = match response {
+        Message::PeerListResponse { peers } => peers,
+        other => return print_status_response(other),
+    };
+    if peers.is_empty() {
+        println!("No peers registered");
+        return Ok(());
+    }
+    println!(
+        "{:<16} {:<12} {:>10} {:>9} {:>6} {:>6}  {:<19} ADDRESS",
+        "PEER", "STATE", "WORKSPACES", "PROCESSES", "ACTIVE", "QUEUED", "LAST SEEN"
+    );
+    for peer in peers {
+        let state = if peer.reachable {
+            "reachable"
+        } else {
+            "unreachable"
+        };
+        let counts = peer.status.as_ref().map_or(
+            (
+                "-".to_string(),
+                "-".to_string(),
+                "-".to_string(),
+                "-".to_string(),
+            ),
+            |status| {
+                (
+                    status.workspace_count.to_string(),
+                    status.process_count.to_string(),
+                    status.active_tasks.to_string(),
+                    status.queued_tasks.to_string(),
+                )
+            },
+        );
+        let last_seen = peer.last_seen.map_or("never".to_string(), |ts| {
+            Timezone::Local.format(ts, "%Y-%m-%d %H:%M:%S")
+        });
+        println!(
+            "{:<16} {:<12} {:>10} {:>9} {:>6} {:>6}  {:<19} {}",
+            peer.name, state, counts.0, counts.1, counts.2, counts.3, last_seen, peer.address
+        );
+        if let Some(error) = peer.last_error {
+            println!("  {error}");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,521 @@
+// WezTerm Multi-Process Development Framework - Task Commands
+// Task queue and control, notes, timelines and history imports
+
+use super::{format_uptime, print_status_response};
+use crate::client::IpcClient;
+use crate::config::Timezone;
+use crate::task::ImportReport;
+use crate::Message;
+
+/// `wezterm-parallel task <queue COMMAND... [--priority N] [--id ID] [--cwd DIR] [--env NAME=VALUE]... [--shell SH] [--timeout SECS]|cancel ID|pause ID|resume ID|trace ID|batch FILE|dead-letters|requeue ID|purge [ID]|github-sync>`
+pub async fn run_task_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let request = match (
+        args.first().map(|s| s.as_str()),
+        args.get(1..).unwrap_or_default(),
+    ) {
+        (Some("queue"), rest) => {
+            let mut id = None;
+            let mut priority = 5;
+            let mut command = Vec::new();
+            let mut working_dir = None;
+            let mut env = std::collections::HashMap::new();
+            let mut shell = None;
+            let mut timeout_secs = None;
+            let mut assignee = None;
+
+            let mut iter = rest.iter();
+            while let Some(arg) = iter.next() {
+                match arg.as_str() {
+                    "--priority" | "-p" => {
+                        priority = iter
+                            .next()
+                            .and_then(|v| v.parse().ok())
+                            .ok_or("--priority requires a number between 0 and 255")?;
+                    }
+                    "--id" => id = Some(iter.next().ok_or("--id requires a value")?.clone()),
+                    "--cwd" => {
+                        working_dir = Some(iter.next().ok_or("--cwd requires a directory")?.clone())
+                    }
+                    "--env" => {
+                        let (name, value) = iter
+                            .next()
+                            .and_then(|v| v.split_once('='))
+                            .ok_or("--env requires NAME=VALUE")?;
+                        env.insert(name.to_string(), value.to_string());
+                    }
+                    "--shell" => shell = Some(iter.next().ok_or("--shell requires a shell")?.clone()),
+                    "--timeout" => {
+                        timeout_secs = Some(
+                            iter.next()
+                                .and_then(|v| v.parse().ok())
+                                .ok_or("--timeout requires a number of seconds")?,
+                        );
+                    }
+                    "--assignee" => {
+                        assignee = Some(iter.next().ok_or("--assignee requires a name")?.clone())
+                    }
+                    "--" => command.extend(iter.by_ref().cloned()),
+                    _ => command.push(arg.clone()),
+                }
+            }
+            if command.is_empty() {
+                return Err("Usage: task queue COMMAND... [--priority N] [--id ID] [--cwd DIR] [--env NAME=VALUE]... [--shell SH] [--timeout SECS] [--assignee NAME]".into());
+            }
+
+            Message::TaskQueue {
+                id: id.unwrap_or_else(|| format!("cli-{}", uuid::Uuid::new_v4().simple())),
+                priority,
+                command: command.join(" "),
+                working_dir,
+                env,
+                shell,
+                timeout_secs,
+                assignee,
+            }
+        }
+        (Some("cancel"), [id]) => Message::TaskCancel { id: id.clone() },
+        (Some("pause"), [id]) => Message::TaskPause { id: id.clone() },
+        (Some("resume"), [id]) => Message::TaskResume { id: id.clone() },
+        (Some("trace"), [id]) => return run_task_trace(id).await,
+        (Some("batch"), rest) => return run_task_batch(rest).await,
+        (Some("result"), [id]) => return run_task_result(id).await,
+        (Some("latency"), rest) => return run_task_latency(rest).await,
+        (Some("dead-letters"), []) => return run_task_dead_letters().await,
+        (Some("github-sync"), []) => return run_task_github_sync().await,
+        (Some("requeue"), [id]) => Message::DeadLetterRequeue { id: id.clone() },
+        (Some("purge"), []) => Message::DeadLetterPurge { id: None },
+        (Some("purge"), [id]) => Message::DeadLetterPurge {
+            id: Some(id.clone()),
+        },
+        _ => return Err(
+            "Usage: task <queue COMMAND... [--priority N] [--id ID] [--cwd DIR] [--env NAME=VALUE]... [--shell SH] [--timeout SECS]|cancel ID|pause ID|resume ID|trace ID|batch FILE [--atomic] [--after ID]...|result ID|latency [--since HOURS]|dead-letters|requeue ID|purge [ID]|github-sync>"
+                .into(),
+        ),
+    };
+
+    print_status_response(IpcClient::default().call(request).await?)
+}
+
+/// `wezterm-parallel task trace ID`
+async fn run_task_trace(id: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let trace = match IpcClient::default()
+        .call(Message::TaskTrace { id: id.to_string() })
+        .await?
+    {
+        Message::TaskTraceResponse { trace } => trace,
+        other => return Err(format!("Unexpected response: {other:?}").into()),
+    };
+
+    println!("{} {:?}  {}", trace.task_id, trace.status, trace.title);
+    if trace.spans.is_empty() {
+        println!("Not run yet");
+        return Ok(());
+    }
+    println!(
+        "Agents: {} ({} handoffs), total {}",
+        trace.agents.join(" -> "),
+        trace.handoffs,
+        format_uptime(trace.total_duration_secs)
+    );
+    println!(
+        "{:>3}  {:<16} {:<19} {:>10}  OUTCOME",
+        "#", "AGENT", "STARTED", "DURATION"
+    );
+    for span in trace.spans {
+        let outcome = match span.error {
+            Some(error) => format!("{:?}: {error}", span.outcome),
+            None => format!("{:?}", span.outcome),
+        };
+        println!(
+            "{:>3}  {:<16} {:<19} {:>10}  {}",
+            span.attempt,
+            span.agent,
+            Timezone::Local.format(span.started_at, "%Y-%m-%d %H:%M:%S"),
+            format_uptime(span.duration_secs),
+            outcome
+        );
+    }
+    Ok(())
+}
+
+/// `wezterm-parallel task batch FILE [--atomic] [--after ID]...`
+///
+/// FILE holds a JSON or YAML list of tasks with the fields of `TaskQueue`,
+/// plus `key` and `depends_on`.
+async fn run_task_batch(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = None;
+    let mut atomic = false;
+    let mut depends_on = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--atomic" => atomic = true,
+            "--after" => depends_on.push(iter.next().ok_or("--after requires a task ID")?.clone()),
+            _ if file.is_none() => file = Some(arg.clone()),
+            _ => return Err(format!("Unexpected argument: {arg}").into()),
+        }
+    }
+    let file = file.ok_or("Usage: task batch FILE [--atomic] [--after ID]...")?;
+    // YAML is a superset of JSON
+    let tasks: Vec<crate::task::BatchTask> = serde_yaml::from_str(&std::fs::read_to_string(&file)?)
+        .map_err(|e| format!("Invalid batch file {file}: {e}"))?;
+
+    let report = match IpcClient::default()
+        .call(Message::TaskQueueBatch {
+            tasks,
+            atomic,
+            depends_on,
+        })
+        .await?
+    {
+        Message::TaskQueueBatchResponse { report } => report,
+        other => return print_status_response(other),
+    };
+
+    for result in &report.results {
+        match (&result.task_id, &result.error) {
+            (Some(task_id), _) => println!("{:<16} queued  {}", result.key, task_id),
+            (None, Some(error)) => println!("{:<16} failed  {}", result.key, error),
+            (None, None) => println!("{:<16} failed", result.key),
+        }
+    }
+    println!("{} queued, {} failed", report.created, report.failed);
+    if report.failed > 0 {
+        return Err("Some tasks of the batch were not queued".into());
+    }
+    Ok(())
+}
+
+/// `wezterm-parallel task github-sync`
+async fn run_task_github_sync() -> Result<(), Box<dyn std::error::Error>> {
+    let report = match IpcClient::default().call(Message::GithubSync).await? {
+        Message::GithubSyncResponse { report } => report,
+        other => return print_status_response(other),
+    };
+
+    for issue in &report.imported {
+        println!("imported  {issue}");
+    }
+    for issue in &report.closed {
+        println!("closed    {issue}");
+    }
+    for error in &report.errors {
+        println!("failed    {error}");
+    }
+    println!(
+        "{} imported, {} closed",
+        report.imported.len(),
+        report.closed.len()
+    );
+    if !report.errors.is_empty() {
+        return Err("Some GitHub requests failed".into());
+    }
+    Ok(())
+}
+
+/// `wezterm-parallel task dead-letters`
+async fn run_task_dead_letters() -> Result<(), Box<dyn std::error::Error>> {
+    let entries = match IpcClient::default().call(Message::DeadLetterList).await? {
+        Message::DeadLetterListResponse { entries } => entries,
+        other => return Err(format!("Unexpected response: {other:?}").into()),
+    };
+
+    if entries.is_empty() {
+        println!("No dead-lettered tasks");
+        return Ok(());
+    }
+    println!(
+        "{:<24} {:<19} {:>8}  {:<24} REASON",
+        "TASK", "DEAD-LETTERED", "ATTEMPTS", "TITLE"
+    );
+    for entry in entries {
+        println!(
+            "{:<24} {:<19} {:>8}  {:<24} {}",
+            entry.task_id,
+            Timezone::Local.format(entry.dead_lettered_at, "%Y-%m-%d %H:%M:%S"),
+            entry.history.len(),
+            entry.title,
+            entry.reason
+        );
+    }
+    Ok(())
+}
+
+/// `wezterm-parallel task latency [--since HOURS]`
+async fn run_task_latency(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let since_hours = match args {
+        [] => None,
+        [flag, hours] if flag == "--since" => Some(
+            hours
+                .parse()
+                .map_err(|_| "--since requires a number of hours")?,
+        ),
+        _ => return Err("Usage: task latency [--since HOURS]".into()),
+    };
+    let report = match IpcClient::default()
+        .call(Message::TaskLatencyReport { since_hours })
+        .await?
+    {
+        Message::TaskLatencyReportResponse { report } => report,
+        other => return Err(format!("Unexpected response: {other:?}").into()),
+    };
+
+    if report.stages.is_empty() {
+        println!("No finished stages yet");
+    } else {
+        println!(
+            "{:<18} {:>7} {:>9} {:>9} {:>9} {:>9} {:>9}",
+            "STAGE", "SAMPLES", "P50", "P90", "P95", "P99", "MAX"
+        );
+        for stage in &report.stages {
+            println!(
+                "{:<18} {:>7} {:>9} {:>9} {:>9} {:>9} {:>9}",
+                stage.stage.as_str(),
+                stage.samples,
+                format_uptime(stage.p50),
+                format_uptime(stage.p90),
+                format_uptime(stage.p95),
+                format_uptime(stage.p99),
+                format_uptime(stage.max)
+            );
+        }
+    }
+
+    if !report.breaches.is_empty() {
+        println!();
+        println!("Over budget:");
+        for breach in &report.breaches {
+            println!(
+                "  {:<24} {:<18} {} (budget {}){}",
+                breach.task_id,
+                breach.stage.as_str(),
+                format_uptime(breach.elapsed_secs),
+                format_uptime(breach.max_secs),
+                if breach.ongoing { ", ongoing" } else { "" }
+            );
+        }
+    }
+    Ok(())
+}
+
+/// `wezterm-parallel task result ID`
+async fn run_task_result(id: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let result = match IpcClient::default()
+        .call(Message::TaskResultGet { id: id.to_string() })
+        .await?
+    {
+        Message::TaskResultResponse { result } => result,
+        other => return Err(format!("Unexpected response: {other:?}").into()),
+    };
+
+    match (&result.result, &result.error) {
+        (Some(outcome), Some(error)) => {
+            println!(
+                "{} {:?} ({outcome:?}: {error})",
+                result.task_id, result.status
+            )
+        }
+        (Some(outcome), None) => println!("{} {:?} ({outcome:?})", result.task_id, result.status),
+        (None, _) => println!("{} {:?}", result.task_id, result.status),
+    }
+    let Some(output) = result.output else {
+        println!("No captured output");
+        return Ok(());
+    };
+    match output.exit_code {
+        Some(code) => println!("Exit code: {code}"),
+        None => println!("Exit code: none (killed by a signal)"),
+    }
+    for (name, stream) in [("stdout", &output.stdout), ("stderr", &output.stderr)] {
+        if stream.bytes == 0 {
+            continue;
+        }
+        if stream.truncated {
+            println!(
+                "--- {name} (last {} of {} bytes) ---",
+                stream.text.len(),
+                stream.bytes
+            );
+        } else {
+            println!("--- {name} ---");
+        }
+        print!("{}", stream.text);
+        if !stream.text.ends_with('\n') {
+            println!();
+        }
+    }
+    if !output.artifacts.is_empty() {
+        println!("Artifacts:");
+        for path in &output.artifacts {
+            println!("  {}", path.display());
+        }
+    }
+    Ok(())
+}
+
+/// `wezterm-parallel note TEXT... [--task ID|--workspace NAME]`
+pub async fn run_note_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut task_id = None;
+    let mut workspace = None;
+    let mut text = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--task" | "-t" => {
+                task_id = Some(iter.next().ok_or("--task requires a task ID")?.clone())
+            }
+            "--workspace" | "-w" => {
+                workspace = Some(iter.next().ok_or("--workspace requires a name")?.clone())
+            }
+            "--" => text.extend(iter.by_ref().cloned()),
+            _ => text.push(arg.clone()),
+        }
+    }
+    if text.is_empty() {
+        return Err("Usage: note TEXT... [--task ID|--workspace NAME]".into());
+    }
+
+    let request = Message::NoteAdd {
+        text: text.join(" "),
+        task_id,
+        workspace,
+    };
+    match IpcClient::default().call(request).await? {
+        Message::NoteAddResponse {
+            task_id, workspace, ..
+        } => {
+            match (task_id, workspace) {
+                (Some(task_id), _) => println!("Note added to task {task_id}"),
+                (None, Some(workspace)) => {
+                    println!("Note added to the scratchpad of workspace {workspace}")
+                }
+                (None, None) => println!("Note added"),
+            }
+            Ok(())
+        }
+        other => print_status_response(other),
+    }
+}
+
+/// `timeline [--from DATE] [--to DATE] [--format csv|json|mermaid] [--output FILE]`
+pub async fn run_timeline_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut from = None;
+    let mut to = None;
+    let mut format = "csv".to_string();
+    let mut output = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("{name} requires a value"))
+        };
+        match arg.as_str() {
+            "--from" => from = Some(value("--from")?),
+            "--to" => to = Some(value("--to")?),
+            "--format" => format = value("--format")?,
+            "--output" | "-o" => output = Some(value("--output")?),
+            _ => {
+                return Err(
+                    "Usage: timeline [--from DATE] [--to DATE] [--format csv|json|mermaid] [--output FILE]"
+                        .into(),
+                )
+            }
+        }
+    }
+
+    let request = Message::ProductivityExport { format, from, to };
+    match IpcClient::default().call(request).await? {
+        Message::ProductivityExportResponse { content, .. } => {
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, content)?;
+                    println!("Timeline written to {path}");
+                }
+                None if content.ends_with('\n') => print!("{content}"),
+                None => println!("{content}"),
+            }
+            Ok(())
+        }
+        other => print_status_response(other),
+    }
+}
+
+/// `import FILE [--format csv|json] [--source NAME] [--dry-run]`
+pub async fn run_import_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = None;
+    let mut format = None;
+    let mut source = None;
+    let mut dry_run = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--format" => {
+                format = Some(iter.next().ok_or("--format requires csv or json")?.clone())
+            }
+            "--source" => source = Some(iter.next().ok_or("--source requires a name")?.clone()),
+            "--dry-run" | "-n" => dry_run = true,
+            _ if file.is_none() => file = Some(arg.clone()),
+            _ => return Err(format!("Unexpected argument '{arg}'").into()),
+        }
+    }
+    let Some(file) = file else {
+        return Err("Usage: import FILE [--format csv|json] [--source NAME] [--dry-run]".into());
+    };
+
+    // The file name tells the imported history apart, e.g. "toggl-2023.csv"
+    let source = source.unwrap_or_else(|| {
+        std::path::Path::new(&file)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| file.clone())
+    });
+    let request = Message::HistoryImport {
+        content: std::fs::read_to_string(&file)?,
+        format,
+        source: Some(source),
+        dry_run,
+    };
+    match IpcClient::default().call(request).await? {
+        Message::HistoryImportResponse { report } => {
+            print_import_report(&report);
+            Ok(())
+        }
+        other => print_status_response(other),
+    }
+}
+
+fn print_import_report(report: &ImportReport) {
+    let verb = if report.dry_run {
+        "Would import"
+    } else {
+        "Imported"
+    };
+    println!(
+        "{verb} {} sessions ({}) and {} tasks from {}",
+        report.sessions_imported,
+        crate::task::format_duration(std::time::Duration::from_secs(report.tracked_seconds)),
+        report.tasks_imported,
+        report.source
+    );
+    if let (Some(first), Some(last)) = (report.first_started_at, report.last_ended_at) {
+        let timezone = Timezone::default();
+        println!(
+            "  covering {} to {}",
+            timezone.format_date(first),
+            timezone.format_date(last)
+        );
+    }
+    if report.sessions_skipped + report.tasks_skipped > 0 {
+        println!(
+            "  skipped {} sessions and {} tasks already in the history or unfinished",
+            report.sessions_skipped, report.tasks_skipped
+        );
+    }
+    for error in &report.errors {
+        println!("  {error}");
+    }
+}
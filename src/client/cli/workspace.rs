@@ -0,0 +1,461 @@
+// WezTerm Multi-Process Development Framework - Workspace Commands
+// Workspaces, projects, templates, snapshots and task boards
+
+use super::{call_with_confirmation, print_status_response};
+use crate::client::IpcClient;
+use crate::config::Timezone;
+use crate::dashboard::BoardFormat;
+use crate::room::{ProjectManifest, SessionSnapshot};
+use crate::Message;
+use std::env;
+use std::path::Path;
+
+/// `wezterm-parallel workspace <create NAME [--template T] [--dir PATH]|list|switch NAME|delete NAME [--yes]|rename NAME NEW>`
+///
+/// `create` roots the workspace at the current directory unless `--dir` is given,
+/// so templates in the project's `.wezterm-parallel/templates` can be used.
+/// `delete` asks before going ahead when the server wants it confirmed,
+/// unless `--yes` is given.
+pub async fn run_workspace_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut assume_yes = false;
+    let request = match (args.first().map(|s| s.as_str()), args.get(1..).unwrap_or_default()) {
+        (Some("create"), [name, rest @ ..]) => {
+            let usage = "Usage: workspace create NAME [--template TEMPLATE] [--dir PATH]";
+            let mut template = "basic".to_string();
+            let mut project_dir = env::current_dir()?;
+            let mut flags = rest.iter();
+            while let Some(flag) = flags.next() {
+                match (flag.as_str(), flags.next()) {
+                    ("--template" | "-t", Some(value)) => template = value.clone(),
+                    ("--dir" | "-d", Some(value)) => project_dir = Path::new(value).canonicalize()?,
+                    _ => return Err(usage.into()),
+                }
+            }
+            Message::WorkspaceCreate {
+                name: name.clone(),
+                template,
+                project_dir: Some(project_dir.to_string_lossy().to_string()),
+                variables: Default::default(),
+            }
+        }
+        (Some("list") | None, []) => Message::WorkspaceList,
+        (Some("switch"), [name]) => Message::WorkspaceSwitch { name: name.clone() },
+        (Some("delete"), [name, flags @ ..])
+            if flags.iter().all(|flag| flag == "--yes" || flag == "-y") =>
+        {
+            assume_yes = !flags.is_empty();
+            Message::WorkspaceDelete { name: name.clone() }
+        }
+        (Some("rename"), [name, new_name]) => Message::WorkspaceRename {
+            name: name.clone(),
+            new_name: new_name.clone(),
+        },
+        (Some("drain"), []) => Message::WorkspaceDrainStatus,
+        (Some("drain"), [name, flags @ ..]) if flags.iter().all(|flag| flag == "--delete") => {
+            Message::WorkspaceDrain {
+                name: name.clone(),
+                delete_when_empty: !flags.is_empty(),
+            }
+        }
+        (Some("undrain"), [name]) => Message::WorkspaceUndrain { name: name.clone() },
+        (Some("git"), []) => Message::GitStatus { workspace: None },
+        (Some("git"), [name]) => Message::GitStatus {
+            workspace: Some(name.clone()),
+        },
+        _ => {
+            return Err(
+                "Usage: workspace <create NAME [--template T] [--dir PATH]|list|switch NAME|delete NAME [--yes]|rename NAME NEW|drain [NAME [--delete]]|undrain NAME|git [NAME]>"
+                    .into(),
+            )
+        }
+    };
+
+    match call_with_confirmation(&IpcClient::default(), request, assume_yes).await? {
+        Message::WorkspaceListResponse { workspaces } => {
+            println!(
+                "  {:<24} {:<16} {:>9} {:>6} {:>6}",
+                "NAME", "TEMPLATE", "PROCESSES", "TASKS", "PANES"
+            );
+            for workspace in workspaces {
+                println!(
+                    "{} {:<24} {:<16} {:>9} {:>6} {:>6}{}{}",
+                    if workspace.is_active { "*" } else { " " },
+                    workspace.name,
+                    workspace.template,
+                    workspace.process_count,
+                    workspace.task_count,
+                    workspace.pane_count,
+                    workspace
+                        .branch
+                        .map(|branch| format!("  on {branch}"))
+                        .unwrap_or_default(),
+                    if workspace.draining { "  draining" } else { "" }
+                );
+            }
+            Ok(())
+        }
+        Message::GitStatusResponse { statuses } => {
+            if statuses.is_empty() {
+                println!("No workspace is in a git repository");
+            }
+            for status in statuses {
+                let mut changes = Vec::new();
+                for (count, label) in [
+                    (status.staged, "staged"),
+                    (status.modified, "modified"),
+                    (status.untracked, "untracked"),
+                    (status.conflicted, "conflicted"),
+                ] {
+                    if count > 0 {
+                        changes.push(format!("{count} {label}"));
+                    }
+                }
+                if changes.is_empty() {
+                    changes.push("clean".to_string());
+                }
+                let mut tracking = String::new();
+                if let Some(ref upstream) = status.upstream {
+                    tracking = format!(" [{upstream} +{} -{}]", status.ahead, status.behind);
+                }
+                println!(
+                    "{:<24} {:<32} {}{}  {}{}",
+                    status.workspace,
+                    status.branch.as_deref().unwrap_or("(detached)"),
+                    changes.join(", "),
+                    tracking,
+                    status.root,
+                    if status.worktree { " (worktree)" } else { "" }
+                );
+            }
+            Ok(())
+        }
+        Message::WorkspaceDrainResponse { drains } => {
+            if drains.is_empty() {
+                println!("No workspace is draining");
+            }
+            let timezone = Timezone::Local;
+            for drain in drains {
+                println!(
+                    "{:<24} {} since {}: {} running, {} held back, {} processes{}",
+                    drain.workspace,
+                    if drain.drained { "drained" } else { "draining" },
+                    timezone.format(drain.since, "%Y-%m-%d %H:%M:%S"),
+                    drain.running_tasks,
+                    drain.queued_tasks,
+                    drain.processes,
+                    if drain.delete_when_empty {
+                        ", deleted once empty"
+                    } else {
+                        ""
+                    }
+                );
+            }
+            Ok(())
+        }
+        other => print_status_response(other),
+    }
+}
+
+/// `wezterm-parallel <up|down> PROJECT.yaml [--yes]`
+///
+/// `up` creates the workspaces of the manifest that do not exist yet,
+/// dependencies first, and stops at the first failure. `down` deletes them in
+/// reverse order.
+pub async fn run_project_command(
+    up: bool,
+    args: &[String],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (path, assume_yes) = match args {
+        [path] => (path, false),
+        [path, flag] if !up && (flag == "--yes" || flag == "-y") => (path, true),
+        _ => return Err("Usage: <up PROJECT.yaml|down PROJECT.yaml [--yes]>".into()),
+    };
+    let project = ProjectManifest::load(Path::new(path))?;
+    let mut order = project.startup_order()?;
+
+    let client = IpcClient::default();
+    let existing: Vec<String> = match client.call(Message::WorkspaceList).await? {
+        Message::WorkspaceListResponse { workspaces } => {
+            workspaces.into_iter().map(|w| w.name).collect()
+        }
+        other => return print_status_response(other),
+    };
+
+    if up {
+        for workspace in order {
+            if existing.contains(&workspace.name) {
+                println!("  {:<24} already up", workspace.name);
+                continue;
+            }
+            client
+                .call(Message::WorkspaceCreate {
+                    name: workspace.name.clone(),
+                    template: workspace.template.clone(),
+                    project_dir: workspace
+                        .dir
+                        .as_ref()
+                        .map(|dir| dir.to_string_lossy().to_string()),
+                    variables: project.variables_of(workspace),
+                })
+                .await
+                .map_err(|e| {
+                    format!(
+                        "Project '{}' stopped at '{}': {}",
+                        project.name, workspace.name, e
+                    )
+                })?;
+            println!("  {:<24} created ({})", workspace.name, workspace.template);
+        }
+        println!("Project '{}' is up", project.name);
+        return Ok(());
+    }
+
+    order.reverse();
+    let mut failed = 0;
+    for workspace in order {
+        if !existing.contains(&workspace.name) {
+            println!("  {:<24} not running", workspace.name);
+            continue;
+        }
+        let delete = Message::WorkspaceDelete {
+            name: workspace.name.clone(),
+        };
+        match call_with_confirmation(&client, delete, assume_yes).await {
+            Ok(_) => println!("  {:<24} deleted", workspace.name),
+            Err(e) => {
+                failed += 1;
+                eprintln!("  {:<24} {}", workspace.name, e);
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(format!(
+            "{failed} workspaces of project '{}' could not be deleted",
+            project.name
+        )
+        .into());
+    }
+    println!("Project '{}' is down", project.name);
+    Ok(())
+}
+
+/// `wezterm-parallel template <list|lint FILE...>`
+pub async fn run_template_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    match args.first().map(|s| s.as_str()) {
+        Some("list") | None => {}
+        Some("lint") => return run_template_lint(&args[1..]),
+        Some(other) => return Err(format!("Unknown template command: {other}").into()),
+    }
+
+    match IpcClient::default().call(Message::TemplateList).await? {
+        Message::TemplateListResponse { templates } => {
+            println!(
+                "{:<20} {:<20} {:>5}  DESCRIPTION",
+                "NAME", "LAYOUT", "PANES"
+            );
+            for template in templates {
+                println!(
+                    "{:<20} {:<20} {:>5}  {}",
+                    template.name, template.layout_type, template.pane_count, template.description
+                );
+            }
+            Ok(())
+        }
+        other => print_status_response(other),
+    }
+}
+
+/// `wezterm-parallel template lint FILE...`, locally without a running instance
+///
+/// Exits non-zero when a file does not parse or has an error-level finding.
+fn run_template_lint(files: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    use crate::room::{lint, LintSeverity, WorkspaceTemplate};
+
+    if files.is_empty() {
+        return Err("Usage: template lint FILE...".into());
+    }
+
+    let mut failed = false;
+    for file in files {
+        let content = std::fs::read_to_string(file)?;
+        let template: WorkspaceTemplate = match serde_yaml::from_str(&content) {
+            Ok(template) => template,
+            Err(e) => {
+                println!("{file}: error: invalid template: {e}");
+                failed = true;
+                continue;
+            }
+        };
+
+        for issue in lint::lint_source(&template, &content) {
+            let pane = issue
+                .pane
+                .as_deref()
+                .map(|pane| format!(" [{pane}]"))
+                .unwrap_or_default();
+            println!(
+                "{file}: {}: {}{pane}: {}",
+                issue.severity, issue.rule, issue.message
+            );
+            failed |= issue.severity == LintSeverity::Error;
+        }
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// `snapshot <save FILE|restore FILE [--dry-run]>`
+pub async fn run_snapshot_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let usage = "Usage: snapshot <save FILE|restore FILE [--dry-run]>";
+    match (
+        args.first().map(|s| s.as_str()),
+        args.get(1),
+        args.get(2).map(|s| s.as_str()),
+    ) {
+        (Some("save"), Some(file), None) => {
+            match IpcClient::default().call(Message::SessionSnapshot).await? {
+                Message::SessionSnapshotResponse { content } => {
+                    let snapshot = SessionSnapshot::parse(&content)?;
+                    snapshot.write_to(Path::new(file))?;
+                    println!(
+                        "Saved {} workspaces and {} unfinished tasks to {file}",
+                        snapshot.workspaces.len(),
+                        snapshot.tasks.len()
+                    );
+                    Ok(())
+                }
+                other => print_status_response(other),
+            }
+        }
+        (Some("restore"), Some(file), dry_run @ (None | Some("--dry-run") | Some("-n"))) => {
+            // Parsed locally first so a bad file fails before reaching the daemon
+            let snapshot = SessionSnapshot::read_from(Path::new(file))?;
+            let request = Message::SessionRestore {
+                content: snapshot.render()?,
+                dry_run: dry_run.is_some(),
+            };
+            match IpcClient::default().call(request).await? {
+                Message::SessionRestoreResponse { report } => {
+                    println!(
+                        "{} {} workspaces, {} processes and {} tasks",
+                        if report.dry_run {
+                            "Would restore"
+                        } else {
+                            "Restored"
+                        },
+                        report.workspaces_created.len(),
+                        report.processes_started,
+                        report.tasks_restored
+                    );
+                    if !report.workspaces_skipped.is_empty() {
+                        println!(
+                            "  skipped existing workspaces: {}",
+                            report.workspaces_skipped.join(", ")
+                        );
+                    }
+                    if report.tasks_skipped > 0 {
+                        println!("  skipped {} tasks already known", report.tasks_skipped);
+                    }
+                    for warning in &report.warnings {
+                        println!("  {warning}");
+                    }
+                    Ok(())
+                }
+                other => print_status_response(other),
+            }
+        }
+        _ => Err(usage.into()),
+    }
+}
+
+/// `board <export [--board ID] [--format json|yaml] [--output FILE]|import FILE [--format json|yaml] [--dry-run]>`
+pub async fn run_board_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let usage = "Usage: board <export [--board ID] [--format json|yaml] [--output FILE]|import FILE [--format json|yaml] [--dry-run]>";
+    let mut board = None;
+    let mut format = None;
+    let mut output = None;
+    let mut file = None;
+    let mut dry_run = false;
+
+    let export = match args.first().map(|s| s.as_str()) {
+        Some("export") => true,
+        Some("import") => false,
+        _ => return Err(usage.into()),
+    };
+    let mut iter = args[1..].iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("{name} requires a value"))
+        };
+        match arg.as_str() {
+            "--format" => format = Some(value("--format")?),
+            "--board" if export => board = Some(value("--board")?),
+            "--output" | "-o" if export => output = Some(value("--output")?),
+            "--dry-run" | "-n" if !export => dry_run = true,
+            _ if !export && file.is_none() => file = Some(arg.clone()),
+            _ => return Err(usage.into()),
+        }
+    }
+
+    if export {
+        // The file extension picks the format when none is given
+        if let (None, Some(path)) = (&format, &output) {
+            format = Path::new(path)
+                .extension()
+                .map(|ext| ext.to_string_lossy().into_owned())
+                .filter(|ext| ext.parse::<BoardFormat>().is_ok());
+        }
+        match IpcClient::default()
+            .call(Message::BoardExport { board, format })
+            .await?
+        {
+            Message::BoardExportResponse { content, .. } => {
+                match output {
+                    Some(path) => {
+                        std::fs::write(&path, content)?;
+                        println!("Board written to {path}");
+                    }
+                    None if content.ends_with('\n') => print!("{content}"),
+                    None => println!("{content}"),
+                }
+                Ok(())
+            }
+            other => print_status_response(other),
+        }
+    } else {
+        let Some(file) = file else {
+            return Err(usage.into());
+        };
+        let request = Message::BoardImport {
+            content: std::fs::read_to_string(&file)?,
+            format,
+            dry_run,
+        };
+        match IpcClient::default().call(request).await? {
+            Message::BoardImportResponse { report } => {
+                println!(
+                    "{} board '{}'{}: {} tasks created, {} updated",
+                    if report.dry_run {
+                        "Would import"
+                    } else {
+                        "Imported"
+                    },
+                    report.board_id,
+                    if report.board_created { " (new)" } else { "" },
+                    report.tasks_created,
+                    report.tasks_updated
+                );
+                for error in &report.errors {
+                    println!("  {error}");
+                }
+                Ok(())
+            }
+            other => print_status_response(other),
+        }
+    }
+}
@@ -0,0 +1,454 @@
+// WezTerm Multi-Process Development Framework - IPC Client
+// Sends requests to a running framework instance over the IPC socket and reassembles streamed replies
+
+pub mod cli;
+
+use crate::ipc::{
+    transport, BoxedIpcStream, ChunkAssembler, ErrorCode, FrameDecoder, IpcError, Request, Response,
+};
+use crate::Message;
+use serde::de::DeserializeOwned;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// Default time to wait for a response
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Client for the framework's IPC socket
+#[derive(Debug, Clone)]
+pub struct IpcClient {
+    socket_path: PathBuf,
+    timeout: Duration,
+    token: Option<String>,
+}
+
+impl Default for IpcClient {
+    /// Client for the default socket, using the API token from the environment if set
+    fn default() -> Self {
+        let client = Self::new(transport::default_socket_path());
+        match std::env::var(crate::auth::TOKEN_ENV_VAR) {
+            Ok(token) if !token.is_empty() => client.with_token(token),
+            _ => client,
+        }
+    }
+}
+
+impl IpcClient {
+    /// Create a client for the given socket path
+    pub fn new(socket_path: impl AsRef<Path>) -> Self {
+        Self {
+            socket_path: socket_path.as_ref().to_path_buf(),
+            timeout: DEFAULT_TIMEOUT,
+            token: None,
+        }
+    }
+
+    /// Set response timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Send an API token with every `call`
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Socket path this client connects to
+    pub fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+
+    /// Send a bare message and wait for the response
+    ///
+    /// Errors are reported by the server as `Message::StatusUpdate`; prefer
+    /// [`IpcClient::call`] when the outcome needs to be checked.
+    pub async fn request(&self, message: &Message) -> Result<Message, String> {
+        let payload = serde_json::to_vec(message).map_err(|e| e.to_string())?;
+        self.send(&payload).await
+    }
+
+    /// Send a message in a request envelope and return the typed outcome
+    ///
+    /// Large list replies streamed in chunks are reassembled into one message.
+    pub async fn call(&self, message: Message) -> Result<Message, IpcError> {
+        self.call_request(Request::new(message)).await
+    }
+
+    /// Re-send a destructive operation with the confirmation token the server
+    /// returned in its `confirmation_required` error
+    pub async fn call_confirmed(
+        &self,
+        message: Message,
+        token: impl Into<String>,
+    ) -> Result<Message, IpcError> {
+        self.call_request(Request::new(message).with_confirmation(token))
+            .await
+    }
+
+    async fn call_request(&self, request: Request) -> Result<Message, IpcError> {
+        let mut responses = self.send_request(request).await?;
+        let mut assembler = ChunkAssembler::new();
+        loop {
+            let response = match responses.next_response().await {
+                Some(response) => response?,
+                None => {
+                    return Err(client_error(
+                        ErrorCode::Internal,
+                        "Streamed response ended without its last chunk".to_string(),
+                    ))
+                }
+            };
+            if let Some(message) = assembler.push(response)? {
+                return Ok(message);
+            }
+        }
+    }
+
+    /// Send a message in a request envelope and read the reply chunk by chunk
+    ///
+    /// Each item is one part of the reply; replies that are not streamed
+    /// yield a single item. Dropping the stream closes the connection, which
+    /// stops the server from sending the remaining chunks.
+    pub async fn call_stream(&self, message: Message) -> Result<ResponseStream, IpcError> {
+        self.send_request(Request::new(message)).await
+    }
+
+    async fn send_request(&self, request: Request) -> Result<ResponseStream, IpcError> {
+        let mut request = request.with_streaming();
+        request.token = self.token.clone();
+
+        let payload = serde_json::to_vec(&request)
+            .map_err(|e| client_error(ErrorCode::InvalidRequest, e.to_string()))?;
+        let stream = self
+            .connect_and_send(&payload)
+            .await
+            .map_err(|e| client_error(ErrorCode::Unavailable, e))?;
+
+        Ok(ResponseStream {
+            stream,
+            frames: FrameDecoder::new(usize::MAX),
+            request_id: request.request_id,
+            timeout: self.timeout,
+            next_seq: 0,
+            finished: false,
+        })
+    }
+
+    async fn send<T: DeserializeOwned>(&self, payload: &[u8]) -> Result<T, String> {
+        let mut stream = self.connect_and_send(payload).await?;
+        tokio::time::timeout(self.timeout, read_response(&mut stream))
+            .await
+            .map_err(|_| "Timed out waiting for response".to_string())?
+    }
+
+    async fn connect_and_send(&self, payload: &[u8]) -> Result<BoxedIpcStream, String> {
+        let mut stream = transport::connect(&self.socket_path).await.map_err(|e| {
+            format!(
+                "Failed to connect to {} (is wezterm-parallel running?): {e}",
+                self.socket_path.display()
+            )
+        })?;
+
+        stream
+            .write_all(payload)
+            .await
+            .map_err(|e| format!("Failed to send request: {e}"))?;
+        Ok(stream)
+    }
+}
+
+/// Reply to [`IpcClient::call_stream`], read one chunk at a time
+#[derive(Debug)]
+pub struct ResponseStream {
+    stream: BoxedIpcStream,
+    frames: FrameDecoder,
+    request_id: String,
+    timeout: Duration,
+    next_seq: u32,
+    finished: bool,
+}
+
+impl ResponseStream {
+    /// Next part of the reply, or `None` once the last chunk has been read
+    pub async fn next(&mut self) -> Option<Result<Message, IpcError>> {
+        let response = self.next_response().await?;
+        Some(response.and_then(Response::into_result))
+    }
+
+    /// Next response frame, checked against the request ID and chunk order
+    async fn next_response(&mut self) -> Option<Result<Response, IpcError>> {
+        if self.finished {
+            return None;
+        }
+        let result = match tokio::time::timeout(self.timeout, self.read_frame()).await {
+            Ok(result) => result.and_then(|reply| self.check(reply)),
+            Err(_) => Err(client_error(
+                ErrorCode::Timeout,
+                "Timed out waiting for response".to_string(),
+            )),
+        };
+        // Errors end the stream, as does the last (or only) chunk
+        self.finished = match &result {
+            Ok(response) => !response.chunk.is_some_and(|chunk| chunk.more),
+            Err(_) => true,
+        };
+        Some(result)
+    }
+
+    async fn read_frame(&mut self) -> Result<serde_json::Value, IpcError> {
+        let mut chunk = [0u8; 4096];
+        loop {
+            let frame = self
+                .frames
+                .next_frame()
+                .map_err(|e| client_error(ErrorCode::Internal, e.message))?;
+            if let Some(frame) = frame {
+                return serde_json::from_slice(&frame).map_err(|e| {
+                    client_error(ErrorCode::Internal, format!("Invalid response: {e}"))
+                });
+            }
+
+            let n = self.stream.read(&mut chunk).await.map_err(|e| {
+                client_error(
+                    ErrorCode::Unavailable,
+                    format!("Failed to read response: {e}"),
+                )
+            })?;
+            if n == 0 {
+                return Err(client_error(
+                    ErrorCode::Unavailable,
+                    "Connection closed before a response was received".to_string(),
+                ));
+            }
+            self.frames.push(&chunk[..n]);
+        }
+    }
+
+    fn check(&mut self, reply: serde_json::Value) -> Result<Response, IpcError> {
+        // Input the server could not attribute to a request (oversized or
+        // unparsable) and servers predating the envelope reply with a bare
+        // StatusUpdate
+        if reply.get("request_id").is_none() {
+            return Err(match serde_json::from_value(reply) {
+                Ok(Message::StatusUpdate { process_id, status }) => {
+                    IpcError::new(ErrorCode::Internal, process_id, status)
+                }
+                Ok(other) => client_error(
+                    ErrorCode::Internal,
+                    format!("Unexpected response: {other:?}"),
+                ),
+                Err(e) => client_error(ErrorCode::Internal, format!("Invalid response: {e}")),
+            });
+        }
+        let response: Response = serde_json::from_value(reply)
+            .map_err(|e| client_error(ErrorCode::Internal, format!("Invalid response: {e}")))?;
+
+        if response.request_id != self.request_id {
+            return Err(client_error(
+                ErrorCode::Internal,
+                format!(
+                    "Response ID {} does not match request ID {}",
+                    response.request_id, self.request_id
+                ),
+            ));
+        }
+        if let Some(chunk) = response.chunk {
+            if chunk.seq != self.next_seq {
+                return Err(client_error(
+                    ErrorCode::Internal,
+                    format!(
+                        "Expected chunk {} but received chunk {}",
+                        self.next_seq, chunk.seq
+                    ),
+                ));
+            }
+            self.next_seq += 1;
+        }
+        Ok(response)
+    }
+}
+
+fn client_error(code: ErrorCode, message: String) -> IpcError {
+    IpcError::new(code, "client", message)
+}
+
+/// Read until a complete JSON value has been received
+async fn read_response<T: DeserializeOwned>(stream: &mut BoxedIpcStream) -> Result<T, String> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    loop {
+        let n = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|e| format!("Failed to read response: {e}"))?;
+        if n == 0 {
+            return Err("Connection closed before a response was received".to_string());
+        }
+        buffer.extend_from_slice(&chunk[..n]);
+
+        match serde_json::from_slice::<T>(&buffer) {
+            Ok(message) => return Ok(message),
+            Err(e) if e.is_eof() => continue,
+            Err(e) => return Err(format!("Invalid response: {e}")),
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use tokio::net::UnixListener;
+
+    #[tokio::test]
+    async fn test_request_roundtrip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let socket_path = dir.path().join("test.sock");
+        let listener = UnixListener::bind(&socket_path).unwrap();
+
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buffer = [0u8; 1024];
+            let n = stream.read(&mut buffer).await.unwrap();
+            let message: Message = serde_json::from_slice(&buffer[..n]).unwrap();
+            assert_eq!(message, Message::Ping);
+            stream
+                .write_all(&serde_json::to_vec(&Message::Pong).unwrap())
+                .await
+                .unwrap();
+        });
+
+        let client = IpcClient::new(&socket_path);
+        let response = client.request(&Message::Ping).await.unwrap();
+        assert_eq!(response, Message::Pong);
+    }
+
+    #[tokio::test]
+    async fn test_request_without_server() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let client = IpcClient::new(dir.path().join("missing.sock"));
+
+        let error = client.request(&Message::Ping).await.unwrap_err();
+        assert!(error.contains("Failed to connect"));
+    }
+
+    #[tokio::test]
+    async fn test_call_correlates_envelope() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let socket_path = dir.path().join("test.sock");
+        let listener = UnixListener::bind(&socket_path).unwrap();
+
+        tokio::spawn(async move {
+            for reply_ok in [true, false] {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buffer = [0u8; 1024];
+                let n = stream.read(&mut buffer).await.unwrap();
+                let request: Request = serde_json::from_slice(&buffer[..n]).unwrap();
+                let result = if reply_ok {
+                    Ok(Message::Pong)
+                } else {
+                    Err(IpcError::new(
+                        ErrorCode::NotFound,
+                        "task_manager",
+                        "missing",
+                    ))
+                };
+                let response = Response::new(request.request_id, result);
+                stream
+                    .write_all(&serde_json::to_vec(&response).unwrap())
+                    .await
+                    .unwrap();
+            }
+        });
+
+        let client = IpcClient::new(&socket_path);
+        assert_eq!(client.call(Message::Ping).await.unwrap(), Message::Pong);
+
+        let error = client.call(Message::Ping).await.unwrap_err();
+        assert_eq!(error.code, ErrorCode::NotFound);
+        assert_eq!(error.component, "task_manager");
+    }
+
+    fn templates(count: usize) -> Message {
+        Message::TemplateListResponse {
+            templates: (0..count)
+                .map(|i| crate::TemplateInfo {
+                    name: format!("template-{i}"),
+                    description: "x".repeat(200),
+                    author: "System".to_string(),
+                    version: "1.0".to_string(),
+                    created_at: "2024-01-01T00:00:00Z".to_string(),
+                    layout_type: "Grid".to_string(),
+                    pane_count: 4,
+                    auto_start_processes: false,
+                })
+                .collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_call_reassembles_chunks() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let socket_path = dir.path().join("test.sock");
+        let listener = UnixListener::bind(&socket_path).unwrap();
+
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buffer = [0u8; 1024];
+            let n = stream.read(&mut buffer).await.unwrap();
+            let request: Request = serde_json::from_slice(&buffer[..n]).unwrap();
+            assert!(request.stream);
+            let frames =
+                crate::ipc::encode_stream(request.request_id, Ok(templates(40)), 2048).unwrap();
+            assert!(frames.len() > 1);
+            for frame in frames {
+                stream.write_all(&frame).await.unwrap();
+            }
+        });
+
+        let client = IpcClient::new(&socket_path);
+        let reply = client.call(Message::TemplateList).await.unwrap();
+        assert_eq!(reply, templates(40));
+    }
+
+    #[tokio::test]
+    async fn test_call_stream_early_termination() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let socket_path = dir.path().join("test.sock");
+        let listener = UnixListener::bind(&socket_path).unwrap();
+        let (dropped_tx, dropped_rx) = tokio::sync::oneshot::channel();
+
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buffer = [0u8; 1024];
+            let n = stream.read(&mut buffer).await.unwrap();
+            let request: Request = serde_json::from_slice(&buffer[..n]).unwrap();
+            let frames =
+                crate::ipc::encode_stream(request.request_id, Ok(templates(40)), 2048).unwrap();
+            stream.write_all(&frames[0]).await.unwrap();
+
+            // The rest is sent after the client has gone away
+            dropped_rx.await.unwrap();
+            for frame in &frames[1..] {
+                stream.write_all(frame).await?;
+            }
+            Ok::<_, std::io::Error>(())
+        });
+
+        let client = IpcClient::new(&socket_path);
+        let mut responses = client.call_stream(Message::TemplateList).await.unwrap();
+        match responses.next().await.unwrap().unwrap() {
+            Message::TemplateListResponse { templates } => {
+                assert!(!templates.is_empty() && templates.len() < 40)
+            }
+            other => panic!("unexpected reply: {other:?}"),
+        }
+        drop(responses);
+        dropped_tx.send(()).unwrap();
+
+        assert!(server.await.unwrap().is_err());
+    }
+}
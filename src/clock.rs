@@ -0,0 +1,115 @@
+// WezTerm Multi-Process Development Framework - Clock
+// Wall-clock timestamps that survive clock adjustments, and monotonic durations
+//
+// The system clock can jump backwards (NTP corrections, a suspended VM
+// resyncing), so `later - earlier` on Unix timestamps may underflow. Code
+// that stores timestamps uses `unix_secs` / `unix_millis`, which never go
+// backwards within the process, and subtracts them with `elapsed_secs`.
+// Durations measured inside the process use `Stopwatch`, which runs on the
+// monotonic clock.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+static LAST_SECS: AtomicU64 = AtomicU64::new(0);
+static LAST_MILLIS: AtomicU64 = AtomicU64::new(0);
+
+/// Current Unix time in seconds, never earlier than a previous call
+pub fn unix_secs() -> u64 {
+    not_before(&LAST_SECS, wall_clock().as_secs())
+}
+
+/// Current Unix time in milliseconds, never earlier than a previous call
+pub fn unix_millis() -> u64 {
+    not_before(&LAST_MILLIS, wall_clock().as_millis() as u64)
+}
+
+/// Seconds from `earlier` to `later`; zero when the clock went backwards in between
+pub fn elapsed_secs(earlier: u64, later: u64) -> u64 {
+    later.saturating_sub(earlier)
+}
+
+/// Seconds since a timestamp taken with [`unix_secs`]
+pub fn secs_since(timestamp: u64) -> u64 {
+    elapsed_secs(timestamp, unix_secs())
+}
+
+/// Duration measured on the monotonic clock, anchored to the wall-clock
+/// time it was started at
+#[derive(Debug, Clone, Copy)]
+pub struct Stopwatch {
+    started: Instant,
+    started_at: u64,
+}
+
+impl Stopwatch {
+    pub fn start() -> Self {
+        Self {
+            started: Instant::now(),
+            started_at: unix_secs(),
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Unix time the stopwatch was started at
+    pub fn started_at(&self) -> u64 {
+        self.started_at
+    }
+
+    /// Wall-clock time now, following the monotonic clock from the start
+    pub fn now(&self) -> u64 {
+        self.started_at + self.elapsed().as_secs()
+    }
+}
+
+impl Default for Stopwatch {
+    fn default() -> Self {
+        Self::start()
+    }
+}
+
+fn wall_clock() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+/// `now`, or the latest value returned before when the clock went backwards
+fn not_before(last: &AtomicU64, now: u64) -> u64 {
+    let previous = last.fetch_max(now, Ordering::Relaxed);
+    previous.max(now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timestamps_do_not_go_backwards() {
+        let last = AtomicU64::new(0);
+        assert_eq!(not_before(&last, 1_000), 1_000);
+
+        // NTP moved the clock back a minute: time stands still until it catches up
+        assert_eq!(not_before(&last, 940), 1_000);
+        assert_eq!(not_before(&last, 999), 1_000);
+        assert_eq!(not_before(&last, 1_005), 1_005);
+
+        let first = unix_millis();
+        assert!(unix_millis() >= first);
+        assert!(unix_secs() > 0);
+    }
+
+    #[test]
+    fn test_elapsed_saturates_on_clock_skew() {
+        assert_eq!(elapsed_secs(100, 160), 60);
+        assert_eq!(elapsed_secs(160, 100), 0);
+        assert_eq!(secs_since(u64::MAX), 0);
+
+        let stopwatch = Stopwatch::start();
+        assert!(stopwatch.now() >= stopwatch.started_at());
+        assert!(stopwatch.elapsed() < Duration::from_secs(60));
+    }
+}
@@ -275,6 +275,11 @@ impl ConfigLoader {
                 "workspace.max_workspaces",
             ),
             ("WEZTERM_MULTI_DEV_LOG_LEVEL", "logging.level"),
+            ("WEZTERM_MULTI_DEV_TELEMETRY", "telemetry.enabled"),
+            ("WEZTERM_MULTI_DEV_TELEMETRY_ENDPOINT", "telemetry.endpoint"),
+            ("WEZTERM_MULTI_DEV_TIMEZONE", "timezone"),
+            ("WEZTERM_MULTI_DEV_LANGUAGE", "language"),
+            ("WEZTERM_MULTI_DEV_AUTH", "auth.enabled"),
             (
                 "WEZTERM_MULTI_DEV_MAX_PROCESSES",
                 "process.max_processes_per_workspace",
@@ -325,6 +330,26 @@ impl ConfigLoader {
                     ConfigError::Environment(format!("Invalid value for {path}: {value}"))
                 })?;
             }
+            "server.max_message_size" => {
+                config.server.max_message_size = value.parse().map_err(|_| {
+                    ConfigError::Environment(format!("Invalid value for {path}: {value}"))
+                })?;
+            }
+            "server.stream_chunk_bytes" => {
+                config.server.stream_chunk_bytes = value.parse().map_err(|_| {
+                    ConfigError::Environment(format!("Invalid value for {path}: {value}"))
+                })?;
+            }
+            "server.rate_limit_per_sec" => {
+                config.server.rate_limit_per_sec = value.parse().map_err(|_| {
+                    ConfigError::Environment(format!("Invalid value for {path}: {value}"))
+                })?;
+            }
+            "server.rate_limit_burst" => {
+                config.server.rate_limit_burst = value.parse().map_err(|_| {
+                    ConfigError::Environment(format!("Invalid value for {path}: {value}"))
+                })?;
+            }
             "workspace.max_workspaces" => {
                 config.workspace.max_workspaces = value.parse().map_err(|_| {
                     ConfigError::Environment(format!("Invalid value for {path}: {value}"))
@@ -342,6 +367,23 @@ impl ConfigLoader {
                     ConfigError::Environment(format!("Invalid value for {path}: {value}"))
                 })?;
             }
+            "telemetry.enabled" => {
+                config.telemetry.enabled = value.parse().map_err(|_| {
+                    ConfigError::Environment(format!("Invalid value for {path}: {value}"))
+                })?;
+            }
+            "telemetry.endpoint" => config.telemetry.endpoint = Some(value.to_string()),
+            "auth.enabled" => {
+                config.auth.enabled = value.parse().map_err(|_| {
+                    ConfigError::Environment(format!("Invalid value for {path}: {value}"))
+                })?;
+            }
+            "timezone" => {
+                config.timezone = value.parse().map_err(ConfigError::Environment)?;
+            }
+            "language" => {
+                config.language = value.parse().map_err(ConfigError::Environment)?;
+            }
             _ => {
                 let unknown_context = LogContext::new("config", "unknown_override_path")
                     .with_metadata("path", serde_json::json!(path));
@@ -407,6 +449,24 @@ impl ConfigLoader {
             ));
         }
 
+        if config.telemetry.enabled {
+            match config.telemetry.endpoint.as_deref() {
+                Some(endpoint)
+                    if endpoint.starts_with("https://") || endpoint.starts_with("http://") => {}
+                _ => {
+                    return Err(ConfigError::Validation(
+                        "Telemetry is enabled but telemetry.endpoint is not an http(s) URL"
+                            .to_string(),
+                    ));
+                }
+            }
+            if config.telemetry.interval_hours == 0 {
+                return Err(ConfigError::Validation(
+                    "Telemetry interval must be greater than 0".to_string(),
+                ));
+            }
+        }
+
         let valid_positions = ["left", "right", "top", "bottom"];
         if !valid_positions.contains(&config.ui.dashboard.position.as_str()) {
             return Err(ConfigError::Validation(format!(
@@ -481,7 +541,10 @@ mod tests {
         let loader = ConfigLoader::with_search_paths(vec![]); // Empty search paths to force default
         let config = loader.load().unwrap();
 
-        assert_eq!(config.server.socket_path, "/tmp/wezterm-parallel.sock");
+        assert_eq!(
+            config.server.socket_path,
+            crate::ipc::default_socket_path().to_string_lossy()
+        );
         assert_eq!(config.workspace.max_workspaces, 8);
 
         // Restore environment variable
@@ -3,8 +3,11 @@
 
 pub mod hot_reload;
 pub mod loader;
+pub mod timezone;
 pub mod validator;
 
+pub use timezone::Timezone;
+
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -29,6 +32,87 @@ pub struct Config {
 
     /// Plugin configuration
     pub plugins: HashMap<String, PluginConfig>,
+
+    /// Usage telemetry configuration (disabled unless explicitly enabled)
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+
+    /// Timezone for daily summaries, report ranges, schedules and displayed timestamps
+    #[serde(default)]
+    pub timezone: Timezone,
+
+    /// Language of alerts, notifications and generated reports (`en` or `ja`);
+    /// dashboard clients may pick their own
+    #[serde(default)]
+    pub language: crate::i18n::Language,
+
+    /// Weekly time budgets per task category or workspace
+    #[serde(default)]
+    pub budgets: Vec<crate::task::TimeBudget>,
+
+    /// Task ID strategy and prefix of short task IDs (`WP-123`)
+    #[serde(default)]
+    pub task_ids: crate::task::TaskIdConfig,
+
+    /// Longest each task pipeline stage may take before an alert
+    #[serde(default)]
+    pub latency_budgets: Vec<crate::task::LatencyBudget>,
+
+    /// Urgent tasks preempting lower-priority running tasks
+    #[serde(default)]
+    pub preemption: crate::task::PreemptionConfig,
+
+    /// Client authentication and IPC socket permissions
+    #[serde(default)]
+    pub auth: crate::auth::AuthConfig,
+
+    /// Focus mode: deferred task priorities and processes paused while focusing
+    #[serde(default)]
+    pub focus: crate::focus::FocusConfig,
+
+    /// Metrics collection and history retention
+    #[serde(default)]
+    pub metrics: crate::metrics::MetricsConfig,
+
+    /// File sync watch roots, ignore patterns and per-workspace switches
+    #[serde(default)]
+    pub sync: crate::sync::SyncConfig,
+
+    /// Outbound webhooks for workspace events
+    #[serde(default)]
+    pub bridge: crate::bridge::BridgeConfig,
+
+    /// Scripts run on workspace events
+    #[serde(default)]
+    pub hooks: crate::hooks::HooksConfig,
+
+    /// Repository detection and per-workspace worktrees
+    #[serde(default)]
+    pub git: crate::git::GitConfig,
+
+    /// GitHub issues imported as tasks
+    #[serde(default)]
+    pub github: crate::github::GithubConfig,
+
+    /// System metrics, health checks, alert thresholds and notification channels
+    #[serde(default)]
+    pub monitoring: crate::monitoring::MonitoringConfig,
+
+    /// Memory sampling of the daemon and its processes, and leak mitigation
+    #[serde(default)]
+    pub memory: crate::performance::memory::MemoryConfig,
+
+    /// Lazy start of subsystems, memory limit and data cache
+    #[serde(default)]
+    pub performance: crate::performance::PerformanceConfig,
+
+    /// Peer daemons whose workspaces, tasks and metrics are federated
+    #[serde(default)]
+    pub federation: crate::federation::FederationConfig,
+
+    /// Journal of state-changing requests and dashboard actions
+    #[serde(default)]
+    pub audit: crate::audit::AuditConfig,
 }
 
 /// Server configuration
@@ -48,6 +132,43 @@ pub struct ServerConfig {
 
     /// Health check interval in seconds
     pub health_check_interval: u64,
+
+    /// Largest IPC message accepted, in bytes
+    #[serde(default = "default_max_message_size")]
+    pub max_message_size: usize,
+
+    /// Messages per second allowed on one IPC connection (0 = unlimited)
+    #[serde(default = "default_rate_limit_per_sec")]
+    pub rate_limit_per_sec: u32,
+
+    /// Messages a connection may send in a burst before the rate applies
+    #[serde(default = "default_rate_limit_burst")]
+    pub rate_limit_burst: u32,
+
+    /// Size of one frame when a list reply is streamed in chunks, in bytes
+    #[serde(default = "default_stream_chunk_bytes")]
+    pub stream_chunk_bytes: usize,
+
+    /// Record all IPC requests and replies to this JSON Lines file, for
+    /// `wezterm-parallel replay`
+    #[serde(default)]
+    pub record_session: Option<String>,
+}
+
+fn default_max_message_size() -> usize {
+    64 * 1024
+}
+
+fn default_stream_chunk_bytes() -> usize {
+    crate::ipc::stream::DEFAULT_CHUNK_BYTES
+}
+
+fn default_rate_limit_per_sec() -> u32 {
+    20
+}
+
+fn default_rate_limit_burst() -> u32 {
+    50
 }
 
 /// Workspace configuration
@@ -67,6 +188,25 @@ pub struct WorkspaceConfig {
 
     /// Workspace templates directory
     pub templates_dir: PathBuf,
+
+    /// Create the template's panes in the terminal when a workspace is
+    /// created
+    #[serde(default)]
+    pub apply_layout: bool,
+
+    /// Terminal the panes are created in (`wezterm` or `tmux`)
+    #[serde(default)]
+    pub terminal_backend: crate::room::TerminalBackendKind,
+}
+
+impl WorkspaceConfig {
+    /// Templates directory with a leading `~` expanded to the home directory
+    pub fn templates_path(&self) -> PathBuf {
+        match (self.templates_dir.strip_prefix("~"), dirs::home_dir()) {
+            (Ok(rest), Some(home)) => home.join(rest),
+            _ => self.templates_dir.clone(),
+        }
+    }
 }
 
 /// Process configuration
@@ -92,6 +232,10 @@ pub struct ProcessConfig {
 
     /// Working directory template
     pub working_dir_template: String,
+
+    /// Provider of the `${secret:NAME}` references in `environment`
+    #[serde(default)]
+    pub secrets: crate::secrets::SecretsConfig,
 }
 
 /// UI configuration
@@ -208,14 +352,45 @@ pub struct PluginConfig {
     pub priority: u32,
 }
 
+/// Telemetry configuration
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct TelemetryConfig {
+    /// Opt-in flag; nothing is collected or sent while false
+    pub enabled: bool,
+
+    /// Endpoint receiving the JSON report
+    pub endpoint: Option<String>,
+
+    /// Report interval in hours
+    pub interval_hours: u64,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: None,
+            interval_hours: 24,
+        }
+    }
+}
+
 impl Default for ServerConfig {
     fn default() -> Self {
         Self {
-            socket_path: "/tmp/wezterm-parallel.sock".to_string(),
+            socket_path: crate::ipc::default_socket_path()
+                .to_string_lossy()
+                .to_string(),
             max_connections: 100,
             connection_timeout: 30,
             enable_metrics: true,
             health_check_interval: 10,
+            max_message_size: default_max_message_size(),
+            rate_limit_per_sec: default_rate_limit_per_sec(),
+            rate_limit_burst: default_rate_limit_burst(),
+            stream_chunk_bytes: default_stream_chunk_bytes(),
+            record_session: None,
         }
     }
 }
@@ -228,6 +403,8 @@ impl Default for WorkspaceConfig {
             state_path: PathBuf::from("~/.config/wezterm-parallel/workspaces.json"),
             auto_save_interval: 30,
             templates_dir: PathBuf::from("~/.config/wezterm-parallel/templates"),
+            apply_layout: false,
+            terminal_backend: Default::default(),
         }
     }
 }
@@ -242,6 +419,7 @@ impl Default for ProcessConfig {
             max_restart_attempts: 3,
             environment: HashMap::new(),
             working_dir_template: "~/projects/{{workspace_name}}".to_string(),
+            secrets: Default::default(),
         }
     }
 }
@@ -285,6 +463,43 @@ impl Default for KeybindingConfig {
     }
 }
 
+impl ProcessConfig {
+    /// Settings of the daemon's process manager
+    pub fn manager_config(&self) -> crate::process::ProcessConfig {
+        let defaults = crate::process::ProcessConfig::default();
+        crate::process::ProcessConfig {
+            max_processes_per_workspace: Some(self.max_processes_per_workspace),
+            health_check_interval_secs: self.health_check_interval,
+            max_restart_attempts: self.max_restart_attempts,
+            default_restart_policy: if self.auto_restart {
+                crate::process::manager::RestartPolicy::OnFailureWithLimit(
+                    self.max_restart_attempts,
+                )
+            } else {
+                crate::process::manager::RestartPolicy::Never
+            },
+            environment_vars: defaults
+                .environment_vars
+                .into_iter()
+                .chain(self.environment.clone())
+                .collect(),
+            secrets: self.secrets.clone(),
+            ..defaults
+        }
+    }
+}
+
+impl LoggingConfig {
+    /// Log file with a leading `~` expanded to the home directory
+    pub fn log_file_path(&self) -> Option<PathBuf> {
+        let path = self.file_path.as_ref()?;
+        match (path.strip_prefix("~"), dirs::home_dir()) {
+            (Ok(rest), Some(home)) => Some(home.join(rest)),
+            _ => Some(path.clone()),
+        }
+    }
+}
+
 impl Default for LoggingConfig {
     fn default() -> Self {
         Self {
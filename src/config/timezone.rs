@@ -0,0 +1,238 @@
+// WezTerm Multi-Process Development Framework - Timezone Handling
+// Resolves the configured timezone used for daily bucketing, schedules and displayed timestamps
+
+use chrono::{
+    DateTime, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, Offset, TimeZone, Utc,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Timezone setting
+///
+/// Accepts `local` (the OS timezone, honouring `TZ`), `UTC`, or a fixed
+/// offset such as `+09:00` / `-0530`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Timezone {
+    /// Operating system timezone
+    #[default]
+    Local,
+
+    /// Coordinated Universal Time
+    Utc,
+
+    /// Fixed offset in seconds east of UTC
+    Fixed(i32),
+}
+
+impl Timezone {
+    /// UTC offset in effect at the given Unix timestamp
+    pub fn offset_at(&self, timestamp: u64) -> FixedOffset {
+        let utc = utc_datetime(timestamp).naive_utc();
+        match self {
+            Timezone::Local => Local.offset_from_utc_datetime(&utc).fix(),
+            Timezone::Utc => Utc.fix(),
+            Timezone::Fixed(seconds) => FixedOffset::east_opt(*seconds).unwrap_or(Utc.fix()),
+        }
+    }
+
+    /// Convert a Unix timestamp into this timezone
+    pub fn datetime(&self, timestamp: u64) -> DateTime<FixedOffset> {
+        utc_datetime(timestamp).with_timezone(&self.offset_at(timestamp))
+    }
+
+    /// Convert a UTC datetime into this timezone, keeping sub-second precision
+    pub fn localize(&self, datetime: &DateTime<Utc>) -> DateTime<FixedOffset> {
+        datetime.with_timezone(&self.offset_at(datetime.timestamp().max(0) as u64))
+    }
+
+    /// Calendar date of a Unix timestamp in this timezone
+    pub fn date_of(&self, timestamp: u64) -> NaiveDate {
+        self.datetime(timestamp).date_naive()
+    }
+
+    /// Format a Unix timestamp as `YYYY-MM-DD` in this timezone
+    pub fn format_date(&self, timestamp: u64) -> String {
+        self.format(timestamp, "%Y-%m-%d")
+    }
+
+    /// Format a Unix timestamp with a chrono format string in this timezone
+    pub fn format(&self, timestamp: u64, format: &str) -> String {
+        self.datetime(timestamp).format(format).to_string()
+    }
+
+    /// Unix timestamp of a local wall-clock time
+    ///
+    /// Times skipped by a DST transition resolve to the first instant after
+    /// the gap; ambiguous times resolve to the earlier instant.
+    pub fn timestamp_of(&self, local: NaiveDateTime) -> u64 {
+        let utc = match self {
+            Timezone::Local => {
+                let mut candidate = local;
+                loop {
+                    if let Some(dt) = Local.from_local_datetime(&candidate).earliest() {
+                        break dt.with_timezone(&Utc).naive_utc();
+                    }
+                    candidate += Duration::minutes(15);
+                }
+            }
+            Timezone::Utc => local,
+            Timezone::Fixed(seconds) => local - Duration::seconds(*seconds as i64),
+        };
+        utc.and_utc().timestamp().max(0) as u64
+    }
+
+    /// Unix timestamp at which the given date starts in this timezone
+    pub fn start_of_day(&self, date: NaiveDate) -> u64 {
+        self.timestamp_of(date.and_hms_opt(0, 0, 0).expect("midnight is valid"))
+    }
+
+    /// Last second of the given date in this timezone
+    pub fn end_of_day(&self, date: NaiveDate) -> u64 {
+        match date.succ_opt() {
+            Some(next) => self.start_of_day(next).saturating_sub(1),
+            None => self.start_of_day(date) + 86_399,
+        }
+    }
+}
+
+impl FromStr for Timezone {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let trimmed = value.trim();
+        match trimmed.to_ascii_lowercase().as_str() {
+            "" | "local" | "system" => return Ok(Timezone::Local),
+            "utc" | "z" | "gmt" | "+00:00" | "-00:00" => return Ok(Timezone::Utc),
+            _ => {}
+        }
+
+        let offset = trimmed
+            .strip_prefix("UTC")
+            .or_else(|| trimmed.strip_prefix("utc"))
+            .unwrap_or(trimmed);
+        parse_offset(offset).map(Timezone::Fixed).ok_or_else(|| {
+            format!(
+                "Invalid timezone '{value}': expected 'local', 'UTC' or an offset like '+09:00'"
+            )
+        })
+    }
+}
+
+impl TryFrom<String> for Timezone {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Timezone> for String {
+    fn from(timezone: Timezone) -> Self {
+        timezone.to_string()
+    }
+}
+
+impl fmt::Display for Timezone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Timezone::Local => write!(f, "local"),
+            Timezone::Utc => write!(f, "UTC"),
+            Timezone::Fixed(seconds) => {
+                let sign = if *seconds < 0 { '-' } else { '+' };
+                let minutes = seconds.unsigned_abs() / 60;
+                write!(f, "{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+            }
+        }
+    }
+}
+
+/// Parse `+HH:MM`, `+HHMM` or `+HH` into seconds east of UTC
+fn parse_offset(value: &str) -> Option<i32> {
+    let (sign, digits) = match value.as_bytes().first()? {
+        b'+' => (1, &value[1..]),
+        b'-' => (-1, &value[1..]),
+        _ => return None,
+    };
+
+    let (hours, minutes) = match digits.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if digits.len() == 4 => digits.split_at(2),
+        None => (digits, "0"),
+    };
+    if hours.is_empty()
+        || !hours
+            .bytes()
+            .chain(minutes.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if hours > 14 || minutes > 59 {
+        return None;
+    }
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
+fn utc_datetime(timestamp: u64) -> DateTime<Utc> {
+    DateTime::from_timestamp(timestamp as i64, 0).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!("local".parse::<Timezone>().unwrap(), Timezone::Local);
+        assert_eq!("UTC".parse::<Timezone>().unwrap(), Timezone::Utc);
+        assert_eq!(
+            "+09:00".parse::<Timezone>().unwrap(),
+            Timezone::Fixed(9 * 3600)
+        );
+        assert_eq!(
+            "UTC-0530".parse::<Timezone>().unwrap(),
+            Timezone::Fixed(-(5 * 3600 + 30 * 60))
+        );
+        assert!("Mars/Olympus".parse::<Timezone>().is_err());
+        assert!("+25:00".parse::<Timezone>().is_err());
+
+        assert_eq!(Timezone::Fixed(-(5 * 3600 + 30 * 60)).to_string(), "-05:30");
+        assert_eq!(Timezone::Utc.to_string(), "UTC");
+    }
+
+    #[test]
+    fn test_serde_roundtrip() {
+        let yaml = serde_yaml::to_string(&Timezone::Fixed(9 * 3600)).unwrap();
+        assert_eq!(yaml.trim(), "+09:00");
+        let parsed: Timezone = serde_yaml::from_str("'-03:00'").unwrap();
+        assert_eq!(parsed, Timezone::Fixed(-3 * 3600));
+        assert!(serde_yaml::from_str::<Timezone>("nowhere").is_err());
+    }
+
+    #[test]
+    fn test_daily_bucketing_follows_offset() {
+        // 2022-01-01 20:00:00 UTC is already 2022-01-02 in Tokyo
+        let timestamp = 1641067200;
+        assert_eq!(Timezone::Utc.format_date(timestamp), "2022-01-01");
+        assert_eq!(
+            Timezone::Fixed(9 * 3600).format_date(timestamp),
+            "2022-01-02"
+        );
+    }
+
+    #[test]
+    fn test_day_boundaries() {
+        let tokyo = Timezone::Fixed(9 * 3600);
+        let date = NaiveDate::from_ymd_opt(2022, 1, 2).unwrap();
+
+        // 2022-01-02 00:00 +09:00 == 2022-01-01 15:00 UTC
+        assert_eq!(tokyo.start_of_day(date), 1641049200);
+        assert_eq!(tokyo.end_of_day(date), 1641049200 + 86_399);
+        assert_eq!(tokyo.date_of(tokyo.start_of_day(date)), date);
+    }
+}
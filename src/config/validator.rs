@@ -1,4 +1,19 @@
-use super::{Config, LoggingConfig, ProcessConfig, ServerConfig, UiConfig, WorkspaceConfig};
+use super::{
+    Config, LoggingConfig, ProcessConfig, ServerConfig, TelemetryConfig, UiConfig, WorkspaceConfig,
+};
+use crate::auth::AuthConfig;
+use crate::bridge::{BridgeConfig, BridgeEvent};
+use crate::federation::FederationConfig;
+use crate::github::GithubConfig;
+use crate::hooks::HooksConfig;
+use crate::metrics::MetricsConfig;
+use crate::monitoring::SloConfig;
+use crate::performance::memory::MemoryConfig;
+use crate::sync::SyncConfig;
+use crate::task::{LatencyBudget, TaskIdConfig, TimeBudget};
+
+/// Smallest allowed IPC message size limit, in bytes
+const MIN_MESSAGE_SIZE: usize = 1024;
 
 pub struct ConfigValidator;
 
@@ -9,6 +24,19 @@ impl ConfigValidator {
         Self::validate_process_config(&config.process)?;
         Self::validate_ui_config(&config.ui)?;
         Self::validate_logging_config(&config.logging)?;
+        Self::validate_telemetry_config(&config.telemetry)?;
+        Self::validate_budgets(&config.budgets)?;
+        Self::validate_task_ids(&config.task_ids)?;
+        Self::validate_latency_budgets(&config.latency_budgets)?;
+        Self::validate_auth_config(&config.auth)?;
+        Self::validate_metrics_config(&config.metrics)?;
+        Self::validate_sync_config(&config.sync)?;
+        Self::validate_bridge_config(&config.bridge)?;
+        Self::validate_hooks_config(&config.hooks)?;
+        Self::validate_federation_config(&config.federation)?;
+        Self::validate_github_config(&config.github)?;
+        Self::validate_slo_config(&config.monitoring.slo)?;
+        Self::validate_memory_config(&config.memory)?;
         Ok(())
     }
 
@@ -19,6 +47,16 @@ impl ConfigValidator {
         if config.max_connections == 0 {
             return Err("Maximum connections cannot be 0".to_string());
         }
+        if config.max_message_size < MIN_MESSAGE_SIZE {
+            return Err(format!(
+                "Maximum message size must be at least {MIN_MESSAGE_SIZE} bytes"
+            ));
+        }
+        if config.stream_chunk_bytes == 0 || config.stream_chunk_bytes > config.max_message_size {
+            return Err(
+                "Stream chunk size must be between 1 byte and the maximum message size".to_string(),
+            );
+        }
         Ok(())
     }
 
@@ -43,6 +81,243 @@ impl ConfigValidator {
         Ok(())
     }
 
+    fn validate_telemetry_config(config: &TelemetryConfig) -> Result<(), String> {
+        if !config.enabled {
+            return Ok(());
+        }
+        match config.endpoint.as_deref() {
+            Some(endpoint)
+                if endpoint.starts_with("https://") || endpoint.starts_with("http://") => {}
+            _ => return Err("Telemetry endpoint must be an http(s) URL".to_string()),
+        }
+        if config.interval_hours == 0 {
+            return Err("Telemetry interval cannot be 0".to_string());
+        }
+        Ok(())
+    }
+
+    fn validate_budgets(budgets: &[TimeBudget]) -> Result<(), String> {
+        for budget in budgets {
+            if !(budget.weekly_hours > 0.0 && budget.weekly_hours <= 168.0) {
+                return Err(format!(
+                    "Budget for {} must be between 0 and 168 weekly hours",
+                    budget.scope
+                ));
+            }
+            if !(0.0..=1.0).contains(&budget.warn_at) {
+                return Err(format!(
+                    "Budget warning threshold for {} must be between 0.0 and 1.0",
+                    budget.scope
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_task_ids(config: &TaskIdConfig) -> Result<(), String> {
+        let valid = !config.prefix.is_empty()
+            && config
+                .prefix
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            return Err(format!(
+                "Task ID prefix '{}' must be non-empty and contain only letters, digits and '_'",
+                config.prefix
+            ));
+        }
+        Ok(())
+    }
+
+    fn validate_latency_budgets(budgets: &[LatencyBudget]) -> Result<(), String> {
+        for budget in budgets {
+            if budget.max_secs == 0 {
+                return Err(format!(
+                    "Latency budget for {} must be at least one second",
+                    budget.stage.as_str()
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_metrics_config(config: &MetricsConfig) -> Result<(), String> {
+        if !config.enabled {
+            return Ok(());
+        }
+        if config.collection_interval == 0 {
+            return Err("Metrics collection interval cannot be 0".to_string());
+        }
+        if config.max_history_points == 0 {
+            return Err("Metrics history must keep at least one point".to_string());
+        }
+        if config.retention_hours == 0 {
+            return Err("Metrics retention cannot be 0 hours".to_string());
+        }
+        Ok(())
+    }
+
+    fn validate_sync_config(config: &SyncConfig) -> Result<(), String> {
+        for (workspace, watch) in &config.workspaces {
+            if workspace.trim().is_empty() {
+                return Err("Sync workspace name cannot be empty".to_string());
+            }
+            if watch.roots.is_empty() {
+                return Err(format!(
+                    "Sync workspace '{workspace}' needs at least one watch root"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_bridge_config(config: &BridgeConfig) -> Result<(), String> {
+        let sample = BridgeEvent::task_completed("workspace", "task", "title");
+        for (workspace, webhooks) in &config.workspaces {
+            if workspace.trim().is_empty() {
+                return Err("Bridge workspace name cannot be empty".to_string());
+            }
+            for webhook in webhooks {
+                if !webhook.url.starts_with("http://") && !webhook.url.starts_with("https://") {
+                    return Err(format!("Invalid webhook URL: {}", webhook.url));
+                }
+                if webhook.max_attempts == 0 {
+                    return Err(format!(
+                        "Webhook {} needs at least one attempt",
+                        webhook.url
+                    ));
+                }
+                if let Err(e) = sample.render(webhook.payload.as_deref()) {
+                    return Err(format!("Invalid payload for webhook {}: {e}", webhook.url));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_hooks_config(config: &HooksConfig) -> Result<(), String> {
+        for hook in config.iter() {
+            if hook.command.trim().is_empty() {
+                return Err("Hook command cannot be empty".to_string());
+            }
+            if hook.timeout_secs == 0 {
+                return Err(format!("Hook timeout must be positive: {}", hook.command));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_federation_config(config: &FederationConfig) -> Result<(), String> {
+        if config.poll_interval_secs == 0 {
+            return Err("Federation poll interval must be greater than 0".to_string());
+        }
+        let mut names = std::collections::HashSet::new();
+        for peer in &config.peers {
+            peer.validate()?;
+            if !names.insert(peer.name.as_str()) {
+                return Err(format!("Duplicate federation peer '{}'", peer.name));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_github_config(config: &GithubConfig) -> Result<(), String> {
+        if config.sync_interval_secs == 0 {
+            return Err("GitHub sync interval must be greater than 0".to_string());
+        }
+        if config.api_url.trim().is_empty() {
+            return Err("GitHub API URL cannot be empty".to_string());
+        }
+        let mut repos = std::collections::HashSet::new();
+        for repository in &config.repositories {
+            repository.validate()?;
+            if !repos.insert(repository.repo.to_ascii_lowercase()) {
+                return Err(format!("Duplicate GitHub repository '{}'", repository.repo));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_slo_config(config: &SloConfig) -> Result<(), String> {
+        if config.window_hours == 0 {
+            return Err("SLO window must be at least one hour".to_string());
+        }
+        let mut components = std::collections::HashSet::new();
+        for objective in &config.objectives {
+            if !components.insert(objective.component.as_str()) {
+                return Err(format!(
+                    "Duplicate SLO objectives for '{}'",
+                    objective.component
+                ));
+            }
+            for (name, percent) in [
+                ("availability", objective.availability),
+                ("error rate", objective.error_rate),
+            ] {
+                if percent.is_some_and(|percent| !(percent > 0.0 && percent < 100.0)) {
+                    return Err(format!(
+                        "SLO {name} of '{}' must be between 0 and 100 percent",
+                        objective.component
+                    ));
+                }
+            }
+            if objective.p95_latency_ms == Some(0) {
+                return Err(format!(
+                    "SLO p95 latency of '{}' must be greater than 0",
+                    objective.component
+                ));
+            }
+        }
+        for alert in &config.burn_rate_alerts {
+            if alert.window_minutes == 0 || alert.burn_rate <= 0.0 {
+                return Err(
+                    "SLO burn rate alerts need a window and a burn rate greater than 0".to_string(),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_memory_config(config: &MemoryConfig) -> Result<(), String> {
+        if config.sample_interval_secs == 0 {
+            return Err("Memory sample interval must be greater than 0".to_string());
+        }
+        if config.leak_windows_minutes.is_empty() || config.leak_windows_minutes.contains(&0) {
+            return Err("Memory leak windows must be at least one minute".to_string());
+        }
+        if config.leak_threshold_mb_per_hour <= 0.0 {
+            return Err("Memory leak threshold must be greater than 0".to_string());
+        }
+        if !(0.0..=1.0).contains(&config.min_fit) {
+            return Err("Memory leak min_fit must be between 0 and 1".to_string());
+        }
+        Ok(())
+    }
+
+    fn validate_auth_config(config: &AuthConfig) -> Result<(), String> {
+        if config.socket_mode & !0o777 != 0 {
+            return Err(format!("Invalid socket mode: {:o}", config.socket_mode));
+        }
+        // The framework's own CLI connects as the socket owner
+        if config.socket_mode & 0o600 != 0o600 {
+            return Err("Socket mode must give the owner read and write access".to_string());
+        }
+
+        let mut names = std::collections::HashSet::new();
+        for client in &config.tokens {
+            if client.token.trim().is_empty() {
+                return Err(format!("API token for '{}' cannot be empty", client.name));
+            }
+            if !names.insert(client.name.as_str()) {
+                return Err(format!("Duplicate API token name: {}", client.name));
+            }
+        }
+        if config.enabled && config.tokens.is_empty() && config.local_scope.is_none() {
+            return Err("Authentication is enabled but no client could connect: add a token or set local_scope".to_string());
+        }
+        Ok(())
+    }
+
     fn validate_logging_config(config: &LoggingConfig) -> Result<(), String> {
         match config.level.as_str() {
             "error" | "warn" | "info" | "debug" | "trace" => Ok(()),
@@ -55,6 +330,7 @@ impl ConfigValidator {
 mod tests {
     use super::*;
     use crate::config::{DashboardConfig, KeybindingConfig, ThemeConfig};
+    use crate::monitoring::MonitoringConfig;
 
     fn create_valid_config() -> Config {
         Config {
@@ -64,6 +340,7 @@ mod tests {
                 connection_timeout: 30,
                 enable_metrics: true,
                 health_check_interval: 10,
+                ..Default::default()
             },
             workspace: WorkspaceConfig {
                 max_workspaces: 10,
@@ -71,6 +348,8 @@ mod tests {
                 state_path: std::path::PathBuf::from("/tmp/workspaces.json"),
                 auto_save_interval: 30,
                 templates_dir: std::path::PathBuf::from("/tmp/templates"),
+                apply_layout: false,
+                terminal_backend: Default::default(),
             },
             process: ProcessConfig {
                 max_processes_per_workspace: 16,
@@ -80,6 +359,7 @@ mod tests {
                 max_restart_attempts: 3,
                 environment: std::collections::HashMap::new(),
                 working_dir_template: "~/projects/{{workspace_name}}".to_string(),
+                secrets: Default::default(),
             },
             ui: UiConfig {
                 dashboard: DashboardConfig {
@@ -116,6 +396,26 @@ mod tests {
                 format: "json".to_string(),
             },
             plugins: std::collections::HashMap::new(),
+            telemetry: TelemetryConfig::default(),
+            timezone: Default::default(),
+            language: Default::default(),
+            budgets: Vec::new(),
+            task_ids: TaskIdConfig::default(),
+            latency_budgets: Vec::new(),
+            preemption: Default::default(),
+            auth: AuthConfig::default(),
+            focus: Default::default(),
+            metrics: MetricsConfig::default(),
+            sync: SyncConfig::default(),
+            bridge: BridgeConfig::default(),
+            hooks: HooksConfig::default(),
+            git: Default::default(),
+            github: Default::default(),
+            monitoring: MonitoringConfig::default(),
+            memory: Default::default(),
+            performance: Default::default(),
+            federation: FederationConfig::default(),
+            audit: Default::default(),
         }
     }
 
@@ -146,6 +446,30 @@ mod tests {
         assert_eq!(result.unwrap_err(), "Maximum connections cannot be 0");
     }
 
+    #[test]
+    fn test_validate_server_config_small_message_size() {
+        let mut config = create_valid_config();
+        config.server.max_message_size = 512;
+
+        let result = ConfigValidator::validate(&config);
+        assert_eq!(
+            result.unwrap_err(),
+            "Maximum message size must be at least 1024 bytes"
+        );
+    }
+
+    #[test]
+    fn test_validate_server_config_stream_chunk_size() {
+        let mut config = create_valid_config();
+        config.server.stream_chunk_bytes = config.server.max_message_size + 1;
+
+        let result = ConfigValidator::validate(&config);
+        assert_eq!(
+            result.unwrap_err(),
+            "Stream chunk size must be between 1 byte and the maximum message size"
+        );
+    }
+
     #[test]
     fn test_validate_server_config_valid() {
         let server_config = ServerConfig {
@@ -154,6 +478,7 @@ mod tests {
             connection_timeout: 30,
             enable_metrics: true,
             health_check_interval: 10,
+            ..Default::default()
         };
 
         let result = ConfigValidator::validate_server_config(&server_config);
@@ -188,6 +513,8 @@ mod tests {
             state_path: std::path::PathBuf::from("/tmp/workspaces.json"),
             auto_save_interval: 30,
             templates_dir: std::path::PathBuf::from("/tmp/templates"),
+            apply_layout: false,
+            terminal_backend: Default::default(),
         };
 
         let result = ConfigValidator::validate_workspace_config(&workspace_config);
@@ -217,6 +544,7 @@ mod tests {
             max_restart_attempts: 3,
             environment: std::collections::HashMap::new(),
             working_dir_template: "~/projects/{{workspace_name}}".to_string(),
+            secrets: Default::default(),
         };
 
         let result = ConfigValidator::validate_process_config(&process_config);
@@ -291,6 +619,24 @@ mod tests {
         assert_eq!(result.unwrap_err(), "Invalid log level: invalid");
     }
 
+    #[test]
+    fn test_validate_telemetry_config() {
+        // Disabled telemetry needs no endpoint
+        assert!(ConfigValidator::validate_telemetry_config(&TelemetryConfig::default()).is_ok());
+
+        let mut telemetry = TelemetryConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        assert!(ConfigValidator::validate_telemetry_config(&telemetry).is_err());
+
+        telemetry.endpoint = Some("https://telemetry.example.com/v1/report".to_string());
+        assert!(ConfigValidator::validate_telemetry_config(&telemetry).is_ok());
+
+        telemetry.interval_hours = 0;
+        assert!(ConfigValidator::validate_telemetry_config(&telemetry).is_err());
+    }
+
     #[test]
     fn test_validate_logging_config_case_sensitive() {
         let logging_config = LoggingConfig {
@@ -358,4 +704,213 @@ mod tests {
         let result = ConfigValidator::validate(&config);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_validate_budgets() {
+        let mut config = create_valid_config();
+        config.budgets = vec![TimeBudget::category("Development", 20.0)];
+        assert!(ConfigValidator::validate(&config).is_ok());
+
+        config.budgets[0].weekly_hours = 0.0;
+        assert!(ConfigValidator::validate(&config).is_err());
+
+        config.budgets[0].weekly_hours = 20.0;
+        config.budgets[0].warn_at = 1.5;
+        assert!(ConfigValidator::validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_latency_budgets() {
+        let mut config = create_valid_config();
+        config.latency_budgets =
+            serde_yaml::from_str("- stage: waiting_for_agent\n  max_secs: 300").unwrap();
+        assert!(ConfigValidator::validate(&config).is_ok());
+
+        config.latency_budgets[0].max_secs = 0;
+        assert!(ConfigValidator::validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_auth() {
+        let mut config = create_valid_config();
+        config.auth.socket_mode = 0o660;
+        assert!(ConfigValidator::validate(&config).is_ok());
+
+        config.auth.socket_mode = 0o066;
+        assert!(ConfigValidator::validate(&config).is_err());
+
+        config.auth.socket_mode = 0o600;
+        config.auth.enabled = true;
+        config.auth.local_scope = None;
+        assert_eq!(
+            ConfigValidator::validate(&config).unwrap_err(),
+            "Authentication is enabled but no client could connect: add a token or set local_scope"
+        );
+    }
+
+    #[test]
+    fn test_validate_metrics() {
+        let mut config = create_valid_config();
+        config.metrics.retention_hours = 0;
+        assert_eq!(
+            ConfigValidator::validate(&config).unwrap_err(),
+            "Metrics retention cannot be 0 hours"
+        );
+
+        // Settings of disabled metrics are not checked
+        config.metrics.enabled = false;
+        assert!(ConfigValidator::validate(&config).is_ok());
+    }
+
+    #[test]
+    fn test_validate_sync_workspaces() {
+        let mut config = create_valid_config();
+        config.sync.workspaces.insert(
+            "frontend".to_string(),
+            crate::sync::WorkspaceWatchConfig {
+                roots: Vec::new(),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            ConfigValidator::validate(&config).unwrap_err(),
+            "Sync workspace 'frontend' needs at least one watch root"
+        );
+    }
+
+    #[test]
+    fn test_validate_federation_peers() {
+        use crate::federation::PeerConfig;
+
+        let mut config = create_valid_config();
+        config.federation.peers = vec![
+            PeerConfig::new("build", "/tmp/build.sock").with_token("t"),
+            PeerConfig::new("ci", "unix:/tmp/ci.sock"),
+        ];
+        assert!(ConfigValidator::validate(&config).is_ok());
+
+        config
+            .federation
+            .peers
+            .push(PeerConfig::new("ci", "/tmp/other.sock"));
+        assert!(ConfigValidator::validate(&config).is_err());
+
+        config.federation.peers.pop();
+        config.federation.peers[1].address = "unix:".to_string();
+        assert!(ConfigValidator::validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_github_repositories() {
+        let mut config = create_valid_config();
+        config.github.repositories = serde_yaml::from_str(
+            "- repo: acme/shop\n  workspace: shop\n- repo: acme/api\n  labels: [agent]\n",
+        )
+        .unwrap();
+        assert!(ConfigValidator::validate(&config).is_ok());
+
+        config.github.repositories[1].repo = "Acme/Shop".to_string();
+        assert!(ConfigValidator::validate(&config)
+            .unwrap_err()
+            .starts_with("Duplicate GitHub repository"));
+
+        config.github.repositories[1].repo = "acme".to_string();
+        assert!(ConfigValidator::validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_slo_objectives() {
+        let mut config = create_valid_config();
+        config.monitoring.slo.objectives = serde_yaml::from_str(
+            "- component: task_manager\n  availability: 99.9\n  p95_latency_ms: 200\n- component: ipc_system\n  error_rate: 1\n",
+        )
+        .unwrap();
+        assert!(ConfigValidator::validate(&config).is_ok());
+
+        config.monitoring.slo.objectives[1].availability = Some(100.0);
+        assert!(ConfigValidator::validate(&config)
+            .unwrap_err()
+            .contains("between 0 and 100"));
+
+        config.monitoring.slo.objectives[1].availability = None;
+        config.monitoring.slo.objectives[1].component = "task_manager".to_string();
+        assert!(ConfigValidator::validate(&config)
+            .unwrap_err()
+            .starts_with("Duplicate SLO objectives"));
+    }
+
+    #[test]
+    fn test_validate_memory_leak_settings() {
+        let mut config = create_valid_config();
+        config.memory = serde_yaml::from_str(
+            "leak_windows_minutes: [15, 60]\nrestart_leaking_processes: true\n",
+        )
+        .unwrap();
+        assert!(ConfigValidator::validate(&config).is_ok());
+
+        config.memory.leak_windows_minutes.push(0);
+        assert!(ConfigValidator::validate(&config)
+            .unwrap_err()
+            .starts_with("Memory leak windows"));
+
+        config.memory.leak_windows_minutes.pop();
+        config.memory.min_fit = 1.5;
+        assert!(ConfigValidator::validate(&config)
+            .unwrap_err()
+            .contains("between 0 and 1"));
+    }
+
+    #[test]
+    fn test_validate_bridge_webhooks() {
+        let mut config = create_valid_config();
+        let webhook: crate::bridge::WebhookConfig =
+            serde_yaml::from_str("url: https://n8n.example.com/webhook/x").unwrap();
+        config
+            .bridge
+            .workspaces
+            .insert("*".to_string(), vec![webhook.clone()]);
+        assert!(ConfigValidator::validate(&config).is_ok());
+
+        config.bridge.workspaces.insert(
+            "frontend".to_string(),
+            vec![crate::bridge::WebhookConfig {
+                payload: Some("{\"text\": {{workspace}}}".to_string()),
+                ..webhook.clone()
+            }],
+        );
+        assert!(ConfigValidator::validate(&config)
+            .unwrap_err()
+            .starts_with("Invalid payload for webhook"));
+
+        config.bridge.workspaces.insert(
+            "frontend".to_string(),
+            vec![crate::bridge::WebhookConfig {
+                url: "ftp://example.com".to_string(),
+                ..webhook
+            }],
+        );
+        assert_eq!(
+            ConfigValidator::validate(&config).unwrap_err(),
+            "Invalid webhook URL: ftp://example.com"
+        );
+    }
+
+    #[test]
+    fn test_validate_hooks() {
+        let mut config = create_valid_config();
+        config.hooks = serde_yaml::from_str("agent_failed:\n  - command: ./alert.sh\n").unwrap();
+        assert!(ConfigValidator::validate(&config).is_ok());
+
+        config.hooks.agent_failed[0].timeout_secs = 0;
+        assert_eq!(
+            ConfigValidator::validate(&config).unwrap_err(),
+            "Hook timeout must be positive: ./alert.sh"
+        );
+
+        config.hooks.agent_failed[0].command = " ".to_string();
+        assert_eq!(
+            ConfigValidator::validate(&config).unwrap_err(),
+            "Hook command cannot be empty"
+        );
+    }
 }
@@ -0,0 +1,326 @@
+// WezTerm Multi-Process Development Framework - Dashboard Message Backlog
+// Keeps a bounded, sequence-numbered history of broadcast messages so that
+// clients can catch up on what they missed (long-poll, reconnection resume)
+
+use super::DashboardMessage;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{broadcast, Notify, RwLock};
+
+/// Default number of messages retained in the backlog
+pub const DEFAULT_BACKLOG_CAPACITY: usize = 1000;
+
+/// A single message recorded in the backlog
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacklogEntry {
+    /// Monotonically increasing sequence number (starts at 1)
+    pub seq: u64,
+
+    /// Time the message was recorded
+    pub timestamp: u64,
+
+    /// Recorded message
+    pub message: DashboardMessage,
+}
+
+/// Result of a backlog read starting at a cursor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacklogSlice {
+    /// Entries newer than the requested cursor, oldest first
+    pub entries: Vec<BacklogEntry>,
+
+    /// Cursor to pass on the next read
+    pub next_cursor: u64,
+
+    /// True when messages between the cursor and the oldest retained entry were dropped
+    pub truncated: bool,
+}
+
+/// Bounded ring buffer of broadcast messages with sequence numbers
+pub struct MessageBacklog {
+    /// Maximum number of retained entries
+    capacity: usize,
+
+    /// Retained entries, oldest first
+    entries: RwLock<VecDeque<BacklogEntry>>,
+
+    /// Sequence number of the most recently recorded message
+    last_seq: RwLock<u64>,
+
+    /// Wakes up readers waiting for new messages
+    notify: Notify,
+}
+
+impl Default for MessageBacklog {
+    fn default() -> Self {
+        Self::new(DEFAULT_BACKLOG_CAPACITY)
+    }
+}
+
+impl MessageBacklog {
+    /// Create a new backlog retaining up to `capacity` messages
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: RwLock::new(VecDeque::new()),
+            last_seq: RwLock::new(0),
+            notify: Notify::new(),
+        }
+    }
+
+    /// Record a message and return its sequence number
+    pub async fn push(&self, message: DashboardMessage) -> u64 {
+        let seq = {
+            let mut last_seq = self.last_seq.write().await;
+            *last_seq += 1;
+            *last_seq
+        };
+
+        {
+            let mut entries = self.entries.write().await;
+            entries.push_back(BacklogEntry {
+                seq,
+                timestamp: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs(),
+                message,
+            });
+            while entries.len() > self.capacity {
+                entries.pop_front();
+            }
+        }
+
+        self.notify.notify_waiters();
+        seq
+    }
+
+    /// Record a broadcast message unless it is process output, which the
+    /// process manager keeps instead
+    pub async fn record(&self, message: &DashboardMessage) -> Option<u64> {
+        match message {
+            DashboardMessage::ProcessOutput { .. } => None,
+            message => Some(self.push(message.clone()).await),
+        }
+    }
+
+    /// Entries after `seq` for a client resuming its stream
+    ///
+    /// A `seq` beyond the latest one was handed out before a restart: the
+    /// client gets everything retained, flagged as truncated since it missed
+    /// an unknown number of messages.
+    pub async fn resume_from(&self, seq: u64) -> BacklogSlice {
+        if seq > self.latest_seq().await {
+            let mut slice = self.since(0, self.capacity).await;
+            slice.truncated = true;
+            return slice;
+        }
+        self.since(seq, self.capacity).await
+    }
+
+    /// Sequence number of the most recently recorded message (0 when empty)
+    pub async fn latest_seq(&self) -> u64 {
+        *self.last_seq.read().await
+    }
+
+    /// Number of retained entries
+    pub async fn len(&self) -> usize {
+        self.entries.read().await.len()
+    }
+
+    /// Check if the backlog holds no entries
+    pub async fn is_empty(&self) -> bool {
+        self.entries.read().await.is_empty()
+    }
+
+    /// Read up to `limit` entries with a sequence number greater than `cursor`
+    pub async fn since(&self, cursor: u64, limit: usize) -> BacklogSlice {
+        let entries = self.entries.read().await;
+
+        let oldest_seq = entries.front().map(|e| e.seq);
+        let truncated = matches!(oldest_seq, Some(oldest) if oldest > cursor.saturating_add(1));
+
+        let selected: Vec<BacklogEntry> = entries
+            .iter()
+            .filter(|e| e.seq > cursor)
+            .take(limit)
+            .cloned()
+            .collect();
+
+        let next_cursor = selected.last().map(|e| e.seq).unwrap_or(cursor);
+
+        BacklogSlice {
+            entries: selected,
+            next_cursor,
+            truncated,
+        }
+    }
+
+    /// Read entries newer than `cursor`, waiting up to `timeout` for one to arrive
+    pub async fn wait_since(&self, cursor: u64, limit: usize, timeout: Duration) -> BacklogSlice {
+        let deadline = tokio::time::Instant::now() + timeout;
+
+        loop {
+            // Register interest before checking so a push in between is not missed
+            let notified = self.notify.notified();
+
+            let slice = self.since(cursor, limit).await;
+            if !slice.entries.is_empty() {
+                return slice;
+            }
+
+            if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                return self.since(cursor, limit).await;
+            }
+        }
+    }
+
+    /// Spawn a task recording every message sent on the broadcast channel
+    pub fn spawn_recorder(
+        self: &Arc<Self>,
+        mut broadcast_rx: broadcast::Receiver<DashboardMessage>,
+    ) -> tokio::task::JoinHandle<()> {
+        let backlog = Arc::clone(self);
+
+        tokio::spawn(async move {
+            loop {
+                match broadcast_rx.recv().await {
+                    Ok(message) => {
+                        backlog.record(&message).await;
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!(
+                            "Dashboard backlog recorder lagged, {} messages lost",
+                            skipped
+                        );
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn heartbeat(timestamp: u64) -> DashboardMessage {
+        DashboardMessage::Heartbeat { timestamp }
+    }
+
+    #[tokio::test]
+    async fn test_push_assigns_increasing_sequence() {
+        let backlog = MessageBacklog::new(10);
+
+        assert_eq!(backlog.push(heartbeat(1)).await, 1);
+        assert_eq!(backlog.push(heartbeat(2)).await, 2);
+        assert_eq!(backlog.latest_seq().await, 2);
+        assert_eq!(backlog.len().await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_since_returns_newer_entries() {
+        let backlog = MessageBacklog::new(10);
+        for i in 0..5 {
+            backlog.push(heartbeat(i)).await;
+        }
+
+        let slice = backlog.since(2, 10).await;
+        assert_eq!(slice.entries.len(), 3);
+        assert_eq!(slice.entries[0].seq, 3);
+        assert_eq!(slice.next_cursor, 5);
+        assert!(!slice.truncated);
+
+        let limited = backlog.since(0, 2).await;
+        assert_eq!(limited.entries.len(), 2);
+        assert_eq!(limited.next_cursor, 2);
+    }
+
+    #[tokio::test]
+    async fn test_capacity_drops_oldest_and_flags_truncation() {
+        let backlog = MessageBacklog::new(3);
+        for i in 0..5 {
+            backlog.push(heartbeat(i)).await;
+        }
+
+        assert_eq!(backlog.len().await, 3);
+
+        let slice = backlog.since(0, 10).await;
+        assert!(slice.truncated);
+        assert_eq!(slice.entries[0].seq, 3);
+
+        let caught_up = backlog.since(2, 10).await;
+        assert!(!caught_up.truncated);
+    }
+
+    #[tokio::test]
+    async fn test_since_accepts_any_client_cursor() {
+        let backlog = MessageBacklog::new(10);
+        backlog.push(heartbeat(1)).await;
+
+        // Cursors come from clients; one past every entry is not an error
+        let slice = backlog.since(u64::MAX, 10).await;
+        assert!(slice.entries.is_empty());
+        assert!(!slice.truncated);
+        assert_eq!(slice.next_cursor, u64::MAX);
+    }
+
+    #[tokio::test]
+    async fn test_wait_since_times_out_with_empty_slice() {
+        let backlog = MessageBacklog::new(10);
+
+        let slice = backlog.wait_since(0, 10, Duration::from_millis(20)).await;
+        assert!(slice.entries.is_empty());
+        assert_eq!(slice.next_cursor, 0);
+    }
+
+    #[tokio::test]
+    async fn test_wait_since_wakes_on_push() {
+        let backlog = Arc::new(MessageBacklog::new(10));
+
+        let waiter = {
+            let backlog = Arc::clone(&backlog);
+            tokio::spawn(async move { backlog.wait_since(0, 10, Duration::from_secs(5)).await })
+        };
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        backlog.push(heartbeat(42)).await;
+
+        let slice = waiter.await.unwrap();
+        assert_eq!(slice.entries.len(), 1);
+        assert_eq!(slice.next_cursor, 1);
+    }
+
+    #[tokio::test]
+    async fn test_resume_from_flags_sequences_from_before_a_restart() {
+        let backlog = MessageBacklog::new(10);
+        for i in 0..3 {
+            backlog.push(heartbeat(i)).await;
+        }
+
+        let slice = backlog.resume_from(1).await;
+        assert_eq!(slice.entries.len(), 2);
+        assert!(!slice.truncated);
+
+        // The client saw seq 40 from the previous server
+        let slice = backlog.resume_from(40).await;
+        assert_eq!(slice.entries.len(), 3);
+        assert!(slice.truncated);
+    }
+
+    #[tokio::test]
+    async fn test_recorder_captures_broadcasts() {
+        let backlog = Arc::new(MessageBacklog::new(10));
+        let (tx, rx) = broadcast::channel(16);
+        let handle = backlog.spawn_recorder(rx);
+
+        tx.send(heartbeat(7)).unwrap();
+        let slice = backlog.wait_since(0, 10, Duration::from_secs(1)).await;
+        assert_eq!(slice.entries.len(), 1);
+
+        handle.abort();
+    }
+}
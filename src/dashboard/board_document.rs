@@ -0,0 +1,338 @@
+// WezTerm Multi-Process Development Framework - Board Documents
+// Versioned JSON/YAML documents holding a task board's columns, tasks and
+// dependencies, for moving boards between machines or into version control
+
+use super::TaskBoardConfig;
+use crate::task::{Task, TaskCategory, TaskId, TaskNote, TaskPriority, TaskStatus};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// Version written by this build; newer documents are refused
+pub const BOARD_DOCUMENT_VERSION: u32 = 1;
+
+/// Serialization of a board document
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BoardFormat {
+    Json,
+    Yaml,
+}
+
+impl BoardFormat {
+    /// JSON documents start with `{`; anything else is read as YAML
+    pub fn detect(content: &str) -> Self {
+        if content.trim_start().starts_with('{') {
+            BoardFormat::Json
+        } else {
+            BoardFormat::Yaml
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BoardFormat::Json => "json",
+            BoardFormat::Yaml => "yaml",
+        }
+    }
+}
+
+impl std::str::FromStr for BoardFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "json" => Ok(BoardFormat::Json),
+            "yaml" | "yml" => Ok(BoardFormat::Yaml),
+            _ => Err(format!(
+                "Unsupported board format '{value}': expected json or yaml"
+            )),
+        }
+    }
+}
+
+/// Planning fields of a task; execution history and time tracking stay on
+/// the machine they were recorded on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BoardTask {
+    pub id: TaskId,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub status: TaskStatus,
+    pub priority: TaskPriority,
+    pub category: TaskCategory,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<TaskId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_duration: Option<u64>,
+    #[serde(default)]
+    pub progress: u8,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<TaskNote>,
+    pub created_at: u64,
+}
+
+impl BoardTask {
+    pub fn from_task(task: &Task) -> Self {
+        Self {
+            id: task.id.clone(),
+            title: task.title.clone(),
+            description: task.description.clone(),
+            status: task.status.clone(),
+            priority: task.priority.clone(),
+            category: task.category.clone(),
+            workspace: task.workspace.clone(),
+            tags: task.tags.clone(),
+            assignee: task.assignee.clone(),
+            dependencies: task.dependencies.clone(),
+            due_date: task.due_date,
+            estimated_duration: task.estimated_duration,
+            progress: task.progress,
+            metadata: task
+                .metadata
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+            notes: task.notes.clone(),
+            created_at: task.created_at,
+        }
+    }
+
+    /// Task with these planning fields, on top of `existing` when the task is
+    /// already known so its execution settings and history are kept
+    pub fn into_task(self, existing: Option<Task>) -> Task {
+        let mut task = existing.unwrap_or_else(|| {
+            let mut task = Task::new(self.title.clone(), self.category.clone());
+            task.id = self.id.clone();
+            task.created_at = self.created_at;
+            task
+        });
+        if task.status != self.status {
+            task.update_status(self.status);
+        }
+        task.title = self.title;
+        task.description = self.description;
+        task.priority = self.priority;
+        task.category = self.category;
+        task.workspace = self.workspace;
+        task.tags = self.tags;
+        task.assignee = self.assignee;
+        task.dependencies = self.dependencies;
+        task.due_date = self.due_date;
+        task.estimated_duration = self.estimated_duration;
+        task.progress = self.progress.min(100);
+        task.metadata = self.metadata.into_iter().collect();
+        task.notes = self.notes;
+        task.updated_at = crate::task::current_timestamp();
+        task
+    }
+}
+
+/// A task board with its tasks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoardDocument {
+    pub version: u32,
+    pub exported_at: u64,
+
+    /// Board settings and columns; each column lists its task IDs in order
+    pub board: TaskBoardConfig,
+    pub tasks: Vec<BoardTask>,
+
+    /// Free-form information about the export (framework version, ...)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl BoardDocument {
+    pub fn new(board: TaskBoardConfig, tasks: Vec<BoardTask>) -> Self {
+        Self {
+            version: BOARD_DOCUMENT_VERSION,
+            exported_at: crate::task::current_timestamp(),
+            board,
+            tasks,
+            metadata: BTreeMap::from([(
+                "framework_version".to_string(),
+                env!("CARGO_PKG_VERSION").to_string(),
+            )]),
+        }
+    }
+
+    pub fn render(&self, format: BoardFormat) -> Result<String, String> {
+        match format {
+            BoardFormat::Json => serde_json::to_string_pretty(self).map_err(|e| e.to_string()),
+            BoardFormat::Yaml => serde_yaml::to_string(self).map_err(|e| e.to_string()),
+        }
+    }
+
+    /// Read a document, detecting the format when not given
+    pub fn parse(content: &str, format: Option<BoardFormat>) -> Result<Self, String> {
+        let document: Self = match format.unwrap_or_else(|| BoardFormat::detect(content)) {
+            BoardFormat::Json => serde_json::from_str(content).map_err(|e| e.to_string())?,
+            BoardFormat::Yaml => serde_yaml::from_str(content).map_err(|e| e.to_string())?,
+        };
+        if document.version > BOARD_DOCUMENT_VERSION {
+            return Err(format!(
+                "Board document version {} is newer than the supported version {}",
+                document.version, BOARD_DOCUMENT_VERSION
+            ));
+        }
+        Ok(document)
+    }
+
+    /// Problems that make parts of the document unusable; tasks listed twice
+    /// are dropped after the first, and dependencies on tasks that are neither
+    /// in the document nor in `known` are removed
+    pub fn sanitize(&mut self, known: &HashSet<TaskId>) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        let mut seen = HashSet::new();
+        self.tasks.retain(|task| {
+            let first = seen.insert(task.id.clone());
+            if !first {
+                errors.push(format!("Task {} is listed more than once", task.id));
+            } else if task.title.trim().is_empty() {
+                errors.push(format!("Task {} has no title", task.id));
+                seen.remove(&task.id);
+                return false;
+            }
+            first
+        });
+
+        for task in &mut self.tasks {
+            let id = task.id.clone();
+            task.dependencies.retain(|dependency| {
+                let exists = seen.contains(dependency) || known.contains(dependency);
+                if !exists {
+                    errors.push(format!("Task {id} depends on unknown task {dependency}"));
+                }
+                exists && dependency != &id
+            });
+        }
+
+        for column in &mut self.board.columns {
+            column.tasks.retain(|task_id| {
+                let exists = seen.contains(task_id);
+                if !exists {
+                    errors.push(format!(
+                        "Column {} lists unknown task {}",
+                        column.id, task_id
+                    ));
+                }
+                exists
+            });
+        }
+        errors
+    }
+}
+
+/// Outcome of importing a board document
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BoardImportReport {
+    pub board_id: String,
+    pub dry_run: bool,
+
+    /// True when the board did not exist before
+    pub board_created: bool,
+    pub tasks_created: usize,
+    pub tasks_updated: usize,
+    pub errors: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dashboard::{BoardVisibility, TaskColumn, WipLimitMode};
+
+    fn document() -> BoardDocument {
+        let mut design = Task::new("Design API".to_string(), TaskCategory::Documentation);
+        design.id = "design".to_string();
+        design
+            .metadata
+            .insert("epic".to_string(), "sync".to_string());
+        let mut build = Task::new("Build API".to_string(), TaskCategory::Development);
+        build.id = "build".to_string();
+        build.dependencies = vec!["design".to_string(), "missing".to_string()];
+
+        let board = TaskBoardConfig {
+            id: "team".to_string(),
+            title: "Team".to_string(),
+            columns: vec![TaskColumn {
+                id: "todo".to_string(),
+                title: "To Do".to_string(),
+                tasks: vec![
+                    "design".to_string(),
+                    "build".to_string(),
+                    "gone".to_string(),
+                ],
+                color: None,
+                max_tasks: None,
+                sort_order: 0,
+            }],
+            refresh_interval: 1000,
+            real_time: true,
+            visibility: BoardVisibility::Public,
+            wip_limits: WipLimitMode::default(),
+        };
+        BoardDocument::new(
+            board,
+            vec![BoardTask::from_task(&design), BoardTask::from_task(&build)],
+        )
+    }
+
+    #[test]
+    fn test_document_round_trips_through_json_and_yaml() {
+        let document = document();
+        for format in [BoardFormat::Json, BoardFormat::Yaml] {
+            let content = document.render(format).unwrap();
+            assert_eq!(BoardFormat::detect(&content), format);
+            let parsed = BoardDocument::parse(&content, None).unwrap();
+            assert_eq!(parsed.tasks, document.tasks);
+            assert_eq!(parsed.board.columns[0].tasks.len(), 3);
+            assert_eq!(parsed.version, BOARD_DOCUMENT_VERSION);
+        }
+
+        let mut newer = document.clone();
+        newer.version = BOARD_DOCUMENT_VERSION + 1;
+        let content = newer.render(BoardFormat::Json).unwrap();
+        assert!(BoardDocument::parse(&content, None)
+            .unwrap_err()
+            .contains("newer"));
+        assert!("toml".parse::<BoardFormat>().is_err());
+    }
+
+    #[test]
+    fn test_sanitize_drops_unknown_references() {
+        let mut document = document();
+        let duplicate = document.tasks[0].clone();
+        document.tasks.push(duplicate);
+
+        let errors = document.sanitize(&HashSet::new());
+        assert_eq!(
+            errors,
+            [
+                "Task design is listed more than once",
+                "Task build depends on unknown task missing",
+                "Column todo lists unknown task gone",
+            ]
+        );
+        assert_eq!(document.tasks.len(), 2);
+        assert_eq!(document.tasks[1].dependencies, ["design"]);
+        assert_eq!(document.board.columns[0].tasks, ["design", "build"]);
+
+        // Known locally: kept
+        let mut document = self::document();
+        let known = HashSet::from(["missing".to_string(), "gone".to_string()]);
+        assert_eq!(document.sanitize(&known).len(), 1);
+        assert_eq!(document.tasks[1].dependencies.len(), 2);
+    }
+}
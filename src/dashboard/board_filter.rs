@@ -0,0 +1,428 @@
+// WezTerm Multi-Process Development Framework - Task Board Views
+// Server-side filter expressions and swimlane grouping of the task board
+//
+// A filter is a list of conditions joined with `AND`, alternatives joined
+// with `OR` (which binds looser, there are no parentheses):
+//
+//     priority >= High AND workspace = frontend OR tag = hotfix
+//
+// | Field       | Operators              | Value                          |
+// |-------------|------------------------|--------------------------------|
+// | `priority`  | `= != < <= > >=`       | `Low` .. `Urgent`              |
+// | `progress`  | `= != < <= > >=`       | 0-100                          |
+// | `status`    | `= !=`                 | `Todo`, `in_progress`, ...     |
+// | `category`  | `= !=`                 | `Development`, `BugFix`, ...   |
+// | `workspace` | `= !=`                 | name, `""` for none            |
+// | `assignee`  | `= !=`                 | name, `""` for none            |
+// | `tag`       | `= !=`                 | tag the task has / lacks       |
+//
+// Keywords, fields and enum values are case-insensitive; values with spaces
+// are quoted with `"`.
+
+use crate::task::{Task, TaskPriority};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// Filter and grouping a client views a board with
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BoardView {
+    /// Filter expression; all tasks when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+
+    /// Grouping of the columns into swimlanes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub swimlanes: Option<Swimlanes>,
+}
+
+impl BoardView {
+    /// Whether the view shows the board as it is
+    pub fn is_unscoped(&self) -> bool {
+        self.filter.is_none() && self.swimlanes.is_none()
+    }
+}
+
+/// Task attribute the board is split into swimlanes by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Swimlanes {
+    Workspace,
+    Assignee,
+    Priority,
+}
+
+impl Swimlanes {
+    /// Lane of a task; `None` for tasks without a workspace or assignee
+    pub fn lane(&self, task: &Task) -> Option<String> {
+        match self {
+            Swimlanes::Workspace => task.workspace.clone(),
+            Swimlanes::Assignee => task.assignee.clone(),
+            Swimlanes::Priority => Some(task.priority.to_string()),
+        }
+    }
+
+    /// Order of lanes: highest priority or alphabetical, unassigned last
+    pub fn compare(&self, a: &Option<String>, b: &Option<String>) -> Ordering {
+        match (a, b) {
+            (Some(a), Some(b)) if *self == Swimlanes::Priority => {
+                parse_priority(b).cmp(&parse_priority(a))
+            }
+            (Some(a), Some(b)) => a.cmp(b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+}
+
+impl FromStr for Swimlanes {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "workspace" => Ok(Swimlanes::Workspace),
+            "assignee" => Ok(Swimlanes::Assignee),
+            "priority" => Ok(Swimlanes::Priority),
+            _ => Err(format!(
+                "Unknown swimlanes '{s}' (expected workspace, assignee or priority)"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Priority,
+    Progress,
+    Status,
+    Category,
+    Workspace,
+    Assignee,
+    Tag,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Operator {
+    fn holds(&self, ordering: Ordering) -> bool {
+        match self {
+            Operator::Eq => ordering == Ordering::Equal,
+            Operator::Ne => ordering != Ordering::Equal,
+            Operator::Lt => ordering == Ordering::Less,
+            Operator::Le => ordering != Ordering::Greater,
+            Operator::Gt => ordering == Ordering::Greater,
+            Operator::Ge => ordering != Ordering::Less,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Priority(TaskPriority),
+    Progress(u8),
+    Text(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Condition {
+    field: Field,
+    operator: Operator,
+    value: Value,
+}
+
+impl Condition {
+    fn matches(&self, task: &Task) -> bool {
+        match (&self.field, &self.value) {
+            (Field::Priority, Value::Priority(priority)) => {
+                self.operator.holds(task.priority.cmp(priority))
+            }
+            (Field::Progress, Value::Progress(progress)) => {
+                self.operator.holds(task.progress.cmp(progress))
+            }
+            (Field::Tag, Value::Text(tag)) => {
+                let tagged = task.tags.iter().any(|t| t.eq_ignore_ascii_case(tag));
+                tagged == (self.operator == Operator::Eq)
+            }
+            (field, Value::Text(value)) => {
+                let actual = match field {
+                    Field::Status => normalize(&task.status.to_string()),
+                    Field::Category => normalize(&task.category.to_string()),
+                    Field::Workspace => task.workspace.clone().unwrap_or_default(),
+                    _ => task.assignee.clone().unwrap_or_default(),
+                };
+                (actual == *value) == (self.operator == Operator::Eq)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Parsed filter expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskFilter {
+    /// Alternatives of conditions that must all hold
+    any_of: Vec<Vec<Condition>>,
+}
+
+impl TaskFilter {
+    /// Parse a filter expression
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let mut tokens = tokenize(expression)?.into_iter();
+        let mut any_of = vec![Vec::new()];
+
+        loop {
+            let field = match tokens.next() {
+                Some(Token::Word(word)) => parse_field(&word)?,
+                Some(token) => return Err(format!("Expected a field, found {token}")),
+                None => return Err("Filter ends without a condition".to_string()),
+            };
+            let operator = match tokens.next() {
+                Some(Token::Operator(operator)) => operator,
+                Some(token) => return Err(format!("Expected an operator, found {token}")),
+                None => return Err("Filter ends without an operator".to_string()),
+            };
+            let value = match tokens.next() {
+                Some(Token::Word(value) | Token::Quoted(value)) => value,
+                Some(token) => return Err(format!("Expected a value, found {token}")),
+                None => return Err("Filter ends without a value".to_string()),
+            };
+            let condition = parse_condition(field, operator, &value)?;
+            if let Some(last) = any_of.last_mut() {
+                last.push(condition);
+            }
+
+            match tokens.next() {
+                None => break,
+                Some(Token::Word(word)) if word.eq_ignore_ascii_case("and") => {}
+                Some(Token::Word(word)) if word.eq_ignore_ascii_case("or") => {
+                    any_of.push(Vec::new())
+                }
+                Some(token) => return Err(format!("Expected AND or OR, found {token}")),
+            }
+        }
+        Ok(Self { any_of })
+    }
+
+    /// Whether a task passes the filter
+    pub fn matches(&self, task: &Task) -> bool {
+        self.any_of
+            .iter()
+            .any(|conditions| conditions.iter().all(|c| c.matches(task)))
+    }
+}
+
+fn parse_field(word: &str) -> Result<Field, String> {
+    Ok(match word.to_ascii_lowercase().as_str() {
+        "priority" => Field::Priority,
+        "progress" => Field::Progress,
+        "status" => Field::Status,
+        "category" => Field::Category,
+        "workspace" => Field::Workspace,
+        "assignee" => Field::Assignee,
+        "tag" | "tags" => Field::Tag,
+        _ => return Err(format!("Unknown filter field '{word}'")),
+    })
+}
+
+fn parse_condition(field: Field, operator: Operator, value: &str) -> Result<Condition, String> {
+    let ordered = matches!(field, Field::Priority | Field::Progress);
+    if !ordered && !matches!(operator, Operator::Eq | Operator::Ne) {
+        return Err(format!("{field:?} can only be compared with = or !="));
+    }
+    let value = match field {
+        Field::Priority => Value::Priority(
+            parse_priority(value).ok_or_else(|| format!("Unknown priority '{value}'"))?,
+        ),
+        Field::Progress => Value::Progress(
+            value
+                .parse()
+                .map_err(|_| format!("Invalid progress '{value}'"))?,
+        ),
+        Field::Status | Field::Category => Value::Text(normalize(value)),
+        Field::Workspace | Field::Assignee | Field::Tag => Value::Text(value.to_string()),
+    };
+    Ok(Condition {
+        field,
+        operator,
+        value,
+    })
+}
+
+fn parse_priority(value: &str) -> Option<TaskPriority> {
+    [
+        TaskPriority::Low,
+        TaskPriority::Medium,
+        TaskPriority::High,
+        TaskPriority::Critical,
+        TaskPriority::Urgent,
+    ]
+    .into_iter()
+    .find(|priority| normalize(&priority.to_string()) == normalize(value))
+}
+
+/// Lowercase letters and digits only, so that `in_progress`, `InProgress`
+/// and `In Progress` are the same status
+fn normalize(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Operator(Operator),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Word(word) => write!(f, "'{word}'"),
+            Token::Quoted(value) => write!(f, "\"{value}\""),
+            Token::Operator(operator) => write!(f, "{operator:?}"),
+        }
+    }
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = expression.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let mut value = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some(c) => value.push(c),
+                    None => return Err("Unterminated quoted value".to_string()),
+                }
+            }
+            tokens.push(Token::Quoted(value));
+        } else if matches!(c, '=' | '!' | '<' | '>') {
+            chars.next();
+            let followed_by_eq = chars.next_if_eq(&'=').is_some();
+            let operator = match (c, followed_by_eq) {
+                ('=', _) => Operator::Eq,
+                ('!', true) => Operator::Ne,
+                ('<', false) => Operator::Lt,
+                ('<', true) => Operator::Le,
+                ('>', false) => Operator::Gt,
+                ('>', true) => Operator::Ge,
+                _ => return Err("Expected != after !".to_string()),
+            };
+            tokens.push(Token::Operator(operator));
+        } else {
+            let mut word = String::new();
+            while let Some(c) =
+                chars.next_if(|c| !c.is_whitespace() && !matches!(c, '"' | '=' | '!' | '<' | '>'))
+            {
+                word.push(c);
+            }
+            tokens.push(Token::Word(word));
+        }
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{TaskCategory, TaskStatus};
+
+    fn task(priority: TaskPriority, workspace: Option<&str>) -> Task {
+        let mut task = Task::new("Task".to_string(), TaskCategory::BugFix);
+        task.priority = priority;
+        task.workspace = workspace.map(str::to_string);
+        task
+    }
+
+    #[test]
+    fn test_and_binds_tighter_than_or() {
+        let filter =
+            TaskFilter::parse("priority >= High AND workspace = frontend OR tag = hotfix").unwrap();
+
+        assert!(filter.matches(&task(TaskPriority::Critical, Some("frontend"))));
+        assert!(!filter.matches(&task(TaskPriority::Medium, Some("frontend"))));
+        assert!(!filter.matches(&task(TaskPriority::Urgent, Some("backend"))));
+
+        let mut hotfix = task(TaskPriority::Low, None);
+        hotfix.tags.push("hotfix".to_string());
+        assert!(filter.matches(&hotfix));
+    }
+
+    #[test]
+    fn test_enum_values_are_normalized() {
+        let mut in_progress = task(TaskPriority::Medium, None);
+        in_progress.status = TaskStatus::InProgress;
+
+        for expression in [
+            "status = in_progress",
+            "STATUS=InProgress",
+            "status = \"In Progress\" and category = bug_fix",
+            "workspace = \"\" AND priority<high",
+        ] {
+            let filter = TaskFilter::parse(expression).unwrap();
+            assert!(filter.matches(&in_progress), "{expression}");
+        }
+        assert!(!TaskFilter::parse("status != todo AND progress > 0")
+            .unwrap()
+            .matches(&in_progress));
+    }
+
+    #[test]
+    fn test_invalid_filters_are_rejected() {
+        for (expression, error) in [
+            ("owner = me", "Unknown filter field 'owner'"),
+            ("priority >= Highest", "Unknown priority 'Highest'"),
+            (
+                "workspace > a",
+                "Workspace can only be compared with = or !=",
+            ),
+            ("priority = High AND", "Filter ends without a condition"),
+            (
+                "priority = High workspace = a",
+                "Expected AND or OR, found 'workspace'",
+            ),
+            ("tag = \"open", "Unterminated quoted value"),
+        ] {
+            assert_eq!(TaskFilter::parse(expression).unwrap_err(), error);
+        }
+    }
+
+    #[test]
+    fn test_priority_lanes_sort_highest_first() {
+        let mut lanes = vec![
+            None,
+            Some("Low".to_string()),
+            Some("Urgent".to_string()),
+            Some("Medium".to_string()),
+        ];
+        lanes.sort_by(|a, b| Swimlanes::Priority.compare(a, b));
+        assert_eq!(
+            lanes,
+            vec![
+                Some("Urgent".to_string()),
+                Some("Medium".to_string()),
+                Some("Low".to_string()),
+                None,
+            ]
+        );
+    }
+}
@@ -0,0 +1,437 @@
+// WezTerm Multi-Process Development Framework - Dashboard Topic Channels
+// Partitions dashboard broadcasts into per-topic channels (workspace, process
+// output, ...) so that each client only wakes up for what it subscribed to
+
+use super::{DashboardMessage, MessageBacklog, MetricSubscription};
+use futures_util::future::select_all;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use tokio::sync::{broadcast, watch};
+
+/// Capacity of each topic channel
+pub const DEFAULT_TOPIC_CAPACITY: usize = 100;
+
+/// Published sequence numbers a subscriber remembers to drop duplicates
+const DEDUP_WINDOW: usize = 64;
+
+/// Partition of the dashboard broadcast stream
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Topic {
+    /// Everything that is not metrics or process output (alerts, status
+    /// changes, task board, heartbeats); every client receives it
+    Events,
+
+    /// Every metrics update (`MetricSubscription::All`)
+    Metrics,
+
+    /// Metrics updates carrying system metrics
+    System,
+
+    /// Metrics updates with process metrics of a workspace
+    Process(String),
+
+    /// Metrics updates with metrics of a workspace
+    Workspace(String),
+
+    /// Live output of a process
+    ProcessOutput(String),
+}
+
+impl fmt::Display for Topic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Topic::Events => f.write_str("events"),
+            Topic::Metrics => f.write_str("metrics"),
+            Topic::System => f.write_str("system"),
+            Topic::Process(workspace) => write!(f, "process:{workspace}"),
+            Topic::Workspace(workspace) => write!(f, "workspace:{workspace}"),
+            Topic::ProcessOutput(process_id) => write!(f, "output:{process_id}"),
+        }
+    }
+}
+
+impl Topic {
+    /// Topics a message is published on
+    pub fn of(message: &DashboardMessage) -> Vec<Topic> {
+        match message {
+            DashboardMessage::MetricsUpdate(update) => {
+                let mut topics = vec![Topic::Metrics];
+                if update.system.is_some() {
+                    topics.push(Topic::System);
+                }
+                let workspaces: HashSet<&String> =
+                    update.processes.iter().map(|p| &p.workspace).collect();
+                topics.extend(workspaces.into_iter().map(|w| Topic::Process(w.clone())));
+                let workspaces: HashSet<&String> = update
+                    .workspaces
+                    .iter()
+                    .map(|w| &w.workspace_name)
+                    .collect();
+                topics.extend(workspaces.into_iter().map(|w| Topic::Workspace(w.clone())));
+                topics
+            }
+            DashboardMessage::ProcessOutput { process_id, .. } => {
+                vec![Topic::ProcessOutput(process_id.clone())]
+            }
+            _ => vec![Topic::Events],
+        }
+    }
+
+    /// Topics a client with these subscriptions listens on
+    pub fn for_subscriptions(subscriptions: &[MetricSubscription]) -> HashSet<Topic> {
+        let mut topics = HashSet::from([Topic::Events]);
+        for subscription in subscriptions {
+            match subscription {
+                MetricSubscription::All => {
+                    topics.insert(Topic::Metrics);
+                }
+                MetricSubscription::System => {
+                    topics.insert(Topic::System);
+                }
+                MetricSubscription::Process(workspace) => {
+                    topics.insert(Topic::Process(workspace.clone()));
+                }
+                MetricSubscription::Workspace(workspace) => {
+                    topics.insert(Topic::Workspace(workspace.clone()));
+                }
+                MetricSubscription::ProcessOutput(process_id) => {
+                    topics.insert(Topic::ProcessOutput(process_id.clone()));
+                }
+                // Log tails are read per client, not broadcast
+                MetricSubscription::Alerts
+                | MetricSubscription::Performance
+                | MetricSubscription::LogTail { .. } => {}
+            }
+        }
+        topics
+    }
+}
+
+/// A message as delivered on topic channels
+#[derive(Debug)]
+pub struct Published {
+    /// Publication number, shared by all topics the message went to
+    pub seq: u64,
+
+    /// Sequence number in the backlog, which clients resume from; `None`
+    /// for messages not kept there (process output)
+    pub backlog_seq: Option<u64>,
+    pub message: DashboardMessage,
+}
+
+/// Subscribers of a topic, for the status endpoint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopicStats {
+    pub topic: String,
+    pub subscribers: usize,
+}
+
+/// Per-topic broadcast channels fed from the global dashboard channel
+///
+/// Producers keep sending on `DashboardState::broadcast_tx`; a router task
+/// records each message in the backlog and republishes it on its topics. Channels are created when the
+/// first client subscribes and dropped once nobody listens, so messages for
+/// topics without subscribers cost a map lookup and nothing else.
+pub struct TopicChannels {
+    capacity: usize,
+    channels: RwLock<HashMap<Topic, broadcast::Sender<Arc<Published>>>>,
+    next_seq: AtomicU64,
+
+    /// Bumped whenever a client changes its subscriptions
+    resubscribe_tx: watch::Sender<u64>,
+}
+
+impl Default for TopicChannels {
+    fn default() -> Self {
+        Self::new(DEFAULT_TOPIC_CAPACITY)
+    }
+}
+
+impl TopicChannels {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            channels: RwLock::new(HashMap::new()),
+            next_seq: AtomicU64::new(1),
+            resubscribe_tx: watch::channel(0).0,
+        }
+    }
+
+    /// Publish a message on its topics; returns the number of topics with subscribers
+    pub fn publish(&self, message: DashboardMessage) -> usize {
+        self.publish_recorded(message, None)
+    }
+
+    /// Publish a message recorded in the backlog under `backlog_seq`
+    pub fn publish_recorded(&self, message: DashboardMessage, backlog_seq: Option<u64>) -> usize {
+        let topics = Topic::of(&message);
+        let senders: Vec<_> = {
+            let channels = self.channels.read().unwrap();
+            topics
+                .iter()
+                .filter_map(|topic| channels.get(topic))
+                .filter(|sender| sender.receiver_count() > 0)
+                .cloned()
+                .collect()
+        };
+        if senders.is_empty() {
+            return 0;
+        }
+
+        let published = Arc::new(Published {
+            seq: self.next_seq.fetch_add(1, Ordering::Relaxed),
+            backlog_seq,
+            message,
+        });
+        senders
+            .iter()
+            .filter(|sender| sender.send(Arc::clone(&published)).is_ok())
+            .count()
+    }
+
+    /// Receiver of one topic, creating its channel if needed
+    pub fn subscribe(&self, topic: &Topic) -> broadcast::Receiver<Arc<Published>> {
+        let mut channels = self.channels.write().unwrap();
+        // Forget channels whose subscribers are gone
+        channels.retain(|_, sender| sender.receiver_count() > 0);
+        channels
+            .entry(topic.clone())
+            .or_insert_with(|| broadcast::channel(self.capacity).0)
+            .subscribe()
+    }
+
+    /// Tell subscribers that some client changed its subscriptions
+    pub fn subscriptions_changed(&self) {
+        self.resubscribe_tx.send_modify(|epoch| *epoch += 1);
+    }
+
+    /// Watch for subscription changes
+    pub fn resubscriptions(&self) -> watch::Receiver<u64> {
+        self.resubscribe_tx.subscribe()
+    }
+
+    /// Topics with subscribers, sorted
+    pub fn stats(&self) -> Vec<TopicStats> {
+        let channels = self.channels.read().unwrap();
+        let mut topics: Vec<_> = channels
+            .iter()
+            .filter(|(_, sender)| sender.receiver_count() > 0)
+            .collect();
+        topics.sort_by(|a, b| a.0.cmp(b.0));
+        topics
+            .into_iter()
+            .map(|(topic, sender)| TopicStats {
+                topic: topic.to_string(),
+                subscribers: sender.receiver_count(),
+            })
+            .collect()
+    }
+
+    /// Spawn a task recording every message of the global channel in the
+    /// backlog and republishing it
+    ///
+    /// Recording comes first, so a client replaying the backlog has seen
+    /// everything up to the sequence number of the next message it receives.
+    pub fn spawn_router(
+        self: &Arc<Self>,
+        mut broadcast_rx: broadcast::Receiver<DashboardMessage>,
+        backlog: Arc<MessageBacklog>,
+    ) -> tokio::task::JoinHandle<()> {
+        let channels = Arc::clone(self);
+
+        tokio::spawn(async move {
+            loop {
+                match broadcast_rx.recv().await {
+                    Ok(message) => {
+                        let backlog_seq = backlog.record(&message).await;
+                        channels.publish_recorded(message, backlog_seq);
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Dashboard topic router lagged, {} messages lost", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+}
+
+/// One client's receivers on the topics of its subscriptions
+///
+/// A metrics update touching several subscribed topics is delivered once.
+pub struct TopicSubscriber {
+    receivers: Vec<(Topic, broadcast::Receiver<Arc<Published>>)>,
+    recent: VecDeque<u64>,
+}
+
+impl TopicSubscriber {
+    pub fn new(channels: &TopicChannels, subscriptions: &[MetricSubscription]) -> Self {
+        let mut subscriber = Self {
+            receivers: Vec::new(),
+            recent: VecDeque::with_capacity(DEDUP_WINDOW),
+        };
+        subscriber.resubscribe(channels, subscriptions);
+        subscriber
+    }
+
+    /// Follow changed subscriptions, keeping the receivers of unchanged topics
+    pub fn resubscribe(&mut self, channels: &TopicChannels, subscriptions: &[MetricSubscription]) {
+        let mut wanted = Topic::for_subscriptions(subscriptions);
+        self.receivers.retain(|(topic, _)| wanted.remove(topic));
+        let mut added: Vec<_> = wanted.into_iter().collect();
+        added.sort();
+        for topic in added {
+            let receiver = channels.subscribe(&topic);
+            self.receivers.push((topic, receiver));
+        }
+    }
+
+    /// Topics currently listened on
+    pub fn topics(&self) -> Vec<&Topic> {
+        self.receivers.iter().map(|(topic, _)| topic).collect()
+    }
+
+    /// Next message on any topic; `None` once all channels are closed
+    ///
+    /// Cancel safe: a message is only consumed when this returns it.
+    pub async fn recv(&mut self) -> Option<Arc<Published>> {
+        loop {
+            if self.receivers.is_empty() {
+                return None;
+            }
+
+            let (result, index, _) = select_all(
+                self.receivers
+                    .iter_mut()
+                    .map(|(_, receiver)| Box::pin(receiver.recv())),
+            )
+            .await;
+            match result {
+                Ok(published) => {
+                    if self.recent.contains(&published.seq) {
+                        continue;
+                    }
+                    if self.recent.len() == DEDUP_WINDOW {
+                        self.recent.pop_front();
+                    }
+                    self.recent.push_back(published.seq);
+                    return Some(published);
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!(
+                        "Dashboard subscriber lagged on {}, {} messages lost",
+                        self.receivers[index].0,
+                        skipped
+                    );
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    self.receivers.remove(index);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dashboard::MetricsUpdate;
+    use crate::metrics::{FrameworkMetrics, WorkspaceMetrics};
+    use std::time::Duration;
+
+    fn workspace_update(names: &[&str]) -> DashboardMessage {
+        let mut update = MetricsUpdate::full(FrameworkMetrics::new());
+        update.system = None;
+        update.processes.clear();
+        update.workspaces = names
+            .iter()
+            .map(|name| WorkspaceMetrics::new(name.to_string()))
+            .collect();
+        DashboardMessage::MetricsUpdate(Box::new(update))
+    }
+
+    async fn next(subscriber: &mut TopicSubscriber) -> Option<Arc<Published>> {
+        tokio::time::timeout(Duration::from_millis(50), subscriber.recv())
+            .await
+            .ok()
+            .flatten()
+    }
+
+    #[tokio::test]
+    async fn test_clients_only_receive_their_topics() {
+        let channels = TopicChannels::default();
+        let mut alpha = TopicSubscriber::new(
+            &channels,
+            &[
+                MetricSubscription::Workspace("alpha".to_string()),
+                MetricSubscription::Workspace("beta".to_string()),
+            ],
+        );
+        let mut gamma = TopicSubscriber::new(
+            &channels,
+            &[MetricSubscription::Workspace("gamma".to_string())],
+        );
+
+        // Touches both of alpha's topics, delivered once
+        assert_eq!(channels.publish(workspace_update(&["alpha", "beta"])), 2);
+        assert!(next(&mut alpha).await.is_some());
+        assert!(next(&mut alpha).await.is_none());
+        assert!(next(&mut gamma).await.is_none());
+
+        // Everybody hears events
+        channels.publish(DashboardMessage::Heartbeat { timestamp: 1 });
+        assert!(next(&mut alpha).await.is_some());
+        assert!(next(&mut gamma).await.is_some());
+
+        // Nobody listens to delta
+        assert_eq!(channels.publish(workspace_update(&["delta"])), 0);
+    }
+
+    #[tokio::test]
+    async fn test_resubscribe_and_router() {
+        let channels = Arc::new(TopicChannels::default());
+        let (tx, rx) = broadcast::channel(16);
+        let backlog = Arc::new(MessageBacklog::new(10));
+        let router = channels.spawn_router(rx, Arc::clone(&backlog));
+
+        let mut subscriber = TopicSubscriber::new(&channels, &[]);
+        assert_eq!(subscriber.topics(), [&Topic::Events]);
+
+        subscriber.resubscribe(
+            &channels,
+            &[MetricSubscription::ProcessOutput("p1".to_string())],
+        );
+        assert_eq!(
+            subscriber.topics(),
+            [&Topic::Events, &Topic::ProcessOutput("p1".to_string())]
+        );
+
+        tx.send(DashboardMessage::ProcessOutput {
+            process_id: "p1".to_string(),
+            lines: Vec::new(),
+        })
+        .unwrap();
+        let published = next(&mut subscriber).await.unwrap();
+        assert!(matches!(
+            published.message,
+            DashboardMessage::ProcessOutput { .. }
+        ));
+        assert_eq!(published.backlog_seq, None);
+
+        tx.send(DashboardMessage::Heartbeat { timestamp: 1 })
+            .unwrap();
+        let published = next(&mut subscriber).await.unwrap();
+        assert_eq!(published.backlog_seq, Some(1));
+        assert_eq!(backlog.latest_seq().await, 1);
+
+        // Dropped receivers leave no channel behind
+        subscriber.resubscribe(&channels, &[]);
+        let topics: Vec<_> = channels.stats().into_iter().map(|s| s.topic).collect();
+        assert_eq!(topics, ["events"]);
+
+        router.abort();
+    }
+}
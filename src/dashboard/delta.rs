@@ -0,0 +1,337 @@
+// WezTerm Multi-Process Development Framework - Dashboard Metrics Deltas
+// Per-client delta encoding of full metrics updates
+//
+// Every full update sent to a WebSocket client carries a per-client
+// `version`. Once the client acknowledges a version (`AckMetrics`), later
+// updates are sent as a `MetricsDelta` holding only the fields that changed
+// since that version. A full update is sent again every `FULL_UPDATE_EVERY`
+// updates, and deltas resume once the client acknowledges it. Clients that
+// never acknowledge keep receiving full updates.
+
+use super::{DashboardMessage, MetricsUpdate, UpdateType};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+/// Updates after which a full update is sent instead of a delta
+pub const FULL_UPDATE_EVERY: u32 = 30;
+
+/// Sent versions a client can still acknowledge
+const ACKNOWLEDGE_WINDOW: usize = 16;
+
+/// Top-level fields of a metrics struct, by name
+pub type Fields = serde_json::Map<String, serde_json::Value>;
+
+/// Changes of a full metrics update relative to an acknowledged one
+///
+/// Applied to the client's copy of update `base`, it yields update
+/// `version`. Processes and workspaces are keyed by ID and list only their
+/// changed fields (all fields when new). `framework` leaves out `system` and
+/// `workspaces`, which are carried by the fields of the same name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetricsDelta {
+    pub version: u64,
+    pub base: u64,
+    pub timestamp: u64,
+
+    #[serde(default, skip_serializing_if = "Fields::is_empty")]
+    pub system: Fields,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub processes: BTreeMap<String, Fields>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed_processes: Vec<String>,
+
+    /// Workspace fields other than `processes`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub workspaces: BTreeMap<String, Fields>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed_workspaces: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Fields::is_empty")]
+    pub framework: Fields,
+}
+
+/// Fields of a full update, as the base of deltas
+#[derive(Debug, Clone, Default)]
+struct Snapshot {
+    version: u64,
+    system: Fields,
+    processes: HashMap<String, Fields>,
+    workspaces: HashMap<String, Fields>,
+    framework: Fields,
+}
+
+impl Snapshot {
+    /// Snapshot of a full update; partial updates cannot be diffed
+    fn of(update: &MetricsUpdate, version: u64) -> Option<Self> {
+        if update.update_type != UpdateType::Full {
+            return None;
+        }
+        let mut snapshot = Self {
+            version,
+            system: fields(&update.system)?,
+            framework: fields(&update.framework)?,
+            ..Default::default()
+        };
+        snapshot.framework.remove("system");
+        snapshot.framework.remove("workspaces");
+        for process in &update.processes {
+            snapshot
+                .processes
+                .insert(process.process_id.clone(), fields(process)?);
+        }
+        for workspace in &update.workspaces {
+            let mut workspace_fields = fields(workspace)?;
+            workspace_fields.remove("processes");
+            snapshot
+                .workspaces
+                .insert(workspace.workspace_name.clone(), workspace_fields);
+        }
+        Some(snapshot)
+    }
+
+    /// Changes from this snapshot to a newer one
+    fn diff(&self, newer: &Snapshot, timestamp: u64) -> MetricsDelta {
+        let (processes, removed_processes) = diff_keyed(&self.processes, &newer.processes);
+        let (workspaces, removed_workspaces) = diff_keyed(&self.workspaces, &newer.workspaces);
+        MetricsDelta {
+            version: newer.version,
+            base: self.version,
+            timestamp,
+            system: diff_fields(&self.system, &newer.system),
+            processes,
+            removed_processes,
+            workspaces,
+            removed_workspaces,
+            framework: diff_fields(&self.framework, &newer.framework),
+        }
+    }
+}
+
+/// Top-level fields of a value; empty for `None`
+fn fields<T: Serialize>(value: &T) -> Option<Fields> {
+    match serde_json::to_value(value).ok()? {
+        serde_json::Value::Object(fields) => Some(fields),
+        serde_json::Value::Null => Some(Fields::new()),
+        _ => None,
+    }
+}
+
+/// Fields of `newer` that are new or differ from `older`
+fn diff_fields(older: &Fields, newer: &Fields) -> Fields {
+    newer
+        .iter()
+        .filter(|(name, value)| older.get(*name) != Some(*value))
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect()
+}
+
+/// Changed fields per ID, and the IDs that are gone
+fn diff_keyed(
+    older: &HashMap<String, Fields>,
+    newer: &HashMap<String, Fields>,
+) -> (BTreeMap<String, Fields>, Vec<String>) {
+    let changed = newer
+        .iter()
+        .filter_map(|(id, fields)| {
+            let changes = match older.get(id) {
+                Some(previous) => diff_fields(previous, fields),
+                None => fields.clone(),
+            };
+            (!changes.is_empty()).then(|| (id.clone(), changes))
+        })
+        .collect();
+    let mut removed: Vec<String> = older
+        .keys()
+        .filter(|id| !newer.contains_key(*id))
+        .cloned()
+        .collect();
+    removed.sort();
+    (changed, removed)
+}
+
+/// Delta state of one client
+#[derive(Debug, Default)]
+pub struct DeltaEncoder {
+    last_version: u64,
+
+    /// Sent full-update snapshots not yet acknowledged, oldest first
+    sent: VecDeque<Snapshot>,
+
+    /// Base of the deltas, once the client acknowledged an update
+    acknowledged: Option<Snapshot>,
+
+    /// Deltas sent since the last full update
+    since_full: u32,
+}
+
+impl DeltaEncoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Message to send for a broadcast metrics update
+    ///
+    /// Full updates get the next version and become a delta when the client
+    /// has acknowledged an earlier one; other updates are sent as they are.
+    pub fn encode(&mut self, update: &MetricsUpdate) -> DashboardMessage {
+        let Some(snapshot) = Snapshot::of(update, self.last_version + 1) else {
+            return DashboardMessage::MetricsUpdate(Box::new(update.clone()));
+        };
+        self.last_version = snapshot.version;
+
+        let message = match &self.acknowledged {
+            Some(base) if self.since_full < FULL_UPDATE_EVERY => {
+                self.since_full += 1;
+                DashboardMessage::MetricsDelta(Box::new(base.diff(&snapshot, update.timestamp)))
+            }
+            _ => {
+                // Deltas resume once the client acknowledges this update
+                self.acknowledged = None;
+                self.since_full = 0;
+                let mut full = update.clone();
+                full.version = Some(snapshot.version);
+                DashboardMessage::MetricsUpdate(Box::new(full))
+            }
+        };
+
+        self.sent.push_back(snapshot);
+        if self.sent.len() > ACKNOWLEDGE_WINDOW {
+            self.sent.pop_front();
+        }
+        message
+    }
+
+    /// Make a received version the base of later deltas; false when it is
+    /// unknown, older than the current base or no longer kept
+    pub fn acknowledge(&mut self, version: u64) -> bool {
+        let Some(position) = self.sent.iter().position(|s| s.version == version) else {
+            return false;
+        };
+        self.sent.drain(..position);
+        self.acknowledged = self.sent.pop_front();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{FrameworkMetrics, ProcessMetrics, WorkspaceMetrics};
+
+    fn framework(processes: &[(&str, f64)]) -> FrameworkMetrics {
+        let mut workspace = WorkspaceMetrics::new("dev".to_string());
+        workspace.update_from_processes(
+            processes
+                .iter()
+                .map(|(id, cpu)| {
+                    let mut process = ProcessMetrics::new(id.to_string(), "dev".to_string());
+                    process.cpu_usage = *cpu;
+                    process.timestamp = 1;
+                    process.last_activity = 1;
+                    process
+                })
+                .collect(),
+        );
+        workspace.timestamp = 1;
+        workspace.last_activity = 1;
+        let mut framework = FrameworkMetrics::new();
+        framework.update_from_workspaces(vec![workspace]);
+        framework.timestamp = 1;
+        framework.system.timestamp = 1;
+        framework
+    }
+
+    fn update(processes: &[(&str, f64)]) -> MetricsUpdate {
+        MetricsUpdate::full(framework(processes))
+    }
+
+    fn expect_delta(message: DashboardMessage) -> MetricsDelta {
+        match message {
+            DashboardMessage::MetricsDelta(delta) => *delta,
+            other => panic!("Expected a delta, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_full_updates_until_acknowledged() {
+        let mut encoder = DeltaEncoder::new();
+        for version in 1..=3 {
+            match encoder.encode(&update(&[("a", 1.0)])) {
+                DashboardMessage::MetricsUpdate(full) => assert_eq!(full.version, Some(version)),
+                other => panic!("Expected a full update, got {other:?}"),
+            }
+        }
+
+        assert!(encoder.acknowledge(2));
+        // Older than the base now
+        assert!(!encoder.acknowledge(1));
+        let delta = expect_delta(encoder.encode(&update(&[("a", 1.0)])));
+        assert_eq!((delta.base, delta.version), (2, 4));
+    }
+
+    #[test]
+    fn test_delta_carries_only_changed_fields() {
+        let mut encoder = DeltaEncoder::new();
+        encoder.encode(&update(&[("a", 1.0), ("b", 2.0), ("c", 3.0)]));
+        assert!(encoder.acknowledge(1));
+
+        let delta = expect_delta(encoder.encode(&update(&[("a", 1.0), ("b", 8.0), ("d", 0.0)])));
+        assert!(delta.system.is_empty());
+        assert!(!delta.processes.contains_key("a"));
+        assert_eq!(
+            delta.processes["b"],
+            Fields::from_iter([("cpu_usage".to_string(), serde_json::json!(8.0))])
+        );
+        // New processes are sent whole
+        assert_eq!(
+            delta.processes["d"].len(),
+            fields(&ProcessMetrics::new("d".to_string(), "dev".to_string()))
+                .unwrap()
+                .len()
+        );
+        assert_eq!(delta.removed_processes, vec!["c".to_string()]);
+        assert!(!delta.workspaces["dev"].contains_key("processes"));
+        assert!(!delta.framework.contains_key("workspaces"));
+    }
+
+    #[test]
+    fn test_periodic_full_update_restarts_deltas() {
+        let mut encoder = DeltaEncoder::new();
+        encoder.encode(&update(&[("a", 1.0)]));
+        assert!(encoder.acknowledge(1));
+        for _ in 0..FULL_UPDATE_EVERY {
+            expect_delta(encoder.encode(&update(&[("a", 1.0)])));
+        }
+
+        let version = match encoder.encode(&update(&[("a", 1.0)])) {
+            DashboardMessage::MetricsUpdate(full) => full.version.unwrap(),
+            other => panic!("Expected a full update, got {other:?}"),
+        };
+        assert!(matches!(
+            encoder.encode(&update(&[("a", 1.0)])),
+            DashboardMessage::MetricsUpdate(_)
+        ));
+        assert!(encoder.acknowledge(version));
+        assert_eq!(
+            expect_delta(encoder.encode(&update(&[("a", 1.0)]))).base,
+            version
+        );
+    }
+
+    #[test]
+    fn test_partial_updates_pass_through() {
+        let mut encoder = DeltaEncoder::new();
+        encoder.encode(&update(&[("a", 1.0)]));
+        assert!(encoder.acknowledge(1));
+
+        let partial = MetricsUpdate::incremental(None, Vec::new(), Vec::new());
+        match encoder.encode(&partial) {
+            DashboardMessage::MetricsUpdate(update) => assert_eq!(update.version, None),
+            other => panic!("Expected the update itself, got {other:?}"),
+        }
+        assert!(!encoder.acknowledge(2));
+    }
+}
@@ -0,0 +1,230 @@
+// WezTerm Multi-Process Development Framework - Dashboard Stream Encoding
+// Per-client wire format of the WebSocket stream, negotiated as a subprotocol
+//
+// Clients list the formats they understand in `Sec-WebSocket-Protocol`, best
+// first, and the server picks the first one it supports:
+//
+// | Subprotocol                        | Frames                          |
+// |------------------------------------|---------------------------------|
+// | (none) / `wezterm-parallel.json`   | text, JSON                      |
+// | `wezterm-parallel.msgpack`         | binary, MessagePack             |
+// | `wezterm-parallel.json+deflate`    | binary, deflate-compressed JSON |
+// | `wezterm-parallel.msgpack+deflate` | binary, deflate-compressed MessagePack |
+//
+// Compression is per message (raw DEFLATE, RFC 1951), since the WebSocket
+// library rejects frames of the `permessage-deflate` extension. The `+deflate`
+// formats are only offered when `DashboardConfig::compression` is on.
+
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt;
+use std::io::{Read, Write};
+use tokio_tungstenite::tungstenite::Message;
+
+/// Prefix of the dashboard subprotocols
+pub const PROTOCOL_PREFIX: &str = "wezterm-parallel.";
+
+/// Largest decompressed client message accepted, against deflate bombs
+const MAX_DECODED_BYTES: u64 = 1024 * 1024;
+
+/// Serialization of a dashboard message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Json,
+    MessagePack,
+}
+
+/// Wire format of one client's stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamFormat {
+    pub encoding: Encoding,
+    pub deflate: bool,
+}
+
+impl Default for StreamFormat {
+    fn default() -> Self {
+        Self::JSON
+    }
+}
+
+impl fmt::Display for StreamFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(PROTOCOL_PREFIX)?;
+        f.write_str(match self.encoding {
+            Encoding::Json => "json",
+            Encoding::MessagePack => "msgpack",
+        })?;
+        if self.deflate {
+            f.write_str("+deflate")?;
+        }
+        Ok(())
+    }
+}
+
+impl StreamFormat {
+    /// Plain JSON text frames, for clients that do not negotiate
+    pub const JSON: Self = Self {
+        encoding: Encoding::Json,
+        deflate: false,
+    };
+
+    /// Format named by a subprotocol, if it is a dashboard one
+    pub fn parse(protocol: &str) -> Option<Self> {
+        let name = protocol.trim().strip_prefix(PROTOCOL_PREFIX)?;
+        let (encoding, deflate) = match name.strip_suffix("+deflate") {
+            Some(encoding) => (encoding, true),
+            None => (name, false),
+        };
+        let encoding = match encoding {
+            "json" => Encoding::Json,
+            "msgpack" => Encoding::MessagePack,
+            _ => return None,
+        };
+        Some(Self { encoding, deflate })
+    }
+
+    /// First supported format of a `Sec-WebSocket-Protocol` header value
+    pub fn negotiate(offered: &str, compression: bool) -> Option<Self> {
+        offered
+            .split(',')
+            .filter_map(Self::parse)
+            .find(|format| compression || !format.deflate)
+    }
+
+    /// Frame carrying a message in this format
+    pub fn encode<T: Serialize>(&self, value: &T) -> Result<Message, String> {
+        if *self == Self::JSON {
+            return serde_json::to_string(value)
+                .map(Message::Text)
+                .map_err(|e| e.to_string());
+        }
+
+        let bytes = match self.encoding {
+            Encoding::Json => serde_json::to_vec(value).map_err(|e| e.to_string())?,
+            // Named fields, so that tagged enums and skipped fields round-trip
+            Encoding::MessagePack => rmp_serde::to_vec_named(value).map_err(|e| e.to_string())?,
+        };
+        if !self.deflate {
+            return Ok(Message::Binary(bytes));
+        }
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::fast());
+        encoder.write_all(&bytes).map_err(|e| e.to_string())?;
+        encoder
+            .finish()
+            .map(Message::Binary)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Message of a client frame: text frames are always JSON, binary frames
+    /// are in this format
+    pub fn decode<T: DeserializeOwned>(&self, message: &Message) -> Result<T, String> {
+        let bytes = match message {
+            Message::Text(text) => return serde_json::from_str(text).map_err(|e| e.to_string()),
+            Message::Binary(bytes) => bytes,
+            _ => return Err("Not a data frame".to_string()),
+        };
+
+        let inflated;
+        let bytes = if self.deflate {
+            let mut decoded = Vec::new();
+            DeflateDecoder::new(bytes.as_slice())
+                .take(MAX_DECODED_BYTES + 1)
+                .read_to_end(&mut decoded)
+                .map_err(|e| e.to_string())?;
+            if decoded.len() as u64 > MAX_DECODED_BYTES {
+                return Err("Decompressed message too large".to_string());
+            }
+            inflated = decoded;
+            &inflated
+        } else {
+            bytes
+        };
+        match self.encoding {
+            Encoding::Json => serde_json::from_slice(bytes).map_err(|e| e.to_string()),
+            Encoding::MessagePack => rmp_serde::from_slice(bytes).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dashboard::{DashboardMessage, MetricsUpdate, WebSocketMessage};
+    use crate::metrics::{FrameworkMetrics, ProcessMetrics, WorkspaceMetrics};
+
+    fn full_update() -> WebSocketMessage {
+        let mut framework = FrameworkMetrics::new();
+        let workspaces = (0..4)
+            .map(|w| {
+                let name = format!("workspace-{w}");
+                let mut workspace = WorkspaceMetrics::new(name.clone());
+                workspace.update_from_processes(
+                    (0..4)
+                        .map(|p| ProcessMetrics::new(format!("{name}-claude-{p}"), name.clone()))
+                        .collect(),
+                );
+                workspace
+            })
+            .collect();
+        framework.update_from_workspaces(workspaces);
+        WebSocketMessage {
+            id: None,
+            seq: Some(42),
+            payload: DashboardMessage::MetricsUpdate(Box::new(MetricsUpdate::full(framework))),
+        }
+    }
+
+    #[test]
+    fn test_negotiate_picks_first_supported_format() {
+        let offered = "graphql-ws, wezterm-parallel.msgpack+deflate, wezterm-parallel.json";
+        let format = StreamFormat::negotiate(offered, true).unwrap();
+        assert_eq!(format.to_string(), "wezterm-parallel.msgpack+deflate");
+
+        // Compressed formats are skipped when compression is off
+        assert_eq!(
+            StreamFormat::negotiate(offered, false),
+            Some(StreamFormat::JSON)
+        );
+        assert_eq!(StreamFormat::negotiate("wezterm-parallel.xml", true), None);
+    }
+
+    #[test]
+    fn test_every_format_round_trips() {
+        let message = full_update();
+        for protocol in ["json", "msgpack", "json+deflate", "msgpack+deflate"] {
+            let format = StreamFormat::parse(&format!("{PROTOCOL_PREFIX}{protocol}")).unwrap();
+            let frame = format.encode(&message).unwrap();
+            assert_eq!(
+                matches!(frame, Message::Text(_)),
+                format == StreamFormat::JSON
+            );
+
+            let decoded: WebSocketMessage = format.decode(&frame).unwrap();
+            assert_eq!(decoded.seq, Some(42));
+            let DashboardMessage::MetricsUpdate(update) = decoded.payload else {
+                panic!("{protocol} decoded to another message");
+            };
+            assert_eq!(update.processes.len(), 16);
+        }
+    }
+
+    #[test]
+    fn test_binary_formats_are_smaller_than_json() {
+        let message = full_update();
+        let json = StreamFormat::JSON.encode(&message).unwrap().len();
+        let msgpack = StreamFormat {
+            encoding: Encoding::MessagePack,
+            deflate: false,
+        };
+        let deflated = StreamFormat {
+            encoding: Encoding::MessagePack,
+            deflate: true,
+        };
+        assert!(msgpack.encode(&message).unwrap().len() < json);
+        // A full update repeats the same field names many times
+        assert!(deflated.encode(&message).unwrap().len() * 4 < json);
+    }
+}
@@ -1,7 +1,20 @@
 use super::broadcast::BroadcastManager;
+use super::task_board::TaskBoardManager;
+use super::{
+    DashboardAction, DashboardMessage, DashboardResponse, DashboardState, MetricsUpdate,
+    StatusChange,
+};
+use crate::audit::{self, AuditEntry, AuditJournal, AuditSource};
+use crate::auth::{self, confirm, Authenticator, ClientIdentity, ConfirmationChallenge};
+use crate::maintenance::MaintenanceController;
+use crate::metrics::FrameworkMetrics;
+use crate::process::{ProcessManager, TailChunk, TailCursor, TailFilter};
+use crate::room::WorkspaceManager;
+use crate::sync::FileSyncManager;
 use futures_util::{SinkExt, StreamExt};
 use log::{error, info, warn};
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 use tokio_tungstenite::{tungstenite::Message, WebSocketStream};
 
 pub async fn handle_websocket<S>(
@@ -66,3 +79,776 @@ pub async fn handle_static_files() -> Result<(), Box<dyn std::error::Error>> {
     // Static file serving implementation
     Ok(())
 }
+
+/// Executes dashboard actions against the framework's managers
+///
+/// Each action is checked against the client's scope and answered with a
+/// `DashboardResponse`. Destructive actions of clients that must confirm them
+/// are answered with a confirmation token instead of being executed. Workspace, process and metrics changes are broadcast
+/// as `StatusChange`/`MetricsUpdate` messages; the task board broadcasts its
+/// own updates.
+pub struct ActionDispatcher {
+    state: Arc<DashboardState>,
+    authenticator: Arc<Authenticator>,
+    task_board: Option<Arc<TaskBoardManager>>,
+    workspace_manager: Option<Arc<WorkspaceManager>>,
+    process_manager: Option<Arc<ProcessManager>>,
+    maintenance: Option<Arc<MaintenanceController>>,
+    file_sync: Option<Arc<tokio::sync::Mutex<FileSyncManager>>>,
+    audit_journal: Option<Arc<AuditJournal>>,
+}
+
+impl ActionDispatcher {
+    pub fn new(state: Arc<DashboardState>, authenticator: Arc<Authenticator>) -> Self {
+        Self {
+            state,
+            authenticator,
+            task_board: None,
+            workspace_manager: None,
+            process_manager: None,
+            maintenance: None,
+            file_sync: None,
+            audit_journal: None,
+        }
+    }
+
+    pub fn with_task_board(mut self, task_board: Option<Arc<TaskBoardManager>>) -> Self {
+        self.task_board = task_board;
+        self
+    }
+
+    /// Task board the dispatcher moves tasks on, if any
+    pub fn task_board(&self) -> Option<Arc<TaskBoardManager>> {
+        self.task_board.clone()
+    }
+
+    pub fn with_workspace_manager(
+        mut self,
+        workspace_manager: Option<Arc<WorkspaceManager>>,
+    ) -> Self {
+        self.workspace_manager = workspace_manager;
+        self
+    }
+
+    pub fn with_process_manager(mut self, process_manager: Option<Arc<ProcessManager>>) -> Self {
+        self.process_manager = process_manager;
+        self
+    }
+
+    /// Maintenance controller used to refuse new work while draining
+    pub fn with_maintenance(mut self, maintenance: Option<Arc<MaintenanceController>>) -> Self {
+        self.maintenance = maintenance;
+        self
+    }
+
+    /// File sync manager whose conflicts the dashboard lists and resolves
+    pub fn with_file_sync(
+        mut self,
+        file_sync: Option<Arc<tokio::sync::Mutex<FileSyncManager>>>,
+    ) -> Self {
+        self.file_sync = file_sync;
+        self
+    }
+
+    /// Journal that state-changing actions are written to
+    pub fn with_audit_journal(mut self, audit_journal: Option<Arc<AuditJournal>>) -> Self {
+        self.audit_journal = audit_journal;
+        self
+    }
+
+    /// Check, execute and answer one action
+    pub async fn execute(
+        &self,
+        client_id: &str,
+        identity: &ClientIdentity,
+        request_id: Option<String>,
+        action: DashboardAction,
+    ) -> DashboardResponse {
+        self.execute_confirmed(client_id, identity, request_id, action, None)
+            .await
+    }
+
+    /// Like [`ActionDispatcher::execute`], with the confirmation token of a
+    /// destructive action returned by an earlier attempt
+    pub async fn execute_confirmed(
+        &self,
+        client_id: &str,
+        identity: &ClientIdentity,
+        request_id: Option<String>,
+        action: DashboardAction,
+        confirm: Option<&str>,
+    ) -> DashboardResponse {
+        let started = std::time::Instant::now();
+        let audit_entry = self
+            .audit_journal
+            .as_ref()
+            .filter(|_| audit::is_audited_action(&action))
+            .map(|_| AuditEntry {
+                timestamp: crate::clock::unix_secs(),
+                source: AuditSource::Dashboard,
+                client: identity.name.clone(),
+                peer_uid: None,
+                connection: client_id.to_string(),
+                request_id: request_id.clone(),
+                operation: auth::operation_name(&action),
+                details: audit::details(&action),
+                success: false,
+                error: None,
+                duration_ms: 0,
+            });
+
+        let result = match self.check(identity, &action).await {
+            Ok(()) => match self.challenge(identity, &action, confirm).await {
+                Some(challenge) => {
+                    info!(
+                        "{} by client {client_id} awaits confirmation: {}",
+                        challenge.operation, challenge.impact
+                    );
+                    return DashboardResponse {
+                        request_id,
+                        success: false,
+                        error: Some(format!(
+                            "{} needs confirmation: {}",
+                            challenge.operation, challenge.impact
+                        )),
+                        data: Some(serde_json::json!({ "confirmation": challenge })),
+                    };
+                }
+                None => self.dispatch(client_id, identity, action).await,
+            },
+            Err(e) => {
+                warn!("Rejected action from client {client_id}: {e}");
+                Err(e)
+            }
+        };
+
+        if let (Some(journal), Some(mut entry)) = (&self.audit_journal, audit_entry) {
+            entry.success = result.is_ok();
+            entry.error = result.as_ref().err().cloned();
+            entry.duration_ms = started.elapsed().as_millis() as u64;
+            journal.record(&entry);
+        }
+
+        match result {
+            Ok(data) => DashboardResponse {
+                request_id,
+                success: true,
+                data: Some(data),
+                error: None,
+            },
+            Err(e) => DashboardResponse {
+                request_id,
+                success: false,
+                data: None,
+                error: Some(e),
+            },
+        }
+    }
+
+    /// Scope and maintenance checks
+    async fn check(
+        &self,
+        identity: &ClientIdentity,
+        action: &DashboardAction,
+    ) -> Result<(), String> {
+        self.authenticator
+            .authorize(
+                identity,
+                auth::action_scope(action),
+                &auth::operation_name(action),
+            )
+            .map_err(|e| e.message)?;
+
+        let operation = match action {
+            DashboardAction::CreateTask { .. } => "task creation",
+            DashboardAction::CreateWorkspace { .. } => "workspace creation",
+            DashboardAction::RestartProcess { .. } => "process restart",
+            _ => return Ok(()),
+        };
+        match &self.maintenance {
+            Some(controller) => match controller.rejection(operation).await {
+                Some(error) => Err(error),
+                None => Ok(()),
+            },
+            None => Ok(()),
+        }
+    }
+
+    /// Confirmation token to hand out instead of executing a destructive action
+    async fn challenge(
+        &self,
+        identity: &ClientIdentity,
+        action: &DashboardAction,
+        confirm: Option<&str>,
+    ) -> Option<ConfirmationChallenge> {
+        if !confirm::is_destructive_action(action) {
+            return None;
+        }
+        let guard = self.authenticator.confirmations();
+        let fingerprint = confirm::fingerprint(action);
+        if guard.confirmed(identity, &fingerprint, confirm) {
+            return None;
+        }
+
+        let impact = match action {
+            DashboardAction::KillProcess { process_id } => match &self.process_manager {
+                Some(process_manager) => {
+                    confirm::process_kill_impact(process_manager, process_id).await
+                }
+                None => format!("Kills process '{process_id}'"),
+            },
+            DashboardAction::DeleteWorkspace { name } => match &self.workspace_manager {
+                Some(workspace_manager) => {
+                    confirm::workspace_delete_impact(workspace_manager, name).await
+                }
+                None => format!("Deletes workspace '{name}'"),
+            },
+            DashboardAction::ResetMetrics { metric_type } => format!(
+                "Clears the current {} metrics of every dashboard client",
+                metric_type.as_deref().unwrap_or("framework")
+            ),
+            _ => format!("Runs {}", auth::operation_name(action)),
+        };
+        Some(guard.issue(identity, &auth::operation_name(action), fingerprint, impact))
+    }
+
+    async fn dispatch(
+        &self,
+        client_id: &str,
+        identity: &ClientIdentity,
+        action: DashboardAction,
+    ) -> Result<serde_json::Value, String> {
+        let reason = Some(format!("Dashboard action by {}", identity.name));
+
+        match action {
+            DashboardAction::KillProcess { process_id } => {
+                self.process_manager()?.kill_process(&process_id).await?;
+                info!("Killed process {process_id} for client {client_id}");
+                self.broadcast_change(
+                    format!("process:{process_id}"),
+                    "running",
+                    "stopped",
+                    reason,
+                );
+                Ok(serde_json::Value::Bool(true))
+            }
+            DashboardAction::RestartProcess { process_id } => {
+                self.process_manager()?.restart_process(&process_id).await?;
+                info!("Restarted process {process_id} for client {client_id}");
+                self.broadcast_change(
+                    format!("process:{process_id}"),
+                    "running",
+                    "restarted",
+                    reason,
+                );
+                Ok(serde_json::Value::Bool(true))
+            }
+            DashboardAction::ResetCircuitBreaker { process_id } => {
+                self.process_manager()?
+                    .reset_circuit_breaker(&process_id)
+                    .await?;
+                info!("Reset circuit breaker of process {process_id} for client {client_id}");
+                self.broadcast_change(
+                    format!("process:{process_id}"),
+                    "quarantined",
+                    "stopped",
+                    reason,
+                );
+                Ok(serde_json::Value::Bool(true))
+            }
+            DashboardAction::CreateWorkspace { name, template } => {
+                self.workspace_manager()?
+                    .create_workspace(&name, &template)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                info!("Created workspace {name} for client {client_id}");
+                self.broadcast_change(format!("workspace:{name}"), "absent", "created", reason);
+                Ok(serde_json::json!({ "name": name, "template": template }))
+            }
+            DashboardAction::DeleteWorkspace { name } => {
+                let stopped = self
+                    .workspace_manager()?
+                    .delete_workspace(&name)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                info!("Deleted workspace {name} for client {client_id}");
+                self.broadcast_change(format!("workspace:{name}"), "active", "deleted", reason);
+                Ok(serde_json::json!({ "name": name, "stopped_processes": stopped }))
+            }
+            DashboardAction::ClearAlerts { category } => {
+                // Alerts are only kept by clients, so tell them all to drop theirs
+                self.broadcast_change(
+                    "alerts".to_string(),
+                    "active",
+                    &match &category {
+                        Some(category) => format!("cleared:{category}"),
+                        None => "cleared".to_string(),
+                    },
+                    reason,
+                );
+                Ok(serde_json::Value::Bool(true))
+            }
+            DashboardAction::ResetMetrics { metric_type } => {
+                let metrics = FrameworkMetrics::new();
+                self.state.update_metrics(metrics.clone()).await;
+                self.state
+                    .broadcast(DashboardMessage::MetricsUpdate(Box::new(
+                        MetricsUpdate::full(metrics),
+                    )));
+                info!("Reset metrics ({metric_type:?}) for client {client_id}");
+                Ok(serde_json::Value::Bool(true))
+            }
+            DashboardAction::TriggerGC => {
+                // Nothing to collect in-process; reclaim finished process entries
+                let removed = self.process_manager()?.cleanup_finished_processes().await;
+                info!("Cleaned up {removed} finished processes for client {client_id}");
+                Ok(serde_json::json!({ "removed_processes": removed }))
+            }
+            DashboardAction::ExportMetrics { format, path } => {
+                let content = match format.to_ascii_lowercase().as_str() {
+                    "json" => {
+                        let metrics = self.state.framework_metrics.read().await.clone();
+                        serde_json::to_string_pretty(&metrics).map_err(|e| e.to_string())?
+                    }
+                    // Request counts and latency per IPC message and dashboard command
+                    "prometheus" => self.state.protocol_metrics.to_prometheus(),
+                    _ => return Err(format!("Unsupported export format: {format}")),
+                };
+                tokio::fs::write(&path, content)
+                    .await
+                    .map_err(|e| format!("Failed to write {path}: {e}"))?;
+                info!("Exported metrics to {path} for client {client_id}");
+                Ok(serde_json::json!({ "path": path }))
+            }
+            DashboardAction::ListSyncConflicts => {
+                let file_sync = self.file_sync()?.lock().await;
+                serde_json::to_value(file_sync.list_conflicts()).map_err(|e| e.to_string())
+            }
+            DashboardAction::GetSyncDiff { target, options } => {
+                let diffs = self
+                    .file_sync()?
+                    .lock()
+                    .await
+                    .diff(&target, &options)
+                    .map_err(|e| e.to_string())?;
+                serde_json::to_value(diffs).map_err(|e| e.to_string())
+            }
+            DashboardAction::SetLogLevel { module, level } => {
+                let levels = crate::logging::levels::set_level(&module, &level)?;
+                serde_json::to_value(levels).map_err(|e| e.to_string())
+            }
+            DashboardAction::QueryLogs { query } => {
+                let result = crate::logging::buffer::query(&query)?;
+                serde_json::to_value(result).map_err(|e| e.to_string())
+            }
+            DashboardAction::ResolveSyncConflict {
+                conflict_id,
+                strategy,
+                content,
+            } => {
+                let resolution = self
+                    .file_sync()?
+                    .lock()
+                    .await
+                    .resolve_conflict(&conflict_id, strategy, content, &identity.name)
+                    .map_err(|e| e.to_string())?;
+                info!(
+                    "Resolved sync conflict {conflict_id} with {} for client {client_id}",
+                    strategy.as_str()
+                );
+                self.broadcast_change(
+                    format!("sync:{}", resolution.file_path.display()),
+                    "conflict",
+                    &format!("resolved:{}", strategy.as_str()),
+                    reason,
+                );
+                serde_json::to_value(resolution).map_err(|e| e.to_string())
+            }
+            action => {
+                let task_board = self
+                    .task_board
+                    .as_ref()
+                    .ok_or_else(|| "Task board not available".to_string())?;
+                Self::execute_task_action(client_id, identity, task_board, action).await
+            }
+        }
+    }
+
+    async fn execute_task_action(
+        client_id: &str,
+        identity: &ClientIdentity,
+        task_board: &TaskBoardManager,
+        action: DashboardAction,
+    ) -> Result<serde_json::Value, String> {
+        match action {
+            DashboardAction::CreateTask { task_data } => {
+                match task_board
+                    .create_task_from_dashboard(task_data, &identity.name)
+                    .await
+                {
+                    Ok(task_id) => {
+                        info!("Created task {} for client {}", task_id, client_id);
+                        serde_json::to_value(task_id).map_err(|e| e.to_string())
+                    }
+                    Err(e) => {
+                        error!("Failed to create task for client {}: {}", client_id, e);
+                        Err(e)
+                    }
+                }
+            }
+            DashboardAction::UpdateTask { task_id, task_data } => {
+                match task_board
+                    .update_task_from_dashboard(&task_id, task_data)
+                    .await
+                {
+                    Ok(_) => {
+                        info!("Updated task {} for client {}", task_id, client_id);
+                        Ok(serde_json::Value::Bool(true))
+                    }
+                    Err(e) => {
+                        error!(
+                            "Failed to update task {} for client {}: {}",
+                            task_id, client_id, e
+                        );
+                        Err(e)
+                    }
+                }
+            }
+            DashboardAction::DeleteTask { task_id } => {
+                match task_board.delete_task_from_dashboard(&task_id).await {
+                    Ok(_) => {
+                        info!("Deleted task {} for client {}", task_id, client_id);
+                        Ok(serde_json::Value::Bool(true))
+                    }
+                    Err(e) => {
+                        error!(
+                            "Failed to delete task {} for client {}: {}",
+                            task_id, client_id, e
+                        );
+                        Err(e)
+                    }
+                }
+            }
+            DashboardAction::MoveTask {
+                task_id,
+                to_column,
+                position,
+            } => {
+                match task_board
+                    .move_task("default", &task_id, &to_column, position)
+                    .await
+                {
+                    Ok(_) => {
+                        info!(
+                            "Moved task {} to {} for client {}",
+                            task_id, to_column, client_id
+                        );
+                        Ok(serde_json::Value::Bool(true))
+                    }
+                    Err(e) => {
+                        error!(
+                            "Failed to move task {} for client {}: {}",
+                            task_id, client_id, e
+                        );
+                        Err(e)
+                    }
+                }
+            }
+            DashboardAction::UpdateTaskProgress { task_id, progress } => {
+                match task_board.update_task_progress(&task_id, progress).await {
+                    Ok(_) => {
+                        info!(
+                            "Updated task {} progress to {}% for client {}",
+                            task_id, progress, client_id
+                        );
+                        Ok(serde_json::Value::Bool(true))
+                    }
+                    Err(e) => {
+                        error!(
+                            "Failed to update task {} progress for client {}: {}",
+                            task_id, client_id, e
+                        );
+                        Err(e)
+                    }
+                }
+            }
+            DashboardAction::TagSession {
+                task_id,
+                started_at,
+                tags,
+            } => {
+                let result = task_board.tag_session(&task_id, started_at, &tags).await;
+                match &result {
+                    Ok(_) => info!(
+                        "Tagged session of task {} for client {}",
+                        task_id, client_id
+                    ),
+                    Err(e) => error!(
+                        "Failed to tag session of task {} for client {}: {}",
+                        task_id, client_id, e
+                    ),
+                }
+                result.map(|_| serde_json::Value::Bool(true))
+            }
+            DashboardAction::AnnotateSession {
+                task_id,
+                started_at,
+                note,
+            } => {
+                let result = task_board
+                    .annotate_session(&task_id, started_at, &note)
+                    .await;
+                match &result {
+                    Ok(_) => info!(
+                        "Annotated session of task {} for client {}",
+                        task_id, client_id
+                    ),
+                    Err(e) => error!(
+                        "Failed to annotate session of task {} for client {}: {}",
+                        task_id, client_id, e
+                    ),
+                }
+                result.map(|_| serde_json::Value::Bool(true))
+            }
+            other => Err(format!(
+                "Unsupported action: {}",
+                auth::operation_name(&other)
+            )),
+        }
+    }
+
+    /// Read a process log file after `cursor`, as the `ProcessLogTail` IPC message does
+    pub async fn tail_process_log(
+        &self,
+        process_id: &str,
+        filter: &TailFilter,
+        cursor: Option<TailCursor>,
+    ) -> Result<TailChunk, String> {
+        let path = self
+            .workspace_manager()?
+            .process_log_path(process_id)
+            .await
+            .ok_or_else(|| format!("Process '{process_id}' not found"))?;
+        crate::process::tail::read(&path, cursor, filter, None)
+    }
+
+    fn process_manager(&self) -> Result<&ProcessManager, String> {
+        self.process_manager
+            .as_deref()
+            .ok_or_else(|| "Process manager not available".to_string())
+    }
+
+    fn workspace_manager(&self) -> Result<&WorkspaceManager, String> {
+        self.workspace_manager
+            .as_deref()
+            .ok_or_else(|| "Workspace manager not available".to_string())
+    }
+
+    fn file_sync(&self) -> Result<&tokio::sync::Mutex<FileSyncManager>, String> {
+        self.file_sync
+            .as_deref()
+            .ok_or_else(|| "File sync not available".to_string())
+    }
+
+    fn broadcast_change(
+        &self,
+        component: String,
+        previous_status: &str,
+        new_status: &str,
+        reason: Option<String>,
+    ) {
+        self.state
+            .broadcast(DashboardMessage::StatusChange(StatusChange {
+                component,
+                previous_status: previous_status.to_string(),
+                new_status: new_status.to_string(),
+                reason,
+                timestamp: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs(),
+            }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::Scope;
+    use crate::dashboard::DashboardConfig;
+
+    fn dispatcher(dir: &tempfile::TempDir) -> (ActionDispatcher, Arc<DashboardState>) {
+        let (state, _metrics_tx) = DashboardState::new(DashboardConfig::default());
+        let state = Arc::new(state);
+        let workspace_manager =
+            Arc::new(WorkspaceManager::new(Some(dir.path().join("workspaces.json"))).unwrap());
+        let dispatcher = ActionDispatcher::new(Arc::clone(&state), Arc::default())
+            .with_workspace_manager(Some(workspace_manager));
+        (dispatcher, state)
+    }
+
+    #[tokio::test]
+    async fn test_workspace_action_broadcasts_change() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (dispatcher, state) = dispatcher(&dir);
+        let mut updates = state.broadcast_tx.subscribe();
+        let identity = ClientIdentity::new("ci", Scope::Control);
+
+        let response = dispatcher
+            .execute(
+                "c1",
+                &identity,
+                Some("r1".to_string()),
+                DashboardAction::CreateWorkspace {
+                    name: "docs".to_string(),
+                    template: "basic".to_string(),
+                },
+            )
+            .await;
+        assert!(response.success, "{:?}", response.error);
+        assert_eq!(response.request_id.as_deref(), Some("r1"));
+
+        match updates.recv().await.unwrap() {
+            DashboardMessage::StatusChange(change) => {
+                assert_eq!(change.component, "workspace:docs");
+                assert_eq!(change.new_status, "created");
+                assert_eq!(change.reason.as_deref(), Some("Dashboard action by ci"));
+            }
+            other => panic!("unexpected message: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_action_errors_and_permissions() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (dispatcher, _state) = dispatcher(&dir);
+        let kill = DashboardAction::KillProcess {
+            process_id: "p1".to_string(),
+        };
+
+        // Scope is checked before the action runs
+        let reader = ClientIdentity::new("statusbar", Scope::Read);
+        let response = dispatcher.execute("c1", &reader, None, kill.clone()).await;
+        assert!(!response.success);
+        assert!(response.error.unwrap().contains("requires control"));
+
+        let admin = ClientIdentity::anonymous();
+        let response = dispatcher.execute("c1", &admin, None, kill).await;
+        assert_eq!(
+            response.error.as_deref(),
+            Some("Process manager not available")
+        );
+
+        let response = dispatcher
+            .execute(
+                "c1",
+                &admin,
+                None,
+                DashboardAction::DeleteWorkspace {
+                    name: "missing".to_string(),
+                },
+            )
+            .await;
+        assert!(!response.success);
+    }
+
+    #[tokio::test]
+    async fn test_destructive_action_needs_confirmation() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (state, _metrics_tx) = DashboardState::new(DashboardConfig::default());
+        let workspace_manager =
+            Arc::new(WorkspaceManager::new(Some(dir.path().join("workspaces.json"))).unwrap());
+        workspace_manager
+            .create_workspace("docs", "basic")
+            .await
+            .unwrap();
+        let authenticator = Authenticator::new(auth::AuthConfig {
+            confirm: serde_yaml::from_str("scopes: [admin]").unwrap(),
+            ..Default::default()
+        });
+        let dispatcher = ActionDispatcher::new(Arc::new(state), Arc::new(authenticator))
+            .with_workspace_manager(Some(Arc::clone(&workspace_manager)));
+        let admin = ClientIdentity::anonymous();
+        let delete = DashboardAction::DeleteWorkspace {
+            name: "docs".to_string(),
+        };
+
+        let response = dispatcher.execute("c1", &admin, None, delete.clone()).await;
+        assert!(!response.success);
+        let challenge: ConfirmationChallenge =
+            serde_json::from_value(response.data.unwrap()["confirmation"].clone()).unwrap();
+        assert_eq!(challenge.operation, "DeleteWorkspace");
+        assert!(challenge
+            .impact
+            .starts_with("Deletes workspace 'docs', stopping 0 process(es)"));
+        assert!(workspace_manager.get_workspace_info("docs").await.is_some());
+
+        let response = dispatcher
+            .execute_confirmed("c1", &admin, None, delete, Some(&challenge.token))
+            .await;
+        assert!(response.success, "{:?}", response.error);
+        assert!(workspace_manager.get_workspace_info("docs").await.is_none());
+
+        // Only admin clients are asked
+        let control = ClientIdentity::new("ci", Scope::Control);
+        let kill = DashboardAction::KillProcess {
+            process_id: "p1".to_string(),
+        };
+        let response = dispatcher.execute("c1", &control, None, kill).await;
+        assert_eq!(
+            response.error.as_deref(),
+            Some("Process manager not available")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sync_conflict_actions() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (dispatcher, _state) = dispatcher(&dir);
+        let identity = ClientIdentity::new("ci", Scope::Control);
+
+        let response = dispatcher
+            .execute("c1", &identity, None, DashboardAction::ListSyncConflicts)
+            .await;
+        assert_eq!(response.error.as_deref(), Some("File sync not available"));
+
+        let dispatcher = dispatcher.with_file_sync(Some(Arc::default()));
+        let response = dispatcher
+            .execute("c1", &identity, None, DashboardAction::ListSyncConflicts)
+            .await;
+        assert_eq!(response.data, Some(serde_json::json!([])));
+
+        let response = dispatcher
+            .execute(
+                "c1",
+                &identity,
+                None,
+                DashboardAction::ResolveSyncConflict {
+                    conflict_id: "missing".to_string(),
+                    strategy: crate::sync::ResolutionStrategy::Ours,
+                    content: None,
+                },
+            )
+            .await;
+        assert!(response.error.unwrap().contains("Conflict not found"));
+
+        let response = dispatcher
+            .execute(
+                "c1",
+                &identity,
+                None,
+                DashboardAction::GetSyncDiff {
+                    target: crate::sync::DiffTarget::Files {
+                        old_path: dir.path().join("a.txt"),
+                        new_path: dir.path().join("b.txt"),
+                    },
+                    options: Default::default(),
+                },
+            )
+            .await;
+        assert!(response
+            .error
+            .unwrap()
+            .contains("outside the watched roots"));
+    }
+}
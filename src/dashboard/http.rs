@@ -0,0 +1,336 @@
+// WezTerm Multi-Process Development Framework - Minimal HTTP Support
+// Plain HTTP/1.1 request handling on the dashboard port for clients that
+// cannot hold a WebSocket connection
+
+use std::collections::HashMap;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// Maximum size of the request line and headers
+const MAX_HEAD_SIZE: usize = 16 * 1024;
+
+/// Maximum size of a request body
+const MAX_BODY_SIZE: usize = 1024 * 1024;
+
+/// Path prefixes served as plain HTTP instead of being upgraded to WebSocket
+pub const HTTP_ROUTE_PREFIXES: &[&str] =
+    &["/poll", "/status", "/dashboard", "/metrics", "/healthz"];
+
+/// Parsed HTTP request
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    /// Request method (GET, POST, ...)
+    pub method: String,
+
+    /// Request path without query string
+    pub path: String,
+
+    /// Decoded query parameters
+    pub query: HashMap<String, String>,
+
+    /// Headers with lower-cased names
+    pub headers: HashMap<String, String>,
+
+    /// Request body
+    pub body: Vec<u8>,
+}
+
+impl HttpRequest {
+    /// Get a header value by case-insensitive name
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map(|v| v.as_str())
+    }
+
+    /// Get a query parameter parsed into `T`
+    pub fn query_param<T: std::str::FromStr>(&self, name: &str) -> Option<T> {
+        self.query.get(name).and_then(|v| v.parse().ok())
+    }
+
+    /// Path segments without empty components
+    pub fn segments(&self) -> Vec<&str> {
+        self.path.split('/').filter(|s| !s.is_empty()).collect()
+    }
+}
+
+/// HTTP response with a JSON body
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    /// Status code
+    pub status: u16,
+
+    /// Content type of the body
+    pub content_type: String,
+
+    /// Response body
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Create a JSON response
+    pub fn json(status: u16, value: &serde_json::Value) -> Self {
+        Self {
+            status,
+            content_type: "application/json".to_string(),
+            body: serde_json::to_vec(value).unwrap_or_default(),
+        }
+    }
+
+    /// Create a plain text response
+    pub fn text(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            content_type: "text/plain; charset=utf-8".to_string(),
+            body: body.into().into_bytes(),
+        }
+    }
+
+    /// Create an HTML page response
+    pub fn html(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            content_type: "text/html; charset=utf-8".to_string(),
+            body: body.into().into_bytes(),
+        }
+    }
+
+    /// Create a Prometheus text exposition response
+    pub fn prometheus(body: impl Into<String>) -> Self {
+        Self {
+            status: 200,
+            content_type: "text/plain; version=0.0.4; charset=utf-8".to_string(),
+            body: body.into().into_bytes(),
+        }
+    }
+
+    /// Create a JSON error response
+    pub fn error(status: u16, message: &str) -> Self {
+        Self::json(status, &serde_json::json!({ "error": message }))
+    }
+
+    /// Standard reason phrase for the status code
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            204 => "No Content",
+            400 => "Bad Request",
+            401 => "Unauthorized",
+            403 => "Forbidden",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            413 => "Payload Too Large",
+            429 => "Too Many Requests",
+            503 => "Service Unavailable",
+            _ => "Internal Server Error",
+        }
+    }
+
+    /// Serialize the response onto the stream
+    pub async fn write_to<W: AsyncWrite + Unpin>(&self, stream: &mut W) -> std::io::Result<()> {
+        let head = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+            self.status,
+            self.reason(),
+            self.content_type,
+            self.body.len()
+        );
+        stream.write_all(head.as_bytes()).await?;
+        stream.write_all(&self.body).await?;
+        stream.flush().await
+    }
+}
+
+/// Decide whether a freshly accepted connection is a plain HTTP request
+///
+/// The request line is peeked without consuming it, so WebSocket handshakes
+/// can still be handed to tungstenite untouched.
+pub async fn is_plain_http_request(stream: &TcpStream) -> bool {
+    let mut buf = [0u8; 512];
+
+    for _ in 0..20 {
+        let n = match stream.peek(&mut buf).await {
+            Ok(0) | Err(_) => return false,
+            Ok(n) => n,
+        };
+
+        let data = &buf[..n];
+        if let Some(line_end) = data.iter().position(|&b| b == b'\n') {
+            let line = String::from_utf8_lossy(&data[..line_end]);
+            let target = line.split_whitespace().nth(1).unwrap_or("");
+            let path = target.split('?').next().unwrap_or("");
+            return HTTP_ROUTE_PREFIXES
+                .iter()
+                .any(|prefix| path == *prefix || path.starts_with(&format!("{prefix}/")));
+        }
+
+        if n == buf.len() {
+            return false;
+        }
+
+        // Request line not complete yet
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    }
+
+    false
+}
+
+/// Read and parse a single HTTP request from the stream
+pub async fn read_request<R: AsyncRead + Unpin>(stream: &mut R) -> Result<HttpRequest, String> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 1024];
+
+    let head_end = loop {
+        if let Some(pos) = find_subsequence(&buffer, b"\r\n\r\n") {
+            break pos;
+        }
+        if buffer.len() > MAX_HEAD_SIZE {
+            return Err("Request header too large".to_string());
+        }
+
+        let n = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|e| format!("Failed to read request: {e}"))?;
+        if n == 0 {
+            return Err("Connection closed before request was complete".to_string());
+        }
+        buffer.extend_from_slice(&chunk[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..head_end]).to_string();
+    let mut lines = head.split("\r\n");
+
+    let request_line = lines.next().ok_or("Empty request")?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().ok_or("Missing method")?.to_string();
+    let target = parts.next().ok_or("Missing request target")?;
+
+    let (path, query_string) = match target.split_once('?') {
+        Some((path, query)) => (path.to_string(), query),
+        None => (target.to_string(), ""),
+    };
+
+    let mut headers = HashMap::new();
+    for line in lines {
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let content_length: usize = headers
+        .get("content-length")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    if content_length > MAX_BODY_SIZE {
+        return Err("Request body too large".to_string());
+    }
+
+    let mut body = buffer[head_end + 4..].to_vec();
+    while body.len() < content_length {
+        let n = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|e| format!("Failed to read request body: {e}"))?;
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+    body.truncate(content_length);
+
+    Ok(HttpRequest {
+        method,
+        path,
+        query: parse_query(query_string),
+        headers,
+        body,
+    })
+}
+
+/// Parse a query string into key/value pairs
+pub fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) => (percent_decode(key), percent_decode(value)),
+            None => (percent_decode(pair), String::new()),
+        })
+        .collect()
+}
+
+/// Decode `%XX` escapes and `+` in a query component
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let decoded = std::str::from_utf8(&bytes[i + 1..i + 3])
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                match decoded {
+                    Some(byte) => {
+                        out.push(byte);
+                        i += 2;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            other => out.push(other),
+        }
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&out).to_string()
+}
+
+fn find_subsequence(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_read_request_with_query_and_body() {
+        let raw = b"POST /poll/register?a=1&name=hello%20world HTTP/1.1\r\nHost: localhost\r\nContent-Length: 7\r\n\r\n{\"x\":1}";
+        let mut reader = &raw[..];
+
+        let request = read_request(&mut reader).await.unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/poll/register");
+        assert_eq!(request.query_param::<u32>("a"), Some(1));
+        assert_eq!(request.query.get("name").unwrap(), "hello world");
+        assert_eq!(request.header("HOST"), Some("localhost"));
+        assert_eq!(request.body, b"{\"x\":1}");
+        assert_eq!(request.segments(), vec!["poll", "register"]);
+    }
+
+    #[tokio::test]
+    async fn test_read_request_rejects_truncated_head() {
+        let raw = b"GET /poll HTTP/1.1\r\nHost: local";
+        let mut reader = &raw[..];
+
+        assert!(read_request(&mut reader).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_response_serialization() {
+        let response = HttpResponse::error(404, "missing");
+        let mut out = Vec::new();
+        response.write_to(&mut out).await.unwrap();
+
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("HTTP/1.1 404 Not Found\r\n"));
+        assert!(text.contains("Content-Type: application/json"));
+        assert!(text.ends_with("{\"error\":\"missing\"}"));
+    }
+}
@@ -0,0 +1,470 @@
+// WezTerm Multi-Process Development Framework - Long-Poll Dashboard Endpoint
+// Serves queued DashboardMessages over plain HTTP for integrations that
+// cannot keep a WebSocket open
+
+use super::backlog::BacklogEntry;
+use super::http::{HttpRequest, HttpResponse};
+use super::{ClientInfo, DashboardMessage, DashboardState, MetricSubscription};
+use crate::i18n::Language;
+use crate::log_info;
+use crate::logging::LogContext;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+/// Client type recorded for long-poll clients
+pub const LONG_POLL_CLIENT_TYPE: &str = "long_poll";
+
+/// Poll timeout used when the client does not specify one
+pub const DEFAULT_POLL_TIMEOUT_MS: u64 = 25_000;
+
+/// Upper bound for a client-requested poll timeout
+pub const MAX_POLL_TIMEOUT_MS: u64 = 60_000;
+
+/// Maximum messages returned per poll when the client does not specify a limit
+pub const DEFAULT_POLL_LIMIT: usize = 100;
+
+/// Long-poll clients that have not polled for this long are unregistered
+pub const LONG_POLL_IDLE_TIMEOUT_SECS: u64 = 120;
+
+/// Registration request body
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RegisterRequest {
+    /// Initial subscriptions (defaults to all)
+    #[serde(default)]
+    pub subscriptions: Option<Vec<MetricSubscription>>,
+
+    /// Language of alerts (defaults to the configured language)
+    #[serde(default)]
+    pub language: Option<Language>,
+}
+
+/// Registration result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisterResponse {
+    /// Assigned client ID
+    pub client_id: String,
+
+    /// Cursor to use for the first poll
+    pub cursor: u64,
+}
+
+/// Result of a single poll
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PollResponse {
+    /// Polling client
+    pub client_id: String,
+
+    /// Messages newer than the cursor, oldest first
+    pub messages: Vec<BacklogEntry>,
+
+    /// Cursor to send with the next poll
+    pub next_cursor: u64,
+
+    /// True when messages were lost because the client fell behind the backlog
+    pub truncated: bool,
+}
+
+/// Register a new long-poll client and return its ID and starting cursor
+pub async fn register_client(
+    state: &DashboardState,
+    subscriptions: Option<Vec<MetricSubscription>>,
+    language: Option<Language>,
+) -> Result<RegisterResponse, String> {
+    if state.client_count().await >= state.config.max_clients {
+        return Err("Maximum client limit reached".to_string());
+    }
+
+    let now = now_secs();
+    let client_id = Uuid::new_v4().to_string();
+    let cursor = state.backlog.latest_seq().await;
+
+    state
+        .register_client(ClientInfo {
+            id: client_id.clone(),
+            connected_at: now,
+            client_type: LONG_POLL_CLIENT_TYPE.to_string(),
+            subscriptions: subscriptions.unwrap_or_else(|| vec![MetricSubscription::All]),
+            last_activity: now,
+            language,
+        })
+        .await;
+    state
+        .long_poll_cursors
+        .write()
+        .await
+        .insert(client_id.clone(), cursor);
+
+    let context = LogContext::new("dashboard", "long_poll_register").with_entity_id(&client_id);
+    log_info!(context, "Long-poll client registered");
+
+    Ok(RegisterResponse { client_id, cursor })
+}
+
+/// Unregister a long-poll client
+pub async fn unregister_client(state: &DashboardState, client_id: &str) -> bool {
+    let known = state
+        .long_poll_cursors
+        .write()
+        .await
+        .remove(client_id)
+        .is_some();
+    if known {
+        state.unregister_client(client_id).await;
+    }
+    known
+}
+
+/// Wait for messages newer than the cursor, filtered by the client's subscriptions
+///
+/// When `cursor` is `None` the position stored from the previous poll is used.
+pub async fn poll(
+    state: &DashboardState,
+    client_id: &str,
+    cursor: Option<u64>,
+    limit: usize,
+    timeout: Duration,
+) -> Result<PollResponse, String> {
+    let stored_cursor = state
+        .long_poll_cursors
+        .read()
+        .await
+        .get(client_id)
+        .copied()
+        .ok_or_else(|| format!("Unknown client: {client_id}"))?;
+
+    touch_client(state, client_id).await;
+    let language = state.client_language(client_id).await;
+
+    let deadline = tokio::time::Instant::now() + timeout;
+    let mut cursor = cursor.unwrap_or(stored_cursor);
+    let mut truncated = false;
+    let mut messages = Vec::new();
+
+    loop {
+        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+        let slice = state
+            .backlog
+            .wait_since(cursor, limit.max(1), remaining)
+            .await;
+
+        truncated |= slice.truncated;
+        cursor = slice.next_cursor;
+
+        for mut entry in slice.entries {
+            if should_deliver(state, client_id, &entry.message).await {
+                entry.message = entry.message.localized(language);
+                messages.push(entry);
+            }
+        }
+
+        // Keep waiting if everything new was filtered out by subscriptions
+        if !messages.is_empty() || tokio::time::Instant::now() >= deadline {
+            break;
+        }
+    }
+
+    state
+        .long_poll_cursors
+        .write()
+        .await
+        .insert(client_id.to_string(), cursor);
+    touch_client(state, client_id).await;
+
+    Ok(PollResponse {
+        client_id: client_id.to_string(),
+        messages,
+        next_cursor: cursor,
+        truncated,
+    })
+}
+
+/// Unregister long-poll clients that have not polled within `max_idle_secs`
+pub async fn expire_idle_clients(state: &DashboardState, max_idle_secs: u64) -> usize {
+    let now = now_secs();
+    let expired: Vec<String> = {
+        let clients = state.connected_clients.read().await;
+        clients
+            .values()
+            .filter(|c| c.client_type == LONG_POLL_CLIENT_TYPE)
+            .filter(|c| now.saturating_sub(c.last_activity) > max_idle_secs)
+            .map(|c| c.id.clone())
+            .collect()
+    };
+
+    for client_id in &expired {
+        unregister_client(state, client_id).await;
+    }
+
+    if !expired.is_empty() {
+        let context = LogContext::new("dashboard", "long_poll_expire")
+            .with_metadata("expired", serde_json::json!(expired.len()));
+        log_info!(context, "Expired {} idle long-poll clients", expired.len());
+    }
+
+    expired.len()
+}
+
+/// Route a parsed HTTP request to the long-poll handlers
+pub async fn handle_request(request: &HttpRequest, state: &DashboardState) -> HttpResponse {
+    let segments = request.segments();
+
+    match (request.method.as_str(), segments.as_slice()) {
+        ("POST", ["poll", "register"]) => {
+            let body: RegisterRequest = if request.body.is_empty() {
+                RegisterRequest::default()
+            } else {
+                match serde_json::from_slice(&request.body) {
+                    Ok(body) => body,
+                    Err(e) => return HttpResponse::error(400, &format!("Invalid body: {e}")),
+                }
+            };
+
+            match register_client(state, body.subscriptions, body.language).await {
+                Ok(response) => HttpResponse::json(200, &serde_json::json!(response)),
+                Err(e) => HttpResponse::error(503, &e),
+            }
+        }
+        ("GET", ["poll", client_id]) => {
+            let timeout_ms = request
+                .query_param::<u64>("timeout_ms")
+                .unwrap_or(DEFAULT_POLL_TIMEOUT_MS)
+                .min(MAX_POLL_TIMEOUT_MS);
+            let limit = request
+                .query_param::<usize>("limit")
+                .unwrap_or(DEFAULT_POLL_LIMIT);
+            let cursor = request.query_param::<u64>("cursor");
+
+            // `?lang=` switches the language of this and later polls
+            if let Some(tag) = request.query.get("lang") {
+                match tag.parse::<Language>() {
+                    Ok(language) => {
+                        state.set_client_language(client_id, language).await;
+                    }
+                    Err(e) => return HttpResponse::error(400, &e),
+                }
+            }
+
+            match poll(
+                state,
+                client_id,
+                cursor,
+                limit,
+                Duration::from_millis(timeout_ms),
+            )
+            .await
+            {
+                Ok(response) => HttpResponse::json(200, &serde_json::json!(response)),
+                Err(e) => HttpResponse::error(404, &e),
+            }
+        }
+        ("DELETE", ["poll", client_id]) => {
+            if unregister_client(state, client_id).await {
+                HttpResponse::json(200, &serde_json::json!({ "client_id": client_id }))
+            } else {
+                HttpResponse::error(404, &format!("Unknown client: {client_id}"))
+            }
+        }
+        (_, ["poll", ..]) => HttpResponse::error(405, "Method not allowed"),
+        _ => HttpResponse::error(404, "Not found"),
+    }
+}
+
+async fn should_deliver(
+    state: &DashboardState,
+    client_id: &str,
+    message: &DashboardMessage,
+) -> bool {
+    match message {
+        DashboardMessage::MetricsUpdate(update) => {
+            state.should_send_update(client_id, update).await
+        }
+        _ => true,
+    }
+}
+
+async fn touch_client(state: &DashboardState, client_id: &str) {
+    let mut clients = state.connected_clients.write().await;
+    if let Some(client) = clients.get_mut(client_id) {
+        client.last_activity = now_secs();
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dashboard::{AlertNotification, AlertSeverity, DashboardConfig};
+    use crate::i18n::{Text, TextKey};
+
+    fn create_state() -> DashboardState {
+        let (state, _metrics_tx) = DashboardState::new(DashboardConfig::default());
+        state
+    }
+
+    #[tokio::test]
+    async fn test_register_and_poll_returns_new_messages() {
+        let state = create_state();
+        state
+            .backlog
+            .push(DashboardMessage::Heartbeat { timestamp: 1 })
+            .await;
+
+        let registration = register_client(&state, None, None).await.unwrap();
+        assert_eq!(registration.cursor, 1);
+
+        state
+            .backlog
+            .push(DashboardMessage::Heartbeat { timestamp: 2 })
+            .await;
+
+        let response = poll(
+            &state,
+            &registration.client_id,
+            None,
+            10,
+            Duration::from_millis(50),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.messages.len(), 1);
+        assert_eq!(response.next_cursor, 2);
+
+        // Stored cursor advances, so a second poll times out empty
+        let response = poll(
+            &state,
+            &registration.client_id,
+            None,
+            10,
+            Duration::from_millis(20),
+        )
+        .await
+        .unwrap();
+        assert!(response.messages.is_empty());
+        assert_eq!(response.next_cursor, 2);
+    }
+
+    #[tokio::test]
+    async fn test_poll_unknown_client_fails() {
+        let state = create_state();
+        let result = poll(&state, "missing", None, 10, Duration::from_millis(10)).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_alerts_are_localized_per_client() {
+        let state = create_state();
+        let english = register_client(&state, None, None).await.unwrap();
+        let japanese = register_client(&state, None, Some(Language::Ja))
+            .await
+            .unwrap();
+
+        let text = Text::new(TextKey::ProcessFailed).arg("process", "worker-1");
+        state
+            .backlog
+            .push(DashboardMessage::Alert(AlertNotification {
+                id: "a1".to_string(),
+                severity: AlertSeverity::Critical,
+                category: "process".to_string(),
+                message: text.render(Language::En),
+                component: None,
+                timestamp: 1,
+                details: None,
+                text: Some(text),
+            }))
+            .await;
+
+        let alert_message = |response: PollResponse| match &response.messages[0].message {
+            DashboardMessage::Alert(alert) => alert.message.clone(),
+            other => panic!("unexpected message: {other:?}"),
+        };
+        let timeout = Duration::from_millis(50);
+
+        let response = poll(&state, &english.client_id, None, 10, timeout)
+            .await
+            .unwrap();
+        assert_eq!(alert_message(response), "Process worker-1 has failed");
+
+        let response = poll(&state, &japanese.client_id, None, 10, timeout)
+            .await
+            .unwrap();
+        assert_eq!(
+            alert_message(response),
+            "プロセス worker-1 が異常終了しました"
+        );
+
+        // Switching language re-renders already buffered alerts
+        assert!(
+            state
+                .set_client_language(&english.client_id, Language::Ja)
+                .await
+        );
+        let response = poll(&state, &english.client_id, Some(0), 10, timeout)
+            .await
+            .unwrap();
+        assert_eq!(
+            alert_message(response),
+            "プロセス worker-1 が異常終了しました"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_register_respects_client_limit() {
+        let (state, _tx) = DashboardState::new(DashboardConfig {
+            max_clients: 1,
+            ..Default::default()
+        });
+
+        assert!(register_client(&state, None, None).await.is_ok());
+        assert!(register_client(&state, None, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_expire_idle_clients() {
+        let state = create_state();
+        let registration = register_client(&state, None, None).await.unwrap();
+
+        {
+            let mut clients = state.connected_clients.write().await;
+            clients
+                .get_mut(&registration.client_id)
+                .unwrap()
+                .last_activity = 0;
+        }
+
+        assert_eq!(expire_idle_clients(&state, 60).await, 1);
+        assert_eq!(state.client_count().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_handle_request_routes() {
+        let state = create_state();
+        let request = HttpRequest {
+            method: "POST".to_string(),
+            path: "/poll/register".to_string(),
+            query: Default::default(),
+            headers: Default::default(),
+            body: Vec::new(),
+        };
+
+        let response = handle_request(&request, &state).await;
+        assert_eq!(response.status, 200);
+
+        let not_found = HttpRequest {
+            method: "GET".to_string(),
+            path: "/poll/unknown".to_string(),
+            query: [("timeout_ms".to_string(), "1".to_string())]
+                .into_iter()
+                .collect(),
+            ..request
+        };
+        assert_eq!(handle_request(&not_found, &state).await.status, 404);
+    }
+}
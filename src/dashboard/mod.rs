@@ -1,15 +1,32 @@
 // WezTerm Multi-Process Development Framework - Real-time Dashboard Backend
 // Provides WebSocket server for real-time metrics streaming to WezTerm UI
 
+pub mod backlog;
+pub mod board_document;
+pub mod board_filter;
 pub mod broadcast;
+pub mod channels;
+pub mod delta;
+pub mod encoding;
 pub mod handlers;
+pub mod http;
+pub mod long_poll;
+pub mod page;
 pub mod server;
 pub mod task_board;
 pub mod websocket_server;
 
+pub use backlog::{BacklogEntry, BacklogSlice, MessageBacklog};
+pub use board_document::{BoardDocument, BoardFormat, BoardImportReport, BoardTask};
+pub use board_filter::{BoardView, Swimlanes, TaskFilter};
+pub use channels::{Topic, TopicChannels, TopicStats, TopicSubscriber};
+pub use delta::{DeltaEncoder, MetricsDelta};
+pub use encoding::{Encoding, StreamFormat};
 pub use task_board::{TaskBoardManager, TaskBoardState};
 pub use websocket_server::WebSocketServer;
 
+use crate::i18n::{Language, Text};
+use crate::metrics::protocol::ProtocolMetrics;
 use crate::metrics::{FrameworkMetrics, ProcessMetrics, SystemMetrics, WorkspaceMetrics};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -37,8 +54,12 @@ pub struct DashboardConfig {
     /// Authentication token
     pub auth_token: Option<String>,
 
-    /// Enable compression
+    /// Offer the deflate-compressed stream formats (see `encoding`)
     pub compression: bool,
+
+    /// Language of alerts for clients that have not chosen one
+    #[serde(default)]
+    pub language: Language,
 }
 
 impl Default for DashboardConfig {
@@ -51,6 +72,7 @@ impl Default for DashboardConfig {
             auth_enabled: false,
             auth_token: None,
             compression: true,
+            language: Language::default(),
         }
     }
 }
@@ -67,10 +89,25 @@ pub struct DashboardState {
     pub config: DashboardConfig,
 
     /// Message broadcast channel
+    ///
+    /// Producers send here; WebSocket clients read the per-topic `channels`
+    /// instead of filtering every message themselves.
     pub broadcast_tx: tokio::sync::broadcast::Sender<DashboardMessage>,
 
+    /// Broadcasts partitioned by workspace, process output and kind
+    pub channels: Arc<TopicChannels>,
+
     /// Metrics update channel
     pub metrics_rx: Arc<RwLock<tokio::sync::mpsc::Receiver<MetricsUpdate>>>,
+
+    /// Sequence-numbered history of broadcast messages
+    pub backlog: Arc<MessageBacklog>,
+
+    /// Next backlog cursor per long-poll client
+    pub long_poll_cursors: Arc<RwLock<HashMap<String, u64>>>,
+
+    /// Latency and errors per IPC message, dashboard command and HTTP route
+    pub protocol_metrics: Arc<ProtocolMetrics>,
 }
 
 /// Client connection information
@@ -90,6 +127,9 @@ pub struct ClientInfo {
 
     /// Last activity timestamp
     pub last_activity: u64,
+
+    /// Preferred language, or `None` for the configured default
+    pub language: Option<Language>,
 }
 
 /// Metric subscription types
@@ -112,6 +152,17 @@ pub enum MetricSubscription {
 
     /// Performance metrics
     Performance,
+
+    /// Live stdout/stderr of a process; never included in `All`
+    ProcessOutput(String),
+
+    /// Log file of a process followed across rotations, filtered on the
+    /// server; never included in `All`
+    LogTail {
+        process_id: String,
+        #[serde(default, flatten)]
+        filter: crate::process::TailFilter,
+    },
 }
 
 /// Dashboard message types
@@ -121,6 +172,9 @@ pub enum DashboardMessage {
     /// Metrics update
     MetricsUpdate(Box<MetricsUpdate>),
 
+    /// Changes since a full update the client acknowledged with `AckMetrics`
+    MetricsDelta(Box<MetricsDelta>),
+
     /// Alert notification
     Alert(AlertNotification),
 
@@ -144,6 +198,12 @@ pub enum DashboardMessage {
     TaskBoardUpdate {
         board_id: String,
         columns: Vec<TaskColumn>,
+        /// The columns split by the client's `swimlanes`, if it chose any
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        swimlanes: Vec<BoardLane>,
+        /// Filter expression the columns were scoped with
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        filter: Option<String>,
         timestamp: u64,
     },
 
@@ -151,6 +211,9 @@ pub enum DashboardMessage {
     TaskUpdate {
         task: serde_json::Value, // Serialized Task
         action: TaskAction,
+        /// Tail of the output when a command task finished
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output: Option<crate::task::TaskOutput>,
         timestamp: u64,
     },
 
@@ -182,6 +245,40 @@ pub enum DashboardMessage {
         stats: serde_json::Value, // Serialized task system stats
         timestamp: u64,
     },
+
+    /// Dead-letter queue after a task was added to or taken out of it,
+    /// newest first
+    DeadLetters {
+        entries: Vec<crate::task::DeadLetter>,
+        timestamp: u64,
+    },
+
+    /// Files changed in a workspace, batched once they stop changing
+    FileChanges {
+        workspace: String,
+        changes: Vec<crate::sync::ChangedFile>,
+        omitted: usize,
+        timestamp: u64,
+    },
+
+    /// New output lines of a process, sent to clients subscribed to its output
+    ProcessOutput {
+        process_id: String,
+        lines: Vec<crate::process::output::OutputLine>,
+    },
+
+    /// Status of a federation peer after a poll, labelled with its name
+    PeerStatus {
+        peer: crate::federation::PeerStatus,
+        timestamp: u64,
+    },
+
+    /// New lines of a followed log file, sent only to the subscribed client
+    LogTail {
+        process_id: String,
+        #[serde(flatten)]
+        chunk: crate::process::TailChunk,
+    },
 }
 
 /// Metrics update payload
@@ -204,6 +301,11 @@ pub struct MetricsUpdate {
 
     /// Update type
     pub update_type: UpdateType,
+
+    /// Per-client number of a full update sent over WebSocket, to pass to
+    /// `AckMetrics`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<u64>,
 }
 
 /// Update type classification
@@ -245,6 +347,10 @@ pub struct AlertNotification {
 
     /// Additional details
     pub details: Option<serde_json::Value>,
+
+    /// Localizable source of `message`, rendered per client
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<Text>,
 }
 
 /// Alert severity levels
@@ -296,7 +402,14 @@ pub enum ClientCommand {
     SetUpdateInterval { interval_ms: u64 },
 
     /// Execute action
-    ExecuteAction { action: DashboardAction },
+    ExecuteAction {
+        action: DashboardAction,
+
+        /// Confirmation token of a destructive action, from the response to
+        /// the first attempt
+        #[serde(default)]
+        confirm: Option<String>,
+    },
 
     /// Query historical data
     QueryHistory {
@@ -305,6 +418,27 @@ pub enum ClientCommand {
         end_time: u64,
         limit: Option<usize>,
     },
+
+    /// Switch the language of alerts sent to this client
+    SetLanguage { language: Language },
+
+    /// Replay the subscribed broadcasts after `seq` that are still in the
+    /// backlog, e.g. after reconnecting
+    ResumeFrom { seq: u64 },
+
+    /// Send a `TaskBoardUpdate` of a board (`default` when omitted) scoped
+    /// to a filter and split into swimlanes; later updates of the board are
+    /// scoped the same way until another view is requested
+    RequestBoardUpdate {
+        #[serde(default)]
+        board_id: Option<String>,
+        #[serde(default, flatten)]
+        view: BoardView,
+    },
+
+    /// Confirm receipt of the full update or delta with this `version`; later
+    /// full updates are sent as deltas against it
+    AckMetrics { version: u64 },
 }
 
 /// Dashboard actions
@@ -317,6 +451,9 @@ pub enum DashboardAction {
     /// Restart a process
     RestartProcess { process_id: String },
 
+    /// Let a process quarantined by its circuit breaker start again
+    ResetCircuitBreaker { process_id: String },
+
     /// Create workspace
     CreateWorkspace { name: String, template: String },
 
@@ -363,6 +500,48 @@ pub enum DashboardAction {
 
     /// Update task progress
     UpdateTaskProgress { task_id: String, progress: u8 },
+
+    /// Add tags to a tracking session (active session when `started_at` is omitted)
+    TagSession {
+        task_id: String,
+        started_at: Option<u64>,
+        tags: Vec<String>,
+    },
+
+    /// Attach a note to a tracking session
+    AnnotateSession {
+        task_id: String,
+        started_at: Option<u64>,
+        note: String,
+    },
+
+    // File Sync Actions
+    /// List file sync conflicts waiting for a resolution
+    ListSyncConflicts,
+
+    /// Resolve a conflict; `content` is the file content for manual resolution
+    ResolveSyncConflict {
+        conflict_id: String,
+        strategy: crate::sync::ResolutionStrategy,
+        #[serde(default)]
+        content: Option<String>,
+    },
+
+    /// Unified diffs of a file pair, a process's changes or a conflict
+    GetSyncDiff {
+        target: crate::sync::DiffTarget,
+        #[serde(default)]
+        options: crate::sync::DiffOptions,
+    },
+
+    /// Change a log level at runtime (`level: "reset"` undoes the change)
+    SetLogLevel { module: String, level: String },
+
+    /// Recent log entries kept in memory, filtered like the `LogQuery` IPC message
+    QueryLogs {
+        #[serde(flatten)]
+        query: crate::logging::buffer::LogQuery,
+    },
 }
 
 /// Dashboard WebSocket message
@@ -371,6 +550,11 @@ pub struct WebSocketMessage {
     /// Message ID for request/response matching
     pub id: Option<String>,
 
+    /// Backlog sequence number of a broadcast, to pass to `ResumeFrom` after
+    /// reconnecting; absent on responses and process output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seq: Option<u64>,
+
     /// Message payload
     pub payload: DashboardMessage,
 }
@@ -405,7 +589,11 @@ impl DashboardState {
             connected_clients: Arc::new(RwLock::new(HashMap::new())),
             config,
             broadcast_tx,
+            channels: Arc::new(TopicChannels::default()),
             metrics_rx: Arc::new(RwLock::new(metrics_rx)),
+            backlog: Arc::new(MessageBacklog::default()),
+            long_poll_cursors: Arc::new(RwLock::new(HashMap::new())),
+            protocol_metrics: Arc::new(ProtocolMetrics::new()),
         };
 
         (state, metrics_tx)
@@ -444,15 +632,15 @@ impl DashboardState {
                 match subscription {
                     MetricSubscription::All => return true,
                     MetricSubscription::System if update.system.is_some() => return true,
-                    MetricSubscription::Process(workspace) => {
-                        if update.processes.iter().any(|p| &p.workspace == workspace) {
-                            return true;
-                        }
+                    MetricSubscription::Process(workspace)
+                        if update.processes.iter().any(|p| &p.workspace == workspace) =>
+                    {
+                        return true;
                     }
-                    MetricSubscription::Workspace(name) => {
-                        if update.workspaces.iter().any(|w| &w.workspace_name == name) {
-                            return true;
-                        }
+                    MetricSubscription::Workspace(name)
+                        if update.workspaces.iter().any(|w| &w.workspace_name == name) =>
+                    {
+                        return true;
                     }
                     _ => {}
                 }
@@ -462,6 +650,51 @@ impl DashboardState {
         false
     }
 
+    /// Check if client subscribed to the output of a process
+    pub async fn wants_process_output(&self, client_id: &str, process_id: &str) -> bool {
+        self.connected_clients
+            .read()
+            .await
+            .get(client_id)
+            .is_some_and(|client| {
+                client.subscriptions.iter().any(|subscription| {
+                    matches!(subscription, MetricSubscription::ProcessOutput(id) if id == process_id)
+                })
+            })
+    }
+
+    /// Log files a client follows, with their filters
+    pub async fn log_tails(&self, client_id: &str) -> Vec<(String, crate::process::TailFilter)> {
+        self.get_client_subscriptions(client_id)
+            .await
+            .into_iter()
+            .filter_map(|subscription| match subscription {
+                MetricSubscription::LogTail { process_id, filter } => Some((process_id, filter)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Replace a client's subscriptions; returns false for unknown clients
+    pub async fn set_client_subscriptions(
+        &self,
+        client_id: &str,
+        subscriptions: Vec<MetricSubscription>,
+    ) -> bool {
+        let updated = match self.connected_clients.write().await.get_mut(client_id) {
+            Some(client) => {
+                client.subscriptions = subscriptions;
+                client.last_activity = crate::clock::unix_secs();
+                true
+            }
+            None => false,
+        };
+        if updated {
+            self.channels.subscriptions_changed();
+        }
+        updated
+    }
+
     /// Get client subscriptions
     pub async fn get_client_subscriptions(&self, client_id: &str) -> Vec<MetricSubscription> {
         let clients = self.connected_clients.read().await;
@@ -472,6 +705,27 @@ impl DashboardState {
             .unwrap_or_default()
     }
 
+    /// Language a client reads alerts in
+    pub async fn client_language(&self, client_id: &str) -> Language {
+        self.connected_clients
+            .read()
+            .await
+            .get(client_id)
+            .and_then(|client| client.language)
+            .unwrap_or(self.config.language)
+    }
+
+    /// Change a client's language; returns false for unknown clients
+    pub async fn set_client_language(&self, client_id: &str, language: Language) -> bool {
+        match self.connected_clients.write().await.get_mut(client_id) {
+            Some(client) => {
+                client.language = Some(language);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Broadcast message to all clients
     pub fn broadcast(&self, message: DashboardMessage) {
         let _ = self.broadcast_tx.send(message);
@@ -488,6 +742,7 @@ impl DashboardState {
             total_processes: metrics.total_processes as usize,
             uptime: metrics.framework_uptime,
             last_update: metrics.timestamp,
+            topics: self.channels.stats(),
         }
     }
 }
@@ -500,6 +755,10 @@ pub struct DashboardStats {
     pub total_processes: usize,
     pub uptime: u64,
     pub last_update: u64,
+
+    /// Topic channels with subscribers
+    #[serde(default)]
+    pub topics: Vec<TopicStats>,
 }
 
 // Task Management Types
@@ -526,6 +785,17 @@ pub struct TaskColumn {
     pub sort_order: usize,
 }
 
+/// Columns of a task board restricted to one swimlane
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoardLane {
+    /// Workspace, assignee or priority of the lane's tasks; `None` for
+    /// tasks without one
+    pub lane: Option<String>,
+
+    /// Board columns with only this lane's tasks
+    pub columns: Vec<TaskColumn>,
+}
+
 /// Task action types for updates
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TaskAction {
@@ -535,6 +805,9 @@ pub enum TaskAction {
     StatusChanged,
     ProgressUpdated,
     Moved,
+
+    /// Interrupted and requeued to make room for an urgent task
+    Preempted,
 }
 
 /// Task board configuration
@@ -557,6 +830,22 @@ pub struct TaskBoardConfig {
 
     /// Board visibility settings
     pub visibility: BoardVisibility,
+
+    /// Whether moves into a column at its `max_tasks` are rejected or
+    /// only alerted
+    #[serde(default)]
+    pub wip_limits: WipLimitMode,
+}
+
+/// Enforcement of column WIP limits (`TaskColumn::max_tasks`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WipLimitMode {
+    /// Reject the move and alert
+    #[default]
+    Hard,
+
+    /// Move the task and alert that the column is over its limit
+    Soft,
 }
 
 /// Board visibility settings
@@ -572,6 +861,21 @@ pub enum BoardVisibility {
     User(String),
 }
 
+impl DashboardMessage {
+    /// Render localizable alert text in the given language
+    pub fn localized(self, language: Language) -> Self {
+        match self {
+            DashboardMessage::Alert(mut alert) => {
+                if let Some(text) = &alert.text {
+                    alert.message = text.render(language);
+                }
+                DashboardMessage::Alert(alert)
+            }
+            message => message,
+        }
+    }
+}
+
 impl MetricsUpdate {
     /// Create a full metrics update
     pub fn full(framework: FrameworkMetrics) -> Self {
@@ -593,6 +897,7 @@ impl MetricsUpdate {
             workspaces,
             framework: Some(framework),
             update_type: UpdateType::Full,
+            version: None,
         }
     }
 
@@ -609,6 +914,7 @@ impl MetricsUpdate {
             workspaces,
             framework: None,
             update_type: UpdateType::Incremental,
+            version: None,
         }
     }
 
@@ -621,6 +927,7 @@ impl MetricsUpdate {
             workspaces: Vec::new(),
             framework: None,
             update_type: UpdateType::Priority,
+            version: None,
         }
     }
 }
@@ -0,0 +1,13 @@
+// WezTerm Multi-Process Development Framework - Browser Dashboard
+// Static HTML/JS dashboard page served on the dashboard port
+
+use super::http::HttpResponse;
+
+/// Dashboard page; it loads snapshots from the `/status` endpoints and follows
+/// the WebSocket stream on the same port
+pub const DASHBOARD_PAGE: &str = include_str!("../../web/dashboard.html");
+
+/// Response for `GET /dashboard`
+pub fn dashboard() -> HttpResponse {
+    HttpResponse::html(200, DASHBOARD_PAGE)
+}
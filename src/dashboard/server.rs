@@ -98,6 +98,7 @@ mod tests {
             connection_timeout: 30,
             enable_metrics: true,
             health_check_interval: 10,
+            ..Default::default()
         }
     }
 
@@ -207,6 +208,7 @@ mod tests {
             connection_timeout: 60,
             enable_metrics: false,
             health_check_interval: 20,
+            ..Default::default()
         };
 
         let server = DashboardServer::new(config);
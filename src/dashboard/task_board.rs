@@ -1,12 +1,17 @@
 // WezTerm Multi-Process Development Framework - Task Board Management
 // Provides Kanban-style task board with real-time WebSocket updates
 
-use super::{BoardVisibility, DashboardMessage, TaskAction, TaskBoardConfig, TaskColumn};
+use super::board_document::{BoardDocument, BoardImportReport, BoardTask};
+use super::{
+    AlertNotification, AlertSeverity, BoardLane, BoardView, BoardVisibility, DashboardMessage,
+    TaskAction, TaskBoardConfig, TaskColumn, TaskFilter, WipLimitMode,
+};
+use crate::i18n::{Language, Text, TextKey};
 use crate::logging::LogContext;
 use crate::task::{Task, TaskManager, TaskStatus};
 use crate::{log_debug, log_error, log_info, log_warn};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -104,6 +109,7 @@ impl TaskBoardManager {
             refresh_interval: 1000, // 1 second
             real_time: true,
             visibility: BoardVisibility::Public,
+            wip_limits: WipLimitMode::default(),
         }
     }
 
@@ -192,61 +198,183 @@ impl TaskBoardManager {
 
     /// Get current board state with tasks
     pub async fn get_board_state(&self, board_id: &str) -> Result<TaskBoardState, String> {
+        self.get_board_view(board_id, &BoardView::default()).await
+    }
+
+    /// Board state with only the tasks passing the view's filter, split into
+    /// the view's swimlanes
+    pub async fn get_board_view(
+        &self,
+        board_id: &str,
+        view: &BoardView,
+    ) -> Result<TaskBoardState, String> {
+        let filter = view.filter.as_deref().map(TaskFilter::parse).transpose()?;
         let board_config = self
             .get_board(board_id)
             .await
             .ok_or_else(|| format!("Board '{board_id}' not found"))?;
 
         // Get all tasks and organize by status
-        let all_tasks = self.task_manager.list_tasks(None).await;
-        let mut columns = board_config.columns.clone();
-
-        // Clear existing task lists and repopulate from current tasks
-        for column in &mut columns {
-            column.tasks.clear();
-
-            // Map column IDs to task statuses
-            let status_filter = match column.id.as_str() {
-                "todo" => TaskStatus::Todo,
-                "in_progress" => TaskStatus::InProgress,
-                "review" => TaskStatus::Review,
-                "done" => TaskStatus::Completed,
-                "blocked" => TaskStatus::Blocked,
-                "on_hold" => TaskStatus::OnHold,
-                _ => continue, // Skip unknown columns
-            };
-
-            // Add tasks that match this column's status
+        let mut all_tasks = self.task_manager.list_tasks(None).await;
+        if let Some(filter) = &filter {
+            all_tasks.retain(|task| filter.matches(task));
+        }
+        let columns = fill_columns(&board_config.columns, &all_tasks.iter().collect::<Vec<_>>());
+
+        let mut swimlanes = Vec::new();
+        if let Some(grouping) = view.swimlanes {
+            let mut lanes: Vec<(Option<String>, Vec<&Task>)> = Vec::new();
             for task in &all_tasks {
-                if task.status == status_filter {
-                    column.tasks.push(task.id.clone());
+                let lane = grouping.lane(task);
+                match lanes.iter_mut().find(|(key, _)| *key == lane) {
+                    Some((_, tasks)) => tasks.push(task),
+                    None => lanes.push((lane, vec![task])),
                 }
             }
-
-            // Sort tasks by priority and creation date
-            column.tasks.sort_by(|a, b| {
-                let task_a = all_tasks.iter().find(|t| &t.id == a);
-                let task_b = all_tasks.iter().find(|t| &t.id == b);
-
-                match (task_a, task_b) {
-                    (Some(a), Some(b)) => b
-                        .priority
-                        .cmp(&a.priority)
-                        .then_with(|| a.created_at.cmp(&b.created_at)),
-                    _ => std::cmp::Ordering::Equal,
-                }
-            });
+            lanes.sort_by(|(a, _), (b, _)| grouping.compare(a, b));
+            swimlanes = lanes
+                .into_iter()
+                .map(|(lane, tasks)| BoardLane {
+                    lane,
+                    columns: fill_columns(&board_config.columns, &tasks),
+                })
+                .collect();
         }
 
         Ok(TaskBoardState {
             board_id: board_config.id,
             title: board_config.title,
             columns,
+            swimlanes,
+            filter: view.filter.clone(),
             tasks: all_tasks,
             last_updated: crate::task::current_timestamp(),
         })
     }
 
+    /// Board with its columns in their current order and the tasks shown on it
+    pub async fn export_board(&self, board_id: &str) -> Result<BoardDocument, String> {
+        if board_id == self.default_config.id {
+            // The default board exists before the dashboard initializes it
+            self.boards
+                .write()
+                .await
+                .entry(board_id.to_string())
+                .or_insert_with(|| self.default_config.clone());
+        }
+        let mut board = self
+            .get_board(board_id)
+            .await
+            .ok_or_else(|| format!("Board '{board_id}' not found"))?;
+        let state = self.get_board_state(board_id).await?;
+        board.columns = state.columns;
+
+        let tasks: Vec<BoardTask> = board
+            .columns
+            .iter()
+            .flat_map(|column| &column.tasks)
+            .filter_map(|id| state.tasks.iter().find(|task| &task.id == id))
+            .map(BoardTask::from_task)
+            .collect();
+        let export_context = LogContext::new("dashboard", "board_export")
+            .with_entity_id(board_id)
+            .with_metadata("tasks", serde_json::json!(tasks.len()));
+        log_info!(export_context, "Exported task board {}", board_id);
+        Ok(BoardDocument::new(board, tasks))
+    }
+
+    /// Create or update the document's board and its tasks
+    ///
+    /// A task's column decides its status; tasks in no known column keep the
+    /// status from the document. Existing tasks keep their execution history.
+    pub async fn import_board(
+        &self,
+        mut document: BoardDocument,
+        dry_run: bool,
+    ) -> Result<BoardImportReport, String> {
+        if document.board.id.trim().is_empty() {
+            return Err("Board ID cannot be empty".to_string());
+        }
+        let existing: HashMap<String, Task> = self
+            .task_manager
+            .list_tasks(None)
+            .await
+            .into_iter()
+            .map(|task| (task.id.clone(), task))
+            .collect();
+        let known: HashSet<String> = existing.keys().cloned().collect();
+
+        let mut report = BoardImportReport {
+            board_id: document.board.id.clone(),
+            dry_run,
+            board_created: self.get_board(&document.board.id).await.is_none(),
+            errors: document.sanitize(&known),
+            ..Default::default()
+        };
+
+        let mut columns: HashMap<String, TaskStatus> = HashMap::new();
+        for column in &document.board.columns {
+            match column_status(&column.id) {
+                Some(status) => {
+                    for task_id in &column.tasks {
+                        columns.insert(task_id.clone(), status.clone());
+                    }
+                }
+                None if !column.tasks.is_empty() => report.errors.push(format!(
+                    "Column {} has no matching task status; its tasks keep theirs",
+                    column.id
+                )),
+                None => {}
+            }
+        }
+
+        let mut tasks = Vec::new();
+        for mut board_task in document.tasks {
+            if let Some(status) = columns.remove(&board_task.id) {
+                board_task.status = status;
+            }
+            let previous = existing.get(&board_task.id).cloned();
+            if previous.is_some() {
+                report.tasks_updated += 1;
+            } else {
+                report.tasks_created += 1;
+            }
+            tasks.push(board_task.into_task(previous));
+        }
+        if dry_run {
+            return Ok(report);
+        }
+
+        self.task_manager.import_tasks(tasks).await;
+        let board_id = document.board.id.clone();
+        self.boards
+            .write()
+            .await
+            .insert(board_id.clone(), document.board);
+        if let Err(e) = self.broadcast_board_update(&board_id).await {
+            let import_error_context = LogContext::new("dashboard", "board_import_broadcast_error")
+                .with_entity_id(&board_id);
+            log_error!(
+                import_error_context,
+                "Failed to broadcast imported board: {}",
+                e
+            );
+        }
+
+        let import_context = LogContext::new("dashboard", "board_import_success")
+            .with_entity_id(&board_id)
+            .with_metadata("created", serde_json::json!(report.tasks_created))
+            .with_metadata("updated", serde_json::json!(report.tasks_updated));
+        log_info!(
+            import_context,
+            "Imported task board {}: {} tasks created, {} updated",
+            board_id,
+            report.tasks_created,
+            report.tasks_updated
+        );
+        Ok(report)
+    }
+
     /// Move task between columns
     pub async fn move_task(
         &self,
@@ -265,15 +393,35 @@ impl TaskBoardManager {
         let old_status = task.status.clone();
 
         // Map column ID to task status
-        let new_status = match to_column {
-            "todo" => TaskStatus::Todo,
-            "in_progress" => TaskStatus::InProgress,
-            "review" => TaskStatus::Review,
-            "done" => TaskStatus::Completed,
-            "blocked" => TaskStatus::Blocked,
-            "on_hold" => TaskStatus::OnHold,
-            _ => return Err(format!("Unknown column: {to_column}")),
-        };
+        let new_status =
+            column_status(to_column).ok_or_else(|| format!("Unknown column: {to_column}"))?;
+
+        // Tasks already in the column do not count against its limit twice
+        let mut over_limit = None;
+        let board = self.get_board(board_id).await;
+        let column = board
+            .as_ref()
+            .and_then(|board| board.columns.iter().find(|c| c.id == to_column));
+        if let (Some(board), Some(column)) = (&board, column) {
+            if let Some(limit) = column.max_tasks.filter(|_| old_status != new_status) {
+                let count = self
+                    .task_manager
+                    .list_tasks(None)
+                    .await
+                    .iter()
+                    .filter(|t| t.status == new_status)
+                    .count();
+                if count >= limit {
+                    if board.wip_limits == WipLimitMode::Hard {
+                        self.broadcast_wip_alert(board, column, task_id, count, limit);
+                        return Err(format!(
+                            "Column '{to_column}' is at its WIP limit of {limit}"
+                        ));
+                    }
+                    over_limit = Some((count + 1, limit));
+                }
+            }
+        }
 
         // Update task status
         task.update_status(new_status);
@@ -314,6 +462,10 @@ impl TaskBoardManager {
             );
         }
 
+        if let (Some(board), Some(column), Some((count, limit))) = (&board, column, over_limit) {
+            self.broadcast_wip_alert(board, column, task_id, count, limit);
+        }
+
         let move_context = LogContext::new("dashboard", "task_move_success")
             .with_entity_id(task_id)
             .with_metadata(
@@ -384,14 +536,69 @@ impl TaskBoardManager {
         Ok(())
     }
 
-    /// Handle task creation from dashboard
+    /// Tag a tracking session and broadcast the updated active session
+    pub async fn tag_session(
+        &self,
+        task_id: &str,
+        started_at: Option<u64>,
+        tags: &[String],
+    ) -> Result<(), String> {
+        let tracker = self.task_manager.get_tracker();
+        if !tracker
+            .tag_session(&task_id.to_string(), started_at, tags)
+            .await
+        {
+            return Err(format!("No tracking session found for task '{task_id}'"));
+        }
+        self.broadcast_tracking_update(task_id).await;
+        Ok(())
+    }
+
+    /// Attach a note to a tracking session and broadcast the updated active session
+    pub async fn annotate_session(
+        &self,
+        task_id: &str,
+        started_at: Option<u64>,
+        note: &str,
+    ) -> Result<(), String> {
+        let tracker = self.task_manager.get_tracker();
+        if !tracker
+            .annotate_session(&task_id.to_string(), started_at, note)
+            .await
+        {
+            return Err(format!("No tracking session found for task '{task_id}'"));
+        }
+        self.broadcast_tracking_update(task_id).await;
+        Ok(())
+    }
+
+    /// Send the task's active tracking session to dashboard clients
+    async fn broadcast_tracking_update(&self, task_id: &str) {
+        let tracker = self.task_manager.get_tracker();
+        let Some(session) = tracker.get_active_session(&task_id.to_string()).await else {
+            return;
+        };
+        let Ok(tracking_data) = serde_json::to_value(&session) else {
+            return;
+        };
+
+        let _ = self.broadcast_tx.send(DashboardMessage::TaskTimeUpdate {
+            task_id: task_id.to_string(),
+            tracking_data,
+            timestamp: crate::task::current_timestamp(),
+        });
+    }
+
+    /// Handle task creation from dashboard, attributed to the creating client
     pub async fn create_task_from_dashboard(
         &self,
         task_data: serde_json::Value,
+        created_by: &str,
     ) -> Result<String, String> {
         // Deserialize task data
-        let task: Task =
+        let mut task: Task =
             serde_json::from_value(task_data).map_err(|e| format!("Invalid task data: {e}"))?;
+        task.created_by = Some(created_by.to_string());
 
         // Create task through manager
         let task_id = self
@@ -492,19 +699,60 @@ impl TaskBoardManager {
     ) -> Result<(), Box<dyn std::error::Error>> {
         let board_state = self.get_board_state(board_id).await?;
 
-        let message = DashboardMessage::TaskBoardUpdate {
-            board_id: board_state.board_id.clone(),
-            columns: board_state.columns,
-            timestamp: board_state.last_updated,
-        };
-
-        self.broadcast_tx.send(message)?;
+        self.broadcast_tx.send(board_state.into_message())?;
         Ok(())
     }
 
+    /// Alert that a move hit a column's WIP limit: rejected under a hard
+    /// limit, `count` tasks in the column under a soft one
+    fn broadcast_wip_alert(
+        &self,
+        board: &TaskBoardConfig,
+        column: &TaskColumn,
+        task_id: &str,
+        count: usize,
+        limit: usize,
+    ) {
+        let text = match board.wip_limits {
+            WipLimitMode::Hard => Text::new(TextKey::WipLimitRejected)
+                .arg("task", task_id)
+                .arg("column", &column.title)
+                .arg("limit", limit),
+            WipLimitMode::Soft => Text::new(TextKey::WipLimitExceeded)
+                .arg("column", &column.title)
+                .arg("count", count)
+                .arg("limit", limit),
+        };
+        let message = text.render(Language::default());
+        let wip_context = LogContext::new("dashboard", "task_move_wip_limit")
+            .with_entity_id(task_id)
+            .with_metadata("column", serde_json::json!(column.id))
+            .with_metadata("limit", serde_json::json!(limit));
+        log_warn!(wip_context, "{}", message);
+
+        let alert = AlertNotification {
+            id: format!("wip-{}-{}", board.id, column.id),
+            severity: AlertSeverity::Warning,
+            category: "wip_limit".to_string(),
+            message,
+            component: Some(board.id.clone()),
+            timestamp: crate::task::current_timestamp(),
+            details: Some(serde_json::json!({
+                "board_id": board.id,
+                "column": column.id,
+                "task_id": task_id,
+                "count": count,
+                "limit": limit,
+                "mode": board.wip_limits,
+            })),
+            text: Some(text),
+        };
+        let _ = self.broadcast_tx.send(DashboardMessage::Alert(alert));
+    }
+
     /// Broadcast task update
     async fn broadcast_task_update(&self, task: &Task, action: TaskAction) {
-        let task_json = match serde_json::to_value(task) {
+        let task_json = match crate::task::task_detail(task) {
             Ok(json) => json,
             Err(e) => {
                 let serialize_error_context =
@@ -517,6 +765,7 @@ impl TaskBoardManager {
         let message = DashboardMessage::TaskUpdate {
             task: task_json,
             action,
+            output: None,
             timestamp: crate::task::current_timestamp(),
         };
 
@@ -586,6 +835,45 @@ impl TaskBoardManager {
     }
 }
 
+/// Task status shown in a column, for the built-in column IDs
+/// Board columns listing the given tasks in the column of their status, by
+/// priority and then creation time
+fn fill_columns(columns: &[TaskColumn], tasks: &[&Task]) -> Vec<TaskColumn> {
+    let mut columns = columns.to_vec();
+    for column in &mut columns {
+        column.tasks.clear();
+
+        // Map column IDs to task statuses
+        let Some(status_filter) = column_status(&column.id) else {
+            continue; // Skip unknown columns
+        };
+
+        let mut column_tasks: Vec<&&Task> = tasks
+            .iter()
+            .filter(|task| task.status == status_filter)
+            .collect();
+        column_tasks.sort_by(|a, b| {
+            b.priority
+                .cmp(&a.priority)
+                .then_with(|| a.created_at.cmp(&b.created_at))
+        });
+        column.tasks = column_tasks.iter().map(|task| task.id.clone()).collect();
+    }
+    columns
+}
+
+fn column_status(column_id: &str) -> Option<TaskStatus> {
+    match column_id {
+        "todo" => Some(TaskStatus::Todo),
+        "in_progress" => Some(TaskStatus::InProgress),
+        "review" => Some(TaskStatus::Review),
+        "done" => Some(TaskStatus::Completed),
+        "blocked" => Some(TaskStatus::Blocked),
+        "on_hold" => Some(TaskStatus::OnHold),
+        _ => None,
+    }
+}
+
 /// Current task board state
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaskBoardState {
@@ -598,6 +886,14 @@ pub struct TaskBoardState {
     /// Board columns with tasks
     pub columns: Vec<TaskColumn>,
 
+    /// Columns per swimlane, when the view groups the board
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub swimlanes: Vec<BoardLane>,
+
+    /// Filter expression the tasks were scoped with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+
     /// All tasks (for quick lookup)
     pub tasks: Vec<Task>,
 
@@ -605,10 +901,24 @@ pub struct TaskBoardState {
     pub last_updated: u64,
 }
 
+impl TaskBoardState {
+    /// `TaskBoardUpdate` message of this state
+    pub fn into_message(self) -> DashboardMessage {
+        DashboardMessage::TaskBoardUpdate {
+            board_id: self.board_id,
+            columns: self.columns,
+            swimlanes: self.swimlanes,
+            filter: self.filter,
+            timestamp: self.last_updated,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::task::{TaskCategory, TaskConfig};
+    use crate::dashboard::Swimlanes;
+    use crate::task::{TaskCategory, TaskConfig, TaskPriority};
 
     fn create_test_task_manager() -> Arc<TaskManager> {
         let config = TaskConfig {
@@ -621,6 +931,13 @@ mod tests {
             metrics_enabled: true,
             cleanup_interval: 300,
             max_task_history: 100,
+            budgets: Vec::new(),
+            timezone: Default::default(),
+            language: Default::default(),
+            artifacts_dir: None,
+            ids: Default::default(),
+            latency_budgets: Vec::new(),
+            preemption: Default::default(),
         };
         Arc::new(TaskManager::new(config))
     }
@@ -728,6 +1045,72 @@ mod tests {
         assert_eq!(updated_task.status, TaskStatus::InProgress);
     }
 
+    #[tokio::test]
+    async fn test_board_export_import_round_trip() {
+        let task_manager = create_test_task_manager();
+        let (broadcast_tx, _rx) = tokio::sync::broadcast::channel(100);
+        let board_manager = TaskBoardManager::new(task_manager.clone(), broadcast_tx);
+
+        let design = task_manager
+            .create_task(crate::task::Task::new(
+                "Design".to_string(),
+                TaskCategory::Documentation,
+            ))
+            .await
+            .unwrap();
+        let mut build = crate::task::Task::new("Build".to_string(), TaskCategory::Development);
+        build.dependencies = vec![design.clone()];
+        let build = task_manager.create_task(build).await.unwrap();
+        board_manager
+            .move_task("default", &design, "review", None)
+            .await
+            .unwrap();
+
+        let content = board_manager
+            .export_board("default")
+            .await
+            .unwrap()
+            .render(crate::dashboard::BoardFormat::Yaml)
+            .unwrap();
+
+        // Into another instance, as a new board where the design is done
+        let other_manager = create_test_task_manager();
+        let (broadcast_tx, _rx) = tokio::sync::broadcast::channel(100);
+        let other_board = TaskBoardManager::new(other_manager.clone(), broadcast_tx);
+        let mut document = BoardDocument::parse(&content, None).unwrap();
+        document.board.id = "team".to_string();
+        let review = document.board.columns.iter().position(|c| c.id == "review");
+        let done = document.board.columns.iter().position(|c| c.id == "done");
+        let moved = document.board.columns[review.unwrap()].tasks.remove(0);
+        document.board.columns[done.unwrap()].tasks.push(moved);
+
+        let report = other_board
+            .import_board(document.clone(), true)
+            .await
+            .unwrap();
+        assert_eq!((report.tasks_created, report.tasks_updated), (2, 0));
+        assert!(other_manager.get_task(&build).await.is_none());
+
+        let report = other_board.import_board(document, false).await.unwrap();
+        assert!(report.board_created);
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        let imported = other_manager.get_task(&build).await.unwrap();
+        assert_eq!(imported.dependencies, std::slice::from_ref(&design));
+        assert_eq!(
+            other_manager.get_task(&design).await.unwrap().status,
+            TaskStatus::Completed
+        );
+        let state = other_board.get_board_state("team").await.unwrap();
+        let done = state.columns.iter().find(|c| c.id == "done").unwrap();
+        assert_eq!(done.tasks, [design]);
+
+        // Importing again updates instead of duplicating
+        let again = BoardDocument::parse(&content, None).unwrap();
+        let report = other_board.import_board(again, false).await.unwrap();
+        assert_eq!((report.tasks_created, report.tasks_updated), (0, 2));
+        assert_eq!(other_manager.list_tasks(None).await.len(), 2);
+    }
+
     #[tokio::test]
     async fn test_update_task_progress() {
         let task_manager = create_test_task_manager();
@@ -757,4 +1140,135 @@ mod tests {
         let updated_task = task_manager.get_task(&task_id).await.unwrap();
         assert_eq!(updated_task.progress, 75);
     }
+
+    #[tokio::test]
+    async fn test_dashboard_task_records_creator() {
+        let task_manager = create_test_task_manager();
+        let (broadcast_tx, _rx) = tokio::sync::broadcast::channel(100);
+        let board_manager = TaskBoardManager::new(task_manager.clone(), broadcast_tx);
+        board_manager.initialize().await.unwrap();
+
+        let mut task = crate::task::Task::new("Review".to_string(), TaskCategory::Review);
+        task.assignee = Some("bob".to_string());
+        task.created_by = Some("spoofed".to_string());
+        let task_id = board_manager
+            .create_task_from_dashboard(serde_json::to_value(task).unwrap(), "alice")
+            .await
+            .unwrap();
+
+        let created = task_manager.get_task(&task_id).await.unwrap();
+        assert_eq!(created.created_by.as_deref(), Some("alice"));
+        assert_eq!(created.assignee.as_deref(), Some("bob"));
+    }
+
+    #[tokio::test]
+    async fn test_move_into_full_column_respects_wip_mode() {
+        let task_manager = create_test_task_manager();
+        let (broadcast_tx, mut rx) = tokio::sync::broadcast::channel(100);
+        let board_manager = TaskBoardManager::new(task_manager.clone(), broadcast_tx);
+        board_manager.initialize().await.unwrap();
+
+        let mut board = board_manager.get_board("default").await.unwrap();
+        board
+            .columns
+            .iter_mut()
+            .find(|c| c.id == "review")
+            .unwrap()
+            .max_tasks = Some(1);
+        board_manager.update_board(board.clone()).await.unwrap();
+
+        let mut ids = Vec::new();
+        for title in ["First", "Second", "Third"] {
+            let task = Task::new(title.to_string(), TaskCategory::Development);
+            ids.push(task_manager.create_task(task).await.unwrap());
+        }
+        board_manager
+            .move_task("default", &ids[0], "review", None)
+            .await
+            .unwrap();
+        // Moving within the full column is not another task
+        board_manager
+            .move_task("default", &ids[0], "review", Some(0))
+            .await
+            .unwrap();
+        while rx.try_recv().is_ok() {}
+
+        let error = board_manager
+            .move_task("default", &ids[1], "review", None)
+            .await
+            .unwrap_err();
+        assert!(error.contains("WIP limit of 1"));
+        let task = task_manager.get_task(&ids[1]).await.unwrap();
+        assert_eq!(task.status, TaskStatus::Todo);
+        let DashboardMessage::Alert(alert) = rx.try_recv().unwrap() else {
+            panic!("Expected a WIP limit alert");
+        };
+        assert_eq!(alert.category, "wip_limit");
+        assert_eq!(alert.text.unwrap().key, TextKey::WipLimitRejected);
+
+        board.wip_limits = WipLimitMode::Soft;
+        board_manager.update_board(board).await.unwrap();
+        while rx.try_recv().is_ok() {}
+        board_manager
+            .move_task("default", &ids[2], "review", None)
+            .await
+            .unwrap();
+        let task = task_manager.get_task(&ids[2]).await.unwrap();
+        assert_eq!(task.status, TaskStatus::Review);
+        let alert = std::iter::from_fn(|| rx.try_recv().ok())
+            .find_map(|message| match message {
+                DashboardMessage::Alert(alert) => Some(alert),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(alert.details.unwrap()["count"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_board_view_filters_and_splits_into_swimlanes() {
+        let task_manager = create_test_task_manager();
+        let (broadcast_tx, _rx) = tokio::sync::broadcast::channel(100);
+        let board_manager = TaskBoardManager::new(task_manager.clone(), broadcast_tx);
+        board_manager.initialize().await.unwrap();
+
+        for (title, priority, workspace) in [
+            ("Login page", TaskPriority::High, Some("frontend")),
+            ("Styles", TaskPriority::Low, Some("frontend")),
+            ("Schema", TaskPriority::Urgent, Some("backend")),
+            ("Triage", TaskPriority::Critical, None),
+        ] {
+            let mut task = Task::new(title.to_string(), TaskCategory::Development);
+            task.priority = priority;
+            task.workspace = workspace.map(str::to_string);
+            task_manager.create_task(task).await.unwrap();
+        }
+
+        let view = BoardView {
+            filter: Some("priority >= High".to_string()),
+            swimlanes: Some(Swimlanes::Workspace),
+        };
+        let state = board_manager
+            .get_board_view("default", &view)
+            .await
+            .unwrap();
+        assert_eq!(state.tasks.len(), 3);
+        assert_eq!(state.filter.as_deref(), Some("priority >= High"));
+        let lanes: Vec<Option<&str>> = state.swimlanes.iter().map(|l| l.lane.as_deref()).collect();
+        assert_eq!(lanes, vec![Some("backend"), Some("frontend"), None]);
+        let frontend_todo = &state.swimlanes[1].columns[0];
+        assert_eq!(frontend_todo.id, "todo");
+        assert_eq!(frontend_todo.tasks.len(), 1);
+
+        let error = board_manager
+            .get_board_view(
+                "default",
+                &BoardView {
+                    filter: Some("priority >= Highest".to_string()),
+                    swimlanes: None,
+                },
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(error, "Unknown priority 'Highest'");
+    }
 }
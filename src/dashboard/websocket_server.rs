@@ -1,29 +1,74 @@
 // WezTerm Multi-Process Development Framework - Enhanced WebSocket Server
 // Provides real-time metrics streaming to WezTerm Lua clients
 
-use super::task_board::TaskBoardManager;
+use super::handlers::ActionDispatcher;
+use super::task_board::{TaskBoardManager, TaskBoardState};
+use super::{http, long_poll, page};
 use super::{
-    ClientInfo, DashboardConfig, DashboardMessage, DashboardState, MetricSubscription,
-    MetricsUpdate,
+    BoardView, ClientInfo, DashboardConfig, DashboardMessage, DashboardState, DeltaEncoder,
+    MetricSubscription, MetricsUpdate, StreamFormat, Topic, TopicSubscriber,
 };
+use crate::audit::AuditJournal;
+use crate::auth::{self, Authenticator, ClientIdentity};
+use crate::bridge::EventBridge;
+use crate::federation::Federation;
+use crate::focus::FocusController;
+use crate::i18n::Language;
 use crate::logging::enhancer::ipc;
 use crate::logging::LogContext;
+use crate::maintenance::MaintenanceController;
+use crate::metrics::storage::{HistoryMetric, MetricsStorage};
 use crate::metrics::FrameworkMetrics;
-use crate::task::TaskManager;
+use crate::monitoring::MonitoringManager;
+use crate::performance::cache::CacheRegistry;
+use crate::performance::lazy::{Activations, LazySubsystem, STARTUP_TRIGGER};
+use crate::process::{tail, ProcessManager, TailCursor};
+use crate::room::WorkspaceManager;
+use crate::supervisor::{AbortOnDrop, Supervisor, TaskHealth};
+use crate::sync::FileSyncManager;
+use crate::task::{budget, TaskManager, TaskTracker};
 use crate::{log_info, log_warn};
 use futures_util::{SinkExt, StreamExt};
+use serde::Serialize;
 use serde_json;
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::net::TcpListener;
-use tokio_tungstenite::{accept_async, tungstenite::Message};
+use tokio_tungstenite::tungstenite::handshake::server::{
+    ErrorResponse, Request as HandshakeRequest, Response as HandshakeResponse,
+};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::{accept_hdr_async, tungstenite::Message};
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
+/// Name the dashboard's start is recorded under
+pub const DASHBOARD_SUBSYSTEM: &str = "dashboard";
+
+/// Trigger of subsystems started by a connecting dashboard client
+pub const CLIENT_TRIGGER: &str = "dashboard_client";
+
 pub struct WebSocketServer {
     state: Arc<DashboardState>,
     config: DashboardConfig,
     task_board_manager: Option<Arc<TaskBoardManager>>,
+    maintenance: Option<Arc<MaintenanceController>>,
+    focus: Option<Arc<FocusController>>,
+    metrics_storage: Option<Arc<MetricsStorage>>,
+    tracker: Option<Arc<TaskTracker>>,
+    authenticator: Arc<Authenticator>,
+    workspace_manager: Option<Arc<WorkspaceManager>>,
+    process_manager: Option<Arc<ProcessManager>>,
+    file_sync_manager: Option<Arc<tokio::sync::Mutex<FileSyncManager>>>,
+    supervisor: Arc<Supervisor>,
+    event_bridge: Option<Arc<EventBridge>>,
+    monitoring: Option<Arc<MonitoringManager>>,
+    federation: Option<Arc<Federation>>,
+    caches: Option<Arc<CacheRegistry>>,
+    activations: Option<Arc<Activations>>,
+    lazy_start: bool,
+    activate_on_connect: Vec<Arc<LazySubsystem>>,
+    audit_journal: Option<Arc<AuditJournal>>,
 }
 
 impl WebSocketServer {
@@ -32,8 +77,25 @@ impl WebSocketServer {
 
         let server = Self {
             state: Arc::new(state),
+            authenticator: Arc::new(Authenticator::from_dashboard(&config)),
             config,
             task_board_manager: None,
+            maintenance: None,
+            focus: None,
+            metrics_storage: None,
+            tracker: None,
+            workspace_manager: None,
+            process_manager: None,
+            file_sync_manager: None,
+            supervisor: Arc::new(Supervisor::new()),
+            event_bridge: None,
+            monitoring: None,
+            federation: None,
+            caches: None,
+            activations: None,
+            lazy_start: false,
+            activate_on_connect: Vec::new(),
+            audit_journal: None,
         };
 
         (server, metrics_tx)
@@ -41,18 +103,134 @@ impl WebSocketServer {
 
     /// Set task manager and enable task board functionality
     pub fn with_task_manager(mut self, task_manager: Arc<TaskManager>) -> Self {
+        self.tracker = Some(task_manager.get_tracker());
         let task_board_manager =
             TaskBoardManager::new(task_manager, self.state.broadcast_tx.clone());
         self.task_board_manager = Some(Arc::new(task_board_manager));
         self
     }
 
+    /// Set maintenance controller used for `/status` and to reject new tasks while draining
+    pub fn with_maintenance(mut self, maintenance: Arc<MaintenanceController>) -> Self {
+        self.maintenance = Some(maintenance);
+        self
+    }
+
+    /// Set focus controller that withholds non-critical alerts while focusing
+    pub fn with_focus(mut self, focus: Arc<FocusController>) -> Self {
+        self.focus = Some(focus);
+        self
+    }
+
+    /// Set metrics history used to answer `QueryHistory`
+    pub fn with_metrics_storage(mut self, metrics_storage: Arc<MetricsStorage>) -> Self {
+        self.metrics_storage = Some(metrics_storage);
+        self
+    }
+
+    /// Set workspace manager used by `CreateWorkspace`/`DeleteWorkspace` actions
+    pub fn with_workspace_manager(mut self, workspace_manager: Arc<WorkspaceManager>) -> Self {
+        self.workspace_manager = Some(workspace_manager);
+        self
+    }
+
+    /// Set process manager used by `KillProcess`/`RestartProcess` actions
+    pub fn with_process_manager(mut self, process_manager: Arc<ProcessManager>) -> Self {
+        self.process_manager = Some(process_manager);
+        self
+    }
+
+    /// Set file sync manager used by `ListSyncConflicts`/`ResolveSyncConflict`/`GetSyncDiff` actions
+    pub fn with_file_sync_manager(
+        mut self,
+        file_sync_manager: Arc<tokio::sync::Mutex<FileSyncManager>>,
+    ) -> Self {
+        self.file_sync_manager = Some(file_sync_manager);
+        self
+    }
+
+    /// Supervise the dashboard's background tasks and report them on `/healthz`
+    pub fn with_supervisor(mut self, supervisor: Arc<Supervisor>) -> Self {
+        self.supervisor = supervisor;
+        self
+    }
+
+    /// Set the event bridge whose webhook deliveries `/status/webhooks` lists
+    pub fn with_event_bridge(mut self, event_bridge: Arc<EventBridge>) -> Self {
+        self.event_bridge = Some(event_bridge);
+        self
+    }
+
+    /// Set the monitoring system whose aggregated state `/status/monitoring` serves
+    pub fn with_monitoring(mut self, monitoring: Arc<MonitoringManager>) -> Self {
+        self.monitoring = Some(monitoring);
+        self
+    }
+
+    /// Set the federation whose peers `/status/peers` and `/metrics` report
+    pub fn with_federation(mut self, federation: Arc<Federation>) -> Self {
+        self.federation = Some(federation);
+        self
+    }
+
+    /// Set the caches whose hit and miss counters `/metrics` reports
+    pub fn with_caches(mut self, caches: Arc<CacheRegistry>) -> Self {
+        self.caches = Some(caches);
+        self
+    }
+
+    /// Record when the dashboard starts in `activations`, which
+    /// `/status/subsystems` and `/metrics` report
+    pub fn with_activations(mut self, activations: Arc<Activations>) -> Self {
+        activations.register(DASHBOARD_SUBSYSTEM);
+        self.activations = Some(activations);
+        self
+    }
+
+    /// Hold back the background tasks until the first client connects; the
+    /// port is bound right away
+    pub fn with_lazy_start(mut self, lazy_start: bool) -> Self {
+        self.lazy_start = lazy_start;
+        self
+    }
+
+    /// Start `subsystem` when a client connects, before serving it
+    pub fn with_activate_on_connect(mut self, subsystem: Arc<LazySubsystem>) -> Self {
+        self.activate_on_connect.push(subsystem);
+        self
+    }
+
+    /// Set the journal that state-changing dashboard actions are written to
+    pub fn with_audit_journal(mut self, audit_journal: Option<Arc<AuditJournal>>) -> Self {
+        self.audit_journal = audit_journal;
+        self
+    }
+
+    /// Set the authenticator checking client tokens and scopes
+    pub fn with_authenticator(mut self, authenticator: Arc<Authenticator>) -> Self {
+        self.authenticator = authenticator;
+        self
+    }
+
     pub async fn start(&self) -> Result<(), Box<dyn std::error::Error>> {
         if !self.config.enabled {
             info!("WebSocket dashboard server is disabled");
             return Ok(());
         }
 
+        let addr = format!("127.0.0.1:{}", self.config.port);
+        let listener = bind_with_retry(&addr).await?;
+        info!("Dashboard WebSocket server listening on {}", addr);
+
+        // With lazy start, the first connection is served once everything is up
+        let (mut pending, trigger) = if self.lazy_start {
+            let first = listener.accept().await?;
+            (Some(first), CLIENT_TRIGGER)
+        } else {
+            (None, STARTUP_TRIGGER)
+        };
+        let started = std::time::Instant::now();
+
         // Initialize task board if available
         if let Some(ref task_board_manager) = self.task_board_manager {
             if let Err(e) = task_board_manager.initialize().await {
@@ -62,55 +240,110 @@ impl WebSocketServer {
             }
         }
 
-        let addr = format!("127.0.0.1:{}", self.config.port);
-        let listener = TcpListener::bind(&addr).await?;
-        info!("Dashboard WebSocket server listening on {}", addr);
-
-        // Start background tasks
-        let metrics_task = self.start_metrics_broadcaster().await;
-        let heartbeat_task = self.start_heartbeat_task().await;
+        // Start background tasks; they stop with the server, also when it is aborted
+        let _metrics_task = AbortOnDrop(self.start_metrics_broadcaster().await.abort_handle());
+        let _heartbeat_task = AbortOnDrop(self.start_heartbeat_task().await.abort_handle());
+        let _router_task = AbortOnDrop(
+            self.state
+                .channels
+                .spawn_router(
+                    self.state.broadcast_tx.subscribe(),
+                    Arc::clone(&self.state.backlog),
+                )
+                .abort_handle(),
+        );
+
+        let dispatcher = Arc::new(
+            ActionDispatcher::new(Arc::clone(&self.state), Arc::clone(&self.authenticator))
+                .with_task_board(self.task_board_manager.clone())
+                .with_workspace_manager(self.workspace_manager.clone())
+                .with_process_manager(self.process_manager.clone())
+                .with_maintenance(self.maintenance.clone())
+                .with_file_sync(self.file_sync_manager.clone())
+                .with_audit_journal(self.audit_journal.clone()),
+        );
+        if let Some(ref activations) = self.activations {
+            activations.record(DASHBOARD_SUBSYSTEM, trigger, started.elapsed());
+        }
 
         // Accept connections
-        while let Ok((stream, client_addr)) = listener.accept().await {
-            if self.state.client_count().await >= self.config.max_clients {
-                warn!(
-                    "Maximum client limit reached, rejecting connection from {}",
-                    client_addr
-                );
-                // 統一ログ: 接続制限
-                let context = LogContext::new("ipc", "connection_rejected")
-                    .with_metadata("client_addr", serde_json::json!(client_addr.to_string()))
-                    .with_metadata("reason", serde_json::json!("max_clients_reached"));
-                log_warn!(context, "WebSocket connection rejected due to client limit");
-                continue;
-            }
-
-            info!("New WebSocket connection from {}", client_addr);
-            // 統一ログ: 新規接続
-            ipc::log_message_receive(
-                "websocket",
-                &client_addr.to_string(),
-                "connection_request",
-                0,
-            );
-
+        loop {
+            let (stream, client_addr) = match pending.take() {
+                Some(first) => first,
+                None => match listener.accept().await {
+                    Ok(accepted) => accepted,
+                    Err(_) => break,
+                },
+            };
             let state = Arc::clone(&self.state);
             let config = self.config.clone();
-            let task_board_manager = self.task_board_manager.clone();
+            let dispatcher = Arc::clone(&dispatcher);
+            let services = HttpServices {
+                maintenance: self.maintenance.clone(),
+                tracker: self.tracker.clone(),
+                task_board: self.task_board_manager.clone(),
+                workspace_manager: self.workspace_manager.clone(),
+                supervisor: Some(Arc::clone(&self.supervisor)),
+                event_bridge: self.event_bridge.clone(),
+                monitoring: self.monitoring.clone(),
+                federation: self.federation.clone(),
+                caches: self.caches.clone(),
+                activations: self.activations.clone(),
+            };
+            let activate_on_connect = self.activate_on_connect.clone();
+            let focus = self.focus.clone();
+            let metrics_storage = self.metrics_storage.clone();
+            let authenticator = Arc::clone(&self.authenticator);
 
             tokio::spawn(async move {
-                if let Err(e) =
-                    handle_client_connection(stream, state, config, task_board_manager).await
+                for subsystem in &activate_on_connect {
+                    subsystem.activate(CLIENT_TRIGGER).await;
+                }
+
+                // Plain HTTP requests (long-poll, status, dashboard page) share the dashboard port
+                if http::is_plain_http_request(&stream).await {
+                    serve_http_connection(stream, state, services, &authenticator).await;
+                    return;
+                }
+
+                if state.client_count().await >= config.max_clients {
+                    warn!(
+                        "Maximum client limit reached, rejecting connection from {}",
+                        client_addr
+                    );
+                    // 統一ログ: 接続制限
+                    let context = LogContext::new("ipc", "connection_rejected")
+                        .with_metadata("client_addr", serde_json::json!(client_addr.to_string()))
+                        .with_metadata("reason", serde_json::json!("max_clients_reached"));
+                    log_warn!(context, "WebSocket connection rejected due to client limit");
+                    return;
+                }
+
+                info!("New WebSocket connection from {}", client_addr);
+                // 統一ログ: 新規接続
+                ipc::log_message_receive(
+                    "websocket",
+                    &client_addr.to_string(),
+                    "connection_request",
+                    0,
+                );
+
+                if let Err(e) = handle_client_connection(
+                    stream,
+                    state,
+                    config,
+                    dispatcher,
+                    &authenticator,
+                    focus,
+                    metrics_storage,
+                )
+                .await
                 {
                     error!("Client connection error: {}", e);
                 }
             });
         }
 
-        // Clean up tasks
-        metrics_task.abort();
-        heartbeat_task.abort();
-
         Ok(())
     }
 
@@ -118,48 +351,69 @@ impl WebSocketServer {
         Arc::clone(&self.state)
     }
 
+    /// Task board manager, when a task manager was set
+    pub fn task_board(&self) -> Option<Arc<TaskBoardManager>> {
+        self.task_board_manager.clone()
+    }
+
     /// Start metrics broadcaster task
     async fn start_metrics_broadcaster(&self) -> tokio::task::JoinHandle<()> {
         let state = Arc::clone(&self.state);
-        let update_interval = self.config.update_interval;
-
-        tokio::spawn(async move {
-            let mut interval =
-                tokio::time::interval(std::time::Duration::from_millis(update_interval));
-
-            loop {
-                interval.tick().await;
-
-                // Check for metrics updates
-                let mut metrics_rx = state.metrics_rx.write().await;
-                while let Ok(update) = metrics_rx.try_recv() {
-                    let message = DashboardMessage::MetricsUpdate(Box::new(update));
-                    state.broadcast(message);
+        let update_interval = std::time::Duration::from_millis(self.config.update_interval);
+
+        self.supervisor
+            .spawn("dashboard_broadcaster", update_interval, move |task| {
+                let state = Arc::clone(&state);
+                async move {
+                    let mut interval = tokio::time::interval(update_interval);
+
+                    loop {
+                        interval.tick().await;
+
+                        // Check for metrics updates
+                        let mut metrics_rx = state.metrics_rx.write().await;
+                        while let Ok(update) = metrics_rx.try_recv() {
+                            let message = DashboardMessage::MetricsUpdate(Box::new(update));
+                            state.broadcast(message);
+                        }
+                        task.ran();
+                    }
                 }
-            }
-        })
+            })
     }
 
     /// Start heartbeat task
     async fn start_heartbeat_task(&self) -> tokio::task::JoinHandle<()> {
         let state = Arc::clone(&self.state);
+        let period = std::time::Duration::from_secs(30);
 
-        tokio::spawn(async move {
-            let mut interval = tokio::time::interval(std::time::Duration::from_secs(30));
+        self.supervisor
+            .spawn("dashboard_heartbeat", period, move |task| {
+                let state = Arc::clone(&state);
+                async move {
+                    let mut interval = tokio::time::interval(period);
 
-            loop {
-                interval.tick().await;
+                    loop {
+                        interval.tick().await;
 
-                let heartbeat = DashboardMessage::Heartbeat {
-                    timestamp: SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap()
-                        .as_secs(),
-                };
+                        let heartbeat = DashboardMessage::Heartbeat {
+                            timestamp: SystemTime::now()
+                                .duration_since(UNIX_EPOCH)
+                                .unwrap()
+                                .as_secs(),
+                        };
 
-                state.broadcast(heartbeat);
-            }
-        })
+                        state.broadcast(heartbeat);
+
+                        long_poll::expire_idle_clients(
+                            &state,
+                            long_poll::LONG_POLL_IDLE_TIMEOUT_SECS,
+                        )
+                        .await;
+                        task.ran();
+                    }
+                }
+            })
     }
 
     /// Update framework metrics
@@ -179,13 +433,352 @@ impl WebSocketServer {
     }
 }
 
+/// Bind the dashboard port, retrying briefly while a previous instance
+/// (e.g. one handing off during an upgrade) still holds it
+async fn bind_with_retry(addr: &str) -> std::io::Result<TcpListener> {
+    const BIND_ATTEMPTS: u32 = 20;
+
+    let mut attempt = 1;
+    loop {
+        match TcpListener::bind(addr).await {
+            Err(e) if e.kind() == std::io::ErrorKind::AddrInUse && attempt < BIND_ATTEMPTS => {
+                debug!(
+                    "{} in use, retrying bind ({}/{})",
+                    addr, attempt, BIND_ATTEMPTS
+                );
+                attempt += 1;
+                tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Components queried by the plain HTTP endpoints
+#[derive(Clone, Default)]
+struct HttpServices {
+    maintenance: Option<Arc<MaintenanceController>>,
+    tracker: Option<Arc<TaskTracker>>,
+    task_board: Option<Arc<TaskBoardManager>>,
+    workspace_manager: Option<Arc<WorkspaceManager>>,
+    supervisor: Option<Arc<Supervisor>>,
+    event_bridge: Option<Arc<EventBridge>>,
+    monitoring: Option<Arc<MonitoringManager>>,
+    federation: Option<Arc<Federation>>,
+    caches: Option<Arc<CacheRegistry>>,
+    activations: Option<Arc<Activations>>,
+}
+
+/// Serve a single plain HTTP request on an accepted connection
+async fn serve_http_connection(
+    mut stream: tokio::net::TcpStream,
+    state: Arc<DashboardState>,
+    services: HttpServices,
+    authenticator: &Authenticator,
+) {
+    let response = match http::read_request(&mut stream).await {
+        Ok(request) => {
+            let started = std::time::Instant::now();
+            let token = request_token(request.header("authorization"), request.query.get("token"));
+            let response = match authenticator.identify(None, token) {
+                Ok(_) => handle_http_request(&request, &state, &services).await,
+                Err(error) => {
+                    log_auth_rejection("http", &error);
+                    http::HttpResponse::error(401, &error.message)
+                }
+            };
+            state.protocol_metrics.record(
+                "http",
+                route_label(&request),
+                started.elapsed(),
+                response.status < 400,
+            );
+            response
+        }
+        Err(e) => {
+            let context = LogContext::new("dashboard", "http_bad_request");
+            log_warn!(context, "Invalid HTTP request: {}", e);
+            http::HttpResponse::error(400, &e)
+        }
+    };
+
+    if let Err(e) = response.write_to(&mut stream).await {
+        let context = LogContext::new("dashboard", "http_write_error");
+        log_warn!(context, "Failed to write HTTP response: {}", e);
+    }
+}
+
+/// Token from an `Authorization: Bearer` header, falling back to a `?token=` parameter
+/// for clients (browsers, status bars) that cannot set headers
+fn request_token<'a>(
+    authorization: Option<&'a str>,
+    query_token: Option<&'a String>,
+) -> Option<&'a str> {
+    authorization
+        .and_then(auth::bearer_token)
+        .or(query_token.map(|token| token.as_str()))
+}
+
+fn log_auth_rejection(transport: &str, error: &crate::ipc::IpcError) {
+    let context = LogContext::new("dashboard", "auth_rejected")
+        .with_metadata("transport", serde_json::json!(transport))
+        .with_metadata("code", serde_json::json!(error.code.as_str()));
+    log_warn!(context, "Rejected dashboard client: {}", error.message);
+}
+
+/// Route a request is counted under in protocol metrics; unknown paths share one
+/// label so arbitrary URLs cannot grow the metrics without bound
+fn route_label(request: &http::HttpRequest) -> &'static str {
+    match request.segments().as_slice() {
+        ["poll", ..] => "/poll",
+        ["dashboard"] => "/dashboard",
+        ["metrics"] => "/metrics",
+        ["healthz"] => "/healthz",
+        ["status"] => "/status",
+        ["status", "budgets"] => "/status/budgets",
+        ["status", "board"] => "/status/board",
+        ["status", "processes"] => "/status/processes",
+        ["status", "protocol"] => "/status/protocol",
+        ["status", "webhooks"] => "/status/webhooks",
+        ["status", "monitoring"] => "/status/monitoring",
+        ["status", "peers"] => "/status/peers",
+        ["status", "git"] => "/status/git",
+        ["status", "subsystems"] => "/status/subsystems",
+        _ => "other",
+    }
+}
+
+/// Route a parsed HTTP request to the matching handler
+async fn handle_http_request(
+    request: &http::HttpRequest,
+    state: &DashboardState,
+    services: &HttpServices,
+) -> http::HttpResponse {
+    match request.segments().as_slice() {
+        ["poll", ..] => long_poll::handle_request(request, state).await,
+        ["dashboard"] if request.method == "GET" => page::dashboard(),
+        ["status"] if request.method == "GET" => {
+            let maintenance_status = match services.maintenance.as_deref() {
+                Some(controller) => Some(controller.status().await),
+                None => None,
+            };
+            let status = match &maintenance_status {
+                Some(m) if m.drained => "drained",
+                Some(m) if m.enabled => "draining",
+                _ => "running",
+            };
+
+            http::HttpResponse::json(
+                200,
+                &serde_json::json!({
+                    "status": status,
+                    "maintenance": maintenance_status,
+                    "dashboard": state.get_stats().await,
+                }),
+            )
+        }
+        ["status", "budgets"] if request.method == "GET" => {
+            let budgets = match services.tracker.as_deref() {
+                Some(tracker) => tracker.get_budget_status().await,
+                None => Vec::new(),
+            };
+
+            // `?format=text` gives a single line for terminal status bars
+            if request.query.get("format").map(|f| f.as_str()) == Some("text") {
+                return http::HttpResponse::text(200, budget::status_line(&budgets));
+            }
+
+            http::HttpResponse::json(
+                200,
+                &serde_json::json!({
+                    "budgets": budgets,
+                    "line": budget::status_line(&budgets),
+                }),
+            )
+        }
+        ["status", "board"] if request.method == "GET" => {
+            let Some(task_board) = services.task_board.as_deref() else {
+                return http::HttpResponse::error(404, "Task board not available");
+            };
+            let board_id = request
+                .query
+                .get("board")
+                .map_or("default", |id| id.as_str());
+            let view = super::BoardView {
+                filter: request.query.get("filter").cloned(),
+                swimlanes: match request.query.get("swimlanes").map(|s| s.parse()) {
+                    Some(Ok(swimlanes)) => Some(swimlanes),
+                    Some(Err(e)) => return http::HttpResponse::error(400, &e),
+                    None => None,
+                },
+            };
+            if let Some(Err(e)) = view.filter.as_deref().map(super::TaskFilter::parse) {
+                return http::HttpResponse::error(400, &e);
+            }
+            match task_board.get_board_view(board_id, &view).await {
+                Ok(board) => match serde_json::to_value(board) {
+                    Ok(board) => http::HttpResponse::json(200, &board),
+                    Err(e) => http::HttpResponse::error(500, &e.to_string()),
+                },
+                Err(e) => http::HttpResponse::error(404, &e),
+            }
+        }
+        ["status", "processes"] if request.method == "GET" => {
+            let mut processes: Vec<crate::ProcessSummary> = match services.workspace_manager {
+                Some(ref workspace_manager) => workspace_manager
+                    .list_workspace_states()
+                    .await
+                    .into_iter()
+                    .flat_map(|workspace| workspace.processes.into_values())
+                    .map(crate::ProcessSummary::from)
+                    .collect(),
+                None => Vec::new(),
+            };
+            processes.sort_by(|a, b| (&a.workspace, &a.id).cmp(&(&b.workspace, &b.id)));
+            http::HttpResponse::json(200, &serde_json::json!({ "processes": processes }))
+        }
+        ["status", "protocol"] if request.method == "GET" => http::HttpResponse::json(
+            200,
+            &serde_json::json!({ "operations": state.protocol_metrics.health() }),
+        ),
+        ["status", "webhooks"] if request.method == "GET" => {
+            let deliveries = match services.event_bridge.as_deref() {
+                Some(bridge) => bridge.deliveries().await,
+                None => Vec::new(),
+            };
+            http::HttpResponse::json(200, &serde_json::json!({ "deliveries": deliveries }))
+        }
+        ["status", "monitoring"] if request.method == "GET" => {
+            let Some(monitoring) = services.monitoring.as_deref() else {
+                return http::HttpResponse::error(404, "Monitoring not available");
+            };
+            match serde_json::to_value(monitoring.status().await) {
+                Ok(status) => http::HttpResponse::json(200, &status),
+                Err(e) => http::HttpResponse::error(500, &e.to_string()),
+            }
+        }
+        ["status", "subsystems"] if request.method == "GET" => {
+            let subsystems = services
+                .activations
+                .as_deref()
+                .map(Activations::list)
+                .unwrap_or_default();
+            http::HttpResponse::json(200, &serde_json::json!({ "subsystems": subsystems }))
+        }
+        ["status", "peers"] if request.method == "GET" => {
+            let peers = match services.federation.as_deref() {
+                Some(federation) => federation.peers().await,
+                None => Vec::new(),
+            };
+            http::HttpResponse::json(200, &serde_json::json!({ "peers": peers }))
+        }
+        ["status", "git"] if request.method == "GET" => {
+            let statuses = match services.workspace_manager {
+                Some(ref workspace_manager) => {
+                    workspace_manager.git_status(None).await.unwrap_or_default()
+                }
+                None => Vec::new(),
+            };
+            http::HttpResponse::json(200, &serde_json::json!({ "workspaces": statuses }))
+        }
+        ["metrics"] if request.method == "GET" => {
+            let mut metrics = state.protocol_metrics.to_prometheus();
+            if let Some(federation) = services.federation.as_deref() {
+                metrics.push_str(&federation.to_prometheus().await);
+            }
+            if let Some(caches) = services.caches.as_deref() {
+                metrics.push_str(&caches.to_prometheus());
+            }
+            if let Some(activations) = services.activations.as_deref() {
+                metrics.push_str(&activations.to_prometheus());
+            }
+            http::HttpResponse::prometheus(metrics)
+        }
+        ["healthz"] if request.method == "GET" => {
+            let tasks = services
+                .supervisor
+                .as_deref()
+                .map(Supervisor::health)
+                .unwrap_or_default();
+            let healthy = tasks.iter().all(TaskHealth::is_healthy);
+            http::HttpResponse::json(
+                if healthy { 200 } else { 503 },
+                &serde_json::json!({
+                    "status": if healthy { "ok" } else { "degraded" },
+                    "tasks": tasks,
+                }),
+            )
+        }
+        ["dashboard"]
+        | ["metrics"]
+        | ["healthz"]
+        | ["status"]
+        | ["status", "budgets" | "board" | "processes" | "protocol" | "webhooks" | "monitoring" | "peers"
+        | "git"] => http::HttpResponse::error(405, "Method not allowed"),
+        _ => http::HttpResponse::error(404, "Not found"),
+    }
+}
+
+// The handshake callback's error type is tungstenite's HTTP response
+#[allow(clippy::result_large_err)]
 async fn handle_client_connection(
     stream: tokio::net::TcpStream,
     state: Arc<DashboardState>,
-    _config: DashboardConfig,
-    task_board_manager: Option<Arc<TaskBoardManager>>,
+    config: DashboardConfig,
+    dispatcher: Arc<ActionDispatcher>,
+    authenticator: &Authenticator,
+    focus: Option<Arc<FocusController>>,
+    metrics_storage: Option<Arc<MetricsStorage>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let ws_stream = accept_async(stream).await?;
+    // Credentials are checked during the handshake, before the upgrade
+    let mut identity = None;
+    let mut language = None;
+    let mut format = StreamFormat::JSON;
+    let ws_stream = accept_hdr_async(
+        stream,
+        |request: &HandshakeRequest, mut response: HandshakeResponse| {
+            let authorization = request
+                .headers()
+                .get("authorization")
+                .and_then(|value| value.to_str().ok());
+            let mut query = request
+                .uri()
+                .query()
+                .map(http::parse_query)
+                .unwrap_or_default();
+            let query_token = query.remove("token");
+            // `?lang=` picks the alert language; unknown tags fall back to the default
+            language = query.get("lang").and_then(|tag| Language::from_tag(tag));
+            // Clients without a dashboard subprotocol get JSON text frames
+            let negotiated = request
+                .headers()
+                .get("sec-websocket-protocol")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|offered| StreamFormat::negotiate(offered, config.compression));
+            match authenticator.identify(None, request_token(authorization, query_token.as_ref())) {
+                Ok(client) => {
+                    identity = Some(client);
+                    if let Some(negotiated) = negotiated {
+                        format = negotiated;
+                        if let Ok(protocol) = negotiated.to_string().parse() {
+                            response
+                                .headers_mut()
+                                .insert("sec-websocket-protocol", protocol);
+                        }
+                    }
+                    Ok(response)
+                }
+                Err(error) => {
+                    log_auth_rejection("websocket", &error);
+                    let mut rejection = ErrorResponse::new(Some(error.message));
+                    *rejection.status_mut() = StatusCode::UNAUTHORIZED;
+                    Err(rejection)
+                }
+            }
+        },
+    )
+    .await?;
+    let identity = identity.unwrap_or_else(ClientIdentity::anonymous);
     let (mut ws_sender, mut ws_receiver) = ws_stream.split();
 
     let client_id = Uuid::new_v4().to_string();
@@ -203,6 +796,7 @@ async fn handle_client_connection(
             .duration_since(UNIX_EPOCH)
             .unwrap()
             .as_secs(),
+        language,
     };
 
     state.register_client(client_info).await;
@@ -217,36 +811,63 @@ async fn handle_client_connection(
     // Create channels for outgoing messages
     let (outgoing_tx, mut outgoing_rx) = tokio::sync::mpsc::channel::<Message>(100);
 
-    // Create broadcast receiver for this client
-    let mut broadcast_rx = state.broadcast_tx.subscribe();
+    // Listen on the topic channels of the client's subscriptions
+    let mut subscriber = TopicSubscriber::new(&state.channels, &[MetricSubscription::All]);
+    let mut resubscriptions = state.channels.resubscriptions();
+
+    // `ResumeFrom` replays are sent by the forwarder to keep the stream in order
+    let (resume_tx, mut resume_rx) = tokio::sync::mpsc::channel::<ResumeRequest>(4);
 
     // Spawn task to handle outgoing messages
-    let client_id_out = client_id.clone();
-    let state_out = Arc::clone(&state);
-    let outgoing_sender = outgoing_tx.clone();
+    let client = ClientOutlet {
+        client_id: client_id.clone(),
+        state: Arc::clone(&state),
+        focus,
+        format,
+        deltas: Arc::new(std::sync::Mutex::new(DeltaEncoder::new())),
+        task_board: dispatcher.task_board(),
+        board_views: Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+        outgoing: outgoing_tx.clone(),
+    };
+    let forwarder = client.clone();
     let broadcast_task = tokio::spawn(async move {
-        while let Ok(message) = broadcast_rx.recv().await {
-            // Check if client should receive this message
-            let should_send = match &message {
-                DashboardMessage::MetricsUpdate(update) => {
-                    state_out.should_send_update(&client_id_out, update).await
+        let client = forwarder;
+        // Backlog sequence up to which a replay already delivered messages
+        let mut replayed_through = 0;
+        loop {
+            let published = tokio::select! {
+                published = subscriber.recv() => match published {
+                    Some(published) => published,
+                    None => break,
+                },
+                changed = resubscriptions.changed() => {
+                    if changed.is_err() {
+                        break;
+                    }
+                    let subscriptions = client.state.get_client_subscriptions(&client.client_id).await;
+                    subscriber.resubscribe(&client.state.channels, &subscriptions);
+                    continue;
                 }
-                _ => true, // Send non-metrics messages to all clients
-            };
-
-            if should_send {
-                let ws_message = super::WebSocketMessage {
-                    id: None,
-                    payload: message,
-                };
-
-                if let Ok(json) = serde_json::to_string(&ws_message) {
-                    if (outgoing_sender.send(Message::Text(json)).await).is_err() {
-                        break; // Channel closed
+                Some(request) = resume_rx.recv() => {
+                    match client.replay(request).await {
+                        Some(through) => replayed_through = replayed_through.max(through),
+                        None => break,
                     }
-                } else {
-                    error!("Failed to serialize message for client {}", client_id_out);
+                    continue;
                 }
+            };
+
+            if published
+                .backlog_seq
+                .is_some_and(|seq| seq <= replayed_through)
+            {
+                continue;
+            }
+            if !client
+                .deliver(published.backlog_seq, &published.message)
+                .await
+            {
+                break; // Channel closed
             }
         }
     });
@@ -261,27 +882,36 @@ async fn handle_client_connection(
         }
     });
 
+    // Follow the log files the client subscribed to
+    let tail_task = tokio::spawn(follow_log_tails(client.clone(), Arc::clone(&dispatcher)));
+
     // Handle incoming messages
     while let Some(msg) = ws_receiver.next().await {
         match msg {
-            Ok(Message::Text(text)) => {
-                debug!("Received message from client {}: {}", client_id, text);
+            Ok(message @ (Message::Text(_) | Message::Binary(_))) => {
+                debug!(
+                    "Received {} byte message from client {}",
+                    message.len(),
+                    client_id
+                );
 
                 // Parse and handle client command
-                if let Ok(ws_msg) = serde_json::from_str::<super::WebSocketMessage>(&text) {
-                    if let Err(e) = handle_client_message(
-                        &client_id,
-                        ws_msg,
-                        &state,
-                        &outgoing_tx,
-                        &task_board_manager,
-                    )
-                    .await
-                    {
-                        error!("Error handling client message: {}", e);
+                match format.decode::<super::WebSocketMessage>(&message) {
+                    Ok(ws_msg) => {
+                        if let Err(e) = handle_client_message(
+                            &client,
+                            &identity,
+                            ws_msg,
+                            &resume_tx,
+                            &dispatcher,
+                            metrics_storage.as_deref(),
+                        )
+                        .await
+                        {
+                            error!("Error handling client message: {}", e);
+                        }
                     }
-                } else {
-                    warn!("Invalid message format from client {}", client_id);
+                    Err(e) => warn!("Invalid message format from client {}: {}", client_id, e),
                 }
             }
             Ok(Message::Close(_)) => {
@@ -306,6 +936,7 @@ async fn handle_client_connection(
 
     // Clean up
     broadcast_task.abort();
+    tail_task.abort();
     sender_task.abort();
     state.unregister_client(&client_id).await;
     info!("Client {} disconnected", client_id);
@@ -313,26 +944,209 @@ async fn handle_client_connection(
     Ok(())
 }
 
+/// A client's request to replay the backlog after `seq`
+struct ResumeRequest {
+    request_id: Option<String>,
+    seq: u64,
+}
+
+/// Where messages for one client go, and what decides how they look
+#[derive(Clone)]
+struct ClientOutlet {
+    client_id: String,
+    state: Arc<DashboardState>,
+    focus: Option<Arc<FocusController>>,
+    format: StreamFormat,
+    /// Acknowledged metrics the client's updates are diffed against
+    deltas: Arc<std::sync::Mutex<DeltaEncoder>>,
+    task_board: Option<Arc<TaskBoardManager>>,
+    /// Views the client requested, by board ID
+    board_views: Arc<std::sync::Mutex<std::collections::HashMap<String, BoardView>>>,
+    outgoing: tokio::sync::mpsc::Sender<Message>,
+}
+
+impl ClientOutlet {
+    /// Send a message in the client's stream format; false once the
+    /// connection is gone
+    async fn send<T: Serialize>(&self, message: &T) -> bool {
+        match self.format.encode(message) {
+            Ok(frame) => self.outgoing.send(frame).await.is_ok(),
+            Err(e) => {
+                error!(
+                    "Failed to encode message for client {}: {}",
+                    self.client_id, e
+                );
+                true
+            }
+        }
+    }
+
+    /// Send a broadcast to the client; false once the connection is gone
+    async fn deliver(&self, seq: Option<u64>, message: &DashboardMessage) -> bool {
+        // Info and warning alerts are withheld while focusing
+        if matches!(message, DashboardMessage::Alert(_))
+            && !self
+                .focus
+                .as_ref()
+                .map_or(true, |focus| focus.should_deliver(message))
+        {
+            return true;
+        }
+
+        let payload = match message {
+            DashboardMessage::Alert(_) => message
+                .clone()
+                .localized(self.state.client_language(&self.client_id).await),
+            DashboardMessage::MetricsUpdate(update) => self.lock_deltas().encode(update),
+            DashboardMessage::TaskBoardUpdate { board_id, .. } => {
+                let view = self.lock_board_views().get(board_id).cloned();
+                match view {
+                    // Falls back to the whole board once it can no longer be scoped
+                    Some(view) => self
+                        .board_view(board_id, &view)
+                        .await
+                        .map_or_else(|_| message.clone(), TaskBoardState::into_message),
+                    None => message.clone(),
+                }
+            }
+            message => message.clone(),
+        };
+        self.send(&super::WebSocketMessage {
+            id: None,
+            seq,
+            payload,
+        })
+        .await
+    }
+
+    fn lock_deltas(&self) -> std::sync::MutexGuard<'_, DeltaEncoder> {
+        self.deltas.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_board_views(
+        &self,
+    ) -> std::sync::MutexGuard<'_, std::collections::HashMap<String, BoardView>> {
+        self.board_views.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// A board as seen through a view
+    async fn board_view(&self, board_id: &str, view: &BoardView) -> Result<TaskBoardState, String> {
+        match &self.task_board {
+            Some(task_board) => task_board.get_board_view(board_id, view).await,
+            None => Err("Task board not available".to_string()),
+        }
+    }
+
+    /// Send the subscribed backlog entries after the requested sequence,
+    /// then a response with `{ replayed, next_seq, truncated }`
+    ///
+    /// Returns the last sequence replayed, or `None` once the connection is
+    /// gone. `truncated` tells the client that some messages were already
+    /// dropped from the backlog and it should request a full update.
+    async fn replay(&self, request: ResumeRequest) -> Option<u64> {
+        let subscriptions = self.state.get_client_subscriptions(&self.client_id).await;
+        let topics = Topic::for_subscriptions(&subscriptions);
+        let slice = self.state.backlog.resume_from(request.seq).await;
+
+        let mut replayed = 0;
+        for entry in &slice.entries {
+            if !Topic::of(&entry.message)
+                .iter()
+                .any(|topic| topics.contains(topic))
+            {
+                continue;
+            }
+            if !self.deliver(Some(entry.seq), &entry.message).await {
+                return None;
+            }
+            replayed += 1;
+        }
+        debug!(
+            "Replayed {} messages after seq {} to client {}",
+            replayed, request.seq, self.client_id
+        );
+
+        let response = super::DashboardResponse {
+            request_id: request.request_id,
+            success: true,
+            data: Some(serde_json::json!({
+                "replayed": replayed,
+                "next_seq": slice.next_cursor,
+                "truncated": slice.truncated,
+            })),
+            error: None,
+        };
+        self.send(&response).await.then_some(slice.next_cursor)
+    }
+}
+
+/// Send new lines of followed log files to one client
+///
+/// Every subscription keeps its own cursor; a new subscription starts with
+/// the last lines of the file, like `tail -F`.
+async fn follow_log_tails(client: ClientOutlet, dispatcher: Arc<ActionDispatcher>) {
+    let client_id = &client.client_id;
+    let mut cursors: std::collections::HashMap<String, TailCursor> =
+        std::collections::HashMap::new();
+    let mut interval = tokio::time::interval(tail::TAIL_POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        let tails = client.state.log_tails(client_id).await;
+        cursors.retain(|process_id, _| tails.iter().any(|(id, _)| id == process_id));
+
+        for (process_id, filter) in tails {
+            let cursor = cursors.get(&process_id).copied();
+            let chunk = match dispatcher
+                .tail_process_log(&process_id, &filter, cursor)
+                .await
+            {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    debug!("Log tail of {} for client {}: {}", process_id, client_id, e);
+                    continue;
+                }
+            };
+            cursors.insert(process_id.clone(), chunk.cursor);
+            if chunk.lines.is_empty() && !chunk.rotated && !chunk.lost {
+                continue;
+            }
+
+            let ws_message = super::WebSocketMessage {
+                id: None,
+                seq: None,
+                payload: DashboardMessage::LogTail { process_id, chunk },
+            };
+            if !client.send(&ws_message).await {
+                return;
+            }
+        }
+    }
+}
+
 async fn handle_client_message(
-    client_id: &str,
+    client: &ClientOutlet,
+    identity: &ClientIdentity,
     ws_msg: super::WebSocketMessage,
-    state: &Arc<DashboardState>,
-    outgoing_tx: &tokio::sync::mpsc::Sender<Message>,
-    task_board_manager: &Option<Arc<TaskBoardManager>>,
+    resume_tx: &tokio::sync::mpsc::Sender<ResumeRequest>,
+    dispatcher: &ActionDispatcher,
+    metrics_storage: Option<&MetricsStorage>,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let client_id = client.client_id.as_str();
+    let state = &client.state;
     match ws_msg.payload {
         DashboardMessage::Command(command) => {
-            match command {
+            // Actions are measured individually, e.g. `KillProcess` rather than `ExecuteAction`
+            let operation = match &command {
+                super::ClientCommand::ExecuteAction { action, .. } => auth::operation_name(action),
+                command => auth::operation_name(command),
+            };
+            let started = std::time::Instant::now();
+            let success = match command {
                 super::ClientCommand::Subscribe { subscriptions } => {
-                    // Update client subscriptions
-                    let mut clients = state.connected_clients.write().await;
-                    if let Some(client) = clients.get_mut(client_id) {
-                        client.subscriptions = subscriptions;
-                        client.last_activity = SystemTime::now()
-                            .duration_since(UNIX_EPOCH)
-                            .unwrap()
-                            .as_secs();
-                    }
+                    // Update client subscriptions; its forwarder moves to the new topics
+                    state
+                        .set_client_subscriptions(client_id, subscriptions)
+                        .await;
 
                     // Send success response
                     let _response = super::DashboardResponse {
@@ -344,40 +1158,120 @@ async fn handle_client_message(
 
                     // For now, just acknowledge the subscription
                     debug!("Client {} updated subscriptions", client_id);
+                    true
                 }
                 super::ClientCommand::RequestFullUpdate => {
                     // Send full metrics update
-                    let metrics = state.framework_metrics.read().await;
-                    let update = MetricsUpdate::full(metrics.clone());
+                    let metrics = state.framework_metrics.read().await.clone();
+                    let update = MetricsUpdate::full(metrics);
 
                     let ws_message = super::WebSocketMessage {
                         id: ws_msg.id,
+                        seq: None,
                         payload: DashboardMessage::MetricsUpdate(Box::new(update)),
                     };
-
-                    if let Ok(json) = serde_json::to_string(&ws_message) {
-                        outgoing_tx.send(Message::Text(json)).await?;
-                    }
+                    client.send(&ws_message).await
                 }
-                super::ClientCommand::ExecuteAction { action } => {
-                    if let Some(task_manager) = task_board_manager {
-                        handle_task_action(
+                super::ClientCommand::ExecuteAction { action, confirm } => {
+                    let response = dispatcher
+                        .execute_confirmed(
                             client_id,
+                            identity,
+                            ws_msg.id.clone(),
                             action,
-                            task_manager,
-                            outgoing_tx,
-                            &ws_msg.id,
+                            confirm.as_deref(),
                         )
+                        .await;
+                    client.send(&response).await && response.success
+                }
+                super::ClientCommand::QueryHistory {
+                    metric_type,
+                    start_time,
+                    end_time,
+                    limit,
+                } => {
+                    let result =
+                        query_history(metrics_storage, &metric_type, start_time, end_time, limit)
+                            .await;
+                    let success = result.is_ok();
+                    let response = match result {
+                        Ok(data) => super::DashboardResponse {
+                            request_id: ws_msg.id,
+                            success: true,
+                            data: Some(data),
+                            error: None,
+                        },
+                        Err(e) => super::DashboardResponse {
+                            request_id: ws_msg.id,
+                            success: false,
+                            data: None,
+                            error: Some(e),
+                        },
+                    };
+                    client.send(&response).await && success
+                }
+                super::ClientCommand::ResumeFrom { seq } => {
+                    // The forwarder answers once the replay has been sent
+                    resume_tx
+                        .send(ResumeRequest {
+                            request_id: ws_msg.id,
+                            seq,
+                        })
                         .await?;
-                    } else {
-                        error!("Task board manager not available for client {}", client_id);
+                    true
+                }
+                super::ClientCommand::RequestBoardUpdate { board_id, view } => {
+                    let board_id = board_id.unwrap_or_else(|| "default".to_string());
+                    match client.board_view(&board_id, &view).await {
+                        Ok(board) => {
+                            if view.is_unscoped() {
+                                client.lock_board_views().remove(&board_id);
+                            } else {
+                                client.lock_board_views().insert(board_id, view);
+                            }
+                            client
+                                .send(&super::WebSocketMessage {
+                                    id: ws_msg.id,
+                                    seq: None,
+                                    payload: board.into_message(),
+                                })
+                                .await
+                        }
+                        Err(e) => {
+                            let response = super::DashboardResponse {
+                                request_id: ws_msg.id,
+                                success: false,
+                                data: None,
+                                error: Some(e),
+                            };
+                            client.send(&response).await;
+                            false
+                        }
                     }
                 }
-                _ => {
+                super::ClientCommand::AckMetrics { version } => {
+                    // Acknowledgements are frequent, so they get no response
+                    client.lock_deltas().acknowledge(version)
+                }
+                super::ClientCommand::SetLanguage { language } => {
+                    state.set_client_language(client_id, language).await;
+                    let response = super::DashboardResponse {
+                        request_id: ws_msg.id,
+                        success: true,
+                        data: Some(serde_json::json!({ "language": language })),
+                        error: None,
+                    };
+                    client.send(&response).await
+                }
+                command => {
                     // Handle other commands as needed
                     debug!("Unhandled command from client {}: {:?}", client_id, command);
+                    false
                 }
-            }
+            };
+            state
+                .protocol_metrics
+                .record("websocket", &operation, started.elapsed(), success);
         }
         DashboardMessage::Heartbeat { .. } => {
             // Update client activity
@@ -397,139 +1291,22 @@ async fn handle_client_message(
     Ok(())
 }
 
-/// Handle task management actions
-async fn handle_task_action(
-    client_id: &str,
-    action: super::DashboardAction,
-    task_board_manager: &Arc<TaskBoardManager>,
-    outgoing_tx: &tokio::sync::mpsc::Sender<Message>,
-    request_id: &Option<String>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let result = match action {
-        super::DashboardAction::CreateTask { task_data } => {
-            match task_board_manager
-                .create_task_from_dashboard(task_data)
-                .await
-            {
-                Ok(task_id) => {
-                    info!("Created task {} for client {}", task_id, client_id);
-                    Ok(serde_json::to_value(task_id)?)
-                }
-                Err(e) => {
-                    error!("Failed to create task for client {}: {}", client_id, e);
-                    Err(e)
-                }
-            }
-        }
-        super::DashboardAction::UpdateTask { task_id, task_data } => {
-            match task_board_manager
-                .update_task_from_dashboard(&task_id, task_data)
-                .await
-            {
-                Ok(_) => {
-                    info!("Updated task {} for client {}", task_id, client_id);
-                    Ok(serde_json::Value::Bool(true))
-                }
-                Err(e) => {
-                    error!(
-                        "Failed to update task {} for client {}: {}",
-                        task_id, client_id, e
-                    );
-                    Err(e)
-                }
-            }
-        }
-        super::DashboardAction::DeleteTask { task_id } => {
-            match task_board_manager
-                .delete_task_from_dashboard(&task_id)
-                .await
-            {
-                Ok(_) => {
-                    info!("Deleted task {} for client {}", task_id, client_id);
-                    Ok(serde_json::Value::Bool(true))
-                }
-                Err(e) => {
-                    error!(
-                        "Failed to delete task {} for client {}: {}",
-                        task_id, client_id, e
-                    );
-                    Err(e)
-                }
-            }
-        }
-        super::DashboardAction::MoveTask {
-            task_id,
-            to_column,
-            position,
-        } => {
-            match task_board_manager
-                .move_task("default", &task_id, &to_column, position)
-                .await
-            {
-                Ok(_) => {
-                    info!(
-                        "Moved task {} to {} for client {}",
-                        task_id, to_column, client_id
-                    );
-                    Ok(serde_json::Value::Bool(true))
-                }
-                Err(e) => {
-                    error!(
-                        "Failed to move task {} for client {}: {}",
-                        task_id, client_id, e
-                    );
-                    Err(e)
-                }
-            }
-        }
-        super::DashboardAction::UpdateTaskProgress { task_id, progress } => {
-            match task_board_manager
-                .update_task_progress(&task_id, progress)
-                .await
-            {
-                Ok(_) => {
-                    info!(
-                        "Updated task {} progress to {}% for client {}",
-                        task_id, progress, client_id
-                    );
-                    Ok(serde_json::Value::Bool(true))
-                }
-                Err(e) => {
-                    error!(
-                        "Failed to update task {} progress for client {}: {}",
-                        task_id, client_id, e
-                    );
-                    Err(e)
-                }
-            }
-        }
-        _ => {
-            debug!(
-                "Unhandled task action for client {}: {:?}",
-                client_id, action
-            );
-            Ok(serde_json::Value::Null)
-        }
-    };
-
-    // Send response back to client
-    let (success, data, error) = match &result {
-        Ok(value) => (true, Some(value.clone()), None),
-        Err(e) => (false, None, Some(e.to_string())),
-    };
-
-    let response = super::DashboardResponse {
-        request_id: request_id.clone(),
-        success,
-        data,
-        error,
-    };
-
-    if let Ok(json) = serde_json::to_string(&response) {
-        outgoing_tx.send(Message::Text(json)).await?;
-    }
-
-    Ok(())
+/// Answer a `QueryHistory` command with `{ metric, points }`
+async fn query_history(
+    metrics_storage: Option<&MetricsStorage>,
+    metric_type: &str,
+    start_time: u64,
+    end_time: u64,
+    limit: Option<usize>,
+) -> Result<serde_json::Value, String> {
+    let storage = metrics_storage.ok_or("Metrics history not available")?;
+    let metric = HistoryMetric::parse(metric_type).ok_or_else(|| {
+        format!("Unknown metric type: {metric_type} (expected cpu, memory or task_throughput)")
+    })?;
+    let points = storage
+        .query_history(metric, start_time, end_time, limit)
+        .await?;
+    Ok(serde_json::json!({ "metric": metric, "points": points }))
 }
 
 #[cfg(test)]
@@ -573,6 +1350,7 @@ mod tests {
             workspaces: Vec::new(),
             framework: Some(FrameworkMetrics::new()),
             update_type: super::super::UpdateType::Full,
+            version: None,
         };
 
         let result = metrics_tx.send(update).await;
@@ -592,4 +1370,363 @@ mod tests {
         let result = timeout(Duration::from_millis(100), server.start()).await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_status_endpoint_reports_maintenance() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (state, _metrics_tx) = DashboardState::new(DashboardConfig::default());
+        let task_manager = Arc::new(TaskManager::new(crate::task::TaskConfig {
+            persistence_enabled: false,
+            ..Default::default()
+        }));
+        let workspace_manager = Arc::new(
+            crate::room::WorkspaceManager::new(Some(dir.path().join("workspaces.json"))).unwrap(),
+        );
+        let maintenance = Arc::new(MaintenanceController::new(task_manager, workspace_manager));
+
+        let request = http::HttpRequest {
+            method: "GET".to_string(),
+            path: "/status".to_string(),
+            query: Default::default(),
+            headers: Default::default(),
+            body: Vec::new(),
+        };
+
+        let services = HttpServices {
+            maintenance: Some(Arc::clone(&maintenance)),
+            ..Default::default()
+        };
+
+        let response = handle_http_request(&request, &state, &services).await;
+        let body: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(body["status"], "running");
+
+        maintenance.enable(Some("upgrade".to_string())).await;
+        let response = handle_http_request(&request, &state, &services).await;
+        let body: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(body["status"], "drained");
+        assert_eq!(body["maintenance"]["reason"], "upgrade");
+    }
+
+    #[tokio::test]
+    async fn test_budget_status_endpoint() {
+        let (state, _metrics_tx) = DashboardState::new(DashboardConfig::default());
+        let tracker = TaskTracker::new()
+            .with_budgets(vec![crate::task::TimeBudget::category("Development", 20.0)]);
+        let services = HttpServices {
+            tracker: Some(Arc::new(tracker)),
+            ..Default::default()
+        };
+
+        let mut request = http::HttpRequest {
+            method: "GET".to_string(),
+            path: "/status/budgets".to_string(),
+            query: Default::default(),
+            headers: Default::default(),
+            body: Vec::new(),
+        };
+
+        let response = handle_http_request(&request, &state, &services).await;
+        let body: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(body["budgets"][0]["category"], "Development");
+        assert_eq!(body["budgets"][0]["state"], "ok");
+
+        request
+            .query
+            .insert("format".to_string(), "text".to_string());
+        let response = handle_http_request(&request, &state, &services).await;
+        assert_eq!(response.content_type, "text/plain; charset=utf-8");
+        assert_eq!(response.body, b"Development 0/20h");
+    }
+
+    #[tokio::test]
+    async fn test_dashboard_page_and_snapshots() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (state, _metrics_tx) = DashboardState::new(DashboardConfig::default());
+        let task_manager = Arc::new(TaskManager::new(crate::task::TaskConfig {
+            persistence_enabled: false,
+            ..Default::default()
+        }));
+        task_manager
+            .create_task(crate::task::Task::new(
+                "Render board".to_string(),
+                crate::task::TaskCategory::Development,
+            ))
+            .await
+            .unwrap();
+        let task_board = TaskBoardManager::new(task_manager, state.broadcast_tx.clone());
+        task_board.initialize().await.unwrap();
+        let workspace_manager = Arc::new(
+            crate::room::WorkspaceManager::new(Some(dir.path().join("workspaces.json"))).unwrap(),
+        );
+        let services = HttpServices {
+            task_board: Some(Arc::new(task_board)),
+            workspace_manager: Some(workspace_manager),
+            ..Default::default()
+        };
+
+        let get = |path: &str| http::HttpRequest {
+            method: "GET".to_string(),
+            path: path.to_string(),
+            query: Default::default(),
+            headers: Default::default(),
+            body: Vec::new(),
+        };
+
+        let response = handle_http_request(&get("/dashboard"), &state, &services).await;
+        assert_eq!(response.status, 200);
+        assert_eq!(response.content_type, "text/html; charset=utf-8");
+        let page = String::from_utf8(response.body).unwrap();
+        for endpoint in [
+            "/status/board",
+            "/status/processes",
+            "/status/protocol",
+            "/status/webhooks",
+            "/status/monitoring",
+            "/status/git",
+            "QueryHistory",
+        ] {
+            assert!(page.contains(endpoint), "page does not use {endpoint}");
+        }
+
+        let response = handle_http_request(&get("/status/board"), &state, &services).await;
+        let body: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(body["columns"][0]["tasks"].as_array().unwrap().len(), 1);
+        assert_eq!(body["tasks"][0]["title"], "Render board");
+
+        let response = handle_http_request(&get("/status/processes"), &state, &services).await;
+        let body: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(body["processes"], serde_json::json!([]));
+
+        let response = handle_http_request(&get("/status/git"), &state, &services).await;
+        assert_eq!(response.status, 200);
+
+        let mut post = get("/dashboard");
+        post.method = "POST".to_string();
+        let response = handle_http_request(&post, &state, &services).await;
+        assert_eq!(response.status, 405);
+
+        let response =
+            handle_http_request(&get("/status/board"), &state, &HttpServices::default()).await;
+        assert_eq!(response.status, 404);
+
+        let services = HttpServices {
+            monitoring: Some(Arc::new(MonitoringManager::new(Default::default()))),
+            ..Default::default()
+        };
+        let response = handle_http_request(&get("/status/monitoring"), &state, &services).await;
+        let body: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(body["overall_status"], "Unknown");
+        assert_eq!(body["active_alerts"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_healthz_reports_supervised_tasks() {
+        let (state, _metrics_tx) = DashboardState::new(DashboardConfig::default());
+        let supervisor = Arc::new(Supervisor::new().with_restart_backoff(
+            std::time::Duration::from_millis(1),
+            std::time::Duration::from_millis(1),
+        ));
+        let services = HttpServices {
+            supervisor: Some(Arc::clone(&supervisor)),
+            ..Default::default()
+        };
+        let request = http::HttpRequest {
+            method: "GET".to_string(),
+            path: "/healthz".to_string(),
+            query: Default::default(),
+            headers: Default::default(),
+            body: Vec::new(),
+        };
+
+        let collector = supervisor.spawn(
+            "collector",
+            std::time::Duration::from_secs(60),
+            |task| async move {
+                loop {
+                    task.ran();
+                    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+                }
+            },
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        let response = handle_http_request(&request, &state, &services).await;
+        let body: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(body["status"], "ok");
+        assert_eq!(body["tasks"][0]["name"], "collector");
+        assert!(body["tasks"][0]["last_success"].is_u64());
+
+        // A task that keeps exiting degrades the whole service
+        let watcher = supervisor.spawn(
+            "watcher",
+            std::time::Duration::from_secs(60),
+            |_task| async {},
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        let response = handle_http_request(&request, &state, &services).await;
+        let body: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(response.status, 503);
+        assert_eq!(body["status"], "degraded");
+        assert_eq!(body["tasks"][1]["state"], "crash_looping");
+        assert_eq!(body["tasks"][1]["last_error"], "task exited");
+
+        collector.abort();
+        watcher.abort();
+    }
+
+    #[tokio::test]
+    async fn test_protocol_metrics_endpoints() {
+        let (state, _metrics_tx) = DashboardState::new(DashboardConfig::default());
+        state.protocol_metrics.record(
+            "ipc",
+            "TaskQueue",
+            std::time::Duration::from_millis(12),
+            true,
+        );
+        state.protocol_metrics.record(
+            "websocket",
+            "KillProcess",
+            std::time::Duration::from_millis(3),
+            false,
+        );
+        let caches = Arc::new(CacheRegistry::new());
+        caches
+            .register("performance", Default::default())
+            .get("missing");
+        let activations = Arc::new(Activations::new());
+        activations.register(DASHBOARD_SUBSYSTEM);
+        activations.record("file_sync", CLIENT_TRIGGER, std::time::Duration::ZERO);
+        let services = HttpServices {
+            caches: Some(caches),
+            activations: Some(activations),
+            ..Default::default()
+        };
+        let get = |path: &str| http::HttpRequest {
+            method: "GET".to_string(),
+            path: path.to_string(),
+            query: Default::default(),
+            headers: Default::default(),
+            body: Vec::new(),
+        };
+
+        let response = handle_http_request(&get("/metrics"), &state, &services).await;
+        assert_eq!(response.status, 200);
+        assert!(response
+            .content_type
+            .starts_with("text/plain; version=0.0.4"));
+        let text = String::from_utf8(response.body).unwrap();
+        assert!(text.contains(
+            "wezterm_parallel_request_errors_total{transport=\"websocket\",operation=\"KillProcess\"} 1"
+        ));
+        assert!(text.contains("wezterm_parallel_cache_misses_total{cache=\"performance\"} 1"));
+        assert!(text.contains("wezterm_parallel_subsystem_active{subsystem=\"dashboard\"} 0"));
+
+        let response = handle_http_request(&get("/status/subsystems"), &state, &services).await;
+        let body: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(body["subsystems"][0]["activation"], serde_json::Value::Null);
+        assert_eq!(
+            body["subsystems"][1]["activation"]["trigger"],
+            "dashboard_client"
+        );
+
+        let response = handle_http_request(&get("/status/protocol"), &state, &services).await;
+        let body: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+        let operations = body["operations"].as_array().unwrap();
+        assert_eq!(operations.len(), 2);
+        assert_eq!(operations[0]["operation"], "TaskQueue");
+        assert_eq!(operations[1]["error_rate"], 1.0);
+
+        assert_eq!(route_label(&get("/poll/client-1")), "/poll");
+        assert_eq!(route_label(&get("/no/such/page")), "other");
+    }
+
+    #[tokio::test]
+    async fn test_resume_replays_subscribed_backlog() {
+        let (state, _metrics_tx) = DashboardState::new(DashboardConfig::default());
+        let state = Arc::new(state);
+        for timestamp in 1..=3 {
+            state
+                .backlog
+                .push(DashboardMessage::Heartbeat { timestamp })
+                .await;
+        }
+        // Not on the events topic an unregistered client listens to
+        state
+            .backlog
+            .push(DashboardMessage::MetricsUpdate(Box::new(
+                MetricsUpdate::full(FrameworkMetrics::new()),
+            )))
+            .await;
+
+        let (outgoing, mut received) = tokio::sync::mpsc::channel(16);
+        let client = ClientOutlet {
+            client_id: "client-1".to_string(),
+            state,
+            focus: None,
+            format: StreamFormat::JSON,
+            deltas: Arc::new(std::sync::Mutex::new(DeltaEncoder::new())),
+            task_board: None,
+            board_views: Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+            outgoing,
+        };
+        let through = client
+            .replay(ResumeRequest {
+                request_id: Some("r1".to_string()),
+                seq: 1,
+            })
+            .await;
+        assert_eq!(through, Some(4));
+
+        let mut texts = Vec::new();
+        while let Ok(Message::Text(text)) = received.try_recv() {
+            texts.push(serde_json::from_str::<serde_json::Value>(&text).unwrap());
+        }
+        assert_eq!(texts.len(), 3);
+        assert_eq!(texts[0]["seq"], 2);
+        assert_eq!(texts[1]["seq"], 3);
+        assert_eq!(texts[2]["request_id"], "r1");
+        assert_eq!(texts[2]["data"]["replayed"], 2);
+        assert_eq!(texts[2]["data"]["truncated"], false);
+    }
+
+    #[tokio::test]
+    async fn test_acknowledged_client_receives_deltas() {
+        let (state, _metrics_tx) = DashboardState::new(DashboardConfig::default());
+        let (outgoing, mut received) = tokio::sync::mpsc::channel(16);
+        let client = ClientOutlet {
+            client_id: "client-1".to_string(),
+            state: Arc::new(state),
+            focus: None,
+            format: StreamFormat::JSON,
+            deltas: Arc::new(std::sync::Mutex::new(DeltaEncoder::new())),
+            task_board: None,
+            board_views: Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+            outgoing,
+        };
+        let update =
+            DashboardMessage::MetricsUpdate(Box::new(MetricsUpdate::full(FrameworkMetrics::new())));
+        let mut next_payload = || match received.try_recv() {
+            Ok(Message::Text(text)) => {
+                serde_json::from_str::<serde_json::Value>(&text).unwrap()["payload"].clone()
+            }
+            other => panic!("Expected a text frame, got {other:?}"),
+        };
+
+        assert!(client.deliver(Some(1), &update).await);
+        let full = next_payload();
+        assert_eq!(full["type"], "MetricsUpdate");
+        assert_eq!(full["data"]["version"], 1);
+
+        assert!(client.lock_deltas().acknowledge(1));
+        assert!(client.deliver(Some(2), &update).await);
+        let delta = next_payload();
+        assert_eq!(delta["type"], "MetricsDelta");
+        assert_eq!(delta["data"]["base"], 1);
+        assert_eq!(delta["data"]["version"], 2);
+    }
 }
@@ -147,6 +147,23 @@ impl UserError {
         }
     }
 
+    pub fn room_rename_failed(room_name: &str, new_name: &str, reason: &str) -> Self {
+        Self {
+            error_type: ErrorType::RoomError,
+            message_jp: format!(
+                "Room '{room_name}' を '{new_name}' に名前変更できませんでした: {reason}"
+            ),
+            message_en: format!("Failed to rename room '{room_name}' to '{new_name}': {reason}"),
+            guidance: "Room一覧を確認し、未使用の名前を指定してください".to_string(),
+            recovery_actions: vec![RecoveryAction {
+                description: "利用可能なRoom一覧を表示 (Ctrl+Shift+W)".to_string(),
+                command: None,
+                automatic: false,
+            }],
+            error_code: "ROOM_004".to_string(),
+        }
+    }
+
     pub fn claude_code_startup_failed(reason: &str) -> Self {
         Self {
             error_type: ErrorType::ProcessError,
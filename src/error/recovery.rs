@@ -473,6 +473,7 @@ mod tests {
         let config = crate::process::ProcessConfig {
             claude_code_binary: "claude-code".to_string(),
             max_processes: 10,
+            max_processes_per_workspace: None,
             health_check_interval_secs: 30,
             restart_delay_secs: 5,
             max_restart_attempts: 3,
@@ -480,6 +481,9 @@ mod tests {
             default_restart_policy: RestartPolicy::OnFailure,
             environment_vars: std::collections::HashMap::new(),
             working_directory: None,
+            watchdog: Default::default(),
+            circuit_breaker: Default::default(),
+            secrets: Default::default(),
         };
         let (process_manager, _receiver) = crate::process::manager::ProcessManager::new(config);
         manager.set_process_manager(Arc::new(process_manager));
@@ -0,0 +1,565 @@
+// WezTerm Multi-Process Development Framework - Federation
+// Peer daemons on other machines: proxies workspace, task and process
+// operations to them and polls their status into the local dashboard
+
+use crate::client::IpcClient;
+use crate::dashboard::DashboardMessage;
+use crate::ipc::{ErrorCode, IpcError};
+use crate::metrics::protocol::escape_label;
+use crate::{Message, SystemStatusInfo};
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+use std::time::Duration;
+use tokio::sync::{broadcast, RwLock};
+
+/// Seconds between status polls of the peers
+pub const DEFAULT_POLL_INTERVAL_SECS: u64 = 15;
+
+/// Time a peer gets to answer a poll or a forwarded request
+pub const PEER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Peer daemons to federate with
+///
+/// A peer is reached through its IPC socket, which for a remote machine is
+/// forwarded over SSH:
+///
+/// ```yaml
+/// # ssh -N -L /tmp/build-server.sock:/tmp/wezterm-parallel.sock build-server
+/// federation:
+///   poll_interval_secs: 15
+///   peers:
+///     - name: build-server
+///       address: /tmp/build-server.sock
+///       token: "..."
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FederationConfig {
+    pub peers: Vec<PeerConfig>,
+    pub poll_interval_secs: u64,
+}
+
+impl Default for FederationConfig {
+    fn default() -> Self {
+        Self {
+            peers: Vec::new(),
+            poll_interval_secs: DEFAULT_POLL_INTERVAL_SECS,
+        }
+    }
+}
+
+/// How to reach one peer daemon
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeerConfig {
+    /// Origin label of the peer's workspaces and metrics
+    pub name: String,
+
+    /// Path of the peer's IPC socket, optionally prefixed with `unix:`
+    pub address: String,
+
+    /// API token the peer accepts (`auth.tokens` on the peer)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+// The token stays out of logs
+impl fmt::Debug for PeerConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PeerConfig")
+            .field("name", &self.name)
+            .field("address", &self.address)
+            .field("token", &self.token.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+impl PeerConfig {
+    pub fn new(name: impl Into<String>, address: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            address: address.into(),
+            token: None,
+        }
+    }
+
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    pub fn socket_path(&self) -> &str {
+        self.address.strip_prefix("unix:").unwrap_or(&self.address)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Peer name cannot be empty".to_string());
+        }
+        if !self
+            .name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        {
+            return Err(format!(
+                "Peer name '{}' may only contain letters, digits, '-', '_' and '.'",
+                self.name
+            ));
+        }
+        if self.socket_path().is_empty() {
+            return Err(format!("Peer '{}' needs an address", self.name));
+        }
+        if self.token.as_deref().is_some_and(|t| t.trim().is_empty()) {
+            return Err(format!(
+                "API token for peer '{}' cannot be empty",
+                self.name
+            ));
+        }
+        Ok(())
+    }
+
+    fn client(&self) -> IpcClient {
+        let client = IpcClient::new(self.socket_path()).with_timeout(PEER_TIMEOUT);
+        match &self.token {
+            Some(token) => client.with_token(token.clone()),
+            None => client,
+        }
+    }
+}
+
+/// Last known state of a peer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeerStatus {
+    pub name: String,
+    pub address: String,
+    pub reachable: bool,
+
+    /// When the peer last answered a poll
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_seen: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+
+    /// The peer's own status from its last answer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<SystemStatusInfo>,
+}
+
+impl PeerStatus {
+    fn unknown(config: &PeerConfig) -> Self {
+        Self {
+            name: config.name.clone(),
+            address: config.address.clone(),
+            reachable: false,
+            last_seen: None,
+            last_error: None,
+            status: None,
+        }
+    }
+}
+
+/// Operations that may be proxied to a peer
+pub fn is_forwardable(message: &Message) -> bool {
+    let operation = crate::auth::operation_name(message);
+    ["Workspace", "Process", "Task"]
+        .iter()
+        .any(|family| operation.starts_with(family))
+        && !operation.ends_with("Response")
+}
+
+/// Metric name, help text and value of a peer gauge
+type PeerGauge = (&'static str, &'static str, fn(&SystemStatusInfo) -> u64);
+
+#[derive(Debug)]
+struct Peer {
+    config: PeerConfig,
+    status: PeerStatus,
+}
+
+/// Registered peers and what they last reported
+///
+/// Each poll is announced to dashboard clients as a `PeerStatus` message
+/// labelled with the peer's name, and `/metrics` exports the peer gauges with
+/// an `origin` label.
+#[derive(Debug)]
+pub struct Federation {
+    peers: RwLock<BTreeMap<String, Peer>>,
+    poll_interval: Duration,
+    dashboard_tx: Option<broadcast::Sender<DashboardMessage>>,
+}
+
+impl Federation {
+    /// Federation with the configured peers; invalid peers are skipped
+    pub fn new(config: &FederationConfig) -> Self {
+        let peers = config
+            .peers
+            .iter()
+            .filter(|peer| match peer.validate() {
+                Ok(()) => true,
+                Err(e) => {
+                    tracing::warn!("Ignoring federation peer: {}", e);
+                    false
+                }
+            })
+            .map(|peer| {
+                let status = PeerStatus::unknown(peer);
+                (
+                    peer.name.clone(),
+                    Peer {
+                        config: peer.clone(),
+                        status,
+                    },
+                )
+            })
+            .collect();
+        Self {
+            peers: RwLock::new(peers),
+            poll_interval: Duration::from_secs(config.poll_interval_secs.max(1)),
+            dashboard_tx: None,
+        }
+    }
+
+    /// Announce peer status to dashboard clients
+    pub fn with_dashboard_broadcaster(mut self, tx: broadcast::Sender<DashboardMessage>) -> Self {
+        self.dashboard_tx = Some(tx);
+        self
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        self.poll_interval
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.peers.read().await.is_empty()
+    }
+
+    /// Add a peer, or replace the address and token of a known one, and poll it
+    pub async fn register(&self, config: PeerConfig) -> Result<PeerStatus, String> {
+        config.validate()?;
+        let name = config.name.clone();
+        let status = PeerStatus::unknown(&config);
+        self.peers
+            .write()
+            .await
+            .insert(name.clone(), Peer { config, status });
+        self.poll_peer(&name).await;
+        self.peer(&name)
+            .await
+            .ok_or_else(|| format!("Peer '{name}' was removed"))
+    }
+
+    /// Forget a peer; false when it was not registered
+    pub async fn remove(&self, name: &str) -> bool {
+        self.peers.write().await.remove(name).is_some()
+    }
+
+    pub async fn peer(&self, name: &str) -> Option<PeerStatus> {
+        self.peers
+            .read()
+            .await
+            .get(name)
+            .map(|peer| peer.status.clone())
+    }
+
+    /// All peers by name
+    pub async fn peers(&self) -> Vec<PeerStatus> {
+        self.peers
+            .read()
+            .await
+            .values()
+            .map(|peer| peer.status.clone())
+            .collect()
+    }
+
+    /// Send a workspace, task or process operation to a peer and return its
+    /// reply; `confirm` answers the peer's confirmation challenge
+    pub async fn forward(
+        &self,
+        name: &str,
+        message: Message,
+        confirm: Option<String>,
+    ) -> Result<Message, IpcError> {
+        if !is_forwardable(&message) {
+            return Err(IpcError::new(
+                ErrorCode::InvalidRequest,
+                "federation",
+                format!(
+                    "{} cannot be forwarded to a peer",
+                    crate::auth::operation_name(&message)
+                ),
+            ));
+        }
+        let config = match self.peers.read().await.get(name) {
+            Some(peer) => peer.config.clone(),
+            None => {
+                return Err(IpcError::new(
+                    ErrorCode::NotFound,
+                    "federation",
+                    format!("Peer '{name}' is not registered"),
+                ))
+            }
+        };
+
+        let client = config.client();
+        let result = match confirm {
+            Some(token) => client.call_confirmed(message, token).await,
+            None => client.call(message).await,
+        };
+        // Errors are labelled with their origin; the peer's own component
+        // stays after the slash
+        result.map_err(|mut error| {
+            error.component = format!("{name}/{}", error.component);
+            error
+        })
+    }
+
+    /// Ask every peer for its status, all at once so one slow peer does not
+    /// hold up the others
+    pub async fn poll(&self) {
+        let names: Vec<String> = self.peers.read().await.keys().cloned().collect();
+        join_all(names.iter().map(|name| self.poll_peer(name))).await;
+    }
+
+    async fn poll_peer(&self, name: &str) {
+        let Some(config) = self
+            .peers
+            .read()
+            .await
+            .get(name)
+            .map(|peer| peer.config.clone())
+        else {
+            return;
+        };
+        let result = config.client().call(Message::SystemStatus).await;
+
+        let status = {
+            let mut peers = self.peers.write().await;
+            // Removed or re-registered while the poll was in flight
+            let Some(peer) = peers.get_mut(name).filter(|peer| peer.config == config) else {
+                return;
+            };
+            match result {
+                Ok(Message::SystemStatusResponse { status }) => {
+                    if !peer.status.reachable {
+                        tracing::info!("Federation peer {} is reachable", name);
+                    }
+                    peer.status.reachable = true;
+                    peer.status.last_seen = Some(crate::clock::unix_secs());
+                    peer.status.last_error = None;
+                    peer.status.status = Some(status);
+                }
+                Ok(other) => {
+                    peer.status.reachable = false;
+                    peer.status.last_error = Some(format!(
+                        "Unexpected response: {}",
+                        crate::auth::operation_name(&other)
+                    ));
+                }
+                Err(error) => {
+                    if peer.status.reachable {
+                        tracing::warn!("Federation peer {} is unreachable: {}", name, error);
+                    }
+                    peer.status.reachable = false;
+                    peer.status.last_error = Some(error.message);
+                }
+            }
+            peer.status.clone()
+        };
+
+        if let Some(tx) = &self.dashboard_tx {
+            let _ = tx.send(DashboardMessage::PeerStatus {
+                peer: status,
+                timestamp: crate::clock::unix_secs(),
+            });
+        }
+    }
+
+    /// Peer gauges in the Prometheus text format, labelled by origin
+    pub async fn to_prometheus(&self) -> String {
+        let peers = self.peers().await;
+        let mut out = String::new();
+        let gauges: [PeerGauge; 5] = [
+            ("workspaces", "Workspaces on the peer", |s| {
+                s.workspace_count as u64
+            }),
+            ("processes", "Processes on the peer", |s| {
+                s.process_count as u64
+            }),
+            ("active_tasks", "Tasks running on the peer", |s| {
+                s.active_tasks as u64
+            }),
+            ("queued_tasks", "Tasks queued on the peer", |s| {
+                s.queued_tasks as u64
+            }),
+            ("failed_tasks", "Tasks failed on the peer", |s| {
+                s.failed_tasks
+            }),
+        ];
+
+        let _ = writeln!(
+            out,
+            "# HELP wezterm_parallel_peer_up Whether the peer answered its last poll"
+        );
+        let _ = writeln!(out, "# TYPE wezterm_parallel_peer_up gauge");
+        for peer in &peers {
+            let _ = writeln!(
+                out,
+                "wezterm_parallel_peer_up{{origin=\"{}\"}} {}",
+                escape_label(&peer.name),
+                u8::from(peer.reachable)
+            );
+        }
+        for (metric, help, value) in gauges {
+            let _ = writeln!(out, "# HELP wezterm_parallel_peer_{metric} {help}");
+            let _ = writeln!(out, "# TYPE wezterm_parallel_peer_{metric} gauge");
+            for peer in &peers {
+                if let Some(status) = &peer.status {
+                    let _ = writeln!(
+                        out,
+                        "wezterm_parallel_peer_{metric}{{origin=\"{}\"}} {}",
+                        escape_label(&peer.name),
+                        value(status)
+                    );
+                }
+            }
+        }
+        out
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::ipc::{Request, Response};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::UnixListener;
+
+    fn peer_status() -> SystemStatusInfo {
+        SystemStatusInfo {
+            version: "0.3.0".to_string(),
+            uptime_secs: 60,
+            active_workspace: Some("build".to_string()),
+            workspace_count: 2,
+            process_count: 3,
+            active_tasks: 1,
+            queued_tasks: 4,
+            completed_tasks: 10,
+            failed_tasks: 0,
+            maintenance: false,
+        }
+    }
+
+    /// Peer answering status polls and workspace listings, checking the token
+    fn spawn_peer(listener: UnixListener) {
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buffer = vec![0u8; 64 * 1024];
+                let n = stream.read(&mut buffer).await.unwrap();
+                let request: Request = serde_json::from_slice(&buffer[..n]).unwrap();
+                let result = match (request.token.as_deref(), request.message) {
+                    (Some("peer-token"), Message::SystemStatus) => {
+                        Ok(Message::SystemStatusResponse {
+                            status: peer_status(),
+                        })
+                    }
+                    (Some("peer-token"), Message::WorkspaceList) => {
+                        Ok(Message::WorkspaceListResponse {
+                            workspaces: Vec::new(),
+                        })
+                    }
+                    (Some("peer-token"), _) => Err(IpcError::new(
+                        ErrorCode::NotFound,
+                        "workspace_manager",
+                        "missing",
+                    )),
+                    _ => Err(IpcError::new(ErrorCode::Unauthorized, "auth", "no token")),
+                };
+                let response = Response::new(request.request_id, result);
+                let _ = stream
+                    .write_all(&serde_json::to_vec(&response).unwrap())
+                    .await;
+            }
+        });
+    }
+
+    #[tokio::test]
+    async fn test_register_poll_and_forward() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let socket_path = dir.path().join("peer.sock");
+        spawn_peer(UnixListener::bind(&socket_path).unwrap());
+
+        let (tx, mut rx) = broadcast::channel(16);
+        let federation =
+            Federation::new(&FederationConfig::default()).with_dashboard_broadcaster(tx);
+        let address = format!("unix:{}", socket_path.display());
+        let status = federation
+            .register(PeerConfig::new("build", &address).with_token("peer-token"))
+            .await
+            .unwrap();
+        assert!(status.reachable);
+        assert_eq!(status.status.unwrap().queued_tasks, 4);
+        match rx.recv().await.unwrap() {
+            DashboardMessage::PeerStatus { peer, .. } => assert_eq!(peer.name, "build"),
+            other => panic!("unexpected {other:?}"),
+        }
+
+        let reply = federation
+            .forward("build", Message::WorkspaceList, None)
+            .await
+            .unwrap();
+        assert!(matches!(reply, Message::WorkspaceListResponse { .. }));
+        let error = federation
+            .forward("build", Message::WorkspaceDelete { name: "x".into() }, None)
+            .await
+            .unwrap_err();
+        assert_eq!(error.component, "build/workspace_manager");
+        let error = federation
+            .forward("build", Message::Ping, None)
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidRequest);
+        let error = federation
+            .forward("other", Message::WorkspaceList, None)
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, ErrorCode::NotFound);
+
+        let metrics = federation.to_prometheus().await;
+        assert!(metrics.contains("wezterm_parallel_peer_up{origin=\"build\"} 1"));
+        assert!(metrics.contains("wezterm_parallel_peer_queued_tasks{origin=\"build\"} 4"));
+
+        assert!(federation.remove("build").await);
+        assert!(federation.is_empty().await);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_and_invalid_peers() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = FederationConfig {
+            peers: vec![
+                PeerConfig::new("gone", dir.path().join("gone.sock").display().to_string()),
+                PeerConfig::new("bad name", "/tmp/x.sock"),
+            ],
+            ..Default::default()
+        };
+        let federation = Federation::new(&config);
+        federation.poll().await;
+
+        let peers = federation.peers().await;
+        assert_eq!(peers.len(), 1);
+        assert!(!peers[0].reachable);
+        assert!(peers[0]
+            .last_error
+            .as_deref()
+            .unwrap()
+            .contains("Failed to connect"));
+        let metrics = federation.to_prometheus().await;
+        assert!(metrics.contains("wezterm_parallel_peer_up{origin=\"gone\"} 0"));
+        assert!(!metrics.contains("peer_workspaces{"));
+
+        let debug = format!("{:?}", PeerConfig::new("p", "/s").with_token("secret"));
+        assert!(!debug.contains("secret"));
+    }
+}
@@ -0,0 +1,440 @@
+// WezTerm Multi-Process Development Framework - Focus Mode
+// Silences non-critical notifications, defers low-priority work and tracks focus sessions
+
+use crate::dashboard::{AlertSeverity, DashboardMessage, StatusChange};
+use crate::logging::LogContext;
+use crate::room::state::ProcessStatus;
+use crate::room::WorkspaceManager;
+use crate::task::types::TaskPriority;
+use crate::task::TaskManager;
+use crate::{log_info, log_warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::RwLock;
+
+/// Component name used in status announcements
+pub const FOCUS_COMPONENT: &str = "focus";
+
+/// Tag attached to the tracking session of a focus period
+pub const FOCUS_TAG: &str = "focus";
+
+/// Focus mode configuration
+///
+/// ```yaml
+/// focus:
+///   defer_below: High
+///   pause_processes:
+///     - indexer
+///     - "--priority=low"
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FocusConfig {
+    /// Queued tasks below this priority wait until focus mode ends
+    pub defer_below: TaskPriority,
+
+    /// Processes suspended while focusing, matched by process ID or a
+    /// substring of the command (empty = none)
+    pub pause_processes: Vec<String>,
+}
+
+impl Default for FocusConfig {
+    fn default() -> Self {
+        Self {
+            defer_below: TaskPriority::High,
+            pause_processes: Vec::new(),
+        }
+    }
+}
+
+/// Focus state as exposed to clients
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FocusStatus {
+    /// Focus mode enabled
+    pub enabled: bool,
+
+    /// What the user is focusing on
+    pub label: Option<String>,
+
+    /// Time focus mode was enabled
+    pub since: Option<u64>,
+
+    /// Task ID of the focus session in the task tracker
+    pub session_id: Option<String>,
+
+    /// Queued tasks below this priority are held back
+    pub defer_below: Option<TaskPriority>,
+
+    /// Queued tasks currently held back
+    pub deferred_tasks: usize,
+
+    /// Processes suspended for the focus period
+    pub paused_processes: Vec<String>,
+
+    /// Info and warning alerts withheld from dashboard clients
+    pub suppressed_notifications: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+struct FocusState {
+    label: Option<String>,
+    since: Option<u64>,
+    session_id: Option<String>,
+    paused: Vec<PausedProcess>,
+}
+
+#[derive(Debug, Clone)]
+struct PausedProcess {
+    id: String,
+    pid: u32,
+}
+
+/// Focus mode controller shared by the IPC and dashboard servers
+pub struct FocusController {
+    active: AtomicBool,
+    state: RwLock<FocusState>,
+    suppressed: Mutex<HashSet<String>>,
+    config: FocusConfig,
+    task_manager: Arc<TaskManager>,
+    workspace_manager: Arc<WorkspaceManager>,
+    broadcast_tx: Option<tokio::sync::broadcast::Sender<DashboardMessage>>,
+}
+
+impl FocusController {
+    /// Create a controller with focus mode disabled
+    pub fn new(
+        task_manager: Arc<TaskManager>,
+        workspace_manager: Arc<WorkspaceManager>,
+        config: FocusConfig,
+    ) -> Self {
+        Self {
+            active: AtomicBool::new(false),
+            state: RwLock::new(FocusState::default()),
+            suppressed: Mutex::new(HashSet::new()),
+            config,
+            task_manager,
+            workspace_manager,
+            broadcast_tx: None,
+        }
+    }
+
+    /// Announce state changes on the dashboard broadcast channel
+    pub fn with_broadcaster(
+        mut self,
+        broadcast_tx: tokio::sync::broadcast::Sender<DashboardMessage>,
+    ) -> Self {
+        self.broadcast_tx = Some(broadcast_tx);
+        self
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::SeqCst)
+    }
+
+    /// Enable focus mode; returns false if it was already enabled
+    pub async fn enable(&self, label: Option<String>) -> bool {
+        let mut state = self.state.write().await;
+        if self.is_active() {
+            return false;
+        }
+
+        let since = now_secs();
+        let session_id = format!("focus-{since}");
+
+        self.task_manager
+            .set_defer_below(Some(self.config.defer_below.clone()))
+            .await;
+
+        let tracker = self.task_manager.get_tracker();
+        tracker.set_task_category(&session_id, "Focus").await;
+        tracker.start_task(&session_id).await;
+        tracker
+            .tag_session(&session_id, None, &[FOCUS_TAG.to_string()])
+            .await;
+        if let Some(ref label) = label {
+            tracker.annotate_session(&session_id, None, label).await;
+        }
+
+        let paused = self.pause_processes().await;
+
+        *state = FocusState {
+            label: label.clone(),
+            since: Some(since),
+            session_id: Some(session_id.clone()),
+            paused,
+        };
+        self.suppressed.lock().unwrap().clear();
+        self.active.store(true, Ordering::SeqCst);
+
+        let context = LogContext::new("system", "focus_enabled")
+            .with_entity_id(&session_id)
+            .with_metadata("label", serde_json::json!(label))
+            .with_metadata("paused_processes", serde_json::json!(state.paused.len()));
+        log_info!(context, "Focus mode enabled");
+
+        self.announce("normal", "focus", label);
+        true
+    }
+
+    /// Disable focus mode; returns false if it was not enabled
+    pub async fn disable(&self) -> bool {
+        let mut state = self.state.write().await;
+        if !self.is_active() {
+            return false;
+        }
+        self.active.store(false, Ordering::SeqCst);
+        let previous = std::mem::take(&mut *state);
+
+        for process in &previous.paused {
+            if let Err(e) = signal_process(process.pid, "CONT").await {
+                let context =
+                    LogContext::new("system", "focus_resume_failed").with_entity_id(&process.id);
+                log_warn!(context, "Failed to resume process {}: {}", process.id, e);
+            }
+        }
+
+        self.task_manager.set_defer_below(None).await;
+        let focused = match previous.session_id {
+            Some(ref session_id) => self.task_manager.get_tracker().stop_task(session_id).await,
+            None => None,
+        };
+
+        let suppressed = self.suppressed.lock().unwrap().len();
+        let context = LogContext::new("system", "focus_disabled")
+            .with_metadata(
+                "focused_secs",
+                serde_json::json!(focused.map(|d| d.as_secs())),
+            )
+            .with_metadata("suppressed_notifications", serde_json::json!(suppressed));
+        log_info!(context, "Focus mode disabled");
+
+        self.announce(
+            "focus",
+            "normal",
+            Some(format!("{suppressed} notifications suppressed")),
+        );
+        true
+    }
+
+    /// Whether a dashboard message should reach clients
+    ///
+    /// While focusing, info and warning alerts are withheld; critical alerts,
+    /// resolutions and all other updates pass through.
+    pub fn should_deliver(&self, message: &DashboardMessage) -> bool {
+        if !self.is_active() {
+            return true;
+        }
+        match message {
+            DashboardMessage::Alert(alert)
+                if matches!(alert.severity, AlertSeverity::Info | AlertSeverity::Warning) =>
+            {
+                self.suppressed.lock().unwrap().insert(alert.id.clone());
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// Current state with the number of deferred tasks
+    pub async fn status(&self) -> FocusStatus {
+        let state = self.state.read().await.clone();
+        let defer_below = self.task_manager.defer_below().await;
+        let deferred_tasks = match defer_below {
+            Some(ref min_priority) => self
+                .task_manager
+                .get_queue()
+                .list_tasks()
+                .await
+                .iter()
+                .filter(|task| task.priority < *min_priority)
+                .count(),
+            None => 0,
+        };
+
+        FocusStatus {
+            enabled: self.is_active(),
+            label: state.label,
+            since: state.since,
+            session_id: state.session_id,
+            defer_below,
+            deferred_tasks,
+            paused_processes: state.paused.into_iter().map(|p| p.id).collect(),
+            suppressed_notifications: self.suppressed.lock().unwrap().len(),
+        }
+    }
+
+    /// Suspend running processes matching `pause_processes`
+    async fn pause_processes(&self) -> Vec<PausedProcess> {
+        if self.config.pause_processes.is_empty() {
+            return Vec::new();
+        }
+
+        let mut paused = Vec::new();
+        for workspace in self.workspace_manager.list_workspace_states().await {
+            for process in workspace.processes.into_values() {
+                let Some(pid) = process.pid else {
+                    continue;
+                };
+                let running = matches!(
+                    process.status,
+                    ProcessStatus::Running | ProcessStatus::Idle | ProcessStatus::Busy
+                );
+                let low_priority = self
+                    .config
+                    .pause_processes
+                    .iter()
+                    .any(|pattern| process.id == *pattern || process.command.contains(pattern));
+                if !running || !low_priority {
+                    continue;
+                }
+
+                match signal_process(pid, "STOP").await {
+                    Ok(()) => paused.push(PausedProcess {
+                        id: process.id,
+                        pid,
+                    }),
+                    Err(e) => {
+                        let context = LogContext::new("system", "focus_pause_failed")
+                            .with_entity_id(&process.id);
+                        log_warn!(context, "Failed to pause process {}: {}", process.id, e);
+                    }
+                }
+            }
+        }
+        paused
+    }
+
+    fn announce(&self, previous_status: &str, new_status: &str, reason: Option<String>) {
+        if let Some(ref tx) = self.broadcast_tx {
+            let _ = tx.send(DashboardMessage::StatusChange(StatusChange {
+                component: FOCUS_COMPONENT.to_string(),
+                previous_status: previous_status.to_string(),
+                new_status: new_status.to_string(),
+                reason,
+                timestamp: now_secs(),
+            }));
+        }
+    }
+}
+
+/// Send a job control signal (`STOP`/`CONT`) to a process
+async fn signal_process(pid: u32, signal: &str) -> Result<(), String> {
+    let status = tokio::process::Command::new("kill")
+        .arg(format!("-{signal}"))
+        .arg(pid.to_string())
+        .status()
+        .await
+        .map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("kill -{signal} exited with {status}"))
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dashboard::AlertNotification;
+    use crate::task::{Task, TaskCategory, TaskConfig};
+
+    fn create_controller() -> (FocusController, tempfile::TempDir) {
+        let dir = tempfile::TempDir::new().unwrap();
+        let workspace_manager =
+            Arc::new(WorkspaceManager::new(Some(dir.path().join("workspaces.json"))).unwrap());
+        let task_manager = Arc::new(TaskManager::new(TaskConfig {
+            persistence_enabled: false,
+            ..Default::default()
+        }));
+        (
+            FocusController::new(task_manager, workspace_manager, FocusConfig::default()),
+            dir,
+        )
+    }
+
+    fn alert(id: &str, severity: AlertSeverity) -> DashboardMessage {
+        DashboardMessage::Alert(AlertNotification {
+            id: id.to_string(),
+            severity,
+            category: "budget".to_string(),
+            message: "Development budget at 90%".to_string(),
+            component: None,
+            timestamp: 0,
+            details: None,
+            text: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_focus_session_is_tracked() {
+        let (controller, _dir) = create_controller();
+        let (tx, mut rx) = tokio::sync::broadcast::channel(16);
+        let controller = controller.with_broadcaster(tx);
+
+        assert!(controller.enable(Some("write chapter 3".to_string())).await);
+        assert!(!controller.enable(None).await);
+
+        let status = controller.status().await;
+        let session_id = status.session_id.clone().unwrap();
+        let tracker = controller.task_manager.get_tracker();
+        let session = tracker.get_active_session(&session_id).await.unwrap();
+        assert!(session.has_tag(FOCUS_TAG));
+
+        match rx.recv().await.unwrap() {
+            DashboardMessage::StatusChange(change) => {
+                assert_eq!(change.component, FOCUS_COMPONENT);
+                assert_eq!(change.new_status, "focus");
+            }
+            other => panic!("Unexpected message: {other:?}"),
+        }
+
+        assert!(controller.disable().await);
+        assert!(!controller.disable().await);
+        assert!(tracker.get_active_session(&session_id).await.is_none());
+        assert_eq!(tracker.get_task_history(&session_id).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_only_critical_alerts_delivered() {
+        let (controller, _dir) = create_controller();
+        assert!(controller.should_deliver(&alert("a1", AlertSeverity::Info)));
+
+        controller.enable(None).await;
+        assert!(!controller.should_deliver(&alert("a1", AlertSeverity::Info)));
+        assert!(!controller.should_deliver(&alert("a2", AlertSeverity::Warning)));
+        // The same alert withheld from several clients counts once
+        assert!(!controller.should_deliver(&alert("a2", AlertSeverity::Warning)));
+        assert!(controller.should_deliver(&alert("a3", AlertSeverity::Critical)));
+        assert_eq!(controller.status().await.suppressed_notifications, 2);
+
+        controller.disable().await;
+        assert!(controller.should_deliver(&alert("a4", AlertSeverity::Warning)));
+    }
+
+    #[tokio::test]
+    async fn test_low_priority_tasks_deferred() {
+        let (controller, _dir) = create_controller();
+        let mut task = Task::new("Reindex docs".to_string(), TaskCategory::Development);
+        task.priority = TaskPriority::Low;
+        controller.task_manager.create_task(task).await.unwrap();
+
+        controller.enable(None).await;
+        let status = controller.status().await;
+        assert_eq!(status.defer_below, Some(TaskPriority::High));
+        assert_eq!(status.deferred_tasks, 1);
+
+        controller.disable().await;
+        assert_eq!(controller.task_manager.defer_below().await, None);
+        assert_eq!(controller.status().await.deferred_tasks, 0);
+    }
+}
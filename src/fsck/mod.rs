@@ -0,0 +1,515 @@
+// WezTerm Multi-Process Development Framework - State Consistency Check
+// Validates cross-references between the persisted workspace and task state
+// and repairs the ones that can be fixed safely
+//
+// Only `workspaces.json` and `tasks.json` are persisted. The task board and
+// time tracking sessions are rebuilt from the task list at startup, so their
+// counterparts here are the persisted queue (`queued` / `executing`) and the
+// task references themselves.
+
+use crate::room::manager::PersistedState;
+use crate::room::WorkspaceManager;
+use crate::task::persistence::{PersistedTaskState, TaskPersistence};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Kind of inconsistency found in the persisted state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    /// State file exists but cannot be parsed
+    Unreadable,
+    /// Two tasks share an ID
+    DuplicateTask,
+    /// Task is assigned to a workspace that no longer exists
+    MissingWorkspace,
+    /// Queue entry points at a task that no longer exists
+    MissingTask,
+    /// Finished task is still queued or marked as executing
+    FinishedTaskQueued,
+    /// Task is listed more than once in the queue
+    DuplicateQueueEntry,
+    /// Task depends on a task that no longer exists
+    MissingDependency,
+    /// Process is recorded under a different workspace than its own
+    ProcessWorkspaceMismatch,
+    /// Pane refers to a process the workspace does not know
+    MissingPaneProcess,
+    /// More than one workspace is marked active
+    MultipleActiveWorkspaces,
+}
+
+/// One problem found by the check
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Issue {
+    pub kind: IssueKind,
+
+    /// Task ID, workspace name or file the issue was found on
+    pub entity: String,
+    pub description: String,
+
+    /// Whether `--fix` can repair it
+    pub fixable: bool,
+}
+
+/// Result of a check or repair run
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FsckReport {
+    pub workspaces_checked: usize,
+    pub tasks_checked: usize,
+    pub issues: Vec<Issue>,
+
+    /// Files rewritten by the repair
+    pub repaired_files: Vec<PathBuf>,
+}
+
+impl FsckReport {
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    pub fn fixable_count(&self) -> usize {
+        self.issues.iter().filter(|issue| issue.fixable).count()
+    }
+}
+
+/// Locations of the persisted state files
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatePaths {
+    pub workspaces: PathBuf,
+    pub tasks: PathBuf,
+}
+
+impl Default for StatePaths {
+    fn default() -> Self {
+        Self {
+            workspaces: WorkspaceManager::default_state_path(),
+            tasks: TaskPersistence::default_path(),
+        }
+    }
+}
+
+/// Report problems without touching any file
+pub fn check(paths: &StatePaths) -> FsckReport {
+    run(paths, false).unwrap_or_else(|report| report)
+}
+
+/// Report problems and rewrite the state files with the fixable ones repaired
+///
+/// The previous files are kept as `*.json.bak`.
+pub fn repair(paths: &StatePaths) -> Result<FsckReport, String> {
+    run(paths, true).map_err(|report| {
+        report
+            .issues
+            .last()
+            .map(|issue| issue.description.clone())
+            .unwrap_or_else(|| "Repair failed".to_string())
+    })
+}
+
+fn run(paths: &StatePaths, fix: bool) -> Result<FsckReport, FsckReport> {
+    let mut report = FsckReport::default();
+    let mut workspaces = read_state::<PersistedState>(&paths.workspaces, &mut report);
+    let mut tasks = read_state::<PersistedTaskState>(&paths.tasks, &mut report);
+
+    let mut issues = Vec::new();
+    if let Some(state) = workspaces.as_mut() {
+        report.workspaces_checked = state.workspaces.len();
+        check_workspaces(state, fix, &mut issues);
+    }
+    if let Some(state) = tasks.as_mut() {
+        report.tasks_checked = state.tasks.len();
+        let known = workspaces
+            .as_ref()
+            .map(|state| state.workspaces.keys().cloned().collect::<HashSet<_>>());
+        check_tasks(state, known.as_ref(), fix, &mut issues);
+    }
+
+    let workspaces_changed = issues
+        .iter()
+        .any(|issue| issue.fixable && is_workspace_issue(issue.kind));
+    let tasks_changed = issues
+        .iter()
+        .any(|issue| issue.fixable && !is_workspace_issue(issue.kind));
+    report.issues.extend(issues);
+    if !fix {
+        return Ok(report);
+    }
+
+    if let (true, Some(state)) = (workspaces_changed, workspaces.as_ref()) {
+        if let Err(e) = write_with_backup(&paths.workspaces, state) {
+            report.issues.push(write_failure(&paths.workspaces, e));
+            return Err(report);
+        }
+        report.repaired_files.push(paths.workspaces.clone());
+    }
+    if let (true, Some(state)) = (tasks_changed, tasks.as_ref()) {
+        if let Err(e) = TaskPersistence::new(paths.tasks.clone()).save(state) {
+            report
+                .issues
+                .push(write_failure(&paths.tasks, e.to_string()));
+            return Err(report);
+        }
+        report.repaired_files.push(paths.tasks.clone());
+    }
+    Ok(report)
+}
+
+fn is_workspace_issue(kind: IssueKind) -> bool {
+    matches!(
+        kind,
+        IssueKind::ProcessWorkspaceMismatch
+            | IssueKind::MissingPaneProcess
+            | IssueKind::MultipleActiveWorkspaces
+    )
+}
+
+fn read_state<T: serde::de::DeserializeOwned>(path: &Path, report: &mut FsckReport) -> Option<T> {
+    if !path.exists() {
+        return None;
+    }
+    let parsed = fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()));
+    match parsed {
+        Ok(state) => Some(state),
+        Err(e) => {
+            report.issues.push(Issue {
+                kind: IssueKind::Unreadable,
+                entity: path.display().to_string(),
+                description: format!("Cannot read state file: {e}"),
+                fixable: false,
+            });
+            None
+        }
+    }
+}
+
+fn write_with_backup(path: &Path, state: &PersistedState) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    fs::copy(path, path.with_extension("json.bak")).map_err(|e| e.to_string())?;
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, json).map_err(|e| e.to_string())?;
+    fs::rename(&temp_path, path).map_err(|e| e.to_string())
+}
+
+fn write_failure(path: &Path, error: String) -> Issue {
+    Issue {
+        kind: IssueKind::Unreadable,
+        entity: path.display().to_string(),
+        description: format!("Failed to write repaired state: {error}"),
+        fixable: false,
+    }
+}
+
+fn check_workspaces(state: &mut PersistedState, fix: bool, issues: &mut Vec<Issue>) {
+    let mut names: Vec<String> = state.workspaces.keys().cloned().collect();
+    names.sort();
+
+    for name in &names {
+        let Some(workspace) = state.workspaces.get_mut(name) else {
+            continue;
+        };
+        for process in workspace.processes.values_mut() {
+            if process.workspace != *name {
+                issues.push(Issue {
+                    kind: IssueKind::ProcessWorkspaceMismatch,
+                    entity: name.clone(),
+                    description: format!(
+                        "Process '{}' is recorded under workspace '{}'",
+                        process.id, process.workspace
+                    ),
+                    fixable: true,
+                });
+                if fix {
+                    process.workspace = name.clone();
+                }
+            }
+        }
+        let processes = &workspace.processes;
+        for pane in &mut workspace.panes {
+            let Some(process_id) = pane.process_id.as_ref() else {
+                continue;
+            };
+            if !processes.contains_key(process_id) {
+                issues.push(Issue {
+                    kind: IssueKind::MissingPaneProcess,
+                    entity: name.clone(),
+                    description: format!(
+                        "Pane '{}' refers to unknown process '{}'",
+                        pane.id, process_id
+                    ),
+                    fixable: true,
+                });
+                if fix {
+                    pane.process_id = None;
+                }
+            }
+        }
+    }
+
+    // Keep the most recently used workspace active
+    let mut active: Vec<&String> = names
+        .iter()
+        .filter(|name| state.workspaces[*name].is_active)
+        .collect();
+    if active.len() > 1 {
+        active.sort_by_key(|name| std::cmp::Reverse(state.workspaces[*name].last_accessed));
+        let keep = active[0].clone();
+        issues.push(Issue {
+            kind: IssueKind::MultipleActiveWorkspaces,
+            entity: keep.clone(),
+            description: format!(
+                "{} workspaces are marked active; keeping '{}'",
+                active.len(),
+                keep
+            ),
+            fixable: true,
+        });
+        if fix {
+            for (name, workspace) in state.workspaces.iter_mut() {
+                workspace.is_active = *name == keep;
+            }
+        }
+    }
+}
+
+fn check_tasks(
+    state: &mut PersistedTaskState,
+    workspaces: Option<&HashSet<String>>,
+    fix: bool,
+    issues: &mut Vec<Issue>,
+) {
+    // Later duplicates are dropped, the first copy wins
+    let mut seen = HashSet::new();
+    let mut duplicates = Vec::new();
+    for (index, task) in state.tasks.iter().enumerate() {
+        if !seen.insert(task.id.clone()) {
+            issues.push(Issue {
+                kind: IssueKind::DuplicateTask,
+                entity: task.id.clone(),
+                description: format!("Task ID is used by more than one task ('{}')", task.title),
+                fixable: true,
+            });
+            duplicates.push(index);
+        }
+    }
+    if fix {
+        for index in duplicates.into_iter().rev() {
+            state.tasks.remove(index);
+        }
+    }
+
+    for task in &mut state.tasks {
+        if let (Some(workspace), Some(known)) = (task.workspace.as_ref(), workspaces) {
+            if !known.contains(workspace) {
+                issues.push(Issue {
+                    kind: IssueKind::MissingWorkspace,
+                    entity: task.id.clone(),
+                    description: format!("Task refers to missing workspace '{workspace}'"),
+                    fixable: true,
+                });
+                if fix {
+                    task.workspace = None;
+                }
+            }
+        }
+        let missing: Vec<String> = task
+            .dependencies
+            .iter()
+            .filter(|dependency| !seen.contains(*dependency))
+            .cloned()
+            .collect();
+        for dependency in &missing {
+            issues.push(Issue {
+                kind: IssueKind::MissingDependency,
+                entity: task.id.clone(),
+                description: format!("Task depends on missing task '{dependency}'"),
+                fixable: true,
+            });
+        }
+        if fix {
+            task.dependencies
+                .retain(|dependency| seen.contains(dependency));
+        }
+    }
+
+    let finished: HashSet<String> = state
+        .tasks
+        .iter()
+        .filter(|task| task.is_finished())
+        .map(|task| task.id.clone())
+        .collect();
+    for (list, ids) in [
+        ("queue", &mut state.queued),
+        ("executing list", &mut state.executing),
+    ] {
+        let mut listed = HashSet::new();
+        ids.retain(|id| {
+            let issue = if !seen.contains(id) {
+                Some((
+                    IssueKind::MissingTask,
+                    format!("The {list} refers to a task that no longer exists"),
+                ))
+            } else if finished.contains(id) {
+                Some((
+                    IssueKind::FinishedTaskQueued,
+                    format!("Finished task is still in the {list}"),
+                ))
+            } else if !listed.insert(id.clone()) {
+                Some((
+                    IssueKind::DuplicateQueueEntry,
+                    format!("Task appears more than once in the {list}"),
+                ))
+            } else {
+                None
+            };
+            let Some((kind, description)) = issue else {
+                return true;
+            };
+            issues.push(Issue {
+                kind,
+                entity: id.clone(),
+                description,
+                fixable: true,
+            });
+            !fix
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::room::state::WorkspaceState;
+    use crate::room::template::TemplateEngine;
+    use crate::task::types::{Task, TaskCategory, TaskStatus};
+    use std::collections::HashMap;
+    use std::time::{Duration, SystemTime};
+
+    fn task(id: &str, workspace: Option<&str>) -> Task {
+        let mut task = Task::new(format!("Task {id}"), TaskCategory::Development);
+        task.id = id.to_string();
+        task.workspace = workspace.map(str::to_string);
+        task
+    }
+
+    fn workspace(name: &str, active: bool, accessed_secs: u64) -> WorkspaceState {
+        let config = TemplateEngine::new().apply_template("basic", name).unwrap();
+        let mut state = WorkspaceState::new(name.to_string(), config);
+        state.is_active = active;
+        state.last_accessed = SystemTime::UNIX_EPOCH + Duration::from_secs(accessed_secs);
+        state
+    }
+
+    fn write_state(dir: &Path) -> StatePaths {
+        let paths = StatePaths {
+            workspaces: dir.join("workspaces.json"),
+            tasks: dir.join("tasks.json"),
+        };
+        let workspaces = PersistedState {
+            workspaces: HashMap::from([
+                ("default".to_string(), workspace("default", true, 100)),
+                ("api".to_string(), workspace("api", true, 200)),
+            ]),
+            last_saved: SystemTime::now(),
+            version: "test".to_string(),
+        };
+        fs::write(
+            &paths.workspaces,
+            serde_json::to_string(&workspaces).unwrap(),
+        )
+        .unwrap();
+
+        let mut done = task("done", Some("api"));
+        done.status = TaskStatus::Completed;
+        let mut blocked = task("blocked", Some("deleted"));
+        blocked.dependencies = vec!["ok".to_string(), "gone".to_string()];
+        let tasks = PersistedTaskState::new(
+            vec![task("ok", Some("default")), blocked, done, task("ok", None)],
+            vec![
+                "ok".to_string(),
+                "gone".to_string(),
+                "done".to_string(),
+                "ok".to_string(),
+            ],
+            vec![],
+        );
+        TaskPersistence::new(paths.tasks.clone())
+            .save(&tasks)
+            .unwrap();
+        paths
+    }
+
+    fn kinds(report: &FsckReport) -> Vec<IssueKind> {
+        report.issues.iter().map(|issue| issue.kind).collect()
+    }
+
+    #[test]
+    fn test_check_reports_without_writing() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = write_state(dir.path());
+        let before = fs::read_to_string(&paths.tasks).unwrap();
+
+        let report = check(&paths);
+        assert_eq!(report.workspaces_checked, 2);
+        assert_eq!(report.tasks_checked, 4);
+        assert_eq!(
+            kinds(&report),
+            [
+                IssueKind::MultipleActiveWorkspaces,
+                IssueKind::DuplicateTask,
+                IssueKind::MissingWorkspace,
+                IssueKind::MissingDependency,
+                IssueKind::MissingTask,
+                IssueKind::FinishedTaskQueued,
+                IssueKind::DuplicateQueueEntry,
+            ]
+        );
+        assert_eq!(report.issues[0].entity, "api");
+        assert!(report.repaired_files.is_empty());
+        assert_eq!(fs::read_to_string(&paths.tasks).unwrap(), before);
+    }
+
+    #[test]
+    fn test_repair_fixes_references() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = write_state(dir.path());
+
+        let report = repair(&paths).unwrap();
+        assert_eq!(report.fixable_count(), 7);
+        assert_eq!(report.repaired_files.len(), 2);
+        assert!(paths.workspaces.with_extension("json.bak").exists());
+        assert!(paths.tasks.with_extension("json.bak").exists());
+
+        let tasks = TaskPersistence::new(paths.tasks.clone())
+            .load()
+            .state
+            .unwrap();
+        assert_eq!(tasks.tasks.len(), 3);
+        assert_eq!(tasks.queued, ["ok"]);
+        let blocked = tasks.tasks.iter().find(|t| t.id == "blocked").unwrap();
+        assert_eq!(blocked.workspace, None);
+        assert_eq!(blocked.dependencies, ["ok"]);
+
+        // Everything fixable is gone on the next run
+        assert!(check(&paths).is_clean());
+    }
+
+    #[test]
+    fn test_unreadable_and_missing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = StatePaths {
+            workspaces: dir.path().join("workspaces.json"),
+            tasks: dir.path().join("tasks.json"),
+        };
+        assert!(check(&paths).is_clean());
+
+        fs::write(&paths.tasks, "{ not json").unwrap();
+        let report = check(&paths);
+        assert_eq!(kinds(&report), [IssueKind::Unreadable]);
+        assert_eq!(report.fixable_count(), 0);
+    }
+}
@@ -0,0 +1,365 @@
+// WezTerm Multi-Process Development Framework - Git Integration
+// Repository and branch of workspace roots, per-workspace worktrees and status
+//
+// Everything goes through the `git` command line. A workspace created in a
+// repository records its root and branch; with `git.worktrees` enabled it
+// gets a worktree of its own on a `<branch_prefix><workspace>` branch under
+// `<repo>/.wezterm-parallel/worktrees/`, and its panes and processes run
+// there. Worktrees are left in place when the workspace is deleted.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Directory of the generated worktrees, relative to the repository root
+pub const WORKTREES_DIR: &str = ".wezterm-parallel/worktrees";
+
+/// Environment variable telling processes the root of their workspace's repository
+pub const GIT_ROOT_VAR: &str = "CLAUDE_GIT_ROOT";
+
+/// Environment variable telling processes the branch of their workspace
+pub const GIT_BRANCH_VAR: &str = "CLAUDE_GIT_BRANCH";
+
+/// Git integration settings
+///
+/// ```yaml
+/// git:
+///   worktrees: true
+///   branch_prefix: "wp/"
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GitConfig {
+    /// Give each workspace created in a repository a worktree of its own
+    pub worktrees: bool,
+
+    /// Prefix of the branch created for a workspace's worktree
+    pub branch_prefix: String,
+}
+
+impl Default for GitConfig {
+    fn default() -> Self {
+        Self {
+            worktrees: false,
+            branch_prefix: "wezterm-parallel/".to_string(),
+        }
+    }
+}
+
+/// Repository a workspace lives in, recorded when it is created
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GitInfo {
+    /// Top-level directory of the (work)tree
+    pub root: String,
+
+    /// Checked-out branch; `None` on a detached HEAD
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+
+    /// The root is a worktree created for the workspace
+    #[serde(default)]
+    pub worktree: bool,
+}
+
+/// Working tree state of a workspace's repository
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GitStatus {
+    pub workspace: String,
+    pub root: String,
+
+    /// `None` on a detached HEAD
+    pub branch: Option<String>,
+
+    /// Commit checked out; `None` before the first commit
+    pub head: Option<String>,
+    pub upstream: Option<String>,
+    pub ahead: u32,
+    pub behind: u32,
+
+    /// Files with changes in the index
+    pub staged: u32,
+
+    /// Files with changes not in the index
+    pub modified: u32,
+    pub untracked: u32,
+    pub conflicted: u32,
+
+    #[serde(default)]
+    pub worktree: bool,
+}
+
+impl GitStatus {
+    pub fn is_clean(&self) -> bool {
+        self.staged == 0 && self.modified == 0 && self.untracked == 0 && self.conflicted == 0
+    }
+
+    /// Parse `git status --porcelain=v2 --branch`
+    pub fn parse(workspace: &str, root: &str, porcelain: &str) -> Self {
+        let mut status = Self {
+            workspace: workspace.to_string(),
+            root: root.to_string(),
+            ..Self::default()
+        };
+        for line in porcelain.lines() {
+            if let Some(header) = line.strip_prefix("# ") {
+                let (key, value) = header.split_once(' ').unwrap_or((header, ""));
+                match key {
+                    "branch.oid" if value != "(initial)" => status.head = Some(value.to_string()),
+                    "branch.head" if value != "(detached)" => {
+                        status.branch = Some(value.to_string())
+                    }
+                    "branch.upstream" => status.upstream = Some(value.to_string()),
+                    "branch.ab" => {
+                        for count in value.split_whitespace() {
+                            if let Some(ahead) = count.strip_prefix('+') {
+                                status.ahead = ahead.parse().unwrap_or_default();
+                            } else if let Some(behind) = count.strip_prefix('-') {
+                                status.behind = behind.parse().unwrap_or_default();
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+
+            let mut fields = line.splitn(3, ' ');
+            match (fields.next(), fields.next()) {
+                (Some("1" | "2"), Some(xy)) => {
+                    let mut xy = xy.chars();
+                    if xy.next().is_some_and(|x| x != '.') {
+                        status.staged += 1;
+                    }
+                    if xy.next().is_some_and(|y| y != '.') {
+                        status.modified += 1;
+                    }
+                }
+                (Some("u"), _) => status.conflicted += 1,
+                (Some("?"), _) => status.untracked += 1,
+                _ => {}
+            }
+        }
+        status
+    }
+}
+
+/// Run git in `dir`, returning its trimmed standard output
+fn git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Repository and branch of `dir`; `None` outside a repository or without git
+pub fn detect(dir: &Path) -> Option<GitInfo> {
+    let root = git(dir, &["rev-parse", "--show-toplevel"]).ok()?;
+    // Fails on a detached HEAD
+    let branch = git(dir, &["symbolic-ref", "--quiet", "--short", "HEAD"]).ok();
+    Some(GitInfo {
+        root,
+        branch,
+        worktree: false,
+    })
+}
+
+/// Current state of the working tree at `info.root`
+pub fn status(workspace: &str, info: &GitInfo) -> Result<GitStatus, String> {
+    let porcelain = git(
+        Path::new(&info.root),
+        &["status", "--porcelain=v2", "--branch"],
+    )?;
+    Ok(GitStatus {
+        worktree: info.worktree,
+        ..GitStatus::parse(workspace, &info.root, &porcelain)
+    })
+}
+
+/// Directory and branch name of a workspace's worktree, limited to
+/// characters that are safe in both
+pub fn worktree_name(workspace: &str) -> String {
+    let name: String = workspace
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    name.trim_matches('.').to_string()
+}
+
+/// Create (or reuse) the worktree of `workspace` in the repository at `root`
+///
+/// The branch is created from the current HEAD unless it already exists.
+/// The worktrees directory ignores itself, so it does not show up as
+/// untracked in the main working tree.
+pub fn create_worktree(
+    root: &Path,
+    workspace: &str,
+    config: &GitConfig,
+) -> Result<GitInfo, String> {
+    let name = worktree_name(workspace);
+    if name.is_empty() {
+        return Err(format!("No worktree name for workspace '{workspace}'"));
+    }
+    let branch = format!("{}{name}", config.branch_prefix);
+    let dir = root.join(WORKTREES_DIR);
+    let path = dir.join(&name);
+    if let Some(info) = detect(&path).filter(|info| Path::new(&info.root) == path) {
+        return Ok(GitInfo {
+            worktree: true,
+            ..info
+        });
+    }
+
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    let ignore = dir.join(".gitignore");
+    if !ignore.exists() {
+        std::fs::write(&ignore, "*\n")
+            .map_err(|e| format!("Failed to write {}: {e}", ignore.display()))?;
+    }
+
+    let path_arg = path.to_string_lossy();
+    let exists = git(
+        root,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("refs/heads/{branch}"),
+        ],
+    )
+    .is_ok();
+    if exists {
+        git(root, &["worktree", "add", &path_arg, &branch])?;
+    } else {
+        git(root, &["worktree", "add", "-b", &branch, &path_arg])?;
+    }
+
+    Ok(GitInfo {
+        root: canonical(&path).to_string_lossy().to_string(),
+        branch: Some(branch),
+        worktree: true,
+    })
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Repository with one empty commit on `main`
+    pub(crate) fn init_repo(dir: &Path) -> PathBuf {
+        let repo = canonical(dir);
+        for args in [
+            &["init", "--quiet", "--initial-branch=main"][..],
+            &[
+                "-c",
+                "user.name=Test",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "--quiet",
+                "--allow-empty",
+                "-m",
+                "init",
+            ],
+        ] {
+            git(&repo, args).unwrap();
+        }
+        repo
+    }
+
+    #[test]
+    fn test_parse_porcelain_status() {
+        let status = GitStatus::parse(
+            "web",
+            "/src/web",
+            "# branch.oid 1a2b3c\n\
+             # branch.head feature/login\n\
+             # branch.upstream origin/feature/login\n\
+             # branch.ab +2 -1\n\
+             1 M. N... 100644 100644 100644 aaa bbb src/a.rs\n\
+             1 .M N... 100644 100644 100644 aaa bbb src/b.rs\n\
+             2 RM N... 100644 100644 100644 aaa bbb R100 src/c.rs\tsrc/old.rs\n\
+             u UU N... 100644 100644 100644 100644 aaa bbb ccc src/d.rs\n\
+             ? notes.txt\n",
+        );
+        assert_eq!(status.branch.as_deref(), Some("feature/login"));
+        assert_eq!(status.head.as_deref(), Some("1a2b3c"));
+        assert_eq!(status.upstream.as_deref(), Some("origin/feature/login"));
+        assert_eq!((status.ahead, status.behind), (2, 1));
+        assert_eq!(
+            (
+                status.staged,
+                status.modified,
+                status.untracked,
+                status.conflicted
+            ),
+            (2, 2, 1, 1)
+        );
+        assert!(!status.is_clean());
+
+        let fresh = GitStatus::parse(
+            "web",
+            "/src/web",
+            "# branch.oid (initial)\n# branch.head (detached)\n",
+        );
+        assert_eq!((&fresh.head, &fresh.branch), (&None, &None));
+        assert!(fresh.is_clean());
+    }
+
+    #[test]
+    fn test_worktree_per_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init_repo(dir.path());
+
+        let info = detect(&repo).unwrap();
+        assert_eq!(info.branch.as_deref(), Some("main"));
+        assert_eq!(detect(&std::env::temp_dir().join("no-such-dir")), None);
+
+        let config = GitConfig::default();
+        let worktree = create_worktree(&repo, "api server", &config).unwrap();
+        assert_eq!(
+            worktree.branch.as_deref(),
+            Some("wezterm-parallel/api-server")
+        );
+        assert_eq!(
+            Path::new(&worktree.root),
+            repo.join(WORKTREES_DIR).join("api-server")
+        );
+        // Creating it again reuses the worktree
+        assert_eq!(
+            create_worktree(&repo, "api server", &config).unwrap(),
+            worktree
+        );
+
+        // The worktrees stay out of the main tree's status
+        let main = status("main", &info).unwrap();
+        assert!(main.is_clean(), "{main:?}");
+        let status = status("api server", &worktree).unwrap();
+        assert_eq!(
+            status.branch.as_deref(),
+            Some("wezterm-parallel/api-server")
+        );
+        assert!(status.worktree);
+    }
+}
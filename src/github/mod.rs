@@ -0,0 +1,596 @@
+// WezTerm Multi-Process Development Framework - GitHub Issue Sync
+// Imports open GitHub issues as tasks and closes issues whose tasks completed
+//
+// Each sync pass lists the open issues of the configured repositories
+// (pull requests excluded) and creates a task for every issue that has none
+// yet, then closes the issues of imported tasks that have completed since.
+// Tasks remember their issue in the `github_issue` metadata
+// (`owner/repo#number`), so nothing is imported twice while the task exists.
+
+use crate::logging::LogContext;
+use crate::task::{Task, TaskCategory, TaskManager, TaskPriority, TaskStatus};
+use crate::{log_info, log_warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+/// Task metadata key holding the issue a task was imported from
+pub const ISSUE_KEY: &str = "github_issue";
+
+/// Task metadata key holding the issue's web page
+pub const ISSUE_URL_KEY: &str = "github_url";
+
+/// Task metadata key set once the issue has been closed for the task
+pub const CLOSED_KEY: &str = "github_closed_at";
+
+/// Time allowed for one GitHub API request
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Issues requested per page; a shorter page is the last one
+const PAGE_SIZE: usize = 100;
+
+/// Pages read per repository and sync pass
+const MAX_PAGES: usize = 10;
+
+/// GitHub issue sync settings
+///
+/// ```yaml
+/// github:
+///   enabled: true
+///   token_env: GITHUB_TOKEN
+///   sync_interval_secs: 300
+///   repositories:
+///     - repo: acme/shop
+///       workspace: shop
+///       labels: [agent]
+///   priority_labels: { "priority: high": High }
+///   category_labels: { bug: BugFix }
+/// ```
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GithubConfig {
+    pub enabled: bool,
+
+    /// API token; read from `token_env` when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+
+    /// Environment variable holding the API token
+    pub token_env: String,
+
+    /// API root, for GitHub Enterprise Server
+    pub api_url: String,
+
+    pub sync_interval_secs: u64,
+    pub repositories: Vec<GithubRepository>,
+
+    /// Task priority of issues with a label (case-insensitive); the highest wins
+    pub priority_labels: BTreeMap<String, TaskPriority>,
+
+    /// Task category of issues with a label (case-insensitive); the issue's
+    /// first mapped label wins
+    pub category_labels: BTreeMap<String, TaskCategory>,
+
+    /// Close an issue when its task completes
+    pub close_on_complete: bool,
+}
+
+impl Default for GithubConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            token: None,
+            token_env: "GITHUB_TOKEN".to_string(),
+            api_url: "https://api.github.com".to_string(),
+            sync_interval_secs: 300,
+            repositories: Vec::new(),
+            priority_labels: BTreeMap::from([
+                ("low priority".to_string(), TaskPriority::Low),
+                ("high priority".to_string(), TaskPriority::High),
+                ("critical".to_string(), TaskPriority::Critical),
+                ("urgent".to_string(), TaskPriority::Urgent),
+            ]),
+            category_labels: BTreeMap::from([
+                ("bug".to_string(), TaskCategory::BugFix),
+                ("enhancement".to_string(), TaskCategory::Feature),
+                ("documentation".to_string(), TaskCategory::Documentation),
+                ("test".to_string(), TaskCategory::Testing),
+            ]),
+            close_on_complete: true,
+        }
+    }
+}
+
+// The token stays out of logs
+impl fmt::Debug for GithubConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GithubConfig")
+            .field("enabled", &self.enabled)
+            .field("token", &self.token.as_ref().map(|_| "<redacted>"))
+            .field("token_env", &self.token_env)
+            .field("api_url", &self.api_url)
+            .field("sync_interval_secs", &self.sync_interval_secs)
+            .field("repositories", &self.repositories)
+            .field("priority_labels", &self.priority_labels)
+            .field("category_labels", &self.category_labels)
+            .field("close_on_complete", &self.close_on_complete)
+            .finish()
+    }
+}
+
+impl GithubConfig {
+    /// Configured token, or the one in `token_env`
+    pub fn token(&self) -> Option<String> {
+        self.token
+            .clone()
+            .or_else(|| std::env::var(&self.token_env).ok())
+            .filter(|token| !token.trim().is_empty())
+    }
+
+    /// Priority of an issue with `labels`; `Medium` when none is mapped
+    pub fn priority(&self, labels: &[String]) -> TaskPriority {
+        labels
+            .iter()
+            .filter_map(|label| lookup(&self.priority_labels, label))
+            .max()
+            .cloned()
+            .unwrap_or(TaskPriority::Medium)
+    }
+
+    /// Category of an issue with `labels`; `Development` when none is mapped
+    pub fn category(&self, labels: &[String]) -> TaskCategory {
+        labels
+            .iter()
+            .find_map(|label| lookup(&self.category_labels, label))
+            .cloned()
+            .unwrap_or(TaskCategory::Development)
+    }
+}
+
+fn lookup<'a, T>(map: &'a BTreeMap<String, T>, label: &str) -> Option<&'a T> {
+    map.iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(label))
+        .map(|(_, value)| value)
+}
+
+/// One repository whose issues are imported
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GithubRepository {
+    /// `owner/name`
+    pub repo: String,
+
+    /// Workspace of the imported tasks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<String>,
+
+    /// Only import issues carrying all of these labels
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+}
+
+impl GithubRepository {
+    pub fn validate(&self) -> Result<(), String> {
+        match self.repo.split_once('/') {
+            Some((owner, name)) if !owner.is_empty() && !name.is_empty() && !name.contains('/') => {
+                Ok(())
+            }
+            _ => Err(format!(
+                "GitHub repository '{}' must be given as owner/name",
+                self.repo
+            )),
+        }
+    }
+}
+
+/// An issue as returned by the issues API
+#[derive(Debug, Clone, Deserialize)]
+pub struct Issue {
+    pub number: u64,
+    pub title: String,
+    #[serde(default)]
+    pub body: Option<String>,
+    pub html_url: String,
+    #[serde(default)]
+    pub labels: Vec<IssueLabel>,
+    #[serde(default)]
+    pub assignee: Option<IssueUser>,
+
+    /// Present when the issue is a pull request
+    #[serde(default)]
+    pub pull_request: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IssueLabel {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IssueUser {
+    pub login: String,
+}
+
+impl Issue {
+    pub fn label_names(&self) -> Vec<String> {
+        self.labels.iter().map(|label| label.name.clone()).collect()
+    }
+}
+
+/// Task for an issue of `repository`
+pub fn issue_task(repository: &GithubRepository, issue: &Issue, config: &GithubConfig) -> Task {
+    let labels = issue.label_names();
+    let mut task = Task::new(issue.title.clone(), config.category(&labels));
+    task.description = issue.body.clone().filter(|body| !body.trim().is_empty());
+    task.priority = config.priority(&labels);
+    task.workspace = repository.workspace.clone();
+    task.assignee = issue.assignee.as_ref().map(|user| user.login.clone());
+    task.tags = labels;
+    task.metadata.insert(
+        ISSUE_KEY.to_string(),
+        format!("{}#{}", repository.repo, issue.number),
+    );
+    task.metadata
+        .insert(ISSUE_URL_KEY.to_string(), issue.html_url.clone());
+    task.metadata
+        .insert("source".to_string(), "github".to_string());
+    task
+}
+
+/// Outcome of one sync pass
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GithubSyncReport {
+    pub synced_at: u64,
+
+    /// Issues imported as tasks, as `owner/repo#number`
+    pub imported: Vec<String>,
+
+    /// Issues closed because their task completed
+    pub closed: Vec<String>,
+
+    /// Requests that failed; the rest of the pass went on
+    pub errors: Vec<String>,
+}
+
+/// Keeps tasks and GitHub issues in step
+pub struct GithubSync {
+    config: GithubConfig,
+    token: Option<String>,
+    client: reqwest::Client,
+    task_manager: Arc<TaskManager>,
+
+    /// Issues imported by this process, so deleting their task does not
+    /// bring them straight back
+    imported: RwLock<HashSet<String>>,
+    last_report: RwLock<Option<GithubSyncReport>>,
+}
+
+impl GithubSync {
+    pub fn new(config: GithubConfig, task_manager: Arc<TaskManager>) -> Self {
+        Self {
+            token: config.token(),
+            config,
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .user_agent(concat!("wezterm-parallel/", env!("CARGO_PKG_VERSION")))
+                .build()
+                .unwrap_or_default(),
+            task_manager,
+            imported: RwLock::new(HashSet::new()),
+            last_report: RwLock::new(None),
+        }
+    }
+
+    /// Whether sync passes run
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled && !self.config.repositories.is_empty()
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.config.sync_interval_secs.max(1))
+    }
+
+    /// Report of the latest pass
+    pub async fn last_report(&self) -> Option<GithubSyncReport> {
+        self.last_report.read().await.clone()
+    }
+
+    /// Import new issues, then close the issues of completed tasks
+    pub async fn sync(&self) -> GithubSyncReport {
+        let mut report = GithubSyncReport {
+            synced_at: crate::clock::unix_secs(),
+            ..GithubSyncReport::default()
+        };
+        let tasks = self.task_manager.list_tasks(None).await;
+
+        let mut known: HashSet<String> = tasks
+            .iter()
+            .filter_map(|task| task.metadata.get(ISSUE_KEY).cloned())
+            .collect();
+        known.extend(self.imported.read().await.iter().cloned());
+        for repository in &self.config.repositories {
+            if let Err(e) = self.import(repository, &mut known, &mut report).await {
+                report.errors.push(format!("{}: {e}", repository.repo));
+            }
+        }
+
+        // Closing needs a token; without one only public issues are imported
+        if self.config.close_on_complete && self.token.is_some() {
+            for task in tasks {
+                let Some(key) = task.metadata.get(ISSUE_KEY).cloned() else {
+                    continue;
+                };
+                if task.status != TaskStatus::Completed || task.metadata.contains_key(CLOSED_KEY) {
+                    continue;
+                }
+                match self.close(&key).await {
+                    Ok(()) => {
+                        let mut task = task;
+                        task.metadata
+                            .insert(CLOSED_KEY.to_string(), report.synced_at.to_string());
+                        if let Err(e) = self.task_manager.update_task(task).await {
+                            report.errors.push(format!("{key}: {e}"));
+                        }
+                        report.closed.push(key);
+                    }
+                    Err(e) => report.errors.push(format!("{key}: {e}")),
+                }
+            }
+        }
+
+        let context = LogContext::new("github", "sync")
+            .with_metadata("imported", serde_json::json!(report.imported.len()))
+            .with_metadata("closed", serde_json::json!(report.closed.len()));
+        if report.errors.is_empty() {
+            log_info!(
+                context,
+                "GitHub sync imported {} issues and closed {}",
+                report.imported.len(),
+                report.closed.len()
+            );
+        } else {
+            log_warn!(
+                context,
+                "GitHub sync finished with errors: {}",
+                report.errors.join("; ")
+            );
+        }
+        *self.last_report.write().await = Some(report.clone());
+        report
+    }
+
+    /// Create tasks for the open issues of `repository` not in `known`
+    async fn import(
+        &self,
+        repository: &GithubRepository,
+        known: &mut HashSet<String>,
+        report: &mut GithubSyncReport,
+    ) -> Result<(), String> {
+        for issue in self.open_issues(repository).await? {
+            let key = format!("{}#{}", repository.repo, issue.number);
+            if issue.pull_request.is_some() || known.contains(&key) {
+                continue;
+            }
+            let task = issue_task(repository, &issue, &self.config);
+            match self.task_manager.create_task(task).await {
+                Ok(_) => {
+                    self.imported.write().await.insert(key.clone());
+                    known.insert(key.clone());
+                    report.imported.push(key);
+                }
+                Err(e) => report.errors.push(format!("{key}: {e}")),
+            }
+        }
+        Ok(())
+    }
+
+    async fn open_issues(&self, repository: &GithubRepository) -> Result<Vec<Issue>, String> {
+        let url = format!("{}/repos/{}/issues", self.api_root(), repository.repo);
+        let mut query = vec![
+            ("state", "open".to_string()),
+            ("per_page", PAGE_SIZE.to_string()),
+        ];
+        if !repository.labels.is_empty() {
+            query.push(("labels", repository.labels.join(",")));
+        }
+
+        let mut issues = Vec::new();
+        for page in 1..=MAX_PAGES {
+            let response = self
+                .request(self.client.get(&url))
+                .query(&query)
+                .query(&[("page", page)])
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+            if !response.status().is_success() {
+                return Err(format!("Listing issues returned {}", response.status()));
+            }
+            let batch: Vec<Issue> = response.json().await.map_err(|e| e.to_string())?;
+            let last = batch.len() < PAGE_SIZE;
+            issues.extend(batch);
+            if last {
+                break;
+            }
+        }
+        Ok(issues)
+    }
+
+    /// Close the issue `owner/repo#number` as completed
+    async fn close(&self, key: &str) -> Result<(), String> {
+        let (repo, number) = key
+            .rsplit_once('#')
+            .ok_or_else(|| format!("Not an issue reference: {key}"))?;
+        let url = format!("{}/repos/{repo}/issues/{number}", self.api_root());
+        let response = self
+            .request(self.client.patch(&url))
+            .json(&serde_json::json!({ "state": "closed", "state_reason": "completed" }))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("Closing the issue returned {}", response.status()))
+        }
+    }
+
+    fn api_root(&self) -> &str {
+        self.config.api_url.trim_end_matches('/')
+    }
+
+    fn request(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        let request = request
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", "2022-11-28");
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::TaskConfig;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn repository() -> GithubRepository {
+        GithubRepository {
+            repo: "acme/shop".to_string(),
+            workspace: Some("shop".to_string()),
+            labels: Vec::new(),
+        }
+    }
+
+    /// Answer the next request on `listener` with a JSON `body`, returning the request
+    async fn reply(listener: &tokio::net::TcpListener, body: &str) -> String {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut chunk = [0; 4096];
+        loop {
+            let n = stream.read(&mut chunk).await.unwrap();
+            request.extend_from_slice(&chunk[..n]);
+            let text = String::from_utf8_lossy(&request).to_string();
+            if let Some((head, rest)) = text.split_once("\r\n\r\n") {
+                let length = head
+                    .lines()
+                    .find_map(|line| {
+                        let (name, value) = line.split_once(':')?;
+                        name.eq_ignore_ascii_case("content-length")
+                            .then(|| value.trim().parse::<usize>().ok())?
+                    })
+                    .unwrap_or(0);
+                if rest.len() >= length || n == 0 {
+                    break;
+                }
+            }
+        }
+        stream
+            .write_all(
+                format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .as_bytes(),
+            )
+            .await
+            .unwrap();
+        String::from_utf8_lossy(&request).into_owned()
+    }
+
+    #[test]
+    fn test_issue_labels_map_to_priority_and_category() {
+        let config = GithubConfig::default();
+        let issue: Issue = serde_json::from_value(serde_json::json!({
+            "number": 7,
+            "title": "Checkout fails",
+            "body": "Steps...",
+            "html_url": "https://github.com/acme/shop/issues/7",
+            "labels": [{ "name": "Bug" }, { "name": "urgent" }, { "name": "high priority" }],
+            "assignee": { "login": "octocat" }
+        }))
+        .unwrap();
+
+        let task = issue_task(&repository(), &issue, &config);
+        assert_eq!(task.title, "Checkout fails");
+        assert_eq!(task.priority, TaskPriority::Urgent);
+        assert_eq!(task.category, TaskCategory::BugFix);
+        assert_eq!(task.assignee.as_deref(), Some("octocat"));
+        assert_eq!(task.workspace.as_deref(), Some("shop"));
+        assert_eq!(task.metadata[ISSUE_KEY], "acme/shop#7");
+
+        assert_eq!(config.priority(&[]), TaskPriority::Medium);
+        assert_eq!(
+            config.category(&["question".to_string()]),
+            TaskCategory::Development
+        );
+        assert!(repository().validate().is_ok());
+        assert!(GithubRepository {
+            repo: "shop".to_string(),
+            ..repository()
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sync_imports_issues_and_closes_completed_ones() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let config = GithubConfig {
+            enabled: true,
+            token: Some("secret".to_string()),
+            api_url: format!("http://{}", listener.local_addr().unwrap()),
+            repositories: vec![repository()],
+            ..GithubConfig::default()
+        };
+        let task_manager = Arc::new(TaskManager::new(TaskConfig {
+            persistence_enabled: false,
+            ..TaskConfig::default()
+        }));
+        let sync = Arc::new(GithubSync::new(config, Arc::clone(&task_manager)));
+        let issues = r#"[
+            {"number": 1, "title": "Add search", "html_url": "https://github.com/acme/shop/issues/1", "labels": [{"name": "enhancement"}]},
+            {"number": 2, "title": "Bump deps", "html_url": "https://github.com/acme/shop/pull/2", "pull_request": {}}
+        ]"#;
+
+        let pass = tokio::spawn({
+            let sync = Arc::clone(&sync);
+            async move { sync.sync().await }
+        });
+        let request = reply(&listener, issues).await;
+        assert!(request.starts_with("GET /repos/acme/shop/issues?state=open"));
+        assert!(
+            request.contains("authorization: Bearer secret"),
+            "{request}"
+        );
+        let report = pass.await.unwrap();
+        assert_eq!(report.imported, ["acme/shop#1"]);
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+
+        let mut task = task_manager.list_tasks(None).await.pop().unwrap();
+        assert_eq!(task.category, TaskCategory::Feature);
+        task.update_status(TaskStatus::Completed);
+        task_manager.update_task(task.clone()).await.unwrap();
+
+        // Known issues are not imported again; the completed one is closed
+        let pass = tokio::spawn({
+            let sync = Arc::clone(&sync);
+            async move { sync.sync().await }
+        });
+        reply(&listener, issues).await;
+        let request = reply(&listener, "{}").await;
+        assert!(request.starts_with("PATCH /repos/acme/shop/issues/1 "));
+        assert!(request.ends_with(r#"{"state":"closed","state_reason":"completed"}"#));
+        let report = pass.await.unwrap();
+        assert!(report.imported.is_empty());
+        assert_eq!(report.closed, ["acme/shop#1"]);
+        assert_eq!(task_manager.get_task_count().await, 1);
+        let task = task_manager.get_task(&task.id).await.unwrap();
+        assert!(task.metadata.contains_key(CLOSED_KEY));
+        assert_eq!(sync.last_report().await, Some(report));
+    }
+}
@@ -0,0 +1,450 @@
+// WezTerm Multi-Process Development Framework - Event Hooks
+// Runs user scripts when workspace events happen
+//
+// Hooks receive the same events as the bridge's webhooks. Each runs through
+// the shell with the event in `WEZTERM_PARALLEL_*` environment variables, is
+// killed when it outlives its timeout, and has its output logged. Failures
+// are raised as dashboard alerts.
+
+use crate::bridge::{BridgeEvent, BridgeEventKind};
+use crate::dashboard::{AlertNotification, AlertSeverity, DashboardMessage};
+use crate::i18n::{Language, Text, TextKey};
+use crate::logging::LogContext;
+use crate::{log_info, log_warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::RwLock;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Prefix of the environment variables describing the event
+pub const ENV_PREFIX: &str = "WEZTERM_PARALLEL_";
+
+/// Output kept in the log per stream
+pub const MAX_LOGGED_OUTPUT: usize = 4096;
+
+/// Scripts to run by event
+///
+/// ```yaml
+/// hooks:
+///   workspace_created:
+///     - command: ./scripts/bootstrap.sh "$WEZTERM_PARALLEL_WORKSPACE"
+///   agent_failed:
+///     - command: notify-send "Agent $WEZTERM_PARALLEL_PROCESS_ID failed"
+///       timeout_secs: 5
+///   task_completed:
+///     - command: ./scripts/archive-task.sh
+///       workspaces: [frontend]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HooksConfig {
+    pub workspace_created: Vec<HookConfig>,
+    pub agent_failed: Vec<HookConfig>,
+    pub task_completed: Vec<HookConfig>,
+}
+
+impl HooksConfig {
+    /// Hooks configured for an event kind
+    pub fn for_event(&self, kind: BridgeEventKind) -> &[HookConfig] {
+        match kind {
+            BridgeEventKind::WorkspaceCreated => &self.workspace_created,
+            BridgeEventKind::AgentFailed => &self.agent_failed,
+            BridgeEventKind::TaskCompleted => &self.task_completed,
+        }
+    }
+
+    /// All configured hooks
+    pub fn iter(&self) -> impl Iterator<Item = &HookConfig> {
+        self.workspace_created
+            .iter()
+            .chain(&self.agent_failed)
+            .chain(&self.task_completed)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+}
+
+/// One script run for an event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HookConfig {
+    /// Shell command line (`sh -c`, or `cmd /C` on Windows)
+    pub command: String,
+
+    /// Seconds before the script is killed
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// Workspaces whose events run this hook; all when empty
+    #[serde(default)]
+    pub workspaces: Vec<String>,
+
+    /// Directory the script runs in; the daemon's when omitted. Hooks of a
+    /// workspace's override file run in the workspace root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<PathBuf>,
+}
+
+fn default_timeout_secs() -> u64 {
+    30
+}
+
+impl HookConfig {
+    pub fn accepts(&self, workspace: &str) -> bool {
+        self.workspaces.is_empty() || self.workspaces.iter().any(|w| w == workspace)
+    }
+}
+
+/// Outcome of one hook run
+#[derive(Debug, Clone, PartialEq)]
+pub struct HookRun {
+    pub command: String,
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+
+    /// Why the run counts as failed
+    pub error: Option<String>,
+}
+
+/// Runs the configured hooks of published events
+#[derive(Debug)]
+pub struct HookRunner {
+    config: HooksConfig,
+
+    /// Hooks of workspace override files, run after the global ones
+    workspace_hooks: RwLock<HashMap<String, HooksConfig>>,
+    dashboard_tx: Option<broadcast::Sender<DashboardMessage>>,
+    language: Language,
+}
+
+impl HookRunner {
+    pub fn new(config: HooksConfig) -> Self {
+        Self {
+            config,
+            workspace_hooks: RwLock::new(HashMap::new()),
+            dashboard_tx: None,
+            language: Language::default(),
+        }
+    }
+
+    /// Raise failed hooks as alerts to dashboard clients
+    pub fn with_dashboard_broadcaster(
+        mut self,
+        tx: broadcast::Sender<DashboardMessage>,
+        language: Language,
+    ) -> Self {
+        self.dashboard_tx = Some(tx);
+        self.language = language;
+        self
+    }
+
+    /// Set the hooks of a workspace's override file
+    pub fn set_workspace_hooks(&self, workspace: &str, hooks: Option<HooksConfig>) {
+        let mut workspace_hooks = self
+            .workspace_hooks
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        match hooks {
+            Some(hooks) if !hooks.is_empty() => {
+                workspace_hooks.insert(workspace.to_string(), hooks)
+            }
+            _ => workspace_hooks.remove(workspace),
+        };
+    }
+
+    /// Run every hook matching `event` in the background
+    pub fn spawn(self: &std::sync::Arc<Self>, event: &BridgeEvent) {
+        for hook in self.matching(event) {
+            let runner = std::sync::Arc::clone(self);
+            let event = event.clone();
+            tokio::spawn(async move { runner.run(&hook, &event).await });
+        }
+    }
+
+    fn matching(&self, event: &BridgeEvent) -> Vec<HookConfig> {
+        let workspace_hooks = self
+            .workspace_hooks
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let own = workspace_hooks
+            .get(&event.workspace)
+            .map(|hooks| hooks.for_event(event.event))
+            .unwrap_or_default();
+        self.config
+            .for_event(event.event)
+            .iter()
+            .chain(own)
+            .filter(|hook| hook.accepts(&event.workspace))
+            .cloned()
+            .collect()
+    }
+
+    /// Run one hook to completion, logging its output and alerting on failure
+    pub async fn run(&self, hook: &HookConfig, event: &BridgeEvent) -> HookRun {
+        let run = execute(hook, event).await;
+
+        let context = LogContext::new("hooks", "hook_run")
+            .with_metadata("event", serde_json::json!(event.event))
+            .with_metadata("workspace", serde_json::json!(event.workspace))
+            .with_metadata("command", serde_json::json!(run.command))
+            .with_metadata("exit_code", serde_json::json!(run.exit_code))
+            .with_metadata("stdout", serde_json::json!(run.stdout))
+            .with_metadata("stderr", serde_json::json!(run.stderr));
+        match run.error {
+            None => {
+                log_info!(
+                    context,
+                    "Hook for {} finished: {}",
+                    event.event.as_str(),
+                    run.command
+                );
+            }
+            Some(ref error) => {
+                log_warn!(
+                    context,
+                    "Hook for {} failed: {}: {}",
+                    event.event.as_str(),
+                    run.command,
+                    error
+                );
+                self.alert(event, &run, error);
+            }
+        }
+        run
+    }
+
+    fn alert(&self, event: &BridgeEvent, run: &HookRun, error: &str) {
+        let Some(ref tx) = self.dashboard_tx else {
+            return;
+        };
+        let text = Text::new(TextKey::HookFailed)
+            .arg("event", event.event.as_str())
+            .arg("command", &run.command)
+            .arg("error", error);
+        let timestamp = crate::clock::unix_secs();
+        let _ = tx.send(DashboardMessage::Alert(AlertNotification {
+            id: format!("hook-{}-{timestamp}", event.event.as_str()),
+            severity: AlertSeverity::Warning,
+            category: "hooks".to_string(),
+            message: text.render(self.language),
+            component: Some(event.workspace.clone()),
+            timestamp,
+            details: Some(serde_json::json!({
+                "command": run.command,
+                "exit_code": run.exit_code,
+                "stderr": run.stderr,
+            })),
+            text: Some(text),
+        }));
+    }
+}
+
+/// Environment describing `event` to a hook
+///
+/// `WEZTERM_PARALLEL_EVENT`, `_WORKSPACE`, `_TIMESTAMP` and `_EVENT_JSON`,
+/// plus one variable per data field, e.g. `WEZTERM_PARALLEL_TASK_ID`.
+pub fn environment(event: &BridgeEvent) -> Vec<(String, String)> {
+    let mut env = vec![
+        (
+            format!("{ENV_PREFIX}EVENT"),
+            event.event.as_str().to_string(),
+        ),
+        (format!("{ENV_PREFIX}WORKSPACE"), event.workspace.clone()),
+        (
+            format!("{ENV_PREFIX}TIMESTAMP"),
+            event.timestamp.to_string(),
+        ),
+        (
+            format!("{ENV_PREFIX}EVENT_JSON"),
+            serde_json::to_string(event).unwrap_or_default(),
+        ),
+    ];
+    for key in event.data.keys() {
+        let name: String = key
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let value = event.field(&format!("data.{key}")).unwrap_or_default();
+        env.push((format!("{ENV_PREFIX}{name}"), value));
+    }
+    env
+}
+
+async fn execute(hook: &HookConfig, event: &BridgeEvent) -> HookRun {
+    let mut run = HookRun {
+        command: hook.command.clone(),
+        exit_code: None,
+        stdout: String::new(),
+        stderr: String::new(),
+        error: None,
+    };
+
+    let mut command = shell(&hook.command);
+    command
+        .envs(environment(event))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if let Some(ref cwd) = hook.cwd {
+        command.current_dir(cwd);
+    }
+    let child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            run.error = Some(format!("failed to start: {e}"));
+            return run;
+        }
+    };
+
+    // Dropping the unfinished wait kills the script
+    let timeout = Duration::from_secs(hook.timeout_secs.max(1));
+    match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(Ok(output)) => {
+            run.exit_code = output.status.code();
+            run.stdout = truncated(&output.stdout);
+            run.stderr = truncated(&output.stderr);
+            if !output.status.success() {
+                run.error = Some(match output.status.code() {
+                    Some(code) => format!("exited with status {code}"),
+                    None => "terminated by a signal".to_string(),
+                });
+            }
+        }
+        Ok(Err(e)) => run.error = Some(e.to_string()),
+        Err(_) => run.error = Some(format!("timed out after {}s", timeout.as_secs())),
+    }
+    run
+}
+
+#[cfg(not(windows))]
+fn shell(command_line: &str) -> tokio::process::Command {
+    let mut command = tokio::process::Command::new("sh");
+    command.arg("-c").arg(command_line);
+    command
+}
+
+#[cfg(windows)]
+fn shell(command_line: &str) -> tokio::process::Command {
+    let mut command = tokio::process::Command::new("cmd");
+    command.arg("/C").arg(command_line);
+    command
+}
+
+/// Output as text, cut to [`MAX_LOGGED_OUTPUT`] bytes
+fn truncated(output: &[u8]) -> String {
+    let text = String::from_utf8_lossy(output);
+    let text = text.trim_end();
+    if text.len() <= MAX_LOGGED_OUTPUT {
+        return text.to_string();
+    }
+    let mut end = MAX_LOGGED_OUTPUT;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}...", &text[..end])
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn hook(command: &str, timeout_secs: u64) -> HookConfig {
+        HookConfig {
+            command: command.to_string(),
+            timeout_secs,
+            workspaces: Vec::new(),
+            cwd: None,
+        }
+    }
+
+    #[test]
+    fn test_config_matches_event_and_workspace() {
+        let config: HooksConfig = serde_yaml::from_str(
+            "task_completed:\n  - command: echo done\n    workspaces: [frontend]\nagent_failed:\n  - command: echo failed\n",
+        )
+        .unwrap();
+        assert_eq!(config.task_completed[0].timeout_secs, 30);
+
+        let runner = HookRunner::new(config);
+        let done = BridgeEvent::task_completed("frontend", "t-1", "Ship it");
+        assert_eq!(runner.matching(&done).len(), 1);
+        let elsewhere = BridgeEvent::task_completed("backend", "t-1", "Ship it");
+        assert!(runner.matching(&elsewhere).is_empty());
+        let created = BridgeEvent::workspace_created("frontend", "basic");
+        assert!(runner.matching(&created).is_empty());
+
+        // A workspace's own hooks only run for its events
+        runner.set_workspace_hooks(
+            "frontend",
+            Some(HooksConfig {
+                workspace_created: vec![hook("npm ci", 60)],
+                ..Default::default()
+            }),
+        );
+        assert_eq!(runner.matching(&created)[0].command, "npm ci");
+        assert!(runner
+            .matching(&BridgeEvent::workspace_created("backend", "basic"))
+            .is_empty());
+        runner.set_workspace_hooks("frontend", None);
+        assert!(runner.matching(&created).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_hook_receives_event_in_environment() {
+        let runner = HookRunner::new(HooksConfig::default());
+        let event = BridgeEvent::agent_failed("frontend", "proc-1", Some(3), "boom");
+
+        let run = runner
+            .run(
+                &hook(
+                    "echo \"$WEZTERM_PARALLEL_EVENT $WEZTERM_PARALLEL_WORKSPACE $WEZTERM_PARALLEL_PROCESS_ID $WEZTERM_PARALLEL_EXIT_CODE\"",
+                    5,
+                ),
+                &event,
+            )
+            .await;
+        assert_eq!(run.error, None);
+        assert_eq!(run.exit_code, Some(0));
+        assert_eq!(run.stdout, "agent_failed frontend proc-1 3");
+    }
+
+    #[tokio::test]
+    async fn test_failed_and_timed_out_hooks_raise_alerts() {
+        let (tx, mut rx) = broadcast::channel(16);
+        let runner = Arc::new(
+            HookRunner::new(HooksConfig::default()).with_dashboard_broadcaster(tx, Language::En),
+        );
+        let event = BridgeEvent::workspace_created("frontend", "basic");
+
+        let run = runner.run(&hook("echo oops >&2; exit 4", 5), &event).await;
+        assert_eq!(run.exit_code, Some(4));
+        assert_eq!(run.stderr, "oops");
+        assert_eq!(run.error.as_deref(), Some("exited with status 4"));
+
+        let run = runner.run(&hook("sleep 5", 1), &event).await;
+        assert_eq!(run.error.as_deref(), Some("timed out after 1s"));
+
+        for expected in ["exited with status 4", "timed out after 1s"] {
+            match rx.try_recv() {
+                Ok(DashboardMessage::Alert(alert)) => {
+                    assert_eq!(alert.category, "hooks");
+                    assert!(alert.message.ends_with(expected), "{}", alert.message);
+                }
+                other => panic!("unexpected message: {other:?}"),
+            }
+        }
+    }
+}
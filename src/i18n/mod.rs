@@ -0,0 +1,272 @@
+// WezTerm Multi-Process Development Framework - Localized Strings
+// Catalog of user-facing alert, notification and report text in English and Japanese
+//
+// Text that reaches users is built as a `Text` (a catalog key plus named
+// arguments) and rendered in the language of whoever reads it: the configured
+// `language` for reports and logs, or a dashboard client's own preference.
+// Every key must have a template in every language; the catalog match is
+// exhaustive so a missing translation fails to compile.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Display language
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    En,
+    Ja,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::En, Language::Ja];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Language::En => "en",
+            Language::Ja => "ja",
+        }
+    }
+
+    /// Language of a tag such as `ja`, `ja-JP` or `en_US.UTF-8`
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag
+            .trim()
+            .split(['-', '_', '.'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match primary.as_str() {
+            "en" => Some(Language::En),
+            "ja" => Some(Language::Ja),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_tag(s).ok_or_else(|| format!("Unsupported language '{s}' (expected en or ja)"))
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Catalog entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextKey {
+    /// `{scope}`
+    BudgetExceeded,
+    /// `{scope}`, `{percent}`
+    BudgetWarning,
+    /// `{usage}`, `{threshold}`
+    SystemCpuHigh,
+    /// `{usage}`, `{threshold}`
+    SystemMemoryHigh,
+    /// `{usage}`, `{threshold}`
+    SystemDiskHigh,
+    /// `{process}`, `{count}`, `{threshold}`
+    ProcessRestarts,
+    /// `{process}`
+    ProcessFailed,
+    /// `{process}`, `{usage}`
+    ProcessCpuHigh,
+    /// `{process}`, `{minutes}`
+    ProcessUnresponsive,
+    /// `{process}`, `{count}`, `{minutes}`
+    ProcessQuarantined,
+    /// `{task}`, `{count}`, `{minutes}`
+    TaskCrashLoop,
+    /// `{task}`, `{stage}`, `{elapsed}`, `{budget}`
+    LatencyBudgetExceeded,
+    /// `{task}`, `{column}`, `{limit}`
+    WipLimitRejected,
+    /// `{column}`, `{count}`, `{limit}`
+    WipLimitExceeded,
+    /// `{event}`, `{command}`, `{error}`
+    HookFailed,
+    /// `{startup}`, `{memory_mb}`, `{peak_mb}`, `{cpu}`, `{tasks}`, `{gc_runs}`,
+    /// `{cache_hit_rate}`, `{pool_used}`, `{pool_size}`
+    PerformanceReport,
+}
+
+fn template(key: TextKey, language: Language) -> &'static str {
+    use Language::{En, Ja};
+    use TextKey::*;
+
+    match (key, language) {
+        (BudgetExceeded, En) => "Weekly budget for {scope} exceeded",
+        (BudgetExceeded, Ja) => "{scope} の週間予算を超過しました",
+        (BudgetWarning, En) => "Weekly budget for {scope} is {percent}% used",
+        (BudgetWarning, Ja) => "{scope} の週間予算を {percent}% 使用しています",
+        (SystemCpuHigh, En) => "High CPU usage: {usage}% (threshold: {threshold}%)",
+        (SystemCpuHigh, Ja) => "CPU使用率が高くなっています: {usage}% (しきい値: {threshold}%)",
+        (SystemMemoryHigh, En) => "High memory usage: {usage}% (threshold: {threshold}%)",
+        (SystemMemoryHigh, Ja) => {
+            "メモリ使用率が高くなっています: {usage}% (しきい値: {threshold}%)"
+        }
+        (SystemDiskHigh, En) => "High disk usage: {usage}% (threshold: {threshold}%)",
+        (SystemDiskHigh, Ja) => {
+            "ディスク使用率が高くなっています: {usage}% (しきい値: {threshold}%)"
+        }
+        (ProcessRestarts, En) => {
+            "Process {process} has restarted {count} times (threshold: {threshold})"
+        }
+        (ProcessRestarts, Ja) => {
+            "プロセス {process} が {count} 回再起動しました (しきい値: {threshold})"
+        }
+        (ProcessFailed, En) => "Process {process} has failed",
+        (ProcessFailed, Ja) => "プロセス {process} が異常終了しました",
+        (ProcessCpuHigh, En) => "Process {process} high CPU usage: {usage}%",
+        (ProcessUnresponsive, En) => {
+            "Process {process} has produced no output for {minutes} minutes while working"
+        }
+        (ProcessUnresponsive, Ja) => "プロセス {process} が作業中に {minutes} 分間応答していません",
+        (ProcessCpuHigh, Ja) => "プロセス {process} のCPU使用率が高くなっています: {usage}%",
+        (ProcessQuarantined, En) => {
+            "Process {process} failed {count} times in {minutes} minutes and was quarantined"
+        }
+        (ProcessQuarantined, Ja) => {
+            "プロセス {process} が {minutes} 分間に {count} 回失敗したため隔離しました"
+        }
+        (TaskCrashLoop, En) => "Background task {task} failed {count} times in {minutes} minutes",
+        (TaskCrashLoop, Ja) => {
+            "バックグラウンドタスク {task} が {minutes} 分間に {count} 回失敗しました"
+        }
+        (LatencyBudgetExceeded, En) => {
+            "Task {task} spent {elapsed}s in {stage}, over its {budget}s budget"
+        }
+        (LatencyBudgetExceeded, Ja) => {
+            "タスク {task} の {stage} に {elapsed} 秒かかり、予算 {budget} 秒を超えました"
+        }
+        (WipLimitRejected, En) => {
+            "Task {task} was not moved: {column} is at its WIP limit of {limit}"
+        }
+        (WipLimitRejected, Ja) => {
+            "タスク {task} を移動できません: {column} は WIP 上限 {limit} に達しています"
+        }
+        (WipLimitExceeded, En) => "{column} is over its WIP limit: {count}/{limit}",
+        (WipLimitExceeded, Ja) => "{column} が WIP 上限を超えています: {count}/{limit}",
+        (HookFailed, En) => "Hook for {event} failed: {command}: {error}",
+        (HookFailed, Ja) => "{event} のフックが失敗しました: {command}: {error}",
+        (PerformanceReport, En) => {
+            "=== Performance report ===\n\
+            Startup time: {startup}\n\
+            Memory usage: {memory_mb}MB (peak: {peak_mb}MB)\n\
+            CPU usage: {cpu}%\n\
+            Active tasks: {tasks}\n\
+            GC runs: {gc_runs}\n\
+            Cache hit rate: {cache_hit_rate}%\n\
+            Memory pool in use: {pool_used}/{pool_size}"
+        }
+        (PerformanceReport, Ja) => {
+            "=== パフォーマンスレポート ===\n\
+            起動時間: {startup}\n\
+            メモリ使用量: {memory_mb}MB (ピーク: {peak_mb}MB)\n\
+            CPU使用率: {cpu}%\n\
+            アクティブタスク: {tasks}\n\
+            GC実行回数: {gc_runs}\n\
+            キャッシュヒット率: {cache_hit_rate}%\n\
+            メモリプール使用中: {pool_used}/{pool_size}"
+        }
+    }
+}
+
+/// User-facing text that can be rendered in any supported language
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Text {
+    pub key: TextKey,
+
+    /// Values substituted for `{name}` placeholders, already formatted
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub args: BTreeMap<String, String>,
+}
+
+impl Text {
+    pub fn new(key: TextKey) -> Self {
+        Self {
+            key,
+            args: BTreeMap::new(),
+        }
+    }
+
+    pub fn arg(mut self, name: &str, value: impl fmt::Display) -> Self {
+        self.args.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Render the text; placeholders without an argument are left as written
+    pub fn render(&self, language: Language) -> String {
+        let template = template(self.key, language);
+        let mut rendered = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            rendered.push_str(&rest[..start]);
+            let placeholder = &rest[start..];
+            match placeholder
+                .find('}')
+                .and_then(|end| Some((end, self.args.get(&placeholder[1..end])?)))
+            {
+                Some((end, value)) => {
+                    rendered.push_str(value);
+                    rest = &placeholder[end + 1..];
+                }
+                None => {
+                    rendered.push('{');
+                    rest = &placeholder[1..];
+                }
+            }
+        }
+        rendered.push_str(rest);
+        rendered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_in_each_language() {
+        let text = Text::new(TextKey::BudgetWarning)
+            .arg("scope", "Development")
+            .arg("percent", 85);
+        assert_eq!(
+            text.render(Language::En),
+            "Weekly budget for Development is 85% used"
+        );
+        assert_eq!(
+            text.render(Language::Ja),
+            "Development の週間予算を 85% 使用しています"
+        );
+
+        // Missing arguments stay visible instead of disappearing
+        assert_eq!(
+            Text::new(TextKey::ProcessFailed).render(Language::En),
+            "Process {process} has failed"
+        );
+    }
+
+    #[test]
+    fn test_language_tags() {
+        assert_eq!(Language::from_tag("ja-JP"), Some(Language::Ja));
+        assert_eq!(Language::from_tag("en_US.UTF-8"), Some(Language::En));
+        assert_eq!(Language::from_tag("EN"), Some(Language::En));
+        assert_eq!(Language::from_tag("fr"), None);
+        assert!("de".parse::<Language>().is_err());
+        assert_eq!(
+            serde_json::to_string(&Language::Ja).unwrap(),
+            "\"ja\"".to_string()
+        );
+    }
+}
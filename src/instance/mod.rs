@@ -0,0 +1,480 @@
+// WezTerm Multi-Process Development Framework - Single-Instance Guard
+// Keeps a second daemon from starting next to a running one and tells
+// `status` and `--takeover` which instance is running
+//
+// The daemon holds an exclusive lock on a pidfile next to its IPC socket for
+// as long as it runs. The lock is released by the OS when the process exits,
+// so a file left behind by a crash does not count as a running instance.
+//
+// `--daemon` starts the daemon detached from the terminal with its output
+// appended to the log file, and writes a plain pidfile for service managers.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+
+/// File name of the instance lock
+pub const LOCK_FILE_NAME: &str = "wezterm-parallel.lock";
+
+/// File name of the plain pidfile, next to the instance lock
+pub const PID_FILE_NAME: &str = "wezterm-parallel.pid";
+
+/// Daemon holding the lock, as recorded in the pidfile
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstanceInfo {
+    pub pid: u32,
+    pub version: String,
+
+    /// IPC socket the daemon listens on
+    pub socket: PathBuf,
+
+    /// Time the daemon took the lock
+    pub started_at: u64,
+
+    /// Arguments the daemon was started with, without the program
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+impl InstanceInfo {
+    /// This process, serving IPC on `socket`
+    pub fn current(socket: &Path) -> Self {
+        Self {
+            pid: std::process::id(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            socket: socket.to_path_buf(),
+            started_at: crate::clock::unix_secs(),
+            args: std::env::args().skip(1).collect(),
+        }
+    }
+
+    /// Arguments that start this daemon again, detached, once it has stopped
+    pub fn restart_args(&self) -> Vec<String> {
+        let mut args = vec!["--daemon".to_string()];
+        args.extend(
+            self.args
+                .iter()
+                .filter(|arg| !matches!(arg.as_str(), "--daemon" | "--takeover"))
+                .cloned(),
+        );
+        args
+    }
+}
+
+/// Whether a daemon holds the lock
+#[derive(Debug, Clone, PartialEq)]
+pub enum InstanceState {
+    NotRunning,
+    /// The holder's record is `None` while it is still being written
+    Running(Option<InstanceInfo>),
+}
+
+#[derive(Debug)]
+pub enum InstanceError {
+    /// Another daemon holds the lock
+    AlreadyRunning {
+        path: PathBuf,
+        holder: Option<InstanceInfo>,
+    },
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+}
+
+impl fmt::Display for InstanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InstanceError::AlreadyRunning {
+                holder: Some(holder),
+                ..
+            } => write!(
+                f,
+                "wezterm-parallel {} is already running (pid {}, socket {}); \
+                 check it with `wezterm-parallel status` or replace it with `wezterm-parallel --takeover`",
+                holder.version,
+                holder.pid,
+                holder.socket.display()
+            ),
+            InstanceError::AlreadyRunning { path, holder: None } => write!(
+                f,
+                "Another wezterm-parallel instance holds {}; \
+                 check it with `wezterm-parallel status` or replace it with `wezterm-parallel --takeover`",
+                path.display()
+            ),
+            InstanceError::Io { path, source } => {
+                write!(f, "Cannot lock {}: {source}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for InstanceError {}
+
+/// Exclusive lock on the pidfile, held until dropped
+#[derive(Debug)]
+pub struct InstanceLock {
+    path: PathBuf,
+    _file: File,
+}
+
+impl InstanceLock {
+    /// Lock file next to the default IPC socket
+    #[cfg(unix)]
+    pub fn default_path() -> PathBuf {
+        crate::ipc::default_socket_path().with_file_name(LOCK_FILE_NAME)
+    }
+
+    /// Lock file in the configuration directory (the socket is a named pipe)
+    #[cfg(not(unix))]
+    pub fn default_path() -> PathBuf {
+        let mut path = dirs::config_dir().unwrap_or_else(|| {
+            log::warn!("設定ディレクトリが取得できません。カレントディレクトリを使用します。");
+            PathBuf::from(".")
+        });
+        path.push("wezterm-parallel");
+        path.push(LOCK_FILE_NAME);
+        path
+    }
+
+    /// Take the lock and record `info` in the pidfile
+    #[allow(clippy::result_large_err)]
+    pub fn acquire(path: &Path, info: &InstanceInfo) -> Result<Self, InstanceError> {
+        let io_error = |source| InstanceError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        let Some(mut file) = try_lock(path).map_err(io_error)? else {
+            return Err(InstanceError::AlreadyRunning {
+                path: path.to_path_buf(),
+                holder: read_info(path),
+            });
+        };
+
+        let json = serde_json::to_string(info).map_err(|e| io_error(e.into()))?;
+        file.set_len(0)
+            .and_then(|_| file.write_all(json.as_bytes()))
+            .and_then(|_| file.sync_all())
+            .map_err(io_error)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            _file: file,
+        })
+    }
+
+    /// Take the lock once the current holder lets go, waiting up to `timeout`
+    pub async fn acquire_within(
+        path: &Path,
+        info: &InstanceInfo,
+        timeout: Duration,
+    ) -> Result<Self, InstanceError> {
+        let deadline = tokio::time::Instant::now() + timeout;
+
+        loop {
+            match Self::acquire(path, info) {
+                Err(InstanceError::AlreadyRunning { .. })
+                    if tokio::time::Instant::now() < deadline =>
+                {
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Check whether a daemon holds the lock at `path`, without taking it
+pub fn probe(path: &Path) -> io::Result<InstanceState> {
+    if !path.exists() {
+        return Ok(InstanceState::NotRunning);
+    }
+    // Dropping the probe's own lock releases it right away
+    match try_lock(path)? {
+        Some(_) => Ok(InstanceState::NotRunning),
+        None => Ok(InstanceState::Running(read_info(path))),
+    }
+}
+
+/// Wait until no daemon holds the lock at `path`; `false` on timeout
+pub async fn wait_until_stopped(path: &Path, timeout: Duration) -> io::Result<bool> {
+    let deadline = tokio::time::Instant::now() + timeout;
+
+    loop {
+        if probe(path)? == InstanceState::NotRunning {
+            return Ok(true);
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return Ok(false);
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+}
+
+/// Plain pidfile holding the daemon's PID, removed when dropped
+///
+/// Written only once the instance lock is held, so it never overwrites the
+/// pidfile of another running daemon.
+#[derive(Debug)]
+pub struct PidFile {
+    path: PathBuf,
+}
+
+impl PidFile {
+    pub fn default_path() -> PathBuf {
+        InstanceLock::default_path().with_file_name(PID_FILE_NAME)
+    }
+
+    pub fn create(path: &Path) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, format!("{}\n", std::process::id()))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Start `binary` detached from the terminal, appending its output to
+/// `log_file` (discarded without one)
+pub fn spawn_detached(
+    binary: &Path,
+    args: &[String],
+    log_file: Option<&Path>,
+) -> io::Result<Child> {
+    let (stdout, stderr) = match log_file {
+        Some(path) => {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let file = OpenOptions::new().create(true).append(true).open(path)?;
+            (Stdio::from(file.try_clone()?), Stdio::from(file))
+        }
+        None => (Stdio::null(), Stdio::null()),
+    };
+
+    let mut command = Command::new(binary);
+    command
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(stdout)
+        .stderr(stderr);
+    detach(&mut command);
+    command.spawn()
+}
+
+/// Run the child in a new session: no controlling terminal, no SIGHUP when
+/// the terminal closes
+#[cfg(unix)]
+fn detach(command: &mut Command) {
+    use std::os::unix::process::CommandExt;
+
+    // SAFETY: setsid is async-signal-safe and touches no parent state
+    unsafe {
+        command.pre_exec(|| {
+            if libc::setsid() == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
+
+/// Run the child without a console, outside the terminal's process group
+#[cfg(windows)]
+fn detach(command: &mut Command) {
+    use std::os::windows::process::CommandExt;
+
+    const DETACHED_PROCESS: u32 = 0x0000_0008;
+    const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+    command.creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
+}
+
+#[cfg(not(any(unix, windows)))]
+fn detach(_command: &mut Command) {}
+
+fn read_info(path: &Path) -> Option<InstanceInfo> {
+    let json = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+/// Open the pidfile and lock it; `None` when another process holds it
+#[cfg(unix)]
+fn try_lock(path: &Path) -> io::Result<Option<File>> {
+    use std::os::unix::io::AsRawFd;
+
+    // Not truncated here: the holder's record must survive a failed attempt
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)?;
+    // SAFETY: the descriptor belongs to `file`, which outlives the call
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } == 0 {
+        return Ok(Some(file));
+    }
+    let error = io::Error::last_os_error();
+    if error.kind() == io::ErrorKind::WouldBlock {
+        Ok(None)
+    } else {
+        Err(error)
+    }
+}
+
+/// Open the pidfile without sharing write access; `None` when another
+/// process has it open
+#[cfg(windows)]
+fn try_lock(path: &Path) -> io::Result<Option<File>> {
+    use std::os::windows::fs::OpenOptionsExt;
+
+    const FILE_SHARE_READ: u32 = 0x1;
+    const ERROR_SHARING_VIOLATION: i32 = 32;
+
+    match OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .share_mode(FILE_SHARE_READ)
+        .open(path)
+    {
+        Ok(file) => Ok(Some(file)),
+        Err(e) if e.raw_os_error() == Some(ERROR_SHARING_VIOLATION) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn try_lock(path: &Path) -> io::Result<Option<File>> {
+    OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+        .map(Some)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn info() -> InstanceInfo {
+        InstanceInfo::current(Path::new("/run/user/1000/wezterm-parallel.sock"))
+    }
+
+    #[test]
+    fn test_second_instance_is_refused_until_the_first_exits() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("runtime").join(LOCK_FILE_NAME);
+        assert_eq!(probe(&path).unwrap(), InstanceState::NotRunning);
+
+        let lock = InstanceLock::acquire(&path, &info()).unwrap();
+        assert_eq!(probe(&path).unwrap(), InstanceState::Running(Some(info())));
+
+        let error = InstanceLock::acquire(&path, &info()).unwrap_err();
+        match &error {
+            InstanceError::AlreadyRunning { holder, .. } => {
+                assert_eq!(holder.as_ref().unwrap().pid, std::process::id())
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+        assert!(error.to_string().contains("--takeover"));
+
+        // The pidfile stays behind, but without a holder it is stale
+        drop(lock);
+        assert!(path.exists());
+        assert_eq!(probe(&path).unwrap(), InstanceState::NotRunning);
+        InstanceLock::acquire(&path, &info()).unwrap();
+    }
+
+    #[test]
+    fn test_restart_args_keep_the_original_options() {
+        let mut holder = info();
+        holder.args = ["--takeover", "--pidfile", "/run/wp.pid"]
+            .map(String::from)
+            .to_vec();
+        assert_eq!(
+            holder.restart_args(),
+            ["--daemon", "--pidfile", "/run/wp.pid"].map(String::from)
+        );
+
+        // Recorded by an older version
+        let old: InstanceInfo = serde_json::from_str(
+            r#"{"pid":1,"version":"0.3.0","socket":"/tmp/wp.sock","started_at":0}"#,
+        )
+        .unwrap();
+        assert_eq!(old.restart_args(), ["--daemon".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_acquire_within_waits_for_the_holder() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(LOCK_FILE_NAME);
+        let lock = InstanceLock::acquire(&path, &info()).unwrap();
+
+        assert!(matches!(
+            InstanceLock::acquire_within(&path, &info(), Duration::from_millis(300)).await,
+            Err(InstanceError::AlreadyRunning { .. })
+        ));
+
+        let release = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            drop(lock);
+        });
+        let taken = InstanceLock::acquire_within(&path, &info(), Duration::from_secs(5))
+            .await
+            .unwrap();
+        release.await.unwrap();
+        assert_eq!(taken.path(), path);
+        drop(taken);
+        assert!(wait_until_stopped(&path, Duration::from_millis(300))
+            .await
+            .unwrap());
+    }
+
+    #[test]
+    fn test_detached_output_goes_to_log_and_pidfile_is_removed() {
+        let dir = TempDir::new().unwrap();
+        let log_file = dir.path().join("logs").join("daemon.log");
+        let args = ["-c".to_string(), "echo out; echo err >&2".to_string()];
+
+        let mut child = spawn_detached(Path::new("sh"), &args, Some(&log_file)).unwrap();
+        assert!(child.wait().unwrap().success());
+        let log = fs::read_to_string(&log_file).unwrap();
+        assert!(log.contains("out") && log.contains("err"));
+
+        let pidfile_path = dir.path().join(PID_FILE_NAME);
+        let pidfile = PidFile::create(&pidfile_path).unwrap();
+        assert_eq!(
+            fs::read_to_string(pidfile.path()).unwrap().trim(),
+            std::process::id().to_string()
+        );
+        drop(pidfile);
+        assert!(!pidfile_path.exists());
+    }
+}
@@ -0,0 +1,104 @@
+// WezTerm Multi-Process Development Framework - Board Requests
+// Task board export and import
+
+use super::misrouted;
+use crate::dashboard::{BoardDocument, BoardFormat, TaskBoardManager};
+use crate::ipc::{ErrorCode, Handler, IpcError, PipelineFuture, RequestContext};
+use crate::logging::LogContext;
+use crate::{log_info, Message};
+use std::sync::Arc;
+
+/// Task board export and import
+pub struct BoardHandler {
+    pub task_board: Option<Arc<TaskBoardManager>>,
+}
+
+impl BoardHandler {
+    async fn execute(&self, message: Message) -> Result<Message, IpcError> {
+        let task_board = self.task_board.as_ref().ok_or_else(|| {
+            IpcError::new(
+                ErrorCode::Unavailable,
+                "task_board",
+                "Task board not available",
+            )
+        })?;
+        let response = match message {
+            Message::BoardExport { board, format } => {
+                let board = board.unwrap_or_else(|| "default".to_string());
+                let format = match format {
+                    Some(format) => parse_board_format(&format)?,
+                    None => BoardFormat::Json,
+                };
+                let export_context =
+                    LogContext::new("ipc", "board_export_request").with_entity_id(&board);
+                log_info!(export_context, "Exporting task board {}", board);
+
+                let document = task_board
+                    .export_board(&board)
+                    .await
+                    .map_err(|e| IpcError::new(ErrorCode::NotFound, "task_board", e))?;
+                let content = document
+                    .render(format)
+                    .map_err(|e| IpcError::new(ErrorCode::Internal, "task_board", e))?;
+                Message::BoardExportResponse {
+                    format: format.as_str().to_string(),
+                    content,
+                }
+            }
+            Message::BoardImport {
+                content,
+                format,
+                dry_run,
+            } => {
+                let format = format.as_deref().map(parse_board_format).transpose()?;
+                let document = BoardDocument::parse(&content, format).map_err(|e| {
+                    IpcError::new(
+                        ErrorCode::InvalidRequest,
+                        "task_board",
+                        format!("Invalid board document: {e}"),
+                    )
+                })?;
+                let import_context = LogContext::new("ipc", "board_import_request")
+                    .with_entity_id(&document.board.id)
+                    .with_metadata("tasks", serde_json::json!(document.tasks.len()))
+                    .with_metadata("dry_run", serde_json::json!(dry_run));
+                log_info!(import_context, "Importing task board {}", document.board.id);
+
+                let report = task_board
+                    .import_board(document, dry_run)
+                    .await
+                    .map_err(|e| IpcError::new(ErrorCode::InvalidRequest, "task_board", e))?;
+                Message::BoardImportResponse { report }
+            }
+            other => return Err(misrouted(self.name(), &other)),
+        };
+        Ok(response)
+    }
+}
+
+impl Handler for BoardHandler {
+    fn name(&self) -> &'static str {
+        "board"
+    }
+
+    fn handles(&self, message: &Message) -> bool {
+        matches!(
+            message,
+            Message::BoardExport { .. } | Message::BoardImport { .. }
+        )
+    }
+
+    fn handle<'a>(
+        &'a self,
+        message: Message,
+        _request: &'a RequestContext,
+    ) -> PipelineFuture<'a, Result<Message, IpcError>> {
+        Box::pin(self.execute(message))
+    }
+}
+
+fn parse_board_format(format: &str) -> Result<BoardFormat, IpcError> {
+    format
+        .parse()
+        .map_err(|e: String| IpcError::new(ErrorCode::InvalidRequest, "task_board", e))
+}
@@ -0,0 +1,87 @@
+// WezTerm Multi-Process Development Framework - Federation Requests
+// Federation peers and operations proxied to them
+
+use super::misrouted;
+use crate::federation::Federation;
+use crate::ipc::{ErrorCode, Handler, IpcError, PipelineFuture, RequestContext};
+use crate::logging::LogContext;
+use crate::{log_info, Message};
+use std::sync::Arc;
+
+/// Federation peers and operations proxied to them
+pub struct FederationHandler {
+    pub federation: Arc<Federation>,
+}
+
+impl FederationHandler {
+    async fn execute(
+        &self,
+        message: Message,
+        confirm: Option<String>,
+    ) -> Result<Message, IpcError> {
+        let response = match message {
+            Message::PeerList => Message::PeerListResponse {
+                peers: self.federation.peers().await,
+            },
+            Message::PeerRegister { peer } => {
+                let name = peer.name.clone();
+                let status = self
+                    .federation
+                    .register(peer)
+                    .await
+                    .map_err(|e| IpcError::new(ErrorCode::InvalidRequest, "federation", e))?;
+                let peer_context = LogContext::new("ipc", "peer_register")
+                    .with_metadata("peer", serde_json::json!(name))
+                    .with_metadata("reachable", serde_json::json!(status.reachable));
+                log_info!(peer_context, "Federation peer {} registered", name);
+                Message::PeerListResponse {
+                    peers: self.federation.peers().await,
+                }
+            }
+            Message::PeerRemove { name } => {
+                if !self.federation.remove(&name).await {
+                    return Err(IpcError::new(
+                        ErrorCode::NotFound,
+                        "federation",
+                        format!("Peer '{name}' is not registered"),
+                    ));
+                }
+                let peer_context = LogContext::new("ipc", "peer_remove")
+                    .with_metadata("peer", serde_json::json!(name));
+                log_info!(peer_context, "Federation peer {} removed", name);
+                Message::PeerListResponse {
+                    peers: self.federation.peers().await,
+                }
+            }
+            Message::PeerForward { peer, message } => {
+                self.federation.forward(&peer, *message, confirm).await?
+            }
+            other => return Err(misrouted(self.name(), &other)),
+        };
+        Ok(response)
+    }
+}
+
+impl Handler for FederationHandler {
+    fn name(&self) -> &'static str {
+        "federation"
+    }
+
+    fn handles(&self, message: &Message) -> bool {
+        matches!(
+            message,
+            Message::PeerList
+                | Message::PeerRegister { .. }
+                | Message::PeerRemove { .. }
+                | Message::PeerForward { .. }
+        )
+    }
+
+    fn handle<'a>(
+        &'a self,
+        message: Message,
+        request: &'a RequestContext,
+    ) -> PipelineFuture<'a, Result<Message, IpcError>> {
+        Box::pin(self.execute(message, request.confirm.clone()))
+    }
+}
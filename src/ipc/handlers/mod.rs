@@ -0,0 +1,47 @@
+// WezTerm Multi-Process Development Framework - IPC Handlers
+// The per-family handlers at the end of the daemon's request pipeline
+//
+// Each handler executes one family of messages (see `Handler::handles`);
+// the pipeline routes every request that passes the middleware to the first
+// handler claiming it.
+
+mod board;
+mod federation;
+mod process;
+mod session;
+mod snapshot;
+mod sync;
+mod system;
+mod task;
+mod template;
+mod workspace;
+
+pub use board::BoardHandler;
+pub use federation::FederationHandler;
+pub use process::ProcessHandler;
+pub use session::SessionHandler;
+pub use snapshot::SnapshotHandler;
+pub use sync::SyncHandler;
+pub use system::{collect_usage_totals, SystemHandler};
+pub use task::TaskHandler;
+pub use template::TemplateHandler;
+pub use workspace::WorkspaceHandler;
+
+use super::{ErrorCode, IpcError};
+use crate::{auth, Message};
+
+/// Trigger of subsystems started by an IPC request
+const IPC_TRIGGER: &str = "ipc";
+
+/// Error for a message routed to a handler that does not execute it
+fn misrouted(handler: &str, message: &Message) -> IpcError {
+    IpcError::new(
+        ErrorCode::Internal,
+        "system",
+        format!(
+            "{} handler cannot execute {}",
+            handler,
+            auth::operation_name(message)
+        ),
+    )
+}
@@ -0,0 +1,314 @@
+// WezTerm Multi-Process Development Framework - Process Requests
+// Process spawn and listing requests
+
+use super::misrouted;
+use crate::ipc::{ErrorCode, Handler, IpcError, PipelineFuture, RequestContext};
+use crate::logging::LogContext;
+use crate::process::{tail, ProcessManager, ProcessSpec, TerminalSize};
+use crate::room::{
+    state::{ProcessInfo, ProcessStatus},
+    WorkspaceManager,
+};
+use crate::{log_error, log_info, Message, ProcessSummary};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Process spawn and listing requests
+pub struct ProcessHandler {
+    pub workspace_manager: Arc<WorkspaceManager>,
+}
+
+impl ProcessHandler {
+    async fn execute(&self, message: Message) -> Result<Message, IpcError> {
+        let response = match message {
+            Message::ProcessSpawn { workspace, command } => {
+                let spawn_context = LogContext::new("ipc", "process_spawn_request")
+                    .with_entity_id(&workspace)
+                    .with_metadata("command", serde_json::json!(command));
+                log_info!(
+                    spawn_context,
+                    "Spawning process in workspace '{}': {}",
+                    workspace,
+                    command
+                );
+
+                if self.workspace_manager.is_draining(&workspace).await {
+                    return Err(IpcError::new(
+                        ErrorCode::Unavailable,
+                        "process_manager",
+                        format!(
+                            "Workspace '{workspace}' is draining: no new processes are started"
+                        ),
+                    ));
+                }
+
+                // Check if workspace exists
+                if self
+                    .workspace_manager
+                    .get_workspace_info(&workspace)
+                    .await
+                    .is_some()
+                {
+                    let spec = ProcessSpec::from_command_line(&command).ok_or_else(|| {
+                        IpcError::new(
+                            ErrorCode::InvalidRequest,
+                            "process_manager",
+                            "Command is required",
+                        )
+                    })?;
+                    let process_id = format!(
+                        "{}-{}-{}",
+                        workspace,
+                        process_name(&spec.program),
+                        uuid::Uuid::new_v4().simple()
+                    );
+                    self.process_manager()?
+                        .spawn_command(process_id.clone(), workspace.clone(), spec)
+                        .await
+                        .map_err(|e| {
+                            IpcError::new(ErrorCode::InvalidRequest, "process_manager", e)
+                        })?;
+                    let now = std::time::SystemTime::now();
+                    let info = ProcessInfo {
+                        id: process_id.clone(),
+                        command: command.clone(),
+                        workspace: workspace.clone(),
+                        pane_id: None,
+                        status: ProcessStatus::Starting,
+                        pid: None,
+                        started_at: now,
+                        last_heartbeat: now,
+                        restart_count: 0,
+                        context: None,
+                    };
+                    let _ = self
+                        .workspace_manager
+                        .update_workspace_state(&workspace, |state| {
+                            state.processes.insert(process_id.clone(), info);
+                        })
+                        .await;
+                    Message::StatusUpdate {
+                        process_id,
+                        status: format!("Process '{command}' spawned in workspace '{workspace}'"),
+                    }
+                } else {
+                    let not_found_context =
+                        LogContext::new("ipc", "workspace_not_found").with_entity_id(&workspace);
+                    log_error!(
+                        not_found_context,
+                        "Workspace '{}' not found for process spawning",
+                        workspace
+                    );
+                    return Err(IpcError::new(
+                        ErrorCode::NotFound,
+                        "process_manager",
+                        format!("Failed to spawn process: workspace '{workspace}' not found"),
+                    ));
+                }
+            }
+            Message::ProcessList { workspace } => {
+                let list_context = LogContext::new("ipc", "process_list_request");
+                log_info!(list_context, "Listing processes");
+
+                let mut processes: Vec<ProcessSummary> = self
+                    .workspace_manager
+                    .list_workspace_states()
+                    .await
+                    .into_iter()
+                    .filter(|w| workspace.as_ref().map_or(true, |name| &w.name == name))
+                    .flat_map(|w| w.processes.into_values())
+                    .map(ProcessSummary::from)
+                    .collect();
+                processes.sort_by(|a, b| (&a.workspace, &a.id).cmp(&(&b.workspace, &b.id)));
+
+                Message::ProcessListResponse { processes }
+            }
+            Message::ProcessEnv { process_id } => {
+                let environment = self
+                    .process_manager()?
+                    .get_environment(&process_id)
+                    .await
+                    .ok_or_else(|| process_not_found(&process_id))?;
+                Message::ProcessEnvResponse { environment }
+            }
+            Message::ProcessEnvSet {
+                process_id,
+                set,
+                unset,
+                reset,
+            } => {
+                let process_manager = self.process_manager()?;
+                if process_manager
+                    .get_process_info(&process_id)
+                    .await
+                    .is_none()
+                {
+                    return Err(process_not_found(&process_id));
+                }
+                let environment = process_manager
+                    .update_environment(&process_id, set, unset, reset)
+                    .await
+                    .map_err(|e| IpcError::new(ErrorCode::InvalidRequest, "process_manager", e))?;
+                Message::ProcessEnvResponse { environment }
+            }
+            Message::ProcessLogsGet {
+                process_id,
+                since,
+                limit,
+            } => {
+                let output = self
+                    .process_manager()?
+                    .get_output(&process_id, since, limit)
+                    .await
+                    .ok_or_else(|| process_not_found(&process_id))?;
+                Message::ProcessLogsResponse { process_id, output }
+            }
+            Message::ProcessLogTail {
+                process_id,
+                filter,
+                cursor,
+                limit,
+            } => {
+                let path = self
+                    .workspace_manager
+                    .process_log_path(&process_id)
+                    .await
+                    .ok_or_else(|| process_not_found(&process_id))?;
+                let chunk = tail::read(&path, cursor, &filter, limit)
+                    .map_err(|e| IpcError::new(ErrorCode::InvalidRequest, "process_manager", e))?;
+                Message::ProcessLogTailResponse { process_id, chunk }
+            }
+            Message::ProcessQuarantineList => Message::ProcessQuarantineResponse {
+                quarantined: self.process_manager()?.quarantined().await,
+            },
+            Message::ProcessCircuitReset { process_id } => {
+                let process_manager = self.process_manager()?;
+                process_manager
+                    .reset_circuit_breaker(&process_id)
+                    .await
+                    .map_err(|e| IpcError::new(ErrorCode::InvalidRequest, "process_manager", e))?;
+                Message::ProcessQuarantineResponse {
+                    quarantined: process_manager.quarantined().await,
+                }
+            }
+            Message::ProcessInput { process_id, data } => {
+                let process_manager = self.process_manager()?;
+                if process_manager
+                    .get_process_info(&process_id)
+                    .await
+                    .is_none()
+                {
+                    return Err(process_not_found(&process_id));
+                }
+                process_manager
+                    .write_input(&process_id, data.as_bytes())
+                    .await
+                    .map_err(|e| IpcError::new(ErrorCode::InvalidRequest, "process_manager", e))?;
+                Message::ProcessInputResponse {
+                    process_id,
+                    bytes: data.len(),
+                }
+            }
+            Message::ProcessStdinWrite {
+                process_id,
+                data,
+                close,
+            } => {
+                let process_manager = self.process_manager()?;
+                if process_manager
+                    .get_process_info(&process_id)
+                    .await
+                    .is_none()
+                {
+                    return Err(process_not_found(&process_id));
+                }
+                process_manager
+                    .write_stdin(&process_id, data.as_bytes(), close)
+                    .await
+                    .map_err(|e| IpcError::new(ErrorCode::InvalidRequest, "process_manager", e))?;
+                Message::ProcessInputResponse {
+                    process_id,
+                    bytes: data.len(),
+                }
+            }
+            Message::ProcessResize {
+                process_id,
+                rows,
+                cols,
+            } => {
+                let process_manager = self.process_manager()?;
+                if process_manager
+                    .get_process_info(&process_id)
+                    .await
+                    .is_none()
+                {
+                    return Err(process_not_found(&process_id));
+                }
+                let size = process_manager
+                    .resize_terminal(&process_id, TerminalSize { rows, cols })
+                    .await
+                    .map_err(|e| IpcError::new(ErrorCode::InvalidRequest, "process_manager", e))?;
+                Message::ProcessResizeResponse { process_id, size }
+            }
+            other => return Err(misrouted(self.name(), &other)),
+        };
+        Ok(response)
+    }
+
+    fn process_manager(&self) -> Result<Arc<ProcessManager>, IpcError> {
+        self.workspace_manager.process_manager().ok_or_else(|| {
+            IpcError::new(
+                ErrorCode::Unavailable,
+                "process_manager",
+                "Process manager not available",
+            )
+        })
+    }
+}
+
+/// File name of a program, for the IDs of processes spawned over IPC
+fn process_name(program: &str) -> &str {
+    Path::new(program)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(program)
+}
+
+fn process_not_found(process_id: &str) -> IpcError {
+    IpcError::new(
+        ErrorCode::NotFound,
+        "process_manager",
+        format!("Process '{process_id}' not found"),
+    )
+}
+
+impl Handler for ProcessHandler {
+    fn name(&self) -> &'static str {
+        "process"
+    }
+
+    fn handles(&self, message: &Message) -> bool {
+        matches!(
+            message,
+            Message::ProcessSpawn { .. }
+                | Message::ProcessList { .. }
+                | Message::ProcessEnv { .. }
+                | Message::ProcessEnvSet { .. }
+                | Message::ProcessLogsGet { .. }
+                | Message::ProcessLogTail { .. }
+                | Message::ProcessQuarantineList
+                | Message::ProcessCircuitReset { .. }
+                | Message::ProcessInput { .. }
+                | Message::ProcessStdinWrite { .. }
+                | Message::ProcessResize { .. }
+        )
+    }
+
+    fn handle<'a>(
+        &'a self,
+        message: Message,
+        _request: &'a RequestContext,
+    ) -> PipelineFuture<'a, Result<Message, IpcError>> {
+        Box::pin(self.execute(message))
+    }
+}
@@ -0,0 +1,209 @@
+// WezTerm Multi-Process Development Framework - Session Requests
+// Tracking session tags, notes, exports, timelines and history imports
+
+use super::misrouted;
+use crate::ipc::{ErrorCode, Handler, IpcError, PipelineFuture, RequestContext};
+use crate::logging::LogContext;
+use crate::task::{import, ImportFormat, TaskManager, TimelineFormat};
+use crate::{log_info, log_warn, Message};
+use std::sync::Arc;
+
+/// Start of a timeline export without `--from`: the last 7 days
+const DEFAULT_TIMELINE_FROM: &str = "6d";
+
+/// Tracking session tags, notes, exports, timelines and history imports
+pub struct SessionHandler {
+    pub task_manager: Arc<TaskManager>,
+}
+
+impl SessionHandler {
+    async fn execute(&self, message: Message) -> Result<Message, IpcError> {
+        let response = match message {
+            Message::SessionTag {
+                task_id,
+                started_at,
+                tags,
+            } => {
+                let task_id = self.task_manager.resolve_task_id(&task_id).await;
+                let tag_context = LogContext::new("ipc", "session_tag_request")
+                    .with_entity_id(&task_id)
+                    .with_metadata("tags", serde_json::json!(tags));
+                log_info!(tag_context, "Tagging session of task {}", task_id);
+                let found = self
+                    .task_manager
+                    .get_tracker()
+                    .tag_session(&task_id, started_at, &tags)
+                    .await;
+                session_status_response(&task_id, "tagged", found)?
+            }
+            Message::SessionUntag {
+                task_id,
+                started_at,
+                tags,
+            } => {
+                let task_id = self.task_manager.resolve_task_id(&task_id).await;
+                let untag_context = LogContext::new("ipc", "session_untag_request")
+                    .with_entity_id(&task_id)
+                    .with_metadata("tags", serde_json::json!(tags));
+                log_info!(
+                    untag_context,
+                    "Removing tags from session of task {}",
+                    task_id
+                );
+                let found = self
+                    .task_manager
+                    .get_tracker()
+                    .untag_session(&task_id, started_at, &tags)
+                    .await;
+                session_status_response(&task_id, "untagged", found)?
+            }
+            Message::SessionAnnotate {
+                task_id,
+                started_at,
+                note,
+            } => {
+                let task_id = self.task_manager.resolve_task_id(&task_id).await;
+                let annotate_context =
+                    LogContext::new("ipc", "session_annotate_request").with_entity_id(&task_id);
+                log_info!(annotate_context, "Annotating session of task {}", task_id);
+                let found = self
+                    .task_manager
+                    .get_tracker()
+                    .annotate_session(&task_id, started_at, &note)
+                    .await;
+                session_status_response(&task_id, "annotated", found)?
+            }
+            Message::SessionExport { format, tag } => {
+                let exported = match format.parse() {
+                    Ok(export_format) => {
+                        self.task_manager
+                            .get_tracker()
+                            .export_sessions(export_format, tag.as_deref())
+                            .await
+                    }
+                    Err(e) => Err(e),
+                };
+
+                match exported {
+                    Ok(content) => Message::SessionExportResponse { format, content },
+                    Err(e) => {
+                        let export_error_context = LogContext::new("ipc", "session_export_error");
+                        log_warn!(export_error_context, "Session export failed: {}", e);
+                        return Err(IpcError::new(
+                            ErrorCode::InvalidRequest,
+                            "task_tracker",
+                            format!("Session export failed: {e}"),
+                        ));
+                    }
+                }
+            }
+            Message::ProductivityExport { format, from, to } => {
+                let from = from.unwrap_or_else(|| DEFAULT_TIMELINE_FROM.to_string());
+                let to = to.unwrap_or_else(|| "today".to_string());
+                let export_context = LogContext::new("ipc", "productivity_export_request")
+                    .with_metadata("format", serde_json::json!(format));
+                log_info!(export_context, "Exporting timeline from {} to {}", from, to);
+
+                let rendered = match format.parse::<TimelineFormat>() {
+                    Ok(timeline_format) => self
+                        .task_manager
+                        .timeline(&from, &to)
+                        .await
+                        .render(timeline_format, self.task_manager.get_tracker().timezone()),
+                    Err(e) => Err(e),
+                };
+                match rendered {
+                    Ok(content) => Message::ProductivityExportResponse { format, content },
+                    Err(e) => {
+                        return Err(IpcError::new(
+                            ErrorCode::InvalidRequest,
+                            "task_tracker",
+                            format!("Timeline export failed: {e}"),
+                        ));
+                    }
+                }
+            }
+            Message::HistoryImport {
+                content,
+                format,
+                source,
+                dry_run,
+            } => {
+                let source = source.unwrap_or_else(|| "import".to_string());
+                let import_context = LogContext::new("ipc", "history_import_request")
+                    .with_metadata("source", serde_json::json!(source))
+                    .with_metadata("dry_run", serde_json::json!(dry_run));
+                log_info!(import_context, "Importing history from {}", source);
+
+                let parsed = match format {
+                    Some(format) => format.parse(),
+                    None => Ok(ImportFormat::detect(&content)),
+                }
+                .and_then(|format| {
+                    import::parse(
+                        &content,
+                        format,
+                        &source,
+                        self.task_manager.get_tracker().timezone(),
+                    )
+                });
+                let history = parsed.map_err(|e| {
+                    IpcError::new(
+                        ErrorCode::InvalidRequest,
+                        "task_tracker",
+                        format!("History import failed: {e}"),
+                    )
+                })?;
+                let report = self
+                    .task_manager
+                    .import_history(history, &source, dry_run)
+                    .await;
+                Message::HistoryImportResponse { report }
+            }
+            other => return Err(misrouted(self.name(), &other)),
+        };
+        Ok(response)
+    }
+}
+
+impl Handler for SessionHandler {
+    fn name(&self) -> &'static str {
+        "session"
+    }
+
+    fn handles(&self, message: &Message) -> bool {
+        matches!(
+            message,
+            Message::SessionTag { .. }
+                | Message::SessionUntag { .. }
+                | Message::SessionAnnotate { .. }
+                | Message::SessionExport { .. }
+                | Message::ProductivityExport { .. }
+                | Message::HistoryImport { .. }
+        )
+    }
+
+    fn handle<'a>(
+        &'a self,
+        message: Message,
+        _request: &'a RequestContext,
+    ) -> PipelineFuture<'a, Result<Message, IpcError>> {
+        Box::pin(self.execute(message))
+    }
+}
+
+/// Build the reply for a session tag or note request
+fn session_status_response(task_id: &str, action: &str, found: bool) -> Result<Message, IpcError> {
+    if found {
+        Ok(Message::StatusUpdate {
+            process_id: "task_tracker".to_string(),
+            status: format!("Session of task {task_id} {action}"),
+        })
+    } else {
+        Err(IpcError::new(
+            ErrorCode::NotFound,
+            "task_tracker",
+            format!("No matching tracking session for task {task_id}"),
+        ))
+    }
+}
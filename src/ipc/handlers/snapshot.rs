@@ -0,0 +1,76 @@
+// WezTerm Multi-Process Development Framework - Snapshot Requests
+// Whole-session snapshots and restores
+
+use super::misrouted;
+use crate::ipc::{ErrorCode, Handler, IpcError, PipelineFuture, RequestContext};
+use crate::logging::LogContext;
+use crate::room::{SessionSnapshot, WorkspaceManager};
+use crate::task::TaskManager;
+use crate::{log_info, Message};
+use std::sync::Arc;
+
+/// Whole-session snapshots and restores
+pub struct SnapshotHandler {
+    pub workspace_manager: Arc<WorkspaceManager>,
+    pub task_manager: Arc<TaskManager>,
+}
+
+impl SnapshotHandler {
+    async fn execute(&self, message: Message) -> Result<Message, IpcError> {
+        let response = match message {
+            Message::SessionSnapshot => {
+                let snapshot =
+                    SessionSnapshot::capture(&self.workspace_manager, &self.task_manager).await;
+                let snapshot_context = LogContext::new("ipc", "session_snapshot_request")
+                    .with_metadata("workspaces", serde_json::json!(snapshot.workspaces.len()))
+                    .with_metadata("tasks", serde_json::json!(snapshot.tasks.len()));
+                log_info!(snapshot_context, "Taking session snapshot");
+                let content = snapshot
+                    .render()
+                    .map_err(|e| IpcError::new(ErrorCode::Internal, "session_snapshot", e))?;
+                Message::SessionSnapshotResponse { content }
+            }
+            Message::SessionRestore { content, dry_run } => {
+                let snapshot = SessionSnapshot::parse(&content).map_err(|e| {
+                    IpcError::new(
+                        ErrorCode::InvalidRequest,
+                        "session_snapshot",
+                        format!("Invalid session snapshot: {e}"),
+                    )
+                })?;
+                let restore_context = LogContext::new("ipc", "session_restore_request")
+                    .with_metadata("workspaces", serde_json::json!(snapshot.workspaces.len()))
+                    .with_metadata("tasks", serde_json::json!(snapshot.tasks.len()))
+                    .with_metadata("dry_run", serde_json::json!(dry_run));
+                log_info!(restore_context, "Restoring session snapshot");
+                let report = snapshot
+                    .restore(&self.workspace_manager, &self.task_manager, dry_run)
+                    .await;
+                Message::SessionRestoreResponse { report }
+            }
+            other => return Err(misrouted(self.name(), &other)),
+        };
+        Ok(response)
+    }
+}
+
+impl Handler for SnapshotHandler {
+    fn name(&self) -> &'static str {
+        "snapshot"
+    }
+
+    fn handles(&self, message: &Message) -> bool {
+        matches!(
+            message,
+            Message::SessionSnapshot | Message::SessionRestore { .. }
+        )
+    }
+
+    fn handle<'a>(
+        &'a self,
+        message: Message,
+        _request: &'a RequestContext,
+    ) -> PipelineFuture<'a, Result<Message, IpcError>> {
+        Box::pin(self.execute(message))
+    }
+}
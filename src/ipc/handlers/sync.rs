@@ -0,0 +1,139 @@
+// WezTerm Multi-Process Development Framework - Sync Requests
+// File sync conflict requests
+
+use super::{misrouted, IPC_TRIGGER};
+use crate::ipc::{ErrorCode, Handler, IpcError, PipelineFuture, RequestContext};
+use crate::performance::lazy::LazySubsystem;
+use crate::sync::{DiffTarget, FileSyncManager};
+use crate::Message;
+use std::sync::Arc;
+
+/// File sync conflict requests
+pub struct SyncHandler {
+    pub file_sync_manager: Arc<tokio::sync::Mutex<FileSyncManager>>,
+
+    /// File watching, started by the first sync request when still held back
+    pub file_sync: Option<Arc<LazySubsystem>>,
+}
+
+impl SyncHandler {
+    async fn execute(&self, message: Message, resolved_by: &str) -> Result<Message, IpcError> {
+        if let Some(ref file_sync) = self.file_sync {
+            file_sync.activate(IPC_TRIGGER).await;
+        }
+        let response = match message {
+            Message::SyncConflictList => {
+                let sync_manager = self.file_sync_manager.lock().await;
+                Message::SyncConflictListResponse {
+                    conflicts: sync_manager.list_conflicts().to_vec(),
+                }
+            }
+            Message::SyncResolve {
+                conflict_id,
+                strategy,
+                content,
+            } => {
+                let mut sync_manager = self.file_sync_manager.lock().await;
+                let exists = sync_manager
+                    .list_conflicts()
+                    .iter()
+                    .any(|conflict| conflict.id == conflict_id);
+                if !exists {
+                    return Err(IpcError::new(
+                        ErrorCode::NotFound,
+                        "file_sync",
+                        format!("Conflict '{conflict_id}' not found"),
+                    ));
+                }
+                match sync_manager.resolve_conflict(&conflict_id, strategy, content, resolved_by) {
+                    Ok(resolution) => Message::SyncResolveResponse { resolution },
+                    Err(e) => {
+                        return Err(IpcError::new(
+                            ErrorCode::InvalidRequest,
+                            "file_sync",
+                            e.to_string(),
+                        ))
+                    }
+                }
+            }
+            Message::SyncDiff { target, options } => {
+                let sync_manager = self.file_sync_manager.lock().await;
+                if let DiffTarget::Conflict { ref conflict_id } = target {
+                    if !sync_manager
+                        .list_conflicts()
+                        .iter()
+                        .any(|conflict| &conflict.id == conflict_id)
+                    {
+                        return Err(IpcError::new(
+                            ErrorCode::NotFound,
+                            "file_sync",
+                            format!("Conflict '{conflict_id}' not found"),
+                        ));
+                    }
+                }
+                match sync_manager.diff(&target, &options) {
+                    Ok(diffs) => Message::SyncDiffResponse { diffs },
+                    Err(e) => {
+                        return Err(IpcError::new(
+                            ErrorCode::InvalidRequest,
+                            "file_sync",
+                            e.to_string(),
+                        ))
+                    }
+                }
+            }
+            Message::SyncWatchList => Message::SyncWatchListResponse {
+                workspaces: self.file_sync_manager.lock().await.watch_status(),
+            },
+            Message::SyncWatchSet { workspace, enabled } => {
+                let mut sync_manager = self.file_sync_manager.lock().await;
+                if let Err(e) = sync_manager.set_workspace_enabled(&workspace, enabled) {
+                    let code = if sync_manager
+                        .watch_status()
+                        .iter()
+                        .any(|status| status.workspace == workspace)
+                    {
+                        ErrorCode::Internal
+                    } else {
+                        ErrorCode::NotFound
+                    };
+                    return Err(IpcError::new(code, "file_sync", e.to_string()));
+                }
+                Message::SyncWatchListResponse {
+                    workspaces: sync_manager.watch_status(),
+                }
+            }
+            other => return Err(misrouted(self.name(), &other)),
+        };
+        Ok(response)
+    }
+}
+
+impl Handler for SyncHandler {
+    fn name(&self) -> &'static str {
+        "sync"
+    }
+
+    fn handles(&self, message: &Message) -> bool {
+        matches!(
+            message,
+            Message::SyncConflictList
+                | Message::SyncResolve { .. }
+                | Message::SyncDiff { .. }
+                | Message::SyncWatchList
+                | Message::SyncWatchSet { .. }
+        )
+    }
+
+    fn handle<'a>(
+        &'a self,
+        message: Message,
+        request: &'a RequestContext,
+    ) -> PipelineFuture<'a, Result<Message, IpcError>> {
+        let resolved_by = request
+            .identity
+            .as_ref()
+            .map_or("ipc", |identity| identity.name.as_str());
+        Box::pin(self.execute(message, resolved_by))
+    }
+}
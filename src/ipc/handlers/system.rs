@@ -0,0 +1,426 @@
+// WezTerm Multi-Process Development Framework - System Requests
+// Ping, status, maintenance, focus, logging, telemetry and upgrade requests
+
+use super::{misrouted, IPC_TRIGGER};
+use crate::audit::AuditJournal;
+use crate::focus::FocusController;
+use crate::ipc::{self, ErrorCode, Handler, IpcError, PipelineFuture, RequestContext};
+use crate::logging::{buffer as log_buffer, levels as log_levels, LogContext};
+use crate::maintenance::MaintenanceController;
+use crate::monitoring::{report, AnalyticsFormat, MonitoringManager, ShedLog, TimeRange};
+use crate::room::{SessionSnapshot, WorkspaceManager};
+use crate::task::TaskManager;
+use crate::telemetry::{Telemetry, UsageTotals};
+use crate::upgrade::{self, HandoffState, UpgradeHandoff};
+use crate::{log_error, log_info, Message, SystemStatusInfo};
+use std::env;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Time a successor started by `self-upgrade` has to report ready
+const UPGRADE_READY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Time a ready successor has to answer on the IPC socket before the
+/// previous instance rolls the upgrade back
+const UPGRADE_HEALTH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Ping, status, maintenance, focus, logging, telemetry and upgrade requests
+pub struct SystemHandler {
+    /// Time the server started, reported by `SystemStatus`
+    pub started_at: Instant,
+    pub workspace_manager: Arc<WorkspaceManager>,
+    pub task_manager: Arc<TaskManager>,
+    pub template_engine: Arc<tokio::sync::Mutex<crate::room::template::TemplateEngine>>,
+    pub maintenance: Arc<MaintenanceController>,
+    pub focus: Arc<FocusController>,
+    pub telemetry: Arc<Telemetry>,
+    pub shed_log: Arc<ShedLog>,
+    pub audit_journal: Option<Arc<AuditJournal>>,
+    pub monitoring: Arc<MonitoringManager>,
+    pub shutdown_requested: Arc<tokio::sync::Notify>,
+}
+
+impl SystemHandler {
+    async fn execute(&self, message: Message) -> Result<Message, IpcError> {
+        let response = match message {
+            Message::Ping => {
+                let ping_context = LogContext::new("ipc", "ping_receive");
+                log_info!(ping_context, "Ping received, responding with Pong");
+                Message::Pong
+            }
+            Message::SystemStatus => {
+                let workspaces = self.workspace_manager.list_workspace_states().await;
+                let stats = self.task_manager.get_stats().await;
+
+                Message::SystemStatusResponse {
+                    status: SystemStatusInfo {
+                        version: env!("CARGO_PKG_VERSION").to_string(),
+                        uptime_secs: self.started_at.elapsed().as_secs(),
+                        active_workspace: workspaces
+                            .iter()
+                            .find(|w| w.is_active)
+                            .map(|w| w.name.clone()),
+                        workspace_count: workspaces.len(),
+                        process_count: workspaces.iter().map(|w| w.processes.len()).sum(),
+                        active_tasks: stats.active_tasks,
+                        queued_tasks: stats.queued_tasks,
+                        completed_tasks: stats.completed_tasks,
+                        failed_tasks: stats.failed_tasks,
+                        maintenance: self.maintenance.status().await.enabled,
+                    },
+                }
+            }
+            Message::TelemetryPreview => {
+                let totals = collect_usage_totals(
+                    &self.workspace_manager,
+                    &self.task_manager,
+                    &self.template_engine,
+                )
+                .await;
+                let payload = self
+                    .telemetry
+                    .report(totals)
+                    .to_payload()
+                    .unwrap_or_else(|e| serde_json::json!({ "error": e }));
+                Message::TelemetryPreviewResponse {
+                    enabled: self.telemetry.config().enabled,
+                    endpoint: self.telemetry.config().endpoint.clone(),
+                    payload,
+                }
+            }
+            Message::MaintenanceSet { enabled, reason } => {
+                let maintenance_context = LogContext::new("ipc", "maintenance_set_request")
+                    .with_metadata("enabled", serde_json::json!(enabled));
+                log_info!(
+                    maintenance_context,
+                    "Setting maintenance mode: {}",
+                    if enabled { "on" } else { "off" }
+                );
+
+                if enabled {
+                    self.maintenance.enable(reason).await;
+                } else {
+                    self.maintenance.disable().await;
+                }
+
+                Message::MaintenanceStatusResponse {
+                    status: self.maintenance.check_drain().await,
+                }
+            }
+            Message::MaintenanceStatus => Message::MaintenanceStatusResponse {
+                status: self.maintenance.status().await,
+            },
+            Message::FocusSet { enabled, label } => {
+                let focus_context = LogContext::new("ipc", "focus_set_request")
+                    .with_metadata("enabled", serde_json::json!(enabled));
+                log_info!(
+                    focus_context,
+                    "Setting focus mode: {}",
+                    if enabled { "on" } else { "off" }
+                );
+
+                if enabled {
+                    self.focus.enable(label).await;
+                } else {
+                    self.focus.disable().await;
+                }
+
+                Message::FocusStatusResponse {
+                    status: self.focus.status().await,
+                }
+            }
+            Message::FocusStatus => Message::FocusStatusResponse {
+                status: self.focus.status().await,
+            },
+            Message::LogLevelSet { module, level } => {
+                let levels = log_levels::set_level(&module, &level)
+                    .map_err(|e| IpcError::new(ErrorCode::InvalidRequest, "logging", e))?;
+                let log_level_context = LogContext::new("ipc", "log_level_set")
+                    .with_entity_id(&module)
+                    .with_metadata("level", serde_json::json!(level));
+                log_info!(
+                    log_level_context,
+                    "Log level of '{}' set to {}",
+                    module,
+                    level
+                );
+                Message::LogLevelResponse { levels }
+            }
+            Message::LogLevelList => Message::LogLevelResponse {
+                levels: log_levels::levels(),
+            },
+            Message::LogQuery { query } => Message::LogQueryResponse {
+                result: log_buffer::query(&query)
+                    .map_err(|e| IpcError::new(ErrorCode::InvalidRequest, "logging", e))?,
+            },
+            Message::AuditQuery { filter } => match &self.audit_journal {
+                Some(journal) => Message::AuditQueryResponse {
+                    entries: journal.query(&filter),
+                },
+                None => {
+                    return Err(IpcError::new(
+                        ErrorCode::Unavailable,
+                        "audit",
+                        "The audit journal is disabled (audit.enabled: false)",
+                    ));
+                }
+            },
+            Message::SheddingReport { since_hours } => {
+                let since =
+                    since_hours.map(|hours| crate::clock::unix_secs().saturating_sub(hours * 3600));
+                Message::SheddingReportResponse {
+                    report: self.shed_log.report(since),
+                }
+            }
+            Message::AnalyticsExport {
+                format,
+                path,
+                range,
+            } => {
+                let range = range.unwrap_or_else(|| report::DEFAULT_RANGE.to_string());
+                let export_context = LogContext::new("ipc", "analytics_export_request")
+                    .with_metadata("format", serde_json::json!(format))
+                    .with_metadata("path", serde_json::json!(path));
+                log_info!(
+                    export_context,
+                    "Exporting analytics report for the last {}",
+                    range
+                );
+
+                let invalid = |e: String| {
+                    IpcError::new(
+                        ErrorCode::InvalidRequest,
+                        "analytics",
+                        format!("Analytics export failed: {e}"),
+                    )
+                };
+                let analytics_format = format.parse::<AnalyticsFormat>().map_err(invalid)?;
+                let time_range =
+                    TimeRange::last(&range, crate::clock::unix_secs()).map_err(invalid)?;
+                if path
+                    .as_deref()
+                    .is_some_and(|path| !std::path::Path::new(path).is_absolute())
+                {
+                    return Err(invalid("the path must be absolute".to_string()));
+                }
+                self.monitoring.activate_analytics(IPC_TRIGGER).await;
+                let content = self
+                    .monitoring
+                    .analytics()
+                    .generate_report(time_range)
+                    .await
+                    .render(analytics_format, self.task_manager.get_tracker().timezone())
+                    .map_err(invalid)?;
+
+                match path {
+                    Some(path) => {
+                        report::write_report(std::path::Path::new(&path), &content).map_err(
+                            |e| {
+                                IpcError::new(
+                                    ErrorCode::Internal,
+                                    "analytics",
+                                    format!("Failed to write {path}: {e}"),
+                                )
+                            },
+                        )?;
+                        Message::AnalyticsExportResponse {
+                            format,
+                            path: Some(path),
+                            content: None,
+                        }
+                    }
+                    None => Message::AnalyticsExportResponse {
+                        format,
+                        path: None,
+                        content: Some(content),
+                    },
+                }
+            }
+            Message::Upgrade { binary, sha256 } => {
+                let upgrade_context = LogContext::new("ipc", "upgrade_request")
+                    .with_metadata("binary", serde_json::json!(binary));
+                log_info!(upgrade_context, "Upgrade requested: {}", binary);
+
+                match start_upgrade(
+                    &binary,
+                    sha256.as_deref(),
+                    &self.task_manager,
+                    &self.maintenance,
+                )
+                .await
+                {
+                    Ok(successor_pid) => Message::UpgradeResponse {
+                        success: true,
+                        successor_pid: Some(successor_pid),
+                        error: None,
+                    },
+                    Err(e) => {
+                        let upgrade_error_context = LogContext::new("ipc", "upgrade_error");
+                        log_error!(upgrade_error_context, "Upgrade failed: {}", e);
+                        Message::UpgradeResponse {
+                            success: false,
+                            successor_pid: None,
+                            error: Some(e),
+                        }
+                    }
+                }
+            }
+            Message::Takeover { pid } => {
+                let snapshot =
+                    SessionSnapshot::capture(&self.workspace_manager, &self.task_manager).await;
+                let content = snapshot
+                    .render()
+                    .map_err(|e| IpcError::new(ErrorCode::Internal, "takeover", e))?;
+                let takeover_context = LogContext::new("system", "takeover")
+                    .with_metadata("successor_pid", serde_json::json!(pid))
+                    .with_metadata("workspaces", serde_json::json!(snapshot.workspaces.len()))
+                    .with_metadata("tasks", serde_json::json!(snapshot.tasks.len()));
+                log_info!(
+                    takeover_context,
+                    "Handing session over to instance pid {} and shutting down",
+                    pid
+                );
+
+                // Leave time for the response to reach the new instance
+                let shutdown_requested = Arc::clone(&self.shutdown_requested);
+                tokio::spawn(async move {
+                    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+                    shutdown_requested.notify_one();
+                });
+                Message::TakeoverResponse { snapshot: content }
+            }
+            other => return Err(misrouted(self.name(), &other)),
+        };
+        Ok(response)
+    }
+}
+
+impl Handler for SystemHandler {
+    fn name(&self) -> &'static str {
+        "system"
+    }
+
+    fn handles(&self, message: &Message) -> bool {
+        matches!(
+            message,
+            Message::Ping
+                | Message::SystemStatus
+                | Message::TelemetryPreview
+                | Message::MaintenanceSet { .. }
+                | Message::MaintenanceStatus
+                | Message::FocusSet { .. }
+                | Message::FocusStatus
+                | Message::LogLevelSet { .. }
+                | Message::LogLevelList
+                | Message::LogQuery { .. }
+                | Message::AuditQuery { .. }
+                | Message::SheddingReport { .. }
+                | Message::AnalyticsExport { .. }
+                | Message::Upgrade { .. }
+                | Message::Takeover { .. }
+        )
+    }
+
+    fn handle<'a>(
+        &'a self,
+        message: Message,
+        _request: &'a RequestContext,
+    ) -> PipelineFuture<'a, Result<Message, IpcError>> {
+        Box::pin(self.execute(message))
+    }
+}
+
+/// Aggregate counts reported by telemetry
+pub async fn collect_usage_totals(
+    workspace_manager: &WorkspaceManager,
+    task_manager: &TaskManager,
+    template_engine: &tokio::sync::Mutex<crate::room::template::TemplateEngine>,
+) -> UsageTotals {
+    UsageTotals {
+        workspaces: workspace_manager.get_workspace_count().await,
+        tasks: task_manager.get_task_count().await,
+        templates: template_engine.lock().await.list_templates().len(),
+    }
+}
+
+/// Hand this drained instance over to the staged binary
+///
+/// The successor is started immediately; a background task waits for it to
+/// report ready and answer on the IPC socket, then exits this process. If it
+/// never gets there, the successor is stopped, the previous binary restored
+/// and this instance keeps running.
+async fn start_upgrade(
+    binary: &str,
+    sha256: Option<&str>,
+    task_manager: &TaskManager,
+    maintenance: &MaintenanceController,
+) -> Result<u32, String> {
+    let status = maintenance.status().await;
+    if !status.enabled || !status.drained {
+        return Err(
+            "Upgrade requires maintenance mode with all in-flight work drained".to_string(),
+        );
+    }
+
+    task_manager
+        .save_state()
+        .await
+        .map_err(|e| format!("Failed to save task state: {e}"))?;
+
+    let current_exe = env::current_exe().map_err(|e| e.to_string())?;
+    let handoff_path = UpgradeHandoff::default_path();
+    let (mut handoff, successor_pid) = upgrade::begin_handoff(
+        Path::new(binary),
+        &current_exe,
+        &handoff_path,
+        status.reason,
+        sha256,
+    )?;
+
+    tokio::spawn(async move {
+        let error = match upgrade::wait_for_handoff(&handoff_path, UPGRADE_READY_TIMEOUT).await {
+            Some(result) if result.state == HandoffState::Ready => {
+                // The successor only takes over the socket path once this
+                // instance has exited and released the lock
+                let socket = result
+                    .successor_socket
+                    .clone()
+                    .unwrap_or_else(ipc::default_socket_path);
+                match upgrade::probe_health(&socket, UPGRADE_HEALTH_TIMEOUT).await {
+                    Ok(()) => {
+                        let handoff_context = LogContext::new("system", "upgrade_handoff_complete")
+                            .with_metadata("successor_pid", serde_json::json!(successor_pid));
+                        log_info!(
+                            handoff_context,
+                            "Handed off to version {}, exiting",
+                            result.target_version
+                        );
+                        std::process::exit(0);
+                    }
+                    Err(e) => e,
+                }
+            }
+            _ => "Successor did not become ready".to_string(),
+        };
+
+        let handoff_context = LogContext::new("system", "upgrade_handoff_failed")
+            .with_metadata("successor_pid", serde_json::json!(successor_pid));
+        log_error!(handoff_context, "{}, keeping current instance", error);
+
+        let _ = upgrade::terminate(successor_pid);
+        let mut state = HandoffState::Failed;
+        if let Some(ref backup) = handoff.previous_binary {
+            match upgrade::restore_binary(backup, &handoff.binary) {
+                Ok(()) => state = HandoffState::RolledBack,
+                Err(e) => {
+                    let restore_context = LogContext::new("system", "upgrade_restore_error");
+                    log_error!(restore_context, "{}", e);
+                }
+            }
+        }
+        let _ = handoff.transition(&handoff_path, state, Some(error));
+    });
+
+    Ok(successor_pid)
+}
@@ -0,0 +1,476 @@
+// WezTerm Multi-Process Development Framework - Task Requests
+// Task queueing, control, traces and quick notes
+
+use super::misrouted;
+use crate::github::GithubSync;
+use crate::ipc::{ErrorCode, Handler, IpcError, PipelineFuture, RequestContext};
+use crate::logging::LogContext;
+use crate::room::WorkspaceManager;
+use crate::task::TaskManager;
+use crate::{log_error, log_info, log_warn, Message};
+use std::env;
+use std::sync::Arc;
+
+/// Task queueing, control, traces and quick notes
+pub struct TaskHandler {
+    pub workspace_manager: Arc<WorkspaceManager>,
+    pub task_manager: Arc<TaskManager>,
+    pub github_sync: Arc<GithubSync>,
+}
+
+impl TaskHandler {
+    /// Active workspace new tasks go to; none are queued while it drains
+    async fn queue_workspace(
+        &self,
+    ) -> Result<Option<(String, crate::room::state::WorkspaceState)>, IpcError> {
+        let workspace = self.workspace_manager.get_active_workspace().await;
+        if let Some((workspace_name, state)) = &workspace {
+            if state.drain.is_some() {
+                return Err(IpcError::new(
+                    ErrorCode::Unavailable,
+                    "task_manager",
+                    format!("Workspace '{workspace_name}' is draining: no new tasks are queued"),
+                ));
+            }
+        }
+        Ok(workspace)
+    }
+
+    async fn execute(
+        &self,
+        message: Message,
+        request: &RequestContext,
+    ) -> Result<Message, IpcError> {
+        // Tasks are attributed to the authenticated client creating them
+        let created_by = request
+            .identity
+            .as_ref()
+            .map(|identity| identity.name.clone());
+        let response = match message {
+            Message::TaskQueue {
+                id,
+                priority,
+                command,
+                working_dir,
+                env,
+                shell,
+                timeout_secs,
+                assignee,
+            } => {
+                let queue_context = LogContext::new("ipc", "task_queue_request")
+                    .with_entity_id(&id)
+                    .with_metadata("priority", serde_json::json!(priority))
+                    .with_metadata("command", serde_json::json!(command));
+                log_info!(
+                    queue_context,
+                    "Queuing task {}: {} (priority: {})",
+                    id,
+                    command,
+                    priority
+                );
+
+                let workspace = self.queue_workspace().await?;
+                let mut task = queued_task(
+                    &command,
+                    priority,
+                    working_dir.as_deref(),
+                    env,
+                    shell,
+                    timeout_secs,
+                    workspace.as_ref(),
+                )?;
+                task.assignee = assignee;
+                task.created_by = created_by;
+
+                // Add task to task manager
+                match self.task_manager.create_task(task).await {
+                    Ok(task_id) => {
+                        // Mention the short ID when it is only an alias
+                        let short_id = self
+                            .task_manager
+                            .get_task(&task_id)
+                            .await
+                            .and_then(|task| task.short_id)
+                            .filter(|short_id| *short_id != task_id)
+                            .map(|short_id| format!(" ({short_id})"))
+                            .unwrap_or_default();
+                        let task_success_context = LogContext::new("ipc", "task_create_success")
+                            .with_entity_id(&task_id)
+                            .with_metadata("command", serde_json::json!(command));
+                        log_info!(
+                            task_success_context,
+                            "Task '{}' created successfully with ID: {}",
+                            command,
+                            task_id
+                        );
+                        Message::StatusUpdate {
+                            process_id: "task_manager".to_string(),
+                            status: format!(
+                                "Task '{command}' created successfully with ID: {task_id}{short_id}"
+                            ),
+                        }
+                    }
+                    Err(e) => {
+                        let task_error_context = LogContext::new("ipc", "task_create_error")
+                            .with_metadata("command", serde_json::json!(command));
+                        log_error!(
+                            task_error_context,
+                            "Failed to create task '{}': {:?}",
+                            command,
+                            e
+                        );
+                        return Err(IpcError::from_user_error(
+                            "task_manager",
+                            format!("Failed to create task '{command}': {e:?}"),
+                            &e.into(),
+                        ));
+                    }
+                }
+            }
+            Message::TaskQueueBatch {
+                tasks,
+                atomic,
+                depends_on,
+            } => {
+                let workspace = self.queue_workspace().await?;
+                let items = tasks
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, spec)| {
+                        let task = queued_task(
+                            &spec.command,
+                            spec.priority,
+                            spec.working_dir.as_deref(),
+                            spec.env,
+                            spec.shell,
+                            spec.timeout_secs,
+                            workspace.as_ref(),
+                        )
+                        .map(|mut task| {
+                            task.assignee = spec.assignee;
+                            task.created_by = created_by.clone();
+                            task
+                        })
+                        .map_err(|e| e.message);
+                        crate::task::BatchItem {
+                            key: spec.key.unwrap_or_else(|| index.to_string()),
+                            task,
+                            depends_on: depends_on
+                                .iter()
+                                .chain(&spec.depends_on)
+                                .cloned()
+                                .collect(),
+                        }
+                    })
+                    .collect();
+                let report = self.task_manager.create_batch(items, atomic).await;
+
+                let batch_context = LogContext::new("ipc", "task_queue_batch")
+                    .with_metadata("atomic", serde_json::json!(atomic))
+                    .with_metadata("created", serde_json::json!(report.created))
+                    .with_metadata("failed", serde_json::json!(report.failed));
+                log_info!(
+                    batch_context,
+                    "Batch queued {} tasks, {} failed",
+                    report.created,
+                    report.failed
+                );
+                Message::TaskQueueBatchResponse { report }
+            }
+            Message::TaskCancel { id } => {
+                let cancel_context =
+                    LogContext::new("ipc", "task_cancel_request").with_entity_id(&id);
+                log_info!(cancel_context, "Cancelling task {}", id);
+                task_status_response(&id, "cancelled", self.task_manager.cancel_task(&id).await)?
+            }
+            Message::TaskPause { id } => {
+                let pause_context =
+                    LogContext::new("ipc", "task_pause_request").with_entity_id(&id);
+                log_info!(pause_context, "Pausing task {}", id);
+                task_status_response(&id, "paused", self.task_manager.pause_task(&id).await)?
+            }
+            Message::TaskResume { id } => {
+                let resume_context =
+                    LogContext::new("ipc", "task_resume_request").with_entity_id(&id);
+                log_info!(resume_context, "Resuming task {}", id);
+                task_status_response(&id, "resumed", self.task_manager.resume_task(&id).await)?
+            }
+            Message::DeadLetterList => Message::DeadLetterListResponse {
+                entries: self.task_manager.dead_letters(),
+            },
+            Message::DeadLetterRequeue { id } => {
+                let requeue_context =
+                    LogContext::new("ipc", "dead_letter_requeue_request").with_entity_id(&id);
+                log_info!(requeue_context, "Requeueing dead-lettered task {}", id);
+                task_status_response(
+                    &id,
+                    "requeued",
+                    self.task_manager.requeue_dead_letter(&id).await,
+                )?
+            }
+            Message::DeadLetterPurge { id } => {
+                let purged = self.task_manager.purge_dead_letters(id.as_ref()).await;
+                let purge_context = LogContext::new("ipc", "dead_letter_purge")
+                    .with_metadata("task_id", serde_json::json!(id))
+                    .with_metadata("purged", serde_json::json!(purged));
+                log_info!(purge_context, "Purged {} dead letters", purged);
+                Message::StatusUpdate {
+                    process_id: "task_manager".to_string(),
+                    status: format!("Purged {purged} dead letters"),
+                }
+            }
+            Message::TaskTrace { id } => {
+                let trace_context =
+                    LogContext::new("ipc", "task_trace_request").with_entity_id(&id);
+                log_info!(trace_context, "Getting trace of task {}", id);
+                match self.task_manager.get_task_trace(&id).await {
+                    Some(trace) => Message::TaskTraceResponse { trace },
+                    None => {
+                        return Err(IpcError::from_user_error(
+                            "task_manager",
+                            format!("Task {id} not found"),
+                            &crate::error::UserError::task_not_found(&id),
+                        ));
+                    }
+                }
+            }
+            Message::TaskLatencyReport { since_hours } => {
+                let since =
+                    since_hours.map(|hours| crate::clock::unix_secs().saturating_sub(hours * 3600));
+                Message::TaskLatencyReportResponse {
+                    report: self.task_manager.latency_report(since).await,
+                }
+            }
+            Message::TaskResultGet { id } => {
+                let result_context =
+                    LogContext::new("ipc", "task_result_request").with_entity_id(&id);
+                log_info!(result_context, "Getting result of task {}", id);
+                match self.task_manager.get_task_result(&id).await {
+                    Some(result) => Message::TaskResultResponse { result },
+                    None => {
+                        return Err(IpcError::from_user_error(
+                            "task_manager",
+                            format!("Task {id} not found"),
+                            &crate::error::UserError::task_not_found(&id),
+                        ));
+                    }
+                }
+            }
+            Message::GithubSync => {
+                if !self.github_sync.is_enabled() {
+                    return Err(IpcError::new(
+                        ErrorCode::Unavailable,
+                        "github",
+                        "GitHub sync is not enabled: set github.enabled and github.repositories",
+                    ));
+                }
+                let sync_context = LogContext::new("ipc", "github_sync_request");
+                log_info!(sync_context, "Running GitHub sync");
+                Message::GithubSyncResponse {
+                    report: self.github_sync.sync().await,
+                }
+            }
+            Message::NoteAdd {
+                text,
+                task_id,
+                workspace,
+            } => {
+                // Blank notes are rejected by the validation middleware
+                add_quick_note(
+                    text.trim().to_string(),
+                    task_id,
+                    workspace,
+                    &self.workspace_manager,
+                    &self.task_manager,
+                )
+                .await?
+            }
+            other => return Err(misrouted(self.name(), &other)),
+        };
+        Ok(response)
+    }
+}
+
+impl Handler for TaskHandler {
+    fn name(&self) -> &'static str {
+        "task"
+    }
+
+    fn handles(&self, message: &Message) -> bool {
+        matches!(
+            message,
+            Message::TaskQueue { .. }
+                | Message::TaskQueueBatch { .. }
+                | Message::TaskCancel { .. }
+                | Message::TaskPause { .. }
+                | Message::TaskResume { .. }
+                | Message::DeadLetterList
+                | Message::DeadLetterRequeue { .. }
+                | Message::DeadLetterPurge { .. }
+                | Message::TaskTrace { .. }
+                | Message::TaskLatencyReport { .. }
+                | Message::TaskResultGet { .. }
+                | Message::GithubSync
+                | Message::NoteAdd { .. }
+        )
+    }
+
+    fn handle<'a>(
+        &'a self,
+        message: Message,
+        request: &'a RequestContext,
+    ) -> PipelineFuture<'a, Result<Message, IpcError>> {
+        Box::pin(self.execute(message, request))
+    }
+}
+
+/// Add a note to the given task or scratchpad, by default the currently
+/// tracked task or else the active workspace
+async fn add_quick_note(
+    text: String,
+    task_id: Option<String>,
+    workspace: Option<String>,
+    workspace_manager: &WorkspaceManager,
+    task_manager: &TaskManager,
+) -> Result<Message, IpcError> {
+    let task_id = match (task_id, &workspace) {
+        (Some(task_id), _) => Some(task_id),
+        (None, Some(_)) => None,
+        (None, None) => task_manager.current_task().await.map(|task| task.id),
+    };
+
+    if let Some(task_id) = task_id {
+        let note_context = LogContext::new("ipc", "note_add_request").with_entity_id(&task_id);
+        log_info!(note_context, "Adding note to task {}", task_id);
+        let note = task_manager
+            .add_task_note(&task_id, text, None)
+            .await
+            .map_err(|e| {
+                IpcError::from_user_error(
+                    "task_manager",
+                    format!("Note could not be added to task {task_id}: {e}"),
+                    &e.into(),
+                )
+            })?;
+        return Ok(Message::NoteAddResponse {
+            task_id: Some(task_id),
+            workspace: None,
+            note,
+        });
+    }
+
+    let workspace = match workspace {
+        Some(workspace) => workspace,
+        None => workspace_manager
+            .get_active_workspace()
+            .await
+            .map(|(name, _)| name)
+            .ok_or_else(|| {
+                IpcError::new(
+                    ErrorCode::NotFound,
+                    "notes",
+                    "No tracked task or active workspace to add the note to",
+                )
+            })?,
+    };
+    let note_context = LogContext::new("ipc", "note_add_request").with_entity_id(&workspace);
+    log_info!(note_context, "Adding note to workspace {}", workspace);
+    let note = workspace_manager
+        .add_workspace_note(&workspace, text, None)
+        .await
+        .map_err(|e| {
+            IpcError::from_user_error(
+                "workspace_manager",
+                format!("Note could not be added to workspace {workspace}"),
+                &e,
+            )
+        })?;
+    Ok(Message::NoteAddResponse {
+        task_id: None,
+        workspace: Some(workspace),
+        note,
+    })
+}
+
+/// Build a task from the fields of a `TaskQueue` message
+///
+/// Working directories stay inside the workspace's project directory.
+fn queued_task(
+    command: &str,
+    priority: u8,
+    working_dir: Option<&str>,
+    env: std::collections::HashMap<String, String>,
+    shell: Option<String>,
+    timeout_secs: Option<u64>,
+    workspace: Option<&(String, crate::room::state::WorkspaceState)>,
+) -> Result<crate::task::Task, IpcError> {
+    let mut task = crate::task::Task::new(
+        format!("Task: {command}"),
+        crate::task::types::TaskCategory::Development,
+    );
+
+    // Set priority based on message priority
+    task.priority = match priority {
+        1 | 2 => crate::task::types::TaskPriority::Low,
+        3 | 4 => crate::task::types::TaskPriority::Medium,
+        5 | 6 => crate::task::types::TaskPriority::High,
+        7 | 8 => crate::task::types::TaskPriority::Critical,
+        _ => crate::task::types::TaskPriority::Urgent,
+    };
+    task.workspace = workspace.map(|(name, _)| name.clone());
+
+    let root = match workspace.and_then(|(_, state)| state.project_dir.as_deref()) {
+        Some(dir) => std::path::PathBuf::from(dir),
+        None => env::current_dir()
+            .map_err(|e| IpcError::new(ErrorCode::Internal, "task_manager", e.to_string()))?,
+    };
+    let working_directory = working_dir
+        .map(|dir| crate::task::types::resolve_working_dir(&root, dir))
+        .transpose()
+        .map_err(|e| IpcError::new(ErrorCode::InvalidRequest, "task_manager", e))?
+        .or_else(|| shell.is_some().then_some(root));
+    task.execution = crate::task::types::TaskExecution {
+        command: Some(command.to_string()),
+        working_directory: working_directory.map(|dir| dir.to_string_lossy().to_string()),
+        environment: env,
+        timeout: timeout_secs,
+        mode: if shell.is_some() {
+            crate::task::types::ExecutionMode::Automatic
+        } else {
+            crate::task::types::ExecutionMode::Manual
+        },
+        shell,
+        ..Default::default()
+    };
+    Ok(task)
+}
+
+/// Build the reply for a task control request
+fn task_status_response(
+    task_id: &str,
+    action: &str,
+    result: crate::task::TaskResult<crate::task::Task>,
+) -> Result<Message, IpcError> {
+    match result {
+        Ok(_) => Ok(Message::StatusUpdate {
+            process_id: "task_manager".to_string(),
+            status: format!("Task {task_id} {action}"),
+        }),
+        Err(e) => {
+            let task_error_context =
+                LogContext::new("ipc", "task_control_error").with_entity_id(task_id);
+            log_warn!(
+                task_error_context,
+                "Task {} could not be {}: {}",
+                task_id,
+                action,
+                e
+            );
+            Err(IpcError::from_user_error(
+                "task_manager",
+                format!("Task {task_id} could not be {action}: {e}"),
+                &e.into(),
+            ))
+        }
+    }
+}
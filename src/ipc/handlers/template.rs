@@ -0,0 +1,151 @@
+// WezTerm Multi-Process Development Framework - Template Requests
+// Workspace template requests
+
+use super::misrouted;
+use crate::ipc::{Handler, IpcError, PipelineFuture, RequestContext};
+use crate::logging::LogContext;
+use crate::{log_error, log_info, Message, TemplateInfo};
+use std::sync::Arc;
+
+/// Workspace template requests
+pub struct TemplateHandler {
+    pub template_engine: Arc<tokio::sync::Mutex<crate::room::template::TemplateEngine>>,
+}
+
+impl TemplateHandler {
+    async fn execute(&self, message: Message) -> Result<Message, IpcError> {
+        let response = match message {
+            Message::TemplateList => {
+                let template_list_context = LogContext::new("ipc", "template_list_request");
+                log_info!(template_list_context, "Listing available templates");
+                let engine = self.template_engine.lock().await;
+                let templates = engine.list_templates();
+                let template_infos: Vec<TemplateInfo> = templates
+                    .iter()
+                    .map(|t| TemplateInfo {
+                        name: t.name.clone(),
+                        description: t.description.clone(),
+                        author: "System".to_string(),
+                        version: "1.0".to_string(),
+                        created_at: chrono::Utc::now().to_rfc3339(),
+                        layout_type: format!("{:?}", t.layout.layout_type),
+                        pane_count: t.layout.pane_sizes.len() as u32,
+                        auto_start_processes: t
+                            .default_commands
+                            .iter()
+                            .any(|cmd| cmd.auto_start && cmd.is_process()),
+                    })
+                    .collect();
+
+                Message::TemplateListResponse {
+                    templates: template_infos,
+                }
+            }
+            Message::TemplateGet { name } => {
+                let template_get_context =
+                    LogContext::new("ipc", "template_get_request").with_entity_id(&name);
+                log_info!(template_get_context, "Getting template: {}", name);
+                let engine = self.template_engine.lock().await;
+                if let Some(template) = engine.get_template(&name) {
+                    match serde_json::to_string(template) {
+                        Ok(content) => Message::TemplateGetResponse {
+                            template: Some(content),
+                        },
+                        Err(e) => {
+                            let serialize_error_context =
+                                LogContext::new("ipc", "template_serialize_error")
+                                    .with_entity_id(&name);
+                            log_error!(
+                                serialize_error_context,
+                                "Failed to serialize template: {}",
+                                e
+                            );
+                            Message::TemplateGetResponse { template: None }
+                        }
+                    }
+                } else {
+                    Message::TemplateGetResponse { template: None }
+                }
+            }
+            Message::TemplateCreate { name, content } => {
+                let template_create_context =
+                    LogContext::new("ipc", "template_create_request").with_entity_id(&name);
+                log_info!(template_create_context, "Creating template: {}", name);
+
+                // JSON as before, or YAML so that `# lint-allow:` comments survive
+                let parsed =
+                    serde_json::from_str::<crate::room::template::WorkspaceTemplate>(&content)
+                        .map_err(|e| e.to_string())
+                        .or_else(|json_error| {
+                            if content.trim_start().starts_with('{') {
+                                return Err(json_error);
+                            }
+                            serde_yaml::from_str(&content).map_err(|e| e.to_string())
+                        });
+                match parsed {
+                    Ok(template) => {
+                        let warnings = crate::room::lint::lint_source(&template, &content);
+                        let mut engine = self.template_engine.lock().await;
+                        engine.register_template(template);
+                        let template_success_context =
+                            LogContext::new("ipc", "template_create_success").with_entity_id(&name);
+                        log_info!(
+                            template_success_context,
+                            "Template '{}' created successfully",
+                            name
+                        );
+                        Message::TemplateCreateResponse {
+                            success: true,
+                            error: None,
+                            warnings,
+                        }
+                    }
+                    Err(e) => {
+                        let parse_error_context =
+                            LogContext::new("ipc", "template_parse_error").with_entity_id(&name);
+                        log_error!(parse_error_context, "Failed to parse template: {}", e);
+                        Message::TemplateCreateResponse {
+                            success: false,
+                            error: Some(format!("Invalid template format: {e}")),
+                            warnings: Vec::new(),
+                        }
+                    }
+                }
+            }
+            Message::TemplateDelete { name: _ } => {
+                // TODO: Implement template deletion
+                Message::TemplateDeleteResponse {
+                    success: false,
+                    error: Some("Template deletion not yet implemented".to_string()),
+                }
+            }
+
+            other => return Err(misrouted(self.name(), &other)),
+        };
+        Ok(response)
+    }
+}
+
+impl Handler for TemplateHandler {
+    fn name(&self) -> &'static str {
+        "template"
+    }
+
+    fn handles(&self, message: &Message) -> bool {
+        matches!(
+            message,
+            Message::TemplateList
+                | Message::TemplateGet { .. }
+                | Message::TemplateCreate { .. }
+                | Message::TemplateDelete { .. }
+        )
+    }
+
+    fn handle<'a>(
+        &'a self,
+        message: Message,
+        _request: &'a RequestContext,
+    ) -> PipelineFuture<'a, Result<Message, IpcError>> {
+        Box::pin(self.execute(message))
+    }
+}
@@ -0,0 +1,306 @@
+// WezTerm Multi-Process Development Framework - Workspace Requests
+// Workspace lifecycle requests
+
+use super::misrouted;
+use crate::ipc::{Handler, IpcError, PipelineFuture, RequestContext};
+use crate::logging::LogContext;
+use crate::room::{drain, WorkspaceManager};
+use crate::task::TaskManager;
+use crate::{log_error, log_info, Message, WorkspaceInfo};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Workspace lifecycle requests
+pub struct WorkspaceHandler {
+    pub workspace_manager: Arc<WorkspaceManager>,
+    pub task_manager: Arc<TaskManager>,
+}
+
+impl WorkspaceHandler {
+    async fn execute(&self, message: Message) -> Result<Message, IpcError> {
+        let response = match message {
+            Message::WorkspaceCreate {
+                name,
+                template,
+                project_dir,
+                variables,
+            } => {
+                let create_context = LogContext::new("ipc", "workspace_create_request")
+                    .with_entity_id(&name)
+                    .with_metadata("template", serde_json::json!(template))
+                    .with_metadata("project_dir", serde_json::json!(project_dir));
+                log_info!(
+                    create_context,
+                    "Creating workspace: {} with template: {}",
+                    name,
+                    template
+                );
+
+                match self
+                    .workspace_manager
+                    .create_workspace_with(
+                        &name,
+                        &template,
+                        project_dir.as_deref().map(Path::new),
+                        &variables,
+                    )
+                    .await
+                {
+                    Ok(()) => {
+                        let success_context = LogContext::new("ipc", "workspace_create_success")
+                            .with_entity_id(&name);
+                        log_info!(success_context, "Successfully created workspace '{}'", name);
+                        Message::StatusUpdate {
+                            process_id: "workspace_manager".to_string(),
+                            status: format!(
+                            "Workspace '{name}' created successfully with template '{template}'"
+                        ),
+                        }
+                    }
+                    Err(e) => {
+                        let error_context =
+                            LogContext::new("ipc", "workspace_create_error").with_entity_id(&name);
+                        log_error!(
+                            error_context,
+                            "Failed to create workspace '{}': {}",
+                            name,
+                            e
+                        );
+                        return Err(IpcError::from_user_error(
+                            "workspace_manager",
+                            format!("Failed to create workspace '{name}': {e}"),
+                            &e,
+                        ));
+                    }
+                }
+            }
+            Message::WorkspaceDelete { name } => {
+                let delete_context =
+                    LogContext::new("ipc", "workspace_delete_request").with_entity_id(&name);
+                log_info!(delete_context, "Deleting workspace: {}", name);
+
+                let status = match self.workspace_manager.delete_workspace(&name).await {
+                    Ok(stopped) => {
+                        format!("Workspace '{name}' deleted ({stopped} attached processes stopped)")
+                    }
+                    Err(e) => {
+                        let error_context =
+                            LogContext::new("ipc", "workspace_delete_error").with_entity_id(&name);
+                        log_error!(
+                            error_context,
+                            "Failed to delete workspace '{}': {}",
+                            name,
+                            e
+                        );
+                        return Err(IpcError::from_user_error(
+                            "workspace_manager",
+                            format!("Failed to delete workspace '{name}': {e}"),
+                            &e,
+                        ));
+                    }
+                };
+                Message::StatusUpdate {
+                    process_id: "workspace_manager".to_string(),
+                    status,
+                }
+            }
+            Message::WorkspaceRename { name, new_name } => {
+                let rename_context = LogContext::new("ipc", "workspace_rename_request")
+                    .with_entity_id(&name)
+                    .with_metadata("new_name", serde_json::json!(new_name));
+                log_info!(
+                    rename_context,
+                    "Renaming workspace '{}' to '{}'",
+                    name,
+                    new_name
+                );
+
+                let status = match self
+                    .workspace_manager
+                    .rename_workspace(&name, &new_name)
+                    .await
+                {
+                    Ok(()) => format!("Workspace '{name}' renamed to '{new_name}'"),
+                    Err(e) => {
+                        let error_context =
+                            LogContext::new("ipc", "workspace_rename_error").with_entity_id(&name);
+                        log_error!(
+                            error_context,
+                            "Failed to rename workspace '{}': {}",
+                            name,
+                            e
+                        );
+                        return Err(IpcError::from_user_error(
+                            "workspace_manager",
+                            format!("Failed to rename workspace '{name}': {e}"),
+                            &e,
+                        ));
+                    }
+                };
+                Message::StatusUpdate {
+                    process_id: "workspace_manager".to_string(),
+                    status,
+                }
+            }
+            Message::WorkspaceList => {
+                let list_context = LogContext::new("ipc", "workspace_list_request");
+                log_info!(list_context, "Listing workspaces");
+
+                let workspaces = self
+                    .workspace_manager
+                    .list_workspace_states()
+                    .await
+                    .into_iter()
+                    .map(|w| WorkspaceInfo {
+                        draining: w.drain.is_some(),
+                        process_count: w.processes.len(),
+                        task_count: w.active_tasks.len(),
+                        pane_count: w.panes.len(),
+                        branch: w.git.and_then(|git| git.branch),
+                        is_active: w.is_active,
+                        template: w.template,
+                        name: w.name,
+                    })
+                    .collect();
+
+                Message::WorkspaceListResponse { workspaces }
+            }
+            Message::WorkspaceSwitch { name } => {
+                let switch_context =
+                    LogContext::new("ipc", "workspace_switch_request").with_entity_id(&name);
+                log_info!(switch_context, "Switching to workspace: {}", name);
+
+                let status = match self.workspace_manager.switch_workspace(&name).await {
+                    Ok(()) => format!("Switched to workspace '{name}'"),
+                    Err(e) => {
+                        let error_context =
+                            LogContext::new("ipc", "workspace_switch_error").with_entity_id(&name);
+                        log_error!(
+                            error_context,
+                            "Failed to switch workspace '{}': {}",
+                            name,
+                            e
+                        );
+                        return Err(IpcError::from_user_error(
+                            "workspace_manager",
+                            format!("Failed to switch to workspace '{name}': {e}"),
+                            &e,
+                        ));
+                    }
+                };
+                Message::StatusUpdate {
+                    process_id: "workspace_manager".to_string(),
+                    status,
+                }
+            }
+            Message::WorkspaceDrain {
+                name,
+                delete_when_empty,
+            } => {
+                let drain_context = LogContext::new("ipc", "workspace_drain_request")
+                    .with_entity_id(&name)
+                    .with_metadata("delete_when_empty", serde_json::json!(delete_when_empty));
+                log_info!(drain_context, "Draining workspace: {}", name);
+
+                if let Err(e) = self
+                    .workspace_manager
+                    .drain_workspace(&name, delete_when_empty)
+                    .await
+                {
+                    return Err(IpcError::from_user_error(
+                        "workspace_manager",
+                        format!("Failed to drain workspace '{name}': {e}"),
+                        &e,
+                    ));
+                }
+                let drains =
+                    drain::drain_progress(&self.workspace_manager, &self.task_manager, &name)
+                        .await
+                        .into_iter()
+                        .collect();
+                Message::WorkspaceDrainResponse { drains }
+            }
+            Message::WorkspaceUndrain { name } => {
+                let undrain_context =
+                    LogContext::new("ipc", "workspace_undrain_request").with_entity_id(&name);
+                log_info!(undrain_context, "Ending drain of workspace: {}", name);
+
+                let status = match self.workspace_manager.undrain_workspace(&name).await {
+                    Ok(true) => format!("Workspace '{name}' accepts new work again"),
+                    Ok(false) => format!("Workspace '{name}' was not draining"),
+                    Err(e) => {
+                        return Err(IpcError::from_user_error(
+                            "workspace_manager",
+                            format!("Failed to end drain of workspace '{name}': {e}"),
+                            &e,
+                        ))
+                    }
+                };
+                Message::StatusUpdate {
+                    process_id: "workspace_manager".to_string(),
+                    status,
+                }
+            }
+            Message::WorkspaceDrainStatus => {
+                let mut drains = Vec::new();
+                for name in self
+                    .workspace_manager
+                    .draining_workspaces()
+                    .await
+                    .into_keys()
+                {
+                    drains.extend(
+                        drain::drain_progress(&self.workspace_manager, &self.task_manager, &name)
+                            .await,
+                    );
+                }
+                Message::WorkspaceDrainResponse { drains }
+            }
+            Message::GitStatus { workspace } => {
+                let statuses = self
+                    .workspace_manager
+                    .git_status(workspace.as_deref())
+                    .await
+                    .map_err(|e| {
+                        IpcError::from_user_error(
+                            "workspace_manager",
+                            format!("Failed to get git status: {e}"),
+                            &e,
+                        )
+                    })?;
+                Message::GitStatusResponse { statuses }
+            }
+            other => return Err(misrouted(self.name(), &other)),
+        };
+        Ok(response)
+    }
+}
+
+impl Handler for WorkspaceHandler {
+    fn name(&self) -> &'static str {
+        "workspace"
+    }
+
+    fn handles(&self, message: &Message) -> bool {
+        matches!(
+            message,
+            Message::WorkspaceCreate { .. }
+                | Message::WorkspaceDelete { .. }
+                | Message::WorkspaceRename { .. }
+                | Message::WorkspaceList
+                | Message::WorkspaceSwitch { .. }
+                | Message::WorkspaceDrain { .. }
+                | Message::WorkspaceUndrain { .. }
+                | Message::WorkspaceDrainStatus
+                | Message::GitStatus { .. }
+        )
+    }
+
+    fn handle<'a>(
+        &'a self,
+        message: Message,
+        _request: &'a RequestContext,
+    ) -> PipelineFuture<'a, Result<Message, IpcError>> {
+        Box::pin(self.execute(message))
+    }
+}
@@ -0,0 +1,279 @@
+// WezTerm Multi-Process Development Framework - IPC Limits
+// Connection limits, per-connection rate limiting and size-capped message framing
+
+use super::{ErrorCode, IpcError};
+use crate::config::ServerConfig;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Limits enforced by the IPC server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpcLimits {
+    pub max_connections: usize,
+    pub max_message_size: usize,
+
+    /// Messages per second per connection (0 = unlimited)
+    pub rate_limit_per_sec: u32,
+    pub rate_limit_burst: u32,
+
+    /// Frame size budget of streamed list replies
+    pub stream_chunk_bytes: usize,
+}
+
+impl From<&ServerConfig> for IpcLimits {
+    fn from(config: &ServerConfig) -> Self {
+        Self {
+            max_connections: config.max_connections,
+            max_message_size: config.max_message_size,
+            rate_limit_per_sec: config.rate_limit_per_sec,
+            rate_limit_burst: config.rate_limit_burst,
+            stream_chunk_bytes: config.stream_chunk_bytes,
+        }
+    }
+}
+
+impl Default for IpcLimits {
+    fn default() -> Self {
+        Self::from(&ServerConfig::default())
+    }
+}
+
+/// Caps the number of connections served at the same time
+#[derive(Debug, Clone)]
+pub struct ConnectionLimiter {
+    permits: Arc<Semaphore>,
+    max_connections: usize,
+}
+
+impl ConnectionLimiter {
+    pub fn new(max_connections: usize) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(max_connections)),
+            max_connections,
+        }
+    }
+
+    /// Reserve a slot for a new connection, released when the permit is dropped
+    pub fn try_acquire(&self) -> Option<OwnedSemaphorePermit> {
+        Arc::clone(&self.permits).try_acquire_owned().ok()
+    }
+
+    /// Connections currently holding a slot
+    pub fn active(&self) -> usize {
+        self.max_connections - self.permits.available_permits()
+    }
+
+    pub fn max_connections(&self) -> usize {
+        self.max_connections
+    }
+
+    /// Error returned to clients turned away at the limit
+    pub fn rejection(&self) -> IpcError {
+        IpcError::new(
+            ErrorCode::Unavailable,
+            "system",
+            format!(
+                "Too many connections (limit {}), try again later",
+                self.max_connections
+            ),
+        )
+    }
+}
+
+/// Token bucket limiting the message rate of one connection
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    rate_per_sec: f64,
+    burst: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// A rate of 0 disables limiting
+    pub fn new(rate_per_sec: u32, burst: u32) -> Self {
+        let burst = f64::from(burst.max(1));
+        Self {
+            rate_per_sec: f64::from(rate_per_sec),
+            burst,
+            tokens: burst,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Take a token for one message; false when the connection is over its rate
+    pub fn try_acquire(&mut self) -> bool {
+        self.try_acquire_at(Instant::now())
+    }
+
+    fn try_acquire_at(&mut self, now: Instant) -> bool {
+        if self.rate_per_sec <= 0.0 {
+            return true;
+        }
+
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate_per_sec).min(self.burst);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Error returned for a message over the rate
+    pub fn rejection(&self) -> IpcError {
+        IpcError::new(
+            ErrorCode::RateLimited,
+            "system",
+            format!(
+                "Rate limit exceeded (at most {} messages per second)",
+                self.rate_per_sec
+            ),
+        )
+    }
+}
+
+/// Splits the byte stream of a connection into complete JSON messages
+///
+/// Messages are concatenated JSON documents, so a message may arrive split
+/// over several reads or several messages in one read.
+#[derive(Debug)]
+pub struct FrameDecoder {
+    buffer: Vec<u8>,
+    max_message_size: usize,
+}
+
+impl FrameDecoder {
+    pub fn new(max_message_size: usize) -> Self {
+        Self {
+            buffer: Vec::new(),
+            max_message_size,
+        }
+    }
+
+    pub fn push(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// Next complete message, or `None` when more data is needed
+    ///
+    /// Data that is not valid JSON is returned as one frame so the caller's
+    /// parser reports it. A message over the size limit is an error, after
+    /// which the stream cannot be resynchronised and the connection should be
+    /// closed.
+    pub fn next_frame(&mut self) -> Result<Option<Vec<u8>>, IpcError> {
+        let start = self
+            .buffer
+            .iter()
+            .position(|b| !b.is_ascii_whitespace())
+            .unwrap_or(self.buffer.len());
+        self.buffer.drain(..start);
+        if self.buffer.is_empty() {
+            return Ok(None);
+        }
+
+        let mut documents =
+            serde_json::Deserializer::from_slice(&self.buffer).into_iter::<serde::de::IgnoredAny>();
+        let end = match documents.next() {
+            Some(Ok(_)) => documents.byte_offset(),
+            Some(Err(e)) if e.is_eof() => {
+                if self.buffer.len() > self.max_message_size {
+                    self.buffer.clear();
+                    return Err(self.too_large());
+                }
+                return Ok(None);
+            }
+            Some(Err(_)) | None => self.buffer.len(),
+        };
+
+        let frame: Vec<u8> = self.buffer.drain(..end).collect();
+        if frame.len() > self.max_message_size {
+            return Err(self.too_large());
+        }
+        Ok(Some(frame))
+    }
+
+    fn too_large(&self) -> IpcError {
+        IpcError::new(
+            ErrorCode::PayloadTooLarge,
+            "system",
+            format!(
+                "Message exceeds the maximum size of {} bytes",
+                self.max_message_size
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_connection_limiter() {
+        let limiter = ConnectionLimiter::new(2);
+        let first = limiter.try_acquire().unwrap();
+        let _second = limiter.try_acquire().unwrap();
+        assert!(limiter.try_acquire().is_none());
+        assert_eq!(limiter.active(), 2);
+
+        drop(first);
+        assert!(limiter.try_acquire().is_some());
+        assert_eq!(limiter.rejection().code, ErrorCode::Unavailable);
+    }
+
+    #[test]
+    fn test_rate_limiter_refills() {
+        let mut limiter = RateLimiter::new(2, 3);
+        let start = limiter.last_refill;
+        assert!((0..3).all(|_| limiter.try_acquire_at(start)));
+        assert!(!limiter.try_acquire_at(start));
+
+        // Two tokens per second
+        assert!(limiter.try_acquire_at(start + Duration::from_millis(500)));
+        assert!(!limiter.try_acquire_at(start + Duration::from_millis(500)));
+
+        let mut unlimited = RateLimiter::new(0, 1);
+        assert!((0..100).all(|_| unlimited.try_acquire_at(start)));
+    }
+
+    #[test]
+    fn test_frame_decoder_splits_and_joins() {
+        let mut decoder = FrameDecoder::new(1024);
+        decoder.push(br#"{"WorkspaceSwitch":{"na"#);
+        assert_eq!(decoder.next_frame().unwrap(), None);
+
+        decoder.push(br#"me":"dev"}} "Ping""#);
+        assert_eq!(
+            decoder.next_frame().unwrap().unwrap(),
+            br#"{"WorkspaceSwitch":{"name":"dev"}}"#.to_vec()
+        );
+        assert_eq!(
+            decoder.next_frame().unwrap().unwrap(),
+            br#""Ping""#.to_vec()
+        );
+        assert_eq!(decoder.next_frame().unwrap(), None);
+
+        decoder.push(b"not json");
+        assert_eq!(decoder.next_frame().unwrap().unwrap(), b"not json".to_vec());
+    }
+
+    #[test]
+    fn test_frame_decoder_size_limit() {
+        let mut decoder = FrameDecoder::new(16);
+        decoder.push(br#"{"TemplateCreate":{"name":"x""#);
+        let error = decoder.next_frame().unwrap_err();
+        assert_eq!(error.code, ErrorCode::PayloadTooLarge);
+
+        let mut decoder = FrameDecoder::new(16);
+        decoder.push(br#"{"name":"a long complete message"}"#);
+        assert!(decoder.next_frame().is_err());
+    }
+}
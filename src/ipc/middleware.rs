@@ -0,0 +1,866 @@
+// WezTerm Multi-Process Development Framework - IPC Middleware
+// Authentication, audit, logging, validation, confirmation, rate limiting and metrics stages of the request pipeline
+
+use super::pipeline::{Middleware, PipelineFuture, RequestContext};
+use super::{ErrorCode, IpcError, IpcLimits, RateLimiter};
+use crate::audit::{self, AuditEntry, AuditJournal, AuditSource};
+use crate::auth::{self, Authenticator, Scope};
+use crate::logging::LogContext;
+use crate::maintenance::MaintenanceController;
+use crate::metrics::protocol::ProtocolMetrics;
+use crate::monitoring::{ShedAction, ShedLog, ShedTrigger};
+use crate::process;
+use crate::room::WorkspaceManager;
+use crate::telemetry::{self, Telemetry};
+use crate::Message;
+use crate::{log_debug, log_info, log_warn};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Identifies the client and checks it has the scope for the message
+pub struct AuthMiddleware {
+    authenticator: Arc<Authenticator>,
+}
+
+impl AuthMiddleware {
+    pub fn new(authenticator: Arc<Authenticator>) -> Self {
+        Self { authenticator }
+    }
+
+    fn authorize(
+        &self,
+        request: &RequestContext,
+        message: &Message,
+    ) -> Result<auth::ClientIdentity, IpcError> {
+        // Clients running as our own user are trusted with the local scope;
+        // everyone else has to send a token with each request
+        let peer = self.authenticator.authenticate_peer(request.peer_uid);
+        let identity = self
+            .authenticator
+            .identify(peer.as_ref(), request.token.as_deref())?;
+        self.authenticator.authorize(
+            &identity,
+            auth::message_scope(message),
+            &request.operation,
+        )?;
+        Ok(identity)
+    }
+}
+
+impl Middleware for AuthMiddleware {
+    fn name(&self) -> &'static str {
+        "auth"
+    }
+
+    fn before<'a>(
+        &'a self,
+        request: &'a mut RequestContext,
+        message: &'a Message,
+    ) -> PipelineFuture<'a, Result<(), IpcError>> {
+        let result = match self.authorize(request, message) {
+            Ok(identity) => {
+                request.identity = Some(identity);
+                Ok(())
+            }
+            Err(error) => {
+                let auth_context = LogContext::new("ipc", "auth_rejected")
+                    .with_metadata("operation", serde_json::json!(request.operation))
+                    .with_metadata("code", serde_json::json!(error.code.as_str()));
+                log_warn!(
+                    auth_context,
+                    "Rejected {}: {}",
+                    request.operation,
+                    error.message
+                );
+                Err(error)
+            }
+        };
+        Box::pin(std::future::ready(result))
+    }
+}
+
+/// Writes every authorized request that changes state, and how it ended, to
+/// the audit journal; register it right after authentication
+pub struct AuditMiddleware {
+    journal: Arc<AuditJournal>,
+
+    /// Entry of the request in flight on each connection
+    pending: Mutex<HashMap<u64, AuditEntry>>,
+}
+
+impl AuditMiddleware {
+    pub fn new(journal: Arc<AuditJournal>) -> Self {
+        Self {
+            journal,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn pending(&self) -> std::sync::MutexGuard<'_, HashMap<u64, AuditEntry>> {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Middleware for AuditMiddleware {
+    fn name(&self) -> &'static str {
+        "audit"
+    }
+
+    fn before<'a>(
+        &'a self,
+        request: &'a mut RequestContext,
+        message: &'a Message,
+    ) -> PipelineFuture<'a, Result<(), IpcError>> {
+        if auth::message_scope(message) > Scope::Read {
+            let entry = AuditEntry {
+                timestamp: crate::clock::unix_secs(),
+                source: AuditSource::Ipc,
+                client: request
+                    .identity
+                    .as_ref()
+                    .map_or_else(|| "unknown".to_string(), |identity| identity.name.clone()),
+                peer_uid: request.peer_uid,
+                connection: request.connection_id.to_string(),
+                request_id: request.request_id.clone(),
+                operation: request.operation.clone(),
+                details: audit::details(&super::recorder::redact(message)),
+                success: false,
+                error: None,
+                duration_ms: 0,
+            };
+            self.pending().insert(request.connection_id, entry);
+        }
+        Box::pin(std::future::ready(Ok(())))
+    }
+
+    fn after(
+        &self,
+        request: &RequestContext,
+        result: &Result<Message, IpcError>,
+        elapsed: Duration,
+    ) {
+        let Some(mut entry) = self.pending().remove(&request.connection_id) else {
+            return;
+        };
+        entry.success = result.is_ok();
+        entry.error = result.as_ref().err().map(|error| error.message.clone());
+        entry.duration_ms = elapsed.as_millis() as u64;
+        self.journal.record(&entry);
+    }
+
+    fn disconnected(&self, connection_id: u64) {
+        self.pending().remove(&connection_id);
+    }
+}
+
+/// Logs every accepted request and how it ended
+#[derive(Debug, Default)]
+pub struct LoggingMiddleware;
+
+impl Middleware for LoggingMiddleware {
+    fn name(&self) -> &'static str {
+        "logging"
+    }
+
+    fn before<'a>(
+        &'a self,
+        request: &'a mut RequestContext,
+        message: &'a Message,
+    ) -> PipelineFuture<'a, Result<(), IpcError>> {
+        let message_context = LogContext::new("ipc", "message_receive")
+            .with_metadata("message_type", serde_json::json!(request.operation))
+            .with_metadata("connection_id", serde_json::json!(request.connection_id));
+        // The log is readable with the read scope; keep typed input and secrets out
+        log_info!(
+            message_context,
+            "Received message: {:?}",
+            super::recorder::redact(message)
+        );
+        Box::pin(std::future::ready(Ok(())))
+    }
+
+    fn after(
+        &self,
+        request: &RequestContext,
+        result: &Result<Message, IpcError>,
+        elapsed: Duration,
+    ) {
+        let outcome = match result {
+            Ok(_) => "ok",
+            Err(error) => error.code.as_str(),
+        };
+        let complete_context = LogContext::new("ipc", "request_complete")
+            .with_metadata("message_type", serde_json::json!(request.operation))
+            .with_metadata("outcome", serde_json::json!(outcome))
+            .with_metadata("duration_ms", serde_json::json!(elapsed.as_millis()));
+        log_debug!(
+            complete_context,
+            "{} finished in {:?}: {}",
+            request.operation,
+            elapsed,
+            outcome
+        );
+    }
+}
+
+/// Rejects malformed arguments and, during maintenance, new work
+#[derive(Default)]
+pub struct ValidationMiddleware {
+    maintenance: Option<Arc<MaintenanceController>>,
+}
+
+impl ValidationMiddleware {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Refuse workspace creation, process spawns and task queueing while
+    /// maintenance mode is draining the system
+    pub fn with_maintenance(mut self, maintenance: Arc<MaintenanceController>) -> Self {
+        self.maintenance = Some(maintenance);
+        self
+    }
+
+    async fn validate(&self, message: &Message) -> Result<(), IpcError> {
+        validate_arguments(message)?;
+
+        let rejected_operation = match message {
+            Message::WorkspaceCreate { .. } => Some(("workspace_manager", "workspace creation")),
+            Message::ProcessSpawn { .. } => Some(("process_manager", "process spawn")),
+            Message::TaskQueue { .. } | Message::TaskQueueBatch { .. } => {
+                Some(("task_manager", "task queueing"))
+            }
+            Message::TaskResume { .. } => Some(("task_manager", "task resume")),
+            Message::DeadLetterRequeue { .. } => Some(("task_manager", "dead letter requeue")),
+            _ => None,
+        };
+        if let (Some((component, operation)), Some(maintenance)) =
+            (rejected_operation, &self.maintenance)
+        {
+            if let Some(error) = maintenance.rejection(operation).await {
+                let rejected_context = LogContext::new("ipc", "maintenance_rejected")
+                    .with_metadata("operation", serde_json::json!(operation));
+                log_warn!(rejected_context, "{}", error);
+                return Err(IpcError::new(ErrorCode::Unavailable, component, error));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Middleware for ValidationMiddleware {
+    fn name(&self) -> &'static str {
+        "validation"
+    }
+
+    fn before<'a>(
+        &'a self,
+        _request: &'a mut RequestContext,
+        message: &'a Message,
+    ) -> PipelineFuture<'a, Result<(), IpcError>> {
+        Box::pin(self.validate(message))
+    }
+}
+
+/// Check that the arguments a handler cannot work without are present
+pub fn validate_arguments(message: &Message) -> Result<(), IpcError> {
+    match message {
+        Message::WorkspaceCreate {
+            name, project_dir, ..
+        } => {
+            required("workspace_manager", "Workspace name", name)?;
+            match project_dir {
+                // Resolved by the server, whose working directory differs from the client's
+                Some(dir) if !std::path::Path::new(dir).is_absolute() => Err(IpcError::new(
+                    ErrorCode::InvalidRequest,
+                    "workspace_manager",
+                    "Project directory must be an absolute path",
+                )),
+                _ => Ok(()),
+            }
+        }
+        Message::WorkspaceRename { new_name, .. } => {
+            required("workspace_manager", "New workspace name", new_name)
+        }
+        Message::ProcessSpawn { command, .. } => required("process_manager", "Command", command),
+        Message::ProcessEnv { process_id }
+        | Message::ProcessEnvSet { process_id, .. }
+        | Message::ProcessLogsGet { process_id, .. }
+        | Message::ProcessLogTail { process_id, .. }
+        | Message::ProcessCircuitReset { process_id }
+        | Message::ProcessInput { process_id, .. }
+        | Message::ProcessStdinWrite { process_id, .. } => {
+            required("process_manager", "Process ID", process_id)
+        }
+        Message::ProcessResize {
+            process_id,
+            rows,
+            cols,
+        } => {
+            required("process_manager", "Process ID", process_id)?;
+            process::TerminalSize {
+                rows: *rows,
+                cols: *cols,
+            }
+            .validate()
+            .map_err(|e| IpcError::new(ErrorCode::InvalidRequest, "process_manager", e))
+        }
+        Message::TaskQueue {
+            command,
+            env,
+            shell,
+            timeout_secs,
+            ..
+        } => validate_task_fields(command, env, shell.as_deref(), *timeout_secs),
+        Message::TaskQueueBatch { tasks, .. } => {
+            if tasks.is_empty() {
+                return Err(IpcError::new(
+                    ErrorCode::InvalidRequest,
+                    "task_manager",
+                    "Batch must contain at least one task",
+                ));
+            }
+            for (index, task) in tasks.iter().enumerate() {
+                validate_task_fields(
+                    &task.command,
+                    &task.env,
+                    task.shell.as_deref(),
+                    task.timeout_secs,
+                )
+                .map_err(|mut e| {
+                    let key = task.key.clone().unwrap_or_else(|| index.to_string());
+                    e.message = format!("Task '{key}': {}", e.message);
+                    e
+                })?;
+            }
+            Ok(())
+        }
+        Message::DeadLetterRequeue { id } => required("task_manager", "Task ID", id),
+        Message::NoteAdd { text, .. } => required("notes", "Note text", text),
+        Message::HistoryImport { content, .. } => {
+            required("task_tracker", "Import content", content)
+        }
+        Message::BoardImport { content, .. } => required("task_board", "Board document", content),
+        Message::SessionRestore { content, .. } => {
+            required("session_snapshot", "Session snapshot", content)
+        }
+        Message::SessionTag { tags, .. } | Message::SessionUntag { tags, .. } => {
+            required("task_tracker", "Tags", &tags.concat())
+        }
+        Message::TemplateCreate { name, .. } => required("template_engine", "Template name", name),
+        Message::SyncResolve { conflict_id, .. } => {
+            required("file_sync", "Conflict ID", conflict_id)
+        }
+        Message::SyncWatchSet { workspace, .. } => required("file_sync", "Workspace", workspace),
+        Message::LogLevelSet { module, level } => {
+            required("logging", "Module", module)?;
+            required("logging", "Level", level)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Check the fields shared by `TaskQueue` and the tasks of a batch
+fn validate_task_fields(
+    command: &str,
+    env: &std::collections::HashMap<String, String>,
+    shell: Option<&str>,
+    timeout_secs: Option<u64>,
+) -> Result<(), IpcError> {
+    required("task_manager", "Task command", command)?;
+    if let Some(shell) = shell {
+        required("task_manager", "Shell", shell)?;
+    }
+    if let Some(name) = env
+        .keys()
+        .find(|name| name.is_empty() || name.contains('=') || name.contains('\0'))
+    {
+        return Err(IpcError::new(
+            ErrorCode::InvalidRequest,
+            "task_manager",
+            format!("Invalid environment variable name: {name:?}"),
+        ));
+    }
+    if timeout_secs == Some(0) {
+        return Err(IpcError::new(
+            ErrorCode::InvalidRequest,
+            "task_manager",
+            "Task timeout must be at least 1 second",
+        ));
+    }
+    Ok(())
+}
+
+fn required(component: &str, field: &str, value: &str) -> Result<(), IpcError> {
+    if value.trim().is_empty() {
+        Err(IpcError::new(
+            ErrorCode::InvalidRequest,
+            component,
+            format!("{field} cannot be empty"),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Holds back destructive operations until the client confirms them
+///
+/// Clients whose scope is listed in `auth.confirm.scopes` first receive a
+/// `confirmation_required` error carrying a token and an impact summary, and
+/// must re-send the identical request with that token in `confirm`.
+pub struct ConfirmationMiddleware {
+    authenticator: Arc<Authenticator>,
+    workspace_manager: Option<Arc<WorkspaceManager>>,
+}
+
+impl ConfirmationMiddleware {
+    pub fn new(authenticator: Arc<Authenticator>) -> Self {
+        Self {
+            authenticator,
+            workspace_manager: None,
+        }
+    }
+
+    /// Workspace manager used to describe what a `WorkspaceDelete` would stop
+    pub fn with_workspace_manager(mut self, workspace_manager: Arc<WorkspaceManager>) -> Self {
+        self.workspace_manager = Some(workspace_manager);
+        self
+    }
+
+    async fn check(&self, request: &RequestContext, message: &Message) -> Result<(), IpcError> {
+        if !auth::confirm::is_destructive_message(message) {
+            return Ok(());
+        }
+        let identity = request
+            .identity
+            .clone()
+            .unwrap_or_else(auth::ClientIdentity::anonymous);
+        let guard = self.authenticator.confirmations();
+        let fingerprint = auth::confirm::fingerprint(message);
+        if guard.confirmed(&identity, &fingerprint, request.confirm.as_deref()) {
+            return Ok(());
+        }
+
+        let (component, impact) = match (message, &self.workspace_manager) {
+            (Message::WorkspaceDelete { name }, Some(workspace_manager)) => (
+                "workspace_manager",
+                auth::confirm::workspace_delete_impact(workspace_manager, name).await,
+            ),
+            (Message::WorkspaceDelete { name }, None) => {
+                ("workspace_manager", format!("Deletes workspace '{name}'"))
+            }
+            _ => ("ipc", format!("Runs {}", request.operation)),
+        };
+        let challenge = guard.issue(&identity, &request.operation, fingerprint, impact);
+
+        let confirm_context = LogContext::new("ipc", "confirmation_required")
+            .with_metadata("operation", serde_json::json!(request.operation))
+            .with_metadata("client", serde_json::json!(identity.name));
+        log_info!(
+            confirm_context,
+            "{} by {} awaits confirmation: {}",
+            request.operation,
+            identity.name,
+            challenge.impact
+        );
+        Err(IpcError::confirmation_required(component, challenge))
+    }
+}
+
+impl Middleware for ConfirmationMiddleware {
+    fn name(&self) -> &'static str {
+        "confirmation"
+    }
+
+    fn before<'a>(
+        &'a self,
+        request: &'a mut RequestContext,
+        message: &'a Message,
+    ) -> PipelineFuture<'a, Result<(), IpcError>> {
+        Box::pin(self.check(request, message))
+    }
+}
+
+/// Limits how fast each connection may send requests
+pub struct RateLimitMiddleware {
+    limits: IpcLimits,
+    limiters: Mutex<HashMap<u64, RateLimiter>>,
+    shed_log: Option<Arc<ShedLog>>,
+}
+
+impl RateLimitMiddleware {
+    pub fn new(limits: IpcLimits) -> Self {
+        Self {
+            limits,
+            limiters: Mutex::new(HashMap::new()),
+            shed_log: None,
+        }
+    }
+
+    /// Record rejected requests as load shedding
+    pub fn with_shed_log(mut self, shed_log: Arc<ShedLog>) -> Self {
+        self.shed_log = Some(shed_log);
+        self
+    }
+
+    /// Connections currently holding a rate limiter
+    pub fn tracked_connections(&self) -> usize {
+        self.limiters.lock().map(|l| l.len()).unwrap_or_default()
+    }
+
+    fn check(&self, request: &RequestContext) -> Result<(), IpcError> {
+        let mut limiters = match self.limiters.lock() {
+            Ok(limiters) => limiters,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let limiter = limiters.entry(request.connection_id).or_insert_with(|| {
+            RateLimiter::new(self.limits.rate_limit_per_sec, self.limits.rate_limit_burst)
+        });
+        if limiter.try_acquire() {
+            return Ok(());
+        }
+
+        let rate_context = LogContext::new("ipc", "rate_limited").with_metadata(
+            "rate_limit_per_sec",
+            serde_json::json!(self.limits.rate_limit_per_sec),
+        );
+        log_warn!(
+            rate_context,
+            "Rate limit exceeded, rejecting {}",
+            request.operation
+        );
+        if let Some(shed_log) = &self.shed_log {
+            let limit = f64::from(self.limits.rate_limit_per_sec);
+            shed_log.record(
+                "ipc_rate_limit",
+                ShedAction::Rejected,
+                ShedTrigger::new("ipc_requests_per_sec", limit, limit),
+                &request.operation,
+            );
+        }
+        Err(limiter.rejection())
+    }
+}
+
+impl Middleware for RateLimitMiddleware {
+    fn name(&self) -> &'static str {
+        "rate_limit"
+    }
+
+    fn before<'a>(
+        &'a self,
+        request: &'a mut RequestContext,
+        _message: &'a Message,
+    ) -> PipelineFuture<'a, Result<(), IpcError>> {
+        Box::pin(std::future::ready(self.check(request)))
+    }
+
+    fn disconnected(&self, connection_id: u64) {
+        if let Ok(mut limiters) = self.limiters.lock() {
+            limiters.remove(&connection_id);
+        }
+    }
+}
+
+/// Records latency and errors per message type, and feature usage for telemetry
+#[derive(Default)]
+pub struct MetricsMiddleware {
+    metrics: Arc<ProtocolMetrics>,
+    telemetry: Option<Arc<Telemetry>>,
+}
+
+impl MetricsMiddleware {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record into a registry shared with the dashboard (`/metrics`, protocol health panel)
+    pub fn with_protocol_metrics(mut self, metrics: Arc<ProtocolMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Count feature usage for anonymous telemetry reports
+    pub fn with_telemetry(mut self, telemetry: Arc<Telemetry>) -> Self {
+        self.telemetry = Some(telemetry);
+        self
+    }
+
+    pub fn protocol_metrics(&self) -> &ProtocolMetrics {
+        &self.metrics
+    }
+}
+
+impl Middleware for MetricsMiddleware {
+    fn name(&self) -> &'static str {
+        "metrics"
+    }
+
+    fn before<'a>(
+        &'a self,
+        _request: &'a mut RequestContext,
+        message: &'a Message,
+    ) -> PipelineFuture<'a, Result<(), IpcError>> {
+        if let (Some(telemetry), Some(feature)) =
+            (&self.telemetry, telemetry::usage_feature(message))
+        {
+            telemetry.record(feature);
+        }
+        Box::pin(std::future::ready(Ok(())))
+    }
+
+    fn after(
+        &self,
+        request: &RequestContext,
+        result: &Result<Message, IpcError>,
+        elapsed: Duration,
+    ) {
+        self.metrics
+            .record("ipc", &request.operation, elapsed, result.is_ok());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{AuthConfig, ClientToken, Scope};
+    use crate::ipc::pipeline::Pipeline;
+
+    #[tokio::test]
+    async fn test_auth_sets_identity_and_enforces_scope() {
+        let authenticator = Authenticator::new(AuthConfig {
+            enabled: true,
+            tokens: vec![ClientToken {
+                name: "statusbar".to_string(),
+                token: "read-secret".to_string(),
+                scope: Scope::Read,
+            }],
+            ..Default::default()
+        });
+        let pipeline =
+            Pipeline::new().with_middleware(AuthMiddleware::new(Arc::new(authenticator)));
+
+        // Not authenticated at all
+        let error = pipeline
+            .dispatch(&mut RequestContext::new(1), Message::Ping)
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, ErrorCode::Unauthorized);
+
+        // Read token: queries pass auth (and fall through to no handler), changes are forbidden
+        let mut request = RequestContext::new(1).with_token(Some("read-secret".to_string()));
+        let error = pipeline
+            .dispatch(&mut request, Message::Ping)
+            .await
+            .unwrap_err();
+        assert_eq!(error.message, "Unknown message type");
+        assert_eq!(request.identity.unwrap().name, "statusbar");
+
+        let mut request = RequestContext::new(1).with_token(Some("read-secret".to_string()));
+        let error = pipeline
+            .dispatch(
+                &mut request,
+                Message::WorkspaceDelete {
+                    name: "dev".to_string(),
+                },
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, ErrorCode::Forbidden);
+    }
+
+    #[tokio::test]
+    async fn test_destructive_requests_need_confirmation() {
+        let authenticator = Arc::new(Authenticator::new(AuthConfig {
+            confirm: serde_yaml::from_str("scopes: [admin]").unwrap(),
+            ..Default::default()
+        }));
+        let pipeline = Pipeline::new()
+            .with_middleware(AuthMiddleware::new(Arc::clone(&authenticator)))
+            .with_middleware(ConfirmationMiddleware::new(authenticator));
+        let delete = || Message::WorkspaceDelete {
+            name: "dev".to_string(),
+        };
+
+        let error = pipeline
+            .dispatch(&mut RequestContext::new(1), delete())
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, ErrorCode::ConfirmationRequired);
+        let challenge = error.confirmation.unwrap();
+        assert_eq!(challenge.impact, "Deletes workspace 'dev'");
+
+        // Confirmed: passes on to the (missing) handler, and the token is used up
+        let mut request = RequestContext::new(1).with_confirmation(Some(challenge.token));
+        let error = pipeline
+            .dispatch(&mut request.clone(), delete())
+            .await
+            .unwrap_err();
+        assert_eq!(error.message, "Unknown message type");
+        let error = pipeline.dispatch(&mut request, delete()).await.unwrap_err();
+        assert_eq!(error.code, ErrorCode::ConfirmationRequired);
+
+        // Other operations are not held back
+        let error = pipeline
+            .dispatch(&mut RequestContext::new(1), Message::WorkspaceList)
+            .await
+            .unwrap_err();
+        assert_eq!(error.message, "Unknown message type");
+    }
+
+    #[tokio::test]
+    async fn test_logged_requests_leave_out_typed_input() {
+        let pipeline = Pipeline::new().with_middleware(LoggingMiddleware);
+        let _ = pipeline
+            .dispatch(
+                &mut RequestContext::new(7),
+                Message::ProcessStdinWrite {
+                    process_id: "shell".to_string(),
+                    data: "hunter2-stdin-payload\n".to_string(),
+                    close: false,
+                },
+            )
+            .await;
+
+        let logged = |search: &str| {
+            crate::logging::buffer::query(&crate::logging::buffer::LogQuery {
+                component: Some("ipc".to_string()),
+                search: Some(search.to_string()),
+                ..Default::default()
+            })
+            .unwrap()
+            .entries
+        };
+        assert!(!logged("ProcessStdinWrite").is_empty());
+        assert!(logged("hunter2-stdin-payload").is_empty());
+    }
+
+    #[test]
+    fn test_validation_rejects_blank_arguments() {
+        let error = validate_arguments(&Message::NoteAdd {
+            text: "  ".to_string(),
+            task_id: None,
+            workspace: None,
+        })
+        .unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidRequest);
+        assert_eq!(error.component, "notes");
+
+        assert!(validate_arguments(&Message::TaskQueue {
+            id: "t1".to_string(),
+            priority: 5,
+            command: String::new(),
+            working_dir: None,
+            env: HashMap::new(),
+            shell: None,
+            timeout_secs: None,
+            assignee: None,
+        })
+        .is_err());
+        assert!(validate_arguments(&Message::TaskQueue {
+            id: "t1".to_string(),
+            priority: 5,
+            command: "make".to_string(),
+            working_dir: None,
+            env: HashMap::from([("A=B".to_string(), "c".to_string())]),
+            shell: None,
+            timeout_secs: None,
+            assignee: None,
+        })
+        .is_err());
+        let error = validate_arguments(&Message::TaskQueueBatch {
+            tasks: vec![crate::task::BatchTask {
+                key: Some("build".to_string()),
+                command: "make".to_string(),
+                timeout_secs: Some(0),
+                ..Default::default()
+            }],
+            atomic: false,
+            depends_on: Vec::new(),
+        })
+        .unwrap_err();
+        assert!(error.message.starts_with("Task 'build': "));
+        assert!(validate_arguments(&Message::SessionTag {
+            task_id: "t1".to_string(),
+            started_at: None,
+            tags: Vec::new(),
+        })
+        .is_err());
+        assert!(validate_arguments(&Message::WorkspaceCreate {
+            name: "dev".to_string(),
+            template: "basic".to_string(),
+            project_dir: None,
+            variables: Default::default(),
+        })
+        .is_ok());
+        assert!(validate_arguments(&Message::WorkspaceCreate {
+            name: "dev".to_string(),
+            template: "basic".to_string(),
+            project_dir: Some("relative/dir".to_string()),
+            variables: Default::default(),
+        })
+        .is_err());
+        assert!(validate_arguments(&Message::SyncResolve {
+            conflict_id: " ".to_string(),
+            strategy: crate::sync::ResolutionStrategy::Ours,
+            content: None,
+        })
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_per_connection() {
+        let limits = IpcLimits {
+            rate_limit_per_sec: 1,
+            rate_limit_burst: 2,
+            ..Default::default()
+        };
+        let shed_log = Arc::new(ShedLog::new());
+        let limiter = RateLimitMiddleware::new(limits).with_shed_log(Arc::clone(&shed_log));
+        let mut first = RequestContext::new(1);
+        let mut second = RequestContext::new(2);
+
+        assert!(limiter.before(&mut first, &Message::Ping).await.is_ok());
+        assert!(limiter.before(&mut first, &Message::Ping).await.is_ok());
+        let error = limiter
+            .before(&mut first, &Message::Ping)
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, ErrorCode::RateLimited);
+        let report = shed_log.report(None);
+        assert_eq!(report.incidents.len(), 1);
+        assert_eq!(report.incidents[0].action, ShedAction::Rejected);
+
+        // Other connections have their own budget
+        assert!(limiter.before(&mut second, &Message::Ping).await.is_ok());
+        assert_eq!(limiter.tracked_connections(), 2);
+
+        limiter.disconnected(1);
+        assert_eq!(limiter.tracked_connections(), 1);
+    }
+
+    #[test]
+    fn test_metrics_per_operation() {
+        let shared = Arc::new(ProtocolMetrics::new());
+        let metrics = MetricsMiddleware::new().with_protocol_metrics(Arc::clone(&shared));
+        let request = RequestContext {
+            operation: "TaskQueue".to_string(),
+            ..Default::default()
+        };
+        metrics.after(&request, &Ok(Message::Pong), Duration::from_millis(10));
+        metrics.after(
+            &request,
+            &Err(IpcError::new(ErrorCode::QueueFull, "task_manager", "full")),
+            Duration::from_millis(30),
+        );
+
+        let health = shared.health();
+        assert_eq!(health.len(), 1);
+        assert_eq!(health[0].transport, "ipc");
+        assert_eq!(health[0].operation, "TaskQueue");
+        assert_eq!(health[0].requests, 2);
+        assert_eq!(health[0].errors, 1);
+        assert!((health[0].avg_ms - 20.0).abs() < 0.01);
+    }
+}
@@ -0,0 +1,472 @@
+// WezTerm Multi-Process Development Framework - IPC Protocol Envelope
+// Request/response envelope with correlation IDs and typed error codes
+//
+// Clients wrap a `Message` in a `Request` carrying a `request_id`; the server
+// answers with a `Response` echoing that ID and holding either the reply
+// message or an `IpcError`. Bare `Message`s are still accepted and answered
+// the legacy way, with errors folded into `Message::StatusUpdate`.
+//
+// On the server every decoded request goes through a `Pipeline` of
+// middleware and per-family handlers (see `pipeline` and `handlers`).
+
+pub mod handlers;
+pub mod limits;
+pub mod middleware;
+pub mod pipeline;
+pub mod recorder;
+pub mod stream;
+pub mod transport;
+
+pub use limits::{ConnectionLimiter, FrameDecoder, IpcLimits, RateLimiter};
+pub use middleware::{
+    AuditMiddleware, AuthMiddleware, ConfirmationMiddleware, LoggingMiddleware, MetricsMiddleware,
+    RateLimitMiddleware, ValidationMiddleware,
+};
+pub use pipeline::{Handler, Middleware, Pipeline, PipelineFuture, RequestContext};
+pub use recorder::{RecordingMiddleware, SessionRecorder};
+pub use stream::{encode_stream, split_message, ChunkAssembler, ChunkInfo};
+pub use transport::{default_socket_path, BoxedIpcStream, IpcListener};
+
+use crate::auth::ConfirmationChallenge;
+use crate::error::UserError;
+use crate::Message;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Machine-readable error category
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// Malformed request, invalid arguments or unsupported message
+    InvalidRequest,
+
+    /// Workspace, task or session does not exist
+    NotFound,
+
+    /// Task queue has no free slots
+    QueueFull,
+
+    /// Operation did not finish in time
+    Timeout,
+
+    /// Task dependency not met
+    DependencyFailed,
+
+    /// Process could not be started or reached
+    ProcessError,
+
+    /// Configuration could not be loaded or is invalid
+    ConfigError,
+
+    /// File operation failed
+    FileError,
+
+    /// Temporarily refused, e.g. during maintenance or resource exhaustion
+    Unavailable,
+
+    /// Connection sent messages faster than the configured rate
+    RateLimited,
+
+    /// Message exceeded the configured size limit
+    PayloadTooLarge,
+
+    /// Missing or invalid credentials
+    Unauthorized,
+
+    /// Authenticated client lacks the scope for the operation
+    Forbidden,
+
+    /// Destructive operation must be re-sent with the returned confirmation token
+    ConfirmationRequired,
+
+    /// Anything else
+    Internal,
+}
+
+impl ErrorCode {
+    /// Map a `UserError::error_code` (e.g. `ROOM_001`) to an error code
+    pub fn from_user_error_code(code: &str) -> Self {
+        match code {
+            "ROOM_001" | "TASK_001" => ErrorCode::NotFound,
+            "TASK_002" => ErrorCode::QueueFull,
+            "TASK_003" => ErrorCode::Timeout,
+            "TASK_004" => ErrorCode::DependencyFailed,
+            _ => match code.split('_').next().unwrap_or_default() {
+                "ROOM" => ErrorCode::InvalidRequest,
+                "PROC" => ErrorCode::ProcessError,
+                "CONF" => ErrorCode::ConfigError,
+                "FILE" => ErrorCode::FileError,
+                "SYS" => ErrorCode::Unavailable,
+                _ => ErrorCode::Internal,
+            },
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::InvalidRequest => "invalid_request",
+            ErrorCode::NotFound => "not_found",
+            ErrorCode::QueueFull => "queue_full",
+            ErrorCode::Timeout => "timeout",
+            ErrorCode::DependencyFailed => "dependency_failed",
+            ErrorCode::ProcessError => "process_error",
+            ErrorCode::ConfigError => "config_error",
+            ErrorCode::FileError => "file_error",
+            ErrorCode::Unavailable => "unavailable",
+            ErrorCode::RateLimited => "rate_limited",
+            ErrorCode::PayloadTooLarge => "payload_too_large",
+            ErrorCode::Unauthorized => "unauthorized",
+            ErrorCode::Forbidden => "forbidden",
+            ErrorCode::ConfirmationRequired => "confirmation_required",
+            ErrorCode::Internal => "internal",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&UserError> for ErrorCode {
+    fn from(error: &UserError) -> Self {
+        ErrorCode::from_user_error_code(&error.error_code)
+    }
+}
+
+/// Error payload of a failed request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IpcError {
+    pub code: ErrorCode,
+    pub message: String,
+
+    /// Component that rejected the request (`workspace_manager`, `task_manager`, ...)
+    pub component: String,
+
+    /// Original `UserError` code, when the failure came from one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_error_code: Option<String>,
+
+    /// Suggested next step for the user
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guidance: Option<String>,
+
+    /// Token and impact summary of an operation awaiting confirmation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirmation: Option<Box<ConfirmationChallenge>>,
+}
+
+impl IpcError {
+    pub fn new(code: ErrorCode, component: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            component: component.into(),
+            user_error_code: None,
+            guidance: None,
+            confirmation: None,
+        }
+    }
+
+    /// Ask the client to confirm a destructive operation
+    pub fn confirmation_required(
+        component: impl Into<String>,
+        challenge: ConfirmationChallenge,
+    ) -> Self {
+        Self {
+            guidance: Some(
+                "Re-send the same request with `confirm` set to the confirmation token".to_string(),
+            ),
+            confirmation: Some(Box::new(challenge.clone())),
+            ..Self::new(
+                ErrorCode::ConfirmationRequired,
+                component,
+                format!(
+                    "{} needs confirmation: {}",
+                    challenge.operation, challenge.impact
+                ),
+            )
+        }
+    }
+
+    /// Wrap a `UserError`, keeping its code and guidance
+    pub fn from_user_error(
+        component: impl Into<String>,
+        message: impl Into<String>,
+        error: &UserError,
+    ) -> Self {
+        Self {
+            code: ErrorCode::from(error),
+            message: message.into(),
+            component: component.into(),
+            user_error_code: Some(error.error_code.clone()),
+            guidance: Some(error.guidance.clone()),
+            confirmation: None,
+        }
+    }
+
+    /// Legacy reply for clients that sent a bare `Message`
+    pub fn into_status_update(self) -> Message {
+        Message::StatusUpdate {
+            process_id: self.component,
+            status: self.message,
+        }
+    }
+}
+
+impl fmt::Display for IpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for IpcError {}
+
+/// Request envelope sent by clients
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Request {
+    /// Client-chosen ID echoed back in the response
+    pub request_id: String,
+    pub message: Message,
+
+    /// API token, required when authentication is enabled for non-local clients
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+
+    /// Client accepts large list replies as several chunk frames (see `stream`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stream: bool,
+
+    /// Confirmation token of a destructive operation (see `ErrorCode::ConfirmationRequired`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirm: Option<String>,
+}
+
+impl Request {
+    /// Wrap a message with a fresh request ID
+    pub fn new(message: Message) -> Self {
+        Self {
+            request_id: uuid::Uuid::new_v4().to_string(),
+            message,
+            token: None,
+            stream: false,
+            confirm: None,
+        }
+    }
+
+    /// Attach an API token
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Accept chunked replies
+    pub fn with_streaming(mut self) -> Self {
+        self.stream = true;
+        self
+    }
+
+    /// Confirm a destructive operation with the token the server returned
+    pub fn with_confirmation(mut self, token: impl Into<String>) -> Self {
+        self.confirm = Some(token.into());
+        self
+    }
+}
+
+/// Outcome carried by a `Response`
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseBody {
+    Ok(Message),
+    Error(IpcError),
+}
+
+/// Response envelope: `{"request_id": "...", "ok": {...}}` or `{"request_id": "...", "error": {...}}`
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Response {
+    pub request_id: String,
+
+    #[serde(flatten)]
+    pub body: ResponseBody,
+
+    /// Set on the frames of a chunked reply
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunk: Option<ChunkInfo>,
+}
+
+impl Response {
+    pub fn new(request_id: impl Into<String>, result: Result<Message, IpcError>) -> Self {
+        Self {
+            request_id: request_id.into(),
+            body: match result {
+                Ok(message) => ResponseBody::Ok(message),
+                Err(error) => ResponseBody::Error(error),
+            },
+            chunk: None,
+        }
+    }
+
+    /// Mark this response as one chunk of a streamed reply
+    pub fn with_chunk(mut self, chunk: ChunkInfo) -> Self {
+        self.chunk = Some(chunk);
+        self
+    }
+
+    pub fn into_result(self) -> Result<Message, IpcError> {
+        match self.body {
+            ResponseBody::Ok(message) => Ok(message),
+            ResponseBody::Error(error) => Err(error),
+        }
+    }
+}
+
+/// A decoded inbound IPC payload
+#[derive(Debug, PartialEq)]
+pub enum Incoming {
+    /// `Request` envelope
+    Envelope(Request),
+
+    /// Bare `Message` from a legacy client
+    Legacy(Message),
+}
+
+/// Inbound payload that could not be decoded
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseFailure {
+    /// Set when the payload was recognisably an envelope
+    pub request_id: Option<String>,
+    pub error: IpcError,
+}
+
+impl Incoming {
+    /// Decode an envelope (an object with a `request_id`) or a bare message
+    #[allow(clippy::result_large_err)]
+    pub fn parse(data: &[u8]) -> Result<Self, ParseFailure> {
+        let failure = |request_id: Option<String>, e: serde_json::Error| ParseFailure {
+            request_id,
+            error: IpcError::new(
+                ErrorCode::InvalidRequest,
+                "system",
+                format!("Parse error: {e}"),
+            ),
+        };
+
+        let value: serde_json::Value =
+            serde_json::from_slice(data).map_err(|e| failure(None, e))?;
+        match value.get("request_id").and_then(|id| id.as_str()) {
+            Some(request_id) => {
+                let request_id = request_id.to_string();
+                serde_json::from_value(value)
+                    .map(Incoming::Envelope)
+                    .map_err(|e| failure(Some(request_id), e))
+            }
+            None => serde_json::from_value(value)
+                .map(Incoming::Legacy)
+                .map_err(|e| failure(None, e)),
+        }
+    }
+}
+
+/// Serialize the reply for a request, in envelope form when it had a `request_id`
+pub fn encode_reply(
+    request_id: Option<String>,
+    result: Result<Message, IpcError>,
+) -> serde_json::Result<Vec<u8>> {
+    match request_id {
+        Some(request_id) => serde_json::to_vec(&Response::new(request_id, result)),
+        None => serde_json::to_vec(&result.unwrap_or_else(IpcError::into_status_update)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_code_mapping() {
+        let cases = [
+            (UserError::room_not_found("dev"), ErrorCode::NotFound),
+            (
+                UserError::room_creation_failed("dev", "exists"),
+                ErrorCode::InvalidRequest,
+            ),
+            (
+                UserError::process_communication_failed("p1"),
+                ErrorCode::ProcessError,
+            ),
+            (
+                UserError::config_load_failed("a.yaml", "bad"),
+                ErrorCode::ConfigError,
+            ),
+            (
+                UserError::system_resource_exhausted("memory"),
+                ErrorCode::Unavailable,
+            ),
+            (UserError::task_queue_full(), ErrorCode::QueueFull),
+            (
+                UserError::from(crate::task::TaskError::TaskNotFound("t1".to_string())),
+                ErrorCode::NotFound,
+            ),
+        ];
+        for (error, code) in cases {
+            assert_eq!(ErrorCode::from(&error), code, "{}", error.error_code);
+        }
+
+        let error = IpcError::from_user_error(
+            "workspace_manager",
+            "Failed to switch",
+            &UserError::room_not_found("dev"),
+        );
+        assert_eq!(error.user_error_code.as_deref(), Some("ROOM_001"));
+        assert_eq!(error.to_string(), "[not_found] Failed to switch");
+    }
+
+    #[test]
+    fn test_envelope_roundtrip() {
+        let request = Request::new(Message::Ping);
+        let json = serde_json::to_vec(&request).unwrap();
+        let Ok(Incoming::Envelope(parsed)) = Incoming::parse(&json) else {
+            panic!("expected envelope");
+        };
+        assert_eq!(parsed, request);
+
+        let reply = encode_reply(Some("r1".to_string()), Ok(Message::Pong)).unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&reply).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({ "request_id": "r1", "ok": "Pong" })
+        );
+
+        let error = IpcError::new(ErrorCode::NotFound, "task_manager", "missing");
+        let reply = encode_reply(Some("r2".to_string()), Err(error.clone())).unwrap();
+        let response: Response = serde_json::from_slice(&reply).unwrap();
+        assert_eq!(response.request_id, "r2");
+        assert_eq!(response.into_result(), Err(error));
+    }
+
+    #[test]
+    fn test_legacy_and_parse_failures() {
+        let json = serde_json::to_vec(&Message::Ping).unwrap();
+        assert_eq!(Incoming::parse(&json), Ok(Incoming::Legacy(Message::Ping)));
+
+        // Legacy clients keep getting errors as StatusUpdate
+        let error = IpcError::new(ErrorCode::Unavailable, "task_manager", "draining");
+        let reply = encode_reply(None, Err(error)).unwrap();
+        assert_eq!(
+            serde_json::from_slice::<Message>(&reply).unwrap(),
+            Message::StatusUpdate {
+                process_id: "task_manager".to_string(),
+                status: "draining".to_string(),
+            }
+        );
+
+        let failure = Incoming::parse(br#"{"request_id":"r3","message":"Nope"}"#).unwrap_err();
+        assert_eq!(failure.request_id.as_deref(), Some("r3"));
+        assert_eq!(failure.error.code, ErrorCode::InvalidRequest);
+
+        let failure = Incoming::parse(b"not json").unwrap_err();
+        assert_eq!(failure.request_id, None);
+        assert!(failure.error.message.starts_with("Parse error:"));
+    }
+}
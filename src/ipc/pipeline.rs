@@ -0,0 +1,362 @@
+// WezTerm Multi-Process Development Framework - IPC Request Pipeline
+// Middleware chain and per-family handlers that every IPC request passes through
+//
+// A request first runs through the middleware in registration order
+// (auth → logging → validation → rate limit → metrics in the server); any
+// stage may reject it. Accepted requests go to the first handler that claims
+// the message, and the middleware that let it through see the outcome in
+// reverse order.
+
+use super::{ErrorCode, IpcError};
+use crate::auth::{self, ClientIdentity};
+use crate::log_warn;
+use crate::logging::LogContext;
+use crate::Message;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Boxed future returned by middleware and handlers
+pub type PipelineFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Per-request state shared by the pipeline stages
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RequestContext {
+    /// Connection the request arrived on
+    pub connection_id: u64,
+
+    /// User ID of the socket peer, when the OS reported it
+    pub peer_uid: Option<u32>,
+
+    /// Envelope request ID (`None` for legacy messages)
+    pub request_id: Option<String>,
+
+    /// API token sent with the envelope
+    pub token: Option<String>,
+
+    /// Confirmation token of a destructive operation sent with the envelope
+    pub confirm: Option<String>,
+
+    /// Authenticated client, set by the auth middleware
+    pub identity: Option<ClientIdentity>,
+
+    /// Variant name of the message (e.g. `TaskQueue`), set by the pipeline
+    pub operation: String,
+}
+
+impl RequestContext {
+    pub fn new(connection_id: u64) -> Self {
+        Self {
+            connection_id,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_peer_uid(mut self, uid: Option<u32>) -> Self {
+        self.peer_uid = uid;
+        self
+    }
+
+    pub fn with_request_id(mut self, request_id: Option<String>) -> Self {
+        self.request_id = request_id;
+        self
+    }
+
+    pub fn with_token(mut self, token: Option<String>) -> Self {
+        self.token = token;
+        self
+    }
+
+    pub fn with_confirmation(mut self, confirm: Option<String>) -> Self {
+        self.confirm = confirm;
+        self
+    }
+}
+
+/// Cross-cutting stage run around every request
+pub trait Middleware: Send + Sync {
+    /// Short name used in logs
+    fn name(&self) -> &'static str;
+
+    /// Inspect the request before it is handled; an error rejects it
+    fn before<'a>(
+        &'a self,
+        request: &'a mut RequestContext,
+        message: &'a Message,
+    ) -> PipelineFuture<'a, Result<(), IpcError>>;
+
+    /// Observe the outcome of a request this stage let through
+    fn after(
+        &self,
+        _request: &RequestContext,
+        _result: &Result<Message, IpcError>,
+        _elapsed: Duration,
+    ) {
+    }
+
+    /// Drop any state kept for a closed connection
+    fn disconnected(&self, _connection_id: u64) {}
+}
+
+/// Executes one family of messages (workspaces, tasks, ...)
+pub trait Handler: Send + Sync {
+    /// Short name used in logs
+    fn name(&self) -> &'static str;
+
+    /// Whether this handler executes the message
+    fn handles(&self, message: &Message) -> bool;
+
+    fn handle<'a>(
+        &'a self,
+        message: Message,
+        request: &'a RequestContext,
+    ) -> PipelineFuture<'a, Result<Message, IpcError>>;
+}
+
+/// Ordered middleware chain plus the registered handlers
+#[derive(Default)]
+pub struct Pipeline {
+    middleware: Vec<Arc<dyn Middleware>>,
+    handlers: Vec<Arc<dyn Handler>>,
+    next_connection_id: AtomicU64,
+}
+
+impl Pipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a middleware stage; stages run in the order they are added
+    pub fn with_middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    pub fn with_handler(mut self, handler: impl Handler + 'static) -> Self {
+        self.register_handler(Arc::new(handler));
+        self
+    }
+
+    /// Add a handler; earlier handlers win when several claim a message
+    pub fn register_handler(&mut self, handler: Arc<dyn Handler>) {
+        self.handlers.push(handler);
+    }
+
+    /// Names of the middleware stages in execution order
+    pub fn middleware_names(&self) -> Vec<&'static str> {
+        self.middleware.iter().map(|m| m.name()).collect()
+    }
+
+    /// Handler that would execute a message
+    pub fn handler_for(&self, message: &Message) -> Option<&Arc<dyn Handler>> {
+        self.handlers
+            .iter()
+            .find(|handler| handler.handles(message))
+    }
+
+    /// Allocate an ID for a new connection
+    pub fn open_connection(&self) -> u64 {
+        self.next_connection_id.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Release per-connection middleware state
+    pub fn close_connection(&self, connection_id: u64) {
+        for middleware in &self.middleware {
+            middleware.disconnected(connection_id);
+        }
+    }
+
+    /// Run a request through the middleware chain and its handler
+    pub async fn dispatch(
+        &self,
+        request: &mut RequestContext,
+        message: Message,
+    ) -> Result<Message, IpcError> {
+        let started = Instant::now();
+        request.operation = auth::operation_name(&message);
+
+        let mut admitted = 0;
+        let mut rejection = None;
+        for middleware in &self.middleware {
+            if let Err(error) = middleware.before(request, &message).await {
+                rejection = Some(error);
+                break;
+            }
+            admitted += 1;
+        }
+
+        let result = match rejection {
+            Some(error) => Err(error),
+            None => self.handle(message, request).await,
+        };
+
+        let elapsed = started.elapsed();
+        for middleware in self.middleware[..admitted].iter().rev() {
+            middleware.after(request, &result, elapsed);
+        }
+        result
+    }
+
+    async fn handle(
+        &self,
+        message: Message,
+        request: &RequestContext,
+    ) -> Result<Message, IpcError> {
+        match self.handler_for(&message) {
+            Some(handler) => handler.handle(message, request).await,
+            None => {
+                let unhandled_context = LogContext::new("ipc", "unhandled_message")
+                    .with_metadata("message_type", serde_json::json!(request.operation));
+                log_warn!(
+                    unhandled_context,
+                    "Unhandled message type: {}",
+                    request.operation
+                );
+                Err(IpcError::new(
+                    ErrorCode::InvalidRequest,
+                    "system",
+                    "Unknown message type",
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Records the order stages run in and optionally rejects
+    struct Recorder {
+        name: &'static str,
+        log: Arc<Mutex<Vec<String>>>,
+        reject: bool,
+    }
+
+    impl Middleware for Recorder {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn before<'a>(
+            &'a self,
+            _request: &'a mut RequestContext,
+            _message: &'a Message,
+        ) -> PipelineFuture<'a, Result<(), IpcError>> {
+            Box::pin(async move {
+                self.log
+                    .lock()
+                    .unwrap()
+                    .push(format!("before:{}", self.name));
+                if self.reject {
+                    Err(IpcError::new(ErrorCode::Forbidden, self.name, "rejected"))
+                } else {
+                    Ok(())
+                }
+            })
+        }
+
+        fn after(
+            &self,
+            _request: &RequestContext,
+            result: &Result<Message, IpcError>,
+            _elapsed: Duration,
+        ) {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("after:{}:{}", self.name, result.is_ok()));
+        }
+    }
+
+    struct PingHandler;
+
+    impl Handler for PingHandler {
+        fn name(&self) -> &'static str {
+            "ping"
+        }
+
+        fn handles(&self, message: &Message) -> bool {
+            matches!(message, Message::Ping)
+        }
+
+        fn handle<'a>(
+            &'a self,
+            _message: Message,
+            _request: &'a RequestContext,
+        ) -> PipelineFuture<'a, Result<Message, IpcError>> {
+            Box::pin(async { Ok(Message::Pong) })
+        }
+    }
+
+    fn pipeline(log: &Arc<Mutex<Vec<String>>>, reject_second: bool) -> Pipeline {
+        let stage = |name, reject| Recorder {
+            name,
+            log: Arc::clone(log),
+            reject,
+        };
+        Pipeline::new()
+            .with_middleware(stage("first", false))
+            .with_middleware(stage("second", reject_second))
+            .with_middleware(stage("third", false))
+            .with_handler(PingHandler)
+    }
+
+    #[tokio::test]
+    async fn test_middleware_order_and_handler() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let pipeline = pipeline(&log, false);
+        assert_eq!(pipeline.middleware_names(), ["first", "second", "third"]);
+
+        let mut request = RequestContext::new(pipeline.open_connection());
+        let reply = pipeline.dispatch(&mut request, Message::Ping).await;
+        assert_eq!(reply, Ok(Message::Pong));
+        assert_eq!(request.operation, "Ping");
+        assert_eq!(
+            *log.lock().unwrap(),
+            [
+                "before:first",
+                "before:second",
+                "before:third",
+                "after:third:true",
+                "after:second:true",
+                "after:first:true",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejection_short_circuits() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let pipeline = pipeline(&log, true);
+
+        let mut request = RequestContext::new(1);
+        let error = pipeline
+            .dispatch(&mut request, Message::Ping)
+            .await
+            .unwrap_err();
+        assert_eq!(error.component, "second");
+        // Later stages and the handler never ran; only the first stage sees the outcome
+        assert_eq!(
+            *log.lock().unwrap(),
+            ["before:first", "before:second", "after:first:false"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unknown_message() {
+        let pipeline = Pipeline::new().with_handler(PingHandler);
+        assert!(pipeline.handler_for(&Message::WorkspaceList).is_none());
+        assert_eq!(pipeline.handler_for(&Message::Ping).unwrap().name(), "ping");
+
+        let error = pipeline
+            .dispatch(&mut RequestContext::new(1), Message::WorkspaceList)
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidRequest);
+        assert_eq!(error.message, "Unknown message type");
+    }
+}
@@ -0,0 +1,410 @@
+// WezTerm Multi-Process Development Framework - IPC Session Recorder
+// Writes every request and its outcome to a JSON Lines session file, and
+// replays recorded sessions to reproduce multi-process interactions
+
+use super::pipeline::{Middleware, Pipeline, PipelineFuture, RequestContext};
+use super::IpcError;
+use crate::auth;
+use crate::process::env::{is_secret, REDACTED};
+use crate::Message;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Which way a recorded message travelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    /// Request from a client
+    Inbound,
+
+    /// Reply or error sent back
+    Outbound,
+}
+
+/// One line of a session file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedMessage {
+    pub timestamp_ms: u64,
+    pub connection_id: u64,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    pub direction: Direction,
+
+    /// Variant name of the request
+    pub operation: String,
+
+    /// Request or reply; secret environment values are redacted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<Message>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<IpcError>,
+
+    /// Time the request took (outbound only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elapsed_ms: Option<u64>,
+}
+
+impl RecordedMessage {
+    /// Reply variant name, or `error:<code>`
+    pub fn outcome(&self) -> Option<String> {
+        match (&self.error, &self.message) {
+            (Some(error), _) => Some(format!("error:{}", error.code)),
+            (None, Some(message)) => Some(auth::operation_name(message)),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Appends recorded messages to a session file readable only by its owner
+#[derive(Debug)]
+pub struct SessionRecorder {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl SessionRecorder {
+    pub fn create(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut options = OpenOptions::new();
+        options.create(true).append(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let file = options.open(&path)?;
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn record(&self, entry: &RecordedMessage) {
+        let mut line = match serde_json::to_vec(entry) {
+            Ok(line) => line,
+            Err(e) => {
+                tracing::warn!("Failed to encode recorded {}: {}", entry.operation, e);
+                return;
+            }
+        };
+        line.push(b'\n');
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = file.write_all(&line) {
+            tracing::warn!("Failed to write to {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// Read a session file written by [`SessionRecorder`]
+pub fn read_session(path: impl AsRef<Path>) -> Result<Vec<RecordedMessage>, String> {
+    let path = path.as_ref();
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str(line).map_err(|e| format!("{}:{}: {e}", path.display(), index + 1))
+        })
+        .collect()
+}
+
+/// Copy of a request that is safe to write to disk; peer tokens are replaced
+/// too
+pub(crate) fn redact(message: &Message) -> Message {
+    let redact_values = |values: &HashMap<String, String>| {
+        values
+            .iter()
+            .map(|(name, value)| {
+                let value = if is_secret(name) { REDACTED } else { value };
+                (name.clone(), value.to_string())
+            })
+            .collect()
+    };
+    let mut message = message.clone();
+    match &mut message {
+        Message::TaskQueue { env, .. } => *env = redact_values(env),
+        Message::ProcessEnvSet { set, .. } => {
+            for (name, value) in set.iter_mut() {
+                if is_secret(name) {
+                    *value = REDACTED.to_string();
+                }
+            }
+        }
+        // Typed input may be a password
+        Message::ProcessInput { data, .. } | Message::ProcessStdinWrite { data, .. } => {
+            *data = REDACTED.to_string()
+        }
+        Message::PeerRegister { peer } => {
+            if let Some(token) = peer.token.as_mut() {
+                *token = REDACTED.to_string();
+            }
+        }
+        Message::PeerForward { message, .. } => **message = redact(message),
+        _ => {}
+    }
+    message
+}
+
+/// Records every request and its outcome; register it first so rejections
+/// by later stages are recorded too
+pub struct RecordingMiddleware {
+    recorder: Arc<SessionRecorder>,
+}
+
+impl RecordingMiddleware {
+    pub fn new(recorder: Arc<SessionRecorder>) -> Self {
+        Self { recorder }
+    }
+}
+
+impl Middleware for RecordingMiddleware {
+    fn name(&self) -> &'static str {
+        "recorder"
+    }
+
+    fn before<'a>(
+        &'a self,
+        request: &'a mut RequestContext,
+        message: &'a Message,
+    ) -> PipelineFuture<'a, Result<(), IpcError>> {
+        self.recorder.record(&RecordedMessage {
+            timestamp_ms: crate::clock::unix_millis(),
+            connection_id: request.connection_id,
+            request_id: request.request_id.clone(),
+            direction: Direction::Inbound,
+            operation: request.operation.clone(),
+            message: Some(redact(message)),
+            error: None,
+            elapsed_ms: None,
+        });
+        Box::pin(std::future::ready(Ok(())))
+    }
+
+    fn after(
+        &self,
+        request: &RequestContext,
+        result: &Result<Message, IpcError>,
+        elapsed: Duration,
+    ) {
+        let (message, error) = match result {
+            Ok(message) => (Some(message.clone()), None),
+            Err(error) => (None, Some(error.clone())),
+        };
+        self.recorder.record(&RecordedMessage {
+            timestamp_ms: crate::clock::unix_millis(),
+            connection_id: request.connection_id,
+            request_id: request.request_id.clone(),
+            direction: Direction::Outbound,
+            operation: request.operation.clone(),
+            message,
+            error,
+            elapsed_ms: Some(elapsed.as_millis() as u64),
+        });
+    }
+}
+
+/// Recorded request with the outcome recorded for it
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedRequest {
+    pub request: RecordedMessage,
+    pub recorded_outcome: Option<String>,
+}
+
+/// Requests of a session in order, paired with the replies sent on their
+/// connection
+pub fn recorded_requests(session: &[RecordedMessage]) -> Vec<RecordedRequest> {
+    let mut requests: Vec<RecordedRequest> = Vec::new();
+    let mut pending: HashMap<u64, VecDeque<usize>> = HashMap::new();
+    for entry in session {
+        match entry.direction {
+            Direction::Inbound if entry.message.is_some() => {
+                pending
+                    .entry(entry.connection_id)
+                    .or_default()
+                    .push_back(requests.len());
+                requests.push(RecordedRequest {
+                    request: entry.clone(),
+                    recorded_outcome: None,
+                });
+            }
+            Direction::Inbound => {}
+            Direction::Outbound => {
+                let index = pending
+                    .get_mut(&entry.connection_id)
+                    .and_then(VecDeque::pop_front);
+                if let Some(index) = index {
+                    requests[index].recorded_outcome = entry.outcome();
+                }
+            }
+        }
+    }
+    requests
+}
+
+/// Outcome of one replayed request next to the recorded one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayStep {
+    pub operation: String,
+    pub recorded: Option<String>,
+    pub replayed: String,
+}
+
+impl ReplayStep {
+    pub fn new(request: &RecordedRequest, result: &Result<Message, IpcError>) -> Self {
+        let replayed = match result {
+            Ok(message) => auth::operation_name(message),
+            Err(error) => format!("error:{}", error.code),
+        };
+        Self {
+            operation: request.request.operation.clone(),
+            recorded: request.recorded_outcome.clone(),
+            replayed,
+        }
+    }
+
+    /// Same reply variant or error code as recorded (or nothing was recorded)
+    pub fn matches(&self) -> bool {
+        self.recorded
+            .as_ref()
+            .map_or(true, |recorded| *recorded == self.replayed)
+    }
+}
+
+/// Feed the requests of a session through a pipeline in recorded order,
+/// one pipeline connection per recorded connection
+pub async fn replay(pipeline: &Pipeline, session: &[RecordedMessage]) -> Vec<ReplayStep> {
+    let mut connections = HashMap::new();
+    let mut steps = Vec::new();
+    for request in recorded_requests(session) {
+        let Some(message) = request.request.message.clone() else {
+            continue;
+        };
+        let connection_id = *connections
+            .entry(request.request.connection_id)
+            .or_insert_with(|| pipeline.open_connection());
+        let mut context =
+            RequestContext::new(connection_id).with_request_id(request.request.request_id.clone());
+        let result = pipeline.dispatch(&mut context, message).await;
+        steps.push(ReplayStep::new(&request, &result));
+    }
+    for connection_id in connections.into_values() {
+        pipeline.close_connection(connection_id);
+    }
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ipc::{ErrorCode, Handler};
+
+    /// Answers pings and rejects everything else
+    struct PingHandler;
+
+    impl Handler for PingHandler {
+        fn name(&self) -> &'static str {
+            "ping"
+        }
+
+        fn handles(&self, _message: &Message) -> bool {
+            true
+        }
+
+        fn handle<'a>(
+            &'a self,
+            message: Message,
+            _request: &'a RequestContext,
+        ) -> PipelineFuture<'a, Result<Message, IpcError>> {
+            let result = match message {
+                Message::Ping => Ok(Message::Pong),
+                _ => Err(IpcError::new(ErrorCode::NotFound, "test", "missing")),
+            };
+            Box::pin(std::future::ready(result))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay_session() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("session.jsonl");
+        let recorder = Arc::new(SessionRecorder::create(&path).unwrap());
+        let pipeline = Pipeline::new()
+            .with_middleware(RecordingMiddleware::new(recorder))
+            .with_handler(PingHandler);
+
+        let connection_id = pipeline.open_connection();
+        let mut context = RequestContext::new(connection_id);
+        pipeline
+            .dispatch(&mut context, Message::Ping)
+            .await
+            .unwrap();
+        let mut context = RequestContext::new(connection_id);
+        let trace = Message::TaskTrace {
+            id: "t-1".to_string(),
+        };
+        assert!(pipeline.dispatch(&mut context, trace).await.is_err());
+
+        let session = read_session(&path).unwrap();
+        assert_eq!(session.len(), 4);
+        assert_eq!(session[0].direction, Direction::Inbound);
+        assert_eq!(session[0].operation, "Ping");
+        assert_eq!(session[1].outcome().as_deref(), Some("Pong"));
+        assert_eq!(session[3].outcome().as_deref(), Some("error:not_found"));
+
+        // A fresh pipeline reproduces the same outcomes
+        let fresh = Pipeline::new().with_handler(PingHandler);
+        let steps = replay(&fresh, &session).await;
+        assert_eq!(steps.len(), 2);
+        assert!(steps.iter().all(ReplayStep::matches));
+    }
+
+    #[test]
+    fn test_secrets_are_redacted() {
+        let mut env = HashMap::new();
+        env.insert("API_TOKEN".to_string(), "s3cret".to_string());
+        env.insert("RUST_LOG".to_string(), "debug".to_string());
+        let message = Message::TaskQueue {
+            id: "t".to_string(),
+            priority: 5,
+            command: "make".to_string(),
+            working_dir: None,
+            env,
+            shell: None,
+            timeout_secs: None,
+            assignee: None,
+        };
+
+        match redact(&message) {
+            Message::TaskQueue { env, .. } => {
+                assert_eq!(env["API_TOKEN"], REDACTED);
+                assert_eq!(env["RUST_LOG"], "debug");
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+
+        let input = Message::ProcessInput {
+            process_id: "shell".to_string(),
+            data: "hunter2\n".to_string(),
+        };
+        match redact(&input) {
+            Message::ProcessInput { data, .. } => assert_eq!(data, REDACTED),
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+}
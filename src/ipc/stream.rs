@@ -0,0 +1,274 @@
+// WezTerm Multi-Process Development Framework - Streamed Responses
+// Splits large list replies into ordered chunk frames and reassembles them on the client
+//
+// A client that sets `stream` in its request envelope may receive a list reply
+// (e.g. `TemplateListResponse`) as several `Response` frames sharing the
+// request ID. Each frame carries a `chunk` marker with its sequence number and
+// whether more follow, and holds the same message variant with a slice of the
+// list. Replies that fit in one chunk are sent unmarked, as before.
+
+use super::{ErrorCode, IpcError, Response};
+use crate::Message;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Default size budget of one chunk frame, in bytes
+pub const DEFAULT_CHUNK_BYTES: usize = 16 * 1024;
+
+/// Continuation marker of a chunked response
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkInfo {
+    /// Position of the chunk, starting at 0
+    pub seq: u32,
+
+    /// Whether more chunks follow
+    pub more: bool,
+}
+
+/// Split a reply into messages of at most roughly `max_chunk_bytes` each
+///
+/// Only messages whose variant holds exactly one list field are split; each
+/// part keeps the other fields and a slice of the list, so every part is a
+/// valid message on its own. A single item larger than the budget gets a
+/// chunk of its own.
+pub fn split_message(message: Message, max_chunk_bytes: usize) -> Vec<Message> {
+    let Some(chunks) = split_value(&message, max_chunk_bytes) else {
+        return vec![message];
+    };
+    match chunks
+        .into_iter()
+        .map(serde_json::from_value)
+        .collect::<Result<Vec<Message>, _>>()
+    {
+        Ok(parts) => parts,
+        Err(_) => vec![message],
+    }
+}
+
+fn split_value(message: &Message, max_chunk_bytes: usize) -> Option<Vec<Value>> {
+    let value = serde_json::to_value(message).ok()?;
+    let (variant, fields) = value.as_object()?.iter().next()?;
+    let fields = fields.as_object()?;
+    let mut lists = fields.iter().filter(|(_, v)| v.is_array());
+    let (list_field, items) = lists.next()?;
+    if lists.next().is_some() {
+        return None;
+    }
+    let items = items.as_array()?;
+
+    let encoded_len = |v: &Value| serde_json::to_vec(v).map_or(0, |bytes| bytes.len());
+    if encoded_len(&value) <= max_chunk_bytes {
+        return None;
+    }
+
+    let wrap = |slice: &[Value]| {
+        let mut part = fields.clone();
+        part.insert(list_field.clone(), Value::Array(slice.to_vec()));
+        let mut outer = Map::new();
+        outer.insert(variant.clone(), Value::Object(part));
+        Value::Object(outer)
+    };
+    let budget = max_chunk_bytes.saturating_sub(encoded_len(&wrap(&[])));
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut size = 0;
+    for (index, item) in items.iter().enumerate() {
+        // Items are separated by a comma
+        let item_len = encoded_len(item) + 1;
+        if index > start && size + item_len > budget {
+            chunks.push(wrap(&items[start..index]));
+            start = index;
+            size = 0;
+        }
+        size += item_len;
+    }
+    chunks.push(wrap(&items[start..]));
+    Some(chunks)
+}
+
+/// Encode the reply to a streaming request as one or more response frames
+/// of at most roughly `max_chunk_bytes` each
+pub fn encode_stream(
+    request_id: String,
+    result: Result<Message, IpcError>,
+    max_chunk_bytes: usize,
+) -> serde_json::Result<Vec<Vec<u8>>> {
+    let message = match result {
+        Ok(message) => message,
+        Err(error) => {
+            return Ok(vec![serde_json::to_vec(&Response::new(
+                request_id,
+                Err(error),
+            ))?])
+        }
+    };
+
+    // Leave room for the envelope around each part
+    let marker = ChunkInfo {
+        seq: u32::MAX,
+        more: true,
+    };
+    let envelope = serde_json::to_vec(
+        &Response::new(request_id.clone(), Ok(Message::Ping)).with_chunk(marker),
+    )?;
+    let overhead = envelope
+        .len()
+        .saturating_sub(serde_json::to_vec(&Message::Ping)?.len());
+
+    let mut parts = split_message(message, max_chunk_bytes.saturating_sub(overhead));
+    if parts.len() == 1 {
+        let response = Response::new(request_id, Ok(parts.remove(0)));
+        return Ok(vec![serde_json::to_vec(&response)?]);
+    }
+
+    let last = parts.len() - 1;
+    parts
+        .into_iter()
+        .enumerate()
+        .map(|(index, part)| {
+            let response = Response::new(request_id.clone(), Ok(part)).with_chunk(ChunkInfo {
+                seq: index as u32,
+                more: index < last,
+            });
+            serde_json::to_vec(&response)
+        })
+        .collect()
+}
+
+/// Rebuilds a chunked reply from its frames
+#[derive(Debug, Default)]
+pub struct ChunkAssembler {
+    merged: Option<Value>,
+    next_seq: u32,
+}
+
+impl ChunkAssembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the next frame; returns the complete message after the last chunk
+    ///
+    /// Unchunked responses complete immediately, and errors end the stream.
+    pub fn push(&mut self, response: Response) -> Result<Option<Message>, IpcError> {
+        let chunk = response.chunk;
+        let message = response.into_result()?;
+        let Some(chunk) = chunk else {
+            return Ok(Some(message));
+        };
+        if chunk.seq != self.next_seq {
+            return Err(assembly_error(format!(
+                "Expected chunk {} but received chunk {}",
+                self.next_seq, chunk.seq
+            )));
+        }
+        self.next_seq += 1;
+
+        let part = serde_json::to_value(&message).map_err(|e| assembly_error(e.to_string()))?;
+        match self.merged.as_mut() {
+            None => self.merged = Some(part),
+            Some(merged) => append_list(merged, part)?,
+        }
+        if chunk.more {
+            return Ok(None);
+        }
+
+        let merged = self.merged.take().unwrap_or(Value::Null);
+        self.next_seq = 0;
+        serde_json::from_value(merged)
+            .map(Some)
+            .map_err(|e| assembly_error(format!("Invalid reassembled response: {e}")))
+    }
+}
+
+/// Append the list items of `part` to the same list in `merged`
+fn append_list(merged: &mut Value, part: Value) -> Result<(), IpcError> {
+    let mismatch = || assembly_error("Chunk does not continue the same response".to_string());
+    let (variant, fields) = match part {
+        Value::Object(outer) => outer.into_iter().next().ok_or_else(mismatch)?,
+        _ => return Err(mismatch()),
+    };
+    let target = merged
+        .get_mut(&variant)
+        .and_then(Value::as_object_mut)
+        .ok_or_else(mismatch)?;
+    for (name, value) in fields.as_object().ok_or_else(mismatch)? {
+        if let (Some(Value::Array(existing)), Value::Array(items)) = (target.get_mut(name), value) {
+            existing.extend(items.iter().cloned());
+        }
+    }
+    Ok(())
+}
+
+fn assembly_error(message: String) -> IpcError {
+    IpcError::new(ErrorCode::Internal, "client", message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TemplateInfo;
+
+    fn templates(count: usize) -> Message {
+        Message::TemplateListResponse {
+            templates: (0..count)
+                .map(|i| TemplateInfo {
+                    name: format!("template-{i}"),
+                    description: "x".repeat(100),
+                    author: "System".to_string(),
+                    version: "1.0".to_string(),
+                    created_at: "2024-01-01T00:00:00Z".to_string(),
+                    layout_type: "Grid".to_string(),
+                    pane_count: 4,
+                    auto_start_processes: false,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_split_and_reassemble() {
+        let frames = encode_stream("r1".to_string(), Ok(templates(50)), 2048).unwrap();
+        assert!(frames.len() > 1);
+        assert!(frames.iter().all(|frame| frame.len() <= 2048));
+
+        let mut assembler = ChunkAssembler::new();
+        let mut complete = None;
+        for (index, frame) in frames.iter().enumerate() {
+            let response: Response = serde_json::from_slice(frame).unwrap();
+            assert_eq!(response.request_id, "r1");
+            assert_eq!(response.chunk.unwrap().seq, index as u32);
+            complete = assembler.push(response).unwrap();
+            assert_eq!(complete.is_some(), index == frames.len() - 1);
+        }
+        assert_eq!(complete.unwrap(), templates(50));
+    }
+
+    #[test]
+    fn test_small_and_unsplittable_replies_stay_whole() {
+        let frames = encode_stream("r1".to_string(), Ok(templates(2)), 4096).unwrap();
+        assert_eq!(frames.len(), 1);
+        let response: Response = serde_json::from_slice(&frames[0]).unwrap();
+        assert_eq!(response.chunk, None);
+
+        // No list to split
+        let status = || Message::StatusUpdate {
+            process_id: "p".to_string(),
+            status: "x".repeat(10_000),
+        };
+        assert_eq!(split_message(status(), 1024), vec![status()]);
+
+        // One item over the budget still goes out, alone
+        assert_eq!(split_message(templates(3), 10).len(), 3);
+    }
+
+    #[test]
+    fn test_out_of_order_chunk_is_rejected() {
+        let frames = encode_stream("r1".to_string(), Ok(templates(50)), 2048).unwrap();
+        let mut assembler = ChunkAssembler::new();
+        let second: Response = serde_json::from_slice(&frames[1]).unwrap();
+        let error = assembler.push(second).unwrap_err();
+        assert!(error.message.contains("Expected chunk 0"));
+    }
+}
@@ -0,0 +1,449 @@
+// WezTerm Multi-Process Development Framework - IPC Transport
+// Platform sockets the IPC protocol runs over: Unix domain sockets, or named
+// pipes on Windows
+//
+// The default socket lives in the user's runtime directory: under
+// `$XDG_RUNTIME_DIR` on Linux (`/tmp` without one), under
+// `~/Library/Application Support/wezterm-parallel` on macOS, and is the
+// `\\.\pipe\wezterm-parallel` named pipe on Windows.
+//
+// Under systemd socket activation the socket unit owns the socket and passes
+// it to the daemon as file descriptor 3 (`LISTEN_FDS`).
+
+use std::io;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncRead, AsyncWrite};
+
+/// File name of the default Unix socket
+pub const SOCKET_NAME: &str = "wezterm-parallel.sock";
+
+/// Socket used when there is no runtime directory
+pub const FALLBACK_SOCKET_PATH: &str = "/tmp/wezterm-parallel.sock";
+
+/// Name of the default named pipe
+pub const PIPE_NAME: &str = r"\\.\pipe\wezterm-parallel";
+
+/// Variables systemd describes activated sockets with
+#[cfg(unix)]
+const LISTEN_PID_ENV: &str = "LISTEN_PID";
+#[cfg(unix)]
+const LISTEN_FDS_ENV: &str = "LISTEN_FDS";
+#[cfg(unix)]
+const LISTEN_FDNAMES_ENV: &str = "LISTEN_FDNAMES";
+
+/// First descriptor of activated sockets (`SD_LISTEN_FDS_START`)
+const LISTEN_FDS_START: i32 = 3;
+
+/// A connected IPC byte stream
+pub trait IpcStream: AsyncRead + AsyncWrite + Send + Unpin + std::fmt::Debug {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin + std::fmt::Debug> IpcStream for T {}
+
+/// IPC stream of either end, whatever the platform
+pub type BoxedIpcStream = Box<dyn IpcStream>;
+
+/// Socket the framework listens on and clients connect to by default
+#[cfg(target_os = "linux")]
+pub fn default_socket_path() -> PathBuf {
+    runtime_socket_path(std::env::var_os("XDG_RUNTIME_DIR").map(PathBuf::from))
+}
+
+/// Socket the framework listens on and clients connect to by default
+#[cfg(target_os = "macos")]
+pub fn default_socket_path() -> PathBuf {
+    dirs::home_dir()
+        .map(|home| {
+            home.join("Library/Application Support/wezterm-parallel")
+                .join(SOCKET_NAME)
+        })
+        .unwrap_or_else(|| PathBuf::from(FALLBACK_SOCKET_PATH))
+}
+
+/// Socket the framework listens on and clients connect to by default
+#[cfg(windows)]
+pub fn default_socket_path() -> PathBuf {
+    PathBuf::from(PIPE_NAME)
+}
+
+/// Socket the framework listens on and clients connect to by default
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+pub fn default_socket_path() -> PathBuf {
+    PathBuf::from(FALLBACK_SOCKET_PATH)
+}
+
+/// Default socket in an XDG runtime directory, if there is one
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn runtime_socket_path(runtime_dir: Option<PathBuf>) -> PathBuf {
+    match runtime_dir {
+        Some(dir) if dir.is_absolute() => dir.join(SOCKET_NAME),
+        _ => PathBuf::from(FALLBACK_SOCKET_PATH),
+    }
+}
+
+/// A client connection accepted by an [`IpcListener`]
+#[derive(Debug)]
+pub struct Accepted {
+    pub stream: BoxedIpcStream,
+
+    /// User ID of the connecting process, where the platform tells
+    pub peer_uid: Option<u32>,
+}
+
+/// Server end of the IPC socket
+#[derive(Debug)]
+pub struct IpcListener {
+    path: PathBuf,
+    owner_uid: Option<u32>,
+
+    #[cfg(unix)]
+    listener: tokio::net::UnixListener,
+
+    /// Pipe instance waiting for the next client
+    #[cfg(windows)]
+    next: tokio::net::windows::named_pipe::NamedPipeServer,
+}
+
+impl IpcListener {
+    /// Bind the socket in place of a stale one
+    ///
+    /// On Unix the socket file gets `mode` as permissions, restricting who
+    /// may connect, and its directory is created when missing. The socket is
+    /// bound in a private directory and moved into place once it has its
+    /// permissions, so it is never reachable with looser ones. Named pipes
+    /// refuse remote clients and ignore `mode`.
+    ///
+    /// Replaces whatever is at `path`; only bind once the instance lock is
+    /// held.
+    #[cfg(unix)]
+    pub fn bind(path: &Path, mode: u32) -> io::Result<Self> {
+        let mut listener = Self::bind_staged(path, mode)?;
+        publish(&listener.path, path)?;
+        listener.path = path.to_path_buf();
+        Ok(listener)
+    }
+
+    /// Bind the socket for `path` without touching `path` itself
+    ///
+    /// The socket stays in a private directory next to `path`, reachable at
+    /// [`IpcListener::path`], until [`publish`] moves it into place. Lets an
+    /// instance serve before it may replace the socket of a running one.
+    #[cfg(unix)]
+    pub fn bind_staged(path: &Path, mode: u32) -> io::Result<Self> {
+        use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let staging = private_dir(path);
+        let _ = std::fs::remove_dir_all(&staging);
+        std::fs::DirBuilder::new().mode(0o700).create(&staging)?;
+        // Short: socket paths are limited to about 100 bytes
+        let staged = staging.join("s");
+        let bound = tokio::net::UnixListener::bind(&staged).and_then(|listener| {
+            std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(mode))?;
+            Ok(Self {
+                owner_uid: Some(std::fs::metadata(&staged)?.uid()),
+                path: staged,
+                listener,
+            })
+        });
+        if bound.is_err() {
+            let _ = std::fs::remove_dir_all(&staging);
+        }
+        bound
+    }
+
+    /// Bind the socket in place of a stale one
+    ///
+    /// On Unix the socket file gets `mode` as permissions, restricting who
+    /// may connect, and its directory is created when missing. Named pipes
+    /// refuse remote clients and ignore `mode`.
+    #[cfg(windows)]
+    pub fn bind(path: &Path, _mode: u32) -> io::Result<Self> {
+        use tokio::net::windows::named_pipe::ServerOptions;
+
+        // Only one server may own the pipe name
+        let next = ServerOptions::new()
+            .first_pipe_instance(true)
+            .reject_remote_clients(true)
+            .create(path)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            owner_uid: None,
+            next,
+        })
+    }
+
+    /// Bind the pipe; it cannot be staged, but a pipe owned by a running
+    /// instance is refused rather than replaced
+    #[cfg(windows)]
+    pub fn bind_staged(path: &Path, mode: u32) -> io::Result<Self> {
+        Self::bind(path, mode)
+    }
+
+    /// Listener for the socket systemd passed in, when started by socket
+    /// activation
+    ///
+    /// The socket file and its permissions belong to the socket unit. The
+    /// activation variables are cleared so managed processes do not inherit
+    /// them.
+    #[cfg(unix)]
+    pub fn from_socket_activation() -> io::Result<Option<Self>> {
+        let listen_pid = std::env::var(LISTEN_PID_ENV).ok();
+        let listen_fds = std::env::var(LISTEN_FDS_ENV).ok();
+        for name in [LISTEN_PID_ENV, LISTEN_FDS_ENV, LISTEN_FDNAMES_ENV] {
+            std::env::remove_var(name);
+        }
+        match activated_fd(
+            listen_pid.as_deref(),
+            listen_fds.as_deref(),
+            std::process::id(),
+        ) {
+            // SAFETY: systemd hands the descriptor over to this process
+            Some(fd) => unsafe { Self::from_raw_fd(fd) }.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Named pipes are never socket activated
+    #[cfg(windows)]
+    pub fn from_socket_activation() -> io::Result<Option<Self>> {
+        Ok(None)
+    }
+
+    /// Take ownership of a listening Unix socket descriptor
+    ///
+    /// # Safety
+    ///
+    /// `fd` must be an open descriptor nothing else owns.
+    #[cfg(unix)]
+    unsafe fn from_raw_fd(fd: i32) -> io::Result<Self> {
+        use std::os::unix::fs::MetadataExt;
+        use std::os::unix::io::FromRawFd;
+
+        let listener = std::os::unix::net::UnixListener::from_raw_fd(fd);
+        listener.set_nonblocking(true)?;
+        let path = listener
+            .local_addr()?
+            .as_pathname()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "activated socket is not bound to a path",
+                )
+            })?;
+        Ok(Self {
+            owner_uid: Some(std::fs::metadata(&path)?.uid()),
+            path,
+            listener: tokio::net::UnixListener::from_std(listener)?,
+        })
+    }
+
+    /// Wait for the next client
+    #[cfg(unix)]
+    pub async fn accept(&mut self) -> io::Result<Accepted> {
+        let (stream, _) = self.listener.accept().await?;
+        let peer_uid = stream.peer_cred().ok().map(|cred| cred.uid());
+        Ok(Accepted {
+            stream: Box::new(stream),
+            peer_uid,
+        })
+    }
+
+    /// Wait for the next client
+    #[cfg(windows)]
+    pub async fn accept(&mut self) -> io::Result<Accepted> {
+        use tokio::net::windows::named_pipe::ServerOptions;
+
+        self.next.connect().await?;
+        // A new instance takes the following client while this one is served
+        let next = ServerOptions::new()
+            .reject_remote_clients(true)
+            .create(&self.path)?;
+        let connected = std::mem::replace(&mut self.next, next);
+        Ok(Accepted {
+            stream: Box::new(connected),
+            peer_uid: None,
+        })
+    }
+
+    /// Path of the socket or pipe
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// User owning the socket file; `None` for named pipes
+    pub fn owner_uid(&self) -> Option<u32> {
+        self.owner_uid
+    }
+}
+
+/// Directory only this user can enter, next to the socket at `path`, that
+/// the socket is bound in before it is moved into place
+#[cfg(unix)]
+fn private_dir(path: &Path) -> PathBuf {
+    path.with_file_name(format!(".wezterm-parallel-{}", std::process::id()))
+}
+
+/// Move a socket bound by [`IpcListener::bind_staged`] to `path`
+///
+/// Replaces a stale socket in one step.
+#[cfg(unix)]
+pub fn publish(staged: &Path, path: &Path) -> io::Result<()> {
+    std::fs::rename(staged, path)?;
+    if let Some(staging) = staged.parent() {
+        let _ = std::fs::remove_dir(staging);
+    }
+    Ok(())
+}
+
+/// Pipes are bound in place
+#[cfg(windows)]
+pub fn publish(_staged: &Path, _path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// Descriptor of the activated socket, if `LISTEN_PID` names this process
+#[cfg_attr(not(unix), allow(dead_code))]
+fn activated_fd(listen_pid: Option<&str>, listen_fds: Option<&str>, pid: u32) -> Option<i32> {
+    let listen_pid: u32 = listen_pid?.parse().ok()?;
+    let listen_fds: i32 = listen_fds?.parse().ok()?;
+    (listen_pid == pid && listen_fds >= 1).then_some(LISTEN_FDS_START)
+}
+
+/// Connect to the IPC socket at `path`
+#[cfg(unix)]
+pub async fn connect(path: &Path) -> io::Result<BoxedIpcStream> {
+    Ok(Box::new(tokio::net::UnixStream::connect(path).await?))
+}
+
+/// Connect to the IPC socket at `path`
+#[cfg(windows)]
+pub async fn connect(path: &Path) -> io::Result<BoxedIpcStream> {
+    use tokio::net::windows::named_pipe::ClientOptions;
+
+    /// All instances of the pipe are serving other clients
+    const ERROR_PIPE_BUSY: i32 = 231;
+
+    loop {
+        match ClientOptions::new().open(path) {
+            Ok(client) => return Ok(Box::new(client)),
+            Err(e) if e.raw_os_error() == Some(ERROR_PIPE_BUSY) => {
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[test]
+    fn test_runtime_socket_path() {
+        assert_eq!(
+            runtime_socket_path(Some(PathBuf::from("/run/user/1000"))),
+            Path::new("/run/user/1000/wezterm-parallel.sock")
+        );
+        assert_eq!(
+            runtime_socket_path(Some(PathBuf::from("relative"))),
+            Path::new(FALLBACK_SOCKET_PATH)
+        );
+        assert_eq!(runtime_socket_path(None), Path::new(FALLBACK_SOCKET_PATH));
+    }
+
+    #[tokio::test]
+    async fn test_listener_replaces_stale_socket_and_reports_peer() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("ipc.sock");
+        drop(IpcListener::bind(&path, 0o600).unwrap());
+
+        let mut listener = IpcListener::bind(&path, 0o600).unwrap();
+        assert!(listener.owner_uid().is_some());
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+            // Nothing is left of the directory it was bound in
+            let entries: Vec<_> = std::fs::read_dir(path.parent().unwrap())
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name())
+                .collect();
+            assert_eq!(entries, vec![std::ffi::OsString::from("ipc.sock")]);
+        }
+        let mut client = connect(&path).await.unwrap();
+        client.write_all(b"ping").await.unwrap();
+
+        let mut accepted = listener.accept().await.unwrap();
+        assert_eq!(accepted.peer_uid, listener.owner_uid());
+        let mut received = [0; 4];
+        accepted.stream.read_exact(&mut received).await.unwrap();
+        assert_eq!(&received, b"ping");
+    }
+
+    #[tokio::test]
+    async fn test_staged_listener_leaves_live_socket_until_published() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ipc.sock");
+        let _running = IpcListener::bind(&path, 0o600).unwrap();
+        let running_inode = {
+            use std::os::unix::fs::MetadataExt;
+            std::fs::metadata(&path).unwrap().ino()
+        };
+
+        let mut staged = IpcListener::bind_staged(&path, 0o600).unwrap();
+        assert_ne!(staged.path(), path);
+        {
+            use std::os::unix::fs::MetadataExt;
+            assert_eq!(std::fs::metadata(&path).unwrap().ino(), running_inode);
+        }
+        let mut client = connect(staged.path()).await.unwrap();
+        client.write_all(b"ping").await.unwrap();
+        let mut accepted = staged.accept().await.unwrap();
+        let mut received = [0; 4];
+        accepted.stream.read_exact(&mut received).await.unwrap();
+        assert_eq!(&received, b"ping");
+
+        publish(staged.path(), &path).unwrap();
+        assert!(!staged.path().exists());
+        let mut client = connect(&path).await.unwrap();
+        client.write_all(b"pong").await.unwrap();
+        let mut accepted = staged.accept().await.unwrap();
+        accepted.stream.read_exact(&mut received).await.unwrap();
+        assert_eq!(&received, b"pong");
+    }
+
+    #[test]
+    fn test_activated_fd_requires_matching_pid() {
+        assert_eq!(
+            activated_fd(Some("42"), Some("1"), 42),
+            Some(LISTEN_FDS_START)
+        );
+        assert_eq!(activated_fd(Some("41"), Some("1"), 42), None);
+        assert_eq!(activated_fd(Some("42"), Some("0"), 42), None);
+        assert_eq!(activated_fd(None, None, 42), None);
+    }
+
+    #[tokio::test]
+    async fn test_listener_from_activated_descriptor() {
+        use std::os::unix::io::IntoRawFd;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("activated.sock");
+        let fd = std::os::unix::net::UnixListener::bind(&path)
+            .unwrap()
+            .into_raw_fd();
+
+        let mut listener = unsafe { IpcListener::from_raw_fd(fd) }.unwrap();
+        assert_eq!(listener.path(), path);
+        let mut client = connect(&path).await.unwrap();
+        client.write_all(b"ping").await.unwrap();
+        let mut accepted = listener.accept().await.unwrap();
+        let mut received = [0; 4];
+        accepted.stream.read_exact(&mut received).await.unwrap();
+        assert_eq!(&received, b"ping");
+    }
+}
@@ -1,30 +1,194 @@
 // WezTerm Multi-Process Development Framework - Library
 
+pub mod audit;
+pub mod auth;
+pub mod bridge;
+pub mod client;
+pub mod clock;
 pub mod config;
 pub mod dashboard;
 pub mod error;
+pub mod federation;
+pub mod focus;
+pub mod fsck;
+pub mod git;
+pub mod github;
+pub mod hooks;
+pub mod i18n;
+pub mod instance;
+pub mod ipc;
 pub mod logging;
+pub mod lua_config;
+pub mod maintenance;
 pub mod metrics;
 pub mod monitoring;
 pub mod performance;
 pub mod process;
 pub mod room;
+pub mod secrets;
+pub mod service;
+pub mod supervisor;
 pub mod sync;
 pub mod task;
+pub mod telemetry;
+pub mod upgrade;
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::time::SystemTime;
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum Message {
     WorkspaceCreate {
         name: String,
         template: String,
+
+        /// Project root; its `.wezterm-parallel/templates` are searched first
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        project_dir: Option<String>,
+
+        /// Environment variables of the workspace's processes, over the template's
+        #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+        variables: std::collections::BTreeMap<String, String>,
+    },
+    WorkspaceDelete {
+        name: String,
+    },
+    WorkspaceRename {
+        name: String,
+        new_name: String,
+    },
+    WorkspaceList,
+    WorkspaceListResponse {
+        workspaces: Vec<WorkspaceInfo>,
+    },
+    WorkspaceSwitch {
+        name: String,
+    },
+    // Draining lets running tasks of a workspace finish while new tasks and
+    // processes are held back; draining again reports progress
+    WorkspaceDrain {
+        name: String,
+        #[serde(default)]
+        delete_when_empty: bool,
+    },
+    WorkspaceUndrain {
+        name: String,
+    },
+    WorkspaceDrainStatus,
+    WorkspaceDrainResponse {
+        drains: Vec<room::DrainProgress>,
+    },
+    // Working tree status of a workspace's repository; every workspace in a
+    // repository when no workspace is given
+    GitStatus {
+        #[serde(default)]
+        workspace: Option<String>,
+    },
+    GitStatusResponse {
+        statuses: Vec<git::GitStatus>,
     },
     ProcessSpawn {
         workspace: String,
         command: String,
     },
+    ProcessList {
+        workspace: Option<String>,
+    },
+    ProcessListResponse {
+        processes: Vec<ProcessSummary>,
+    },
+    // Effective environment of a managed process (secrets redacted) and
+    // overrides that apply on its next restart
+    ProcessEnv {
+        process_id: String,
+    },
+    ProcessEnvSet {
+        process_id: String,
+        #[serde(default)]
+        set: std::collections::BTreeMap<String, String>,
+        #[serde(default)]
+        unset: Vec<String>,
+        // Drop earlier overrides before applying `set` and `unset`
+        #[serde(default)]
+        reset: bool,
+    },
+    ProcessEnvResponse {
+        environment: process::ProcessEnvironment,
+    },
+    // Recent stdout/stderr of a managed process; pass the returned `cursor`
+    // as `since` to follow it
+    ProcessLogsGet {
+        process_id: String,
+        #[serde(default)]
+        since: Option<u64>,
+        #[serde(default)]
+        limit: Option<usize>,
+    },
+    ProcessLogsResponse {
+        process_id: String,
+        #[serde(flatten)]
+        output: process::output::OutputChunk,
+    },
+    // Follow the log file of a managed process across rotations; pass the
+    // returned `cursor` back to continue
+    ProcessLogTail {
+        process_id: String,
+        #[serde(flatten)]
+        filter: process::TailFilter,
+        #[serde(default)]
+        cursor: Option<process::TailCursor>,
+        #[serde(default)]
+        limit: Option<usize>,
+    },
+    ProcessLogTailResponse {
+        process_id: String,
+        #[serde(flatten)]
+        chunk: process::TailChunk,
+    },
+    // Processes quarantined by their circuit breaker after failing repeatedly
+    ProcessQuarantineList,
+    // Close the circuit breaker of a quarantined process so it may start again
+    ProcessCircuitReset {
+        process_id: String,
+    },
+    ProcessQuarantineResponse {
+        quarantined: Vec<process::Quarantine>,
+    },
+    // Type into the terminal of a process started on a PTY; `data` is sent
+    // as is, so end lines with "\n" (or "\r" for tools in raw mode)
+    ProcessInput {
+        process_id: String,
+        data: String,
+    },
+    // Write to the stdin of a managed process: its terminal when it runs on
+    // a PTY, otherwise the pipe its spec asked for (`stdin: true`); `close`
+    // ends the input afterwards
+    ProcessStdinWrite {
+        process_id: String,
+        data: String,
+        #[serde(default)]
+        close: bool,
+    },
+    ProcessInputResponse {
+        process_id: String,
+        bytes: usize,
+    },
+    // Resize the terminal of a process started on a PTY
+    ProcessResize {
+        process_id: String,
+        rows: u16,
+        cols: u16,
+    },
+    ProcessResizeResponse {
+        process_id: String,
+        size: process::TerminalSize,
+    },
+    // Overall framework status for CLI and status bars
+    SystemStatus,
+    SystemStatusResponse {
+        status: SystemStatusInfo,
+    },
     StatusUpdate {
         process_id: String,
         status: String,
@@ -33,6 +197,180 @@ pub enum Message {
         id: String,
         priority: u8,
         command: String,
+
+        /// Directory to run in, relative to the workspace root and inside it
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        working_dir: Option<String>,
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        env: HashMap<String, String>,
+
+        /// Shell to run `command` with; with a shell the framework runs the
+        /// command itself instead of handing the task to a workspace process
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        shell: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_secs: Option<u64>,
+
+        /// User the task is assigned to
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        assignee: Option<String>,
+    },
+    // Several tasks in one call; tasks name each other by key in
+    // `depends_on`, and the batch-wide `depends_on` applies to every task.
+    // Atomic batches create all tasks or none.
+    TaskQueueBatch {
+        tasks: Vec<task::BatchTask>,
+        #[serde(default)]
+        atomic: bool,
+        #[serde(default)]
+        depends_on: Vec<String>,
+    },
+    TaskQueueBatchResponse {
+        report: task::BatchReport,
+    },
+    TaskCancel {
+        id: String,
+    },
+    TaskPause {
+        id: String,
+    },
+    TaskResume {
+        id: String,
+    },
+    // Tasks that exhausted their retries, with failure history and last
+    // output; purging drops the entries but leaves the tasks failed
+    DeadLetterList,
+    DeadLetterListResponse {
+        entries: Vec<task::DeadLetter>,
+    },
+    DeadLetterRequeue {
+        id: String,
+    },
+    DeadLetterPurge {
+        #[serde(default)]
+        id: Option<String>,
+    },
+    // Execution attempts of a task across agents
+    TaskTrace {
+        id: String,
+    },
+    TaskTraceResponse {
+        trace: task::TaskTrace,
+    },
+    // Percentiles of each pipeline stage (enqueue → assigned → started →
+    // finished → reviewed) and stages over their latency budget
+    TaskLatencyReport {
+        #[serde(default)]
+        since_hours: Option<u64>,
+    },
+    TaskLatencyReportResponse {
+        report: task::LatencyReport,
+    },
+    // Exit status, captured stdout/stderr and artifacts of a task's last run
+    TaskResultGet {
+        id: String,
+    },
+    TaskResultResponse {
+        result: task::TaskRunResult,
+    },
+    // Run a GitHub issue sync pass now: import new issues, close the issues
+    // of completed tasks
+    GithubSync,
+    GithubSyncResponse {
+        report: github::GithubSyncReport,
+    },
+    // Tracking session tags and notes; `started_at` selects a completed
+    // session, otherwise the task's active session is used
+    SessionTag {
+        task_id: String,
+        started_at: Option<u64>,
+        tags: Vec<String>,
+    },
+    SessionUntag {
+        task_id: String,
+        started_at: Option<u64>,
+        tags: Vec<String>,
+    },
+    SessionAnnotate {
+        task_id: String,
+        started_at: Option<u64>,
+        note: String,
+    },
+    SessionExport {
+        format: String,
+        tag: Option<String>,
+    },
+    SessionExportResponse {
+        format: String,
+        content: String,
+    },
+    // Tracked sessions between two report dates (`YYYY-MM-DD`, `today`,
+    // `7d`, ...; the last 7 days when omitted) as csv, json or mermaid
+    ProductivityExport {
+        format: String,
+        from: Option<String>,
+        to: Option<String>,
+    },
+    ProductivityExportResponse {
+        format: String,
+        content: String,
+    },
+    // History from other trackers or old exports; `format` is detected from
+    // the content when omitted, `dry_run` only reports what would be added
+    HistoryImport {
+        content: String,
+        format: Option<String>,
+        source: Option<String>,
+        #[serde(default)]
+        dry_run: bool,
+    },
+    HistoryImportResponse {
+        report: task::ImportReport,
+    },
+    // Whole task board as a versioned JSON/YAML document; `board` defaults
+    // to `default`, `format` to JSON on export and is detected on import
+    BoardExport {
+        board: Option<String>,
+        format: Option<String>,
+    },
+    BoardExportResponse {
+        format: String,
+        content: String,
+    },
+    BoardImport {
+        content: String,
+        format: Option<String>,
+        #[serde(default)]
+        dry_run: bool,
+    },
+    BoardImportResponse {
+        report: dashboard::BoardImportReport,
+    },
+    // Whole-session archive: workspaces with their templates and process
+    // commands, plus unfinished tasks (see room::SessionSnapshot)
+    SessionSnapshot,
+    SessionSnapshotResponse {
+        content: String,
+    },
+    SessionRestore {
+        content: String,
+        #[serde(default)]
+        dry_run: bool,
+    },
+    SessionRestoreResponse {
+        report: room::SessionRestoreReport,
+    },
+    // Quick notes: added to `task_id` or the `workspace` scratchpad; with
+    // neither, to the currently tracked task or else the active workspace
+    NoteAdd {
+        text: String,
+        task_id: Option<String>,
+        workspace: Option<String>,
+    },
+    NoteAddResponse {
+        task_id: Option<String>,
+        workspace: Option<String>,
+        note: task::TaskNote,
     },
     // Template System IPC Messages
     TemplateList,
@@ -52,6 +390,10 @@ pub enum Message {
     TemplateCreateResponse {
         success: bool,
         error: Option<String>,
+
+        /// Best-practice findings of the template lint pass
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        warnings: Vec<room::LintIssue>,
     },
     TemplateDelete {
         name: String,
@@ -60,10 +402,231 @@ pub enum Message {
         success: bool,
         error: Option<String>,
     },
+    // Maintenance Mode IPC Messages
+    MaintenanceSet {
+        enabled: bool,
+        reason: Option<String>,
+    },
+    MaintenanceStatus,
+    MaintenanceStatusResponse {
+        status: maintenance::MaintenanceStatus,
+    },
+    // Runtime log levels: `module` is a component such as `sync`, a tracing
+    // target such as `tokio::net` or `default`; `level` may be `reset`
+    LogLevelSet {
+        module: String,
+        level: String,
+    },
+    LogLevelList,
+    LogLevelResponse {
+        levels: logging::levels::LogLevels,
+    },
+    // Recent log entries kept in memory; pass the returned `cursor` as
+    // `since` to tail
+    LogQuery {
+        #[serde(flatten)]
+        query: logging::buffer::LogQuery,
+    },
+    LogQueryResponse {
+        #[serde(flatten)]
+        result: logging::buffer::LogQueryResult,
+    },
+    // Audit journal of state-changing requests and dashboard actions,
+    // newest first
+    AuditQuery {
+        #[serde(flatten)]
+        filter: audit::AuditFilter,
+    },
+    AuditQueryResponse {
+        entries: Vec<audit::AuditEntry>,
+    },
+    // Work rejected, deferred or reclaimed under load, grouped into
+    // incidents; `since_hours` limits it to the recent past
+    SheddingReport {
+        #[serde(default)]
+        since_hours: Option<u64>,
+    },
+    SheddingReportResponse {
+        report: monitoring::ShedReport,
+    },
+    // Analytics report over the last `range` (`24h`, `7d`, `2w`; `7d` when
+    // omitted) as markdown, html or json; written to the absolute `path` on
+    // the daemon's machine when given, otherwise returned as `content`
+    AnalyticsExport {
+        format: String,
+        #[serde(default)]
+        path: Option<String>,
+        #[serde(default)]
+        range: Option<String>,
+    },
+    AnalyticsExportResponse {
+        format: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        path: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        content: Option<String>,
+    },
+    // Peer daemons on other machines, reached through their (forwarded) IPC
+    // socket; `PeerForward` proxies a workspace, task or process operation
+    // and passes the request's confirmation token on to the peer
+    PeerRegister {
+        peer: federation::PeerConfig,
+    },
+    PeerRemove {
+        name: String,
+    },
+    PeerList,
+    PeerListResponse {
+        peers: Vec<federation::PeerStatus>,
+    },
+    PeerForward {
+        peer: String,
+        message: Box<Message>,
+    },
+    // Focus Mode IPC Messages
+    FocusSet {
+        enabled: bool,
+        label: Option<String>,
+    },
+    FocusStatus,
+    FocusStatusResponse {
+        status: focus::FocusStatus,
+    },
+    // File sync conflicts: changes held back until the user picks a
+    // resolution; `content` is the file content for manual resolution
+    SyncConflictList,
+    SyncConflictListResponse {
+        conflicts: Vec<sync::SyncConflict>,
+    },
+    SyncResolve {
+        conflict_id: String,
+        strategy: sync::ResolutionStrategy,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        content: Option<String>,
+    },
+    SyncResolveResponse {
+        resolution: sync::ConflictResolutionRecord,
+    },
+    // Unified diffs of a file pair, a process's changes or a conflict
+    SyncDiff {
+        target: sync::DiffTarget,
+        #[serde(default)]
+        options: sync::DiffOptions,
+    },
+    SyncDiffResponse {
+        diffs: Vec<sync::FileDiff>,
+    },
+    // File sync watching: watched workspaces and runtime enable/disable
+    SyncWatchList,
+    SyncWatchSet {
+        workspace: String,
+        enabled: bool,
+    },
+    SyncWatchListResponse {
+        workspaces: Vec<sync::WatchStatus>,
+    },
+    // Telemetry: show the exact payload that would be reported
+    TelemetryPreview,
+    TelemetryPreviewResponse {
+        enabled: bool,
+        endpoint: Option<String>,
+        payload: serde_json::Value,
+    },
+    // In-place upgrade: hand off to a staged binary once drained
+    Upgrade {
+        binary: String,
+        /// Hex SHA-256 the binary must have before it is run
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sha256: Option<String>,
+    },
+    UpgradeResponse {
+        success: bool,
+        successor_pid: Option<u32>,
+        error: Option<String>,
+    },
+    // Single-instance takeover: a daemon started with `--takeover` asks the
+    // running one for its session snapshot, then waits for it to shut down
+    Takeover {
+        pid: u32,
+    },
+    TakeoverResponse {
+        snapshot: String,
+    },
     Ping,
     Pong,
 }
 
+// Workspace information for IPC communication
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct WorkspaceInfo {
+    pub name: String,
+    pub template: String,
+    pub is_active: bool,
+    pub process_count: usize,
+    pub task_count: usize,
+    pub pane_count: usize,
+    #[serde(default)]
+    pub draining: bool,
+
+    /// Branch the workspace was created on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+}
+
+// Process information for IPC communication
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct ProcessSummary {
+    pub id: String,
+    pub workspace: String,
+    pub command: String,
+    pub status: String,
+    pub pid: Option<u32>,
+    pub restart_count: u32,
+
+    /// Version of the workspace context file the process received, and
+    /// whether the file changed since
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_version: Option<String>,
+    #[serde(default)]
+    pub context_outdated: bool,
+}
+
+impl From<room::state::ProcessInfo> for ProcessSummary {
+    fn from(process: room::state::ProcessInfo) -> Self {
+        Self {
+            context_version: process
+                .context
+                .as_ref()
+                .map(|context| context.version.clone()),
+            context_outdated: process
+                .context
+                .as_ref()
+                .is_some_and(|context| context.is_outdated()),
+            status: format!("{:?}", process.status),
+            id: process.id,
+            workspace: process.workspace,
+            command: process.command,
+            pid: process.pid,
+            restart_count: process.restart_count,
+        }
+    }
+}
+
+// Framework status for IPC communication
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct SystemStatusInfo {
+    pub version: String,
+    pub uptime_secs: u64,
+    pub active_workspace: Option<String>,
+    pub workspace_count: usize,
+    pub process_count: usize,
+    pub active_tasks: usize,
+    pub queued_tasks: usize,
+    pub completed_tasks: u64,
+    pub failed_tasks: u64,
+    pub maintenance: bool,
+}
+
 // Template information for IPC communication
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct TemplateInfo {
@@ -168,13 +731,15 @@ mod tests {
         let message = Message::WorkspaceCreate {
             name: "test-workspace".to_string(),
             template: "default".to_string(),
+            project_dir: None,
+            variables: Default::default(),
         };
 
         let serialized = serde_json::to_string(&message).unwrap();
         let deserialized: Message = serde_json::from_str(&serialized).unwrap();
 
         match deserialized {
-            Message::WorkspaceCreate { name, template } => {
+            Message::WorkspaceCreate { name, template, .. } => {
                 assert_eq!(name, "test-workspace");
                 assert_eq!(template, "default");
             }
@@ -201,12 +766,98 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_process_list_and_status_messages() {
+        let message = Message::ProcessListResponse {
+            processes: vec![ProcessSummary {
+                id: "claude-1".to_string(),
+                workspace: "frontend".to_string(),
+                command: "claude-code".to_string(),
+                status: "Running".to_string(),
+                pid: Some(4242),
+                restart_count: 0,
+                context_version: Some("5f3a9c0d12e4b7a8".to_string()),
+                context_outdated: false,
+            }],
+        };
+        let serialized = serde_json::to_string(&message).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Message>(&serialized).unwrap(),
+            message
+        );
+
+        let serialized = serde_json::to_string(&Message::SystemStatus).unwrap();
+        assert_eq!(serialized, "\"SystemStatus\"");
+    }
+
+    #[test]
+    fn test_log_query_message() {
+        let message: Message =
+            serde_json::from_str(r#"{"LogQuery":{"component":"process","since":42}}"#).unwrap();
+        match message {
+            Message::LogQuery { ref query } => {
+                assert_eq!(query.component.as_deref(), Some("process"));
+                assert_eq!(query.since, Some(42));
+                assert_eq!(query.limit, None);
+            }
+            _ => panic!("Unexpected message type"),
+        }
+
+        let response = Message::LogQueryResponse {
+            result: logging::buffer::LogQueryResult {
+                entries: Vec::new(),
+                cursor: 42,
+                truncated: 0,
+            },
+        };
+        let serialized = serde_json::to_string(&response).unwrap();
+        assert!(serialized.contains(r#""cursor":42"#));
+        assert_eq!(
+            serde_json::from_str::<Message>(&serialized).unwrap(),
+            response
+        );
+    }
+
+    #[test]
+    fn test_process_logs_message() {
+        let message: Message =
+            serde_json::from_str(r#"{"ProcessLogsGet":{"process_id":"claude-1"}}"#).unwrap();
+        assert_eq!(
+            message,
+            Message::ProcessLogsGet {
+                process_id: "claude-1".to_string(),
+                since: None,
+                limit: None,
+            }
+        );
+
+        let response = Message::ProcessLogsResponse {
+            process_id: "claude-1".to_string(),
+            output: process::OutputChunk {
+                lines: Vec::new(),
+                cursor: 7,
+                dropped: 0,
+            },
+        };
+        let serialized = serde_json::to_string(&response).unwrap();
+        assert!(serialized.contains(r#""cursor":7"#));
+        assert_eq!(
+            serde_json::from_str::<Message>(&serialized).unwrap(),
+            response
+        );
+    }
+
     #[test]
     fn test_task_queue_message() {
         let message = Message::TaskQueue {
             id: "task-001".to_string(),
             priority: 5,
             command: "build project".to_string(),
+            working_dir: None,
+            env: HashMap::new(),
+            shell: None,
+            timeout_secs: None,
+            assignee: None,
         };
 
         let serialized = serde_json::to_string(&message).unwrap();
@@ -217,6 +868,7 @@ mod tests {
                 id,
                 priority,
                 command,
+                ..
             } => {
                 assert_eq!(id, "task-001");
                 assert_eq!(priority, 5);
@@ -277,13 +929,15 @@ mod tests {
         let message = Message::WorkspaceCreate {
             name: long_name.clone(),
             template: long_template.clone(),
+            project_dir: None,
+            variables: Default::default(),
         };
 
         let serialized = serde_json::to_string(&message).unwrap();
         let deserialized: Message = serde_json::from_str(&serialized).unwrap();
 
         match deserialized {
-            Message::WorkspaceCreate { name, template } => {
+            Message::WorkspaceCreate { name, template, .. } => {
                 assert_eq!(name, long_name);
                 assert_eq!(template, long_template);
             }
@@ -0,0 +1,285 @@
+// WezTerm Multi-Process Development Framework - Log Buffer
+// 直近のログをメモリに保持し、IPC・ダッシュボードから検索・追跡できるようにする
+
+use super::{UnifiedLogEntry, UnifiedLogLevel};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+
+/// Entries kept per component or process
+pub const SOURCE_CAPACITY: usize = 500;
+
+/// Components and processes with their own ring; the least recently
+/// written one is dropped beyond this
+pub const MAX_SOURCES: usize = 128;
+
+/// Entries returned when a query sets no limit, and at most
+pub const DEFAULT_LIMIT: usize = 100;
+pub const MAX_LIMIT: usize = 1000;
+
+static BUFFER: Mutex<LogBuffer> = Mutex::new(LogBuffer::new());
+
+/// Log entry with its position in the buffer
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BufferedLogEntry {
+    /// Increases with every recorded entry; pass the last one as `since` to tail
+    pub seq: u64,
+
+    #[serde(flatten)]
+    pub entry: UnifiedLogEntry,
+}
+
+/// Filter for recent log entries; every field is optional
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct LogQuery {
+    /// Component such as `process` or `sync`
+    #[serde(default)]
+    pub component: Option<String>,
+
+    /// Process ID or other entity the entries are about
+    #[serde(default)]
+    pub entity: Option<String>,
+
+    /// Minimum level (`trace` to `error`)
+    #[serde(default)]
+    pub level: Option<String>,
+
+    /// Text in the message or error, case-insensitive
+    #[serde(default)]
+    pub search: Option<String>,
+
+    /// Only entries after this `seq` (the `cursor` of a previous result)
+    #[serde(default)]
+    pub since: Option<u64>,
+
+    /// Newest entries returned, [`DEFAULT_LIMIT`] when omitted
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// Matching entries, oldest first
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct LogQueryResult {
+    pub entries: Vec<BufferedLogEntry>,
+
+    /// `seq` of the newest recorded entry; the `since` of the next query to tail
+    pub cursor: u64,
+
+    /// Matching entries left out by the limit
+    pub truncated: usize,
+}
+
+/// Bounded rings of recent entries by component and process
+#[derive(Debug)]
+pub struct LogBuffer {
+    last_seq: u64,
+    sources: BTreeMap<String, VecDeque<BufferedLogEntry>>,
+}
+
+impl LogBuffer {
+    pub const fn new() -> Self {
+        Self {
+            last_seq: 0,
+            sources: BTreeMap::new(),
+        }
+    }
+
+    pub fn record(&mut self, entry: UnifiedLogEntry) {
+        self.last_seq += 1;
+        let source = match entry.context.entity_id {
+            Some(ref entity) => format!("{}/{}", entry.context.component, entity),
+            None => entry.context.component.clone(),
+        };
+
+        if !self.sources.contains_key(&source) && self.sources.len() >= MAX_SOURCES {
+            let stalest = self
+                .sources
+                .iter()
+                .min_by_key(|(_, entries)| entries.back().map(|e| e.seq).unwrap_or(0))
+                .map(|(source, _)| source.clone());
+            if let Some(stalest) = stalest {
+                self.sources.remove(&stalest);
+            }
+        }
+
+        let entries = self.sources.entry(source).or_default();
+        if entries.len() >= SOURCE_CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back(BufferedLogEntry {
+            seq: self.last_seq,
+            entry,
+        });
+    }
+
+    pub fn query(&self, query: &LogQuery) -> Result<LogQueryResult, String> {
+        let min_level = match query.level.as_deref() {
+            Some(level) => Some(
+                UnifiedLogLevel::from_string(level)
+                    .ok_or_else(|| format!("Unknown log level '{level}'"))?,
+            ),
+            None => None,
+        };
+        let search = query.search.as_ref().map(|text| text.to_lowercase());
+        let since = query.since.unwrap_or(0);
+
+        let mut entries: Vec<&BufferedLogEntry> = self
+            .sources
+            .values()
+            .flatten()
+            .filter(|e| e.seq > since)
+            .filter(|e| {
+                query
+                    .component
+                    .as_ref()
+                    .map_or(true, |component| &e.entry.context.component == component)
+            })
+            .filter(|e| {
+                query.entity.as_ref().map_or(true, |entity| {
+                    e.entry.context.entity_id.as_ref() == Some(entity)
+                })
+            })
+            .filter(|e| min_level.map_or(true, |level| e.entry.level >= level))
+            .filter(|e| {
+                search.as_ref().map_or(true, |text| {
+                    e.entry.message.to_lowercase().contains(text)
+                        || e.entry
+                            .error
+                            .as_ref()
+                            .is_some_and(|error| error.to_lowercase().contains(text))
+                })
+            })
+            .collect();
+        entries.sort_by_key(|e| e.seq);
+
+        let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        let truncated = entries.len().saturating_sub(limit);
+        Ok(LogQueryResult {
+            entries: entries.into_iter().skip(truncated).cloned().collect(),
+            cursor: self.last_seq,
+            truncated,
+        })
+    }
+}
+
+impl Default for LogBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Keep an entry that passed the level filter
+pub fn record(entry: UnifiedLogEntry) {
+    BUFFER.lock().unwrap().record(entry);
+}
+
+/// Recent entries of the whole process
+pub fn query(query: &LogQuery) -> Result<LogQueryResult, String> {
+    BUFFER.lock().unwrap().query(query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logging::LogContext;
+
+    fn entry(level: UnifiedLogLevel, context: LogContext, message: &str) -> UnifiedLogEntry {
+        UnifiedLogEntry {
+            timestamp: "2026-01-01T00:00:00+00:00".to_string(),
+            level,
+            context,
+            message: message.to_string(),
+            error: None,
+            duration_ms: None,
+            location: None,
+        }
+    }
+
+    #[test]
+    fn test_query_filters_and_tails() {
+        let mut buffer = LogBuffer::new();
+        let process = |id: &str| LogContext::new("process", "output").with_entity_id(id);
+        buffer.record(entry(UnifiedLogLevel::Info, process("claude-1"), "started"));
+        buffer.record(entry(
+            UnifiedLogLevel::Error,
+            process("claude-1"),
+            "Panic in worker",
+        ));
+        buffer.record(entry(UnifiedLogLevel::Warn, process("claude-2"), "slow"));
+        buffer.record(entry(
+            UnifiedLogLevel::Info,
+            LogContext::new("sync", "scan"),
+            "scanned",
+        ));
+
+        let result = buffer
+            .query(&LogQuery {
+                entity: Some("claude-1".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        let messages: Vec<_> = result.entries.iter().map(|e| &e.entry.message).collect();
+        assert_eq!(messages, ["started", "Panic in worker"]);
+        assert_eq!(result.cursor, 4);
+
+        let result = buffer
+            .query(&LogQuery {
+                component: Some("process".to_string()),
+                level: Some("warn".to_string()),
+                search: Some("PANIC".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(result.entries.len(), 1);
+        assert_eq!(result.entries[0].seq, 2);
+
+        buffer.record(entry(UnifiedLogLevel::Info, process("claude-2"), "done"));
+        let tail = buffer
+            .query(&LogQuery {
+                since: Some(result.cursor),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(tail.entries.len(), 1);
+        assert_eq!(tail.entries[0].entry.message, "done");
+        assert!(buffer
+            .query(&LogQuery {
+                level: Some("loud".to_string()),
+                ..Default::default()
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn test_rings_are_bounded() {
+        let mut buffer = LogBuffer::new();
+        for i in 0..SOURCE_CAPACITY + 10 {
+            buffer.record(entry(
+                UnifiedLogLevel::Info,
+                LogContext::new("ipc", "receive"),
+                &format!("message {i}"),
+            ));
+        }
+        for i in 0..MAX_SOURCES {
+            buffer.record(entry(
+                UnifiedLogLevel::Info,
+                LogContext::new("process", "spawn").with_entity_id(&format!("p{i}")),
+                "spawned",
+            ));
+        }
+
+        // The ipc ring was written least recently and made room for the last process
+        assert_eq!(buffer.sources.len(), MAX_SOURCES);
+        assert!(!buffer.sources.contains_key("ipc"));
+
+        let result = buffer
+            .query(&LogQuery {
+                limit: Some(5),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(result.entries.len(), 5);
+        assert_eq!(result.truncated, MAX_SOURCES - 5);
+        assert_eq!(result.entries.last().unwrap().seq, result.cursor);
+    }
+}
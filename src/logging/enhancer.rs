@@ -12,6 +12,16 @@ lazy_static! {
         Arc::new(Mutex::new(StrategyManager::from_environment()));
 }
 
+/// 現在のログ戦略
+pub fn current_strategy() -> LoggingStrategy {
+    STRATEGY_MANAGER.lock().unwrap().get_strategy().clone()
+}
+
+/// ログ戦略を実行時に差し替える
+pub fn set_strategy(strategy: LoggingStrategy) {
+    STRATEGY_MANAGER.lock().unwrap().update_strategy(strategy);
+}
+
 /// コンテキスト付きログ出力のメイン関数
 pub fn log_with_context(
     level: UnifiedLogLevel,
@@ -39,6 +49,9 @@ pub fn log_with_context(
 
     // 出力
     output_log_entry(&entry, strategy);
+
+    // LogQuery で参照できるようにメモリにも保持
+    super::buffer::record(entry);
 }
 
 /// ログエントリを作成
@@ -2,6 +2,7 @@
 // 構造化ログフォーマット定義とカスタムフォーマッター
 
 use super::{UnifiedLogEntry, UnifiedLogLevel};
+use crate::config::Timezone;
 use serde_json::{json, Value};
 use std::collections::HashMap;
 
@@ -21,6 +22,7 @@ pub struct HumanReadableFormatter {
     show_location: bool,
     show_metadata: bool,
     color_enabled: bool,
+    timezone: Timezone,
 }
 
 impl Default for HumanReadableFormatter {
@@ -32,6 +34,7 @@ impl Default for HumanReadableFormatter {
             show_location: false,
             show_metadata: true,
             color_enabled: true,
+            timezone: Timezone::default(),
         }
     }
 }
@@ -49,6 +52,7 @@ impl HumanReadableFormatter {
             show_location: false,
             show_metadata: false,
             color_enabled: true,
+            timezone: Timezone::default(),
         }
     }
 
@@ -60,9 +64,16 @@ impl HumanReadableFormatter {
             show_location: true,
             show_metadata: true,
             color_enabled: true,
+            timezone: Timezone::default(),
         }
     }
 
+    /// タイムスタンプ表示に使うタイムゾーンを設定
+    pub fn with_timezone(mut self, timezone: Timezone) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
     fn get_level_color(&self, level: UnifiedLogLevel) -> &'static str {
         if !self.color_enabled {
             return "";
@@ -95,7 +106,12 @@ impl LogFormatter for HumanReadableFormatter {
             let timestamp = entry
                 .timestamp
                 .parse::<chrono::DateTime<chrono::Utc>>()
-                .map(|dt| dt.format("%H:%M:%S%.3f").to_string())
+                .map(|dt| {
+                    self.timezone
+                        .localize(&dt)
+                        .format("%H:%M:%S%.3f")
+                        .to_string()
+                })
                 .unwrap_or_else(|_| entry.timestamp.clone());
             parts.push(timestamp.to_string());
         }
@@ -467,6 +483,17 @@ mod tests {
         assert!(formatted.contains("ERROR: Test error"));
     }
 
+    #[test]
+    fn test_human_readable_formatter_timezone() {
+        let entry = create_test_entry();
+
+        let utc = HumanReadableFormatter::new().with_timezone(Timezone::Utc);
+        assert!(utc.format(&entry).contains("12:00:00.123"));
+
+        let tokyo = HumanReadableFormatter::new().with_timezone(Timezone::Fixed(9 * 3600));
+        assert!(tokyo.format(&entry).contains("21:00:00.123"));
+    }
+
     #[test]
     fn test_json_formatter() {
         let formatter = JsonFormatter::new();
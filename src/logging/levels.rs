@@ -0,0 +1,268 @@
+// WezTerm Multi-Process Development Framework - Runtime Log Levels
+// 実行中のデーモンのログレベルを再起動なしで変更する
+
+use super::enhancer;
+use super::strategy::LoggingStrategy;
+use super::UnifiedLogLevel;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Mutex, OnceLock};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+/// Module name that sets the level of everything without its own entry
+pub const DEFAULT_MODULE: &str = "default";
+
+/// Level name that removes a runtime change
+pub const RESET_LEVEL: &str = "reset";
+
+/// Crate prefix of tracing targets for short module names
+const CRATE_TARGET: &str = "wezterm_parallel";
+
+static TRACING_FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+static STATE: Mutex<Option<LevelState>> = Mutex::new(None);
+
+/// Levels changed at runtime on top of the startup configuration
+#[derive(Debug, Clone)]
+struct LevelState {
+    /// `RUST_LOG` at startup
+    base_filter: String,
+
+    /// Unified logging strategy at startup
+    base_strategy: LoggingStrategy,
+
+    default: Option<UnifiedLogLevel>,
+    modules: BTreeMap<String, UnifiedLogLevel>,
+}
+
+/// Effective log levels reported after a change
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LogLevels {
+    /// Level of components without their own entry
+    pub default: String,
+
+    /// Effective level by module; short names are unified logging components
+    /// and `wezterm_parallel::<name>` tracing targets at the same time
+    pub modules: BTreeMap<String, String>,
+
+    /// Modules (including `default`) changed at runtime
+    pub overridden: Vec<String>,
+
+    /// Tracing filter in `RUST_LOG` syntax
+    pub filter: String,
+}
+
+/// Install the tracing subscriber with a filter that can be changed at runtime
+///
+/// Replaces `tracing_subscriber::fmt::init()`; the filter starts from `RUST_LOG`.
+pub fn init_tracing() {
+    let base_filter = std::env::var("RUST_LOG").unwrap_or_default();
+    let filter = EnvFilter::try_new(&base_filter).unwrap_or_else(|_| EnvFilter::new(""));
+    let (filter, handle) = reload::Layer::new(filter);
+    if tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer())
+        .try_init()
+        .is_ok()
+    {
+        let _ = TRACING_FILTER.set(handle);
+    }
+}
+
+/// Change the level of one module, or of all of them with [`DEFAULT_MODULE`]
+///
+/// `module` is a unified logging component such as `sync` (also applied to the
+/// `wezterm_parallel::sync` tracing target) or a full tracing target such as
+/// `tokio::net`. `level` is `trace`, `debug`, `info`, `warn`, `error` or
+/// [`RESET_LEVEL`] to return to the startup level.
+pub fn set_level(module: &str, level: &str) -> Result<LogLevels, String> {
+    let module = module.trim();
+    if module.is_empty()
+        || !module
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+    {
+        return Err(format!("Invalid module name '{module}'"));
+    }
+    let level = if level.eq_ignore_ascii_case(RESET_LEVEL) {
+        None
+    } else {
+        Some(
+            UnifiedLogLevel::from_string(level)
+                .ok_or_else(|| format!("Unknown log level '{level}'"))?,
+        )
+    };
+
+    let mut guard = STATE.lock().unwrap();
+    let mut state = guard.get_or_insert_with(startup_state).clone();
+    if module == DEFAULT_MODULE {
+        state.default = level;
+    } else {
+        match level {
+            Some(level) => {
+                state.modules.insert(module.to_string(), level);
+            }
+            None => {
+                state.modules.remove(module);
+            }
+        }
+    }
+
+    let filter = EnvFilter::try_new(state.tracing_filter()).map_err(|e| e.to_string())?;
+    if let Some(handle) = TRACING_FILTER.get() {
+        handle.reload(filter).map_err(|e| e.to_string())?;
+    }
+    enhancer::set_strategy(state.strategy());
+
+    let levels = state.report();
+    *guard = Some(state);
+    Ok(levels)
+}
+
+/// Effective log levels without changing anything
+pub fn levels() -> LogLevels {
+    STATE
+        .lock()
+        .unwrap()
+        .get_or_insert_with(startup_state)
+        .report()
+}
+
+fn startup_state() -> LevelState {
+    LevelState {
+        base_filter: std::env::var("RUST_LOG").unwrap_or_default(),
+        base_strategy: enhancer::current_strategy(),
+        default: None,
+        modules: BTreeMap::new(),
+    }
+}
+
+fn level_name(level: UnifiedLogLevel) -> String {
+    level.as_str().to_lowercase()
+}
+
+impl LevelState {
+    /// `RUST_LOG` with the runtime changes appended; a changed default
+    /// replaces the startup filter
+    fn tracing_filter(&self) -> String {
+        let mut directives: Vec<String> = match self.default {
+            Some(level) => vec![level_name(level)],
+            None if self.base_filter.trim().is_empty() => Vec::new(),
+            None => vec![self.base_filter.trim().to_string()],
+        };
+        directives.extend(self.modules.iter().map(|(module, level)| {
+            if module.contains("::") {
+                format!("{module}={}", level_name(*level))
+            } else {
+                format!("{CRATE_TARGET}::{module}={}", level_name(*level))
+            }
+        }));
+        directives.join(",")
+    }
+
+    fn strategy(&self) -> LoggingStrategy {
+        let mut strategy = self.base_strategy.clone();
+        if let Some(level) = self.default {
+            strategy.default_level = level;
+            strategy
+                .component_levels
+                .values_mut()
+                .for_each(|component| *component = level);
+        }
+        for (module, level) in &self.modules {
+            if !module.contains("::") {
+                strategy.component_levels.insert(module.clone(), *level);
+            }
+        }
+        strategy
+    }
+
+    fn report(&self) -> LogLevels {
+        let strategy = self.strategy();
+        let mut modules: BTreeMap<String, String> = strategy
+            .component_levels
+            .iter()
+            .map(|(component, level)| (component.clone(), level_name(*level)))
+            .collect();
+        modules.extend(
+            self.modules
+                .iter()
+                .map(|(module, level)| (module.clone(), level_name(*level))),
+        );
+
+        let mut overridden: Vec<String> = self.modules.keys().cloned().collect();
+        if self.default.is_some() {
+            overridden.insert(0, DEFAULT_MODULE.to_string());
+        }
+
+        LogLevels {
+            default: level_name(strategy.default_level),
+            modules,
+            overridden,
+            filter: self.tracing_filter(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> LevelState {
+        LevelState {
+            base_filter: "info,hyper=warn".to_string(),
+            base_strategy: LoggingStrategy::default(),
+            default: None,
+            modules: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_module_levels_apply_to_both_log_systems() {
+        let mut state = state();
+        state
+            .modules
+            .insert("sync".to_string(), UnifiedLogLevel::Trace);
+        state
+            .modules
+            .insert("tokio::net".to_string(), UnifiedLogLevel::Debug);
+
+        assert_eq!(
+            state.tracing_filter(),
+            "info,hyper=warn,wezterm_parallel::sync=trace,tokio::net=debug"
+        );
+        assert_eq!(
+            state.strategy().get_log_level("sync"),
+            UnifiedLogLevel::Trace
+        );
+        assert!(EnvFilter::try_new(state.tracing_filter()).is_ok());
+
+        let report = state.report();
+        assert_eq!(report.modules["sync"], "trace");
+        assert_eq!(report.modules["tokio::net"], "debug");
+        assert_eq!(report.modules["process"], "info");
+        assert_eq!(report.overridden, vec!["sync", "tokio::net"]);
+    }
+
+    #[test]
+    fn test_default_level_replaces_startup_filter() {
+        let mut state = state();
+        state.default = Some(UnifiedLogLevel::Warn);
+        state
+            .modules
+            .insert("ipc".to_string(), UnifiedLogLevel::Debug);
+
+        assert_eq!(state.tracing_filter(), "warn,wezterm_parallel::ipc=debug");
+        let strategy = state.strategy();
+        assert_eq!(strategy.get_log_level("process"), UnifiedLogLevel::Warn);
+        assert_eq!(strategy.get_log_level("ipc"), UnifiedLogLevel::Debug);
+        assert_eq!(state.report().overridden, vec!["default", "ipc"]);
+    }
+
+    #[test]
+    fn test_set_level_rejects_invalid_input() {
+        assert!(set_level("sync,hyper", "debug").is_err());
+        assert!(set_level("sync", "loud").is_err());
+    }
+}
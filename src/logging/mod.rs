@@ -1,8 +1,10 @@
 // WezTerm Multi-Process Development Framework - Unified Logging System
 // 統一されたログシステム - デバッグ効率化とトラブルシューティング強化
 
+pub mod buffer;
 pub mod enhancer;
 pub mod formatter;
+pub mod levels;
 pub mod strategy;
 
 use serde::{Deserialize, Serialize};
@@ -24,7 +26,7 @@ pub enum UnifiedLogLevel {
 }
 
 /// ログコンテキスト - 構造化ログの基盤
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct LogContext {
     /// コンポーネント名 (process, room, config, ipc, etc.)
     pub component: String,
@@ -41,7 +43,7 @@ pub struct LogContext {
 }
 
 /// 統一ログエントリ
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct UnifiedLogEntry {
     /// タイムスタンプ (ISO 8601)
     pub timestamp: String,
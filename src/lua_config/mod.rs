@@ -0,0 +1,259 @@
+// WezTerm Multi-Process Development Framework - Lua Client Generation
+// Renders and validates the bundled WezTerm Lua client library (lua/wezterm_parallel.lua)
+
+/// Lua client library shipped with the crate
+pub const LUA_CLIENT_TEMPLATE: &str = include_str!("../../lua/wezterm_parallel.lua");
+
+/// IPC messages the Lua client is allowed to send
+pub const LUA_MESSAGES: &[&str] = &[
+    "WorkspaceCreate",
+    "WorkspaceList",
+    "WorkspaceSwitch",
+    "TaskQueue",
+    "FocusStatus",
+    "FocusSet",
+    "NoteAdd",
+];
+
+const DEFAULTS_BEGIN: &str = "-- BEGIN GENERATED DEFAULTS";
+const DEFAULTS_END: &str = "-- END GENERATED DEFAULTS";
+
+/// Values baked into the generated Lua module
+#[derive(Debug, Clone, PartialEq)]
+pub struct LuaConfigOptions {
+    pub socket_path: String,
+    pub dashboard_url: String,
+    pub mods: String,
+    pub default_template: String,
+    pub task_priority: u8,
+}
+
+impl Default for LuaConfigOptions {
+    fn default() -> Self {
+        Self {
+            socket_path: crate::ipc::default_socket_path()
+                .to_string_lossy()
+                .to_string(),
+            dashboard_url: "http://127.0.0.1:9999".to_string(),
+            mods: "CTRL|SHIFT".to_string(),
+            default_template: "basic".to_string(),
+            task_priority: 5,
+        }
+    }
+}
+
+impl LuaConfigOptions {
+    fn to_lua(&self) -> String {
+        format!(
+            "M.defaults = {{\n  socket_path = {},\n  dashboard_url = {},\n  mods = {},\n  default_template = {},\n  task_priority = {},\n}}",
+            lua_string(&self.socket_path),
+            lua_string(&self.dashboard_url),
+            lua_string(&self.mods),
+            lua_string(&self.default_template),
+            self.task_priority
+        )
+    }
+}
+
+/// Render the Lua client with the given defaults and validate the result
+pub fn generate(options: &LuaConfigOptions) -> Result<String, String> {
+    let begin = LUA_CLIENT_TEMPLATE
+        .find(DEFAULTS_BEGIN)
+        .ok_or("Lua template is missing the generated defaults block")?;
+    let end = LUA_CLIENT_TEMPLATE
+        .find(DEFAULTS_END)
+        .filter(|end| *end > begin)
+        .ok_or("Lua template is missing the end of the generated defaults block")?;
+
+    let rendered = format!(
+        "{}{}\n{}\n{}",
+        &LUA_CLIENT_TEMPLATE[..begin],
+        DEFAULTS_BEGIN,
+        options.to_lua(),
+        &LUA_CLIENT_TEMPLATE[end..]
+    );
+
+    validate(&rendered)?;
+    Ok(rendered)
+}
+
+/// Check that a Lua client only sends known messages and its blocks are balanced
+pub fn validate(source: &str) -> Result<(), String> {
+    let code = strip_comments_and_strings(source);
+
+    let mut depth: i64 = 0;
+    for word in code.split(|c: char| !(c.is_alphanumeric() || c == '_')) {
+        match word {
+            "function" | "if" | "do" | "repeat" => depth += 1,
+            "end" | "until" => depth -= 1,
+            _ => {}
+        }
+        if depth < 0 {
+            return Err("Unbalanced Lua block: unexpected 'end'".to_string());
+        }
+    }
+    if depth != 0 {
+        return Err(format!("Unbalanced Lua block: {depth} unclosed block(s)"));
+    }
+
+    for variant in requested_messages(source) {
+        if !LUA_MESSAGES.contains(&variant.as_str()) {
+            return Err(format!("Lua client sends unknown message '{variant}'"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Message variants passed to `M.request(...)`
+fn requested_messages(source: &str) -> Vec<String> {
+    source
+        .match_indices("M.request('")
+        .filter_map(|(index, pattern)| {
+            let rest = &source[index + pattern.len()..];
+            rest.find('\'').map(|end| rest[..end].to_string())
+        })
+        .collect()
+}
+
+/// Replace comments and string literals with spaces so keywords can be counted
+fn strip_comments_and_strings(source: &str) -> String {
+    let mut output = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '-' if chars.peek() == Some(&'-') => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        output.push('\n');
+                        break;
+                    }
+                }
+            }
+            '\'' | '"' => {
+                let quote = c;
+                while let Some(next) = chars.next() {
+                    if next == '\\' {
+                        chars.next();
+                    } else if next == quote {
+                        break;
+                    }
+                }
+                output.push(' ');
+            }
+            _ => output.push(c),
+        }
+    }
+
+    output
+}
+
+/// Quote a value as a Lua string literal
+fn lua_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Message;
+
+    #[test]
+    fn test_bundled_client_is_valid() {
+        assert!(validate(LUA_CLIENT_TEMPLATE).is_ok());
+        assert_eq!(
+            generate(&LuaConfigOptions::default()).unwrap(),
+            LUA_CLIENT_TEMPLATE
+        );
+    }
+
+    #[test]
+    fn test_lua_messages_match_ipc_protocol() {
+        let samples = [
+            Message::WorkspaceCreate {
+                name: "w".to_string(),
+                template: "basic".to_string(),
+                project_dir: None,
+                variables: Default::default(),
+            },
+            Message::WorkspaceList,
+            Message::WorkspaceSwitch {
+                name: "w".to_string(),
+            },
+            Message::TaskQueue {
+                id: "t".to_string(),
+                priority: 5,
+                command: "echo".to_string(),
+                working_dir: None,
+                env: Default::default(),
+                shell: None,
+                timeout_secs: None,
+                assignee: None,
+            },
+            Message::FocusStatus,
+            Message::FocusSet {
+                enabled: true,
+                label: None,
+            },
+            Message::NoteAdd {
+                text: "n".to_string(),
+                task_id: None,
+                workspace: None,
+            },
+        ];
+
+        for (name, message) in LUA_MESSAGES.iter().zip(samples) {
+            let json = serde_json::to_value(&message).unwrap();
+            let tag = match &json {
+                serde_json::Value::String(tag) => tag.clone(),
+                serde_json::Value::Object(map) => map.keys().next().unwrap().clone(),
+                other => panic!("Unexpected encoding: {other}"),
+            };
+            assert_eq!(&tag, name);
+        }
+
+        for variant in requested_messages(LUA_CLIENT_TEMPLATE) {
+            assert!(LUA_MESSAGES.contains(&variant.as_str()), "{variant}");
+        }
+    }
+
+    #[test]
+    fn test_generate_substitutes_defaults() {
+        let options = LuaConfigOptions {
+            socket_path: "/run/user/1000/wp \"dev\".sock".to_string(),
+            mods: "CTRL|ALT".to_string(),
+            ..Default::default()
+        };
+        let lua = generate(&options).unwrap();
+
+        assert!(lua.contains(r#"socket_path = "/run/user/1000/wp \"dev\".sock","#));
+        assert!(lua.contains(r#"mods = "CTRL|ALT","#));
+        let default_path = crate::ipc::default_socket_path();
+        assert!(!lua.contains(&format!(
+            "socket_path = {},",
+            lua_string(&default_path.to_string_lossy())
+        )));
+    }
+
+    #[test]
+    fn test_validate_rejects_broken_clients() {
+        assert!(validate("local function f()\n  if x then\n  end\n").is_err());
+        assert!(validate("end").is_err());
+        assert!(validate("M.request('ProcessKill', { id = 1 })").is_err());
+        // Keywords inside strings and comments are ignored
+        assert!(validate("local s = 'do end' -- function\n").is_ok());
+    }
+}
@@ -1,25 +1,87 @@
+use std::collections::HashMap;
 use std::env;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::net::{UnixListener, UnixStream};
-use wezterm_parallel::logging::LogContext;
+use tokio::sync::OwnedSemaphorePermit;
+use wezterm_parallel::logging::{levels as log_levels, LogContext};
 use wezterm_parallel::{
-    dashboard::{DashboardConfig, WebSocketServer},
-    performance::memory::MemoryMonitor,
+    audit::AuditJournal,
+    auth::Authenticator,
+    bridge::EventBridge,
+    client::{
+        cli::{
+            pidfile_arg, run_attach_command, run_audit_command, run_board_command, run_daemonized,
+            run_env_command, run_focus_command, run_fsck_command, run_generate_lua_config,
+            run_import_command, run_install_service, run_log_level_command, run_logs_command,
+            run_maintenance_command, run_note_command, run_output_command, run_peer_command,
+            run_project_command, run_ps_command, run_replay_command, run_report_command,
+            run_restart_command, run_shedding_command, run_snapshot_command, run_status_command,
+            run_stop_command, run_sync_command, run_task_command, run_telemetry_command,
+            run_template_command, run_timeline_command, run_upgrade_command, run_workspace_command,
+        },
+        IpcClient,
+    },
+    config::loader::ConfigLoader,
+    dashboard::{
+        DashboardConfig, DashboardMessage, DashboardState, MetricsUpdate, TaskBoardManager,
+        WebSocketServer,
+    },
+    federation::Federation,
+    focus::FocusController,
+    fsck::{self, StatePaths},
+    github::GithubSync,
+    hooks::HookRunner,
+    instance::{InstanceError, InstanceInfo, InstanceLock, PidFile},
+    ipc::{
+        self,
+        handlers::{
+            collect_usage_totals, BoardHandler, FederationHandler, ProcessHandler, SessionHandler,
+            SnapshotHandler, SyncHandler, SystemHandler, TaskHandler, TemplateHandler,
+            WorkspaceHandler,
+        },
+        AuditMiddleware, AuthMiddleware, BoxedIpcStream, ConfirmationMiddleware, ConnectionLimiter,
+        FrameDecoder, Incoming, IpcError, IpcLimits, IpcListener, LoggingMiddleware,
+        MetricsMiddleware, Pipeline, RateLimitMiddleware, RecordingMiddleware, RequestContext,
+        SessionRecorder, ValidationMiddleware,
+    },
+    maintenance::MaintenanceController,
+    metrics::aggregator::{ManagerSnapshot, MetricsAggregator},
+    metrics::collector::ProcessInfo as CollectedProcess,
+    metrics::protocol::ProtocolMetrics,
+    metrics::storage::{HistorySample, MetricsStorage},
+    monitoring::{Alert, AlertSeverity, MonitoringManager, RecoveryAction, ShedLog},
+    performance::lazy::{Activations, LazySubsystem, STARTUP_TRIGGER},
+    performance::memory::{LeakCheck, MemoryLeak, MemoryMonitor, DAEMON_TARGET},
     performance::metrics::MetricsCollector,
     performance::startup::StartupOptimizer,
-    performance::{PerformanceConfig, PerformanceManager},
-    room::WorkspaceManager,
-    sync::FileSyncManager,
-    task::{TaskConfig, TaskManager},
+    performance::PerformanceManager,
+    process::{ProcessCoordinator, ProcessManager},
+    room::{drain, state::ProcessStatus, LayoutApplier, SessionSnapshot, WorkspaceManager},
+    supervisor::Supervisor,
+    sync::{FileSyncManager, SyncConfig},
+    task::{TaskConfig, TaskManager, TaskStatus},
+    telemetry::{Telemetry, TelemetryReporter},
+    upgrade::{self, HandoffState, UpgradeHandoff},
     Message,
 };
-use wezterm_parallel::{log_error, log_info, log_warn};
+use wezterm_parallel::{log_debug, log_error, log_info, log_warn};
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Time the server started, reported by `SystemStatus`
+static STARTED_AT: std::sync::OnceLock<Instant> = std::sync::OnceLock::new();
+
+/// Single-instance lock, held until the process exits
+static INSTANCE_LOCK: std::sync::OnceLock<InstanceLock> = std::sync::OnceLock::new();
+
+/// Time a taken-over instance gets to shut down and release the lock
+const TAKEOVER_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Time an upgrade successor waits for its predecessor to release the lock
+const HANDOFF_LOCK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let startup_start = Instant::now();
@@ -33,19 +95,238 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     if args.len() > 1 && (args[1] == "--help" || args[1] == "-h") {
         println!("WezTerm Multi-Process Development Framework v{VERSION}");
-        println!("Usage: wezterm-parallel [OPTIONS]");
+        println!("Usage: wezterm-parallel [OPTIONS] [--daemon] [--pidfile PATH] [--takeover]");
+        println!("       wezterm-parallel <up|down> PROJECT.yaml [--yes]");
+        println!("       wezterm-parallel workspace <create NAME [--template T]|list|switch NAME|delete NAME [--yes]|rename NAME NEW|drain [NAME [--delete]]|undrain NAME|git [NAME]>");
+        println!("       wezterm-parallel task <queue COMMAND... [--priority N] [--id ID] [--cwd DIR] [--env NAME=VALUE]... [--shell SH] [--timeout SECS]|cancel ID|pause ID|resume ID|trace ID|batch FILE [--atomic] [--after ID]...|result ID|latency [--since HOURS]|dead-letters|requeue ID|purge [ID]|github-sync>");
+        println!("       wezterm-parallel ps [--workspace NAME]");
+        println!(
+            "       wezterm-parallel env ID [--set NAME=VALUE]... [--unset NAME]... [--reset]"
+        );
+        println!("       wezterm-parallel output ID [-n N] [-f]");
+        println!(
+            "       wezterm-parallel attach ID [--level L] [--grep TEXT] [-n N] [--no-follow]"
+        );
+        println!("       wezterm-parallel status");
+        println!("       wezterm-parallel <stop|restart> [--timeout SECS]");
+        println!("       wezterm-parallel template <list|lint FILE...>");
+        println!("       wezterm-parallel maintenance <on [REASON]|off|status>");
+        println!("       wezterm-parallel focus <on [LABEL]|off|toggle [LABEL]|status>");
+        println!("       wezterm-parallel log-level [MODULE <trace|debug|info|warn|error|reset>]");
+        println!("       wezterm-parallel logs [--component C] [--process ID] [--level L] [--grep TEXT] [-n N] [-f]");
+        println!("       wezterm-parallel shedding [--since HOURS] [--json]");
+        println!("       wezterm-parallel audit [--client NAME] [--operation OP] [--source ipc|dashboard] [--since HOURS] [--failed] [-n N] [--json]");
+        println!("       wezterm-parallel replay FILE [--realtime]");
+        println!("       wezterm-parallel peer <list|add NAME ADDRESS [--token TOKEN]|remove NAME|call NAME JSON [--yes]>");
+        println!("       wezterm-parallel note TEXT... [--task ID|--workspace NAME]");
+        println!("       wezterm-parallel timeline [--from DATE] [--to DATE] [--format csv|json|mermaid] [--output FILE]");
+        println!("       wezterm-parallel report [daily|weekly] [--range 24h|7d|2w] [--format markdown|html|json] [--output FILE]");
+        println!(
+            "       wezterm-parallel import FILE [--format csv|json] [--source NAME] [--dry-run]"
+        );
+        println!("       wezterm-parallel board <export [--board ID] [--format json|yaml] [--output FILE]|import FILE [--format json|yaml] [--dry-run]>");
+        println!("       wezterm-parallel snapshot <save FILE|restore FILE [--dry-run]>");
+        println!(
+            "       wezterm-parallel self-upgrade <PATH|https://URL> [--sha256 HEX] [--drain-timeout SECS]"
+        );
+        println!("       wezterm-parallel telemetry preview");
+        println!("       wezterm-parallel fsck [--fix] [--json]");
+        println!(
+            "       wezterm-parallel sync <conflicts|resolve ID <ours|theirs|merge|manual FILE>|watch [WORKSPACE <on|off>]>"
+        );
+        println!("       wezterm-parallel install-service [--manager systemd|launchd] [--socket-activation] [--output DIR] [--print]");
+        println!("       wezterm-parallel generate-lua-config [--output PATH] [--socket PATH] [--mods MODS]");
         println!();
         println!("Options:");
         println!("  -h, --help     Show this help message");
         println!("  -v, --version  Show version information");
+        println!("  --daemon       Run detached from the terminal, logging to logging.file_path");
+        println!("  --pidfile PATH Write the daemon's PID to PATH (default next to the socket with --daemon)");
+        println!(
+            "  --takeover     Replace a running daemon, carrying over its workspaces and tasks"
+        );
+        println!();
+        println!("Commands:");
+        println!("  workspace      Create, list, switch, delete, rename or drain workspaces, show their git status");
+        println!("  up, down       Create or tear down the workspaces of a project manifest");
+        println!(
+            "  task           Queue tasks one by one or in batches, control or trace them, show output, latency and dead letters"
+        );
+        println!("  ps             List managed processes");
+        println!("  env            Show a process's environment or change it for the next restart");
+        println!("  output         Show or follow stdout/stderr of a managed process");
+        println!("  attach         Follow the log file of a managed process across rotations");
+        println!("  status         Show whether a daemon is running and its status");
+        println!("  stop, restart  Stop the running daemon with SIGTERM, or stop it and start it detached");
+        println!("  template       List workspace templates or lint template files");
+        println!("  maintenance    Toggle maintenance mode on a running instance");
+        println!("  focus          Silence non-critical alerts and defer low-priority tasks");
+        println!("  log-level      Show or change log levels of a running instance");
+        println!("  logs           Show or follow recent logs of a running instance");
+        println!("  shedding       Report work rejected, deferred or reclaimed under load");
+        println!("  audit          Show who changed workspaces, processes, tasks and settings");
+        println!(
+            "  replay         Send the requests of a recorded IPC session to a running instance"
+        );
+        println!(
+            "  peer           Federate with daemons on other machines and proxy requests to them"
+        );
+        println!("  note           Jot a note on the tracked task or workspace scratchpad");
+        println!("  timeline       Export tracked sessions as CSV, JSON or a Mermaid gantt chart");
+        println!("  report         Export the analytics report as Markdown, HTML or JSON");
+        println!("  import         Import time-tracking CSVs or old exports into the history");
+        println!("  board          Export or import the task board as a JSON/YAML document");
+        println!(
+            "  snapshot       Save all workspaces and unfinished tasks to a file, or restore them"
+        );
+        println!("  self-upgrade   Drain and hand a running instance over to a new binary");
+        println!("  telemetry      Show the anonymous usage report (opt-in via config)");
+        println!("  fsck           Check persisted state for broken references (--fix repairs)");
+        println!("  sync           List and resolve file sync conflicts, pause or resume watching");
+        println!(
+            "  install-service      Write systemd user units or a launchd agent that start the daemon on login"
+        );
+        println!(
+            "  generate-lua-config  Write the WezTerm Lua client (keybindings and IPC helpers)"
+        );
         println!();
         println!("The framework provides multi-process development environment");
         println!("with real-time dashboard and workspace management for WezTerm.");
         return Ok(());
     }
 
+    if args.len() > 1 && args[1] == "maintenance" {
+        return run_maintenance_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "focus" {
+        return run_focus_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "log-level" {
+        return run_log_level_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "logs" {
+        return run_logs_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "shedding" {
+        return run_shedding_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "audit" {
+        return run_audit_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "peer" {
+        return run_peer_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "replay" {
+        return run_replay_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "note" {
+        return run_note_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "timeline" {
+        return run_timeline_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "report" {
+        return run_report_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "import" {
+        return run_import_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "board" {
+        return run_board_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "snapshot" {
+        return run_snapshot_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "self-upgrade" {
+        return run_upgrade_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "telemetry" {
+        return run_telemetry_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "fsck" {
+        return run_fsck_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "sync" {
+        return run_sync_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && (args[1] == "up" || args[1] == "down") {
+        return run_project_command(args[1] == "up", &args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "workspace" {
+        return run_workspace_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "task" {
+        return run_task_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "ps" {
+        return run_ps_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "env" {
+        return run_env_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "output" {
+        return run_output_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "attach" {
+        return run_attach_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "status" {
+        return run_status_command().await;
+    }
+
+    if args.len() > 1 && args[1] == "stop" {
+        return run_stop_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "restart" {
+        return run_restart_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "template" {
+        return run_template_command(&args[2..]).await;
+    }
+
+    if args.len() > 1 && args[1] == "install-service" {
+        return run_install_service(&args[2..]);
+    }
+
+    if args.len() > 1 && args[1] == "generate-lua-config" {
+        return run_generate_lua_config(&args[2..]);
+    }
+
+    if args.iter().any(|arg| arg == "--daemon") {
+        return run_daemonized(&args[1..]).await;
+    }
+
+    STARTED_AT.get_or_init(|| startup_start);
+
     // Initialize tracing
-    tracing_subscriber::fmt::init();
+    log_levels::init_tracing();
 
     let startup_context =
         LogContext::new("system", "startup").with_metadata("version", serde_json::json!(VERSION));
@@ -55,17 +336,33 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         VERSION
     );
 
-    // === パフォーマンス最適化初期化 ===
-    let perf_config = PerformanceConfig {
-        lazy_initialization: true,
-        max_preload_modules: 5,
-        initial_memory_pool_size: 1024 * 1024, // 1MB
-        async_task_pool_size: 4,
-        gc_interval_secs: 300,
-        cpu_limit_percent: 80.0,
-        memory_limit_mb: 512,
+    // One daemon per socket: refuse to start next to a running instance
+    // unless asked to take over from it
+    let takeover = args.iter().any(|arg| arg == "--takeover");
+    let migrated_session = acquire_instance_lock(takeover).await?;
+    // Removed again on a clean shutdown
+    let _pidfile = match pidfile_arg(&args)? {
+        Some(path) => Some(PidFile::create(&path)?),
+        None => None,
     };
 
+    // Framework configuration (telemetry is opt-in and disabled by default)
+    let framework_config = ConfigLoader::new().load().unwrap_or_else(|e| {
+        let config_warn_context = LogContext::new("system", "config_load_error");
+        log_warn!(
+            config_warn_context,
+            "Failed to load configuration, using defaults: {}",
+            e
+        );
+        Default::default()
+    });
+    let telemetry = Arc::new(Telemetry::new(framework_config.telemetry.clone()));
+
+    // === パフォーマンス最適化初期化 ===
+    let perf_config = framework_config.performance.clone();
+    // Subsystems started on first use, or right away without lazy initialization
+    let activations = Arc::new(Activations::new());
+
     // 起動最適化開始
     let mut startup_optimizer = StartupOptimizer::new(perf_config.clone());
 
@@ -76,12 +373,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     startup_optimizer.preload_critical_resources().await?;
 
     // パフォーマンスマネージャー初期化
-    let perf_manager = Arc::new(std::sync::Mutex::new(PerformanceManager::new(
-        perf_config.clone(),
-    )));
+    let perf_manager = Arc::new(PerformanceManager::new(perf_config.clone()));
 
     // メモリ監視開始
-    let mut memory_monitor = MemoryMonitor::new(perf_config.memory_limit_mb);
+    let memory_monitor = MemoryMonitor::new(perf_config.memory_limit_mb)
+        .with_config(framework_config.memory.clone());
 
     // メトリクス収集開始
     let metrics_collector = Arc::new(tokio::sync::RwLock::new(MetricsCollector::new(
@@ -96,8 +392,127 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let perf_context = LogContext::new("system", "performance_init");
     log_info!(perf_context, "パフォーマンス最適化システム初期化完了");
 
+    // Check persisted state before loading it; repairs are left to `fsck --fix`
+    let fsck_report = fsck::check(&StatePaths::default());
+    for issue in &fsck_report.issues {
+        let fsck_context = LogContext::new("system", "fsck_issue")
+            .with_entity_id(&issue.entity)
+            .with_metadata("kind", serde_json::json!(issue.kind));
+        log_warn!(fsck_context, "State check: {}", issue.description);
+    }
+    if fsck_report.fixable_count() > 0 {
+        let fsck_context = LogContext::new("system", "fsck_summary")
+            .with_metadata("issues", serde_json::json!(fsck_report.issues.len()));
+        log_warn!(
+            fsck_context,
+            "Found {} repairable state issues; stop the server and run `wezterm-parallel fsck --fix`",
+            fsck_report.fixable_count()
+        );
+    }
+
+    // Initialize WebSocket dashboard server (workspace and task updates are published to it)
+    let dashboard_config = DashboardConfig {
+        port: 9999,
+        enabled: true,
+        update_interval: 1000, // 1 second
+        max_clients: 10,
+        auth_enabled: framework_config.auth.enabled,
+        auth_token: None,
+        compression: true,
+        language: framework_config.language,
+    };
+
+    let authenticator = Authenticator::new(framework_config.auth.clone());
+    let (websocket_server, metrics_tx) = WebSocketServer::new(dashboard_config);
+    let websocket_server = websocket_server.with_authenticator(Arc::new(authenticator.clone()));
+
+    // Background tasks are restarted when they die and reported on /healthz
+    let supervisor = Arc::new(Supervisor::new().with_dashboard_broadcaster(
+        websocket_server.get_state().broadcast_tx.clone(),
+        framework_config.language,
+    ));
+
+    // Selected workspace events are posted to external automation webhooks
+    // and run the user's hook scripts
+    let hook_runner = Arc::new(
+        HookRunner::new(framework_config.hooks.clone()).with_dashboard_broadcaster(
+            websocket_server.get_state().broadcast_tx.clone(),
+            framework_config.language,
+        ),
+    );
+    let event_bridge = Arc::new(
+        EventBridge::new(framework_config.bridge.clone())
+            .with_dashboard_broadcaster(websocket_server.get_state().broadcast_tx.clone())
+            .with_hooks(hook_runner),
+    );
+
     // Initialize workspace manager
-    let workspace_manager = Arc::new(WorkspaceManager::new(None)?);
+    // User templates from `workspace.templates_dir` extend and override the built-in ones
+    let templates_dir = framework_config.workspace.templates_path();
+    let mut workspace_manager = WorkspaceManager::new(None)?;
+    let user_templates = workspace_manager.load_templates(&templates_dir);
+    workspace_manager.set_event_bridge(Arc::clone(&event_bridge));
+    workspace_manager.set_git_config(framework_config.git.clone());
+
+    // Peer daemons on other machines, polled for the dashboard
+    let federation = Arc::new(
+        Federation::new(&framework_config.federation)
+            .with_dashboard_broadcaster(websocket_server.get_state().broadcast_tx.clone()),
+    );
+    {
+        let federation = Arc::clone(&federation);
+        let period = federation.poll_interval() + wezterm_parallel::federation::PEER_TIMEOUT;
+        supervisor.spawn("federation_poller", period, move |task| {
+            let federation = Arc::clone(&federation);
+            async move {
+                let mut interval = tokio::time::interval(federation.poll_interval());
+                loop {
+                    interval.tick().await;
+                    federation.poll().await;
+                    task.ran();
+                }
+            }
+        });
+    }
+    // Relays CoordinationMessages between processes and answers the ones
+    // addressed to the coordinator
+    let coordinator = Arc::new(ProcessCoordinator::new());
+    tokio::spawn(Arc::clone(&coordinator).run());
+
+    // Processes started by ProcessSpawn and workspace templates; their events
+    // reach the dashboard and the event bridge directly
+    let (process_manager, _) = ProcessManager::new(framework_config.process.manager_config());
+    let process_manager = Arc::new(
+        process_manager
+            .with_dashboard_broadcaster(
+                websocket_server.get_state().broadcast_tx.clone(),
+                framework_config.language,
+            )
+            .with_event_bridge(Arc::clone(&event_bridge))
+            .with_coordinator(Arc::clone(&coordinator)),
+    );
+    workspace_manager.set_process_manager(Arc::clone(&process_manager));
+    {
+        let process_manager = Arc::clone(&process_manager);
+        let period = wezterm_parallel::process::watchdog::WATCHDOG_CHECK_INTERVAL;
+        supervisor.spawn("process_watchdog", period * 5, move |task| {
+            let process_manager = Arc::clone(&process_manager);
+            async move {
+                let mut interval = tokio::time::interval(period);
+                loop {
+                    interval.tick().await;
+                    process_manager.check_watchdog().await;
+                    task.ran();
+                }
+            }
+        });
+    }
+    if framework_config.workspace.apply_layout {
+        workspace_manager.set_layout_applier(Arc::new(LayoutApplier::system(
+            framework_config.workspace.terminal_backend,
+        )));
+    }
+    let workspace_manager = Arc::new(workspace_manager);
     let workspace_count = workspace_manager.get_workspace_count().await;
     let ws_context = LogContext::new("system", "workspace_init")
         .with_metadata("workspace_count", serde_json::json!(workspace_count));
@@ -109,27 +524,69 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Initialize template engine
     use wezterm_parallel::room::template::TemplateEngine;
-    let template_engine = Arc::new(tokio::sync::Mutex::new(TemplateEngine::new()));
-    let template_context = LogContext::new("system", "template_init");
-    log_info!(template_context, "Template engine initialized");
+    let mut templates = TemplateEngine::new();
+    templates.load_dir(&templates_dir);
+    let template_engine = Arc::new(tokio::sync::Mutex::new(templates));
+    let template_context = LogContext::new("system", "template_init")
+        .with_metadata("templates_dir", serde_json::json!(templates_dir))
+        .with_metadata("user_templates", serde_json::json!(user_templates));
+    log_info!(
+        template_context,
+        "Template engine initialized with {} user templates",
+        user_templates
+    );
 
     // Initialize task manager
     let task_config = TaskConfig {
         max_concurrent_tasks: 10,
         default_timeout: 3600, // 1 hour
         max_retry_attempts: 3,
-        persistence_enabled: false,
+        persistence_enabled: true,
         persistence_path: None,
         auto_save_interval: 300, // 5 minutes
         metrics_enabled: true,
         cleanup_interval: 600, // 10 minutes
         max_task_history: 1000,
+        timezone: framework_config.timezone,
+        budgets: framework_config.budgets.clone(),
+        language: framework_config.language,
+        artifacts_dir: None,
+        ids: framework_config.task_ids.clone(),
+        latency_budgets: framework_config.latency_budgets.clone(),
+        preemption: framework_config.preemption.clone(),
     };
 
-    let task_manager = Arc::new(TaskManager::new(task_config));
+    // Rejected, deferred and reclaimed work, for the shedding report
+    let shed_log = Arc::new(ShedLog::new());
+
+    let task_manager = Arc::new(
+        TaskManager::new(task_config)
+            .with_shed_log(Arc::clone(&shed_log))
+            .with_workspace_manager(Arc::clone(&workspace_manager))
+            .with_dashboard_broadcaster(websocket_server.get_state().broadcast_tx.clone())
+            .with_event_bridge(Arc::clone(&event_bridge))
+            .with_coordinator(Arc::clone(&coordinator)),
+    );
     let task_init_context = LogContext::new("system", "task_init");
     log_info!(task_init_context, "Task manager initialized");
 
+    // Restore tasks left over from the previous run
+    match task_manager.restore_state().await {
+        Ok(requeued) => {
+            let restore_context = LogContext::new("system", "task_restore")
+                .with_metadata("requeued", serde_json::json!(requeued));
+            log_info!(
+                restore_context,
+                "Task state restored ({} tasks requeued)",
+                requeued
+            );
+        }
+        Err(e) => {
+            let restore_error_context = LogContext::new("system", "task_restore_error");
+            log_warn!(restore_error_context, "Failed to restore task state: {}", e);
+        }
+    }
+
     // Start task manager background processing
     let _task_handle = task_manager.start().await?;
     let task_bg_context = LogContext::new("system", "task_background_start");
@@ -138,49 +595,209 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         "Task manager background processing started"
     );
 
+    // GitHub issues imported as tasks, and closed when their tasks complete
+    let github_sync = Arc::new(GithubSync::new(
+        framework_config.github.clone(),
+        Arc::clone(&task_manager),
+    ));
+    if github_sync.is_enabled() {
+        if framework_config.github.token().is_none() {
+            let context = LogContext::new("github", "sync_start");
+            log_warn!(
+                context,
+                "No GitHub token in config or ${}: private repositories cannot be read and issues cannot be closed",
+                framework_config.github.token_env
+            );
+        }
+        let github_sync = Arc::clone(&github_sync);
+        let period = github_sync.interval() + wezterm_parallel::github::REQUEST_TIMEOUT;
+        supervisor.spawn("github_sync", period, move |task| {
+            let github_sync = Arc::clone(&github_sync);
+            async move {
+                let mut interval = tokio::time::interval(github_sync.interval());
+                loop {
+                    interval.tick().await;
+                    github_sync.sync().await;
+                    task.ran();
+                }
+            }
+        });
+    }
+
     // Initialize file sync manager
     let file_sync_manager = Arc::new(tokio::sync::Mutex::new(FileSyncManager::new()));
     let sync_init_context = LogContext::new("system", "file_sync_init");
     log_info!(sync_init_context, "File sync manager initialized");
 
-    // Start file watching for current directory
+    // File watching starts with the first dashboard client or sync request
+    let file_sync = framework_config.sync.enabled.then(|| {
+        let file_sync_manager = Arc::clone(&file_sync_manager);
+        let sync_config = framework_config.sync.clone();
+        let supervisor = Arc::clone(&supervisor);
+        let broadcast_tx = websocket_server.get_state().broadcast_tx.clone();
+        Arc::new(LazySubsystem::new(
+            FILE_SYNC_SUBSYSTEM,
+            Arc::clone(&activations),
+            move || {
+                start_file_sync(
+                    Arc::clone(&file_sync_manager),
+                    sync_config.clone(),
+                    Arc::clone(&supervisor),
+                    broadcast_tx.clone(),
+                )
+            },
+        ))
+    });
+    if let Some(file_sync) = file_sync.as_deref() {
+        if !perf_config.lazy_initialization {
+            file_sync.activate(STARTUP_TRIGGER).await;
+        }
+    }
+
+    // Maintenance mode is announced to dashboard clients and exposed via /status
+    let maintenance = Arc::new(
+        MaintenanceController::new(Arc::clone(&task_manager), Arc::clone(&workspace_manager))
+            .with_broadcaster(websocket_server.get_state().broadcast_tx.clone()),
+    );
+    let _drain_monitor = maintenance.spawn_drain_monitor(std::time::Duration::from_secs(2));
+
+    // Draining workspaces marked for deletion are deleted once empty
     {
-        let mut sync_manager = file_sync_manager.lock().await;
-        if let Err(e) = sync_manager.start_watching(".") {
-            let sync_warn_context = LogContext::new("system", "file_watch_failure");
-            log_warn!(sync_warn_context, "Failed to start file watching: {}", e);
-        } else {
-            let sync_start_context = LogContext::new("system", "file_watch_start")
-                .with_metadata("directory", serde_json::json!("."));
-            log_info!(
-                sync_start_context,
-                "File watching started for current directory"
-            );
+        let workspace_manager = Arc::clone(&workspace_manager);
+        let task_manager = Arc::clone(&task_manager);
+        let period = std::time::Duration::from_secs(2);
+        supervisor.spawn("workspace_drain_monitor", period * 5, move |task| {
+            let workspace_manager = Arc::clone(&workspace_manager);
+            let task_manager = Arc::clone(&task_manager);
+            async move {
+                let mut interval = tokio::time::interval(period);
+                loop {
+                    interval.tick().await;
+                    drain::check_drains(&workspace_manager, &task_manager).await;
+                    task.ran();
+                }
+            }
+        });
+    }
+
+    // Changes to the `.wezterm-parallel.yaml` of workspace roots apply without a restart
+    {
+        let workspace_manager = Arc::clone(&workspace_manager);
+        let period = std::time::Duration::from_secs(2);
+        supervisor.spawn("workspace_overrides_watcher", period * 5, move |task| {
+            let workspace_manager = Arc::clone(&workspace_manager);
+            async move {
+                let mut interval = tokio::time::interval(period);
+                loop {
+                    interval.tick().await;
+                    workspace_manager.reload_overrides().await;
+                    task.ran();
+                }
+            }
+        });
+    }
+
+    // Focus mode silences non-critical dashboard alerts and defers low-priority tasks
+    let focus = Arc::new(
+        FocusController::new(
+            Arc::clone(&task_manager),
+            Arc::clone(&workspace_manager),
+            framework_config.focus.clone(),
+        )
+        .with_broadcaster(websocket_server.get_state().broadcast_tx.clone()),
+    );
+
+    // Metrics history is sampled periodically and answers dashboard history queries
+    let metrics_storage = if framework_config.metrics.enabled {
+        match MetricsStorage::from_config(&framework_config.metrics).await {
+            Ok(storage) => Some(Arc::new(storage)),
+            Err(e) => {
+                let history_error_context = LogContext::new("system", "metrics_history_error");
+                log_warn!(history_error_context, "Metrics history disabled: {}", e);
+                None
+            }
         }
+    } else {
+        None
+    };
+    // Framework metrics are assembled from the managers and pushed to the dashboard
+    if framework_config.metrics.enabled {
+        spawn_metrics_aggregator(
+            &supervisor,
+            metrics_storage.clone(),
+            Arc::clone(&workspace_manager),
+            Arc::clone(&task_manager),
+            websocket_server.get_state(),
+            metrics_tx,
+            framework_config.metrics.clone(),
+        );
     }
 
-    // Initialize WebSocket dashboard server
-    let dashboard_config = DashboardConfig {
-        port: 9999,
-        enabled: true,
-        update_interval: 1000, // 1 second
-        max_clients: 10,
-        auth_enabled: false,
-        auth_token: None,
-        compression: true,
+    // System metrics, alert thresholds and component health checks
+    let mut monitoring = MonitoringManager::new(framework_config.monitoring.clone())
+        .with_language(framework_config.language)
+        .with_health_checks(Arc::clone(&workspace_manager), Arc::clone(&task_manager))
+        .with_supervisor(Arc::clone(&supervisor))
+        .with_shed_log(Arc::clone(&shed_log));
+    // Analytics records from the first report export on
+    if perf_config.lazy_initialization {
+        monitoring = monitoring.with_lazy_analytics(Arc::clone(&activations));
+    }
+    let monitoring = Arc::new(monitoring);
+    if let Err(e) = monitoring.start().await {
+        let monitoring_error_context = LogContext::new("system", "monitoring_error");
+        log_warn!(
+            monitoring_error_context,
+            "Monitoring system failed to start: {}",
+            e
+        );
+    }
+
+    let mut websocket_server = websocket_server;
+    if let Some(ref storage) = metrics_storage {
+        websocket_server = websocket_server.with_metrics_storage(Arc::clone(storage));
+    }
+    if let Some(ref file_sync) = file_sync {
+        websocket_server = websocket_server.with_activate_on_connect(Arc::clone(file_sync));
+    }
+    // Who changed what, from the IPC socket and the dashboard
+    let audit_journal = match AuditJournal::from_config(&framework_config.audit) {
+        Ok(journal) => journal.map(Arc::new),
+        Err(e) => {
+            let audit_context = LogContext::new("system", "audit_journal_error");
+            log_warn!(audit_context, "Audit journal disabled: {}", e);
+            None
+        }
     };
 
-    let (websocket_server, _metrics_tx) = WebSocketServer::new(dashboard_config);
-    let websocket_server = Arc::new(websocket_server.with_task_manager(Arc::clone(&task_manager)));
+    let websocket_server = Arc::new(
+        websocket_server
+            .with_audit_journal(audit_journal.clone())
+            .with_task_manager(Arc::clone(&task_manager))
+            .with_workspace_manager(Arc::clone(&workspace_manager))
+            .with_maintenance(Arc::clone(&maintenance))
+            .with_focus(Arc::clone(&focus))
+            .with_file_sync_manager(Arc::clone(&file_sync_manager))
+            .with_supervisor(Arc::clone(&supervisor))
+            .with_event_bridge(Arc::clone(&event_bridge))
+            .with_monitoring(Arc::clone(&monitoring))
+            .with_federation(Arc::clone(&federation))
+            .with_caches(perf_manager.caches())
+            .with_activations(Arc::clone(&activations))
+            .with_lazy_start(perf_config.lazy_initialization),
+    );
 
     // Start WebSocket server in background
-    let ws_server = Arc::clone(&websocket_server);
-    tokio::spawn(async move {
-        if let Err(e) = ws_server.start().await {
-            let ws_error_context = LogContext::new("system", "websocket_error");
-            log_error!(ws_error_context, "WebSocket server error: {}", e);
-        }
-    });
+    let ws_task = Arc::new(std::sync::Mutex::new(Some(spawn_websocket_server(
+        Arc::clone(&websocket_server),
+    ))));
+    register_recovery_actions(
+        &monitoring,
+        &websocket_server,
+        ws_task,
+        &workspace_manager,
+        &perf_manager,
+    );
 
     let ws_start_context =
         LogContext::new("system", "websocket_start").with_metadata("port", serde_json::json!(9999));
@@ -189,6 +806,27 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         "WebSocket dashboard server started on port 9999"
     );
 
+    // Anonymous usage reports (only when opted in)
+    {
+        let telemetry = Arc::clone(&telemetry);
+        let workspace_manager = Arc::clone(&workspace_manager);
+        let task_manager = Arc::clone(&task_manager);
+        let template_engine = Arc::clone(&template_engine);
+        let _telemetry_handle =
+            TelemetryReporter::new(framework_config.telemetry.clone()).spawn(move || {
+                let telemetry = Arc::clone(&telemetry);
+                let workspace_manager = Arc::clone(&workspace_manager);
+                let task_manager = Arc::clone(&task_manager);
+                let template_engine = Arc::clone(&template_engine);
+                async move {
+                    let totals =
+                        collect_usage_totals(&workspace_manager, &task_manager, &template_engine)
+                            .await;
+                    telemetry.report(totals)
+                }
+            });
+    }
+
     // 遅延初期化をスケジュール
     startup_optimizer.schedule_lazy_initialization();
 
@@ -207,80 +845,275 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         startup_time
     );
 
-    if let Ok(mut perf_mgr) = perf_manager.lock() {
-        perf_mgr.record_startup_complete();
-        let perf_report_context = LogContext::new("system", "performance_report");
-        log_info!(perf_report_context, "{}", perf_mgr.generate_report());
-    }
+    perf_manager.record_startup_complete();
+    let perf_report_context = LogContext::new("system", "performance_report");
+    log_info!(
+        perf_report_context,
+        "{}",
+        perf_manager.generate_report(framework_config.language)
+    );
+
+    // Platform socket (Unix domain socket or named pipe)
+    let socket_path = ipc::default_socket_path();
 
-    // Unix Domain Socket path
-    let socket_path = "/tmp/wezterm-parallel.sock";
+    let handoff_path = UpgradeHandoff::path_from_env();
 
-    // Remove existing socket file if it exists
-    if Path::new(socket_path).exists() {
-        std::fs::remove_file(socket_path)?;
+    // Create the listener, or take the one a systemd socket unit passed in;
+    // remember our own user to recognise local clients
+    let mut listener = match IpcListener::from_socket_activation()? {
+        Some(listener) => {
+            let activation_context = LogContext::new("system", "socket_activation")
+                .with_metadata("socket_path", serde_json::json!(listener.path()));
+            log_info!(
+                activation_context,
+                "Using IPC socket {} passed by systemd",
+                listener.path().display()
+            );
+            listener
+        }
+        // The previous instance still owns the socket path during an
+        // upgrade; serve next to it until the instance lock is ours
+        None if handoff_path.is_some() => {
+            let listener = IpcListener::bind_staged(&socket_path, authenticator.socket_mode())?;
+            publish_after_handoff(listener.path().to_path_buf(), socket_path.clone());
+            listener
+        }
+        None => IpcListener::bind(&socket_path, authenticator.socket_mode())?,
+    };
+
+    // The recovery policy binds a fresh socket when the file went missing
+    // and hands it to the accept loop below
+    let (rebind_tx, mut rebind_rx) = tokio::sync::mpsc::channel::<IpcListener>(1);
+    {
+        let socket_mode = authenticator.socket_mode();
+        let socket_path = socket_path.clone();
+        monitoring
+            .recovery()
+            .set_handler(RecoveryAction::RebindIpc, move || {
+                let rebind_tx = rebind_tx.clone();
+                let socket_path = socket_path.clone();
+                async move {
+                    // Without the lock the file may belong to another instance
+                    if INSTANCE_LOCK.get().is_none() {
+                        return Err("Instance lock is not held yet".to_string());
+                    }
+                    let listener = IpcListener::bind(&socket_path, socket_mode)
+                        .map_err(|e| format!("Failed to bind {}: {e}", socket_path.display()))?;
+                    rebind_tx
+                        .send(listener)
+                        .await
+                        .map_err(|_| "IPC server is shutting down".to_string())?;
+                    Ok(format!("IPC socket rebound at {}", socket_path.display()))
+                }
+            });
     }
+    let authenticator = match listener.owner_uid() {
+        Some(owner_uid) => authenticator.with_owner_uid(owner_uid),
+        None => authenticator,
+    };
+    let authenticator = Arc::new(authenticator);
 
-    // Create Unix Domain Socket listener
-    let listener = UnixListener::bind(socket_path)?;
     let ipc_start_context = LogContext::new("system", "ipc_server_start")
-        .with_metadata("socket_path", serde_json::json!(socket_path));
-    log_info!(ipc_start_context, "IPC Server listening on {}", socket_path);
+        .with_metadata("socket_path", serde_json::json!(socket_path))
+        .with_metadata(
+            "socket_mode",
+            serde_json::json!(format!("{:o}", authenticator.socket_mode())),
+        )
+        .with_metadata(
+            "auth_enabled",
+            serde_json::json!(authenticator.is_enabled()),
+        );
+    log_info!(
+        ipc_start_context,
+        "IPC Server listening on {}",
+        socket_path.display()
+    );
+
+    // Started by an upgrade: tell the previous instance we have taken over
+    if let Some(handoff_path) = handoff_path {
+        complete_handoff(&handoff_path, listener.path());
+    }
+
+    // Took over from a running instance: bring its session across
+    if let Some(content) = migrated_session {
+        restore_migrated_session(&content, &workspace_manager, &task_manager).await;
+    }
 
     // パフォーマンス監視タスクを開始
+    let memory_monitor = Arc::new(tokio::sync::Mutex::new(memory_monitor));
     let perf_manager_clone = Arc::clone(&perf_manager);
     let metrics_collector_clone = Arc::clone(&metrics_collector);
+    let memory_workspace_manager = Arc::clone(&workspace_manager);
+    let memory_monitoring = Arc::clone(&monitoring);
+    let perf_period =
+        std::time::Duration::from_secs(framework_config.memory.sample_interval_secs.max(1));
 
-    tokio::spawn(async move {
-        let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
-        loop {
-            interval.tick().await;
-
-            // メモリ使用量チェック
-            if let Err(e) = memory_monitor.check_memory_usage().await {
-                let memory_warn_context = LogContext::new("system", "memory_monitor_error");
-                log_warn!(memory_warn_context, "メモリ監視エラー: {}", e);
-            }
+    supervisor.spawn("metrics_collector", perf_period, move |task| {
+        let memory_monitor = Arc::clone(&memory_monitor);
+        let perf_manager = Arc::clone(&perf_manager_clone);
+        let metrics_collector = Arc::clone(&metrics_collector_clone);
+        let workspace_manager = Arc::clone(&memory_workspace_manager);
+        let monitoring = Arc::clone(&memory_monitoring);
+        async move {
+            let mut interval = tokio::time::interval(perf_period);
+            loop {
+                interval.tick().await;
 
-            // パフォーマンス統計更新
-            {
-                if let Ok(mut perf_mgr) = perf_manager_clone.lock() {
-                    perf_mgr.periodic_gc();
+                // メモリ使用量チェック
+                let mut memory_monitor = memory_monitor.lock().await;
+                if let Err(e) = memory_monitor.check_memory_usage().await {
+                    let memory_warn_context = LogContext::new("system", "memory_monitor_error");
+                    log_warn!(memory_warn_context, "メモリ監視エラー: {}", e);
+                }
+                let rss = memory_monitor.get_current_memory_usage().await.unwrap_or(0);
 
-                    // CPU・メモリ使用量を更新（実際の値を取得する必要がある）
-                    perf_mgr.update_cpu_usage(25.0); // サンプル値
-                    perf_mgr.update_memory_usage(64 * 1024 * 1024); // 64MB サンプル値
+                // デーモンと子プロセスの RSS の推移からリークを検出
+                let process_manager = workspace_manager.process_manager();
+                let processes: Vec<(String, u32)> = match process_manager {
+                    Some(ref process_manager) => process_manager
+                        .list_processes()
+                        .await
+                        .into_iter()
+                        .filter_map(|process| Some((process.id, process.pid?)))
+                        .collect(),
+                    None => Vec::new(),
+                };
+                for (target, check) in memory_monitor.sample_leaks(&processes).await {
+                    match check {
+                        LeakCheck::Leaking(leak) => {
+                            mitigate_memory_leak(
+                                &leak,
+                                &mut memory_monitor,
+                                &perf_manager,
+                                process_manager.as_deref(),
+                                &monitoring,
+                            )
+                            .await;
+                        }
+                        LeakCheck::Clean => {
+                            monitoring
+                                .resolve_alert(&memory_leak_alert_id(&target))
+                                .await;
+                        }
+                        LeakCheck::Warming => {}
+                    }
                 }
-            }
+                drop(memory_monitor);
+
+                // パフォーマンス統計更新
+                perf_manager.periodic_gc();
 
-            // メトリクス更新
-            {
-                let metrics = metrics_collector_clone.read().await;
-                metrics.update_cpu_usage(25.0).await;
-                metrics
-                    .update_memory_usage(64 * 1024 * 1024, 128 * 1024 * 1024)
-                    .await;
+                // CPU使用率は未計測のためサンプル値
+                perf_manager.update_cpu_usage(25.0);
+                perf_manager.update_memory_usage(rss);
+                let peak = perf_manager.get_metrics().peak_memory;
+
+                // メトリクス更新
+                {
+                    let metrics = metrics_collector.read().await;
+                    metrics.update_cpu_usage(25.0).await;
+                    metrics.update_memory_usage(rss, peak).await;
+                }
+                task.ran();
             }
         }
     });
 
+    // Saved again on shutdown so active tracking sessions survive the restart
+    let shutdown_task_manager = Arc::clone(&task_manager);
+
+    // Optional recording of all IPC traffic for `wezterm-parallel replay`
+    let session_recorder = framework_config
+        .server
+        .record_session
+        .as_ref()
+        .and_then(|path| match SessionRecorder::create(path) {
+            Ok(recorder) => {
+                let record_context = LogContext::new("ipc", "session_recording")
+                    .with_metadata("path", serde_json::json!(path));
+                log_info!(record_context, "Recording IPC session to {}", path);
+                Some(Arc::new(recorder))
+            }
+            Err(e) => {
+                let record_context = LogContext::new("ipc", "session_recording_error");
+                log_warn!(
+                    record_context,
+                    "Cannot record IPC session to {}: {}",
+                    path,
+                    e
+                );
+                None
+            }
+        });
+
+    // Raised by a `Takeover` request once its snapshot has been handed over
+    let shutdown_requested = Arc::new(tokio::sync::Notify::new());
+
+    let limits = IpcLimits::from(&framework_config.server);
+    let services = IpcServices {
+        pipeline: Arc::new(build_ipc_pipeline(
+            workspace_manager,
+            task_manager,
+            template_engine,
+            maintenance,
+            focus,
+            telemetry,
+            authenticator,
+            limits,
+            // Shared with the dashboard's /metrics endpoint and protocol health panel
+            Arc::clone(&websocket_server.get_state().protocol_metrics),
+            file_sync_manager,
+            file_sync,
+            websocket_server.task_board(),
+            shed_log,
+            session_recorder,
+            federation,
+            audit_journal,
+            github_sync,
+            Arc::clone(&monitoring),
+            Arc::clone(&shutdown_requested),
+        )),
+        perf_manager,
+    };
+    let connections = ConnectionLimiter::new(limits.max_connections);
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+
     loop {
-        match listener.accept().await {
-            Ok((stream, _)) => {
-                let connection_context = LogContext::new("ipc", "client_connect");
-                log_info!(connection_context, "New client connected");
-                let ws_manager = Arc::clone(&workspace_manager);
-                let task_mgr = Arc::clone(&task_manager);
-                let perf_mgr = Arc::clone(&perf_manager);
-                let tmpl_engine = Arc::clone(&template_engine);
-                tokio::spawn(handle_client(
-                    stream,
-                    ws_manager,
-                    task_mgr,
-                    perf_mgr,
-                    tmpl_engine,
-                ));
+        let accepted = tokio::select! {
+            accepted = listener.accept() => accepted,
+            Some(rebound) = rebind_rx.recv() => {
+                listener = rebound;
+                continue;
             }
+            _ = &mut shutdown => break,
+            _ = shutdown_requested.notified() => break,
+        };
+        match accepted {
+            Ok(accepted) => match connections.try_acquire() {
+                Some(permit) => {
+                    let connection_context = LogContext::new("ipc", "client_connect")
+                        .with_metadata("active", serde_json::json!(connections.active()));
+                    log_info!(connection_context, "New client connected");
+                    tokio::spawn(handle_client(accepted, services.clone(), limits, permit));
+                }
+                None => {
+                    let limit_context = LogContext::new("ipc", "connection_limit").with_metadata(
+                        "max_connections",
+                        serde_json::json!(limits.max_connections),
+                    );
+                    log_warn!(
+                        limit_context,
+                        "Rejecting client: {} connections already open",
+                        limits.max_connections
+                    );
+                    tokio::spawn(reject_client(
+                        accepted.stream,
+                        limits,
+                        connections.rejection(),
+                    ));
+                }
+            },
             Err(e) => {
                 let connection_error_context = LogContext::new("ipc", "connection_accept_error");
                 log_error!(
@@ -291,345 +1124,821 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
         }
     }
+
+    let shutdown_context = LogContext::new("system", "shutdown");
+    log_info!(shutdown_context, "Shutting down, saving task state");
+    if let Err(e) = shutdown_task_manager.save_state().await {
+        let save_context = LogContext::new("system", "shutdown_save_error");
+        log_error!(save_context, "Failed to save task state on shutdown: {}", e);
+    }
+    // Managed processes do not outlive the daemon
+    process_manager.shutdown_all().await;
+    Ok(())
 }
 
-async fn handle_client(
-    mut stream: UnixStream,
-    workspace_manager: Arc<WorkspaceManager>,
-    task_manager: Arc<TaskManager>,
-    perf_manager: Arc<std::sync::Mutex<PerformanceManager>>,
-    template_engine: Arc<tokio::sync::Mutex<wezterm_parallel::room::template::TemplateEngine>>,
-) {
-    let mut buffer = [0; 1024];
+/// Name file watching's start is recorded under
+const FILE_SYNC_SUBSYSTEM: &str = "file_sync";
 
-    loop {
-        match stream.read(&mut buffer).await {
-            Ok(0) => {
-                let disconnect_context = LogContext::new("ipc", "client_disconnect");
-                log_info!(disconnect_context, "Client disconnected");
-                break;
+/// Watch each configured workspace (current directory by default) and push
+/// debounced file changes to dashboard clients
+async fn start_file_sync(
+    file_sync_manager: Arc<tokio::sync::Mutex<FileSyncManager>>,
+    sync_config: SyncConfig,
+    supervisor: Arc<Supervisor>,
+    broadcast_tx: tokio::sync::broadcast::Sender<DashboardMessage>,
+) {
+    {
+        let mut sync_manager = file_sync_manager.lock().await;
+        sync_manager.set_ignore_patterns(sync_config.ignore.clone(), sync_config.use_gitignore);
+        for (workspace, watch) in sync_config.workspace_watches() {
+            let roots = watch.roots.clone();
+            if let Err(e) = sync_manager.watch_workspace(&workspace, watch) {
+                let sync_warn_context =
+                    LogContext::new("system", "file_watch_failure").with_entity_id(&workspace);
+                log_warn!(sync_warn_context, "Failed to start file watching: {}", e);
+            } else {
+                let sync_start_context = LogContext::new("system", "file_watch_start")
+                    .with_entity_id(&workspace)
+                    .with_metadata("roots", serde_json::json!(roots));
+                log_info!(
+                    sync_start_context,
+                    "File watching started for workspace {}",
+                    workspace
+                );
             }
-            Ok(n) => {
-                let data = &buffer[..n];
-
-                // Try to parse JSON message
-                match serde_json::from_slice::<Message>(data) {
-                    Ok(message) => {
-                        let message_context = LogContext::new("ipc", "message_receive")
-                            .with_metadata(
-                                "message_type",
-                                serde_json::json!(format!("{:?}", message)),
-                            );
-                        log_info!(message_context, "Received message: {:?}", message);
-
-                        // Handle message with performance tracking
-                        let start_time = Instant::now();
-                        let response = handle_message(
-                            message,
-                            &workspace_manager,
-                            &task_manager,
-                            &template_engine,
-                        )
-                        .await;
-                        let _response_time = start_time.elapsed();
-
-                        // パフォーマンス統計を更新
-                        if let Ok(mut perf_mgr) = perf_manager.lock() {
-                            perf_mgr.update_cpu_usage(20.0); // リクエスト処理によるCPU使用量
-                        }
-
-                        // Send response
-                        if let Ok(response_json) = serde_json::to_vec(&response) {
-                            if let Err(e) = stream.write_all(&response_json).await {
-                                let send_error_context =
-                                    LogContext::new("ipc", "response_send_error");
-                                log_error!(send_error_context, "Failed to send response: {}", e);
-                                break;
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        let parse_error_context = LogContext::new("ipc", "message_parse_error");
-                        log_warn!(parse_error_context, "Failed to parse message: {}", e);
-
-                        // Send error response
-                        let error_msg = Message::StatusUpdate {
-                            process_id: "system".to_string(),
-                            status: format!("Parse error: {e}"),
-                        };
+        }
+    }
 
-                        if let Ok(error_json) = serde_json::to_vec(&error_msg) {
-                            let _ = stream.write_all(&error_json).await;
-                        }
+    supervisor.spawn(
+        "file_watcher",
+        std::time::Duration::from_secs(1),
+        move |task| {
+            let sync_manager = Arc::clone(&file_sync_manager);
+            let broadcast_tx = broadcast_tx.clone();
+            async move {
+                let mut interval = tokio::time::interval(std::time::Duration::from_millis(250));
+                loop {
+                    interval.tick().await;
+                    let batches = sync_manager.lock().await.collect_change_batches();
+                    for batch in batches {
+                        let _ = broadcast_tx.send(DashboardMessage::FileChanges {
+                            workspace: batch.workspace,
+                            changes: batch.changes,
+                            omitted: batch.omitted,
+                            timestamp: upgrade::now_secs(),
+                        });
                     }
+                    task.ran();
                 }
             }
-            Err(e) => {
-                let read_error_context = LogContext::new("ipc", "stream_read_error");
-                log_error!(read_error_context, "Failed to read from stream: {}", e);
-                break;
-            }
+        },
+    );
+}
+
+/// Run the dashboard server until it stops or is aborted
+fn spawn_websocket_server(server: Arc<WebSocketServer>) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        if let Err(e) = server.start().await {
+            let ws_error_context = LogContext::new("system", "websocket_error");
+            log_error!(ws_error_context, "WebSocket server error: {}", e);
         }
-    }
+    })
 }
 
-async fn handle_message(
-    message: Message,
-    workspace_manager: &WorkspaceManager,
-    task_manager: &TaskManager,
-    template_engine: &Arc<tokio::sync::Mutex<wezterm_parallel::room::template::TemplateEngine>>,
-) -> Message {
-    use wezterm_parallel::TemplateInfo;
-
-    match message {
-        Message::Ping => {
-            let ping_context = LogContext::new("ipc", "ping_receive");
-            log_info!(ping_context, "Ping received, responding with Pong");
-            Message::Pong
+fn memory_leak_alert_id(target: &str) -> String {
+    format!("memory_leak_{target}")
+}
+
+/// Respond to steady memory growth of the daemon or a managed process:
+/// collect garbage, raise an alert and, when configured, restart the process
+async fn mitigate_memory_leak(
+    leak: &MemoryLeak,
+    memory_monitor: &mut MemoryMonitor,
+    perf_manager: &PerformanceManager,
+    process_manager: Option<&ProcessManager>,
+    monitoring: &MonitoringManager,
+) {
+    let config = memory_monitor.config().clone();
+    let context = LogContext::new("system", "memory_leak")
+        .with_entity_id(&leak.target)
+        .with_metadata(
+            "growth_mb_per_hour",
+            serde_json::json!(leak.growth_mb_per_hour),
+        )
+        .with_metadata("window_minutes", serde_json::json!(leak.window_minutes));
+    log_warn!(
+        context.clone(),
+        "メモリリークの疑い: {} が {:.1}MB/時間で増加中 ({}分間, R²={:.2})",
+        leak.target,
+        leak.growth_mb_per_hour,
+        leak.window_minutes,
+        leak.fit
+    );
+
+    let mut actions = Vec::new();
+    if config.trigger_gc {
+        perf_manager.trigger_gc();
+        memory_monitor.perform_cleanup().await;
+        actions.push("gc".to_string());
+    }
+    if config.restart_leaking_processes && leak.target != DAEMON_TARGET {
+        let result = match process_manager {
+            Some(process_manager) => process_manager.restart_process(&leak.target).await,
+            None => Err("Process manager not available".to_string()),
+        };
+        match result {
+            Ok(()) => actions.push("restart".to_string()),
+            Err(e) => log_warn!(context, "リークしたプロセスの再起動に失敗: {}", e),
         }
-        Message::WorkspaceCreate { name, template } => {
-            let create_context = LogContext::new("ipc", "workspace_create_request")
-                .with_entity_id(&name)
-                .with_metadata("template", serde_json::json!(template));
-            log_info!(
-                create_context,
-                "Creating workspace: {} with template: {}",
-                name,
-                template
-            );
+    }
+    if config.alert {
+        let mut data = HashMap::new();
+        data.insert("rss_bytes".to_string(), serde_json::json!(leak.rss_bytes));
+        data.insert(
+            "growth_mb_per_hour".to_string(),
+            serde_json::json!(leak.growth_mb_per_hour),
+        );
+        data.insert(
+            "window_minutes".to_string(),
+            serde_json::json!(leak.window_minutes),
+        );
+        data.insert("fit".to_string(), serde_json::json!(leak.fit));
+        data.insert("actions".to_string(), serde_json::json!(actions));
+        monitoring
+            .create_alert(Alert {
+                id: memory_leak_alert_id(&leak.target),
+                severity: AlertSeverity::Warning,
+                category: "memory".to_string(),
+                message: format!(
+                    "Memory of {} grew {:.1} MB/hour over {} minutes",
+                    leak.target, leak.growth_mb_per_hour, leak.window_minutes
+                ),
+                component: Some(leak.target.clone()),
+                timestamp: chrono::Utc::now().timestamp() as u64,
+                data,
+                resolved: false,
+                resolved_at: None,
+            })
+            .await;
+    }
 
-            match workspace_manager.create_workspace(&name, &template).await {
-                Ok(()) => {
-                    let success_context =
-                        LogContext::new("ipc", "workspace_create_success").with_entity_id(&name);
-                    log_info!(success_context, "Successfully created workspace '{}'", name);
-                    Message::StatusUpdate {
-                        process_id: "workspace_manager".to_string(),
-                        status: format!(
-                            "Workspace '{name}' created successfully with template '{template}'"
-                        ),
-                    }
-                }
-                Err(e) => {
-                    let error_context =
-                        LogContext::new("ipc", "workspace_create_error").with_entity_id(&name);
-                    log_error!(
-                        error_context,
-                        "Failed to create workspace '{}': {}",
-                        name,
-                        e
-                    );
-                    Message::StatusUpdate {
-                        process_id: "workspace_manager".to_string(),
-                        status: format!("Failed to create workspace '{name}': {e}"),
-                    }
-                }
+    // Judge the target afresh from here on
+    memory_monitor.reset_leak(&leak.target);
+}
+
+/// Teach the monitoring recovery policy how to restart the dashboard server,
+/// failed processes and caches; the IPC socket is rebound by the accept loop
+fn register_recovery_actions(
+    monitoring: &MonitoringManager,
+    websocket_server: &Arc<WebSocketServer>,
+    ws_task: Arc<std::sync::Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    workspace_manager: &Arc<WorkspaceManager>,
+    perf_manager: &Arc<PerformanceManager>,
+) {
+    let recovery = monitoring.recovery();
+
+    let websocket_server = Arc::clone(websocket_server);
+    recovery.set_handler(RecoveryAction::RestartWebsocket, move || {
+        let websocket_server = Arc::clone(&websocket_server);
+        let ws_task = Arc::clone(&ws_task);
+        async move {
+            // Wait for the old server to let go of its port before binding it again
+            let previous = ws_task.lock().unwrap().take();
+            if let Some(previous) = previous {
+                previous.abort();
+                let _ = previous.await;
             }
+            *ws_task.lock().unwrap() = Some(spawn_websocket_server(websocket_server));
+            Ok("WebSocket server restarted".to_string())
         }
-        Message::ProcessSpawn { workspace, command } => {
-            let spawn_context = LogContext::new("ipc", "process_spawn_request")
-                .with_entity_id(&workspace)
-                .with_metadata("command", serde_json::json!(command));
-            log_info!(
-                spawn_context,
-                "Spawning process in workspace '{}': {}",
-                workspace,
-                command
-            );
+    });
 
-            // Check if workspace exists
-            if workspace_manager
-                .get_workspace_info(&workspace)
+    let processes = Arc::clone(workspace_manager);
+    recovery.set_handler(RecoveryAction::RestartFailedProcesses, move || {
+        let workspace_manager = Arc::clone(&processes);
+        async move {
+            let process_manager = workspace_manager
+                .process_manager()
+                .ok_or_else(|| "Process manager not available".to_string())?;
+            let failed: Vec<String> = process_manager
+                .list_processes()
                 .await
-                .is_some()
-            {
-                // TODO: Implement actual process spawning logic
-                Message::StatusUpdate {
-                    process_id: "process_manager".to_string(),
-                    status: format!("Process '{command}' spawned in workspace '{workspace}'"),
+                .into_iter()
+                .filter(|process| process.status == ProcessStatus::Failed)
+                .map(|process| process.id)
+                .collect();
+
+            let mut errors = Vec::new();
+            for process_id in &failed {
+                if let Err(e) = process_manager.restart_process(process_id).await {
+                    errors.push(format!("{process_id}: {e}"));
                 }
+            }
+            if errors.is_empty() {
+                Ok(format!("Restarted processes: {}", failed.join(", ")))
             } else {
-                let not_found_context =
-                    LogContext::new("ipc", "workspace_not_found").with_entity_id(&workspace);
-                log_error!(
-                    not_found_context,
-                    "Workspace '{}' not found for process spawning",
-                    workspace
-                );
-                Message::StatusUpdate {
-                    process_id: "process_manager".to_string(),
-                    status: format!("Failed to spawn process: workspace '{workspace}' not found"),
-                }
+                Err(format!("Failed to restart {}", errors.join("; ")))
             }
         }
-        Message::TaskQueue {
-            id,
-            priority,
-            command,
-        } => {
-            let queue_context = LogContext::new("ipc", "task_queue_request")
-                .with_entity_id(&id)
-                .with_metadata("priority", serde_json::json!(priority))
-                .with_metadata("command", serde_json::json!(command));
-            log_info!(
-                queue_context,
-                "Queuing task {}: {} (priority: {})",
-                id,
-                command,
-                priority
-            );
-
-            // Create a task from the queue message
-            let mut task = wezterm_parallel::task::Task::new(
-                format!("Task: {command}"),
-                wezterm_parallel::task::types::TaskCategory::Development,
-            );
+    });
 
-            // Set priority based on message priority
-            task.priority = match priority {
-                1 | 2 => wezterm_parallel::task::types::TaskPriority::Low,
-                3 | 4 => wezterm_parallel::task::types::TaskPriority::Medium,
-                5 | 6 => wezterm_parallel::task::types::TaskPriority::High,
-                7 | 8 => wezterm_parallel::task::types::TaskPriority::Critical,
-                _ => wezterm_parallel::task::types::TaskPriority::Urgent,
+    let workspace_manager = Arc::clone(workspace_manager);
+    let perf_manager = Arc::clone(perf_manager);
+    recovery.set_handler(RecoveryAction::TriggerGc, move || {
+        let workspace_manager = Arc::clone(&workspace_manager);
+        let perf_manager = Arc::clone(&perf_manager);
+        async move {
+            let cleaned = match workspace_manager.process_manager() {
+                Some(process_manager) => process_manager.cleanup_finished_processes().await,
+                None => 0,
             };
+            perf_manager.trigger_gc();
+            Ok(format!("Cleared caches and {cleaned} finished processes"))
+        }
+    });
+}
 
-            // Set workspace if available
-            if let Some((workspace_name, _)) = workspace_manager.get_active_workspace().await {
-                task.workspace = Some(workspace_name.clone());
+/// Resolves on Ctrl-C or SIGTERM
+#[cfg(unix)]
+async fn shutdown_signal() {
+    match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+        Ok(mut terminate) => {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = terminate.recv() => {}
             }
+        }
+        Err(_) => {
+            let _ = tokio::signal::ctrl_c().await;
+        }
+    }
+}
 
-            // Add task to task manager
-            match task_manager.create_task(task).await {
-                Ok(task_id) => {
-                    let task_success_context = LogContext::new("ipc", "task_create_success")
-                        .with_entity_id(&task_id)
-                        .with_metadata("command", serde_json::json!(command));
-                    log_info!(
-                        task_success_context,
-                        "Task '{}' created successfully with ID: {}",
-                        command,
-                        task_id
-                    );
-                    Message::StatusUpdate {
-                        process_id: "task_manager".to_string(),
-                        status: format!("Task '{command}' created successfully with ID: {task_id}"),
+/// Resolves on Ctrl-C
+#[cfg(not(unix))]
+async fn shutdown_signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+/// Every collection interval, build the framework metrics from the workspace,
+/// process and task managers, push them to the dashboard and record them (with
+/// a history sample) to storage; prune old history hourly
+fn spawn_metrics_aggregator(
+    supervisor: &Arc<Supervisor>,
+    storage: Option<Arc<MetricsStorage>>,
+    workspace_manager: Arc<WorkspaceManager>,
+    task_manager: Arc<TaskManager>,
+    dashboard: Arc<DashboardState>,
+    metrics_tx: tokio::sync::mpsc::Sender<MetricsUpdate>,
+    config: wezterm_parallel::metrics::MetricsConfig,
+) -> tokio::task::JoinHandle<()> {
+    let period = std::time::Duration::from_secs(config.collection_interval.max(1));
+    // Kept across restarts so the in-memory history survives them
+    let aggregator = Arc::new(MetricsAggregator::new(config.clone()));
+    supervisor.spawn("metrics_aggregator", period, move |task| {
+        let storage = storage.clone();
+        let workspace_manager = Arc::clone(&workspace_manager);
+        let task_manager = Arc::clone(&task_manager);
+        let dashboard = Arc::clone(&dashboard);
+        let metrics_tx = metrics_tx.clone();
+        let config = config.clone();
+        let aggregator = Arc::clone(&aggregator);
+        async move {
+            let mut collector =
+                wezterm_parallel::metrics::collector::MetricsCollector::new(config.clone());
+            let mut registered: HashMap<String, u32> = HashMap::new();
+            let mut sample_interval = tokio::time::interval(period);
+            let mut cleanup_interval =
+                tokio::time::interval(std::time::Duration::from_secs(3600));
+
+            loop {
+                tokio::select! {
+                    _ = sample_interval.tick() => {
+                        let snapshot = ManagerSnapshot {
+                            workspaces: workspace_manager.list_workspaces().await,
+                            processes: match workspace_manager.process_manager() {
+                                Some(process_manager) => process_manager.list_processes().await,
+                                None => Vec::new(),
+                            },
+                            finished_tasks: task_manager
+                                .list_tasks(None)
+                                .await
+                                .into_iter()
+                                .filter(|t| matches!(t.status, TaskStatus::Completed | TaskStatus::Failed))
+                                .collect(),
+                        };
+
+                        // Only processes with a PID can be sampled; restarts change it
+                        let sampleable: HashMap<String, u32> = snapshot
+                            .processes
+                            .iter()
+                            .filter_map(|p| Some((p.id.clone(), p.pid?)))
+                            .collect();
+                        for process_id in registered.keys().filter(|id| !sampleable.contains_key(*id)) {
+                            collector.unregister_process(process_id);
+                        }
+                        for process in &snapshot.processes {
+                            match process.pid {
+                                Some(pid) if registered.get(&process.id) != Some(&pid) => {
+                                    collector.register_process(CollectedProcess {
+                                        process_id: process.id.clone(),
+                                        workspace: process.workspace.clone(),
+                                        pid,
+                                        command_args: Vec::new(),
+                                    });
+                                }
+                                _ => {}
+                            }
+                        }
+                        registered = sampleable;
+
+                        let system = collector.collect_system_metrics().unwrap_or_default();
+                        let sampled = collector.collect_process_metrics().unwrap_or_default();
+                        let sample = HistorySample {
+                            timestamp: system.timestamp,
+                            cpu_usage: system.cpu_usage,
+                            memory_usage: system.memory_usage,
+                            tasks_completed: snapshot
+                                .finished_tasks
+                                .iter()
+                                .filter(|t| t.status == TaskStatus::Completed)
+                                .count() as u64,
+                        };
+                        let framework = aggregator.aggregate(system, sampled, &snapshot).await;
+
+                        dashboard.update_metrics(framework.clone()).await;
+                        // A full channel means the dashboard has not caught up; the next update replaces this one
+                        let _ = metrics_tx.try_send(MetricsUpdate::full(framework.clone()));
+
+                        if let Some(ref storage) = storage {
+                            let recorded = match storage.record_sample(sample).await {
+                                Ok(()) => storage.save_framework_metrics(&framework).await,
+                                Err(e) => Err(e),
+                            };
+                            if let Err(e) = recorded {
+                                let sample_error_context = LogContext::new("system", "metrics_history_error");
+                                log_warn!(sample_error_context, "Failed to record metrics history: {}", e);
+                                continue;
+                            }
+                        }
+                        task.ran();
                     }
-                }
-                Err(e) => {
-                    let task_error_context = LogContext::new("ipc", "task_create_error")
-                        .with_metadata("command", serde_json::json!(command));
-                    log_error!(
-                        task_error_context,
-                        "Failed to create task '{}': {:?}",
-                        command,
-                        e
-                    );
-                    Message::StatusUpdate {
-                        process_id: "task_manager".to_string(),
-                        status: format!("Failed to create task '{command}': {e:?}"),
+                    _ = cleanup_interval.tick() => {
+                        aggregator.cleanup_old_data().await;
+                        if let Some(ref storage) = storage {
+                            if let Err(e) = storage.cleanup_old_files(config.retention_hours).await {
+                                let cleanup_error_context = LogContext::new("system", "metrics_history_error");
+                                log_warn!(cleanup_error_context, "Failed to prune metrics history: {}", e);
+                            }
+                        }
                     }
                 }
             }
         }
-        Message::TemplateList => {
-            let template_list_context = LogContext::new("ipc", "template_list_request");
-            log_info!(template_list_context, "Listing available templates");
-            let engine = template_engine.lock().await;
-            let templates = engine.list_templates();
-            let template_infos: Vec<TemplateInfo> = templates
-                .iter()
-                .map(|t| TemplateInfo {
-                    name: t.name.clone(),
-                    description: t.description.clone(),
-                    author: "System".to_string(),
-                    version: "1.0".to_string(),
-                    created_at: chrono::Utc::now().to_rfc3339(),
-                    layout_type: format!("{:?}", t.layout.layout_type),
-                    pane_count: t.layout.pane_sizes.len() as u32,
-                    auto_start_processes: !t.default_commands.is_empty(),
-                })
-                .collect();
+    })
+}
+
+/// Shared state handed to every IPC connection
+#[derive(Clone)]
+struct IpcServices {
+    pipeline: Arc<Pipeline>,
+    perf_manager: Arc<PerformanceManager>,
+}
+
+/// How long a client turned away at the connection limit has to send its request
+const REJECT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+
+async fn handle_client(
+    accepted: ipc::transport::Accepted,
+    services: IpcServices,
+    limits: IpcLimits,
+    _permit: OwnedSemaphorePermit,
+) {
+    let mut chunk = [0; 4096];
+    let mut frames = FrameDecoder::new(limits.max_message_size);
+    let connection_id = services.pipeline.open_connection();
+    let (mut stream, peer_uid) = (accepted.stream, accepted.peer_uid);
 
-            Message::TemplateListResponse {
-                templates: template_infos,
+    'connection: loop {
+        match stream.read(&mut chunk).await {
+            Ok(0) => {
+                let disconnect_context = LogContext::new("ipc", "client_disconnect");
+                log_info!(disconnect_context, "Client disconnected");
+                break;
             }
-        }
-        Message::TemplateGet { name } => {
-            let template_get_context =
-                LogContext::new("ipc", "template_get_request").with_entity_id(&name);
-            log_info!(template_get_context, "Getting template: {}", name);
-            let engine = template_engine.lock().await;
-            if let Some(template) = engine.get_template(&name) {
-                match serde_json::to_string(template) {
-                    Ok(content) => Message::TemplateGetResponse {
-                        template: Some(content),
-                    },
-                    Err(e) => {
-                        let serialize_error_context =
-                            LogContext::new("ipc", "template_serialize_error")
-                                .with_entity_id(&name);
-                        log_error!(
-                            serialize_error_context,
-                            "Failed to serialize template: {}",
-                            e
-                        );
-                        Message::TemplateGetResponse { template: None }
-                    }
-                }
-            } else {
-                Message::TemplateGetResponse { template: None }
+            Ok(n) => frames.push(&chunk[..n]),
+            Err(e) => {
+                let read_error_context = LogContext::new("ipc", "stream_read_error");
+                log_error!(read_error_context, "Failed to read from stream: {}", e);
+                break;
             }
         }
-        Message::TemplateCreate { name, content } => {
-            let template_create_context =
-                LogContext::new("ipc", "template_create_request").with_entity_id(&name);
-            log_info!(template_create_context, "Creating template: {}", name);
-
-            match serde_json::from_str::<wezterm_parallel::room::template::WorkspaceTemplate>(
-                &content,
-            ) {
-                Ok(template) => {
-                    let mut engine = template_engine.lock().await;
-                    engine.register_template(template);
-                    let template_success_context =
-                        LogContext::new("ipc", "template_create_success").with_entity_id(&name);
-                    log_info!(
-                        template_success_context,
-                        "Template '{}' created successfully",
-                        name
+
+        loop {
+            let frame = match frames.next_frame() {
+                Ok(Some(frame)) => frame,
+                Ok(None) => break,
+                Err(error) => {
+                    // The rest of the oversized message cannot be told apart from
+                    // the next one, so the connection is closed
+                    let size_context = LogContext::new("ipc", "message_too_large").with_metadata(
+                        "max_message_size",
+                        serde_json::json!(limits.max_message_size),
                     );
-                    Message::TemplateCreateResponse {
-                        success: true,
-                        error: None,
-                    }
+                    log_warn!(size_context, "{}", error.message);
+                    let _ = write_reply(&mut stream, None, Err(error)).await;
+                    break 'connection;
                 }
-                Err(e) => {
-                    let parse_error_context =
-                        LogContext::new("ipc", "template_parse_error").with_entity_id(&name);
-                    log_error!(parse_error_context, "Failed to parse template JSON: {}", e);
-                    Message::TemplateCreateResponse {
-                        success: false,
-                        error: Some(format!("Invalid template format: {e}")),
-                    }
+            };
+
+            // Accept both request envelopes and bare legacy messages
+            let (request_id, message, token, confirm, streaming) = match Incoming::parse(&frame) {
+                Ok(Incoming::Envelope(request)) => (
+                    Some(request.request_id),
+                    request.message,
+                    request.token,
+                    request.confirm,
+                    request.stream,
+                ),
+                Ok(Incoming::Legacy(message)) => (None, message, None, None, false),
+                Err(failure) => {
+                    let parse_error_context = LogContext::new("ipc", "message_parse_error");
+                    log_warn!(parse_error_context, "{}", failure.error.message);
+                    let _ = write_reply(&mut stream, failure.request_id, Err(failure.error)).await;
+                    continue;
                 }
+            };
+
+            // Auth, logging, validation, confirmation, rate limiting and metrics run in the pipeline
+            let mut request = RequestContext::new(connection_id)
+                .with_peer_uid(peer_uid)
+                .with_request_id(request_id)
+                .with_token(token)
+                .with_confirmation(confirm);
+            let result = services.pipeline.dispatch(&mut request, message).await;
+
+            // パフォーマンス統計を更新
+            services.perf_manager.update_cpu_usage(20.0); // リクエスト処理によるCPU使用量
+
+            // Send response, in chunks when the client asked for a streamed reply
+            let sent = match request.request_id {
+                Some(request_id) if streaming => {
+                    write_stream(&mut stream, request_id, result, limits.stream_chunk_bytes).await
+                }
+                request_id => write_reply(&mut stream, request_id, result).await,
+            };
+            if let Err(e) = sent {
+                let send_error_context = LogContext::new("ipc", "response_send_error");
+                log_error!(send_error_context, "Failed to send response: {}", e);
+                break 'connection;
             }
         }
-        Message::TemplateDelete { name: _ } => {
-            // TODO: Implement template deletion
-            Message::TemplateDeleteResponse {
-                success: false,
-                error: Some("Template deletion not yet implemented".to_string()),
+    }
+
+    services.pipeline.close_connection(connection_id);
+}
+
+/// Build the IPC request pipeline: (recorder →) auth → (audit →) logging → validation → confirmation → rate limit → metrics,
+/// then one handler per message family
+#[allow(clippy::too_many_arguments)]
+fn build_ipc_pipeline(
+    workspace_manager: Arc<WorkspaceManager>,
+    task_manager: Arc<TaskManager>,
+    template_engine: Arc<tokio::sync::Mutex<wezterm_parallel::room::template::TemplateEngine>>,
+    maintenance: Arc<MaintenanceController>,
+    focus: Arc<FocusController>,
+    telemetry: Arc<Telemetry>,
+    authenticator: Arc<Authenticator>,
+    limits: IpcLimits,
+    protocol_metrics: Arc<ProtocolMetrics>,
+    file_sync_manager: Arc<tokio::sync::Mutex<FileSyncManager>>,
+    file_sync: Option<Arc<LazySubsystem>>,
+    task_board: Option<Arc<TaskBoardManager>>,
+    shed_log: Arc<ShedLog>,
+    session_recorder: Option<Arc<SessionRecorder>>,
+    federation: Arc<Federation>,
+    audit_journal: Option<Arc<AuditJournal>>,
+    github_sync: Arc<GithubSync>,
+    monitoring: Arc<MonitoringManager>,
+    shutdown_requested: Arc<tokio::sync::Notify>,
+) -> Pipeline {
+    // Recorded first so requests rejected by any later stage are recorded too
+    let mut pipeline = Pipeline::new();
+    if let Some(recorder) = session_recorder {
+        pipeline = pipeline.with_middleware(RecordingMiddleware::new(recorder));
+    }
+    pipeline = pipeline.with_middleware(AuthMiddleware::new(Arc::clone(&authenticator)));
+    // Audited right after authentication, so it knows the client and sees
+    // rejections by every later stage
+    if let Some(journal) = &audit_journal {
+        pipeline = pipeline.with_middleware(AuditMiddleware::new(Arc::clone(journal)));
+    }
+    pipeline
+        .with_middleware(LoggingMiddleware)
+        .with_middleware(ValidationMiddleware::new().with_maintenance(Arc::clone(&maintenance)))
+        .with_middleware(
+            ConfirmationMiddleware::new(authenticator)
+                .with_workspace_manager(Arc::clone(&workspace_manager)),
+        )
+        .with_middleware(RateLimitMiddleware::new(limits).with_shed_log(Arc::clone(&shed_log)))
+        .with_middleware(
+            MetricsMiddleware::new()
+                .with_protocol_metrics(protocol_metrics)
+                .with_telemetry(Arc::clone(&telemetry)),
+        )
+        .with_handler(SystemHandler {
+            started_at: STARTED_AT.get().copied().unwrap_or_else(Instant::now),
+            workspace_manager: Arc::clone(&workspace_manager),
+            task_manager: Arc::clone(&task_manager),
+            template_engine: Arc::clone(&template_engine),
+            maintenance,
+            focus,
+            telemetry,
+            shed_log,
+            audit_journal,
+            monitoring,
+            shutdown_requested,
+        })
+        .with_handler(WorkspaceHandler {
+            workspace_manager: Arc::clone(&workspace_manager),
+            task_manager: Arc::clone(&task_manager),
+        })
+        .with_handler(ProcessHandler {
+            workspace_manager: Arc::clone(&workspace_manager),
+        })
+        .with_handler(TaskHandler {
+            workspace_manager: Arc::clone(&workspace_manager),
+            task_manager: Arc::clone(&task_manager),
+            github_sync,
+        })
+        .with_handler(SnapshotHandler {
+            workspace_manager,
+            task_manager: Arc::clone(&task_manager),
+        })
+        .with_handler(SessionHandler { task_manager })
+        .with_handler(BoardHandler { task_board })
+        .with_handler(TemplateHandler { template_engine })
+        .with_handler(SyncHandler {
+            file_sync_manager,
+            file_sync,
+        })
+        .with_handler(FederationHandler { federation })
+}
+
+/// Answer the first request of a client over the connection limit, then close
+async fn reject_client(mut stream: BoxedIpcStream, limits: IpcLimits, error: IpcError) {
+    let mut chunk = [0; 4096];
+    let mut frames = FrameDecoder::new(limits.max_message_size);
+
+    let first_frame = tokio::time::timeout(REJECT_READ_TIMEOUT, async {
+        loop {
+            match stream.read(&mut chunk).await {
+                Ok(0) | Err(_) => return None,
+                Ok(n) => frames.push(&chunk[..n]),
+            }
+            match frames.next_frame() {
+                Ok(Some(frame)) => return Some(frame),
+                Ok(None) => continue,
+                Err(_) => return None,
             }
         }
-        other => {
-            let unhandled_context = LogContext::new("ipc", "unhandled_message")
-                .with_metadata("message_type", serde_json::json!(format!("{:?}", other)));
-            log_warn!(unhandled_context, "Unhandled message type: {:?}", other);
-            Message::StatusUpdate {
-                process_id: "system".to_string(),
-                status: "Unknown message type".to_string(),
+    })
+    .await;
+
+    let request_id = match first_frame {
+        Ok(Some(frame)) => match Incoming::parse(&frame) {
+            Ok(Incoming::Envelope(request)) => Some(request.request_id),
+            Err(failure) => failure.request_id,
+            Ok(Incoming::Legacy(_)) => None,
+        },
+        _ => None,
+    };
+    let _ = write_reply(&mut stream, request_id, Err(error)).await;
+}
+
+/// Encode and send the reply to one request
+async fn write_reply(
+    stream: &mut BoxedIpcStream,
+    request_id: Option<String>,
+    result: Result<Message, IpcError>,
+) -> std::io::Result<()> {
+    match ipc::encode_reply(request_id, result) {
+        Ok(reply) => stream.write_all(&reply).await,
+        Err(e) => {
+            let encode_error_context = LogContext::new("ipc", "response_encode_error");
+            log_error!(encode_error_context, "Failed to encode response: {}", e);
+            Ok(())
+        }
+    }
+}
+
+/// Send the reply to a streaming request as ordered chunk frames
+///
+/// A client that stops reading part-way closes its socket, which ends the
+/// stream with a write error.
+async fn write_stream(
+    stream: &mut BoxedIpcStream,
+    request_id: String,
+    result: Result<Message, IpcError>,
+    max_chunk_bytes: usize,
+) -> std::io::Result<()> {
+    let frames = match ipc::encode_stream(request_id, result, max_chunk_bytes) {
+        Ok(frames) => frames,
+        Err(e) => {
+            let encode_error_context = LogContext::new("ipc", "response_encode_error");
+            log_error!(encode_error_context, "Failed to encode response: {}", e);
+            return Ok(());
+        }
+    };
+
+    let total = frames.len();
+    for (index, frame) in frames.into_iter().enumerate() {
+        if let Err(e) = stream.write_all(&frame).await {
+            let cancel_context = LogContext::new("ipc", "stream_cancelled")
+                .with_metadata("chunks_sent", serde_json::json!(index))
+                .with_metadata("chunks_total", serde_json::json!(total));
+            log_debug!(cancel_context, "Client stopped reading streamed reply");
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+/// Mark a pending handoff as ready once this instance is serving IPC on
+/// `socket`
+fn complete_handoff(handoff_path: &Path, socket: &Path) {
+    let handoff_context = LogContext::new("system", "upgrade_handoff");
+
+    match UpgradeHandoff::load(handoff_path) {
+        Ok(mut handoff) if handoff.state == HandoffState::Pending => {
+            handoff.successor_pid = Some(std::process::id());
+            handoff.successor_socket = Some(socket.to_path_buf());
+            match handoff.transition(handoff_path, HandoffState::Ready, None) {
+                Ok(()) => log_info!(
+                    handoff_context,
+                    "Took over from previous instance (pid {}, version {})",
+                    handoff.previous_pid,
+                    handoff.previous_version
+                ),
+                Err(e) => log_error!(handoff_context, "Failed to update handoff file: {}", e),
+            }
+        }
+        Ok(handoff) => log_info!(
+            handoff_context,
+            "Started from handoff in state {:?}",
+            handoff.state
+        ),
+        Err(e) => log_warn!(handoff_context, "Failed to read handoff file: {}", e),
+    }
+}
+
+/// Take the instance lock before anything is bound
+///
+/// With `takeover`, a running daemon is asked for its session snapshot and
+/// to shut down; the snapshot is returned so it can be restored once this
+/// instance is serving. A successor started by `self-upgrade` takes the lock
+/// later, in [`publish_after_handoff`], since its predecessor holds it until
+/// the handoff.
+async fn acquire_instance_lock(
+    takeover: bool,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    if UpgradeHandoff::path_from_env().is_some() {
+        return Ok(None);
+    }
+    let lock_path = InstanceLock::default_path();
+    let info = InstanceInfo::current(&ipc::default_socket_path());
+
+    let migrated_session = match InstanceLock::acquire(&lock_path, &info) {
+        Ok(lock) => {
+            let _ = INSTANCE_LOCK.set(lock);
+            None
+        }
+        Err(InstanceError::AlreadyRunning { holder, .. }) if takeover => {
+            let snapshot = request_takeover(holder.as_ref()).await?;
+            let lock = InstanceLock::acquire_within(&lock_path, &info, TAKEOVER_TIMEOUT)
+                .await
+                .map_err(|e| format!("Previous instance did not shut down: {e}"))?;
+            let _ = INSTANCE_LOCK.set(lock);
+            Some(snapshot)
+        }
+        // `main` reports errors with `Debug`; keep the message readable
+        Err(e) => return Err(e.to_string().into()),
+    };
+
+    let lock_context = LogContext::new("system", "instance_lock")
+        .with_metadata("path", serde_json::json!(lock_path));
+    log_info!(
+        lock_context,
+        "Holding instance lock {}",
+        lock_path.display()
+    );
+    Ok(migrated_session)
+}
+
+/// Wait for the previous instance to release the instance lock, then move
+/// the socket bound at `staged` to `socket_path`
+///
+/// Until then the previous instance may still be serving at `socket_path`,
+/// so the path is left alone if the lock cannot be taken.
+fn publish_after_handoff(staged: PathBuf, socket_path: PathBuf) {
+    tokio::spawn(async move {
+        let lock_path = InstanceLock::default_path();
+        let info = InstanceInfo::current(&socket_path);
+        let lock_context = LogContext::new("system", "instance_lock")
+            .with_metadata("path", serde_json::json!(lock_path));
+        match InstanceLock::acquire_within(&lock_path, &info, HANDOFF_LOCK_TIMEOUT).await {
+            Ok(lock) => {
+                let _ = INSTANCE_LOCK.set(lock);
+                log_info!(
+                    lock_context,
+                    "Holding instance lock {}",
+                    lock_path.display()
+                );
             }
+            Err(e) => {
+                log_error!(
+                    lock_context,
+                    "Previous instance kept the instance lock, still serving at {}: {}",
+                    staged.display(),
+                    e
+                );
+                return;
+            }
+        }
+        if let Err(e) = ipc::transport::publish(&staged, &socket_path) {
+            let publish_context = LogContext::new("system", "ipc_publish_error");
+            log_error!(
+                publish_context,
+                "Failed to move IPC socket to {}: {}",
+                socket_path.display(),
+                e
+            );
+        }
+    });
+}
+
+/// Ask the running daemon to hand over its session and shut down
+async fn request_takeover(
+    holder: Option<&InstanceInfo>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let holder_pid = holder.map(|holder| holder.pid.to_string());
+    let holder_pid = holder_pid.as_deref().unwrap_or("unknown");
+    let takeover_context = LogContext::new("system", "takeover_request")
+        .with_metadata("previous_pid", serde_json::json!(holder_pid));
+    log_info!(
+        takeover_context,
+        "Taking over from running instance (pid {})",
+        holder_pid
+    );
+
+    let request = Message::Takeover {
+        pid: std::process::id(),
+    };
+    match IpcClient::default().call(request).await {
+        Ok(Message::TakeoverResponse { snapshot }) => Ok(snapshot),
+        Ok(other) => Err(format!("Unexpected response: {other:?}").into()),
+        Err(e) => Err(format!(
+            "Running instance (pid {holder_pid}) refused the takeover: {e}; stop it manually"
+        )
+        .into()),
+    }
+}
+
+/// Recreate the session handed over by the previous instance
+async fn restore_migrated_session(
+    content: &str,
+    workspace_manager: &WorkspaceManager,
+    task_manager: &TaskManager,
+) {
+    let restore_context = LogContext::new("system", "takeover_restore");
+    let snapshot = match SessionSnapshot::parse(content) {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            log_error!(
+                restore_context,
+                "Invalid session snapshot from previous instance: {}",
+                e
+            );
+            return;
         }
+    };
+    let report = snapshot
+        .restore(workspace_manager, task_manager, false)
+        .await;
+    log_info!(
+        restore_context
+            .with_metadata(
+                "workspaces",
+                serde_json::json!(report.workspaces_created.len())
+            )
+            .with_metadata("tasks", serde_json::json!(report.tasks_restored)),
+        "Took over {} workspaces, {} processes and {} tasks",
+        report.workspaces_created.len(),
+        report.processes_started,
+        report.tasks_restored
+    );
+    for warning in &report.warnings {
+        let warning_context = LogContext::new("system", "takeover_restore_warning");
+        log_warn!(warning_context, "{}", warning);
     }
 }
@@ -0,0 +1,307 @@
+// WezTerm Multi-Process Development Framework - Maintenance Mode
+// Stops accepting new work while in-flight tasks and processes drain
+
+use crate::dashboard::{DashboardMessage, StatusChange};
+use crate::log_info;
+use crate::logging::LogContext;
+use crate::room::WorkspaceManager;
+use crate::task::TaskManager;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::RwLock;
+
+/// Component name used in status announcements
+pub const MAINTENANCE_COMPONENT: &str = "maintenance";
+
+/// Maintenance state as exposed to clients
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaintenanceStatus {
+    /// Maintenance mode enabled
+    pub enabled: bool,
+
+    /// Operator supplied reason
+    pub reason: Option<String>,
+
+    /// Time maintenance mode was enabled
+    pub since: Option<u64>,
+
+    /// Tasks still executing
+    pub running_tasks: usize,
+
+    /// Tasks still waiting in the queue
+    pub queued_tasks: usize,
+
+    /// Managed processes still attached to workspaces
+    pub active_processes: usize,
+
+    /// True once all in-flight work has finished
+    pub drained: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+struct MaintenanceState {
+    enabled: bool,
+    reason: Option<String>,
+    since: Option<u64>,
+    drained_announced: bool,
+}
+
+/// Maintenance mode controller shared by the IPC and dashboard servers
+pub struct MaintenanceController {
+    state: RwLock<MaintenanceState>,
+    task_manager: Arc<TaskManager>,
+    workspace_manager: Arc<WorkspaceManager>,
+    broadcast_tx: Option<tokio::sync::broadcast::Sender<DashboardMessage>>,
+}
+
+impl MaintenanceController {
+    /// Create a controller with maintenance mode disabled
+    pub fn new(task_manager: Arc<TaskManager>, workspace_manager: Arc<WorkspaceManager>) -> Self {
+        Self {
+            state: RwLock::new(MaintenanceState::default()),
+            task_manager,
+            workspace_manager,
+            broadcast_tx: None,
+        }
+    }
+
+    /// Announce state changes on the dashboard broadcast channel
+    pub fn with_broadcaster(
+        mut self,
+        broadcast_tx: tokio::sync::broadcast::Sender<DashboardMessage>,
+    ) -> Self {
+        self.broadcast_tx = Some(broadcast_tx);
+        self
+    }
+
+    /// Check whether new work is currently rejected
+    pub async fn is_active(&self) -> bool {
+        self.state.read().await.enabled
+    }
+
+    /// Enable maintenance mode; returns false if it was already enabled
+    pub async fn enable(&self, reason: Option<String>) -> bool {
+        {
+            let mut state = self.state.write().await;
+            if state.enabled {
+                return false;
+            }
+            *state = MaintenanceState {
+                enabled: true,
+                reason: reason.clone(),
+                since: Some(now_secs()),
+                drained_announced: false,
+            };
+        }
+
+        let context = LogContext::new("system", "maintenance_enabled")
+            .with_metadata("reason", serde_json::json!(reason));
+        log_info!(context, "Maintenance mode enabled, draining in-flight work");
+
+        self.announce("running", "draining", reason);
+        true
+    }
+
+    /// Disable maintenance mode; returns false if it was not enabled
+    pub async fn disable(&self) -> bool {
+        let previous = {
+            let mut state = self.state.write().await;
+            if !state.enabled {
+                return false;
+            }
+            let previous = if state.drained_announced {
+                "drained"
+            } else {
+                "draining"
+            };
+            *state = MaintenanceState::default();
+            previous
+        };
+
+        let context = LogContext::new("system", "maintenance_disabled");
+        log_info!(context, "Maintenance mode disabled, accepting new work");
+
+        self.announce(previous, "running", None);
+        true
+    }
+
+    /// Error message returned for rejected operations, if maintenance is active
+    pub async fn rejection(&self, operation: &str) -> Option<String> {
+        let state = self.state.read().await;
+        state.enabled.then(|| match &state.reason {
+            Some(reason) => {
+                format!("Maintenance mode active ({reason}): {operation} is not accepted")
+            }
+            None => format!("Maintenance mode active: {operation} is not accepted"),
+        })
+    }
+
+    /// Current state with drain progress
+    pub async fn status(&self) -> MaintenanceStatus {
+        let stats = self.task_manager.get_stats().await;
+        let active_processes = self.workspace_manager.get_total_process_count().await;
+        let state = self.state.read().await.clone();
+
+        MaintenanceStatus {
+            enabled: state.enabled,
+            reason: state.reason,
+            since: state.since,
+            running_tasks: stats.active_tasks,
+            queued_tasks: stats.queued_tasks,
+            active_processes,
+            drained: state.enabled && stats.active_tasks == 0 && stats.queued_tasks == 0,
+        }
+    }
+
+    /// Check drain progress once and announce completion the first time it is reached
+    pub async fn check_drain(&self) -> MaintenanceStatus {
+        let status = self.status().await;
+
+        if status.drained {
+            let newly_drained = {
+                let mut state = self.state.write().await;
+                let newly = state.enabled && !state.drained_announced;
+                state.drained_announced = true;
+                newly
+            };
+
+            if newly_drained {
+                let context = LogContext::new("system", "maintenance_drained");
+                log_info!(context, "All in-flight work drained");
+                self.announce("draining", "drained", status.reason.clone());
+            }
+        }
+
+        status
+    }
+
+    /// Periodically check drain progress while maintenance mode is enabled
+    pub fn spawn_drain_monitor(
+        self: &Arc<Self>,
+        interval: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let controller = Arc::clone(self);
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if controller.is_active().await {
+                    controller.check_drain().await;
+                }
+            }
+        })
+    }
+
+    fn announce(&self, previous_status: &str, new_status: &str, reason: Option<String>) {
+        if let Some(ref tx) = self.broadcast_tx {
+            let _ = tx.send(DashboardMessage::StatusChange(StatusChange {
+                component: MAINTENANCE_COMPONENT.to_string(),
+                previous_status: previous_status.to_string(),
+                new_status: new_status.to_string(),
+                reason,
+                timestamp: now_secs(),
+            }));
+        }
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{Task, TaskCategory, TaskConfig};
+
+    fn create_controller() -> (MaintenanceController, tempfile::TempDir) {
+        let dir = tempfile::TempDir::new().unwrap();
+        let workspace_manager =
+            Arc::new(WorkspaceManager::new(Some(dir.path().join("workspaces.json"))).unwrap());
+        let task_manager = Arc::new(TaskManager::new(TaskConfig {
+            persistence_enabled: false,
+            ..Default::default()
+        }));
+        (
+            MaintenanceController::new(task_manager, workspace_manager),
+            dir,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_enable_disable() {
+        let (controller, _dir) = create_controller();
+        assert!(!controller.is_active().await);
+        assert!(controller.rejection("task queue").await.is_none());
+
+        assert!(controller.enable(Some("upgrade".to_string())).await);
+        assert!(!controller.enable(None).await);
+        assert!(controller.is_active().await);
+
+        let rejection = controller.rejection("task queue").await.unwrap();
+        assert!(rejection.contains("upgrade"));
+
+        assert!(controller.disable().await);
+        assert!(!controller.disable().await);
+        assert!(!controller.is_active().await);
+    }
+
+    #[tokio::test]
+    async fn test_state_changes_are_broadcast() {
+        let (controller, _dir) = create_controller();
+        let (tx, mut rx) = tokio::sync::broadcast::channel(16);
+        let controller = controller.with_broadcaster(tx);
+
+        controller.enable(None).await;
+        match rx.recv().await.unwrap() {
+            DashboardMessage::StatusChange(change) => {
+                assert_eq!(change.component, MAINTENANCE_COMPONENT);
+                assert_eq!(change.new_status, "draining");
+            }
+            other => panic!("Unexpected message: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_drain_completion_announced_once() {
+        let (controller, _dir) = create_controller();
+        let (tx, mut rx) = tokio::sync::broadcast::channel(16);
+        let controller = controller.with_broadcaster(tx);
+
+        controller.enable(None).await;
+        let _ = rx.recv().await;
+
+        let status = controller.check_drain().await;
+        assert!(status.enabled);
+        assert!(status.drained);
+
+        controller.check_drain().await;
+
+        match rx.try_recv().unwrap() {
+            DashboardMessage::StatusChange(change) => assert_eq!(change.new_status, "drained"),
+            other => panic!("Unexpected message: {other:?}"),
+        }
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_status_reports_queued_work() {
+        let (controller, _dir) = create_controller();
+        controller.enable(None).await;
+
+        controller
+            .task_manager
+            .create_task(Task::new("Pending".to_string(), TaskCategory::Development))
+            .await
+            .unwrap();
+
+        let status = controller.status().await;
+        assert_eq!(status.queued_tasks, 1);
+        assert!(!status.drained);
+    }
+}
@@ -1,9 +1,12 @@
 // Metrics aggregation and analysis for dashboard display
 
 use super::{
-    FrameworkMetrics, MetricsConfig, PerformanceSummary, ProcessMetrics, SystemMetrics,
-    WorkspaceMetrics,
+    FrameworkMetrics, MetricsConfig, PerformanceSummary, ProcessMetrics, ProcessStatus,
+    SystemMetrics, WorkspaceMetrics,
 };
+use crate::clock::Stopwatch;
+use crate::room::state::{self, ProcessInfo};
+use crate::task::{Task, TaskStatus};
 use log::{debug, info};
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -31,6 +34,22 @@ pub struct MetricsAggregator {
 
     /// Alert thresholds
     alert_thresholds: AlertThresholds,
+
+    /// Since when the framework has been aggregated
+    started: Stopwatch,
+}
+
+/// State of the framework read from the managers at one collection
+#[derive(Debug, Clone, Default)]
+pub struct ManagerSnapshot {
+    /// Names of all workspaces, including ones without processes
+    pub workspaces: Vec<String>,
+
+    /// Managed processes, as listed by the process manager
+    pub processes: Vec<ProcessInfo>,
+
+    /// Completed and failed tasks
+    pub finished_tasks: Vec<Task>,
 }
 
 /// Performance tracking for calculating statistics
@@ -46,7 +65,7 @@ struct PerformanceTracker {
     total_errors: u64,
 
     /// Start time for rate calculations
-    start_time: SystemTime,
+    start_time: Stopwatch,
 
     /// Last reset time
     last_reset: SystemTime,
@@ -98,9 +117,77 @@ impl MetricsAggregator {
             framework_metrics: RwLock::new(FrameworkMetrics::new()),
             performance_tracker: RwLock::new(PerformanceTracker::new()),
             alert_thresholds: AlertThresholds::default(),
+            started: Stopwatch::start(),
         }
     }
 
+    /// Rebuild the framework metrics from the managers' current state
+    ///
+    /// `sampled` holds the CPU and memory readings the collector took; managed
+    /// processes without one (no PID yet, or already gone) are still counted
+    /// with their manager status. Workspaces and processes that are no longer
+    /// in `snapshot` are dropped.
+    pub async fn aggregate(
+        &self,
+        system: SystemMetrics,
+        sampled: Vec<ProcessMetrics>,
+        snapshot: &ManagerSnapshot,
+    ) -> FrameworkMetrics {
+        self.add_system_metrics(system).await;
+
+        let mut sampled: HashMap<String, ProcessMetrics> = sampled
+            .into_iter()
+            .map(|metrics| (metrics.process_id.clone(), metrics))
+            .collect();
+        let processes: Vec<ProcessMetrics> = snapshot
+            .processes
+            .iter()
+            .map(|info| managed_process_metrics(info, sampled.remove(&info.id)))
+            .collect();
+
+        {
+            let mut process_history = self.process_history.write().await;
+            for metrics in &processes {
+                let history = process_history
+                    .entry(metrics.process_id.clone())
+                    .or_default();
+                history.push(metrics.clone());
+                if history.len() > self.config.max_history_points {
+                    history.drain(0..history.len() - self.config.max_history_points);
+                }
+            }
+        }
+
+        let mut by_workspace: HashMap<String, Vec<ProcessMetrics>> = snapshot
+            .workspaces
+            .iter()
+            .map(|name| (name.clone(), Vec::new()))
+            .collect();
+        for metrics in processes {
+            by_workspace
+                .entry(metrics.workspace.clone())
+                .or_default()
+                .push(metrics);
+        }
+        let workspaces: HashMap<String, WorkspaceMetrics> = by_workspace
+            .into_iter()
+            .map(|(name, processes)| {
+                let mut workspace = WorkspaceMetrics::new(name.clone());
+                workspace.update_from_processes(processes);
+                (name, workspace)
+            })
+            .collect();
+        *self.workspace_metrics.write().await = workspaces.clone();
+
+        let mut framework = self.framework_metrics.write().await;
+        framework.workspaces.clear();
+        framework.update_from_workspaces(workspaces.into_values().collect());
+        framework.framework_uptime = self.started.elapsed().as_secs();
+        framework.performance =
+            task_performance(&snapshot.finished_tasks, framework.framework_uptime);
+        framework.clone()
+    }
+
     /// Add system metrics to aggregation
     pub async fn add_system_metrics(&self, metrics: SystemMetrics) {
         debug!("Adding system metrics to aggregation");
@@ -430,10 +517,7 @@ impl MetricsAggregator {
 
     /// Get current timestamp
     fn current_timestamp() -> u64 {
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map(|d| d.as_secs())
-            .unwrap_or(0)
+        crate::clock::unix_secs()
     }
 
     /// Update configuration
@@ -468,20 +552,96 @@ impl MetricsAggregator {
     }
 }
 
+/// Metrics of a managed process, from its sampled readings when there are some
+fn managed_process_metrics(info: &ProcessInfo, sampled: Option<ProcessMetrics>) -> ProcessMetrics {
+    let mut metrics =
+        sampled.unwrap_or_else(|| ProcessMetrics::new(info.id.clone(), info.workspace.clone()));
+    metrics.workspace = info.workspace.clone();
+    metrics.status = match info.status {
+        state::ProcessStatus::Starting | state::ProcessStatus::Restarting => {
+            ProcessStatus::Starting
+        }
+        state::ProcessStatus::Running => ProcessStatus::Running,
+        state::ProcessStatus::Idle => ProcessStatus::Idle,
+        state::ProcessStatus::Busy => ProcessStatus::Busy,
+        state::ProcessStatus::Stopping => ProcessStatus::Stopping,
+        state::ProcessStatus::Stopped => ProcessStatus::Stopped,
+        state::ProcessStatus::Failed => ProcessStatus::Failed,
+        state::ProcessStatus::Unresponsive => ProcessStatus::Unresponsive,
+    };
+    metrics.uptime = info
+        .started_at
+        .elapsed()
+        .map_or(0, |uptime| uptime.as_secs());
+    metrics.last_activity = info
+        .last_heartbeat
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    metrics.error_count = info.restart_count;
+    if metrics.command_args.is_empty() {
+        metrics.command_args = vec![info.command.clone()];
+    }
+    metrics
+}
+
+/// Task outcomes as the framework's requests: a finished task is one request,
+/// a failed one an error, and its run time the response time
+fn task_performance(finished_tasks: &[Task], uptime: u64) -> PerformanceSummary {
+    let total = finished_tasks.len() as u64;
+    let errors = finished_tasks
+        .iter()
+        .filter(|task| task.status == TaskStatus::Failed)
+        .count() as u64;
+    let mut durations_ms: Vec<u64> = finished_tasks
+        .iter()
+        .filter_map(|task| task.actual_duration)
+        .map(|secs| secs * 1000)
+        .collect();
+    durations_ms.sort_unstable();
+    let percentile = |quantile: f64| {
+        let rank = (durations_ms.len() as f64 * quantile).ceil() as usize;
+        durations_ms
+            .get(rank.saturating_sub(1))
+            .copied()
+            .unwrap_or(0) as f64
+    };
+
+    PerformanceSummary {
+        avg_response_time: if durations_ms.is_empty() {
+            0.0
+        } else {
+            durations_ms.iter().sum::<u64>() as f64 / durations_ms.len() as f64
+        },
+        p95_response_time: percentile(0.95),
+        p99_response_time: percentile(0.99),
+        total_requests: total,
+        requests_per_second: if uptime > 0 {
+            total as f64 / uptime as f64
+        } else {
+            0.0
+        },
+        error_rate: if total > 0 {
+            errors as f64 / total as f64 * 100.0
+        } else {
+            0.0
+        },
+        total_errors: errors,
+    }
+}
+
 impl PerformanceTracker {
     fn new() -> Self {
-        let now = SystemTime::now();
         Self {
             response_times: Vec::new(),
             total_requests: 0,
             total_errors: 0,
-            start_time: now,
-            last_reset: now,
+            start_time: Stopwatch::start(),
+            last_reset: SystemTime::now(),
         }
     }
 
     fn calculate_summary(&self) -> PerformanceSummary {
-        let elapsed = self.start_time.elapsed().unwrap_or_default().as_secs_f64();
+        let elapsed = self.start_time.elapsed().as_secs_f64();
         let requests_per_second = if elapsed > 0.0 {
             self.total_requests as f64 / elapsed
         } else {
@@ -532,7 +692,7 @@ impl PerformanceTracker {
         self.response_times.clear();
         self.total_requests = 0;
         self.total_errors = 0;
-        self.start_time = SystemTime::now();
+        self.start_time = Stopwatch::start();
         self.last_reset = SystemTime::now();
     }
 }
@@ -604,4 +764,91 @@ mod tests {
         assert_eq!(summary.total_errors, 1);
         assert!(summary.avg_response_time > 0.0);
     }
+
+    fn managed(id: &str, workspace: &str, status: state::ProcessStatus) -> ProcessInfo {
+        ProcessInfo {
+            id: id.to_string(),
+            command: "claude-code".to_string(),
+            workspace: workspace.to_string(),
+            pane_id: None,
+            status,
+            pid: None,
+            started_at: SystemTime::now(),
+            last_heartbeat: SystemTime::now(),
+            restart_count: 0,
+            context: None,
+        }
+    }
+
+    fn finished(status: TaskStatus, secs: u64) -> Task {
+        let mut task = Task::new("build".to_string(), crate::task::TaskCategory::Development);
+        task.status = status;
+        task.actual_duration = Some(secs);
+        task
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_from_manager_snapshot() {
+        let aggregator = MetricsAggregator::new(MetricsConfig::default());
+        let mut sampled = ProcessMetrics::new("dev-1".to_string(), "dev".to_string());
+        sampled.cpu_usage = 40.0;
+        sampled.memory_usage = 1024;
+        let snapshot = ManagerSnapshot {
+            workspaces: vec!["dev".to_string(), "idle".to_string()],
+            processes: vec![
+                managed("dev-1", "dev", state::ProcessStatus::Running),
+                managed("dev-2", "dev", state::ProcessStatus::Failed),
+            ],
+            finished_tasks: vec![
+                finished(TaskStatus::Completed, 2),
+                finished(TaskStatus::Completed, 4),
+                finished(TaskStatus::Failed, 6),
+                finished(TaskStatus::Completed, 8),
+            ],
+        };
+
+        let framework = aggregator
+            .aggregate(SystemMetrics::new(), vec![sampled], &snapshot)
+            .await;
+        assert_eq!(framework.total_workspaces, 2);
+        assert_eq!(framework.total_processes, 2);
+        let dev = &framework.workspaces["dev"];
+        assert_eq!(dev.running_processes, 1);
+        assert_eq!(dev.failed_processes, 1);
+        assert_eq!(dev.total_memory_usage, 1024);
+        assert_eq!(dev.processes["dev-1"].cpu_usage, 40.0);
+        assert_eq!(framework.workspaces["idle"].total_processes, 0);
+
+        assert_eq!(framework.performance.total_requests, 4);
+        assert_eq!(framework.performance.total_errors, 1);
+        assert_eq!(framework.performance.error_rate, 25.0);
+        assert_eq!(framework.performance.avg_response_time, 5000.0);
+        assert_eq!(framework.performance.p95_response_time, 8000.0);
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_drops_removed_workspaces() {
+        let aggregator = MetricsAggregator::new(MetricsConfig::default());
+        let snapshot = ManagerSnapshot {
+            workspaces: vec!["dev".to_string()],
+            processes: vec![managed("dev-1", "dev", state::ProcessStatus::Running)],
+            ..Default::default()
+        };
+        aggregator
+            .aggregate(SystemMetrics::new(), Vec::new(), &snapshot)
+            .await;
+
+        let framework = aggregator
+            .aggregate(
+                SystemMetrics::new(),
+                Vec::new(),
+                &ManagerSnapshot::default(),
+            )
+            .await;
+        assert!(framework.workspaces.is_empty());
+        assert_eq!(framework.total_processes, 0);
+        assert_eq!(aggregator.get_all_workspace_metrics().await.len(), 0);
+        // History of the removed process is kept until it ages out
+        assert_eq!(aggregator.get_process_history("dev-1", None).await.len(), 1);
+    }
 }
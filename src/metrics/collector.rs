@@ -1,9 +1,10 @@
 // Metrics collection implementation for system and process monitoring
 
 use super::{MetricsConfig, NetworkIoStats, ProcessMetrics, ProcessStatus, SystemMetrics};
+use crate::clock::Stopwatch;
 use log::{debug, info, warn};
 use std::collections::HashMap;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::Duration;
 use sysinfo::{CpuExt, DiskExt, NetworkExt, ProcessExt, System, SystemExt};
 use tokio::time::{interval, Interval};
 
@@ -25,7 +26,7 @@ pub struct MetricsCollector {
     previous_network_stats: Option<NetworkIoStats>,
 
     /// Collection start time for uptime calculation
-    start_time: SystemTime,
+    start_time: Stopwatch,
 }
 
 /// Process information for metrics collection
@@ -51,7 +52,7 @@ impl MetricsCollector {
             interval,
             managed_processes: HashMap::new(),
             previous_network_stats: None,
-            start_time: SystemTime::now(),
+            start_time: Stopwatch::start(),
         }
     }
 
@@ -170,11 +171,7 @@ impl MetricsCollector {
         };
 
         // Process uptime calculation
-        let uptime = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs()
-            .saturating_sub(process.start_time());
+        let uptime = crate::clock::secs_since(process.start_time());
 
         // Thread and file descriptor counts
         let thread_count = 1; // sysinfo doesn't provide task count directly
@@ -299,6 +296,7 @@ impl MetricsCollector {
 
         #[cfg(not(unix))]
         {
+            let _ = pid;
             None
         }
     }
@@ -355,10 +353,7 @@ impl MetricsCollector {
 
     /// Get current timestamp
     fn current_timestamp() -> u64 {
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map(|d| d.as_secs())
-            .unwrap_or(0)
+        crate::clock::unix_secs()
     }
 
     /// Update collection configuration
@@ -378,7 +373,7 @@ impl MetricsCollector {
         CollectionStats {
             managed_processes_count: self.managed_processes.len(),
             collection_interval: self.config.collection_interval,
-            uptime: self.start_time.elapsed().unwrap_or_default().as_secs(),
+            uptime: self.start_time.elapsed().as_secs(),
             config: self.config.clone(),
         }
     }
@@ -453,6 +448,7 @@ mod tests {
             collect_network_metrics: true,
             retention_hours: 24,
             enable_profiling: false,
+            history_path: None,
         };
         let mut collector = MetricsCollector::new(config);
 
@@ -505,6 +501,7 @@ mod tests {
             collect_network_metrics: true,
             retention_hours: 24,
             enable_profiling: false,
+            history_path: None,
         };
         let mut collector = MetricsCollector::new(config);
 
@@ -641,6 +638,7 @@ mod tests {
             collect_network_metrics: true,
             retention_hours: 24,
             enable_profiling: false,
+            history_path: None,
         };
 
         let collector = MetricsCollector::new(config.clone());
@@ -3,11 +3,11 @@
 
 pub mod aggregator;
 pub mod collector;
+pub mod protocol;
 pub mod storage;
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
 
 /// System metrics snapshot
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -253,6 +253,7 @@ pub struct PerformanceSummary {
 
 /// Metrics collection configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct MetricsConfig {
     /// Enable metrics collection
     pub enabled: bool,
@@ -277,6 +278,9 @@ pub struct MetricsConfig {
 
     /// Enable performance profiling
     pub enable_profiling: bool,
+
+    /// Directory of the on-disk metrics history (default: the data directory)
+    pub history_path: Option<String>,
 }
 
 impl Default for MetricsConfig {
@@ -290,6 +294,7 @@ impl Default for MetricsConfig {
             collect_network_metrics: true,
             retention_hours: 24,
             enable_profiling: false,
+            history_path: None,
         }
     }
 }
@@ -320,10 +325,7 @@ impl SystemMetrics {
 
     /// Get current timestamp in seconds since Unix epoch
     pub fn current_timestamp() -> u64 {
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map(|d| d.as_secs())
-            .unwrap_or(0)
+        crate::clock::unix_secs()
     }
 }
 
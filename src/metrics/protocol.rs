@@ -0,0 +1,291 @@
+// WezTerm Multi-Process Development Framework - Protocol Metrics
+// Request counts, latency histograms and error rates per IPC message and dashboard command
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Upper bounds of the latency histogram buckets, in seconds
+pub const LATENCY_BUCKETS: &[f64] = &[
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Prefix of every exported Prometheus metric
+const METRIC_PREFIX: &str = "wezterm_parallel";
+
+/// Counters and latency histogram of one operation
+#[derive(Debug, Clone, PartialEq)]
+struct OperationCounters {
+    requests: u64,
+    errors: u64,
+    total_secs: f64,
+    max_secs: f64,
+
+    /// Non-cumulative count per bucket, plus one for values above the last bound
+    buckets: Vec<u64>,
+}
+
+impl Default for OperationCounters {
+    fn default() -> Self {
+        Self {
+            requests: 0,
+            errors: 0,
+            total_secs: 0.0,
+            max_secs: 0.0,
+            buckets: vec![0; LATENCY_BUCKETS.len() + 1],
+        }
+    }
+}
+
+impl OperationCounters {
+    fn record(&mut self, elapsed: Duration, success: bool) {
+        let secs = elapsed.as_secs_f64();
+        self.requests += 1;
+        self.errors += u64::from(!success);
+        self.total_secs += secs;
+        self.max_secs = self.max_secs.max(secs);
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|bound| secs <= *bound)
+            .unwrap_or(LATENCY_BUCKETS.len());
+        self.buckets[bucket] += 1;
+    }
+
+    /// Upper bound of the bucket holding the given quantile, capped at the maximum seen
+    fn quantile_secs(&self, quantile: f64) -> f64 {
+        if self.requests == 0 {
+            return 0.0;
+        }
+        let rank = (quantile * self.requests as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (index, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return LATENCY_BUCKETS
+                    .get(index)
+                    .map_or(self.max_secs, |bound| bound.min(self.max_secs));
+            }
+        }
+        self.max_secs
+    }
+}
+
+/// Health summary of one operation, as shown in the dashboard
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OperationHealth {
+    /// `ipc`, `websocket` or `http`
+    pub transport: String,
+
+    /// Message, command or route (e.g. `TaskQueue`, `QueryHistory`, `/status/board`)
+    pub operation: String,
+    pub requests: u64,
+    pub errors: u64,
+
+    /// Failed share of requests (0.0 - 1.0)
+    pub error_rate: f64,
+    pub avg_ms: f64,
+
+    /// Estimated from the histogram buckets
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Per-operation request metrics shared by the IPC server and the dashboard
+#[derive(Debug, Default)]
+pub struct ProtocolMetrics {
+    operations: Mutex<BTreeMap<(String, String), OperationCounters>>,
+}
+
+impl ProtocolMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count one finished request
+    pub fn record(&self, transport: &str, operation: &str, elapsed: Duration, success: bool) {
+        if let Ok(mut operations) = self.operations.lock() {
+            operations
+                .entry((transport.to_string(), operation.to_string()))
+                .or_default()
+                .record(elapsed, success);
+        }
+    }
+
+    /// Health of every operation seen so far, slowest (by p95) first
+    pub fn health(&self) -> Vec<OperationHealth> {
+        let operations = match self.operations.lock() {
+            Ok(operations) => operations.clone(),
+            Err(_) => return Vec::new(),
+        };
+        let mut health: Vec<OperationHealth> = operations
+            .into_iter()
+            .map(|((transport, operation), counters)| OperationHealth {
+                transport,
+                operation,
+                requests: counters.requests,
+                errors: counters.errors,
+                error_rate: ratio(counters.errors, counters.requests),
+                avg_ms: counters.total_secs * 1000.0 / counters.requests.max(1) as f64,
+                p95_ms: counters.quantile_secs(0.95) * 1000.0,
+                max_ms: counters.max_secs * 1000.0,
+            })
+            .collect();
+        health.sort_by(|a, b| b.p95_ms.total_cmp(&a.p95_ms));
+        health
+    }
+
+    /// Prometheus text exposition of all counters and histograms
+    pub fn to_prometheus(&self) -> String {
+        let operations = match self.operations.lock() {
+            Ok(operations) => operations.clone(),
+            Err(_) => BTreeMap::new(),
+        };
+        let mut out = String::new();
+
+        let _ = writeln!(
+            out,
+            "# HELP {METRIC_PREFIX}_requests_total Requests handled, by transport and operation"
+        );
+        let _ = writeln!(out, "# TYPE {METRIC_PREFIX}_requests_total counter");
+        for ((transport, operation), counters) in &operations {
+            let _ = writeln!(
+                out,
+                "{METRIC_PREFIX}_requests_total{{{}}} {}",
+                labels(transport, operation),
+                counters.requests
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP {METRIC_PREFIX}_request_errors_total Requests that ended in an error"
+        );
+        let _ = writeln!(out, "# TYPE {METRIC_PREFIX}_request_errors_total counter");
+        for ((transport, operation), counters) in &operations {
+            let _ = writeln!(
+                out,
+                "{METRIC_PREFIX}_request_errors_total{{{}}} {}",
+                labels(transport, operation),
+                counters.errors
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP {METRIC_PREFIX}_request_duration_seconds Request handling time"
+        );
+        let _ = writeln!(
+            out,
+            "# TYPE {METRIC_PREFIX}_request_duration_seconds histogram"
+        );
+        for ((transport, operation), counters) in &operations {
+            let labels = labels(transport, operation);
+            let mut cumulative = 0;
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(&counters.buckets) {
+                cumulative += count;
+                let _ = writeln!(
+                    out,
+                    "{METRIC_PREFIX}_request_duration_seconds_bucket{{{labels},le=\"{bound}\"}} {cumulative}"
+                );
+            }
+            let _ = writeln!(
+                out,
+                "{METRIC_PREFIX}_request_duration_seconds_bucket{{{labels},le=\"+Inf\"}} {}",
+                counters.requests
+            );
+            let _ = writeln!(
+                out,
+                "{METRIC_PREFIX}_request_duration_seconds_sum{{{labels}}} {}",
+                counters.total_secs
+            );
+            let _ = writeln!(
+                out,
+                "{METRIC_PREFIX}_request_duration_seconds_count{{{labels}}} {}",
+                counters.requests
+            );
+        }
+        out
+    }
+}
+
+fn ratio(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+
+fn labels(transport: &str, operation: &str) -> String {
+    format!(
+        "transport=\"{}\",operation=\"{}\"",
+        escape_label(transport),
+        escape_label(operation)
+    )
+}
+
+/// Escape a Prometheus label value
+pub(crate) fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_per_operation() {
+        let metrics = ProtocolMetrics::new();
+        for ms in [2, 3, 4, 40] {
+            metrics.record("ipc", "TaskQueue", Duration::from_millis(ms), true);
+        }
+        metrics.record("ipc", "TaskQueue", Duration::from_millis(1), false);
+        metrics.record(
+            "websocket",
+            "QueryHistory",
+            Duration::from_micros(500),
+            true,
+        );
+
+        let health = metrics.health();
+        assert_eq!(health.len(), 2);
+
+        // Slowest operation first
+        let queue = &health[0];
+        assert_eq!(queue.operation, "TaskQueue");
+        assert_eq!(queue.requests, 5);
+        assert_eq!(queue.errors, 1);
+        assert!((queue.error_rate - 0.2).abs() < f64::EPSILON);
+        assert!((queue.avg_ms - 10.0).abs() < 0.01);
+        // 95th percentile falls in the 25-50ms bucket, capped at the slowest request
+        assert!((queue.p95_ms - 40.0).abs() < 0.01);
+        assert!((queue.max_ms - 40.0).abs() < 0.01);
+
+        assert_eq!(health[1].transport, "websocket");
+        assert_eq!(health[1].error_rate, 0.0);
+    }
+
+    #[test]
+    fn test_prometheus_exposition() {
+        let metrics = ProtocolMetrics::new();
+        metrics.record("ipc", "Ping", Duration::from_millis(3), true);
+        metrics.record("http", "/status/\"x\"", Duration::from_secs(20), false);
+
+        let text = metrics.to_prometheus();
+        assert!(text.contains("# TYPE wezterm_parallel_requests_total counter"));
+        assert!(text
+            .contains("wezterm_parallel_requests_total{transport=\"ipc\",operation=\"Ping\"} 1"));
+        assert!(text.contains("wezterm_parallel_request_errors_total{transport=\"http\",operation=\"/status/\\\"x\\\"\"} 1"));
+        // Cumulative buckets: 3ms is below 5ms but above 2.5ms
+        assert!(text.contains("operation=\"Ping\",le=\"0.0025\"} 0"));
+        assert!(text.contains("operation=\"Ping\",le=\"0.005\"} 1"));
+        // Requests slower than the last bound only show up in +Inf
+        assert!(text.contains("le=\"10\"} 0"));
+        assert!(text.contains("le=\"+Inf\"} 1"));
+    }
+}
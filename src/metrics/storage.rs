@@ -1,9 +1,11 @@
 // Metrics storage and retrieval for historical data
 
-use super::{FrameworkMetrics, ProcessMetrics, SystemMetrics, WorkspaceMetrics};
+use super::{FrameworkMetrics, MetricsConfig, ProcessMetrics, SystemMetrics, WorkspaceMetrics};
 use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
 use serde_json;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::fs;
@@ -24,6 +26,84 @@ pub struct MetricsStorage {
 
     /// Maximum file size in bytes before rotation
     max_file_size: u64,
+
+    /// Ring buffer of recent history samples, oldest first
+    history: Arc<RwLock<VecDeque<HistorySample>>>,
+
+    /// Samples kept in memory
+    history_capacity: usize,
+
+    /// Hours of history kept in memory and on disk
+    retention_hours: u64,
+}
+
+/// Points returned by a history query when the client gives no limit
+pub const DEFAULT_HISTORY_POINTS: usize = 500;
+
+/// Metric series that can be queried from history
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryMetric {
+    /// System CPU usage in percent
+    Cpu,
+
+    /// System memory usage in bytes
+    Memory,
+
+    /// Completed tasks per minute
+    TaskThroughput,
+}
+
+impl HistoryMetric {
+    /// Parse a `metric_type` from a dashboard query
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "cpu" | "cpu_usage" => Some(HistoryMetric::Cpu),
+            "memory" | "memory_usage" => Some(HistoryMetric::Memory),
+            "task_throughput" | "throughput" | "tasks" => Some(HistoryMetric::TaskThroughput),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HistoryMetric::Cpu => "cpu",
+            HistoryMetric::Memory => "memory",
+            HistoryMetric::TaskThroughput => "task_throughput",
+        }
+    }
+}
+
+impl fmt::Display for HistoryMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One periodic sample of the history time series
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistorySample {
+    pub timestamp: u64,
+
+    /// CPU usage percentage (0.0 - 100.0)
+    pub cpu_usage: f64,
+
+    /// Memory usage in bytes
+    pub memory_usage: u64,
+
+    /// Tasks completed since the framework started (a counter)
+    pub tasks_completed: u64,
+}
+
+/// A (downsampled) point of a history query result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryPoint {
+    /// Start of the bucket the point summarises
+    pub timestamp: u64,
+    pub value: f64,
+
+    /// Samples averaged into this point
+    pub samples: usize,
 }
 
 /// In-memory metrics cache
@@ -45,6 +125,32 @@ struct MetricsCache {
 impl MetricsStorage {
     /// Create a new metrics storage instance
     pub async fn new(base_path: PathBuf) -> Result<Self, String> {
+        Self::with_config(base_path, &MetricsConfig::default()).await
+    }
+
+    /// Create a storage instance in the configured history directory
+    pub async fn from_config(config: &MetricsConfig) -> Result<Self, String> {
+        let base_path = config
+            .history_path
+            .as_deref()
+            .map(PathBuf::from)
+            .unwrap_or_else(Self::default_path);
+        Self::with_config(base_path, config).await
+    }
+
+    /// Default history directory
+    pub fn default_path() -> PathBuf {
+        let mut path = dirs::data_dir().unwrap_or_else(|| {
+            warn!("データディレクトリが取得できません。カレントディレクトリを使用します。");
+            PathBuf::from(".")
+        });
+        path.push("wezterm-parallel");
+        path.push("metrics");
+        path
+    }
+
+    /// Create a storage instance with history capacity and retention from `config`
+    pub async fn with_config(base_path: PathBuf, config: &MetricsConfig) -> Result<Self, String> {
         // Create base directory if it doesn't exist
         fs::create_dir_all(&base_path)
             .await
@@ -55,14 +161,121 @@ impl MetricsStorage {
             cache: Arc::new(RwLock::new(MetricsCache::new())),
             max_files_per_type: 24,          // Keep 24 hours of hourly files
             max_file_size: 10 * 1024 * 1024, // 10MB per file
+            history: Arc::new(RwLock::new(VecDeque::new())),
+            history_capacity: config.max_history_points.max(1),
+            retention_hours: config.retention_hours,
         };
 
         // Load recent metrics into cache
         storage.load_recent_metrics().await?;
+        storage.load_history().await;
 
         Ok(storage)
     }
 
+    /// Record a history sample in the ring buffer and on disk
+    pub async fn record_sample(&self, sample: HistorySample) -> Result<(), String> {
+        {
+            let mut history = self.history.write().await;
+            history.push_back(sample.clone());
+            Self::trim_history(&mut history, self.history_capacity, self.retention_cutoff());
+        }
+
+        let file_path = self.get_metrics_file_path("history", sample.timestamp);
+        self.append_to_file(&file_path, &sample).await
+    }
+
+    /// Query a metric series between two timestamps, downsampled to at most `limit` points
+    ///
+    /// Recent ranges are served from the ring buffer; ranges reaching further
+    /// back than it holds are read from the hourly history files.
+    pub async fn query_history(
+        &self,
+        metric: HistoryMetric,
+        start_time: u64,
+        end_time: u64,
+        limit: Option<usize>,
+    ) -> Result<Vec<HistoryPoint>, String> {
+        if start_time > end_time {
+            return Err(format!(
+                "Invalid time range: start {start_time} is after end {end_time}"
+            ));
+        }
+
+        let in_memory = {
+            let history = self.history.read().await;
+            history
+                .front()
+                .filter(|oldest| oldest.timestamp <= start_time)
+                .map(|_| {
+                    history
+                        .iter()
+                        .filter(|s| s.timestamp >= start_time && s.timestamp <= end_time)
+                        .cloned()
+                        .collect::<Vec<_>>()
+                })
+        };
+
+        let samples = match in_memory {
+            Some(samples) => samples,
+            None => {
+                let mut samples: Vec<HistorySample> = self
+                    .load_metrics_range("history", start_time, end_time)
+                    .await?
+                    .into_iter()
+                    .filter_map(|value| serde_json::from_value(value).ok())
+                    .collect();
+                samples.sort_by_key(|s| s.timestamp);
+                samples
+            }
+        };
+
+        Ok(downsample(
+            &series(metric, &samples),
+            start_time,
+            end_time,
+            limit.unwrap_or(DEFAULT_HISTORY_POINTS),
+        ))
+    }
+
+    /// Number of samples currently held in the ring buffer
+    pub async fn history_len(&self) -> usize {
+        self.history.read().await.len()
+    }
+
+    /// Refill the ring buffer from the history files within the retention period
+    async fn load_history(&self) {
+        let now = SystemMetrics::current_timestamp();
+        match self
+            .load_metrics_range("history", self.retention_cutoff(), now)
+            .await
+        {
+            Ok(values) => {
+                let mut samples: Vec<HistorySample> = values
+                    .into_iter()
+                    .filter_map(|value| serde_json::from_value(value).ok())
+                    .collect();
+                samples.sort_by_key(|s| s.timestamp);
+
+                let mut history = self.history.write().await;
+                history.extend(samples);
+                Self::trim_history(&mut history, self.history_capacity, self.retention_cutoff());
+                debug!("Loaded {} history samples", history.len());
+            }
+            Err(e) => warn!("Failed to load metrics history: {e}"),
+        }
+    }
+
+    fn retention_cutoff(&self) -> u64 {
+        SystemMetrics::current_timestamp().saturating_sub(self.retention_hours * 3600)
+    }
+
+    fn trim_history(history: &mut VecDeque<HistorySample>, capacity: usize, cutoff: u64) {
+        while history.len() > capacity || history.front().is_some_and(|s| s.timestamp < cutoff) {
+            history.pop_front();
+        }
+    }
+
     /// Save system metrics
     pub async fn save_system_metrics(&self, metrics: &SystemMetrics) -> Result<(), String> {
         debug!("Saving system metrics");
@@ -281,7 +494,7 @@ impl MetricsStorage {
 
         let cutoff_time = SystemMetrics::current_timestamp().saturating_sub(retention_hours * 3600);
 
-        for metric_type in &["system", "process", "workspace", "framework"] {
+        for metric_type in &["system", "process", "workspace", "framework", "history"] {
             let type_dir = self.base_path.join(metric_type);
 
             if type_dir.exists() {
@@ -482,7 +695,7 @@ impl MetricsStorage {
         let mut total_size = 0;
         let mut metrics_by_type = HashMap::new();
 
-        for metric_type in &["system", "process", "workspace", "framework"] {
+        for metric_type in &["system", "process", "workspace", "framework", "history"] {
             let type_dir = self.base_path.join(metric_type);
             let mut type_files = 0;
             let mut type_size = 0;
@@ -551,6 +764,73 @@ impl MetricsStorage {
     }
 }
 
+/// Values of one metric at each sample time
+///
+/// Task throughput is derived from consecutive completion counters, so it has
+/// one point fewer than there are samples; a drop in the counter (restart,
+/// deleted tasks) counts as no completions.
+fn series(metric: HistoryMetric, samples: &[HistorySample]) -> Vec<(u64, f64)> {
+    match metric {
+        HistoryMetric::Cpu => samples.iter().map(|s| (s.timestamp, s.cpu_usage)).collect(),
+        HistoryMetric::Memory => samples
+            .iter()
+            .map(|s| (s.timestamp, s.memory_usage as f64))
+            .collect(),
+        HistoryMetric::TaskThroughput => samples
+            .windows(2)
+            .filter(|w| w[1].timestamp > w[0].timestamp)
+            .map(|w| {
+                let completed = w[1].tasks_completed.saturating_sub(w[0].tasks_completed);
+                let minutes = (w[1].timestamp - w[0].timestamp) as f64 / 60.0;
+                (w[1].timestamp, completed as f64 / minutes)
+            })
+            .collect(),
+    }
+}
+
+/// Average points into at most `limit` equal-width time buckets, dropping empty ones
+fn downsample(
+    points: &[(u64, f64)],
+    start_time: u64,
+    end_time: u64,
+    limit: usize,
+) -> Vec<HistoryPoint> {
+    let limit = limit.max(1);
+    if points.len() <= limit {
+        return points
+            .iter()
+            .map(|&(timestamp, value)| HistoryPoint {
+                timestamp,
+                value,
+                samples: 1,
+            })
+            .collect();
+    }
+
+    let span = end_time.saturating_sub(start_time) + 1;
+    let limit = limit as u64;
+    let width = (span / limit + u64::from(span % limit != 0)).max(1);
+    let mut buckets: Vec<HistoryPoint> = Vec::new();
+    for &(timestamp, value) in points {
+        let bucket_start = start_time + timestamp.saturating_sub(start_time) / width * width;
+        match buckets.last_mut() {
+            Some(bucket) if bucket.timestamp == bucket_start => {
+                bucket.value += value;
+                bucket.samples += 1;
+            }
+            _ => buckets.push(HistoryPoint {
+                timestamp: bucket_start,
+                value,
+                samples: 1,
+            }),
+        }
+    }
+    for bucket in &mut buckets {
+        bucket.value /= bucket.samples as f64;
+    }
+    buckets
+}
+
 impl MetricsCache {
     fn new() -> Self {
         Self {
@@ -618,4 +898,100 @@ mod tests {
         assert!(recent.len() < 1500);
         assert!(recent.len() >= 500);
     }
+
+    fn sample(timestamp: u64, cpu_usage: f64, tasks_completed: u64) -> HistorySample {
+        HistorySample {
+            timestamp,
+            cpu_usage,
+            memory_usage: 1024,
+            tasks_completed,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_history_ring_buffer_and_downsampling() {
+        let temp_dir = tempdir().unwrap();
+        let config = MetricsConfig {
+            max_history_points: 50,
+            ..Default::default()
+        };
+        let storage = MetricsStorage::with_config(temp_dir.path().to_path_buf(), &config)
+            .await
+            .unwrap();
+
+        // One sample a minute, one task completed every other minute
+        let start = SystemMetrics::current_timestamp() - 3600;
+        for i in 0..60 {
+            storage
+                .record_sample(sample(start + i * 60, i as f64, i / 2))
+                .await
+                .unwrap();
+        }
+        assert_eq!(storage.history_len().await, 50);
+
+        let end = start + 59 * 60;
+        let recent_start = start + 10 * 60;
+        let cpu = storage
+            .query_history(HistoryMetric::Cpu, recent_start, end, Some(10))
+            .await
+            .unwrap();
+        assert_eq!(cpu.len(), 10);
+        assert_eq!(cpu[0].samples, 5);
+        assert_eq!(cpu[0].value, 12.0);
+
+        let throughput = storage
+            .query_history(HistoryMetric::TaskThroughput, recent_start, end, None)
+            .await
+            .unwrap();
+        assert_eq!(throughput.len(), 49);
+        let average = throughput.iter().map(|p| p.value).sum::<f64>() / 49.0;
+        assert!((average - 0.5).abs() < 0.02, "{average}");
+
+        // Older than the ring buffer: read back from the hourly files
+        let from_disk = storage
+            .query_history(HistoryMetric::Cpu, start, start + 120, None)
+            .await
+            .unwrap();
+        assert_eq!(from_disk.len(), 3);
+        assert_eq!(from_disk[2].value, 2.0);
+
+        assert!(storage
+            .query_history(HistoryMetric::Memory, end, start, None)
+            .await
+            .is_err());
+        assert_eq!(
+            HistoryMetric::parse("tasks"),
+            Some(HistoryMetric::TaskThroughput)
+        );
+        assert_eq!(HistoryMetric::parse("disk"), None);
+    }
+
+    #[tokio::test]
+    async fn test_history_reloaded_within_retention() {
+        let temp_dir = tempdir().unwrap();
+        let config = MetricsConfig {
+            retention_hours: 1,
+            ..Default::default()
+        };
+        let now = SystemMetrics::current_timestamp();
+        {
+            let storage = MetricsStorage::with_config(temp_dir.path().to_path_buf(), &config)
+                .await
+                .unwrap();
+            storage
+                .record_sample(sample(now - 7200, 1.0, 0))
+                .await
+                .unwrap();
+            storage
+                .record_sample(sample(now - 60, 2.0, 0))
+                .await
+                .unwrap();
+            assert_eq!(storage.history_len().await, 1);
+        }
+
+        let storage = MetricsStorage::with_config(temp_dir.path().to_path_buf(), &config)
+            .await
+            .unwrap();
+        assert_eq!(storage.history_len().await, 1);
+    }
 }
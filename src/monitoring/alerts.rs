@@ -1,11 +1,19 @@
 // WezTerm Multi-Process Development Framework - Alert System
 // Provides intelligent alerting and notification capabilities
 
-use super::{Alert, AlertSeverity, AlertThresholds, SystemMetrics};
-use std::collections::HashMap;
+use super::{
+    Alert, AlertSeverity, AlertThresholds, NotificationChannel, NotificationChannelConfig,
+    SystemMetrics,
+};
+use crate::i18n::{Language, Text, TextKey};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::{mpsc, RwLock};
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
+
+/// Time allowed for a webhook delivery
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// Alert manager for processing and dispatching alerts
 pub struct AlertManager {
@@ -23,6 +31,9 @@ pub struct AlertManager {
 
     /// Alert evaluation state
     evaluation_state: Arc<RwLock<AlertEvaluationState>>,
+
+    /// Language of alert messages
+    language: Language,
 }
 
 /// Alert evaluation state for tracking trends and preventing alert spam
@@ -63,13 +74,31 @@ pub struct LogAlertSender {
     log_path: String,
 }
 
-/// Webhook alert notification sender
+/// Webhook alert notification sender, posting the alert as JSON
 pub struct WebhookAlertSender {
     webhook_url: String,
-    #[allow(dead_code)]
+    headers: BTreeMap<String, String>,
     client: reqwest::Client,
 }
 
+/// Slack incoming webhook sender
+pub struct SlackAlertSender {
+    webhook_url: String,
+    channel: Option<String>,
+    username: Option<String>,
+    client: reqwest::Client,
+}
+
+/// OS desktop notification sender
+pub struct DesktopAlertSender;
+
+/// Delivers only alerts at or above a severity to the wrapped sender
+pub struct FilteredAlertSender {
+    inner: Box<dyn AlertNotificationSender + Send + Sync>,
+    min_severity: AlertSeverity,
+    notify_resolved: bool,
+}
+
 impl AlertManager {
     /// Create new alert manager
     pub fn new(thresholds: AlertThresholds) -> Self {
@@ -79,9 +108,16 @@ impl AlertManager {
             alert_history: Arc::new(RwLock::new(Vec::new())),
             notification_senders: Vec::new(),
             evaluation_state: Arc::new(RwLock::new(AlertEvaluationState::default())),
+            language: Language::default(),
         }
     }
 
+    /// Set the language of alert messages
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
+
     /// Add notification sender
     pub fn add_notification_sender(
         &mut self,
@@ -91,6 +127,13 @@ impl AlertManager {
         self.notification_senders.push(sender);
     }
 
+    /// Add a sender for each configured notification channel
+    pub fn add_notification_channels(&mut self, channels: &[NotificationChannelConfig]) {
+        for channel in channels {
+            self.add_notification_sender(Box::new(FilteredAlertSender::from_config(channel)));
+        }
+    }
+
     /// Start alert processing
     pub async fn start(
         &self,
@@ -109,7 +152,7 @@ impl AlertManager {
     }
 
     /// Evaluate metrics and generate alerts
-    async fn evaluate_metrics(
+    pub async fn evaluate_metrics(
         &self,
         metrics: &SystemMetrics,
     ) -> Result<(), Box<dyn std::error::Error>> {
@@ -162,10 +205,9 @@ impl AlertManager {
                 "system_cpu_high",
                 AlertSeverity::Warning,
                 "System",
-                format!(
-                    "High CPU usage: {:.2}% (threshold: {:.2}%)",
-                    metrics.cpu_usage, self.thresholds.cpu_usage
-                ),
+                Text::new(TextKey::SystemCpuHigh)
+                    .arg("usage", format!("{:.2}", metrics.cpu_usage))
+                    .arg("threshold", format!("{:.2}", self.thresholds.cpu_usage)),
                 Some("system"),
                 metrics.timestamp,
                 serde_json::json!({
@@ -177,22 +219,16 @@ impl AlertManager {
         }
 
         // Memory usage alert
-        let memory_usage_percentage = if metrics.memory_usage + metrics.memory_available > 0 {
-            (metrics.memory_usage as f64 / (metrics.memory_usage + metrics.memory_available) as f64)
-                * 100.0
-        } else {
-            0.0
-        };
+        let memory_usage_percentage = metrics.memory_usage_percent();
 
         if memory_usage_percentage > self.thresholds.memory_usage {
             self.create_alert_if_needed(
                 "system_memory_high",
                 AlertSeverity::Warning,
                 "System",
-                format!(
-                    "High memory usage: {:.2}% (threshold: {:.2}%)",
-                    memory_usage_percentage, self.thresholds.memory_usage
-                ),
+                Text::new(TextKey::SystemMemoryHigh)
+                    .arg("usage", format!("{memory_usage_percentage:.2}"))
+                    .arg("threshold", format!("{:.2}", self.thresholds.memory_usage)),
                 Some("system"),
                 metrics.timestamp,
                 serde_json::json!({
@@ -217,10 +253,9 @@ impl AlertManager {
                 "system_disk_high",
                 AlertSeverity::Critical,
                 "System",
-                format!(
-                    "High disk usage: {:.2}% (threshold: {:.2}%)",
-                    disk_usage_percentage, self.thresholds.disk_usage
-                ),
+                Text::new(TextKey::SystemDiskHigh)
+                    .arg("usage", format!("{disk_usage_percentage:.2}"))
+                    .arg("threshold", format!("{:.2}", self.thresholds.disk_usage)),
                 Some("system"),
                 metrics.timestamp,
                 serde_json::json!({
@@ -247,10 +282,10 @@ impl AlertManager {
                     &format!("process_restart_{process_name}"),
                     AlertSeverity::Error,
                     "Process",
-                    format!(
-                        "Process {} has restarted {} times (threshold: {})",
-                        process_name, process_metrics.restart_count, self.thresholds.restart_count
-                    ),
+                    Text::new(TextKey::ProcessRestarts)
+                        .arg("process", process_name)
+                        .arg("count", process_metrics.restart_count)
+                        .arg("threshold", self.thresholds.restart_count),
                     Some(process_name),
                     metrics.timestamp,
                     serde_json::json!({
@@ -268,7 +303,7 @@ impl AlertManager {
                     &format!("process_failed_{process_name}"),
                     AlertSeverity::Critical,
                     "Process",
-                    format!("Process {process_name} has failed"),
+                    Text::new(TextKey::ProcessFailed).arg("process", process_name),
                     Some(process_name),
                     metrics.timestamp,
                     serde_json::json!({
@@ -287,10 +322,9 @@ impl AlertManager {
                     &format!("process_cpu_high_{process_name}"),
                     AlertSeverity::Warning,
                     "Process",
-                    format!(
-                        "Process {} high CPU usage: {:.2}%",
-                        process_name, process_metrics.cpu_usage
-                    ),
+                    Text::new(TextKey::ProcessCpuHigh)
+                        .arg("process", process_name)
+                        .arg("usage", format!("{:.2}", process_metrics.cpu_usage)),
                     Some(process_name),
                     metrics.timestamp,
                     serde_json::json!({
@@ -397,7 +431,7 @@ impl AlertManager {
         alert_id: &str,
         severity: AlertSeverity,
         category: &str,
-        message: String,
+        text: Text,
         component: Option<&str>,
         timestamp: u64,
         data: serde_json::Value,
@@ -406,7 +440,7 @@ impl AlertManager {
 
         // Check if we recently sent this alert (prevent spam)
         if let Some(&last_time) = state.last_alert_times.get(alert_id) {
-            if timestamp - last_time < 300 {
+            if crate::clock::elapsed_secs(last_time, timestamp) < 300 {
                 // 5 minutes cooldown
                 return Ok(());
             }
@@ -424,7 +458,7 @@ impl AlertManager {
             id: alert_id.to_string(),
             severity,
             category: category.to_string(),
-            message,
+            message: text.render(self.language),
             component: component.map(|s| s.to_string()),
             timestamp,
             data: {
@@ -559,21 +593,25 @@ impl WebhookAlertSender {
     pub fn new(webhook_url: String) -> Self {
         Self {
             webhook_url,
-            client: reqwest::Client::new(),
+            headers: BTreeMap::new(),
+            client: webhook_client(),
         }
     }
+
+    /// Send extra headers, e.g. `Authorization`
+    pub fn with_headers(mut self, headers: BTreeMap<String, String>) -> Self {
+        self.headers = headers;
+        self
+    }
 }
 
 impl AlertNotificationSender for WebhookAlertSender {
     fn send_alert_sync(&self, alert: &Alert) -> Result<(), Box<dyn std::error::Error>> {
-        // For sync implementation, we'll skip the actual HTTP call
-        // In a real implementation, you'd use a blocking HTTP client
-        tracing::info!(
-            "Would send webhook alert to {}: {}",
-            self.webhook_url,
-            alert.message
-        );
-        Ok(())
+        let mut request = self.client.post(&self.webhook_url).json(alert);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        post_in_background("webhook", request)
     }
 
     fn name(&self) -> &str {
@@ -581,6 +619,231 @@ impl AlertNotificationSender for WebhookAlertSender {
     }
 }
 
+impl SlackAlertSender {
+    pub fn new(webhook_url: String) -> Self {
+        Self {
+            webhook_url,
+            channel: None,
+            username: None,
+            client: webhook_client(),
+        }
+    }
+
+    /// Override the webhook's default channel and bot name
+    pub fn with_channel(mut self, channel: Option<String>, username: Option<String>) -> Self {
+        self.channel = channel;
+        self.username = username;
+        self
+    }
+
+    /// Message in Slack's incoming webhook format
+    pub fn payload(&self, alert: &Alert) -> serde_json::Value {
+        let (color, emoji) = if alert.resolved {
+            ("good", ":white_check_mark:")
+        } else {
+            match alert.severity {
+                AlertSeverity::Info => ("#439fe0", ":information_source:"),
+                AlertSeverity::Warning => ("warning", ":warning:"),
+                AlertSeverity::Error => ("danger", ":x:"),
+                AlertSeverity::Critical => ("danger", ":rotating_light:"),
+            }
+        };
+        let status = if alert.resolved { "RESOLVED" } else { "ACTIVE" };
+
+        let mut fields = vec![
+            serde_json::json!({ "title": "Severity", "value": alert.severity.to_string(), "short": true }),
+            serde_json::json!({ "title": "Category", "value": alert.category, "short": true }),
+        ];
+        if let Some(ref component) = alert.component {
+            fields.push(
+                serde_json::json!({ "title": "Component", "value": component, "short": true }),
+            );
+        }
+
+        let mut payload = serde_json::json!({
+            "text": format!("{emoji} [{status}] {}", alert.message),
+            "attachments": [{
+                "color": color,
+                "fallback": alert.message,
+                "fields": fields,
+                "footer": "wezterm-parallel",
+                "ts": alert.resolved_at.unwrap_or(alert.timestamp),
+            }],
+        });
+        if let Some(ref channel) = self.channel {
+            payload["channel"] = serde_json::json!(channel);
+        }
+        if let Some(ref username) = self.username {
+            payload["username"] = serde_json::json!(username);
+        }
+        payload
+    }
+}
+
+impl AlertNotificationSender for SlackAlertSender {
+    fn send_alert_sync(&self, alert: &Alert) -> Result<(), Box<dyn std::error::Error>> {
+        let request = self
+            .client
+            .post(&self.webhook_url)
+            .json(&self.payload(alert));
+        post_in_background("slack", request)
+    }
+
+    fn name(&self) -> &str {
+        "slack"
+    }
+}
+
+impl DesktopAlertSender {
+    /// Program and arguments that show a notification on `os`
+    pub fn command(os: &str, alert: &Alert) -> Option<(&'static str, Vec<String>)> {
+        let title = if alert.resolved {
+            format!("Resolved: {}", alert.category)
+        } else {
+            format!("{}: {}", alert.severity, alert.category)
+        };
+
+        match os {
+            "linux" | "freebsd" | "openbsd" => {
+                let urgency = match alert.severity {
+                    AlertSeverity::Critical if !alert.resolved => "critical",
+                    AlertSeverity::Info => "low",
+                    _ => "normal",
+                };
+                Some((
+                    "notify-send",
+                    vec![
+                        format!("--urgency={urgency}"),
+                        "--app-name=wezterm-parallel".to_string(),
+                        title,
+                        alert.message.clone(),
+                    ],
+                ))
+            }
+            "macos" => Some((
+                "osascript",
+                vec![
+                    "-e".to_string(),
+                    format!(
+                        "display notification {} with title \"wezterm-parallel\" subtitle {}",
+                        applescript_string(&alert.message),
+                        applescript_string(&title)
+                    ),
+                ],
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl AlertNotificationSender for DesktopAlertSender {
+    fn send_alert_sync(&self, alert: &Alert) -> Result<(), Box<dyn std::error::Error>> {
+        let (program, args) = Self::command(std::env::consts::OS, alert).ok_or_else(|| {
+            format!(
+                "desktop notifications are not supported on {}",
+                std::env::consts::OS
+            )
+        })?;
+        // Not waited for; the notifier exits on its own
+        std::process::Command::new(program)
+            .args(args)
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn()?;
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "desktop"
+    }
+}
+
+impl FilteredAlertSender {
+    pub fn new(
+        inner: Box<dyn AlertNotificationSender + Send + Sync>,
+        min_severity: AlertSeverity,
+        notify_resolved: bool,
+    ) -> Self {
+        Self {
+            inner,
+            min_severity,
+            notify_resolved,
+        }
+    }
+
+    pub fn from_config(config: &NotificationChannelConfig) -> Self {
+        let inner: Box<dyn AlertNotificationSender + Send + Sync> = match &config.channel {
+            NotificationChannel::Console => Box::new(ConsoleAlertSender),
+            NotificationChannel::Webhook { url, headers } => {
+                Box::new(WebhookAlertSender::new(url.clone()).with_headers(headers.clone()))
+            }
+            NotificationChannel::Slack {
+                webhook_url,
+                channel,
+                username,
+            } => Box::new(
+                SlackAlertSender::new(webhook_url.clone())
+                    .with_channel(channel.clone(), username.clone()),
+            ),
+            NotificationChannel::Desktop => Box::new(DesktopAlertSender),
+        };
+        Self::new(inner, config.min_severity.clone(), config.notify_resolved)
+    }
+
+    pub fn accepts(&self, alert: &Alert) -> bool {
+        alert.severity >= self.min_severity && (self.notify_resolved || !alert.resolved)
+    }
+}
+
+impl AlertNotificationSender for FilteredAlertSender {
+    fn send_alert_sync(&self, alert: &Alert) -> Result<(), Box<dyn std::error::Error>> {
+        if self.accepts(alert) {
+            self.inner.send_alert_sync(alert)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+}
+
+fn webhook_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+}
+
+/// Deliver a request without blocking alert evaluation; failures are logged
+fn post_in_background(
+    sender: &'static str,
+    request: reqwest::RequestBuilder,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let runtime = tokio::runtime::Handle::try_current()
+        .map_err(|_| format!("{sender} notifications need a Tokio runtime"))?;
+    runtime.spawn(async move {
+        match request.send().await {
+            Ok(response) if !response.status().is_success() => {
+                warn!(
+                    "Alert notification via {} rejected: HTTP {}",
+                    sender,
+                    response.status()
+                );
+            }
+            Ok(_) => debug!("Alert notification delivered via {}", sender),
+            Err(e) => warn!("Alert notification via {} failed: {}", sender, e),
+        }
+    });
+    Ok(())
+}
+
+/// Quote a string for AppleScript
+fn applescript_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -625,7 +888,7 @@ mod tests {
                 "test_alert",
                 AlertSeverity::Warning,
                 "Test",
-                "Test message".to_string(),
+                Text::new(TextKey::ProcessFailed).arg("process", "test"),
                 None,
                 timestamp,
                 serde_json::json!({}),
@@ -639,7 +902,7 @@ mod tests {
                 "test_alert",
                 AlertSeverity::Warning,
                 "Test",
-                "Test message".to_string(),
+                Text::new(TextKey::ProcessFailed).arg("process", "test"),
                 None,
                 timestamp + 60, // 1 minute later
                 serde_json::json!({}),
@@ -650,4 +913,128 @@ mod tests {
         let active_alerts = manager.get_active_alerts().await;
         assert_eq!(active_alerts.len(), 1); // Should still be only 1 alert
     }
+
+    fn alert(severity: AlertSeverity, message: &str) -> Alert {
+        Alert {
+            id: "cpu_high".to_string(),
+            severity,
+            category: "System".to_string(),
+            message: message.to_string(),
+            component: Some("system".to_string()),
+            timestamp: 1234567890,
+            data: HashMap::new(),
+            resolved: false,
+            resolved_at: None,
+        }
+    }
+
+    #[test]
+    fn test_notification_channels_filter_by_severity() {
+        let channels: Vec<NotificationChannelConfig> = serde_yaml::from_str(
+            r##"
+- type: slack
+  webhook_url: https://hooks.slack.com/services/T000/B000/XXXX
+  channel: "#alerts"
+  min_severity: Error
+- type: desktop
+  min_severity: Critical
+  notify_resolved: false
+- type: console
+"##,
+        )
+        .unwrap();
+        assert_eq!(
+            channels[0].channel,
+            NotificationChannel::Slack {
+                webhook_url: "https://hooks.slack.com/services/T000/B000/XXXX".to_string(),
+                channel: Some("#alerts".to_string()),
+                username: None,
+            }
+        );
+        assert_eq!(channels[2].min_severity, AlertSeverity::Warning);
+        assert!(channels[2].notify_resolved);
+
+        let slack = FilteredAlertSender::from_config(&channels[0]);
+        let desktop = FilteredAlertSender::from_config(&channels[1]);
+        assert_eq!(slack.name(), "slack");
+        assert!(!slack.accepts(&alert(AlertSeverity::Warning, "CPU high")));
+        assert!(slack.accepts(&alert(AlertSeverity::Error, "CPU high")));
+        assert!(slack.accepts(&alert(AlertSeverity::Critical, "CPU high")));
+
+        let mut resolved = alert(AlertSeverity::Critical, "CPU high");
+        resolved.resolved = true;
+        assert!(desktop.accepts(&alert(AlertSeverity::Critical, "CPU high")));
+        assert!(!desktop.accepts(&resolved));
+        assert!(slack.accepts(&resolved));
+    }
+
+    #[test]
+    fn test_slack_payload() {
+        let sender = SlackAlertSender::new("https://hooks.slack.com/x".to_string())
+            .with_channel(Some("#ops".to_string()), Some("wezterm".to_string()));
+        let payload = sender.payload(&alert(AlertSeverity::Critical, "CPU usage 97%"));
+
+        assert_eq!(payload["text"], ":rotating_light: [ACTIVE] CPU usage 97%");
+        assert_eq!(payload["channel"], "#ops");
+        assert_eq!(payload["username"], "wezterm");
+        assert_eq!(payload["attachments"][0]["color"], "danger");
+        assert_eq!(payload["attachments"][0]["fields"][0]["value"], "CRITICAL");
+        assert_eq!(payload["attachments"][0]["fields"][2]["value"], "system");
+        assert_eq!(payload["attachments"][0]["ts"], 1234567890);
+    }
+
+    #[test]
+    fn test_desktop_notification_command() {
+        let alert = alert(AlertSeverity::Critical, r#"Disk "data" full"#);
+
+        let (program, args) = DesktopAlertSender::command("linux", &alert).unwrap();
+        assert_eq!(program, "notify-send");
+        assert_eq!(args[0], "--urgency=critical");
+        assert_eq!(args[2], "CRITICAL: System");
+        assert_eq!(args[3], r#"Disk "data" full"#);
+
+        let (program, args) = DesktopAlertSender::command("macos", &alert).unwrap();
+        assert_eq!(program, "osascript");
+        assert_eq!(
+            args[1],
+            r#"display notification "Disk \"data\" full" with title "wezterm-parallel" subtitle "CRITICAL: System""#
+        );
+
+        assert!(DesktopAlertSender::command("windows", &alert).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_webhook_posts_alert_json() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/alerts", listener.local_addr().unwrap());
+        let sender = WebhookAlertSender::new(url).with_headers(BTreeMap::from([(
+            "Authorization".to_string(),
+            "Bearer secret".to_string(),
+        )]));
+
+        sender
+            .send_alert_sync(&alert(AlertSeverity::Error, "Process failed"))
+            .unwrap();
+
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut chunk = [0; 4096];
+        while !String::from_utf8_lossy(&request).contains("Process failed") {
+            let n = stream.read(&mut chunk).await.unwrap();
+            assert!(n > 0, "connection closed before the body arrived");
+            request.extend_from_slice(&chunk[..n]);
+        }
+        stream
+            .write_all(b"HTTP/1.1 204 No Content\r\ncontent-length: 0\r\n\r\n")
+            .await
+            .unwrap();
+
+        let request = String::from_utf8_lossy(&request).to_lowercase();
+        assert!(request.starts_with("post /alerts http/1.1"));
+        assert!(request.contains("authorization: bearer secret"));
+        assert!(request.contains("content-type: application/json"));
+        assert!(request.contains(r#""severity":"error""#));
+    }
 }
@@ -2,11 +2,53 @@
 // Provides advanced analytics and insights for system performance and usage
 
 use super::{Alert, HealthCheck, SystemMetrics};
+use crate::task::TaskManager;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+/// Only every n-th metrics sample is kept in the trend series; anomalies always are
+const TREND_SAMPLE_STEP: usize = 10;
+
+/// Samples of the trailing window the direction of a trend point is fitted over
+const TREND_WINDOW: usize = 20;
+
+/// Samples needed before a fit or the anomaly detection is trusted
+const MIN_TREND_SAMPLES: usize = 5;
+
+/// A change over the window below this fraction of the mean is stable
+const STABLE_CHANGE_RATIO: f64 = 0.1;
+
+/// Residuals above this fraction of the mean make a trend volatile
+const VOLATILITY_RATIO: f64 = 0.5;
+
+/// Smoothing factor of the exponentially weighted mean and variance
+const EWMA_ALPHA: f64 = 0.3;
+
+/// Deviations from the EWMA beyond this many standard deviations are anomalies
+const ANOMALY_SIGMA: f64 = 3.0;
+
+/// Smallest standard deviation used for anomalies, so a flat series does
+/// not flag every small change
+const MIN_ANOMALY_DEVIATION: f64 = 1.0;
+
+/// Forecast horizons after the last sample
+const FORECAST_HORIZONS: [u64; 2] = [3600, 24 * 3600];
+
+/// Confidence level of the forecast intervals and its normal quantile
+const CONFIDENCE_LEVEL: f64 = 0.95;
+const CONFIDENCE_Z: f64 = 1.96;
+
+/// Capacity exhaustion is only projected this far ahead
+const CAPACITY_HORIZON: u64 = 30 * 24 * 3600;
+
+/// Usage (in percent) above which a resource needs attention
+const HIGH_USAGE_PERCENT: f64 = 80.0;
+
+/// Average usage (in percent) below which a resource has room to spare
+const LOW_USAGE_PERCENT: f64 = 25.0;
+
 /// Analytics manager for comprehensive system analysis
 pub struct AnalyticsManager {
     /// Historical metrics data
@@ -24,6 +66,9 @@ pub struct AnalyticsManager {
     /// Usage patterns
     #[allow(dead_code)]
     usage_patterns: Arc<RwLock<UsagePatterns>>,
+
+    /// Source of the task completions behind the throughput trend
+    task_manager: Option<Arc<TaskManager>>,
 }
 
 /// Performance baselines for comparison
@@ -291,10 +336,15 @@ pub struct TrendData {
     pub metric: String,
     pub value: f64,
     pub trend_direction: TrendDirection,
+
+    /// The value deviates from the exponentially weighted mean by more
+    /// than three standard deviations
+    #[serde(default)]
+    pub anomaly: bool,
 }
 
 /// Trend direction
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TrendDirection {
     Increasing,
     Decreasing,
@@ -305,7 +355,10 @@ pub enum TrendDirection {
 /// Capacity planning insights
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CapacityInsights {
+    /// Latest usage (in percent) of the busiest resource
     pub current_capacity_usage: f64,
+
+    /// When the first resource is projected to reach 100%, if within 30 days
     pub projected_capacity_exhaustion: Option<u64>,
     pub scaling_recommendations: Vec<String>,
     pub resource_optimization_opportunities: Vec<String>,
@@ -374,9 +427,16 @@ impl AnalyticsManager {
             health_history: Arc::new(RwLock::new(Vec::new())),
             baselines: Arc::new(RwLock::new(PerformanceBaselines::default())),
             usage_patterns: Arc::new(RwLock::new(UsagePatterns::default())),
+            task_manager: None,
         }
     }
 
+    /// Include the throughput of the task manager's completed tasks in the trends
+    pub fn with_task_manager(mut self, task_manager: Arc<TaskManager>) -> Self {
+        self.task_manager = Some(task_manager);
+        self
+    }
+
     /// Add metrics data for analysis
     pub async fn add_metrics(&self, metrics: SystemMetrics) {
         let mut history = self.metrics_history.write().await;
@@ -387,8 +447,11 @@ impl AnalyticsManager {
             history.drain(0..100);
         }
 
-        // Update baselines periodically
-        if history.len() % 100 == 0 {
+        // Update baselines periodically; the history lock must be released
+        // first because the baseline calculation reads it
+        let update_due = history.len() % 100 == 0;
+        drop(history);
+        if update_due {
             self.update_baselines().await;
         }
     }
@@ -423,6 +486,7 @@ impl AnalyticsManager {
         let metrics = self.get_metrics_in_range(&time_range).await;
         let alerts = self.get_alerts_in_range(&time_range).await;
         let health_checks = self.get_health_checks_in_range(&time_range).await;
+        let completions = self.get_task_completions_in_range(&time_range).await;
 
         // Perform analyses
         let performance = self.analyze_performance(&metrics).await;
@@ -430,7 +494,9 @@ impl AnalyticsManager {
             .analyze_reliability(&metrics, &alerts, &health_checks)
             .await;
         let usage = self.analyze_usage(&metrics).await;
-        let trends = self.analyze_trends(&metrics, &alerts).await;
+        let trends = self
+            .analyze_trends(&metrics, &alerts, &completions, time_range.end)
+            .await;
         let recommendations = self
             .generate_recommendations(&performance, &reliability, &usage, &trends)
             .await;
@@ -480,6 +546,24 @@ impl AnalyticsManager {
             .collect()
     }
 
+    /// Completion times of the tasks completed within time range, oldest first
+    async fn get_task_completions_in_range(&self, time_range: &TimeRange) -> Vec<u64> {
+        let Some(ref task_manager) = self.task_manager else {
+            return Vec::new();
+        };
+        let mut completions: Vec<u64> = task_manager
+            .list_tasks(None)
+            .await
+            .iter()
+            .filter_map(|task| task.completed_at)
+            .filter(|completed_at| {
+                *completed_at >= time_range.start && *completed_at <= time_range.end
+            })
+            .collect();
+        completions.sort_unstable();
+        completions
+    }
+
     /// Analyze performance metrics
     async fn analyze_performance(&self, metrics: &[SystemMetrics]) -> PerformanceAnalysis {
         if metrics.is_empty() {
@@ -571,7 +655,7 @@ impl AnalyticsManager {
         }
 
         let memory_usage_pct = if avg_memory > 0 {
-            (avg_memory as f64 / (8 * 1024 * 1024 * 1024) as f64) * 100.0
+            (avg_memory as f64 / (8u64 * 1024 * 1024 * 1024) as f64) * 100.0
         } else {
             0.0
         };
@@ -716,51 +800,78 @@ impl AnalyticsManager {
     }
 
     /// Analyze trends
-    async fn analyze_trends(&self, metrics: &[SystemMetrics], alerts: &[Alert]) -> TrendAnalysis {
-        let mut performance_trends = Vec::new();
-        let mut error_trends = Vec::new();
-
-        // Simple trend analysis - in production, this would use more sophisticated algorithms
-        for (i, metric) in metrics.iter().enumerate() {
-            if i % 10 == 0 {
-                // Sample every 10th metric
-                performance_trends.push(TrendData {
-                    timestamp: metric.timestamp,
-                    metric: "cpu_usage".to_string(),
-                    value: metric.cpu_usage,
-                    trend_direction: TrendDirection::Stable, // Simplified
-                });
-            }
-        }
+    ///
+    /// CPU and memory usage (in percent) and task throughput (completions per
+    /// hour) are fitted by least squares: each point's direction over its
+    /// trailing window, forecasts and capacity exhaustion over the whole range.
+    async fn analyze_trends(
+        &self,
+        metrics: &[SystemMetrics],
+        alerts: &[Alert],
+        completions: &[u64],
+        end: u64,
+    ) -> TrendAnalysis {
+        let cpu: Vec<(u64, f64)> = metrics.iter().map(|m| (m.timestamp, m.cpu_usage)).collect();
+        let memory: Vec<(u64, f64)> = metrics
+            .iter()
+            .filter_map(|m| {
+                Some((
+                    m.timestamp,
+                    usage_percent(m.memory_usage, m.memory_available)?,
+                ))
+            })
+            .collect();
+        let disk: Vec<(u64, f64)> = metrics
+            .iter()
+            .filter_map(|m| Some((m.timestamp, usage_percent(m.disk_usage, m.disk_available)?)))
+            .collect();
+        let start = metrics
+            .first()
+            .map(|m| m.timestamp)
+            .into_iter()
+            .chain(completions.first().copied())
+            .min();
+        let throughput = match start {
+            Some(start) if !completions.is_empty() => hourly_counts(completions, start, end),
+            _ => Vec::new(),
+        };
 
-        for alert in alerts {
-            error_trends.push(TrendData {
+        let mut performance_trends = trend_points("cpu_usage", &cpu, TREND_SAMPLE_STEP);
+        performance_trends.extend(trend_points("memory_percent", &memory, TREND_SAMPLE_STEP));
+        let usage_trends = trend_points("task_throughput", &throughput, 1);
+
+        let error_trends = alerts
+            .iter()
+            .map(|alert| TrendData {
                 timestamp: alert.timestamp,
                 metric: "alert_count".to_string(),
                 value: 1.0,
-                trend_direction: TrendDirection::Stable, // Simplified
-            });
-        }
+                trend_direction: TrendDirection::Stable,
+                anomaly: false,
+            })
+            .collect();
 
-        let capacity_insights = CapacityInsights {
-            current_capacity_usage: 65.0,
-            projected_capacity_exhaustion: None,
-            scaling_recommendations: vec![
-                "Monitor CPU usage trends".to_string(),
-                "Consider memory optimization".to_string(),
-            ],
-            resource_optimization_opportunities: vec![
-                "Implement process pooling".to_string(),
-                "Optimize disk I/O operations".to_string(),
-            ],
-        };
+        let forecasts = [
+            ("cpu_usage", &cpu, 100.0),
+            ("memory_percent", &memory, 100.0),
+            ("task_throughput", &throughput, f64::INFINITY),
+        ]
+        .into_iter()
+        .flat_map(|(metric, series, max)| forecast(metric, series, max))
+        .collect();
+
+        let capacity_insights = capacity_insights(
+            &[("CPU", &cpu), ("Memory", &memory), ("Disk", &disk)],
+            &performance_trends,
+            &usage_trends,
+        );
 
         TrendAnalysis {
             performance_trends,
-            usage_trends: vec![], // Placeholder
+            usage_trends,
             error_trends,
             capacity_insights,
-            forecasts: vec![], // Placeholder
+            forecasts,
         }
     }
 
@@ -890,15 +1001,280 @@ impl AnalyticsManager {
     }
 }
 
+/// Least-squares line through `(timestamp, value)` points
+#[derive(Debug, Clone)]
+struct LinearFit {
+    /// Timestamps are taken relative to the first one to keep the precision
+    origin: u64,
+    slope: f64,
+    intercept: f64,
+    mean_x: f64,
+    mean_y: f64,
+    sxx: f64,
+    residual_std: f64,
+    samples: usize,
+}
+
+impl LinearFit {
+    /// `None` below three points or when all share one timestamp
+    fn new(points: &[(u64, f64)]) -> Option<Self> {
+        if points.len() < 3 {
+            return None;
+        }
+        let origin = points[0].0;
+        let n = points.len() as f64;
+        let x = |timestamp: u64| timestamp.saturating_sub(origin) as f64;
+        let mean_x = points.iter().map(|(t, _)| x(*t)).sum::<f64>() / n;
+        let mean_y = points.iter().map(|(_, v)| v).sum::<f64>() / n;
+        let sxx: f64 = points.iter().map(|(t, _)| (x(*t) - mean_x).powi(2)).sum();
+        if sxx <= 0.0 {
+            return None;
+        }
+        let sxy: f64 = points
+            .iter()
+            .map(|(t, v)| (x(*t) - mean_x) * (v - mean_y))
+            .sum();
+        let slope = sxy / sxx;
+        let intercept = mean_y - slope * mean_x;
+        let sse: f64 = points
+            .iter()
+            .map(|(t, v)| (v - (intercept + slope * x(*t))).powi(2))
+            .sum();
+        Some(Self {
+            origin,
+            slope,
+            intercept,
+            mean_x,
+            mean_y,
+            sxx,
+            residual_std: (sse / (n - 2.0)).sqrt(),
+            samples: points.len(),
+        })
+    }
+
+    fn x(&self, timestamp: u64) -> f64 {
+        timestamp as f64 - self.origin as f64
+    }
+
+    fn at(&self, timestamp: u64) -> f64 {
+        self.intercept + self.slope * self.x(timestamp)
+    }
+
+    /// Half width of the prediction interval at `timestamp`
+    fn margin(&self, timestamp: u64) -> f64 {
+        let distance = self.x(timestamp) - self.mean_x;
+        CONFIDENCE_Z
+            * self.residual_std
+            * (1.0 + 1.0 / self.samples as f64 + distance * distance / self.sxx).sqrt()
+    }
+
+    /// When the line reaches `limit`; `None` when it does not rise
+    fn reaches(&self, limit: f64) -> Option<u64> {
+        if self.slope <= 0.0 {
+            return None;
+        }
+        let x = ((limit - self.intercept) / self.slope).max(0.0);
+        Some(self.origin.saturating_add(x as u64))
+    }
+
+    fn direction(&self, span: f64) -> TrendDirection {
+        let scale = self.mean_y.abs().max(1.0);
+        if self.residual_std / scale > VOLATILITY_RATIO {
+            TrendDirection::Volatile
+        } else if (self.slope * span).abs() / scale < STABLE_CHANGE_RATIO {
+            TrendDirection::Stable
+        } else if self.slope > 0.0 {
+            TrendDirection::Increasing
+        } else {
+            TrendDirection::Decreasing
+        }
+    }
+}
+
+/// Direction of `points` fitted as a whole
+fn trend_direction(points: &[(u64, f64)]) -> TrendDirection {
+    match (LinearFit::new(points), points.first(), points.last()) {
+        (Some(fit), Some(first), Some(last)) if points.len() >= MIN_TREND_SAMPLES => {
+            fit.direction(last.0.saturating_sub(first.0) as f64)
+        }
+        _ => TrendDirection::Stable,
+    }
+}
+
+/// Flag the values deviating from their exponentially weighted mean by
+/// more than [`ANOMALY_SIGMA`] exponentially weighted standard deviations
+fn anomalies(values: &[f64]) -> Vec<bool> {
+    let mut flags = vec![false; values.len()];
+    let Some(&first) = values.first() else {
+        return flags;
+    };
+    let (mut mean, mut variance) = (first, 0.0_f64);
+    for (index, &value) in values.iter().enumerate().skip(1) {
+        let deviation = value - mean;
+        flags[index] = index >= MIN_TREND_SAMPLES
+            && deviation.abs() > ANOMALY_SIGMA * variance.sqrt().max(MIN_ANOMALY_DEVIATION);
+        mean += EWMA_ALPHA * deviation;
+        variance = (1.0 - EWMA_ALPHA) * (variance + EWMA_ALPHA * deviation * deviation);
+    }
+    flags
+}
+
+/// Every `step`-th point of a series and every anomaly, each with the
+/// direction of its trailing window
+fn trend_points(metric: &str, series: &[(u64, f64)], step: usize) -> Vec<TrendData> {
+    let values: Vec<f64> = series.iter().map(|(_, value)| *value).collect();
+    let anomalies = anomalies(&values);
+    series
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| index % step == 0 || anomalies[*index])
+        .map(|(index, &(timestamp, value))| {
+            let window = &series[(index + 1).saturating_sub(TREND_WINDOW)..=index];
+            TrendData {
+                timestamp,
+                metric: metric.to_string(),
+                value,
+                trend_direction: trend_direction(window),
+                anomaly: anomalies[index],
+            }
+        })
+        .collect()
+}
+
+/// Forecasts of the fitted series, kept within `0..=max`
+fn forecast(metric: &str, series: &[(u64, f64)], max: f64) -> Vec<Forecast> {
+    let (Some(fit), Some(last)) = (LinearFit::new(series), series.last()) else {
+        return Vec::new();
+    };
+    if series.len() < MIN_TREND_SAMPLES {
+        return Vec::new();
+    }
+    FORECAST_HORIZONS
+        .iter()
+        .map(|horizon| {
+            let timestamp = last.0 + horizon;
+            let predicted = fit.at(timestamp);
+            let margin = fit.margin(timestamp);
+            Forecast {
+                metric: metric.to_string(),
+                prediction_timestamp: timestamp,
+                predicted_value: predicted.clamp(0.0, max),
+                confidence_interval: (
+                    (predicted - margin).clamp(0.0, max),
+                    (predicted + margin).clamp(0.0, max),
+                ),
+                confidence_level: CONFIDENCE_LEVEL,
+            }
+        })
+        .collect()
+}
+
+/// Current usage, projected exhaustion and advice from the resource usage series
+fn capacity_insights(
+    resources: &[(&str, &Vec<(u64, f64)>)],
+    performance_trends: &[TrendData],
+    usage_trends: &[TrendData],
+) -> CapacityInsights {
+    let mut current_capacity_usage: f64 = 0.0;
+    let mut projected_capacity_exhaustion: Option<u64> = None;
+    let mut scaling_recommendations = Vec::new();
+    let mut resource_optimization_opportunities = Vec::new();
+
+    for (name, series) in resources {
+        let Some(&(last_timestamp, latest)) = series.last() else {
+            continue;
+        };
+        current_capacity_usage = current_capacity_usage.max(latest);
+
+        let exhaustion = LinearFit::new(series)
+            .filter(|_| series.len() >= MIN_TREND_SAMPLES)
+            .and_then(|fit| fit.reaches(100.0))
+            .map(|at| at.max(last_timestamp))
+            .filter(|at| *at - last_timestamp <= CAPACITY_HORIZON);
+        if let Some(at) = exhaustion {
+            projected_capacity_exhaustion =
+                Some(projected_capacity_exhaustion.map_or(at, |earliest| earliest.min(at)));
+            scaling_recommendations.push(format!(
+                "{name} is projected to reach 100% in {:.1} h at the current rate",
+                (at - last_timestamp) as f64 / 3600.0
+            ));
+        }
+        if latest >= HIGH_USAGE_PERCENT {
+            scaling_recommendations.push(format!(
+                "{name} is at {latest:.0}%; run fewer processes in parallel or add capacity"
+            ));
+        }
+
+        let average = series.iter().map(|(_, value)| value).sum::<f64>() / series.len() as f64;
+        if series.len() >= MIN_TREND_SAMPLES && average < LOW_USAGE_PERCENT {
+            resource_optimization_opportunities.push(format!(
+                "{name} averages {average:.0}%; there is room for more parallel processes"
+            ));
+        }
+    }
+    if scaling_recommendations.is_empty() {
+        scaling_recommendations
+            .push("No resource is projected to run out within 30 days".to_string());
+    }
+
+    let mut anomalous: Vec<(&str, usize)> = Vec::new();
+    for point in performance_trends.iter().filter(|point| point.anomaly) {
+        match anomalous
+            .iter_mut()
+            .find(|(metric, _)| *metric == point.metric)
+        {
+            Some((_, count)) => *count += 1,
+            None => anomalous.push((&point.metric, 1)),
+        }
+    }
+    for (metric, count) in anomalous {
+        resource_optimization_opportunities.push(format!(
+            "{count} anomalous {metric} samples; check what ran at those times"
+        ));
+    }
+    if usage_trends
+        .last()
+        .is_some_and(|point| point.trend_direction == TrendDirection::Decreasing)
+    {
+        resource_optimization_opportunities
+            .push("Task throughput is decreasing; look for blocked or failing tasks".to_string());
+    }
+
+    CapacityInsights {
+        current_capacity_usage,
+        projected_capacity_exhaustion,
+        scaling_recommendations,
+        resource_optimization_opportunities,
+    }
+}
+
+/// Share of `used` in `used + available`, in percent; `None` without a total
+fn usage_percent(used: u64, available: u64) -> Option<f64> {
+    let total = used.saturating_add(available);
+    (total > 0).then(|| used as f64 / total as f64 * 100.0)
+}
+
+/// Completions per hour, in hours ending at `end` and going back to `start`;
+/// each point is stamped with the end of its hour
+fn hourly_counts(completions: &[u64], start: u64, end: u64) -> Vec<(u64, f64)> {
+    let hours = ((end.saturating_sub(start) + 3599) / 3600).max(1);
+    (0..hours)
+        .rev()
+        .map(|hour| {
+            let bucket_end = end.saturating_sub(hour * 3600);
+            let bucket_start = bucket_end.saturating_sub(3600);
+            let count = completions
+                .iter()
+                .filter(|at| **at > bucket_start && **at <= bucket_end)
+                .count();
+            (bucket_end, count as f64)
+        })
+        .collect()
+}
+
 /// Get current timestamp
 fn current_timestamp() -> u64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_else(|_| {
-            log::warn!("System time error in analytics, using fallback timestamp");
-            std::time::Duration::from_secs(0)
-        })
-        .as_secs()
+    crate::clock::unix_secs()
 }
 
 impl Default for AnalyticsManager {
@@ -943,4 +1319,126 @@ mod tests {
         let history = manager.metrics_history.read().await;
         assert_eq!(history.len(), 1);
     }
+
+    #[tokio::test]
+    async fn test_baselines_update_after_hundred_samples() {
+        let manager = AnalyticsManager::new();
+
+        for i in 0..100 {
+            let metrics = SystemMetrics {
+                timestamp: current_timestamp() + i,
+                cpu_usage: 40.0,
+                memory_usage: 1024,
+                memory_available: 3072,
+                disk_usage: 2048,
+                disk_available: 8192,
+                network_io: crate::monitoring::NetworkIO {
+                    bytes_received: 0,
+                    bytes_sent: 0,
+                    packets_received: 0,
+                    packets_sent: 0,
+                },
+                process_metrics: std::collections::HashMap::new(),
+            };
+            tokio::time::timeout(
+                std::time::Duration::from_secs(1),
+                manager.add_metrics(metrics),
+            )
+            .await
+            .expect("add_metrics must not block on the baseline update");
+        }
+
+        let baselines = manager.get_baselines().await;
+        assert_eq!(baselines.sample_count, 50);
+        assert!((baselines.cpu_baseline - 40.0).abs() < f64::EPSILON);
+    }
+
+    fn metrics_at(timestamp: u64, cpu_usage: f64, memory_percent: u64) -> SystemMetrics {
+        SystemMetrics {
+            timestamp,
+            cpu_usage,
+            memory_usage: memory_percent,
+            memory_available: 100 - memory_percent,
+            disk_usage: 0,
+            disk_available: 0,
+            network_io: crate::monitoring::NetworkIO {
+                bytes_received: 0,
+                bytes_sent: 0,
+                packets_received: 0,
+                packets_sent: 0,
+            },
+            process_metrics: std::collections::HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_trend_direction_and_anomalies() {
+        let rising: Vec<(u64, f64)> = (0..40).map(|i| (i * 60, 20.0 + i as f64)).collect();
+        let points = trend_points("cpu_usage", &rising, TREND_SAMPLE_STEP);
+        assert_eq!(points.len(), 4);
+        assert_eq!(points[0].trend_direction, TrendDirection::Stable);
+        assert_eq!(points[3].trend_direction, TrendDirection::Increasing);
+        assert!(points.iter().all(|point| !point.anomaly));
+
+        let mut flat: Vec<(u64, f64)> = (0..40).map(|i| (i * 60, 30.0)).collect();
+        flat[25].1 = 95.0;
+        let points = trend_points("cpu_usage", &flat, TREND_SAMPLE_STEP);
+        // The spike is kept although it is not a sampled point
+        let spike = points.iter().find(|point| point.anomaly).unwrap();
+        assert_eq!((spike.timestamp, spike.value), (25 * 60, 95.0));
+        assert_eq!(points.iter().filter(|point| point.anomaly).count(), 1);
+        assert_eq!(points[0].trend_direction, TrendDirection::Stable);
+
+        let falling: Vec<(u64, f64)> = (0..20).map(|i| (i * 60, 80.0 - 2.0 * i as f64)).collect();
+        assert_eq!(trend_direction(&falling), TrendDirection::Decreasing);
+        let noisy: Vec<(u64, f64)> = (0..20)
+            .map(|i| (i * 60, if i % 2 == 0 { 5.0 } else { 95.0 }))
+            .collect();
+        assert_eq!(trend_direction(&noisy), TrendDirection::Volatile);
+    }
+
+    #[tokio::test]
+    async fn test_forecasts_and_capacity_exhaustion() {
+        let manager = AnalyticsManager::new();
+        // Memory grows by one point a minute from 40% and reaches 100% after an hour
+        let metrics: Vec<SystemMetrics> = (0..30)
+            .map(|i| metrics_at(1_000_000 + i * 60, 10.0, 40 + i))
+            .collect();
+        let completions = [1_000_100, 1_000_200, 1_003_000, 1_005_000];
+        let trends = manager
+            .analyze_trends(&metrics, &[], &completions, 1_007_200)
+            .await;
+
+        let insights = &trends.capacity_insights;
+        assert!((insights.current_capacity_usage - 69.0).abs() < 1e-9);
+        assert_eq!(insights.projected_capacity_exhaustion, Some(1_003_600));
+        assert!(insights.scaling_recommendations[0].starts_with("Memory is projected"));
+        assert!(insights.resource_optimization_opportunities[0].starts_with("CPU averages 10%"));
+
+        let memory: Vec<&Forecast> = trends
+            .forecasts
+            .iter()
+            .filter(|f| f.metric == "memory_percent")
+            .collect();
+        assert_eq!(memory.len(), FORECAST_HORIZONS.len());
+        assert_eq!(memory[0].prediction_timestamp, 1_001_740 + 3600);
+        assert_eq!(memory[0].predicted_value, 100.0);
+        assert_eq!(memory[0].confidence_level, 0.95);
+        let cpu = trends
+            .forecasts
+            .iter()
+            .find(|f| f.metric == "cpu_usage")
+            .unwrap();
+        assert!((cpu.predicted_value - 10.0).abs() < 1e-9);
+        assert!(cpu.confidence_interval.0 <= cpu.predicted_value);
+        assert!(cpu.confidence_interval.1 >= cpu.predicted_value);
+
+        // Two hours of completions, counted per hour up to the end of the range
+        let throughput: Vec<(u64, f64)> = trends
+            .usage_trends
+            .iter()
+            .map(|point| (point.timestamp, point.value))
+            .collect();
+        assert_eq!(throughput, vec![(1_003_600, 3.0), (1_007_200, 1.0)]);
+    }
 }
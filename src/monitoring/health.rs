@@ -2,9 +2,13 @@
 // Provides comprehensive health monitoring for all system components
 
 use super::{ComponentHealth, HealthCheck, HealthStatus};
+use crate::room::state::ProcessStatus;
 use crate::room::WorkspaceManager;
 use crate::task::TaskManager;
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::time::sleep;
@@ -142,6 +146,16 @@ impl HealthCheckManager {
             self.create_component_health(&filesystem_check).await,
         );
 
+        // Check managed processes
+        let processes_check = self.check_processes().await;
+        if processes_check.status != HealthStatus::Healthy {
+            overall_status = worst_status(overall_status, processes_check.status.clone());
+        }
+        components.insert(
+            "processes".to_string(),
+            self.create_component_health(&processes_check).await,
+        );
+
         // Check database/persistence
         let persistence_check = self.check_persistence_layer().await;
         if persistence_check.status != HealthStatus::Healthy {
@@ -272,9 +286,9 @@ impl HealthCheckManager {
         let component_name = "ipc_system".to_string();
 
         // Check if IPC socket exists and is accessible
-        let socket_path = "/tmp/wezterm-parallel.sock";
+        let socket_path = crate::ipc::default_socket_path();
 
-        match tokio::fs::metadata(socket_path).await {
+        match tokio::fs::metadata(&socket_path).await {
             Ok(metadata) => {
                 let response_time = start_time.elapsed().as_millis() as u64;
 
@@ -288,7 +302,7 @@ impl HealthCheckManager {
                             let mut details = HashMap::new();
                             details.insert(
                                 "socket_path".to_string(),
-                                serde_json::Value::String(socket_path.to_string()),
+                                serde_json::Value::String(socket_path.display().to_string()),
                             );
                             details
                                 .insert("socket_exists".to_string(), serde_json::Value::Bool(true));
@@ -305,7 +319,7 @@ impl HealthCheckManager {
                             let mut details = HashMap::new();
                             details.insert(
                                 "socket_path".to_string(),
-                                serde_json::Value::String(socket_path.to_string()),
+                                serde_json::Value::String(socket_path.display().to_string()),
                             );
                             details.insert(
                                 "socket_size".to_string(),
@@ -325,7 +339,7 @@ impl HealthCheckManager {
                     let mut details = HashMap::new();
                     details.insert(
                         "socket_path".to_string(),
-                        serde_json::Value::String(socket_path.to_string()),
+                        serde_json::Value::String(socket_path.display().to_string()),
                     );
                     details.insert("socket_exists".to_string(), serde_json::Value::Bool(false));
                     details
@@ -368,6 +382,51 @@ impl HealthCheckManager {
         }
     }
 
+    /// Check managed processes; any of them failed makes the component unhealthy
+    async fn check_processes(&self) -> ComponentCheckResult {
+        let start_time = Instant::now();
+        let component_name = "processes".to_string();
+
+        let Some(process_manager) = self.workspace_manager.process_manager() else {
+            return ComponentCheckResult {
+                component_name,
+                status: HealthStatus::Healthy,
+                message: "No process manager running".to_string(),
+                response_time_ms: start_time.elapsed().as_millis() as u64,
+                details: HashMap::new(),
+            };
+        };
+
+        let processes = process_manager.list_processes().await;
+        let failed: Vec<String> = processes
+            .iter()
+            .filter(|process| process.status == ProcessStatus::Failed)
+            .map(|process| process.id.clone())
+            .collect();
+        let mut details = HashMap::new();
+        details.insert(
+            "process_count".to_string(),
+            serde_json::Value::Number(processes.len().into()),
+        );
+        details.insert("failed".to_string(), serde_json::json!(failed));
+
+        ComponentCheckResult {
+            component_name,
+            status: if failed.is_empty() {
+                HealthStatus::Healthy
+            } else {
+                HealthStatus::Unhealthy
+            },
+            message: if failed.is_empty() {
+                format!("{} processes running", processes.len())
+            } else {
+                format!("Failed processes: {}", failed.join(", "))
+            },
+            response_time_ms: start_time.elapsed().as_millis() as u64,
+            details,
+        }
+    }
+
     /// Check file system health
     async fn check_file_system(&self) -> ComponentCheckResult {
         let start_time = Instant::now();
@@ -558,6 +617,173 @@ impl HealthCheckManager {
     }
 }
 
+/// What is done about a component that keeps failing its health check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryAction {
+    /// Stop the dashboard server and bind its port again
+    RestartWebsocket,
+
+    /// Recreate the IPC socket file and listen on it again
+    RebindIpc,
+
+    /// Restart every managed process that has failed
+    RestartFailedProcesses,
+
+    /// Drop cached data and finished processes
+    TriggerGc,
+}
+
+/// Run `action` once `component` was unhealthy in `after_failures` checks in a row
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecoveryRule {
+    pub component: String,
+    pub action: RecoveryAction,
+
+    #[serde(default = "default_after_failures")]
+    pub after_failures: u32,
+
+    /// Seconds before the same rule runs again while the component stays unhealthy
+    #[serde(default = "default_cooldown_secs")]
+    pub cooldown_secs: u64,
+}
+
+fn default_after_failures() -> u32 {
+    3
+}
+
+fn default_cooldown_secs() -> u64 {
+    300
+}
+
+/// Automated recovery of unhealthy components (`monitoring.recovery`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecoveryConfig {
+    pub enabled: bool,
+    pub rules: Vec<RecoveryRule>,
+}
+
+impl Default for RecoveryConfig {
+    fn default() -> Self {
+        let rule = |component: &str, action, after_failures| RecoveryRule {
+            component: component.to_string(),
+            action,
+            after_failures,
+            cooldown_secs: default_cooldown_secs(),
+        };
+        Self {
+            enabled: true,
+            rules: vec![
+                rule("websocket_server", RecoveryAction::RestartWebsocket, 3),
+                rule("ipc_system", RecoveryAction::RebindIpc, 2),
+                rule("processes", RecoveryAction::RestartFailedProcesses, 2),
+            ],
+        }
+    }
+}
+
+/// Outcome of one recovery action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecoveryEvent {
+    pub timestamp: u64,
+    pub component: String,
+    pub action: RecoveryAction,
+
+    /// Consecutive failed checks that triggered the action
+    pub failure_count: u32,
+    pub succeeded: bool,
+    pub message: String,
+}
+
+type RecoveryHandler = Arc<dyn Fn() -> BoxFuture<'static, Result<String, String>> + Send + Sync>;
+
+/// Matches health checks against the recovery rules and runs their actions
+///
+/// The actions are registered by whoever owns the recovered component; a rule
+/// whose action has no handler never fires.
+pub struct RecoveryPolicy {
+    config: RecoveryConfig,
+    handlers: std::sync::RwLock<HashMap<RecoveryAction, RecoveryHandler>>,
+
+    /// When each rule (by index) last fired
+    last_fired: std::sync::Mutex<HashMap<usize, u64>>,
+}
+
+impl RecoveryPolicy {
+    pub fn new(config: RecoveryConfig) -> Self {
+        Self {
+            config,
+            handlers: std::sync::RwLock::new(HashMap::new()),
+            last_fired: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Register how an action is carried out; it resolves to a description of what was done
+    pub fn set_handler<F, Fut>(&self, action: RecoveryAction, handler: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<String, String>> + Send + 'static,
+    {
+        let handler: RecoveryHandler = Arc::new(move || Box::pin(handler()));
+        self.handlers.write().unwrap().insert(action, handler);
+    }
+
+    /// Rules that fire for a health check taken at `now`, marked as fired
+    pub fn due(&self, health_check: &HealthCheck, now: u64) -> Vec<(RecoveryRule, u32)> {
+        if !self.config.enabled {
+            return Vec::new();
+        }
+        let handlers = self.handlers.read().unwrap();
+        let mut last_fired = self.last_fired.lock().unwrap();
+
+        let mut due = Vec::new();
+        for (index, rule) in self.config.rules.iter().enumerate() {
+            let Some(component) = health_check.components.get(&rule.component) else {
+                continue;
+            };
+            if component.status != HealthStatus::Unhealthy
+                || component.failure_count < rule.after_failures.max(1)
+                || !handlers.contains_key(&rule.action)
+            {
+                continue;
+            }
+            let cooling_down = last_fired
+                .get(&index)
+                .is_some_and(|fired| crate::clock::elapsed_secs(*fired, now) < rule.cooldown_secs);
+            if !cooling_down {
+                last_fired.insert(index, now);
+                due.push((rule.clone(), component.failure_count));
+            }
+        }
+        due
+    }
+
+    /// Run the actions of the rules that fire for a health check
+    pub async fn apply(&self, health_check: &HealthCheck) -> Vec<RecoveryEvent> {
+        let mut events = Vec::new();
+        for (rule, failure_count) in self.due(health_check, current_timestamp()) {
+            let handler = self.handlers.read().unwrap().get(&rule.action).cloned();
+            let Some(handler) = handler else {
+                continue;
+            };
+            let (succeeded, message) = match handler().await {
+                Ok(message) => (true, message),
+                Err(message) => (false, message),
+            };
+            events.push(RecoveryEvent {
+                timestamp: current_timestamp(),
+                component: rule.component,
+                action: rule.action,
+                failure_count,
+                succeeded,
+                message,
+            });
+        }
+        events
+    }
+}
+
 /// Determine the worst health status between two statuses
 fn worst_status(status1: HealthStatus, status2: HealthStatus) -> HealthStatus {
     match (status1, status2) {
@@ -580,13 +806,7 @@ fn health_status_to_string(status: &HealthStatus) -> &'static str {
 
 /// Get current timestamp
 fn current_timestamp() -> u64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_else(|_| {
-            log::warn!("System time error in health check, using fallback timestamp");
-            std::time::Duration::from_secs(0)
-        })
-        .as_secs()
+    crate::clock::unix_secs()
 }
 
 #[cfg(test)]
@@ -618,6 +838,13 @@ mod tests {
             metrics_enabled: true,
             cleanup_interval: 600,
             max_task_history: 1000,
+            timezone: Default::default(),
+            budgets: Vec::new(),
+            language: Default::default(),
+            artifacts_dir: None,
+            ids: Default::default(),
+            latency_budgets: Vec::new(),
+            preemption: Default::default(),
         };
         Arc::new(TaskManager::new(config))
     }
@@ -656,7 +883,7 @@ mod tests {
         assert!(result.is_ok());
 
         let health_check = result.unwrap();
-        assert_eq!(health_check.components.len(), 6); // 6つのコンポーネント
+        assert_eq!(health_check.components.len(), 7); // 7つのコンポーネント
                                                       // check_duration_ms は u64 型なので常に0以上
 
         // 各コンポーネントが存在することを確認
@@ -666,6 +893,7 @@ mod tests {
         assert!(health_check.components.contains_key("websocket_server"));
         assert!(health_check.components.contains_key("file_system"));
         assert!(health_check.components.contains_key("persistence"));
+        assert!(health_check.components.contains_key("processes"));
     }
 
     #[tokio::test]
@@ -1011,4 +1239,84 @@ mod tests {
         assert_eq!(component_health2.failure_count, 0);
         assert!(component_health2.last_success.is_some());
     }
+
+    // === 自動復旧テスト ===
+
+    fn check_with(component: &str, status: HealthStatus, failure_count: u32) -> HealthCheck {
+        let mut components = HashMap::new();
+        components.insert(
+            component.to_string(),
+            ComponentHealth {
+                status,
+                message: String::new(),
+                last_success: None,
+                failure_count,
+                response_time_ms: 0,
+            },
+        );
+        HealthCheck {
+            timestamp: 0,
+            overall_status: HealthStatus::Unhealthy,
+            components,
+            check_duration_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_recovery_rules_fire_after_failures_and_cool_down() {
+        let policy = RecoveryPolicy::new(RecoveryConfig::default());
+
+        // 実行手段が登録されていないアクションは発火しない
+        let down = check_with("websocket_server", HealthStatus::Unhealthy, 3);
+        assert!(policy.due(&down, 1_000).is_empty());
+
+        policy.set_handler(RecoveryAction::RestartWebsocket, || async {
+            Ok("restarted".to_string())
+        });
+        let flaky = check_with("websocket_server", HealthStatus::Unhealthy, 2);
+        assert!(policy.due(&flaky, 1_000).is_empty());
+        let degraded = check_with("websocket_server", HealthStatus::Degraded, 5);
+        assert!(policy.due(&degraded, 1_000).is_empty());
+
+        let due = policy.due(&down, 1_000);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].0.action, RecoveryAction::RestartWebsocket);
+        assert_eq!(due[0].1, 3);
+
+        // クールダウン中は再実行しない
+        assert!(policy.due(&down, 1_299).is_empty());
+        assert_eq!(policy.due(&down, 1_300).len(), 1);
+
+        let disabled = RecoveryPolicy::new(RecoveryConfig {
+            enabled: false,
+            ..Default::default()
+        });
+        disabled.set_handler(RecoveryAction::RestartWebsocket, || async {
+            Ok(String::new())
+        });
+        assert!(disabled.due(&down, 1_000).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_recovery_apply_reports_outcome() {
+        let config: RecoveryConfig = serde_yaml::from_str(
+            "rules:\n- { component: ipc_system, action: rebind_ipc, after_failures: 1 }\n",
+        )
+        .unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.rules[0].cooldown_secs, 300);
+
+        let policy = RecoveryPolicy::new(config);
+        policy.set_handler(RecoveryAction::RebindIpc, || async {
+            Err("Address in use".to_string())
+        });
+
+        let events = policy
+            .apply(&check_with("ipc_system", HealthStatus::Unhealthy, 1))
+            .await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].component, "ipc_system");
+        assert!(!events[0].succeeded);
+        assert_eq!(events[0].message, "Address in use");
+    }
 }
@@ -4,7 +4,6 @@
 use super::{NetworkIO, ProcessMetrics, ProcessStatus, SystemMetrics};
 use std::collections::HashMap;
 use std::process::Command;
-use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::{debug, warn};
 
 /// Metrics collector for system and process data
@@ -165,7 +164,7 @@ impl MetricsCollector {
     #[cfg(target_os = "windows")]
     async fn collect_cpu_usage_windows(&self) -> Result<f64, Box<dyn std::error::Error>> {
         let output = Command::new("wmic")
-            .args(&["cpu", "get", "loadpercentage", "/value"])
+            .args(["cpu", "get", "loadpercentage", "/value"])
             .output()?;
 
         let output_str = String::from_utf8(output.stdout)?;
@@ -269,7 +268,7 @@ impl MetricsCollector {
         &self,
     ) -> Result<(u64, u64), Box<dyn std::error::Error>> {
         let output = Command::new("wmic")
-            .args(&[
+            .args([
                 "OS",
                 "get",
                 "TotalVisibleMemorySize,FreePhysicalMemory",
@@ -316,15 +315,8 @@ impl MetricsCollector {
             }
         }
 
-        #[cfg(target_os = "windows")]
-        {
-            let output = Command::new("dir").args(&["/-c"]).output()?;
-
-            // Parse Windows dir output for disk usage
-            // This is a simplified implementation
-            return Ok((0, 0));
-        }
-
+        // Disk usage is not collected on Windows yet
+        #[cfg(not(target_os = "windows"))]
         warn!("Failed to collect disk metrics");
         Ok((0, 0))
     }
@@ -392,6 +384,7 @@ impl MetricsCollector {
 
     /// Find processes related to wezterm-parallel
     async fn find_related_processes(&self) -> Result<Vec<ProcessInfo>, Box<dyn std::error::Error>> {
+        #[cfg_attr(not(unix), allow(unused_mut))]
         let mut processes = Vec::new();
 
         #[cfg(unix)]
@@ -435,6 +428,7 @@ impl MetricsCollector {
     }
 
     /// Extract process name from command
+    #[cfg(unix)]
     fn extract_process_name(command: &str) -> String {
         if let Some(last_slash) = command.rfind('/') {
             command[last_slash + 1..]
@@ -462,7 +456,7 @@ impl MetricsCollector {
         let cached_metrics = self.process_cache.get(&process_info.pid);
         let restart_count = cached_metrics.map(|m| m.restart_count).unwrap_or(0);
         let start_time = cached_metrics
-            .map(|m| current_time - m.uptime)
+            .map(|m| current_time.saturating_sub(m.uptime))
             .unwrap_or(current_time);
 
         let metrics = ProcessMetrics {
@@ -472,7 +466,7 @@ impl MetricsCollector {
             memory_usage: process_info.memory_usage,
             thread_count: self.get_thread_count(process_info.pid).await.unwrap_or(1),
             fd_count: self.get_fd_count(process_info.pid).await.unwrap_or(0),
-            uptime: current_time - start_time,
+            uptime: crate::clock::elapsed_secs(start_time, current_time),
             status: ProcessStatus::Running,
             restart_count,
         };
@@ -542,6 +536,7 @@ impl MetricsCollector {
     }
 
     /// Parse disk size from df output
+    #[cfg(unix)]
     fn parse_disk_size(&self, size_str: &str) -> Result<u64, Box<dyn std::error::Error>> {
         let size_str = size_str.to_uppercase();
         let multiplier = if size_str.ends_with('K') {
@@ -580,13 +575,7 @@ struct ProcessInfo {
 
 /// Get current timestamp
 fn current_timestamp() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_else(|_| {
-            log::warn!("System time error in metrics, using fallback timestamp");
-            std::time::Duration::from_secs(0)
-        })
-        .as_secs()
+    crate::clock::unix_secs()
 }
 
 #[cfg(test)]
@@ -600,6 +589,7 @@ mod tests {
         assert!(collector.network_baseline.is_none());
     }
 
+    #[cfg(unix)]
     #[tokio::test]
     async fn test_disk_size_parsing() {
         let collector = MetricsCollector::new();
@@ -6,14 +6,26 @@ pub mod analytics;
 pub mod health;
 pub mod logger;
 pub mod metrics;
-
+pub mod report;
+pub mod shedding;
+pub mod slo;
+
+use crate::i18n::Language;
+use crate::logging::LogContext;
+use crate::performance::lazy::{Activations, LazySubsystem};
+use crate::room::WorkspaceManager;
+use crate::supervisor::Supervisor;
+use crate::task::TaskManager;
+use analytics::PerformanceBaselines;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex, RwLock};
 
 /// Monitoring system configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct MonitoringConfig {
     /// Enable monitoring system
     pub enabled: bool,
@@ -44,6 +56,78 @@ pub struct MonitoringConfig {
 
     /// Number of log files to retain
     pub log_retention_count: u32,
+
+    /// Where alerts are delivered besides the dashboard
+    #[serde(default)]
+    pub notifications: Vec<NotificationChannelConfig>,
+
+    /// Actions taken when components keep failing their health checks
+    #[serde(default)]
+    pub recovery: RecoveryConfig,
+
+    /// Service level objectives of the health check components
+    #[serde(default)]
+    pub slo: SloConfig,
+}
+
+/// Alert notification channel with its severity filter
+///
+/// ```yaml
+/// notifications:
+///   - type: slack
+///     webhook_url: https://hooks.slack.com/services/T000/B000/XXXX
+///     min_severity: Error
+///   - type: webhook
+///     url: http://localhost:8080/alerts
+///     headers: { Authorization: "Bearer secret" }
+///   - type: desktop
+///     min_severity: Critical
+///     notify_resolved: false
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationChannelConfig {
+    #[serde(flatten)]
+    pub channel: NotificationChannel,
+
+    /// Least severe alert delivered on this channel
+    #[serde(default = "default_min_severity")]
+    pub min_severity: AlertSeverity,
+
+    /// Also deliver resolution notices
+    #[serde(default = "default_notify_resolved")]
+    pub notify_resolved: bool,
+}
+
+/// Alert notification destination
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotificationChannel {
+    /// Print to standard output
+    Console,
+    /// POST the alert as JSON
+    Webhook {
+        url: String,
+        #[serde(default)]
+        headers: std::collections::BTreeMap<String, String>,
+    },
+    /// POST a Slack incoming webhook message (Mattermost accepts the same payload)
+    Slack {
+        webhook_url: String,
+        #[serde(default)]
+        channel: Option<String>,
+        #[serde(default)]
+        username: Option<String>,
+    },
+    /// OS notification (`notify-send` on Linux, Notification Center on macOS)
+    Desktop,
+}
+
+fn default_min_severity() -> AlertSeverity {
+    AlertSeverity::Warning
+}
+
+fn default_notify_resolved() -> bool {
+    true
 }
 
 /// Log format options
@@ -120,6 +204,17 @@ pub struct SystemMetrics {
     pub process_metrics: HashMap<String, ProcessMetrics>,
 }
 
+impl SystemMetrics {
+    /// Memory in use as a percentage of the total
+    pub fn memory_usage_percent(&self) -> f64 {
+        let total = self.memory_usage + self.memory_available;
+        if total == 0 {
+            return 0.0;
+        }
+        self.memory_usage as f64 / total as f64 * 100.0
+    }
+}
+
 /// Network I/O statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkIO {
@@ -184,8 +279,8 @@ pub enum ProcessStatus {
     Restarting,
 }
 
-/// Alert severity levels
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// Alert severity levels, ordered from least to most severe
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum AlertSeverity {
     /// Informational alert
     Info,
@@ -287,7 +382,24 @@ pub struct ComponentHealth {
     pub response_time_ms: u64,
 }
 
+/// Metrics samples kept in memory for the status API
+const METRICS_HISTORY_LIMIT: usize = 120;
+
+/// Buffered metrics samples waiting for alert evaluation
+const ALERT_QUEUE_CAPACITY: usize = 16;
+
+/// Recovery actions kept for the status API
+const RECOVERY_HISTORY_LIMIT: usize = 50;
+
+/// Name analytics' start is recorded under
+pub const ANALYTICS_SUBSYSTEM: &str = "analytics";
+
 /// Monitoring system manager
+///
+/// `start` spawns supervised background tasks that collect system metrics,
+/// evaluate them against the alert thresholds and run health checks. Their
+/// results feed the analytics baselines and the aggregated [`MonitoringStatus`].
+#[derive(Clone)]
 pub struct MonitoringManager {
     /// Configuration
     config: MonitoringConfig,
@@ -302,11 +414,69 @@ pub struct MonitoringManager {
     health_status: Arc<RwLock<Option<HealthCheck>>>,
 
     /// Metrics history for analytics
-    #[allow(dead_code)]
     metrics_history: Arc<RwLock<Vec<SystemMetrics>>>,
 
     /// Alert history
     alert_history: Arc<RwLock<Vec<Alert>>>,
+
+    /// Threshold evaluation and notification delivery
+    alert_manager: Arc<AlertManager>,
+
+    /// Ids of active alerts raised by threshold evaluation, as opposed to manual ones
+    evaluated_alerts: Arc<RwLock<HashSet<String>>>,
+
+    /// Baselines and reports over everything collected
+    analytics: Arc<AnalyticsManager>,
+
+    /// With lazy initialization, analytics only records from its first use on
+    analytics_subsystem: Option<Arc<LazySubsystem>>,
+
+    /// Component health checks; skipped when not configured
+    health_checker: Option<Arc<HealthCheckManager>>,
+
+    /// Restarts the background tasks and reports them on `/healthz`
+    supervisor: Arc<Supervisor>,
+
+    /// Acts on components that keep failing their health checks
+    recovery: Arc<RecoveryPolicy>,
+
+    /// Recovery actions taken, oldest first
+    recovery_events: Arc<RwLock<Vec<RecoveryEvent>>>,
+
+    /// Memory reclaimed by recovery is reported as load shedding
+    shed_log: Option<Arc<ShedLog>>,
+
+    /// Error budgets of the components with objectives
+    slo: Arc<SloTracker>,
+}
+
+/// Aggregated monitoring state served on `/status/monitoring`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitoringStatus {
+    pub enabled: bool,
+
+    /// Latest health check, degraded further by active alerts; `Unknown`
+    /// until the first check has run
+    pub overall_status: HealthStatus,
+
+    pub metrics: Option<SystemMetrics>,
+    pub health: Option<HealthCheck>,
+
+    /// Most severe first
+    pub active_alerts: Vec<Alert>,
+
+    pub baselines: PerformanceBaselines,
+
+    /// Metrics samples held in memory
+    pub sample_count: usize,
+
+    /// Recent automated recovery actions, newest first
+    #[serde(default)]
+    pub recovery_events: Vec<RecoveryEvent>,
+
+    /// Error budgets of the configured objectives
+    #[serde(default)]
+    pub slos: Vec<SloStatus>,
 }
 
 impl Default for MonitoringConfig {
@@ -322,6 +492,9 @@ impl Default for MonitoringConfig {
             log_rotation: true,
             max_log_size_mb: 100,
             log_retention_count: 10,
+            notifications: Vec::new(),
+            recovery: RecoveryConfig::default(),
+            slo: SloConfig::default(),
         }
     }
 }
@@ -343,15 +516,80 @@ impl MonitoringManager {
     /// Create new monitoring manager
     pub fn new(config: MonitoringConfig) -> Self {
         Self {
+            alert_manager: Arc::new(Self::build_alert_manager(&config, Language::default())),
+            recovery: Arc::new(RecoveryPolicy::new(config.recovery.clone())),
+            slo: Arc::new(SloTracker::new(config.slo.clone())),
             config,
             current_metrics: Arc::new(RwLock::new(None)),
             active_alerts: Arc::new(RwLock::new(HashMap::new())),
             health_status: Arc::new(RwLock::new(None)),
             metrics_history: Arc::new(RwLock::new(Vec::new())),
             alert_history: Arc::new(RwLock::new(Vec::new())),
+            evaluated_alerts: Arc::new(RwLock::new(HashSet::new())),
+            analytics: Arc::new(AnalyticsManager::new()),
+            analytics_subsystem: None,
+            health_checker: None,
+            supervisor: Arc::new(Supervisor::new()),
+            recovery_events: Arc::new(RwLock::new(Vec::new())),
+            shed_log: None,
         }
     }
 
+    /// Language of the alert messages
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.alert_manager = Arc::new(Self::build_alert_manager(&self.config, language));
+        self
+    }
+
+    /// Run health checks against the workspace and task managers, and
+    /// include the task throughput in the analytics trends
+    pub fn with_health_checks(
+        mut self,
+        workspace_manager: Arc<WorkspaceManager>,
+        task_manager: Arc<TaskManager>,
+    ) -> Self {
+        self.analytics =
+            Arc::new(AnalyticsManager::new().with_task_manager(Arc::clone(&task_manager)));
+        self.health_checker = Some(Arc::new(HealthCheckManager::new(
+            workspace_manager,
+            task_manager,
+            self.health_check_interval(),
+        )));
+        self
+    }
+
+    /// Supervise the background tasks with a shared supervisor
+    pub fn with_supervisor(mut self, supervisor: Arc<Supervisor>) -> Self {
+        self.supervisor = supervisor;
+        self
+    }
+
+    /// Record successful memory reclaims as load shedding
+    pub fn with_shed_log(mut self, shed_log: Arc<ShedLog>) -> Self {
+        self.shed_log = Some(shed_log);
+        self
+    }
+
+    /// Recovery policy; its actions are registered by the owners of the components
+    pub fn recovery(&self) -> Arc<RecoveryPolicy> {
+        Arc::clone(&self.recovery)
+    }
+
+    fn build_alert_manager(config: &MonitoringConfig, language: Language) -> AlertManager {
+        let mut alert_manager =
+            AlertManager::new(config.alert_thresholds.clone()).with_language(language);
+        alert_manager.add_notification_channels(&config.notifications);
+        alert_manager
+    }
+
+    fn metrics_interval(&self) -> Duration {
+        Duration::from_secs(self.config.metrics_interval.max(1))
+    }
+
+    fn health_check_interval(&self) -> Duration {
+        Duration::from_secs(self.config.health_check_interval.max(1))
+    }
+
     /// Start monitoring system
     pub async fn start(&self) -> Result<(), Box<dyn std::error::Error>> {
         if !self.config.enabled {
@@ -364,14 +602,17 @@ impl MonitoringManager {
         // Initialize logger
         self.initialize_logger().await?;
 
+        // Metrics samples flow from collection to alert evaluation
+        let (metrics_tx, metrics_rx) = mpsc::channel(ALERT_QUEUE_CAPACITY);
+
         // Start metrics collection
-        self.start_metrics_collection().await?;
+        self.start_metrics_collection(metrics_tx).await?;
 
         // Start health checks
         self.start_health_checks().await?;
 
         // Start alert processing
-        self.start_alert_processing().await?;
+        self.start_alert_processing(metrics_rx).await?;
 
         tracing::info!("Monitoring system started successfully");
         Ok(())
@@ -385,26 +626,353 @@ impl MonitoringManager {
     }
 
     /// Start metrics collection task
-    async fn start_metrics_collection(&self) -> Result<(), Box<dyn std::error::Error>> {
-        // Start background task for metrics collection
-        // This will be implemented in metrics.rs
+    async fn start_metrics_collection(
+        &self,
+        metrics_tx: mpsc::Sender<SystemMetrics>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let interval = self.metrics_interval();
+        let collector = Arc::new(Mutex::new(MetricsCollector::new()));
+        let manager = self.clone();
+
+        let _metrics_handle = self
+            .supervisor
+            .spawn("monitoring_metrics", interval, move |task| {
+                let collector = Arc::clone(&collector);
+                let manager = manager.clone();
+                let metrics_tx = metrics_tx.clone();
+                async move {
+                    let mut ticker = tokio::time::interval(interval);
+                    loop {
+                        ticker.tick().await;
+                        let collected = collector
+                            .lock()
+                            .await
+                            .collect_metrics()
+                            .await
+                            .map_err(|e| e.to_string());
+                        match collected {
+                            Ok(metrics) => {
+                                manager.record_metrics(metrics.clone()).await;
+                                // A slow evaluation skips samples instead of delaying collection
+                                if metrics_tx.try_send(metrics).is_err() {
+                                    tracing::debug!("Alert evaluation is behind; sample skipped");
+                                }
+                                task.ran();
+                            }
+                            Err(e) => tracing::warn!("Failed to collect system metrics: {}", e),
+                        }
+                    }
+                }
+            });
         Ok(())
     }
 
     /// Start health check task
     async fn start_health_checks(&self) -> Result<(), Box<dyn std::error::Error>> {
-        // Start background health check task
-        // This will be implemented in health.rs
+        let Some(health_checker) = self.health_checker.clone() else {
+            tracing::debug!("No health checks configured");
+            return Ok(());
+        };
+        let interval = self.health_check_interval();
+        let manager = self.clone();
+
+        let _health_handle = self
+            .supervisor
+            .spawn("monitoring_health", interval, move |task| {
+                let health_checker = Arc::clone(&health_checker);
+                let manager = manager.clone();
+                async move {
+                    let mut ticker = tokio::time::interval(interval);
+                    loop {
+                        ticker.tick().await;
+                        let checked = health_checker
+                            .perform_health_check()
+                            .await
+                            .map_err(|e| e.to_string());
+                        match checked {
+                            Ok(health_check) => {
+                                manager.record_health_check(health_check).await;
+                                task.ran();
+                            }
+                            Err(e) => tracing::warn!("Health check failed: {}", e),
+                        }
+                    }
+                }
+            });
         Ok(())
     }
 
     /// Start alert processing task
-    async fn start_alert_processing(&self) -> Result<(), Box<dyn std::error::Error>> {
-        // Start alert processing and notification
-        // This will be implemented in alerts.rs
+    async fn start_alert_processing(
+        &self,
+        metrics_rx: mpsc::Receiver<SystemMetrics>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // Shared so that a restarted task keeps reading the same channel
+        let metrics_rx = Arc::new(Mutex::new(metrics_rx));
+        let manager = self.clone();
+
+        let _alerts_handle =
+            self.supervisor
+                .spawn("monitoring_alerts", self.metrics_interval(), move |task| {
+                    let metrics_rx = Arc::clone(&metrics_rx);
+                    let manager = manager.clone();
+                    async move {
+                        let mut metrics_rx = metrics_rx.lock().await;
+                        while let Some(metrics) = metrics_rx.recv().await {
+                            manager.evaluate_alerts(&metrics).await;
+                            task.ran();
+                        }
+                    }
+                });
         Ok(())
     }
 
+    /// Store a metrics sample as the current one and in the history
+    async fn record_metrics(&self, metrics: SystemMetrics) {
+        *self.current_metrics.write().await = Some(metrics.clone());
+
+        let mut history = self.metrics_history.write().await;
+        history.push(metrics.clone());
+        if history.len() > METRICS_HISTORY_LIMIT {
+            let excess = history.len() - METRICS_HISTORY_LIMIT;
+            history.drain(0..excess);
+        }
+        drop(history);
+
+        if let Some(analytics) = self.recording_analytics() {
+            analytics.add_metrics(metrics).await;
+        }
+    }
+
+    /// Evaluate a metrics sample and mirror the raised and resolved alerts
+    async fn evaluate_alerts(&self, metrics: &SystemMetrics) {
+        let evaluated = self
+            .alert_manager
+            .evaluate_metrics(metrics)
+            .await
+            .map_err(|e| e.to_string());
+        if let Err(e) = evaluated {
+            tracing::error!("Failed to evaluate metrics for alerts: {}", e);
+        }
+
+        let current: HashMap<String, Alert> = self
+            .alert_manager
+            .get_active_alerts()
+            .await
+            .into_iter()
+            .map(|alert| (alert.id.clone(), alert))
+            .collect();
+
+        let mut evaluated_alerts = self.evaluated_alerts.write().await;
+        let resolved: Vec<String> = evaluated_alerts
+            .iter()
+            .filter(|id| !current.contains_key(*id))
+            .cloned()
+            .collect();
+        for alert_id in resolved {
+            evaluated_alerts.remove(&alert_id);
+            if let Some(alert) = self.take_resolved(&alert_id).await {
+                if let Some(analytics) = self.recording_analytics() {
+                    analytics.add_alert(alert).await;
+                }
+            }
+        }
+        for (alert_id, alert) in current {
+            if evaluated_alerts.insert(alert_id) {
+                self.create_alert(alert.clone()).await;
+                if let Some(analytics) = self.recording_analytics() {
+                    analytics.add_alert(alert).await;
+                }
+            }
+        }
+    }
+
+    async fn record_health_check(&self, health_check: HealthCheck) {
+        *self.health_status.write().await = Some(health_check.clone());
+        self.recover(&health_check).await;
+        self.track_slos(&health_check).await;
+        if let Some(analytics) = self.recording_analytics() {
+            analytics.add_health_check(health_check).await;
+        }
+    }
+
+    /// Run the recovery actions due for a health check and raise an alert for
+    /// each; the alert is resolved once its component is healthy again
+    async fn recover(&self, health_check: &HealthCheck) {
+        for (component, health) in &health_check.components {
+            if health.status == HealthStatus::Healthy {
+                if let Some(alert) = self.take_resolved(&recovery_alert_id(component)).await {
+                    if let Some(analytics) = self.recording_analytics() {
+                        analytics.add_alert(alert).await;
+                    }
+                }
+            }
+        }
+
+        for event in self.recovery.apply(health_check).await {
+            let log_context = LogContext::new("monitoring", "recovery")
+                .with_entity_id(&event.component)
+                .with_metadata("action", serde_json::json!(event.action))
+                .with_metadata("failure_count", serde_json::json!(event.failure_count));
+            let message = if event.succeeded {
+                crate::log_info!(
+                    log_context,
+                    "Recovered {} after {} failed health checks: {}",
+                    event.component,
+                    event.failure_count,
+                    event.message
+                );
+                format!(
+                    "Automated recovery of {}: {}",
+                    event.component, event.message
+                )
+            } else {
+                crate::log_error!(
+                    log_context,
+                    "Recovery of {} failed: {}",
+                    event.component,
+                    event.message
+                );
+                format!(
+                    "Automated recovery of {} failed: {}",
+                    event.component, event.message
+                )
+            };
+
+            let mut data = HashMap::new();
+            data.insert("action".to_string(), serde_json::json!(event.action));
+            data.insert(
+                "failure_count".to_string(),
+                serde_json::json!(event.failure_count),
+            );
+            data.insert("succeeded".to_string(), serde_json::json!(event.succeeded));
+            let alert = Alert {
+                id: recovery_alert_id(&event.component),
+                severity: if event.succeeded {
+                    AlertSeverity::Warning
+                } else {
+                    AlertSeverity::Critical
+                },
+                category: "recovery".to_string(),
+                message,
+                component: Some(event.component.clone()),
+                timestamp: event.timestamp,
+                data,
+                resolved: false,
+                resolved_at: None,
+            };
+            self.create_alert(alert.clone()).await;
+            if let Some(analytics) = self.recording_analytics() {
+                analytics.add_alert(alert).await;
+            }
+            if event.succeeded && event.action == RecoveryAction::TriggerGc {
+                self.record_reclaim(&event.component).await;
+            }
+
+            let mut events = self.recovery_events.write().await;
+            events.push(event);
+            if events.len() > RECOVERY_HISTORY_LIMIT {
+                let excess = events.len() - RECOVERY_HISTORY_LIMIT;
+                events.drain(0..excess);
+            }
+        }
+    }
+
+    /// Count a health check against the objectives and raise an alert for
+    /// each error budget burning too fast; it is resolved once the burn slows
+    async fn track_slos(&self, health_check: &HealthCheck) {
+        if !self.slo.is_enabled() {
+            return;
+        }
+        self.slo.record(health_check);
+
+        for status in self.slo.status(health_check.timestamp) {
+            let alert_id = status.alert_id();
+            let Some(severity) = status.alert.clone() else {
+                if let Some(alert) = self.take_resolved(&alert_id).await {
+                    if let Some(analytics) = self.recording_analytics() {
+                        analytics.add_alert(alert).await;
+                    }
+                }
+                continue;
+            };
+            let raised = self
+                .active_alerts
+                .read()
+                .await
+                .get(&alert_id)
+                .is_some_and(|alert| alert.severity == severity);
+            if raised {
+                continue;
+            }
+
+            let message = status.alert_message(self.slo.config());
+            let log_context = LogContext::new("monitoring", "slo_burn")
+                .with_entity_id(&status.component)
+                .with_metadata("indicator", serde_json::json!(status.indicator))
+                .with_metadata(
+                    "budget_remaining",
+                    serde_json::json!(status.budget_remaining),
+                );
+            crate::log_warn!(log_context, "{}", message);
+
+            let mut data = HashMap::new();
+            data.insert("indicator".to_string(), serde_json::json!(status.indicator));
+            data.insert("target".to_string(), serde_json::json!(status.target));
+            data.insert(
+                "budget_remaining".to_string(),
+                serde_json::json!(status.budget_remaining),
+            );
+            data.insert(
+                "burn_rates".to_string(),
+                serde_json::json!(status.burn_rates),
+            );
+            let alert = Alert {
+                id: alert_id,
+                severity,
+                category: "slo".to_string(),
+                message,
+                component: Some(status.component.clone()),
+                timestamp: health_check.timestamp,
+                data,
+                resolved: false,
+                resolved_at: None,
+            };
+            self.create_alert(alert.clone()).await;
+            if let Some(analytics) = self.recording_analytics() {
+                analytics.add_alert(alert).await;
+            }
+        }
+    }
+
+    /// Error budgets of the configured objectives
+    pub fn slo_status(&self) -> Vec<SloStatus> {
+        self.slo.status(utils::current_timestamp())
+    }
+
+    async fn record_reclaim(&self, component: &str) {
+        let Some(shed_log) = &self.shed_log else {
+            return;
+        };
+        let memory_percent = self
+            .current_metrics
+            .read()
+            .await
+            .as_ref()
+            .map(SystemMetrics::memory_usage_percent)
+            .unwrap_or_default();
+        shed_log.record(
+            "memory_recovery",
+            ShedAction::Reclaimed,
+            ShedTrigger::new(
+                "memory_usage_percent",
+                memory_percent,
+                self.config.alert_thresholds.memory_usage,
+            ),
+            component,
+        );
+    }
+
     /// Get current system metrics
     pub async fn get_current_metrics(&self) -> Option<SystemMetrics> {
         let metrics = self.current_metrics.read().await;
@@ -423,6 +991,80 @@ impl MonitoringManager {
         health.clone()
     }
 
+    /// Analytics over the collected metrics, alerts and health checks
+    pub fn analytics(&self) -> Arc<AnalyticsManager> {
+        Arc::clone(&self.analytics)
+    }
+
+    /// Hold back analytics until [`activate_analytics`](Self::activate_analytics);
+    /// nothing collected before is recorded
+    pub fn with_lazy_analytics(mut self, activations: Arc<Activations>) -> Self {
+        self.analytics_subsystem = Some(Arc::new(LazySubsystem::new(
+            ANALYTICS_SUBSYSTEM,
+            activations,
+            || async {},
+        )));
+        self
+    }
+
+    /// Start recording analytics, when held back by lazy initialization
+    pub async fn activate_analytics(&self, trigger: &str) {
+        if let Some(ref subsystem) = self.analytics_subsystem {
+            subsystem.activate(trigger).await;
+        }
+    }
+
+    /// Analytics, unless it has not been started yet
+    fn recording_analytics(&self) -> Option<&AnalyticsManager> {
+        self.analytics_subsystem
+            .as_ref()
+            .map_or(true, |subsystem| subsystem.is_active())
+            .then_some(&*self.analytics)
+    }
+
+    /// Metrics, health and alerts in one snapshot for the dashboard
+    pub async fn status(&self) -> MonitoringStatus {
+        let health = self.get_health_status().await;
+        let mut active_alerts = self.get_active_alerts().await;
+        active_alerts.sort_by(|a, b| {
+            b.severity
+                .cmp(&a.severity)
+                .then(b.timestamp.cmp(&a.timestamp))
+        });
+
+        let overall_status = active_alerts.iter().fold(
+            health
+                .as_ref()
+                .map_or(HealthStatus::Unknown, |check| check.overall_status.clone()),
+            |status, alert| match alert.severity {
+                AlertSeverity::Critical => worse(status, HealthStatus::Unhealthy),
+                AlertSeverity::Error | AlertSeverity::Warning => {
+                    worse(status, HealthStatus::Degraded)
+                }
+                AlertSeverity::Info => status,
+            },
+        );
+
+        MonitoringStatus {
+            enabled: self.config.enabled,
+            overall_status,
+            metrics: self.get_current_metrics().await,
+            health,
+            active_alerts,
+            baselines: self.analytics.get_baselines().await,
+            sample_count: self.metrics_history.read().await.len(),
+            recovery_events: self
+                .recovery_events
+                .read()
+                .await
+                .iter()
+                .rev()
+                .cloned()
+                .collect(),
+            slos: self.slo_status(),
+        }
+    }
+
     /// Create manual alert
     pub async fn create_alert(&self, alert: Alert) {
         let mut alerts = self.active_alerts.write().await;
@@ -439,35 +1081,251 @@ impl MonitoringManager {
 
     /// Resolve alert
     pub async fn resolve_alert(&self, alert_id: &str) {
+        self.take_resolved(alert_id).await;
+    }
+
+    /// Move an active alert to the history as resolved
+    async fn take_resolved(&self, alert_id: &str) -> Option<Alert> {
         let mut alerts = self.active_alerts.write().await;
-        if let Some(mut alert) = alerts.remove(alert_id) {
-            alert.resolved = true;
-            alert.resolved_at = Some(utils::current_timestamp());
+        let mut alert = alerts.remove(alert_id)?;
+        alert.resolved = true;
+        alert.resolved_at = Some(utils::current_timestamp());
 
-            let mut history = self.alert_history.write().await;
-            history.push(alert);
+        let mut history = self.alert_history.write().await;
+        history.push(alert.clone());
+        Some(alert)
+    }
+}
+
+fn recovery_alert_id(component: &str) -> String {
+    format!("recovery_{component}")
+}
+
+/// The more severe of two health states; unlike the health checks, `Unknown`
+/// gives way to any known state
+fn worse(current: HealthStatus, other: HealthStatus) -> HealthStatus {
+    fn rank(status: &HealthStatus) -> u8 {
+        match status {
+            HealthStatus::Unknown => 0,
+            HealthStatus::Healthy => 1,
+            HealthStatus::Degraded => 2,
+            HealthStatus::Unhealthy => 3,
         }
     }
+    if rank(&other) > rank(&current) {
+        other
+    } else {
+        current
+    }
 }
 
 /// Utility functions
 pub mod utils {
-    use std::time::{SystemTime, UNIX_EPOCH};
-
     pub fn current_timestamp() -> u64 {
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_else(|_| {
-                log::warn!("System time error in monitoring, using fallback timestamp");
-                std::time::Duration::from_secs(0)
-            })
-            .as_secs()
+        crate::clock::unix_secs()
     }
 }
 
 // Re-export public types from submodules
-pub use alerts::{AlertManager, AlertNotificationSender, ConsoleAlertSender};
-pub use analytics::{AnalyticsManager, AnalyticsReport};
-pub use health::HealthCheckManager;
+pub use alerts::{
+    AlertManager, AlertNotificationSender, ConsoleAlertSender, DesktopAlertSender,
+    FilteredAlertSender, SlackAlertSender, WebhookAlertSender,
+};
+pub use analytics::{AnalyticsManager, AnalyticsReport, TimeRange};
+pub use health::{
+    HealthCheckManager, RecoveryAction, RecoveryConfig, RecoveryEvent, RecoveryPolicy, RecoveryRule,
+};
 pub use logger::{LogEntry, LogStats, LoggingManager};
 pub use metrics::MetricsCollector;
+pub use report::{AnalyticsFormat, ChartSeries};
+pub use shedding::{ShedAction, ShedEvent, ShedLog, ShedReport, ShedSummary, ShedTrigger};
+pub use slo::{SloConfig, SloObjective, SloStatus, SloTracker};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(timestamp: u64, cpu_usage: f64) -> SystemMetrics {
+        SystemMetrics {
+            timestamp,
+            cpu_usage,
+            memory_usage: 1024,
+            memory_available: 3072,
+            disk_usage: 1024,
+            disk_available: 3072,
+            network_io: NetworkIO {
+                bytes_received: 0,
+                bytes_sent: 0,
+                packets_received: 0,
+                packets_sent: 0,
+            },
+            process_metrics: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_status_follows_evaluated_alerts() {
+        let manager = MonitoringManager::new(MonitoringConfig::default());
+        let status = manager.status().await;
+        assert_eq!(status.overall_status, HealthStatus::Unknown);
+        assert!(status.metrics.is_none());
+
+        let high = sample(1_000, 95.0);
+        manager.record_metrics(high.clone()).await;
+        manager.evaluate_alerts(&high).await;
+
+        let status = manager.status().await;
+        assert_eq!(status.overall_status, HealthStatus::Degraded);
+        assert_eq!(status.sample_count, 1);
+        assert_eq!(status.active_alerts.len(), 1);
+        assert_eq!(status.active_alerts[0].id, "system_cpu_high");
+
+        // A manual alert is not touched by threshold evaluation
+        manager
+            .create_alert(Alert {
+                id: "manual".to_string(),
+                severity: AlertSeverity::Info,
+                category: "Manual".to_string(),
+                message: "note".to_string(),
+                component: None,
+                timestamp: 1_001,
+                data: HashMap::new(),
+                resolved: false,
+                resolved_at: None,
+            })
+            .await;
+
+        let low = sample(1_030, 10.0);
+        manager.record_metrics(low.clone()).await;
+        manager.evaluate_alerts(&low).await;
+
+        let status = manager.status().await;
+        let ids: Vec<&str> = status.active_alerts.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, vec!["manual"]);
+        assert_eq!(status.sample_count, 2);
+        assert_eq!(status.metrics.unwrap().timestamp, 1_030);
+
+        let history = manager.alert_history.read().await;
+        assert!(history
+            .iter()
+            .any(|alert| alert.id == "system_cpu_high" && alert.resolved));
+    }
+
+    #[tokio::test]
+    async fn test_recovery_raises_and_resolves_alert() {
+        let manager = MonitoringManager::new(MonitoringConfig::default());
+        manager
+            .recovery()
+            .set_handler(RecoveryAction::RestartWebsocket, || async {
+                Ok("Dashboard server restarted".to_string())
+            });
+        let check = |status: HealthStatus, failure_count| HealthCheck {
+            timestamp: 0,
+            overall_status: status.clone(),
+            components: HashMap::from([(
+                "websocket_server".to_string(),
+                ComponentHealth {
+                    status,
+                    message: String::new(),
+                    last_success: None,
+                    failure_count,
+                    response_time_ms: 0,
+                },
+            )]),
+            check_duration_ms: 0,
+        };
+
+        manager
+            .record_health_check(check(HealthStatus::Unhealthy, 3))
+            .await;
+        let status = manager.status().await;
+        assert_eq!(status.recovery_events.len(), 1);
+        assert!(status.recovery_events[0].succeeded);
+        assert_eq!(status.active_alerts[0].id, "recovery_websocket_server");
+        assert_eq!(status.active_alerts[0].category, "recovery");
+
+        manager
+            .record_health_check(check(HealthStatus::Healthy, 0))
+            .await;
+        assert!(manager.status().await.active_alerts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_lazy_analytics_records_from_activation() {
+        let activations = Arc::new(Activations::new());
+        let manager = MonitoringManager::new(MonitoringConfig::default())
+            .with_lazy_analytics(Arc::clone(&activations));
+        let all = TimeRange {
+            start: 0,
+            end: u64::MAX,
+            duration_hours: 0,
+        };
+
+        manager.record_metrics(sample(1_000, 95.0)).await;
+        assert!(!activations.is_active(ANALYTICS_SUBSYSTEM));
+
+        manager.activate_analytics("ipc").await;
+        manager.record_metrics(sample(1_030, 40.0)).await;
+        let report = manager.analytics().generate_report(all).await;
+        assert_eq!(report.performance.peaks.max_cpu_usage, 40.0);
+        assert_eq!(
+            activations.list()[0].activation.as_ref().unwrap().trigger,
+            "ipc"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_slo_burn_raises_and_resolves_alert() {
+        let config: MonitoringConfig = serde_yaml::from_str(
+            "slo:\n  objectives:\n    - component: websocket_server\n      availability: 99.0\n",
+        )
+        .unwrap();
+        let manager = MonitoringManager::new(config);
+        let check = |timestamp, status: HealthStatus| HealthCheck {
+            timestamp,
+            overall_status: status.clone(),
+            components: HashMap::from([(
+                "websocket_server".to_string(),
+                ComponentHealth {
+                    status,
+                    message: String::new(),
+                    last_success: None,
+                    failure_count: 1,
+                    response_time_ms: 0,
+                },
+            )]),
+            check_duration_ms: 0,
+        };
+
+        manager
+            .record_health_check(check(1_000_000, HealthStatus::Unhealthy))
+            .await;
+        let status = manager.status().await;
+        assert_eq!(status.active_alerts.len(), 1);
+        assert_eq!(
+            status.active_alerts[0].id,
+            "slo_websocket_server_availability"
+        );
+        assert_eq!(status.active_alerts[0].severity, AlertSeverity::Critical);
+        assert_eq!(status.slos.len(), 1);
+
+        // Enough healthy checks slow the burn below both thresholds
+        for minute in 1..=20 {
+            manager
+                .record_health_check(check(1_000_000 + minute * 60, HealthStatus::Healthy))
+                .await;
+        }
+        assert!(manager.status().await.active_alerts.is_empty());
+    }
+
+    #[test]
+    fn test_monitoring_config_defaults() {
+        let config: MonitoringConfig =
+            serde_yaml::from_str("metrics_interval: 10\nnotifications:\n  - type: console\n")
+                .unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.metrics_interval, 10);
+        assert_eq!(config.health_check_interval, 60);
+        assert_eq!(config.notifications.len(), 1);
+    }
+}
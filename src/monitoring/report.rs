@@ -0,0 +1,604 @@
+// WezTerm Multi-Process Development Framework - Analytics Report Export
+// Renders analytics reports as Markdown or standalone HTML pages
+//
+// HTML reports embed their chart data as JSON (`<script id="chart-data">`)
+// and draw each series as an inline SVG, so they open without network access.
+
+use super::analytics::{AnalyticsReport, TimeRange, TrendData};
+use crate::config::Timezone;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+/// Lookback of a report when no range is given
+pub const DEFAULT_RANGE: &str = "7d";
+
+/// Write an exported report, creating its directory
+pub fn write_report(path: &std::path::Path, content: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, content)
+}
+
+/// Output format of an analytics export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnalyticsFormat {
+    Markdown,
+    Html,
+    Json,
+}
+
+impl std::str::FromStr for AnalyticsFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "markdown" | "md" => Ok(AnalyticsFormat::Markdown),
+            "html" => Ok(AnalyticsFormat::Html),
+            "json" => Ok(AnalyticsFormat::Json),
+            _ => Err(format!(
+                "Unsupported report format '{value}': expected markdown, html or json"
+            )),
+        }
+    }
+}
+
+impl AnalyticsFormat {
+    /// File extension of exported reports
+    pub fn extension(&self) -> &'static str {
+        match self {
+            AnalyticsFormat::Markdown => "md",
+            AnalyticsFormat::Html => "html",
+            AnalyticsFormat::Json => "json",
+        }
+    }
+}
+
+impl TimeRange {
+    /// The `range` up to `now`: `Nh`, `Nd` or `Nw` (hours, days or weeks)
+    pub fn last(range: &str, now: u64) -> Result<Self, String> {
+        let range = range.trim().to_ascii_lowercase();
+        let invalid = || format!("Invalid report range '{range}': expected Nh, Nd or Nw");
+        let (count, unit) = range.split_at(range.len().saturating_sub(1));
+        let count: u64 = count.parse().map_err(|_| invalid())?;
+        let hours = match unit {
+            "h" => count,
+            "d" => count * 24,
+            "w" => count * 24 * 7,
+            _ => return Err(invalid()),
+        };
+        if hours == 0 {
+            return Err(invalid());
+        }
+        Ok(Self {
+            start: now.saturating_sub(hours * 3600),
+            end: now,
+            duration_hours: hours,
+        })
+    }
+}
+
+/// One chart of the report: a metric over time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChartSeries {
+    pub metric: String,
+
+    /// `[timestamp, value]` pairs in time order
+    pub points: Vec<(u64, f64)>,
+}
+
+impl AnalyticsReport {
+    /// Render the report; times are shown in `timezone`
+    pub fn render(&self, format: AnalyticsFormat, timezone: Timezone) -> Result<String, String> {
+        match format {
+            AnalyticsFormat::Json => serde_json::to_string_pretty(self).map_err(|e| e.to_string()),
+            AnalyticsFormat::Markdown => Ok(self.to_markdown(timezone)),
+            AnalyticsFormat::Html => self.to_html(timezone),
+        }
+    }
+
+    /// Performance and usage trends by metric, and alerts counted per hour
+    pub fn chart_series(&self) -> Vec<ChartSeries> {
+        let mut series: BTreeMap<String, Vec<(u64, f64)>> = BTreeMap::new();
+        for point in self
+            .trends
+            .performance_trends
+            .iter()
+            .chain(&self.trends.usage_trends)
+        {
+            series
+                .entry(point.metric.clone())
+                .or_default()
+                .push((point.timestamp, point.value));
+        }
+        if !self.trends.error_trends.is_empty() {
+            series.insert(
+                "alerts_per_hour".to_string(),
+                per_hour(&self.trends.error_trends),
+            );
+        }
+        series
+            .into_iter()
+            .map(|(metric, mut points)| {
+                points.sort_by_key(|(timestamp, _)| *timestamp);
+                ChartSeries { metric, points }
+            })
+            .collect()
+    }
+
+    fn period(&self, timezone: Timezone) -> String {
+        format!(
+            "{} – {} ({}h)",
+            timezone.format(self.time_range.start, TIME_FORMAT),
+            timezone.format(self.time_range.end, TIME_FORMAT),
+            self.time_range.duration_hours
+        )
+    }
+
+    /// Key figures as label/value rows, shared by both formats
+    fn sections(&self) -> Vec<(&'static str, Vec<(String, String)>)> {
+        let performance = &self.performance;
+        let reliability = &self.reliability;
+        let utilization = &self.usage.resource_utilization;
+        let mut error_rates: Vec<(String, String)> = reliability
+            .error_rates
+            .iter()
+            .map(|(component, rate)| (format!("Error rate: {component}"), percent(*rate)))
+            .collect();
+        error_rates.sort();
+
+        vec![
+            (
+                "Summary",
+                vec![
+                    row("Overall health", score(self.summary.overall_health_score)),
+                    row("Performance", score(performance.performance_score)),
+                    row("Reliability", score(reliability.reliability_score)),
+                    row(
+                        "Resource efficiency",
+                        score(self.summary.resource_efficiency),
+                    ),
+                ],
+            ),
+            (
+                "Performance",
+                vec![
+                    row("Average CPU", percent(performance.averages.cpu_usage)),
+                    row("Peak CPU", percent(performance.peaks.max_cpu_usage)),
+                    row("Average memory", bytes(performance.averages.memory_usage)),
+                    row("Peak memory", bytes(performance.peaks.max_memory_usage)),
+                    row("Average disk", bytes(performance.averages.disk_usage)),
+                    row(
+                        "Average response time",
+                        format!("{} ms", performance.averages.response_time),
+                    ),
+                    row("Stability", score(performance.variance.stability_score)),
+                ],
+            ),
+            ("Reliability", {
+                let mut rows = vec![
+                    row("Uptime", percent(reliability.uptime_percentage)),
+                    row("MTBF", format!("{:.1} h", reliability.mtbf_hours)),
+                    row("MTTR", format!("{:.1} min", reliability.mttr_minutes)),
+                    row(
+                        "Response time p95 / p99",
+                        format!(
+                            "{} / {} ms",
+                            reliability.sli_metrics.response_time_p95,
+                            reliability.sli_metrics.response_time_p99
+                        ),
+                    ),
+                ];
+                rows.extend(error_rates);
+                rows
+            }),
+            (
+                "Usage",
+                vec![
+                    row("Active sessions", self.usage.active_sessions.to_string()),
+                    row("CPU utilization", percent(utilization.cpu_utilization)),
+                    row(
+                        "Memory utilization",
+                        percent(utilization.memory_utilization),
+                    ),
+                    row("Disk utilization", percent(utilization.disk_utilization)),
+                    row(
+                        "Peak hours",
+                        self.usage
+                            .behavior_patterns
+                            .peak_usage_hours
+                            .iter()
+                            .map(|hour| format!("{hour:02}:00"))
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    ),
+                ],
+            ),
+        ]
+    }
+
+    /// Findings as titled lists, shared by both formats
+    fn findings(&self, timezone: Timezone) -> Vec<(&'static str, Vec<String>)> {
+        vec![
+            ("Critical issues", self.summary.critical_issues.clone()),
+            (
+                "Bottlenecks",
+                self.performance
+                    .bottlenecks
+                    .iter()
+                    .map(|b| {
+                        format!(
+                            "{:?}: {} {} — {}",
+                            b.severity, b.component, b.metric, b.recommendation
+                        )
+                    })
+                    .collect(),
+            ),
+            ("Key achievements", self.summary.key_achievements.clone()),
+            (
+                "Capacity",
+                self.trends
+                    .capacity_insights
+                    .scaling_recommendations
+                    .iter()
+                    .chain(
+                        &self
+                            .trends
+                            .capacity_insights
+                            .resource_optimization_opportunities,
+                    )
+                    .cloned()
+                    .collect(),
+            ),
+            (
+                "Forecasts",
+                self.trends
+                    .forecasts
+                    .iter()
+                    .map(|f| {
+                        format!(
+                            "{} at {}: {:.1} ({:.0}% interval {:.1}–{:.1})",
+                            f.metric,
+                            timezone.format(f.prediction_timestamp, TIME_FORMAT),
+                            f.predicted_value,
+                            f.confidence_level * 100.0,
+                            f.confidence_interval.0,
+                            f.confidence_interval.1
+                        )
+                    })
+                    .collect(),
+            ),
+        ]
+    }
+
+    fn to_markdown(&self, timezone: Timezone) -> String {
+        let mut md = String::from("# Analytics report\n\n");
+        let _ = writeln!(md, "- Period: {}", self.period(timezone));
+        let _ = writeln!(
+            md,
+            "- Generated: {}",
+            timezone.format(self.generated_at, TIME_FORMAT)
+        );
+
+        for (title, rows) in self.sections() {
+            let _ = writeln!(md, "\n## {title}\n\n| Metric | Value |\n|---|---|");
+            for (label, value) in rows {
+                let _ = writeln!(
+                    md,
+                    "| {} | {} |",
+                    markdown_cell(&label),
+                    markdown_cell(&value)
+                );
+            }
+        }
+
+        for (title, items) in self.findings(timezone) {
+            if items.is_empty() {
+                continue;
+            }
+            let _ = writeln!(md, "\n## {title}\n");
+            for item in items {
+                let _ = writeln!(md, "- {item}");
+            }
+        }
+
+        if !self.recommendations.is_empty() {
+            md.push_str("\n## Recommendations\n");
+            for recommendation in &self.recommendations {
+                let _ = writeln!(
+                    md,
+                    "\n### {} ({:?}, {:?})\n\n{}\n\n- Impact: {}\n- Effort: {}",
+                    recommendation.title,
+                    recommendation.priority,
+                    recommendation.category,
+                    recommendation.description,
+                    recommendation.impact,
+                    recommendation.effort
+                );
+                for (index, step) in recommendation.implementation_steps.iter().enumerate() {
+                    let _ = writeln!(md, "{}. {step}", index + 1);
+                }
+            }
+        }
+
+        let series = self.chart_series();
+        if !series.is_empty() {
+            md.push_str(
+                "\n## Trends\n\n| Metric | Samples | Min | Max | Last |\n|---|---|---|---|---|\n",
+            );
+            for chart in &series {
+                let values = chart.points.iter().map(|(_, value)| *value);
+                let min = values.clone().fold(f64::INFINITY, f64::min);
+                let max = values.clone().fold(f64::NEG_INFINITY, f64::max);
+                let last = chart.points.last().map(|(_, value)| *value).unwrap_or(0.0);
+                let _ = writeln!(
+                    md,
+                    "| {} | {} | {min:.1} | {max:.1} | {last:.1} |",
+                    chart.metric,
+                    chart.points.len()
+                );
+            }
+        }
+        md
+    }
+
+    fn to_html(&self, timezone: Timezone) -> Result<String, String> {
+        let series = self.chart_series();
+        // `</` would end the script element early
+        let chart_data = serde_json::to_string(&series)
+            .map_err(|e| e.to_string())?
+            .replace("</", "<\\/");
+
+        let mut html = String::from(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>Analytics report</title>\n<style>\n\
+             body { font-family: system-ui, sans-serif; margin: 2rem auto; max-width: 960px; color: #1e1e2e; }\n\
+             table { border-collapse: collapse; margin-bottom: 1rem; }\n\
+             th, td { border: 1px solid #ccd; padding: 0.3rem 0.8rem; text-align: left; }\n\
+             .chart { margin: 0.5rem 0 1.5rem; }\n\
+             .chart polyline { fill: none; stroke: #1e66f5; stroke-width: 2; }\n\
+             </style>\n</head>\n<body>\n<h1>Analytics report</h1>\n",
+        );
+        let _ = writeln!(
+            html,
+            "<p>Period: {}<br>Generated: {}</p>",
+            escape(&self.period(timezone)),
+            timezone.format(self.generated_at, TIME_FORMAT)
+        );
+
+        for (title, rows) in self.sections() {
+            let _ = writeln!(html, "<h2>{title}</h2>\n<table>");
+            for (label, value) in rows {
+                let _ = writeln!(
+                    html,
+                    "<tr><th>{}</th><td>{}</td></tr>",
+                    escape(&label),
+                    escape(&value)
+                );
+            }
+            html.push_str("</table>\n");
+        }
+
+        if !series.is_empty() {
+            html.push_str("<h2>Trends</h2>\n");
+            for chart in &series {
+                let _ = writeln!(
+                    html,
+                    "<h3>{}</h3>\n{}",
+                    escape(&chart.metric),
+                    svg_chart(chart)
+                );
+            }
+        }
+
+        for (title, items) in self.findings(timezone) {
+            if items.is_empty() {
+                continue;
+            }
+            let _ = writeln!(html, "<h2>{title}</h2>\n<ul>");
+            for item in items {
+                let _ = writeln!(html, "<li>{}</li>", escape(&item));
+            }
+            html.push_str("</ul>\n");
+        }
+
+        if !self.recommendations.is_empty() {
+            html.push_str("<h2>Recommendations</h2>\n");
+            for recommendation in &self.recommendations {
+                let _ = writeln!(
+                    html,
+                    "<h3>{} ({:?}, {:?})</h3>\n<p>{}</p>\n<p>Impact: {}<br>Effort: {}</p>\n<ol>",
+                    escape(&recommendation.title),
+                    recommendation.priority,
+                    recommendation.category,
+                    escape(&recommendation.description),
+                    escape(&recommendation.impact),
+                    escape(&recommendation.effort)
+                );
+                for step in &recommendation.implementation_steps {
+                    let _ = writeln!(html, "<li>{}</li>", escape(step));
+                }
+                html.push_str("</ol>\n");
+            }
+        }
+
+        let _ = write!(
+            html,
+            "<script type=\"application/json\" id=\"chart-data\">{chart_data}</script>\n</body>\n</html>\n"
+        );
+        Ok(html)
+    }
+}
+
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Chart size in SVG units
+const CHART_WIDTH: f64 = 600.0;
+const CHART_HEIGHT: f64 = 120.0;
+
+/// Line chart of a series scaled to its own range
+fn svg_chart(chart: &ChartSeries) -> String {
+    let (first, last) = match (chart.points.first(), chart.points.last()) {
+        (Some((first, _)), Some((last, _))) => (*first, *last),
+        _ => return String::new(),
+    };
+    let values = chart.points.iter().map(|(_, value)| *value);
+    let min = values.clone().fold(f64::INFINITY, f64::min);
+    let max = values.fold(f64::NEG_INFINITY, f64::max);
+    let span = (last - first).max(1) as f64;
+    let height = if max > min { max - min } else { 1.0 };
+
+    let points: Vec<String> = chart
+        .points
+        .iter()
+        .map(|(timestamp, value)| {
+            let x = (timestamp - first) as f64 / span * CHART_WIDTH;
+            let y = CHART_HEIGHT - (value - min) / height * CHART_HEIGHT;
+            format!("{x:.1},{y:.1}")
+        })
+        .collect();
+    format!(
+        "<svg class=\"chart\" viewBox=\"0 0 {CHART_WIDTH} {CHART_HEIGHT}\" width=\"{CHART_WIDTH}\" height=\"{CHART_HEIGHT}\" role=\"img\" aria-label=\"{} from {min:.1} to {max:.1}\"><polyline points=\"{}\"/></svg>",
+        escape(&chart.metric),
+        points.join(" ")
+    )
+}
+
+/// Count of trend points per hour
+fn per_hour(points: &[TrendData]) -> Vec<(u64, f64)> {
+    let mut hours: BTreeMap<u64, f64> = BTreeMap::new();
+    for point in points {
+        *hours.entry(point.timestamp / 3600 * 3600).or_default() += point.value;
+    }
+    hours.into_iter().collect()
+}
+
+fn row(label: &str, value: String) -> (String, String) {
+    (label.to_string(), value)
+}
+
+fn score(value: f64) -> String {
+    format!("{value:.0} / 100")
+}
+
+fn percent(value: f64) -> String {
+    format!("{value:.1}%")
+}
+
+fn bytes(value: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = value as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+/// Text safe inside a Markdown table cell
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitoring::analytics::AnalyticsManager;
+    use crate::monitoring::{Alert, AlertSeverity, NetworkIO, SystemMetrics};
+
+    async fn sample_report() -> AnalyticsReport {
+        let manager = AnalyticsManager::new();
+        for i in 0..30u64 {
+            manager
+                .add_metrics(SystemMetrics {
+                    timestamp: 1_700_000_000 + i * 60,
+                    cpu_usage: 20.0 + i as f64,
+                    memory_usage: 512 * 1024 * 1024,
+                    memory_available: 1024 * 1024 * 1024,
+                    disk_usage: 0,
+                    disk_available: 0,
+                    network_io: NetworkIO {
+                        bytes_received: 0,
+                        bytes_sent: 0,
+                        packets_received: 0,
+                        packets_sent: 0,
+                    },
+                    process_metrics: Default::default(),
+                })
+                .await;
+        }
+        manager
+            .add_alert(Alert {
+                id: "a-1".to_string(),
+                severity: AlertSeverity::Warning,
+                category: "process".to_string(),
+                message: "Agent <restarted>".to_string(),
+                component: None,
+                timestamp: 1_700_000_100,
+                data: Default::default(),
+                resolved: false,
+                resolved_at: None,
+            })
+            .await;
+        manager
+            .generate_report(TimeRange::last("1w", 1_700_003_600).unwrap())
+            .await
+    }
+
+    #[test]
+    fn test_range_and_format_parsing() {
+        let range = TimeRange::last("7d", 1_000_000).unwrap();
+        assert_eq!((range.start, range.end), (1_000_000 - 7 * 86400, 1_000_000));
+        assert_eq!(TimeRange::last("24H", 100_000).unwrap().duration_hours, 24);
+        assert!(TimeRange::last("0d", 100).is_err());
+        assert!(TimeRange::last("week", 100).is_err());
+
+        assert_eq!("md".parse(), Ok(AnalyticsFormat::Markdown));
+        assert_eq!("HTML".parse(), Ok(AnalyticsFormat::Html));
+        assert!("pdf".parse::<AnalyticsFormat>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_render_markdown_and_html() {
+        let report = sample_report().await;
+        let series = report.chart_series();
+        assert_eq!(series.len(), 3);
+        assert_eq!(series[1].metric, "cpu_usage");
+        assert_eq!(series[1].points.len(), 3);
+        assert_eq!(series[2].metric, "memory_percent");
+
+        let md = report
+            .render(AnalyticsFormat::Markdown, Timezone::Utc)
+            .unwrap();
+        assert!(md.starts_with("# Analytics report\n"));
+        assert!(md.contains("| Average CPU | 34.5% |"), "{md}");
+        assert!(md.contains("| Average memory | 512.0 MiB |"), "{md}");
+        assert!(
+            md.contains("| cpu_usage | 3 | 20.0 | 40.0 | 40.0 |"),
+            "{md}"
+        );
+        assert!(
+            md.contains("- cpu_usage at 2023-11-14 23:42: 100.0 "),
+            "{md}"
+        );
+
+        let html = report.render(AnalyticsFormat::Html, Timezone::Utc).unwrap();
+        assert!(html.contains("<polyline points=\"0.0,120.0 "), "{html}");
+        let data = html
+            .split("<script type=\"application/json\" id=\"chart-data\">")
+            .nth(1)
+            .and_then(|rest| rest.split("</script>").next())
+            .unwrap();
+        let embedded: Vec<ChartSeries> = serde_json::from_str(data).unwrap();
+        assert_eq!(embedded, series);
+    }
+}
@@ -0,0 +1,307 @@
+// WezTerm Multi-Process Development Framework - Load Shedding Log
+// Records what admission control and pressure responses rejected, deferred,
+// paused or reclaimed, grouped into incidents, for tuning the limits
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+
+/// Shedding of the same kind this many seconds apart belongs to one incident
+pub const INCIDENT_GAP_SECS: u64 = 60;
+
+/// Incidents kept in memory, oldest dropped first
+const SHED_HISTORY_LIMIT: usize = 200;
+
+/// Distinct items (operations, task IDs, ...) kept per incident
+const SAMPLE_ITEMS: usize = 20;
+
+/// What was done to the work that could not be taken on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShedAction {
+    /// Refused outright (rate limit, full queue)
+    Rejected,
+
+    /// Accepted but held back until capacity frees up
+    Deferred,
+
+    /// Stopped for the time being
+    Paused,
+
+    /// Memory reclaimed by dropping caches and finished processes
+    Reclaimed,
+}
+
+impl ShedAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ShedAction::Rejected => "rejected",
+            ShedAction::Deferred => "deferred",
+            ShedAction::Paused => "paused",
+            ShedAction::Reclaimed => "reclaimed",
+        }
+    }
+}
+
+/// Metric that made the shedding kick in
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShedTrigger {
+    pub metric: String,
+    pub value: f64,
+    pub limit: f64,
+}
+
+impl ShedTrigger {
+    pub fn new(metric: &str, value: f64, limit: f64) -> Self {
+        Self {
+            metric: metric.to_string(),
+            value,
+            limit,
+        }
+    }
+}
+
+/// Shedding from one source, from its first to its last occurrence
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShedEvent {
+    /// Subsystem that shed the work (`ipc_rate_limit`, `task_queue`, ...)
+    pub source: String,
+    pub action: ShedAction,
+
+    /// Trigger at its peak value during the incident
+    pub trigger: ShedTrigger,
+    pub started_at: u64,
+    pub ended_at: u64,
+
+    /// Number of times work was shed
+    pub occurrences: u64,
+
+    /// First distinct items shed
+    pub items: Vec<String>,
+}
+
+impl ShedEvent {
+    pub fn duration_secs(&self) -> u64 {
+        self.ended_at.saturating_sub(self.started_at)
+    }
+}
+
+/// Totals of one source and action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShedSummary {
+    pub source: String,
+    pub action: ShedAction,
+    pub incidents: usize,
+    pub occurrences: u64,
+    pub total_secs: u64,
+    pub longest_secs: u64,
+
+    /// Highest trigger value seen, with the limit in force at that time
+    pub peak: ShedTrigger,
+}
+
+/// Answer to a shedding report query
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShedReport {
+    pub generated_at: u64,
+    pub since: Option<u64>,
+    pub summaries: Vec<ShedSummary>,
+
+    /// Newest first
+    pub incidents: Vec<ShedEvent>,
+}
+
+/// Shared record of shedding events; cheap to call from hot paths
+#[derive(Debug, Default)]
+pub struct ShedLog {
+    events: Mutex<VecDeque<ShedEvent>>,
+}
+
+impl ShedLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that `item` was shed by `source`
+    pub fn record(&self, source: &str, action: ShedAction, trigger: ShedTrigger, item: &str) {
+        self.record_at(source, action, trigger, item, crate::clock::unix_secs());
+    }
+
+    /// Record with an explicit timestamp; extends the source's open incident
+    /// when it saw shedding within [`INCIDENT_GAP_SECS`]
+    pub fn record_at(
+        &self,
+        source: &str,
+        action: ShedAction,
+        trigger: ShedTrigger,
+        item: &str,
+        now: u64,
+    ) {
+        let mut events = self.lock();
+        let open = events.iter_mut().rev().find(|event| {
+            event.source == source
+                && event.action == action
+                && now.saturating_sub(event.ended_at) <= INCIDENT_GAP_SECS
+        });
+        match open {
+            Some(event) => {
+                event.ended_at = event.ended_at.max(now);
+                event.occurrences += 1;
+                if trigger.value > event.trigger.value {
+                    event.trigger = trigger;
+                }
+                if event.items.len() < SAMPLE_ITEMS && !event.items.iter().any(|i| i == item) {
+                    event.items.push(item.to_string());
+                }
+            }
+            None => {
+                tracing::info!(
+                    "Load shedding started: {} {} ({} {} / {})",
+                    source,
+                    action.as_str(),
+                    trigger.metric,
+                    trigger.value,
+                    trigger.limit
+                );
+                events.push_back(ShedEvent {
+                    source: source.to_string(),
+                    action,
+                    trigger,
+                    started_at: now,
+                    ended_at: now,
+                    occurrences: 1,
+                    items: vec![item.to_string()],
+                });
+                if events.len() > SHED_HISTORY_LIMIT {
+                    events.pop_front();
+                }
+            }
+        }
+    }
+
+    /// Incidents that were still going on at or after `since`, per source and
+    /// action, with the incidents themselves newest first
+    pub fn report(&self, since: Option<u64>) -> ShedReport {
+        let incidents: Vec<ShedEvent> = self
+            .lock()
+            .iter()
+            .rev()
+            .filter(|event| since.map_or(true, |since| event.ended_at >= since))
+            .cloned()
+            .collect();
+
+        let mut summaries: BTreeMap<(String, ShedAction), ShedSummary> = BTreeMap::new();
+        for event in &incidents {
+            let summary = summaries
+                .entry((event.source.clone(), event.action))
+                .or_insert_with(|| ShedSummary {
+                    source: event.source.clone(),
+                    action: event.action,
+                    incidents: 0,
+                    occurrences: 0,
+                    total_secs: 0,
+                    longest_secs: 0,
+                    peak: event.trigger.clone(),
+                });
+            summary.incidents += 1;
+            summary.occurrences += event.occurrences;
+            summary.total_secs += event.duration_secs();
+            summary.longest_secs = summary.longest_secs.max(event.duration_secs());
+            if event.trigger.value > summary.peak.value {
+                summary.peak = event.trigger.clone();
+            }
+        }
+
+        ShedReport {
+            generated_at: crate::clock::unix_secs(),
+            since,
+            summaries: summaries.into_values().collect(),
+            incidents,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<ShedEvent>> {
+        self.events.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue_full(size: f64) -> ShedTrigger {
+        ShedTrigger::new("task_queue_size", size, 100.0)
+    }
+
+    #[test]
+    fn test_shedding_is_grouped_into_incidents() {
+        let log = ShedLog::new();
+        log.record_at(
+            "task_queue",
+            ShedAction::Rejected,
+            queue_full(100.0),
+            "a",
+            1000,
+        );
+        log.record_at(
+            "task_queue",
+            ShedAction::Rejected,
+            queue_full(100.0),
+            "b",
+            1030,
+        );
+        log.record_at(
+            "task_queue",
+            ShedAction::Rejected,
+            queue_full(100.0),
+            "b",
+            1080,
+        );
+        // Too long after the last one: a new incident
+        log.record_at(
+            "task_queue",
+            ShedAction::Rejected,
+            queue_full(100.0),
+            "c",
+            1200,
+        );
+        // Another kind of shedding never joins the incident
+        log.record_at(
+            "task_queue",
+            ShedAction::Deferred,
+            queue_full(40.0),
+            "d",
+            1210,
+        );
+
+        let report = log.report(None);
+        assert_eq!(report.incidents.len(), 3);
+        let first = &report.incidents[2];
+        assert_eq!((first.started_at, first.ended_at), (1000, 1080));
+        assert_eq!(first.occurrences, 3);
+        assert_eq!(first.items, ["a", "b"]);
+
+        let rejected = report
+            .summaries
+            .iter()
+            .find(|summary| summary.action == ShedAction::Rejected)
+            .unwrap();
+        assert_eq!(rejected.incidents, 2);
+        assert_eq!(rejected.occurrences, 4);
+        assert_eq!((rejected.total_secs, rejected.longest_secs), (80, 80));
+    }
+
+    #[test]
+    fn test_report_since_keeps_peak_trigger() {
+        let log = ShedLog::new();
+        let rate = |value| ShedTrigger::new("memory_usage_percent", value, 85.0);
+        log.record_at("recovery", ShedAction::Reclaimed, rate(88.0), "gc", 500);
+        log.record_at("recovery", ShedAction::Reclaimed, rate(93.5), "gc", 520);
+        log.record_at("recovery", ShedAction::Reclaimed, rate(90.0), "gc", 540);
+
+        let report = log.report(Some(530));
+        assert_eq!(report.incidents.len(), 1);
+        assert_eq!(report.summaries[0].peak.value, 93.5);
+        assert!(log.report(Some(541)).incidents.is_empty());
+    }
+}
@@ -0,0 +1,435 @@
+// WezTerm Multi-Process Development Framework - Service Level Objectives
+// Error budgets of the components' health checks and how fast they burn
+//
+// Every health check is an event per component: it is unavailable when the
+// component is unhealthy (or could not be checked), slow when its response
+// time exceeds the latency objective, and an error when it is not healthy.
+// Events are counted per minute over the SLO window.
+
+use super::{AlertSeverity, HealthCheck, HealthStatus};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// Share of checks allowed to be slower than a p95 latency objective
+const LATENCY_BUDGET: f64 = 0.05;
+
+/// Events are counted per bucket of this many seconds
+const BUCKET_SECS: u64 = 60;
+
+/// SLO settings (`monitoring.slo`)
+///
+/// ```yaml
+/// slo:
+///   window_hours: 168
+///   objectives:
+///     - component: task_manager
+///       availability: 99.5
+///       p95_latency_ms: 200
+///       error_rate: 1.0
+///   burn_rate_alerts:
+///     - window_minutes: 60
+///       burn_rate: 14.4
+///       severity: Critical
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SloConfig {
+    /// Rolling window the error budget is spent over
+    pub window_hours: u64,
+
+    pub objectives: Vec<SloObjective>,
+
+    /// Alerts raised when the budget burns faster than a rate over a window
+    pub burn_rate_alerts: Vec<BurnRateAlert>,
+}
+
+impl Default for SloConfig {
+    fn default() -> Self {
+        Self {
+            window_hours: 7 * 24,
+            objectives: Vec::new(),
+            burn_rate_alerts: vec![
+                BurnRateAlert {
+                    window_minutes: 60,
+                    burn_rate: 14.4,
+                    severity: AlertSeverity::Critical,
+                },
+                BurnRateAlert {
+                    window_minutes: 6 * 60,
+                    burn_rate: 6.0,
+                    severity: AlertSeverity::Warning,
+                },
+            ],
+        }
+    }
+}
+
+/// Objectives of one health check component; each is optional
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SloObjective {
+    pub component: String,
+
+    /// Checks that must find the component available, in percent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub availability: Option<f64>,
+
+    /// Response time 95% of the checks must stay within
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub p95_latency_ms: Option<u64>,
+
+    /// Checks allowed to find the component not healthy, in percent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_rate: Option<f64>,
+}
+
+impl SloObjective {
+    /// Configured indicators with their allowed share of bad events
+    fn budgets(&self) -> Vec<(Indicator, f64)> {
+        let mut budgets = Vec::new();
+        if let Some(availability) = self.availability {
+            budgets.push((Indicator::Availability, (100.0 - availability) / 100.0));
+        }
+        if self.p95_latency_ms.is_some() {
+            budgets.push((Indicator::Latency, LATENCY_BUDGET));
+        }
+        if let Some(error_rate) = self.error_rate {
+            budgets.push((Indicator::ErrorRate, error_rate / 100.0));
+        }
+        budgets
+    }
+}
+
+/// Raise an alert of `severity` when the budget burns at least `burn_rate`
+/// times as fast as the SLO window allows, measured over `window_minutes`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BurnRateAlert {
+    pub window_minutes: u64,
+    pub burn_rate: f64,
+    pub severity: AlertSeverity,
+}
+
+/// Service level indicator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Indicator {
+    Availability,
+    Latency,
+    ErrorRate,
+}
+
+impl Indicator {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Indicator::Availability => "availability",
+            Indicator::Latency => "latency",
+            Indicator::ErrorRate => "error_rate",
+        }
+    }
+}
+
+/// Burn rate over one alert window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BurnRate {
+    pub window_minutes: u64,
+
+    /// 1.0 spends exactly the budget over the SLO window; `None` without checks
+    pub rate: Option<f64>,
+}
+
+/// State of one objective of a component
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SloStatus {
+    pub component: String,
+    pub indicator: Indicator,
+
+    /// Good checks required, in percent
+    pub target: f64,
+
+    /// Good checks over the SLO window, in percent; `None` without checks
+    pub actual: Option<f64>,
+    pub checks: u64,
+
+    /// Share of the error budget left; negative once overspent
+    pub budget_remaining: f64,
+
+    pub burn_rates: Vec<BurnRate>,
+
+    /// Most severe burn-rate alert that applies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alert: Option<AlertSeverity>,
+}
+
+impl SloStatus {
+    /// Id of the alert raised for this objective
+    pub fn alert_id(&self) -> String {
+        format!("slo_{}_{}", self.component, self.indicator.as_str())
+    }
+
+    /// Alert message with the fastest burn that applies
+    pub fn alert_message(&self, config: &SloConfig) -> String {
+        let burning = config
+            .burn_rate_alerts
+            .iter()
+            .zip(&self.burn_rates)
+            .filter(|(alert, burn)| burn.rate.is_some_and(|rate| rate >= alert.burn_rate))
+            .filter_map(|(_, burn)| burn.rate.map(|rate| (burn.window_minutes, rate)))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        let (window_minutes, rate) = burning.unwrap_or_default();
+        format!(
+            "Error budget of {} {} burning at {rate:.1}x over {window_minutes} min ({:.0}% left)",
+            self.component,
+            self.indicator.as_str(),
+            self.budget_remaining * 100.0
+        )
+    }
+}
+
+/// Events of one component in one bucket
+#[derive(Debug, Clone, Default)]
+struct Bucket {
+    start: u64,
+    checks: u64,
+    unavailable: u64,
+    slow: u64,
+    errors: u64,
+}
+
+impl Bucket {
+    fn bad(&self, indicator: Indicator) -> u64 {
+        match indicator {
+            Indicator::Availability => self.unavailable,
+            Indicator::Latency => self.slow,
+            Indicator::ErrorRate => self.errors,
+        }
+    }
+}
+
+/// Counts the health checks of the components with objectives
+#[derive(Debug)]
+pub struct SloTracker {
+    config: SloConfig,
+    buckets: Mutex<HashMap<String, VecDeque<Bucket>>>,
+}
+
+impl SloTracker {
+    pub fn new(config: SloConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &SloConfig {
+        &self.config
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.config.objectives.is_empty()
+    }
+
+    fn window_secs(&self) -> u64 {
+        self.config.window_hours * 3600
+    }
+
+    /// Count a health check of every component with objectives
+    pub fn record(&self, health_check: &HealthCheck) {
+        let start = health_check.timestamp - health_check.timestamp % BUCKET_SECS;
+        let mut buckets = self.lock();
+        for objective in &self.config.objectives {
+            let Some(health) = health_check.components.get(&objective.component) else {
+                continue;
+            };
+            let buckets = buckets.entry(objective.component.clone()).or_default();
+            if buckets.back().map_or(true, |bucket| bucket.start < start) {
+                buckets.push_back(Bucket {
+                    start,
+                    ..Bucket::default()
+                });
+            }
+            let Some(bucket) = buckets
+                .iter_mut()
+                .rev()
+                .find(|bucket| bucket.start <= start)
+            else {
+                continue;
+            };
+            bucket.checks += 1;
+            if matches!(
+                health.status,
+                HealthStatus::Unhealthy | HealthStatus::Unknown
+            ) {
+                bucket.unavailable += 1;
+            }
+            if health.status != HealthStatus::Healthy {
+                bucket.errors += 1;
+            }
+            if objective
+                .p95_latency_ms
+                .is_some_and(|limit| health.response_time_ms > limit)
+            {
+                bucket.slow += 1;
+            }
+
+            let expired = start.saturating_sub(self.window_secs());
+            while buckets.front().is_some_and(|bucket| bucket.start < expired) {
+                buckets.pop_front();
+            }
+        }
+    }
+
+    /// Budget and burn rates of every objective at `now`
+    pub fn status(&self, now: u64) -> Vec<SloStatus> {
+        let buckets = self.lock();
+        let mut statuses = Vec::new();
+        for objective in &self.config.objectives {
+            let buckets = buckets.get(&objective.component);
+            let totals = |since: u64, indicator: Indicator| {
+                buckets
+                    .into_iter()
+                    .flatten()
+                    .filter(|bucket| bucket.start + BUCKET_SECS > since)
+                    .fold((0, 0), |(checks, bad), bucket| {
+                        (checks + bucket.checks, bad + bucket.bad(indicator))
+                    })
+            };
+
+            for (indicator, budget) in objective.budgets() {
+                let (checks, bad) = totals(now.saturating_sub(self.window_secs()), indicator);
+                let bad_share = (checks > 0).then(|| bad as f64 / checks as f64);
+                let burn_rates: Vec<BurnRate> = self
+                    .config
+                    .burn_rate_alerts
+                    .iter()
+                    .map(|alert| {
+                        let (checks, bad) =
+                            totals(now.saturating_sub(alert.window_minutes * 60), indicator);
+                        BurnRate {
+                            window_minutes: alert.window_minutes,
+                            rate: (checks > 0).then(|| bad as f64 / checks as f64 / budget),
+                        }
+                    })
+                    .collect();
+                let alert = self
+                    .config
+                    .burn_rate_alerts
+                    .iter()
+                    .zip(&burn_rates)
+                    .filter(|(alert, burn)| burn.rate.is_some_and(|rate| rate >= alert.burn_rate))
+                    .map(|(alert, _)| alert.severity.clone())
+                    .max();
+
+                statuses.push(SloStatus {
+                    component: objective.component.clone(),
+                    indicator,
+                    target: (1.0 - budget) * 100.0,
+                    actual: bad_share.map(|share| (1.0 - share) * 100.0),
+                    checks,
+                    budget_remaining: 1.0 - bad_share.unwrap_or_default() / budget,
+                    burn_rates,
+                    alert,
+                });
+            }
+        }
+        statuses
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, VecDeque<Bucket>>> {
+        self.buckets.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitoring::ComponentHealth;
+
+    fn check(timestamp: u64, status: HealthStatus, response_time_ms: u64) -> HealthCheck {
+        let mut components = HashMap::new();
+        components.insert(
+            "task_manager".to_string(),
+            ComponentHealth {
+                status: status.clone(),
+                message: String::new(),
+                last_success: None,
+                failure_count: 0,
+                response_time_ms,
+            },
+        );
+        HealthCheck {
+            timestamp,
+            overall_status: status,
+            components,
+            check_duration_ms: 0,
+        }
+    }
+
+    fn tracker() -> SloTracker {
+        SloTracker::new(SloConfig {
+            window_hours: 24,
+            objectives: vec![SloObjective {
+                component: "task_manager".to_string(),
+                availability: Some(99.0),
+                p95_latency_ms: Some(100),
+                error_rate: None,
+            }],
+            ..SloConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_error_budget_and_burn_rates() {
+        let tracker = tracker();
+        let start = 1_700_000_000 - 1_700_000_000 % 3600;
+        // A day of checks, one a minute: every third check of the last six
+        // hours is slow and every fifth one of the last hour fails
+        for minute in 0..24 * 60 {
+            let status = if minute >= 23 * 60 && minute % 5 == 0 {
+                HealthStatus::Unhealthy
+            } else {
+                HealthStatus::Healthy
+            };
+            let response_time_ms = if minute >= 18 * 60 && minute % 3 == 0 {
+                150
+            } else {
+                20
+            };
+            tracker.record(&check(start + minute * 60, status, response_time_ms));
+        }
+
+        let statuses = tracker.status(start + 24 * 3600);
+        assert_eq!(statuses.len(), 2);
+        let availability = &statuses[0];
+        assert_eq!(availability.indicator, Indicator::Availability);
+        assert_eq!(availability.checks, 24 * 60);
+        assert!((availability.target - 99.0).abs() < 1e-9);
+        // 12 of 1440 checks failed against a budget of 14.4
+        assert!((availability.budget_remaining - (1.0 - 12.0 / 14.4)).abs() < 1e-9);
+        assert!((availability.burn_rates[0].rate.unwrap() - 20.0).abs() < 1e-9);
+        assert_eq!(availability.alert, Some(AlertSeverity::Critical));
+
+        // 120 slow checks against a budget of 72
+        let latency = &statuses[1];
+        assert_eq!(latency.indicator, Indicator::Latency);
+        assert!(latency.budget_remaining < 0.0);
+        assert_eq!(latency.alert, Some(AlertSeverity::Warning));
+        assert_eq!(latency.alert_id(), "slo_task_manager_latency");
+        assert!(latency
+            .alert_message(tracker.config())
+            .starts_with("Error budget of task_manager latency burning at 6.7x over 360 min"));
+    }
+
+    #[test]
+    fn test_old_checks_leave_the_window() {
+        let tracker = tracker();
+        tracker.record(&check(1_000_000, HealthStatus::Unhealthy, 20));
+        tracker.record(&check(1_000_000 + 25 * 3600, HealthStatus::Healthy, 20));
+
+        let status = &tracker.status(1_000_000 + 25 * 3600)[0];
+        assert_eq!((status.checks, status.actual), (1, Some(100.0)));
+        assert_eq!(status.budget_remaining, 1.0);
+        assert_eq!(status.alert, None);
+        assert!(tracker.status(1_000_000)[0].burn_rates[0].rate.is_some());
+        assert!(SloTracker::new(SloConfig::default()).status(0).is_empty());
+    }
+}
@@ -0,0 +1,503 @@
+// WezTerm Multi-Process Development Framework - Cache
+// TTL とサイズ上限つきのシャード化 LRU キャッシュ
+//
+// キーのハッシュでシャードを選び、シャードごとの短いロックの中だけで
+// 読み書きするため、非同期タスクから呼んでもランタイムを止めない。
+// ヒット・ミス・追い出しの回数はキャッシュごとに数え、`CacheRegistry`
+// 経由で `/metrics` に出す。
+
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// キャッシュ設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    /// エントリ数の上限
+    pub max_entries: usize,
+    /// キーと値を合わせたバイト数の上限
+    pub max_bytes: usize,
+    /// エントリの有効期間（秒）。0 なら期限なし
+    pub ttl_secs: u64,
+    /// シャード数。上限はシャードごとに等分される
+    pub shards: usize,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: 100,
+            max_bytes: 16 * 1024 * 1024, // 16MB
+            ttl_secs: 300,
+            shards: 8,
+        }
+    }
+}
+
+/// キャッシュごとの統計
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CacheStats {
+    pub name: String,
+    pub entries: usize,
+    pub bytes: usize,
+    pub hits: u64,
+    pub misses: u64,
+    /// 上限を超えて追い出したエントリ数
+    pub evictions: u64,
+    /// 期限切れで捨てたエントリ数
+    pub expirations: u64,
+}
+
+/// `/metrics` に出す項目（名前、型、説明）。値は `CacheStats::prometheus_values` の順
+const PROMETHEUS_METRICS: [(&str, &str, &str); 6] = [
+    ("hits_total", "counter", "Cache lookups that found a value"),
+    (
+        "misses_total",
+        "counter",
+        "Cache lookups that found nothing",
+    ),
+    (
+        "evictions_total",
+        "counter",
+        "Entries evicted to stay within the size limits",
+    ),
+    (
+        "expirations_total",
+        "counter",
+        "Entries dropped after their TTL",
+    ),
+    ("entries", "gauge", "Entries currently cached"),
+    (
+        "bytes",
+        "gauge",
+        "Bytes of keys and values currently cached",
+    ),
+];
+
+impl CacheStats {
+    fn prometheus_values(&self) -> [u64; 6] {
+        [
+            self.hits,
+            self.misses,
+            self.evictions,
+            self.expirations,
+            self.entries as u64,
+            self.bytes as u64,
+        ]
+    }
+
+    /// ヒット率（%）
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64 * 100.0
+        }
+    }
+}
+
+struct Entry {
+    data: Vec<u8>,
+    inserted: Instant,
+    /// 最後に使われた順番（`Shard::order` のキー）
+    tick: u64,
+}
+
+#[derive(Default)]
+struct Shard {
+    entries: HashMap<String, Entry>,
+    /// 使われた順番 → キー。先頭が最も古い
+    order: BTreeMap<u64, String>,
+    bytes: usize,
+    tick: u64,
+}
+
+impl Shard {
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    fn remove(&mut self, key: &str) -> Option<Entry> {
+        let entry = self.entries.remove(key)?;
+        self.order.remove(&entry.tick);
+        self.bytes -= key.len() + entry.data.len();
+        Some(entry)
+    }
+
+    /// 最も長く使われていないエントリを捨てる
+    fn pop_oldest(&mut self) -> bool {
+        match self.order.pop_first() {
+            Some((_, key)) => {
+                let entry = self.entries.remove(&key).expect("ordered key has an entry");
+                self.bytes -= key.len() + entry.data.len();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// TTL とサイズ上限つきの LRU キャッシュ
+pub struct Cache {
+    name: String,
+    config: CacheConfig,
+    shards: Vec<Mutex<Shard>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+    expirations: AtomicU64,
+}
+
+impl Cache {
+    pub fn new(name: &str, config: CacheConfig) -> Self {
+        let shards = (0..config.shards.max(1))
+            .map(|_| Mutex::new(Shard::default()))
+            .collect();
+        Self {
+            name: name.to_string(),
+            config,
+            shards,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+            expirations: AtomicU64::new(0),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn config(&self) -> &CacheConfig {
+        &self.config
+    }
+
+    fn shard(&self, key: &str) -> MutexGuard<'_, Shard> {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        let index = (hasher.finish() % self.shards.len() as u64) as usize;
+        self.shards[index]
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn shard_max_entries(&self) -> usize {
+        (self.config.max_entries / self.shards.len()).max(1)
+    }
+
+    fn shard_max_bytes(&self) -> usize {
+        self.config.max_bytes / self.shards.len()
+    }
+
+    fn expired(&self, entry: &Entry, now: Instant) -> bool {
+        self.config.ttl_secs > 0
+            && now.duration_since(entry.inserted) >= Duration::from_secs(self.config.ttl_secs)
+    }
+
+    /// キーの値を取得し、最近使われたものとして記録する
+    pub fn get(&self, key: &str) -> Option<Vec<u8>> {
+        self.get_at(key, Instant::now())
+    }
+
+    fn get_at(&self, key: &str, now: Instant) -> Option<Vec<u8>> {
+        let mut shard = self.shard(key);
+        let expired = match shard.entries.get(key) {
+            Some(entry) => self.expired(entry, now),
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
+        };
+        if expired {
+            shard.remove(key);
+            self.expirations.fetch_add(1, Ordering::Relaxed);
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+
+        let tick = shard.next_tick();
+        let entry = shard.entries.get_mut(key).expect("entry checked above");
+        let previous = std::mem::replace(&mut entry.tick, tick);
+        let data = entry.data.clone();
+        shard.order.remove(&previous);
+        shard.order.insert(tick, key.to_string());
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        Some(data)
+    }
+
+    /// 値を保存する。上限を超える分は最も長く使われていないエントリから捨てる
+    ///
+    /// シャードの容量より大きい値は保存しない。
+    pub fn insert(&self, key: String, data: Vec<u8>) {
+        self.insert_at(key, data, Instant::now());
+    }
+
+    fn insert_at(&self, key: String, data: Vec<u8>, now: Instant) {
+        let size = key.len() + data.len();
+        let max_bytes = self.shard_max_bytes();
+        let max_entries = self.shard_max_entries();
+
+        let mut shard = self.shard(&key);
+        shard.remove(&key);
+        if size > max_bytes {
+            return;
+        }
+
+        let mut evicted = 0;
+        while shard.entries.len() >= max_entries || shard.bytes + size > max_bytes {
+            if !shard.pop_oldest() {
+                break;
+            }
+            evicted += 1;
+        }
+        self.evictions.fetch_add(evicted, Ordering::Relaxed);
+
+        let tick = shard.next_tick();
+        shard.order.insert(tick, key.clone());
+        shard.bytes += size;
+        shard.entries.insert(
+            key,
+            Entry {
+                data,
+                inserted: now,
+                tick,
+            },
+        );
+    }
+
+    pub fn remove(&self, key: &str) -> Option<Vec<u8>> {
+        self.shard(key).remove(key).map(|entry| entry.data)
+    }
+
+    /// 期限切れのエントリを捨て、その数を返す
+    pub fn purge_expired(&self) -> usize {
+        self.purge_expired_at(Instant::now())
+    }
+
+    fn purge_expired_at(&self, now: Instant) -> usize {
+        if self.config.ttl_secs == 0 {
+            return 0;
+        }
+        let mut purged = 0;
+        for shard in &self.shards {
+            let mut shard = shard
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            let expired: Vec<String> = shard
+                .entries
+                .iter()
+                .filter(|(_, entry)| self.expired(entry, now))
+                .map(|(key, _)| key.clone())
+                .collect();
+            for key in expired {
+                shard.remove(&key);
+                purged += 1;
+            }
+        }
+        self.expirations.fetch_add(purged as u64, Ordering::Relaxed);
+        purged
+    }
+
+    /// 各シャードを上限の `ratio` 倍まで、古いエントリから捨てて縮める
+    pub fn shrink(&self, ratio: f64) -> usize {
+        let ratio = ratio.clamp(0.0, 1.0);
+        let max_entries = (self.shard_max_entries() as f64 * ratio) as usize;
+        let max_bytes = (self.shard_max_bytes() as f64 * ratio) as usize;
+        let mut evicted = 0;
+        for shard in &self.shards {
+            let mut shard = shard
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            while (shard.entries.len() > max_entries || shard.bytes > max_bytes)
+                && shard.pop_oldest()
+            {
+                evicted += 1;
+            }
+        }
+        self.evictions.fetch_add(evicted as u64, Ordering::Relaxed);
+        evicted
+    }
+
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| {
+                shard
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .entries
+                    .len()
+            })
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let (entries, bytes) = self.shards.iter().fold((0, 0), |(entries, bytes), shard| {
+            let shard = shard
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            (entries + shard.entries.len(), bytes + shard.bytes)
+        });
+        CacheStats {
+            name: self.name.clone(),
+            entries,
+            bytes,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+            expirations: self.expirations.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// 名前つきキャッシュの一覧。統計をまとめて出す
+#[derive(Default)]
+pub struct CacheRegistry {
+    caches: Mutex<Vec<Arc<Cache>>>,
+}
+
+impl CacheRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// キャッシュを作って登録する。同じ名前があればそれを返す
+    pub fn register(&self, name: &str, config: CacheConfig) -> Arc<Cache> {
+        let mut caches = self
+            .caches
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(cache) = caches.iter().find(|cache| cache.name == name) {
+            return Arc::clone(cache);
+        }
+        let cache = Arc::new(Cache::new(name, config));
+        caches.push(Arc::clone(&cache));
+        cache
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<Cache>> {
+        self.caches
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .find(|cache| cache.name == name)
+            .cloned()
+    }
+
+    pub fn stats(&self) -> Vec<CacheStats> {
+        let caches = self
+            .caches
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone();
+        caches.iter().map(|cache| cache.stats()).collect()
+    }
+
+    /// キャッシュごとのカウンタとサイズの Prometheus テキスト形式
+    pub fn to_prometheus(&self) -> String {
+        let stats = self.stats();
+        let mut out = String::new();
+        for (index, (metric, kind, help)) in PROMETHEUS_METRICS.iter().enumerate() {
+            let _ = writeln!(out, "# HELP wezterm_parallel_cache_{metric} {help}");
+            let _ = writeln!(out, "# TYPE wezterm_parallel_cache_{metric} {kind}");
+            for cache in &stats {
+                let _ = writeln!(
+                    out,
+                    "wezterm_parallel_cache_{metric}{{cache=\"{}\"}} {}",
+                    cache.name,
+                    cache.prometheus_values()[index]
+                );
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn single_shard(max_entries: usize, max_bytes: usize) -> Cache {
+        Cache::new(
+            "test",
+            CacheConfig {
+                max_entries,
+                max_bytes,
+                ttl_secs: 60,
+                shards: 1,
+            },
+        )
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let cache = single_shard(3, 1024);
+        for key in ["a", "b", "c"] {
+            cache.insert(key.to_string(), vec![0; 10]);
+        }
+        // "a" を使うと、次に古いのは "b"
+        assert!(cache.get("a").is_some());
+        cache.insert("d".to_string(), vec![0; 10]);
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("c").is_some());
+
+        // バイト数の上限でも追い出す
+        cache.insert("big".to_string(), vec![0; 1020]);
+        assert_eq!(cache.len(), 1);
+        // 容量を超える値は保存しない
+        cache.insert("huge".to_string(), vec![0; 2048]);
+        assert!(cache.get("huge").is_none());
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (3, 2));
+        assert_eq!(stats.evictions, 4);
+        assert_eq!(stats.bytes, "big".len() + 1020);
+    }
+
+    #[test]
+    fn test_entries_expire_after_ttl() {
+        let cache = single_shard(10, 1024);
+        let start = Instant::now();
+        cache.insert_at("old".to_string(), vec![1], start);
+        cache.insert_at("new".to_string(), vec![2], start + Duration::from_secs(30));
+
+        let later = start + Duration::from_secs(61);
+        assert_eq!(cache.get_at("old", later), None);
+        assert_eq!(cache.purge_expired_at(later + Duration::from_secs(30)), 1);
+        assert!(cache.is_empty());
+        assert_eq!(cache.stats().expirations, 2);
+    }
+
+    #[test]
+    fn test_registry_reports_per_cache_metrics() {
+        let registry = CacheRegistry::new();
+        let templates = registry.register("templates", CacheConfig::default());
+        registry
+            .register("performance", CacheConfig::default())
+            .insert("k".to_string(), vec![1]);
+        assert!(Arc::ptr_eq(
+            &registry.register("templates", CacheConfig::default()),
+            &templates
+        ));
+        assert!(templates.get("missing").is_none());
+        assert!(registry.get("performance").unwrap().get("k").is_some());
+
+        let text = registry.to_prometheus();
+        assert!(text.contains("wezterm_parallel_cache_misses_total{cache=\"templates\"} 1"));
+        assert!(text.contains("wezterm_parallel_cache_hits_total{cache=\"performance\"} 1"));
+        assert!(text.contains("wezterm_parallel_cache_entries{cache=\"performance\"} 1"));
+    }
+}
@@ -0,0 +1,287 @@
+// WezTerm Multi-Process Development Framework - Lazy Subsystems
+// 初回利用時に起動するサブシステムと、その起動記録
+//
+// `performance.lazy_initialization` が有効なとき、ダッシュボードの
+// バックグラウンドタスク・ファイル監視・アナリティクスは最初に必要になった
+// 時点で起動する。起動は一度だけで、同時に要求されたときは最初の呼び出しの
+// 初期化を待つ。いつ何がきっかけで起動したかは `Activations` に残り、
+// `/status/subsystems` と `/metrics` で確認できる。
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 起動のきっかけ: 遅延初期化が無効で、デーモンの起動時に初期化した
+pub const STARTUP_TRIGGER: &str = "startup";
+
+/// サブシステムの起動記録
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Activation {
+    /// 起動のきっかけ（[`STARTUP_TRIGGER`]、`dashboard_client` など）
+    pub trigger: String,
+    /// 起動時刻（UNIX 秒）
+    pub activated_at: u64,
+    /// デーモン起動からの経過ミリ秒
+    pub after_startup_ms: u64,
+    /// 初期化にかかったミリ秒
+    pub init_ms: u64,
+}
+
+/// サブシステムの状態。起動前は `activation` が `None`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubsystemStatus {
+    pub name: String,
+    pub activation: Option<Activation>,
+}
+
+/// サブシステムの起動記録の一覧
+pub struct Activations {
+    started: Instant,
+    subsystems: Mutex<Vec<SubsystemStatus>>,
+}
+
+impl Default for Activations {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Activations {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            subsystems: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<SubsystemStatus>> {
+        self.subsystems
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 起動前のサブシステムとして一覧に加える
+    pub fn register(&self, name: &str) {
+        let mut subsystems = self.lock();
+        if !subsystems.iter().any(|subsystem| subsystem.name == name) {
+            subsystems.push(SubsystemStatus {
+                name: name.to_string(),
+                activation: None,
+            });
+        }
+    }
+
+    /// 起動を記録する。2回目以降の記録は無視する
+    pub fn record(&self, name: &str, trigger: &str, init: Duration) {
+        self.register(name);
+        let after_startup = self.started.elapsed();
+        let mut subsystems = self.lock();
+        let Some(subsystem) = subsystems
+            .iter_mut()
+            .find(|subsystem| subsystem.name == name)
+        else {
+            return;
+        };
+        if subsystem.activation.is_none() {
+            subsystem.activation = Some(Activation {
+                trigger: trigger.to_string(),
+                activated_at: crate::clock::unix_secs(),
+                after_startup_ms: after_startup.as_millis() as u64,
+                init_ms: init.as_millis() as u64,
+            });
+        }
+    }
+
+    pub fn is_active(&self, name: &str) -> bool {
+        self.lock()
+            .iter()
+            .any(|subsystem| subsystem.name == name && subsystem.activation.is_some())
+    }
+
+    pub fn list(&self) -> Vec<SubsystemStatus> {
+        self.lock().clone()
+    }
+
+    /// サブシステムごとの起動状態の Prometheus テキスト形式
+    pub fn to_prometheus(&self) -> String {
+        let subsystems = self.list();
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "# HELP wezterm_parallel_subsystem_active Whether the subsystem has been started"
+        );
+        let _ = writeln!(out, "# TYPE wezterm_parallel_subsystem_active gauge");
+        for subsystem in &subsystems {
+            let _ = writeln!(
+                out,
+                "wezterm_parallel_subsystem_active{{subsystem=\"{}\"}} {}",
+                subsystem.name,
+                u8::from(subsystem.activation.is_some())
+            );
+        }
+
+        let activated: Vec<_> = subsystems
+            .iter()
+            .filter_map(|subsystem| Some((&subsystem.name, subsystem.activation.as_ref()?)))
+            .collect();
+        let _ = writeln!(
+            out,
+            "# HELP wezterm_parallel_subsystem_activation_seconds Time after daemon startup the subsystem was started"
+        );
+        let _ = writeln!(
+            out,
+            "# TYPE wezterm_parallel_subsystem_activation_seconds gauge"
+        );
+        for (name, activation) in &activated {
+            let _ = writeln!(
+                out,
+                "wezterm_parallel_subsystem_activation_seconds{{subsystem=\"{name}\",trigger=\"{}\"}} {}",
+                activation.trigger,
+                activation.after_startup_ms as f64 / 1000.0
+            );
+        }
+        let _ = writeln!(
+            out,
+            "# HELP wezterm_parallel_subsystem_init_seconds Time the subsystem took to initialize"
+        );
+        let _ = writeln!(out, "# TYPE wezterm_parallel_subsystem_init_seconds gauge");
+        for (name, activation) in &activated {
+            let _ = writeln!(
+                out,
+                "wezterm_parallel_subsystem_init_seconds{{subsystem=\"{name}\"}} {}",
+                activation.init_ms as f64 / 1000.0
+            );
+        }
+        out
+    }
+}
+
+type InitFn = Box<dyn Fn() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+/// 初回の `activate` で一度だけ初期化するサブシステム
+pub struct LazySubsystem {
+    name: String,
+    activations: Arc<Activations>,
+    once: tokio::sync::OnceCell<()>,
+    init: InitFn,
+}
+
+impl LazySubsystem {
+    pub fn new<F, Fut>(name: &str, activations: Arc<Activations>, init: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        activations.register(name);
+        Self {
+            name: name.to_string(),
+            activations,
+            once: tokio::sync::OnceCell::new(),
+            init: Box::new(move || Box::pin(init())),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.once.initialized()
+    }
+
+    /// 未起動なら初期化する。この呼び出しで初期化したときは `true`
+    ///
+    /// 初期化中の呼び出しはその完了を待つ。
+    pub async fn activate(&self, trigger: &str) -> bool {
+        if self.is_active() {
+            return false;
+        }
+        let mut initialized = false;
+        let initialized_here = &mut initialized;
+        self.once
+            .get_or_init(move || async move {
+                let started = Instant::now();
+                (self.init)().await;
+                self.activations
+                    .record(&self.name, trigger, started.elapsed());
+                *initialized_here = true;
+            })
+            .await;
+        initialized
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test]
+    async fn test_subsystem_initializes_once() {
+        let activations = Arc::new(Activations::new());
+        let runs = Arc::new(AtomicU32::new(0));
+        let subsystem = {
+            let runs = Arc::clone(&runs);
+            Arc::new(LazySubsystem::new(
+                "file_sync",
+                Arc::clone(&activations),
+                move || {
+                    let runs = Arc::clone(&runs);
+                    async move {
+                        tokio::time::sleep(Duration::from_millis(20)).await;
+                        runs.fetch_add(1, Ordering::SeqCst);
+                    }
+                },
+            ))
+        };
+        assert!(!subsystem.is_active());
+        assert_eq!(activations.list()[0].activation, None);
+
+        // 同時に要求されても初期化は1回で、全員がその完了を待つ
+        let handles: Vec<_> = ["dashboard_client", "ipc", "ipc"]
+            .into_iter()
+            .map(|trigger| {
+                let subsystem = Arc::clone(&subsystem);
+                tokio::spawn(async move { subsystem.activate(trigger).await })
+            })
+            .collect();
+        let mut initialized = 0;
+        for handle in handles {
+            initialized += u32::from(handle.await.unwrap());
+            assert!(subsystem.is_active());
+        }
+        assert_eq!((initialized, runs.load(Ordering::SeqCst)), (1, 1));
+        assert!(!subsystem.activate("ipc").await);
+
+        let activation = activations.list()[0].activation.clone().unwrap();
+        assert!(activation.init_ms >= 20);
+        assert!(activations.is_active("file_sync"));
+    }
+
+    #[test]
+    fn test_activations_report_pending_subsystems() {
+        let activations = Activations::new();
+        activations.register("dashboard");
+        activations.register("analytics");
+        activations.record("dashboard", "dashboard_client", Duration::from_millis(1500));
+        activations.record("dashboard", "later", Duration::ZERO);
+
+        let list = activations.list();
+        assert_eq!(list.len(), 2);
+        assert_eq!(
+            list[0].activation.as_ref().unwrap().trigger,
+            "dashboard_client"
+        );
+        assert!(!activations.is_active("analytics"));
+
+        let text = activations.to_prometheus();
+        assert!(text.contains("wezterm_parallel_subsystem_active{subsystem=\"analytics\"} 0"));
+        assert!(text.contains("wezterm_parallel_subsystem_active{subsystem=\"dashboard\"} 1"));
+        assert!(
+            text.contains("wezterm_parallel_subsystem_init_seconds{subsystem=\"dashboard\"} 1.5")
+        );
+    }
+}
@@ -3,11 +3,289 @@
 
 use crate::logging::LogContext;
 use crate::{log_debug, log_info, log_warn};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
+/// リーク検出でデーモン自身を表す対象名
+pub const DAEMON_TARGET: &str = "daemon";
+
+/// 傾きを求めるのに必要な最小サンプル数
+const MIN_LEAK_SAMPLES: usize = 5;
+
+/// ウィンドウのうちサンプルが覆っているべき割合
+const MIN_WINDOW_COVERAGE: f64 = 0.75;
+
+/// メモリ監視とリーク検出の設定（`memory`）
+///
+/// ```yaml
+/// memory:
+///   sample_interval_secs: 60
+///   leak_windows_minutes: [30, 180]
+///   leak_threshold_mb_per_hour: 50
+///   restart_leaking_processes: true
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MemoryConfig {
+    /// デーモンと子プロセスの RSS を採取する間隔（秒）
+    pub sample_interval_secs: u64,
+
+    /// 傾きを求めるウィンドウ（分）。いずれかで閾値を超えるとリークとみなす
+    pub leak_windows_minutes: Vec<u64>,
+
+    /// リークとみなす RSS の増加率（MB/時）
+    pub leak_threshold_mb_per_hour: f64,
+
+    /// 回帰直線の決定係数の下限。GC による上下動ではなく単調な増加だけを拾う
+    pub min_fit: f64,
+
+    /// リーク検出時にキャッシュとメモリプールを掃除する
+    pub trigger_gc: bool,
+
+    /// リーク検出時にアラートを上げる
+    pub alert: bool,
+
+    /// リークしている子プロセスを再起動する
+    pub restart_leaking_processes: bool,
+}
+
+impl Default for MemoryConfig {
+    fn default() -> Self {
+        Self {
+            sample_interval_secs: 60,
+            leak_windows_minutes: vec![30, 180],
+            leak_threshold_mb_per_hour: 50.0,
+            min_fit: 0.8,
+            trigger_gc: true,
+            alert: true,
+            restart_leaking_processes: false,
+        }
+    }
+}
+
+/// プロセスの常駐メモリとヒープ（データセグメント）のサイズ
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ProcessMemory {
+    pub rss_bytes: u64,
+
+    /// 取得できないプラットフォームでは `None`
+    pub heap_bytes: Option<u64>,
+}
+
+/// プロセスのメモリ使用量を取得
+///
+/// Linux は `/proc/<pid>/status` の `VmRSS` / `VmData`、その他の Unix は
+/// `ps -o rss=`、Windows は `tasklist` から読み取る。
+pub async fn read_process_memory(pid: u32) -> Result<ProcessMemory, String> {
+    #[cfg(target_os = "linux")]
+    {
+        let status = tokio::fs::read_to_string(format!("/proc/{pid}/status"))
+            .await
+            .map_err(|e| format!("Failed to read memory of process {pid}: {e}"))?;
+        parse_proc_status(&status).ok_or_else(|| format!("No VmRSS for process {pid}"))
+    }
+    #[cfg(all(unix, not(target_os = "linux")))]
+    {
+        let output = tokio::process::Command::new("ps")
+            .args(["-o", "rss=", "-p", &pid.to_string()])
+            .output()
+            .await
+            .map_err(|e| format!("Failed to run ps: {e}"))?;
+        let kb: u64 = String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse()
+            .map_err(|_| format!("No such process: {pid}"))?;
+        Ok(ProcessMemory {
+            rss_bytes: kb * 1024,
+            heap_bytes: None,
+        })
+    }
+    #[cfg(windows)]
+    {
+        let output = tokio::process::Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {pid}"), "/FO", "CSV", "/NH"])
+            .output()
+            .await
+            .map_err(|e| format!("Failed to run tasklist: {e}"))?;
+        parse_tasklist_csv(&String::from_utf8_lossy(&output.stdout))
+            .ok_or_else(|| format!("No such process: {pid}"))
+    }
+}
+
+/// `/proc/<pid>/status` の `VmRSS` と `VmData`（kB）
+#[cfg(any(target_os = "linux", test))]
+fn parse_proc_status(status: &str) -> Option<ProcessMemory> {
+    let field = |name: &str| {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix(name))
+            .and_then(|value| value.split_whitespace().next())
+            .and_then(|kb| kb.parse::<u64>().ok())
+            .map(|kb| kb * 1024)
+    };
+    Some(ProcessMemory {
+        rss_bytes: field("VmRSS:")?,
+        heap_bytes: field("VmData:"),
+    })
+}
+
+/// `tasklist /FO CSV /NH` の1行目のメモリ使用量（`"12,345 K"`）
+#[cfg(any(windows, test))]
+fn parse_tasklist_csv(csv: &str) -> Option<ProcessMemory> {
+    let line = csv.lines().next()?;
+    let memory = line.rsplit("\",\"").next()?;
+    let kb: u64 = memory
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect::<String>()
+        .parse()
+        .ok()?;
+    Some(ProcessMemory {
+        rss_bytes: kb * 1024,
+        heap_bytes: None,
+    })
+}
+
+/// RSS が増え続けている対象
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryLeak {
+    /// [`DAEMON_TARGET`] またはプロセス ID
+    pub target: String,
+    pub window_minutes: u64,
+    pub growth_mb_per_hour: f64,
+
+    /// 回帰直線の決定係数
+    pub fit: f64,
+    pub rss_bytes: u64,
+}
+
+/// 対象ごとのリーク判定
+#[derive(Debug, Clone, PartialEq)]
+pub enum LeakCheck {
+    /// どのウィンドウもまだサンプルが足りない
+    Warming,
+    Clean,
+    Leaking(MemoryLeak),
+}
+
+/// 対象ごとの RSS の推移から、傾きでリークを検出
+#[derive(Debug, Default)]
+pub struct LeakDetector {
+    config: MemoryConfig,
+    samples: HashMap<String, VecDeque<(u64, u64)>>,
+}
+
+impl LeakDetector {
+    pub fn new(config: MemoryConfig) -> Self {
+        Self {
+            config,
+            samples: HashMap::new(),
+        }
+    }
+
+    fn longest_window_secs(&self) -> u64 {
+        self.config
+            .leak_windows_minutes
+            .iter()
+            .max()
+            .copied()
+            .unwrap_or(0)
+            * 60
+    }
+
+    /// `timestamp`（秒）時点の RSS を記録
+    pub fn record(&mut self, target: &str, timestamp: u64, rss_bytes: u64) {
+        let expired = timestamp.saturating_sub(self.longest_window_secs());
+        let samples = self.samples.entry(target.to_string()).or_default();
+        samples.push_back((timestamp, rss_bytes));
+        while samples.front().is_some_and(|(at, _)| *at < expired) {
+            samples.pop_front();
+        }
+    }
+
+    /// 各ウィンドウの傾きから判定する。短いウィンドウから順に調べる
+    pub fn check(&self, target: &str, now: u64) -> LeakCheck {
+        let Some(samples) = self.samples.get(target) else {
+            return LeakCheck::Warming;
+        };
+        let mut windows = self.config.leak_windows_minutes.clone();
+        windows.sort_unstable();
+
+        let mut evaluated = false;
+        for window_minutes in windows {
+            let window_secs = window_minutes * 60;
+            let since = now.saturating_sub(window_secs);
+            let points: Vec<(u64, u64)> = samples
+                .iter()
+                .filter(|(at, _)| *at >= since)
+                .copied()
+                .collect();
+            let (Some(first), Some(last)) = (points.first(), points.last()) else {
+                continue;
+            };
+            if points.len() < MIN_LEAK_SAMPLES
+                || ((last.0 - first.0) as f64) < window_secs as f64 * MIN_WINDOW_COVERAGE
+            {
+                continue;
+            }
+            evaluated = true;
+
+            let (slope, fit) = linear_trend(&points);
+            let growth_mb_per_hour = slope * 3600.0 / 1024.0 / 1024.0;
+            if growth_mb_per_hour >= self.config.leak_threshold_mb_per_hour
+                && fit >= self.config.min_fit
+            {
+                return LeakCheck::Leaking(MemoryLeak {
+                    target: target.to_string(),
+                    window_minutes,
+                    growth_mb_per_hour,
+                    fit,
+                    rss_bytes: last.1,
+                });
+            }
+        }
+        if evaluated {
+            LeakCheck::Clean
+        } else {
+            LeakCheck::Warming
+        }
+    }
+
+    /// 対処後に記録を捨て、ウィンドウを貯め直す
+    pub fn reset(&mut self, target: &str) {
+        self.samples.remove(target);
+    }
+
+    /// なくなった対象の記録を捨てる
+    pub fn retain(&mut self, targets: &HashSet<String>) {
+        self.samples.retain(|target, _| targets.contains(target));
+    }
+}
+
+/// 最小二乗法による傾き（バイト/秒）と決定係数
+fn linear_trend(points: &[(u64, u64)]) -> (f64, f64) {
+    let origin = points[0].0;
+    let n = points.len() as f64;
+    let xs: Vec<f64> = points.iter().map(|(at, _)| (at - origin) as f64).collect();
+    let ys: Vec<f64> = points.iter().map(|(_, rss)| *rss as f64).collect();
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = ys.iter().sum::<f64>() / n;
+    let sxx: f64 = xs.iter().map(|x| (x - mean_x).powi(2)).sum();
+    let syy: f64 = ys.iter().map(|y| (y - mean_y).powi(2)).sum();
+    if sxx == 0.0 || syy == 0.0 {
+        return (0.0, 0.0);
+    }
+    let sxy: f64 = xs
+        .iter()
+        .zip(&ys)
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    (sxy / sxx, sxy * sxy / (sxx * syy))
+}
+
 /// メモリプール管理
 pub struct MemoryPool {
     pools: HashMap<usize, Vec<Vec<u8>>>,
@@ -191,6 +469,8 @@ pub struct MemoryMonitor {
     check_interval: Duration,
     memory_limit: usize,
     warning_threshold: f64,
+    config: MemoryConfig,
+    leak_detector: LeakDetector,
 }
 
 impl MemoryMonitor {
@@ -202,7 +482,53 @@ impl MemoryMonitor {
             check_interval: Duration::from_secs(30),
             memory_limit: memory_limit_mb * 1024 * 1024,
             warning_threshold: 0.8, // 80%
+            config: MemoryConfig::default(),
+            leak_detector: LeakDetector::default(),
+        }
+    }
+
+    /// リーク検出の設定を適用
+    pub fn with_config(mut self, config: MemoryConfig) -> Self {
+        self.leak_detector = LeakDetector::new(config.clone());
+        self.config = config;
+        self
+    }
+
+    pub fn config(&self) -> &MemoryConfig {
+        &self.config
+    }
+
+    /// デーモンと子プロセス（ID と PID）の RSS を採取し、対象ごとのリーク判定を返す
+    ///
+    /// 終了したプロセスの記録は捨てる。採取できなかった対象は判定に含まれない。
+    pub async fn sample_leaks(&mut self, processes: &[(String, u32)]) -> Vec<(String, LeakCheck)> {
+        let now = crate::clock::unix_secs();
+        let targets = std::iter::once((DAEMON_TARGET.to_string(), std::process::id()))
+            .chain(processes.iter().cloned());
+
+        let mut sampled = HashSet::new();
+        let mut checks = Vec::new();
+        for (target, pid) in targets {
+            match read_process_memory(pid).await {
+                Ok(memory) => {
+                    self.leak_detector.record(&target, now, memory.rss_bytes);
+                    checks.push((target.clone(), self.leak_detector.check(&target, now)));
+                    sampled.insert(target);
+                }
+                Err(e) => {
+                    let sample_context = LogContext::new("performance", "memory_sample_error")
+                        .with_entity_id(&target);
+                    log_debug!(sample_context, "メモリ使用量を取得できません: {}", e);
+                }
+            }
         }
+        self.leak_detector.retain(&sampled);
+        checks
+    }
+
+    /// 対処した対象の記録を捨て、ウィンドウを貯め直す
+    pub fn reset_leak(&mut self, target: &str) {
+        self.leak_detector.reset(target);
     }
 
     /// メモリ使用量をチェック
@@ -272,55 +598,15 @@ impl MemoryMonitor {
         }
     }
 
-    /// システムメモリ使用量を取得
-    async fn get_current_memory_usage(&self) -> Result<usize, Box<dyn std::error::Error>> {
-        // 実際の実装ではプラットフォーム固有のAPIを使用
-        #[cfg(target_os = "linux")]
-        {
-            self.get_linux_memory_usage().await
-        }
-        #[cfg(target_os = "macos")]
-        {
-            self.get_macos_memory_usage().await
-        }
-        #[cfg(target_os = "windows")]
-        {
-            self.get_windows_memory_usage().await
-        }
-        #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
-        {
+    /// デーモンの常駐メモリ（RSS）を取得
+    pub async fn get_current_memory_usage(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        match read_process_memory(std::process::id()).await {
+            Ok(memory) => Ok(memory.rss_bytes as usize),
             // フォールバック: プロセス統計から推定
-            Ok(self.estimate_memory_usage().await)
+            Err(_) => Ok(self.estimate_memory_usage().await),
         }
     }
 
-    #[cfg(target_os = "linux")]
-    async fn get_linux_memory_usage(&self) -> Result<usize, Box<dyn std::error::Error>> {
-        // /proc/self/statusから読み取り
-        let status = tokio::fs::read_to_string("/proc/self/status").await?;
-        for line in status.lines() {
-            if line.starts_with("VmRSS:") {
-                if let Some(kb_str) = line.split_whitespace().nth(1) {
-                    return Ok(kb_str.parse::<usize>()? * 1024);
-                }
-            }
-        }
-        Ok(0)
-    }
-
-    #[cfg(target_os = "macos")]
-    async fn get_macos_memory_usage(&self) -> Result<usize, Box<dyn std::error::Error>> {
-        // macOS: task_info APIを使用（簡略化）
-        Ok(self.estimate_memory_usage().await)
-    }
-
-    #[cfg(target_os = "windows")]
-    async fn get_windows_memory_usage(&self) -> Result<usize, Box<dyn std::error::Error>> {
-        // Windows: GetProcessMemoryInfo APIを使用（簡略化）
-        Ok(self.estimate_memory_usage().await)
-    }
-
-    #[allow(dead_code)]
     async fn estimate_memory_usage(&self) -> usize {
         // メモリプールとインターナーの使用量から推定
         let pool_stats = {
@@ -338,7 +624,7 @@ impl MemoryMonitor {
     }
 
     /// 通常のクリーンアップ
-    async fn perform_cleanup(&self) {
+    pub async fn perform_cleanup(&self) {
         let cleanup_context = LogContext::new("performance", "memory_cleanup");
         log_info!(cleanup_context, "メモリクリーンアップを実行中...");
 
@@ -838,4 +1124,76 @@ mod tests {
         assert!(debug_output.contains("pool_count"));
         assert!(debug_output.contains("pooled_buffers"));
     }
+
+    #[test]
+    fn test_parse_process_memory() {
+        let status = "Name:\twezterm-parallel\nVmPeak:\t  20000 kB\nVmData:\t   8000 kB\nVmRSS:\t  12000 kB\n";
+        assert_eq!(
+            parse_proc_status(status),
+            Some(ProcessMemory {
+                rss_bytes: 12000 * 1024,
+                heap_bytes: Some(8000 * 1024),
+            })
+        );
+        // カーネルスレッドには VmRSS がない
+        assert_eq!(parse_proc_status("Name:\tkthreadd\n"), None);
+
+        let csv = "\"claude.exe\",\"4242\",\"Console\",\"1\",\"12,345 K\"\r\n";
+        assert_eq!(
+            parse_tasklist_csv(csv).map(|memory| memory.rss_bytes),
+            Some(12345 * 1024)
+        );
+        assert_eq!(parse_tasklist_csv("INFO: No tasks are running."), None);
+    }
+
+    #[test]
+    fn test_leak_detector_flags_steady_growth() {
+        const MB: u64 = 1024 * 1024;
+        let mut detector = LeakDetector::new(MemoryConfig::default());
+
+        // 30分窓が埋まるまでは判定しない
+        for minute in 0..10 {
+            detector.record("grow", minute * 60, 100 * MB + minute * 2 * MB);
+        }
+        assert_eq!(detector.check("grow", 9 * 60), LeakCheck::Warming);
+
+        // 2MB/分 = 120MB/時間で増え続ける対象と、GC で上下する対象
+        for minute in 10..=30 {
+            detector.record("grow", minute * 60, 100 * MB + minute * 2 * MB);
+        }
+        for minute in 0..=30 {
+            let sawtooth = if minute % 2 == 0 { 0 } else { 40 * MB };
+            detector.record("sawtooth", minute * 60, 200 * MB + sawtooth);
+        }
+
+        match detector.check("grow", 30 * 60) {
+            LeakCheck::Leaking(leak) => {
+                assert_eq!(leak.window_minutes, 30);
+                assert!((leak.growth_mb_per_hour - 120.0).abs() < 0.01);
+                assert!(leak.fit > 0.99);
+                assert_eq!(leak.rss_bytes, 160 * MB);
+            }
+            other => panic!("expected a leak, got {other:?}"),
+        }
+        assert_eq!(detector.check("sawtooth", 30 * 60), LeakCheck::Clean);
+
+        // 対処後は貯め直し、いなくなった対象は忘れる
+        detector.reset("grow");
+        assert_eq!(detector.check("grow", 30 * 60), LeakCheck::Warming);
+        detector.retain(&HashSet::new());
+        assert_eq!(detector.check("sawtooth", 30 * 60), LeakCheck::Warming);
+    }
+
+    #[tokio::test]
+    async fn test_sample_leaks_reads_daemon_rss() {
+        let mut monitor = MemoryMonitor::new(512);
+        let checks = monitor.sample_leaks(&[]).await;
+        if cfg!(target_os = "linux") {
+            assert_eq!(
+                checks,
+                vec![(DAEMON_TARGET.to_string(), LeakCheck::Warming)]
+            );
+            assert!(monitor.get_current_memory_usage().await.unwrap() > 0);
+        }
+    }
 }
@@ -2,18 +2,31 @@
 // パフォーマンス最適化モジュール
 
 pub mod async_opt;
+pub mod cache;
+pub mod lazy;
 pub mod memory;
 pub mod metrics;
 pub mod startup;
 
+use crate::i18n::{Language, Text, TextKey};
+use cache::{Cache, CacheConfig, CacheRegistry};
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
 /// パフォーマンス設定
+///
+/// ```yaml
+/// performance:
+///   lazy_initialization: true   # ダッシュボード・ファイル監視・アナリティクスを初回利用時に起動
+///   memory_limit_mb: 512
+/// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PerformanceConfig {
-    /// 遅延初期化を有効にする
+    /// ダッシュボード・ファイル監視・アナリティクスを初回利用時まで起動しない
     pub lazy_initialization: bool,
     /// プリロードするモジュール数の制限
     pub max_preload_modules: usize,
@@ -27,6 +40,8 @@ pub struct PerformanceConfig {
     pub cpu_limit_percent: f64,
     /// メモリ使用量制限（MB）
     pub memory_limit_mb: usize,
+    /// データキャッシュ
+    pub cache: CacheConfig,
 }
 
 impl Default for PerformanceConfig {
@@ -39,6 +54,7 @@ impl Default for PerformanceConfig {
             gc_interval_secs: 300, // 5分
             cpu_limit_percent: 80.0,
             memory_limit_mb: 512,
+            cache: CacheConfig::default(),
         }
     }
 }
@@ -72,13 +88,25 @@ impl Default for PerformanceMetrics {
 }
 
 /// パフォーマンス最適化マネージャー
+///
+/// 統計はアトミック変数に持つので、`Arc` で共有したまま非同期タスクから
+/// ロックなしで更新できる。ロックを取るのはメモリプールの出し入れと GC だけで、
+/// プールが使用中ならプールを使わずに確保・破棄する。
 pub struct PerformanceManager {
     config: PerformanceConfig,
-    metrics: PerformanceMetrics,
     start_time: Instant,
-    last_gc: Instant,
-    memory_pool: Vec<Vec<u8>>,
-    cache: std::collections::HashMap<String, Vec<u8>>,
+    startup_time_nanos: AtomicU64,
+    memory_usage: AtomicUsize,
+    peak_memory: AtomicUsize,
+    /// `f64` のビット列
+    cpu_usage: AtomicU64,
+    active_tasks: AtomicUsize,
+    gc_runs: AtomicU32,
+    /// 最後の GC の `start_time` からの経過ミリ秒
+    last_gc_millis: AtomicU64,
+    memory_pool: Mutex<Vec<Vec<u8>>>,
+    caches: Arc<CacheRegistry>,
+    cache: Arc<Cache>,
 }
 
 impl PerformanceManager {
@@ -92,28 +120,41 @@ impl PerformanceManager {
             memory_pool.push(Vec::with_capacity(config.initial_memory_pool_size / 8));
         }
 
+        let caches = Arc::new(CacheRegistry::new());
+        let cache = caches.register("performance", config.cache.clone());
+
         Self {
             config,
-            metrics: PerformanceMetrics::default(),
             start_time,
-            last_gc: start_time,
-            memory_pool,
-            cache: std::collections::HashMap::new(),
+            startup_time_nanos: AtomicU64::new(0),
+            memory_usage: AtomicUsize::new(0),
+            peak_memory: AtomicUsize::new(0),
+            cpu_usage: AtomicU64::new(0f64.to_bits()),
+            active_tasks: AtomicUsize::new(0),
+            gc_runs: AtomicU32::new(0),
+            last_gc_millis: AtomicU64::new(0),
+            memory_pool: Mutex::new(memory_pool),
+            caches,
+            cache,
         }
     }
 
+    fn elapsed_millis(&self) -> u64 {
+        self.start_time.elapsed().as_millis() as u64
+    }
+
     /// 起動完了を記録
-    pub fn record_startup_complete(&mut self) {
-        self.metrics.startup_time = self.start_time.elapsed();
-        info!("起動完了: {:?}", self.metrics.startup_time);
+    pub fn record_startup_complete(&self) {
+        let startup_time = self.start_time.elapsed();
+        self.startup_time_nanos
+            .store(startup_time.as_nanos() as u64, Ordering::Relaxed);
+        info!("起動完了: {:?}", startup_time);
     }
 
     /// メモリ使用量を更新
-    pub fn update_memory_usage(&mut self, usage: usize) {
-        self.metrics.memory_usage = usage;
-        if usage > self.metrics.peak_memory {
-            self.metrics.peak_memory = usage;
-        }
+    pub fn update_memory_usage(&self, usage: usize) {
+        self.memory_usage.store(usage, Ordering::Relaxed);
+        self.peak_memory.fetch_max(usage, Ordering::Relaxed);
 
         // メモリ制限チェック
         let limit_bytes = self.config.memory_limit_mb * 1024 * 1024;
@@ -128,8 +169,8 @@ impl PerformanceManager {
     }
 
     /// CPU使用率を更新
-    pub fn update_cpu_usage(&mut self, usage: f64) {
-        self.metrics.cpu_usage = usage;
+    pub fn update_cpu_usage(&self, usage: f64) {
+        self.cpu_usage.store(usage.to_bits(), Ordering::Relaxed);
 
         if usage > self.config.cpu_limit_percent {
             warn!(
@@ -140,33 +181,39 @@ impl PerformanceManager {
     }
 
     /// アクティブタスク数を更新
-    pub fn update_active_tasks(&mut self, count: usize) {
-        self.metrics.active_tasks = count;
+    pub fn update_active_tasks(&self, count: usize) {
+        self.active_tasks.store(count, Ordering::Relaxed);
     }
 
     /// ガベージコレクションを実行
-    pub fn trigger_gc(&mut self) {
-        let now = Instant::now();
-        if now.duration_since(self.last_gc).as_secs() < 60 {
-            // 1分以内のGC実行を制限
+    pub fn trigger_gc(&self) {
+        // 1分以内のGC実行を制限。同時に呼ばれても実行するのは1つだけ
+        let now = self.elapsed_millis();
+        let last_gc = self.last_gc_millis.load(Ordering::Relaxed);
+        if now.saturating_sub(last_gc) < 60_000
+            || self
+                .last_gc_millis
+                .compare_exchange(last_gc, now, Ordering::AcqRel, Ordering::Relaxed)
+                .is_err()
+        {
             return;
         }
 
         debug!("ガベージコレクションを実行中...");
 
-        // キャッシュクリーンアップ
+        // 期限切れを捨て、残りも古い順に半分まで減らす
         let cache_size_before = self.cache.len();
-        self.cache.retain(|_, v| v.capacity() <= 1024); // 1KB以下のみ保持
+        self.cache.purge_expired();
+        self.cache.shrink(0.5);
         let cache_size_after = self.cache.len();
 
         // メモリプールリセット
-        for buffer in &mut self.memory_pool {
+        for buffer in self.lock_pool().iter_mut() {
             buffer.clear();
             buffer.shrink_to_fit();
         }
 
-        self.metrics.gc_runs += 1;
-        self.last_gc = now;
+        self.gc_runs.fetch_add(1, Ordering::Relaxed);
 
         info!(
             "GC完了: キャッシュ {}→{} エントリ",
@@ -175,43 +222,52 @@ impl PerformanceManager {
     }
 
     /// 定期的なガベージコレクション
-    pub fn periodic_gc(&mut self) {
-        let now = Instant::now();
-        if now.duration_since(self.last_gc).as_secs() >= self.config.gc_interval_secs {
+    pub fn periodic_gc(&self) {
+        self.cache.purge_expired();
+
+        let since_gc = self
+            .elapsed_millis()
+            .saturating_sub(self.last_gc_millis.load(Ordering::Relaxed));
+        if since_gc >= self.config.gc_interval_secs * 1000 {
             self.trigger_gc();
         }
     }
 
+    /// データキャッシュ。マネージャーを経由せずに非同期タスクから使える
+    pub fn cache(&self) -> Arc<Cache> {
+        Arc::clone(&self.cache)
+    }
+
+    /// キャッシュの一覧。キャッシュごとのヒット・ミス数を出す
+    pub fn caches(&self) -> Arc<CacheRegistry> {
+        Arc::clone(&self.caches)
+    }
+
     /// キャッシュからデータを取得
-    pub fn get_cached(&mut self, key: &str) -> Option<Vec<u8>> {
-        if let Some(data) = self.cache.get(key) {
-            self.metrics.cache_hits += 1;
-            Some(data.clone())
-        } else {
-            self.metrics.cache_misses += 1;
-            None
-        }
+    pub fn get_cached(&self, key: &str) -> Option<Vec<u8>> {
+        self.cache.get(key)
     }
 
     /// データをキャッシュに保存
-    pub fn cache_data(&mut self, key: String, data: Vec<u8>) {
-        // キャッシュサイズ制限
-        if self.cache.len() >= 100 {
-            // 最も古いエントリを削除
-            if let Some(oldest_key) = self.cache.keys().next().cloned() {
-                self.cache.remove(&oldest_key);
-            }
-        }
-
+    pub fn cache_data(&self, key: String, data: Vec<u8>) {
         self.cache.insert(key, data);
     }
 
+    fn lock_pool(&self) -> std::sync::MutexGuard<'_, Vec<Vec<u8>>> {
+        self.memory_pool
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
     /// メモリプールからバッファを取得
-    pub fn get_buffer(&mut self, size: usize) -> Vec<u8> {
-        for buffer in &mut self.memory_pool {
-            if buffer.is_empty() {
-                buffer.reserve(size);
-                return std::mem::take(buffer);
+    pub fn get_buffer(&self, size: usize) -> Vec<u8> {
+        // 他のタスクがプールを使用中なら待たずに新規作成
+        if let Ok(mut pool) = self.memory_pool.try_lock() {
+            for buffer in pool.iter_mut() {
+                if buffer.is_empty() {
+                    buffer.reserve(size);
+                    return std::mem::take(buffer);
+                }
             }
         }
 
@@ -220,54 +276,58 @@ impl PerformanceManager {
     }
 
     /// バッファをメモリプールに返却
-    pub fn return_buffer(&mut self, mut buffer: Vec<u8>) {
+    pub fn return_buffer(&self, mut buffer: Vec<u8>) {
         buffer.clear();
 
         if buffer.capacity() <= self.config.initial_memory_pool_size {
-            for slot in &mut self.memory_pool {
-                if slot.is_empty() {
-                    *slot = buffer;
-                    return;
+            if let Ok(mut pool) = self.memory_pool.try_lock() {
+                for slot in pool.iter_mut() {
+                    if slot.is_empty() {
+                        *slot = buffer;
+                        return;
+                    }
                 }
             }
         }
 
-        // プールが満杯またはバッファが大きすぎる場合は破棄
+        // プールが満杯・使用中またはバッファが大きすぎる場合は破棄
         drop(buffer);
     }
 
-    /// パフォーマンス統計を取得
-    pub fn get_metrics(&self) -> &PerformanceMetrics {
-        &self.metrics
+    /// パフォーマンス統計を取得。キャッシュのヒット・ミス数はデータキャッシュのもの
+    pub fn get_metrics(&self) -> PerformanceMetrics {
+        let cache = self.cache.stats();
+        PerformanceMetrics {
+            startup_time: Duration::from_nanos(self.startup_time_nanos.load(Ordering::Relaxed)),
+            memory_usage: self.memory_usage.load(Ordering::Relaxed),
+            cpu_usage: f64::from_bits(self.cpu_usage.load(Ordering::Relaxed)),
+            active_tasks: self.active_tasks.load(Ordering::Relaxed),
+            peak_memory: self.peak_memory.load(Ordering::Relaxed),
+            gc_runs: self.gc_runs.load(Ordering::Relaxed),
+            cache_hits: cache.hits,
+            cache_misses: cache.misses,
+        }
     }
 
-    /// パフォーマンスレポートを生成
-    pub fn generate_report(&self) -> String {
-        format!(
-            "=== パフォーマンスレポート ===\n\
-            起動時間: {:?}\n\
-            メモリ使用量: {}MB (ピーク: {}MB)\n\
-            CPU使用率: {:.1}%\n\
-            アクティブタスク: {}\n\
-            GC実行回数: {}\n\
-            キャッシュヒット率: {:.1}%\n\
-            メモリプール使用中: {}/{}",
-            self.metrics.startup_time,
-            self.metrics.memory_usage / 1024 / 1024,
-            self.metrics.peak_memory / 1024 / 1024,
-            self.metrics.cpu_usage,
-            self.metrics.active_tasks,
-            self.metrics.gc_runs,
-            if self.metrics.cache_hits + self.metrics.cache_misses > 0 {
-                (self.metrics.cache_hits as f64
-                    / (self.metrics.cache_hits + self.metrics.cache_misses) as f64)
-                    * 100.0
-            } else {
-                0.0
-            },
-            self.memory_pool.iter().filter(|b| !b.is_empty()).count(),
-            self.memory_pool.len()
-        )
+    /// パフォーマンスレポートを指定した言語で生成
+    pub fn generate_report(&self, language: Language) -> String {
+        let metrics = self.get_metrics();
+        let cache_hit_rate = self.cache.stats().hit_rate();
+        let (pool_used, pool_size) = {
+            let pool = self.lock_pool();
+            (pool.iter().filter(|b| !b.is_empty()).count(), pool.len())
+        };
+        Text::new(TextKey::PerformanceReport)
+            .arg("startup", format!("{:?}", metrics.startup_time))
+            .arg("memory_mb", metrics.memory_usage / 1024 / 1024)
+            .arg("peak_mb", metrics.peak_memory / 1024 / 1024)
+            .arg("cpu", format!("{:.1}", metrics.cpu_usage))
+            .arg("tasks", metrics.active_tasks)
+            .arg("gc_runs", metrics.gc_runs)
+            .arg("cache_hit_rate", format!("{cache_hit_rate:.1}"))
+            .arg("pool_used", pool_used)
+            .arg("pool_size", pool_size)
+            .render(language)
     }
 }
 
@@ -280,14 +340,14 @@ mod tests {
         let config = PerformanceConfig::default();
         let manager = PerformanceManager::new(config);
 
-        assert_eq!(manager.metrics.startup_time, Duration::from_secs(0));
-        assert_eq!(manager.memory_pool.len(), 8);
+        assert_eq!(manager.get_metrics().startup_time, Duration::from_secs(0));
+        assert_eq!(manager.lock_pool().len(), 8);
     }
 
     #[test]
     fn test_memory_pool() {
         let config = PerformanceConfig::default();
-        let mut manager = PerformanceManager::new(config);
+        let manager = PerformanceManager::new(config);
 
         let buffer = manager.get_buffer(1024);
         assert!(buffer.capacity() >= 1024);
@@ -298,11 +358,11 @@ mod tests {
     #[test]
     fn test_cache_operations() {
         let config = PerformanceConfig::default();
-        let mut manager = PerformanceManager::new(config);
+        let manager = PerformanceManager::new(config);
 
         // キャッシュミス
         assert!(manager.get_cached("test").is_none());
-        assert_eq!(manager.metrics.cache_misses, 1);
+        assert_eq!(manager.get_metrics().cache_misses, 1);
 
         // データをキャッシュ
         manager.cache_data("test".to_string(), vec![1, 2, 3]);
@@ -311,20 +371,49 @@ mod tests {
         let data = manager.get_cached("test");
         assert!(data.is_some());
         assert_eq!(data.unwrap(), vec![1, 2, 3]);
-        assert_eq!(manager.metrics.cache_hits, 1);
+        assert_eq!(manager.get_metrics().cache_hits, 1);
+        assert_eq!(manager.caches().stats()[0].name, "performance");
     }
 
     #[test]
     fn test_metrics_update() {
         let config = PerformanceConfig::default();
-        let mut manager = PerformanceManager::new(config);
+        let manager = PerformanceManager::new(config);
 
         manager.update_memory_usage(1024 * 1024); // 1MB
         manager.update_cpu_usage(50.0);
         manager.update_active_tasks(5);
 
-        assert_eq!(manager.metrics.memory_usage, 1024 * 1024);
-        assert_eq!(manager.metrics.cpu_usage, 50.0);
-        assert_eq!(manager.metrics.active_tasks, 5);
+        assert_eq!(manager.get_metrics().memory_usage, 1024 * 1024);
+        assert_eq!(manager.get_metrics().cpu_usage, 50.0);
+        assert_eq!(manager.get_metrics().active_tasks, 5);
+    }
+
+    #[test]
+    fn test_concurrent_updates_without_locking() {
+        let manager = Arc::new(PerformanceManager::new(PerformanceConfig::default()));
+        let handles: Vec<_> = (1..=8)
+            .map(|client| {
+                let manager = Arc::clone(&manager);
+                std::thread::spawn(move || {
+                    for i in 0..1000 {
+                        manager.update_memory_usage(client * 1000 + i);
+                        manager.update_cpu_usage(client as f64);
+                        let key = format!("{client}-{}", i % 10);
+                        if manager.get_cached(&key).is_none() {
+                            manager.cache_data(key, vec![0; 16]);
+                        }
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let metrics = manager.get_metrics();
+        assert_eq!(metrics.peak_memory, 8999);
+        assert!((1.0..=8.0).contains(&metrics.cpu_usage));
+        assert_eq!(metrics.cache_hits + metrics.cache_misses, 8000);
     }
 }
@@ -9,7 +9,7 @@ use tracing::{debug, info, warn};
 
 /// 起動最適化マネージャー
 pub struct StartupOptimizer {
-    performance_manager: Arc<std::sync::Mutex<PerformanceManager>>,
+    performance_manager: Arc<PerformanceManager>,
     lazy_init_tasks: Vec<JoinHandle<()>>,
     startup_start: Instant,
 }
@@ -19,7 +19,7 @@ impl StartupOptimizer {
         let startup_start = Instant::now();
         info!("起動最適化を開始: lazy_init={}", config.lazy_initialization);
 
-        let performance_manager = Arc::new(std::sync::Mutex::new(PerformanceManager::new(config)));
+        let performance_manager = Arc::new(PerformanceManager::new(config));
 
         Self {
             performance_manager,
@@ -83,9 +83,7 @@ impl StartupOptimizer {
         info!("起動完了: {:?}", startup_time);
 
         // パフォーマンスマネージャーに記録
-        if let Ok(mut perf_manager) = self.performance_manager.lock() {
-            perf_manager.record_startup_complete();
-        }
+        self.performance_manager.record_startup_complete();
 
         // 遅延初期化タスクの状況をログ
         let pending_tasks = self.lazy_init_tasks.len();
@@ -245,7 +243,7 @@ impl StartupOptimizer {
     }
 
     /// パフォーマンスマネージャーへの参照を取得
-    pub fn get_performance_manager(&self) -> Arc<std::sync::Mutex<PerformanceManager>> {
+    pub fn get_performance_manager(&self) -> Arc<PerformanceManager> {
         Arc::clone(&self.performance_manager)
     }
 }
@@ -0,0 +1,142 @@
+// WezTerm Multi-Process Development Framework - Process Circuit Breaker
+// Quarantines processes that keep failing instead of restarting them forever
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Circuit breaker for crash-looping processes
+///
+/// A process that fails `max_failures` times within `window_secs` is
+/// quarantined: it is neither restarted nor spawned again until its breaker
+/// is reset (`ProcessCircuitReset` over IPC, `ResetCircuitBreaker` on the
+/// dashboard).
+///
+/// ```yaml
+/// circuit_breaker:
+///   max_failures: 5
+///   window_secs: 600
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct CircuitBreakerConfig {
+    pub enabled: bool,
+    pub max_failures: u32,
+    pub window_secs: u64,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_failures: 5,
+            window_secs: 600,
+        }
+    }
+}
+
+/// Failures of one process, kept across its restarts
+#[derive(Debug, Clone, Default)]
+pub struct CircuitBreaker {
+    /// Unix times of the failures within the window
+    failures: VecDeque<u64>,
+
+    /// When the circuit opened; the process is quarantined while set
+    opened_at: Option<u64>,
+}
+
+/// Quarantined process as reported to clients
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Quarantine {
+    pub process_id: String,
+    pub opened_at: u64,
+
+    /// Failures within the window that opened the circuit
+    pub failures: u32,
+}
+
+impl CircuitBreaker {
+    /// Record a failure at `now`; true when it opened the circuit
+    pub fn record_failure(&mut self, config: &CircuitBreakerConfig, now: u64) -> bool {
+        self.failures.push_back(now);
+        while self
+            .failures
+            .front()
+            .is_some_and(|failed| crate::clock::elapsed_secs(*failed, now) >= config.window_secs)
+        {
+            self.failures.pop_front();
+        }
+
+        if !config.enabled || self.is_open() {
+            return false;
+        }
+        if self.failures.len() >= config.max_failures.max(1) as usize {
+            self.opened_at = Some(now);
+            return true;
+        }
+        false
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.opened_at.is_some()
+    }
+
+    pub fn opened_at(&self) -> Option<u64> {
+        self.opened_at
+    }
+
+    /// Failures within the window
+    pub fn failures(&self) -> u32 {
+        self.failures.len() as u32
+    }
+
+    /// Close the circuit and forget the failures
+    pub fn reset(&mut self) {
+        self.failures.clear();
+        self.opened_at = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breaker_opens_after_failures_within_window() {
+        let config = CircuitBreakerConfig {
+            enabled: true,
+            max_failures: 3,
+            window_secs: 60,
+        };
+        let mut breaker = CircuitBreaker::default();
+        assert!(!breaker.record_failure(&config, 1_000));
+        assert!(!breaker.record_failure(&config, 1_030));
+
+        // The first failure has left the window
+        assert!(!breaker.record_failure(&config, 1_070));
+        assert_eq!(breaker.failures(), 2);
+        assert!(!breaker.is_open());
+
+        assert!(breaker.record_failure(&config, 1_080));
+        assert_eq!(breaker.opened_at(), Some(1_080));
+
+        // Further failures keep it open without reporting it again
+        assert!(!breaker.record_failure(&config, 1_090));
+        assert!(breaker.is_open());
+
+        breaker.reset();
+        assert!(!breaker.is_open());
+        assert_eq!(breaker.failures(), 0);
+    }
+
+    #[test]
+    fn test_disabled_breaker_never_opens() {
+        let config = CircuitBreakerConfig {
+            enabled: false,
+            max_failures: 1,
+            window_secs: 60,
+        };
+        let mut breaker = CircuitBreaker::default();
+        assert!(!breaker.record_failure(&config, 1_000));
+        assert!(!breaker.is_open());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::config::Timezone;
 use chrono::{DateTime, Utc};
 use log::{debug, error, info, warn};
 use serde::{Deserialize, Serialize};
@@ -50,6 +51,9 @@ pub struct LogConfig {
     pub enable_structured_logs: bool,
     /// デバッグ情報の有効化
     pub enable_debug_info: bool,
+    /// ログ出力時のタイムスタンプのタイムゾーン
+    #[serde(default)]
+    pub timezone: Timezone,
 }
 
 /// ログレベル
@@ -143,10 +147,21 @@ impl Default for LogConfig {
             rotation_interval_hours: 24,
             enable_structured_logs: true,
             enable_debug_info: true,
+            timezone: Timezone::default(),
         }
     }
 }
 
+impl LogConfig {
+    /// プロセスのログファイルパス（ローテート後は `<id>.log.<timestamp>`）
+    pub fn log_file_path(&self, process_id: &str, workspace: &str) -> PathBuf {
+        let mut path = self.base_dir.clone();
+        path.push(workspace);
+        path.push(format!("{process_id}.log"));
+        path
+    }
+}
+
 impl ClaudeLogger {
     /// 新しいClaudeLoggerを作成
     pub fn new(config: Option<LogConfig>) -> Result<Self> {
@@ -344,10 +359,7 @@ impl ClaudeLogger {
 
     /// ログファイルパスを取得
     fn get_log_file_path(&self, process_id: &str, workspace: &str) -> PathBuf {
-        let mut path = self.config.base_dir.clone();
-        path.push(workspace);
-        path.push(format!("{process_id}.log"));
-        path
+        self.config.log_file_path(process_id, workspace)
     }
 
     /// タスク用のCloneを作成
@@ -405,10 +417,12 @@ impl ClaudeLogger {
 
     /// ログエントリをフォーマット
     fn format_log_entry(&self, entry: &LogEntry) -> Result<String> {
+        let timestamp = self.config.timezone.localize(&entry.timestamp);
+
         match self.config.format {
             LogFormat::Plain => Ok(format!(
                 "[{}] [{}] [{}] [{}] {}",
-                entry.timestamp.format("%Y-%m-%d %H:%M:%S%.3f"),
+                timestamp.format("%Y-%m-%d %H:%M:%S%.3f"),
                 self.level_to_string(&entry.level),
                 entry.process_id,
                 self.source_to_string(&entry.source),
@@ -419,7 +433,7 @@ impl ClaudeLogger {
             LogFormat::Structured => {
                 let mut output = format!(
                     "[{}] [{}] [{}:{}] [{}] {}",
-                    entry.timestamp.format("%Y-%m-%d %H:%M:%S%.3f"),
+                    timestamp.format("%Y-%m-%d %H:%M:%S%.3f"),
                     self.level_to_string(&entry.level),
                     entry.workspace,
                     entry.process_id,
@@ -527,7 +541,7 @@ impl ClaudeLogger {
         }
 
         // 作成時間でソート
-        log_files.sort_by(|a, b| b.1.cmp(&a.1));
+        log_files.sort_by_key(|entry| std::cmp::Reverse(entry.1));
 
         // 保持数を超えたファイルを削除
         if log_files.len() > self.config.max_files as usize {
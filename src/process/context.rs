@@ -0,0 +1,200 @@
+// WezTerm Multi-Process Development Framework - Agent Context
+// Context file (AGENTS.md, CLAUDE.md, ...) handed to the agents of a workspace
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Environment variable pointing an agent at the context file it received
+pub const CONTEXT_FILE_VAR: &str = "CLAUDE_CONTEXT_FILE";
+
+/// Context file a workspace declares for its agents
+///
+/// ```yaml
+/// context:
+///   file: AGENTS.md
+///   framework_instructions: true
+///   required: false
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AgentContextConfig {
+    /// Path of the file, relative to the project directory
+    pub file: PathBuf,
+
+    /// Append how to report progress to the framework
+    #[serde(default = "default_framework_instructions")]
+    pub framework_instructions: bool,
+
+    /// Refuse to start agents while the file is missing; otherwise they
+    /// start without it and a warning is logged
+    #[serde(default)]
+    pub required: bool,
+}
+
+fn default_framework_instructions() -> bool {
+    true
+}
+
+impl AgentContextConfig {
+    /// Copy with the file resolved against `base_dir`
+    pub fn resolve_in(&self, base_dir: &Path) -> Self {
+        Self {
+            file: base_dir.join(&self.file),
+            ..self.clone()
+        }
+    }
+}
+
+/// Context an agent received when it was started
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AgentContext {
+    /// Declared context file
+    pub source: PathBuf,
+
+    /// File handed to the agent; a copy with the framework instructions
+    /// appended, or the source itself
+    pub delivered: PathBuf,
+
+    /// Content hash of the source file when the agent was started
+    pub version: String,
+
+    pub framework_instructions: bool,
+    pub delivered_at: SystemTime,
+}
+
+impl AgentContext {
+    /// Whether the source file changed after the agent received it
+    pub fn is_outdated(&self) -> bool {
+        std::fs::read_to_string(&self.source)
+            .map(|content| content_version(&content) != self.version)
+            .unwrap_or(true)
+    }
+}
+
+/// Verify the context file and write the copy handed to one agent into `output_dir`
+pub fn prepare(
+    config: &AgentContextConfig,
+    output_dir: &Path,
+    workspace: &str,
+    process_id: &str,
+) -> Result<AgentContext, String> {
+    let content = std::fs::read_to_string(&config.file).map_err(|e| {
+        format!(
+            "Context file '{}' of workspace '{}' is not readable: {}",
+            config.file.display(),
+            workspace,
+            e
+        )
+    })?;
+
+    let delivered = if config.framework_instructions {
+        let path = output_dir.join(format!("{}.md", file_stem(process_id)));
+        std::fs::create_dir_all(output_dir)
+            .and_then(|_| {
+                std::fs::write(
+                    &path,
+                    format!(
+                        "{}\n\n{}",
+                        content.trim_end(),
+                        framework_instructions(workspace, process_id)
+                    ),
+                )
+            })
+            .map_err(|e| format!("Failed to write context for '{process_id}': {e}"))?;
+        path
+    } else {
+        config.file.clone()
+    };
+
+    Ok(AgentContext {
+        source: config.file.clone(),
+        delivered,
+        version: content_version(&content),
+        framework_instructions: config.framework_instructions,
+        delivered_at: SystemTime::now(),
+    })
+}
+
+/// How an agent reports back to the framework
+pub fn framework_instructions(workspace: &str, process_id: &str) -> String {
+    format!(
+        "## wezterm-parallel\n\
+         \n\
+         You run as process `{process_id}` in workspace `{workspace}` of the WezTerm \
+         multi-process development framework. Report through its CLI:\n\
+         \n\
+         - Progress and decisions: `wezterm-parallel note \"TEXT\" --workspace {workspace}` \
+         (or `--task ID` for a task)\n\
+         - Work for another agent: `wezterm-parallel task queue COMMAND... --priority N`\n\
+         - Other agents and their state: `wezterm-parallel ps`\n"
+    )
+}
+
+fn content_version(content: &str) -> String {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+fn file_stem(process_id: &str) -> String {
+    process_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prepare_appends_instructions_and_records_version() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("AGENTS.md"), "# Rules\nRun the tests.\n").unwrap();
+        let config: AgentContextConfig = serde_yaml::from_str("file: AGENTS.md").unwrap();
+        let config = config.resolve_in(dir.path());
+
+        let output = dir.path().join("contexts");
+        let context = prepare(&config, &output, "api", "claude/1").unwrap();
+        assert_eq!(context.delivered, output.join("claude_1.md"));
+        let delivered = std::fs::read_to_string(&context.delivered).unwrap();
+        assert!(delivered.starts_with("# Rules\nRun the tests.\n\n## wezterm-parallel"));
+        assert!(delivered.contains("--workspace api"));
+        assert!(!context.is_outdated());
+
+        std::fs::write(&config.file, "# Rules\nRun clippy too.\n").unwrap();
+        assert!(context.is_outdated());
+        let updated = prepare(&config, &output, "api", "claude/1").unwrap();
+        assert_ne!(updated.version, context.version);
+    }
+
+    #[test]
+    fn test_prepare_without_instructions_or_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("CLAUDE.md"), "context").unwrap();
+        let config = AgentContextConfig {
+            file: PathBuf::from("CLAUDE.md"),
+            framework_instructions: false,
+            required: false,
+        }
+        .resolve_in(dir.path());
+
+        let context = prepare(&config, dir.path(), "api", "agent").unwrap();
+        assert_eq!(context.delivered, config.file);
+
+        let missing = AgentContextConfig {
+            file: dir.path().join("MISSING.md"),
+            ..config
+        };
+        assert!(prepare(&missing, dir.path(), "api", "agent").is_err());
+    }
+}
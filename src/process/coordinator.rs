@@ -1,12 +1,299 @@
+// WezTerm Multi-Process Development Framework - Process Coordinator
+// Task assignment across processes and the coordination bus that carries CoordinationMessage
+//
+// 協調バスは1行1 JSON のプロトコルでプロセスとやり取りします。フレームワークから
+// プロセスへは `CoordinationMessage` を送り、プロセスは `CoordinationResponse` を1行
+// 返します。プロセスから `CoordinationMessage` を書き出すと、コーディネーターが宛先の
+// プロセスへ中継し（宛先が `coordinator` なら自身で処理し）、その応答を送信元へ
+// `CoordinationResponse` として書き戻します。
+
+use crate::logging::LogContext;
 use crate::sync::FileSyncManager;
 use crate::task::TaskDistributor;
-use crate::{CoordinationEvent, CoordinationResponse, ProcessStatus};
+use crate::{log_debug, log_warn};
+use crate::{CoordinationEvent, CoordinationMessage, CoordinationResponse, ProcessStatus};
 use std::collections::HashMap;
 use std::error::Error;
+use std::path::Path;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::process::Child;
+use tokio::sync::{broadcast, mpsc, oneshot, Mutex, RwLock};
+use tokio::task::JoinHandle;
 use uuid::Uuid;
 
+/// コーディネーター自身を表す宛先ID
+pub const COORDINATOR_ID: &str = "coordinator";
+
+/// 応答待ちの既定タイムアウト
+const DEFAULT_RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 購読者が受け取るまで保持するタスク完了の報告数
+const COMPLETION_CAPACITY: usize = 256;
+
+/// プロセスが `TaskCompleted` で報告したタスクの完了
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskCompletion {
+    pub process_id: String,
+    pub task_id: String,
+    pub result: String,
+}
+
+type PendingReply = Arc<std::sync::Mutex<Option<oneshot::Sender<CoordinationResponse>>>>;
+
+/// 1プロセスとの協調チャネル
+struct Channel {
+    writer: Mutex<Box<dyn AsyncWrite + Send + Unpin>>,
+    /// 送信中のメッセージへの応答の受け口
+    pending: PendingReply,
+    /// 1チャネルにつき同時に1メッセージだけ応答を待つ
+    exchange: Mutex<()>,
+    reader: JoinHandle<()>,
+}
+
+impl Drop for Channel {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+impl Channel {
+    async fn write_line<T: serde::Serialize>(&self, value: &T) -> Result<(), String> {
+        let mut line = serde_json::to_vec(value).map_err(|e| e.to_string())?;
+        line.push(b'\n');
+        let mut writer = self.writer.lock().await;
+        writer.write_all(&line).await.map_err(|e| e.to_string())?;
+        writer.flush().await.map_err(|e| e.to_string())
+    }
+}
+
+/// プロセス間で `CoordinationMessage` を配送するバス
+pub struct CoordinationBus {
+    channels: RwLock<HashMap<String, Arc<Channel>>>,
+    /// プロセスが書き出したメッセージ（送信元IDは接続元で上書き済み）
+    inbound: mpsc::UnboundedSender<CoordinationMessage>,
+    response_timeout: Duration,
+}
+
+impl CoordinationBus {
+    /// バスと、プロセスから届いたメッセージの受信口を作成
+    pub fn new() -> (Self, mpsc::UnboundedReceiver<CoordinationMessage>) {
+        let (inbound, inbound_receiver) = mpsc::unbounded_channel();
+        let bus = Self {
+            channels: RwLock::new(HashMap::new()),
+            inbound,
+            response_timeout: DEFAULT_RESPONSE_TIMEOUT,
+        };
+        (bus, inbound_receiver)
+    }
+
+    /// 応答待ちのタイムアウトを設定
+    pub fn with_response_timeout(mut self, timeout: Duration) -> Self {
+        self.response_timeout = timeout;
+        self
+    }
+
+    /// 任意の入出力をプロセスのチャネルとして接続（既存のチャネルは置き換え）
+    pub async fn attach<R, W>(&self, process_id: &str, reader: R, writer: W)
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+        W: AsyncWrite + Send + Unpin + 'static,
+    {
+        let pending: PendingReply = Arc::default();
+        let reader = tokio::spawn(read_channel(
+            process_id.to_string(),
+            reader,
+            Arc::clone(&pending),
+            self.inbound.clone(),
+        ));
+        let channel = Channel {
+            writer: Mutex::new(Box::new(writer)),
+            pending,
+            exchange: Mutex::new(()),
+            reader,
+        };
+        self.channels
+            .write()
+            .await
+            .insert(process_id.to_string(), Arc::new(channel));
+    }
+
+    /// 子プロセスの標準入出力を接続（stdin / stdout を piped で起動しておくこと）
+    pub async fn attach_child(&self, process_id: &str, child: &mut Child) -> Result<(), String> {
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| format!("Process {process_id} has no piped stdin"))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| format!("Process {process_id} has no piped stdout"))?;
+        self.attach(process_id, stdout, stdin).await;
+        Ok(())
+    }
+
+    /// プロセスごとのソケット（Windows では名前付きパイプ）に接続
+    pub async fn attach_socket(
+        &self,
+        process_id: &str,
+        socket_path: impl AsRef<Path>,
+    ) -> std::io::Result<()> {
+        let stream = crate::ipc::transport::connect(socket_path.as_ref()).await?;
+        let (reader, writer) = tokio::io::split(stream);
+        self.attach(process_id, reader, writer).await;
+        Ok(())
+    }
+
+    /// チャネルを切断
+    pub async fn detach(&self, process_id: &str) -> bool {
+        self.channels.write().await.remove(process_id).is_some()
+    }
+
+    /// チャネルが接続されているか
+    pub async fn is_attached(&self, process_id: &str) -> bool {
+        self.channels.read().await.contains_key(process_id)
+    }
+
+    /// チャネルが接続されているプロセスのID（昇順）
+    pub async fn attached_processes(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.channels.read().await.keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    /// 宛先プロセスへメッセージを送り、応答を待つ
+    pub async fn send(&self, message: CoordinationMessage) -> Result<CoordinationResponse, String> {
+        let process_id = message.receiver_id.clone();
+        let channel = self
+            .channels
+            .read()
+            .await
+            .get(&process_id)
+            .cloned()
+            .ok_or_else(|| format!("Process {process_id} has no coordination channel"))?;
+
+        let _turn = channel.exchange.lock().await;
+        let (reply_sender, reply) = oneshot::channel();
+        if let Ok(mut pending) = channel.pending.lock() {
+            *pending = Some(reply_sender);
+        }
+        if let Err(e) = channel.write_line(&message).await {
+            self.detach(&process_id).await;
+            return Err(format!("Failed to send to process {process_id}: {e}"));
+        }
+
+        match tokio::time::timeout(self.response_timeout, reply).await {
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(_)) => {
+                self.detach(&process_id).await;
+                Err(format!(
+                    "Coordination channel of process {process_id} closed"
+                ))
+            }
+            Err(_) => {
+                if let Ok(mut pending) = channel.pending.lock() {
+                    pending.take();
+                }
+                Err(format!(
+                    "Process {process_id} did not respond within {}ms",
+                    self.response_timeout.as_millis()
+                ))
+            }
+        }
+    }
+
+    /// チャネルが接続された全プロセスへ送信（送信元は除外可能）
+    pub async fn broadcast(
+        &self,
+        message: CoordinationMessage,
+        exclude_sender: bool,
+    ) -> Vec<(String, Result<CoordinationResponse, String>)> {
+        let targets: Vec<String> = self
+            .attached_processes()
+            .await
+            .into_iter()
+            .filter(|id| !(exclude_sender && *id == message.sender_id))
+            .collect();
+        self.send_to(&message, targets).await
+    }
+
+    /// 同じメッセージを指定したプロセスへ並行して送信
+    async fn send_to(
+        &self,
+        message: &CoordinationMessage,
+        targets: Vec<String>,
+    ) -> Vec<(String, Result<CoordinationResponse, String>)> {
+        let deliveries = targets.into_iter().map(|process_id| {
+            let addressed = CoordinationMessage {
+                receiver_id: process_id.clone(),
+                ..message.clone()
+            };
+            async move { (process_id, self.send(addressed).await) }
+        });
+        futures_util::future::join_all(deliveries).await
+    }
+
+    /// プロセスが送ってきたメッセージへの応答を書き戻す
+    pub async fn reply(
+        &self,
+        process_id: &str,
+        response: &CoordinationResponse,
+    ) -> Result<(), String> {
+        let channel = self
+            .channels
+            .read()
+            .await
+            .get(process_id)
+            .cloned()
+            .ok_or_else(|| format!("Process {process_id} has no coordination channel"))?;
+        channel.write_line(response).await
+    }
+}
+
+/// プロセスの出力を読み、応答は送信中のメッセージへ、メッセージはバスへ渡す
+async fn read_channel<R: AsyncRead + Unpin>(
+    process_id: String,
+    reader: R,
+    pending: PendingReply,
+    inbound: mpsc::UnboundedSender<CoordinationMessage>,
+) {
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Ok(response) = serde_json::from_str::<CoordinationResponse>(line) {
+            let waiting = pending.lock().ok().and_then(|mut pending| pending.take());
+            match waiting {
+                Some(waiting) => {
+                    let _ = waiting.send(response);
+                }
+                None => {
+                    let stray_context = LogContext::new("coordination", "unexpected_response")
+                        .with_entity_id(&process_id);
+                    log_debug!(stray_context, "Dropped response nobody was waiting for");
+                }
+            }
+        } else if let Ok(mut message) = serde_json::from_str::<CoordinationMessage>(line) {
+            // 送信元は名乗ったIDではなく接続元で決まる
+            message.sender_id = process_id.clone();
+            if inbound.send(message).is_err() {
+                break;
+            }
+        } else {
+            let output_context =
+                LogContext::new("coordination", "ignored_output").with_entity_id(&process_id);
+            log_debug!(output_context, "Ignored non-coordination output: {}", line);
+        }
+    }
+    // 応答待ちの送信側は受け口が破棄されることで切断を知る
+    if let Ok(mut pending) = pending.lock() {
+        pending.take();
+    }
+}
+
 /// プロセス協調のためのコーディネーター
 pub struct ProcessCoordinator {
     /// 登録されたプロセスの状態
@@ -20,6 +307,12 @@ pub struct ProcessCoordinator {
     task_distributor: Arc<RwLock<TaskDistributor>>,
     /// ファイル同期マネージャー
     file_sync_manager: Arc<tokio::sync::Mutex<FileSyncManager>>,
+    /// プロセスとの協調バス
+    bus: Arc<CoordinationBus>,
+    /// バスの受信口（`run` が受け取る）
+    inbound: Mutex<Option<mpsc::UnboundedReceiver<CoordinationMessage>>>,
+    /// プロセスから報告されたタスク完了の通知先
+    completions: broadcast::Sender<TaskCompletion>,
 }
 
 #[derive(Debug, Clone)]
@@ -28,14 +321,18 @@ struct ProcessState {
     id: String,
     status: ProcessStatus,
     task_count: usize,
-    #[allow(dead_code)]
     cpu_usage: f64,
-    #[allow(dead_code)]
     memory_usage: u64,
     #[allow(dead_code)]
     uuid: Uuid,
 }
 
+impl std::fmt::Debug for ProcessCoordinator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProcessCoordinator").finish_non_exhaustive()
+    }
+}
+
 impl Default for ProcessCoordinator {
     fn default() -> Self {
         Self::new()
@@ -45,12 +342,153 @@ impl Default for ProcessCoordinator {
 impl ProcessCoordinator {
     /// 新しいコーディネーターを作成
     pub fn new() -> Self {
+        let (bus, inbound) = CoordinationBus::new();
+        Self::with_bus(bus, inbound)
+    }
+
+    /// 設定済みのバスでコーディネーターを作成
+    pub fn with_bus(
+        bus: CoordinationBus,
+        inbound: mpsc::UnboundedReceiver<CoordinationMessage>,
+    ) -> Self {
         Self {
             processes: Arc::new(RwLock::new(HashMap::new())),
             task_assignments: Arc::new(RwLock::new(HashMap::new())),
             reassigned_tasks: Arc::new(RwLock::new(Vec::new())),
             task_distributor: Arc::new(RwLock::new(TaskDistributor::new())),
             file_sync_manager: Arc::new(tokio::sync::Mutex::new(FileSyncManager::new())),
+            bus: Arc::new(bus),
+            inbound: Mutex::new(Some(inbound)),
+            completions: broadcast::channel(COMPLETION_CAPACITY).0,
+        }
+    }
+
+    /// プロセスとの協調バス
+    pub fn bus(&self) -> &Arc<CoordinationBus> {
+        &self.bus
+    }
+
+    /// プロセスが報告するタスク完了を購読
+    pub fn subscribe_completions(&self) -> broadcast::Receiver<TaskCompletion> {
+        self.completions.subscribe()
+    }
+
+    /// プロセスが書き出したメッセージを配送し、応答を送信元へ返し続ける
+    ///
+    /// 2回目以降の呼び出しは何もせずに戻ります。
+    pub async fn run(self: Arc<Self>) {
+        let Some(mut inbound) = self.inbound.lock().await.take() else {
+            return;
+        };
+        while let Some(message) = inbound.recv().await {
+            let coordinator = Arc::clone(&self);
+            tokio::spawn(async move {
+                let sender_id = message.sender_id.clone();
+                let response = coordinator.dispatch(message).await;
+                if let Err(e) = coordinator.bus.reply(&sender_id, &response).await {
+                    let reply_context =
+                        LogContext::new("coordination", "reply_failed").with_entity_id(&sender_id);
+                    log_warn!(reply_context, "Failed to return response: {}", e);
+                }
+            });
+        }
+    }
+
+    /// メッセージを宛先へ配送し、応答を返す
+    ///
+    /// 宛先が [`COORDINATOR_ID`] のメッセージはコーディネーター自身が処理します。
+    /// 配送に失敗した場合は `CoordinationResponse::Error` を返します。
+    pub async fn dispatch(&self, message: CoordinationMessage) -> CoordinationResponse {
+        if message.receiver_id == COORDINATOR_ID {
+            return self.handle_event(message).await;
+        }
+        let receiver_id = message.receiver_id.clone();
+        self.bus
+            .send(message)
+            .await
+            .unwrap_or_else(|error| CoordinationResponse::Error {
+                process_id: receiver_id,
+                error,
+            })
+    }
+
+    /// コーディネーター宛てのイベントを処理
+    async fn handle_event(&self, message: CoordinationMessage) -> CoordinationResponse {
+        let sender_id = message.sender_id.clone();
+        match message.event {
+            CoordinationEvent::StatusUpdate {
+                status,
+                cpu_usage,
+                memory_usage,
+            } => {
+                let mut processes = self.processes.write().await;
+                if let Some(process) = processes.get_mut(&sender_id) {
+                    process.status = status;
+                    process.cpu_usage = cpu_usage;
+                    process.memory_usage = memory_usage;
+                }
+            }
+            CoordinationEvent::TaskCompleted { task_id, result } => {
+                let assigned = self.task_assignments.write().await.remove(&task_id);
+                if let Some(process_id) = assigned {
+                    if let Some(process) = self.processes.write().await.get_mut(&process_id) {
+                        process.task_count = process.task_count.saturating_sub(1);
+                    }
+                }
+                let _ = self.completions.send(TaskCompletion {
+                    process_id: sender_id,
+                    task_id,
+                    result,
+                });
+            }
+            CoordinationEvent::TaskAssignment {
+                task_id,
+                description,
+            } => {
+                // 最も負荷の低いプロセスへ割り当てて転送
+                let process_id = match self.assign_task(task_id.clone(), description.clone()).await
+                {
+                    Ok(process_id) => process_id,
+                    Err(e) => {
+                        return CoordinationResponse::Error {
+                            process_id: COORDINATOR_ID.to_string(),
+                            error: e.to_string(),
+                        }
+                    }
+                };
+                let forwarded = CoordinationMessage::new(
+                    sender_id,
+                    process_id,
+                    CoordinationEvent::TaskAssignment {
+                        task_id,
+                        description,
+                    },
+                );
+                return Box::pin(self.dispatch(forwarded)).await;
+            }
+            CoordinationEvent::GlobalCommand { .. } => {
+                let responses = self.broadcast_message(sender_id, message.event).await;
+                return CoordinationResponse::Data {
+                    process_id: COORDINATOR_ID.to_string(),
+                    payload: serde_json::to_value(responses).unwrap_or_default(),
+                };
+            }
+            CoordinationEvent::ErrorOccurred {
+                ref error_type,
+                message: ref error_message,
+            } => {
+                let error_context = LogContext::new("coordination", "process_error")
+                    .with_entity_id(&sender_id)
+                    .with_metadata("error_type", serde_json::json!(error_type));
+                log_warn!(
+                    error_context,
+                    "Process reported an error: {}",
+                    error_message
+                );
+            }
+        }
+        CoordinationResponse::Acknowledged {
+            process_id: COORDINATOR_ID.to_string(),
         }
     }
 
@@ -75,6 +513,12 @@ impl ProcessCoordinator {
         file_sync.register_process(process_uuid);
     }
 
+    /// プロセスの登録を解除し、協調チャネルを切断
+    pub async fn unregister_process(&self, process_id: &str) {
+        self.processes.write().await.remove(process_id);
+        self.bus.detach(process_id).await;
+    }
+
     /// タスクを割り当て
     pub async fn assign_task(
         &self,
@@ -125,24 +569,44 @@ impl ProcessCoordinator {
             .collect()
     }
 
-    /// メッセージをブロードキャスト
+    /// 送信元以外の登録済みプロセスへイベントを送り、応答を集める
+    ///
+    /// 協調チャネルを持たないプロセスは配送先がないため、受理した旨の
+    /// `Acknowledged` をコーディネーターが代わりに返します。
     pub async fn broadcast_message(
         &self,
         sender_id: String,
-        _event: CoordinationEvent,
+        event: CoordinationEvent,
     ) -> Vec<CoordinationResponse> {
-        let processes = self.processes.read().await;
-        let mut responses = Vec::new();
+        let mut targets: Vec<String> = self
+            .processes
+            .read()
+            .await
+            .keys()
+            .filter(|process_id| **process_id != sender_id)
+            .cloned()
+            .collect();
+        targets.sort();
 
-        for (process_id, _) in processes.iter() {
-            if process_id != &sender_id {
-                // 実際の実装では、ここでメッセージを送信し、レスポンスを待つ
-                responses.push(CoordinationResponse::Acknowledged {
-                    process_id: process_id.clone(),
-                });
-            }
-        }
+        let attached = self.bus.attached_processes().await;
+        let (connected, unconnected): (Vec<String>, Vec<String>) =
+            targets.into_iter().partition(|id| attached.contains(id));
 
+        let message = CoordinationMessage::new(sender_id, String::new(), event);
+        let mut responses: Vec<CoordinationResponse> = self
+            .bus
+            .send_to(&message, connected)
+            .await
+            .into_iter()
+            .map(|(process_id, result)| {
+                result.unwrap_or_else(|error| CoordinationResponse::Error { process_id, error })
+            })
+            .collect();
+        responses.extend(
+            unconnected
+                .into_iter()
+                .map(|process_id| CoordinationResponse::Acknowledged { process_id }),
+        );
         responses
     }
 
@@ -154,6 +618,7 @@ impl ProcessCoordinator {
 
         // 失敗したプロセスを削除
         processes.remove(&failed_process_id);
+        self.bus.detach(&failed_process_id).await;
 
         // 失敗したプロセスに割り当てられていたタスクを特定
         let failed_tasks: Vec<String> = task_assignments
@@ -184,6 +649,43 @@ impl ProcessCoordinator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tokio::io::{AsyncBufReadExt, DuplexStream};
+
+    /// バスに接続し、プロセス側の端を返す
+    async fn attach_pipe(bus: &CoordinationBus, process_id: &str) -> DuplexStream {
+        let (framework_side, process_side) = tokio::io::duplex(4096);
+        let (reader, writer) = tokio::io::split(framework_side);
+        bus.attach(process_id, reader, writer).await;
+        process_side
+    }
+
+    /// 受け取ったイベントを `Data` で返す疑似プロセスを接続
+    async fn attach_echo_process(bus: &CoordinationBus, process_id: &str) {
+        let process_side = attach_pipe(bus, process_id).await;
+        let process_id = process_id.to_string();
+        tokio::spawn(async move {
+            let (reader, mut writer) = tokio::io::split(process_side);
+            let mut lines = BufReader::new(reader).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                let message: CoordinationMessage = serde_json::from_str(&line).unwrap();
+                let response = CoordinationResponse::Data {
+                    process_id: process_id.clone(),
+                    payload: serde_json::to_value(&message.event).unwrap(),
+                };
+                // 協調メッセージ以外の出力は読み飛ばされる
+                let mut output = b"working...\n".to_vec();
+                output.extend(serde_json::to_vec(&response).unwrap());
+                output.push(b'\n');
+                writer.write_all(&output).await.unwrap();
+            }
+        });
+    }
+
+    async fn write_line<T: serde::Serialize>(stream: &mut (impl AsyncWrite + Unpin), value: &T) {
+        let mut line = serde_json::to_vec(value).unwrap();
+        line.push(b'\n');
+        stream.write_all(&line).await.unwrap();
+    }
 
     #[tokio::test]
     async fn test_process_registration() {
@@ -274,4 +776,110 @@ mod tests {
         assert!(!statuses.contains_key("process-x"));
         assert!(statuses.contains_key("process-y"));
     }
+
+    #[tokio::test]
+    async fn test_bus_delivers_and_returns_response() {
+        let (bus, _inbound) = CoordinationBus::new();
+        attach_echo_process(&bus, "worker").await;
+        assert_eq!(bus.attached_processes().await, ["worker"]);
+
+        let event = CoordinationEvent::TaskAssignment {
+            task_id: "task-1".to_string(),
+            description: "Write tests".to_string(),
+        };
+        let message = CoordinationMessage::new(
+            COORDINATOR_ID.to_string(),
+            "worker".to_string(),
+            event.clone(),
+        );
+        match bus.send(message).await.unwrap() {
+            CoordinationResponse::Data {
+                process_id,
+                payload,
+            } => {
+                assert_eq!(process_id, "worker");
+                assert_eq!(payload, serde_json::to_value(&event).unwrap());
+            }
+            other => panic!("unexpected response: {other:?}"),
+        }
+
+        let missing =
+            CoordinationMessage::new(COORDINATOR_ID.to_string(), "missing".to_string(), event);
+        let error = bus.send(missing).await.unwrap_err();
+        assert!(error.contains("no coordination channel"));
+    }
+
+    #[tokio::test]
+    async fn test_bus_timeout_and_closed_channel() {
+        let (bus, _inbound) = CoordinationBus::new();
+        let bus = bus.with_response_timeout(Duration::from_millis(50));
+        let silent = attach_pipe(&bus, "silent").await;
+        let ping = || {
+            CoordinationMessage::new(
+                COORDINATOR_ID.to_string(),
+                "silent".to_string(),
+                CoordinationEvent::GlobalCommand {
+                    command: "ping".to_string(),
+                    parameters: vec![],
+                },
+            )
+        };
+
+        let error = bus.send(ping()).await.unwrap_err();
+        assert!(error.contains("did not respond within 50ms"));
+        assert!(bus.is_attached("silent").await);
+
+        // プロセスが終了するとチャネルは外れる
+        drop(silent);
+        assert!(bus.send(ping()).await.is_err());
+        assert!(!bus.is_attached("silent").await);
+    }
+
+    #[tokio::test]
+    async fn test_process_messages_are_routed() {
+        let coordinator = Arc::new(ProcessCoordinator::new());
+        coordinator.register_process("a".to_string()).await;
+        coordinator.register_process("b".to_string()).await;
+        attach_echo_process(coordinator.bus(), "b").await;
+        let (reader, mut process_a) = tokio::io::split(attach_pipe(coordinator.bus(), "a").await);
+        let mut replies = BufReader::new(reader).lines();
+        tokio::spawn(Arc::clone(&coordinator).run());
+
+        // a → b: 名乗った送信元ではなく接続元が送信元になる
+        let to_b = CoordinationMessage::new(
+            "spoofed".to_string(),
+            "b".to_string(),
+            CoordinationEvent::GlobalCommand {
+                command: "pause".to_string(),
+                parameters: vec![],
+            },
+        );
+        write_line(&mut process_a, &to_b).await;
+        let reply = replies.next_line().await.unwrap().unwrap();
+        match serde_json::from_str(&reply).unwrap() {
+            CoordinationResponse::Data { process_id, .. } => assert_eq!(process_id, "b"),
+            other => panic!("unexpected response: {other:?}"),
+        }
+
+        // a → coordinator: ステータス更新はコーディネーターが処理する
+        let status = CoordinationMessage::new(
+            "a".to_string(),
+            COORDINATOR_ID.to_string(),
+            CoordinationEvent::StatusUpdate {
+                status: ProcessStatus::Busy,
+                cpu_usage: 42.0,
+                memory_usage: 1024,
+            },
+        );
+        write_line(&mut process_a, &status).await;
+        let reply = replies.next_line().await.unwrap().unwrap();
+        assert_eq!(
+            serde_json::from_str::<CoordinationResponse>(&reply).unwrap(),
+            CoordinationResponse::Acknowledged {
+                process_id: COORDINATOR_ID.to_string()
+            }
+        );
+        let statuses = coordinator.get_all_process_statuses().await;
+        assert!(matches!(statuses["a"], ProcessStatus::Busy));
+    }
 }
@@ -0,0 +1,284 @@
+// WezTerm Multi-Process Development Framework - Process Environment
+// Effective environment of managed processes and per-process override layers
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Shown instead of the value of a variable that looks like a secret
+pub const REDACTED: &str = "[redacted]";
+
+/// Variables set by the framework itself; overrides cannot replace them
+pub const FRAMEWORK_VARS: [&str; 5] = [
+    "CLAUDE_WORKSPACE",
+    "CLAUDE_PROCESS_ID",
+    super::context::CONTEXT_FILE_VAR,
+    crate::git::GIT_ROOT_VAR,
+    crate::git::GIT_BRANCH_VAR,
+];
+
+/// Name fragments of variables whose values are never reported
+const SECRET_MARKERS: [&str; 7] = [
+    "TOKEN",
+    "SECRET",
+    "PASSWORD",
+    "PASSWD",
+    "CREDENTIAL",
+    "PRIVATE",
+    "API_KEY",
+];
+
+/// Layer a variable of the effective environment comes from, lowest first
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvSource {
+    /// Environment of the framework process
+    Inherited,
+    /// `process.environment_vars` in the configuration, or the process's spec
+    Config,
+    /// Per-process override set over IPC
+    Override,
+    /// Workspace and process identity set by the framework
+    Framework,
+}
+
+/// One variable of a process environment
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EnvVar {
+    pub name: String,
+    pub value: String,
+    pub source: EnvSource,
+
+    /// Value replaced by [`REDACTED`]
+    #[serde(default)]
+    pub redacted: bool,
+}
+
+impl EnvVar {
+    /// Copy that is safe to report, with secret values replaced
+    pub fn redact(&self) -> Self {
+        if self.redacted || !is_secret(&self.name) {
+            return self.clone();
+        }
+        Self {
+            value: REDACTED.to_string(),
+            redacted: true,
+            ..self.clone()
+        }
+    }
+}
+
+/// Changes to a process environment applied when it is next started;
+/// `None` removes the variable
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(transparent)]
+pub struct EnvOverrides(BTreeMap<String, Option<String>>);
+
+impl EnvOverrides {
+    /// Set and unset variables, validating every name first
+    pub fn apply(
+        &mut self,
+        set: BTreeMap<String, String>,
+        unset: Vec<String>,
+    ) -> Result<(), String> {
+        for name in set.keys().chain(unset.iter()) {
+            validate_name(name)?;
+        }
+        if let Some((name, _)) = set.iter().find(|(_, value)| value.contains('\0')) {
+            return Err(format!("Value of '{name}' contains a NUL byte"));
+        }
+
+        self.0
+            .extend(set.into_iter().map(|(name, value)| (name, Some(value))));
+        self.0.extend(unset.into_iter().map(|name| (name, None)));
+        Ok(())
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Option<String>)> {
+        self.0.iter()
+    }
+
+    /// Copy that is safe to report, with secret values replaced
+    pub fn redact(&self) -> Self {
+        Self(
+            self.0
+                .iter()
+                .map(|(name, value)| {
+                    let value = match value {
+                        Some(_) if is_secret(name) => Some(REDACTED.to_string()),
+                        other => other.clone(),
+                    };
+                    (name.clone(), value)
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Effective environment reported for a managed process
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ProcessEnvironment {
+    pub process_id: String,
+
+    /// Variables the running process was started with, by name
+    pub variables: Vec<EnvVar>,
+
+    /// Override layer applied on the next (re)start
+    pub overrides: EnvOverrides,
+
+    /// Overrides changed since the process was started
+    pub restart_required: bool,
+}
+
+impl ProcessEnvironment {
+    /// Copy that is safe to report, with secret values replaced
+    pub fn redact(&self) -> Self {
+        Self {
+            variables: self.variables.iter().map(EnvVar::redact).collect(),
+            overrides: self.overrides.redact(),
+            ..self.clone()
+        }
+    }
+}
+
+/// Whether the value of a variable must not be reported
+pub fn is_secret(name: &str) -> bool {
+    let name = name.to_ascii_uppercase();
+    name.ends_with("_KEY")
+        || name.ends_with("_AUTH")
+        || SECRET_MARKERS.iter().any(|marker| name.contains(marker))
+}
+
+pub(crate) fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.contains(['=', '\0']) {
+        return Err(format!("Invalid environment variable name '{name}'"));
+    }
+    if FRAMEWORK_VARS.contains(&name) {
+        return Err(format!("'{name}' is set by the framework"));
+    }
+    Ok(())
+}
+
+/// Resolve the layers into the environment a process is started with
+pub fn resolve(
+    inherited: impl IntoIterator<Item = (String, String)>,
+    config: &HashMap<String, String>,
+    overrides: &EnvOverrides,
+    framework: &[(&str, &str)],
+) -> Vec<EnvVar> {
+    let mut variables: BTreeMap<String, (String, EnvSource)> = inherited
+        .into_iter()
+        .map(|(name, value)| (name, (value, EnvSource::Inherited)))
+        .collect();
+    for (name, value) in config {
+        variables.insert(name.clone(), (value.clone(), EnvSource::Config));
+    }
+    for (name, value) in overrides.iter() {
+        match value {
+            Some(value) => {
+                variables.insert(name.clone(), (value.clone(), EnvSource::Override));
+            }
+            None => {
+                variables.remove(name);
+            }
+        }
+    }
+    for (name, value) in framework {
+        variables.insert(name.to_string(), (value.to_string(), EnvSource::Framework));
+    }
+
+    variables
+        .into_iter()
+        .map(|(name, (value, source))| EnvVar {
+            name,
+            value,
+            source,
+            redacted: false,
+        })
+        .collect()
+}
+
+/// Environment of the framework process, skipping variables that are not UTF-8
+pub fn inherited() -> impl Iterator<Item = (String, String)> {
+    std::env::vars_os()
+        .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layers_and_redaction() {
+        let mut overrides = EnvOverrides::default();
+        overrides
+            .apply(
+                BTreeMap::from([
+                    ("RUST_LOG".to_string(), "debug".to_string()),
+                    ("GITHUB_TOKEN".to_string(), "ghp_123".to_string()),
+                ]),
+                vec!["HOME".to_string()],
+            )
+            .unwrap();
+        let config = HashMap::from([("RUST_LOG".to_string(), "info".to_string())]);
+        let inherited = [
+            ("HOME".to_string(), "/home/dev".to_string()),
+            ("PATH".to_string(), "/usr/bin".to_string()),
+        ];
+
+        let variables = resolve(
+            inherited,
+            &config,
+            &overrides,
+            &[("CLAUDE_WORKSPACE", "frontend")],
+        );
+        let find = |name: &str| variables.iter().find(|v| v.name == name);
+
+        assert!(find("HOME").is_none());
+        assert_eq!(find("PATH").unwrap().source, EnvSource::Inherited);
+        assert_eq!(find("RUST_LOG").unwrap().value, "debug");
+        assert_eq!(find("RUST_LOG").unwrap().source, EnvSource::Override);
+        assert_eq!(
+            find("CLAUDE_WORKSPACE").unwrap().source,
+            EnvSource::Framework
+        );
+
+        let token = find("GITHUB_TOKEN").unwrap().redact();
+        assert_eq!(token.value, REDACTED);
+        assert!(token.redacted);
+        assert_eq!(find("PATH").unwrap().redact().value, "/usr/bin");
+
+        let reported = serde_json::to_value(overrides.redact()).unwrap();
+        assert_eq!(reported["GITHUB_TOKEN"], REDACTED);
+        assert_eq!(reported["HOME"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn test_secret_names_and_validation() {
+        assert!(is_secret("OPENAI_API_KEY"));
+        assert!(is_secret("db_password"));
+        assert!(is_secret("SSH_KEY"));
+        assert!(!is_secret("KEYBOARD_LAYOUT"));
+        assert!(!is_secret("GIT_AUTHOR_NAME"));
+        assert!(!is_secret("RUST_LOG"));
+
+        let mut overrides = EnvOverrides::default();
+        assert!(overrides
+            .apply(
+                BTreeMap::from([("A=B".to_string(), "x".to_string())]),
+                vec![]
+            )
+            .is_err());
+        assert!(overrides
+            .apply(BTreeMap::new(), vec!["CLAUDE_PROCESS_ID".to_string()])
+            .is_err());
+        assert!(overrides.is_empty());
+    }
+}
@@ -0,0 +1,197 @@
+// WezTerm Multi-Process Development Framework - Process Log File
+// Append the output of a process to its log file, rotating it by size
+//
+// The file is the one `tail` follows (`tail::log_path`). Lines are written in
+// the structured format (`[timestamp] [LEVEL] [workspace:id] [STREAM] line`)
+// so the tail level filter applies to them. Rotation renames the file to
+// `<id>.log.<millis>` and starts a new one; the oldest rotated files beyond
+// the limit are removed.
+
+use super::claude_logger::LogConfig;
+use super::output::OutputStream;
+use super::tail;
+use crate::config::Timezone;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub struct ProcessLogFile {
+    path: PathBuf,
+    file: File,
+
+    /// Bytes in the current file
+    size: u64,
+    max_size: u64,
+
+    /// Rotated files kept next to the current one
+    max_files: usize,
+
+    /// `workspace:id`, written into every line
+    source: String,
+    timezone: Timezone,
+}
+
+impl ProcessLogFile {
+    /// The log file of a process, with the default size and file limits
+    pub fn open(workspace: &str, process_id: &str) -> io::Result<Self> {
+        let config = LogConfig::default();
+        Self::open_at(
+            tail::log_path(workspace, process_id),
+            format!("{workspace}:{process_id}"),
+            config.max_file_size_mb * 1024 * 1024,
+            config.max_files as usize,
+            config.timezone,
+        )
+    }
+
+    fn open_at(
+        path: PathBuf,
+        source: String,
+        max_size: u64,
+        max_files: usize,
+        timezone: Timezone,
+    ) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path,
+            file,
+            size,
+            max_size,
+            max_files,
+            source,
+            timezone,
+        })
+    }
+
+    /// Append one output line; stderr is logged at `ERROR`, stdout at `INFO`
+    pub fn write_line(&mut self, stream: OutputStream, line: &str) -> io::Result<()> {
+        if self.size >= self.max_size {
+            self.rotate()?;
+        }
+        let (level, stream) = match stream {
+            OutputStream::Stdout => ("INFO", "STDOUT"),
+            OutputStream::Stderr => ("ERROR", "STDERR"),
+        };
+        let entry = format!(
+            "[{}] [{level}] [{}] [{stream}] {line}\n",
+            self.timezone
+                .localize(&chrono::Utc::now())
+                .format("%Y-%m-%d %H:%M:%S%.3f"),
+            self.source,
+        );
+        self.file.write_all(entry.as_bytes())?;
+        self.size += entry.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        // Two rotations within a millisecond must not overwrite each other
+        let mut millis = crate::clock::unix_millis();
+        let rotated = loop {
+            let mut rotated = self.path.clone().into_os_string();
+            rotated.push(format!(".{millis}"));
+            let rotated = PathBuf::from(rotated);
+            if !rotated.exists() {
+                break rotated;
+            }
+            millis += 1;
+        };
+        fs::rename(&self.path, rotated)?;
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.size = 0;
+        self.prune()
+    }
+
+    /// Remove the oldest rotated files beyond `max_files`
+    fn prune(&self) -> io::Result<()> {
+        let (Some(dir), Some(name)) = (self.path.parent(), self.path.file_name()) else {
+            return Ok(());
+        };
+        let prefix = format!("{}.", name.to_string_lossy());
+        let mut rotated: Vec<(u64, PathBuf)> = fs::read_dir(dir)?
+            .flatten()
+            .filter_map(|entry| {
+                let millis = entry
+                    .file_name()
+                    .to_string_lossy()
+                    .strip_prefix(&prefix)?
+                    .parse()
+                    .ok()?;
+                Some((millis, entry.path()))
+            })
+            .collect();
+        rotated.sort_unstable_by_key(|(millis, _)| std::cmp::Reverse(*millis));
+        for (_, path) in rotated.into_iter().skip(self.max_files) {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::tail::TailFilter;
+
+    #[test]
+    fn test_rotates_and_keeps_max_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("claude-1.log");
+        let mut log = ProcessLogFile::open_at(
+            path.clone(),
+            "dev:claude-1".to_string(),
+            1,
+            2,
+            Timezone::default(),
+        )
+        .unwrap();
+
+        let chunk = tail::read(&path, None, &TailFilter::default(), None).unwrap();
+        for n in 0..5 {
+            log.write_line(OutputStream::Stdout, &format!("line {n}"))
+                .unwrap();
+        }
+
+        // Every write after the first rotated the file; two rotated files remain
+        let files = fs::read_dir(dir.path()).unwrap().count();
+        assert_eq!(files, 3);
+        assert!(fs::read_to_string(&path).unwrap().ends_with("line 4\n"));
+
+        // A reader that fell behind still gets the newest lines in the current file
+        let chunk = tail::read(&path, Some(chunk.cursor), &TailFilter::default(), None).unwrap();
+        assert!(chunk.lines.last().unwrap().ends_with("[STDOUT] line 4"));
+    }
+
+    #[test]
+    fn test_stderr_matches_level_filter() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("claude-1.log");
+        let mut log = ProcessLogFile::open_at(
+            path.clone(),
+            "dev:claude-1".to_string(),
+            u64::MAX,
+            2,
+            Timezone::default(),
+        )
+        .unwrap();
+        log.write_line(OutputStream::Stdout, "building").unwrap();
+        log.write_line(OutputStream::Stderr, "build failed")
+            .unwrap();
+
+        let errors = TailFilter {
+            level: Some("error".to_string()),
+            ..Default::default()
+        };
+        let chunk = tail::read(&path, None, &errors, None).unwrap();
+        assert_eq!(chunk.lines.len(), 1);
+        assert!(chunk.lines[0].ends_with("[dev:claude-1] [STDERR] build failed"));
+    }
+}
@@ -1,40 +1,327 @@
 // WezTerm Multi-Process Development Framework - Process Manager
 
+use super::breaker::{CircuitBreaker, CircuitBreakerConfig, Quarantine};
+use super::context::{self, AgentContext, AgentContextConfig, CONTEXT_FILE_VAR};
+use super::coordinator::ProcessCoordinator;
+use super::env::{self, EnvOverrides, EnvVar, ProcessEnvironment};
+use super::log_file::ProcessLogFile;
+use super::output::{
+    OutputBuffer, OutputChunk, OutputLine, OutputStream, MAX_EXITED_OUTPUTS, OUTPUT_FLUSH_INTERVAL,
+};
+use super::pty::{self, ProcessExit, PtyProcess, TerminalSize};
+use super::spec::ProcessSpec;
+use super::watchdog::{ActivityClock, WatchdogConfig, WATCHDOG_CHECK_INTERVAL};
+use crate::bridge::{BridgeEvent, EventBridge};
+use crate::dashboard::{AlertNotification, AlertSeverity, DashboardMessage};
+use crate::git::{GitInfo, GIT_BRANCH_VAR, GIT_ROOT_VAR};
+use crate::i18n::{Language, Text, TextKey};
 use crate::logging::enhancer::process;
 use crate::logging::LogContext;
+use crate::secrets::{self, SecretProvider, SecretsConfig};
 use crate::{log_debug, log_error, log_info, log_warn};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
+use std::sync::Arc;
 use std::time::{Duration, SystemTime};
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::{Child, Command};
-use tokio::sync::{mpsc, RwLock};
+use tokio::io::{
+    AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream, Lines, ReadHalf, WriteHalf,
+};
+use tokio::process::{Child, ChildStderr, ChildStdin, ChildStdout, Command};
+use tokio::sync::{broadcast, mpsc, Mutex, RwLock};
 use tokio::time::sleep;
 
 use crate::room::state::{ProcessInfo, ProcessStatus};
 
+/// Longest a write to a process's stdin may wait for the process to read
+const STDIN_WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Sent to a terminal for `close`: end of input (Ctrl-D)
+const END_OF_TRANSMISSION: u8 = 0x04;
+
+/// Pipe to a process's stdin; `None` once closed
+type StdinPipe = Arc<Mutex<Option<ChildStdin>>>;
+
+/// Bytes of coordination traffic buffered between a process and the bus
+const COORDINATION_BUFFER: usize = 64 * 1024;
+
+/// Where the output monitor hands a process's coordination lines to the bus
+type CoordinationOutput = WriteHalf<DuplexStream>;
+
 #[derive(Debug)]
 pub struct ProcessManager {
     processes: RwLock<HashMap<String, ManagedProcess>>,
     config: ProcessConfig,
     event_sender: mpsc::UnboundedSender<ProcessEvent>,
+
+    /// Watchdog settings of workspaces whose template overrides the global one
+    workspace_watchdogs: RwLock<HashMap<String, WatchdogConfig>>,
+
+    /// Dashboard channel for watchdog alerts
+    dashboard_tx: Option<broadcast::Sender<DashboardMessage>>,
+    language: Language,
+
+    /// Reports failed processes to external webhooks
+    event_bridge: Option<Arc<EventBridge>>,
+
+    /// Environment overrides by process ID, applied whenever the process starts
+    env_overrides: RwLock<HashMap<String, EnvOverrides>>,
+
+    /// Context files declared by workspace templates, and where the copies
+    /// handed to agents are written
+    workspace_contexts: RwLock<HashMap<String, AgentContextConfig>>,
+    context_dir: PathBuf,
+
+    /// Repositories of workspaces, handed to their processes; processes of
+    /// a workspace with its own worktree run in it
+    workspace_git: RwLock<HashMap<String, GitInfo>>,
+
+    /// Recent stdout/stderr by process ID, kept across restarts and for a
+    /// few processes that are gone
+    outputs: RwLock<HashMap<String, OutputBuffer>>,
+
+    /// Failures by process ID, kept across restarts; an open breaker quarantines the process
+    breakers: RwLock<HashMap<String, CircuitBreaker>>,
+
+    /// Environment variables of workspaces (from their template), between
+    /// the configuration's and the spec's
+    workspace_env: RwLock<HashMap<String, HashMap<String, String>>>,
+
+    /// Most processes a workspace may run, from its override file; the
+    /// configured limits apply to the others
+    workspace_limits: RwLock<HashMap<String, usize>>,
+
+    /// Resolves secret references when a process starts
+    secrets: Arc<dyn SecretProvider>,
+
+    /// Carries `CoordinationMessage`s to and from processes started with stdin
+    coordinator: Option<Arc<ProcessCoordinator>>,
 }
 
 #[derive(Debug)]
 pub struct ManagedProcess {
     info: ProcessInfo,
-    child: Option<Child>,
+    child: Option<ProcessChild>,
     output_monitor: Option<tokio::task::JoinHandle<()>>,
     health_monitor: Option<tokio::task::JoinHandle<()>>,
-    #[allow(dead_code)]
     restart_policy: RestartPolicy,
+
+    /// What the process was started with; restarts run it again
+    spec: ProcessSpec,
+
+    /// Open when the spec asked for stdin and the process is not on a PTY
+    stdin: Option<StdinPipe>,
+
+    /// Last output line or heartbeat
+    activity: ActivityClock,
+
+    /// Tasks currently assigned, and since when the process has had work
+    assigned_tasks: HashSet<String>,
+    assigned_since: Option<SystemTime>,
+
+    /// Environment the process was started with, and the overrides it included
+    environment: Vec<EnvVar>,
+    applied_overrides: EnvOverrides,
+}
+
+/// A running process, started with piped stdio or on a PTY
+#[derive(Debug)]
+enum ProcessChild {
+    Piped(Child),
+    Pty(PtyProcess),
+}
+
+/// Where the output monitor reads a process's output
+enum OutputSource {
+    Piped(ChildStdout, ChildStderr),
+    Pty(mpsc::UnboundedReceiver<std::io::Result<String>>),
+}
+
+impl ProcessChild {
+    fn spawn(
+        spec: &ProcessSpec,
+        env_vars: &[(String, Option<String>)],
+        working_dir: Option<&Path>,
+    ) -> std::io::Result<(Self, OutputSource)> {
+        if let Some(size) = spec.pty {
+            let (process, reader) =
+                PtyProcess::spawn(&spec.program, &spec.args, env_vars, working_dir, size)?;
+            return Ok((
+                ProcessChild::Pty(process),
+                OutputSource::Pty(pty::stream_lines(reader)),
+            ));
+        }
+
+        let mut cmd = Command::new(&spec.program);
+        cmd.args(&spec.args);
+        if spec.stdin {
+            cmd.stdin(Stdio::piped());
+        }
+        cmd.stdout(Stdio::piped());
+        cmd.stderr(Stdio::piped());
+        cmd.kill_on_drop(true);
+        for (key, value) in env_vars {
+            match value {
+                Some(value) => cmd.env(key, value),
+                None => cmd.env_remove(key),
+            };
+        }
+        if let Some(dir) = working_dir {
+            cmd.current_dir(dir);
+        }
+        let mut child = cmd.spawn()?;
+        let stdout = child.stdout.take().unwrap();
+        let stderr = child.stderr.take().unwrap();
+        Ok((
+            ProcessChild::Piped(child),
+            OutputSource::Piped(stdout, stderr),
+        ))
+    }
+
+    fn take_stdin(&mut self) -> Option<ChildStdin> {
+        match self {
+            ProcessChild::Piped(child) => child.stdin.take(),
+            ProcessChild::Pty(_) => None,
+        }
+    }
+
+    fn id(&self) -> Option<u32> {
+        match self {
+            ProcessChild::Piped(child) => child.id(),
+            ProcessChild::Pty(process) => process.id(),
+        }
+    }
+
+    fn try_wait(&mut self) -> std::io::Result<Option<ProcessExit>> {
+        match self {
+            ProcessChild::Piped(child) => Ok(child.try_wait()?.map(ProcessExit::from)),
+            ProcessChild::Pty(process) => process.try_wait(),
+        }
+    }
+
+    async fn kill(self) -> std::io::Result<()> {
+        match self {
+            ProcessChild::Piped(mut child) => child.kill().await,
+            ProcessChild::Pty(process) => tokio::task::spawn_blocking(move || process.kill())
+                .await
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?,
+        }
+    }
+}
+
+/// Lines of one output stream
+enum OutputLines {
+    Stdout(Lines<BufReader<ChildStdout>>),
+    Stderr(Lines<BufReader<ChildStderr>>),
+    Terminal(mpsc::UnboundedReceiver<std::io::Result<String>>),
+    Closed,
+}
+
+impl OutputLines {
+    async fn next_line(&mut self) -> std::io::Result<Option<String>> {
+        match self {
+            OutputLines::Stdout(lines) => lines.next_line().await,
+            OutputLines::Stderr(lines) => lines.next_line().await,
+            OutputLines::Terminal(lines) => lines.recv().await.transpose(),
+            OutputLines::Closed => Ok(None),
+        }
+    }
+}
+
+/// Connect a process to the coordination bus
+///
+/// Messages for the process are written to its stdin; the output monitor
+/// hands its JSON stdout lines to the returned end.
+async fn attach_coordination(
+    coordinator: &ProcessCoordinator,
+    process_id: &str,
+    stdin: StdinPipe,
+) -> CoordinationOutput {
+    let (process_side, bus_side) = tokio::io::duplex(COORDINATION_BUFFER);
+    let (bus_reader, bus_writer) = tokio::io::split(bus_side);
+    coordinator
+        .bus()
+        .attach(process_id, bus_reader, bus_writer)
+        .await;
+    coordinator.register_process(process_id.to_string()).await;
+
+    let (from_bus, to_bus) = tokio::io::split(process_side);
+    tokio::spawn(forward_to_stdin(process_id.to_string(), from_bus, stdin));
+    to_bus
+}
+
+/// Write the bus's messages to a process's stdin until the bus lets go of it
+///
+/// A message the process does not take is dropped; the bus then reports
+/// that the process did not respond.
+async fn forward_to_stdin(process_id: String, from_bus: ReadHalf<DuplexStream>, stdin: StdinPipe) {
+    let mut lines = BufReader::new(from_bus).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let mut pipe = stdin.lock().await;
+        let Some(writer) = pipe.as_mut() else {
+            continue;
+        };
+        let written = tokio::time::timeout(STDIN_WRITE_TIMEOUT, async {
+            writer.write_all(line.as_bytes()).await?;
+            writer.write_all(b"\n").await?;
+            writer.flush().await
+        })
+        .await;
+        if !matches!(written, Ok(Ok(()))) {
+            let drop_context =
+                LogContext::new("coordination", "stdin_write_failure").with_entity_id(&process_id);
+            log_debug!(
+                drop_context,
+                "Dropped a coordination message for '{}'",
+                process_id
+            );
+        }
+    }
+}
+
+/// Hand a stdout line that may be a coordination message to the bus
+async fn forward_to_bus(to_bus: &mut Option<CoordinationOutput>, line: &str) {
+    let Some(writer) = to_bus.as_mut() else {
+        return;
+    };
+    if !line.trim_start().starts_with('{') {
+        return;
+    }
+    let mut framed = line.as_bytes().to_vec();
+    framed.push(b'\n');
+    if writer.write_all(&framed).await.is_err() {
+        // The bus let go of the process
+        *to_bus = None;
+    }
+}
+
+/// Append a line to the process's log file; it is given up after the first failed write
+fn write_log_line(
+    log_file: &mut Option<ProcessLogFile>,
+    process_id: &str,
+    stream: OutputStream,
+    line: &str,
+) {
+    let Some(file) = log_file else {
+        return;
+    };
+    if let Err(e) = file.write_line(stream, line) {
+        let log_context = LogContext::new("process", "log_file_failure").with_entity_id(process_id);
+        log_warn!(log_context, "Stopped writing output to the log file: {}", e);
+        *log_file = None;
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ProcessConfig {
     pub claude_code_binary: String,
     pub max_processes: usize,
+
+    /// Most processes one workspace may run unless its override file sets
+    /// `max_processes`
+    #[serde(default)]
+    pub max_processes_per_workspace: Option<usize>,
     pub health_check_interval_secs: u64,
     pub restart_delay_secs: u64,
     pub max_restart_attempts: u32,
@@ -42,9 +329,17 @@ pub struct ProcessConfig {
     pub default_restart_policy: RestartPolicy,
     pub environment_vars: HashMap<String, String>,
     pub working_directory: Option<String>,
+    #[serde(default)]
+    pub watchdog: WatchdogConfig,
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerConfig,
+
+    /// Provider of the `${secret:NAME}` references in environment variables
+    #[serde(default)]
+    pub secrets: SecretsConfig,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum RestartPolicy {
     Never,
     Always,
@@ -52,6 +347,17 @@ pub enum RestartPolicy {
     OnFailureWithLimit(u32),
 }
 
+impl RestartPolicy {
+    /// Whether a failed process that has restarted `restart_count` times may restart again
+    pub fn allows_restart(&self, restart_count: u32) -> bool {
+        match self {
+            RestartPolicy::Never => false,
+            RestartPolicy::Always | RestartPolicy::OnFailure => true,
+            RestartPolicy::OnFailureWithLimit(limit) => restart_count < *limit,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum ProcessEvent {
     Started {
@@ -82,6 +388,16 @@ pub enum ProcessEvent {
         process_id: String,
         attempt: u32,
     },
+    Unresponsive {
+        process_id: String,
+        workspace: String,
+        idle_secs: u64,
+    },
+    Quarantined {
+        process_id: String,
+        workspace: String,
+        failures: u32,
+    },
 }
 
 impl Default for ProcessConfig {
@@ -92,6 +408,7 @@ impl Default for ProcessConfig {
         Self {
             claude_code_binary: "claude-code".to_string(),
             max_processes: 16,
+            max_processes_per_workspace: None,
             health_check_interval_secs: 30,
             restart_delay_secs: 5,
             max_restart_attempts: 3,
@@ -99,17 +416,26 @@ impl Default for ProcessConfig {
             default_restart_policy: RestartPolicy::OnFailureWithLimit(3),
             environment_vars: env_vars,
             working_directory: None,
+            watchdog: WatchdogConfig::default(),
+            circuit_breaker: CircuitBreakerConfig::default(),
+            secrets: SecretsConfig::default(),
         }
     }
 }
 
 impl ProcessConfig {
+    /// Claude Code with `args`, the profile `spawn_process` starts
+    pub fn claude_code(&self, args: Vec<String>) -> ProcessSpec {
+        ProcessSpec::new(self.claude_code_binary.clone()).with_args(args)
+    }
+
     #[cfg(test)]
     pub fn default_for_testing() -> Self {
         use std::collections::HashMap;
         Self {
             claude_code_binary: "echo".to_string(), // Use echo for testing instead of actual claude-code
             max_processes: 16,
+            max_processes_per_workspace: None,
             health_check_interval_secs: 1,
             restart_delay_secs: 1,
             max_restart_attempts: 3,
@@ -117,6 +443,9 @@ impl ProcessConfig {
             default_restart_policy: RestartPolicy::OnFailure,
             environment_vars: HashMap::new(),
             working_directory: None,
+            watchdog: WatchdogConfig::default(),
+            circuit_breaker: CircuitBreakerConfig::default(),
+            secrets: SecretsConfig::default(),
         }
     }
 }
@@ -125,21 +454,348 @@ impl ProcessManager {
     pub fn new(config: ProcessConfig) -> (Self, mpsc::UnboundedReceiver<ProcessEvent>) {
         let (event_sender, event_receiver) = mpsc::unbounded_channel();
 
+        let secrets = Arc::from(config.secrets.provider());
         let manager = Self {
             processes: RwLock::new(HashMap::new()),
             config,
             event_sender,
+            workspace_watchdogs: RwLock::new(HashMap::new()),
+            dashboard_tx: None,
+            language: Language::default(),
+            event_bridge: None,
+            env_overrides: RwLock::new(HashMap::new()),
+            workspace_contexts: RwLock::new(HashMap::new()),
+            context_dir: Self::default_context_dir(),
+            workspace_git: RwLock::new(HashMap::new()),
+            outputs: RwLock::new(HashMap::new()),
+            breakers: RwLock::new(HashMap::new()),
+            workspace_env: RwLock::new(HashMap::new()),
+            workspace_limits: RwLock::new(HashMap::new()),
+            secrets,
+            coordinator: None,
         };
 
         (manager, event_receiver)
     }
 
+    /// Publish watchdog alerts to the dashboard, rendered in `language` by default
+    pub fn with_dashboard_broadcaster(
+        mut self,
+        dashboard_tx: broadcast::Sender<DashboardMessage>,
+        language: Language,
+    ) -> Self {
+        self.dashboard_tx = Some(dashboard_tx);
+        self.language = language;
+        self
+    }
+
+    /// Report failed processes through the event bridge
+    pub fn with_event_bridge(mut self, event_bridge: Arc<EventBridge>) -> Self {
+        self.event_bridge = Some(event_bridge);
+        self
+    }
+
+    /// Resolve secret references with `provider` instead of `config.secrets`
+    pub fn with_secret_provider(mut self, provider: Arc<dyn SecretProvider>) -> Self {
+        self.secrets = provider;
+        self
+    }
+
+    /// Connect processes started with stdin to the coordination bus
+    ///
+    /// The bus writes messages to the process's stdin, one JSON line each, and
+    /// reads its responses and messages from the JSON lines on its stdout.
+    pub fn with_coordinator(mut self, coordinator: Arc<ProcessCoordinator>) -> Self {
+        self.coordinator = Some(coordinator);
+        self
+    }
+
+    /// Write the context files handed to agents into `dir`
+    pub fn with_context_dir(mut self, dir: PathBuf) -> Self {
+        self.context_dir = dir;
+        self
+    }
+
+    /// Default location of the context files handed to agents
+    pub fn default_context_dir() -> PathBuf {
+        let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+        path.push("wezterm-parallel");
+        path.push("contexts");
+        path
+    }
+
+    /// Declare the context file for the agents of a workspace (from its template)
+    pub async fn set_workspace_context(
+        &self,
+        workspace: &str,
+        context: Option<AgentContextConfig>,
+    ) {
+        let mut contexts = self.workspace_contexts.write().await;
+        match context {
+            Some(context) => contexts.insert(workspace.to_string(), context),
+            None => contexts.remove(workspace),
+        };
+    }
+
+    /// Set the environment variables of a workspace's processes (from its template)
+    pub async fn set_workspace_env(&self, workspace: &str, env: Option<HashMap<String, String>>) {
+        let mut workspace_env = self.workspace_env.write().await;
+        match env {
+            Some(env) => workspace_env.insert(workspace.to_string(), env),
+            None => workspace_env.remove(workspace),
+        };
+    }
+
+    /// Limit the processes of a workspace (from its override file)
+    pub async fn set_workspace_limit(&self, workspace: &str, limit: Option<usize>) {
+        let mut limits = self.workspace_limits.write().await;
+        match limit {
+            Some(limit) => limits.insert(workspace.to_string(), limit),
+            None => limits.remove(workspace),
+        };
+    }
+
+    /// Record the repository and branch of a workspace
+    pub async fn set_workspace_git(&self, workspace: &str, git: Option<GitInfo>) {
+        let mut repositories = self.workspace_git.write().await;
+        match git {
+            Some(git) => repositories.insert(workspace.to_string(), git),
+            None => repositories.remove(workspace),
+        };
+    }
+
+    /// Verify the workspace's context file and prepare the copy for one agent
+    ///
+    /// A missing file fails the spawn only when the workspace requires it.
+    async fn prepare_context(
+        &self,
+        workspace: &str,
+        process_id: &str,
+    ) -> Result<Option<AgentContext>, String> {
+        let Some(config) = self.workspace_contexts.read().await.get(workspace).cloned() else {
+            return Ok(None);
+        };
+
+        match context::prepare(&config, &self.context_dir, workspace, process_id) {
+            Ok(context) => {
+                let log_context = LogContext::new("process", "context")
+                    .with_entity_id(process_id)
+                    .with_metadata("workspace", serde_json::json!(workspace))
+                    .with_metadata("version", serde_json::json!(context.version));
+                log_info!(
+                    log_context,
+                    "Handing context '{}' to process '{}'",
+                    context.source.display(),
+                    process_id
+                );
+                Ok(Some(context))
+            }
+            Err(e) if config.required => Err(e),
+            Err(e) => {
+                let log_context = LogContext::new("process", "context_missing")
+                    .with_entity_id(process_id)
+                    .with_metadata("workspace", serde_json::json!(workspace));
+                log_warn!(log_context, "Starting without context: {}", e);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Override the watchdog for the processes of a workspace (from its template)
+    pub async fn set_workspace_watchdog(&self, workspace: &str, watchdog: Option<WatchdogConfig>) {
+        let mut watchdogs = self.workspace_watchdogs.write().await;
+        match watchdog {
+            Some(watchdog) => watchdogs.insert(workspace.to_string(), watchdog),
+            None => watchdogs.remove(workspace),
+        };
+    }
+
+    /// Record a heartbeat sent by the process itself
+    pub async fn record_heartbeat(&self, process_id: &str) -> bool {
+        let mut processes = self.processes.write().await;
+        match processes.get_mut(process_id) {
+            Some(managed_process) => {
+                managed_process.activity.touch();
+                managed_process.info.last_heartbeat = SystemTime::now();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Replace the task assignments of all processes
+    ///
+    /// The watchdog only watches processes with work assigned; silence counts
+    /// from the moment a process went from no tasks to some.
+    pub async fn sync_assignments(&self, assignments: &HashMap<String, HashSet<String>>) {
+        let now = SystemTime::now();
+        let mut processes = self.processes.write().await;
+        for (process_id, managed_process) in processes.iter_mut() {
+            let tasks = assignments.get(process_id).cloned().unwrap_or_default();
+            managed_process.assigned_since =
+                match (&managed_process.assigned_since, tasks.is_empty()) {
+                    (_, true) => None,
+                    (None, false) => Some(now),
+                    (Some(since), false) => Some(*since),
+                };
+            managed_process.assigned_tasks = tasks;
+        }
+    }
+
+    /// Check for silent working processes every [`WATCHDOG_CHECK_INTERVAL`]
+    pub fn start_watchdog(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let manager = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(WATCHDOG_CHECK_INTERVAL);
+            loop {
+                interval.tick().await;
+                manager.check_watchdog().await;
+            }
+        })
+    }
+
+    /// Mark working processes that stayed silent too long as `Unresponsive`
+    ///
+    /// Returns the processes newly marked. An unresponsive process that shows
+    /// activity again goes back to `Running`.
+    pub async fn check_watchdog(&self) -> Vec<String> {
+        self.check_watchdog_at(SystemTime::now()).await
+    }
+
+    async fn check_watchdog_at(&self, now: SystemTime) -> Vec<String> {
+        let overrides = self.workspace_watchdogs.read().await.clone();
+        let mut unresponsive = Vec::new();
+        {
+            let mut processes = self.processes.write().await;
+            for (process_id, managed_process) in processes.iter_mut() {
+                let watchdog = overrides
+                    .get(&managed_process.info.workspace)
+                    .unwrap_or(&self.config.watchdog);
+                let idle = managed_process
+                    .activity
+                    .idle_for(managed_process.assigned_since.unwrap_or(now), now);
+                let silent = watchdog.enabled
+                    && managed_process.assigned_since.is_some()
+                    && idle >= watchdog.timeout();
+
+                match managed_process.info.status {
+                    ProcessStatus::Unresponsive if !silent => {
+                        managed_process.info.status = ProcessStatus::Running;
+                        let context = LogContext::new("process", "watchdog_recovered")
+                            .with_entity_id(process_id);
+                        log_info!(context, "Process '{}' is responsive again", process_id);
+                    }
+                    ProcessStatus::Running | ProcessStatus::Idle | ProcessStatus::Busy
+                        if silent =>
+                    {
+                        managed_process.info.status = ProcessStatus::Unresponsive;
+                        unresponsive.push((
+                            process_id.clone(),
+                            managed_process.info.workspace.clone(),
+                            idle,
+                            watchdog.restart
+                                && managed_process
+                                    .restart_policy
+                                    .allows_restart(managed_process.info.restart_count),
+                            managed_process.assigned_tasks.len(),
+                        ));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        for (process_id, workspace, idle, restart, task_count) in &unresponsive {
+            self.report_unresponsive(process_id, workspace, *idle, *task_count);
+            if *restart {
+                if let Err(e) = self.restart_process(process_id).await {
+                    let context = LogContext::new("process", "watchdog_restart_failure")
+                        .with_entity_id(process_id);
+                    log_error!(
+                        context,
+                        "Failed to restart unresponsive process '{}': {}",
+                        process_id,
+                        e
+                    );
+                }
+            }
+        }
+        unresponsive
+            .into_iter()
+            .map(|(process_id, ..)| process_id)
+            .collect()
+    }
+
+    fn report_unresponsive(
+        &self,
+        process_id: &str,
+        workspace: &str,
+        idle: Duration,
+        task_count: usize,
+    ) {
+        let text = Text::new(TextKey::ProcessUnresponsive)
+            .arg("process", process_id)
+            .arg("minutes", idle.as_secs() / 60);
+        let message = text.render(self.language);
+
+        let context = LogContext::new("process", "watchdog_unresponsive")
+            .with_entity_id(process_id)
+            .with_metadata("workspace", serde_json::json!(workspace))
+            .with_metadata("idle_secs", serde_json::json!(idle.as_secs()))
+            .with_metadata("assigned_tasks", serde_json::json!(task_count));
+        log_warn!(context, "{}", message);
+
+        let _ = self.event_sender.send(ProcessEvent::Unresponsive {
+            process_id: process_id.to_string(),
+            workspace: workspace.to_string(),
+            idle_secs: idle.as_secs(),
+        });
+
+        if let Some(ref tx) = self.dashboard_tx {
+            let timestamp = SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs());
+            let _ = tx.send(DashboardMessage::Alert(AlertNotification {
+                id: format!("watchdog-{process_id}-{timestamp}"),
+                severity: AlertSeverity::Warning,
+                category: "process".to_string(),
+                message,
+                component: Some(process_id.to_string()),
+                timestamp,
+                details: Some(serde_json::json!({
+                    "workspace": workspace,
+                    "idle_secs": idle.as_secs(),
+                    "assigned_tasks": task_count,
+                })),
+                text: Some(text),
+            }));
+        }
+    }
+
+    /// Start Claude Code with `command_args`
     pub async fn spawn_process(
         &self,
         process_id: String,
         workspace: String,
         command_args: Vec<String>,
     ) -> Result<(), String> {
+        let spec = self.config.claude_code(command_args);
+        self.spawn_command(process_id, workspace, spec).await
+    }
+
+    /// Start an arbitrary program with its own arguments, environment,
+    /// working directory and restart policy
+    pub async fn spawn_command(
+        &self,
+        process_id: String,
+        workspace: String,
+        spec: ProcessSpec,
+    ) -> Result<(), String> {
+        spec.validate()?;
+        if let Some(error) = self.quarantine_error(&process_id).await {
+            return Err(error);
+        }
+
         // Check if process already exists
         {
             let processes = self.processes.read().await;
@@ -157,14 +813,30 @@ impl ProcessManager {
                     self.config.max_processes
                 ));
             }
+            let limit = self
+                .workspace_limits
+                .read()
+                .await
+                .get(&workspace)
+                .copied()
+                .or(self.config.max_processes_per_workspace);
+            if let Some(limit) = limit {
+                let running = processes
+                    .values()
+                    .filter(|p| p.info.workspace == workspace)
+                    .count();
+                if running >= limit {
+                    return Err(format!(
+                        "Workspace '{workspace}' reached its process limit ({limit})"
+                    ));
+                }
+            }
         }
 
+        let agent_context = self.prepare_context(&workspace, &process_id).await?;
+
         // 統一ログ: プロセス起動開始
-        let command_string = format!(
-            "{} {}",
-            self.config.claude_code_binary,
-            command_args.join(" ")
-        );
+        let command_string = spec.command_line();
         process::log_process_start(&process_id, &command_string);
 
         let context = LogContext::new("process", "spawn")
@@ -177,34 +849,78 @@ impl ProcessManager {
             workspace
         );
 
-        let mut cmd = Command::new(&self.config.claude_code_binary);
-        cmd.args(&command_args);
-        cmd.stdout(Stdio::piped());
-        cmd.stderr(Stdio::piped());
-        cmd.kill_on_drop(true);
-
-        // Set environment variables
-        for (key, value) in &self.config.environment_vars {
-            cmd.env(key, value);
+        // Set environment variables; the workspace's, then the spec's own take precedence
+        let mut configured = self.config.environment_vars.clone();
+        if let Some(workspace_env) = self.workspace_env.read().await.get(&workspace) {
+            configured.extend(workspace_env.clone());
         }
+        configured.extend(spec.env.clone());
+        let mut env_vars: Vec<(String, Option<String>)> = configured
+            .iter()
+            .map(|(key, value)| (key.clone(), Some(value.clone())))
+            .collect();
+
+        // Per-process overrides take precedence over the configuration
+        let overrides = self
+            .env_overrides
+            .read()
+            .await
+            .get(&process_id)
+            .cloned()
+            .unwrap_or_default();
+        env_vars.extend(
+            overrides
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone())),
+        );
 
         // Set working directory
-        if let Some(ref wd) = self.config.working_directory {
-            cmd.current_dir(wd);
-        }
+        let repository = self.workspace_git.read().await.get(&workspace).cloned();
+        let base_dir = match repository {
+            Some(ref git) if git.worktree => Some(PathBuf::from(&git.root)),
+            _ => self.config.working_directory.as_ref().map(PathBuf::from),
+        };
+        let working_dir = spec.working_dir(base_dir.as_deref());
 
         // Add workspace-specific environment
-        cmd.env("CLAUDE_WORKSPACE", &workspace);
-        cmd.env("CLAUDE_PROCESS_ID", &process_id);
+        let context_file = agent_context
+            .as_ref()
+            .map(|context| context.delivered.to_string_lossy().to_string());
+        let mut framework_vars = vec![
+            ("CLAUDE_WORKSPACE", workspace.as_str()),
+            ("CLAUDE_PROCESS_ID", process_id.as_str()),
+        ];
+        if let Some(ref context_file) = context_file {
+            framework_vars.push((CONTEXT_FILE_VAR, context_file));
+        }
+        if let Some(ref git) = repository {
+            framework_vars.push((GIT_ROOT_VAR, &git.root));
+            if let Some(ref branch) = git.branch {
+                framework_vars.push((GIT_BRANCH_VAR, branch));
+            }
+        }
+        env_vars.extend(
+            framework_vars
+                .iter()
+                .map(|(key, value)| (key.to_string(), Some(value.to_string()))),
+        );
+        // The reported environment keeps the references; only the child gets the values
+        let environment = env::resolve(env::inherited(), &configured, &overrides, &framework_vars);
+        let (env_vars, secret_values) = self.resolve_secrets(env_vars).await.map_err(|e| {
+            let context = LogContext::new("process", "secret_error").with_entity_id(&process_id);
+            log_error!(context, "Failed to start process '{}': {}", process_id, e);
+            format!("Failed to resolve secrets: {e}")
+        })?;
 
         let start_time = std::time::Instant::now();
-        let mut child = cmd.spawn().map_err(|e| {
-            let context = LogContext::new("process", "spawn_error").with_entity_id(&process_id);
-            log_error!(context, "Failed to spawn process '{}': {}", process_id, e);
-            // 統一ログ: プロセス起動エラー
-            process::log_process_error(&process_id, &format!("Spawn failed: {e}"));
-            format!("Failed to spawn process: {e}")
-        })?;
+        let (mut child, output_source) =
+            ProcessChild::spawn(&spec, &env_vars, working_dir.as_deref()).map_err(|e| {
+                let context = LogContext::new("process", "spawn_error").with_entity_id(&process_id);
+                log_error!(context, "Failed to spawn process '{}': {}", process_id, e);
+                // 統一ログ: プロセス起動エラー
+                process::log_process_error(&process_id, &format!("Spawn failed: {e}"));
+                format!("Failed to spawn process: {e}")
+            })?;
 
         let pid = child.id().unwrap_or(0);
         let _spawn_duration = start_time.elapsed();
@@ -220,11 +936,7 @@ impl ProcessManager {
         // Create process info
         let process_info = ProcessInfo {
             id: process_id.clone(),
-            command: format!(
-                "{} {}",
-                self.config.claude_code_binary,
-                command_args.join(" ")
-            ),
+            command: command_string,
             workspace: workspace.clone(),
             pane_id: None,
             status: ProcessStatus::Starting,
@@ -232,18 +944,65 @@ impl ProcessManager {
             started_at: SystemTime::now(),
             last_heartbeat: SystemTime::now(),
             restart_count: 0,
+            context: agent_context,
         };
 
         // Setup output monitoring
-        let output_monitor = self.spawn_output_monitor(&process_id, &mut child).await;
+        let activity = ActivityClock::new(SystemTime::now());
+        let output = self
+            .outputs
+            .write()
+            .await
+            .entry(process_id.clone())
+            .or_default()
+            .clone();
+        let log_file = match ProcessLogFile::open(&workspace, &process_id) {
+            Ok(log_file) => Some(log_file),
+            Err(e) => {
+                let log_context = LogContext::new("process", "log_file_failure")
+                    .with_entity_id(&process_id)
+                    .with_metadata("workspace", serde_json::json!(workspace));
+                log_warn!(log_context, "Output is not written to the log file: {}", e);
+                None
+            }
+        };
+        let stdin = child
+            .take_stdin()
+            .map(|stdin| Arc::new(Mutex::new(Some(stdin))));
+        let coordination = match (&self.coordinator, &stdin) {
+            (Some(coordinator), Some(stdin)) => {
+                Some(attach_coordination(coordinator, &process_id, Arc::clone(stdin)).await)
+            }
+            _ => None,
+        };
+        let output_monitor = self
+            .spawn_output_monitor(
+                &process_id,
+                output_source,
+                activity.clone(),
+                output,
+                log_file,
+                coordination,
+                secret_values,
+            )
+            .await;
         let health_monitor = self.spawn_health_monitor(&process_id).await;
-
         let managed_process = ManagedProcess {
             info: process_info,
             child: Some(child),
             output_monitor: Some(output_monitor),
             health_monitor: Some(health_monitor),
-            restart_policy: self.config.default_restart_policy.clone(),
+            restart_policy: spec
+                .restart_policy
+                .clone()
+                .unwrap_or_else(|| self.config.default_restart_policy.clone()),
+            spec,
+            stdin,
+            activity,
+            assigned_tasks: HashSet::new(),
+            assigned_since: None,
+            environment,
+            applied_overrides: overrides,
         };
 
         // Add to collection
@@ -284,9 +1043,12 @@ impl ProcessManager {
             if let Some(health_monitor) = managed_process.health_monitor.take() {
                 health_monitor.abort();
             }
+            if let Some(ref coordinator) = self.coordinator {
+                coordinator.unregister_process(process_id).await;
+            }
 
             // Kill child process
-            if let Some(mut child) = managed_process.child.take() {
+            if let Some(child) = managed_process.child.take() {
                 if let Err(e) = child.kill().await {
                     let warn_context =
                         LogContext::new("process", "kill_failure").with_entity_id(process_id);
@@ -322,24 +1084,20 @@ impl ProcessManager {
     }
 
     pub async fn restart_process(&self, process_id: &str) -> Result<(), String> {
+        if let Some(error) = self.quarantine_error(process_id).await {
+            return Err(error);
+        }
+
         let restart_context = LogContext::new("process", "restart").with_entity_id(process_id);
         log_info!(restart_context, "Restarting process '{}'", process_id);
 
         // Get process info before killing
-        let (workspace, command_args, restart_count) = {
+        let (workspace, spec, restart_count) = {
             let processes = self.processes.read().await;
             if let Some(managed_process) = processes.get(process_id) {
-                let command_parts: Vec<String> = managed_process
-                    .info
-                    .command
-                    .split_whitespace()
-                    .skip(1) // Skip binary name
-                    .map(|s| s.to_string())
-                    .collect();
-
                 (
                     managed_process.info.workspace.clone(),
-                    command_parts,
+                    managed_process.spec.clone(),
                     managed_process.info.restart_count,
                 )
             } else {
@@ -366,7 +1124,7 @@ impl ProcessManager {
         sleep(Duration::from_secs(self.config.restart_delay_secs)).await;
 
         // Spawn new process
-        self.spawn_process(process_id.to_string(), workspace, command_args)
+        self.spawn_command(process_id.to_string(), workspace, spec)
             .await?;
 
         // Update restart count
@@ -380,6 +1138,38 @@ impl ProcessManager {
         Ok(())
     }
 
+    /// Recent stdout/stderr of a process, also after it exited
+    ///
+    /// Without `since` the newest `limit` lines; with it the lines after that cursor.
+    pub async fn get_output(
+        &self,
+        process_id: &str,
+        since: Option<u64>,
+        limit: Option<usize>,
+    ) -> Option<OutputChunk> {
+        self.outputs
+            .read()
+            .await
+            .get(process_id)
+            .map(|output| output.since(since, limit))
+    }
+
+    /// Forget the output of all but the most recently active processes that are gone
+    async fn prune_outputs(&self, processes: &HashMap<String, ManagedProcess>) {
+        let mut outputs = self.outputs.write().await;
+        let mut exited: Vec<(u64, String)> = outputs
+            .iter()
+            .filter(|(process_id, _)| !processes.contains_key(*process_id))
+            .map(|(process_id, output)| (output.last_written(), process_id.clone()))
+            .collect();
+        if exited.len() > MAX_EXITED_OUTPUTS {
+            exited.sort();
+            for (_, process_id) in &exited[..exited.len() - MAX_EXITED_OUTPUTS] {
+                outputs.remove(process_id);
+            }
+        }
+    }
+
     pub async fn get_process_info(&self, process_id: &str) -> Option<ProcessInfo> {
         let processes = self.processes.read().await;
         processes.get(process_id).map(|p| p.info.clone())
@@ -390,6 +1180,198 @@ impl ProcessManager {
         processes.values().map(|p| p.info.clone()).collect()
     }
 
+    /// Effective environment of a process and its pending overrides, secrets redacted
+    pub async fn get_environment(&self, process_id: &str) -> Option<ProcessEnvironment> {
+        let processes = self.processes.read().await;
+        let managed_process = processes.get(process_id)?;
+        let overrides = self
+            .env_overrides
+            .read()
+            .await
+            .get(process_id)
+            .cloned()
+            .unwrap_or_default();
+
+        let environment = ProcessEnvironment {
+            process_id: process_id.to_string(),
+            variables: managed_process.environment.clone(),
+            restart_required: overrides != managed_process.applied_overrides,
+            overrides,
+        };
+        Some(environment.redact())
+    }
+
+    /// Change the environment overrides of a process; they apply when it is
+    /// next started, e.g. by `restart_process`
+    pub async fn update_environment(
+        &self,
+        process_id: &str,
+        set: BTreeMap<String, String>,
+        unset: Vec<String>,
+        reset: bool,
+    ) -> Result<ProcessEnvironment, String> {
+        if !self.processes.read().await.contains_key(process_id) {
+            return Err(format!("Process '{process_id}' not found"));
+        }
+
+        {
+            let mut env_overrides = self.env_overrides.write().await;
+            let mut overrides = env_overrides.get(process_id).cloned().unwrap_or_default();
+            if reset {
+                overrides.clear();
+            }
+            overrides.apply(set, unset)?;
+            env_overrides.insert(process_id.to_string(), overrides);
+        }
+
+        let context = LogContext::new("process", "env_update").with_entity_id(process_id);
+        log_info!(
+            context,
+            "Updated environment overrides of process '{}'; they apply on the next restart",
+            process_id
+        );
+
+        self.get_environment(process_id)
+            .await
+            .ok_or_else(|| format!("Process '{process_id}' not found"))
+    }
+
+    /// Type into the terminal of a process started on a PTY
+    pub async fn write_input(&self, process_id: &str, data: &[u8]) -> Result<(), String> {
+        let processes = self.processes.read().await;
+        let managed_process = processes
+            .get(process_id)
+            .ok_or_else(|| format!("Process '{process_id}' not found"))?;
+        match managed_process.child {
+            Some(ProcessChild::Pty(ref terminal)) => terminal
+                .write_input(data)
+                .map_err(|e| format!("Failed to write to process '{process_id}': {e}")),
+            Some(ProcessChild::Piped(_)) => Err(format!(
+                "Process '{process_id}' was not started with a terminal"
+            )),
+            None => Err(format!("Process '{process_id}' is not running")),
+        }
+    }
+
+    /// Write to the stdin of a process: its terminal when it runs on a PTY,
+    /// otherwise the pipe its spec asked for
+    ///
+    /// `close` ends the input afterwards; a terminal gets Ctrl-D instead.
+    pub async fn write_stdin(
+        &self,
+        process_id: &str,
+        data: &[u8],
+        close: bool,
+    ) -> Result<(), String> {
+        let pipe = {
+            let processes = self.processes.read().await;
+            let managed_process = processes
+                .get(process_id)
+                .ok_or_else(|| format!("Process '{process_id}' not found"))?;
+            match managed_process.child {
+                Some(ProcessChild::Pty(ref terminal)) => {
+                    let mut input = data.to_vec();
+                    if close {
+                        input.push(END_OF_TRANSMISSION);
+                    }
+                    terminal
+                        .write_input(&input)
+                        .map_err(|e| format!("Failed to write to process '{process_id}': {e}"))?;
+                    None
+                }
+                Some(ProcessChild::Piped(_)) => {
+                    Some(managed_process.stdin.clone().ok_or_else(|| {
+                        format!(
+                            "Process '{process_id}' was started without stdin; set `stdin: true` in its spec"
+                        )
+                    })?)
+                }
+                None => return Err(format!("Process '{process_id}' is not running")),
+            }
+        };
+
+        // The lock on all processes is released while waiting for the process to read
+        if let Some(pipe) = pipe {
+            let mut pipe = pipe.lock().await;
+            let writer = pipe
+                .as_mut()
+                .ok_or_else(|| format!("Stdin of process '{process_id}' was closed"))?;
+            tokio::time::timeout(STDIN_WRITE_TIMEOUT, async {
+                writer.write_all(data).await?;
+                writer.flush().await
+            })
+            .await
+            .map_err(|_| format!("Process '{process_id}' is not reading its stdin"))?
+            .map_err(|e| format!("Failed to write to stdin of process '{process_id}': {e}"))?;
+            if close {
+                *pipe = None;
+            }
+        }
+
+        let context = LogContext::new("process", "stdin_write")
+            .with_entity_id(process_id)
+            .with_metadata("bytes", serde_json::json!(data.len()))
+            .with_metadata("closed", serde_json::json!(close));
+        log_info!(
+            context,
+            "Wrote {} bytes to stdin of process '{}'",
+            data.len(),
+            process_id
+        );
+        Ok(())
+    }
+
+    /// Replace secret references with their values, which are returned too
+    /// so they can be scrubbed from the process's output
+    async fn resolve_secrets(
+        &self,
+        env_vars: Vec<(String, Option<String>)>,
+    ) -> Result<(Vec<(String, Option<String>)>, Vec<String>), String> {
+        // Providers may run a command or wait on a keychain prompt
+        let provider = Arc::clone(&self.secrets);
+        tokio::task::spawn_blocking(move || {
+            let mut resolved = Vec::new();
+            let env_vars = env_vars
+                .into_iter()
+                .map(|(name, value)| {
+                    let value = value
+                        .map(|value| secrets::expand(&value, provider.as_ref(), &mut resolved))
+                        .transpose()
+                        .map_err(|e| format!("{name}: {e}"))?;
+                    Ok((name, value))
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            Ok((env_vars, resolved))
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
+    /// Resize the terminal of a process started on a PTY; restarts keep the size
+    pub async fn resize_terminal(
+        &self,
+        process_id: &str,
+        size: TerminalSize,
+    ) -> Result<TerminalSize, String> {
+        let mut processes = self.processes.write().await;
+        let managed_process = processes
+            .get_mut(process_id)
+            .ok_or_else(|| format!("Process '{process_id}' not found"))?;
+        match managed_process.child {
+            Some(ProcessChild::Pty(ref mut terminal)) => {
+                terminal
+                    .resize(size)
+                    .map_err(|e| format!("Failed to resize process '{process_id}': {e}"))?;
+                managed_process.spec.pty = Some(size);
+                Ok(size)
+            }
+            Some(ProcessChild::Piped(_)) => Err(format!(
+                "Process '{process_id}' was not started with a terminal"
+            )),
+            None => Err(format!("Process '{process_id}' is not running")),
+        }
+    }
+
     pub async fn get_processes_by_workspace(&self, workspace: &str) -> Vec<ProcessInfo> {
         let processes = self.processes.read().await;
         processes
@@ -407,68 +1389,120 @@ impl ProcessManager {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn spawn_output_monitor(
         &self,
         process_id: &str,
-        child: &mut Child,
+        source: OutputSource,
+        activity: ActivityClock,
+        output: OutputBuffer,
+        mut log_file: Option<ProcessLogFile>,
+        mut coordination: Option<CoordinationOutput>,
+        secret_values: Vec<String>,
     ) -> tokio::task::JoinHandle<()> {
         let process_id = process_id.to_string();
         let event_sender = self.event_sender.clone();
+        let dashboard_tx = self.dashboard_tx.clone();
 
-        let stdout = child.stdout.take().unwrap();
-        let stderr = child.stderr.take().unwrap();
+        // A terminal has one stream, reported as stdout
+        let (mut stdout_reader, mut stderr_reader) = match source {
+            OutputSource::Piped(stdout, stderr) => (
+                OutputLines::Stdout(BufReader::new(stdout).lines()),
+                OutputLines::Stderr(BufReader::new(stderr).lines()),
+            ),
+            OutputSource::Pty(lines) => (OutputLines::Terminal(lines), OutputLines::Closed),
+        };
 
         tokio::spawn(async move {
-            let mut stdout_reader = BufReader::new(stdout).lines();
-            let mut stderr_reader = BufReader::new(stderr).lines();
+            // Lines not yet streamed to the dashboard
+            let mut pending: Vec<OutputLine> = Vec::new();
+            let mut flush = tokio::time::interval(OUTPUT_FLUSH_INTERVAL);
 
-            loop {
+            // Both streams are read to their end; one closing first must not drop the other's lines
+            let mut stdout_open = true;
+            let mut stderr_open = !matches!(stderr_reader, OutputLines::Closed);
+
+            while stdout_open || stderr_open {
                 tokio::select! {
-                    line = stdout_reader.next_line() => {
+                    _ = flush.tick() => {
+                        if let Some(ref tx) = dashboard_tx {
+                            if !pending.is_empty() {
+                                let _ = tx.send(DashboardMessage::ProcessOutput {
+                                    process_id: process_id.clone(),
+                                    lines: std::mem::take(&mut pending),
+                                });
+                            }
+                        }
+                    }
+                    line = stdout_reader.next_line(), if stdout_open => {
                         match line {
                             Ok(Some(line)) => {
+                                let line = secrets::scrub(&line, &secret_values);
+                                activity.touch();
                                 let debug_context = LogContext::new("process", "stdout")
                                     .with_entity_id(&process_id);
                                 log_debug!(debug_context, "Process '{}' stdout: {}", process_id, line);
+                                write_log_line(&mut log_file, &process_id, OutputStream::Stdout, &line);
+                                forward_to_bus(&mut coordination, &line).await;
+                                let buffered = output.push(OutputStream::Stdout, line.clone());
+                                if dashboard_tx.is_some() {
+                                    pending.push(buffered);
+                                }
                                 let _ = event_sender.send(ProcessEvent::OutputLine {
                                     process_id: process_id.clone(),
                                     line,
                                     is_stderr: false,
                                 });
                             }
-                            Ok(None) => break, // EOF
+                            Ok(None) => stdout_open = false, // EOF
                             Err(e) => {
                                 let error_context = LogContext::new("process", "stdout_error")
                                     .with_entity_id(&process_id);
                                 log_error!(error_context, "Error reading stdout for process '{}': {}", process_id, e);
-                                break;
+                                stdout_open = false;
                             }
                         }
                     }
-                    line = stderr_reader.next_line() => {
+                    line = stderr_reader.next_line(), if stderr_open => {
                         match line {
                             Ok(Some(line)) => {
+                                let line = secrets::scrub(&line, &secret_values);
+                                activity.touch();
                                 let debug_context = LogContext::new("process", "stderr")
                                     .with_entity_id(&process_id);
                                 log_debug!(debug_context, "Process '{}' stderr: {}", process_id, line);
+                                write_log_line(&mut log_file, &process_id, OutputStream::Stderr, &line);
+                                let buffered = output.push(OutputStream::Stderr, line.clone());
+                                if dashboard_tx.is_some() {
+                                    pending.push(buffered);
+                                }
                                 let _ = event_sender.send(ProcessEvent::OutputLine {
                                     process_id: process_id.clone(),
                                     line,
                                     is_stderr: true,
                                 });
                             }
-                            Ok(None) => break, // EOF
+                            Ok(None) => stderr_open = false, // EOF
                             Err(e) => {
                                 let error_context = LogContext::new("process", "stderr_error")
                                     .with_entity_id(&process_id);
                                 log_error!(error_context, "Error reading stderr for process '{}': {}", process_id, e);
-                                break;
+                                stderr_open = false;
                             }
                         }
                     }
                 }
             }
 
+            if let Some(ref tx) = dashboard_tx {
+                if !pending.is_empty() {
+                    let _ = tx.send(DashboardMessage::ProcessOutput {
+                        process_id: process_id.clone(),
+                        lines: pending,
+                    });
+                }
+            }
+
             let debug_context =
                 LogContext::new("process", "monitor_terminated").with_entity_id(&process_id);
             log_debug!(
@@ -517,33 +1551,41 @@ impl ProcessManager {
 
         // Check for finished processes
         let mut to_remove = Vec::new();
+        let mut failed = Vec::new();
 
         for (process_id, managed_process) in processes.iter_mut() {
             if let Some(ref mut child) = managed_process.child {
                 match child.try_wait() {
-                    Ok(Some(exit_status)) => {
+                    Ok(Some(exit)) => {
                         let finish_context = LogContext::new("process", "finished")
                             .with_entity_id(process_id)
-                            .with_metadata(
-                                "exit_status",
-                                serde_json::json!(exit_status.to_string()),
-                            );
+                            .with_metadata("exit_status", serde_json::json!(exit.description));
                         log_info!(
                             finish_context,
-                            "Process '{}' finished with exit status: {:?}",
+                            "Process '{}' finished with exit status: {}",
                             process_id,
-                            exit_status
+                            exit.description
                         );
 
-                        managed_process.info.status = if exit_status.success() {
+                        managed_process.info.status = if exit.success {
                             ProcessStatus::Stopped
                         } else {
+                            if let Some(ref bridge) = self.event_bridge {
+                                bridge.publish(BridgeEvent::agent_failed(
+                                    &managed_process.info.workspace,
+                                    process_id,
+                                    exit.code,
+                                    &exit.description,
+                                ));
+                            }
+                            failed
+                                .push((process_id.clone(), managed_process.info.workspace.clone()));
                             ProcessStatus::Failed
                         };
 
                         let _ = self.event_sender.send(ProcessEvent::Stopped {
                             process_id: process_id.clone(),
-                            exit_code: exit_status.code(),
+                            exit_code: exit.code,
                             workspace: managed_process.info.workspace.clone(),
                         });
 
@@ -562,6 +1604,15 @@ impl ProcessManager {
                             e
                         );
                         managed_process.info.status = ProcessStatus::Failed;
+                        if let Some(ref bridge) = self.event_bridge {
+                            bridge.publish(BridgeEvent::agent_failed(
+                                &managed_process.info.workspace,
+                                process_id,
+                                None,
+                                &e.to_string(),
+                            ));
+                        }
+                        failed.push((process_id.clone(), managed_process.info.workspace.clone()));
                         to_remove.push(process_id.clone());
                     }
                 }
@@ -579,10 +1630,18 @@ impl ProcessManager {
                     health_monitor.abort();
                 }
             }
+            if let Some(ref coordinator) = self.coordinator {
+                if failed.iter().any(|(id, _)| id == process_id) {
+                    coordinator.handle_process_failure(process_id.clone()).await;
+                } else {
+                    coordinator.unregister_process(process_id).await;
+                }
+            }
         }
 
         let cleaned_count = initial_count - processes.len();
         if cleaned_count > 0 {
+            self.prune_outputs(&processes).await;
             let cleanup_context = LogContext::new("process", "cleanup")
                 .with_metadata("cleaned_count", serde_json::json!(cleaned_count));
             log_info!(
@@ -591,10 +1650,108 @@ impl ProcessManager {
                 cleaned_count
             );
         }
+        drop(processes);
+
+        for (process_id, workspace) in failed {
+            self.record_failure(&process_id, &workspace).await;
+        }
 
         cleaned_count
     }
 
+    /// Count a failure against the process's circuit breaker and quarantine
+    /// the process when it opens
+    async fn record_failure(&self, process_id: &str, workspace: &str) {
+        let failures = {
+            let mut breakers = self.breakers.write().await;
+            let breaker = breakers.entry(process_id.to_string()).or_default();
+            if !breaker.record_failure(&self.config.circuit_breaker, crate::clock::unix_secs()) {
+                return;
+            }
+            breaker.failures()
+        };
+
+        let minutes = self.config.circuit_breaker.window_secs / 60;
+        let text = Text::new(TextKey::ProcessQuarantined)
+            .arg("process", process_id)
+            .arg("count", failures)
+            .arg("minutes", minutes);
+        let message = text.render(self.language);
+
+        let context = LogContext::new("process", "quarantined")
+            .with_entity_id(process_id)
+            .with_metadata("workspace", serde_json::json!(workspace))
+            .with_metadata("failures", serde_json::json!(failures));
+        log_error!(context, "{}", message);
+
+        let _ = self.event_sender.send(ProcessEvent::Quarantined {
+            process_id: process_id.to_string(),
+            workspace: workspace.to_string(),
+            failures,
+        });
+
+        if let Some(ref tx) = self.dashboard_tx {
+            let _ = tx.send(DashboardMessage::Alert(AlertNotification {
+                id: format!("quarantine-{process_id}"),
+                severity: AlertSeverity::Critical,
+                category: "process".to_string(),
+                message,
+                component: Some(process_id.to_string()),
+                timestamp: crate::clock::unix_secs(),
+                details: Some(serde_json::json!({
+                    "workspace": workspace,
+                    "failures": failures,
+                    "window_secs": self.config.circuit_breaker.window_secs,
+                })),
+                text: Some(text),
+            }));
+        }
+    }
+
+    /// Why a quarantined process may not start
+    async fn quarantine_error(&self, process_id: &str) -> Option<String> {
+        let breakers = self.breakers.read().await;
+        let breaker = breakers
+            .get(process_id)
+            .filter(|breaker| breaker.is_open())?;
+        Some(format!(
+            "Process '{process_id}' is quarantined after {} failures; reset its circuit breaker to start it again",
+            breaker.failures()
+        ))
+    }
+
+    /// Processes whose circuit breaker is open
+    pub async fn quarantined(&self) -> Vec<Quarantine> {
+        let breakers = self.breakers.read().await;
+        let mut quarantined: Vec<Quarantine> = breakers
+            .iter()
+            .filter_map(|(process_id, breaker)| {
+                Some(Quarantine {
+                    process_id: process_id.clone(),
+                    opened_at: breaker.opened_at()?,
+                    failures: breaker.failures(),
+                })
+            })
+            .collect();
+        quarantined.sort_by(|a, b| a.process_id.cmp(&b.process_id));
+        quarantined
+    }
+
+    /// Close the circuit breaker of a quarantined process so it may start again
+    pub async fn reset_circuit_breaker(&self, process_id: &str) -> Result<(), String> {
+        let mut breakers = self.breakers.write().await;
+        match breakers.get_mut(process_id) {
+            Some(breaker) if breaker.is_open() => {
+                breaker.reset();
+                let context =
+                    LogContext::new("process", "circuit_reset").with_entity_id(process_id);
+                log_info!(context, "Circuit breaker of process '{}' reset", process_id);
+                Ok(())
+            }
+            _ => Err(format!("Process '{process_id}' is not quarantined")),
+        }
+    }
+
     pub async fn get_process_count(&self) -> usize {
         let processes = self.processes.read().await;
         processes.len()
@@ -638,6 +1795,7 @@ mod tests {
         ProcessConfig {
             claude_code_binary: "echo".to_string(), // Use echo for testing
             max_processes: 2,
+            max_processes_per_workspace: None,
             health_check_interval_secs: 1,
             restart_delay_secs: 1,
             max_restart_attempts: 1,
@@ -645,6 +1803,9 @@ mod tests {
             default_restart_policy: RestartPolicy::Never,
             environment_vars: HashMap::new(),
             working_directory: None,
+            watchdog: WatchdogConfig::default(),
+            circuit_breaker: CircuitBreakerConfig::default(),
+            secrets: SecretsConfig::default(),
         }
     }
 
@@ -688,6 +1849,463 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_crash_looping_process_is_quarantined() {
+        let config = ProcessConfig {
+            claude_code_binary: "false".to_string(),
+            circuit_breaker: CircuitBreakerConfig {
+                enabled: true,
+                max_failures: 2,
+                window_secs: 600,
+            },
+            ..create_test_config()
+        };
+        let (manager, mut receiver) = ProcessManager::new(config);
+        let (dashboard_tx, mut dashboard_rx) = broadcast::channel(16);
+        let manager = manager.with_dashboard_broadcaster(dashboard_tx, Language::En);
+
+        for _ in 0..2 {
+            manager
+                .spawn_process("crasher".to_string(), "ws".to_string(), Vec::new())
+                .await
+                .unwrap();
+            tokio::time::timeout(Duration::from_secs(5), async {
+                while manager.cleanup_finished_processes().await == 0 {
+                    sleep(Duration::from_millis(10)).await;
+                }
+            })
+            .await
+            .unwrap();
+        }
+
+        let quarantined = manager.quarantined().await;
+        assert_eq!(quarantined.len(), 1);
+        assert_eq!(quarantined[0].failures, 2);
+        let error = manager
+            .spawn_process("crasher".to_string(), "ws".to_string(), Vec::new())
+            .await
+            .unwrap_err();
+        assert!(error.contains("quarantined"));
+
+        let mut events = Vec::new();
+        while let Ok(event) = receiver.try_recv() {
+            events.push(event);
+        }
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, ProcessEvent::Quarantined { failures: 2, .. })));
+        let mut alerts = Vec::new();
+        while let Ok(message) = dashboard_rx.try_recv() {
+            if let DashboardMessage::Alert(alert) = message {
+                alerts.push(alert);
+            }
+        }
+        assert!(alerts
+            .iter()
+            .any(|alert| alert.id == "quarantine-crasher"
+                && alert.severity == AlertSeverity::Critical));
+
+        manager.reset_circuit_breaker("crasher").await.unwrap();
+        assert!(manager.quarantined().await.is_empty());
+        assert!(manager.reset_circuit_breaker("crasher").await.is_err());
+        manager
+            .spawn_process("crasher".to_string(), "ws".to_string(), Vec::new())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_environment_overrides_apply_on_restart() {
+        let mut config = create_test_config();
+        config
+            .environment_vars
+            .insert("RUST_LOG".to_string(), "info".to_string());
+        let (manager, _receiver) = ProcessManager::new(config);
+        manager
+            .spawn_process("env-process".to_string(), "ws".to_string(), Vec::new())
+            .await
+            .unwrap();
+
+        let environment = manager.get_environment("env-process").await.unwrap();
+        let rust_log = environment
+            .variables
+            .iter()
+            .find(|v| v.name == "RUST_LOG")
+            .unwrap();
+        assert_eq!(rust_log.source, env::EnvSource::Config);
+        assert!(!environment.restart_required);
+
+        let environment = manager
+            .update_environment(
+                "env-process",
+                BTreeMap::from([
+                    ("RUST_LOG".to_string(), "debug".to_string()),
+                    ("SERVICE_TOKEN".to_string(), "abc".to_string()),
+                ]),
+                Vec::new(),
+                false,
+            )
+            .await
+            .unwrap();
+        assert!(environment.restart_required);
+        let pending = serde_json::to_value(&environment.overrides).unwrap();
+        assert_eq!(pending["SERVICE_TOKEN"], env::REDACTED);
+
+        manager.restart_process("env-process").await.unwrap();
+        let environment = manager.get_environment("env-process").await.unwrap();
+        assert!(!environment.restart_required);
+        let find = |name: &str| {
+            environment
+                .variables
+                .iter()
+                .find(|v| v.name == name)
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(find("RUST_LOG").value, "debug");
+        assert_eq!(find("SERVICE_TOKEN").value, env::REDACTED);
+        assert_eq!(find("CLAUDE_PROCESS_ID").value, "env-process");
+
+        assert!(manager
+            .update_environment("missing", BTreeMap::new(), Vec::new(), true)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_spawn_hands_workspace_context_to_agent() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("AGENTS.md"), "# Project rules\n").unwrap();
+        let (manager, _receiver) = ProcessManager::new(create_test_config());
+        let manager = manager.with_context_dir(dir.path().join("contexts"));
+
+        manager
+            .set_workspace_context(
+                "api",
+                Some(AgentContextConfig {
+                    file: dir.path().join("MISSING.md"),
+                    framework_instructions: true,
+                    required: true,
+                }),
+            )
+            .await;
+        assert!(manager
+            .spawn_process("agent".to_string(), "api".to_string(), Vec::new())
+            .await
+            .is_err());
+
+        manager
+            .set_workspace_context(
+                "api",
+                Some(AgentContextConfig {
+                    file: dir.path().join("AGENTS.md"),
+                    framework_instructions: true,
+                    required: true,
+                }),
+            )
+            .await;
+        manager
+            .spawn_process("agent".to_string(), "api".to_string(), Vec::new())
+            .await
+            .unwrap();
+
+        let context = manager.get_process_info("agent").await.unwrap().context;
+        let context = context.unwrap();
+        assert_eq!(context.source, dir.path().join("AGENTS.md"));
+        assert!(!context.is_outdated());
+        let environment = manager.get_environment("agent").await.unwrap();
+        let context_file = environment
+            .variables
+            .iter()
+            .find(|v| v.name == CONTEXT_FILE_VAR)
+            .unwrap();
+        assert_eq!(context_file.value, context.delivered.to_string_lossy());
+        assert_eq!(context_file.source, env::EnvSource::Framework);
+    }
+
+    #[tokio::test]
+    async fn test_output_is_buffered_and_streamed() {
+        let (manager, _receiver) = ProcessManager::new(create_test_config());
+        let (dashboard_tx, mut dashboard_rx) = broadcast::channel(8);
+        let manager = manager.with_dashboard_broadcaster(dashboard_tx, Language::En);
+        manager
+            .spawn_process(
+                "echo-process".to_string(),
+                "ws".to_string(),
+                vec!["hello".to_string()],
+            )
+            .await
+            .unwrap();
+
+        let message = tokio::time::timeout(Duration::from_secs(5), dashboard_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        match message {
+            DashboardMessage::ProcessOutput { process_id, lines } => {
+                assert_eq!(process_id, "echo-process");
+                assert_eq!(lines[0].line, "hello");
+                assert_eq!(lines[0].stream, OutputStream::Stdout);
+            }
+            other => panic!("Expected ProcessOutput, got {other:?}"),
+        }
+
+        let output = manager
+            .get_output("echo-process", None, None)
+            .await
+            .unwrap();
+        assert_eq!(output.lines.len(), 1);
+        assert_eq!(output.cursor, 1);
+        let newer = manager
+            .get_output("echo-process", Some(output.cursor), None)
+            .await
+            .unwrap();
+        assert!(newer.lines.is_empty());
+        assert!(manager.get_output("missing", None, None).await.is_none());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_spawn_command_runs_spec() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("web")).unwrap();
+        let mut config = create_test_config();
+        config.working_directory = Some(dir.path().to_string_lossy().to_string());
+        let (manager, _receiver) = ProcessManager::new(config);
+        let spec = ProcessSpec::new("sh")
+            .with_args(["-c", "basename \"$(pwd)\"; echo \"$PORT\""])
+            .with_cwd("web")
+            .with_env("PORT", "3000")
+            .with_restart_policy(RestartPolicy::Always);
+        manager
+            .spawn_command("dev-server".to_string(), "ws".to_string(), spec.clone())
+            .await
+            .unwrap();
+
+        let mut lines = Vec::new();
+        for _ in 0..50 {
+            lines = manager
+                .get_output("dev-server", None, None)
+                .await
+                .unwrap()
+                .lines;
+            if lines.len() == 2 {
+                break;
+            }
+            sleep(Duration::from_millis(100)).await;
+        }
+        let lines: Vec<_> = lines.into_iter().map(|line| line.line).collect();
+        assert_eq!(lines, ["web", "3000"]);
+        {
+            let processes = manager.processes.read().await;
+            let process = &processes["dev-server"];
+            assert_eq!(process.restart_policy, RestartPolicy::Always);
+            assert_eq!(process.info.command, spec.command_line());
+        }
+
+        // Restarts run the same spec, not the Claude Code profile
+        manager.restart_process("dev-server").await.unwrap();
+        let processes = manager.processes.read().await;
+        assert_eq!(processes["dev-server"].spec, spec);
+        drop(processes);
+
+        assert!(manager
+            .spawn_command("empty".to_string(), "ws".to_string(), ProcessSpec::new(""))
+            .await
+            .is_err());
+    }
+
+    #[cfg(unix)]
+    async fn next_output_line(receiver: &mut mpsc::UnboundedReceiver<ProcessEvent>) -> String {
+        loop {
+            let event = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            if let ProcessEvent::OutputLine {
+                line, is_stderr, ..
+            } = event
+            {
+                assert!(!is_stderr);
+                return line;
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_pty_process_takes_input_and_resizes() {
+        let (manager, mut receiver) = ProcessManager::new(create_test_config());
+        let spec = ProcessSpec::new("sh")
+            .with_args(["-c", "test -t 1 && echo tty; read line; stty size"])
+            .with_pty(TerminalSize::default());
+        manager
+            .spawn_command("shell".to_string(), "ws".to_string(), spec)
+            .await
+            .unwrap();
+
+        assert_eq!(next_output_line(&mut receiver).await, "tty");
+
+        let size = TerminalSize {
+            rows: 50,
+            cols: 132,
+        };
+        assert_eq!(manager.resize_terminal("shell", size).await, Ok(size));
+        manager.write_input("shell", b"go\n").await.unwrap();
+        assert_eq!(next_output_line(&mut receiver).await, "go");
+        assert_eq!(next_output_line(&mut receiver).await, "50 132");
+        assert_eq!(manager.processes.read().await["shell"].spec.pty, Some(size));
+
+        manager
+            .spawn_process("piped".to_string(), "ws".to_string(), Vec::new())
+            .await
+            .unwrap();
+        assert!(manager.write_input("piped", b"x").await.is_err());
+        assert!(manager.write_input("missing", b"x").await.is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_write_stdin_answers_prompt_and_closes() {
+        let (manager, mut receiver) = ProcessManager::new(create_test_config());
+        let spec = ProcessSpec::new("sh")
+            .with_args(["-c", "read answer; echo \"answer: $answer\"; cat"])
+            .with_stdin();
+        manager
+            .spawn_command("prompt".to_string(), "ws".to_string(), spec)
+            .await
+            .unwrap();
+
+        manager
+            .write_stdin("prompt", b"yes\n", false)
+            .await
+            .unwrap();
+        assert_eq!(next_output_line(&mut receiver).await, "answer: yes");
+
+        // `cat` exits once stdin is closed
+        manager.write_stdin("prompt", b"bye\n", true).await.unwrap();
+        assert_eq!(next_output_line(&mut receiver).await, "bye");
+        let error = manager
+            .write_stdin("prompt", b"more", false)
+            .await
+            .unwrap_err();
+        assert!(error.contains("closed"), "{error}");
+
+        manager
+            .spawn_process("no-stdin".to_string(), "ws".to_string(), Vec::new())
+            .await
+            .unwrap();
+        let error = manager
+            .write_stdin("no-stdin", b"x", false)
+            .await
+            .unwrap_err();
+        assert!(error.contains("without stdin"), "{error}");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_coordination_messages_reach_process_stdin() {
+        use crate::process::coordinator::COORDINATOR_ID;
+        use crate::{CoordinationEvent, CoordinationMessage, CoordinationResponse};
+
+        let coordinator = Arc::new(ProcessCoordinator::new());
+        let (manager, _receiver) = ProcessManager::new(create_test_config());
+        let manager = manager.with_coordinator(Arc::clone(&coordinator));
+
+        // Answers the first message with the message itself, ignores what follows
+        let script = r#"echo starting; read message; printf '{"Data":{"process_id":"worker","payload":%s}}\n' "$message"; exec cat >/dev/null"#;
+        let spec = ProcessSpec::new("sh")
+            .with_args(["-c", script])
+            .with_stdin();
+        manager
+            .spawn_command("worker".to_string(), "ws".to_string(), spec)
+            .await
+            .unwrap();
+        assert!(coordinator.bus().is_attached("worker").await);
+        assert!(coordinator
+            .get_all_process_statuses()
+            .await
+            .contains_key("worker"));
+
+        let message = CoordinationMessage::new(
+            COORDINATOR_ID.to_string(),
+            "worker".to_string(),
+            CoordinationEvent::TaskAssignment {
+                task_id: "task-1".to_string(),
+                description: "Write tests".to_string(),
+            },
+        );
+        match coordinator.dispatch(message.clone()).await {
+            CoordinationResponse::Data {
+                process_id,
+                payload,
+            } => {
+                assert_eq!(process_id, "worker");
+                let received: CoordinationMessage = serde_json::from_value(payload).unwrap();
+                assert_eq!(received.event, message.event);
+            }
+            other => panic!("unexpected response: {other:?}"),
+        }
+
+        // Stopping the process takes it off the bus
+        manager.kill_process("worker").await.unwrap();
+        assert!(!coordinator.bus().is_attached("worker").await);
+    }
+
+    #[cfg(unix)]
+    #[derive(Debug)]
+    struct FixedSecrets;
+
+    #[cfg(unix)]
+    impl SecretProvider for FixedSecrets {
+        fn name(&self) -> &'static str {
+            "fixed"
+        }
+
+        fn get(&self, name: &str) -> Result<Option<String>, String> {
+            Ok((name == "deploy").then(|| "dk-0123456789".to_string()))
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_secrets_resolved_at_spawn_and_scrubbed() {
+        let (manager, mut receiver) = ProcessManager::new(create_test_config());
+        let manager = manager.with_secret_provider(Arc::new(FixedSecrets));
+        manager
+            .set_workspace_env(
+                "ws",
+                Some(HashMap::from([(
+                    "UPLOAD_URL".to_string(),
+                    "${secret:deploy}".to_string(),
+                )])),
+            )
+            .await;
+        let spec = ProcessSpec::new("sh").with_args(["-c", "echo \"url=$UPLOAD_URL\""]);
+        manager
+            .spawn_command("deployer".to_string(), "ws".to_string(), spec)
+            .await
+            .unwrap();
+
+        // The process got the value, its output and environment only show the reference
+        assert_eq!(next_output_line(&mut receiver).await, "url=[redacted]");
+        let environment = manager.get_environment("deployer").await.unwrap();
+        let upload_url = environment
+            .variables
+            .iter()
+            .find(|v| v.name == "UPLOAD_URL")
+            .unwrap();
+        assert_eq!(upload_url.value, "${secret:deploy}");
+
+        let spec = ProcessSpec::new("true").with_env("UPLOAD_URL", "${secret:missing}");
+        let error = manager
+            .spawn_command("broken".to_string(), "ws".to_string(), spec)
+            .await
+            .unwrap_err();
+        assert!(error.contains("Secret 'missing' was not found"), "{error}");
+        assert!(manager.get_process_info("broken").await.is_none());
+    }
+
     #[tokio::test]
     async fn test_process_limit() {
         let config = create_test_config();
@@ -795,4 +2413,81 @@ mod tests {
         assert_eq!(workspace_1_processes.len(), 1);
         assert_eq!(workspace_1_processes[0].id, "test-process-1");
     }
+
+    #[tokio::test]
+    async fn test_watchdog_flags_silent_working_process() {
+        let config = ProcessConfig {
+            claude_code_binary: "sleep".to_string(),
+            ..create_test_config()
+        };
+        let (manager, mut receiver) = ProcessManager::new(config);
+        let (dashboard_tx, mut dashboard_rx) = broadcast::channel(8);
+        let manager = manager.with_dashboard_broadcaster(dashboard_tx, Language::En);
+        manager
+            .spawn_process(
+                "agent".to_string(),
+                "ws".to_string(),
+                vec!["30".to_string()],
+            )
+            .await
+            .unwrap();
+
+        // Silence without work is fine
+        let later = SystemTime::now() + Duration::from_secs(11 * 60 + 30);
+        assert!(manager.check_watchdog_at(later).await.is_empty());
+
+        let assignments =
+            HashMap::from([("agent".to_string(), HashSet::from(["task-1".to_string()]))]);
+        manager.sync_assignments(&assignments).await;
+        assert!(manager
+            .check_watchdog_at(SystemTime::now())
+            .await
+            .is_empty());
+
+        let later = SystemTime::now() + Duration::from_secs(11 * 60 + 30);
+        assert_eq!(manager.check_watchdog_at(later).await, vec!["agent"]);
+        assert_eq!(
+            manager.get_process_info("agent").await.unwrap().status,
+            ProcessStatus::Unresponsive
+        );
+        assert!(manager.check_watchdog_at(later).await.is_empty());
+
+        match dashboard_rx.try_recv().unwrap() {
+            DashboardMessage::Alert(alert) => assert_eq!(
+                alert.message,
+                "Process agent has produced no output for 11 minutes while working"
+            ),
+            other => panic!("unexpected message: {other:?}"),
+        }
+        let mut events = Vec::new();
+        while let Ok(event) = receiver.try_recv() {
+            events.push(event);
+        }
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, ProcessEvent::Unresponsive { idle_secs, .. } if *idle_secs >= 11 * 60)));
+
+        // A heartbeat brings it back
+        assert!(manager.record_heartbeat("agent").await);
+        manager.check_watchdog_at(SystemTime::now()).await;
+        assert_eq!(
+            manager.get_process_info("agent").await.unwrap().status,
+            ProcessStatus::Running
+        );
+
+        // The workspace template can switch the watchdog off
+        manager
+            .set_workspace_watchdog(
+                "ws",
+                Some(WatchdogConfig {
+                    enabled: false,
+                    ..Default::default()
+                }),
+            )
+            .await;
+        let later = SystemTime::now() + Duration::from_secs(60 * 60);
+        assert!(manager.check_watchdog_at(later).await.is_empty());
+
+        manager.kill_process("agent").await.unwrap();
+    }
 }
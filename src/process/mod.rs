@@ -1,24 +1,42 @@
 // WezTerm Multi-Process Development Framework - Process Management Module
 
+pub mod breaker;
 pub mod claude_config;
 pub mod claude_health;
 pub mod claude_logger;
+pub mod context;
 pub mod coordinator;
 pub mod detector;
+pub mod env;
+pub mod log_file;
 pub mod manager;
 pub mod monitor;
+pub mod output;
 pub mod pool;
+pub mod pty;
 pub mod router;
+pub mod spec;
+pub mod tail;
+pub mod watchdog;
 
 pub use crate::room::state::ProcessInfo;
+pub use breaker::{CircuitBreakerConfig, Quarantine};
 pub use claude_config::{ClaudeCodeConfig, ClaudeCodeConfigBuilder, WorkspaceSpecificConfig};
 pub use claude_health::{ClaudeHealthMonitor, HealthConfig, HealthState, HealthStatus};
 pub use claude_logger::{
     ClaudeLogger, DebugInfo, DebugType, LogConfig, LogEntry, LogLevel, LogSource, LogStatistics,
 };
+pub use context::{AgentContext, AgentContextConfig};
 pub use coordinator::ProcessCoordinator;
 pub use detector::ClaudeCodeDetector;
+pub use env::{EnvOverrides, EnvSource, EnvVar, ProcessEnvironment};
+pub use log_file::ProcessLogFile;
 pub use manager::{ProcessConfig, ProcessEvent, ProcessManager};
 pub use monitor::ProcessMonitor;
+pub use output::{OutputBuffer, OutputChunk, OutputLine, OutputStream};
 pub use pool::ProcessPool;
+pub use pty::TerminalSize;
 pub use router::MessageRouter;
+pub use spec::ProcessSpec;
+pub use tail::{TailChunk, TailCursor, TailFilter};
+pub use watchdog::WatchdogConfig;
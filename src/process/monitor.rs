@@ -224,6 +224,7 @@ impl ProcessMonitor {
             ProcessStatus::Stopped => (0.0, 0, 0),
             ProcessStatus::Stopping => (10.0, 48, 300),
             ProcessStatus::Restarting => (20.0, 112, 250),
+            ProcessStatus::Unresponsive => (1.0, 128, 5000),
         };
 
         // Add some variance based on existing metrics
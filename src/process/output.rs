@@ -0,0 +1,180 @@
+// WezTerm Multi-Process Development Framework - Process Output
+// Rolling stdout/stderr buffers of managed processes
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Lines kept per process
+pub const OUTPUT_CAPACITY: usize = 2000;
+
+/// Longer lines are cut at this many bytes
+pub const MAX_LINE_LEN: usize = 4096;
+
+/// Output of processes that are gone is kept for this many of them
+pub const MAX_EXITED_OUTPUTS: usize = 16;
+
+/// How often new lines are streamed to the dashboard
+pub const OUTPUT_FLUSH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Lines returned when a request sets no limit, and at most
+pub const DEFAULT_OUTPUT_LIMIT: usize = 200;
+pub const MAX_OUTPUT_LIMIT: usize = OUTPUT_CAPACITY;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// One line a process wrote
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OutputLine {
+    /// Increases with every line of the process, across restarts
+    pub seq: u64,
+
+    /// Unix time in milliseconds
+    pub timestamp: u64,
+    pub stream: OutputStream,
+    pub line: String,
+}
+
+/// Lines after a cursor
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct OutputChunk {
+    pub lines: Vec<OutputLine>,
+
+    /// `seq` of the last line returned (or the newest line); the `since` of the next request
+    pub cursor: u64,
+
+    /// Lines after `since` that no longer fit in the buffer
+    pub dropped: u64,
+}
+
+#[derive(Debug, Default)]
+struct Ring {
+    lines: VecDeque<OutputLine>,
+    last_seq: u64,
+}
+
+/// Rolling output of one process, shared with its output monitor
+#[derive(Debug, Clone, Default)]
+pub struct OutputBuffer(Arc<Mutex<Ring>>);
+
+impl OutputBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&self, stream: OutputStream, mut line: String) -> OutputLine {
+        if line.len() > MAX_LINE_LEN {
+            let mut end = MAX_LINE_LEN;
+            while !line.is_char_boundary(end) {
+                end -= 1;
+            }
+            line.truncate(end);
+            line.push('…');
+        }
+
+        let mut ring = self.0.lock().unwrap();
+        ring.last_seq += 1;
+        let line = OutputLine {
+            seq: ring.last_seq,
+            timestamp: crate::clock::unix_millis(),
+            stream,
+            line,
+        };
+        if ring.lines.len() >= OUTPUT_CAPACITY {
+            ring.lines.pop_front();
+        }
+        ring.lines.push_back(line.clone());
+        line
+    }
+
+    /// The newest `limit` lines, or with `since` the oldest `limit` lines after it
+    pub fn since(&self, since: Option<u64>, limit: Option<usize>) -> OutputChunk {
+        let ring = self.0.lock().unwrap();
+        let limit = limit
+            .unwrap_or(DEFAULT_OUTPUT_LIMIT)
+            .clamp(1, MAX_OUTPUT_LIMIT);
+
+        let lines: Vec<OutputLine> = match since {
+            Some(since) => ring
+                .lines
+                .iter()
+                .filter(|line| line.seq > since)
+                .take(limit)
+                .cloned()
+                .collect(),
+            None => ring
+                .lines
+                .iter()
+                .skip(ring.lines.len().saturating_sub(limit))
+                .cloned()
+                .collect(),
+        };
+        let dropped = match (since, ring.lines.front()) {
+            (Some(since), Some(oldest)) => oldest.seq.saturating_sub(since + 1),
+            (Some(since), None) => ring.last_seq.saturating_sub(since),
+            (None, _) => 0,
+        };
+
+        OutputChunk {
+            cursor: lines.last().map_or(ring.last_seq, |line| line.seq),
+            lines,
+            dropped,
+        }
+    }
+
+    /// Unix time in milliseconds of the newest line, 0 without output
+    pub fn last_written(&self) -> u64 {
+        let ring = self.0.lock().unwrap();
+        ring.lines.back().map_or(0, |line| line.timestamp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_since_pages_through_output() {
+        let buffer = OutputBuffer::new();
+        for i in 1..=5 {
+            buffer.push(OutputStream::Stdout, format!("line {i}"));
+        }
+        buffer.push(OutputStream::Stderr, "error: failed".to_string());
+
+        let tail = buffer.since(None, Some(2));
+        let lines: Vec<_> = tail.lines.iter().map(|l| l.line.as_str()).collect();
+        assert_eq!(lines, ["line 5", "error: failed"]);
+        assert_eq!(tail.lines[1].stream, OutputStream::Stderr);
+        assert_eq!(tail.cursor, 6);
+
+        let page = buffer.since(Some(1), Some(2));
+        assert_eq!(page.lines[0].line, "line 2");
+        assert_eq!(page.cursor, 3);
+        assert_eq!(page.dropped, 0);
+
+        let nothing_new = buffer.since(Some(6), None);
+        assert!(nothing_new.lines.is_empty());
+        assert_eq!(nothing_new.cursor, 6);
+    }
+
+    #[test]
+    fn test_buffer_is_bounded() {
+        let buffer = OutputBuffer::new();
+        for i in 0..OUTPUT_CAPACITY + 10 {
+            buffer.push(OutputStream::Stdout, i.to_string());
+        }
+        let chunk = buffer.since(Some(0), Some(1));
+        assert_eq!(chunk.dropped, 10);
+        assert_eq!(chunk.lines[0].line, "10");
+
+        let long = buffer.push(OutputStream::Stdout, "é".repeat(MAX_LINE_LEN));
+        assert!(long.line.len() <= MAX_LINE_LEN + '…'.len_utf8());
+        assert!(long.line.ends_with('…'));
+    }
+}
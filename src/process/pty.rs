@@ -0,0 +1,298 @@
+// WezTerm Multi-Process Development Framework - Pseudo Terminals
+// Processes that need a TTY run on a PTY instead of piped stdio
+
+use portable_pty::{native_pty_system, CommandBuilder, MasterPty, PtySize};
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use tokio::sync::mpsc;
+
+/// Size of a process's terminal
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerminalSize {
+    pub rows: u16,
+    pub cols: u16,
+}
+
+impl Default for TerminalSize {
+    fn default() -> Self {
+        Self { rows: 24, cols: 80 }
+    }
+}
+
+impl TerminalSize {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.rows == 0 || self.cols == 0 {
+            return Err(format!(
+                "Terminal size {}x{} must be at least 1x1",
+                self.cols, self.rows
+            ));
+        }
+        Ok(())
+    }
+
+    fn to_pty_size(self) -> PtySize {
+        PtySize {
+            rows: self.rows,
+            cols: self.cols,
+            pixel_width: 0,
+            pixel_height: 0,
+        }
+    }
+}
+
+/// How a process ended, whether it ran on a PTY or not
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessExit {
+    pub success: bool,
+    /// `None` when the process was killed by a signal
+    pub code: Option<i32>,
+    pub description: String,
+}
+
+impl From<std::process::ExitStatus> for ProcessExit {
+    fn from(status: std::process::ExitStatus) -> Self {
+        Self {
+            success: status.success(),
+            code: status.code(),
+            description: status.to_string(),
+        }
+    }
+}
+
+/// A process running on a PTY; the master side stays with the framework
+pub struct PtyProcess {
+    // Neither end is `Sync`; the manager shares processes across tasks
+    master: Mutex<Box<dyn MasterPty + Send>>,
+    writer: Mutex<Box<dyn Write + Send>>,
+    child: Box<dyn portable_pty::Child + Send + Sync>,
+    size: TerminalSize,
+}
+
+impl std::fmt::Debug for PtyProcess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PtyProcess")
+            .field("pid", &self.child.process_id())
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
+impl PtyProcess {
+    /// Start `program` on a new PTY of `size`
+    ///
+    /// `env` is applied in order on top of the framework's environment; a
+    /// `None` value removes the variable. Returns the process and the
+    /// terminal's output.
+    pub fn spawn(
+        program: &str,
+        args: &[String],
+        env: &[(String, Option<String>)],
+        cwd: Option<&Path>,
+        size: TerminalSize,
+    ) -> io::Result<(Self, Box<dyn Read + Send>)> {
+        size.validate()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let pair = native_pty_system()
+            .openpty(size.to_pty_size())
+            .map_err(pty_error)?;
+
+        let mut cmd = CommandBuilder::new(program);
+        cmd.args(args);
+        for (key, value) in env {
+            match value {
+                Some(value) => cmd.env(key, value),
+                None => cmd.env_remove(key),
+            }
+        }
+        if let Some(cwd) = cwd {
+            cmd.cwd(cwd);
+        }
+
+        let child = pair.slave.spawn_command(cmd).map_err(pty_error)?;
+        // Only the child keeps the slave open, so output ends when it exits
+        drop(pair.slave);
+        let reader = pair.master.try_clone_reader().map_err(pty_error)?;
+        let writer = pair.master.take_writer().map_err(pty_error)?;
+
+        Ok((
+            Self {
+                master: Mutex::new(pair.master),
+                writer: Mutex::new(writer),
+                child,
+                size,
+            },
+            reader,
+        ))
+    }
+
+    pub fn id(&self) -> Option<u32> {
+        self.child.process_id()
+    }
+
+    pub fn size(&self) -> TerminalSize {
+        self.size
+    }
+
+    /// Send input as if typed into the terminal
+    pub fn write_input(&self, data: &[u8]) -> io::Result<()> {
+        let mut writer = self
+            .writer
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        writer.write_all(data)?;
+        writer.flush()
+    }
+
+    /// Resize the terminal; the process receives SIGWINCH
+    pub fn resize(&mut self, size: TerminalSize) -> io::Result<()> {
+        size.validate()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        self.master
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .resize(size.to_pty_size())
+            .map_err(pty_error)?;
+        self.size = size;
+        Ok(())
+    }
+
+    pub fn try_wait(&mut self) -> io::Result<Option<ProcessExit>> {
+        Ok(self.child.try_wait()?.map(|status| {
+            // portable-pty only tells signals apart in the description
+            let description = status.to_string();
+            ProcessExit {
+                success: status.success(),
+                code: (!description.starts_with("Terminated by"))
+                    .then(|| status.exit_code() as i32),
+                description,
+            }
+        }))
+    }
+
+    /// Hang up the terminal and wait for the process to exit
+    ///
+    /// Blocks: a process still running shortly after SIGHUP is killed.
+    pub fn kill(mut self) -> io::Result<()> {
+        self.child.kill()?;
+        self.child.wait().map(drop)
+    }
+}
+
+/// Read the terminal's output line by line on a blocking thread
+///
+/// Carriage returns before the line end are dropped; a last line without a
+/// newline is delivered when the terminal closes.
+pub fn stream_lines(reader: Box<dyn Read + Send>) -> mpsc::UnboundedReceiver<io::Result<String>> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(reader);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            let closed = match reader.read_until(b'\n', &mut buf) {
+                Ok(0) => true,
+                Ok(_) => false,
+                Err(e) if is_closed(&e) => true,
+                Err(e) => {
+                    let _ = tx.send(Err(e));
+                    break;
+                }
+            };
+            if closed && buf.is_empty() {
+                break;
+            }
+            while matches!(buf.last(), Some(b'\n' | b'\r')) {
+                buf.pop();
+            }
+            if tx
+                .send(Ok(String::from_utf8_lossy(&buf).into_owned()))
+                .is_err()
+                || closed
+            {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+/// The master reports EIO once every process closed the terminal
+#[cfg(unix)]
+fn is_closed(e: &io::Error) -> bool {
+    e.raw_os_error() == Some(libc::EIO)
+}
+
+#[cfg(not(unix))]
+fn is_closed(e: &io::Error) -> bool {
+    e.kind() == io::ErrorKind::BrokenPipe
+}
+
+fn pty_error(e: anyhow::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, e.to_string())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    async fn next_line(lines: &mut mpsc::UnboundedReceiver<io::Result<String>>) -> String {
+        tokio::time::timeout(Duration::from_secs(5), lines.recv())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_pty_process_is_interactive_and_resizable() {
+        let script = "test -t 0 && echo tty; read line; echo \"got $line\"; read done; stty size";
+        let (mut process, reader) = PtyProcess::spawn(
+            "sh",
+            &["-c".to_string(), script.to_string()],
+            &[("TERM".to_string(), Some("dumb".to_string()))],
+            None,
+            TerminalSize::default(),
+        )
+        .unwrap();
+        let mut lines = stream_lines(reader);
+        assert_eq!(next_line(&mut lines).await, "tty");
+
+        // The terminal echoes input back
+        process.write_input(b"hello\n").unwrap();
+        assert_eq!(next_line(&mut lines).await, "hello");
+        assert_eq!(next_line(&mut lines).await, "got hello");
+
+        process
+            .resize(TerminalSize {
+                rows: 40,
+                cols: 120,
+            })
+            .unwrap();
+        assert_eq!(
+            process.size(),
+            TerminalSize {
+                rows: 40,
+                cols: 120
+            }
+        );
+        assert!(process.resize(TerminalSize { rows: 0, cols: 80 }).is_err());
+        process.write_input(b"\n").unwrap();
+        let mut rest = Vec::new();
+        while let Some(Ok(line)) = lines.recv().await {
+            rest.push(line);
+        }
+        assert!(rest.contains(&"40 120".to_string()), "{rest:?}");
+
+        let exit = loop {
+            if let Some(exit) = process.try_wait().unwrap() {
+                break exit;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        };
+        assert!(exit.success);
+        assert_eq!(exit.code, Some(0));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::process::coordinator::CoordinationBus;
 use crate::process::ProcessManager;
 use crate::{CoordinationMessage, CoordinationResponse};
 use std::collections::HashMap;
@@ -9,6 +10,8 @@ use tokio::sync::{Mutex, RwLock};
 pub struct MessageRouter {
     /// 登録されたプロセスマネージャー
     processes: Arc<RwLock<HashMap<String, Arc<Mutex<ProcessManager>>>>>,
+    /// 協調チャネルの配送先（未設定なら受理のみ）
+    bus: Option<Arc<CoordinationBus>>,
 }
 
 impl Default for MessageRouter {
@@ -22,9 +25,16 @@ impl MessageRouter {
     pub fn new() -> Self {
         Self {
             processes: Arc::new(RwLock::new(HashMap::new())),
+            bus: None,
         }
     }
 
+    /// 協調バス経由で実際にメッセージを配送する
+    pub fn with_bus(mut self, bus: Arc<CoordinationBus>) -> Self {
+        self.bus = Some(bus);
+        self
+    }
+
     /// プロセスを登録
     pub async fn register_process(&self, process_id: String, manager: Arc<Mutex<ProcessManager>>) {
         let mut processes = self.processes.write().await;
@@ -49,8 +59,12 @@ impl MessageRouter {
             .get(&message.receiver_id)
             .ok_or_else(|| format!("Process {} not found", message.receiver_id))?;
 
-        // TODO: 実際のメッセージ送信とレスポンス処理を実装
-        // 現在はモックレスポンスを返す
+        // 協調チャネルがあれば配送して応答を返す
+        if let Some(bus) = &self.bus {
+            if bus.is_attached(&message.receiver_id).await {
+                return Ok(bus.send(message).await?);
+            }
+        }
         Ok(CoordinationResponse::Acknowledged {
             process_id: message.receiver_id.clone(),
         })
@@ -70,17 +84,19 @@ impl MessageRouter {
                 continue;
             }
 
-            let _msg_clone = CoordinationMessage {
+            let msg_clone = CoordinationMessage {
                 sender_id: message.sender_id.clone(),
                 receiver_id: process_id.clone(),
                 timestamp: message.timestamp,
                 event: message.event.clone(),
             };
 
-            // TODO: 実際のメッセージ送信とレスポンス処理を実装
-            let response = Ok(CoordinationResponse::Acknowledged {
-                process_id: process_id.clone(),
-            });
+            let response = match &self.bus {
+                Some(bus) if bus.is_attached(process_id).await => bus.send(msg_clone).await,
+                _ => Ok(CoordinationResponse::Acknowledged {
+                    process_id: process_id.clone(),
+                }),
+            };
 
             responses.push((process_id.clone(), response));
         }
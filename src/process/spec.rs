@@ -0,0 +1,195 @@
+// WezTerm Multi-Process Development Framework - Process Spec
+// Program, arguments and per-process settings a managed process runs with
+
+use super::manager::RestartPolicy;
+use super::pty::TerminalSize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// What to run for one managed process
+///
+/// Anything can be managed: Claude Code (see `ProcessConfig::claude_code`),
+/// dev servers, watchers or language servers.
+///
+/// ```yaml
+/// program: npm
+/// args: [run, dev]
+/// cwd: web
+/// env:
+///   PORT: "3000"
+/// restart_policy: Always
+/// pty:
+///   rows: 24
+///   cols: 80
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ProcessSpec {
+    /// Executable, looked up in `PATH` unless it is a path
+    pub program: String,
+
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// Working directory; a relative one is resolved against the workspace's
+    /// worktree or `working_directory`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<PathBuf>,
+
+    /// Variables set on top of `environment_vars`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+
+    /// Overrides `default_restart_policy` for this process
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restart_policy: Option<RestartPolicy>,
+
+    /// Run on a pseudo terminal of this size instead of piped stdio, for
+    /// tools that need a TTY; its output is reported as stdout
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pty: Option<TerminalSize>,
+
+    /// Keep a pipe to the process's stdin open for `ProcessStdinWrite` and
+    /// coordination messages; otherwise it inherits the framework's. Processes on a PTY always take
+    /// input on their terminal
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stdin: bool,
+}
+
+impl ProcessSpec {
+    pub fn new(program: impl Into<String>) -> Self {
+        Self {
+            program: program.into(),
+            args: Vec::new(),
+            cwd: None,
+            env: BTreeMap::new(),
+            restart_policy: None,
+            pty: None,
+            stdin: false,
+        }
+    }
+
+    /// Spec from a command line split on whitespace, as recorded in
+    /// `ProcessInfo::command`; `None` when it is empty
+    pub fn from_command_line(command: &str) -> Option<Self> {
+        let mut words = command.split_whitespace().map(str::to_string);
+        let program = words.next()?;
+        Some(Self::new(program).with_args(words))
+    }
+
+    pub fn with_args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_cwd(mut self, cwd: impl Into<PathBuf>) -> Self {
+        self.cwd = Some(cwd.into());
+        self
+    }
+
+    pub fn with_env(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn with_restart_policy(mut self, policy: RestartPolicy) -> Self {
+        self.restart_policy = Some(policy);
+        self
+    }
+
+    pub fn with_pty(mut self, size: TerminalSize) -> Self {
+        self.pty = Some(size);
+        self
+    }
+
+    pub fn with_stdin(mut self) -> Self {
+        self.stdin = true;
+        self
+    }
+
+    /// Program and arguments joined for display and logs
+    pub fn command_line(&self) -> String {
+        std::iter::once(self.program.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Directory to start in, given the one the workspace's processes use
+    pub fn working_dir(&self, base: Option<&Path>) -> Option<PathBuf> {
+        match (&self.cwd, base) {
+            (Some(cwd), Some(base)) if cwd.is_relative() => Some(base.join(cwd)),
+            (Some(cwd), _) => Some(cwd.clone()),
+            (None, base) => base.map(Path::to_path_buf),
+        }
+    }
+
+    /// Check the spec before anything is started
+    pub fn validate(&self) -> Result<(), String> {
+        if self.program.trim().is_empty() {
+            return Err("Process program must not be empty".to_string());
+        }
+        for name in self.env.keys() {
+            super::env::validate_name(name)?;
+        }
+        if let Some(size) = self.pty {
+            size.validate()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spec_from_yaml_and_command_line() {
+        let spec: ProcessSpec = serde_yaml::from_str(
+            "program: npm\nargs: [run, dev]\ncwd: web\nenv:\n  PORT: \"3000\"\nrestart_policy: Always\npty: {rows: 40, cols: 120}\n",
+        )
+        .unwrap();
+        assert_eq!(
+            spec,
+            ProcessSpec::new("npm")
+                .with_args(["run", "dev"])
+                .with_cwd("web")
+                .with_env("PORT", "3000")
+                .with_restart_policy(RestartPolicy::Always)
+                .with_pty(TerminalSize {
+                    rows: 40,
+                    cols: 120
+                })
+        );
+        assert_eq!(spec.command_line(), "npm run dev");
+        assert_eq!(
+            ProcessSpec::from_command_line("cargo watch -x test"),
+            Some(ProcessSpec::new("cargo").with_args(["watch", "-x", "test"]))
+        );
+        assert_eq!(ProcessSpec::from_command_line("  "), None);
+    }
+
+    #[test]
+    fn test_working_dir_and_validation() {
+        let base = Path::new("/repo");
+        let spec = ProcessSpec::new("rust-analyzer");
+        assert_eq!(spec.working_dir(Some(base)), Some(PathBuf::from("/repo")));
+        assert_eq!(spec.working_dir(None), None);
+        assert_eq!(
+            spec.clone().with_cwd("web").working_dir(Some(base)),
+            Some(PathBuf::from("/repo/web"))
+        );
+        assert_eq!(
+            spec.clone().with_cwd("/tmp").working_dir(Some(base)),
+            Some(PathBuf::from("/tmp"))
+        );
+
+        assert!(spec.validate().is_ok());
+        assert!(ProcessSpec::new(" ").validate().is_err());
+        assert!(spec.with_env("CLAUDE_PROCESS_ID", "x").validate().is_err());
+    }
+}
@@ -0,0 +1,410 @@
+// WezTerm Multi-Process Development Framework - Log Tail
+// Follow the log file of a process across rotations (like `tail -F`)
+//
+// Rotation renames `<id>.log` to `<id>.log.<timestamp>` and opens a new file.
+// A cursor remembers the file it points into (its inode on Unix), so a reader
+// that falls behind finishes the renamed file before it continues with the
+// new one. Cursors are
+// plain values handed back by every read: the IPC client keeps its own, and
+// each dashboard subscription keeps one on the server.
+
+use super::claude_logger::LogConfig;
+use crate::logging::UnifiedLogLevel;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, Metadata};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Lines returned when a request sets no limit, and at most
+pub const DEFAULT_TAIL_LINES: usize = 50;
+pub const MAX_TAIL_LINES: usize = 1000;
+
+/// Bytes read from one file per call
+pub const MAX_TAIL_READ: u64 = 1024 * 1024;
+
+/// How often dashboard subscriptions look for new lines
+pub const TAIL_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Server-side filter of followed lines; every field is optional
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TailFilter {
+    /// Minimum level (`trace` to `error`); lines without a level are left out
+    #[serde(default)]
+    pub level: Option<String>,
+
+    /// Text in the line, case-insensitive
+    #[serde(default)]
+    pub pattern: Option<String>,
+}
+
+/// Position in a followed log file
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TailCursor {
+    /// Identity of the file the offset is in (see [`file_id`]); 0 before the file existed
+    pub file: u64,
+
+    /// Byte offset after the last line read
+    pub offset: u64,
+}
+
+/// Lines after a cursor, oldest first
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TailChunk {
+    pub lines: Vec<String>,
+
+    /// Pass as `cursor` to the next read
+    pub cursor: TailCursor,
+
+    /// The file was rotated or truncated since the previous cursor
+    pub rotated: bool,
+
+    /// The rotated file was removed before it was read to the end
+    pub lost: bool,
+}
+
+/// Log file of a process as written by [`ProcessLogFile`](super::ProcessLogFile)
+pub fn log_path(workspace: &str, process_id: &str) -> PathBuf {
+    LogConfig::default().log_file_path(process_id, workspace)
+}
+
+/// Read the lines after `cursor`, or the last `limit` lines without one
+pub fn read(
+    path: &Path,
+    cursor: Option<TailCursor>,
+    filter: &TailFilter,
+    limit: Option<usize>,
+) -> Result<TailChunk, String> {
+    read_file(path, cursor, &Matcher::new(filter)?, limit)
+        .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))
+}
+
+fn read_file(
+    path: &Path,
+    cursor: Option<TailCursor>,
+    matcher: &Matcher,
+    limit: Option<usize>,
+) -> io::Result<TailChunk> {
+    let limit = limit.unwrap_or(DEFAULT_TAIL_LINES).clamp(1, MAX_TAIL_LINES);
+    let current = open(path)?;
+
+    let Some(cursor) = cursor else {
+        let Some((mut file, inode, len)) = current else {
+            return Ok(TailChunk::default());
+        };
+        let start = len.saturating_sub(MAX_TAIL_READ);
+        let mut read = read_lines(&mut file, start, matcher, usize::MAX, true, false)?;
+        let skip = read.lines.len().saturating_sub(limit);
+        return Ok(TailChunk {
+            lines: read.lines.split_off(skip),
+            cursor: TailCursor {
+                file: inode,
+                offset: read.end,
+            },
+            ..Default::default()
+        });
+    };
+
+    let mut chunk = TailChunk {
+        cursor,
+        ..Default::default()
+    };
+    let current_inode = current.as_ref().map(|(_, inode, _)| *inode);
+    if cursor.file != 0 && current_inode != Some(cursor.file) {
+        // Rotated: finish the renamed file first
+        match find_rotated(path, cursor.file)? {
+            Some(mut rotated) => {
+                let read = read_lines(&mut rotated, cursor.offset, matcher, limit, false, true)?;
+                chunk.lines = read.lines;
+                chunk.cursor.offset = read.end;
+                if chunk.lines.len() >= limit || !read.at_eof {
+                    return Ok(chunk);
+                }
+            }
+            None => chunk.lost = true,
+        }
+        let Some(inode) = current_inode else {
+            return Ok(chunk);
+        };
+        chunk.rotated = true;
+        chunk.cursor = TailCursor {
+            file: inode,
+            offset: 0,
+        };
+    }
+
+    let Some((mut file, inode, len)) = current else {
+        return Ok(chunk);
+    };
+    if chunk.cursor.file == 0 || len < chunk.cursor.offset {
+        chunk.rotated |= chunk.cursor.file != 0;
+        chunk.cursor = TailCursor {
+            file: inode,
+            offset: 0,
+        };
+    }
+    let read = read_lines(
+        &mut file,
+        chunk.cursor.offset,
+        matcher,
+        limit - chunk.lines.len(),
+        false,
+        false,
+    )?;
+    chunk.lines.extend(read.lines);
+    chunk.cursor.offset = read.end;
+    Ok(chunk)
+}
+
+/// The file with its identity and length, `None` while it does not exist
+fn open(path: &Path) -> io::Result<Option<(File, u64, u64)>> {
+    match File::open(path) {
+        Ok(file) => {
+            let metadata = file.metadata()?;
+            Ok(Some((file, file_id(&metadata), metadata.len())))
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Rotated copy (`<name>.*` next to the file) with the given identity
+fn find_rotated(path: &Path, inode: u64) -> io::Result<Option<File>> {
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+        return Ok(None);
+    };
+    let prefix = format!("{}.", name.to_string_lossy());
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    for entry in entries.flatten() {
+        if entry.file_name().to_string_lossy().starts_with(&prefix)
+            && entry
+                .metadata()
+                .is_ok_and(|metadata| file_id(&metadata) == inode)
+        {
+            return File::open(entry.path()).map(Some);
+        }
+    }
+    Ok(None)
+}
+
+/// Inode of a file, which a rename keeps
+#[cfg(unix)]
+fn file_id(metadata: &Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.ino()
+}
+
+/// Creation time of a file, which a rename keeps
+///
+/// Where that is not available every file has the same identity, so a
+/// rotation is only noticed when the new file is shorter than the cursor.
+#[cfg(not(unix))]
+fn file_id(metadata: &Metadata) -> u64 {
+    metadata
+        .created()
+        .ok()
+        .and_then(|created| created.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(1, |since| (since.as_nanos() as u64).max(1))
+}
+
+struct ReadLines {
+    lines: Vec<String>,
+
+    /// Offset after the last consumed line
+    end: u64,
+
+    /// Everything up to the end of the file was consumed
+    at_eof: bool,
+}
+
+/// Matching complete lines from `offset`
+///
+/// With `align` a line that started before `offset` is skipped; a trailing
+/// line without newline is only taken with `take_partial` (the file will not
+/// grow any more).
+fn read_lines(
+    file: &mut File,
+    offset: u64,
+    matcher: &Matcher,
+    limit: usize,
+    align: bool,
+    take_partial: bool,
+) -> io::Result<ReadLines> {
+    let start = offset.saturating_sub(u64::from(offset > 0));
+    file.seek(SeekFrom::Start(start))?;
+    let mut buffer = Vec::new();
+    file.by_ref().take(MAX_TAIL_READ).read_to_end(&mut buffer)?;
+    let full = buffer.len() as u64 == MAX_TAIL_READ;
+
+    // Reading one byte early tells whether `offset` is at the start of a line
+    let mut position = usize::from(offset > 0).min(buffer.len());
+    if align && offset > 0 && buffer.first() != Some(&b'\n') {
+        position = buffer
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(buffer.len(), |newline| newline + 1);
+    }
+
+    let first = position;
+    let mut lines = Vec::new();
+    while lines.len() < limit && position < buffer.len() {
+        let rest = &buffer[position..];
+        let (line, consumed) = match rest.iter().position(|&b| b == b'\n') {
+            Some(newline) => (&rest[..newline], newline + 1),
+            // A line longer than the whole read is cut rather than never returned
+            None if take_partial || (full && position == first) => (rest, rest.len()),
+            None => break,
+        };
+        position += consumed;
+        let line = String::from_utf8_lossy(line);
+        let line = line.trim_end_matches('\r');
+        if matcher.matches(line) {
+            lines.push(line.to_string());
+        }
+    }
+
+    Ok(ReadLines {
+        lines,
+        end: start + position as u64,
+        at_eof: position == buffer.len() && !full,
+    })
+}
+
+struct Matcher {
+    level: Option<UnifiedLogLevel>,
+    pattern: Option<String>,
+}
+
+impl Matcher {
+    fn new(filter: &TailFilter) -> Result<Self, String> {
+        let level = match filter.level.as_deref() {
+            Some(level) => Some(
+                UnifiedLogLevel::from_string(level)
+                    .ok_or_else(|| format!("Unknown log level '{level}'"))?,
+            ),
+            None => None,
+        };
+        Ok(Self {
+            level,
+            pattern: filter.pattern.as_ref().map(|text| text.to_lowercase()),
+        })
+    }
+
+    fn matches(&self, line: &str) -> bool {
+        self.level.map_or(true, |min| {
+            line_level(line).is_some_and(|level| level >= min)
+        }) && self
+            .pattern
+            .as_ref()
+            .map_or(true, |text| line.to_lowercase().contains(text))
+    }
+}
+
+/// Level of a line in any of the [`LogFormat`](super::LogFormat)s
+fn line_level(line: &str) -> Option<UnifiedLogLevel> {
+    if line.starts_with('{') {
+        let entry: serde_json::Value = serde_json::from_str(line).ok()?;
+        return UnifiedLogLevel::from_string(entry.get("level")?.as_str()?);
+    }
+    // `[timestamp] [LEVEL] ...`
+    line.split('[')
+        .skip(1)
+        .take(2)
+        .filter_map(|part| part.split_once(']'))
+        .find_map(|(token, _)| UnifiedLogLevel::from_string(token))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn append(path: &Path, text: &str) {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn test_follows_file_across_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("claude-1.log");
+        let all = TailFilter::default();
+
+        // Nothing logged yet
+        let chunk = read(&path, None, &all, None).unwrap();
+        assert!(chunk.lines.is_empty());
+
+        append(&path, "one\ntwo\nthr");
+        let chunk = read(&path, Some(chunk.cursor), &all, None).unwrap();
+        assert_eq!(chunk.lines, ["one", "two"]);
+
+        // The partial line is finished after rotation, then the new file is read
+        append(&path, "ee\nfour\n");
+        std::fs::rename(&path, dir.path().join("claude-1.log.20260101_000000")).unwrap();
+        append(&path, "five\n");
+        let chunk = read(&path, Some(chunk.cursor), &all, Some(2)).unwrap();
+        assert_eq!(chunk.lines, ["three", "four"]);
+        assert!(!chunk.rotated);
+        let chunk = read(&path, Some(chunk.cursor), &all, None).unwrap();
+        assert_eq!(chunk.lines, ["five"]);
+        assert!(chunk.rotated);
+
+        // Truncation starts over; a removed rotated file is reported
+        std::fs::write(&path, "six\n").unwrap();
+        let chunk = read(&path, Some(chunk.cursor), &all, None).unwrap();
+        assert_eq!(chunk.lines, ["six"]);
+        assert!(chunk.rotated);
+        let stale = TailCursor {
+            file: u64::MAX,
+            offset: 3,
+        };
+        let chunk = read(&path, Some(stale), &all, None).unwrap();
+        assert!(chunk.lost);
+        assert_eq!(chunk.lines, ["six"]);
+    }
+
+    #[test]
+    fn test_filters_by_level_and_pattern() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("claude-1.log");
+        append(
+            &path,
+            "[2026-01-01 00:00:00.000] [INFO] [api:claude-1] [STDOUT] Running tests\n\
+             [2026-01-01 00:00:01.000] [ERROR] [api:claude-1] [STDERR] Test failed\n\
+             {\"level\":\"Warn\",\"message\":\"slow test\"}\n\
+             continuation without level\n",
+        );
+
+        let warnings = TailFilter {
+            level: Some("warn".to_string()),
+            pattern: None,
+        };
+        let chunk = read(&path, None, &warnings, None).unwrap();
+        assert_eq!(chunk.lines.len(), 2);
+        assert!(chunk.lines[0].contains("Test failed"));
+
+        let tests = TailFilter {
+            level: None,
+            pattern: Some("TEST".to_string()),
+        };
+        let chunk = read(&path, None, &tests, Some(1)).unwrap();
+        assert_eq!(
+            chunk.lines,
+            ["{\"level\":\"Warn\",\"message\":\"slow test\"}"]
+        );
+
+        let invalid = TailFilter {
+            level: Some("loud".to_string()),
+            pattern: None,
+        };
+        assert!(read(&path, None, &invalid, None).is_err());
+    }
+}
@@ -0,0 +1,79 @@
+// WezTerm Multi-Process Development Framework - Process Watchdog
+// Detects processes that stay silent while they have work assigned
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How often the watchdog looks at process activity
+pub const WATCHDOG_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Inactivity watchdog
+///
+/// A process that has a task assigned but produces no output and sends no
+/// heartbeat for `inactivity_minutes` is marked `Unresponsive`. Templates can
+/// override the global setting for the processes of their workspaces:
+///
+/// ```yaml
+/// watchdog:
+///   inactivity_minutes: 20
+///   restart: true
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct WatchdogConfig {
+    pub enabled: bool,
+
+    /// Silence tolerated while a task is assigned
+    pub inactivity_minutes: u64,
+
+    /// Restart unresponsive processes, subject to their restart policy
+    pub restart: bool,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            inactivity_minutes: 10,
+            restart: false,
+        }
+    }
+}
+
+impl WatchdogConfig {
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.inactivity_minutes.saturating_mul(60))
+    }
+}
+
+/// Time of the last sign of life of a process, shared with its output monitor
+#[derive(Debug, Clone)]
+pub struct ActivityClock(Arc<AtomicU64>);
+
+impl ActivityClock {
+    pub fn new(now: SystemTime) -> Self {
+        Self(Arc::new(AtomicU64::new(millis(now))))
+    }
+
+    pub fn touch(&self) {
+        self.0.store(millis(SystemTime::now()), Ordering::Relaxed);
+    }
+
+    pub fn last(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(self.0.load(Ordering::Relaxed))
+    }
+
+    /// Silence at `now`, counted from the later of the last activity and
+    /// the moment work was assigned
+    pub fn idle_for(&self, assigned_since: SystemTime, now: SystemTime) -> Duration {
+        now.duration_since(self.last().max(assigned_since))
+            .unwrap_or_default()
+    }
+}
+
+fn millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
@@ -0,0 +1,163 @@
+// WezTerm Multi-Process Development Framework - Workspace Drain
+// Progress of draining workspaces and deletion once they are empty
+
+use super::WorkspaceManager;
+use crate::log_info;
+use crate::logging::LogContext;
+use crate::task::{TaskManager, TaskStatus};
+use serde::{Deserialize, Serialize};
+
+/// Drain progress of a workspace
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DrainProgress {
+    pub workspace: String,
+
+    /// Time the drain started (seconds since the epoch)
+    pub since: u64,
+    pub delete_when_empty: bool,
+
+    /// Tasks still executing in the workspace
+    pub running_tasks: usize,
+
+    /// Queued tasks held back until the drain ends
+    pub queued_tasks: usize,
+
+    /// Processes still attached to the workspace
+    pub processes: usize,
+
+    /// True once no task runs in the workspace any more
+    pub drained: bool,
+
+    /// The workspace was deleted after draining
+    #[serde(default)]
+    pub deleted: bool,
+}
+
+/// Drain progress of `name`, `None` if it is not draining
+pub async fn drain_progress(
+    workspace_manager: &WorkspaceManager,
+    task_manager: &TaskManager,
+    name: &str,
+) -> Option<DrainProgress> {
+    let workspace = workspace_manager.get_workspace_info(name).await?;
+    let drain = workspace.drain?;
+    let in_workspace = |task_workspace: &Option<String>| task_workspace.as_deref() == Some(name);
+
+    let running_tasks = task_manager
+        .list_tasks(None)
+        .await
+        .iter()
+        .filter(|task| task.status == TaskStatus::InProgress && in_workspace(&task.workspace))
+        .count();
+    let queued_tasks = task_manager
+        .get_queue()
+        .list_tasks()
+        .await
+        .iter()
+        .filter(|task| in_workspace(&task.workspace))
+        .count();
+
+    Some(DrainProgress {
+        workspace: workspace.name,
+        since: drain.since,
+        delete_when_empty: drain.delete_when_empty,
+        running_tasks,
+        queued_tasks,
+        processes: workspace.processes.len(),
+        drained: running_tasks == 0,
+        deleted: false,
+    })
+}
+
+/// Drain progress of every draining workspace, deleting those that are
+/// drained and asked to be deleted; their held-back tasks are cancelled
+pub async fn check_drains(
+    workspace_manager: &WorkspaceManager,
+    task_manager: &TaskManager,
+) -> Vec<DrainProgress> {
+    let mut reports = Vec::new();
+    for name in workspace_manager.draining_workspaces().await.into_keys() {
+        let Some(mut progress) = drain_progress(workspace_manager, task_manager, &name).await
+        else {
+            continue;
+        };
+        if progress.drained && progress.delete_when_empty {
+            for task in task_manager.get_queue().list_tasks().await {
+                if task.workspace.as_deref() == Some(name.as_str()) {
+                    let _ = task_manager.cancel_task(&task.id).await;
+                }
+            }
+            if workspace_manager.delete_workspace(&name).await.is_ok() {
+                let context = LogContext::new("room", "workspace_drained")
+                    .with_entity_id(&name)
+                    .with_metadata("cancelled_tasks", serde_json::json!(progress.queued_tasks));
+                log_info!(context, "Workspace '{}' drained and deleted", name);
+                progress.deleted = true;
+            }
+        }
+        reports.push(progress);
+    }
+    reports
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{Task, TaskCategory, TaskConfig};
+
+    fn managers(dir: &tempfile::TempDir) -> (WorkspaceManager, TaskManager) {
+        let workspace_manager =
+            WorkspaceManager::new(Some(dir.path().join("workspaces.json"))).unwrap();
+        let task_manager = TaskManager::new(TaskConfig {
+            persistence_enabled: false,
+            ..Default::default()
+        });
+        (workspace_manager, task_manager)
+    }
+
+    #[tokio::test]
+    async fn test_drain_progress() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (workspaces, tasks) = managers(&dir);
+        workspaces.create_workspace("api", "basic").await.unwrap();
+        assert!(drain_progress(&workspaces, &tasks, "api").await.is_none());
+
+        let mut task = Task::new("Build".to_string(), TaskCategory::Development);
+        task.workspace = Some("api".to_string());
+        tasks.create_task(task).await.unwrap();
+
+        let drain = workspaces.drain_workspace("api", false).await.unwrap();
+        let again = workspaces.drain_workspace("api", false).await.unwrap();
+        assert_eq!(drain.since, again.since);
+        let progress = drain_progress(&workspaces, &tasks, "api").await.unwrap();
+        assert_eq!(progress.queued_tasks, 1);
+        assert!(progress.drained);
+
+        assert!(workspaces.undrain_workspace("api").await.unwrap());
+        assert!(!workspaces.is_draining("api").await);
+        assert!(workspaces.drain_workspace("default", true).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_drained_workspace_is_deleted() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (workspaces, tasks) = managers(&dir);
+        workspaces.create_workspace("api", "basic").await.unwrap();
+        workspaces.create_workspace("web", "basic").await.unwrap();
+        let mut task = Task::new("Build".to_string(), TaskCategory::Development);
+        task.workspace = Some("api".to_string());
+        let task_id = tasks.create_task(task).await.unwrap();
+
+        workspaces.drain_workspace("api", true).await.unwrap();
+        workspaces.drain_workspace("web", false).await.unwrap();
+        let reports = check_drains(&workspaces, &tasks).await;
+        assert_eq!(reports.len(), 2);
+        assert!(reports[0].deleted);
+        assert!(!reports[1].deleted);
+
+        assert!(workspaces.get_workspace_info("api").await.is_none());
+        assert!(workspaces.is_draining("web").await);
+        let task = tasks.get_task(&task_id).await.unwrap();
+        assert_eq!(task.status, TaskStatus::Cancelled);
+    }
+}
@@ -1,6 +1,7 @@
 // WezTerm Multi-Process Development Framework - Workspace-Process Integration
 
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::time::Duration;
 use tokio::sync::RwLock;
 use tokio::time::sleep;
@@ -51,6 +52,20 @@ impl IntegratedWorkspaceManager {
             .create_workspace(name, template)
             .await?;
 
+        // The template may override the process watchdog and declare a context file
+        let template = self.workspace_manager.get_template(template);
+        let watchdog = template.and_then(|template| template.watchdog.clone());
+        self.process_manager
+            .set_workspace_watchdog(name, watchdog)
+            .await;
+        let base_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let context = template
+            .and_then(|template| template.context.as_ref())
+            .map(|context| context.resolve_in(&base_dir));
+        self.process_manager
+            .set_workspace_context(name, context)
+            .await;
+
         // 2. Generate a unique process ID for this workspace
         let process_id = format!("claude-code-{name}");
 
@@ -295,6 +310,7 @@ mod tests {
         let config = ProcessConfig {
             claude_code_binary: "echo".to_string(),
             max_processes: 10,
+            max_processes_per_workspace: None,
             health_check_interval_secs: 1,
             restart_delay_secs: 1,
             max_restart_attempts: 3,
@@ -302,6 +318,9 @@ mod tests {
             default_restart_policy: crate::process::manager::RestartPolicy::OnFailure,
             environment_vars: HashMap::new(),
             working_directory: None,
+            watchdog: Default::default(),
+            circuit_breaker: Default::default(),
+            secrets: Default::default(),
         };
 
         let (process_manager, _event_receiver) = ProcessManager::new(config);
@@ -0,0 +1,401 @@
+// WezTerm Multi-Process Development Framework - Pane Layout Application
+// Creates a template's panes through a terminal backend (WezTerm or tmux)
+// and starts the default commands in them
+
+use crate::room::state::{LayoutConfig, LayoutType, PanePosition, SplitDirection};
+use crate::room::template::WorkspaceTemplate;
+use crate::room::terminal_backend::{SplitSide, TerminalBackend, TerminalBackendKind};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::Arc;
+use tracing::info;
+
+/// How a pane is created from one created before it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaneSplit {
+    /// Index of the pane that is split
+    pub from: usize,
+    pub side: SplitSide,
+
+    /// Share of the split pane the new pane takes
+    pub percent: u8,
+}
+
+/// One pane of a layout
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PanePlan {
+    /// Name of the template command, `pane-N` for panes the layout adds
+    pub name: String,
+
+    /// Command typed into the pane's shell; `None` leaves a plain shell
+    pub command: Option<String>,
+    pub working_directory: String,
+    pub row: u32,
+    pub col: u32,
+
+    /// `None` for the first pane, which opens the workspace's window
+    pub split: Option<PaneSplit>,
+
+    /// Whether the pane runs a managed process rather than a notes, timer
+    /// or preview pane
+    pub is_process: bool,
+}
+
+/// Panes of a template in creation order: columns left to right, then the
+/// rows of each column top to bottom
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayoutPlan {
+    pub workspace: String,
+    pub panes: Vec<PanePlan>,
+}
+
+impl LayoutPlan {
+    /// Plan the panes of `template` for a workspace rooted at `base_dir`
+    ///
+    /// There is a pane per default command, placed at its `pane_position`
+    /// or else in the next slot of the layout type; layouts with more slots
+    /// than commands get plain shells in the remaining ones.
+    pub fn from_template(template: &WorkspaceTemplate, workspace: &str, base_dir: &Path) -> Self {
+        let layout = &template.layout;
+        let count = slot_count(layout).max(template.default_commands.len());
+        let mut cells: Vec<PanePlan> = (0..count)
+            .map(|index| {
+                let command = template.default_commands.get(index);
+                let (row, col) = command
+                    .and_then(|command| command.pane_position.as_ref())
+                    .map(|position| (position.row, position.col))
+                    .unwrap_or_else(|| slot(layout, index));
+                let working_directory = command
+                    .and_then(|command| command.working_directory.as_deref())
+                    .map(|dir| base_dir.join(dir.trim_start_matches("./")))
+                    .unwrap_or_else(|| base_dir.to_path_buf());
+                PanePlan {
+                    name: command
+                        .map(|command| command.name.clone())
+                        .unwrap_or_else(|| format!("pane-{}", index + 1)),
+                    command: command
+                        .filter(|command| command.auto_start)
+                        .map(|command| command.resolved_command())
+                        .filter(|command| !command.trim().is_empty()),
+                    working_directory: working_directory.to_string_lossy().to_string(),
+                    row,
+                    col,
+                    split: None,
+                    is_process: command.is_some_and(|command| command.is_process()),
+                }
+            })
+            .collect();
+        cells.sort_by_key(|pane| (pane.col, pane.row));
+
+        let size_of = |pane: &PanePlan| {
+            template
+                .default_commands
+                .iter()
+                .find(|command| command.name == pane.name)
+                .and_then(|command| command.pane_position.as_ref())
+                .map(|position| position.size_percentage)
+        };
+        let columns: Vec<u32> = cells
+            .iter()
+            .map(|pane| pane.col)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let column_widths: Vec<f32> = columns
+            .iter()
+            .enumerate()
+            .map(|(index, col)| {
+                let top = cells.iter().find(|pane| pane.col == *col);
+                top.and_then(size_of)
+                    .or_else(|| {
+                        (layout.pane_sizes.len() == columns.len()).then(|| layout.pane_sizes[index])
+                    })
+                    .unwrap_or(1.0)
+            })
+            .collect();
+
+        // Columns first, each split off the previous column's top pane so it
+        // spans the full height; then the rows within each column
+        let mut panes: Vec<PanePlan> = Vec::with_capacity(cells.len());
+        let mut tops = Vec::new();
+        for (index, col) in columns.iter().enumerate() {
+            let Some(position) = cells.iter().position(|pane| pane.col == *col) else {
+                continue;
+            };
+            let mut top = cells.remove(position);
+            if let Some(&previous) = tops.last() {
+                top.split = Some(PaneSplit {
+                    from: previous,
+                    side: SplitSide::Right,
+                    percent: share(&column_widths[index - 1..]),
+                });
+            }
+            tops.push(panes.len());
+            panes.push(top);
+        }
+        for (index, col) in columns.iter().enumerate() {
+            let rows: Vec<PanePlan> = cells
+                .iter()
+                .filter(|pane| pane.col == *col)
+                .cloned()
+                .collect();
+            let heights: Vec<f32> =
+                if columns.len() == 1 && layout.pane_sizes.len() == rows.len() + 1 {
+                    layout.pane_sizes.clone()
+                } else {
+                    vec![1.0; rows.len() + 1]
+                };
+            let mut previous = tops[index];
+            for (row_index, mut pane) in rows.into_iter().enumerate() {
+                pane.split = Some(PaneSplit {
+                    from: previous,
+                    side: SplitSide::Bottom,
+                    percent: share(&heights[row_index..]),
+                });
+                previous = panes.len();
+                panes.push(pane);
+            }
+        }
+
+        Self {
+            workspace: workspace.to_string(),
+            panes,
+        }
+    }
+}
+
+/// Panes a layout type has room for
+fn slot_count(layout: &LayoutConfig) -> usize {
+    match &layout.layout_type {
+        LayoutType::Single => 1,
+        LayoutType::TwoPaneHorizontal | LayoutType::TwoPaneVertical => 2,
+        LayoutType::ThreePaneHorizontal | LayoutType::ThreePaneVertical => 3,
+        LayoutType::FourPaneGrid => 4,
+        LayoutType::Custom(_) => layout.pane_sizes.len().max(1),
+    }
+}
+
+/// Grid cell (row, col) of the `index`th pane of a layout; panes beyond the
+/// layout's slots continue along its axis
+fn slot(layout: &LayoutConfig, index: usize) -> (u32, u32) {
+    let index = index as u32;
+    match &layout.layout_type {
+        LayoutType::TwoPaneHorizontal | LayoutType::ThreePaneHorizontal => (0, index),
+        LayoutType::TwoPaneVertical | LayoutType::ThreePaneVertical => (index, 0),
+        LayoutType::FourPaneGrid => (index / 2, index % 2),
+        LayoutType::Single | LayoutType::Custom(_) => match layout.primary_direction {
+            SplitDirection::Horizontal => (0, index),
+            SplitDirection::Vertical => (index, 0),
+        },
+    }
+}
+
+/// Percentage the first of `sizes` leaves to the rest when its pane is split
+fn share(sizes: &[f32]) -> u8 {
+    let total: f32 = sizes.iter().map(|size| size.max(0.0)).sum();
+    let rest: f32 = sizes.iter().skip(1).map(|size| size.max(0.0)).sum();
+    if total <= 0.0 {
+        return 50;
+    }
+    ((rest / total * 100.0).round() as u8).clamp(1, 99)
+}
+
+/// A pane created by [`LayoutApplier::apply`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppliedPane {
+    /// Pane ID of the terminal backend
+    pub pane_id: String,
+    pub plan: PanePlan,
+}
+
+impl AppliedPane {
+    pub fn position(&self) -> PanePosition {
+        PanePosition {
+            row: self.plan.row,
+            col: self.plan.col,
+            span_rows: 1,
+            span_cols: 1,
+        }
+    }
+}
+
+/// Realizes layout plans through a terminal backend
+#[derive(Debug, Clone)]
+pub struct LayoutApplier {
+    backend: Arc<dyn TerminalBackend>,
+}
+
+impl LayoutApplier {
+    pub fn new(backend: Arc<dyn TerminalBackend>) -> Self {
+        Self { backend }
+    }
+
+    /// Applier using the terminal's binary on the PATH
+    pub fn system(kind: TerminalBackendKind) -> Self {
+        Self::new(kind.system())
+    }
+
+    /// Create the panes of `plan` in a new window of its workspace and start
+    /// their commands
+    ///
+    /// When a step fails the panes created so far are closed again.
+    pub fn apply(&self, plan: &LayoutPlan) -> Result<Vec<AppliedPane>, String> {
+        let applied = self.backend.apply(plan)?;
+        info!(
+            "Created {} panes for workspace '{}'",
+            applied.len(),
+            plan.workspace
+        );
+        Ok(applied)
+    }
+
+    /// Close panes, e.g. when their workspace is deleted; returns the IDs of
+    /// the panes that were closed
+    pub fn close(&self, pane_ids: &[String]) -> Vec<String> {
+        self.backend.close(pane_ids)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::room::template::TemplateEngine;
+    use crate::room::terminal_backend::{TerminalCli, WeztermBackend};
+    use std::sync::Mutex;
+
+    /// WezTerm and tmux subcommands that create a pane
+    const CREATING: [&str; 5] = [
+        "spawn",
+        "split-pane",
+        "new-session",
+        "new-window",
+        "split-window",
+    ];
+
+    /// Records the commands and hands out pane IDs from 10 upwards (`%10`
+    /// for tmux)
+    #[derive(Debug, Default)]
+    pub(crate) struct FakeCli {
+        pub(crate) calls: Mutex<Vec<Vec<String>>>,
+        pub(crate) fail_on: Option<&'static str>,
+    }
+
+    impl TerminalCli for FakeCli {
+        fn run(&self, args: &[String]) -> Result<String, String> {
+            let mut calls = self.calls.lock().unwrap();
+            calls.push(args.to_vec());
+            if self.fail_on == Some(args[0].as_str()) {
+                return Err(format!("{} refused", args[0]));
+            }
+            let created = calls
+                .iter()
+                .filter(|call| CREATING.contains(&call[0].as_str()))
+                .count();
+            Ok(match args[0].as_str() {
+                "spawn" | "split-pane" => (9 + created).to_string(),
+                "new-session" | "new-window" | "split-window" => format!("%{}", 9 + created),
+                _ => String::new(),
+            })
+        }
+    }
+
+    pub(crate) fn wezterm(cli: &Arc<FakeCli>) -> Arc<dyn TerminalBackend> {
+        Arc::new(WeztermBackend::new(cli.clone()))
+    }
+
+    #[test]
+    fn test_plan_builds_grid_columns_before_rows() {
+        let engine = TemplateEngine::new();
+        let template = engine.get_template("web_dev").unwrap();
+        let plan = LayoutPlan::from_template(template, "web", Path::new("/src/app"));
+
+        let cells: Vec<(u32, u32)> = plan.panes.iter().map(|p| (p.row, p.col)).collect();
+        assert_eq!(cells, [(0, 0), (0, 1), (1, 0), (1, 1)]);
+        assert_eq!(plan.panes[0].split, None);
+        let splits: Vec<_> = plan.panes[1..]
+            .iter()
+            .map(|p| p.split.clone().unwrap())
+            .map(|split| (split.from, split.side, split.percent))
+            .collect();
+        assert_eq!(
+            splits,
+            [
+                (0, SplitSide::Right, 50),
+                (0, SplitSide::Bottom, 50),
+                (1, SplitSide::Bottom, 50),
+            ]
+        );
+        assert_eq!(plan.panes[0].working_directory, "/src/app/frontend");
+
+        // Three columns of 33/33/34: the first split leaves two thirds
+        let template = engine.get_template("parallel_dev").unwrap();
+        let plan = LayoutPlan::from_template(template, "par", Path::new("/src"));
+        assert_eq!(plan.panes[1].split.as_ref().unwrap().percent, 67);
+        assert_eq!(plan.panes[2].split.as_ref().unwrap().percent, 50);
+        assert!(plan.panes.iter().all(|pane| pane.is_process));
+    }
+
+    #[test]
+    fn test_apply_reports_pane_ids_and_starts_commands() {
+        let engine = TemplateEngine::new();
+        let template = engine.get_template("parallel_dev").unwrap();
+        let plan = LayoutPlan::from_template(template, "par", Path::new("/src"));
+
+        let cli = Arc::new(FakeCli::default());
+        let applied = LayoutApplier::new(wezterm(&cli)).apply(&plan).unwrap();
+        let ids: Vec<&str> = applied.iter().map(|p| p.pane_id.as_str()).collect();
+        assert_eq!(ids, ["10", "11", "12"]);
+
+        let calls = cli.calls.lock().unwrap();
+        assert_eq!(
+            calls[0],
+            [
+                "spawn",
+                "--new-window",
+                "--workspace",
+                "par",
+                "--cwd",
+                "/src"
+            ]
+        );
+        assert_eq!(
+            calls[1][..7],
+            [
+                "split-pane",
+                "--pane-id",
+                "10",
+                "--right",
+                "--percent",
+                "67",
+                "--cwd"
+            ]
+        );
+        assert_eq!(
+            calls[3],
+            [
+                "send-text",
+                "--pane-id",
+                "10",
+                "--no-paste",
+                "claude-code --workspace=main --priority=high\n"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_failed_split_closes_created_panes() {
+        let engine = TemplateEngine::new();
+        let template = engine.get_template("parallel_dev").unwrap();
+        let plan = LayoutPlan::from_template(template, "par", Path::new("/src"));
+
+        let cli = Arc::new(FakeCli {
+            fail_on: Some("split-pane"),
+            ..Default::default()
+        });
+        let error = LayoutApplier::new(wezterm(&cli)).apply(&plan).unwrap_err();
+        assert!(error.contains("claude-test"), "{error}");
+        let calls = cli.calls.lock().unwrap();
+        assert_eq!(calls.last().unwrap(), &["kill-pane", "--pane-id", "10"]);
+    }
+}
@@ -0,0 +1,313 @@
+// WezTerm Multi-Process Development Framework - Template Lint
+// Best-practice warnings for workspace templates
+
+use super::template::{PaneKind, WorkspaceTemplate};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
+
+/// Auto-started processes beyond this compete for CPU, memory and API quota
+pub const MAX_AUTO_START_PROCESSES: usize = 4;
+
+/// Panes beyond these no longer fit a terminal window usefully
+pub const PANE_WARNING_LIMIT: usize = 9;
+pub const PANE_ERROR_LIMIT: usize = 16;
+
+/// Comment that turns rules off for a whole template file:
+/// `# lint-allow: absolute-path, unpinned-binary`
+pub const SUPPRESSION_PREFIX: &str = "lint-allow:";
+
+/// Runners that fetch and execute a package, so the version should be pinned
+const PACKAGE_RUNNERS: &[&str] = &["npx", "bunx", "uvx", "pnpx"];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for LintSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LintSeverity::Info => "info",
+            LintSeverity::Warning => "warning",
+            LintSeverity::Error => "error",
+        })
+    }
+}
+
+/// One finding of the lint pass
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LintIssue {
+    /// Rule ID such as `absolute-path`, usable in a suppression comment
+    pub rule: String,
+    pub severity: LintSeverity,
+    pub message: String,
+
+    /// Pane the finding is about, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pane: Option<String>,
+}
+
+impl LintIssue {
+    fn new(rule: &str, severity: LintSeverity, pane: Option<&str>, message: String) -> Self {
+        Self {
+            rule: rule.to_string(),
+            severity,
+            message,
+            pane: pane.map(str::to_string),
+        }
+    }
+}
+
+/// Findings for a template, most severe first
+pub fn lint(template: &WorkspaceTemplate) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+
+    let panes = template.default_commands.len();
+    if panes > PANE_ERROR_LIMIT {
+        issues.push(LintIssue::new(
+            "too-many-panes",
+            LintSeverity::Error,
+            None,
+            format!("{panes} panes (at most {PANE_ERROR_LIMIT}); split the template into several workspaces"),
+        ));
+    } else if panes > PANE_WARNING_LIMIT {
+        issues.push(LintIssue::new(
+            "too-many-panes",
+            LintSeverity::Warning,
+            None,
+            format!(
+                "{panes} panes are hard to follow in one window (more than {PANE_WARNING_LIMIT})"
+            ),
+        ));
+    }
+
+    let auto_started = template
+        .default_commands
+        .iter()
+        .filter(|pane| pane.is_process() && pane.auto_start)
+        .count();
+    if auto_started > MAX_AUTO_START_PROCESSES {
+        issues.push(LintIssue::new(
+            "too-many-auto-start",
+            LintSeverity::Warning,
+            None,
+            format!(
+                "{auto_started} processes start with the workspace (more than {MAX_AUTO_START_PROCESSES}); set auto_start: false on the ones not always needed"
+            ),
+        ));
+    }
+
+    match &template.watchdog {
+        Some(watchdog) if !watchdog.enabled && auto_started > 0 => {
+            issues.push(LintIssue::new(
+                "missing-health-check",
+                LintSeverity::Warning,
+                None,
+                "The watchdog is disabled, so hung processes are never detected".to_string(),
+            ));
+        }
+        watchdog => {
+            let recovers_hangs = watchdog.as_ref().is_some_and(|watchdog| watchdog.restart);
+            for pane in &template.default_commands {
+                if pane.is_process() && pane.auto_start && !pane.restart_on_exit && !recovers_hangs
+                {
+                    issues.push(LintIssue::new(
+                        "missing-health-check",
+                        LintSeverity::Info,
+                        Some(&pane.name),
+                        "Neither restarted when it exits nor when the watchdog finds it hung"
+                            .to_string(),
+                    ));
+                }
+            }
+        }
+    }
+
+    for pane in &template.default_commands {
+        let name = Some(pane.name.as_str());
+        if let Some(dir) = pane
+            .working_directory
+            .as_deref()
+            .filter(|dir| is_absolute(dir))
+        {
+            issues.push(absolute_path(name, "working_directory", dir));
+        }
+        match &pane.kind {
+            PaneKind::Process => {
+                for word in pane
+                    .command
+                    .split_whitespace()
+                    .filter(|word| is_absolute(word))
+                {
+                    issues.push(absolute_path(name, "command", word));
+                }
+                if let Some(package) = unpinned_package(&pane.command) {
+                    issues.push(LintIssue::new(
+                        "unpinned-binary",
+                        LintSeverity::Warning,
+                        name,
+                        format!("'{package}' runs whatever version is latest; pin it (e.g. {package}@1.2.3)"),
+                    ));
+                }
+            }
+            PaneKind::Notes { file } | PaneKind::Preview { file, .. } if is_absolute(file) => {
+                issues.push(absolute_path(name, "file", file));
+            }
+            _ => {}
+        }
+    }
+    if let Some(context) = &template.context {
+        if context.file.is_absolute() {
+            issues.push(absolute_path(
+                None,
+                "context.file",
+                &context.file.to_string_lossy(),
+            ));
+        }
+    }
+
+    issues.sort_by_key(|issue| std::cmp::Reverse(issue.severity));
+    issues
+}
+
+/// Findings for a template read from `source`, without the rules its
+/// suppression comments turn off
+pub fn lint_source(template: &WorkspaceTemplate, source: &str) -> Vec<LintIssue> {
+    let allowed = suppressions(source);
+    lint(template)
+        .into_iter()
+        .filter(|issue| !allowed.contains(&issue.rule))
+        .collect()
+}
+
+/// Rules named in `# lint-allow:` comments
+pub fn suppressions(source: &str) -> HashSet<String> {
+    source
+        .lines()
+        .filter_map(|line| line.split_once('#').map(|(_, comment)| comment.trim()))
+        .filter_map(|comment| comment.strip_prefix(SUPPRESSION_PREFIX))
+        .flat_map(|rules| rules.split([',', ' ']))
+        .map(str::trim)
+        .filter(|rule| !rule.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn absolute_path(pane: Option<&str>, field: &str, path: &str) -> LintIssue {
+    LintIssue::new(
+        "absolute-path",
+        LintSeverity::Warning,
+        pane,
+        format!("{field} uses the absolute path '{path}', which other machines may not have; make it relative to the project"),
+    )
+}
+
+fn is_absolute(path: &str) -> bool {
+    let path = path.trim_matches(|c| c == '"' || c == '\'');
+    Path::new(path).is_absolute() && !path.starts_with("/dev/")
+}
+
+/// Package a runner such as `npx` fetches without a version
+fn unpinned_package(command: &str) -> Option<String> {
+    let mut words = command.split_whitespace();
+    words.find(|word| PACKAGE_RUNNERS.contains(word))?;
+    let package = words.find(|word| !word.starts_with('-'))?;
+
+    // `@scope/name` is not a version, `name@1.2.3` and `name==1.2.3` are
+    let (name, version) = match package.rfind('@') {
+        Some(at) if at > 0 => (&package[..at], Some(&package[at + 1..])),
+        _ => (package, None),
+    };
+    let pinned = match version {
+        Some(version) => !version.eq_ignore_ascii_case("latest"),
+        None => package.contains("=="),
+    };
+    (!pinned).then(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::room::template::TemplateEngine;
+
+    fn template(yaml: &str) -> WorkspaceTemplate {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    fn rules(issues: &[LintIssue]) -> Vec<&str> {
+        issues.iter().map(|issue| issue.rule.as_str()).collect()
+    }
+
+    #[test]
+    fn test_lint_finds_bad_practices() {
+        let bad = template(
+            "name: bad\n\
+             watchdog: { enabled: false }\n\
+             default_commands:\n\
+             - { name: a, command: claude-code, restart_on_exit: true }\n\
+             - { name: b, command: claude-code, restart_on_exit: true }\n\
+             - { name: c, command: claude-code, restart_on_exit: true }\n\
+             - { name: d, command: claude-code, restart_on_exit: true }\n\
+             - { name: lint, command: 'npx eslint --watch', working_directory: /home/me/app }\n\
+             - { name: mcp, command: 'npx -y @acme/server@latest /opt/data', auto_start: false }\n",
+        );
+        let issues = lint(&bad);
+        assert_eq!(
+            rules(&issues),
+            [
+                "too-many-auto-start",
+                "missing-health-check",
+                "absolute-path",
+                "unpinned-binary",
+                "absolute-path",
+                "unpinned-binary",
+            ]
+        );
+        assert_eq!(issues[3].pane.as_deref(), Some("lint"));
+        assert!(issues[5].message.contains("'@acme/server'"));
+
+        let mut huge = bad.clone();
+        huge.default_commands = vec![bad.default_commands[0].clone(); PANE_ERROR_LIMIT + 1];
+        assert_eq!(lint(&huge)[0].severity, LintSeverity::Error);
+    }
+
+    #[test]
+    fn test_pinned_and_builtin_templates_are_clean() {
+        let pinned = template(
+            "name: ok\n\
+             default_commands:\n\
+             - { name: mcp, command: 'npx -y @acme/server@2.1.0 ./data', restart_on_exit: true }\n\
+             - { name: py, command: 'uvx ruff==0.5.0 check', restart_on_exit: true }\n\
+             - { name: log, command: 'tail -f logs/app.log 2>/dev/null', restart_on_exit: true }\n",
+        );
+        assert!(lint(&pinned).is_empty());
+
+        let engine = TemplateEngine::new();
+        for builtin in engine.list_templates() {
+            let issues = lint(builtin);
+            assert!(
+                issues
+                    .iter()
+                    .all(|issue| issue.severity == LintSeverity::Info),
+                "{}: {issues:?}",
+                builtin.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_suppression_comments() {
+        let source = "name: local  # lint-allow: absolute-path\n\
+                      # lint-allow: unpinned-binary, missing-health-check\n\
+                      default_commands:\n\
+                      - { name: tool, command: 'npx tool /opt/tool.json' }\n";
+        let local = template(source);
+        assert_eq!(rules(&lint(&local)).len(), 3);
+        assert!(lint_source(&local, source).is_empty());
+    }
+}
@@ -1,19 +1,24 @@
 // WezTerm Multi-Process Development Framework - Workspace Manager
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 use tokio::sync::RwLock;
 use tracing::{info, warn};
 
+use crate::bridge::{BridgeEvent, EventBridge};
 use crate::error::{Result, UserError};
+use crate::git::{self, GitConfig, GitInfo, GitStatus};
 use crate::process::{
-    ClaudeCodeConfig, ClaudeCodeConfigBuilder, ClaudeCodeDetector, ProcessManager,
+    ClaudeCodeConfig, ClaudeCodeConfigBuilder, ClaudeCodeDetector, ProcessManager, ProcessSpec,
 };
-use crate::room::state::{ProcessInfo, ProcessStatus, WorkspaceState};
+use crate::room::layout::{AppliedPane, LayoutApplier, LayoutPlan};
+use crate::room::overrides::WorkspaceOverrides;
+use crate::room::state::{DrainState, PaneState, ProcessInfo, ProcessStatus, WorkspaceState};
 use crate::room::template::{TemplateEngine, WorkspaceTemplate};
+use crate::task::TaskNote;
 
 #[derive(Debug)]
 pub struct WorkspaceManager {
@@ -25,27 +30,43 @@ pub struct WorkspaceManager {
     claude_code_detector: ClaudeCodeDetector,
     process_manager: Option<std::sync::Arc<ProcessManager>>,
     auto_start_claude_code: bool,
+    event_bridge: Option<std::sync::Arc<EventBridge>>,
+    layout_applier: Option<std::sync::Arc<LayoutApplier>>,
+    git_config: GitConfig,
+
+    /// Override files of workspaces rooted in a project directory, by workspace
+    override_files: RwLock<HashMap<String, OverrideFile>>,
 }
 
+/// A workspace's override file as last read
+#[derive(Debug, Clone, Default)]
+struct OverrideFile {
+    /// Modification time and size; a change means the file is read again
+    stamp: Option<(SystemTime, u64)>,
+
+    /// Environment from the template and creation variables, under the overrides
+    base_env: HashMap<String, String>,
+}
+
+impl OverrideFile {
+    fn stamp(root: &Path) -> Option<(SystemTime, u64)> {
+        let metadata = fs::metadata(WorkspaceOverrides::path(root)?).ok()?;
+        Some((metadata.modified().ok()?, metadata.len()))
+    }
+}
+
+/// On-disk format of the workspace state file
 #[derive(Serialize, Deserialize, Debug)]
-struct PersistedState {
-    workspaces: HashMap<String, WorkspaceState>,
-    last_saved: SystemTime,
-    version: String,
+pub(crate) struct PersistedState {
+    pub(crate) workspaces: HashMap<String, WorkspaceState>,
+    pub(crate) last_saved: SystemTime,
+    pub(crate) version: String,
 }
 
 impl WorkspaceManager {
     #[allow(clippy::result_large_err)]
     pub fn new(state_file_path: Option<PathBuf>) -> Result<Self> {
-        let state_path = state_file_path.unwrap_or_else(|| {
-            let mut path = dirs::config_dir().unwrap_or_else(|| {
-                log::warn!("設定ディレクトリが取得できません。カレントディレクトリを使用します。");
-                PathBuf::from(".")
-            });
-            path.push("wezterm-parallel");
-            path.push("workspaces.json");
-            path
-        });
+        let state_path = state_file_path.unwrap_or_else(Self::default_state_path);
 
         // Ensure the parent directory exists
         if let Some(parent) = state_path.parent() {
@@ -63,6 +84,10 @@ impl WorkspaceManager {
             claude_code_detector: ClaudeCodeDetector::new(),
             process_manager: None,
             auto_start_claude_code: true,
+            event_bridge: None,
+            layout_applier: None,
+            git_config: GitConfig::default(),
+            override_files: RwLock::new(HashMap::new()),
         };
 
         // Load existing state if available
@@ -102,6 +127,32 @@ impl WorkspaceManager {
     }
 
     pub async fn create_workspace(&self, name: &str, template_name: &str) -> Result<()> {
+        self.create_workspace_in(name, template_name, None).await
+    }
+
+    /// Create a workspace rooted at a project directory
+    ///
+    /// The project's own templates (`.wezterm-parallel/templates/*.yaml`)
+    /// override global ones of the same name.
+    pub async fn create_workspace_in(
+        &self,
+        name: &str,
+        template_name: &str,
+        project_dir: Option<&Path>,
+    ) -> Result<()> {
+        self.create_workspace_with(name, template_name, project_dir, &BTreeMap::new())
+            .await
+    }
+
+    /// Create a workspace whose processes get `variables` on top of the
+    /// template's environment (project manifests)
+    pub async fn create_workspace_with(
+        &self,
+        name: &str,
+        template_name: &str,
+        project_dir: Option<&Path>,
+        variables: &BTreeMap<String, String>,
+    ) -> Result<()> {
         if name.is_empty() {
             return Err(UserError::room_creation_failed(name, "Room名が空です"));
         }
@@ -125,16 +176,64 @@ impl WorkspaceManager {
             }
         }
 
+        if let Some(dir) = project_dir {
+            if !dir.is_dir() {
+                return Err(UserError::room_creation_failed(
+                    name,
+                    &format!("プロジェクトディレクトリが存在しません: {}", dir.display()),
+                ));
+            }
+        }
+
+        // A repository workspace gets a worktree of its own when configured
+        let (git, worktree_dir) = self.workspace_repository(name, project_dir)?;
+        let project_dir = worktree_dir.as_deref().or(project_dir);
+
+        let override_stamp = project_dir.and_then(OverrideFile::stamp);
+        let overrides = match project_dir {
+            Some(dir) => WorkspaceOverrides::load(dir)
+                .map_err(|e| UserError::room_creation_failed(name, &e))?,
+            None => None,
+        };
+
         // Apply template to create config
-        let config = self
+        let mut config = self
             .template_engine
-            .apply_template(template_name, name)
+            .apply_template_in(template_name, name, project_dir)
             .map_err(|e| {
                 UserError::room_creation_failed(name, &format!("テンプレートの適用に失敗: {e}"))
             })?;
+        config.environment_vars.extend(variables.clone());
+
+        // The template's watchdog, context file and environment (secret references
+        // included) apply to every process of the workspace, as do the overrides
+        self.apply_overrides(name, overrides.as_ref(), &config.environment_vars)
+            .await;
+        if let Some(ref process_manager) = self.process_manager {
+            process_manager
+                .set_workspace_watchdog(name, config.watchdog.clone())
+                .await;
+            process_manager
+                .set_workspace_context(name, config.context.clone())
+                .await;
+            process_manager.set_workspace_git(name, git.clone()).await;
+        }
 
         // Create workspace state
-        let workspace_state = WorkspaceState::new(name.to_string(), config);
+        let base_env = config.environment_vars.clone();
+        let mut workspace_state = WorkspaceState::new(name.to_string(), config);
+        workspace_state.project_dir = project_dir.map(|dir| dir.to_string_lossy().to_string());
+        workspace_state.git = git;
+        workspace_state.overrides = overrides;
+        if project_dir.is_some() {
+            self.override_files.write().await.insert(
+                name.to_string(),
+                OverrideFile {
+                    stamp: override_stamp,
+                    base_env,
+                },
+            );
+        }
 
         // Add to collection
         {
@@ -147,6 +246,20 @@ impl WorkspaceManager {
             name, template_name
         );
 
+        if let Some(ref bridge) = self.event_bridge {
+            bridge.publish(BridgeEvent::workspace_created(name, template_name));
+        }
+
+        // Create the template's panes in WezTerm
+        if let Some(ref applier) = self.layout_applier {
+            if let Err(e) = self
+                .apply_layout(applier, name, template_name, project_dir)
+                .await
+            {
+                warn!("Room '{}' のペインレイアウト適用に失敗: {}", name, e);
+            }
+        }
+
         // Auto-start Claude Code if enabled
         if self.auto_start_claude_code {
             if let Err(e) = self.auto_start_claude_code_for_workspace(name).await {
@@ -164,7 +277,83 @@ impl WorkspaceManager {
         Ok(())
     }
 
-    pub async fn delete_workspace(&self, name: &str) -> Result<()> {
+    /// Repository of a new workspace's root, and the directory of the
+    /// worktree created for it when `git.worktrees` is enabled
+    #[allow(clippy::result_large_err)]
+    fn workspace_repository(
+        &self,
+        name: &str,
+        project_dir: Option<&Path>,
+    ) -> Result<(Option<GitInfo>, Option<PathBuf>)> {
+        let base_dir = match project_dir {
+            Some(dir) => dir.to_path_buf(),
+            None => std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/")),
+        };
+        let Some(repository) = git::detect(&base_dir) else {
+            return Ok((None, None));
+        };
+        if !self.git_config.worktrees {
+            return Ok((Some(repository), None));
+        }
+
+        let root = PathBuf::from(&repository.root);
+        let worktree = git::create_worktree(&root, name, &self.git_config).map_err(|e| {
+            UserError::room_creation_failed(name, &format!("worktreeの作成に失敗: {e}"))
+        })?;
+        info!(
+            "Room '{}' の worktree を {} に作成しました",
+            name, worktree.root
+        );
+
+        // The same subdirectory of the worktree as of the repository, if it is there
+        let dir = base_dir
+            .canonicalize()
+            .ok()
+            .and_then(|dir| dir.strip_prefix(&root).ok().map(Path::to_path_buf))
+            .map(|subdir| Path::new(&worktree.root).join(subdir))
+            .filter(|dir| dir.is_dir())
+            .unwrap_or_else(|| PathBuf::from(&worktree.root));
+        Ok((Some(worktree), Some(dir)))
+    }
+
+    /// Working tree status of a workspace's repository, or of every
+    /// workspace in a repository when `name` is `None`
+    pub async fn git_status(&self, name: Option<&str>) -> Result<Vec<GitStatus>> {
+        let repositories: Vec<(String, Option<GitInfo>)> = {
+            let workspaces = self.workspaces.read().await;
+            match name {
+                Some(name) => {
+                    let workspace = workspaces
+                        .get(name)
+                        .ok_or_else(|| UserError::room_not_found(name))?;
+                    vec![(name.to_string(), workspace.git.clone())]
+                }
+                None => workspaces
+                    .values()
+                    .map(|workspace| (workspace.name.clone(), workspace.git.clone()))
+                    .collect(),
+            }
+        };
+
+        let mut statuses = Vec::new();
+        for (workspace, repository) in repositories {
+            let Some(repository) = repository else {
+                continue;
+            };
+            match git::status(&workspace, &repository) {
+                Ok(status) => statuses.push(status),
+                Err(e) => warn!("Room '{}' のgit状態を取得できません: {}", workspace, e),
+            }
+        }
+        statuses.sort_by(|a, b| a.workspace.cmp(&b.workspace));
+        Ok(statuses)
+    }
+
+    /// Delete a workspace and stop every process attached to it
+    ///
+    /// Returns the number of processes that were stopped. Deleting the active
+    /// workspace makes the default workspace active.
+    pub async fn delete_workspace(&self, name: &str) -> Result<usize> {
         if name == "default" {
             return Err(UserError::room_creation_failed(
                 name,
@@ -174,14 +363,29 @@ impl WorkspaceManager {
 
         let removed = {
             let mut workspaces = self.workspaces.write().await;
-            workspaces.remove(name)
+            let removed = workspaces.remove(name);
+            if removed.as_ref().is_some_and(|w| w.is_active) {
+                if let Some(default) = workspaces.get_mut("default") {
+                    default.activate();
+                }
+            }
+            removed
         };
 
         match removed {
             Some(workspace) => {
+                let stopped = self.stop_workspace_processes(name, &workspace).await
+                    + self.close_workspace_panes(&workspace).await;
+                if let Some(ref process_manager) = self.process_manager {
+                    process_manager.set_workspace_watchdog(name, None).await;
+                    process_manager.set_workspace_context(name, None).await;
+                    process_manager.set_workspace_git(name, None).await;
+                }
+                self.clear_overrides(name).await;
                 info!(
-                    "Deleted workspace '{}' (had {} processes)",
+                    "Deleted workspace '{}' (stopped {} of {} processes)",
                     name,
+                    stopped,
                     workspace.processes.len()
                 );
 
@@ -192,12 +396,325 @@ impl WorkspaceManager {
                     }
                 }
 
-                Ok(())
+                Ok(stopped)
             }
             None => Err(UserError::room_not_found(name)),
         }
     }
 
+    /// Rename a workspace, keeping its processes, tasks and history
+    pub async fn rename_workspace(&self, name: &str, new_name: &str) -> Result<()> {
+        if name == "default" {
+            return Err(UserError::room_rename_failed(
+                name,
+                new_name,
+                "デフォルトRoomは名前変更できません",
+            ));
+        }
+        if new_name.trim().is_empty() {
+            return Err(UserError::room_rename_failed(
+                name,
+                new_name,
+                "Room名が空です",
+            ));
+        }
+
+        {
+            let mut workspaces = self.workspaces.write().await;
+            if !workspaces.contains_key(name) {
+                return Err(UserError::room_not_found(name));
+            }
+            if workspaces.contains_key(new_name) {
+                return Err(UserError::room_rename_failed(
+                    name,
+                    new_name,
+                    "同名のRoomが既に存在します",
+                ));
+            }
+
+            if let Some(mut workspace) = workspaces.remove(name) {
+                workspace.name = new_name.to_string();
+                workspaces.insert(new_name.to_string(), workspace);
+            }
+        }
+
+        // The overrides follow the workspace to its new name
+        let override_file = self.override_files.write().await.remove(name);
+        if let Some(override_file) = override_file {
+            let overrides = self
+                .get_workspace_info(new_name)
+                .await
+                .and_then(|workspace| workspace.overrides);
+            self.clear_overrides(name).await;
+            self.apply_overrides(new_name, overrides.as_ref(), &override_file.base_env)
+                .await;
+            self.override_files
+                .write()
+                .await
+                .insert(new_name.to_string(), override_file);
+        }
+
+        info!("Renamed workspace '{}' to '{}'", name, new_name);
+
+        // Auto-save if enabled
+        if self.auto_save_enabled {
+            if let Err(e) = self.save_state().await {
+                warn!("Failed to auto-save after workspace rename: {}", e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Apply a workspace's overrides to the process manager and hooks;
+    /// `None` goes back to the global settings
+    async fn apply_overrides(
+        &self,
+        name: &str,
+        overrides: Option<&WorkspaceOverrides>,
+        base_env: &HashMap<String, String>,
+    ) {
+        if let Some(ref process_manager) = self.process_manager {
+            let environment = match overrides {
+                Some(overrides) => overrides.environment(base_env),
+                None => base_env.clone(),
+            };
+            process_manager
+                .set_workspace_env(name, Some(environment))
+                .await;
+            process_manager
+                .set_workspace_limit(name, overrides.and_then(|o| o.max_processes))
+                .await;
+        }
+        if let Some(hooks) = self.event_bridge.as_ref().and_then(|b| b.hooks()) {
+            hooks.set_workspace_hooks(name, overrides.map(|o| o.hooks.clone()));
+        }
+    }
+
+    /// Forget the environment, limit, hooks and override file of a workspace
+    async fn clear_overrides(&self, name: &str) {
+        if let Some(ref process_manager) = self.process_manager {
+            process_manager.set_workspace_env(name, None).await;
+            process_manager.set_workspace_limit(name, None).await;
+        }
+        if let Some(hooks) = self.event_bridge.as_ref().and_then(|b| b.hooks()) {
+            hooks.set_workspace_hooks(name, None);
+        }
+        self.override_files.write().await.remove(name);
+    }
+
+    /// Read the override files that changed since they were last read and
+    /// apply them to their workspaces
+    ///
+    /// Workspaces loaded from the state file are picked up on the first call.
+    /// A file that fails to parse is reported and the settings in effect are
+    /// kept. Returns the workspaces whose overrides changed.
+    pub async fn reload_overrides(&self) -> Vec<String> {
+        let roots: Vec<(String, PathBuf)> = {
+            let workspaces = self.workspaces.read().await;
+            workspaces
+                .iter()
+                .filter_map(|(name, workspace)| {
+                    let dir = workspace.project_dir.as_ref()?;
+                    Some((name.clone(), PathBuf::from(dir)))
+                })
+                .collect()
+        };
+
+        let mut reloaded = Vec::new();
+        for (name, root) in roots {
+            let stamp = OverrideFile::stamp(&root);
+            let (base_env, first_read) = {
+                let mut override_files = self.override_files.write().await;
+                match override_files.get_mut(&name) {
+                    Some(file) if file.stamp == stamp => continue,
+                    Some(file) => {
+                        file.stamp = stamp;
+                        (file.base_env.clone(), false)
+                    }
+                    None => {
+                        override_files.insert(
+                            name.clone(),
+                            OverrideFile {
+                                stamp,
+                                base_env: HashMap::new(),
+                            },
+                        );
+                        (HashMap::new(), true)
+                    }
+                }
+            };
+
+            let overrides = match WorkspaceOverrides::load(&root) {
+                Ok(overrides) => overrides,
+                Err(e) => {
+                    warn!("Keeping the overrides of workspace '{}': {}", name, e);
+                    continue;
+                }
+            };
+            let changed = {
+                let mut workspaces = self.workspaces.write().await;
+                match workspaces.get_mut(&name) {
+                    Some(workspace) if workspace.overrides != overrides => {
+                        workspace.overrides = overrides.clone();
+                        true
+                    }
+                    _ => false,
+                }
+            };
+            // Overrides restored with the state still have to be applied
+            if changed || first_read {
+                self.apply_overrides(&name, overrides.as_ref(), &base_env)
+                    .await;
+            }
+            if changed {
+                info!("Reloaded the overrides of workspace '{}'", name);
+                reloaded.push(name);
+            }
+        }
+
+        if !reloaded.is_empty() && self.auto_save_enabled {
+            if let Err(e) = self.save_state().await {
+                warn!("Failed to auto-save after reloading overrides: {}", e);
+            }
+        }
+        reloaded
+    }
+
+    /// Stop processes recorded in the workspace state and any the process manager tracks for it
+    /// IDs of the processes deleting a workspace would stop, sorted
+    pub async fn attached_processes(&self, name: &str) -> Vec<String> {
+        match self.get_workspace_info(name).await {
+            Some(workspace) => self.process_ids(name, &workspace).await,
+            None => Vec::new(),
+        }
+    }
+
+    /// Processes recorded in the workspace state plus those the process manager runs for it
+    async fn process_ids(&self, name: &str, workspace: &WorkspaceState) -> Vec<String> {
+        let mut process_ids: Vec<String> = workspace.processes.keys().cloned().collect();
+        if let Some(ref process_manager) = self.process_manager {
+            for process in process_manager.get_processes_by_workspace(name).await {
+                if !process_ids.contains(&process.id) {
+                    process_ids.push(process.id);
+                }
+            }
+        }
+        process_ids.sort();
+        process_ids
+    }
+
+    async fn stop_workspace_processes(&self, name: &str, workspace: &WorkspaceState) -> usize {
+        let Some(ref process_manager) = self.process_manager else {
+            return 0;
+        };
+
+        let mut stopped = 0;
+        for process_id in &self.process_ids(name, workspace).await {
+            // Processes running in panes go away with their pane
+            if workspace
+                .processes
+                .get(process_id)
+                .is_some_and(|process| process.pane_id.is_some())
+            {
+                continue;
+            }
+            match process_manager.kill_process(process_id).await {
+                Ok(()) => stopped += 1,
+                Err(e) => warn!("Failed to stop process '{}': {}", process_id, e),
+            }
+        }
+        stopped
+    }
+
+    /// Close the panes created for a workspace, returning how many of its
+    /// processes ran in them
+    async fn close_workspace_panes(&self, workspace: &WorkspaceState) -> usize {
+        let Some(applier) = self.layout_applier.clone() else {
+            return 0;
+        };
+        let pane_ids: Vec<String> = workspace.panes.iter().map(|pane| pane.id.clone()).collect();
+        if pane_ids.is_empty() {
+            return 0;
+        }
+        let closed = tokio::task::spawn_blocking(move || applier.close(&pane_ids))
+            .await
+            .unwrap_or_default();
+        workspace
+            .processes
+            .values()
+            .filter(|process| {
+                process
+                    .pane_id
+                    .as_ref()
+                    .is_some_and(|pane_id| closed.contains(pane_id))
+            })
+            .count()
+    }
+
+    /// Create the panes of a workspace's template and record them, with the
+    /// processes started in them, in the workspace state
+    async fn apply_layout(
+        &self,
+        applier: &std::sync::Arc<LayoutApplier>,
+        name: &str,
+        template_name: &str,
+        project_dir: Option<&Path>,
+    ) -> std::result::Result<Vec<AppliedPane>, String> {
+        let template = self
+            .template_engine
+            .resolve_template(template_name, project_dir)?;
+        let base_dir = match project_dir {
+            Some(dir) => dir.to_path_buf(),
+            None => std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/")),
+        };
+        let plan = LayoutPlan::from_template(&template, name, &base_dir);
+
+        let applier = std::sync::Arc::clone(applier);
+        let applied = tokio::task::spawn_blocking(move || applier.apply(&plan))
+            .await
+            .map_err(|e| e.to_string())??;
+
+        let now = SystemTime::now();
+        self.update_workspace_state(name, |workspace| {
+            workspace.layout = template.layout.clone();
+            for (index, pane) in applied.iter().enumerate() {
+                let process_id = (pane.plan.is_process && pane.plan.command.is_some())
+                    .then(|| format!("{name}-{}", pane.plan.name));
+                workspace.add_pane(PaneState {
+                    id: pane.pane_id.clone(),
+                    position: pane.position(),
+                    size: pane
+                        .plan
+                        .split
+                        .as_ref()
+                        .map_or(100.0, |split| f32::from(split.percent)),
+                    command: pane.plan.command.clone(),
+                    working_directory: pane.plan.working_directory.clone(),
+                    is_active: index == 0,
+                    process_id: process_id.clone(),
+                });
+                if let (Some(process_id), Some(command)) = (process_id, &pane.plan.command) {
+                    workspace.add_process(ProcessInfo {
+                        id: process_id,
+                        command: command.clone(),
+                        workspace: name.to_string(),
+                        pane_id: Some(pane.pane_id.clone()),
+                        status: ProcessStatus::Running,
+                        pid: None,
+                        started_at: now,
+                        last_heartbeat: now,
+                        restart_count: 0,
+                        context: None,
+                    });
+                }
+            }
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(applied)
+    }
+
     pub async fn switch_workspace(&self, name: &str) -> Result<()> {
         let mut workspaces = self.workspaces.write().await;
 
@@ -227,11 +744,28 @@ impl WorkspaceManager {
         workspaces.keys().cloned().collect()
     }
 
+    /// All workspace states, sorted by name
+    pub async fn list_workspace_states(&self) -> Vec<WorkspaceState> {
+        let workspaces = self.workspaces.read().await;
+        let mut states: Vec<WorkspaceState> = workspaces.values().cloned().collect();
+        states.sort_by(|a, b| a.name.cmp(&b.name));
+        states
+    }
+
     pub async fn get_workspace_info(&self, name: &str) -> Option<WorkspaceState> {
         let workspaces = self.workspaces.read().await;
         workspaces.get(name).cloned()
     }
 
+    /// Log file of a managed process, `None` for unknown processes
+    pub async fn process_log_path(&self, process_id: &str) -> Option<PathBuf> {
+        let workspaces = self.workspaces.read().await;
+        workspaces
+            .values()
+            .find(|workspace| workspace.processes.contains_key(process_id))
+            .map(|workspace| crate::process::tail::log_path(&workspace.name, process_id))
+    }
+
     pub async fn get_active_workspace(&self) -> Option<(String, WorkspaceState)> {
         let workspaces = self.workspaces.read().await;
         workspaces
@@ -264,10 +798,96 @@ impl WorkspaceManager {
         }
     }
 
+    /// Start draining a workspace, or change whether it is deleted once empty
+    ///
+    /// Draining again keeps the original start time.
+    pub async fn drain_workspace(&self, name: &str, delete_when_empty: bool) -> Result<DrainState> {
+        if name == "default" && delete_when_empty {
+            return Err(UserError::room_creation_failed(
+                name,
+                "デフォルトRoomは削除できません",
+            ));
+        }
+        let mut drain = None;
+        self.update_workspace_state(name, |workspace| {
+            let since = workspace
+                .drain
+                .as_ref()
+                .map_or_else(crate::task::current_timestamp, |drain| drain.since);
+            let state = DrainState {
+                since,
+                delete_when_empty,
+            };
+            workspace.drain = Some(state.clone());
+            drain = Some(state);
+        })
+        .await?;
+        info!("Draining workspace '{}'", name);
+        Ok(drain.expect("drain was just set"))
+    }
+
+    /// Stop draining a workspace; returns false if it was not draining
+    pub async fn undrain_workspace(&self, name: &str) -> Result<bool> {
+        let mut was_draining = false;
+        self.update_workspace_state(name, |workspace| {
+            was_draining = workspace.drain.take().is_some();
+        })
+        .await?;
+        if was_draining {
+            info!("Workspace '{}' no longer draining", name);
+        }
+        Ok(was_draining)
+    }
+
+    /// Drain state of every draining workspace
+    pub async fn draining_workspaces(&self) -> BTreeMap<String, DrainState> {
+        let workspaces = self.workspaces.read().await;
+        workspaces
+            .iter()
+            .filter_map(|(name, workspace)| Some((name.clone(), workspace.drain.clone()?)))
+            .collect()
+    }
+
+    pub async fn is_draining(&self, name: &str) -> bool {
+        let workspaces = self.workspaces.read().await;
+        workspaces.get(name).is_some_and(|w| w.drain.is_some())
+    }
+
+    /// Append a note to a workspace scratchpad
+    pub async fn add_workspace_note(
+        &self,
+        name: &str,
+        content: String,
+        author: Option<String>,
+    ) -> Result<TaskNote> {
+        let mut added = None;
+        self.update_workspace_state(name, |workspace| {
+            added = Some(workspace.add_note(content, author));
+        })
+        .await?;
+        Ok(added.expect("note was just added"))
+    }
+
+    /// Default workspace state file location
+    pub fn default_state_path() -> PathBuf {
+        let mut path = dirs::config_dir().unwrap_or_else(|| {
+            log::warn!("設定ディレクトリが取得できません。カレントディレクトリを使用します。");
+            PathBuf::from(".")
+        });
+        path.push("wezterm-parallel");
+        path.push("workspaces.json");
+        path
+    }
+
     pub fn register_template(&mut self, template: WorkspaceTemplate) {
         self.template_engine.register_template(template);
     }
 
+    /// Load the templates of a directory (e.g. `workspace.templates_dir`)
+    pub fn load_templates(&mut self, dir: &Path) -> usize {
+        self.template_engine.load_dir(dir)
+    }
+
     pub fn list_templates(&self) -> Vec<&WorkspaceTemplate> {
         self.template_engine.list_templates()
     }
@@ -403,7 +1023,27 @@ impl WorkspaceManager {
         self.process_manager = Some(process_manager);
     }
 
+    /// 設定されたプロセスマネージャー
+    pub fn process_manager(&self) -> Option<std::sync::Arc<ProcessManager>> {
+        self.process_manager.clone()
+    }
+
+    /// テンプレートのペインレイアウトを WezTerm に作成するアプライヤーを設定
+    pub fn set_layout_applier(&mut self, layout_applier: std::sync::Arc<LayoutApplier>) {
+        self.layout_applier = Some(layout_applier);
+    }
+
+    /// ワークスペースのイベントを外部Webhookに送るブリッジを設定
+    pub fn set_event_bridge(&mut self, event_bridge: std::sync::Arc<EventBridge>) {
+        self.event_bridge = Some(event_bridge);
+    }
+
     /// Claude Code自動起動を有効/無効にする
+    /// Git integration settings of new workspaces
+    pub fn set_git_config(&mut self, git_config: GitConfig) {
+        self.git_config = git_config;
+    }
+
     pub fn set_auto_start_claude_code(&mut self, enabled: bool) {
         self.auto_start_claude_code = enabled;
     }
@@ -423,16 +1063,21 @@ impl WorkspaceManager {
         info!("Detected Claude Code binary at: {:?}", binary_path);
 
         // ワークスペース情報を取得
-        let _workspace_info = self
+        let workspace_info = self
             .get_workspace_info(workspace_name)
             .await
             .ok_or_else(|| UserError::room_not_found(workspace_name))?;
 
         // プロジェクトルートを取得（現在のディレクトリ、または指定されたディレクトリ）
-        let project_root = std::env::current_dir().unwrap_or_else(|_| {
-            log::warn!("現在のディレクトリが取得できません。カレントディレクトリを使用します。");
-            PathBuf::from(".")
-        });
+        let project_root = match workspace_info.project_dir {
+            Some(dir) => PathBuf::from(dir),
+            None => std::env::current_dir().unwrap_or_else(|_| {
+                log::warn!(
+                    "現在のディレクトリが取得できません。カレントディレクトリを使用します。"
+                );
+                PathBuf::from(".")
+            }),
+        };
 
         // Claude Code設定を構築
         let claude_config = match ClaudeCodeConfigBuilder::new(binary_path, workspace_name)
@@ -469,6 +1114,12 @@ impl WorkspaceManager {
                         process_id, workspace_name
                     );
 
+                    // 起動時に渡したコンテキストファイルも記録する
+                    let context = process_manager
+                        .get_process_info(&process_id)
+                        .await
+                        .and_then(|info| info.context);
+
                     // ワークスペース状態を更新してプロセス情報を追加
                     self.update_workspace_state(workspace_name, |workspace| {
                         workspace.processes.insert(
@@ -483,6 +1134,7 @@ impl WorkspaceManager {
                                 started_at: SystemTime::now(),
                                 last_heartbeat: SystemTime::now(),
                                 restart_count: 0,
+                                context,
                             },
                         );
                     })
@@ -517,13 +1169,13 @@ impl WorkspaceManager {
             uuid::Uuid::new_v4().simple()
         );
 
-        // コマンド引数を構築（バイナリパス + 引数）
-        let mut command_args = vec![claude_config.binary_path.to_string_lossy().to_string()];
-        command_args.extend(claude_config.get_complete_arguments());
+        // 検出したバイナリと引数で起動する
+        let spec = ProcessSpec::new(claude_config.binary_path.to_string_lossy())
+            .with_args(claude_config.get_complete_arguments());
 
         // プロセスを起動
         match process_manager
-            .spawn_process(process_id.clone(), workspace_name.to_string(), command_args)
+            .spawn_command(process_id.clone(), workspace_name.to_string(), spec)
             .await
         {
             Ok(_) => {
@@ -536,6 +1188,11 @@ impl WorkspaceManager {
 
     /// ワークスペース用のClaude Codeプロセスを手動で起動
     pub async fn start_claude_code_for_workspace(&self, workspace_name: &str) -> Result<String> {
+        if self.is_draining(workspace_name).await {
+            return Err(UserError::claude_code_startup_failed(&format!(
+                "workspace '{workspace_name}' is draining"
+            )));
+        }
         self.auto_start_claude_code_for_workspace(workspace_name)
             .await?;
         // プロセスIDを返す（実際の実装では起動したプロセスIDを返す）
@@ -606,6 +1263,113 @@ mod tests {
         assert!(workspaces.contains(&"test".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_create_workspace_with_project_template() {
+        let manager = create_test_manager().await;
+        let project = tempdir().unwrap();
+        let templates = project.path().join(".wezterm-parallel/templates");
+        fs::create_dir_all(&templates).unwrap();
+        fs::write(
+            templates.join("repo-agents.yaml"),
+            "default_commands:\n  - name: shell\n    command: bash\n",
+        )
+        .unwrap();
+
+        manager
+            .create_workspace_in("repo", "repo-agents", Some(project.path()))
+            .await
+            .unwrap();
+        let workspace = manager.get_workspace_info("repo").await.unwrap();
+        assert_eq!(workspace.template, "repo-agents");
+        assert_eq!(
+            workspace.project_dir.as_deref(),
+            Some(project.path().to_str().unwrap())
+        );
+
+        // Project templates are not visible to other workspaces
+        assert!(manager
+            .create_workspace("other", "repo-agents")
+            .await
+            .is_err());
+        assert!(manager
+            .create_workspace_in("gone", "basic", Some(Path::new("/nonexistent/project")))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_workspace_in_own_worktree() {
+        use crate::process::manager::RestartPolicy;
+        use crate::process::ProcessConfig;
+
+        let project = tempdir().unwrap();
+        let repo = crate::git::tests::init_repo(project.path());
+        fs::create_dir(repo.join("web")).unwrap();
+
+        let mut manager = create_test_manager().await;
+        let (process_manager, _events) = ProcessManager::new(ProcessConfig {
+            claude_code_binary: "sleep".to_string(),
+            max_processes: 2,
+            max_processes_per_workspace: None,
+            health_check_interval_secs: 1,
+            restart_delay_secs: 1,
+            max_restart_attempts: 0,
+            process_timeout_secs: 10,
+            default_restart_policy: RestartPolicy::Never,
+            environment_vars: HashMap::new(),
+            working_directory: None,
+            watchdog: Default::default(),
+            circuit_breaker: Default::default(),
+            secrets: Default::default(),
+        });
+        let process_manager = std::sync::Arc::new(process_manager);
+        manager.set_auto_start_claude_code(false);
+        manager.set_process_manager(process_manager.clone());
+        manager.set_git_config(GitConfig {
+            worktrees: true,
+            ..GitConfig::default()
+        });
+
+        manager
+            .create_workspace_in("web", "basic", Some(&repo.join("web")))
+            .await
+            .unwrap();
+        let workspace = manager.get_workspace_info("web").await.unwrap();
+        let git = workspace.git.unwrap();
+        let worktree = repo.join(crate::git::WORKTREES_DIR).join("web");
+        assert!(git.worktree);
+        assert_eq!(git.branch.as_deref(), Some("wezterm-parallel/web"));
+        assert_eq!(Path::new(&git.root), worktree);
+        // The uncommitted subdirectory is not in the worktree
+        assert_eq!(
+            workspace.project_dir.as_deref(),
+            Some(worktree.to_str().unwrap())
+        );
+
+        let statuses = manager.git_status(None).await.unwrap();
+        assert!(statuses
+            .iter()
+            .any(|status| status.workspace == "web" && status.is_clean()));
+        assert!(manager.git_status(Some("missing")).await.is_err());
+
+        process_manager
+            .spawn_process(
+                "agent".to_string(),
+                "web".to_string(),
+                vec!["30".to_string()],
+            )
+            .await
+            .unwrap();
+        let environment = process_manager.get_environment("agent").await.unwrap();
+        let branch = environment
+            .variables
+            .iter()
+            .find(|v| v.name == crate::git::GIT_BRANCH_VAR)
+            .unwrap();
+        assert_eq!(branch.value, "wezterm-parallel/web");
+        process_manager.kill_process("agent").await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_create_duplicate_workspace() {
         let manager = create_test_manager().await;
@@ -647,6 +1411,183 @@ mod tests {
         assert!(!workspaces.contains(&"test".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_delete_workspace_stops_attached_processes() {
+        use crate::process::manager::RestartPolicy;
+        use crate::process::ProcessConfig;
+
+        let mut manager = create_test_manager().await;
+        let (process_manager, _events) = ProcessManager::new(ProcessConfig {
+            claude_code_binary: "sleep".to_string(),
+            max_processes: 2,
+            max_processes_per_workspace: None,
+            health_check_interval_secs: 1,
+            restart_delay_secs: 1,
+            max_restart_attempts: 0,
+            process_timeout_secs: 10,
+            default_restart_policy: RestartPolicy::Never,
+            environment_vars: HashMap::new(),
+            working_directory: None,
+            watchdog: Default::default(),
+            circuit_breaker: Default::default(),
+            secrets: Default::default(),
+        });
+        let process_manager = std::sync::Arc::new(process_manager);
+        manager.set_auto_start_claude_code(false);
+        manager.set_process_manager(process_manager.clone());
+
+        manager.create_workspace("test", "basic").await.unwrap();
+        manager.switch_workspace("test").await.unwrap();
+        process_manager
+            .spawn_process(
+                "test-sleep".to_string(),
+                "test".to_string(),
+                vec!["30".to_string()],
+            )
+            .await
+            .unwrap();
+
+        let stopped = manager.delete_workspace("test").await.unwrap();
+        assert_eq!(stopped, 1);
+        let process = process_manager
+            .get_process_info("test-sleep")
+            .await
+            .unwrap();
+        assert_eq!(process.status, ProcessStatus::Stopped);
+
+        // The default workspace takes over as active
+        let active = manager.get_active_workspace().await.unwrap();
+        assert_eq!(active.0, "default");
+    }
+
+    #[tokio::test]
+    async fn test_workspace_overrides_are_applied_and_reloaded() {
+        use crate::process::manager::RestartPolicy;
+        use crate::process::ProcessConfig;
+        use crate::room::overrides::OVERRIDES_FILE;
+
+        let mut manager = create_test_manager().await;
+        let (process_manager, _events) = ProcessManager::new(ProcessConfig {
+            claude_code_binary: "sleep".to_string(),
+            max_processes: 4,
+            max_processes_per_workspace: None,
+            health_check_interval_secs: 1,
+            restart_delay_secs: 1,
+            max_restart_attempts: 0,
+            process_timeout_secs: 10,
+            default_restart_policy: RestartPolicy::Never,
+            environment_vars: HashMap::new(),
+            working_directory: None,
+            watchdog: Default::default(),
+            circuit_breaker: Default::default(),
+            secrets: Default::default(),
+        });
+        let process_manager = std::sync::Arc::new(process_manager);
+        manager.set_auto_start_claude_code(false);
+        manager.set_process_manager(process_manager.clone());
+
+        let project = tempdir().unwrap();
+        let templates = project.path().join("tools/templates");
+        fs::create_dir_all(&templates).unwrap();
+        fs::write(
+            templates.join("agents.yaml"),
+            "default_commands:\n  - name: shell\n    command: bash\n",
+        )
+        .unwrap();
+        let overrides_file = project.path().join(OVERRIDES_FILE);
+        fs::write(
+            &overrides_file,
+            "max_processes: 1\nenvironment_vars:\n  RUST_LOG: debug\ntemplates_dir: tools/templates\n",
+        )
+        .unwrap();
+
+        manager
+            .create_workspace_in("api", "agents", Some(project.path()))
+            .await
+            .unwrap();
+        let workspace = manager.get_workspace_info("api").await.unwrap();
+        assert_eq!(workspace.overrides.unwrap().max_processes, Some(1));
+
+        let spawn = |id: &str| {
+            process_manager.spawn_process(id.to_string(), "api".to_string(), vec!["30".to_string()])
+        };
+        spawn("api-1").await.unwrap();
+        let error = spawn("api-2").await.unwrap_err();
+        assert!(error.contains("process limit (1)"), "{error}");
+        let environment = process_manager.get_environment("api-1").await.unwrap();
+        assert!(environment
+            .variables
+            .iter()
+            .any(|v| v.name == "RUST_LOG" && v.value == "debug"));
+
+        // Edits apply without recreating the workspace; broken ones are ignored
+        assert!(manager.reload_overrides().await.is_empty());
+        fs::write(&overrides_file, "max_processes: 2\n").unwrap();
+        assert_eq!(manager.reload_overrides().await, ["api"]);
+        spawn("api-2").await.unwrap();
+        fs::write(&overrides_file, "max_processes: [oops]\n").unwrap();
+        assert!(manager.reload_overrides().await.is_empty());
+        let workspace = manager.get_workspace_info("api").await.unwrap();
+        assert_eq!(workspace.overrides.unwrap().max_processes, Some(2));
+
+        assert_eq!(manager.delete_workspace("api").await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_layout_panes_are_recorded_and_closed_with_workspace() {
+        use crate::room::layout::tests::{wezterm, FakeCli};
+
+        let mut manager = create_test_manager().await;
+        let cli = std::sync::Arc::new(FakeCli::default());
+        manager.set_auto_start_claude_code(false);
+        manager.set_layout_applier(std::sync::Arc::new(LayoutApplier::new(wezterm(&cli))));
+
+        manager
+            .create_workspace("par", "parallel_dev")
+            .await
+            .unwrap();
+        let workspace = manager.get_workspace_info("par").await.unwrap();
+        let pane_ids: Vec<&str> = workspace.panes.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(pane_ids, ["10", "11", "12"]);
+        let process = &workspace.processes["par-claude-test"];
+        assert_eq!(process.pane_id.as_deref(), Some("11"));
+        assert_eq!(
+            process.command,
+            "claude-code --workspace=test --priority=medium"
+        );
+
+        // Without a process manager only the panes are closed
+        let stopped = manager.delete_workspace("par").await.unwrap();
+        assert_eq!(stopped, 3);
+        let calls = cli.calls.lock().unwrap();
+        let killed: Vec<&str> = calls
+            .iter()
+            .filter(|call| call[0] == "kill-pane")
+            .map(|call| call[2].as_str())
+            .collect();
+        assert_eq!(killed, ["10", "11", "12"]);
+    }
+
+    #[tokio::test]
+    async fn test_rename_workspace() {
+        let manager = create_test_manager().await;
+        manager.create_workspace("old", "basic").await.unwrap();
+        manager.create_workspace("taken", "basic").await.unwrap();
+
+        assert!(manager.rename_workspace("old", "taken").await.is_err());
+        assert!(manager.rename_workspace("default", "main").await.is_err());
+        assert!(manager.rename_workspace("missing", "new").await.is_err());
+
+        manager.rename_workspace("old", "new").await.unwrap();
+        let names: Vec<String> = manager
+            .list_workspace_states()
+            .await
+            .into_iter()
+            .map(|w| w.name)
+            .collect();
+        assert_eq!(names, vec!["default", "new", "taken"]);
+    }
+
     #[tokio::test]
     async fn test_cannot_delete_default_workspace() {
         let manager = create_test_manager().await;
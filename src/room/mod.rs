@@ -1,11 +1,25 @@
 // WezTerm Multi-Process Development Framework - Workspace Management Module
 
+pub mod drain;
 pub mod integration;
+pub mod layout;
+pub mod lint;
 pub mod manager;
+pub mod overrides;
+pub mod project;
+pub mod snapshot;
 pub mod state;
 pub mod template;
+pub mod terminal_backend;
 
+pub use drain::DrainProgress;
 pub use integration::IntegratedWorkspaceManager;
+pub use layout::{LayoutApplier, LayoutPlan};
+pub use lint::{LintIssue, LintSeverity};
 pub use manager::WorkspaceManager;
+pub use overrides::WorkspaceOverrides;
+pub use project::{ProjectManifest, ProjectWorkspace};
+pub use snapshot::{SessionRestoreReport, SessionSnapshot, SESSION_SNAPSHOT_VERSION};
 pub use state::{WorkspaceConfig, WorkspaceState};
 pub use template::{TemplateEngine, WorkspaceTemplate};
+pub use terminal_backend::{TerminalBackend, TerminalBackendKind};
@@ -0,0 +1,167 @@
+// WezTerm Multi-Process Development Framework - Workspace Overrides
+// `.wezterm-parallel.yaml` in a workspace root, layered over the global
+// configuration for that workspace only
+//
+// The file is read when the workspace is created and re-read when it
+// changes (see `WorkspaceManager::reload_overrides`).
+
+use crate::hooks::HooksConfig;
+use crate::process::env::validate_name;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Name of the override file in a workspace root
+pub const OVERRIDES_FILE: &str = ".wezterm-parallel.yaml";
+
+/// Settings of one workspace over the global configuration
+///
+/// ```yaml
+/// max_processes: 4
+/// environment_vars:
+///   RUST_LOG: debug
+///   DATABASE_URL: "${secret:staging_db}"
+/// templates_dir: tools/templates
+/// hooks:
+///   workspace_created:
+///     - command: ./scripts/bootstrap.sh
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorkspaceOverrides {
+    /// Most processes the workspace may run at once
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_processes: Option<usize>,
+
+    /// Environment of the workspace's processes, over the template's and
+    /// the variables it was created with
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub environment_vars: BTreeMap<String, String>,
+
+    /// Templates searched before `.wezterm-parallel/templates`, relative to
+    /// the workspace root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub templates_dir: Option<PathBuf>,
+
+    /// Hooks run for the workspace's events after the global ones; they run
+    /// in the workspace root
+    #[serde(skip_serializing_if = "HooksConfig::is_empty")]
+    pub hooks: HooksConfig,
+}
+
+impl WorkspaceOverrides {
+    /// Override file of the workspace rooted at `root`
+    ///
+    /// `None` for the home directory, where the file is the global
+    /// configuration.
+    pub fn path(root: &Path) -> Option<PathBuf> {
+        if dirs::home_dir().is_some_and(|home| home == root) {
+            return None;
+        }
+        Some(root.join(OVERRIDES_FILE))
+    }
+
+    /// Read the override file of `root`; `Ok(None)` when there is none
+    ///
+    /// Relative paths are resolved against `root`.
+    pub fn load(root: &Path) -> Result<Option<Self>, String> {
+        let Some(path) = Self::path(root) else {
+            return Ok(None);
+        };
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("Failed to read '{}': {}", path.display(), e)),
+        };
+        let mut overrides: Self = serde_yaml::from_str(&content)
+            .map_err(|e| format!("Invalid workspace overrides '{}': {}", path.display(), e))?;
+        overrides
+            .validate()
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        if let Some(dir) = overrides.templates_dir.take() {
+            overrides.templates_dir = Some(root.join(dir));
+        }
+        for hook in [
+            &mut overrides.hooks.workspace_created,
+            &mut overrides.hooks.agent_failed,
+            &mut overrides.hooks.task_completed,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            hook.cwd = Some(match hook.cwd.take() {
+                Some(cwd) => root.join(cwd),
+                None => root.to_path_buf(),
+            });
+        }
+        Ok(Some(overrides))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_processes == Some(0) {
+            return Err("max_processes must be at least 1".to_string());
+        }
+        for name in self.environment_vars.keys() {
+            validate_name(name)?;
+        }
+        Ok(())
+    }
+
+    /// Environment of the workspace's processes, given the one it was created with
+    pub fn environment(&self, base: &HashMap<String, String>) -> HashMap<String, String> {
+        let mut environment = base.clone();
+        environment.extend(self.environment_vars.clone());
+        environment
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_load_resolves_paths_against_root() {
+        let root = tempdir().unwrap();
+        assert_eq!(WorkspaceOverrides::load(root.path()).unwrap(), None);
+
+        std::fs::write(
+            root.path().join(OVERRIDES_FILE),
+            "max_processes: 2\nenvironment_vars:\n  RUST_LOG: debug\ntemplates_dir: tools/templates\nhooks:\n  workspace_created:\n    - command: ./bootstrap.sh\n    - command: make\n      cwd: web\n",
+        )
+        .unwrap();
+        let overrides = WorkspaceOverrides::load(root.path()).unwrap().unwrap();
+        assert_eq!(overrides.max_processes, Some(2));
+        assert_eq!(
+            overrides.templates_dir,
+            Some(root.path().join("tools/templates"))
+        );
+        let hooks = &overrides.hooks.workspace_created;
+        assert_eq!(hooks[0].cwd.as_deref(), Some(root.path()));
+        assert_eq!(hooks[1].cwd, Some(root.path().join("web")));
+
+        let base = HashMap::from([
+            ("RUST_LOG".to_string(), "info".to_string()),
+            ("PORT".to_string(), "3000".to_string()),
+        ]);
+        let environment = overrides.environment(&base);
+        assert_eq!(environment["RUST_LOG"], "debug");
+        assert_eq!(environment["PORT"], "3000");
+    }
+
+    #[test]
+    fn test_invalid_overrides_are_rejected() {
+        let root = tempdir().unwrap();
+        let file = root.path().join(OVERRIDES_FILE);
+        for content in [
+            "max_processes: 0\n",
+            "environment_vars:\n  CLAUDE_WORKSPACE: other\n",
+            // Typos are reported rather than ignored
+            "max_process: 4\n",
+        ] {
+            std::fs::write(&file, content).unwrap();
+            assert!(WorkspaceOverrides::load(root.path()).is_err(), "{content}");
+        }
+    }
+}
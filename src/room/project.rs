@@ -0,0 +1,229 @@
+// WezTerm Multi-Process Development Framework - Project Manifest
+// Several workspaces declared together, brought up and torn down in dependency order
+
+use crate::process::env::validate_name;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Workspaces of a project (`wezterm-parallel up project.yaml`)
+///
+/// ```yaml
+/// name: shop
+/// variables:
+///   RUST_LOG: info
+/// workspaces:
+///   - name: api
+///     template: backend
+///     dir: services/api
+///     variables:
+///       PORT: "8080"
+///   - name: web
+///     template: web_dev
+///     dir: web
+///     depends_on: [api]
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ProjectManifest {
+    pub name: String,
+
+    /// Environment variables of every workspace's processes
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+
+    pub workspaces: Vec<ProjectWorkspace>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ProjectWorkspace {
+    pub name: String,
+
+    #[serde(default = "default_template")]
+    pub template: String,
+
+    /// Project directory of the workspace, relative to the manifest; the
+    /// manifest's own directory when omitted
+    #[serde(default)]
+    pub dir: Option<PathBuf>,
+
+    /// Variables of this workspace, overriding the project's
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+
+    /// Workspaces that are created before and deleted after this one
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+}
+
+fn default_template() -> String {
+    "basic".to_string()
+}
+
+impl ProjectManifest {
+    /// Read a manifest and resolve the workspace directories against its location
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+        let mut manifest: Self = serde_yaml::from_str(&content)
+            .map_err(|e| format!("Invalid project manifest '{}': {}", path.display(), e))?;
+
+        let base_dir = path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."))
+            .canonicalize()
+            .map_err(|e| format!("Failed to resolve '{}': {}", path.display(), e))?;
+        for workspace in &mut manifest.workspaces {
+            workspace.dir = Some(match workspace.dir.take() {
+                Some(dir) => base_dir.join(dir),
+                None => base_dir.clone(),
+            });
+        }
+
+        manifest.startup_order()?;
+        Ok(manifest)
+    }
+
+    /// Workspaces in the order they are created: dependencies first, otherwise
+    /// as declared. Fails on duplicate names, unknown dependencies and cycles.
+    pub fn startup_order(&self) -> Result<Vec<&ProjectWorkspace>, String> {
+        let mut names = HashSet::new();
+        for workspace in &self.workspaces {
+            if workspace.name.trim().is_empty() {
+                return Err(format!(
+                    "Project '{}' has a workspace without name",
+                    self.name
+                ));
+            }
+            if !names.insert(workspace.name.as_str()) {
+                return Err(format!("Workspace '{}' is declared twice", workspace.name));
+            }
+            for name in self.variables.keys().chain(workspace.variables.keys()) {
+                validate_name(name)?;
+            }
+        }
+        for workspace in &self.workspaces {
+            if let Some(unknown) = workspace
+                .depends_on
+                .iter()
+                .find(|dependency| !names.contains(dependency.as_str()))
+            {
+                return Err(format!(
+                    "Workspace '{}' depends on unknown workspace '{}'",
+                    workspace.name, unknown
+                ));
+            }
+        }
+
+        let mut order: Vec<&ProjectWorkspace> = Vec::with_capacity(self.workspaces.len());
+        while order.len() < self.workspaces.len() {
+            let next = self.workspaces.iter().find(|workspace| {
+                !order.iter().any(|placed| placed.name == workspace.name)
+                    && workspace
+                        .depends_on
+                        .iter()
+                        .all(|dependency| order.iter().any(|placed| &placed.name == dependency))
+            });
+            match next {
+                Some(workspace) => order.push(workspace),
+                None => {
+                    let cycle: Vec<&str> = self
+                        .workspaces
+                        .iter()
+                        .filter(|workspace| {
+                            !order.iter().any(|placed| placed.name == workspace.name)
+                        })
+                        .map(|workspace| workspace.name.as_str())
+                        .collect();
+                    return Err(format!(
+                        "Workspaces depend on each other in a cycle: {}",
+                        cycle.join(", ")
+                    ));
+                }
+            }
+        }
+        Ok(order)
+    }
+
+    /// Project variables with the workspace's own on top
+    pub fn variables_of(&self, workspace: &ProjectWorkspace) -> BTreeMap<String, String> {
+        let mut variables = self.variables.clone();
+        variables.extend(workspace.variables.clone());
+        variables
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(yaml: &str) -> ProjectManifest {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_startup_order_follows_dependencies() {
+        let project = manifest(
+            "name: shop\n\
+             variables: { RUST_LOG: info, PORT: '80' }\n\
+             workspaces:\n\
+             - { name: web, template: web_dev, depends_on: [api, auth] }\n\
+             - { name: api, depends_on: [db] }\n\
+             - { name: db }\n\
+             - { name: auth, variables: { PORT: '9000' } }\n",
+        );
+        let order: Vec<_> = project
+            .startup_order()
+            .unwrap()
+            .iter()
+            .map(|workspace| workspace.name.as_str())
+            .collect();
+        assert_eq!(order, ["db", "api", "auth", "web"]);
+
+        let auth = &project.workspaces[3];
+        assert_eq!(auth.template, "basic");
+        let variables = project.variables_of(auth);
+        assert_eq!(variables["PORT"], "9000");
+        assert_eq!(variables["RUST_LOG"], "info");
+    }
+
+    #[test]
+    fn test_invalid_manifests_are_rejected() {
+        let cycle = manifest(
+            "name: p\nworkspaces:\n\
+             - { name: a, depends_on: [b] }\n\
+             - { name: b, depends_on: [a] }\n\
+             - { name: c }\n",
+        );
+        assert_eq!(
+            cycle.startup_order().unwrap_err(),
+            "Workspaces depend on each other in a cycle: a, b"
+        );
+
+        let unknown = manifest("name: p\nworkspaces:\n- { name: a, depends_on: [x] }\n");
+        assert!(unknown.startup_order().is_err());
+        let duplicate = manifest("name: p\nworkspaces:\n- { name: a }\n- { name: a }\n");
+        assert!(duplicate.startup_order().is_err());
+        let reserved =
+            manifest("name: p\nworkspaces:\n- { name: a, variables: { CLAUDE_WORKSPACE: x } }\n");
+        assert!(reserved.startup_order().is_err());
+    }
+
+    #[test]
+    fn test_load_resolves_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("api")).unwrap();
+        let path = dir.path().join("project.yaml");
+        std::fs::write(
+            &path,
+            "name: p\nworkspaces:\n- { name: api, dir: api }\n- { name: root }\n",
+        )
+        .unwrap();
+
+        let project = ProjectManifest::load(&path).unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        assert_eq!(project.workspaces[0].dir, Some(root.join("api")));
+        assert_eq!(project.workspaces[1].dir, Some(root));
+        assert!(ProjectManifest::load(&dir.path().join("missing.yaml")).is_err());
+    }
+}
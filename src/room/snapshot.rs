@@ -0,0 +1,487 @@
+// WezTerm Multi-Process Development Framework - Session Snapshots
+// Versioned archive of all workspaces, their processes and unfinished tasks,
+// for recreating the whole environment later or on another machine
+
+use super::state::{ProcessInfo, ProcessStatus};
+use super::WorkspaceManager;
+use crate::process::ProcessSpec;
+use crate::task::{Task, TaskId, TaskManager, TaskNote};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Version written by this build; newer archives are refused
+pub const SESSION_SNAPSHOT_VERSION: u32 = 1;
+
+/// A process running in a workspace when the snapshot was taken
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessSnapshot {
+    pub id: String,
+    pub command: String,
+}
+
+/// A workspace with the template it was created from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceSnapshot {
+    pub name: String,
+    pub template: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_dir: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub processes: Vec<ProcessSnapshot>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<TaskNote>,
+}
+
+/// Everything needed to recreate a session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    pub version: u32,
+    pub created_at: u64,
+
+    /// Framework version that took the snapshot
+    #[serde(default)]
+    pub framework_version: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_workspace: Option<String>,
+    pub workspaces: Vec<WorkspaceSnapshot>,
+
+    /// Tasks that had not finished; they are queued again on restore
+    #[serde(default)]
+    pub tasks: Vec<Task>,
+}
+
+/// Outcome of restoring a snapshot
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionRestoreReport {
+    pub dry_run: bool,
+    pub workspaces_created: Vec<String>,
+
+    /// Workspaces that already existed and were left alone
+    pub workspaces_skipped: Vec<String>,
+    pub processes_started: usize,
+    pub tasks_restored: usize,
+
+    /// Tasks whose ID was already known
+    pub tasks_skipped: usize,
+
+    /// Parts that could not be restored
+    pub warnings: Vec<String>,
+}
+
+impl SessionSnapshot {
+    /// Snapshot of the current workspaces, their processes and unfinished tasks
+    pub async fn capture(workspace_manager: &WorkspaceManager, task_manager: &TaskManager) -> Self {
+        let mut workspaces = workspace_manager.list_workspace_states().await;
+        workspaces.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.name.cmp(&b.name)));
+
+        let mut tasks: Vec<Task> = task_manager
+            .list_tasks(None)
+            .await
+            .into_iter()
+            .filter(|task| !task.is_finished())
+            .collect();
+        tasks.sort_by(|a, b| (a.created_at, &a.id).cmp(&(b.created_at, &b.id)));
+
+        Self {
+            version: SESSION_SNAPSHOT_VERSION,
+            created_at: crate::task::current_timestamp(),
+            framework_version: env!("CARGO_PKG_VERSION").to_string(),
+            active_workspace: workspaces
+                .iter()
+                .find(|w| w.is_active)
+                .map(|w| w.name.clone()),
+            workspaces: workspaces
+                .into_iter()
+                .map(|workspace| {
+                    let mut processes: Vec<ProcessSnapshot> = workspace
+                        .processes
+                        .values()
+                        .filter(|process| is_live(&process.status))
+                        .map(|process| ProcessSnapshot {
+                            id: process.id.clone(),
+                            command: process.command.clone(),
+                        })
+                        .collect();
+                    processes.sort_by(|a, b| a.id.cmp(&b.id));
+                    WorkspaceSnapshot {
+                        name: workspace.name,
+                        template: workspace.template,
+                        project_dir: workspace.project_dir,
+                        processes,
+                        notes: workspace.notes,
+                    }
+                })
+                .collect(),
+            tasks,
+        }
+    }
+
+    pub fn render(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| e.to_string())
+    }
+
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let snapshot: Self = serde_json::from_str(content).map_err(|e| e.to_string())?;
+        if snapshot.version > SESSION_SNAPSHOT_VERSION {
+            return Err(format!(
+                "Session snapshot version {} is newer than the supported version {}",
+                snapshot.version, SESSION_SNAPSHOT_VERSION
+            ));
+        }
+        Ok(snapshot)
+    }
+
+    /// Write the archive, readable only by its owner since task environments
+    /// may hold credentials
+    pub fn write_to(&self, path: &Path) -> Result<(), String> {
+        let content = self.render()?;
+        let mut options = std::fs::OpenOptions::new();
+        options.create(true).write(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options
+            .open(path)
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+        std::io::Write::write_all(&mut file, content.as_bytes())
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+    }
+
+    pub fn read_from(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        Self::parse(&content)
+            .map_err(|e| format!("Invalid session snapshot {}: {e}", path.display()))
+    }
+
+    /// Recreate the snapshot's workspaces, processes and tasks
+    ///
+    /// Existing workspaces and known task IDs are left alone. Missing project
+    /// directories (another machine) fall back to the current directory.
+    /// Processes are started only when a process manager is attached and the
+    /// workspace does not already run the same command (auto-started agents).
+    pub async fn restore(
+        self,
+        workspace_manager: &WorkspaceManager,
+        task_manager: &TaskManager,
+        dry_run: bool,
+    ) -> SessionRestoreReport {
+        let mut report = SessionRestoreReport {
+            dry_run,
+            ..Default::default()
+        };
+
+        let existing: HashSet<String> = workspace_manager
+            .list_workspaces()
+            .await
+            .into_iter()
+            .collect();
+        for workspace in self.workspaces {
+            if existing.contains(&workspace.name) {
+                report.workspaces_skipped.push(workspace.name);
+                continue;
+            }
+            if dry_run {
+                report.processes_started += workspace.processes.len();
+                report.workspaces_created.push(workspace.name);
+                continue;
+            }
+            restore_workspace(workspace_manager, workspace, &mut report).await;
+        }
+
+        if let Some(active) = self.active_workspace {
+            let restored = report.workspaces_created.contains(&active);
+            if restored && !dry_run {
+                if let Err(e) = workspace_manager.switch_workspace(&active).await {
+                    report
+                        .warnings
+                        .push(format!("Could not switch to workspace {active}: {e}"));
+                }
+            }
+        }
+
+        restore_tasks(task_manager, self.tasks, &mut report).await;
+        report
+    }
+}
+
+fn is_live(status: &ProcessStatus) -> bool {
+    !matches!(status, ProcessStatus::Stopped | ProcessStatus::Failed)
+}
+
+async fn restore_workspace(
+    workspace_manager: &WorkspaceManager,
+    workspace: WorkspaceSnapshot,
+    report: &mut SessionRestoreReport,
+) {
+    let name = workspace.name;
+    let project_dir = workspace
+        .project_dir
+        .as_deref()
+        .map(Path::new)
+        .filter(|dir| {
+            let exists = dir.is_dir();
+            if !exists {
+                report.warnings.push(format!(
+                    "Project directory {} of workspace {name} does not exist; using the current directory",
+                    dir.display()
+                ));
+            }
+            exists
+        });
+    if let Err(e) = workspace_manager
+        .create_workspace_in(&name, &workspace.template, project_dir)
+        .await
+    {
+        report
+            .warnings
+            .push(format!("Could not create workspace {name}: {e}"));
+        return;
+    }
+    if !workspace.notes.is_empty() {
+        let notes = workspace.notes;
+        let _ = workspace_manager
+            .update_workspace_state(&name, |state| state.notes = notes)
+            .await;
+    }
+
+    let running: HashSet<String> = workspace_manager
+        .get_workspace_info(&name)
+        .await
+        .map(|state| {
+            state
+                .processes
+                .values()
+                .map(|process| process.command.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    let process_manager = workspace_manager.process_manager();
+    for process in workspace.processes {
+        if running.contains(&process.command) {
+            continue;
+        }
+        let Some(ref process_manager) = process_manager else {
+            report.warnings.push(format!(
+                "Process {} ({}) in workspace {name} was not started: no process manager",
+                process.id, process.command
+            ));
+            continue;
+        };
+        let Some(spec) = ProcessSpec::from_command_line(&process.command) else {
+            report.warnings.push(format!(
+                "Process {} in workspace {name} was not started: no command recorded",
+                process.id
+            ));
+            continue;
+        };
+        let process_id = if process_manager
+            .get_process_info(&process.id)
+            .await
+            .is_some()
+        {
+            format!("{}-{}", process.id, uuid::Uuid::new_v4().simple())
+        } else {
+            process.id.clone()
+        };
+        match process_manager
+            .spawn_command(process_id.clone(), name.clone(), spec)
+            .await
+        {
+            Ok(()) => {
+                report.processes_started += 1;
+                let now = SystemTime::now();
+                let info = ProcessInfo {
+                    id: process_id.clone(),
+                    command: process.command,
+                    workspace: name.clone(),
+                    pane_id: None,
+                    status: ProcessStatus::Starting,
+                    pid: None,
+                    started_at: now,
+                    last_heartbeat: now,
+                    restart_count: 0,
+                    context: None,
+                };
+                let _ = workspace_manager
+                    .update_workspace_state(&name, |state| {
+                        state.processes.insert(process_id, info);
+                    })
+                    .await;
+            }
+            Err(e) => report.warnings.push(format!(
+                "Process {} in workspace {name} failed to start: {e}",
+                process.id
+            )),
+        }
+    }
+    report.workspaces_created.push(name);
+}
+
+/// Queue the snapshot's tasks again, dependencies first; dependencies on
+/// tasks that are neither in the snapshot nor known here had finished and
+/// are dropped
+async fn restore_tasks(
+    task_manager: &TaskManager,
+    tasks: Vec<Task>,
+    report: &mut SessionRestoreReport,
+) {
+    let mut known: HashSet<TaskId> = task_manager
+        .list_tasks(None)
+        .await
+        .into_iter()
+        .map(|task| task.id)
+        .collect();
+    let (skipped, mut pending): (Vec<Task>, Vec<Task>) =
+        tasks.into_iter().partition(|task| known.contains(&task.id));
+    report.tasks_skipped = skipped.len();
+
+    let in_snapshot: HashSet<TaskId> = pending.iter().map(|task| task.id.clone()).collect();
+    for task in &mut pending {
+        task.dependencies
+            .retain(|dependency| in_snapshot.contains(dependency) || known.contains(dependency));
+    }
+
+    while !pending.is_empty() {
+        let (ready, waiting): (Vec<Task>, Vec<Task>) = pending
+            .into_iter()
+            .partition(|task| task.dependencies.iter().all(|d| known.contains(d)));
+        if ready.is_empty() {
+            for task in &waiting {
+                report.warnings.push(format!(
+                    "Task {} was not restored: its dependencies form a cycle",
+                    task.id
+                ));
+            }
+            break;
+        }
+        for mut task in ready {
+            known.insert(task.id.clone());
+            if report.dry_run {
+                report.tasks_restored += 1;
+                continue;
+            }
+            if task.status != crate::task::TaskStatus::Todo {
+                task.add_note(
+                    format!(
+                        "Restored from a session snapshot (was {:?}) and requeued",
+                        task.status
+                    ),
+                    Some("system".to_string()),
+                );
+            }
+            task.stages = Default::default();
+            let task_id = task.id.clone();
+            match task_manager.create_task(task).await {
+                Ok(_) => report.tasks_restored += 1,
+                Err(e) => report
+                    .warnings
+                    .push(format!("Task {task_id} was not restored: {e}")),
+            }
+        }
+        pending = waiting;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{TaskCategory, TaskConfig, TaskStatus};
+
+    fn managers(dir: &tempfile::TempDir, name: &str) -> (WorkspaceManager, TaskManager) {
+        let workspace_manager =
+            WorkspaceManager::new(Some(dir.path().join(format!("{name}.json")))).unwrap();
+        let task_manager = TaskManager::new(TaskConfig {
+            persistence_enabled: false,
+            ..Default::default()
+        });
+        (workspace_manager, task_manager)
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_and_restore_elsewhere() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (workspaces, tasks) = managers(&dir, "source");
+        workspaces
+            .create_workspace_in("api", "basic", Some(dir.path()))
+            .await
+            .unwrap();
+        workspaces
+            .add_workspace_note("api", "check the logs".to_string(), None)
+            .await
+            .unwrap();
+        workspaces.switch_workspace("api").await.unwrap();
+
+        let mut build = Task::new("Build".to_string(), TaskCategory::Development);
+        build.id = "build".to_string();
+        let mut deploy = Task::new("Deploy".to_string(), TaskCategory::Development);
+        deploy.id = "deploy".to_string();
+        deploy.dependencies = vec!["build".to_string()];
+        let mut done = Task::new("Done".to_string(), TaskCategory::Development);
+        done.id = "done".to_string();
+        tasks.create_task(deploy).await.unwrap_err();
+        tasks.create_task(build).await.unwrap();
+        tasks.create_task(done).await.unwrap();
+        let mut deploy = Task::new("Deploy".to_string(), TaskCategory::Development);
+        deploy.id = "deploy".to_string();
+        deploy.dependencies = vec!["build".to_string()];
+        tasks.create_task(deploy).await.unwrap();
+        let mut finished = tasks.get_task(&"done".to_string()).await.unwrap();
+        finished.update_status(TaskStatus::Completed);
+        tasks.update_task(finished).await.unwrap();
+
+        let snapshot = SessionSnapshot::capture(&workspaces, &tasks).await;
+        assert_eq!(snapshot.active_workspace.as_deref(), Some("api"));
+        assert_eq!(snapshot.tasks.len(), 2);
+        let archive = dir.path().join("session.json");
+        snapshot.write_to(&archive).unwrap();
+        let snapshot = SessionSnapshot::read_from(&archive).unwrap();
+
+        let (target_workspaces, target_tasks) = managers(&dir, "target");
+        let preview = snapshot
+            .clone()
+            .restore(&target_workspaces, &target_tasks, true)
+            .await;
+        assert_eq!(preview.workspaces_created, ["api"]);
+        assert_eq!(preview.tasks_restored, 2);
+        assert!(target_workspaces.get_workspace_info("api").await.is_none());
+
+        let report = snapshot
+            .clone()
+            .restore(&target_workspaces, &target_tasks, false)
+            .await;
+        assert_eq!(report.workspaces_created, ["api"]);
+        assert_eq!(report.workspaces_skipped, ["default"]);
+        assert_eq!(report.tasks_restored, 2, "{:?}", report.warnings);
+        let api = target_workspaces.get_workspace_info("api").await.unwrap();
+        assert!(api.is_active);
+        assert_eq!(api.notes.len(), 1);
+        let deploy = target_tasks.get_task(&"deploy".to_string()).await.unwrap();
+        assert_eq!(deploy.dependencies, ["build"]);
+
+        // Restoring again changes nothing
+        let again = snapshot
+            .restore(&target_workspaces, &target_tasks, false)
+            .await;
+        assert!(again.workspaces_created.is_empty());
+        assert_eq!(again.tasks_skipped, 2);
+    }
+
+    #[test]
+    fn test_newer_snapshot_is_refused() {
+        let content = r#"{"version": 99, "created_at": 0, "workspaces": []}"#;
+        assert!(SessionSnapshot::parse(content)
+            .unwrap_err()
+            .contains("newer"));
+        let content = r#"{"version": 1, "created_at": 0, "workspaces": []}"#;
+        assert!(SessionSnapshot::parse(content).unwrap().tasks.is_empty());
+    }
+}
@@ -1,8 +1,13 @@
 // WezTerm Multi-Process Development Framework - Workspace State Management
 
+use crate::git::GitInfo;
+use crate::process::context::{AgentContext, AgentContextConfig};
+use crate::process::watchdog::WatchdogConfig;
+use crate::room::overrides::WorkspaceOverrides;
+use crate::task::TaskNote;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::time::SystemTime;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AccessRecord {
@@ -42,6 +47,37 @@ pub struct WorkspaceState {
     pub access_history: Vec<AccessRecord>,
     pub session_count: u32,
     pub total_duration: u64, // in seconds
+
+    /// Scratchpad notes not tied to any task
+    #[serde(default)]
+    pub notes: Vec<TaskNote>,
+
+    /// Project directory the workspace was created in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_dir: Option<String>,
+
+    /// Set while the workspace drains: running tasks finish, new ones and
+    /// new processes are held back
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drain: Option<DrainState>,
+
+    /// Repository and branch of the workspace root, when it is in one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git: Option<GitInfo>,
+
+    /// Settings of the workspace root's `.wezterm-parallel.yaml` in effect
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overrides: Option<WorkspaceOverrides>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DrainState {
+    /// Time the drain started (seconds since the epoch)
+    pub since: u64,
+
+    /// Delete the workspace once no task runs in it any more
+    #[serde(default)]
+    pub delete_when_empty: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -99,6 +135,10 @@ pub struct ProcessInfo {
     pub started_at: SystemTime,
     pub last_heartbeat: SystemTime,
     pub restart_count: u32,
+
+    /// Context file the process received when it was started
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<AgentContext>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -111,6 +151,9 @@ pub enum ProcessStatus {
     Stopped,
     Failed,
     Restarting,
+
+    /// Alive but silent for too long while working (see the process watchdog)
+    Unresponsive,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -157,6 +200,14 @@ pub struct WorkspaceConfig {
     pub startup_commands: Vec<String>,
     pub keybindings: HashMap<String, String>,
     pub theme: Option<String>,
+
+    /// Inactivity watchdog from the template, if it overrides the global one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watchdog: Option<WatchdogConfig>,
+
+    /// Context file for agents from the template, resolved against the project directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<AgentContextConfig>,
 }
 
 impl Default for LayoutConfig {
@@ -185,6 +236,8 @@ impl Default for WorkspaceConfig {
             startup_commands: vec!["claude-code".to_string()],
             keybindings: HashMap::new(),
             theme: None,
+            watchdog: None,
+            context: None,
         }
     }
 }
@@ -210,6 +263,11 @@ impl WorkspaceState {
             }],
             session_count: 1,
             total_duration: 0,
+            notes: Vec::new(),
+            project_dir: None,
+            drain: None,
+            git: None,
+            overrides: None,
         }
     }
 
@@ -310,6 +368,21 @@ impl WorkspaceState {
             pane.is_active = pane.id == pane_id;
         }
     }
+
+    /// Append a note to the workspace scratchpad
+    pub fn add_note(&mut self, content: String, author: Option<String>) -> TaskNote {
+        let note = TaskNote {
+            id: uuid::Uuid::new_v4().to_string(),
+            content,
+            author,
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        };
+        self.notes.push(note.clone());
+        note
+    }
 }
 
 #[cfg(test)]
@@ -326,6 +399,20 @@ mod tests {
         assert!(workspace.processes.is_empty());
         assert!(workspace.active_tasks.is_empty());
         assert!(workspace.panes.is_empty());
+        assert!(workspace.notes.is_empty());
+    }
+
+    #[test]
+    fn test_scratchpad_notes() {
+        let mut workspace = WorkspaceState::new("test".to_string(), WorkspaceConfig::default());
+        let note = workspace.add_note("check flaky test".to_string(), None);
+        assert_eq!(workspace.notes, vec![note]);
+
+        // State saved before scratchpads existed still loads
+        let mut json = serde_json::to_value(&workspace).unwrap();
+        json.as_object_mut().unwrap().remove("notes");
+        let restored: WorkspaceState = serde_json::from_value(json).unwrap();
+        assert!(restored.notes.is_empty());
     }
 
     #[test]
@@ -357,6 +444,7 @@ mod tests {
             started_at: SystemTime::now(),
             last_heartbeat: SystemTime::now(),
             restart_count: 0,
+            context: None,
         };
 
         workspace.add_process(process.clone());
@@ -1,30 +1,161 @@
 // WezTerm Multi-Process Development Framework - Workspace Template System
 
+use crate::process::context::AgentContextConfig;
+use crate::process::watchdog::WatchdogConfig;
+use crate::room::overrides::WorkspaceOverrides;
 use crate::room::state::{LayoutConfig, LayoutType, SplitDirection, WorkspaceConfig};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Template directory a project can ship, relative to its root
+pub const LOCAL_TEMPLATES_DIR: &str = ".wezterm-parallel/templates";
+
+/// Workspace layout and panes
+///
+/// Templates can be written as YAML files; everything but the panes is
+/// optional and the name defaults to the file name.
+///
+/// ```yaml
+/// description: Agents for this repo
+/// layout: { layout_type: TwoPaneVertical, primary_direction: Vertical, pane_sizes: [60, 40], auto_balance: false }
+/// default_commands:
+///   - name: agent
+///     command: claude-code --workspace=api
+///   - name: tests
+///     command: cargo watch -x test
+///     working_directory: ./api
+/// ```
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct WorkspaceTemplate {
+    #[serde(default)]
     pub name: String,
+    #[serde(default)]
     pub description: String,
+    #[serde(default)]
     pub layout: LayoutConfig,
     pub default_commands: Vec<CommandTemplate>,
+    #[serde(default)]
     pub environment_vars: HashMap<String, String>,
+    #[serde(default)]
     pub required_tools: Vec<String>,
+    #[serde(default)]
     pub startup_script: Option<String>,
+    #[serde(default)]
     pub keybindings: HashMap<String, String>,
+    #[serde(default)]
     pub theme: Option<String>,
+
+    /// Inactivity watchdog for the workspace's processes, overriding the global one
+    #[serde(default)]
+    pub watchdog: Option<WatchdogConfig>,
+
+    /// Context file handed to the workspace's agents, relative to the project directory
+    #[serde(default)]
+    pub context: Option<AgentContextConfig>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CommandTemplate {
     pub name: String,
+
+    /// Command of a `process` pane; other pane kinds build their own
+    #[serde(default)]
     pub command: String,
+    #[serde(default)]
     pub working_directory: Option<String>,
+    #[serde(default)]
     pub pane_position: Option<PaneTemplatePosition>,
+    #[serde(default = "default_auto_start")]
     pub auto_start: bool,
+    #[serde(default)]
     pub restart_on_exit: bool,
+
+    #[serde(default)]
+    pub kind: PaneKind,
+}
+
+impl CommandTemplate {
+    /// Shell command that runs the pane
+    pub fn resolved_command(&self) -> String {
+        match &self.kind {
+            PaneKind::Process => self.command.clone(),
+            PaneKind::Notes { file } => {
+                format!("${{EDITOR:-{DEFAULT_EDITOR}}} {}", shell_quote(file))
+            }
+            PaneKind::Timer { minutes, label } => {
+                let label = shell_quote(label.as_deref().unwrap_or("Timer"));
+                format!(
+                    "s={}; while [ $s -gt 0 ]; do printf '\\r%s %02d:%02d ' {label} $((s/60)) $((s%60)); sleep 1; s=$((s-1)); done; printf '\\a\\n%s done\\n' {label}",
+                    u64::from(*minutes) * 60
+                )
+            }
+            PaneKind::Preview { file, command } => format!(
+                "{} {}",
+                command.as_deref().unwrap_or(DEFAULT_PREVIEW_COMMAND),
+                shell_quote(file)
+            ),
+        }
+    }
+
+    /// Whether the pane runs a managed process (agent, server, ...)
+    pub fn is_process(&self) -> bool {
+        self.kind == PaneKind::Process
+    }
+}
+
+fn default_auto_start() -> bool {
+    true
+}
+
+/// Editor for notes panes when `$EDITOR` is not set
+const DEFAULT_EDITOR: &str = "vi";
+
+/// Markdown previewer used when a preview pane does not name one
+const DEFAULT_PREVIEW_COMMAND: &str = "glow -p";
+
+/// What a template pane shows
+///
+/// ```yaml
+/// - name: draft
+///   kind: { type: notes, file: draft.md }
+///   auto_start: true
+///   restart_on_exit: false
+/// - name: pomodoro
+///   kind: { type: timer, minutes: 25, label: Focus }
+///   ...
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PaneKind {
+    /// Runs `command`
+    #[default]
+    Process,
+
+    /// Notes file opened in `$EDITOR`
+    Notes { file: String },
+
+    /// Countdown timer that rings the bell when done
+    Timer {
+        minutes: u32,
+        #[serde(default)]
+        label: Option<String>,
+    },
+
+    /// Markdown preview of a file
+    Preview {
+        file: String,
+
+        /// Previewer command, `glow -p` by default
+        #[serde(default)]
+        command: Option<String>,
+    },
+}
+
+/// Quote a value for use as a single shell word
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -62,39 +193,93 @@ impl TemplateEngine {
         self.templates.values().collect()
     }
 
+    /// Register every template in a directory, replacing templates of the same name
+    ///
+    /// Returns the number of templates loaded.
+    pub fn load_dir(&mut self, dir: &Path) -> usize {
+        let templates = load_templates(dir);
+        let count = templates.len();
+        for template in templates {
+            self.register_template(template);
+        }
+        count
+    }
+
     pub fn apply_template(
         &self,
         template_name: &str,
         workspace_name: &str,
     ) -> Result<WorkspaceConfig, String> {
-        let template = self
-            .get_template(template_name)
-            .ok_or_else(|| format!("Template '{template_name}' not found"))?;
+        self.apply_template_in(template_name, workspace_name, None)
+    }
+
+    /// Template used for a workspace rooted at `project_dir`: the project's
+    /// own template of that name, otherwise the registered one
+    ///
+    /// The `templates_dir` of the project's override file is searched before
+    /// [`LOCAL_TEMPLATES_DIR`].
+    pub fn resolve_template(
+        &self,
+        template_name: &str,
+        project_dir: Option<&Path>,
+    ) -> Result<WorkspaceTemplate, String> {
+        project_dir
+            .map(project_templates)
+            .unwrap_or_default()
+            .into_iter()
+            .find(|template| template.name == template_name)
+            .or_else(|| self.get_template(template_name).cloned())
+            .ok_or_else(|| format!("Template '{template_name}' not found"))
+    }
+
+    /// Apply a template for a workspace rooted at `project_dir`
+    ///
+    /// Templates in the project's [`LOCAL_TEMPLATES_DIR`] take precedence
+    /// over registered ones, and the project becomes the working directory.
+    pub fn apply_template_in(
+        &self,
+        template_name: &str,
+        workspace_name: &str,
+        project_dir: Option<&Path>,
+    ) -> Result<WorkspaceConfig, String> {
+        let template = &self.resolve_template(template_name, project_dir)?;
 
+        let base_dir = match project_dir {
+            Some(dir) => dir.to_path_buf(),
+            None => std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/")),
+        };
         let mut config = WorkspaceConfig {
             name: workspace_name.to_string(),
             template: template_name.to_string(),
             auto_start_processes: true,
             max_processes: 8,
-            working_directory: std::env::current_dir()
-                .unwrap_or_else(|_| std::path::PathBuf::from("/"))
-                .to_string_lossy()
-                .to_string(),
+            working_directory: base_dir.to_string_lossy().to_string(),
             environment_vars: template.environment_vars.clone(),
             startup_commands: template
                 .default_commands
                 .iter()
                 .filter(|cmd| cmd.auto_start)
-                .map(|cmd| cmd.command.clone())
+                .map(CommandTemplate::resolved_command)
                 .collect(),
             keybindings: template.keybindings.clone(),
             theme: template.theme.clone(),
+            watchdog: template.watchdog.clone(),
+            context: template
+                .context
+                .as_ref()
+                .map(|context| context.resolve_in(&base_dir)),
         };
 
         // Apply template-specific workspace directory if needed
         if let Some(first_cmd) = template.default_commands.first() {
             if let Some(ref wd) = first_cmd.working_directory {
-                config.working_directory = wd.clone();
+                config.working_directory = match project_dir {
+                    Some(dir) => dir
+                        .join(wd.trim_start_matches("./"))
+                        .to_string_lossy()
+                        .to_string(),
+                    None => wd.clone(),
+                };
             }
         }
 
@@ -119,12 +304,15 @@ impl TemplateEngine {
                 pane_position: None,
                 auto_start: true,
                 restart_on_exit: true,
+                kind: PaneKind::Process,
             }],
             environment_vars: HashMap::new(),
             required_tools: vec!["claude-code".to_string()],
             startup_script: None,
             keybindings: HashMap::new(),
             theme: None,
+            watchdog: None,
+            context: None,
         };
 
         // Web development template
@@ -149,6 +337,7 @@ impl TemplateEngine {
                     }),
                     auto_start: true,
                     restart_on_exit: true,
+                    kind: PaneKind::Process,
                 },
                 CommandTemplate {
                     name: "backend-claude".to_string(),
@@ -161,6 +350,7 @@ impl TemplateEngine {
                     }),
                     auto_start: true,
                     restart_on_exit: true,
+                    kind: PaneKind::Process,
                 },
                 CommandTemplate {
                     name: "dev-server".to_string(),
@@ -173,6 +363,7 @@ impl TemplateEngine {
                     }),
                     auto_start: false,
                     restart_on_exit: false,
+                    kind: PaneKind::Process,
                 },
                 CommandTemplate {
                     name: "logs".to_string(),
@@ -185,6 +376,7 @@ impl TemplateEngine {
                     }),
                     auto_start: false,
                     restart_on_exit: false,
+                    kind: PaneKind::Process,
                 },
             ],
             environment_vars: {
@@ -207,6 +399,8 @@ impl TemplateEngine {
                 keys
             },
             theme: Some("dark".to_string()),
+            watchdog: None,
+            context: None,
         };
 
         // Parallel development template
@@ -233,6 +427,7 @@ impl TemplateEngine {
                     }),
                     auto_start: true,
                     restart_on_exit: true,
+                    kind: PaneKind::Process,
                 },
                 CommandTemplate {
                     name: "claude-test".to_string(),
@@ -245,6 +440,7 @@ impl TemplateEngine {
                     }),
                     auto_start: true,
                     restart_on_exit: true,
+                    kind: PaneKind::Process,
                 },
                 CommandTemplate {
                     name: "claude-docs".to_string(),
@@ -257,6 +453,7 @@ impl TemplateEngine {
                     }),
                     auto_start: true,
                     restart_on_exit: true,
+                    kind: PaneKind::Process,
                 },
             ],
             environment_vars: {
@@ -276,6 +473,8 @@ impl TemplateEngine {
                 keys
             },
             theme: Some("dark".to_string()),
+            watchdog: None,
+            context: None,
         };
 
         // Research template
@@ -300,10 +499,11 @@ impl TemplateEngine {
                     }),
                     auto_start: true,
                     restart_on_exit: true,
+                    kind: PaneKind::Process,
                 },
                 CommandTemplate {
                     name: "notes".to_string(),
-                    command: "vim notes.md".to_string(),
+                    command: String::new(),
                     working_directory: None,
                     pane_position: Some(PaneTemplatePosition {
                         row: 1,
@@ -312,6 +512,9 @@ impl TemplateEngine {
                     }),
                     auto_start: false,
                     restart_on_exit: false,
+                    kind: PaneKind::Notes {
+                        file: "notes.md".to_string(),
+                    },
                 },
             ],
             environment_vars: {
@@ -319,7 +522,7 @@ impl TemplateEngine {
                 env.insert("CLAUDE_MODE".to_string(), "research".to_string());
                 env
             },
-            required_tools: vec!["claude-code".to_string(), "vim".to_string()],
+            required_tools: vec!["claude-code".to_string()],
             startup_script: None,
             keybindings: {
                 let mut keys = HashMap::new();
@@ -328,6 +531,82 @@ impl TemplateEngine {
                 keys
             },
             theme: Some("light".to_string()),
+            watchdog: None,
+            context: None,
+        };
+
+        // Writing template: no agents, just a draft, its preview and a focus timer
+        let writing_template = WorkspaceTemplate {
+            name: "writing".to_string(),
+            description: "Documentation sprint with notes, live preview and a focus timer"
+                .to_string(),
+            layout: LayoutConfig {
+                layout_type: LayoutType::ThreePaneHorizontal,
+                primary_direction: SplitDirection::Horizontal,
+                pane_sizes: vec![50.0, 35.0, 15.0],
+                auto_balance: false,
+            },
+            default_commands: vec![
+                CommandTemplate {
+                    name: "draft".to_string(),
+                    command: String::new(),
+                    working_directory: None,
+                    pane_position: Some(PaneTemplatePosition {
+                        row: 0,
+                        col: 0,
+                        size_percentage: 50.0,
+                    }),
+                    auto_start: true,
+                    restart_on_exit: false,
+                    kind: PaneKind::Notes {
+                        file: "draft.md".to_string(),
+                    },
+                },
+                CommandTemplate {
+                    name: "preview".to_string(),
+                    command: String::new(),
+                    working_directory: None,
+                    pane_position: Some(PaneTemplatePosition {
+                        row: 0,
+                        col: 1,
+                        size_percentage: 35.0,
+                    }),
+                    auto_start: true,
+                    restart_on_exit: true,
+                    kind: PaneKind::Preview {
+                        file: "draft.md".to_string(),
+                        command: None,
+                    },
+                },
+                CommandTemplate {
+                    name: "focus-timer".to_string(),
+                    command: String::new(),
+                    working_directory: None,
+                    pane_position: Some(PaneTemplatePosition {
+                        row: 0,
+                        col: 2,
+                        size_percentage: 15.0,
+                    }),
+                    auto_start: true,
+                    restart_on_exit: false,
+                    kind: PaneKind::Timer {
+                        minutes: 25,
+                        label: Some("Focus".to_string()),
+                    },
+                },
+            ],
+            environment_vars: HashMap::new(),
+            required_tools: vec!["glow".to_string()],
+            startup_script: None,
+            keybindings: {
+                let mut keys = HashMap::new();
+                keys.insert("ctrl+shift+t".to_string(), "restart_timer".to_string());
+                keys.insert("ctrl+shift+p".to_string(), "focus_preview".to_string());
+                keys
+            },
+            theme: Some("light".to_string()),
+            watchdog: None,
+            context: None,
         };
 
         // Register all templates
@@ -335,6 +614,7 @@ impl TemplateEngine {
         self.register_template(web_dev_template);
         self.register_template(parallel_dev_template);
         self.register_template(research_template);
+        self.register_template(writing_template);
     }
 }
 
@@ -344,6 +624,62 @@ impl Default for TemplateEngine {
     }
 }
 
+/// Read the `*.yaml` / `*.yml` templates of a directory, in file name order
+///
+/// A missing directory yields no templates; files that fail to parse are
+/// skipped with a warning so one broken template does not hide the others.
+pub fn load_templates(dir: &Path) -> Vec<WorkspaceTemplate> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext == "yaml" || ext == "yml")
+        })
+        .collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .filter_map(|path| match read_template(&path) {
+            Ok(template) => Some(template),
+            Err(e) => {
+                warn!("Skipping template {}: {}", path.display(), e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Templates a project ships, the override file's `templates_dir` first;
+/// a broken override file is reported when the workspace is created
+fn project_templates(project_dir: &Path) -> Vec<WorkspaceTemplate> {
+    let overrides_dir = WorkspaceOverrides::load(project_dir)
+        .ok()
+        .flatten()
+        .and_then(|overrides| overrides.templates_dir);
+    overrides_dir
+        .into_iter()
+        .chain(std::iter::once(project_dir.join(LOCAL_TEMPLATES_DIR)))
+        .flat_map(|dir| load_templates(&dir))
+        .collect()
+}
+
+fn read_template(path: &Path) -> Result<WorkspaceTemplate, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let mut template: WorkspaceTemplate =
+        serde_yaml::from_str(&content).map_err(|e| e.to_string())?;
+    if template.name.is_empty() {
+        template.name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+    }
+    Ok(template)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -361,6 +697,7 @@ mod tests {
         assert!(template_names.contains(&"web_dev"));
         assert!(template_names.contains(&"parallel_dev"));
         assert!(template_names.contains(&"research"));
+        assert!(template_names.contains(&"writing"));
     }
 
     #[test]
@@ -404,6 +741,98 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_writing_template_panes() {
+        let engine = TemplateEngine::new();
+
+        let template = engine.get_template("writing").unwrap();
+        assert!(template
+            .default_commands
+            .iter()
+            .all(|cmd| !cmd.is_process()));
+
+        let config = engine.apply_template("writing", "docs-sprint").unwrap();
+        assert_eq!(config.startup_commands.len(), 3);
+        assert_eq!(config.startup_commands[0], "${EDITOR:-vi} 'draft.md'");
+        assert_eq!(config.startup_commands[1], "glow -p 'draft.md'");
+        assert!(config.startup_commands[2].starts_with("s=1500;"));
+        assert!(config.startup_commands[2].contains("'Focus'"));
+    }
+
+    #[test]
+    fn test_load_templates_from_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("review.yaml"),
+            "description: Review pair\ndefault_commands:\n  - name: reviewer\n    command: claude-code --mode=review\n    working_directory: ./src\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("basic.yml"),
+            "name: basic\ndefault_commands:\n  - name: shell\n    command: zsh\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("broken.yaml"), "default_commands: 3\n").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let templates = load_templates(dir.path());
+        let names: Vec<&str> = templates.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["basic", "review"]);
+        assert!(templates[1].default_commands[0].auto_start);
+        assert!(load_templates(&dir.path().join("missing")).is_empty());
+
+        // User templates override built-ins of the same name
+        let mut engine = TemplateEngine::new();
+        assert_eq!(engine.load_dir(dir.path()), 2);
+        let config = engine.apply_template("basic", "w").unwrap();
+        assert_eq!(config.startup_commands, ["zsh"]);
+    }
+
+    #[test]
+    fn test_project_templates_take_precedence() {
+        let project = tempfile::tempdir().unwrap();
+        let local = project.path().join(LOCAL_TEMPLATES_DIR);
+        std::fs::create_dir_all(&local).unwrap();
+        std::fs::write(
+            local.join("web_dev.yaml"),
+            "default_commands:\n  - name: api\n    command: cargo run\n    working_directory: ./api\n",
+        )
+        .unwrap();
+
+        let engine = TemplateEngine::new();
+        let config = engine
+            .apply_template_in("web_dev", "shop", Some(project.path()))
+            .unwrap();
+        assert_eq!(config.startup_commands, ["cargo run"]);
+        assert_eq!(
+            config.working_directory,
+            project.path().join("api").to_string_lossy()
+        );
+
+        // Built-in templates still apply, rooted at the project
+        let config = engine
+            .apply_template_in("basic", "shop", Some(project.path()))
+            .unwrap();
+        assert_eq!(config.working_directory, project.path().to_string_lossy());
+    }
+
+    #[test]
+    fn test_pane_kind_from_json() {
+        let pane: CommandTemplate = serde_json::from_str(
+            r#"{"name":"notes","working_directory":null,"pane_position":null,"auto_start":true,"restart_on_exit":false,"kind":{"type":"notes","file":"it's.md"}}"#,
+        )
+        .unwrap();
+        assert_eq!(pane.resolved_command(), r#"${EDITOR:-vi} 'it'\''s.md'"#);
+
+        // Templates written before pane kinds run their command as a process
+        let pane: CommandTemplate = serde_json::from_str(
+            r#"{"name":"shell","command":"bash","working_directory":null,"pane_position":null,"auto_start":true,"restart_on_exit":false}"#,
+        )
+        .unwrap();
+        assert!(pane.is_process());
+        assert_eq!(pane.resolved_command(), "bash");
+    }
+
     #[test]
     fn test_custom_template_registration() {
         let mut engine = TemplateEngine::new();
@@ -418,6 +847,8 @@ mod tests {
             startup_script: None,
             keybindings: HashMap::new(),
             theme: None,
+            watchdog: None,
+            context: None,
         };
 
         engine.register_template(custom_template);
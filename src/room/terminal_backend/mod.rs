@@ -0,0 +1,413 @@
+// WezTerm Multi-Process Development Framework - Terminal Backends
+// Lays out workspace panes through `wezterm cli`, tmux or Zellij
+
+pub mod zellij;
+
+pub use zellij::ZellijBackend;
+
+use crate::room::layout::{AppliedPane, LayoutPlan};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Arc;
+use tracing::warn;
+
+/// Runs the subcommands of a terminal's command-line interface
+pub trait TerminalCli: Send + Sync + std::fmt::Debug {
+    /// Run a subcommand, returning its standard output
+    fn run(&self, args: &[String]) -> Result<String, String>;
+}
+
+/// A terminal binary on the PATH (or another one), with the arguments that
+/// precede every subcommand
+#[derive(Debug, Clone)]
+pub struct SystemCli {
+    binary: PathBuf,
+    leading_args: Vec<String>,
+}
+
+impl SystemCli {
+    pub fn new(binary: impl Into<PathBuf>, leading_args: &[&str]) -> Self {
+        Self {
+            binary: binary.into(),
+            leading_args: leading_args.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+
+    /// `wezterm cli`
+    pub fn wezterm() -> Self {
+        Self::new("wezterm", &["cli"])
+    }
+
+    /// `tmux`
+    pub fn tmux() -> Self {
+        Self::new("tmux", &[])
+    }
+
+    /// `zellij`
+    pub fn zellij() -> Self {
+        Self::new("zellij", &[])
+    }
+}
+
+impl TerminalCli for SystemCli {
+    fn run(&self, args: &[String]) -> Result<String, String> {
+        let output = Command::new(&self.binary)
+            .args(&self.leading_args)
+            .args(args)
+            .output()
+            .map_err(|e| format!("Failed to run {}: {e}", self.binary.display()))?;
+        if !output.status.success() {
+            return Err(format!(
+                "{} {} failed: {}",
+                self.binary.display(),
+                self.leading_args
+                    .iter()
+                    .chain(args.first())
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+}
+
+/// Side of an existing pane a new pane is split off to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SplitSide {
+    Right,
+    Bottom,
+}
+
+/// Creates and closes the panes of workspace layouts in a terminal
+pub trait TerminalBackend: Send + Sync + std::fmt::Debug {
+    /// Create the panes of `plan` in a new window of its workspace and start
+    /// their commands
+    ///
+    /// When a step fails the panes created so far are closed again.
+    fn apply(&self, plan: &LayoutPlan) -> Result<Vec<AppliedPane>, String>;
+
+    /// Close panes, returning the IDs of the panes that were closed
+    fn close(&self, pane_ids: &[String]) -> Vec<String>;
+}
+
+/// Pane operations of a terminal that splits panes one at a time; pane IDs
+/// are the terminal's own
+pub trait PaneBackend: Send + Sync + std::fmt::Debug {
+    /// Open a new window for a workspace, returning its pane's ID
+    fn open_window(&self, workspace: &str, cwd: &str) -> Result<String, String>;
+
+    /// Split a pane, returning the new pane's ID
+    fn split_pane(
+        &self,
+        pane_id: &str,
+        side: SplitSide,
+        percent: u8,
+        cwd: &str,
+    ) -> Result<String, String>;
+
+    /// Type a command into a pane's shell and run it
+    fn run_command(&self, pane_id: &str, command: &str) -> Result<(), String>;
+
+    fn close_pane(&self, pane_id: &str) -> Result<(), String>;
+}
+
+impl<T: PaneBackend> TerminalBackend for T {
+    fn apply(&self, plan: &LayoutPlan) -> Result<Vec<AppliedPane>, String> {
+        let mut applied: Vec<AppliedPane> = Vec::with_capacity(plan.panes.len());
+        for pane in &plan.panes {
+            let created = match &pane.split {
+                None => self.open_window(&plan.workspace, &pane.working_directory),
+                Some(split) => match applied.get(split.from) {
+                    Some(from) => self.split_pane(
+                        &from.pane_id,
+                        split.side,
+                        split.percent,
+                        &pane.working_directory,
+                    ),
+                    None => Err(format!("split of unknown pane #{}", split.from)),
+                },
+            };
+            match created {
+                Ok(pane_id) => applied.push(AppliedPane {
+                    pane_id,
+                    plan: pane.clone(),
+                }),
+                Err(e) => {
+                    let created: Vec<String> =
+                        applied.iter().map(|pane| pane.pane_id.clone()).collect();
+                    self.close(&created);
+                    return Err(format!("Pane '{}': {e}", pane.name));
+                }
+            }
+        }
+
+        for pane in &applied {
+            if let Some(ref command) = pane.plan.command {
+                self.run_command(&pane.pane_id, command)?;
+            }
+        }
+        Ok(applied)
+    }
+
+    fn close(&self, pane_ids: &[String]) -> Vec<String> {
+        pane_ids
+            .iter()
+            .filter(|pane_id| {
+                let result = self.close_pane(pane_id);
+                if let Err(ref e) = result {
+                    warn!("Failed to close pane {}: {}", pane_id, e);
+                }
+                result.is_ok()
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// Terminal the workspace panes are created in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TerminalBackendKind {
+    #[default]
+    Wezterm,
+    Tmux,
+    Zellij,
+}
+
+impl TerminalBackendKind {
+    /// Backend using the terminal's binary on the PATH
+    pub fn system(self) -> Arc<dyn TerminalBackend> {
+        match self {
+            Self::Wezterm => Arc::new(WeztermBackend::new(Arc::new(SystemCli::wezterm()))),
+            Self::Tmux => Arc::new(TmuxBackend::new(Arc::new(SystemCli::tmux()))),
+            Self::Zellij => Arc::new(ZellijBackend::new(
+                Arc::new(SystemCli::zellij()),
+                std::env::temp_dir().join("wezterm-parallel-zellij"),
+            )),
+        }
+    }
+}
+
+/// WezTerm through `wezterm cli`; workspaces are WezTerm workspaces
+#[derive(Debug, Clone)]
+pub struct WeztermBackend {
+    cli: Arc<dyn TerminalCli>,
+}
+
+impl WeztermBackend {
+    pub fn new(cli: Arc<dyn TerminalCli>) -> Self {
+        Self { cli }
+    }
+
+    fn created_pane(&self, args: &[String]) -> Result<String, String> {
+        let pane_id = self.cli.run(args)?;
+        if pane_id.is_empty() || !pane_id.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("unexpected pane ID '{pane_id}'"));
+        }
+        Ok(pane_id)
+    }
+}
+
+impl PaneBackend for WeztermBackend {
+    fn open_window(&self, workspace: &str, cwd: &str) -> Result<String, String> {
+        self.created_pane(&args(&[
+            "spawn",
+            "--new-window",
+            "--workspace",
+            workspace,
+            "--cwd",
+            cwd,
+        ]))
+    }
+
+    fn split_pane(
+        &self,
+        pane_id: &str,
+        side: SplitSide,
+        percent: u8,
+        cwd: &str,
+    ) -> Result<String, String> {
+        self.created_pane(&args(&[
+            "split-pane",
+            "--pane-id",
+            pane_id,
+            match side {
+                SplitSide::Right => "--right",
+                SplitSide::Bottom => "--bottom",
+            },
+            "--percent",
+            &percent.to_string(),
+            "--cwd",
+            cwd,
+        ]))
+    }
+
+    fn run_command(&self, pane_id: &str, command: &str) -> Result<(), String> {
+        self.cli
+            .run(&args(&[
+                "send-text",
+                "--pane-id",
+                pane_id,
+                "--no-paste",
+                &format!("{command}\n"),
+            ]))
+            .map(drop)
+    }
+
+    fn close_pane(&self, pane_id: &str) -> Result<(), String> {
+        self.cli
+            .run(&args(&["kill-pane", "--pane-id", pane_id]))
+            .map(drop)
+    }
+}
+
+/// tmux; each workspace is a detached session of the same name, and further
+/// windows of a workspace are added to its session
+#[derive(Debug, Clone)]
+pub struct TmuxBackend {
+    cli: Arc<dyn TerminalCli>,
+}
+
+impl TmuxBackend {
+    pub fn new(cli: Arc<dyn TerminalCli>) -> Self {
+        Self { cli }
+    }
+
+    fn created_pane(&self, args: &[String]) -> Result<String, String> {
+        let pane_id = self.cli.run(args)?;
+        let valid = pane_id
+            .strip_prefix('%')
+            .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+        if !valid {
+            return Err(format!("unexpected pane ID '{pane_id}'"));
+        }
+        Ok(pane_id)
+    }
+}
+
+/// tmux session name of a workspace; `.` and `:` separate window and pane
+/// in tmux targets
+fn session_name(workspace: &str) -> String {
+    workspace.replace(['.', ':'], "_")
+}
+
+impl PaneBackend for TmuxBackend {
+    fn open_window(&self, workspace: &str, cwd: &str) -> Result<String, String> {
+        let session = session_name(workspace);
+        // `=` matches the session name exactly rather than as a prefix
+        let exists = self
+            .cli
+            .run(&args(&["has-session", "-t", &format!("={session}")]))
+            .is_ok();
+        let command = if exists {
+            args(&["new-window", "-t", &format!("={session}:")])
+        } else {
+            args(&["new-session", "-d", "-s", &session])
+        };
+        self.created_pane(&[command, args(&["-c", cwd, "-P", "-F", "#{pane_id}"])].concat())
+    }
+
+    fn split_pane(
+        &self,
+        pane_id: &str,
+        side: SplitSide,
+        percent: u8,
+        cwd: &str,
+    ) -> Result<String, String> {
+        self.created_pane(&args(&[
+            "split-window",
+            "-t",
+            pane_id,
+            match side {
+                SplitSide::Right => "-h",
+                SplitSide::Bottom => "-v",
+            },
+            "-l",
+            &format!("{percent}%"),
+            "-c",
+            cwd,
+            "-P",
+            "-F",
+            "#{pane_id}",
+        ]))
+    }
+
+    fn run_command(&self, pane_id: &str, command: &str) -> Result<(), String> {
+        self.cli
+            .run(&args(&["send-keys", "-t", pane_id, "-l", command]))?;
+        self.cli
+            .run(&args(&["send-keys", "-t", pane_id, "Enter"]))
+            .map(drop)
+    }
+
+    fn close_pane(&self, pane_id: &str) -> Result<(), String> {
+        self.cli.run(&args(&["kill-pane", "-t", pane_id])).map(drop)
+    }
+}
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::room::layout::tests::FakeCli;
+
+    #[test]
+    fn test_tmux_opens_a_session_per_workspace() {
+        let cli = Arc::new(FakeCli {
+            fail_on: Some("has-session"),
+            ..Default::default()
+        });
+        let backend = TmuxBackend::new(cli.clone());
+
+        let pane = backend.open_window("api.v2", "/src").unwrap();
+        assert_eq!(pane, "%10");
+        let split = backend
+            .split_pane(&pane, SplitSide::Bottom, 40, "/src/tests")
+            .unwrap();
+        backend.run_command(&split, "cargo test").unwrap();
+
+        let calls = cli.calls.lock().unwrap();
+        assert_eq!(
+            calls[1],
+            args(&[
+                "new-session",
+                "-d",
+                "-s",
+                "api_v2",
+                "-c",
+                "/src",
+                "-P",
+                "-F",
+                "#{pane_id}"
+            ])
+        );
+        assert_eq!(
+            calls[2][..5],
+            args(&["split-window", "-t", "%10", "-v", "-l"])
+        );
+        assert_eq!(calls[2][5], "40%");
+        assert_eq!(
+            calls[3],
+            args(&["send-keys", "-t", "%11", "-l", "cargo test"])
+        );
+        assert_eq!(calls[4], args(&["send-keys", "-t", "%11", "Enter"]));
+    }
+
+    #[test]
+    fn test_tmux_adds_windows_to_an_existing_session() {
+        let cli = Arc::new(FakeCli::default());
+        let backend = TmuxBackend::new(cli.clone());
+
+        backend.open_window("api", "/src").unwrap();
+        assert_eq!(
+            cli.calls.lock().unwrap()[1][..3],
+            args(&["new-window", "-t", "=api:"])
+        );
+    }
+}
@@ -0,0 +1,324 @@
+// WezTerm Multi-Process Development Framework - Zellij Backend
+// Renders layout plans as Zellij KDL layouts and opens them in a session
+//
+// Zellij cannot split or address single panes from the command line, so a
+// whole plan is written to `<layouts_dir>/<session>.kdl` and opened as a new
+// tab of the workspace's session, which is created in the background when
+// missing. Pane IDs are `<session>/<index>` in plan order, and closing the
+// panes of a session kills the session.
+
+use super::{SplitSide, TerminalBackend, TerminalCli};
+use crate::room::layout::{AppliedPane, LayoutPlan, PanePlan};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::warn;
+
+/// Zellij through its command line, one session per workspace
+#[derive(Debug, Clone)]
+pub struct ZellijBackend {
+    cli: Arc<dyn TerminalCli>,
+
+    /// Directory the generated layout files are written to
+    layouts_dir: PathBuf,
+}
+
+impl ZellijBackend {
+    pub fn new(cli: Arc<dyn TerminalCli>, layouts_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            cli,
+            layouts_dir: layouts_dir.into(),
+        }
+    }
+
+    fn session_exists(&self, session: &str) -> bool {
+        self.cli
+            .run(&args(&["list-sessions", "--short", "--no-formatting"]))
+            .is_ok_and(|sessions| sessions.lines().any(|line| line.trim() == session))
+    }
+}
+
+/// Zellij session name of a workspace, limited to characters that are safe
+/// in session names and file names
+fn session_name(workspace: &str) -> String {
+    workspace
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+impl TerminalBackend for ZellijBackend {
+    fn apply(&self, plan: &LayoutPlan) -> Result<Vec<AppliedPane>, String> {
+        let session = session_name(&plan.workspace);
+        let layout = render_layout(plan)?;
+        std::fs::create_dir_all(&self.layouts_dir)
+            .map_err(|e| format!("Failed to create {}: {e}", self.layouts_dir.display()))?;
+        let path = self.layouts_dir.join(format!("{session}.kdl"));
+        std::fs::write(&path, layout)
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+
+        let created = !self.session_exists(&session);
+        if created {
+            self.cli
+                .run(&args(&["attach", "--create-background", &session]))?;
+        }
+        let opened = self.cli.run(&args(&[
+            "--session",
+            &session,
+            "action",
+            "new-tab",
+            "--layout",
+            &path.to_string_lossy(),
+            "--name",
+            &plan.workspace,
+        ]));
+        if let Err(e) = opened {
+            // Leave sessions that were there before alone
+            if created {
+                self.close(&[format!("{session}/0")]);
+            }
+            return Err(e);
+        }
+
+        Ok(plan
+            .panes
+            .iter()
+            .enumerate()
+            .map(|(index, pane)| AppliedPane {
+                pane_id: format!("{session}/{index}"),
+                plan: pane.clone(),
+            })
+            .collect())
+    }
+
+    fn close(&self, pane_ids: &[String]) -> Vec<String> {
+        let mut sessions: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for pane_id in pane_ids {
+            match pane_id.rsplit_once('/') {
+                Some((session, _)) => sessions.entry(session).or_default().push(pane_id.clone()),
+                None => warn!("Failed to close pane {}: not a Zellij pane ID", pane_id),
+            }
+        }
+        sessions
+            .into_iter()
+            .filter_map(
+                |(session, panes)| match self.cli.run(&args(&["kill-session", session])) {
+                    Ok(_) => Some(panes),
+                    Err(e) => {
+                        warn!("Failed to close Zellij session {}: {}", session, e);
+                        None
+                    }
+                },
+            )
+            .flatten()
+            .collect()
+    }
+}
+
+/// Panes of a plan as a tree of splits
+#[derive(Debug)]
+enum Node {
+    Pane(usize),
+    Split {
+        side: SplitSide,
+
+        /// Share of the split the second node takes
+        percent: u8,
+        first: Box<Node>,
+        second: Box<Node>,
+    },
+}
+
+impl Node {
+    /// Replace the pane `from` by a split of it and pane `index`
+    fn split(&mut self, from: usize, index: usize, side: SplitSide, percent: u8) -> bool {
+        match self {
+            Node::Pane(pane) if *pane == from => {
+                *self = Node::Split {
+                    side,
+                    percent,
+                    first: Box::new(Node::Pane(from)),
+                    second: Box::new(Node::Pane(index)),
+                };
+                true
+            }
+            Node::Pane(_) => false,
+            Node::Split { first, second, .. } => {
+                first.split(from, index, side, percent) || second.split(from, index, side, percent)
+            }
+        }
+    }
+}
+
+/// Zellij KDL layout of a plan, with the default tab and status bars
+pub fn render_layout(plan: &LayoutPlan) -> Result<String, String> {
+    let mut tree = Node::Pane(0);
+    for (index, pane) in plan.panes.iter().enumerate().skip(1) {
+        let split = pane
+            .split
+            .as_ref()
+            .ok_or_else(|| format!("Pane '{}': only the first pane opens a window", pane.name))?;
+        if split.from >= index || !tree.split(split.from, index, split.side, split.percent) {
+            return Err(format!(
+                "Pane '{}': split of unknown pane #{}",
+                pane.name, split.from
+            ));
+        }
+    }
+
+    let mut kdl = String::from("layout {\n");
+    kdl.push_str("    default_tab_template {\n");
+    kdl.push_str("        pane size=1 borderless=true {\n");
+    kdl.push_str("            plugin location=\"zellij:tab-bar\"\n");
+    kdl.push_str("        }\n");
+    kdl.push_str("        children\n");
+    kdl.push_str("        pane size=2 borderless=true {\n");
+    kdl.push_str("            plugin location=\"zellij:status-bar\"\n");
+    kdl.push_str("        }\n");
+    kdl.push_str("    }\n");
+    if !plan.panes.is_empty() {
+        render_node(&mut kdl, &tree, &plan.panes, None, 1);
+    }
+    kdl.push_str("}\n");
+    Ok(kdl)
+}
+
+fn render_node(kdl: &mut String, node: &Node, panes: &[PanePlan], size: Option<u8>, depth: usize) {
+    let indent = "    ".repeat(depth);
+    let size = size
+        .map(|size| format!(" size=\"{size}%\""))
+        .unwrap_or_default();
+    match node {
+        Node::Pane(index) => {
+            let pane = &panes[*index];
+            let _ = write!(
+                kdl,
+                "{indent}pane{size} name={} cwd={}",
+                quote(&pane.name),
+                quote(&pane.working_directory)
+            );
+            match &pane.command {
+                // Through a shell, like a command typed into the pane
+                Some(command) => {
+                    let _ = writeln!(
+                        kdl,
+                        " command=\"sh\" {{\n{indent}    args \"-c\" {}\n{indent}}}",
+                        quote(command)
+                    );
+                }
+                None => kdl.push('\n'),
+            }
+        }
+        Node::Split {
+            side,
+            percent,
+            first,
+            second,
+        } => {
+            // Zellij names the direction of the dividing line
+            let direction = match side {
+                SplitSide::Right => "vertical",
+                SplitSide::Bottom => "horizontal",
+            };
+            let _ = writeln!(kdl, "{indent}pane{size} split_direction=\"{direction}\" {{");
+            render_node(kdl, first, panes, Some(100 - percent), depth + 1);
+            render_node(kdl, second, panes, Some(*percent), depth + 1);
+            let _ = writeln!(kdl, "{indent}}}");
+        }
+    }
+}
+
+/// KDL string literal
+fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::room::layout::tests::FakeCli;
+    use crate::room::template::TemplateEngine;
+    use std::path::Path;
+
+    #[test]
+    fn test_layout_nests_columns_and_rows() {
+        let engine = TemplateEngine::new();
+        let template = engine.get_template("web_dev").unwrap();
+        let plan = LayoutPlan::from_template(template, "web", Path::new("/src/app"));
+
+        let kdl = render_layout(&plan).unwrap();
+        let structure: Vec<&str> = kdl
+            .lines()
+            .skip(10)
+            .map(str::trim)
+            .filter(|line| line.starts_with("pane"))
+            .map(|line| line.split(" name=").next().unwrap())
+            .collect();
+        assert_eq!(
+            structure,
+            [
+                "pane split_direction=\"vertical\" {",
+                "pane size=\"50%\" split_direction=\"horizontal\" {",
+                "pane size=\"50%\"",
+                "pane size=\"50%\"",
+                "pane size=\"50%\" split_direction=\"horizontal\" {",
+                "pane size=\"50%\"",
+                "pane size=\"50%\"",
+            ]
+        );
+        assert!(kdl.contains("cwd=\"/src/app/frontend\""), "{kdl}");
+    }
+
+    #[test]
+    fn test_apply_opens_layout_in_a_background_session() {
+        let engine = TemplateEngine::new();
+        let template = engine.get_template("parallel_dev").unwrap();
+        let plan = LayoutPlan::from_template(template, "par.1", Path::new("/src"));
+        let dir = tempfile::tempdir().unwrap();
+
+        let cli = Arc::new(FakeCli::default());
+        let backend = ZellijBackend::new(cli.clone(), dir.path());
+        let applied = backend.apply(&plan).unwrap();
+        let ids: Vec<&str> = applied.iter().map(|p| p.pane_id.as_str()).collect();
+        assert_eq!(ids, ["par_1/0", "par_1/1", "par_1/2"]);
+
+        let layout = std::fs::read_to_string(dir.path().join("par_1.kdl")).unwrap();
+        assert!(
+            layout.contains("args \"-c\" \"claude-code --workspace=main --priority=high\""),
+            "{layout}"
+        );
+        let ids: Vec<String> = applied.iter().map(|p| p.pane_id.clone()).collect();
+        assert_eq!(backend.close(&ids), ids);
+
+        let calls = cli.calls.lock().unwrap();
+        assert_eq!(calls[1], args(&["attach", "--create-background", "par_1"]));
+        assert_eq!(
+            calls[2][..4],
+            args(&["--session", "par_1", "action", "new-tab"])
+        );
+        assert_eq!(calls[3], args(&["kill-session", "par_1"]));
+    }
+}
@@ -0,0 +1,354 @@
+// WezTerm Multi-Process Development Framework - Secrets
+// Values referenced as `${secret:NAME}` in process environments, looked up
+// from a provider only when a process starts
+//
+// Configuration and templates keep the reference, never the value: the
+// environment reported over IPC shows `${secret:NAME}`, and values a process
+// prints are replaced in its buffered output.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::process::Command;
+
+const REFERENCE_START: &str = "${secret:";
+
+/// Service name secrets are stored under in the OS keychain
+pub const DEFAULT_KEYCHAIN_SERVICE: &str = "wezterm-parallel";
+
+/// Where secrets come from
+///
+/// ```yaml
+/// secrets:
+///   provider: keychain          # or env, encrypted_file
+///   service: wezterm-parallel
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "provider", rename_all = "snake_case")]
+pub enum SecretsConfig {
+    /// The framework's own environment, e.g. `ANTHROPIC_API_KEY` exported
+    /// before starting the daemon; `prefix` is prepended to the name
+    Env {
+        #[serde(default)]
+        prefix: String,
+    },
+
+    /// macOS Keychain (`security`) or the Secret Service on Linux
+    /// (`secret-tool`), with the secret name as the account
+    Keychain {
+        #[serde(default = "default_keychain_service")]
+        service: String,
+    },
+
+    /// YAML map of names to values, decrypted by `decrypt_command` with the
+    /// file's path appended (gpg by default, e.g. `[age, -d, -i, key.txt]`)
+    EncryptedFile {
+        path: String,
+        #[serde(default = "default_decrypt_command")]
+        decrypt_command: Vec<String>,
+    },
+}
+
+fn default_keychain_service() -> String {
+    DEFAULT_KEYCHAIN_SERVICE.to_string()
+}
+
+fn default_decrypt_command() -> Vec<String> {
+    ["gpg", "--quiet", "--batch", "--decrypt"]
+        .map(str::to_string)
+        .to_vec()
+}
+
+impl Default for SecretsConfig {
+    fn default() -> Self {
+        SecretsConfig::Env {
+            prefix: String::new(),
+        }
+    }
+}
+
+impl SecretsConfig {
+    pub fn provider(&self) -> Box<dyn SecretProvider> {
+        match self {
+            SecretsConfig::Env { prefix } => Box::new(EnvProvider {
+                prefix: prefix.clone(),
+            }),
+            SecretsConfig::Keychain { service } => Box::new(KeychainProvider {
+                service: service.clone(),
+            }),
+            SecretsConfig::EncryptedFile {
+                path,
+                decrypt_command,
+            } => Box::new(EncryptedFileProvider {
+                path: expand_home(path),
+                decrypt_command: decrypt_command.clone(),
+            }),
+        }
+    }
+}
+
+/// Looks up secrets by name; `Ok(None)` when the provider has no such secret
+pub trait SecretProvider: fmt::Debug + Send + Sync {
+    /// Provider name for error messages
+    fn name(&self) -> &'static str;
+
+    /// May block, e.g. on a keychain prompt
+    fn get(&self, name: &str) -> Result<Option<String>, String>;
+}
+
+#[derive(Debug)]
+pub struct EnvProvider {
+    prefix: String,
+}
+
+impl SecretProvider for EnvProvider {
+    fn name(&self) -> &'static str {
+        "env"
+    }
+
+    fn get(&self, name: &str) -> Result<Option<String>, String> {
+        Ok(std::env::var(format!("{}{name}", self.prefix)).ok())
+    }
+}
+
+#[derive(Debug)]
+pub struct KeychainProvider {
+    service: String,
+}
+
+impl KeychainProvider {
+    fn command(&self, name: &str) -> Option<Command> {
+        if cfg!(target_os = "macos") {
+            let mut cmd = Command::new("security");
+            cmd.args([
+                "find-generic-password",
+                "-s",
+                &self.service,
+                "-a",
+                name,
+                "-w",
+            ]);
+            Some(cmd)
+        } else if cfg!(unix) {
+            let mut cmd = Command::new("secret-tool");
+            cmd.args(["lookup", "service", &self.service, "account", name]);
+            Some(cmd)
+        } else {
+            None
+        }
+    }
+}
+
+impl SecretProvider for KeychainProvider {
+    fn name(&self) -> &'static str {
+        "keychain"
+    }
+
+    fn get(&self, name: &str) -> Result<Option<String>, String> {
+        let mut cmd = self
+            .command(name)
+            .ok_or("The OS keychain is not supported on this platform")?;
+        let output = cmd
+            .output()
+            .map_err(|e| format!("Failed to query the keychain: {e}"))?;
+        // Both tools exit non-zero when the item does not exist
+        if !output.status.success() || output.stdout.is_empty() {
+            return Ok(None);
+        }
+        let value = String::from_utf8(output.stdout)
+            .map_err(|_| format!("Keychain item '{name}' is not UTF-8"))?;
+        Ok(Some(value.trim_end_matches('\n').to_string()))
+    }
+}
+
+#[derive(Debug)]
+pub struct EncryptedFileProvider {
+    path: PathBuf,
+    decrypt_command: Vec<String>,
+}
+
+impl EncryptedFileProvider {
+    /// Decrypted on every lookup; the plaintext is not kept
+    fn decrypt(&self) -> Result<HashMap<String, String>, String> {
+        let (program, args) = self
+            .decrypt_command
+            .split_first()
+            .ok_or("secrets.decrypt_command is empty")?;
+        let output = Command::new(program)
+            .args(args)
+            .arg(&self.path)
+            .output()
+            .map_err(|e| format!("Failed to run {program}: {e}"))?;
+        if !output.status.success() {
+            return Err(format!(
+                "{program} could not decrypt {} ({})",
+                self.path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        // Parse errors could quote the plaintext, so they are not passed on
+        serde_yaml::from_slice(&output.stdout).map_err(|_| {
+            format!(
+                "{} does not decrypt to a map of names to values",
+                self.path.display()
+            )
+        })
+    }
+}
+
+impl SecretProvider for EncryptedFileProvider {
+    fn name(&self) -> &'static str {
+        "encrypted_file"
+    }
+
+    fn get(&self, name: &str) -> Result<Option<String>, String> {
+        Ok(self.decrypt()?.remove(name))
+    }
+}
+
+/// Names of the secrets `value` references, in order
+pub fn references(value: &str) -> Result<Vec<&str>, String> {
+    let mut names = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find(REFERENCE_START) {
+        let after = &rest[start + REFERENCE_START.len()..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| format!("Unterminated secret reference in '{value}'"))?;
+        let name = &after[..end];
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '/'))
+        {
+            return Err(format!("Invalid secret name '{name}'"));
+        }
+        names.push(name);
+        rest = &after[end + 1..];
+    }
+    Ok(names)
+}
+
+/// Replace the secret references in `value`, adding each secret's value to
+/// `resolved`
+pub fn expand(
+    value: &str,
+    provider: &dyn SecretProvider,
+    resolved: &mut Vec<String>,
+) -> Result<String, String> {
+    let names = references(value)?;
+    let mut expanded = value.to_string();
+    for name in names {
+        let secret = provider.get(name)?.ok_or_else(|| {
+            format!(
+                "Secret '{name}' was not found ({} provider)",
+                provider.name()
+            )
+        })?;
+        expanded = expanded.replacen(&format!("{REFERENCE_START}{name}}}"), &secret, 1);
+        resolved.push(secret);
+    }
+    Ok(expanded)
+}
+
+/// Shortest value replaced in output; shorter ones would mangle ordinary text
+const MIN_SCRUBBED_LEN: usize = 4;
+
+/// Replace the values of `secrets` in a line of output
+pub fn scrub(line: &str, secrets: &[String]) -> String {
+    secrets
+        .iter()
+        .filter(|secret| secret.len() >= MIN_SCRUBBED_LEN)
+        .fold(line.to_string(), |line, secret| {
+            line.replace(secret.as_str(), crate::process::env::REDACTED)
+        })
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Fixed(HashMap<String, String>);
+
+    impl SecretProvider for Fixed {
+        fn name(&self) -> &'static str {
+            "fixed"
+        }
+
+        fn get(&self, name: &str) -> Result<Option<String>, String> {
+            Ok(self.0.get(name).cloned())
+        }
+    }
+
+    #[test]
+    fn test_references_are_expanded_and_scrubbed() {
+        let provider = Fixed(HashMap::from([
+            ("ANTHROPIC_API_KEY".to_string(), "sk-ant-123".to_string()),
+            ("db/password".to_string(), "hunter22".to_string()),
+        ]));
+        let mut resolved = Vec::new();
+        assert_eq!(expand("plain", &provider, &mut resolved).unwrap(), "plain");
+        assert_eq!(
+            expand(
+                "postgres://app:${secret:db/password}@db/${secret:ANTHROPIC_API_KEY}",
+                &provider,
+                &mut resolved
+            )
+            .unwrap(),
+            "postgres://app:hunter22@db/sk-ant-123"
+        );
+        assert_eq!(resolved, ["hunter22", "sk-ant-123"]);
+
+        let error = expand("${secret:MISSING}", &provider, &mut resolved).unwrap_err();
+        assert_eq!(error, "Secret 'MISSING' was not found (fixed provider)");
+        assert!(references("${secret:UNTERMINATED").is_err());
+        assert!(references("${secret:}").is_err());
+
+        assert_eq!(
+            scrub("connecting with sk-ant-123", &resolved),
+            "connecting with [redacted]"
+        );
+        assert_eq!(scrub("abc", &["ab".to_string()]), "abc");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_encrypted_file_and_env_providers() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("secrets.yaml.enc");
+        std::fs::write(&file, "GITHUB_TOKEN: ghp_abc\n").unwrap();
+
+        // `cat` stands in for the decryption tool
+        let config: SecretsConfig = serde_yaml::from_str(&format!(
+            "provider: encrypted_file\npath: {}\ndecrypt_command: [cat]\n",
+            file.display()
+        ))
+        .unwrap();
+        let provider = config.provider();
+        assert_eq!(
+            provider.get("GITHUB_TOKEN").unwrap().as_deref(),
+            Some("ghp_abc")
+        );
+        assert_eq!(provider.get("OTHER").unwrap(), None);
+
+        std::fs::write(&file, "- not\n- a map\n").unwrap();
+        assert!(provider.get("GITHUB_TOKEN").is_err());
+
+        let env = SecretsConfig::Env {
+            prefix: "WEZTERM_PARALLEL_TEST_".to_string(),
+        }
+        .provider();
+        std::env::set_var("WEZTERM_PARALLEL_TEST_SECRET", "s3cret");
+        assert_eq!(env.get("SECRET").unwrap().as_deref(), Some("s3cret"));
+        assert_eq!(SecretsConfig::default().provider().name(), "env");
+    }
+}
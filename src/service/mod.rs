@@ -0,0 +1,281 @@
+// WezTerm Multi-Process Development Framework - Service Units
+// Renders systemd user units and launchd agents that start the daemon on
+// login and restart it when it fails
+//
+// With socket activation a systemd socket unit owns the IPC socket and
+// starts the daemon on the first connection (see
+// `IpcListener::from_socket_activation`). launchd agents always start the
+// daemon at login.
+
+use std::path::{Path, PathBuf};
+
+/// Name of the systemd units
+pub const SYSTEMD_UNIT: &str = "wezterm-parallel";
+
+/// Label of the launchd agent
+pub const LAUNCHD_LABEL: &str = "com.github.daktu32.wezterm-parallel";
+
+const DESCRIPTION: &str = "WezTerm Multi-Process Development Framework";
+
+/// Service manager the units are generated for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceManager {
+    Systemd,
+    Launchd,
+}
+
+impl ServiceManager {
+    /// Service manager of the platform this was built for
+    pub fn native() -> Self {
+        if cfg!(target_os = "macos") {
+            ServiceManager::Launchd
+        } else {
+            ServiceManager::Systemd
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "systemd" => Some(ServiceManager::Systemd),
+            "launchd" => Some(ServiceManager::Launchd),
+            _ => None,
+        }
+    }
+
+    /// Directory per-user units are loaded from
+    pub fn user_unit_dir(self) -> Option<PathBuf> {
+        match self {
+            ServiceManager::Systemd => dirs::config_dir().map(|dir| dir.join("systemd/user")),
+            ServiceManager::Launchd => {
+                dirs::home_dir().map(|home| home.join("Library/LaunchAgents"))
+            }
+        }
+    }
+}
+
+/// Values baked into the generated units
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServiceOptions {
+    /// Daemon binary the units start
+    pub binary: PathBuf,
+
+    /// Let a systemd socket unit own the IPC socket and start the daemon on
+    /// the first connection
+    pub socket_activation: bool,
+
+    /// Permissions of the activated socket (`auth.socket_mode`)
+    pub socket_mode: u32,
+
+    /// File launchd appends the daemon's output to; systemd uses the journal
+    pub log_file: Option<PathBuf>,
+}
+
+/// A rendered unit and the file name it is installed under
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitFile {
+    pub file_name: String,
+    pub content: String,
+}
+
+/// Render the units for `manager`
+pub fn render(manager: ServiceManager, options: &ServiceOptions) -> Result<Vec<UnitFile>, String> {
+    match manager {
+        ServiceManager::Systemd => {
+            let mut units = vec![UnitFile {
+                file_name: format!("{SYSTEMD_UNIT}.service"),
+                content: systemd_service(options),
+            }];
+            if options.socket_activation {
+                units.push(UnitFile {
+                    file_name: format!("{SYSTEMD_UNIT}.socket"),
+                    content: systemd_socket(options),
+                });
+            }
+            Ok(units)
+        }
+        ServiceManager::Launchd if options.socket_activation => {
+            Err("Socket activation is only supported with systemd".to_string())
+        }
+        ServiceManager::Launchd => Ok(vec![UnitFile {
+            file_name: format!("{LAUNCHD_LABEL}.plist"),
+            content: launchd_plist(options),
+        }]),
+    }
+}
+
+/// Commands that load the units installed in `dir` and start the daemon
+pub fn enable_commands(
+    manager: ServiceManager,
+    options: &ServiceOptions,
+    dir: &Path,
+) -> Vec<String> {
+    match manager {
+        ServiceManager::Systemd => {
+            let unit = if options.socket_activation {
+                format!("{SYSTEMD_UNIT}.socket")
+            } else {
+                format!("{SYSTEMD_UNIT}.service")
+            };
+            vec![
+                "systemctl --user daemon-reload".to_string(),
+                format!("systemctl --user enable --now {unit}"),
+            ]
+        }
+        ServiceManager::Launchd => vec![format!(
+            "launchctl load -w {}",
+            dir.join(format!("{LAUNCHD_LABEL}.plist")).display()
+        )],
+    }
+}
+
+fn systemd_service(options: &ServiceOptions) -> String {
+    let mut unit = format!("[Unit]\nDescription={DESCRIPTION}\n");
+    if options.socket_activation {
+        unit.push_str(&format!(
+            "Requires={SYSTEMD_UNIT}.socket\nAfter={SYSTEMD_UNIT}.socket\n"
+        ));
+    }
+    unit.push_str(&format!(
+        "\n[Service]\n\
+         Type=simple\n\
+         ExecStart={}\n\
+         Restart=on-failure\n\
+         RestartSec=2\n\
+         # SIGTERM saves task state before exiting\n\
+         TimeoutStopSec=30\n\
+         \n[Install]\n\
+         WantedBy=default.target\n",
+        systemd_quote(&options.binary)
+    ));
+    if options.socket_activation {
+        unit.push_str(&format!("Also={SYSTEMD_UNIT}.socket\n"));
+    }
+    unit
+}
+
+fn systemd_socket(options: &ServiceOptions) -> String {
+    format!(
+        "[Unit]\n\
+         Description={DESCRIPTION} IPC socket\n\
+         \n[Socket]\n\
+         ListenStream=%t/{}\n\
+         SocketMode={:04o}\n\
+         RemoveOnStop=true\n\
+         \n[Install]\n\
+         WantedBy=sockets.target\n",
+        crate::ipc::transport::SOCKET_NAME,
+        options.socket_mode
+    )
+}
+
+fn launchd_plist(options: &ServiceOptions) -> String {
+    let mut plist = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+         <plist version=\"1.0\">\n\
+         <dict>\n\
+         \x20 <key>Label</key>\n\
+         \x20 <string>{LAUNCHD_LABEL}</string>\n\
+         \x20 <key>ProgramArguments</key>\n\
+         \x20 <array>\n\
+         \x20   <string>{}</string>\n\
+         \x20 </array>\n\
+         \x20 <key>RunAtLoad</key>\n\
+         \x20 <true/>\n\
+         \x20 <key>KeepAlive</key>\n\
+         \x20 <dict>\n\
+         \x20   <key>SuccessfulExit</key>\n\
+         \x20   <false/>\n\
+         \x20 </dict>\n\
+         \x20 <key>ThrottleInterval</key>\n\
+         \x20 <integer>5</integer>\n",
+        xml_escape(&options.binary.to_string_lossy())
+    );
+    if let Some(ref log_file) = options.log_file {
+        let log_file = xml_escape(&log_file.to_string_lossy());
+        plist.push_str(&format!(
+            "  <key>StandardOutPath</key>\n  <string>{log_file}</string>\n  <key>StandardErrorPath</key>\n  <string>{log_file}</string>\n"
+        ));
+    }
+    plist.push_str("</dict>\n</plist>\n");
+    plist
+}
+
+/// Quote a path for `ExecStart=` when it has spaces or quotes
+fn systemd_quote(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    if path.contains(|c: char| c.is_whitespace() || c == '"' || c == '\\') {
+        format!("\"{}\"", path.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        path.to_string()
+    }
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(socket_activation: bool) -> ServiceOptions {
+        ServiceOptions {
+            binary: PathBuf::from("/home/me/.cargo/bin/wezterm-parallel"),
+            socket_activation,
+            socket_mode: 0o600,
+            log_file: Some(PathBuf::from("/Users/me/logs/a&b.log")),
+        }
+    }
+
+    #[test]
+    fn test_systemd_units_with_socket_activation() {
+        let units = render(ServiceManager::Systemd, &options(true)).unwrap();
+        assert_eq!(units.len(), 2);
+        assert_eq!(units[0].file_name, "wezterm-parallel.service");
+        let service = &units[0].content;
+        assert!(service.contains("ExecStart=/home/me/.cargo/bin/wezterm-parallel\n"));
+        assert!(service.contains("Restart=on-failure"));
+        assert!(service.contains("Requires=wezterm-parallel.socket"));
+        assert!(service.contains("Also=wezterm-parallel.socket"));
+
+        let socket = &units[1].content;
+        assert!(socket.contains("ListenStream=%t/wezterm-parallel.sock"));
+        assert!(socket.contains("SocketMode=0600"));
+
+        let commands = enable_commands(ServiceManager::Systemd, &options(true), Path::new("."));
+        assert_eq!(
+            commands[1],
+            "systemctl --user enable --now wezterm-parallel.socket"
+        );
+
+        let plain = render(ServiceManager::Systemd, &options(false)).unwrap();
+        assert_eq!(plain.len(), 1);
+        assert!(!plain[0].content.contains(".socket"));
+        assert_eq!(
+            systemd_quote(Path::new("/opt/my apps/wezterm-parallel")),
+            "\"/opt/my apps/wezterm-parallel\""
+        );
+    }
+
+    #[test]
+    fn test_launchd_plist() {
+        let units = render(ServiceManager::Launchd, &options(false)).unwrap();
+        assert_eq!(
+            units[0].file_name,
+            "com.github.daktu32.wezterm-parallel.plist"
+        );
+        let plist = &units[0].content;
+        assert!(plist.contains("<string>/home/me/.cargo/bin/wezterm-parallel</string>"));
+        assert!(plist.contains("<key>RunAtLoad</key>\n  <true/>"));
+        assert!(plist.contains("<key>SuccessfulExit</key>\n    <false/>"));
+        assert!(plist.contains("<string>/Users/me/logs/a&amp;b.log</string>"));
+        assert!(plist.ends_with("</dict>\n</plist>\n"));
+
+        assert!(render(ServiceManager::Launchd, &options(true)).is_err());
+    }
+}
@@ -0,0 +1,482 @@
+// WezTerm Multi-Process Development Framework - Task Supervisor
+// Restarts background tasks that panic or exit and reports when they last made progress
+
+use crate::dashboard::{AlertNotification, AlertSeverity, DashboardMessage};
+use crate::i18n::{Language, Text, TextKey};
+use crate::logging::LogContext;
+use crate::{log_error, log_info, log_warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+use tokio::task::{AbortHandle, JoinHandle};
+
+/// Delay before the first restart; doubles with each failure in the crash window
+pub const RESTART_BACKOFF_MIN: Duration = Duration::from_secs(1);
+
+/// Longest delay between restarts
+pub const RESTART_BACKOFF_MAX: Duration = Duration::from_secs(60);
+
+/// Failures older than this no longer count towards a crash loop
+pub const CRASH_LOOP_WINDOW: Duration = Duration::from_secs(300);
+
+/// Failures within the window that raise a crash loop alert
+pub const CRASH_LOOP_THRESHOLD: usize = 3;
+
+/// A task without a successful run for this many periods is reported stale
+pub const STALE_PERIODS: u32 = 3;
+
+/// State of a supervised task
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskState {
+    Running,
+    /// Waiting to be restarted after a failure
+    Restarting,
+    /// Failed repeatedly within the crash window; still restarted with backoff
+    CrashLooping,
+    /// Alive but no successful run for several periods
+    Stale,
+    /// Cancelled by its owner
+    Stopped,
+}
+
+/// Health of a supervised task as exposed via `/healthz`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskHealth {
+    pub name: String,
+    pub state: TaskState,
+
+    /// Restarts since startup
+    pub restarts: u32,
+
+    /// Time of the last successful run
+    pub last_success: Option<u64>,
+
+    /// Time of the last panic or unexpected exit
+    pub last_failure: Option<u64>,
+
+    pub last_error: Option<String>,
+
+    /// Expected time between successful runs
+    pub period_secs: u64,
+}
+
+impl TaskHealth {
+    pub fn is_healthy(&self) -> bool {
+        matches!(self.state, TaskState::Running | TaskState::Stopped)
+    }
+}
+
+#[derive(Debug)]
+struct TaskRecord {
+    health: TaskHealth,
+    period: Duration,
+    started_at: Instant,
+    last_success_at: Option<Instant>,
+    failures: VecDeque<Instant>,
+    alerted: bool,
+}
+
+/// Handed to each run of a supervised task to report progress
+#[derive(Debug, Clone)]
+pub struct TaskContext {
+    name: Arc<str>,
+    tasks: Arc<Mutex<BTreeMap<String, TaskRecord>>>,
+}
+
+impl TaskContext {
+    /// Record a successful run (one loop iteration)
+    pub fn ran(&self) {
+        if let Some(record) = self.tasks.lock().unwrap().get_mut(&*self.name) {
+            record.last_success_at = Some(Instant::now());
+            record.health.last_success = Some(now_secs());
+            record.health.state = TaskState::Running;
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Supervises long-running background tasks
+///
+/// A task that panics or returns is restarted with exponential backoff. Each
+/// run reports progress through its [`TaskContext`], so a task that is alive
+/// but stuck shows up as stale instead of silently freezing its values.
+#[derive(Debug)]
+pub struct Supervisor {
+    tasks: Arc<Mutex<BTreeMap<String, TaskRecord>>>,
+    backoff_min: Duration,
+    backoff_max: Duration,
+    dashboard_tx: Option<broadcast::Sender<DashboardMessage>>,
+    language: Language,
+}
+
+impl Default for Supervisor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Supervisor {
+    pub fn new() -> Self {
+        Self {
+            tasks: Arc::new(Mutex::new(BTreeMap::new())),
+            backoff_min: RESTART_BACKOFF_MIN,
+            backoff_max: RESTART_BACKOFF_MAX,
+            dashboard_tx: None,
+            language: Language::default(),
+        }
+    }
+
+    /// Raise crash loop alerts on the dashboard
+    pub fn with_dashboard_broadcaster(
+        mut self,
+        tx: broadcast::Sender<DashboardMessage>,
+        language: Language,
+    ) -> Self {
+        self.dashboard_tx = Some(tx);
+        self.language = language;
+        self
+    }
+
+    pub fn with_restart_backoff(mut self, min: Duration, max: Duration) -> Self {
+        self.backoff_min = min;
+        self.backoff_max = max.max(min);
+        self
+    }
+
+    /// Run `task` under supervision, restarting it whenever it panics or returns
+    ///
+    /// `period` is the expected time between successful runs reported through
+    /// [`TaskContext::ran`]. Aborting the returned handle stops the task.
+    pub fn spawn<F, Fut>(
+        self: &Arc<Self>,
+        name: &str,
+        period: Duration,
+        mut task: F,
+    ) -> JoinHandle<()>
+    where
+        F: FnMut(TaskContext) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.register(name, period);
+        let supervisor = Arc::clone(self);
+        let context = TaskContext {
+            name: Arc::from(name),
+            tasks: Arc::clone(&self.tasks),
+        };
+
+        tokio::spawn(async move {
+            let _stopped = MarkStopped(context.clone());
+            loop {
+                let run = tokio::spawn(task(context.clone()));
+                let _guard = AbortOnDrop(run.abort_handle());
+                let error = match run.await {
+                    Ok(()) => "task exited".to_string(),
+                    Err(e) if e.is_panic() => panic_message(e.into_panic()),
+                    Err(_) => return,
+                };
+
+                let (backoff, crash_loop) =
+                    supervisor.record_failure(context.name(), &error, Instant::now());
+                if crash_loop {
+                    supervisor.report_crash_loop(context.name(), &error);
+                } else {
+                    let log_context = LogContext::new("supervisor", "task_restart")
+                        .with_entity_id(context.name())
+                        .with_metadata("error", serde_json::json!(error))
+                        .with_metadata("backoff_ms", serde_json::json!(backoff.as_millis() as u64));
+                    log_warn!(
+                        log_context,
+                        "Background task {} failed: {}; restarting in {:?}",
+                        context.name(),
+                        error,
+                        backoff
+                    );
+                }
+                tokio::time::sleep(backoff).await;
+
+                let log_context =
+                    LogContext::new("supervisor", "task_restarted").with_entity_id(context.name());
+                log_info!(log_context, "Restarting background task {}", context.name());
+            }
+        })
+    }
+
+    /// Health of every supervised task, by name
+    pub fn health(&self) -> Vec<TaskHealth> {
+        self.health_at(Instant::now())
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.health().iter().all(TaskHealth::is_healthy)
+    }
+
+    fn health_at(&self, now: Instant) -> Vec<TaskHealth> {
+        self.tasks
+            .lock()
+            .unwrap()
+            .values()
+            .map(|record| {
+                let mut health = record.health.clone();
+                let since = record.last_success_at.unwrap_or(record.started_at);
+                if health.state == TaskState::Running
+                    && now.duration_since(since) > record.period * STALE_PERIODS
+                {
+                    health.state = TaskState::Stale;
+                }
+                health
+            })
+            .collect()
+    }
+
+    fn register(&self, name: &str, period: Duration) {
+        self.tasks.lock().unwrap().insert(
+            name.to_string(),
+            TaskRecord {
+                health: TaskHealth {
+                    name: name.to_string(),
+                    state: TaskState::Running,
+                    restarts: 0,
+                    last_success: None,
+                    last_failure: None,
+                    last_error: None,
+                    period_secs: period.as_secs().max(1),
+                },
+                period,
+                started_at: Instant::now(),
+                last_success_at: None,
+                failures: VecDeque::new(),
+                alerted: false,
+            },
+        );
+    }
+
+    /// Count a failure and return the restart delay and whether a crash loop
+    /// alert is due (raised once per loop)
+    fn record_failure(&self, name: &str, error: &str, now: Instant) -> (Duration, bool) {
+        let mut tasks = self.tasks.lock().unwrap();
+        let Some(record) = tasks.get_mut(name) else {
+            return (self.backoff_min, false);
+        };
+
+        while record
+            .failures
+            .front()
+            .is_some_and(|at| now.duration_since(*at) > CRASH_LOOP_WINDOW)
+        {
+            record.failures.pop_front();
+        }
+        if record.failures.is_empty() {
+            record.alerted = false;
+        }
+        record.failures.push_back(now);
+        record.health.restarts += 1;
+        record.health.last_failure = Some(now_secs());
+        record.health.last_error = Some(error.to_string());
+        record.started_at = now;
+
+        let exponent = (record.failures.len() - 1).min(16) as u32;
+        let backoff = self
+            .backoff_min
+            .saturating_mul(2u32.pow(exponent))
+            .min(self.backoff_max);
+
+        let crash_loop = record.failures.len() >= CRASH_LOOP_THRESHOLD;
+        record.health.state = if crash_loop {
+            TaskState::CrashLooping
+        } else {
+            TaskState::Restarting
+        };
+        let alert = crash_loop && !record.alerted;
+        record.alerted |= crash_loop;
+        (backoff, alert)
+    }
+
+    fn report_crash_loop(&self, name: &str, error: &str) {
+        let count = self
+            .tasks
+            .lock()
+            .unwrap()
+            .get(name)
+            .map_or(0, |record| record.failures.len());
+        let text = Text::new(TextKey::TaskCrashLoop)
+            .arg("task", name)
+            .arg("count", count)
+            .arg("minutes", CRASH_LOOP_WINDOW.as_secs() / 60);
+        let message = text.render(self.language);
+
+        let context = LogContext::new("supervisor", "task_crash_loop")
+            .with_entity_id(name)
+            .with_metadata("error", serde_json::json!(error))
+            .with_metadata("failures", serde_json::json!(count));
+        log_error!(context, "{}: {}", message, error);
+
+        if let Some(ref tx) = self.dashboard_tx {
+            let timestamp = now_secs();
+            let _ = tx.send(DashboardMessage::Alert(AlertNotification {
+                id: format!("supervisor-{name}-{timestamp}"),
+                severity: AlertSeverity::Critical,
+                category: "system".to_string(),
+                message,
+                component: Some(name.to_string()),
+                timestamp,
+                details: Some(serde_json::json!({ "error": error, "failures": count })),
+                text: Some(text),
+            }));
+        }
+    }
+}
+
+/// Aborts the current run when the supervising task itself is aborted
+pub(crate) struct AbortOnDrop(pub(crate) AbortHandle);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Marks the task stopped once its supervising loop ends
+struct MarkStopped(TaskContext);
+
+impl Drop for MarkStopped {
+    fn drop(&mut self) {
+        if let Ok(mut tasks) = self.0.tasks.lock() {
+            if let Some(record) = tasks.get_mut(&*self.0.name) {
+                record.health.state = TaskState::Stopped;
+            }
+        }
+    }
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        format!("panicked: {message}")
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        format!("panicked: {message}")
+    } else {
+        "panicked".to_string()
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test]
+    async fn test_panicking_task_is_restarted() {
+        let supervisor = Arc::new(
+            Supervisor::new()
+                .with_restart_backoff(Duration::from_millis(10), Duration::from_millis(10)),
+        );
+        let runs = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&runs);
+        let handle = supervisor.spawn("collector", Duration::from_secs(60), move |task| {
+            let run = counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if run == 0 {
+                    panic!("collector lost its source");
+                }
+                loop {
+                    task.ran();
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                }
+            }
+        });
+
+        // Wait for the restarted run to report, however loaded the machine
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+        while supervisor.health()[0].last_success.is_none() {
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "task never restarted"
+            );
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let health = &supervisor.health()[0];
+        assert_eq!(health.name, "collector");
+        assert_eq!(health.state, TaskState::Running);
+        assert_eq!(health.restarts, 1);
+        assert!(health.last_success.is_some());
+        assert_eq!(
+            health.last_error.as_deref(),
+            Some("panicked: collector lost its source")
+        );
+        assert!(supervisor.is_healthy());
+
+        handle.abort();
+        while supervisor.health()[0].state != TaskState::Stopped {
+            assert!(tokio::time::Instant::now() < deadline, "task never stopped");
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_crash_loop_alerts_once_and_backs_off() {
+        let (tx, mut rx) = broadcast::channel(8);
+        let supervisor = Supervisor::new().with_dashboard_broadcaster(tx, Language::En);
+        supervisor.register("watcher", Duration::from_secs(1));
+
+        let start = Instant::now();
+        let delays: Vec<(Duration, bool)> = (0..4)
+            .map(|i| {
+                supervisor.record_failure("watcher", "task exited", start + Duration::from_secs(i))
+            })
+            .collect();
+        assert_eq!(
+            delays,
+            vec![
+                (Duration::from_secs(1), false),
+                (Duration::from_secs(2), false),
+                (Duration::from_secs(4), true),
+                (Duration::from_secs(8), false),
+            ]
+        );
+        assert_eq!(supervisor.health()[0].state, TaskState::CrashLooping);
+        assert!(!supervisor.is_healthy());
+
+        supervisor.report_crash_loop("watcher", "task exited");
+        match rx.try_recv().unwrap() {
+            DashboardMessage::Alert(alert) => {
+                assert_eq!(alert.severity, AlertSeverity::Critical);
+                assert_eq!(alert.component.as_deref(), Some("watcher"));
+            }
+            other => panic!("unexpected message: {other:?}"),
+        }
+
+        // Old failures fall out of the window and the next loop alerts again
+        let later = start + CRASH_LOOP_WINDOW + Duration::from_secs(10);
+        assert_eq!(
+            supervisor.record_failure("watcher", "task exited", later),
+            (Duration::from_secs(1), false)
+        );
+    }
+
+    #[test]
+    fn test_task_without_progress_is_stale() {
+        let supervisor = Supervisor::new();
+        supervisor.register("broadcaster", Duration::from_secs(1));
+        let now = Instant::now();
+
+        assert_eq!(supervisor.health_at(now)[0].state, TaskState::Running);
+        assert_eq!(
+            supervisor.health_at(now + Duration::from_secs(5))[0].state,
+            TaskState::Stale
+        );
+    }
+}
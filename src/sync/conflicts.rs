@@ -0,0 +1,120 @@
+// WezTerm Multi-Process Development Framework - Sync Conflicts
+// Conflicting file changes held back until a user picks a resolution
+
+use super::file_sync::{ChangeType, FileChange};
+use super::merger::ConflictType;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+/// How a detected conflict is settled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResolutionStrategy {
+    /// Keep the change that reached the file first
+    Ours,
+
+    /// Apply the change that was rejected
+    Theirs,
+
+    /// Three-way merge of both changes; fails if they touch the same lines
+    Merge,
+
+    /// Write content supplied by the user
+    Manual,
+}
+
+impl ResolutionStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ours => "ours",
+            Self::Theirs => "theirs",
+            Self::Merge => "merge",
+            Self::Manual => "manual",
+        }
+    }
+}
+
+impl std::str::FromStr for ResolutionStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ours" => Ok(Self::Ours),
+            "theirs" => Ok(Self::Theirs),
+            "merge" => Ok(Self::Merge),
+            "manual" => Ok(Self::Manual),
+            other => Err(format!(
+                "Unknown resolution strategy '{other}' (expected ours, theirs, merge or manual)"
+            )),
+        }
+    }
+}
+
+/// Two changes to the same file from different processes, close enough in
+/// time that neither can be applied blindly
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncConflict {
+    pub id: String,
+    pub file_path: PathBuf,
+    pub conflict_type: ConflictType,
+
+    /// File content before either change
+    pub base_content: String,
+
+    /// Change already written to the file
+    pub ours_content: String,
+    pub ours_process: String,
+
+    /// Change rejected because of the conflict
+    pub theirs_content: String,
+    pub theirs_process: String,
+
+    /// Whether the rejected change deletes the file
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub theirs_deletes: bool,
+
+    /// Unix timestamp in seconds
+    pub detected_at: u64,
+}
+
+impl SyncConflict {
+    pub(crate) fn new(base_content: &str, ours: &FileChange, theirs: &FileChange) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            file_path: theirs.file_path.clone(),
+            conflict_type: ConflictType::ContentConflict,
+            base_content: base_content.to_string(),
+            ours_content: ours.content.clone(),
+            ours_process: ours.process_id.to_string(),
+            theirs_content: theirs.content.clone(),
+            theirs_process: theirs.process_id.to_string(),
+            theirs_deletes: theirs.change_type == ChangeType::Deleted,
+            detected_at: unix_now(),
+        }
+    }
+}
+
+/// Audit record of a resolved conflict
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConflictResolutionRecord {
+    pub conflict_id: String,
+    pub file_path: PathBuf,
+    pub strategy: ResolutionStrategy,
+
+    /// Client that chose the resolution
+    pub resolved_by: String,
+
+    /// Unix timestamp in seconds
+    pub resolved_at: u64,
+
+    /// Size of the content written, or `None` when the file was deleted
+    pub bytes_written: Option<usize>,
+}
+
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
@@ -0,0 +1,432 @@
+// WezTerm Multi-Process Development Framework - Sync Diff
+// Unified diffs of file versions for review and conflict resolution frontends
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Unchanged lines shown around each change
+pub const DEFAULT_CONTEXT_LINES: usize = 3;
+
+/// Larger inputs are reported as `too_large` instead of being diffed
+pub const MAX_DIFF_INPUT_BYTES: usize = 1024 * 1024;
+
+/// Lines of diff output returned before it is truncated
+pub const MAX_DIFF_OUTPUT_LINES: usize = 5000;
+
+/// Bytes inspected for NUL characters when detecting binary content
+const BINARY_SNIFF_BYTES: usize = 8000;
+
+/// Edit distance at which the line matching gives up and replaces the
+/// remaining lines wholesale, bounding time and memory on unrelated files
+const MAX_EDIT_DISTANCE: usize = 2000;
+
+/// What to diff
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DiffTarget {
+    /// Two files inside the watched roots; a missing file counts as empty
+    Files {
+        old_path: PathBuf,
+        new_path: PathBuf,
+    },
+
+    /// Every file a process changed, from before its first change to after
+    /// its last one
+    Process { process_id: String },
+
+    /// The base of a held conflict against both competing versions
+    Conflict { conflict_id: String },
+}
+
+/// Limits of a diff request
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiffOptions {
+    pub context_lines: usize,
+    pub max_input_bytes: usize,
+    pub max_output_lines: usize,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            context_lines: DEFAULT_CONTEXT_LINES,
+            max_input_bytes: MAX_DIFF_INPUT_BYTES,
+            max_output_lines: MAX_DIFF_OUTPUT_LINES,
+        }
+    }
+}
+
+/// Diff of one file between two versions
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FileDiff {
+    pub old_path: PathBuf,
+    pub new_path: PathBuf,
+
+    /// Process that made the change, for process and conflict diffs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process_id: Option<String>,
+
+    /// Unified diff text; empty when the versions are equal, binary or too large
+    pub unified: String,
+    pub additions: usize,
+    pub deletions: usize,
+
+    /// Either version is not UTF-8 text
+    pub binary: bool,
+
+    /// Either version exceeds `max_input_bytes`
+    pub too_large: bool,
+
+    /// `unified` was cut at `max_output_lines`
+    pub truncated: bool,
+}
+
+impl FileDiff {
+    /// Diff two versions of a file, given as raw bytes
+    pub fn between(
+        old_path: PathBuf,
+        new_path: PathBuf,
+        old: &[u8],
+        new: &[u8],
+        options: &DiffOptions,
+    ) -> Self {
+        let mut diff = Self {
+            old_path,
+            new_path,
+            ..Default::default()
+        };
+        if old.len() > options.max_input_bytes || new.len() > options.max_input_bytes {
+            diff.too_large = true;
+            return diff;
+        }
+        let (Some(old), Some(new)) = (as_text(old), as_text(new)) else {
+            diff.binary = true;
+            return diff;
+        };
+        if old == new {
+            return diff;
+        }
+
+        let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+        let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+        let edits = line_edits(&old_lines, &new_lines);
+        diff.additions = edits
+            .iter()
+            .filter(|e| matches!(e, Edit::Insert(_)))
+            .count();
+        diff.deletions = edits
+            .iter()
+            .filter(|e| matches!(e, Edit::Delete(_)))
+            .count();
+        diff.render(&edits, &old_lines, &new_lines, options);
+        diff
+    }
+
+    /// Diff two text versions
+    pub fn between_text(
+        old_path: PathBuf,
+        new_path: PathBuf,
+        old: &str,
+        new: &str,
+        options: &DiffOptions,
+    ) -> Self {
+        Self::between(old_path, new_path, old.as_bytes(), new.as_bytes(), options)
+    }
+
+    pub fn with_process(mut self, process_id: impl Into<String>) -> Self {
+        self.process_id = Some(process_id.into());
+        self
+    }
+
+    fn render(&mut self, edits: &[Edit], old: &[&str], new: &[&str], options: &DiffOptions) {
+        let mut out = vec![
+            format!("--- {}", label(&self.old_path, old.is_empty())),
+            format!("+++ {}", label(&self.new_path, new.is_empty())),
+        ];
+
+        for hunk in hunks(edits, options.context_lines) {
+            let (old_start, new_start) = position(&edits[..hunk.start]);
+            let (old_count, new_count) = position(&edits[hunk.clone()]);
+            out.push(format!(
+                "@@ -{} +{} @@",
+                range(old_start, old_count),
+                range(new_start, new_count)
+            ));
+            for edit in &edits[hunk] {
+                let (prefix, line) = match *edit {
+                    Edit::Equal(i, _) => (' ', old[i]),
+                    Edit::Delete(i) => ('-', old[i]),
+                    Edit::Insert(j) => ('+', new[j]),
+                };
+                out.push(format!(
+                    "{prefix}{}",
+                    line.strip_suffix('\n').unwrap_or(line)
+                ));
+                if !line.ends_with('\n') {
+                    out.push("\\ No newline at end of file".to_string());
+                }
+            }
+        }
+
+        if out.len() > options.max_output_lines {
+            out.truncate(options.max_output_lines);
+            self.truncated = true;
+        }
+        self.unified = out.join("\n");
+        self.unified.push('\n');
+    }
+}
+
+/// Content as text, or `None` for binary data
+fn as_text(bytes: &[u8]) -> Option<&str> {
+    let sniff = &bytes[..bytes.len().min(BINARY_SNIFF_BYTES)];
+    if sniff.contains(&0) {
+        return None;
+    }
+    std::str::from_utf8(bytes).ok()
+}
+
+fn label(path: &std::path::Path, missing: bool) -> String {
+    if missing {
+        "/dev/null".to_string()
+    } else {
+        path.display().to_string()
+    }
+}
+
+/// Hunk header range: 1-based start line and count, `start,0` for empty ranges
+fn range(start: usize, count: usize) -> String {
+    match count {
+        0 => format!("{start},0"),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{count}", start + 1),
+    }
+}
+
+/// Old and new lines covered by a run of edits
+fn position(edits: &[Edit]) -> (usize, usize) {
+    edits.iter().fold((0, 0), |(old, new), edit| match edit {
+        Edit::Equal(..) => (old + 1, new + 1),
+        Edit::Delete(_) => (old + 1, new),
+        Edit::Insert(_) => (old, new + 1),
+    })
+}
+
+/// Edit script entry, with indices into the old and new lines
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Edit {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// Ranges of the edit script to show, each change with its context
+fn hunks(edits: &[Edit], context: usize) -> Vec<std::ops::Range<usize>> {
+    let mut hunks: Vec<std::ops::Range<usize>> = Vec::new();
+    for (index, _) in edits
+        .iter()
+        .enumerate()
+        .filter(|(_, edit)| !matches!(edit, Edit::Equal(..)))
+    {
+        let start = index.saturating_sub(context);
+        let end = (index + context + 1).min(edits.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.end => last.end = end,
+            _ => hunks.push(start..end),
+        }
+    }
+    hunks
+}
+
+/// Shortest line edit script (Myers), after trimming the common prefix and suffix
+fn line_edits(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut edits: Vec<Edit> = (0..prefix).map(|i| Edit::Equal(i, i)).collect();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+    let middle = myers(old_middle, new_middle).unwrap_or_else(|| {
+        (0..old_middle.len())
+            .map(Edit::Delete)
+            .chain((0..new_middle.len()).map(Edit::Insert))
+            .collect()
+    });
+    edits.extend(middle.into_iter().map(|edit| match edit {
+        Edit::Equal(i, j) => Edit::Equal(i + prefix, j + prefix),
+        Edit::Delete(i) => Edit::Delete(i + prefix),
+        Edit::Insert(j) => Edit::Insert(j + prefix),
+    }));
+    edits.extend((0..suffix).map(|s| Edit::Equal(old.len() - suffix + s, new.len() - suffix + s)));
+    edits
+}
+
+/// Myers' O(ND) diff; `None` when the edit distance exceeds `MAX_EDIT_DISTANCE`
+fn myers(old: &[&str], new: &[&str]) -> Option<Vec<Edit>> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let max = (n + m) as usize;
+    let offset = max as isize + 1;
+    let mut v = vec![0isize; 2 * max + 3];
+    // Furthest x per diagonal k in -d..=d at the start of each round
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+
+    for d in 0..=max.min(MAX_EDIT_DISTANCE) as isize {
+        trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let at = |k: isize| v[(offset + k) as usize];
+            let mut x = if k == -d || (k != d && at(k - 1) < at(k + 1)) {
+                at(k + 1)
+            } else {
+                at(k - 1) + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[(offset + k) as usize] = x;
+            if x >= n && y >= m {
+                return Some(backtrack(&trace, n, m));
+            }
+        }
+    }
+    None
+}
+
+fn backtrack(trace: &[Vec<isize>], n: isize, m: isize) -> Vec<Edit> {
+    let (mut x, mut y) = (n, m);
+    let mut edits = Vec::new();
+    for (d, v) in trace.iter().enumerate().skip(1).rev() {
+        let d = d as isize;
+        let at = |k: isize| v[(k + d) as usize];
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = at(prev_k);
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            edits.push(Edit::Equal(x as usize, y as usize));
+        }
+        if x == prev_x {
+            y -= 1;
+            edits.push(Edit::Insert(y as usize));
+        } else {
+            x -= 1;
+            edits.push(Edit::Delete(x as usize));
+        }
+    }
+    while x > 0 && y > 0 {
+        x -= 1;
+        y -= 1;
+        edits.push(Edit::Equal(x as usize, y as usize));
+    }
+    edits.reverse();
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff(old: &str, new: &str, options: &DiffOptions) -> FileDiff {
+        FileDiff::between_text(
+            PathBuf::from("a.txt"),
+            PathBuf::from("a.txt"),
+            old,
+            new,
+            options,
+        )
+    }
+
+    #[test]
+    fn test_unified_diff_hunks() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        let new = "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk";
+        let options = DiffOptions {
+            context_lines: 1,
+            ..Default::default()
+        };
+
+        let diff = diff(old, new, &options);
+        assert_eq!((diff.additions, diff.deletions), (2, 1));
+        assert_eq!(
+            diff.unified,
+            "--- a.txt\n+++ a.txt\n\
+             @@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n\
+             @@ -10 +10,2 @@\n j\n+k\n\\ No newline at end of file\n"
+        );
+
+        let created = FileDiff::between_text(
+            PathBuf::from("new.txt"),
+            PathBuf::from("new.txt"),
+            "",
+            "x\n",
+            &options,
+        );
+        assert_eq!(
+            created.unified,
+            "--- /dev/null\n+++ new.txt\n@@ -0,0 +1 @@\n+x\n"
+        );
+        assert!(self::diff(old, old, &options).unified.is_empty());
+    }
+
+    #[test]
+    fn test_binary_size_and_output_limits() {
+        let options = DiffOptions {
+            max_input_bytes: 16,
+            max_output_lines: 4,
+            ..Default::default()
+        };
+
+        let binary = FileDiff::between(
+            PathBuf::from("logo.png"),
+            PathBuf::from("logo.png"),
+            b"\x89PNG\0\x01",
+            b"\x89PNG\0\x02",
+            &options,
+        );
+        assert!(binary.binary && binary.unified.is_empty());
+
+        let large = diff("short\n", &"long line\n".repeat(4), &options);
+        assert!(large.too_large && large.unified.is_empty());
+
+        let truncated = diff("1\n2\n3\n", "4\n5\n6\n", &options);
+        assert!(truncated.truncated);
+        assert_eq!(truncated.unified.lines().count(), 4);
+        assert_eq!((truncated.additions, truncated.deletions), (3, 3));
+    }
+
+    #[test]
+    fn test_edit_script_is_minimal() {
+        let old: Vec<&str> = "a b c a b b a".split(' ').collect();
+        let new: Vec<&str> = "c b a b a c".split(' ').collect();
+        let edits = line_edits(&old, &new);
+        let changes = edits
+            .iter()
+            .filter(|edit| !matches!(edit, Edit::Equal(..)))
+            .count();
+        assert_eq!(changes, 5);
+
+        // Applying the script to the old lines gives the new ones
+        let rebuilt: Vec<&str> = edits
+            .iter()
+            .filter_map(|edit| match *edit {
+                Edit::Equal(i, _) => Some(old[i]),
+                Edit::Insert(j) => Some(new[j]),
+                Edit::Delete(_) => None,
+            })
+            .collect();
+        assert_eq!(rebuilt, new);
+    }
+}
@@ -1,12 +1,22 @@
+use super::conflicts::{unix_now, ConflictResolutionRecord, ResolutionStrategy, SyncConflict};
+use super::diff::{DiffOptions, DiffTarget, FileDiff};
+use super::merger::{MergeManager, MergeResult};
+use super::watch::{
+    IgnoreMatcher, WatchStatus, WorkspaceWatchConfig, DEFAULT_IGNORE, DEFAULT_WORKSPACE,
+};
+use crate::logging::LogContext;
+use crate::{log_info, log_warn};
 use anyhow::{anyhow, Result};
 use notify::{Event, EventKind, RecursiveMode, Watcher};
-use std::collections::{HashMap, VecDeque};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::path::{Path, PathBuf};
-use std::sync::mpsc::{self, Receiver};
-use std::time::{Duration, SystemTime};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant, SystemTime};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ChangeType {
     Created,
     Modified,
@@ -64,6 +74,133 @@ impl FileChange {
     }
 }
 
+/// Quiet period after the last change before a batch is published
+pub const CHANGE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Longest a batch is held back while files keep changing
+pub const MAX_CHANGE_DELAY: Duration = Duration::from_secs(3);
+
+/// Files listed per batch; the rest are only counted
+pub const MAX_BATCH_FILES: usize = 100;
+
+/// A file in a batch of watched changes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangedFile {
+    pub path: PathBuf,
+    pub change_type: ChangeType,
+}
+
+/// Files changed in one workspace, collected until they stop changing
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeBatch {
+    pub workspace: String,
+
+    /// Changed files in path order, at most [`MAX_BATCH_FILES`]
+    pub changes: Vec<ChangedFile>,
+
+    /// Changed files left out of `changes`
+    pub omitted: usize,
+}
+
+#[derive(Debug)]
+struct PendingBatch {
+    first_at: Instant,
+    last_at: Instant,
+    files: BTreeMap<PathBuf, ChangeType>,
+}
+
+/// Groups watch changes per workspace into batches
+///
+/// A batch is ready once no change arrived for the quiet period, or once it
+/// has been held for the maximum delay. Repeated changes to a file collapse
+/// into one entry: a file created and then modified stays `Created`, and a
+/// file created and deleted again within the batch drops out.
+#[derive(Debug)]
+pub struct ChangeDebouncer {
+    quiet: Duration,
+    max_delay: Duration,
+    pending: BTreeMap<String, PendingBatch>,
+}
+
+impl ChangeDebouncer {
+    pub fn new(quiet: Duration, max_delay: Duration) -> Self {
+        Self {
+            quiet,
+            max_delay,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    pub fn push(&mut self, workspace: &str, path: PathBuf, change_type: ChangeType, now: Instant) {
+        let batch = self
+            .pending
+            .entry(workspace.to_string())
+            .or_insert_with(|| PendingBatch {
+                first_at: now,
+                last_at: now,
+                files: BTreeMap::new(),
+            });
+        batch.last_at = now;
+
+        match (batch.files.get(&path), change_type) {
+            (Some(ChangeType::Created), ChangeType::Modified) => {}
+            (Some(ChangeType::Created), ChangeType::Deleted) => {
+                batch.files.remove(&path);
+            }
+            (Some(ChangeType::Deleted), ChangeType::Created) => {
+                batch.files.insert(path, ChangeType::Modified);
+            }
+            (_, change_type) => {
+                batch.files.insert(path, change_type);
+            }
+        }
+    }
+
+    /// Take the batches that are ready at `now`
+    pub fn flush_due(&mut self, now: Instant) -> Vec<ChangeBatch> {
+        let due: Vec<String> = self
+            .pending
+            .iter()
+            .filter(|(_, batch)| {
+                now.duration_since(batch.last_at) >= self.quiet
+                    || now.duration_since(batch.first_at) >= self.max_delay
+            })
+            .map(|(workspace, _)| workspace.clone())
+            .collect();
+
+        due.into_iter()
+            .filter_map(|workspace| {
+                let batch = self.pending.remove(&workspace)?;
+                if batch.files.is_empty() {
+                    return None;
+                }
+                let omitted = batch.files.len().saturating_sub(MAX_BATCH_FILES);
+                let changes = batch
+                    .files
+                    .into_iter()
+                    .take(MAX_BATCH_FILES)
+                    .map(|(path, change_type)| ChangedFile { path, change_type })
+                    .collect();
+                Some(ChangeBatch {
+                    workspace,
+                    changes,
+                    omitted,
+                })
+            })
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+impl Default for ChangeDebouncer {
+    fn default() -> Self {
+        Self::new(CHANGE_DEBOUNCE, MAX_CHANGE_DELAY)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ProcessInfo {
     pub id: Uuid,
@@ -79,6 +216,17 @@ pub struct SyncStats {
     pub last_sync_time: SystemTime,
 }
 
+/// Watched roots of one workspace
+struct WorkspaceWatch {
+    config: WorkspaceWatchConfig,
+
+    /// Canonical roots with the patterns that apply below each
+    roots: Vec<(PathBuf, IgnoreMatcher)>,
+
+    /// Present while the workspace is enabled
+    watcher: Option<notify::RecommendedWatcher>,
+}
+
 pub struct FileSyncManager {
     // ファイル変更履歴
     file_history: HashMap<PathBuf, VecDeque<FileChange>>,
@@ -89,13 +237,22 @@ pub struct FileSyncManager {
     // 登録されたプロセス
     registered_processes: HashMap<Uuid, ProcessInfo>,
 
-    // ファイル監視
-    watcher: Option<notify::RecommendedWatcher>,
-    file_event_receiver: Option<Receiver<notify::Result<Event>>>,
+    // ファイル監視（ワークスペースごとの監視ルートと無視パターン）
+    watches: BTreeMap<String, WorkspaceWatch>,
+    ignore_patterns: Vec<String>,
+    use_gitignore: bool,
+    file_event_sender: Sender<notify::Result<Event>>,
+    file_event_receiver: Receiver<notify::Result<Event>>,
+    change_debouncer: ChangeDebouncer,
 
     // 同期統計
     stats: SyncStats,
 
+    // ユーザーの解決待ちの競合と解決履歴
+    conflicts: Vec<SyncConflict>,
+    resolutions: VecDeque<ConflictResolutionRecord>,
+    merge_manager: MergeManager,
+
     // 設定
     conflict_resolution: ConflictResolution,
     max_history_size: usize,
@@ -105,18 +262,26 @@ pub struct FileSyncManager {
 
 impl FileSyncManager {
     pub fn new() -> Self {
+        let (file_event_sender, file_event_receiver) = mpsc::channel();
         Self {
             file_history: HashMap::new(),
             pending_changes: HashMap::new(),
             registered_processes: HashMap::new(),
-            watcher: None,
-            file_event_receiver: None,
+            watches: BTreeMap::new(),
+            ignore_patterns: DEFAULT_IGNORE.iter().map(|p| p.to_string()).collect(),
+            use_gitignore: true,
+            file_event_sender,
+            file_event_receiver,
+            change_debouncer: ChangeDebouncer::default(),
             stats: SyncStats {
                 total_changes_applied: 0,
                 total_conflicts_detected: 0,
                 average_apply_time: Duration::from_millis(0),
                 last_sync_time: SystemTime::now(),
             },
+            conflicts: Vec::new(),
+            resolutions: VecDeque::new(),
+            merge_manager: MergeManager::new(),
             conflict_resolution: ConflictResolution::PreferLatest,
             max_history_size: 100,
             backup_enabled: true,
@@ -140,24 +305,189 @@ impl FileSyncManager {
         self.pending_changes.remove(&process_id);
     }
 
+    /// Watch `path` as the only root of the default workspace
     pub fn start_watching<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
-        let (tx, rx) = mpsc::channel();
+        let config = WorkspaceWatchConfig {
+            roots: vec![path.as_ref().to_path_buf()],
+            ..WorkspaceWatchConfig::default()
+        };
+        self.watch_workspace(DEFAULT_WORKSPACE, config)
+    }
 
-        let mut watcher = notify::recommended_watcher(tx)?;
-        watcher.watch(path.as_ref(), RecursiveMode::Recursive)?;
+    /// Patterns ignored in every workspace; applies to workspaces watched afterwards
+    pub fn set_ignore_patterns(&mut self, patterns: Vec<String>, use_gitignore: bool) {
+        self.ignore_patterns = patterns;
+        self.use_gitignore = use_gitignore;
+    }
 
-        self.watcher = Some(watcher);
-        self.file_event_receiver = Some(rx);
+    /// Watch (or re-watch) the roots of a workspace
+    ///
+    /// The workspace is registered even when disabled, so it can be enabled
+    /// later with [`set_workspace_enabled`](Self::set_workspace_enabled).
+    pub fn watch_workspace(&mut self, workspace: &str, config: WorkspaceWatchConfig) -> Result<()> {
+        let mut roots = Vec::with_capacity(config.roots.len());
+        for root in &config.roots {
+            let canonical = root
+                .canonicalize()
+                .map_err(|e| anyhow!("Cannot watch {}: {}", root.display(), e))?;
+            if !canonical.is_dir() {
+                return Err(anyhow!("Cannot watch {}: not a directory", root.display()));
+            }
+            roots.push((canonical, IgnoreMatcher::default()));
+        }
 
+        self.watches.remove(workspace);
+        let mut watch = WorkspaceWatch {
+            config,
+            roots,
+            watcher: None,
+        };
+        self.compile_ignore(&mut watch);
+        if watch.config.enabled {
+            watch.watcher = Some(self.start_watcher(&watch)?);
+        }
+        self.watches.insert(workspace.to_string(), watch);
         Ok(())
     }
 
+    /// Stop watching a workspace and forget it
+    pub fn unwatch_workspace(&mut self, workspace: &str) -> bool {
+        self.watches.remove(workspace).is_some()
+    }
+
+    /// Pause or resume watching a registered workspace
+    pub fn set_workspace_enabled(&mut self, workspace: &str, enabled: bool) -> Result<WatchStatus> {
+        let mut watch = self
+            .watches
+            .remove(workspace)
+            .ok_or_else(|| anyhow!("Workspace '{}' is not watched", workspace))?;
+
+        watch.config.enabled = enabled;
+        watch.watcher = None;
+        if enabled {
+            // Pick up .gitignore edits made while paused
+            self.compile_ignore(&mut watch);
+            match self.start_watcher(&watch) {
+                Ok(watcher) => watch.watcher = Some(watcher),
+                Err(e) => {
+                    watch.config.enabled = false;
+                    self.watches.insert(workspace.to_string(), watch);
+                    return Err(e);
+                }
+            }
+        }
+
+        let context = LogContext::new("sync", "watch_toggled")
+            .with_entity_id(workspace)
+            .with_metadata("enabled", serde_json::json!(enabled));
+        log_info!(
+            context,
+            "File watching {} for workspace {}",
+            if enabled { "resumed" } else { "paused" },
+            workspace
+        );
+
+        let status = self.status_of(workspace, &watch);
+        self.watches.insert(workspace.to_string(), watch);
+        Ok(status)
+    }
+
+    /// Watched workspaces in name order
+    pub fn watch_status(&self) -> Vec<WatchStatus> {
+        self.watches
+            .iter()
+            .map(|(workspace, watch)| self.status_of(workspace, watch))
+            .collect()
+    }
+
+    /// Whether changes to `path` are left out of sync
+    ///
+    /// Paths outside every watch root are never ignored.
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        self.watch_for(path)
+            .is_some_and(|(_, matcher, relative)| matcher.is_ignored(&relative, path.is_dir()))
+    }
+
+    fn status_of(&self, workspace: &str, watch: &WorkspaceWatch) -> WatchStatus {
+        WatchStatus {
+            workspace: workspace.to_string(),
+            enabled: watch.config.enabled,
+            roots: watch.roots.iter().map(|(root, _)| root.clone()).collect(),
+            ignore: self
+                .ignore_patterns
+                .iter()
+                .chain(&watch.config.ignore)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    fn compile_ignore(&self, watch: &mut WorkspaceWatch) {
+        for (root, matcher) in &mut watch.roots {
+            *matcher = IgnoreMatcher::new(self.ignore_patterns.iter().chain(&watch.config.ignore));
+            if self.use_gitignore {
+                matcher.add_file(&root.join(".gitignore"));
+            }
+        }
+    }
+
+    fn start_watcher(&self, watch: &WorkspaceWatch) -> Result<notify::RecommendedWatcher> {
+        let mut watcher = notify::recommended_watcher(self.file_event_sender.clone())?;
+        for (root, _) in &watch.roots {
+            watcher.watch(root, RecursiveMode::Recursive)?;
+        }
+        Ok(watcher)
+    }
+
+    /// Innermost watch root containing `path`, with its workspace and the relative path
+    fn watch_for(&self, path: &Path) -> Option<(&WorkspaceWatch, &IgnoreMatcher, PathBuf)> {
+        self.watches
+            .values()
+            .flat_map(|watch| {
+                watch.roots.iter().filter_map(move |(root, matcher)| {
+                    let relative = path.strip_prefix(root).ok()?;
+                    Some((watch, matcher, relative.to_path_buf(), root))
+                })
+            })
+            .max_by_key(|(_, _, _, root)| root.as_os_str().len())
+            .map(|(watch, matcher, relative, _)| (watch, matcher, relative))
+    }
+
+    /// Name of the workspace whose watch root contains `path`
+    fn watch_workspace_of(&self, path: &Path) -> Option<&str> {
+        self.watches
+            .iter()
+            .flat_map(|(workspace, watch)| {
+                watch
+                    .roots
+                    .iter()
+                    .filter(move |(root, _)| path.starts_with(root))
+                    .map(move |(root, _)| (workspace.as_str(), root.as_os_str().len()))
+            })
+            .max_by_key(|(_, root_len)| *root_len)
+            .map(|(workspace, _)| workspace)
+    }
+
+    /// Whether a watch event should become a change
+    fn accepts_event(&self, event: &Event) -> bool {
+        let Some(path) = event.paths.first() else {
+            return false;
+        };
+        match self.watch_for(path) {
+            Some((watch, matcher, relative)) => {
+                watch.config.enabled && !matcher.is_ignored(&relative, path.is_dir())
+            }
+            None => true,
+        }
+    }
+
     pub fn apply_change(&mut self, change: FileChange) -> Result<()> {
         let start_time = SystemTime::now();
 
         // 競合チェック
         if let Some(conflict) = self.detect_conflict(&change)? {
             self.stats.total_conflicts_detected += 1;
+            self.record_conflict(&conflict, &change);
             return Err(anyhow!("Conflict detected: {:?}", conflict));
         }
 
@@ -202,20 +532,49 @@ impl FileSyncManager {
     }
 
     pub fn get_pending_changes(&self) -> Vec<FileChange> {
-        if let Some(receiver) = &self.file_event_receiver {
-            let mut changes = Vec::new();
+        let mut changes = Vec::new();
 
-            // 非ブロッキングで監視イベントを処理
-            while let Ok(Ok(event)) = receiver.try_recv() {
-                if let Some(change) = self.event_to_change(event) {
-                    changes.push(change);
-                }
+        // 非ブロッキングで監視イベントを処理（無視パターンと停止中のワークスペースは除外）
+        while let Ok(Ok(event)) = self.file_event_receiver.try_recv() {
+            if !self.accepts_event(&event) {
+                continue;
+            }
+            if let Some(change) = self.event_to_change(event) {
+                changes.push(change);
             }
+        }
 
-            changes
-        } else {
-            Vec::new()
+        changes
+    }
+
+    /// Drain watch events into per-workspace batches and return those that are ready
+    ///
+    /// Consumes the same events as [`get_pending_changes`](Self::get_pending_changes);
+    /// use one or the other.
+    pub fn collect_change_batches(&mut self) -> Vec<ChangeBatch> {
+        let now = Instant::now();
+        while let Ok(Ok(event)) = self.file_event_receiver.try_recv() {
+            if !self.accepts_event(&event) {
+                continue;
+            }
+            let change_type = match event.kind {
+                EventKind::Create(_) => ChangeType::Created,
+                EventKind::Modify(_) => ChangeType::Modified,
+                EventKind::Remove(_) => ChangeType::Deleted,
+                _ => continue,
+            };
+            let Some(path) = event.paths.first() else {
+                continue;
+            };
+            let workspace = self
+                .watch_workspace_of(path)
+                .unwrap_or(DEFAULT_WORKSPACE)
+                .to_string();
+            let path = self.normalize_path(path);
+            self.change_debouncer
+                .push(&workspace, path, change_type, now);
         }
+        self.change_debouncer.flush_due(now)
     }
 
     pub fn get_file_history(&self, file_path: &Path) -> Option<&VecDeque<FileChange>> {
@@ -234,6 +593,144 @@ impl FileSyncManager {
         }
     }
 
+    /// Unified diffs for review and conflict frontends, so they need no
+    /// filesystem access of their own
+    pub fn diff(&self, target: &DiffTarget, options: &DiffOptions) -> Result<Vec<FileDiff>> {
+        match target {
+            DiffTarget::Files { old_path, new_path } => {
+                Ok(vec![self.diff_files(old_path, new_path, options)?])
+            }
+            DiffTarget::Process { process_id } => self.diff_process(process_id, options),
+            DiffTarget::Conflict { conflict_id } => {
+                let conflict = self
+                    .conflicts
+                    .iter()
+                    .find(|conflict| &conflict.id == conflict_id)
+                    .ok_or_else(|| anyhow!("Conflict '{}' not found", conflict_id))?;
+                let path = conflict.file_path.clone();
+                let theirs = if conflict.theirs_deletes {
+                    ""
+                } else {
+                    &conflict.theirs_content
+                };
+                Ok(vec![
+                    FileDiff::between_text(
+                        path.clone(),
+                        path.clone(),
+                        &conflict.base_content,
+                        &conflict.ours_content,
+                        options,
+                    )
+                    .with_process(conflict.ours_process.clone()),
+                    FileDiff::between_text(
+                        path.clone(),
+                        path,
+                        &conflict.base_content,
+                        theirs,
+                        options,
+                    )
+                    .with_process(conflict.theirs_process.clone()),
+                ])
+            }
+        }
+    }
+
+    /// Diff two files inside the watched roots; a missing file counts as empty
+    fn diff_files(
+        &self,
+        old_path: &Path,
+        new_path: &Path,
+        options: &DiffOptions,
+    ) -> Result<FileDiff> {
+        let old_path = self.resolve_watched(old_path)?;
+        let new_path = self.resolve_watched(new_path)?;
+        if !old_path.is_file() && !new_path.is_file() {
+            return Err(anyhow!(
+                "Neither {} nor {} is a file",
+                old_path.display(),
+                new_path.display()
+            ));
+        }
+
+        // Large files are not read at all
+        let size = |path: &Path| std::fs::metadata(path).map_or(0, |metadata| metadata.len());
+        if size(&old_path).max(size(&new_path)) > options.max_input_bytes as u64 {
+            return Ok(FileDiff {
+                old_path,
+                new_path,
+                too_large: true,
+                ..Default::default()
+            });
+        }
+        let read = |path: &Path| {
+            if path.is_file() {
+                std::fs::read(path)
+            } else {
+                Ok(Vec::new())
+            }
+        };
+        let (old, new) = (read(&old_path)?, read(&new_path)?);
+        Ok(FileDiff::between(old_path, new_path, &old, &new, options))
+    }
+
+    /// Files changed by a process, from before its first change to after its last one
+    fn diff_process(&self, process_id: &str, options: &DiffOptions) -> Result<Vec<FileDiff>> {
+        let process = Uuid::parse_str(process_id)
+            .map_err(|_| anyhow!("Invalid process ID: {}", process_id))?;
+        let content = |change: &FileChange| match change.change_type {
+            ChangeType::Deleted => String::new(),
+            _ => change.content.clone(),
+        };
+
+        let mut diffs: Vec<FileDiff> = self
+            .file_history
+            .iter()
+            .filter_map(|(path, history)| {
+                let first = history.iter().position(|c| c.process_id == process)?;
+                let last = history.iter().rposition(|c| c.process_id == process)?;
+                // As for conflicts, the entry before the change is its base
+                let before = first
+                    .checked_sub(1)
+                    .map(|previous| content(&history[previous]))
+                    .unwrap_or_default();
+                let diff = FileDiff::between_text(
+                    path.clone(),
+                    path.clone(),
+                    &before,
+                    &content(&history[last]),
+                    options,
+                );
+                Some(diff.with_process(process_id))
+            })
+            .collect();
+        diffs.sort_by(|a, b| a.new_path.cmp(&b.new_path));
+        Ok(diffs)
+    }
+
+    /// Canonical path inside a watch root; missing files resolve through their directory
+    fn resolve_watched(&self, path: &Path) -> Result<PathBuf> {
+        let resolved = match path.canonicalize() {
+            Ok(resolved) => resolved,
+            Err(_) => {
+                let name = path
+                    .file_name()
+                    .ok_or_else(|| anyhow!("Invalid file path: {}", path.display()))?;
+                let parent = path
+                    .parent()
+                    .filter(|parent| !parent.as_os_str().is_empty())
+                    .unwrap_or(Path::new("."));
+                parent
+                    .canonicalize()
+                    .map_err(|e| anyhow!("Cannot read {}: {}", path.display(), e))?
+                    .join(name)
+            }
+        };
+        if self.watch_for(&resolved).is_none() {
+            return Err(anyhow!("{} is outside the watched roots", path.display()));
+        }
+        Ok(resolved)
+    }
+
     pub fn create_backup(&self, file_path: &Path) -> Result<()> {
         if !file_path.exists() {
             return Ok(());
@@ -305,6 +802,134 @@ impl FileSyncManager {
         &self.stats
     }
 
+    /// Conflicts waiting for a resolution, oldest first
+    pub fn list_conflicts(&self) -> &[SyncConflict] {
+        &self.conflicts
+    }
+
+    /// Resolved conflicts, oldest first
+    pub fn resolution_history(&self) -> &VecDeque<ConflictResolutionRecord> {
+        &self.resolutions
+    }
+
+    /// Settle a conflict and write the chosen content to the file
+    ///
+    /// `content` is required for [`ResolutionStrategy::Manual`] and ignored
+    /// otherwise. A merge that still overlaps leaves the conflict open.
+    pub fn resolve_conflict(
+        &mut self,
+        conflict_id: &str,
+        strategy: ResolutionStrategy,
+        content: Option<String>,
+        resolved_by: &str,
+    ) -> Result<ConflictResolutionRecord> {
+        let index = self
+            .conflicts
+            .iter()
+            .position(|conflict| conflict.id == conflict_id)
+            .ok_or_else(|| anyhow!("Conflict not found: {}", conflict_id))?;
+        let conflict = &self.conflicts[index];
+
+        // None deletes the file
+        let resolved = match strategy {
+            ResolutionStrategy::Ours => Some(conflict.ours_content.clone()),
+            ResolutionStrategy::Theirs if conflict.theirs_deletes => None,
+            ResolutionStrategy::Theirs => Some(conflict.theirs_content.clone()),
+            ResolutionStrategy::Merge => match self.merge_manager.merge_content(
+                &conflict.file_path,
+                &conflict.base_content,
+                &conflict.ours_content,
+                &conflict.theirs_content,
+            )? {
+                MergeResult::Success(mut merged) => {
+                    // The line merge drops the final newline
+                    if conflict.ours_content.ends_with('\n')
+                        && conflict.theirs_content.ends_with('\n')
+                        && !merged.ends_with('\n')
+                    {
+                        merged.push('\n');
+                    }
+                    Some(merged)
+                }
+                MergeResult::Conflict(info) => {
+                    self.conflicts[index].conflict_type = info.conflict_type;
+                    return Err(anyhow!(
+                        "Changes to {:?} overlap and cannot be merged automatically",
+                        info.file_path
+                    ));
+                }
+            },
+            ResolutionStrategy::Manual => {
+                Some(content.ok_or_else(|| anyhow!("Manual resolution requires content"))?)
+            }
+        };
+
+        let conflict = self.conflicts.remove(index);
+        if self.backup_enabled && conflict.file_path.exists() {
+            self.create_backup(&conflict.file_path)?;
+        }
+        let change = match &resolved {
+            Some(content) => {
+                if let Some(parent) = conflict.file_path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&conflict.file_path, content)?;
+                FileChange::new(
+                    conflict.file_path.clone(),
+                    ChangeType::Modified,
+                    content.clone(),
+                    SystemTime::now(),
+                    Uuid::nil(),
+                )
+            }
+            None => {
+                if conflict.file_path.exists() {
+                    std::fs::remove_file(&conflict.file_path)?;
+                }
+                FileChange::new(
+                    conflict.file_path.clone(),
+                    ChangeType::Deleted,
+                    String::new(),
+                    SystemTime::now(),
+                    Uuid::nil(),
+                )
+            }
+        };
+        // Every process, including both sides of the conflict, receives the result
+        self.add_to_history(change.clone());
+        self.propagate_change_to_processes(&change);
+
+        let record = ConflictResolutionRecord {
+            conflict_id: conflict.id,
+            file_path: conflict.file_path,
+            strategy,
+            resolved_by: resolved_by.to_string(),
+            resolved_at: unix_now(),
+            bytes_written: resolved.as_ref().map(String::len),
+        };
+        let audit_context = LogContext::new("sync", "conflict_resolved")
+            .with_entity_id(&record.conflict_id)
+            .with_metadata("file_path", serde_json::json!(record.file_path))
+            .with_metadata("strategy", serde_json::json!(strategy.as_str()))
+            .with_metadata("resolved_by", serde_json::json!(record.resolved_by));
+        log_info!(
+            audit_context,
+            "Resolved conflict on {:?} with {}",
+            record.file_path,
+            strategy.as_str()
+        );
+
+        self.resolutions.push_back(record.clone());
+        while self.resolutions.len() > self.max_history_size {
+            self.resolutions.pop_front();
+        }
+        Ok(record)
+    }
+
+    pub fn set_backup_directory(&mut self, directory: PathBuf) {
+        self.backup_directory = directory;
+    }
+
     pub fn set_conflict_resolution(&mut self, strategy: ConflictResolution) {
         self.conflict_resolution = strategy;
     }
@@ -346,6 +971,31 @@ impl FileSyncManager {
         Ok(None)
     }
 
+    /// Keep a rejected change so the user can resolve it later
+    fn record_conflict(&mut self, ours: &FileChange, theirs: &FileChange) {
+        // The entry before the accepted change is the common base
+        let base_content = self
+            .file_history
+            .get(&ours.file_path)
+            .and_then(|history| history.iter().rev().nth(1))
+            .map(|previous| previous.content.clone())
+            .unwrap_or_default();
+        let conflict = SyncConflict::new(&base_content, ours, theirs);
+
+        let detected_context = LogContext::new("sync", "conflict_detected")
+            .with_entity_id(&conflict.id)
+            .with_metadata("file_path", serde_json::json!(conflict.file_path))
+            .with_metadata("ours_process", serde_json::json!(conflict.ours_process))
+            .with_metadata("theirs_process", serde_json::json!(conflict.theirs_process));
+        log_warn!(
+            detected_context,
+            "Conflicting change to {:?} held for resolution",
+            conflict.file_path
+        );
+
+        self.conflicts.push(conflict);
+    }
+
     fn add_to_history(&mut self, change: FileChange) {
         let history = self
             .file_history
@@ -458,7 +1108,6 @@ impl Default for FileSyncManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    #[allow(unused_imports)]
     use tempfile::TempDir;
 
     #[test]
@@ -493,4 +1142,261 @@ mod tests {
         assert!(manager.registered_processes.contains_key(&process_id));
         assert!(manager.pending_changes.contains_key(&process_id));
     }
+
+    fn conflicting_manager(temp_dir: &TempDir, ours: &str, theirs: &str) -> FileSyncManager {
+        let mut manager = FileSyncManager::new();
+        manager.set_backup_directory(temp_dir.path().join("backups"));
+        let path = temp_dir.path().join("lib.rs");
+        let now = SystemTime::now();
+        let ours_process = Uuid::new_v4();
+
+        let base = FileChange::new(
+            path.clone(),
+            ChangeType::Modified,
+            "a\nb\nc\n".to_string(),
+            now,
+            ours_process,
+        );
+        manager.apply_change(base).unwrap();
+        let ours = FileChange::new(
+            path.clone(),
+            ChangeType::Modified,
+            ours.to_string(),
+            now + Duration::from_millis(1),
+            ours_process,
+        );
+        manager.apply_change(ours).unwrap();
+        let theirs = FileChange::new(
+            path,
+            ChangeType::Modified,
+            theirs.to_string(),
+            now + Duration::from_millis(2),
+            Uuid::new_v4(),
+        );
+        assert!(manager.apply_change(theirs).is_err());
+        manager
+    }
+
+    #[test]
+    fn test_conflict_is_held_and_resolved_with_theirs() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = conflicting_manager(&temp_dir, "A\nb\nc\n", "a\nb\nC\n");
+        let process = Uuid::new_v4();
+        manager.register_process(process);
+
+        let conflict = manager.list_conflicts()[0].clone();
+        assert_eq!(conflict.base_content, "a\nb\nc\n");
+        assert_eq!(conflict.ours_content, "A\nb\nc\n");
+        assert_eq!(conflict.theirs_content, "a\nb\nC\n");
+
+        let record = manager
+            .resolve_conflict(&conflict.id, ResolutionStrategy::Theirs, None, "cli")
+            .unwrap();
+        assert_eq!(record.resolved_by, "cli");
+        assert!(manager.list_conflicts().is_empty());
+        assert_eq!(manager.resolution_history().len(), 1);
+        assert_eq!(
+            std::fs::read_to_string(&conflict.file_path).unwrap(),
+            "a\nb\nC\n"
+        );
+        // The resolution reaches every process and the overwritten file is backed up
+        assert_eq!(manager.get_changes_for_process(process).len(), 1);
+        assert!(temp_dir.path().join("backups").exists());
+
+        let missing = manager.resolve_conflict(&conflict.id, ResolutionStrategy::Ours, None, "cli");
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn test_merge_and_manual_resolution() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = conflicting_manager(&temp_dir, "A\nb\nc\n", "a\nb\nC\n");
+        let id = manager.list_conflicts()[0].id.clone();
+        manager
+            .resolve_conflict(&id, ResolutionStrategy::Merge, None, "dashboard")
+            .unwrap();
+        let path = temp_dir.path().join("lib.rs");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "A\nb\nC\n");
+
+        // Both sides changed the same line: the merge fails and the conflict stays open
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = conflicting_manager(&temp_dir, "X\nb\nc\n", "Y\nb\nc\n");
+        let id = manager.list_conflicts()[0].id.clone();
+        assert!(manager
+            .resolve_conflict(&id, ResolutionStrategy::Merge, None, "dashboard")
+            .is_err());
+        assert!(manager
+            .resolve_conflict(&id, ResolutionStrategy::Manual, None, "dashboard")
+            .is_err());
+        assert_eq!(manager.list_conflicts().len(), 1);
+
+        manager
+            .resolve_conflict(
+                &id,
+                ResolutionStrategy::Manual,
+                Some("XY\nb\nc\n".to_string()),
+                "dashboard",
+            )
+            .unwrap();
+        let path = temp_dir.path().join("lib.rs");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "XY\nb\nc\n");
+    }
+
+    #[test]
+    fn test_diffs_for_conflicts_processes_and_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = conflicting_manager(&temp_dir, "a\nB\nc\n", "a\nb\nC\n");
+        let options = DiffOptions::default();
+
+        let conflict = manager.list_conflicts()[0].clone();
+        let target = DiffTarget::Conflict {
+            conflict_id: conflict.id.clone(),
+        };
+        let diffs = manager.diff(&target, &options).unwrap();
+        assert_eq!(diffs.len(), 2);
+        assert!(diffs[0].unified.contains("-b\n+B\n"));
+        assert!(diffs[1].unified.contains("-c\n+C\n"));
+        assert_eq!(diffs[1].process_id, Some(conflict.theirs_process));
+
+        // The process wrote the file twice; its change set spans both writes
+        let target = DiffTarget::Process {
+            process_id: conflict.ours_process,
+        };
+        let diffs = manager.diff(&target, &options).unwrap();
+        assert_eq!(diffs.len(), 1);
+        assert_eq!((diffs[0].additions, diffs[0].deletions), (3, 0));
+
+        let old_path = temp_dir.path().join("lib.rs");
+        let new_path = temp_dir.path().join("lib_new.rs");
+        std::fs::write(&new_path, "a\nB\nc\nd\n").unwrap();
+        let target = DiffTarget::Files { old_path, new_path };
+        assert!(manager.diff(&target, &options).is_err());
+
+        manager
+            .watch_workspace(
+                "app",
+                WorkspaceWatchConfig {
+                    roots: vec![temp_dir.path().to_path_buf()],
+                    enabled: false,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let diffs = manager.diff(&target, &options).unwrap();
+        assert_eq!((diffs[0].additions, diffs[0].deletions), (1, 0));
+        assert!(diffs[0].unified.ends_with(" c\n+d\n"));
+    }
+
+    #[test]
+    fn test_workspace_roots_ignore_and_toggle() {
+        let temp_dir = TempDir::new().unwrap();
+        let web = temp_dir.path().join("web");
+        let server = temp_dir.path().join("server");
+        std::fs::create_dir_all(web.join("node_modules")).unwrap();
+        std::fs::create_dir_all(&server).unwrap();
+        std::fs::write(server.join(".gitignore"), "*.log\n").unwrap();
+
+        let mut manager = FileSyncManager::new();
+        manager
+            .watch_workspace(
+                "app",
+                WorkspaceWatchConfig {
+                    roots: vec![web.clone(), server.clone()],
+                    ignore: vec!["/dist/".to_string()],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let web = web.canonicalize().unwrap();
+        let server = server.canonicalize().unwrap();
+        assert!(manager.is_ignored(&web.join("node_modules/react/index.js")));
+        assert!(manager.is_ignored(&web.join("dist/bundle.js")));
+        assert!(!manager.is_ignored(&web.join("src/dist/bundle.js")));
+        assert!(manager.is_ignored(&server.join("debug.log")));
+        assert!(!manager.is_ignored(&web.join("debug.log")));
+        assert!(!manager.is_ignored(&server.join("main.rs")));
+
+        let status = manager.set_workspace_enabled("app", false).unwrap();
+        assert!(!status.enabled);
+        assert_eq!(status.roots, vec![web, server]);
+        assert!(status.ignore.contains(&"/dist/".to_string()));
+        assert!(manager.set_workspace_enabled("app", true).unwrap().enabled);
+        assert!(manager.set_workspace_enabled("missing", true).is_err());
+
+        assert!(manager
+            .watch_workspace(
+                "gone",
+                WorkspaceWatchConfig {
+                    roots: vec![temp_dir.path().join("missing")],
+                    ..Default::default()
+                },
+            )
+            .is_err());
+        assert_eq!(manager.watch_status().len(), 1);
+    }
+
+    #[test]
+    fn test_change_debouncer_batches_per_workspace() {
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let mut debouncer =
+            ChangeDebouncer::new(Duration::from_millis(500), Duration::from_secs(3));
+
+        debouncer.push("web", PathBuf::from("a.rs"), ChangeType::Created, at(0));
+        debouncer.push("web", PathBuf::from("a.rs"), ChangeType::Modified, at(100));
+        debouncer.push("web", PathBuf::from("tmp.rs"), ChangeType::Created, at(150));
+        debouncer.push("web", PathBuf::from("tmp.rs"), ChangeType::Deleted, at(200));
+        debouncer.push("web", PathBuf::from("b.rs"), ChangeType::Deleted, at(250));
+        debouncer.push("web", PathBuf::from("b.rs"), ChangeType::Created, at(300));
+        assert!(debouncer.flush_due(at(600)).is_empty());
+
+        let batches = debouncer.flush_due(at(800));
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].workspace, "web");
+        assert_eq!(
+            batches[0].changes,
+            vec![
+                ChangedFile {
+                    path: PathBuf::from("a.rs"),
+                    change_type: ChangeType::Created,
+                },
+                ChangedFile {
+                    path: PathBuf::from("b.rs"),
+                    change_type: ChangeType::Modified,
+                },
+            ]
+        );
+        assert!(debouncer.is_empty());
+
+        // A steady stream is still published after the maximum delay
+        for ms in (0..3000).step_by(400) {
+            debouncer.push(
+                "api",
+                PathBuf::from(format!("f{ms}.rs")),
+                ChangeType::Modified,
+                at(ms),
+            );
+        }
+        assert!(debouncer.flush_due(at(2900)).is_empty());
+        assert_eq!(debouncer.flush_due(at(3000))[0].changes.len(), 8);
+
+        // Created then deleted leaves nothing to publish
+        debouncer.push("api", PathBuf::from("x.rs"), ChangeType::Created, at(4000));
+        debouncer.push("api", PathBuf::from("x.rs"), ChangeType::Deleted, at(4100));
+        assert!(debouncer.flush_due(at(5000)).is_empty());
+        assert!(debouncer.is_empty());
+
+        for i in 0..MAX_BATCH_FILES + 5 {
+            debouncer.push(
+                "api",
+                PathBuf::from(format!("{i:04}.rs")),
+                ChangeType::Modified,
+                at(6000),
+            );
+        }
+        let batch = &debouncer.flush_due(at(7000))[0];
+        assert_eq!(batch.changes.len(), MAX_BATCH_FILES);
+        assert_eq!(batch.omitted, 5);
+    }
 }
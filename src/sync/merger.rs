@@ -1,11 +1,12 @@
 use super::file_sync::ConflictResolution;
 use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ConflictType {
     ContentConflict,
     StructuralConflict,
@@ -379,18 +380,13 @@ impl MergeManager {
         for ch in content.chars() {
             match ch {
                 '(' | '[' | '{' => stack.push(ch),
-                ')' => {
-                    if stack.pop() != Some('(') {
-                        return Ok(false);
-                    }
-                }
-                ']' => {
-                    if stack.pop() != Some('[') {
-                        return Ok(false);
-                    }
-                }
-                '}' => {
-                    if stack.pop() != Some('{') {
+                ')' | ']' | '}' => {
+                    let open = match ch {
+                        ')' => '(',
+                        ']' => '[',
+                        _ => '{',
+                    };
+                    if stack.pop() != Some(open) {
                         return Ok(false);
                     }
                 }
@@ -1,5 +1,11 @@
+pub mod conflicts;
+pub mod diff;
 pub mod file_sync;
 pub mod merger;
+pub mod watch;
 
-pub use file_sync::{ChangeType, FileChange, FileSyncManager};
+pub use conflicts::{ConflictResolutionRecord, ResolutionStrategy, SyncConflict};
+pub use diff::{DiffOptions, DiffTarget, FileDiff};
+pub use file_sync::{ChangeBatch, ChangeType, ChangedFile, FileChange, FileSyncManager};
 pub use merger::{ConflictType, MergeManager, MergeResult};
+pub use watch::{IgnoreMatcher, SyncConfig, WatchStatus, WorkspaceWatchConfig};
@@ -0,0 +1,316 @@
+// WezTerm Multi-Process Development Framework - Sync Watch Configuration
+// Watch roots per workspace and .gitignore-style patterns for files that are never synced
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+/// Workspace watched when no workspaces are configured
+pub const DEFAULT_WORKSPACE: &str = "default";
+
+/// Build output, VCS metadata and dependencies are noise for every project
+pub const DEFAULT_IGNORE: [&str; 4] = [
+    ".git/",
+    "target/",
+    "node_modules/",
+    ".wezterm-parallel-backups/",
+];
+
+/// File sync watching
+///
+/// ```yaml
+/// sync:
+///   ignore: ["*.log", "dist/"]
+///   workspaces:
+///     frontend:
+///       roots: [./web, ./shared]
+///     backend:
+///       roots: [./server]
+///       ignore: ["/fixtures/**/*.json"]
+///       enabled: false
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncConfig {
+    /// Watch files at all
+    pub enabled: bool,
+
+    /// Patterns ignored in every workspace, in `.gitignore` syntax
+    pub ignore: Vec<String>,
+
+    /// Also apply the `.gitignore` at the top of each watch root
+    pub use_gitignore: bool,
+
+    /// Watched workspaces by name; when empty the current directory is
+    /// watched as the `default` workspace
+    pub workspaces: BTreeMap<String, WorkspaceWatchConfig>,
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ignore: DEFAULT_IGNORE.iter().map(|p| p.to_string()).collect(),
+            use_gitignore: true,
+            workspaces: BTreeMap::new(),
+        }
+    }
+}
+
+impl SyncConfig {
+    /// Workspaces to watch, falling back to the current directory
+    pub fn workspace_watches(&self) -> Vec<(String, WorkspaceWatchConfig)> {
+        if self.workspaces.is_empty() {
+            return vec![(
+                DEFAULT_WORKSPACE.to_string(),
+                WorkspaceWatchConfig::default(),
+            )];
+        }
+        self.workspaces
+            .iter()
+            .map(|(name, watch)| (name.clone(), watch.clone()))
+            .collect()
+    }
+}
+
+/// Watch settings of one workspace
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkspaceWatchConfig {
+    /// Start watching at startup; can be toggled at runtime over IPC
+    pub enabled: bool,
+
+    /// Directories watched recursively
+    pub roots: Vec<PathBuf>,
+
+    /// Patterns ignored in this workspace in addition to the global ones
+    pub ignore: Vec<String>,
+}
+
+impl Default for WorkspaceWatchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            roots: vec![PathBuf::from(".")],
+            ignore: Vec::new(),
+        }
+    }
+}
+
+/// Watch state of a workspace as reported to clients
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchStatus {
+    pub workspace: String,
+    pub enabled: bool,
+    pub roots: Vec<PathBuf>,
+
+    /// Configured patterns, global ones first (without `.gitignore` entries)
+    pub ignore: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct IgnoreRule {
+    segments: Vec<String>,
+    negated: bool,
+    dir_only: bool,
+
+    /// Pattern contains a slash, so it matches from the root instead of any name
+    anchored: bool,
+}
+
+/// Compiled `.gitignore`-style patterns
+///
+/// Supports `#` comments, `!` negation, a trailing `/` for directories, a
+/// leading or inner `/` to anchor at the root, and `*`, `?` and `**`
+/// wildcards. As in git, the last matching pattern wins and nothing inside
+/// an ignored directory can be re-included.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IgnoreMatcher {
+    rules: Vec<IgnoreRule>,
+}
+
+impl IgnoreMatcher {
+    pub fn new<S: AsRef<str>>(patterns: impl IntoIterator<Item = S>) -> Self {
+        let mut matcher = Self::default();
+        for pattern in patterns {
+            matcher.add(pattern.as_ref());
+        }
+        matcher
+    }
+
+    /// Add one pattern line; blank lines and comments are skipped
+    pub fn add(&mut self, line: &str) {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return;
+        }
+        let (negated, pattern) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let (dir_only, pattern) = match pattern.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, pattern),
+        };
+        let anchored = pattern.contains('/');
+        let segments: Vec<String> = pattern
+            .trim_start_matches('/')
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(str::to_string)
+            .collect();
+        if segments.is_empty() {
+            return;
+        }
+        self.rules.push(IgnoreRule {
+            segments,
+            negated,
+            dir_only,
+            anchored,
+        });
+    }
+
+    /// Add the patterns of a `.gitignore` file, if it exists
+    pub fn add_file(&mut self, path: &Path) {
+        if let Ok(content) = std::fs::read_to_string(path) {
+            content.lines().for_each(|line| self.add(line));
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Whether a path relative to the watch root is ignored
+    pub fn is_ignored(&self, relative: &Path, is_dir: bool) -> bool {
+        let components: Vec<String> = relative
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+                _ => None,
+            })
+            .collect();
+
+        for depth in 1..=components.len() {
+            let last = depth == components.len();
+            let ignored = self.decide(&components[..depth], !last || is_dir);
+            if ignored || last {
+                return ignored;
+            }
+        }
+        false
+    }
+
+    fn decide(&self, path: &[String], is_dir: bool) -> bool {
+        let matching = self.rules.iter().rev().find(|rule| {
+            if rule.dir_only && !is_dir {
+                return false;
+            }
+            if rule.anchored {
+                match_segments(&rule.segments, path)
+            } else {
+                path.last()
+                    .is_some_and(|name| match_name(&rule.segments[0], name))
+            }
+        });
+        matching.is_some_and(|rule| !rule.negated)
+    }
+}
+
+/// Match path segments against pattern segments, where `**` spans any number
+fn match_segments(pattern: &[String], path: &[String]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((first, rest)) if first == "**" => {
+            (0..=path.len()).any(|skip| match_segments(rest, &path[skip..]))
+        }
+        Some((first, rest)) => path
+            .split_first()
+            .is_some_and(|(name, tail)| match_name(first, name) && match_segments(rest, tail)),
+    }
+}
+
+/// Match one name against a pattern with `*` and `?`
+fn match_name(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    n = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ignored(matcher: &IgnoreMatcher, path: &str) -> bool {
+        matcher.is_ignored(Path::new(path), false)
+    }
+
+    #[test]
+    fn test_gitignore_patterns() {
+        let matcher = IgnoreMatcher::new([
+            "# build output",
+            "target/",
+            "*.log",
+            "!keep.log",
+            "/docs/*.pdf",
+            "assets/**/*.png",
+        ]);
+
+        assert!(ignored(&matcher, "target/debug/app"));
+        assert!(ignored(&matcher, "crates/core/target/x.rs"));
+        assert!(!ignored(&matcher, "src/target.rs"));
+
+        assert!(ignored(&matcher, "logs/server.log"));
+        assert!(!ignored(&matcher, "logs/keep.log"));
+
+        assert!(ignored(&matcher, "docs/manual.pdf"));
+        assert!(!ignored(&matcher, "src/docs/manual.pdf"));
+
+        assert!(ignored(&matcher, "assets/icon.png"));
+        assert!(ignored(&matcher, "assets/img/x/icon.png"));
+        assert!(!ignored(&matcher, "icon.png"));
+    }
+
+    #[test]
+    fn test_ignored_directory_cannot_be_reincluded() {
+        let matcher = IgnoreMatcher::new(["build/", "!build/keep.txt"]);
+        assert!(ignored(&matcher, "build/keep.txt"));
+        assert!(matcher.is_ignored(Path::new("build"), true));
+        assert!(!matcher.is_ignored(Path::new("build"), false));
+    }
+
+    #[test]
+    fn test_default_workspace_watch() {
+        let config: SyncConfig = serde_yaml::from_str("ignore: ['*.tmp']").unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.ignore, vec!["*.tmp".to_string()]);
+
+        let watches = config.workspace_watches();
+        assert_eq!(watches.len(), 1);
+        assert_eq!(watches[0].0, DEFAULT_WORKSPACE);
+        assert_eq!(watches[0].1.roots, vec![PathBuf::from(".")]);
+    }
+}
@@ -0,0 +1,189 @@
+// WezTerm Multi-Process Development Framework - Batch Task Submission
+// Several tasks queued in one call, with dependencies between them
+
+use super::types::{Task, TaskId};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A task of a batch, with the same fields as a single `TaskQueue` message
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct BatchTask {
+    /// Name other tasks of the batch refer to it by; defaults to its index
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    pub command: String,
+    #[serde(default = "default_priority")]
+    pub priority: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shell: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+
+    /// Keys of tasks in the batch, or IDs of existing tasks, to wait for
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+}
+
+fn default_priority() -> u8 {
+    5
+}
+
+/// Outcome of one task of a batch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchItemResult {
+    pub key: String,
+
+    /// ID of the created task
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<TaskId>,
+
+    /// Why the task was not created, or was rolled back
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Outcome of a batch, with one result per task in submission order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchReport {
+    pub atomic: bool,
+    pub created: usize,
+    pub failed: usize,
+    pub results: Vec<BatchItemResult>,
+}
+
+impl BatchReport {
+    pub fn new(atomic: bool, results: Vec<BatchItemResult>) -> Self {
+        let created = results.iter().filter(|r| r.task_id.is_some()).count();
+        Self {
+            atomic,
+            created,
+            failed: results.len() - created,
+            results,
+        }
+    }
+}
+
+/// A task of a batch ready to be created, or why it could not be built
+#[derive(Debug)]
+pub struct BatchItem {
+    pub key: String,
+    pub task: Result<Task, String>,
+    pub depends_on: Vec<String>,
+}
+
+/// Order in which to create the items so that every item comes after the
+/// batch items it depends on
+///
+/// Items that already have an error are left out; so are items with a
+/// duplicate key or in a dependency cycle, which get an error in `results`.
+/// Dependents of a left-out item are still ordered and fail on creation.
+pub(crate) fn creation_order(items: &[BatchItem], results: &mut [BatchItemResult]) -> Vec<usize> {
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    for (i, item) in items.iter().enumerate() {
+        if index.contains_key(item.key.as_str()) {
+            results[i].error = Some(format!("Duplicate key '{}'", item.key));
+        } else {
+            index.insert(&item.key, i);
+        }
+    }
+
+    let mut placed = vec![false; items.len()];
+    let mut order = Vec::with_capacity(items.len());
+    loop {
+        let mut progressed = false;
+        for (i, item) in items.iter().enumerate() {
+            if placed[i] || results[i].error.is_some() {
+                continue;
+            }
+            let ready = item
+                .depends_on
+                .iter()
+                .filter_map(|dependency| index.get(dependency.as_str()))
+                .all(|&dependency| {
+                    dependency != i && (placed[dependency] || results[dependency].error.is_some())
+                });
+            if ready {
+                placed[i] = true;
+                order.push(i);
+                progressed = true;
+            }
+        }
+        if !progressed {
+            break;
+        }
+    }
+
+    for (i, result) in results.iter_mut().enumerate() {
+        if !placed[i] && result.error.is_none() {
+            result.error = Some("Dependency cycle".to_string());
+        }
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::TaskCategory;
+
+    fn item(key: &str, depends_on: &[&str]) -> BatchItem {
+        BatchItem {
+            key: key.to_string(),
+            task: Ok(Task::new(key.to_string(), TaskCategory::Development)),
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+        }
+    }
+
+    fn results(items: &[BatchItem]) -> Vec<BatchItemResult> {
+        items
+            .iter()
+            .map(|item| BatchItemResult {
+                key: item.key.clone(),
+                task_id: None,
+                error: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_dependencies_come_first() {
+        let items = vec![
+            item("deploy", &["test", "build"]),
+            item("test", &["build", "existing-task"]),
+            item("build", &[]),
+        ];
+        let mut results = results(&items);
+        assert_eq!(creation_order(&items, &mut results), [2, 1, 0]);
+        assert!(results.iter().all(|r| r.error.is_none()));
+    }
+
+    #[test]
+    fn test_cycles_and_duplicates_are_rejected() {
+        let items = vec![
+            item("a", &["b"]),
+            item("b", &["a"]),
+            item("c", &["c"]),
+            item("d", &[]),
+            item("d", &[]),
+        ];
+        let mut results = results(&items);
+        assert_eq!(creation_order(&items, &mut results), [3]);
+        let errors: Vec<_> = results.iter().map(|r| r.error.as_deref()).collect();
+        assert_eq!(
+            errors,
+            [
+                Some("Dependency cycle"),
+                Some("Dependency cycle"),
+                Some("Dependency cycle"),
+                None,
+                Some("Duplicate key 'd'"),
+            ]
+        );
+    }
+}
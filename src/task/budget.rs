@@ -0,0 +1,236 @@
+// WezTerm Multi-Process Development Framework - Time Budgets
+// Weekly hour budgets per task category or workspace, with burn-down status
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Share of a budget at which a warning is raised by default
+const DEFAULT_WARN_AT: f64 = 0.9;
+
+/// What a budget applies to
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetScope {
+    /// Tasks of a `TaskCategory` (e.g. `Development`)
+    Category(String),
+
+    /// Tasks belonging to a workspace
+    Workspace(String),
+}
+
+impl BudgetScope {
+    /// Whether a task with the given category and workspace counts towards this budget
+    pub fn matches(&self, category: Option<&str>, workspace: Option<&str>) -> bool {
+        match self {
+            BudgetScope::Category(name) => category.is_some_and(|c| c.eq_ignore_ascii_case(name)),
+            BudgetScope::Workspace(name) => workspace == Some(name.as_str()),
+        }
+    }
+}
+
+impl fmt::Display for BudgetScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BudgetScope::Category(name) => write!(f, "{name}"),
+            BudgetScope::Workspace(name) => write!(f, "@{name}"),
+        }
+    }
+}
+
+/// Weekly time budget
+///
+/// ```yaml
+/// budgets:
+///   - category: Development
+///     weekly_hours: 20
+///   - workspace: frontend
+///     weekly_hours: 8
+///     warn_at: 0.75
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeBudget {
+    #[serde(flatten)]
+    pub scope: BudgetScope,
+
+    /// Hours available per ISO week
+    pub weekly_hours: f64,
+
+    /// Share of the budget (0.0-1.0) at which to warn
+    #[serde(default = "default_warn_at")]
+    pub warn_at: f64,
+}
+
+fn default_warn_at() -> f64 {
+    DEFAULT_WARN_AT
+}
+
+impl TimeBudget {
+    /// Budget for a task category
+    pub fn category(name: impl Into<String>, weekly_hours: f64) -> Self {
+        Self {
+            scope: BudgetScope::Category(name.into()),
+            weekly_hours,
+            warn_at: DEFAULT_WARN_AT,
+        }
+    }
+
+    /// Budget for a workspace
+    pub fn workspace(name: impl Into<String>, weekly_hours: f64) -> Self {
+        Self {
+            scope: BudgetScope::Workspace(name.into()),
+            weekly_hours,
+            warn_at: DEFAULT_WARN_AT,
+        }
+    }
+
+    /// Budget in seconds
+    pub fn budget_secs(&self) -> u64 {
+        (self.weekly_hours.max(0.0) * 3600.0).round() as u64
+    }
+
+    /// Burn state for the given usage
+    pub fn state_for(&self, used_secs: u64) -> BudgetState {
+        let budget = self.budget_secs();
+        if used_secs >= budget {
+            BudgetState::Exceeded
+        } else if used_secs as f64 >= budget as f64 * self.warn_at.clamp(0.0, 1.0) {
+            BudgetState::Warning
+        } else {
+            BudgetState::Ok
+        }
+    }
+}
+
+/// Burn state of a budget, ordered by severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetState {
+    Ok,
+    Warning,
+    Exceeded,
+}
+
+/// Live burn-down of one budget for the current week
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BudgetStatus {
+    #[serde(flatten)]
+    pub scope: BudgetScope,
+
+    /// ISO week, e.g. `2022-W01`
+    pub period: String,
+    pub budget_secs: u64,
+
+    /// Tracked time this week, including running sessions
+    pub used_secs: u64,
+    pub remaining_secs: u64,
+
+    /// `used / budget`
+    pub burn_ratio: f64,
+
+    /// Usage at the end of the week if the current pace continues
+    pub projected_secs: u64,
+    pub state: BudgetState,
+}
+
+impl BudgetStatus {
+    /// Build the status for a budget given usage and how far through the week we are
+    pub fn new(budget: &TimeBudget, period: String, used_secs: u64, week_elapsed: f64) -> Self {
+        let budget_secs = budget.budget_secs();
+        let projected_secs = if week_elapsed > 0.0 {
+            (used_secs as f64 / week_elapsed.min(1.0)).round() as u64
+        } else {
+            used_secs
+        };
+
+        Self {
+            scope: budget.scope.clone(),
+            period,
+            budget_secs,
+            used_secs,
+            remaining_secs: budget_secs.saturating_sub(used_secs),
+            burn_ratio: if budget_secs == 0 {
+                1.0
+            } else {
+                used_secs as f64 / budget_secs as f64
+            },
+            projected_secs,
+            state: budget.state_for(used_secs),
+        }
+    }
+
+    /// Compact `Development 12.5/20h` segment for status bars
+    pub fn short_label(&self) -> String {
+        let marker = match self.state {
+            BudgetState::Ok => "",
+            BudgetState::Warning => " !",
+            BudgetState::Exceeded => " !!",
+        };
+        format!(
+            "{} {}/{}h{}",
+            self.scope,
+            format_hours(self.used_secs),
+            format_hours(self.budget_secs),
+            marker
+        )
+    }
+}
+
+/// Join budget statuses into a single status bar line
+pub fn status_line(statuses: &[BudgetStatus]) -> String {
+    statuses
+        .iter()
+        .map(BudgetStatus::short_label)
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/// Hours with at most one decimal place
+fn format_hours(secs: u64) -> String {
+    let hours = (secs as f64 / 360.0).round() / 10.0;
+    if hours.fract() == 0.0 {
+        format!("{hours:.0}")
+    } else {
+        format!("{hours:.1}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_yaml_and_states() {
+        let budgets: Vec<TimeBudget> = serde_yaml::from_str(
+            "- category: Development\n  weekly_hours: 10\n- workspace: frontend\n  weekly_hours: 2\n  warn_at: 0.5\n",
+        )
+        .unwrap();
+        assert_eq!(budgets[0], TimeBudget::category("Development", 10.0));
+        assert_eq!(
+            budgets[1].scope,
+            BudgetScope::Workspace("frontend".to_string())
+        );
+
+        assert_eq!(budgets[0].state_for(8 * 3600), BudgetState::Ok);
+        assert_eq!(budgets[0].state_for(9 * 3600), BudgetState::Warning);
+        assert_eq!(budgets[0].state_for(10 * 3600), BudgetState::Exceeded);
+        assert_eq!(budgets[1].state_for(3600), BudgetState::Warning);
+    }
+
+    #[test]
+    fn test_status_and_status_line() {
+        let budget = TimeBudget::category("Development", 20.0);
+        // 12.5 hours used halfway through the week
+        let status = BudgetStatus::new(&budget, "2022-W01".to_string(), 45_000, 0.5);
+        assert_eq!(status.remaining_secs, 27_000);
+        assert_eq!(status.projected_secs, 90_000);
+        assert_eq!(status.state, BudgetState::Ok);
+
+        let workspace = TimeBudget::workspace("frontend", 2.0);
+        let over = BudgetStatus::new(&workspace, "2022-W01".to_string(), 7_300, 0.5);
+        assert_eq!(over.remaining_secs, 0);
+        assert_eq!(
+            status_line(&[status, over]),
+            "Development 12.5/20h | @frontend 2/2h !!"
+        );
+    }
+}
@@ -0,0 +1,197 @@
+// WezTerm Multi-Process Development Framework - Cron Expressions
+// Parses five-field cron expressions and finds the next matching local time
+
+use chrono::{Datelike, Duration, NaiveDateTime, Timelike};
+
+/// How far ahead to search for a matching time (covers leap-day schedules)
+const MAX_SEARCH_DAYS: i64 = 366 * 5;
+
+/// Parsed `minute hour day-of-month month day-of-week` expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronExpression {
+    minutes: Vec<bool>,
+    hours: Vec<bool>,
+    days_of_month: Vec<bool>,
+    months: Vec<bool>,
+    days_of_week: Vec<bool>,
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+impl CronExpression {
+    /// Parse a five-field cron expression
+    ///
+    /// Each field accepts `*`, single values, ranges (`1-5`), lists (`1,3`)
+    /// and steps (`*/15`, `0-30/10`). Day-of-week is 0-7 with both 0 and 7
+    /// meaning Sunday.
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!(
+                "Invalid cron pattern '{expression}': expected 5 fields, found {}",
+                fields.len()
+            ));
+        }
+
+        let mut days_of_week = parse_field(fields[4], 0, 7)?;
+        if days_of_week[7] {
+            days_of_week[0] = true;
+        }
+        days_of_week.truncate(7);
+
+        Ok(Self {
+            minutes: parse_field(fields[0], 0, 59)?,
+            hours: parse_field(fields[1], 0, 23)?,
+            days_of_month: parse_field(fields[2], 1, 31)?,
+            months: parse_field(fields[3], 1, 12)?,
+            days_of_week,
+            day_of_month_restricted: fields[2] != "*",
+            day_of_week_restricted: fields[4] != "*",
+        })
+    }
+
+    /// First matching wall-clock minute strictly after `after`
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut candidate = after
+            .with_second(0)?
+            .with_nanosecond(0)?
+            .checked_add_signed(Duration::minutes(1))?;
+        let limit = after.checked_add_signed(Duration::days(MAX_SEARCH_DAYS))?;
+
+        while candidate <= limit {
+            if !self.months[candidate.month() as usize] || !self.matches_day(&candidate) {
+                candidate = candidate.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !self.hours[candidate.hour() as usize] {
+                candidate = candidate.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if !self.minutes[candidate.minute() as usize] {
+                candidate += Duration::minutes(1);
+                continue;
+            }
+            return Some(candidate);
+        }
+
+        None
+    }
+
+    /// Standard cron semantics: when both day fields are restricted either may match
+    fn matches_day(&self, candidate: &NaiveDateTime) -> bool {
+        let day_of_month = self.days_of_month[candidate.day() as usize];
+        let day_of_week = self.days_of_week[candidate.weekday().num_days_from_sunday() as usize];
+
+        match (self.day_of_month_restricted, self.day_of_week_restricted) {
+            (true, true) => day_of_month || day_of_week,
+            (true, false) => day_of_month,
+            (false, true) => day_of_week,
+            (false, false) => true,
+        }
+    }
+}
+
+/// Parse one field into a lookup table indexed by value
+fn parse_field(field: &str, min: u32, max: u32) -> Result<Vec<bool>, String> {
+    let mut allowed = vec![false; max as usize + 1];
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| format!("Invalid cron step '{part}'"))?;
+                if step == 0 {
+                    return Err(format!("Invalid cron step '{part}'"));
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (parse_value(start, min, max)?, parse_value(end, min, max)?)
+        } else {
+            let value = parse_value(range, min, max)?;
+            // `5/10` means "from 5 to the end in steps of 10"
+            (value, if step > 1 { max } else { value })
+        };
+
+        if start > end {
+            return Err(format!("Invalid cron range '{range}'"));
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            allowed[value as usize] = true;
+        }
+    }
+
+    Ok(allowed)
+}
+
+fn parse_value(value: &str, min: u32, max: u32) -> Result<u32, String> {
+    let parsed: u32 = value
+        .parse()
+        .map_err(|_| format!("Invalid cron value '{value}'"))?;
+    if parsed < min || parsed > max {
+        return Err(format!("Cron value {parsed} out of range {min}-{max}"));
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_expressions() {
+        assert!(CronExpression::parse("* * * *").is_err());
+        assert!(CronExpression::parse("60 * * * *").is_err());
+        assert!(CronExpression::parse("*/0 * * * *").is_err());
+        assert!(CronExpression::parse("5-1 * * * *").is_err());
+        assert!(CronExpression::parse("0 9 * * 1-5").is_ok());
+    }
+
+    #[test]
+    fn test_next_after() {
+        let weekdays_at_nine = CronExpression::parse("0 9 * * 1-5").unwrap();
+        // 2022-01-07 is a Friday; the next run is Monday morning
+        assert_eq!(
+            weekdays_at_nine.next_after(at(2022, 1, 7, 9, 0)),
+            Some(at(2022, 1, 10, 9, 0))
+        );
+
+        let quarter_hourly = CronExpression::parse("*/15 * * * *").unwrap();
+        assert_eq!(
+            quarter_hourly.next_after(at(2022, 1, 1, 10, 7)),
+            Some(at(2022, 1, 1, 10, 15))
+        );
+
+        let leap_day = CronExpression::parse("0 0 29 2 *").unwrap();
+        assert_eq!(
+            leap_day.next_after(at(2022, 3, 1, 0, 0)),
+            Some(at(2024, 2, 29, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_day_fields_are_ored_when_both_restricted() {
+        // 1st of the month or any Sunday
+        let expression = CronExpression::parse("0 0 1 * 0").unwrap();
+        // 2022-01-02 is a Sunday
+        assert_eq!(
+            expression.next_after(at(2022, 1, 1, 0, 0)),
+            Some(at(2022, 1, 2, 0, 0))
+        );
+    }
+}
@@ -0,0 +1,170 @@
+// WezTerm Multi-Process Development Framework - Dead Letter Queue
+// Tasks that failed for good, with their failure history and last output
+
+use super::output::TaskOutput;
+use super::types::{ExecutionResult, Task, TaskExecutionRecord, TaskId, TaskPriority};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Most dead letters kept; the oldest are dropped first
+pub const MAX_DEAD_LETTERS: usize = 500;
+
+/// Bytes of each output stream kept with a dead letter
+const OUTPUT_LIMIT: usize = 4096;
+
+/// A task that failed and will not be retried
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub task_id: TaskId,
+    pub title: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<String>,
+    pub priority: TaskPriority,
+
+    /// Error of the last failed attempt
+    pub reason: String,
+    pub dead_lettered_at: u64,
+
+    /// Every attempt, oldest first
+    pub history: Vec<TaskExecutionRecord>,
+
+    /// Output of the last run, cut down to the last few KiB per stream
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<TaskOutput>,
+}
+
+impl DeadLetter {
+    pub fn new(task: &Task, output: Option<&TaskOutput>, dead_lettered_at: u64) -> Self {
+        let reason = task
+            .execution_history
+            .iter()
+            .rev()
+            .find(|record| record.result != ExecutionResult::Success)
+            .and_then(|record| record.error.clone())
+            .unwrap_or_else(|| "Task failed".to_string());
+        Self {
+            task_id: task.id.clone(),
+            title: task.title.clone(),
+            workspace: task.workspace.clone(),
+            priority: task.priority.clone(),
+            reason,
+            dead_lettered_at,
+            history: task.execution_history.clone(),
+            output: output.map(|output| output.preview(OUTPUT_LIMIT)),
+        }
+    }
+}
+
+/// Dead letters in the order they arrived
+#[derive(Debug, Default)]
+pub struct DeadLetterStore {
+    entries: Mutex<VecDeque<DeadLetter>>,
+}
+
+impl DeadLetterStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a dead letter, replacing an earlier one of the same task
+    pub fn push(&self, entry: DeadLetter) {
+        let mut entries = self.lock();
+        entries.retain(|existing| existing.task_id != entry.task_id);
+        entries.push_back(entry);
+        while entries.len() > MAX_DEAD_LETTERS {
+            entries.pop_front();
+        }
+    }
+
+    /// All dead letters, newest first
+    pub fn list(&self) -> Vec<DeadLetter> {
+        self.lock().iter().rev().cloned().collect()
+    }
+
+    pub fn contains(&self, task_id: &TaskId) -> bool {
+        self.lock().iter().any(|entry| &entry.task_id == task_id)
+    }
+
+    pub fn remove(&self, task_id: &TaskId) -> Option<DeadLetter> {
+        let mut entries = self.lock();
+        let index = entries.iter().position(|entry| &entry.task_id == task_id)?;
+        entries.remove(index)
+    }
+
+    /// Drop the dead letter of `task_id`, or all of them; returns how many
+    pub fn purge(&self, task_id: Option<&TaskId>) -> usize {
+        match task_id {
+            Some(task_id) => self.remove(task_id).map_or(0, |_| 1),
+            None => {
+                let mut entries = self.lock();
+                let purged = entries.len();
+                entries.clear();
+                purged
+            }
+        }
+    }
+
+    /// Replace the contents with persisted dead letters, oldest first
+    pub fn restore(&self, dead_letters: Vec<DeadLetter>) {
+        let mut entries = self.lock();
+        entries.clear();
+        entries.extend(dead_letters);
+        while entries.len() > MAX_DEAD_LETTERS {
+            entries.pop_front();
+        }
+    }
+
+    /// Dead letters oldest first, for persistence
+    pub fn snapshot(&self) -> Vec<DeadLetter> {
+        self.lock().iter().cloned().collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<DeadLetter>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::TaskCategory;
+
+    fn failed_task(id: &str, error: &str) -> Task {
+        let mut task = Task::new(id.to_string(), TaskCategory::Development);
+        task.id = id.to_string();
+        task.begin_attempt();
+        task.end_attempt(ExecutionResult::Failed, Some(error.to_string()));
+        task
+    }
+
+    #[test]
+    fn test_dead_letter_keeps_last_failure() {
+        let mut task = failed_task("build", "first");
+        task.begin_attempt();
+        task.end_attempt(ExecutionResult::Timeout, Some("timed out".to_string()));
+
+        let entry = DeadLetter::new(&task, None, 100);
+        assert_eq!(entry.reason, "timed out");
+        assert_eq!(entry.history.len(), 2);
+    }
+
+    #[test]
+    fn test_store_replaces_and_purges() {
+        let store = DeadLetterStore::new();
+        store.push(DeadLetter::new(&failed_task("a", "boom"), None, 1));
+        store.push(DeadLetter::new(&failed_task("b", "boom"), None, 2));
+        store.push(DeadLetter::new(&failed_task("a", "again"), None, 3));
+
+        let entries = store.list();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].task_id, "a");
+        assert_eq!(entries[0].reason, "again");
+
+        assert_eq!(store.purge(Some(&"b".to_string())), 1);
+        assert_eq!(store.purge(Some(&"b".to_string())), 0);
+        assert_eq!(store.purge(None), 1);
+        assert!(store.list().is_empty());
+    }
+}
@@ -59,6 +59,18 @@ impl DistributedTask {
         }
     }
 
+    /// Wrap a queued task with the default resource requirements
+    pub fn from_task(base_task: BaseTask) -> Self {
+        Self {
+            base_task,
+            distribution_id: Uuid::new_v4(),
+            dependencies: Vec::new(),
+            cpu_requirement: 0.5,
+            memory_requirement: 0.5,
+            assigned_process: None,
+        }
+    }
+
     pub fn depends_on(&self, task_id: &Uuid) -> bool {
         self.dependencies
             .iter()
@@ -103,6 +115,28 @@ impl ProcessLoad {
     }
 }
 
+/// Candidate with the lowest load score that can take the task
+///
+/// Ties go to the earliest candidate.
+pub fn least_loaded<'a, K>(
+    candidates: impl IntoIterator<Item = (K, &'a ProcessLoad)>,
+    task: &DistributedTask,
+) -> Option<K> {
+    let mut best = None;
+    let mut best_score = f64::MAX;
+    for (key, load) in candidates {
+        if !load.can_handle_task(task) {
+            continue;
+        }
+        let score = load.calculate_score();
+        if score < best_score {
+            best_score = score;
+            best = Some(key);
+        }
+    }
+    best
+}
+
 pub struct TaskDistributor {
     tasks: HashMap<Uuid, DistributedTask>,
     process_loads: HashMap<Uuid, ProcessLoad>,
@@ -153,39 +187,20 @@ impl TaskDistributor {
     }
 
     pub fn assign_task(&self, task: &DistributedTask) -> Result<Uuid> {
-        let mut best_process = None;
-        let mut best_score = f64::MAX;
-
-        for (process_id, load) in &self.process_loads {
-            if !load.can_handle_task(task) {
-                continue;
-            }
-
-            // ファイルロック競合チェック
-            let mut has_conflict = false;
-            for dep in &task.dependencies {
-                if let TaskDependency::FileAccess(file_path) = dep {
-                    if let Some(locked_process) = self.file_locks.get(file_path) {
-                        if *locked_process != *process_id {
-                            has_conflict = true;
-                            break;
-                        }
-                    }
-                }
-            }
-
-            if has_conflict {
-                continue;
-            }
-
-            let score = load.calculate_score();
-            if score < best_score {
-                best_score = score;
-                best_process = Some(*process_id);
-            }
-        }
-
-        best_process.ok_or_else(|| anyhow!("No suitable process found for task assignment"))
+        // ファイルロック競合のあるプロセスは除外
+        let candidates = self
+            .process_loads
+            .iter()
+            .filter(|(process_id, _)| {
+                !task.dependencies.iter().any(|dep| {
+                    matches!(dep, TaskDependency::FileAccess(file_path)
+                        if self.file_locks.get(file_path).is_some_and(|locked| locked != *process_id))
+                })
+            })
+            .map(|(process_id, load)| (*process_id, load));
+
+        least_loaded(candidates, task)
+            .ok_or_else(|| anyhow!("No suitable process found for task assignment"))
     }
 
     pub fn resolve_execution_order(&self) -> Result<Vec<Uuid>> {
@@ -435,6 +450,31 @@ mod tests {
         assert_eq!(load.calculate_score(), expected_score);
     }
 
+    #[test]
+    fn test_least_loaded_skips_saturated_processes() {
+        let load = |cpu_usage, active_tasks| ProcessLoad {
+            cpu_usage,
+            memory_usage: 0.0,
+            active_tasks,
+        };
+        let task = DistributedTask::from_task(BaseTask::new(
+            "Route me".to_string(),
+            crate::task::types::TaskCategory::Development,
+        ));
+        let loads = [
+            ("busy", load(0.0, 3)),
+            ("full", load(0.9, 0)),
+            ("idle", load(0.0, 1)),
+        ];
+
+        let picked = least_loaded(loads.iter().map(|(id, load)| (*id, load)), &task);
+        assert_eq!(picked, Some("idle"));
+        assert_eq!(
+            least_loaded(loads[1..2].iter().map(|(id, load)| (*id, load)), &task),
+            None
+        );
+    }
+
     #[test]
     fn test_task_distributor_creation() {
         let distributor = TaskDistributor::new();
@@ -0,0 +1,158 @@
+// WezTerm Multi-Process Development Framework - Task IDs
+// Primary task ID strategies and the short, human-friendly IDs (e.g. WP-123)
+// kept alongside them for use in conversation and keybindings
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Prefix of short task IDs unless configured otherwise
+pub const DEFAULT_SHORT_ID_PREFIX: &str = "WP";
+
+/// How the primary ID of a new task is chosen
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskIdStrategy {
+    /// Random UUID; the short ID is an alias
+    #[default]
+    Uuid,
+
+    /// The short ID itself is the primary ID
+    Short,
+}
+
+/// Task ID settings
+///
+/// ```yaml
+/// task_ids:
+///   strategy: uuid
+///   prefix: WP
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskIdConfig {
+    pub strategy: TaskIdStrategy,
+
+    /// Prefix of short IDs (letters, digits and `_`), followed by `-` and a
+    /// sequence number
+    pub prefix: String,
+}
+
+impl Default for TaskIdConfig {
+    fn default() -> Self {
+        Self {
+            strategy: TaskIdStrategy::default(),
+            prefix: DEFAULT_SHORT_ID_PREFIX.to_string(),
+        }
+    }
+}
+
+/// Hands out monotonic short IDs
+///
+/// Numbers are never reused: the counter is persisted with the task state
+/// and moved past any short ID seen on restored or imported tasks.
+#[derive(Debug)]
+pub struct ShortIdAllocator {
+    config: TaskIdConfig,
+    next: AtomicU64,
+}
+
+impl ShortIdAllocator {
+    pub fn new(config: TaskIdConfig) -> Self {
+        Self {
+            config,
+            next: AtomicU64::new(1),
+        }
+    }
+
+    /// Number the next short ID will get
+    pub fn next_number(&self) -> u64 {
+        self.next.load(Ordering::SeqCst)
+    }
+
+    /// Never hand out numbers below `number` again
+    pub fn reserve_from(&self, number: u64) {
+        self.next.fetch_max(number, Ordering::SeqCst);
+    }
+
+    /// Record a short ID already in use so it is not handed out again
+    pub fn observe(&self, short_id: &str) {
+        if let Some(number) = self.parse(short_id) {
+            self.reserve_from(number + 1);
+        }
+    }
+
+    /// Next short ID for which `taken` is false
+    pub fn allocate(&self, taken: impl Fn(&str) -> bool) -> String {
+        loop {
+            let number = self.next.fetch_add(1, Ordering::SeqCst);
+            let short_id = self.format(number);
+            if !taken(&short_id) {
+                return short_id;
+            }
+        }
+    }
+
+    /// Primary ID of a new task: under the short strategy a generated UUID
+    /// is replaced by the short ID, explicitly chosen IDs are kept
+    pub fn primary_id(&self, id: String, short_id: &str) -> String {
+        match self.config.strategy {
+            TaskIdStrategy::Short if uuid::Uuid::parse_str(&id).is_ok() => short_id.to_string(),
+            _ => id,
+        }
+    }
+
+    pub fn format(&self, number: u64) -> String {
+        format!("{}-{number}", self.config.prefix)
+    }
+
+    /// Sequence number of a short ID with this allocator's prefix; the prefix
+    /// is matched case-insensitively (`wp-12` is `WP-12`)
+    pub fn parse(&self, short_id: &str) -> Option<u64> {
+        let (prefix, number) = short_id.trim().rsplit_once('-')?;
+        if !prefix.eq_ignore_ascii_case(&self.config.prefix) {
+            return None;
+        }
+        number.parse().ok()
+    }
+}
+
+impl Default for ShortIdAllocator {
+    fn default() -> Self {
+        Self::new(TaskIdConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_ids_are_monotonic_and_skip_taken() {
+        let allocator = ShortIdAllocator::default();
+        assert_eq!(allocator.allocate(|_| false), "WP-1");
+        assert_eq!(allocator.allocate(|id| id == "WP-2"), "WP-3");
+
+        allocator.observe("wp-41");
+        assert_eq!(allocator.allocate(|_| false), "WP-42");
+        allocator.observe("WP-7");
+        allocator.observe("OTHER-100");
+        assert_eq!(allocator.next_number(), 43);
+
+        assert_eq!(allocator.parse(" Wp-12 "), Some(12));
+        assert_eq!(allocator.parse("WP-x"), None);
+        assert_eq!(allocator.parse("0f6c1a52-2b0e"), None);
+    }
+
+    #[test]
+    fn test_strategy_chooses_primary_id() {
+        let config: TaskIdConfig = serde_yaml::from_str("strategy: short").unwrap();
+        assert_eq!(config.prefix, DEFAULT_SHORT_ID_PREFIX);
+        let allocator = ShortIdAllocator::new(config);
+        let generated = crate::task::generate_task_id();
+        assert_eq!(allocator.primary_id(generated.clone(), "WP-5"), "WP-5");
+        assert_eq!(allocator.primary_id("deploy".to_string(), "WP-5"), "deploy");
+
+        let allocator = ShortIdAllocator::default();
+        assert_eq!(allocator.primary_id(generated.clone(), "WP-5"), generated);
+    }
+}
@@ -0,0 +1,472 @@
+// WezTerm Multi-Process Development Framework - History Import
+// Reads time-tracking CSVs and old session/task exports so that reports and
+// budgets have history from day one
+
+use super::tracker::CompletedSession;
+use super::types::{Task, TaskStatus};
+use crate::config::Timezone;
+use chrono::{DateTime, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Input formats of a history import
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    /// Time-tracking CSV with a header row (our session export, Toggl, Clockify, ...)
+    Csv,
+
+    /// Session export, task list or task state snapshot
+    Json,
+}
+
+impl std::str::FromStr for ImportFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "csv" => Ok(ImportFormat::Csv),
+            "json" => Ok(ImportFormat::Json),
+            _ => Err(format!(
+                "Unsupported import format '{value}': expected csv or json"
+            )),
+        }
+    }
+}
+
+impl ImportFormat {
+    /// Guess the format from the content
+    pub fn detect(content: &str) -> Self {
+        match content.trim_start().chars().next() {
+            Some('[') | Some('{') => ImportFormat::Json,
+            _ => ImportFormat::Csv,
+        }
+    }
+}
+
+/// Parsed history, not yet applied
+#[derive(Debug, Default)]
+pub struct ImportedHistory {
+    pub sessions: Vec<CompletedSession>,
+    pub tasks: Vec<Task>,
+
+    /// Category and workspace of imported tasks, for roll-ups and budgets
+    pub categories: HashMap<String, String>,
+    pub workspaces: HashMap<String, String>,
+
+    /// Rows or entries that could not be read
+    pub errors: Vec<String>,
+}
+
+/// Outcome of an import, also returned for dry runs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportReport {
+    /// Label stored on every imported session and task
+    pub source: String,
+
+    /// True when nothing was stored
+    pub dry_run: bool,
+
+    pub sessions_imported: usize,
+
+    /// Sessions already known (same task and start time)
+    pub sessions_skipped: usize,
+
+    pub tasks_imported: usize,
+
+    /// Tasks already known or not finished
+    pub tasks_skipped: usize,
+
+    /// Active time of the imported sessions in seconds
+    pub tracked_seconds: u64,
+
+    /// Time span of the imported sessions
+    pub first_started_at: Option<u64>,
+    pub last_ended_at: Option<u64>,
+
+    /// Rows or entries that could not be read
+    pub errors: Vec<String>,
+}
+
+/// Parse history in `format`; naive timestamps are read in `timezone`
+///
+/// Fails only when nothing at all can be read; bad rows are reported in
+/// `errors`.
+pub fn parse(
+    content: &str,
+    format: ImportFormat,
+    source: &str,
+    timezone: Timezone,
+) -> Result<ImportedHistory, String> {
+    let mut history = match format {
+        ImportFormat::Csv => parse_csv(content, timezone)?,
+        ImportFormat::Json => parse_json(content)?,
+    };
+
+    for session in &mut history.sessions {
+        session.source = Some(source.to_string());
+    }
+    for task in &mut history.tasks {
+        task.metadata
+            .insert("source".to_string(), source.to_string());
+    }
+    Ok(history)
+}
+
+/// Finished tasks can be history; open ones belong in the queue
+pub fn is_history(task: &Task) -> bool {
+    matches!(
+        task.status,
+        TaskStatus::Completed | TaskStatus::Cancelled | TaskStatus::Failed
+    )
+}
+
+fn parse_json(content: &str) -> Result<ImportedHistory, String> {
+    #[derive(Deserialize)]
+    struct Snapshot {
+        tasks: Vec<Task>,
+        #[serde(default)]
+        completed_sessions: Vec<CompletedSession>,
+    }
+
+    let mut history = ImportedHistory::default();
+    if let Ok(sessions) = serde_json::from_str::<Vec<CompletedSession>>(content) {
+        history.sessions = sessions;
+    } else if let Ok(tasks) = serde_json::from_str::<Vec<Task>>(content) {
+        history.tasks = tasks;
+    } else {
+        let snapshot: Snapshot = serde_json::from_str(content).map_err(|e| {
+            format!("Expected a session export, a task list or a task state snapshot: {e}")
+        })?;
+        history.tasks = snapshot.tasks;
+        history.sessions = snapshot.completed_sessions;
+    }
+
+    for task in &history.tasks {
+        history
+            .categories
+            .insert(task.id.clone(), task.category.to_string());
+        if let Some(workspace) = &task.workspace {
+            history
+                .workspaces
+                .insert(task.id.clone(), workspace.clone());
+        }
+    }
+    Ok(history)
+}
+
+/// Header names of the columns we read, with the aliases other tools use
+const TASK_COLUMNS: &[&str] = &["task_id", "task", "description", "title", "name"];
+const PROJECT_COLUMNS: &[&str] = &["workspace", "project"];
+const START_COLUMNS: &[&str] = &["started_at", "start", "start_date", "start_time"];
+const END_COLUMNS: &[&str] = &["ended_at", "end", "end_date", "end_time"];
+const DURATION_COLUMNS: &[&str] = &["active_duration", "duration", "duration_seconds"];
+
+fn parse_csv(content: &str, timezone: Timezone) -> Result<ImportedHistory, String> {
+    let mut rows = csv_rows(content).into_iter();
+    let header: Vec<String> = rows
+        .next()
+        .ok_or("The CSV is empty")?
+        .iter()
+        .map(|name| name.trim().to_ascii_lowercase().replace([' ', '-'], "_"))
+        .collect();
+    let column = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| header.iter().position(|column| column == name))
+    };
+
+    let task_column = column(TASK_COLUMNS).ok_or_else(|| {
+        format!(
+            "The CSV has no task column (one of {})",
+            TASK_COLUMNS.join(", ")
+        )
+    })?;
+    let start_column = column(START_COLUMNS).ok_or_else(|| {
+        format!(
+            "The CSV has no start column (one of {})",
+            START_COLUMNS.join(", ")
+        )
+    })?;
+    let columns = CsvColumns {
+        task: task_column,
+        task_is_id: header[task_column] == "task_id",
+        project: column(PROJECT_COLUMNS),
+        category: column(&["category"]),
+        start: start_column,
+        // Toggl splits date and time: "Start date", "Start time"
+        start_time: column(&["start_time"]).filter(|&c| c != start_column),
+        end: column(END_COLUMNS),
+        end_time: column(&["end_time"]).filter(|&c| Some(c) != column(END_COLUMNS)),
+        duration: column(DURATION_COLUMNS),
+        break_duration: column(&["break_duration"]),
+        interruptions: column(&["interruptions"]),
+        tags: column(&["tags"]),
+        notes: column(&["notes"]),
+    };
+    if columns.end.is_none() && columns.duration.is_none() {
+        return Err("The CSV has neither an end nor a duration column".to_string());
+    }
+
+    let mut history = ImportedHistory::default();
+    for (index, row) in rows.enumerate() {
+        if row.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+        // Line numbers as seen in a spreadsheet, header being line 1
+        match columns.session(&row, timezone) {
+            Ok((session, project, category)) => {
+                if let Some(project) = project {
+                    history.workspaces.insert(session.task_id.clone(), project);
+                }
+                if let Some(category) = category {
+                    history.categories.insert(session.task_id.clone(), category);
+                }
+                history.sessions.push(session);
+            }
+            Err(e) => history.errors.push(format!("Row {}: {e}", index + 2)),
+        }
+    }
+    Ok(history)
+}
+
+struct CsvColumns {
+    task: usize,
+    task_is_id: bool,
+    project: Option<usize>,
+    category: Option<usize>,
+    start: usize,
+    start_time: Option<usize>,
+    end: Option<usize>,
+    end_time: Option<usize>,
+    duration: Option<usize>,
+    break_duration: Option<usize>,
+    interruptions: Option<usize>,
+    tags: Option<usize>,
+    notes: Option<usize>,
+}
+
+type ParsedRow = (CompletedSession, Option<String>, Option<String>);
+
+impl CsvColumns {
+    fn session(&self, row: &[String], timezone: Timezone) -> Result<ParsedRow, String> {
+        let field = |column: Option<usize>| {
+            column
+                .and_then(|c| row.get(c))
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+        };
+
+        let task = field(Some(self.task)).ok_or("no task")?;
+        let started_at = timestamp(
+            field(Some(self.start)).ok_or("no start time")?,
+            field(self.start_time),
+            timezone,
+        )?;
+        let ended_at = field(self.end)
+            .map(|end| timestamp(end, field(self.end_time), timezone))
+            .transpose()?;
+        let duration = field(self.duration).map(duration_secs).transpose()?;
+
+        let (ended_at, active_duration) = match (ended_at, duration) {
+            (Some(ended_at), _) if ended_at < started_at => {
+                return Err("ends before it starts".to_string())
+            }
+            (Some(ended_at), Some(duration)) => (ended_at, duration.min(ended_at - started_at)),
+            (Some(ended_at), None) => (ended_at, ended_at - started_at),
+            (None, Some(duration)) => (started_at + duration, duration),
+            (None, None) => return Err("no end time or duration".to_string()),
+        };
+        let total_duration = ended_at - started_at;
+        let break_duration = match field(self.break_duration) {
+            Some(value) => duration_secs(value)?,
+            None => total_duration - active_duration,
+        };
+        let interruptions = field(self.interruptions)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| format!("bad interruptions '{value}'"))
+            })
+            .transpose()?
+            .unwrap_or(0);
+        let tags = field(self.tags)
+            .map(|tags| {
+                tags.split([';', ','])
+                    .map(str::trim)
+                    .filter(|tag| !tag.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let notes = field(self.notes)
+            .map(|notes| {
+                vec![super::tracker::SessionNote {
+                    text: notes.to_string(),
+                    created_at: ended_at,
+                }]
+            })
+            .unwrap_or_default();
+
+        let session = CompletedSession {
+            task_id: task_id(task, self.task_is_id),
+            started_at,
+            ended_at,
+            total_duration,
+            active_duration,
+            break_duration,
+            interruptions,
+            segments: Vec::new(),
+            productivity_score: super::tracker::productivity_score(
+                active_duration,
+                break_duration,
+                interruptions,
+            ),
+            tags,
+            notes,
+            source: None,
+        };
+        Ok((
+            session,
+            field(self.project).map(str::to_string),
+            field(self.category).map(str::to_string),
+        ))
+    }
+}
+
+/// Task ID of a CSV row: IDs are kept, descriptions become `imported-<slug>`
+fn task_id(value: &str, is_id_column: bool) -> String {
+    if is_id_column && !value.contains(char::is_whitespace) {
+        return value.to_string();
+    }
+    let mut slug = String::new();
+    for c in value.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.ends_with('-') && !slug.is_empty() {
+            slug.push('-');
+        }
+    }
+    format!("imported-{}", slug.trim_end_matches('-'))
+}
+
+/// Unix seconds, RFC 3339, or a local date and time (optionally in two columns)
+fn timestamp(value: &str, time: Option<&str>, timezone: Timezone) -> Result<u64, String> {
+    let value = match time {
+        Some(time) => format!("{value} {time}"),
+        None => value.to_string(),
+    };
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Ok(seconds);
+    }
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(&value) {
+        return Ok(datetime.timestamp().max(0) as u64);
+    }
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(&value, format).ok())
+        .map(|local| timezone.timestamp_of(local))
+        .ok_or_else(|| format!("bad time '{value}'"))
+}
+
+/// Seconds, or `H:MM:SS` / `H:MM` as exported by time trackers
+fn duration_secs(value: &str) -> Result<u64, String> {
+    let bad = || format!("bad duration '{value}'");
+    if !value.contains(':') {
+        return value.parse().map_err(|_| bad());
+    }
+    let parts: Vec<u64> = value
+        .split(':')
+        .map(|part| part.parse().map_err(|_| bad()))
+        .collect::<Result<_, _>>()?;
+    match parts.as_slice() {
+        [hours, minutes] => Ok(hours * 3600 + minutes * 60),
+        [hours, minutes, seconds] => Ok(hours * 3600 + minutes * 60 + seconds),
+        _ => Err(bad()),
+    }
+}
+
+/// Rows of a CSV document; quoted fields may contain commas, quotes and newlines
+fn csv_rows(content: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = content.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', true) => quoted = false,
+            ('"', false) if field.is_empty() => quoted = true,
+            (',', false) => row.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            (c, _) => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_toggl_csv() {
+        let csv = "\u{feff}Project,Description,Start date,Start time,End date,End time,Duration,Tags\n\
+                   api,\"Fix login, again\",2024-03-01,09:00:00,2024-03-01,10:30:00,01:15:00,bug;auth\n\
+                   api,Review,2024-03-01,11:00:00,2024-03-01,10:00:00,01:00:00,\n\
+                   \n";
+        let history = parse(csv, ImportFormat::Csv, "toggl", Timezone::Fixed(3600)).unwrap();
+
+        assert_eq!(history.sessions.len(), 1);
+        let session = &history.sessions[0];
+        assert_eq!(session.task_id, "imported-fix-login-again");
+        // 2024-03-01 09:00 +01:00
+        assert_eq!(session.started_at, 1709280000);
+        assert_eq!(session.total_duration, 5400);
+        assert_eq!(session.active_duration, 4500);
+        assert_eq!(session.break_duration, 900);
+        assert_eq!(session.tags, ["bug", "auth"]);
+        assert_eq!(session.source.as_deref(), Some("toggl"));
+        assert_eq!(history.workspaces[&session.task_id], "api");
+        assert_eq!(history.errors, ["Row 3: ends before it starts"]);
+    }
+
+    #[test]
+    fn test_parse_own_exports() {
+        let csv = "task_id,started_at,ended_at,active_duration,break_duration,interruptions,tags,notes\n\
+                   task-1,2024-03-01T09:00:00+00:00,2024-03-01T10:00:00+00:00,3000,600,2,deep work,\"line 1\nline 2\"\n";
+        let history = parse(csv, ImportFormat::detect(csv), "old", Timezone::Utc).unwrap();
+        let session = &history.sessions[0];
+        assert_eq!(session.task_id, "task-1");
+        assert_eq!(session.interruptions, 2);
+        assert_eq!(session.notes[0].text, "line 1\nline 2");
+
+        let json = serde_json::to_string(&history.sessions).unwrap();
+        assert_eq!(ImportFormat::detect(&json), ImportFormat::Json);
+        let again = parse(&json, ImportFormat::Json, "again", Timezone::Utc).unwrap();
+        assert_eq!(again.sessions[0].started_at, session.started_at);
+        assert_eq!(again.sessions[0].source.as_deref(), Some("again"));
+
+        let mut task = Task::new("Done".to_string(), super::super::TaskCategory::Testing);
+        task.workspace = Some("api".to_string());
+        let tasks = serde_json::to_string(&vec![task]).unwrap();
+        let history = parse(&tasks, ImportFormat::Json, "old", Timezone::Utc).unwrap();
+        assert_eq!(history.tasks[0].metadata["source"], "old");
+        assert_eq!(history.workspaces[&history.tasks[0].id], "api");
+
+        assert!(parse("a,b\n1,2\n", ImportFormat::Csv, "x", Timezone::Utc).is_err());
+    }
+}
@@ -0,0 +1,330 @@
+// WezTerm Multi-Process Development Framework - Task Latency
+// Stage timestamps of the task pipeline (enqueue → assigned → started →
+// finished → reviewed), stage percentiles and latency budgets
+
+use super::{Task, TaskId};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Span of the task pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LatencyStage {
+    /// Enqueued until picked up by an agent process (or the local executor)
+    WaitingForAgent,
+
+    /// Assigned until execution started
+    Dispatch,
+
+    /// Started until finished (done, failed, cancelled or handed to review)
+    Execution,
+
+    /// Finished until reviewed
+    Review,
+
+    /// Enqueued until reviewed, or finished when there was no review
+    EndToEnd,
+}
+
+impl LatencyStage {
+    pub const ALL: [LatencyStage; 5] = [
+        LatencyStage::WaitingForAgent,
+        LatencyStage::Dispatch,
+        LatencyStage::Execution,
+        LatencyStage::Review,
+        LatencyStage::EndToEnd,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LatencyStage::WaitingForAgent => "waiting_for_agent",
+            LatencyStage::Dispatch => "dispatch",
+            LatencyStage::Execution => "execution",
+            LatencyStage::Review => "review",
+            LatencyStage::EndToEnd => "end_to_end",
+        }
+    }
+}
+
+/// When a task reached each point of the pipeline; the first time counts
+/// when a task is retried or requeued
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageTimestamps {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enqueued_at: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assigned_at: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reviewed_at: Option<u64>,
+}
+
+impl StageTimestamps {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Start and end of a stage, as far as reached
+    pub fn bounds(&self, stage: LatencyStage) -> (Option<u64>, Option<u64>) {
+        match stage {
+            LatencyStage::WaitingForAgent => (self.enqueued_at, self.assigned_at),
+            LatencyStage::Dispatch => (self.assigned_at, self.started_at),
+            LatencyStage::Execution => (self.started_at, self.finished_at),
+            LatencyStage::Review => (self.finished_at, self.reviewed_at),
+            LatencyStage::EndToEnd => (self.enqueued_at, self.reviewed_at.or(self.finished_at)),
+        }
+    }
+
+    /// Seconds a completed stage took
+    pub fn duration(&self, stage: LatencyStage) -> Option<u64> {
+        match self.bounds(stage) {
+            (Some(start), Some(end)) => Some(end.saturating_sub(start)),
+            _ => None,
+        }
+    }
+}
+
+/// Longest a stage may take before an alert is raised
+///
+/// ```yaml
+/// latency_budgets:
+///   - stage: waiting_for_agent
+///     max_secs: 300
+///   - stage: end_to_end
+///     max_secs: 3600
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencyBudget {
+    pub stage: LatencyStage,
+    pub max_secs: u64,
+}
+
+/// Distribution of one stage's durations, in seconds
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StagePercentiles {
+    pub stage: LatencyStage,
+    pub samples: usize,
+    pub p50: u64,
+    pub p90: u64,
+    pub p95: u64,
+    pub p99: u64,
+    pub max: u64,
+}
+
+/// A stage that took, or has been taking, longer than its budget
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencyBreach {
+    pub task_id: TaskId,
+    pub stage: LatencyStage,
+    pub elapsed_secs: u64,
+    pub max_secs: u64,
+
+    /// The stage has not ended yet
+    pub ongoing: bool,
+}
+
+/// Stage percentiles over tasks whose stages ended at or after `since`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LatencyReport {
+    pub generated_at: u64,
+    pub since: Option<u64>,
+    pub stages: Vec<StagePercentiles>,
+    pub breaches: Vec<LatencyBreach>,
+}
+
+/// Time spent in a stage, and whether it is still going on; stages of
+/// finished tasks that were never reached are not going on
+fn elapsed(task: &Task, stage: LatencyStage, now: u64) -> Option<(u64, bool)> {
+    match task.stages.bounds(stage) {
+        (Some(start), Some(end)) => Some((end.saturating_sub(start), false)),
+        (Some(start), None) if !task.is_finished() => Some((now.saturating_sub(start), true)),
+        _ => None,
+    }
+}
+
+/// Stages over budget that are still going on or ended at or after `since`
+pub fn budget_breaches<'a>(
+    tasks: impl IntoIterator<Item = &'a Task>,
+    budgets: &[LatencyBudget],
+    since: Option<u64>,
+    now: u64,
+) -> Vec<LatencyBreach> {
+    let mut breaches = Vec::new();
+    for task in tasks {
+        for budget in budgets {
+            let Some((elapsed_secs, ongoing)) = elapsed(task, budget.stage, now) else {
+                continue;
+            };
+            let recent = ongoing
+                || since.map_or(true, |since| {
+                    task.stages.bounds(budget.stage).1.unwrap_or_default() >= since
+                });
+            if recent && elapsed_secs > budget.max_secs {
+                breaches.push(LatencyBreach {
+                    task_id: task.id.clone(),
+                    stage: budget.stage,
+                    elapsed_secs,
+                    max_secs: budget.max_secs,
+                    ongoing,
+                });
+            }
+        }
+    }
+    breaches.sort_by_key(|breach| std::cmp::Reverse(breach.elapsed_secs));
+    breaches
+}
+
+/// Percentiles of every stage with at least one sample, and the budget breaches
+pub fn latency_report<'a>(
+    tasks: impl IntoIterator<Item = &'a Task> + Clone,
+    budgets: &[LatencyBudget],
+    since: Option<u64>,
+    now: u64,
+) -> LatencyReport {
+    let mut samples: BTreeMap<LatencyStage, Vec<u64>> = BTreeMap::new();
+    for task in tasks.clone() {
+        for stage in LatencyStage::ALL {
+            let (_, end) = task.stages.bounds(stage);
+            let Some(duration) = task.stages.duration(stage) else {
+                continue;
+            };
+            if since.map_or(true, |since| end.unwrap_or_default() >= since) {
+                samples.entry(stage).or_default().push(duration);
+            }
+        }
+    }
+
+    let stages = samples
+        .into_iter()
+        .map(|(stage, mut durations)| {
+            durations.sort_unstable();
+            StagePercentiles {
+                stage,
+                samples: durations.len(),
+                p50: percentile(&durations, 50),
+                p90: percentile(&durations, 90),
+                p95: percentile(&durations, 95),
+                p99: percentile(&durations, 99),
+                max: durations.last().copied().unwrap_or_default(),
+            }
+        })
+        .collect();
+
+    LatencyReport {
+        generated_at: now,
+        since,
+        stages,
+        breaches: budget_breaches(tasks, budgets, since, now),
+    }
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[u64], percent: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (sorted.len() * percent + 99) / 100;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{TaskCategory, TaskStatus};
+
+    fn task_with(stages: StageTimestamps, status: TaskStatus) -> Task {
+        let mut task = Task::new("Latency".to_string(), TaskCategory::Development);
+        task.stages = stages;
+        task.status = status;
+        task
+    }
+
+    #[test]
+    fn test_stage_percentiles() {
+        let tasks: Vec<Task> = (1..=10)
+            .map(|i| {
+                task_with(
+                    StageTimestamps {
+                        enqueued_at: Some(100),
+                        assigned_at: Some(100 + i * 10),
+                        started_at: Some(101 + i * 10),
+                        finished_at: Some(200 + i * 10),
+                        reviewed_at: None,
+                    },
+                    TaskStatus::Completed,
+                )
+            })
+            .collect();
+
+        let report = latency_report(&tasks, &[], None, 1000);
+        let waiting = &report.stages[0];
+        assert_eq!(waiting.stage, LatencyStage::WaitingForAgent);
+        assert_eq!(waiting.samples, 10);
+        assert_eq!((waiting.p50, waiting.p90, waiting.max), (50, 90, 100));
+        let stages: Vec<LatencyStage> = report.stages.iter().map(|s| s.stage).collect();
+        assert_eq!(
+            stages,
+            [
+                LatencyStage::WaitingForAgent,
+                LatencyStage::Dispatch,
+                LatencyStage::Execution,
+                LatencyStage::EndToEnd
+            ]
+        );
+
+        // Only stages that ended at or after `since`
+        let recent = latency_report(&tasks, &[], Some(195), 1000);
+        assert_eq!(recent.stages[0].samples, 1);
+    }
+
+    #[test]
+    fn test_budget_breaches() {
+        let waiting = task_with(
+            StageTimestamps {
+                enqueued_at: Some(100),
+                ..Default::default()
+            },
+            TaskStatus::Todo,
+        );
+        let cancelled = task_with(waiting.stages.clone(), TaskStatus::Cancelled);
+        let reviewed = task_with(
+            StageTimestamps {
+                enqueued_at: Some(0),
+                assigned_at: Some(5),
+                started_at: Some(5),
+                finished_at: Some(50),
+                reviewed_at: Some(400),
+            },
+            TaskStatus::Completed,
+        );
+        let budgets = [
+            LatencyBudget {
+                stage: LatencyStage::WaitingForAgent,
+                max_secs: 60,
+            },
+            LatencyBudget {
+                stage: LatencyStage::Review,
+                max_secs: 300,
+            },
+        ];
+
+        let breaches = budget_breaches([&waiting, &cancelled, &reviewed], &budgets, None, 500);
+        assert_eq!(breaches.len(), 2);
+        assert_eq!(breaches[0].task_id, waiting.id);
+        assert!(breaches[0].ongoing);
+        assert_eq!(breaches[0].elapsed_secs, 400);
+        assert_eq!(breaches[1].stage, LatencyStage::Review);
+        assert!(!breaches[1].ongoing);
+
+        // Ended before the last check
+        let breaches = budget_breaches([&reviewed], &budgets, Some(450), 500);
+        assert!(breaches.is_empty());
+    }
+}
@@ -1,31 +1,61 @@
 // WezTerm Multi-Process Development Framework - Task Manager
 // Central task management system with scheduling, execution, and tracking
 
+use super::batch::{self, BatchItem, BatchItemResult, BatchReport};
+use super::budget::BudgetState;
+use super::dead_letter::{DeadLetter, DeadLetterStore};
+use super::distributor::{least_loaded, DistributedTask, ProcessLoad};
+use super::ids::ShortIdAllocator;
+use super::import::{self, ImportReport, ImportedHistory};
+use super::latency::{self, LatencyReport, LatencyStage};
+use super::output::{
+    TaskOutput, TaskOutputStore, TaskRunResult, ARTIFACTS_DIR_ENV, OUTPUT_PREVIEW_LIMIT,
+};
+use super::persistence::{PersistedTaskState, TaskPersistence};
 use super::queue::{QueueConfig, TaskQueue};
+use super::scheduler::{PreemptionPolicy, RunningTask};
+use super::trace::{task_detail, TaskTrace};
 use super::tracker::TaskTracker;
-use super::types::{Task, TaskCategory, TaskExecution, TaskFilter, TaskId, TaskStatus};
+use super::types::{
+    ExecutionResult, Task, TaskCategory, TaskExecution, TaskFilter, TaskId, TaskNote, TaskPriority,
+    TaskStatus,
+};
 use super::{current_timestamp, TaskConfig, TaskError, TaskResult, TaskSystemStats};
+use crate::bridge::{BridgeEvent, EventBridge};
+use crate::dashboard::{AlertNotification, AlertSeverity, DashboardMessage, TaskAction};
+use crate::i18n::{Language, Text, TextKey};
+use crate::monitoring::{ShedAction, ShedLog, ShedTrigger};
+use crate::process::coordinator::{ProcessCoordinator, COORDINATOR_ID};
 use crate::process::manager::ProcessManager;
+use crate::room::state::{ProcessInfo, ProcessStatus};
 use crate::room::WorkspaceManager;
+use crate::{CoordinationEvent, CoordinationMessage, CoordinationResponse};
 
 use serde::{Deserialize, Serialize};
 
 /// Type alias for task event listeners
 type TaskEventListener = Box<dyn Fn(&TaskEvent) + Send + Sync>;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use tokio::time::{interval, sleep};
 use tracing::{debug, info, warn};
 
+/// How often task stages are checked against their latency budgets
+const LATENCY_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often budgets are checked for warnings
+const BUDGET_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
 /// Central task management system
 pub struct TaskManager {
     /// Task management configuration
     config: TaskConfig,
 
     /// Task storage (all tasks)
-    tasks: RwLock<HashMap<TaskId, Task>>,
+    tasks: Arc<RwLock<HashMap<TaskId, Task>>>,
 
     /// Task queue for pending tasks
     queue: Arc<TaskQueue>,
@@ -48,8 +78,35 @@ pub struct TaskManager {
     /// Process manager reference
     process_manager: Option<Arc<ProcessManager>>,
 
+    /// Delivers routed tasks to their processes
+    coordinator: Option<Arc<ProcessCoordinator>>,
+
     /// Event listeners
     event_listeners: RwLock<Vec<TaskEventListener>>,
+
+    /// Task state store (when persistence is enabled)
+    persistence: Option<TaskPersistence>,
+
+    /// Dashboard channel for task status changes
+    dashboard_tx: Option<tokio::sync::broadcast::Sender<DashboardMessage>>,
+
+    /// Queued tasks below this priority are held back (focus mode)
+    defer_below: Arc<RwLock<Option<TaskPriority>>>,
+
+    /// Reports completed tasks to external webhooks
+    event_bridge: Option<Arc<EventBridge>>,
+
+    /// Output of recently run command tasks
+    outputs: Arc<TaskOutputStore>,
+
+    /// Short, human-friendly task IDs
+    short_ids: Arc<ShortIdAllocator>,
+
+    /// Records tasks rejected or deferred under load
+    shed_log: Option<Arc<ShedLog>>,
+
+    /// Tasks that failed for good
+    dead_letters: Arc<DeadLetterStore>,
 }
 
 impl TaskManager {
@@ -61,11 +118,33 @@ impl TaskManager {
         };
 
         let queue = Arc::new(TaskQueue::new(queue_config));
-        let tracker = Arc::new(TaskTracker::new());
+        let tracker = Arc::new(
+            TaskTracker::new()
+                .with_timezone(config.timezone)
+                .with_budgets(config.budgets.clone()),
+        );
+        let persistence = config
+            .persistence_enabled
+            .then(|| TaskPersistence::from_config(config.persistence_path.as_deref()));
+        let artifacts_root = config
+            .artifacts_dir
+            .as_ref()
+            .map(PathBuf::from)
+            .or_else(|| {
+                persistence
+                    .as_ref()
+                    .and_then(|store| store.path().parent())
+                    .map(|dir| dir.join("artifacts"))
+            });
+        let outputs = Arc::new(TaskOutputStore::new(
+            artifacts_root,
+            config.max_task_history,
+        ));
+        let short_ids = Arc::new(ShortIdAllocator::new(config.ids.clone()));
 
         Self {
             config,
-            tasks: RwLock::new(HashMap::new()),
+            tasks: Arc::new(RwLock::new(HashMap::new())),
             queue,
             tracker,
             executing_tasks: Arc::new(RwLock::new(HashMap::new())),
@@ -73,7 +152,16 @@ impl TaskManager {
             stats: RwLock::new(TaskSystemStats::new()),
             workspace_manager: None,
             process_manager: None,
+            coordinator: None,
             event_listeners: RwLock::new(Vec::new()),
+            persistence,
+            dashboard_tx: None,
+            defer_below: Arc::new(RwLock::new(None)),
+            event_bridge: None,
+            outputs,
+            short_ids,
+            shed_log: None,
+            dead_letters: Arc::new(DeadLetterStore::new()),
         }
     }
 
@@ -89,26 +177,93 @@ impl TaskManager {
         self
     }
 
+    /// Hand tasks routed to a process to it over the coordination bus
+    ///
+    /// Only processes connected to the bus are routed to.
+    pub fn with_coordinator(mut self, coordinator: Arc<ProcessCoordinator>) -> Self {
+        self.coordinator = Some(coordinator);
+        self
+    }
+
+    /// Publish task status changes to the dashboard
+    pub fn with_dashboard_broadcaster(
+        mut self,
+        dashboard_tx: tokio::sync::broadcast::Sender<DashboardMessage>,
+    ) -> Self {
+        self.dashboard_tx = Some(dashboard_tx);
+        self
+    }
+
+    /// Report completed tasks through the event bridge
+    pub fn with_event_bridge(mut self, event_bridge: Arc<EventBridge>) -> Self {
+        self.event_bridge = Some(event_bridge);
+        self
+    }
+
+    /// Record tasks rejected by a full queue or deferred by the concurrency
+    /// limit as load shedding
+    pub fn with_shed_log(mut self, shed_log: Arc<ShedLog>) -> Self {
+        self.shed_log = Some(shed_log);
+        self
+    }
+
     /// Start the task manager (background processing)
     pub async fn start(&self) -> TaskResult<tokio::task::JoinHandle<()>> {
         info!("Starting task manager");
 
+        let tasks = Arc::clone(&self.tasks);
         let queue = Arc::clone(&self.queue);
         let executing_tasks = Arc::clone(&self.executing_tasks);
         let config = self.config.clone();
         let tracker = Arc::clone(&self.tracker);
+        let persistence = self.persistence.clone();
+        let dashboard_tx = self.dashboard_tx.clone();
+        let dead_letters = Arc::clone(&self.dead_letters);
+        let has_budgets = !tracker.budgets().is_empty();
+        let defer_below = Arc::clone(&self.defer_below);
+        let event_bridge = self.event_bridge.clone();
+        let outputs = Arc::clone(&self.outputs);
+        let short_ids = Arc::clone(&self.short_ids);
+        let directory = ProcessDirectory {
+            process_manager: self.process_manager.clone(),
+            workspace_manager: self.workspace_manager.clone(),
+            coordinator: self.coordinator.clone(),
+        };
 
         let task_handle = tokio::spawn(async move {
             let mut processing_interval = interval(Duration::from_millis(100));
+            let mut budget_interval = interval(BUDGET_CHECK_INTERVAL);
+            let mut latency_interval = interval(LATENCY_CHECK_INTERVAL);
+            let mut latency_checked_at = current_timestamp();
+            let mut latency_alerted = HashSet::new();
             let mut cleanup_interval = interval(Duration::from_secs(config.cleanup_interval));
+            let mut save_interval = interval(Duration::from_secs(config.auto_save_interval.max(1)));
 
             loop {
                 tokio::select! {
                     _ = processing_interval.tick() => {
-                        Self::process_queue_tick(&queue, &executing_tasks, &config, &tracker).await;
+                        let min_priority = defer_below.read().await.clone();
+                        Self::release_unblocked(&tasks, &queue).await;
+                        Self::recover_lost_assignments(&tasks, &queue, &executing_tasks, &config, &tracker, &directory, &dead_letters, dashboard_tx.as_ref()).await;
+                        Self::process_queue_tick(&tasks, &queue, &executing_tasks, &config, &tracker, min_priority.as_ref(), &directory, event_bridge.as_ref(), &outputs, &dead_letters, dashboard_tx.as_ref()).await;
+                        directory.sync_assignments(&executing_tasks).await;
                     }
                     _ = cleanup_interval.tick() => {
-                        Self::cleanup_completed_tasks(&executing_tasks, &config).await;
+                        Self::cleanup_completed_tasks(&tasks, &executing_tasks, &config).await;
+                    }
+                    _ = budget_interval.tick(), if has_budgets => {
+                        Self::check_budgets(&tracker, dashboard_tx.as_ref(), config.language).await;
+                    }
+                    _ = latency_interval.tick(), if !config.latency_budgets.is_empty() => {
+                        latency_checked_at = Self::check_latency_budgets(&tasks, &config, latency_checked_at, &mut latency_alerted, dashboard_tx.as_ref()).await;
+                    }
+                    _ = save_interval.tick(), if persistence.is_some() => {
+                        if let Some(ref store) = persistence {
+                            let snapshot = Self::build_snapshot(&tasks, &queue, &executing_tasks, &tracker, &short_ids, &dead_letters).await;
+                            if let Err(e) = store.save(&snapshot) {
+                                warn!("Failed to auto-save task state: {}", e);
+                            }
+                        }
                     }
                 }
             }
@@ -117,12 +272,111 @@ impl TaskManager {
         Ok(task_handle)
     }
 
+    /// Warn about budgets that are about to be, or have been, exceeded
+    async fn check_budgets(
+        tracker: &Arc<TaskTracker>,
+        dashboard_tx: Option<&tokio::sync::broadcast::Sender<DashboardMessage>>,
+        language: Language,
+    ) {
+        for status in tracker.check_budget_alerts().await {
+            let (severity, text) = match status.state {
+                BudgetState::Exceeded => (
+                    AlertSeverity::Critical,
+                    Text::new(TextKey::BudgetExceeded).arg("scope", &status.scope),
+                ),
+                _ => (
+                    AlertSeverity::Warning,
+                    Text::new(TextKey::BudgetWarning)
+                        .arg("scope", &status.scope)
+                        .arg("percent", format!("{:.0}", status.burn_ratio * 100.0)),
+                ),
+            };
+            let message = text.render(language);
+            warn!("{}: {}", message, status.short_label());
+
+            if let Some(tx) = dashboard_tx {
+                let _ = tx.send(DashboardMessage::Alert(AlertNotification {
+                    id: format!("budget-{}-{}", status.scope, status.period),
+                    severity,
+                    category: "budget".to_string(),
+                    message,
+                    component: Some("task_tracker".to_string()),
+                    timestamp: current_timestamp(),
+                    details: serde_json::to_value(&status).ok(),
+                    text: Some(text),
+                }));
+            }
+        }
+    }
+
+    /// Alert once per task and stage that took, or is taking, longer than its
+    /// latency budget; returns the time of this check
+    async fn check_latency_budgets(
+        tasks: &Arc<RwLock<HashMap<TaskId, Task>>>,
+        config: &TaskConfig,
+        checked_at: u64,
+        alerted: &mut HashSet<(TaskId, LatencyStage)>,
+        dashboard_tx: Option<&tokio::sync::broadcast::Sender<DashboardMessage>>,
+    ) -> u64 {
+        let now = current_timestamp();
+        let breaches = {
+            let tasks = tasks.read().await;
+            latency::budget_breaches(
+                tasks.values(),
+                &config.latency_budgets,
+                Some(checked_at),
+                now,
+            )
+        };
+
+        let current: HashSet<(TaskId, LatencyStage)> = breaches
+            .iter()
+            .map(|breach| (breach.task_id.clone(), breach.stage))
+            .collect();
+        alerted.retain(|key| current.contains(key));
+
+        for breach in breaches {
+            if !alerted.insert((breach.task_id.clone(), breach.stage)) {
+                continue;
+            }
+            let text = Text::new(TextKey::LatencyBudgetExceeded)
+                .arg("task", &breach.task_id)
+                .arg("stage", breach.stage.as_str())
+                .arg("elapsed", breach.elapsed_secs)
+                .arg("budget", breach.max_secs);
+            let message = text.render(config.language);
+            warn!("{}", message);
+
+            if let Some(tx) = dashboard_tx {
+                let _ = tx.send(DashboardMessage::Alert(AlertNotification {
+                    id: format!("latency-{}-{}", breach.task_id, breach.stage.as_str()),
+                    severity: AlertSeverity::Warning,
+                    category: "latency".to_string(),
+                    message,
+                    component: Some("task_manager".to_string()),
+                    timestamp: now,
+                    details: serde_json::to_value(&breach).ok(),
+                    text: Some(text),
+                }));
+            }
+        }
+        now
+    }
+
     /// Process one tick of the queue
+    #[allow(clippy::too_many_arguments)]
     async fn process_queue_tick(
+        tasks: &Arc<RwLock<HashMap<TaskId, Task>>>,
         queue: &Arc<TaskQueue>,
         executing_tasks: &Arc<RwLock<HashMap<TaskId, ExecutingTask>>>,
         config: &TaskConfig,
         tracker: &Arc<TaskTracker>,
+        min_priority: Option<&TaskPriority>,
+        directory: &ProcessDirectory,
+        event_bridge: Option<&Arc<EventBridge>>,
+        outputs: &Arc<TaskOutputStore>,
+        dead_letters: &Arc<DeadLetterStore>,
+        dashboard_tx: Option<&tokio::sync::broadcast::Sender<DashboardMessage>>,
     ) {
         // Check if we can start more tasks
         let current_executing = {
@@ -130,22 +384,109 @@ impl TaskManager {
             executing.len()
         };
 
+        let draining = directory.draining_workspaces().await;
         if current_executing >= config.max_concurrent_tasks {
-            return; // At capacity
+            // At capacity; an urgent task may take the slot of a lesser one
+            if config.preemption.policy != PreemptionPolicy::Off {
+                Self::preempt_for_waiting(
+                    tasks,
+                    queue,
+                    executing_tasks,
+                    config,
+                    tracker,
+                    min_priority,
+                    &draining,
+                    dashboard_tx,
+                )
+                .await;
+            }
+            return;
         }
 
-        // Try to dequeue a ready task
-        if let Some(mut task) = queue.dequeue().await {
-            task.update_status(TaskStatus::InProgress);
+        // Try to dequeue a ready task, holding back deferred priorities and
+        // tasks of draining workspaces
+        let next = match min_priority {
+            _ if !draining.is_empty() => {
+                queue
+                    .dequeue_where(|task| {
+                        min_priority.map_or(true, |min_priority| task.priority >= *min_priority)
+                            && !task
+                                .workspace
+                                .as_ref()
+                                .is_some_and(|workspace| draining.contains(workspace))
+                    })
+                    .await
+            }
+            Some(min_priority) => queue.dequeue_at_least(min_priority).await,
+            None => queue.dequeue().await,
+        };
+        if let Some(mut task) = next {
+            // Tasks of a workspace with processes run on its least-loaded healthy
+            // one; shell commands are run by the executor itself
+            let process_id = match task.workspace.as_deref().filter(|_| !task.runs_locally()) {
+                Some(workspace) => {
+                    match Self::route_task(&task, workspace, directory, executing_tasks).await {
+                        Route::Local => None,
+                        Route::Process(process_id) => Some(process_id),
+                        Route::Wait => {
+                            debug!(
+                                "No healthy process in workspace {} for task {}, requeueing",
+                                workspace, task.id
+                            );
+                            if let Err(e) = queue.enqueue(task).await {
+                                warn!("Failed to requeue task: {}", e);
+                            }
+                            return;
+                        }
+                    }
+                }
+                None => None,
+            };
+            if let Some(ref process_id) = process_id {
+                task.assignee = Some(process_id.clone());
+            }
+            task.stages.assigned_at.get_or_insert(current_timestamp());
 
+            task.update_status(TaskStatus::InProgress);
+            let attempt = task.begin_attempt();
+            debug!(
+                "Task {} attempt {} started by {}",
+                task.id,
+                attempt,
+                task.agent_id()
+            );
+
+            // Routed tasks are handed to their process
+            let delegate = process_id.clone().zip(directory.coordinator.clone());
             let executing_task = ExecutingTask {
                 task_id: task.id.clone(),
                 started_at: current_timestamp(),
                 timeout_at: task.execution.timeout.map(|t| current_timestamp() + t),
+                workspace: task.workspace.clone(),
+                process_id,
+                abort_handle: None,
             };
 
-            // Start tracking
-            tracker.start_task(&task.id).await;
+            // Start tracking, continuing the session of a resumed or
+            // preempted task
+            if tracker.get_active_session(&task.id).await.is_some() {
+                tracker.resume_task(&task.id).await;
+            } else {
+                tracker
+                    .set_task_category(&task.id, task.category.to_string())
+                    .await;
+                if let Some(ref workspace) = task.workspace {
+                    tracker
+                        .set_task_workspace(&task.id, workspace.clone())
+                        .await;
+                }
+                if let Some(ref assignee) = task.assignee {
+                    tracker.set_task_assignee(&task.id, assignee.clone()).await;
+                }
+                tracker.start_task(&task.id).await;
+            }
+
+            Self::store_task_state(tasks, &task).await;
 
             // Add to executing tasks
             {
@@ -155,28 +496,431 @@ impl TaskManager {
 
             // Spawn execution task
             let task_id = task.id.clone();
+            let handle_task_id = task.id.clone();
+            let tasks_ref = Arc::clone(tasks);
             let executing_tasks_ref = Arc::clone(executing_tasks);
             let tracker_ref = Arc::clone(tracker);
+            let event_bridge = event_bridge.cloned();
+            let outputs = Arc::clone(outputs);
+            let dead_letters = Arc::clone(dead_letters);
+            let dashboard_tx = dashboard_tx.cloned();
+
+            let handle = tokio::spawn(async move {
+                let result = match delegate {
+                    Some((process_id, coordinator)) => {
+                        Self::delegate_task(&mut task, &process_id, &coordinator, &outputs).await
+                    }
+                    None => Self::execute_task(&mut task, &outputs).await,
+                };
+                match &result {
+                    Ok(()) => task.end_attempt(ExecutionResult::Success, None),
+                    Err(e) => task.end_attempt(ExecutionResult::Failed, Some(e.to_string())),
+                };
+
+                // Remove from executing; if it is already gone the task was
+                // cancelled or paused and its state is owned by that call
+                let still_executing = {
+                    let mut executing = executing_tasks_ref.write().await;
+                    executing.remove(&task_id).is_some()
+                };
 
-            tokio::spawn(async move {
-                let result = Self::execute_task(task).await;
+                if still_executing {
+                    Self::store_task_state(&tasks_ref, &task).await;
 
-                // Remove from executing
-                {
-                    let mut executing = executing_tasks_ref.write().await;
-                    executing.remove(&task_id);
-                }
+                    // Stop tracking
+                    tracker_ref.stop_task(&task_id).await;
 
-                // Stop tracking
-                tracker_ref.stop_task(&task_id).await;
+                    if task.status == TaskStatus::Completed {
+                        publish_completion(event_bridge.as_ref(), &task);
+                    }
+                    let output = outputs.get(&task_id).await;
+                    if task.status == TaskStatus::Failed {
+                        dead_letter(&dead_letters, &task, output.as_ref(), dashboard_tx.as_ref());
+                    }
+                    if let Some(ref tx) = dashboard_tx {
+                        send_task_update(tx, &task, TaskAction::StatusChanged, output.as_ref());
+                    }
+                }
 
                 debug!("Task {} execution completed: {:?}", task_id, result);
             });
+
+            let mut executing = executing_tasks.write().await;
+            if let Some(executing_task) = executing.get_mut(&handle_task_id) {
+                executing_task.abort_handle = Some(handle.abort_handle());
+            }
+        }
+    }
+
+    /// Interrupt one running task for the highest-priority waiting task
+    ///
+    /// The preempted task is requeued; its tracking session is paused until
+    /// it runs again. With the pause policy it keeps its process assignment,
+    /// with reassign it goes to whichever process is free first.
+    #[allow(clippy::too_many_arguments)]
+    async fn preempt_for_waiting(
+        tasks: &Arc<RwLock<HashMap<TaskId, Task>>>,
+        queue: &Arc<TaskQueue>,
+        executing_tasks: &Arc<RwLock<HashMap<TaskId, ExecutingTask>>>,
+        config: &TaskConfig,
+        tracker: &Arc<TaskTracker>,
+        min_priority: Option<&TaskPriority>,
+        draining: &HashSet<String>,
+        dashboard_tx: Option<&tokio::sync::broadcast::Sender<DashboardMessage>>,
+    ) {
+        let Some(waiting) = queue
+            .list_tasks()
+            .await
+            .into_iter()
+            .filter(|task| {
+                min_priority.map_or(true, |min_priority| task.priority >= *min_priority)
+                    && !task
+                        .workspace
+                        .as_ref()
+                        .is_some_and(|workspace| draining.contains(workspace))
+            })
+            .max_by(|a, b| a.priority.cmp(&b.priority))
+        else {
+            return;
+        };
+
+        let running: Vec<RunningTask> = {
+            let executing = executing_tasks.read().await;
+            let tasks = tasks.read().await;
+            executing
+                .values()
+                .filter_map(|e| {
+                    Some(RunningTask {
+                        task_id: e.task_id.clone(),
+                        priority: tasks.get(&e.task_id)?.priority.clone(),
+                        started_at: e.started_at,
+                    })
+                })
+                .collect()
+        };
+        let Some(victim) = config
+            .preemption
+            .select_victim(&waiting.priority, &running)
+            .map(|victim| victim.task_id.clone())
+        else {
+            return;
+        };
+
+        // Gone already when the execution finished in the meantime
+        let Some(executing_task) = executing_tasks.write().await.remove(&victim) else {
+            return;
+        };
+        if let Some(handle) = executing_task.abort_handle {
+            handle.abort();
+        }
+
+        let policy = config.preemption.policy;
+        let preempted = {
+            let mut tasks = tasks.write().await;
+            let Some(task) = tasks.get_mut(&victim) else {
+                return;
+            };
+            task.end_attempt(
+                ExecutionResult::Paused,
+                Some(format!("Preempted by {}", waiting.id)),
+            );
+            task.update_status(TaskStatus::Todo);
+            if policy == PreemptionPolicy::Reassign {
+                task.assignee = None;
+            }
+            task.add_note(
+                format!(
+                    "Preempted by {:?} task {} ({}), requeued",
+                    waiting.priority,
+                    waiting.id,
+                    policy.as_str()
+                ),
+                None,
+            );
+            task.clone()
+        };
+        tracker.pause_task(&victim).await;
+
+        info!(
+            "Task {} ({:?}) preempted by {} ({:?}) with policy {}",
+            victim,
+            preempted.priority,
+            waiting.id,
+            waiting.priority,
+            policy.as_str()
+        );
+        if let Some(tx) = dashboard_tx {
+            send_task_update(tx, &preempted, TaskAction::Preempted, None);
+        }
+        if let Err(e) = queue.enqueue(preempted).await {
+            warn!("Failed to requeue preempted task {}: {}", victim, e);
+        }
+    }
+
+    /// Pick the process a task of `workspace` runs on
+    ///
+    /// Only processes connected to the coordination bus can take tasks. An
+    /// assigned process keeps the task while it is healthy; otherwise the
+    /// distributor picks the healthy process with the fewest running tasks.
+    async fn route_task(
+        task: &Task,
+        workspace: &str,
+        directory: &ProcessDirectory,
+        executing_tasks: &Arc<RwLock<HashMap<TaskId, ExecutingTask>>>,
+    ) -> Route {
+        let processes = directory.receiving_processes(workspace).await;
+        if processes.is_empty() {
+            return Route::Local;
+        }
+
+        let mut healthy: Vec<String> = processes
+            .into_iter()
+            .filter(|process| is_healthy(&process.status))
+            .map(|process| process.id)
+            .collect();
+        healthy.sort();
+        if let Some(assignee) = task.assignee.as_ref().filter(|a| healthy.contains(a)) {
+            return Route::Process(assignee.clone());
+        }
+
+        let loads: Vec<(String, ProcessLoad)> = {
+            let executing = executing_tasks.read().await;
+            healthy
+                .into_iter()
+                .map(|process_id| {
+                    let active_tasks = executing
+                        .values()
+                        .filter(|e| e.process_id.as_ref() == Some(&process_id))
+                        .count();
+                    let load = ProcessLoad {
+                        cpu_usage: 0.0,
+                        memory_usage: 0.0,
+                        active_tasks,
+                    };
+                    (process_id, load)
+                })
+                .collect()
+        };
+        let candidate = DistributedTask::from_task(task.clone());
+        least_loaded(loads.iter().map(|(id, load)| (id, load)), &candidate)
+            .map_or(Route::Wait, |process_id| Route::Process(process_id.clone()))
+    }
+
+    /// Requeue tasks whose process stopped or disappeared
+    ///
+    /// The interrupted attempt is recorded as failed; once a task has failed
+    /// more than `max_retry_attempts` times it stays failed and is
+    /// dead-lettered.
+    #[allow(clippy::too_many_arguments)]
+    async fn recover_lost_assignments(
+        tasks: &Arc<RwLock<HashMap<TaskId, Task>>>,
+        queue: &Arc<TaskQueue>,
+        executing_tasks: &Arc<RwLock<HashMap<TaskId, ExecutingTask>>>,
+        config: &TaskConfig,
+        tracker: &Arc<TaskTracker>,
+        directory: &ProcessDirectory,
+        dead_letters: &DeadLetterStore,
+        dashboard_tx: Option<&tokio::sync::broadcast::Sender<DashboardMessage>>,
+    ) {
+        let assigned: Vec<(TaskId, String, String)> = {
+            let executing = executing_tasks.read().await;
+            executing
+                .iter()
+                .filter_map(|(task_id, e)| {
+                    Some((task_id.clone(), e.workspace.clone()?, e.process_id.clone()?))
+                })
+                .collect()
+        };
+
+        for (task_id, workspace, process_id) in assigned {
+            let alive = directory
+                .processes(&workspace)
+                .await
+                .iter()
+                .any(|process| process.id == process_id && is_healthy(&process.status));
+            if alive {
+                continue;
+            }
+
+            // Gone already when the execution finished in the meantime
+            let Some(executing_task) = executing_tasks.write().await.remove(&task_id) else {
+                continue;
+            };
+            if let Some(handle) = executing_task.abort_handle {
+                handle.abort();
+            }
+
+            let retry = {
+                let mut tasks = tasks.write().await;
+                let Some(task) = tasks.get_mut(&task_id) else {
+                    continue;
+                };
+                task.end_attempt(
+                    ExecutionResult::Failed,
+                    Some(format!("Process {process_id} is no longer available")),
+                );
+                let failures = task
+                    .execution_history
+                    .iter()
+                    .filter(|record| record.result == ExecutionResult::Failed)
+                    .count() as u32;
+                if failures > config.max_retry_attempts {
+                    task.update_status(TaskStatus::Failed);
+                    dead_letter(dead_letters, task, None, dashboard_tx);
+                    None
+                } else {
+                    task.update_status(TaskStatus::Todo);
+                    Some(task.clone())
+                }
+            };
+
+            match retry {
+                Some(task) => {
+                    info!(
+                        "Process {} lost, requeueing task {} for another process",
+                        process_id, task_id
+                    );
+                    if let Err(e) = queue.enqueue(task).await {
+                        warn!("Failed to requeue task {}: {}", task_id, e);
+                    }
+                }
+                None => {
+                    warn!(
+                        "Process {} lost, task {} failed after {} retries",
+                        process_id, task_id, config.max_retry_attempts
+                    );
+                    tracker.stop_task(&task_id).await;
+                }
+            }
+        }
+    }
+
+    /// Queue tasks held back by dependencies once all of them have completed
+    async fn release_unblocked(tasks: &Arc<RwLock<HashMap<TaskId, Task>>>, queue: &Arc<TaskQueue>) {
+        let released: Vec<Task> = {
+            let mut tasks = tasks.write().await;
+            let ready: Vec<TaskId> = tasks
+                .values()
+                .filter(|task| {
+                    task.status == TaskStatus::Todo
+                        && task.stages.enqueued_at.is_none()
+                        && !task.dependencies.is_empty()
+                        && task.dependencies.iter().all(|dependency| {
+                            tasks.get(dependency).is_some_and(|dependency| {
+                                dependency.status == TaskStatus::Completed
+                            })
+                        })
+                })
+                .map(|task| task.id.clone())
+                .collect();
+            let now = current_timestamp();
+            ready
+                .iter()
+                .filter_map(|task_id| {
+                    let task = tasks.get_mut(task_id)?;
+                    task.stages.enqueued_at = Some(now);
+                    Some(task.clone())
+                })
+                .collect()
+        };
+
+        for task in released {
+            let task_id = task.id.clone();
+            match queue.enqueue(task).await {
+                Ok(()) => debug!("Dependencies of task {} completed, queued", task_id),
+                Err(e) => {
+                    // Tried again on the next tick
+                    warn!("Failed to queue unblocked task {}: {}", task_id, e);
+                    if let Some(task) = tasks.write().await.get_mut(&task_id) {
+                        task.stages.enqueued_at = None;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Write back the status of a task the executor owns, unless it was deleted meanwhile
+    async fn store_task_state(tasks: &Arc<RwLock<HashMap<TaskId, Task>>>, task: &Task) {
+        let mut tasks = tasks.write().await;
+        if let Some(stored) = tasks.get_mut(&task.id) {
+            stored.status = task.status.clone();
+            stored.assignee = task.assignee.clone();
+            stored.started_at = task.started_at;
+            stored.completed_at = task.completed_at;
+            stored.actual_duration = task.actual_duration;
+            stored.progress = task.progress;
+            stored.updated_at = task.updated_at;
+            stored.execution_history = task.execution_history.clone();
+        }
+    }
+
+    /// Hand a task to its process over the coordination bus
+    ///
+    /// The process answers the `TaskAssignment` with `Data` once it is done,
+    /// or with `Acknowledged` and reports `TaskCompleted` to the coordinator
+    /// later. Only then does the task move on: manual tasks to review, the
+    /// others to completed. The result is kept as the task's output.
+    async fn delegate_task(
+        task: &mut Task,
+        process_id: &str,
+        coordinator: &ProcessCoordinator,
+        outputs: &TaskOutputStore,
+    ) -> TaskResult<()> {
+        debug!("Delegating task {} to process {}", task.id, process_id);
+
+        // Subscribed first so a quick completion is not missed
+        let mut completions = coordinator.subscribe_completions();
+        let assignment = CoordinationMessage::new(
+            COORDINATOR_ID.to_string(),
+            process_id.to_string(),
+            CoordinationEvent::TaskAssignment {
+                task_id: task.id.clone(),
+                description: task
+                    .description
+                    .clone()
+                    .unwrap_or_else(|| task.title.clone()),
+            },
+        );
+        let result = match coordinator.dispatch(assignment).await {
+            CoordinationResponse::Data { payload, .. } => match payload {
+                serde_json::Value::String(text) => text,
+                payload => payload.to_string(),
+            },
+            CoordinationResponse::Acknowledged { .. } => loop {
+                match completions.recv().await {
+                    Ok(completion)
+                        if completion.task_id == task.id && completion.process_id == process_id =>
+                    {
+                        break completion.result;
+                    }
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => {
+                        task.update_status(TaskStatus::Failed);
+                        return Err(TaskError::ExecutionFailed(
+                            "Coordinator stopped before the task completed".to_string(),
+                        ));
+                    }
+                }
+            },
+            CoordinationResponse::Error { error, .. } => {
+                task.update_status(TaskStatus::Failed);
+                return Err(TaskError::ExecutionFailed(format!(
+                    "Process {process_id} did not take the task: {error}"
+                )));
+            }
+        };
+
+        outputs.record(task, Some(0), result.as_bytes(), b"").await;
+        if task.execution.mode == super::types::ExecutionMode::Manual {
+            task.update_status(TaskStatus::Review);
+        } else {
+            task.update_status(TaskStatus::Completed);
         }
+        Ok(())
     }
 
     /// Execute a single task
-    async fn execute_task(mut task: Task) -> TaskResult<()> {
+    async fn execute_task(task: &mut Task, outputs: &TaskOutputStore) -> TaskResult<()> {
         debug!("Executing task: {}", task.id);
 
         // Simulate task execution based on task type
@@ -189,9 +933,30 @@ impl TaskManager {
             }
             super::types::ExecutionMode::Automatic => {
                 // Automatic tasks execute their command
-                if let Some(command) = &task.execution.command {
-                    match Self::execute_command(command, &task).await {
-                        Ok(_) => task.update_status(TaskStatus::Completed),
+                if let Some(command) = task.execution.command.clone() {
+                    let artifacts_dir = outputs.artifacts_dir(&task.id);
+                    let result =
+                        match Self::execute_command(&command, task, artifacts_dir.as_deref()).await
+                        {
+                            Ok(output) => {
+                                let captured = outputs
+                                    .record(
+                                        task,
+                                        output.status.code(),
+                                        &output.stdout,
+                                        &output.stderr,
+                                    )
+                                    .await;
+                                check_exit(&command, output.status, &captured)
+                            }
+                            Err(e) => Err(e),
+                        };
+                    if let Some(dir) = artifacts_dir {
+                        // Only spilled output or files the command wrote keep it
+                        let _ = std::fs::remove_dir(dir);
+                    }
+                    match result {
+                        Ok(()) => task.update_status(TaskStatus::Completed),
                         Err(e) => {
                             task.update_status(TaskStatus::Failed);
                             return Err(e);
@@ -212,19 +977,54 @@ impl TaskManager {
         Ok(())
     }
 
-    /// Execute a command for a task
-    async fn execute_command(command: &str, task: &Task) -> TaskResult<String> {
-        debug!("Executing command for task {}: {}", task.id, command);
-
-        // In a real implementation, this would execute the actual command
-        // For now, we simulate success
-        sleep(Duration::from_millis(100)).await;
+    /// Run a task's command in its shell, working directory and environment
+    ///
+    /// The command finds its artifacts directory in `$WEZTERM_PARALLEL_ARTIFACTS_DIR`.
+    async fn execute_command(
+        command: &str,
+        task: &Task,
+        artifacts_dir: Option<&Path>,
+    ) -> TaskResult<std::process::Output> {
+        let execution = &task.execution;
+        debug!(
+            "Executing command for task {} with {}: {}",
+            task.id,
+            execution.shell(),
+            command
+        );
+
+        let mut child = tokio::process::Command::new(execution.shell());
+        child
+            .arg("-c")
+            .arg(command)
+            .envs(&execution.environment)
+            .stdin(std::process::Stdio::null())
+            .kill_on_drop(true);
+        if let Some(dir) = &execution.working_directory {
+            child.current_dir(dir);
+        }
+        if let Some(dir) = artifacts_dir {
+            match std::fs::create_dir_all(dir) {
+                Ok(()) => {
+                    child.env(ARTIFACTS_DIR_ENV, dir);
+                }
+                Err(e) => warn!("Failed to create artifacts directory {:?}: {}", dir, e),
+            }
+        }
 
-        Ok("Command executed successfully".to_string())
+        // Dropping the output future on timeout kills the command
+        match execution.timeout {
+            Some(secs) => tokio::time::timeout(Duration::from_secs(secs), child.output())
+                .await
+                .map_err(|_| TaskError::Timeout(format!("'{command}' ran longer than {secs}s")))?,
+            None => child.output().await,
+        }
+        .map_err(|e| TaskError::ExecutionFailed(format!("Failed to run '{command}': {e}")))
     }
 
     /// Clean up completed tasks
     async fn cleanup_completed_tasks(
+        tasks: &Arc<RwLock<HashMap<TaskId, Task>>>,
         executing_tasks: &Arc<RwLock<HashMap<TaskId, ExecutingTask>>>,
         _config: &TaskConfig,
     ) {
@@ -247,15 +1047,24 @@ impl TaskManager {
         if !to_timeout.is_empty() {
             warn!("Timing out {} tasks", to_timeout.len());
             let mut executing = executing_tasks.write().await;
+            let mut tasks = tasks.write().await;
             for task_id in to_timeout {
                 executing.remove(&task_id);
+                if let Some(task) = tasks.get_mut(&task_id) {
+                    task.end_attempt(ExecutionResult::Timeout, None);
+                }
             }
         }
     }
 
     /// Create a new task
+    ///
+    /// The task gets a short ID; under the short ID strategy that also becomes
+    /// its primary ID. Dependencies may be given by short ID.
     pub async fn create_task(&self, mut task: Task) -> TaskResult<TaskId> {
-        let task_id = task.id.clone();
+        for dependency in task.dependencies.iter_mut() {
+            *dependency = self.resolve_task_id(dependency).await;
+        }
 
         // Validate task
         self.validate_task(&task).await?;
@@ -263,15 +1072,31 @@ impl TaskManager {
         // Set initial status
         task.update_status(TaskStatus::Todo);
 
+        // Tasks blocked by dependencies are not queued yet
+        let ready = task.dependencies.is_empty() || self.are_dependencies_met(&task).await;
+        if ready {
+            task.stages.enqueued_at.get_or_insert(current_timestamp());
+        }
+
         // Store task
-        {
+        let task_id = {
             let mut tasks = self.tasks.write().await;
-            tasks.insert(task_id.clone(), task.clone());
-        }
+            assign_short_id(&self.short_ids, &tasks, &mut task);
+            if let Some(ref short_id) = task.short_id {
+                task.id = self.short_ids.primary_id(task.id, short_id);
+            }
+            tasks.insert(task.id.clone(), task.clone());
+            task.id.clone()
+        };
 
-        // Add to queue if not blocked by dependencies
-        if task.dependencies.is_empty() || self.are_dependencies_met(&task).await {
-            self.queue.enqueue(task).await?;
+        if ready {
+            if let Err(e) = self.queue.enqueue(task).await {
+                if matches!(e, TaskError::QueueFull) {
+                    self.record_shedding(ShedAction::Rejected, &task_id).await;
+                }
+                return Err(e);
+            }
+            self.record_shedding(ShedAction::Deferred, &task_id).await;
         }
 
         // Update statistics
@@ -289,6 +1114,126 @@ impl TaskManager {
         Ok(task_id)
     }
 
+    /// Create the tasks of a batch, each after the batch tasks it depends on
+    ///
+    /// Dependencies name other items of the batch by key, or existing tasks
+    /// by ID; keys win over task IDs. Atomic batches create nothing unless
+    /// every task can be created and roll back the tasks already created when
+    /// a later one fails; otherwise each task is created on its own and only
+    /// the dependents of a failed task fail with it.
+    pub async fn create_batch(&self, items: Vec<BatchItem>, atomic: bool) -> BatchReport {
+        let mut results: Vec<BatchItemResult> = items
+            .iter()
+            .map(|item| BatchItemResult {
+                key: item.key.clone(),
+                task_id: None,
+                error: item.task.as_ref().err().cloned(),
+            })
+            .collect();
+        let order = batch::creation_order(&items, &mut results);
+        if atomic && results.iter().any(|result| result.error.is_some()) {
+            return reject_batch(results);
+        }
+
+        let keys: HashMap<String, usize> = items
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(i, item)| (item.key.clone(), i))
+            .collect();
+        let mut created = Vec::new();
+        for i in order {
+            let mut dependencies = Vec::new();
+            for dependency in &items[i].depends_on {
+                match keys.get(dependency) {
+                    Some(&index) => match &results[index].task_id {
+                        Some(task_id) => dependencies.push(task_id.clone()),
+                        None => {
+                            results[i].error =
+                                Some(format!("Dependency '{dependency}' was not created"));
+                            break;
+                        }
+                    },
+                    None => dependencies.push(dependency.clone()),
+                }
+            }
+            if let (None, Ok(task)) = (&results[i].error, &items[i].task) {
+                let mut task = task.clone();
+                task.dependencies.extend(dependencies);
+                match self.create_task(task).await {
+                    Ok(task_id) => {
+                        results[i].task_id = Some(task_id.clone());
+                        created.push(task_id);
+                    }
+                    Err(e) => results[i].error = Some(e.to_string()),
+                }
+            }
+
+            if atomic && results[i].error.is_some() {
+                for task_id in &created {
+                    self.stop_execution(task_id).await;
+                    let _ = self.delete_task(task_id).await;
+                }
+                for result in results.iter_mut().filter(|r| r.task_id.is_some()) {
+                    result.error =
+                        Some("Rolled back: another task of the batch failed".to_string());
+                }
+                info!(
+                    "Batch rolled back after {} of {} tasks",
+                    created.len(),
+                    results.len()
+                );
+                return reject_batch(results);
+            }
+        }
+
+        let report = BatchReport::new(atomic, results);
+        info!(
+            "Batch created {} tasks, {} failed",
+            report.created, report.failed
+        );
+        report
+    }
+
+    /// Stage percentiles of tasks whose stages ended at or after `since`, and
+    /// the stages over their latency budget
+    pub async fn latency_report(&self, since: Option<u64>) -> LatencyReport {
+        let tasks = self.tasks.read().await;
+        latency::latency_report(
+            tasks.values(),
+            &self.config.latency_budgets,
+            since,
+            current_timestamp(),
+        )
+    }
+
+    /// Record a task rejected by the full queue, or deferred because all
+    /// execution slots are taken
+    async fn record_shedding(&self, action: ShedAction, task_id: &str) {
+        let Some(shed_log) = &self.shed_log else {
+            return;
+        };
+        let trigger = match action {
+            ShedAction::Rejected => ShedTrigger::new(
+                "task_queue_size",
+                self.queue.size().await as f64,
+                self.queue.get_config().max_size as f64,
+            ),
+            _ => {
+                let executing = self.executing_tasks.read().await.len();
+                if executing < self.config.max_concurrent_tasks {
+                    return;
+                }
+                ShedTrigger::new(
+                    "executing_tasks",
+                    executing as f64,
+                    self.config.max_concurrent_tasks as f64,
+                )
+            }
+        };
+        shed_log.record("task_queue", action, trigger, task_id);
+    }
+
     /// Create task from template
     pub async fn create_task_from_template(
         &self,
@@ -327,13 +1272,15 @@ impl TaskManager {
         task.updated_at = current_timestamp();
 
         // Update in storage
-        {
+        let previous_status = {
             let mut tasks = self.tasks.write().await;
-            if tasks.contains_key(&task_id) {
-                tasks.insert(task_id.clone(), task.clone());
-            } else {
+            let Some(stored) = tasks.get_mut(&task_id) else {
                 return Err(TaskError::TaskNotFound(task_id));
-            }
+            };
+            std::mem::replace(stored, task.clone()).status
+        };
+        if task.status == TaskStatus::Completed && previous_status != TaskStatus::Completed {
+            publish_completion(self.event_bridge.as_ref(), &task);
         }
 
         // Update in queue if present
@@ -349,6 +1296,8 @@ impl TaskManager {
 
     /// Delete a task
     pub async fn delete_task(&self, task_id: &TaskId) -> TaskResult<Task> {
+        let task_id = &self.resolve_task_id(task_id).await;
+
         // Remove from storage
         let task = {
             let mut tasks = self.tasks.write().await;
@@ -369,6 +1318,8 @@ impl TaskManager {
         // Stop tracking
         self.tracker.stop_task(task_id).await;
 
+        self.dead_letters.remove(task_id);
+
         // Notify listeners
         self.notify_listeners(TaskEvent::TaskDeleted(task_id.clone()))
             .await;
@@ -377,60 +1328,329 @@ impl TaskManager {
         Ok(task)
     }
 
-    /// Get a task by ID
-    pub async fn get_task(&self, task_id: &TaskId) -> Option<Task> {
-        let tasks = self.tasks.read().await;
-        tasks.get(task_id).cloned()
-    }
+    /// Cancel a queued, running or paused task
+    ///
+    /// Running executions are aborted and the tracking session is closed.
+    pub async fn cancel_task(&self, task_id: &TaskId) -> TaskResult<Task> {
+        let task_id = &self.resolve_task_id(task_id).await;
+        let task = self
+            .get_task(task_id)
+            .await
+            .ok_or_else(|| TaskError::TaskNotFound(task_id.clone()))?;
+        if task.is_finished() {
+            return Err(TaskError::InvalidConfig(format!(
+                "Task {task_id} is already {:?}",
+                task.status
+            )));
+        }
 
-    /// Get total task count
-    pub async fn get_task_count(&self) -> usize {
-        let tasks = self.tasks.read().await;
-        tasks.len()
-    }
+        let _ = self.queue.remove(task_id).await;
+        self.stop_execution(task_id).await;
+        self.tracker.stop_task(task_id).await;
 
-    /// List tasks with optional filter
-    pub async fn list_tasks(&self, filter: Option<TaskFilter>) -> Vec<Task> {
-        let tasks = self.tasks.read().await;
-        let mut result: Vec<Task> = tasks.values().cloned().collect();
+        let task = self
+            .set_task_status(task_id, TaskStatus::Cancelled, "Task cancelled")
+            .await?;
 
-        // Apply filters
-        if let Some(filter) = filter {
-            result = self.apply_filter(result, filter);
-        }
+        self.notify_listeners(TaskEvent::TaskCancelled(task_id.clone()))
+            .await;
+        self.broadcast_task_update(&task, TaskAction::StatusChanged);
 
-        result
+        info!("Task cancelled: {}", task_id);
+        Ok(task)
     }
 
-    /// Get task statistics
-    pub async fn get_stats(&self) -> TaskSystemStats {
-        let mut stats = self.stats.read().await.clone();
+    /// Put a queued or running task on hold
+    ///
+    /// A running execution is stopped and will start again on resume; the
+    /// tracking session is paused rather than closed.
+    pub async fn pause_task(&self, task_id: &TaskId) -> TaskResult<Task> {
+        let task_id = &self.resolve_task_id(task_id).await;
+        let task = self
+            .get_task(task_id)
+            .await
+            .ok_or_else(|| TaskError::TaskNotFound(task_id.clone()))?;
+        if !matches!(task.status, TaskStatus::Todo | TaskStatus::InProgress) {
+            return Err(TaskError::InvalidConfig(format!(
+                "Task {task_id} cannot be paused while {:?}",
+                task.status
+            )));
+        }
 
-        // Update real-time stats
-        stats.active_tasks = {
-            let executing = self.executing_tasks.read().await;
-            executing.len()
-        };
+        let _ = self.queue.remove(task_id).await;
+        self.stop_execution(task_id).await;
+        self.tracker.pause_task(task_id).await;
 
-        stats.queued_tasks = self.queue.size().await;
-        stats.update();
+        let task = self
+            .set_task_status(task_id, TaskStatus::OnHold, "Task paused")
+            .await?;
 
-        stats
+        self.notify_listeners(TaskEvent::TaskPaused(task_id.clone()))
+            .await;
+        self.broadcast_task_update(&task, TaskAction::StatusChanged);
+
+        info!("Task paused: {}", task_id);
+        Ok(task)
     }
 
-    /// Validate task data
-    async fn validate_task(&self, task: &Task) -> TaskResult<()> {
-        if task.title.trim().is_empty() {
-            return Err(TaskError::InvalidConfig(
-                "Task title cannot be empty".to_string(),
-            ));
+    /// Put a paused task back into the queue
+    pub async fn resume_task(&self, task_id: &TaskId) -> TaskResult<Task> {
+        let task_id = &self.resolve_task_id(task_id).await;
+        let task = self
+            .get_task(task_id)
+            .await
+            .ok_or_else(|| TaskError::TaskNotFound(task_id.clone()))?;
+        if task.status != TaskStatus::OnHold {
+            return Err(TaskError::InvalidConfig(format!(
+                "Task {task_id} is not paused"
+            )));
         }
 
-        // Validate dependencies exist
-        for dep_id in &task.dependencies {
-            if self.get_task(dep_id).await.is_none() {
-                return Err(TaskError::DependencyNotMet(dep_id.clone()));
-            }
+        let task = self
+            .set_task_status(task_id, TaskStatus::Todo, "Task resumed")
+            .await?;
+        self.queue.enqueue(task.clone()).await?;
+        self.tracker.resume_task(task_id).await;
+
+        self.notify_listeners(TaskEvent::TaskResumed(task_id.clone()))
+            .await;
+        self.broadcast_task_update(&task, TaskAction::StatusChanged);
+
+        info!("Task resumed: {}", task_id);
+        Ok(task)
+    }
+
+    /// Tasks that failed for good, newest first
+    pub fn dead_letters(&self) -> Vec<DeadLetter> {
+        self.dead_letters.list()
+    }
+
+    /// Give a dead-lettered task another run
+    pub async fn requeue_dead_letter(&self, task_id: &TaskId) -> TaskResult<Task> {
+        let task_id = &self.resolve_task_id(task_id).await;
+        if !self.dead_letters.contains(task_id) {
+            return Err(TaskError::InvalidConfig(format!(
+                "Task {task_id} is not in the dead-letter queue"
+            )));
+        }
+
+        let task = self
+            .set_task_status(
+                task_id,
+                TaskStatus::Todo,
+                "Requeued from the dead-letter queue",
+            )
+            .await?;
+        self.queue.enqueue(task.clone()).await?;
+        self.dead_letters.remove(task_id);
+
+        self.broadcast_task_update(&task, TaskAction::StatusChanged);
+        if let Some(ref tx) = self.dashboard_tx {
+            send_dead_letters(tx, &self.dead_letters);
+        }
+
+        info!("Task requeued from the dead-letter queue: {}", task_id);
+        Ok(task)
+    }
+
+    /// Drop the dead letter of `task_id`, or all of them; the tasks stay
+    /// failed. Returns how many were dropped.
+    pub async fn purge_dead_letters(&self, task_id: Option<&TaskId>) -> usize {
+        let purged = match task_id {
+            Some(task_id) => {
+                let task_id = self.resolve_task_id(task_id).await;
+                self.dead_letters.purge(Some(&task_id))
+            }
+            None => self.dead_letters.purge(None),
+        };
+        if purged > 0 {
+            if let Some(ref tx) = self.dashboard_tx {
+                send_dead_letters(tx, &self.dead_letters);
+            }
+        }
+        purged
+    }
+
+    /// Abort a running execution, if any
+    async fn stop_execution(&self, task_id: &TaskId) {
+        let executing_task = {
+            let mut executing = self.executing_tasks.write().await;
+            executing.remove(task_id)
+        };
+
+        if let Some(handle) = executing_task.and_then(|t| t.abort_handle) {
+            handle.abort();
+        }
+    }
+
+    /// Update the stored status of a task and record a note
+    async fn set_task_status(
+        &self,
+        task_id: &TaskId,
+        status: TaskStatus,
+        note: &str,
+    ) -> TaskResult<Task> {
+        let mut tasks = self.tasks.write().await;
+        let task = tasks
+            .get_mut(task_id)
+            .ok_or_else(|| TaskError::TaskNotFound(task_id.clone()))?;
+
+        // Close the span of an execution stopped by this change
+        match status {
+            TaskStatus::Cancelled => task.end_attempt(ExecutionResult::Cancelled, None),
+            TaskStatus::OnHold => task.end_attempt(ExecutionResult::Paused, None),
+            _ => false,
+        };
+        task.update_status(status);
+        task.add_note(note.to_string(), None);
+        Ok(task.clone())
+    }
+
+    /// Append a note to a task, returning the stored note
+    pub async fn add_task_note(
+        &self,
+        task_id: &TaskId,
+        content: String,
+        author: Option<String>,
+    ) -> TaskResult<TaskNote> {
+        let task_id = &self.resolve_task_id(task_id).await;
+        let task = {
+            let mut tasks = self.tasks.write().await;
+            let task = tasks
+                .get_mut(task_id)
+                .ok_or_else(|| TaskError::TaskNotFound(task_id.clone()))?;
+            task.add_note(content, author);
+            task.clone()
+        };
+
+        self.notify_listeners(TaskEvent::TaskUpdated(task_id.clone()))
+            .await;
+        self.broadcast_task_update(&task, TaskAction::Updated);
+
+        debug!("Note added to task: {}", task_id);
+        Ok(task.notes.last().cloned().expect("note was just added"))
+    }
+
+    /// Task whose tracking session saw the most recent activity
+    ///
+    /// Sessions of unknown tasks (e.g. focus sessions) are ignored.
+    pub async fn current_task(&self) -> Option<Task> {
+        let sessions = self.tracker.get_active_sessions().await;
+        let tasks = self.tasks.read().await;
+        sessions
+            .iter()
+            .filter(|session| tasks.contains_key(&session.task_id))
+            .max_by_key(|session| (!session.is_paused, session.last_activity))
+            .and_then(|session| tasks.get(&session.task_id).cloned())
+    }
+
+    /// Send a task change to the dashboard, if connected
+    fn broadcast_task_update(&self, task: &Task, action: TaskAction) {
+        if let Some(ref tx) = self.dashboard_tx {
+            send_task_update(tx, task, action, None);
+        }
+    }
+
+    /// Status, exit code, output and artifacts of a task's last run
+    pub async fn get_task_result(&self, task_id: &TaskId) -> Option<TaskRunResult> {
+        let task = self.get_task(task_id).await?;
+        let output = self.outputs.get(&task.id).await;
+        Some(TaskRunResult::new(
+            &task,
+            output,
+            self.outputs.artifacts_dir(&task.id),
+        ))
+    }
+
+    /// Get a task by ID or short ID
+    pub async fn get_task(&self, task_id: &TaskId) -> Option<Task> {
+        let tasks = self.tasks.read().await;
+        tasks
+            .get(task_id)
+            .or_else(|| find_by_short_id(&self.short_ids, &tasks, task_id))
+            .cloned()
+    }
+
+    /// Canonical ID of the task referenced by ID or short ID (`WP-12`, matched
+    /// case-insensitively); unknown references are returned unchanged so the
+    /// caller reports them as not found
+    pub async fn resolve_task_id(&self, reference: &str) -> TaskId {
+        let tasks = self.tasks.read().await;
+        if tasks.contains_key(reference) {
+            return reference.to_string();
+        }
+        find_by_short_id(&self.short_ids, &tasks, reference)
+            .map(|task| task.id.clone())
+            .unwrap_or_else(|| reference.to_string())
+    }
+
+    /// Execution trace of a task across its attempts and agents
+    pub async fn get_task_trace(&self, task_id: &TaskId) -> Option<TaskTrace> {
+        self.get_task(task_id)
+            .await
+            .map(|task| TaskTrace::from_task(&task))
+    }
+
+    /// Get total task count
+    pub async fn get_task_count(&self) -> usize {
+        let tasks = self.tasks.read().await;
+        tasks.len()
+    }
+
+    /// List tasks with optional filter
+    pub async fn list_tasks(&self, filter: Option<TaskFilter>) -> Vec<Task> {
+        let tasks = self.tasks.read().await;
+        let mut result: Vec<Task> = tasks.values().cloned().collect();
+
+        // Apply filters
+        if let Some(filter) = filter {
+            result = self.apply_filter(result, filter);
+        }
+
+        result
+    }
+
+    /// Get task statistics
+    pub async fn get_stats(&self) -> TaskSystemStats {
+        let mut stats = self.stats.read().await.clone();
+
+        // Update real-time stats
+        stats.active_tasks = {
+            let executing = self.executing_tasks.read().await;
+            executing.len()
+        };
+
+        stats.queued_tasks = self.queue.size().await;
+
+        {
+            let tasks = self.tasks.read().await;
+            stats.completed_tasks = tasks
+                .values()
+                .filter(|task| task.status == TaskStatus::Completed)
+                .count() as u64;
+            stats.failed_tasks = tasks
+                .values()
+                .filter(|task| task.status == TaskStatus::Failed)
+                .count() as u64;
+        }
+        stats.update();
+
+        stats
+    }
+
+    /// Validate task data
+    async fn validate_task(&self, task: &Task) -> TaskResult<()> {
+        if task.title.trim().is_empty() {
+            return Err(TaskError::InvalidConfig(
+                "Task title cannot be empty".to_string(),
+            ));
+        }
+
+        // Validate dependencies exist
+        for dep_id in &task.dependencies {
+            if self.get_task(dep_id).await.is_none() {
+                return Err(TaskError::DependencyNotMet(dep_id.clone()));
+            }
         }
 
         Ok(())
@@ -514,11 +1734,171 @@ impl TaskManager {
         templates.insert(name, template);
     }
 
+    /// Build a persistable snapshot of all tasks and queue state
+    async fn build_snapshot(
+        tasks: &Arc<RwLock<HashMap<TaskId, Task>>>,
+        queue: &Arc<TaskQueue>,
+        executing_tasks: &Arc<RwLock<HashMap<TaskId, ExecutingTask>>>,
+        tracker: &TaskTracker,
+        short_ids: &ShortIdAllocator,
+        dead_letters: &DeadLetterStore,
+    ) -> PersistedTaskState {
+        let all_tasks: Vec<Task> = tasks.read().await.values().cloned().collect();
+        let queued = queue.list_tasks().await.into_iter().map(|t| t.id).collect();
+        let executing = executing_tasks.read().await.keys().cloned().collect();
+
+        PersistedTaskState::new(all_tasks, queued, executing)
+            .with_tracking_sessions(tracker.get_active_sessions().await)
+            .with_imported_sessions(tracker.imported_sessions().await)
+            .with_next_short_id(short_ids.next_number())
+            .with_dead_letters(dead_letters.snapshot())
+    }
+
+    /// Save the current task state (no-op when persistence is disabled)
+    pub async fn save_state(&self) -> TaskResult<()> {
+        let Some(ref store) = self.persistence else {
+            return Ok(());
+        };
+
+        let snapshot = Self::build_snapshot(
+            &self.tasks,
+            &self.queue,
+            &self.executing_tasks,
+            &self.tracker,
+            &self.short_ids,
+            &self.dead_letters,
+        )
+        .await;
+        store.save(&snapshot)
+    }
+
+    /// Restore tasks from the persisted state and requeue unfinished work
+    ///
+    /// Tasks that were pending or in progress when the daemon stopped are put
+    /// back into the queue; finished tasks are kept as history up to
+    /// `max_task_history`. Time tracking sessions that were active resume,
+    /// with the downtime recorded as a break. Returns the number of requeued
+    /// tasks.
+    pub async fn restore_state(&self) -> TaskResult<usize> {
+        let Some(ref store) = self.persistence else {
+            return Ok(0);
+        };
+
+        let outcome = store.load();
+        if let Some(path) = outcome.quarantined {
+            warn!("Corrupted task state moved to {:?}", path);
+        }
+        let Some(state) = outcome.state else {
+            return Ok(0);
+        };
+
+        let restored_sessions = self
+            .tracker
+            .restore_sessions(state.tracking_sessions, state.saved_at)
+            .await;
+        self.tracker.import_sessions(state.imported_sessions).await;
+        self.short_ids.reserve_from(state.next_short_id);
+        self.dead_letters.restore(state.dead_letters);
+
+        let interrupted: std::collections::HashSet<TaskId> = state.executing.into_iter().collect();
+        let queue_order: HashMap<TaskId, usize> = state
+            .queued
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (id.clone(), i))
+            .collect();
+
+        let (mut unfinished, mut finished): (Vec<Task>, Vec<Task>) = state
+            .tasks
+            .into_iter()
+            .partition(|task| matches!(task.status, TaskStatus::Todo | TaskStatus::InProgress));
+
+        // Keep only the most recent finished tasks
+        finished.sort_by_key(|task| std::cmp::Reverse(task.updated_at));
+        finished.truncate(self.config.max_task_history);
+
+        // Requeue in the original queue order, interrupted tasks first
+        unfinished.sort_by_key(|task| {
+            (
+                !interrupted.contains(&task.id),
+                queue_order.get(&task.id).copied().unwrap_or(usize::MAX),
+                task.created_at,
+            )
+        });
+
+        for task in unfinished.iter_mut() {
+            if task.status == TaskStatus::InProgress || interrupted.contains(&task.id) {
+                let interrupted_at = task.updated_at;
+                task.end_attempt_at(
+                    ExecutionResult::Interrupted,
+                    Some("daemon restart".to_string()),
+                    interrupted_at,
+                );
+                task.add_note(
+                    "Task interrupted by daemon restart and requeued".to_string(),
+                    Some("system".to_string()),
+                );
+                task.status = TaskStatus::Todo;
+            }
+        }
+
+        // Known short IDs first, so tasks without one cannot take them
+        for short_id in finished
+            .iter()
+            .chain(unfinished.iter())
+            .filter_map(|task| task.short_id.as_deref())
+        {
+            self.short_ids.observe(short_id);
+        }
+        {
+            let mut tasks = self.tasks.write().await;
+            for task in finished.iter_mut().chain(unfinished.iter_mut()) {
+                assign_short_id(&self.short_ids, &tasks, task);
+                tasks.insert(task.id.clone(), task.clone());
+            }
+        }
+
+        let mut requeued = 0;
+        for task in unfinished {
+            let task_id = task.id.clone();
+            if task.dependencies.is_empty() || self.are_dependencies_met(&task).await {
+                match self.queue.enqueue(task).await {
+                    Ok(()) => requeued += 1,
+                    Err(e) => warn!("Failed to requeue restored task {}: {}", task_id, e),
+                }
+            }
+        }
+
+        {
+            let mut stats = self.stats.write().await;
+            stats.total_tasks = self.tasks.read().await.len() as u64;
+            stats.update();
+        }
+
+        info!(
+            "Restored task state from {:?} ({} requeued, {} tracking sessions resumed)",
+            store.path(),
+            requeued,
+            restored_sessions
+        );
+        Ok(requeued)
+    }
+
     /// Get task queue reference
     pub fn get_queue(&self) -> Arc<TaskQueue> {
         Arc::clone(&self.queue)
     }
 
+    /// Hold back queued tasks below `priority` until cleared with `None`
+    pub async fn set_defer_below(&self, priority: Option<TaskPriority>) {
+        *self.defer_below.write().await = priority;
+    }
+
+    /// Priority below which queued tasks are currently held back
+    pub async fn defer_below(&self) -> Option<TaskPriority> {
+        self.defer_below.read().await.clone()
+    }
+
     /// Get task tracker reference
     pub fn get_tracker(&self) -> Arc<TaskTracker> {
         Arc::clone(&self.tracker)
@@ -534,6 +1914,29 @@ impl TaskManager {
             .await
     }
 
+    /// Generate productivity report of the tasks of one assignee
+    pub async fn generate_assignee_productivity_report(
+        &self,
+        since_timestamp: Option<u64>,
+        assignee: &str,
+    ) -> super::tracker::ProductivityReport {
+        self.tracker
+            .generate_assignee_productivity_report(since_timestamp, assignee)
+            .await
+    }
+
+    /// Tracked sessions between two report dates, labelled with task titles
+    pub async fn timeline(&self, start_date: &str, end_date: &str) -> super::timeline::Timeline {
+        let titles = {
+            let tasks = self.tasks.read().await;
+            tasks
+                .values()
+                .map(|task| (task.id.clone(), task.title.clone()))
+                .collect()
+        };
+        self.tracker.timeline(start_date, end_date, titles).await
+    }
+
     /// Get productivity insights for a specific task
     pub async fn get_task_insights(
         &self,
@@ -542,6 +1945,135 @@ impl TaskManager {
         self.tracker.get_task_insights(task_id).await
     }
 
+    /// Add imported history: finished tasks are stored without being queued
+    /// and sessions join the tracker history, so reports and budgets count
+    /// them. Tasks and sessions already known are skipped.
+    ///
+    /// With `dry_run` nothing is stored and the report shows what would be.
+    pub async fn import_history(
+        &self,
+        history: ImportedHistory,
+        source: &str,
+        dry_run: bool,
+    ) -> ImportReport {
+        let mut report = ImportReport {
+            source: source.to_string(),
+            dry_run,
+            errors: history.errors,
+            ..Default::default()
+        };
+
+        let mut tasks = Vec::new();
+        {
+            let known = self.tasks.read().await;
+            for task in history.tasks {
+                if !import::is_history(&task) {
+                    report.errors.push(format!(
+                        "Task {} is not finished ({:?})",
+                        task.id, task.status
+                    ));
+                    report.tasks_skipped += 1;
+                } else if known.contains_key(&task.id)
+                    || tasks.iter().any(|t: &Task| t.id == task.id)
+                {
+                    report.tasks_skipped += 1;
+                } else {
+                    tasks.push(task);
+                }
+            }
+        }
+
+        let mut sessions = Vec::new();
+        for session in history.sessions {
+            let duplicate = sessions.iter().any(|s: &super::tracker::CompletedSession| {
+                s.task_id == session.task_id && s.started_at == session.started_at
+            }) || self
+                .tracker
+                .has_session(&session.task_id, session.started_at)
+                .await;
+            if duplicate {
+                report.sessions_skipped += 1;
+                continue;
+            }
+            report.tracked_seconds += session.active_duration;
+            report.first_started_at = Some(
+                report
+                    .first_started_at
+                    .map_or(session.started_at, |t| t.min(session.started_at)),
+            );
+            report.last_ended_at = Some(
+                report
+                    .last_ended_at
+                    .map_or(session.ended_at, |t| t.max(session.ended_at)),
+            );
+            sessions.push(session);
+        }
+        report.tasks_imported = tasks.len();
+        report.sessions_imported = sessions.len();
+        if dry_run {
+            return report;
+        }
+
+        // Categories and workspaces first, so the roll-ups and budgets see them
+        for (task_id, category) in history.categories {
+            self.tracker.set_task_category(&task_id, category).await;
+        }
+        for (task_id, workspace) in history.workspaces {
+            self.tracker.set_task_workspace(&task_id, workspace).await;
+        }
+        {
+            let mut known = self.tasks.write().await;
+            for task in tasks {
+                known.insert(task.id.clone(), task);
+            }
+        }
+        self.tracker.import_sessions(sessions).await;
+        {
+            let mut stats = self.stats.write().await;
+            stats.total_tasks += report.tasks_imported as u64;
+            stats.update();
+        }
+
+        info!(
+            "Imported history from {}: {} tasks, {} sessions",
+            source, report.tasks_imported, report.sessions_imported
+        );
+        report
+    }
+
+    /// Store planned tasks from a board document, replacing tasks with the same ID
+    ///
+    /// The tasks are not queued: they are plans, not work to run now. Tasks
+    /// whose short ID is missing or already used by another task get a new one.
+    pub async fn import_tasks(&self, tasks: Vec<Task>) {
+        let mut events = Vec::new();
+        {
+            let mut known = self.tasks.write().await;
+            for mut task in tasks {
+                assign_short_id(&self.short_ids, &known, &mut task);
+                let event = if known.contains_key(&task.id) {
+                    TaskEvent::TaskUpdated(task.id.clone())
+                } else {
+                    TaskEvent::TaskCreated(task.id.clone())
+                };
+                known.insert(task.id.clone(), task);
+                events.push(event);
+            }
+        }
+        let created = events
+            .iter()
+            .filter(|event| matches!(event, TaskEvent::TaskCreated(_)))
+            .count();
+        {
+            let mut stats = self.stats.write().await;
+            stats.total_tasks += created as u64;
+            stats.update();
+        }
+        for event in events {
+            self.notify_listeners(event).await;
+        }
+    }
+
     /// Start time tracking for a task
     pub async fn start_task_tracking(&self, task_id: &TaskId) {
         self.tracker.start_task(task_id).await;
@@ -563,15 +2095,229 @@ impl TaskManager {
     }
 }
 
+/// Task with the given short ID, if `reference` is one
+fn find_by_short_id<'a>(
+    short_ids: &ShortIdAllocator,
+    tasks: &'a HashMap<TaskId, Task>,
+    reference: &str,
+) -> Option<&'a Task> {
+    let number = short_ids.parse(reference)?;
+    tasks
+        .values()
+        .find(|task| task.short_id.as_deref().and_then(|id| short_ids.parse(id)) == Some(number))
+}
+
+/// Keep the task's short ID unless another stored task uses it, otherwise
+/// give it the next free one
+fn assign_short_id(short_ids: &ShortIdAllocator, tasks: &HashMap<TaskId, Task>, task: &mut Task) {
+    let in_use = |short_id: &str| {
+        (short_id != task.id && tasks.contains_key(short_id))
+            || tasks.values().any(|other| {
+                other.id != task.id
+                    && other
+                        .short_id
+                        .as_deref()
+                        .is_some_and(|id| id.eq_ignore_ascii_case(short_id))
+            })
+    };
+    if let Some(ref short_id) = task.short_id {
+        if !in_use(short_id) {
+            short_ids.observe(short_id);
+            return;
+        }
+    }
+    let short_id = short_ids.allocate(in_use);
+    task.short_id = Some(short_id);
+}
+
 /// Currently executing task information
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 struct ExecutingTask {
-    #[allow(dead_code)]
     task_id: TaskId,
-    #[allow(dead_code)]
     started_at: u64,
     timeout_at: Option<u64>,
+    /// Workspace of the task, when it has one
+    workspace: Option<String>,
+    /// Process the task was routed to, if any
+    process_id: Option<String>,
+    /// Handle used to stop the execution on cancel/pause
+    abort_handle: Option<tokio::task::AbortHandle>,
+}
+
+/// Where a dequeued task runs
+#[derive(Debug, Clone, PartialEq)]
+enum Route {
+    /// No process of the workspace can be handed tasks; run in the task manager itself
+    Local,
+    /// Run on this process
+    Process(String),
+    /// Workspace has processes but none can take the task right now
+    Wait,
+}
+
+/// Send a task change to the dashboard, with the tail of its output if any
+fn send_task_update(
+    tx: &tokio::sync::broadcast::Sender<DashboardMessage>,
+    task: &Task,
+    action: TaskAction,
+    output: Option<&TaskOutput>,
+) {
+    match task_detail(task) {
+        Ok(task_json) => {
+            let _ = tx.send(DashboardMessage::TaskUpdate {
+                task: task_json,
+                action,
+                output: output.map(|output| output.preview(OUTPUT_PREVIEW_LIMIT)),
+                timestamp: current_timestamp(),
+            });
+        }
+        Err(e) => warn!("Failed to serialize task {}: {}", task.id, e),
+    }
+}
+
+/// Report of an atomic batch of which nothing was created
+fn reject_batch(mut results: Vec<BatchItemResult>) -> BatchReport {
+    for result in results.iter_mut() {
+        result.task_id = None;
+        result
+            .error
+            .get_or_insert_with(|| "Not created: another task of the batch failed".to_string());
+    }
+    BatchReport::new(true, results)
+}
+
+/// Move a task that failed for good to the dead-letter queue
+fn dead_letter(
+    dead_letters: &DeadLetterStore,
+    task: &Task,
+    output: Option<&TaskOutput>,
+    dashboard_tx: Option<&tokio::sync::broadcast::Sender<DashboardMessage>>,
+) {
+    let entry = DeadLetter::new(task, output, current_timestamp());
+    warn!("Task {} dead-lettered: {}", task.id, entry.reason);
+    dead_letters.push(entry);
+    if let Some(tx) = dashboard_tx {
+        send_dead_letters(tx, dead_letters);
+    }
+}
+
+/// Send the current dead letters to the dashboard
+fn send_dead_letters(
+    tx: &tokio::sync::broadcast::Sender<DashboardMessage>,
+    dead_letters: &DeadLetterStore,
+) {
+    let _ = tx.send(DashboardMessage::DeadLetters {
+        entries: dead_letters.list(),
+        timestamp: current_timestamp(),
+    });
+}
+
+/// Fail a command that exited unsuccessfully, quoting the end of its stderr
+fn check_exit(
+    command: &str,
+    status: std::process::ExitStatus,
+    output: &TaskOutput,
+) -> TaskResult<()> {
+    if status.success() {
+        return Ok(());
+    }
+    let stderr = output.stderr.preview(OUTPUT_PREVIEW_LIMIT).text;
+    Err(TaskError::ExecutionFailed(format!(
+        "'{command}' exited with {status}: {}",
+        stderr.trim()
+    )))
+}
+
+/// Report a completed task to the webhooks of its workspace
+fn publish_completion(event_bridge: Option<&Arc<EventBridge>>, task: &Task) {
+    if let Some(bridge) = event_bridge {
+        bridge.publish(BridgeEvent::task_completed(
+            task.workspace.as_deref().unwrap_or("default"),
+            &task.id,
+            &task.title,
+        ));
+    }
+}
+
+/// Lookup of the processes tasks can be routed to
+///
+/// The process manager is used when set, otherwise the processes recorded
+/// in the workspace state.
+#[derive(Clone, Default)]
+struct ProcessDirectory {
+    process_manager: Option<Arc<ProcessManager>>,
+    workspace_manager: Option<Arc<WorkspaceManager>>,
+
+    /// Tasks are only routed to processes connected to its bus
+    coordinator: Option<Arc<ProcessCoordinator>>,
+}
+
+impl ProcessDirectory {
+    /// Tell the process manager which tasks each process works on, for its watchdog
+    async fn sync_assignments(
+        &self,
+        executing_tasks: &Arc<RwLock<HashMap<TaskId, ExecutingTask>>>,
+    ) {
+        let Some(ref process_manager) = self.process_manager else {
+            return;
+        };
+        let mut assignments: HashMap<String, HashSet<String>> = HashMap::new();
+        for (task_id, executing_task) in executing_tasks.read().await.iter() {
+            if let Some(ref process_id) = executing_task.process_id {
+                assignments
+                    .entry(process_id.clone())
+                    .or_default()
+                    .insert(task_id.clone());
+            }
+        }
+        process_manager.sync_assignments(&assignments).await;
+    }
+
+    async fn draining_workspaces(&self) -> HashSet<String> {
+        match self.workspace_manager {
+            Some(ref workspace_manager) => workspace_manager
+                .draining_workspaces()
+                .await
+                .into_keys()
+                .collect(),
+            None => HashSet::new(),
+        }
+    }
+
+    /// Processes of a workspace that can be handed tasks
+    async fn receiving_processes(&self, workspace: &str) -> Vec<ProcessInfo> {
+        let Some(ref coordinator) = self.coordinator else {
+            return Vec::new();
+        };
+        let attached = coordinator.bus().attached_processes().await;
+        self.processes(workspace)
+            .await
+            .into_iter()
+            .filter(|process| attached.contains(&process.id))
+            .collect()
+    }
+
+    async fn processes(&self, workspace: &str) -> Vec<ProcessInfo> {
+        if let Some(ref process_manager) = self.process_manager {
+            return process_manager.get_processes_by_workspace(workspace).await;
+        }
+        match self.workspace_manager {
+            Some(ref workspace_manager) => workspace_manager
+                .get_workspace_info(workspace)
+                .await
+                .map(|state| state.processes.into_values().collect())
+                .unwrap_or_default(),
+            None => Vec::new(),
+        }
+    }
+}
+
+fn is_healthy(status: &ProcessStatus) -> bool {
+    matches!(
+        status,
+        ProcessStatus::Running | ProcessStatus::Idle | ProcessStatus::Busy
+    )
 }
 
 /// Task template for quick task creation
@@ -595,12 +2341,16 @@ pub enum TaskEvent {
     TaskStarted(TaskId),
     TaskCompleted(TaskId),
     TaskFailed(TaskId),
+    TaskCancelled(TaskId),
+    TaskPaused(TaskId),
+    TaskResumed(TaskId),
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::task::types::{TaskCategory, TaskPriority};
+    use crate::task::ImportFormat;
 
     fn create_test_config() -> TaskConfig {
         TaskConfig {
@@ -613,6 +2363,13 @@ mod tests {
             metrics_enabled: true,
             cleanup_interval: 1,
             max_task_history: 100,
+            timezone: Default::default(),
+            budgets: Vec::new(),
+            language: Default::default(),
+            artifacts_dir: None,
+            ids: Default::default(),
+            latency_budgets: Vec::new(),
+            preemption: Default::default(),
         }
     }
 
@@ -750,4 +2507,930 @@ mod tests {
         assert_eq!(task.workspace, Some("frontend".to_string()));
         assert!(task.tags.contains(&"bug".to_string()));
     }
+
+    #[tokio::test]
+    async fn test_save_and_restore_state() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = TaskConfig {
+            persistence_enabled: true,
+            persistence_path: Some(dir.path().join("tasks.json").to_string_lossy().to_string()),
+            ..create_test_config()
+        };
+
+        let manager = TaskManager::new(config.clone());
+        let pending_id = manager
+            .create_task(Task::new("Pending".to_string(), TaskCategory::Development))
+            .await
+            .unwrap();
+        let done_id = manager
+            .create_task(Task::new("Done".to_string(), TaskCategory::Testing))
+            .await
+            .unwrap();
+        let mut done = manager.get_task(&done_id).await.unwrap();
+        done.update_status(TaskStatus::Completed);
+        manager.update_task(done).await.unwrap();
+        manager.get_queue().remove(&done_id).await.unwrap();
+        manager.get_tracker().start_task(&pending_id).await;
+
+        manager.save_state().await.unwrap();
+
+        let restored = TaskManager::new(config);
+        let requeued = restored.restore_state().await.unwrap();
+
+        assert_eq!(requeued, 1);
+        assert_eq!(restored.get_task_count().await, 2);
+        assert!(restored
+            .get_tracker()
+            .get_active_session(&pending_id)
+            .await
+            .is_some());
+        assert!(restored.get_queue().get_task(&pending_id).await.is_some());
+        assert_eq!(
+            restored.get_task(&done_id).await.unwrap().status,
+            TaskStatus::Completed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_short_ids_are_accepted_as_task_ids() {
+        let manager = TaskManager::new(create_test_config());
+        let design_id = manager
+            .create_task(Task::new("Design".to_string(), TaskCategory::Development))
+            .await
+            .unwrap();
+        let design = manager.get_task(&design_id).await.unwrap();
+        assert_eq!(design.short_id.as_deref(), Some("WP-1"));
+
+        let mut build = Task::new("Build".to_string(), TaskCategory::Development);
+        build.dependencies = vec!["wp-1".to_string()];
+        let build_id = manager.create_task(build).await.unwrap();
+        let build = manager.get_task(&"WP-2".to_string()).await.unwrap();
+        assert_eq!(build.id, build_id);
+        assert_eq!(build.dependencies, std::slice::from_ref(&design_id));
+
+        assert_eq!(manager.resolve_task_id("wp-1").await, design_id);
+        assert_eq!(manager.resolve_task_id("WP-9").await, "WP-9");
+        let paused = manager.pause_task(&"WP-1".to_string()).await.unwrap();
+        assert_eq!(paused.id, design_id);
+
+        // Imported tasks never take a short ID that is already in use
+        let mut imported = Task::new("Imported".to_string(), TaskCategory::Testing);
+        imported.short_id = Some("WP-2".to_string());
+        manager.import_tasks(vec![imported.clone()]).await;
+        let stored = manager.get_task(&imported.id).await.unwrap();
+        assert_eq!(stored.short_id.as_deref(), Some("WP-3"));
+
+        // Under the short strategy the short ID is the primary ID
+        let manager = TaskManager::new(TaskConfig {
+            ids: crate::task::TaskIdConfig {
+                strategy: crate::task::TaskIdStrategy::Short,
+                prefix: "OPS".to_string(),
+            },
+            ..create_test_config()
+        });
+        let task_id = manager
+            .create_task(Task::new("Deploy".to_string(), TaskCategory::Deployment))
+            .await
+            .unwrap();
+        assert_eq!(task_id, "OPS-1");
+    }
+
+    #[tokio::test]
+    async fn test_short_ids_are_not_reused_after_restart() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = TaskConfig {
+            persistence_enabled: true,
+            persistence_path: Some(dir.path().join("tasks.json").to_string_lossy().to_string()),
+            ..create_test_config()
+        };
+
+        let manager = TaskManager::new(config.clone());
+        let first = manager
+            .create_task(Task::new("First".to_string(), TaskCategory::Development))
+            .await
+            .unwrap();
+        manager
+            .create_task(Task::new("Second".to_string(), TaskCategory::Development))
+            .await
+            .unwrap();
+        manager.delete_task(&"WP-2".to_string()).await.unwrap();
+        manager.save_state().await.unwrap();
+
+        let restored = TaskManager::new(config);
+        restored.restore_state().await.unwrap();
+        assert_eq!(restored.resolve_task_id("WP-1").await, first);
+        let third = restored
+            .create_task(Task::new("Third".to_string(), TaskCategory::Development))
+            .await
+            .unwrap();
+        let third = restored.get_task(&third).await.unwrap();
+        assert_eq!(third.short_id.as_deref(), Some("WP-3"));
+    }
+
+    #[tokio::test]
+    async fn test_import_history_survives_restart() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = TaskConfig {
+            persistence_enabled: true,
+            persistence_path: Some(dir.path().join("tasks.json").to_string_lossy().to_string()),
+            timezone: crate::config::Timezone::Utc,
+            ..create_test_config()
+        };
+        let csv = "Project,Description,Start,End,Category\n\
+                   api,Write tests,2024-03-04 09:00,2024-03-04 10:00,Testing\n\
+                   api,Write tests,2024-03-04 09:00,2024-03-04 10:00,Testing\n";
+        let timezone = config.timezone;
+        let parse = || import::parse(csv, ImportFormat::Csv, "toggl", timezone).unwrap();
+
+        let manager = TaskManager::new(config.clone());
+        let preview = manager.import_history(parse(), "toggl", true).await;
+        assert_eq!(
+            (preview.sessions_imported, preview.sessions_skipped),
+            (1, 1)
+        );
+        assert_eq!(
+            manager.get_tracker().get_stats().await.sessions_completed,
+            0
+        );
+
+        let report = manager.import_history(parse(), "toggl", false).await;
+        assert_eq!(report.tracked_seconds, 3600);
+        let week = manager
+            .get_tracker()
+            .get_weekly_summary("2024-W10")
+            .await
+            .unwrap();
+        assert_eq!(week.category_breakdown["Testing"], 3600);
+        manager.save_state().await.unwrap();
+
+        let restored = TaskManager::new(config);
+        restored.restore_state().await.unwrap();
+        let again = restored.import_history(parse(), "toggl", false).await;
+        assert_eq!((again.sessions_imported, again.sessions_skipped), (0, 2));
+        let history = restored
+            .get_tracker()
+            .get_task_history(&"imported-write-tests".to_string())
+            .await;
+        assert_eq!(history[0].source.as_deref(), Some("toggl"));
+    }
+
+    #[tokio::test]
+    async fn test_restore_requeues_interrupted_tasks() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("tasks.json");
+        let config = TaskConfig {
+            persistence_enabled: true,
+            persistence_path: Some(path.to_string_lossy().to_string()),
+            ..create_test_config()
+        };
+
+        let mut running = Task::new("Running".to_string(), TaskCategory::Development);
+        running.update_status(TaskStatus::InProgress);
+        let running_id = running.id.clone();
+        TaskPersistence::new(path)
+            .save(&PersistedTaskState::new(
+                vec![running],
+                Vec::new(),
+                vec![running_id.clone()],
+            ))
+            .unwrap();
+
+        let manager = TaskManager::new(config);
+        assert_eq!(manager.restore_state().await.unwrap(), 1);
+
+        let task = manager.get_task(&running_id).await.unwrap();
+        assert_eq!(task.status, TaskStatus::Todo);
+        assert_eq!(task.notes.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_restore_with_persistence_disabled() {
+        let manager = TaskManager::new(create_test_config());
+        assert_eq!(manager.restore_state().await.unwrap(), 0);
+        assert!(manager.save_state().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_queued_task() {
+        let manager = TaskManager::new(create_test_config());
+        let task = Task::new("Cancel me".to_string(), TaskCategory::Development);
+        let task_id = manager.create_task(task).await.unwrap();
+
+        let cancelled = manager.cancel_task(&task_id).await.unwrap();
+        assert_eq!(cancelled.status, TaskStatus::Cancelled);
+        assert_eq!(manager.get_stats().await.queued_tasks, 0);
+
+        // Finished tasks cannot be cancelled again
+        assert!(manager.cancel_task(&task_id).await.is_err());
+        assert!(matches!(
+            manager.cancel_task(&"missing".to_string()).await,
+            Err(TaskError::TaskNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_running_task_stops_tracking() {
+        let manager = TaskManager::new(create_test_config());
+        let task = Task::new("Running".to_string(), TaskCategory::Development);
+        let task_id = manager.create_task(task).await.unwrap();
+
+        let _handle = manager.start().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(
+            manager.get_task(&task_id).await.unwrap().status,
+            TaskStatus::InProgress
+        );
+        assert!(manager.tracker.get_active_session(&task_id).await.is_some());
+
+        manager.cancel_task(&task_id).await.unwrap();
+        assert!(manager.tracker.get_active_session(&task_id).await.is_none());
+
+        // The aborted execution must not overwrite the cancelled status
+        tokio::time::sleep(Duration::from_millis(1200)).await;
+        assert_eq!(
+            manager.get_task(&task_id).await.unwrap().status,
+            TaskStatus::Cancelled
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pause_and_resume_task() {
+        let (tx, mut rx) = tokio::sync::broadcast::channel(16);
+        let manager = TaskManager::new(create_test_config()).with_dashboard_broadcaster(tx);
+        let task = Task::new("Pause me".to_string(), TaskCategory::Development);
+        let task_id = manager.create_task(task).await.unwrap();
+
+        let paused = manager.pause_task(&task_id).await.unwrap();
+        assert_eq!(paused.status, TaskStatus::OnHold);
+        assert_eq!(manager.get_stats().await.queued_tasks, 0);
+        assert!(manager.pause_task(&task_id).await.is_err());
+
+        match rx.try_recv().unwrap() {
+            DashboardMessage::TaskUpdate { task, action, .. } => {
+                assert!(matches!(action, TaskAction::StatusChanged));
+                assert_eq!(task["status"], "OnHold");
+            }
+            other => panic!("Unexpected message: {other:?}"),
+        }
+
+        let resumed = manager.resume_task(&task_id).await.unwrap();
+        assert_eq!(resumed.status, TaskStatus::Todo);
+        assert_eq!(manager.get_stats().await.queued_tasks, 1);
+        assert!(manager.resume_task(&task_id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_task_trace_across_reassignment() {
+        let manager = TaskManager::new(create_test_config());
+        let mut task = Task::new("Traced".to_string(), TaskCategory::Development);
+        task.assignee = Some("agent-a".to_string());
+        let task_id = manager.create_task(task).await.unwrap();
+
+        let _handle = manager.start().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        manager.pause_task(&task_id).await.unwrap();
+
+        // Hand the task to another agent and run it to the end
+        let mut task = manager.get_task(&task_id).await.unwrap();
+        task.assignee = Some("agent-b".to_string());
+        manager.update_task(task).await.unwrap();
+        manager.resume_task(&task_id).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+
+        let trace = manager.get_task_trace(&task_id).await.unwrap();
+        assert_eq!(trace.agents, vec!["agent-a", "agent-b"]);
+        assert_eq!(trace.handoffs, 1);
+        let outcomes: Vec<_> = trace.spans.iter().map(|s| s.outcome.clone()).collect();
+        assert_eq!(
+            outcomes,
+            vec![ExecutionResult::Paused, ExecutionResult::Success]
+        );
+        assert!(manager
+            .get_task_trace(&"missing".to_string())
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_note_on_tracked_task() {
+        let (tx, mut rx) = tokio::sync::broadcast::channel(16);
+        let manager = TaskManager::new(create_test_config()).with_dashboard_broadcaster(tx);
+        let task = Task::new("Note me".to_string(), TaskCategory::Development);
+        let task_id = manager.create_task(task).await.unwrap();
+        assert!(manager.current_task().await.is_none());
+
+        // Sessions of tasks the manager does not know are skipped
+        manager.start_task_tracking(&"focus-1".to_string()).await;
+        manager.start_task_tracking(&task_id).await;
+        assert_eq!(manager.current_task().await.unwrap().id, task_id);
+
+        let note = manager
+            .add_task_note(&task_id, "retry with --release".to_string(), None)
+            .await
+            .unwrap();
+        let stored = manager.get_task(&task_id).await.unwrap();
+        assert_eq!(stored.notes, vec![note]);
+
+        match rx.try_recv().unwrap() {
+            DashboardMessage::TaskUpdate { task, action, .. } => {
+                assert!(matches!(action, TaskAction::Updated));
+                assert_eq!(task["notes"][0]["content"], "retry with --release");
+            }
+            other => panic!("Unexpected message: {other:?}"),
+        }
+
+        assert!(matches!(
+            manager
+                .add_task_note(&"missing".to_string(), "x".to_string(), None)
+                .await,
+            Err(TaskError::TaskNotFound(_))
+        ));
+    }
+
+    async fn workspace_with_processes(
+        dir: &tempfile::TempDir,
+        processes: &[(&str, ProcessStatus)],
+    ) -> Arc<WorkspaceManager> {
+        let workspace_manager =
+            Arc::new(WorkspaceManager::new(Some(dir.path().join("workspaces.json"))).unwrap());
+        set_processes(&workspace_manager, processes).await;
+        workspace_manager
+    }
+
+    async fn set_processes(
+        workspace_manager: &WorkspaceManager,
+        processes: &[(&str, ProcessStatus)],
+    ) {
+        let processes: HashMap<String, ProcessInfo> = processes
+            .iter()
+            .map(|(id, status)| {
+                let info = ProcessInfo {
+                    id: id.to_string(),
+                    command: "claude-code".to_string(),
+                    workspace: "default".to_string(),
+                    pane_id: None,
+                    status: status.clone(),
+                    pid: None,
+                    started_at: std::time::SystemTime::now(),
+                    last_heartbeat: std::time::SystemTime::now(),
+                    restart_count: 0,
+                    context: None,
+                };
+                (id.to_string(), info)
+            })
+            .collect();
+        workspace_manager
+            .update_workspace_state("default", |state| state.processes = processes)
+            .await
+            .unwrap();
+    }
+
+    fn workspace_task(title: &str) -> Task {
+        let mut task = Task::new(title.to_string(), TaskCategory::Development);
+        task.workspace = Some("default".to_string());
+        task
+    }
+
+    /// How a fake process answers the tasks assigned to it
+    #[derive(Clone, Copy)]
+    enum Worker {
+        /// Reply with the result right away
+        Finish,
+        /// Acknowledge, then report `TaskCompleted` to the coordinator
+        Report,
+        /// Acknowledge and never finish
+        Hold,
+    }
+
+    /// Connect a fake process to the coordination bus
+    ///
+    /// Returns the assignments the process received.
+    async fn attach_worker(
+        coordinator: &ProcessCoordinator,
+        process_id: &str,
+        worker: Worker,
+    ) -> Arc<std::sync::Mutex<Vec<CoordinationEvent>>> {
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        let (framework_side, process_side) = tokio::io::duplex(4096);
+        let (reader, writer) = tokio::io::split(framework_side);
+        coordinator.bus().attach(process_id, reader, writer).await;
+
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = Arc::clone(&received);
+        let process_id = process_id.to_string();
+        tokio::spawn(async move {
+            let (reader, mut writer) = tokio::io::split(process_side);
+            let mut lines = BufReader::new(reader).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                // Responses to our own reports are not assignments
+                let Ok(message) = serde_json::from_str::<CoordinationMessage>(&line) else {
+                    continue;
+                };
+                log.lock().unwrap().push(message.event.clone());
+                let CoordinationEvent::TaskAssignment { task_id, .. } = message.event else {
+                    continue;
+                };
+                let result = format!("{process_id} did {task_id}");
+                let mut output = match worker {
+                    Worker::Finish => serde_json::to_vec(&CoordinationResponse::Data {
+                        process_id: process_id.clone(),
+                        payload: serde_json::json!(result),
+                    }),
+                    Worker::Report | Worker::Hold => {
+                        serde_json::to_vec(&CoordinationResponse::Acknowledged {
+                            process_id: process_id.clone(),
+                        })
+                    }
+                }
+                .unwrap();
+                output.push(b'\n');
+                if let Worker::Report = worker {
+                    let completed = CoordinationMessage::new(
+                        process_id.clone(),
+                        COORDINATOR_ID.to_string(),
+                        CoordinationEvent::TaskCompleted { task_id, result },
+                    );
+                    output.extend(serde_json::to_vec(&completed).unwrap());
+                    output.push(b'\n');
+                }
+                writer.write_all(&output).await.unwrap();
+            }
+        });
+        received
+    }
+
+    #[tokio::test]
+    async fn test_command_honors_working_dir_env_and_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut task = Task::new("Shell".to_string(), TaskCategory::Development);
+        task.execution.mode = super::super::types::ExecutionMode::Automatic;
+        task.execution.command =
+            Some("printf '%s ' \"$GREETING\" > out.txt; pwd >> out.txt".to_string());
+        task.execution.working_directory = Some(dir.path().to_string_lossy().to_string());
+        task.execution.environment = HashMap::from([("GREETING".to_string(), "hello".to_string())]);
+        task.execution.shell = Some("sh".to_string());
+        let outputs = TaskOutputStore::new(None, 1);
+
+        TaskManager::execute_task(&mut task, &outputs)
+            .await
+            .unwrap();
+        assert_eq!(task.status, TaskStatus::Completed);
+        let written = std::fs::read_to_string(dir.path().join("out.txt")).unwrap();
+        let (greeting, cwd) = written.trim().split_once(' ').unwrap();
+        assert_eq!(greeting, "hello");
+        assert_eq!(
+            std::path::Path::new(cwd).canonicalize().unwrap(),
+            dir.path().canonicalize().unwrap()
+        );
+
+        task.execution.command = Some("sleep 5".to_string());
+        task.execution.timeout = Some(1);
+        let started = std::time::Instant::now();
+        let error = TaskManager::execute_task(&mut task, &outputs)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, TaskError::Timeout(_)), "{error:?}");
+        assert!(started.elapsed() < Duration::from_secs(3));
+        assert_eq!(task.status, TaskStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_urgent_task_preempts_running_task() {
+        let config = TaskConfig {
+            max_concurrent_tasks: 1,
+            preemption: super::super::PreemptionConfig {
+                policy: PreemptionPolicy::Reassign,
+                ..Default::default()
+            },
+            ..create_test_config()
+        };
+        let (tx, mut rx) = tokio::sync::broadcast::channel(64);
+        let manager = TaskManager::new(config).with_dashboard_broadcaster(tx);
+        let command_task = |title: &str, command: &str, priority| {
+            let mut task = Task::new(title.to_string(), TaskCategory::Development);
+            task.priority = priority;
+            task.execution.mode = super::super::types::ExecutionMode::Automatic;
+            task.execution.command = Some(command.to_string());
+            task
+        };
+        let slow_id = manager
+            .create_task(command_task("Slow", "sleep 5", TaskPriority::Low))
+            .await
+            .unwrap();
+        let _handle = manager.start().await.unwrap();
+        while manager.get_task(&slow_id).await.unwrap().status != TaskStatus::InProgress {
+            sleep(Duration::from_millis(20)).await;
+        }
+
+        let urgent_id = manager
+            .create_task(command_task("Hotfix", "true", TaskPriority::Urgent))
+            .await
+            .unwrap();
+        loop {
+            match tokio::time::timeout(Duration::from_secs(3), rx.recv())
+                .await
+                .unwrap()
+                .unwrap()
+            {
+                DashboardMessage::TaskUpdate {
+                    action: TaskAction::Preempted,
+                    task,
+                    ..
+                } => {
+                    assert_eq!(task["id"], slow_id.as_str());
+                    break;
+                }
+                _ => continue,
+            }
+        }
+        while manager.get_task(&urgent_id).await.unwrap().status != TaskStatus::Completed {
+            sleep(Duration::from_millis(20)).await;
+        }
+
+        // The preempted task runs again once the slot is free
+        while manager.get_task(&slow_id).await.unwrap().status != TaskStatus::InProgress {
+            sleep(Duration::from_millis(20)).await;
+        }
+        let slow = manager.get_task(&slow_id).await.unwrap();
+        assert!(slow
+            .notes
+            .iter()
+            .any(|note| note.content.contains("Preempted")));
+        assert_eq!(slow.execution_history[0].result, ExecutionResult::Paused);
+    }
+
+    #[tokio::test]
+    async fn test_failed_task_is_dead_lettered_and_requeued() {
+        let (tx, mut rx) = tokio::sync::broadcast::channel(16);
+        let manager = TaskManager::new(create_test_config()).with_dashboard_broadcaster(tx);
+        let mut task = Task::new("Broken".to_string(), TaskCategory::Development);
+        task.execution.mode = super::super::types::ExecutionMode::Automatic;
+        task.execution.command = Some("echo boom >&2; exit 1".to_string());
+        let task_id = manager.create_task(task).await.unwrap();
+        let _handle = manager.start().await.unwrap();
+
+        let entries = loop {
+            match tokio::time::timeout(Duration::from_secs(3), rx.recv())
+                .await
+                .unwrap()
+                .unwrap()
+            {
+                DashboardMessage::DeadLetters { entries, .. } => break entries,
+                _ => continue,
+            }
+        };
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].task_id, task_id);
+        assert!(entries[0].reason.contains("boom"));
+        assert!(entries[0]
+            .output
+            .as_ref()
+            .unwrap()
+            .stderr
+            .text
+            .contains("boom"));
+
+        let task = manager.requeue_dead_letter(&task_id).await.unwrap();
+        assert_eq!(task.status, TaskStatus::Todo);
+        assert!(manager.dead_letters().is_empty());
+        assert!(manager.requeue_dead_letter(&task_id).await.is_err());
+
+        while manager.dead_letters().is_empty() {
+            sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(manager.purge_dead_letters(Some(&task_id)).await, 1);
+        assert_eq!(
+            manager.get_task(&task_id).await.unwrap().status,
+            TaskStatus::Failed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_command_output_and_artifacts_are_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = TaskConfig {
+            artifacts_dir: Some(dir.path().to_string_lossy().to_string()),
+            ..create_test_config()
+        };
+        let (tx, mut rx) = tokio::sync::broadcast::channel(16);
+        let manager = TaskManager::new(config).with_dashboard_broadcaster(tx);
+        let mut task = Task::new("Report".to_string(), TaskCategory::Testing);
+        task.execution.mode = super::super::types::ExecutionMode::Automatic;
+        task.execution.command = Some(
+            "echo built; echo '<xml/>' > \"$WEZTERM_PARALLEL_ARTIFACTS_DIR/junit.xml\"; echo boom >&2; exit 3"
+                .to_string(),
+        );
+        let task_id = manager.create_task(task).await.unwrap();
+
+        let _handle = manager.start().await.unwrap();
+        let output = loop {
+            match tokio::time::timeout(Duration::from_secs(5), rx.recv())
+                .await
+                .unwrap()
+                .unwrap()
+            {
+                DashboardMessage::TaskUpdate {
+                    output: Some(output),
+                    ..
+                } => break output,
+                _ => continue,
+            }
+        };
+        assert_eq!(output.exit_code, Some(3));
+        assert_eq!(output.stdout.text, "built\n");
+
+        let result = manager.get_task_result(&task_id).await.unwrap();
+        assert_eq!(result.status, TaskStatus::Failed);
+        assert!(result.error.unwrap().contains("boom"));
+        let artifacts_dir = dir.path().join(&task_id);
+        assert_eq!(result.artifacts_dir.as_ref(), Some(&artifacts_dir));
+        assert_eq!(
+            result.output.unwrap().artifacts,
+            [artifacts_dir.join("junit.xml")]
+        );
+        assert!(manager
+            .get_task_result(&"missing".to_string())
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_tasks_routed_to_least_loaded_healthy_process() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace_manager = workspace_with_processes(
+            &dir,
+            &[
+                ("claude-1", ProcessStatus::Running),
+                ("claude-2", ProcessStatus::Idle),
+                ("claude-0", ProcessStatus::Failed),
+            ],
+        )
+        .await;
+        let coordinator = Arc::new(ProcessCoordinator::new());
+        for id in ["claude-0", "claude-1", "claude-2"] {
+            attach_worker(&coordinator, id, Worker::Hold).await;
+        }
+        let config = TaskConfig {
+            max_concurrent_tasks: 3,
+            ..create_test_config()
+        };
+        let manager = TaskManager::new(config)
+            .with_workspace_manager(workspace_manager)
+            .with_coordinator(coordinator);
+        let first = manager.create_task(workspace_task("First")).await.unwrap();
+        let local = manager
+            .create_task(Task::new("Local".to_string(), TaskCategory::Development))
+            .await
+            .unwrap();
+
+        let _handle = manager.start().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let second = manager.create_task(workspace_task("Second")).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        // The failed process is skipped and the busy one is avoided
+        let tasks = manager.list_tasks(None).await;
+        let assignee = |id: &TaskId| tasks.iter().find(|t| &t.id == id).unwrap().assignee.clone();
+        assert_eq!(assignee(&first).as_deref(), Some("claude-1"));
+        assert_eq!(assignee(&second).as_deref(), Some("claude-2"));
+        // Tasks without a workspace still run in the task manager
+        assert_eq!(assignee(&local), None);
+    }
+
+    #[tokio::test]
+    async fn test_task_retried_on_another_process_after_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace_manager = workspace_with_processes(
+            &dir,
+            &[
+                ("claude-1", ProcessStatus::Running),
+                ("claude-2", ProcessStatus::Running),
+            ],
+        )
+        .await;
+        let coordinator = Arc::new(ProcessCoordinator::new());
+        let first = attach_worker(&coordinator, "claude-1", Worker::Hold).await;
+        let second = attach_worker(&coordinator, "claude-2", Worker::Finish).await;
+        let manager = TaskManager::new(create_test_config())
+            .with_workspace_manager(Arc::clone(&workspace_manager))
+            .with_coordinator(coordinator);
+        let task_id = manager.create_task(workspace_task("Retry")).await.unwrap();
+
+        let _handle = manager.start().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        set_processes(
+            &workspace_manager,
+            &[
+                ("claude-1", ProcessStatus::Failed),
+                ("claude-2", ProcessStatus::Running),
+            ],
+        )
+        .await;
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+
+        let trace = manager.get_task_trace(&task_id).await.unwrap();
+        assert_eq!(trace.agents, vec!["claude-1", "claude-2"]);
+        let outcomes: Vec<_> = trace.spans.iter().map(|s| s.outcome.clone()).collect();
+        assert_eq!(
+            outcomes,
+            vec![ExecutionResult::Failed, ExecutionResult::Success]
+        );
+        // Both processes were handed the task
+        assert_eq!(first.lock().unwrap().len(), 1);
+        assert_eq!(second.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_routed_task_delivered_to_process() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace_manager = workspace_with_processes(
+            &dir,
+            &[
+                ("claude-1", ProcessStatus::Running),
+                ("claude-2", ProcessStatus::Running),
+            ],
+        )
+        .await;
+        let coordinator = Arc::new(ProcessCoordinator::new());
+        tokio::spawn(Arc::clone(&coordinator).run());
+        let received = attach_worker(&coordinator, "claude-1", Worker::Report).await;
+        let manager = TaskManager::new(create_test_config())
+            .with_workspace_manager(workspace_manager)
+            .with_coordinator(coordinator);
+        let mut task = workspace_task("Review");
+        task.description = Some("Review the parser".to_string());
+        let task_id = manager.create_task(task).await.unwrap();
+
+        let _handle = manager.start().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        // claude-2 is not on the bus and cannot take tasks
+        let events = received.lock().unwrap().clone();
+        assert_eq!(
+            events,
+            vec![CoordinationEvent::TaskAssignment {
+                task_id: task_id.clone(),
+                description: "Review the parser".to_string(),
+            }]
+        );
+
+        // The task waits for review only once the process reported it done
+        let task = manager.get_task(&task_id).await.unwrap();
+        assert_eq!(task.assignee.as_deref(), Some("claude-1"));
+        assert_eq!(task.status, TaskStatus::Review);
+        let output = manager.get_task_result(&task_id).await.unwrap().output;
+        assert_eq!(
+            output.unwrap().stdout.text,
+            format!("claude-1 did {task_id}")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_task_stays_in_progress_until_process_reports() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace_manager =
+            workspace_with_processes(&dir, &[("claude-1", ProcessStatus::Running)]).await;
+        let coordinator = Arc::new(ProcessCoordinator::new());
+        attach_worker(&coordinator, "claude-1", Worker::Hold).await;
+        let manager = TaskManager::new(create_test_config())
+            .with_workspace_manager(workspace_manager)
+            .with_coordinator(coordinator);
+        let task_id = manager.create_task(workspace_task("Hold")).await.unwrap();
+
+        let _handle = manager.start().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+
+        // Manual tasks no longer move to review on their own
+        let task = manager.get_task(&task_id).await.unwrap();
+        assert_eq!(task.status, TaskStatus::InProgress);
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_is_recorded_as_shedding() {
+        let config = TaskConfig {
+            max_concurrent_tasks: 1,
+            ..create_test_config()
+        };
+        let shed_log = Arc::new(ShedLog::new());
+        let manager = TaskManager::new(config).with_shed_log(Arc::clone(&shed_log));
+
+        // The queue holds 10x the concurrency limit
+        for i in 0..10 {
+            let task = Task::new(format!("Task {i}"), TaskCategory::Development);
+            manager.create_task(task).await.unwrap();
+        }
+        assert!(shed_log.report(None).incidents.is_empty());
+
+        let overflow = Task::new("Overflow".to_string(), TaskCategory::Development);
+        let overflow_id = overflow.id.clone();
+        assert!(matches!(
+            manager.create_task(overflow).await,
+            Err(TaskError::QueueFull)
+        ));
+
+        let report = shed_log.report(None);
+        let incident = &report.incidents[0];
+        assert_eq!(incident.action, ShedAction::Rejected);
+        assert_eq!(incident.items, [overflow_id]);
+        assert_eq!(incident.trigger.value, 10.0);
+        assert_eq!(incident.trigger.limit, 10.0);
+    }
+
+    #[tokio::test]
+    async fn test_batch_creation_and_rollback() {
+        let manager = TaskManager::new(create_test_config());
+        let item = |key: &str, depends_on: &[&str]| BatchItem {
+            key: key.to_string(),
+            task: Ok(Task::new(key.to_string(), TaskCategory::Development)),
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+        };
+        let broken = BatchItem {
+            task: Err("bad working directory".to_string()),
+            ..item("lint", &[])
+        };
+
+        let report = manager
+            .create_batch(
+                vec![
+                    item("test", &["build"]),
+                    item("build", &[]),
+                    broken,
+                    item("fix", &["lint"]),
+                ],
+                false,
+            )
+            .await;
+        assert_eq!((report.created, report.failed), (2, 2));
+        let build_id = report.results[1].task_id.clone().unwrap();
+        let test_id = report.results[0].task_id.clone().unwrap();
+        let test = manager.get_task(&test_id).await.unwrap();
+        assert_eq!(test.dependencies, std::slice::from_ref(&build_id));
+        assert_eq!(
+            report.results[3].error.as_deref(),
+            Some("Dependency 'lint' was not created")
+        );
+
+        // The dependent is queued once its dependency completes
+        assert_eq!(manager.get_queue().size().await, 1);
+        let mut build = manager.get_task(&build_id).await.unwrap();
+        build.update_status(TaskStatus::Completed);
+        manager.update_task(build).await.unwrap();
+        TaskManager::release_unblocked(&manager.tasks, &manager.queue).await;
+        assert!(manager
+            .get_queue()
+            .list_tasks()
+            .await
+            .iter()
+            .any(|t| t.id == test_id));
+
+        let before = manager.list_tasks(None).await.len();
+        let report = manager
+            .create_batch(vec![item("a", &[]), item("b", &["a", "missing"])], true)
+            .await;
+        assert_eq!(report.created, 0);
+        assert!(report.results[0]
+            .error
+            .as_deref()
+            .unwrap()
+            .starts_with("Rolled back"));
+        assert_eq!(manager.list_tasks(None).await.len(), before);
+    }
+
+    #[tokio::test]
+    async fn test_stage_timestamps_feed_latency_report() {
+        let manager = TaskManager::new(create_test_config());
+        let review_id = manager
+            .create_task(Task::new("Review".to_string(), TaskCategory::Development))
+            .await
+            .unwrap();
+        let mut blocked = Task::new("Blocked".to_string(), TaskCategory::Development);
+        blocked.dependencies = vec![review_id.clone()];
+        let blocked_id = manager.create_task(blocked).await.unwrap();
+
+        let mut task = manager.get_task(&review_id).await.unwrap();
+        assert!(task.stages.enqueued_at.is_some());
+        assert!(manager
+            .get_task(&blocked_id)
+            .await
+            .unwrap()
+            .stages
+            .is_empty());
+
+        task.stages.assigned_at = task.stages.enqueued_at;
+        for status in [
+            TaskStatus::InProgress,
+            TaskStatus::Review,
+            TaskStatus::Completed,
+        ] {
+            task.update_status(status);
+        }
+        assert!(task.stages.reviewed_at.is_some());
+        manager.update_task(task).await.unwrap();
+
+        let report = manager.latency_report(None).await;
+        let stages: Vec<LatencyStage> = report.stages.iter().map(|s| s.stage).collect();
+        assert_eq!(stages, LatencyStage::ALL);
+        assert!(report.breaches.is_empty());
+    }
 }
@@ -1,22 +1,51 @@
 // WezTerm Multi-Process Development Framework - Task Management System
 // Provides task creation, scheduling, prioritization, and tracking capabilities
 
+pub mod batch;
+pub mod budget;
+pub mod cron;
+pub mod dead_letter;
 pub mod distributor;
+pub mod ids;
+pub mod import;
+pub mod latency;
 pub mod manager;
+pub mod output;
+pub mod persistence;
 pub mod queue;
 pub mod scheduler;
+pub mod timeline;
+pub mod trace;
 pub mod tracker;
 pub mod types;
 
+pub use batch::{BatchItem, BatchItemResult, BatchReport, BatchTask};
+pub use budget::{BudgetScope, BudgetState, BudgetStatus, TimeBudget};
+pub use dead_letter::{DeadLetter, DeadLetterStore};
 pub use distributor::{DistributedTask, ProcessLoad, TaskDependency, TaskDistributor};
+pub use ids::{ShortIdAllocator, TaskIdConfig, TaskIdStrategy};
+pub use import::{ImportFormat, ImportReport};
+pub use latency::{
+    LatencyBreach, LatencyBudget, LatencyReport, LatencyStage, StagePercentiles, StageTimestamps,
+};
 pub use manager::TaskManager;
+pub use output::{CapturedStream, TaskOutput, TaskOutputStore, TaskRunResult};
+pub use persistence::{PersistedTaskState, TaskPersistence};
 pub use queue::{QueueConfig, TaskQueue};
-pub use scheduler::{SchedulingStrategy, TaskScheduler};
-pub use tracker::{TaskTracker, TimeTracker};
+pub use scheduler::{
+    PreemptionConfig, PreemptionPolicy, RunningTask, SchedulingStrategy, TaskScheduler,
+};
+pub use timeline::{Timeline, TimelineEntry, TimelineFormat};
+pub use trace::{task_detail, TaskTrace, TraceSpan};
+pub use tracker::{
+    PeriodSummary, SessionExportFormat, SessionNote, SummaryPeriod, TaskTracker, TimeTracker,
+};
 pub use types::*;
 
+use crate::config::Timezone;
+use crate::i18n::Language;
 use serde::{Deserialize, Serialize};
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::Duration;
 use uuid::Uuid;
 
 /// Task management configuration
@@ -48,6 +77,35 @@ pub struct TaskConfig {
 
     /// Maximum task history to keep
     pub max_task_history: usize,
+
+    /// Timezone for daily tracking summaries and report ranges
+    #[serde(default)]
+    pub timezone: Timezone,
+
+    /// Weekly time budgets per category or workspace
+    #[serde(default)]
+    pub budgets: Vec<TimeBudget>,
+
+    /// Language of budget alerts
+    #[serde(default)]
+    pub language: Language,
+
+    /// Root of the per-task artifacts directories (spilled output and files
+    /// written by commands); defaults to `artifacts/` next to the state file
+    #[serde(default)]
+    pub artifacts_dir: Option<String>,
+
+    /// Primary ID strategy and short ID prefix
+    #[serde(default)]
+    pub ids: TaskIdConfig,
+
+    /// Longest each pipeline stage may take before an alert
+    #[serde(default)]
+    pub latency_budgets: Vec<LatencyBudget>,
+
+    /// Whether urgent tasks preempt lower-priority running ones
+    #[serde(default)]
+    pub preemption: PreemptionConfig,
 }
 
 impl Default for TaskConfig {
@@ -62,6 +120,13 @@ impl Default for TaskConfig {
             metrics_enabled: true,
             cleanup_interval: 3600, // 1 hour
             max_task_history: 1000,
+            timezone: Timezone::default(),
+            budgets: Vec::new(),
+            language: Language::default(),
+            artifacts_dir: None,
+            ids: TaskIdConfig::default(),
+            latency_budgets: Vec::new(),
+            preemption: PreemptionConfig::default(),
         }
     }
 }
@@ -102,13 +167,7 @@ impl Default for TaskSystemStats {
 
 impl TaskSystemStats {
     pub fn new() -> Self {
-        let current_time = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_else(|_| {
-                log::warn!("System time error, using 0 timestamp");
-                std::time::Duration::from_secs(0)
-            })
-            .as_secs();
+        let current_time = current_timestamp();
 
         Self {
             total_tasks: 0,
@@ -123,13 +182,7 @@ impl TaskSystemStats {
     }
 
     pub fn update(&mut self) {
-        self.last_update = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_else(|_| {
-                log::warn!("System time error during update, keeping previous timestamp");
-                std::time::Duration::from_secs(self.last_update)
-            })
-            .as_secs();
+        self.last_update = current_timestamp();
     }
 }
 
@@ -190,26 +243,14 @@ pub fn generate_task_id() -> String {
     Uuid::new_v4().to_string()
 }
 
-/// Get current timestamp in seconds
+/// Get current timestamp in seconds (never earlier than a previous call)
 pub fn current_timestamp() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_else(|_| {
-            log::warn!("System time error, returning fallback timestamp");
-            std::time::Duration::from_secs(0)
-        })
-        .as_secs()
+    crate::clock::unix_secs()
 }
 
-/// Get current timestamp in milliseconds
+/// Get current timestamp in milliseconds (never earlier than a previous call)
 pub fn current_timestamp_millis() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_else(|_| {
-            log::warn!("System time error, returning fallback timestamp");
-            std::time::Duration::from_millis(0)
-        })
-        .as_millis() as u64
+    crate::clock::unix_millis()
 }
 
 /// Format duration for display
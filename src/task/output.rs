@@ -0,0 +1,276 @@
+// WezTerm Multi-Process Development Framework - Task Output
+// Captures stdout/stderr and exit status of command tasks, spilling large
+// output and task-written files into a per-task artifacts directory
+
+use super::current_timestamp;
+use super::types::{ExecutionResult, Task, TaskId, TaskStatus};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use tokio::sync::RwLock;
+use tracing::warn;
+
+/// Bytes of each stream kept in memory; the rest only in the artifacts directory
+pub const OUTPUT_CAPTURE_LIMIT: usize = 64 * 1024;
+
+/// Bytes of each stream sent with dashboard task updates
+pub const OUTPUT_PREVIEW_LIMIT: usize = 2 * 1024;
+
+/// Environment variable telling a command where to put its artifacts
+pub const ARTIFACTS_DIR_ENV: &str = "WEZTERM_PARALLEL_ARTIFACTS_DIR";
+
+/// Tail of an output stream
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CapturedStream {
+    /// Last bytes of the stream (lossy UTF-8)
+    pub text: String,
+
+    /// Size of the whole stream
+    pub bytes: u64,
+
+    /// True when `text` is only the tail
+    pub truncated: bool,
+}
+
+impl CapturedStream {
+    /// Keep the last `limit` bytes, cut at a character boundary
+    pub fn tail(data: &[u8], limit: usize) -> Self {
+        let mut start = data.len().saturating_sub(limit);
+        // Skip UTF-8 continuation bytes so the tail starts at a character
+        while start < data.len() && start > 0 && (data[start] & 0xC0) == 0x80 {
+            start += 1;
+        }
+        Self {
+            text: String::from_utf8_lossy(&data[start..]).into_owned(),
+            bytes: data.len() as u64,
+            truncated: start > 0,
+        }
+    }
+
+    /// Tail of at most `limit` bytes, keeping the size of the whole stream
+    pub fn preview(&self, limit: usize) -> Self {
+        let mut preview = Self::tail(self.text.as_bytes(), limit);
+        preview.bytes = self.bytes;
+        preview.truncated |= self.truncated;
+        preview
+    }
+}
+
+/// Output of the last run of a command task
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskOutput {
+    pub task_id: TaskId,
+    pub attempt: u32,
+
+    /// `None` when the command was killed by a signal
+    pub exit_code: Option<i32>,
+    pub stdout: CapturedStream,
+    pub stderr: CapturedStream,
+    pub finished_at: u64,
+
+    /// Files in the artifacts directory: spilled output and whatever the
+    /// command wrote to `$WEZTERM_PARALLEL_ARTIFACTS_DIR`
+    #[serde(default)]
+    pub artifacts: Vec<PathBuf>,
+}
+
+impl TaskOutput {
+    /// Same output with both streams cut down to `limit` bytes
+    pub fn preview(&self, limit: usize) -> Self {
+        Self {
+            stdout: self.stdout.preview(limit),
+            stderr: self.stderr.preview(limit),
+            ..self.clone()
+        }
+    }
+}
+
+/// Everything known about how a task ended
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskRunResult {
+    pub task_id: TaskId,
+    pub status: TaskStatus,
+
+    /// Result and error of the last execution attempt
+    pub result: Option<ExecutionResult>,
+    pub error: Option<String>,
+
+    /// Captured output; `None` for tasks without a command, or whose output
+    /// is no longer kept in memory
+    pub output: Option<TaskOutput>,
+
+    /// Directory with the task's artifacts, if it exists
+    pub artifacts_dir: Option<PathBuf>,
+}
+
+impl TaskRunResult {
+    pub fn new(task: &Task, output: Option<TaskOutput>, artifacts_dir: Option<PathBuf>) -> Self {
+        let last = task.execution_history.last();
+        Self {
+            task_id: task.id.clone(),
+            status: task.status.clone(),
+            result: last.map(|record| record.result.clone()),
+            error: last.and_then(|record| record.error.clone()),
+            output,
+            artifacts_dir: artifacts_dir.filter(|dir| dir.is_dir()),
+        }
+    }
+}
+
+/// Outputs of the most recent command tasks
+#[derive(Debug)]
+pub struct TaskOutputStore {
+    /// Root of the per-task artifacts directories; nothing is spilled without it
+    root: Option<PathBuf>,
+
+    /// Number of outputs kept in memory
+    capacity: usize,
+
+    outputs: RwLock<(HashMap<TaskId, TaskOutput>, VecDeque<TaskId>)>,
+}
+
+impl TaskOutputStore {
+    pub fn new(root: Option<PathBuf>, capacity: usize) -> Self {
+        Self {
+            root,
+            capacity: capacity.max(1),
+            outputs: RwLock::new((HashMap::new(), VecDeque::new())),
+        }
+    }
+
+    /// Artifacts directory of a task (not created)
+    pub fn artifacts_dir(&self, task_id: &str) -> Option<PathBuf> {
+        // Task IDs may come from users, so keep them to a single path component
+        let name: String = task_id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.root.as_ref().map(|root| root.join(name))
+    }
+
+    /// Capture the output of a finished command
+    ///
+    /// Streams longer than [`OUTPUT_CAPTURE_LIMIT`] are written in full to
+    /// `stdout.log` / `stderr.log` in the artifacts directory.
+    pub async fn record(
+        &self,
+        task: &Task,
+        exit_code: Option<i32>,
+        stdout: &[u8],
+        stderr: &[u8],
+    ) -> TaskOutput {
+        let dir = self.artifacts_dir(&task.id);
+        if let Some(dir) = &dir {
+            for (name, data) in [("stdout.log", stdout), ("stderr.log", stderr)] {
+                if data.len() > OUTPUT_CAPTURE_LIMIT {
+                    spill(dir, name, data);
+                } else {
+                    // Left over from an earlier attempt
+                    let _ = std::fs::remove_file(dir.join(name));
+                }
+            }
+        }
+
+        let output = TaskOutput {
+            task_id: task.id.clone(),
+            attempt: task.execution_history.last().map_or(0, |r| r.attempt),
+            exit_code,
+            stdout: CapturedStream::tail(stdout, OUTPUT_CAPTURE_LIMIT),
+            stderr: CapturedStream::tail(stderr, OUTPUT_CAPTURE_LIMIT),
+            finished_at: current_timestamp(),
+            artifacts: dir.as_deref().map(list_files).unwrap_or_default(),
+        };
+
+        let mut guard = self.outputs.write().await;
+        let (outputs, order) = &mut *guard;
+        if outputs.insert(task.id.clone(), output.clone()).is_some() {
+            order.retain(|id| id != &task.id);
+        }
+        order.push_back(task.id.clone());
+        while order.len() > self.capacity {
+            if let Some(oldest) = order.pop_front() {
+                outputs.remove(&oldest);
+            }
+        }
+        output
+    }
+
+    /// Output of the last run of a task
+    pub async fn get(&self, task_id: &str) -> Option<TaskOutput> {
+        self.outputs.read().await.0.get(task_id).cloned()
+    }
+}
+
+fn spill(dir: &Path, name: &str, data: &[u8]) {
+    let path = dir.join(name);
+    if let Err(e) = std::fs::create_dir_all(dir).and_then(|_| std::fs::write(&path, data)) {
+        warn!("Failed to write task output to {:?}: {}", path, e);
+    }
+}
+
+/// Files directly in `dir`, sorted
+fn list_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::TaskCategory;
+
+    #[test]
+    fn test_tail_keeps_whole_characters() {
+        let stream = CapturedStream::tail("aあい".as_bytes(), 4);
+        assert_eq!(stream.text, "い");
+        assert_eq!(stream.bytes, 7);
+        assert!(stream.truncated);
+
+        let short = CapturedStream::tail(b"ok\n", 4);
+        assert_eq!(short.text, "ok\n");
+        assert!(!short.truncated);
+        assert!(short.preview(1).truncated);
+    }
+
+    #[tokio::test]
+    async fn test_store_spills_large_output_and_evicts_oldest() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = TaskOutputStore::new(Some(dir.path().to_path_buf()), 1);
+        let task = Task::new("Build".to_string(), TaskCategory::Development);
+
+        let big = vec![b'x'; OUTPUT_CAPTURE_LIMIT + 10];
+        let output = store.record(&task, Some(0), &big, b"warning\n").await;
+        assert!(output.stdout.truncated);
+        assert_eq!(output.stdout.text.len(), OUTPUT_CAPTURE_LIMIT);
+        let artifacts_dir = store.artifacts_dir(&task.id).unwrap();
+        assert_eq!(output.artifacts, [artifacts_dir.join("stdout.log")]);
+        assert_eq!(
+            std::fs::read(&output.artifacts[0]).unwrap().len(),
+            big.len()
+        );
+
+        let other = Task::new("Test".to_string(), TaskCategory::Testing);
+        store.record(&other, Some(1), b"", b"").await;
+        assert!(store.get(&task.id).await.is_none());
+        assert_eq!(store.get(&other.id).await.unwrap().exit_code, Some(1));
+
+        assert_eq!(
+            store.artifacts_dir("../etc").unwrap(),
+            dir.path().join("___etc")
+        );
+    }
+}
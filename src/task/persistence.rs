@@ -0,0 +1,310 @@
+// WezTerm Multi-Process Development Framework - Task Persistence
+// Stores tasks and queue state as JSON so pending work survives daemon restarts
+
+use super::dead_letter::DeadLetter;
+use super::tracker::{CompletedSession, TrackingSession};
+use super::types::{Task, TaskId};
+use super::{current_timestamp, TaskError, TaskResult};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// Current on-disk format version
+pub const TASK_STATE_VERSION: u32 = 1;
+
+/// Snapshot of the task system written to disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersistedTaskState {
+    /// Format version
+    pub version: u32,
+
+    /// Time the snapshot was taken
+    pub saved_at: u64,
+
+    /// All known tasks
+    pub tasks: Vec<Task>,
+
+    /// IDs of tasks waiting in the queue, in queue order
+    pub queued: Vec<TaskId>,
+
+    /// IDs of tasks that were executing when the snapshot was taken
+    pub executing: Vec<TaskId>,
+
+    /// Time tracking sessions that were active when the snapshot was taken
+    #[serde(default)]
+    pub tracking_sessions: Vec<TrackingSession>,
+
+    /// Sessions imported from other trackers, which exist nowhere else
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub imported_sessions: Vec<CompletedSession>,
+
+    /// Number of the next short task ID, so numbers of deleted tasks are not
+    /// handed out again
+    #[serde(default)]
+    pub next_short_id: u64,
+
+    /// Tasks that failed for good, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dead_letters: Vec<DeadLetter>,
+}
+
+impl PersistedTaskState {
+    /// Create a snapshot from the given parts
+    pub fn new(tasks: Vec<Task>, queued: Vec<TaskId>, executing: Vec<TaskId>) -> Self {
+        Self {
+            version: TASK_STATE_VERSION,
+            saved_at: current_timestamp(),
+            tasks,
+            queued,
+            executing,
+            tracking_sessions: Vec::new(),
+            imported_sessions: Vec::new(),
+            next_short_id: 0,
+            dead_letters: Vec::new(),
+        }
+    }
+
+    /// Include the active time tracking sessions
+    pub fn with_tracking_sessions(mut self, sessions: Vec<TrackingSession>) -> Self {
+        self.tracking_sessions = sessions;
+        self
+    }
+
+    /// Include the sessions imported from other trackers
+    pub fn with_imported_sessions(mut self, sessions: Vec<CompletedSession>) -> Self {
+        self.imported_sessions = sessions;
+        self
+    }
+
+    /// Include the short task ID counter
+    pub fn with_next_short_id(mut self, next_short_id: u64) -> Self {
+        self.next_short_id = next_short_id;
+        self
+    }
+
+    /// Include the dead-letter queue
+    pub fn with_dead_letters(mut self, dead_letters: Vec<DeadLetter>) -> Self {
+        self.dead_letters = dead_letters;
+        self
+    }
+}
+
+/// Outcome of loading persisted state
+#[derive(Debug, Default)]
+pub struct LoadOutcome {
+    /// Loaded state, if any usable file was found
+    pub state: Option<PersistedTaskState>,
+
+    /// True when the primary file was unusable and the backup was loaded instead
+    pub recovered_from_backup: bool,
+
+    /// Where a corrupted state file was moved to, if any
+    pub quarantined: Option<PathBuf>,
+}
+
+/// JSON file store for task state
+#[derive(Debug, Clone)]
+pub struct TaskPersistence {
+    path: PathBuf,
+}
+
+impl TaskPersistence {
+    /// Create a store writing to `path`
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Create a store from the configured path, falling back to the default location
+    pub fn from_config(persistence_path: Option<&str>) -> Self {
+        Self::new(
+            persistence_path
+                .map(PathBuf::from)
+                .unwrap_or_else(Self::default_path),
+        )
+    }
+
+    /// Default state file location
+    pub fn default_path() -> PathBuf {
+        let mut path = dirs::config_dir().unwrap_or_else(|| {
+            log::warn!("設定ディレクトリが取得できません。カレントディレクトリを使用します。");
+            PathBuf::from(".")
+        });
+        path.push("wezterm-parallel");
+        path.push("tasks.json");
+        path
+    }
+
+    /// State file path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Path of the last known-good copy
+    pub fn backup_path(&self) -> PathBuf {
+        self.path.with_extension("json.bak")
+    }
+
+    /// Write the snapshot atomically, keeping the previous file as a backup
+    pub fn save(&self, state: &PersistedTaskState) -> TaskResult<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|e| TaskError::PersistenceError(e.to_string()))?;
+        }
+
+        let json = serde_json::to_string_pretty(state)
+            .map_err(|e| TaskError::SerializationError(e.to_string()))?;
+
+        // Keep the previous snapshot around in case the new one gets corrupted
+        if self.path.exists() {
+            if let Err(e) = fs::copy(&self.path, self.backup_path()) {
+                warn!("Failed to back up task state file: {}", e);
+            }
+        }
+
+        // Write to temporary file first, then rename for atomic operation
+        let temp_path = self.path.with_extension("json.tmp");
+        fs::write(&temp_path, json).map_err(|e| TaskError::PersistenceError(e.to_string()))?;
+        fs::rename(&temp_path, &self.path)
+            .map_err(|e| TaskError::PersistenceError(e.to_string()))?;
+
+        info!(
+            "Saved {} tasks to {:?}",
+            state.tasks.len(),
+            self.path.display()
+        );
+        Ok(())
+    }
+
+    /// Load the snapshot, falling back to the backup when the primary file is corrupted
+    pub fn load(&self) -> LoadOutcome {
+        let mut outcome = LoadOutcome::default();
+
+        if !self.path.exists() {
+            info!("No existing task state file found");
+            return outcome;
+        }
+
+        match Self::read_state(&self.path) {
+            Ok(state) => {
+                outcome.state = Some(state);
+                return outcome;
+            }
+            Err(e) => {
+                warn!("Task state file {:?} is unreadable: {}", self.path, e);
+                outcome.quarantined = self.quarantine();
+            }
+        }
+
+        let backup_path = self.backup_path();
+        if backup_path.exists() {
+            match Self::read_state(&backup_path) {
+                Ok(state) => {
+                    warn!("Recovered task state from backup {:?}", backup_path);
+                    outcome.state = Some(state);
+                    outcome.recovered_from_backup = true;
+                }
+                Err(e) => warn!("Task state backup {:?} is unreadable: {}", backup_path, e),
+            }
+        }
+
+        outcome
+    }
+
+    fn read_state(path: &Path) -> Result<PersistedTaskState, String> {
+        let json = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let state: PersistedTaskState = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+
+        if state.version > TASK_STATE_VERSION {
+            return Err(format!(
+                "unsupported state version {} (expected <= {})",
+                state.version, TASK_STATE_VERSION
+            ));
+        }
+
+        Ok(state)
+    }
+
+    /// Move a corrupted state file aside so it is not overwritten
+    fn quarantine(&self) -> Option<PathBuf> {
+        let target = self
+            .path
+            .with_extension(format!("json.corrupt-{}", current_timestamp()));
+        match fs::rename(&self.path, &target) {
+            Ok(()) => {
+                warn!("Moved corrupted task state file to {:?}", target);
+                Some(target)
+            }
+            Err(e) => {
+                warn!("Failed to move corrupted task state file: {}", e);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::types::TaskCategory;
+    use tempfile::TempDir;
+
+    fn sample_state() -> PersistedTaskState {
+        let task = Task::new("Persisted".to_string(), TaskCategory::Development);
+        let id = task.id.clone();
+        PersistedTaskState::new(vec![task], vec![id], Vec::new())
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let store = TaskPersistence::new(dir.path().join("tasks.json"));
+
+        store.save(&sample_state()).unwrap();
+        let outcome = store.load();
+
+        let state = outcome.state.unwrap();
+        assert_eq!(state.tasks.len(), 1);
+        assert_eq!(state.queued.len(), 1);
+        assert!(!outcome.recovered_from_backup);
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let dir = TempDir::new().unwrap();
+        let store = TaskPersistence::new(dir.path().join("tasks.json"));
+
+        let outcome = store.load();
+        assert!(outcome.state.is_none());
+        assert!(outcome.quarantined.is_none());
+    }
+
+    #[test]
+    fn test_corrupted_file_falls_back_to_backup() {
+        let dir = TempDir::new().unwrap();
+        let store = TaskPersistence::new(dir.path().join("tasks.json"));
+
+        // Two saves leave a valid backup behind
+        store.save(&sample_state()).unwrap();
+        store.save(&sample_state()).unwrap();
+        fs::write(store.path(), "{ not valid json").unwrap();
+
+        let outcome = store.load();
+        assert!(outcome.state.is_some());
+        assert!(outcome.recovered_from_backup);
+
+        let quarantined = outcome.quarantined.unwrap();
+        assert!(quarantined.exists());
+        assert!(!store.path().exists());
+    }
+
+    #[test]
+    fn test_corrupted_file_without_backup_starts_fresh() {
+        let dir = TempDir::new().unwrap();
+        let store = TaskPersistence::new(dir.path().join("tasks.json"));
+        fs::write(store.path(), "garbage").unwrap();
+
+        let outcome = store.load();
+        assert!(outcome.state.is_none());
+        assert!(outcome.quarantined.is_some());
+    }
+}
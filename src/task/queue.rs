@@ -5,7 +5,7 @@ use super::types::{Task, TaskId, TaskPriority, TaskStatus};
 use super::{TaskError, TaskResult};
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
@@ -172,6 +172,87 @@ impl TaskQueue {
         None
     }
 
+    /// Remove and return the next task of at least `min_priority`
+    ///
+    /// Lower priority tasks keep their place in the queue, so they are picked
+    /// up again once the caller stops deferring them.
+    pub async fn dequeue_at_least(&self, min_priority: &TaskPriority) -> Option<Task> {
+        if let Some(queued_task) = {
+            let mut priority_queue = self.priority_queue.write().await;
+            let mut deferred = Vec::new();
+            let mut found = None;
+            while let Some(queued_task) = priority_queue.pop() {
+                if queued_task.priority >= *min_priority {
+                    found = Some(queued_task);
+                    break;
+                }
+                deferred.push(queued_task);
+            }
+            priority_queue.extend(deferred);
+            found
+        } {
+            return self.complete_dequeue(queued_task).await;
+        }
+
+        if let Some(queued_task) = {
+            let mut standard_queue = self.standard_queue.write().await;
+            standard_queue
+                .iter()
+                .position(|qt| qt.priority >= *min_priority)
+                .and_then(|index| standard_queue.remove(index))
+        } {
+            return self.complete_dequeue(queued_task).await;
+        }
+
+        None
+    }
+
+    /// Remove and return the next task accepted by `accept`
+    ///
+    /// Like `dequeue_at_least`, rejected tasks keep their place in the queue.
+    pub async fn dequeue_where(&self, accept: impl Fn(&Task) -> bool) -> Option<Task> {
+        let accepted: HashSet<TaskId> = {
+            let lookup = self.task_lookup.read().await;
+            lookup
+                .values()
+                .filter(|task| accept(task))
+                .map(|task| task.id.clone())
+                .collect()
+        };
+        if accepted.is_empty() {
+            return None;
+        }
+
+        if let Some(queued_task) = {
+            let mut priority_queue = self.priority_queue.write().await;
+            let mut held = Vec::new();
+            let mut found = None;
+            while let Some(queued_task) = priority_queue.pop() {
+                if accepted.contains(&queued_task.task_id) {
+                    found = Some(queued_task);
+                    break;
+                }
+                held.push(queued_task);
+            }
+            priority_queue.extend(held);
+            found
+        } {
+            return self.complete_dequeue(queued_task).await;
+        }
+
+        if let Some(queued_task) = {
+            let mut standard_queue = self.standard_queue.write().await;
+            standard_queue
+                .iter()
+                .position(|qt| accepted.contains(&qt.task_id))
+                .and_then(|index| standard_queue.remove(index))
+        } {
+            return self.complete_dequeue(queued_task).await;
+        }
+
+        None
+    }
+
     /// Complete the dequeue operation
     async fn complete_dequeue(&self, queued_task: QueuedTask) -> Option<Task> {
         let task_id = queued_task.task_id.clone();
@@ -384,7 +465,6 @@ impl TaskQueue {
 #[allow(dead_code)]
 struct QueuedTask {
     task_id: TaskId,
-    #[allow(dead_code)]
     priority: TaskPriority,
     #[allow(dead_code)]
     due_date: Option<u64>,
@@ -581,6 +661,44 @@ mod tests {
         assert!(titles.contains(&"Low Priority".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_dequeue_at_least_defers_lower_priorities() {
+        let queue = TaskQueue::new(QueueConfig::default());
+
+        let mut low_task = Task::new("Low Priority".to_string(), TaskCategory::Development);
+        low_task.priority = TaskPriority::Low;
+        let mut urgent_task = Task::new("Urgent".to_string(), TaskCategory::Development);
+        urgent_task.priority = TaskPriority::Urgent;
+
+        queue.enqueue(low_task).await.unwrap();
+        assert!(queue.dequeue_at_least(&TaskPriority::High).await.is_none());
+
+        queue.enqueue(urgent_task).await.unwrap();
+        let next = queue.dequeue_at_least(&TaskPriority::High).await.unwrap();
+        assert_eq!(next.title, "Urgent");
+
+        // The deferred task is still queued for later
+        assert_eq!(queue.size().await, 1);
+        assert_eq!(queue.dequeue().await.unwrap().title, "Low Priority");
+    }
+
+    #[tokio::test]
+    async fn test_dequeue_where_holds_back_rejected_tasks() {
+        let queue = TaskQueue::new(QueueConfig::default());
+
+        let mut held_task = Task::new("Held".to_string(), TaskCategory::Development);
+        held_task.priority = TaskPriority::Urgent;
+        held_task.workspace = Some("draining".to_string());
+        let other_task = Task::new("Other".to_string(), TaskCategory::Development);
+        queue.enqueue(held_task).await.unwrap();
+        queue.enqueue(other_task).await.unwrap();
+
+        let accept = |task: &Task| task.workspace.is_none();
+        assert_eq!(queue.dequeue_where(accept).await.unwrap().title, "Other");
+        assert!(queue.dequeue_where(accept).await.is_none());
+        assert_eq!(queue.dequeue().await.unwrap().title, "Held");
+    }
+
     #[tokio::test]
     async fn test_queue_capacity() {
         let config = QueueConfig {
@@ -1,8 +1,11 @@
 // WezTerm Multi-Process Development Framework - Task Scheduler
 // Provides advanced task scheduling, dependency resolution, and execution planning
 
-use super::types::{Task, TaskId, TaskStatus};
+use super::cron::CronExpression;
+use super::types::{Task, TaskId, TaskPriority, TaskStatus};
 use super::{current_timestamp, TaskError, TaskResult};
+use crate::config::Timezone;
+use chrono::Duration;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet, VecDeque};
 use tokio::sync::RwLock;
@@ -12,7 +15,6 @@ use tracing::{debug, info};
 #[derive(Debug)]
 pub struct TaskScheduler {
     /// Scheduler configuration
-    #[allow(dead_code)]
     config: SchedulerConfig,
 
     /// Scheduled tasks
@@ -147,10 +149,8 @@ impl TaskScheduler {
                 RepeatPattern::Once => {
                     scheduled_task.is_active = false;
                 }
-                RepeatPattern::Count(max_count) => {
-                    if scheduled_task.execution_count >= max_count {
-                        scheduled_task.is_active = false;
-                    }
+                RepeatPattern::Count(max_count) if scheduled_task.execution_count >= max_count => {
+                    scheduled_task.is_active = false;
                 }
                 _ => {
                     // Continue scheduling for other patterns
@@ -344,19 +344,16 @@ impl TaskScheduler {
 
     /// Validate schedule configuration
     async fn validate_schedule(&self, schedule: &Schedule) -> TaskResult<()> {
+        self.schedule_timezone(schedule)?;
+
         match &schedule.repeat {
-            RepeatPattern::Interval(duration) => {
-                if *duration == 0 {
-                    return Err(TaskError::InvalidConfig(
-                        "Interval cannot be zero".to_string(),
-                    ));
-                }
+            RepeatPattern::Interval(duration) if *duration == 0 => {
+                return Err(TaskError::InvalidConfig(
+                    "Interval cannot be zero".to_string(),
+                ));
             }
             RepeatPattern::Cron(pattern) => {
-                // Basic cron validation (in a real implementation, use a cron parser)
-                if pattern.split_whitespace().count() != 5 {
-                    return Err(TaskError::InvalidConfig("Invalid cron pattern".to_string()));
-                }
+                CronExpression::parse(pattern).map_err(TaskError::InvalidConfig)?;
             }
             _ => {} // Other patterns are valid
         }
@@ -364,24 +361,60 @@ impl TaskScheduler {
         Ok(())
     }
 
+    /// Timezone for a schedule, falling back to the scheduler default
+    fn schedule_timezone(&self, schedule: &Schedule) -> TaskResult<Timezone> {
+        match &schedule.timezone {
+            Some(timezone) => timezone.parse().map_err(TaskError::InvalidConfig),
+            None => Ok(self.config.timezone),
+        }
+    }
+
     /// Calculate next execution time based on schedule
     fn calculate_next_execution(&self, schedule: &Schedule) -> u64 {
-        let now = current_timestamp();
+        self.next_execution_after(schedule, current_timestamp())
+    }
+
+    /// Calculate next execution time after `now`
+    ///
+    /// Daily, weekly and cron schedules are evaluated on the wall clock of the
+    /// schedule's timezone, so they stay at the same local time across DST
+    /// changes.
+    fn next_execution_after(&self, schedule: &Schedule, now: u64) -> u64 {
+        let timezone = self
+            .schedule_timezone(schedule)
+            .unwrap_or(self.config.timezone);
 
         match &schedule.repeat {
             RepeatPattern::Once => schedule.start_time.unwrap_or(now),
             RepeatPattern::Interval(seconds) => now + seconds,
-            RepeatPattern::Daily => {
-                // Next day at same time
-                now + 86400 // 24 hours
-            }
-            RepeatPattern::Weekly => {
-                // Next week at same time
-                now + 604800 // 7 days
+            RepeatPattern::Daily | RepeatPattern::Weekly => {
+                let anchor = schedule.start_time.unwrap_or(now);
+                if anchor > now {
+                    return anchor;
+                }
+
+                // Same local time of day (and weekday), strictly after now
+                let days = if matches!(schedule.repeat, RepeatPattern::Weekly) {
+                    7
+                } else {
+                    1
+                };
+                let anchor_local = timezone.datetime(anchor).naive_local();
+                let now_local = timezone.datetime(now).naive_local();
+                let elapsed = (now_local.date() - anchor_local.date()).num_days() / days;
+                let mut next = anchor_local + Duration::days(elapsed * days);
+                while timezone.timestamp_of(next) <= now {
+                    next += Duration::days(days);
+                }
+                timezone.timestamp_of(next)
             }
-            RepeatPattern::Cron(_pattern) => {
-                // For simplicity, add 1 hour (real implementation would parse cron)
-                now + 3600
+            RepeatPattern::Cron(pattern) => {
+                let now_local = timezone.datetime(now).naive_local();
+                CronExpression::parse(pattern)
+                    .ok()
+                    .and_then(|expression| expression.next_after(now_local))
+                    .map(|next| timezone.timestamp_of(next).max(now + 1))
+                    .unwrap_or(now + 3600)
             }
             RepeatPattern::Count(_) => schedule.start_time.unwrap_or(now),
         }
@@ -428,6 +461,10 @@ pub struct SchedulerConfig {
 
     /// Schedule check interval in seconds
     pub check_interval: u64,
+
+    /// Default timezone for daily, weekly and cron schedules
+    #[serde(default)]
+    pub timezone: Timezone,
 }
 
 impl Default for SchedulerConfig {
@@ -438,10 +475,94 @@ impl Default for SchedulerConfig {
             dependency_resolution: true,
             execution_planning: true,
             check_interval: 60, // 1 minute
+            timezone: Timezone::default(),
+        }
+    }
+}
+
+/// What happens to lower-priority running tasks while an urgent task waits
+/// for a free slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PreemptionPolicy {
+    /// Urgent tasks wait like any other task
+    #[default]
+    Off,
+
+    /// The preempted task is requeued and resumes on the process it ran on
+    Pause,
+
+    /// The preempted task is requeued for whichever process is free first
+    Reassign,
+}
+
+impl PreemptionPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PreemptionPolicy::Off => "off",
+            PreemptionPolicy::Pause => "pause",
+            PreemptionPolicy::Reassign => "reassign",
+        }
+    }
+}
+
+/// Preemption settings (`preemption:` in the config file)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PreemptionConfig {
+    #[serde(default)]
+    pub policy: PreemptionPolicy,
+
+    /// Queued tasks of at least this priority preempt running tasks below it
+    #[serde(default = "default_preempting_priority")]
+    pub min_priority: TaskPriority,
+}
+
+fn default_preempting_priority() -> TaskPriority {
+    TaskPriority::Urgent
+}
+
+impl Default for PreemptionConfig {
+    fn default() -> Self {
+        Self {
+            policy: PreemptionPolicy::Off,
+            min_priority: default_preempting_priority(),
         }
     }
 }
 
+/// A running task as seen by preemption
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunningTask {
+    pub task_id: TaskId,
+    pub priority: TaskPriority,
+    pub started_at: u64,
+}
+
+impl PreemptionConfig {
+    /// Running task to preempt for a waiting task of `waiting` priority
+    ///
+    /// The lowest-priority task goes first and, among equals, the one started
+    /// last, which loses the least work. Tasks at or above `min_priority` are
+    /// never preempted, so urgent tasks do not preempt each other.
+    pub fn select_victim<'a>(
+        &self,
+        waiting: &TaskPriority,
+        running: &'a [RunningTask],
+    ) -> Option<&'a RunningTask> {
+        if self.policy == PreemptionPolicy::Off || *waiting < self.min_priority {
+            return None;
+        }
+        running
+            .iter()
+            .filter(|task| task.priority < self.min_priority && task.priority < *waiting)
+            .min_by(|a, b| {
+                a.priority
+                    .cmp(&b.priority)
+                    .then(b.started_at.cmp(&a.started_at))
+            })
+    }
+}
+
 /// Task scheduling strategy
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SchedulingStrategy {
@@ -484,7 +605,7 @@ pub struct Schedule {
     /// Repeat pattern
     pub repeat: RepeatPattern,
 
-    /// Time zone (optional)
+    /// Time zone (`local`, `UTC` or `+HH:MM`; defaults to the scheduler timezone)
     pub timezone: Option<String>,
 }
 
@@ -622,6 +743,48 @@ impl SchedulerStats {
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    #[test]
+    fn test_preemption_victim_selection() {
+        let running = |task_id: &str, priority, started_at| RunningTask {
+            task_id: task_id.to_string(),
+            priority,
+            started_at,
+        };
+        let tasks = vec![
+            running("old-low", TaskPriority::Low, 100),
+            running("new-low", TaskPriority::Low, 200),
+            running("medium", TaskPriority::Medium, 300),
+            running("urgent", TaskPriority::Urgent, 50),
+        ];
+
+        let config = PreemptionConfig::default();
+        assert!(config
+            .select_victim(&TaskPriority::Urgent, &tasks)
+            .is_none());
+
+        let config = PreemptionConfig {
+            policy: PreemptionPolicy::Pause,
+            ..Default::default()
+        };
+        let victim = config.select_victim(&TaskPriority::Urgent, &tasks).unwrap();
+        assert_eq!(victim.task_id, "new-low");
+        assert!(config
+            .select_victim(&TaskPriority::Critical, &tasks)
+            .is_none());
+
+        let config = PreemptionConfig {
+            policy: PreemptionPolicy::Reassign,
+            min_priority: TaskPriority::Critical,
+        };
+        let victim = config
+            .select_victim(&TaskPriority::Critical, &tasks[2..])
+            .unwrap();
+        assert_eq!(victim.task_id, "medium");
+        assert!(config
+            .select_victim(&TaskPriority::Critical, &tasks[3..])
+            .is_none());
+    }
     use crate::task::types::{Task, TaskCategory};
 
     fn create_test_config() -> SchedulerConfig {
@@ -631,6 +794,7 @@ mod tests {
             dependency_resolution: true,
             execution_planning: true,
             check_interval: 1,
+            timezone: Timezone::Utc,
         }
     }
 
@@ -759,4 +923,60 @@ mod tests {
         assert!(!scheduled_task.is_active); // Should be inactive after 3 executions
         assert_eq!(scheduled_task.execution_count, 3);
     }
+
+    #[tokio::test]
+    async fn test_invalid_cron_and_timezone_rejected() {
+        let scheduler = TaskScheduler::new(create_test_config());
+        let task = Task::new("Cron Task".to_string(), TaskCategory::Development);
+
+        let schedule = Schedule {
+            start_time: None,
+            end_time: None,
+            repeat: RepeatPattern::Cron("61 * * * *".to_string()),
+            timezone: None,
+        };
+        assert!(scheduler
+            .schedule_task(task.clone(), schedule)
+            .await
+            .is_err());
+
+        let schedule = Schedule {
+            start_time: None,
+            end_time: None,
+            repeat: RepeatPattern::Daily,
+            timezone: Some("Somewhere/Else".to_string()),
+        };
+        assert!(scheduler.schedule_task(task, schedule).await.is_err());
+    }
+
+    #[test]
+    fn test_next_execution_uses_schedule_timezone() {
+        let scheduler = TaskScheduler::new(create_test_config());
+        // 2022-01-01 20:00:00 UTC == 2022-01-02 05:00:00 +09:00
+        let now = 1641067200;
+
+        let cron = Schedule {
+            start_time: None,
+            end_time: None,
+            repeat: RepeatPattern::Cron("0 9 * * *".to_string()),
+            timezone: Some("+09:00".to_string()),
+        };
+        // 09:00 in Tokyo is 00:00 UTC
+        assert_eq!(scheduler.next_execution_after(&cron, now), 1641081600);
+
+        let utc_cron = Schedule {
+            timezone: None,
+            ..cron
+        };
+        // Scheduler default (UTC): 2022-01-02 09:00 UTC
+        assert_eq!(scheduler.next_execution_after(&utc_cron, now), 1641114000);
+
+        let daily = Schedule {
+            start_time: Some(1641081600 - 86400), // 2022-01-01 09:00 +09:00
+            end_time: None,
+            repeat: RepeatPattern::Daily,
+            timezone: Some("+09:00".to_string()),
+        };
+        assert_eq!(scheduler.next_execution_after(&daily, now), 1641081600);
+    }
 }
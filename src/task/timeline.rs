@@ -0,0 +1,291 @@
+// WezTerm Multi-Process Development Framework - Timeline Export
+// Renders tracked sessions of a date range as CSV, JSON or a Mermaid gantt chart
+
+use super::tracker::{csv_field, CompletedSession, SegmentType, TimeSegment};
+use super::types::TaskId;
+use crate::config::Timezone;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+/// Timestamps in Mermaid charts, which take local times without an offset
+const TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// Output format of a timeline export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimelineFormat {
+    Csv,
+    Json,
+    Mermaid,
+}
+
+impl std::str::FromStr for TimelineFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "csv" => Ok(TimelineFormat::Csv),
+            "json" => Ok(TimelineFormat::Json),
+            "mermaid" | "gantt" => Ok(TimelineFormat::Mermaid),
+            _ => Err(format!(
+                "Unsupported timeline format '{value}': expected csv, json or mermaid"
+            )),
+        }
+    }
+}
+
+/// One tracked session on the timeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineEntry {
+    pub task_id: TaskId,
+
+    /// Task title when known, otherwise the task ID
+    pub label: String,
+    pub category: Option<String>,
+    pub workspace: Option<String>,
+    pub started_at: u64,
+    pub ended_at: u64,
+    pub active_duration: u64,
+    pub break_duration: u64,
+    pub interruptions: u32,
+    pub tags: Vec<String>,
+
+    /// Breaks and interruptions within the session
+    pub gaps: Vec<TimeSegment>,
+
+    /// Import source, for sessions not tracked here
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+/// Sessions overlapping a period, oldest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Timeline {
+    pub period_start: u64,
+    pub period_end: u64,
+    pub entries: Vec<TimelineEntry>,
+}
+
+/// Titles, categories and workspaces that label timeline entries
+#[derive(Debug, Clone, Default)]
+pub struct TaskLabels {
+    pub titles: HashMap<TaskId, String>,
+    pub categories: HashMap<TaskId, String>,
+    pub workspaces: HashMap<TaskId, String>,
+}
+
+impl Timeline {
+    /// Timeline of the sessions overlapping `period_start..=period_end`
+    pub fn build(
+        sessions: &[CompletedSession],
+        period_start: u64,
+        period_end: u64,
+        labels: &TaskLabels,
+    ) -> Self {
+        let mut entries: Vec<TimelineEntry> = sessions
+            .iter()
+            .filter(|s| s.started_at <= period_end && s.ended_at >= period_start)
+            .map(|s| TimelineEntry {
+                task_id: s.task_id.clone(),
+                label: labels
+                    .titles
+                    .get(&s.task_id)
+                    .cloned()
+                    .unwrap_or_else(|| s.task_id.clone()),
+                category: labels.categories.get(&s.task_id).cloned(),
+                workspace: labels.workspaces.get(&s.task_id).cloned(),
+                started_at: s.started_at,
+                ended_at: s.ended_at,
+                active_duration: s.active_duration,
+                break_duration: s.break_duration,
+                interruptions: s.interruptions,
+                tags: s.tags.clone(),
+                gaps: s
+                    .segments
+                    .iter()
+                    .filter(|segment| segment.segment_type != SegmentType::Active)
+                    .cloned()
+                    .collect(),
+                source: s.source.clone(),
+            })
+            .collect();
+        entries.sort_by_key(|entry| entry.started_at);
+
+        Self {
+            period_start,
+            period_end,
+            entries,
+        }
+    }
+
+    /// Render the timeline; times are shown in `timezone`
+    pub fn render(&self, format: TimelineFormat, timezone: Timezone) -> Result<String, String> {
+        match format {
+            TimelineFormat::Json => serde_json::to_string_pretty(self).map_err(|e| e.to_string()),
+            TimelineFormat::Csv => Ok(self.to_csv(timezone)),
+            TimelineFormat::Mermaid => Ok(self.to_mermaid(timezone)),
+        }
+    }
+
+    fn to_csv(&self, timezone: Timezone) -> String {
+        let mut csv = String::from(
+            "task_id,label,category,workspace,started_at,ended_at,active_duration,break_duration,interruptions,tags,source\n",
+        );
+        for entry in &self.entries {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{},{},{},{},{}",
+                csv_field(&entry.task_id),
+                csv_field(&entry.label),
+                csv_field(entry.category.as_deref().unwrap_or_default()),
+                csv_field(entry.workspace.as_deref().unwrap_or_default()),
+                timezone.format(entry.started_at, "%Y-%m-%dT%H:%M:%S%:z"),
+                timezone.format(entry.ended_at, "%Y-%m-%dT%H:%M:%S%:z"),
+                entry.active_duration,
+                entry.break_duration,
+                entry.interruptions,
+                csv_field(&entry.tags.join(";")),
+                csv_field(entry.source.as_deref().unwrap_or_default()),
+            );
+        }
+        csv
+    }
+
+    /// Gantt chart with a section per task; breaks and interruptions are
+    /// marked `crit` so they stand out
+    fn to_mermaid(&self, timezone: Timezone) -> String {
+        let mut chart = String::from("gantt\n");
+        let _ = writeln!(
+            chart,
+            "    title Tracked time {} to {}",
+            timezone.format_date(self.period_start),
+            timezone.format_date(self.period_end)
+        );
+        chart.push_str("    dateFormat YYYY-MM-DDTHH:mm:ss\n");
+        chart.push_str("    axisFormat %m-%d %H:%M\n");
+
+        // Sections in order of each task's first session
+        let mut sections: Vec<(&str, Vec<&TimelineEntry>)> = Vec::new();
+        for entry in &self.entries {
+            match sections.iter_mut().find(|(label, _)| *label == entry.label) {
+                Some((_, entries)) => entries.push(entry),
+                None => sections.push((&entry.label, vec![entry])),
+            }
+        }
+
+        for (label, entries) in sections {
+            let _ = writeln!(chart, "    section {}", mermaid_text(label));
+            for entry in entries {
+                let _ = writeln!(
+                    chart,
+                    "    {} :active, {}, {}",
+                    super::format_duration(std::time::Duration::from_secs(entry.active_duration)),
+                    timezone.format(entry.started_at, TIME_FORMAT),
+                    timezone.format(entry.ended_at, TIME_FORMAT)
+                );
+                for gap in &entry.gaps {
+                    let kind = match gap.segment_type {
+                        SegmentType::Interruption => "interruption",
+                        _ => "break",
+                    };
+                    let _ = writeln!(
+                        chart,
+                        "    {kind} :crit, {}, {}",
+                        timezone.format(gap.started_at, TIME_FORMAT),
+                        timezone.format(gap.ended_at, TIME_FORMAT)
+                    );
+                }
+            }
+        }
+        chart
+    }
+}
+
+/// Text safe for a Mermaid section or task name, where `:`, `#` and `;` are syntax
+fn mermaid_text(text: &str) -> String {
+    text.split(|c: char| matches!(c, ':' | '#' | ';') || c.is_whitespace())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(task_id: &str, started_at: u64, minutes: u64) -> CompletedSession {
+        CompletedSession {
+            task_id: task_id.to_string(),
+            started_at,
+            ended_at: started_at + minutes * 60,
+            total_duration: minutes * 60,
+            active_duration: minutes * 60 - 300,
+            break_duration: 300,
+            interruptions: 1,
+            segments: vec![TimeSegment {
+                started_at: started_at + 600,
+                ended_at: started_at + 900,
+                segment_type: SegmentType::Interruption,
+            }],
+            productivity_score: 80.0,
+            tags: vec!["deep work".to_string()],
+            notes: Vec::new(),
+            source: None,
+        }
+    }
+
+    #[test]
+    fn test_timeline_keeps_sessions_in_range() {
+        // 2024-03-01 09:00 UTC and the day before
+        let day = 1709283600;
+        let sessions = vec![
+            session("fix", day + 7200, 30),
+            session("old", day - 86_400, 60),
+            session("fix", day, 60),
+        ];
+        let labels = TaskLabels {
+            titles: HashMap::from([("fix".to_string(), "Fix: login; again".to_string())]),
+            ..Default::default()
+        };
+        let timeline = Timeline::build(&sessions, day - 3600, day + 86_400, &labels);
+        let started: Vec<u64> = timeline.entries.iter().map(|e| e.started_at).collect();
+        assert_eq!(started, [day, day + 7200]);
+
+        let csv = timeline.render(TimelineFormat::Csv, Timezone::Utc).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[1],
+            "fix,Fix: login; again,,,2024-03-01T09:00:00+00:00,2024-03-01T10:00:00+00:00,3300,300,1,deep work,"
+        );
+
+        let json = timeline
+            .render(TimelineFormat::Json, Timezone::Utc)
+            .unwrap();
+        let parsed: Timeline = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.entries[0].gaps.len(), 1);
+    }
+
+    #[test]
+    fn test_mermaid_gantt() {
+        let day = 1709283600;
+        let labels = TaskLabels {
+            titles: HashMap::from([("fix".to_string(), "Fix: login".to_string())]),
+            ..Default::default()
+        };
+        let timeline = Timeline::build(
+            &[session("fix", day, 60), session("review", day + 3600, 20)],
+            day,
+            day + 7200,
+            &labels,
+        );
+        let chart = timeline
+            .render("gantt".parse().unwrap(), Timezone::Utc)
+            .unwrap();
+        assert!(chart.starts_with("gantt\n    title Tracked time 2024-03-01 to 2024-03-01\n"));
+        assert!(chart.contains("    section Fix login\n    55m 0s :active, 2024-03-01T09:00:00, 2024-03-01T10:00:00\n    interruption :crit, 2024-03-01T09:10:00, 2024-03-01T09:15:00\n"));
+        assert!(chart.contains("    section review\n"));
+        assert!("pdf".parse::<TimelineFormat>().is_err());
+    }
+}
@@ -0,0 +1,145 @@
+// WezTerm Multi-Process Development Framework - Task Traces
+// Stitches the execution attempts of a task, across agents, into a single trace
+
+use super::current_timestamp;
+use super::types::{ExecutionResult, Task, TaskId, TaskStatus, DEFAULT_AGENT};
+use serde::{Deserialize, Serialize};
+
+/// One execution attempt of a task
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceSpan {
+    /// `<task id>/<attempt>`
+    pub span_id: String,
+    pub attempt: u32,
+    pub agent: String,
+    pub started_at: u64,
+    pub ended_at: Option<u64>,
+
+    /// Seconds; for a running attempt, the time so far
+    pub duration_secs: u64,
+    pub outcome: ExecutionResult,
+    pub error: Option<String>,
+}
+
+/// The journey of a task through all of its execution attempts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskTrace {
+    /// Same as the task ID, so traces can be correlated with task events
+    pub trace_id: String,
+    pub task_id: TaskId,
+    pub title: String,
+    pub status: TaskStatus,
+
+    /// Agents in the order they first worked on the task
+    pub agents: Vec<String>,
+
+    /// Number of times the task moved to a different agent
+    pub handoffs: usize,
+
+    /// Summed span durations
+    pub total_duration_secs: u64,
+    pub spans: Vec<TraceSpan>,
+}
+
+impl TaskTrace {
+    /// Build the trace from a task's execution history
+    pub fn from_task(task: &Task) -> Self {
+        Self::from_task_at(task, current_timestamp())
+    }
+
+    fn from_task_at(task: &Task, now: u64) -> Self {
+        let spans: Vec<TraceSpan> = task
+            .execution_history
+            .iter()
+            .map(|record| TraceSpan {
+                span_id: format!("{}/{}", task.id, record.attempt),
+                attempt: record.attempt,
+                agent: record
+                    .agent
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_AGENT.to_string()),
+                started_at: record.started_at,
+                ended_at: record.ended_at,
+                duration_secs: record.duration.unwrap_or_else(|| {
+                    record
+                        .ended_at
+                        .unwrap_or(now)
+                        .saturating_sub(record.started_at)
+                }),
+                outcome: record.result.clone(),
+                error: record.error.clone(),
+            })
+            .collect();
+
+        let mut agents: Vec<String> = Vec::new();
+        for span in &spans {
+            if !agents.contains(&span.agent) {
+                agents.push(span.agent.clone());
+            }
+        }
+
+        Self {
+            trace_id: task.id.clone(),
+            task_id: task.id.clone(),
+            title: task.title.clone(),
+            status: task.status.clone(),
+            agents,
+            handoffs: spans
+                .windows(2)
+                .filter(|w| w[0].agent != w[1].agent)
+                .count(),
+            total_duration_secs: spans.iter().map(|span| span.duration_secs).sum(),
+            spans,
+        }
+    }
+}
+
+/// Task detail payload sent to the dashboard: the task plus its trace
+pub fn task_detail(task: &Task) -> serde_json::Result<serde_json::Value> {
+    let mut detail = serde_json::to_value(task)?;
+    if let serde_json::Value::Object(ref mut fields) = detail {
+        fields.insert(
+            "trace".to_string(),
+            serde_json::to_value(TaskTrace::from_task(task))?,
+        );
+    }
+    Ok(detail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::types::TaskCategory;
+
+    #[test]
+    fn test_trace_across_agents() {
+        let mut task = Task::new("Fix build".to_string(), TaskCategory::Development);
+        task.assignee = Some("claude-1".to_string());
+        assert_eq!(task.begin_attempt(), 1);
+        assert!(task.end_attempt_at(
+            ExecutionResult::Failed,
+            Some("tests failed".to_string()),
+            task.execution_history[0].started_at + 30,
+        ));
+        assert!(!task.end_attempt(ExecutionResult::Success, None));
+
+        // Reassigned to another agent for the retry, still running
+        task.assignee = Some("claude-2".to_string());
+        task.begin_attempt();
+        let started = task.execution_history[1].started_at;
+
+        let trace = TaskTrace::from_task_at(&task, started + 12);
+        assert_eq!(trace.trace_id, task.id);
+        assert_eq!(trace.agents, vec!["claude-1", "claude-2"]);
+        assert_eq!(trace.handoffs, 1);
+        assert_eq!(trace.total_duration_secs, 42);
+        assert_eq!(trace.spans[0].outcome, ExecutionResult::Failed);
+        assert_eq!(trace.spans[0].error.as_deref(), Some("tests failed"));
+        assert_eq!(trace.spans[1].span_id, format!("{}/2", task.id));
+        assert_eq!(trace.spans[1].ended_at, None);
+
+        let detail = task_detail(&task).unwrap();
+        assert_eq!(detail["trace"]["spans"].as_array().unwrap().len(), 2);
+        assert_eq!(detail["id"], task.id.as_str());
+    }
+}
@@ -1,11 +1,15 @@
 // WezTerm Multi-Process Development Framework - Task Tracker
 // Provides time tracking, progress monitoring, and productivity analytics
 
+use super::budget::{BudgetState, BudgetStatus, TimeBudget};
+use super::timeline::{TaskLabels, Timeline};
 use super::types::TaskId;
 use super::{current_timestamp, format_duration};
+use crate::clock::{elapsed_secs, Stopwatch};
+use crate::config::Timezone;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::time::{Duration, SystemTime};
+use std::time::Duration;
 use tokio::sync::RwLock;
 use tracing::{debug, info};
 
@@ -24,8 +28,32 @@ pub struct TaskTracker {
     /// Daily summaries
     daily_summaries: RwLock<HashMap<String, DailySummary>>, // date -> summary
 
+    /// Weekly roll-ups, updated as sessions complete
+    weekly_summaries: RwLock<HashMap<String, PeriodSummary>>, // ISO week -> summary
+
+    /// Monthly roll-ups, updated as sessions complete
+    monthly_summaries: RwLock<HashMap<String, PeriodSummary>>, // YYYY-MM -> summary
+
+    /// Category of each tracked task, used for roll-up breakdowns
+    task_categories: RwLock<HashMap<TaskId, String>>,
+
+    /// Workspace of each tracked task, used for workspace budgets
+    task_workspaces: RwLock<HashMap<TaskId, String>>,
+
+    /// Assignee of each tracked task, used for per-user reports
+    task_assignees: RwLock<HashMap<TaskId, String>>,
+
+    /// Weekly time budgets
+    budgets: Vec<TimeBudget>,
+
+    /// Last alerted state of each budget, keyed by budget label
+    budget_alerts: RwLock<HashMap<String, (String, BudgetState)>>, // label -> (week, state)
+
     /// Tracker statistics
     stats: RwLock<TrackerStats>,
+
+    /// Timezone used to bucket sessions into days
+    timezone: Timezone,
 }
 
 impl Default for TaskTracker {
@@ -42,8 +70,136 @@ impl TaskTracker {
             completed_sessions: RwLock::new(Vec::new()),
             productivity_metrics: RwLock::new(HashMap::new()),
             daily_summaries: RwLock::new(HashMap::new()),
+            weekly_summaries: RwLock::new(HashMap::new()),
+            monthly_summaries: RwLock::new(HashMap::new()),
+            task_categories: RwLock::new(HashMap::new()),
+            task_workspaces: RwLock::new(HashMap::new()),
+            task_assignees: RwLock::new(HashMap::new()),
+            budgets: Vec::new(),
+            budget_alerts: RwLock::new(HashMap::new()),
             stats: RwLock::new(TrackerStats::new()),
+            timezone: Timezone::default(),
+        }
+    }
+
+    /// Set the timezone used for daily summaries and report ranges
+    pub fn with_timezone(mut self, timezone: Timezone) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// Timezone used for daily summaries and report ranges
+    pub fn timezone(&self) -> Timezone {
+        self.timezone
+    }
+
+    /// Set weekly time budgets per category or workspace
+    pub fn with_budgets(mut self, budgets: Vec<TimeBudget>) -> Self {
+        self.budgets = budgets;
+        self
+    }
+
+    /// Configured weekly time budgets
+    pub fn budgets(&self) -> &[TimeBudget] {
+        &self.budgets
+    }
+
+    /// Record a task's category for weekly and monthly breakdowns
+    pub async fn set_task_category(&self, task_id: &TaskId, category: impl Into<String>) {
+        let mut categories = self.task_categories.write().await;
+        categories.insert(task_id.clone(), category.into());
+    }
+
+    /// Record a task's workspace for workspace budgets
+    pub async fn set_task_workspace(&self, task_id: &TaskId, workspace: impl Into<String>) {
+        let mut workspaces = self.task_workspaces.write().await;
+        workspaces.insert(task_id.clone(), workspace.into());
+    }
+
+    /// Record a task's assignee for per-user reports
+    pub async fn set_task_assignee(&self, task_id: &TaskId, assignee: impl Into<String>) {
+        let mut assignees = self.task_assignees.write().await;
+        assignees.insert(task_id.clone(), assignee.into());
+    }
+
+    /// Live burn-down of every budget for the current week
+    pub async fn get_budget_status(&self) -> Vec<BudgetStatus> {
+        self.budget_status_at(current_timestamp()).await
+    }
+
+    /// Budgets that reached a more severe state since they were last reported
+    ///
+    /// Each budget alerts at most once per state per week.
+    pub async fn check_budget_alerts(&self) -> Vec<BudgetStatus> {
+        let statuses = self.get_budget_status().await;
+        let mut alerts = self.budget_alerts.write().await;
+
+        statuses
+            .into_iter()
+            .filter(|status| {
+                let previous = alerts
+                    .get(&status.scope.to_string())
+                    .filter(|(period, _)| *period == status.period)
+                    .map(|(_, state)| *state)
+                    .unwrap_or(BudgetState::Ok);
+                alerts.insert(
+                    status.scope.to_string(),
+                    (status.period.clone(), previous.max(status.state)),
+                );
+                status.state > previous
+            })
+            .collect()
+    }
+
+    async fn budget_status_at(&self, now: u64) -> Vec<BudgetStatus> {
+        if self.budgets.is_empty() {
+            return Vec::new();
         }
+
+        let (period, first_day, next_first_day) =
+            SummaryPeriod::Week.bounds(self.timezone.date_of(now));
+        let week_start = self.timezone.start_of_day(first_day);
+        let week_end = self.timezone.start_of_day(next_first_day);
+        let week_elapsed = now.saturating_sub(week_start) as f64
+            / week_end.saturating_sub(week_start).max(1) as f64;
+
+        // Time per task this week, including sessions still running
+        let mut task_time: HashMap<TaskId, u64> = HashMap::new();
+        {
+            let completed = self.completed_sessions.read().await;
+            for session in completed
+                .iter()
+                .filter(|s| s.started_at >= week_start && s.started_at < week_end)
+            {
+                *task_time.entry(session.task_id.clone()).or_insert(0) += session.active_duration;
+            }
+        }
+        {
+            let active = self.active_sessions.read().await;
+            for session in active.values().filter(|s| s.started_at >= week_start) {
+                *task_time.entry(session.task_id.clone()).or_insert(0) +=
+                    session.get_current_duration();
+            }
+        }
+
+        let categories = self.task_categories.read().await;
+        let workspaces = self.task_workspaces.read().await;
+        self.budgets
+            .iter()
+            .map(|budget| {
+                let used = task_time
+                    .iter()
+                    .filter(|(task_id, _)| {
+                        budget.scope.matches(
+                            categories.get(*task_id).map(|c| c.as_str()),
+                            workspaces.get(*task_id).map(|w| w.as_str()),
+                        )
+                    })
+                    .map(|(_, time)| *time)
+                    .sum();
+                BudgetStatus::new(budget, period.clone(), used, week_elapsed)
+            })
+            .collect()
     }
 
     /// Start tracking a task
@@ -57,6 +213,8 @@ impl TaskTracker {
             interruptions: 0,
             is_paused: false,
             segments: Vec::new(),
+            tags: Vec::new(),
+            notes: Vec::new(),
         };
 
         {
@@ -81,7 +239,7 @@ impl TaskTracker {
 
         if let Some(mut session) = session {
             let now = current_timestamp();
-            session.total_active_time += now - session.last_activity;
+            session.total_active_time += elapsed_secs(session.last_activity, now);
 
             // Create completed session
             let productivity_score = self.calculate_productivity_score(&session);
@@ -89,12 +247,15 @@ impl TaskTracker {
                 task_id: task_id.clone(),
                 started_at: session.started_at,
                 ended_at: now,
-                total_duration: now - session.started_at,
+                total_duration: elapsed_secs(session.started_at, now),
                 active_duration: session.total_active_time,
                 break_duration: session.break_time,
                 interruptions: session.interruptions,
                 segments: session.segments,
                 productivity_score,
+                tags: session.tags,
+                notes: session.notes,
+                source: None,
             };
 
             let duration = Duration::from_secs(completed.active_duration);
@@ -105,8 +266,9 @@ impl TaskTracker {
                 completed_sessions.push(completed.clone());
             }
 
-            // Update daily summary
+            // Update daily summary and weekly/monthly roll-ups
             self.update_daily_summary(&completed).await;
+            self.update_period_summaries(&completed).await;
 
             // Update productivity metrics
             self.update_productivity_metrics(task_id, &completed).await;
@@ -135,7 +297,7 @@ impl TaskTracker {
         if let Some(session) = active.get_mut(task_id) {
             if !session.is_paused {
                 let now = current_timestamp();
-                session.total_active_time += now - session.last_activity;
+                session.total_active_time += elapsed_secs(session.last_activity, now);
                 session.is_paused = true;
 
                 // Add segment
@@ -158,7 +320,7 @@ impl TaskTracker {
         if let Some(session) = active.get_mut(task_id) {
             if session.is_paused {
                 let now = current_timestamp();
-                session.break_time += now - session.last_activity;
+                session.break_time += elapsed_secs(session.last_activity, now);
                 session.last_activity = now;
                 session.is_paused = false;
 
@@ -179,7 +341,7 @@ impl TaskTracker {
             // Add interruption segment
             let now = current_timestamp();
             session.segments.push(TimeSegment {
-                started_at: now - duration_seconds,
+                started_at: now.saturating_sub(duration_seconds),
                 ended_at: now,
                 segment_type: SegmentType::Interruption,
             });
@@ -213,6 +375,97 @@ impl TaskTracker {
         active.values().cloned().collect()
     }
 
+    /// Resume sessions that were active when the daemon stopped
+    ///
+    /// Running sessions count as active until `saved_at`, the last time they
+    /// were persisted, and the downtime until now is recorded as a break.
+    /// Paused sessions are restored as they were. Tasks that are already
+    /// being tracked keep their current session. Returns the number of
+    /// restored sessions.
+    pub async fn restore_sessions(&self, sessions: Vec<TrackingSession>, saved_at: u64) -> usize {
+        let now = current_timestamp();
+        let mut active = self.active_sessions.write().await;
+        let mut restored = 0;
+        for mut session in sessions {
+            if active.contains_key(&session.task_id) {
+                continue;
+            }
+            if !session.is_paused {
+                // Persisted after the last activity, and never in the future
+                let stopped_at = saved_at.min(now).max(session.last_activity);
+                session.total_active_time += stopped_at - session.last_activity;
+                session.segments.push(TimeSegment {
+                    started_at: session.last_activity,
+                    ended_at: stopped_at,
+                    segment_type: SegmentType::Active,
+                });
+                if now > stopped_at {
+                    session.break_time += now - stopped_at;
+                    session.segments.push(TimeSegment {
+                        started_at: stopped_at,
+                        ended_at: now,
+                        segment_type: SegmentType::Break,
+                    });
+                }
+                session.last_activity = now.max(stopped_at);
+            }
+            info!("Resumed tracking task after restart: {}", session.task_id);
+            active.insert(session.task_id.clone(), session);
+            restored += 1;
+        }
+        restored
+    }
+
+    /// Add sessions tracked elsewhere to the history, as if they had been
+    /// stopped here; sessions already known (same task and start) are skipped
+    ///
+    /// Returns the number of sessions added.
+    pub async fn import_sessions(&self, sessions: Vec<CompletedSession>) -> usize {
+        let mut imported = 0;
+        for session in sessions {
+            {
+                let mut completed = self.completed_sessions.write().await;
+                if completed
+                    .iter()
+                    .any(|c| c.task_id == session.task_id && c.started_at == session.started_at)
+                {
+                    continue;
+                }
+                completed.push(session.clone());
+            }
+
+            self.update_daily_summary(&session).await;
+            self.update_period_summaries(&session).await;
+            self.update_productivity_metrics(&session.task_id, &session)
+                .await;
+            {
+                let mut stats = self.stats.write().await;
+                stats.sessions_completed += 1;
+                stats.total_tracked_time += session.active_duration;
+            }
+            imported += 1;
+        }
+        imported
+    }
+
+    /// Sessions that came from an import rather than being tracked here
+    pub async fn imported_sessions(&self) -> Vec<CompletedSession> {
+        let completed = self.completed_sessions.read().await;
+        completed
+            .iter()
+            .filter(|session| session.source.is_some())
+            .cloned()
+            .collect()
+    }
+
+    /// Whether a session of the task starting at `started_at` is in the history
+    pub async fn has_session(&self, task_id: &str, started_at: u64) -> bool {
+        let completed = self.completed_sessions.read().await;
+        completed
+            .iter()
+            .any(|c| c.task_id == task_id && c.started_at == started_at)
+    }
+
     /// Get task history for a specific task
     pub async fn get_task_history(&self, task_id: &TaskId) -> Vec<CompletedSession> {
         let completed = self.completed_sessions.read().await;
@@ -223,6 +476,165 @@ impl TaskTracker {
             .collect()
     }
 
+    /// Add tags to a tracking session
+    ///
+    /// `started_at` selects a specific session of the task; `None` targets
+    /// its active session. Returns false if no such session exists.
+    pub async fn tag_session(
+        &self,
+        task_id: &TaskId,
+        started_at: Option<u64>,
+        tags: &[String],
+    ) -> bool {
+        self.modify_session(task_id, started_at, |session_tags, _| {
+            for tag in tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+                if !has_tag(session_tags, tag) {
+                    session_tags.push(tag.to_string());
+                }
+            }
+        })
+        .await
+    }
+
+    /// Remove tags from a tracking session
+    pub async fn untag_session(
+        &self,
+        task_id: &TaskId,
+        started_at: Option<u64>,
+        tags: &[String],
+    ) -> bool {
+        self.modify_session(task_id, started_at, |session_tags, _| {
+            session_tags.retain(|existing| !has_tag(tags, existing.trim()));
+        })
+        .await
+    }
+
+    /// Attach a note to a tracking session
+    pub async fn annotate_session(
+        &self,
+        task_id: &TaskId,
+        started_at: Option<u64>,
+        note: &str,
+    ) -> bool {
+        let note = note.trim();
+        if note.is_empty() {
+            return false;
+        }
+
+        self.modify_session(task_id, started_at, |_, notes| {
+            notes.push(SessionNote {
+                text: note.to_string(),
+                created_at: current_timestamp(),
+            });
+        })
+        .await
+    }
+
+    /// Apply `update` to the tags and notes of the selected session
+    async fn modify_session(
+        &self,
+        task_id: &TaskId,
+        started_at: Option<u64>,
+        update: impl FnOnce(&mut Vec<String>, &mut Vec<SessionNote>),
+    ) -> bool {
+        {
+            let mut active = self.active_sessions.write().await;
+            if let Some(session) = active
+                .get_mut(task_id)
+                .filter(|s| started_at.map_or(true, |at| s.started_at == at))
+            {
+                update(&mut session.tags, &mut session.notes);
+                return true;
+            }
+        }
+
+        let Some(started_at) = started_at else {
+            return false;
+        };
+        let mut completed = self.completed_sessions.write().await;
+        match completed
+            .iter_mut()
+            .find(|s| s.task_id == *task_id && s.started_at == started_at)
+        {
+            Some(session) => {
+                update(&mut session.tags, &mut session.notes);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Export completed sessions, optionally only those with a tag
+    pub async fn export_sessions(
+        &self,
+        format: SessionExportFormat,
+        tag: Option<&str>,
+    ) -> Result<String, String> {
+        let mut sessions: Vec<CompletedSession> = {
+            let completed = self.completed_sessions.read().await;
+            completed
+                .iter()
+                .filter(|s| tag.map_or(true, |tag| s.has_tag(tag)))
+                .cloned()
+                .collect()
+        };
+        sessions.sort_by_key(|s| s.started_at);
+
+        match format {
+            SessionExportFormat::Json => {
+                serde_json::to_string_pretty(&sessions).map_err(|e| e.to_string())
+            }
+            SessionExportFormat::Csv => {
+                let mut csv = String::from(
+                    "task_id,started_at,ended_at,active_duration,break_duration,interruptions,tags,notes\n",
+                );
+                for session in &sessions {
+                    let notes: Vec<&str> = session.notes.iter().map(|n| n.text.as_str()).collect();
+                    csv.push_str(&format!(
+                        "{},{},{},{},{},{},{},{}\n",
+                        csv_field(&session.task_id),
+                        self.timezone
+                            .format(session.started_at, "%Y-%m-%dT%H:%M:%S%:z"),
+                        self.timezone
+                            .format(session.ended_at, "%Y-%m-%dT%H:%M:%S%:z"),
+                        session.active_duration,
+                        session.break_duration,
+                        session.interruptions,
+                        csv_field(&session.tags.join(";")),
+                        csv_field(&notes.join("\n")),
+                    ));
+                }
+                Ok(csv)
+            }
+        }
+    }
+
+    /// Sessions between two report dates (`YYYY-MM-DD`, `today`, `7d`, ...),
+    /// labelled with the given task titles
+    pub async fn timeline(
+        &self,
+        start_date: &str,
+        end_date: &str,
+        titles: HashMap<TaskId, String>,
+    ) -> Timeline {
+        let now = current_timestamp();
+        let start = self.resolve_report_date(start_date, now);
+        let end = self.resolve_report_date(end_date, now);
+        let labels = TaskLabels {
+            titles,
+            categories: self.task_categories.read().await.clone(),
+            workspaces: self.task_workspaces.read().await.clone(),
+        };
+
+        let completed = self.completed_sessions.read().await;
+        Timeline::build(
+            &completed,
+            self.timezone.start_of_day(start),
+            self.timezone.end_of_day(end),
+            &labels,
+        )
+    }
+
     /// Get productivity metrics for a task
     pub async fn get_productivity_metrics(&self, task_id: &TaskId) -> Option<ProductivityMetrics> {
         let metrics = self.productivity_metrics.read().await;
@@ -235,6 +647,50 @@ impl TaskTracker {
         summaries.get(date).cloned()
     }
 
+    /// Get weekly roll-up for an ISO week (e.g. `2022-W01`)
+    pub async fn get_weekly_summary(&self, week: &str) -> Option<PeriodSummary> {
+        let summaries = self.weekly_summaries.read().await;
+        summaries.get(week).cloned()
+    }
+
+    /// Get monthly roll-up for a month (e.g. `2022-01`)
+    pub async fn get_monthly_summary(&self, month: &str) -> Option<PeriodSummary> {
+        let summaries = self.monthly_summaries.read().await;
+        summaries.get(month).cloned()
+    }
+
+    /// Roll-ups overlapping a date range, oldest first
+    ///
+    /// Dates accept the same forms as [`Self::get_productivity_report`]. Only
+    /// the stored roll-ups are read, so long ranges stay cheap.
+    pub async fn get_period_summaries(
+        &self,
+        period: SummaryPeriod,
+        start_date: &str,
+        end_date: &str,
+    ) -> Vec<PeriodSummary> {
+        let now = current_timestamp();
+        let start = self
+            .timezone
+            .start_of_day(self.resolve_report_date(start_date, now));
+        let end = self
+            .timezone
+            .end_of_day(self.resolve_report_date(end_date, now));
+
+        let summaries = match period {
+            SummaryPeriod::Week => self.weekly_summaries.read().await,
+            SummaryPeriod::Month => self.monthly_summaries.read().await,
+        };
+
+        let mut selected: Vec<PeriodSummary> = summaries
+            .values()
+            .filter(|summary| summary.period_start <= end && summary.period_end >= start)
+            .cloned()
+            .collect();
+        selected.sort_by_key(|summary| summary.period_start);
+        selected
+    }
+
     /// Get tracker statistics
     pub async fn get_stats(&self) -> TrackerStats {
         let stats = self.stats.read().await;
@@ -245,16 +701,66 @@ impl TaskTracker {
     pub async fn generate_enhanced_productivity_report(
         &self,
         since_timestamp: Option<u64>,
+    ) -> ProductivityReport {
+        self.generate_tagged_productivity_report(since_timestamp, None)
+            .await
+    }
+
+    /// Generate enhanced productivity report limited to sessions with a tag
+    pub async fn generate_tagged_productivity_report(
+        &self,
+        since_timestamp: Option<u64>,
+        tag: Option<&str>,
+    ) -> ProductivityReport {
+        self.generate_filtered_productivity_report(since_timestamp, tag, None)
+            .await
+    }
+
+    /// Generate enhanced productivity report limited to the tasks of one
+    /// assignee
+    pub async fn generate_assignee_productivity_report(
+        &self,
+        since_timestamp: Option<u64>,
+        assignee: &str,
+    ) -> ProductivityReport {
+        self.generate_filtered_productivity_report(since_timestamp, None, Some(assignee))
+            .await
+    }
+
+    async fn generate_filtered_productivity_report(
+        &self,
+        since_timestamp: Option<u64>,
+        tag: Option<&str>,
+        assignee: Option<&str>,
     ) -> ProductivityReport {
         let completed = self.completed_sessions.read().await;
         let active = self.active_sessions.read().await;
+        let assignees = self.task_assignees.read().await;
         let since = since_timestamp.unwrap_or(0);
+        let assignee_matches = |task_id: &TaskId| {
+            assignee.map_or(true, |assignee| {
+                assignees.get(task_id).map(|a| a.as_str()) == Some(assignee)
+            })
+        };
 
-        // Filter sessions by timestamp
-        let relevant_completed: Vec<_> =
-            completed.iter().filter(|s| s.started_at >= since).collect();
-
-        let relevant_active: Vec<_> = active.values().filter(|s| s.started_at >= since).collect();
+        // Filter sessions by timestamp, tag and assignee
+        let relevant_completed: Vec<_> = completed
+            .iter()
+            .filter(|s| {
+                s.started_at >= since
+                    && tag.map_or(true, |tag| s.has_tag(tag))
+                    && assignee_matches(&s.task_id)
+            })
+            .collect();
+
+        let relevant_active: Vec<_> = active
+            .values()
+            .filter(|s| {
+                s.started_at >= since
+                    && tag.map_or(true, |tag| s.has_tag(tag))
+                    && assignee_matches(&s.task_id)
+            })
+            .collect();
 
         // Calculate metrics
         let total_sessions = relevant_completed.len() + relevant_active.len();
@@ -310,9 +816,9 @@ impl TaskTracker {
         // Daily breakdown
         let mut daily_stats = std::collections::HashMap::new();
         for session in &relevant_completed {
-            let day = session.started_at / 86400; // Convert to days
+            let day = self.timezone.date_of(session.started_at);
             let entry = daily_stats.entry(day).or_insert(DailyStats {
-                date: day * 86400,
+                date: self.timezone.start_of_day(day),
                 total_time: 0,
                 focused_time: 0,
                 sessions: 0,
@@ -324,7 +830,37 @@ impl TaskTracker {
             entry.interruptions += session.interruptions as u64;
         }
 
-        let daily_breakdown: Vec<_> = daily_stats.into_values().collect();
+        let mut daily_breakdown: Vec<_> = daily_stats.into_values().collect();
+        daily_breakdown.sort_by_key(|stats| stats.date);
+
+        // Assignee breakdown, busiest first
+        let mut assignee_stats: HashMap<Option<String>, AssigneeStats> = HashMap::new();
+        let sessions = relevant_completed
+            .iter()
+            .map(|s| (&s.task_id, s.total_duration, s.active_duration))
+            .chain(
+                relevant_active
+                    .iter()
+                    .map(|s| (&s.task_id, s.get_current_duration(), s.get_focused_time())),
+            );
+        for (task_id, total_time, focused_time) in sessions {
+            let assignee = assignees.get(task_id).cloned();
+            let entry = assignee_stats
+                .entry(assignee.clone())
+                .or_insert_with(|| AssigneeStats {
+                    assignee,
+                    ..Default::default()
+                });
+            entry.sessions += 1;
+            entry.total_time += total_time;
+            entry.focused_time += focused_time;
+        }
+        let mut by_assignee: Vec<_> = assignee_stats.into_values().collect();
+        by_assignee.sort_by(|a, b| {
+            b.total_time
+                .cmp(&a.total_time)
+                .then_with(|| a.assignee.cmp(&b.assignee))
+        });
 
         ProductivityReport {
             period_start: since,
@@ -338,6 +874,7 @@ impl TaskTracker {
             focus_efficiency,
             avg_interruptions_per_session,
             daily_breakdown,
+            by_assignee,
         }
     }
 
@@ -395,73 +932,101 @@ impl TaskTracker {
     }
 
     /// Get productivity report for date range
+    ///
+    /// Dates are `YYYY-MM-DD` or relative to today (`today`, `yesterday`,
+    /// `7d`, `2w`) and both ends are inclusive, evaluated in the tracker's
+    /// timezone. Unparseable dates fall back to today.
     pub async fn get_productivity_report(
         &self,
         start_date: &str,
         end_date: &str,
     ) -> ProductivityReport {
+        let now = current_timestamp();
+        let start = self.resolve_report_date(start_date, now);
+        let end = self.resolve_report_date(end_date, now);
+
         let summaries = self.daily_summaries.read().await;
 
         let mut total_time = 0;
+        let mut total_break_time = 0;
         let mut total_sessions = 0;
         let mut total_interruptions = 0;
-        let mut daily_reports = Vec::new();
+        let mut daily_breakdown = Vec::new();
 
         for (date, summary) in summaries.iter() {
-            if date.as_str() >= start_date && date.as_str() <= end_date {
-                total_time += summary.total_active_time;
-                total_sessions += summary.total_sessions;
-                total_interruptions += summary.total_interruptions;
-                daily_reports.push(summary.clone());
+            let Ok(day) = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d") else {
+                continue;
+            };
+            if day < start || day > end {
+                continue;
             }
+
+            total_time += summary.total_active_time;
+            total_break_time += summary.total_break_time;
+            total_sessions += summary.total_sessions;
+            total_interruptions += summary.total_interruptions;
+            daily_breakdown.push(DailyStats {
+                date: self.timezone.start_of_day(day),
+                total_time: summary.total_active_time + summary.total_break_time,
+                focused_time: summary.total_active_time,
+                sessions: summary.total_sessions.into(),
+                interruptions: summary.total_interruptions.into(),
+            });
         }
+        daily_breakdown.sort_by_key(|stats| stats.date);
+
+        let tracked_time = total_time + total_break_time;
 
         ProductivityReport {
-            period_start: parse_date_to_timestamp(start_date),
-            period_end: parse_date_to_timestamp(end_date),
+            period_start: self.timezone.start_of_day(start),
+            period_end: self.timezone.end_of_day(end),
             total_time,
             total_sessions: total_sessions.into(),
             total_interruptions: total_interruptions.into(),
-            total_focused_time: total_time, // For now, assume all time is focused in this legacy method
-            total_break_time: 0,
+            total_focused_time: total_time,
+            total_break_time,
             avg_session_duration: if total_sessions > 0 {
                 total_time / total_sessions as u64
             } else {
                 0
             },
-            focus_efficiency: 100.0, // Default to 100% for legacy method
+            focus_efficiency: if tracked_time > 0 {
+                (total_time as f64 / tracked_time as f64) * 100.0
+            } else {
+                100.0
+            },
             avg_interruptions_per_session: if total_sessions > 0 {
                 total_interruptions as f64 / total_sessions as f64
             } else {
                 0.0
             },
-            daily_breakdown: vec![], // Empty for legacy method
+            daily_breakdown,
+            // Daily summaries do not keep track of tasks
+            by_assignee: Vec::new(),
         }
     }
 
-    /// Calculate productivity score for a session
-    fn calculate_productivity_score(&self, session: &TrackingSession) -> f64 {
-        if session.total_active_time == 0 {
-            return 0.0;
-        }
-
-        let total_time = session.total_active_time + session.break_time;
-        let active_ratio = session.total_active_time as f64 / total_time as f64;
-
-        // Base score from active time ratio
-        let mut score = active_ratio * 100.0;
-
-        // Penalty for interruptions
-        let interruption_penalty = (session.interruptions as f64) * 5.0;
-        score = (score - interruption_penalty).max(0.0);
+    /// Timestamp at which a report date (`YYYY-MM-DD`, `today`, `7d`, ...) starts
+    pub fn parse_date_to_timestamp(&self, date: &str) -> Option<u64> {
+        parse_report_date(date, self.timezone, current_timestamp())
+            .map(|day| self.timezone.start_of_day(day))
+    }
 
-        // Bonus for longer focused sessions
-        if session.total_active_time > 1800 {
-            // 30+ minutes
-            score += 10.0;
-        }
+    /// Resolve a report date, falling back to today
+    fn resolve_report_date(&self, date: &str, now: u64) -> chrono::NaiveDate {
+        parse_report_date(date, self.timezone, now).unwrap_or_else(|| {
+            debug!("Unrecognised report date '{}', using today", date);
+            self.timezone.date_of(now)
+        })
+    }
 
-        score.min(100.0)
+    /// Calculate productivity score for a session
+    fn calculate_productivity_score(&self, session: &TrackingSession) -> f64 {
+        productivity_score(
+            session.total_active_time,
+            session.break_time,
+            session.interruptions,
+        )
     }
 
     /// Calculate productivity score for a period
@@ -477,7 +1042,7 @@ impl TaskTracker {
 
     /// Update daily summary with completed session
     async fn update_daily_summary(&self, session: &CompletedSession) {
-        let date = format_date_from_timestamp(session.started_at);
+        let date = format_date_from_timestamp(session.started_at, self.timezone);
 
         let mut summaries = self.daily_summaries.write().await;
         let summary = summaries
@@ -509,6 +1074,55 @@ impl TaskTracker {
         summary.productivity_score = session.productivity_score;
     }
 
+    /// Fold a completed session into its weekly and monthly roll-ups
+    async fn update_period_summaries(&self, session: &CompletedSession) {
+        let day = self.timezone.date_of(session.started_at);
+        let category = {
+            let categories = self.task_categories.read().await;
+            categories
+                .get(&session.task_id)
+                .cloned()
+                .unwrap_or_else(|| "Uncategorized".to_string())
+        };
+
+        for period in [SummaryPeriod::Week, SummaryPeriod::Month] {
+            let (key, first_day, next_first_day) = period.bounds(day);
+            let mut summaries = match period {
+                SummaryPeriod::Week => self.weekly_summaries.write().await,
+                SummaryPeriod::Month => self.monthly_summaries.write().await,
+            };
+
+            let summary = summaries
+                .entry(key.clone())
+                .or_insert_with(|| PeriodSummary {
+                    period: key,
+                    period_start: self.timezone.start_of_day(first_day),
+                    period_end: self.timezone.start_of_day(next_first_day) - 1,
+                    focus_time: 0,
+                    break_time: 0,
+                    sessions: 0,
+                    interruptions: 0,
+                    longest_session: 0,
+                    task_breakdown: HashMap::new(),
+                    category_breakdown: HashMap::new(),
+                });
+
+            summary.focus_time += session.active_duration;
+            summary.break_time += session.break_duration;
+            summary.sessions += 1;
+            summary.interruptions += session.interruptions;
+            summary.longest_session = summary.longest_session.max(session.active_duration);
+            *summary
+                .task_breakdown
+                .entry(session.task_id.clone())
+                .or_insert(0) += session.active_duration;
+            *summary
+                .category_breakdown
+                .entry(category.clone())
+                .or_insert(0) += session.active_duration;
+        }
+    }
+
     /// Update productivity metrics for a task
     async fn update_productivity_metrics(&self, task_id: &TaskId, session: &CompletedSession) {
         let mut metrics = self.productivity_metrics.write().await;
@@ -553,6 +1167,14 @@ pub struct TrackingSession {
     pub interruptions: u32,
     pub is_paused: bool,
     pub segments: Vec<TimeSegment>,
+
+    /// Free-form labels such as `pairing` or `deep work`
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Notes attached to the session
+    #[serde(default)]
+    pub notes: Vec<SessionNote>,
 }
 
 impl TrackingSession {
@@ -572,6 +1194,11 @@ impl TrackingSession {
         let interruption_overhead = self.interruptions as u64 * 30; // 30 seconds per interruption
         self.total_active_time.saturating_sub(interruption_overhead)
     }
+
+    /// Whether the session carries the given tag (case-insensitive)
+    pub fn has_tag(&self, tag: &str) -> bool {
+        has_tag(&self.tags, tag)
+    }
 }
 
 /// Completed tracking session
@@ -586,6 +1213,18 @@ pub struct CompletedSession {
     pub interruptions: u32,
     pub segments: Vec<TimeSegment>,
     pub productivity_score: f64,
+
+    /// Tags carried over from the tracking session
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Notes carried over from the tracking session
+    #[serde(default)]
+    pub notes: Vec<SessionNote>,
+
+    /// Where an imported session came from; `None` when tracked here
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
 }
 
 impl CompletedSession {
@@ -600,6 +1239,65 @@ impl CompletedSession {
         let interruption_overhead = self.interruptions as u64 * 30; // 30 seconds per interruption
         self.active_duration.saturating_sub(interruption_overhead)
     }
+
+    /// Whether the session carries the given tag (case-insensitive)
+    pub fn has_tag(&self, tag: &str) -> bool {
+        has_tag(&self.tags, tag)
+    }
+}
+
+/// Productivity score of a session from its active and break time
+pub(crate) fn productivity_score(active_time: u64, break_time: u64, interruptions: u32) -> f64 {
+    if active_time == 0 {
+        return 0.0;
+    }
+
+    let total_time = active_time + break_time;
+    let active_ratio = active_time as f64 / total_time as f64;
+
+    // Base score from active time ratio
+    let mut score = active_ratio * 100.0;
+
+    // Penalty for interruptions
+    let interruption_penalty = (interruptions as f64) * 5.0;
+    score = (score - interruption_penalty).max(0.0);
+
+    // Bonus for longer focused sessions
+    if active_time > 1800 {
+        // 30+ minutes
+        score += 10.0;
+    }
+
+    score.min(100.0)
+}
+
+/// Note attached to a tracking session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionNote {
+    pub text: String,
+    pub created_at: u64,
+}
+
+/// Output format for session exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionExportFormat {
+    Json,
+    Csv,
+}
+
+impl std::str::FromStr for SessionExportFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "json" => Ok(SessionExportFormat::Json),
+            "csv" => Ok(SessionExportFormat::Csv),
+            _ => Err(format!(
+                "Unsupported export format '{value}': expected json or csv"
+            )),
+        }
+    }
 }
 
 /// Time segment within a session
@@ -644,6 +1342,72 @@ pub struct DailySummary {
     pub task_breakdown: HashMap<TaskId, u64>,
 }
 
+/// Roll-up granularity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SummaryPeriod {
+    /// ISO week starting on Monday
+    Week,
+
+    /// Calendar month
+    Month,
+}
+
+impl SummaryPeriod {
+    /// Key, first day and first day of the next period containing `day`
+    fn bounds(self, day: chrono::NaiveDate) -> (String, chrono::NaiveDate, chrono::NaiveDate) {
+        use chrono::Datelike;
+
+        match self {
+            SummaryPeriod::Week => {
+                let first = day - chrono::Days::new(day.weekday().num_days_from_monday() as u64);
+                (
+                    day.format("%G-W%V").to_string(),
+                    first,
+                    first + chrono::Days::new(7),
+                )
+            }
+            SummaryPeriod::Month => {
+                let first = day.with_day(1).expect("first of month is valid");
+                (
+                    day.format("%Y-%m").to_string(),
+                    first,
+                    first + chrono::Months::new(1),
+                )
+            }
+        }
+    }
+}
+
+/// Weekly or monthly roll-up of tracking activity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeriodSummary {
+    /// `2022-W01` for weeks, `2022-01` for months
+    pub period: String,
+    pub period_start: u64,
+    pub period_end: u64,
+    pub focus_time: u64,
+    pub break_time: u64,
+    pub sessions: u32,
+    pub interruptions: u32,
+    pub longest_session: u64,
+    pub task_breakdown: HashMap<TaskId, u64>,
+    pub category_breakdown: HashMap<String, u64>,
+}
+
+impl PeriodSummary {
+    /// Tasks with the most focus time, largest first
+    pub fn top_tasks(&self, limit: usize) -> Vec<(TaskId, u64)> {
+        let mut tasks: Vec<(TaskId, u64)> = self
+            .task_breakdown
+            .iter()
+            .map(|(task_id, time)| (task_id.clone(), *time))
+            .collect();
+        tasks.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        tasks.truncate(limit);
+        tasks
+    }
+}
+
 /// Tracker statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrackerStats {
@@ -668,6 +1432,20 @@ pub struct ProductivityReport {
     pub focus_efficiency: f64,
     pub avg_interruptions_per_session: f64,
     pub daily_breakdown: Vec<DailyStats>,
+
+    /// Time per task assignee, busiest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub by_assignee: Vec<AssigneeStats>,
+}
+
+/// Productivity statistics of one task assignee
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AssigneeStats {
+    /// `None` for tasks without an assignee
+    pub assignee: Option<String>,
+    pub sessions: u64,
+    pub total_time: u64,
+    pub focused_time: u64,
 }
 
 /// Daily productivity statistics
@@ -705,10 +1483,10 @@ impl TrackerStats {
     }
 }
 
-/// Time tracker for simple time tracking operations
+/// Time tracker for simple time tracking operations, on the monotonic clock
 #[derive(Debug)]
 pub struct TimeTracker {
-    start_time: Option<u64>,
+    start_time: Option<Stopwatch>,
 }
 
 impl Default for TimeTracker {
@@ -723,25 +1501,15 @@ impl TimeTracker {
     }
 
     pub fn start(&mut self) {
-        self.start_time = Some(current_timestamp());
+        self.start_time = Some(Stopwatch::start());
     }
 
     pub fn stop(&mut self) -> Option<Duration> {
-        if let Some(start) = self.start_time.take() {
-            let duration = current_timestamp() - start;
-            Some(Duration::from_secs(duration))
-        } else {
-            None
-        }
+        self.start_time.take().map(|start| start.elapsed())
     }
 
     pub fn elapsed(&self) -> Option<Duration> {
-        if let Some(start) = self.start_time {
-            let duration = current_timestamp() - start;
-            Some(Duration::from_secs(duration))
-        } else {
-            None
-        }
+        self.start_time.map(|start| start.elapsed())
     }
 
     pub fn is_running(&self) -> bool {
@@ -749,18 +1517,40 @@ impl TimeTracker {
     }
 }
 
-/// Format timestamp to date string (YYYY-MM-DD)
-fn format_date_from_timestamp(timestamp: u64) -> String {
-    let datetime = SystemTime::UNIX_EPOCH + Duration::from_secs(timestamp);
-    let datetime = chrono::DateTime::<chrono::Utc>::from(datetime);
-    datetime.format("%Y-%m-%d").to_string()
+/// Parse a report date: `YYYY-MM-DD`, `today`, `yesterday`, `Nd` or `Nw` (N days/weeks ago)
+fn parse_report_date(date: &str, timezone: Timezone, now: u64) -> Option<chrono::NaiveDate> {
+    let date = date.trim().to_ascii_lowercase();
+    let today = timezone.date_of(now);
+
+    let days_ago = match date.as_str() {
+        "today" => 0,
+        "yesterday" => 1,
+        relative if relative.ends_with('d') => relative[..relative.len() - 1].parse().ok()?,
+        relative if relative.ends_with('w') => {
+            relative[..relative.len() - 1].parse::<u64>().ok()? * 7
+        }
+        absolute => return chrono::NaiveDate::parse_from_str(absolute, "%Y-%m-%d").ok(),
+    };
+
+    today.checked_sub_days(chrono::Days::new(days_ago))
 }
 
-/// Parse date string to timestamp (simplified for now)
-fn parse_date_to_timestamp(_date: &str) -> u64 {
-    // For now, just return current timestamp
-    // In a real implementation, this would parse the date string
-    current_timestamp()
+/// Format timestamp to date string (YYYY-MM-DD) in the given timezone
+fn has_tag(tags: &[String], tag: &str) -> bool {
+    tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+}
+
+/// Quote a CSV field when it contains separators, quotes or newlines
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn format_date_from_timestamp(timestamp: u64, timezone: Timezone) -> String {
+    timezone.format_date(timestamp)
 }
 
 #[cfg(test)]
@@ -806,6 +1596,100 @@ mod tests {
         assert_eq!(stats.sessions_completed, 1);
     }
 
+    #[tokio::test]
+    async fn test_restore_sessions_after_restart() {
+        let tracker = TaskTracker::new();
+        let now = current_timestamp();
+        let session = |task_id: &str, is_paused: bool| TrackingSession {
+            task_id: task_id.to_string(),
+            started_at: now - 300,
+            last_activity: now - 100,
+            total_active_time: 200,
+            break_time: 0,
+            interruptions: 0,
+            is_paused,
+            segments: Vec::new(),
+            tags: vec!["deep work".to_string()],
+            notes: Vec::new(),
+        };
+        tracker.start_task(&"tracked".to_string()).await;
+
+        let sessions = vec![
+            session("running", false),
+            session("paused", true),
+            session("tracked", false),
+        ];
+        assert_eq!(tracker.restore_sessions(sessions, now - 60).await, 2);
+
+        // Active until the last save, then a break until the restart
+        let running = tracker
+            .get_active_session(&"running".to_string())
+            .await
+            .unwrap();
+        assert_eq!(running.total_active_time, 240);
+        assert!(running.break_time >= 60);
+        let kinds: Vec<_> = running
+            .segments
+            .iter()
+            .map(|s| s.segment_type.clone())
+            .collect();
+        assert_eq!(kinds, vec![SegmentType::Active, SegmentType::Break]);
+        assert_eq!(running.segments[1].started_at, now - 60);
+        assert!(running.last_activity >= now);
+        assert!(running.has_tag("deep work"));
+
+        let paused = tracker
+            .get_active_session(&"paused".to_string())
+            .await
+            .unwrap();
+        assert_eq!(paused.total_active_time, 200);
+        assert!(paused.segments.is_empty());
+
+        let tracked = tracker
+            .get_active_session(&"tracked".to_string())
+            .await
+            .unwrap();
+        assert_eq!(tracked.total_active_time, 0);
+    }
+
+    #[tokio::test]
+    async fn test_sessions_survive_clock_going_backwards() {
+        let tracker = TaskTracker::new();
+        let now = current_timestamp();
+
+        // Saved while the clock was an hour ahead, before NTP corrected it
+        let session = TrackingSession {
+            task_id: "skewed".to_string(),
+            started_at: now + 3000,
+            last_activity: now + 3600,
+            total_active_time: 600,
+            break_time: 0,
+            interruptions: 0,
+            is_paused: false,
+            segments: Vec::new(),
+            tags: Vec::new(),
+            notes: Vec::new(),
+        };
+        assert_eq!(tracker.restore_sessions(vec![session], now + 3600).await, 1);
+        assert!(tracker.pause_task(&"skewed".to_string()).await);
+        assert!(tracker.resume_task(&"skewed".to_string()).await);
+        // Longer than the clock has been running
+        tracker
+            .record_interruption(&"skewed".to_string(), now + 1)
+            .await;
+
+        let duration = tracker.stop_task(&"skewed".to_string()).await.unwrap();
+        assert_eq!(duration, Duration::from_secs(600));
+        let history = tracker.get_task_history(&"skewed".to_string()).await;
+        assert_eq!(history[0].total_duration, 0);
+
+        let mut timer = TimeTracker::new();
+        timer.start();
+        assert!(timer.elapsed().unwrap() < Duration::from_secs(60));
+        assert!(timer.stop().is_some());
+        assert!(!timer.is_running());
+    }
+
     #[tokio::test]
     async fn test_pause_resume_tracking() {
         let tracker = TaskTracker::new();
@@ -888,7 +1772,312 @@ mod tests {
     #[test]
     fn test_format_date_from_timestamp() {
         let timestamp = 1640995200; // 2022-01-01 00:00:00 UTC
-        let date = format_date_from_timestamp(timestamp);
+        let date = format_date_from_timestamp(timestamp, Timezone::Utc);
         assert_eq!(date, "2022-01-01");
+
+        // Still the previous evening in New York
+        let date = format_date_from_timestamp(timestamp, Timezone::Fixed(-5 * 3600));
+        assert_eq!(date, "2021-12-31");
+    }
+
+    #[tokio::test]
+    async fn test_report_range_uses_timezone() {
+        let tracker = TaskTracker::new().with_timezone(Timezone::Fixed(9 * 3600));
+        let report = tracker
+            .get_productivity_report("2022-01-02", "2022-01-02")
+            .await;
+
+        // 2022-01-02 00:00:00 +09:00 through 23:59:59 +09:00
+        assert_eq!(report.period_start, 1641049200);
+        assert_eq!(report.period_end, 1641049200 + 86_399);
+    }
+
+    fn completed_session(task_id: &str, started_at: u64, active_duration: u64) -> CompletedSession {
+        CompletedSession {
+            task_id: task_id.to_string(),
+            started_at,
+            ended_at: started_at + active_duration,
+            total_duration: active_duration,
+            active_duration,
+            break_duration: 0,
+            interruptions: 0,
+            segments: Vec::new(),
+            productivity_score: 100.0,
+            tags: Vec::new(),
+            notes: Vec::new(),
+            source: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_productivity_report_by_assignee() {
+        let tracker = TaskTracker::new();
+        tracker.set_task_assignee(&"a".to_string(), "alice").await;
+        tracker.set_task_assignee(&"b".to_string(), "bob").await;
+        {
+            let mut completed = tracker.completed_sessions.write().await;
+            completed.push(completed_session("a", 1641211200, 600));
+            completed.push(completed_session("a", 1641297600, 900));
+            completed.push(completed_session("b", 1641297600, 3600));
+            completed.push(completed_session("c", 1641297600, 300));
+        }
+
+        let report = tracker.generate_enhanced_productivity_report(None).await;
+        let breakdown: Vec<_> = report
+            .by_assignee
+            .iter()
+            .map(|stats| (stats.assignee.as_deref(), stats.sessions, stats.total_time))
+            .collect();
+        assert_eq!(
+            breakdown,
+            vec![
+                (Some("bob"), 1, 3600),
+                (Some("alice"), 2, 1500),
+                (None, 1, 300)
+            ]
+        );
+
+        let report = tracker
+            .generate_assignee_productivity_report(None, "alice")
+            .await;
+        assert_eq!(report.total_sessions, 2);
+        assert_eq!(report.total_focused_time, 1500);
+        assert_eq!(report.by_assignee.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_report_date() {
+        let now = 1641816000; // 2022-01-10 12:00:00 UTC
+        let utc = Timezone::Utc;
+        let date = |s| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok();
+
+        assert_eq!(
+            parse_report_date("2022-01-05", utc, now),
+            date("2022-01-05")
+        );
+        assert_eq!(parse_report_date("today", utc, now), date("2022-01-10"));
+        assert_eq!(parse_report_date("Yesterday", utc, now), date("2022-01-09"));
+        assert_eq!(parse_report_date("7d", utc, now), date("2022-01-03"));
+        assert_eq!(parse_report_date("2w", utc, now), date("2021-12-27"));
+        assert_eq!(parse_report_date("2022-13-01", utc, now), None);
+        assert_eq!(parse_report_date("soon", utc, now), None);
+
+        // Still 2022-01-10 in UTC, already 2022-01-11 in Auckland
+        let auckland = Timezone::Fixed(13 * 3600);
+        assert_eq!(
+            parse_report_date("today", auckland, now),
+            date("2022-01-11")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_report_filters_by_date_range() {
+        let tracker = TaskTracker::new().with_timezone(Timezone::Fixed(9 * 3600));
+        // 2022-01-01 23:30, 2022-01-02 00:30 and 2022-01-03 10:00 (+09:00)
+        for (task, started_at) in [("a", 1641047400), ("b", 1641051000), ("c", 1641171600)] {
+            tracker
+                .update_daily_summary(&completed_session(task, started_at, 600))
+                .await;
+        }
+
+        let report = tracker
+            .get_productivity_report("2022-01-02", "2022-01-02")
+            .await;
+        assert_eq!(report.total_sessions, 1);
+        assert_eq!(report.total_time, 600);
+
+        let report = tracker
+            .get_productivity_report("2022-01-01", "2022-01-02")
+            .await;
+        assert_eq!(report.total_sessions, 2);
+        let days: Vec<u64> = report.daily_breakdown.iter().map(|d| d.date).collect();
+        assert_eq!(days, vec![1641049200 - 86_400, 1641049200]);
+
+        let report = tracker
+            .get_productivity_report("2022-01-04", "2022-01-10")
+            .await;
+        assert_eq!(report.total_sessions, 0);
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_report_range_across_dst_change() {
+        let previous_tz = std::env::var("TZ").ok();
+        std::env::set_var("TZ", "America/New_York");
+
+        let tracker = TaskTracker::new().with_timezone(Timezone::Local);
+        // 2022-03-13 23:30 EDT is already 2022-03-14 in UTC
+        tracker
+            .update_daily_summary(&completed_session("late", 1647228600, 600))
+            .await;
+
+        let report = tracker
+            .get_productivity_report("2022-03-13", "2022-03-13")
+            .await;
+
+        match previous_tz {
+            Some(tz) => std::env::set_var("TZ", tz),
+            None => std::env::remove_var("TZ"),
+        }
+
+        // Clocks spring forward, so the day is only 23 hours long
+        assert_eq!(report.period_start, 1647147600);
+        assert_eq!(report.period_end, 1647147600 + 23 * 3600 - 1);
+        assert_eq!(report.total_sessions, 1);
+    }
+
+    #[tokio::test]
+    async fn test_weekly_and_monthly_rollups() {
+        let tracker = TaskTracker::new().with_timezone(Timezone::Utc);
+        tracker
+            .set_task_category(&"a".to_string(), "Development")
+            .await;
+        tracker.set_task_category(&"b".to_string(), "Testing").await;
+
+        // Fri 2021-12-31, Sat 2022-01-01 and Mon 2022-01-03 (UTC noon)
+        for (task, started_at, active) in [
+            ("a", 1640952000, 1200),
+            ("b", 1641038400, 600),
+            ("a", 1641211200, 300),
+        ] {
+            tracker
+                .update_period_summaries(&completed_session(task, started_at, active))
+                .await;
+        }
+
+        // 2021-12-31 and 2022-01-01 both fall in ISO week 2021-W52
+        let week = tracker.get_weekly_summary("2021-W52").await.unwrap();
+        assert_eq!(week.sessions, 2);
+        assert_eq!(week.focus_time, 1800);
+        assert_eq!(week.category_breakdown["Testing"], 600);
+        assert_eq!(week.period_end - week.period_start + 1, 7 * 86_400);
+
+        let january = tracker.get_monthly_summary("2022-01").await.unwrap();
+        assert_eq!(january.sessions, 2);
+        assert_eq!(january.top_tasks(1), vec![("b".to_string(), 600)]);
+
+        let weeks = tracker
+            .get_period_summaries(SummaryPeriod::Week, "2022-01-01", "2022-01-31")
+            .await;
+        let keys: Vec<&str> = weeks.iter().map(|w| w.period.as_str()).collect();
+        assert_eq!(keys, vec!["2021-W52", "2022-W01"]);
+    }
+
+    #[tokio::test]
+    async fn test_budget_burn_down_and_alerts() {
+        let tracker = TaskTracker::new()
+            .with_timezone(Timezone::Utc)
+            .with_budgets(vec![
+                TimeBudget::category("Development", 1.0),
+                TimeBudget::workspace("frontend", 10.0),
+            ]);
+        tracker
+            .set_task_category(&"a".to_string(), "Development")
+            .await;
+        tracker
+            .set_task_workspace(&"a".to_string(), "frontend")
+            .await;
+        tracker.set_task_category(&"b".to_string(), "Testing").await;
+
+        // Mon 2022-01-03 and Tue 2022-01-04 (ISO week 2022-W01), plus the previous week
+        {
+            let mut completed = tracker.completed_sessions.write().await;
+            completed.push(completed_session("a", 1641211200, 2700));
+            completed.push(completed_session("b", 1641297600, 3600));
+            completed.push(completed_session("a", 1640952000, 3600));
+        }
+
+        // Wed 2022-01-05 12:00 UTC
+        let statuses = tracker.budget_status_at(1641384000).await;
+        assert_eq!(statuses[0].period, "2022-W01");
+        assert_eq!(statuses[0].used_secs, 2700);
+        assert_eq!(statuses[0].remaining_secs, 900);
+        assert_eq!(statuses[0].state, BudgetState::Ok);
+        assert_eq!(statuses[1].used_secs, 2700);
+
+        // Crossing a threshold this week alerts once per state
+        tracker
+            .completed_sessions
+            .write()
+            .await
+            .push(completed_session("a", current_timestamp(), 3300));
+        let alerts = tracker.check_budget_alerts().await;
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].state, BudgetState::Warning);
+        assert!(tracker.check_budget_alerts().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_session_tags_notes_and_export() {
+        let tracker = TaskTracker::new().with_timezone(Timezone::Utc);
+        let task = "task-1".to_string();
+        {
+            let mut completed = tracker.completed_sessions.write().await;
+            completed.push(completed_session("task-1", 1641038400, 1800));
+            completed.push(completed_session("task-2", 1641042000, 600));
+        }
+
+        // Active session: tags and notes follow it into the completed session
+        tracker.start_task(&task).await;
+        assert!(
+            tracker
+                .tag_session(&task, None, &["pairing".to_string(), " ".to_string()])
+                .await
+        );
+        assert!(tracker.annotate_session(&task, None, "with Alex").await);
+        tracker.stop_task(&task).await;
+        let history = tracker.get_task_history(&task).await;
+        let live = history.last().unwrap();
+        assert_eq!(live.tags, vec!["pairing".to_string()]);
+        assert_eq!(live.notes[0].text, "with Alex");
+
+        // Completed sessions are selected by start time
+        let deep_work = vec!["Deep Work".to_string()];
+        assert!(
+            tracker
+                .tag_session(&task, Some(1641038400), &deep_work)
+                .await
+        );
+        assert!(
+            tracker
+                .tag_session(&task, Some(1641038400), &deep_work)
+                .await
+        );
+        assert!(!tracker.tag_session(&task, Some(1), &deep_work).await);
+        assert!(
+            !tracker
+                .annotate_session(&task, None, "no active session")
+                .await
+        );
+
+        let report = tracker
+            .generate_tagged_productivity_report(None, Some("deep work"))
+            .await;
+        assert_eq!(report.total_sessions, 1);
+        assert_eq!(report.total_focused_time, 1800);
+
+        let csv = tracker
+            .export_sessions(SessionExportFormat::Csv, Some("deep work"))
+            .await
+            .unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[1],
+            "task-1,2022-01-01T12:00:00+00:00,2022-01-01T12:30:00+00:00,1800,0,0,Deep Work,"
+        );
+
+        assert!(
+            tracker
+                .untag_session(&task, Some(1641038400), &["deep work".to_string()])
+                .await
+        );
+        let json = tracker
+            .export_sessions(SessionExportFormat::Json, None)
+            .await
+            .unwrap();
+        let exported: Vec<CompletedSession> = serde_json::from_str(&json).unwrap();
+        assert_eq!(exported.len(), 3);
+        assert!(exported.iter().all(|s| !s.has_tag("deep work")));
     }
 }
@@ -1,19 +1,31 @@
 // WezTerm Multi-Process Development Framework - Task Type Definitions
 // Defines core task types, states, and data structures
 
+use super::latency::StageTimestamps;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// Task unique identifier
 pub type TaskId = String;
 
+/// Agent recorded for attempts of unassigned tasks
+pub const DEFAULT_AGENT: &str = "system";
+
+/// Shell running task commands unless the task names another
+pub const DEFAULT_SHELL: &str = "sh";
+
 /// Task definition and metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
     /// Unique task identifier
     pub id: TaskId,
 
+    /// Human-friendly ID (e.g. `WP-123`), assigned when the task is stored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub short_id: Option<String>,
+
     /// Task title/name
     pub title: String,
 
@@ -59,6 +71,10 @@ pub struct Task {
     /// Task assignee (user/system)
     pub assignee: Option<String>,
 
+    /// Authenticated client that created the task
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+
     /// Task dependencies (must complete before this task)
     pub dependencies: Vec<TaskId>,
 
@@ -76,18 +92,20 @@ pub struct Task {
 
     /// Task execution history
     pub execution_history: Vec<TaskExecutionRecord>,
+
+    /// When the task passed each point of the pipeline
+    #[serde(default, skip_serializing_if = "StageTimestamps::is_empty")]
+    pub stages: StageTimestamps,
 }
 
 impl Task {
     /// Create a new task
     pub fn new(title: String, category: TaskCategory) -> Self {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        let now = crate::clock::unix_secs();
 
         Self {
             id: crate::task::generate_task_id(),
+            short_id: None,
             title,
             description: None,
             status: TaskStatus::Todo,
@@ -103,39 +121,55 @@ impl Task {
             actual_duration: None,
             tags: Vec::new(),
             assignee: None,
+            created_by: None,
             dependencies: Vec::new(),
             metadata: HashMap::new(),
             execution: TaskExecution::default(),
             progress: 0,
             notes: Vec::new(),
             execution_history: Vec::new(),
+            stages: StageTimestamps::default(),
         }
     }
 
     /// Update task status and timestamp
     pub fn update_status(&mut self, status: TaskStatus) {
-        self.updated_at = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        self.updated_at = crate::clock::unix_secs();
 
+        let now = self.updated_at;
         match &status {
             TaskStatus::InProgress => {
-                if self.started_at.is_none() {
-                    self.started_at = Some(self.updated_at);
+                self.stages.started_at.get_or_insert(now);
+            }
+            TaskStatus::Review => {
+                self.stages.finished_at.get_or_insert(now);
+            }
+            TaskStatus::Completed | TaskStatus::Cancelled | TaskStatus::Failed => {
+                if self.status == TaskStatus::Review {
+                    self.stages.reviewed_at.get_or_insert(now);
                 }
+                self.stages.finished_at.get_or_insert(now);
+            }
+            _ => {}
+        }
+
+        match &status {
+            TaskStatus::InProgress if self.started_at.is_none() => {
+                self.started_at = Some(self.updated_at);
             }
             TaskStatus::Completed => {
                 self.completed_at = Some(self.updated_at);
                 if let Some(started) = self.started_at {
-                    self.actual_duration = Some(self.updated_at - started);
+                    self.actual_duration =
+                        Some(crate::clock::elapsed_secs(started, self.updated_at));
                 }
                 self.progress = 100;
             }
             TaskStatus::Cancelled | TaskStatus::Failed => {
                 self.completed_at = Some(self.updated_at);
                 if let Some(started) = self.started_at {
-                    self.actual_duration = Some(self.updated_at - started);
+                    self.actual_duration =
+                        Some(crate::clock::elapsed_secs(started, self.updated_at));
                 }
             }
             _ => {}
@@ -150,25 +184,16 @@ impl Task {
             id: crate::task::generate_task_id(),
             content,
             author,
-            created_at: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
+            created_at: crate::clock::unix_secs(),
         };
         self.notes.push(note);
-        self.updated_at = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        self.updated_at = crate::clock::unix_secs();
     }
 
     /// Update task progress
     pub fn update_progress(&mut self, progress: u8) {
         self.progress = progress.min(100);
-        self.updated_at = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        self.updated_at = crate::clock::unix_secs();
 
         if progress >= 100 {
             self.update_status(TaskStatus::Completed);
@@ -178,10 +203,7 @@ impl Task {
     /// Check if task is overdue
     pub fn is_overdue(&self) -> bool {
         if let Some(due_date) = self.due_date {
-            let now = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_secs();
+            let now = crate::clock::unix_secs();
             now > due_date && !self.is_completed()
         } else {
             false
@@ -193,6 +215,69 @@ impl Task {
         matches!(self.status, TaskStatus::Completed)
     }
 
+    /// True for shell commands the executor runs itself instead of handing
+    /// them to a workspace process
+    pub fn runs_locally(&self) -> bool {
+        self.execution.mode == ExecutionMode::Automatic && self.execution.command.is_some()
+    }
+
+    /// Agent working on the task: its assignee, or `system` when unassigned
+    pub fn agent_id(&self) -> String {
+        self.assignee
+            .clone()
+            .unwrap_or_else(|| DEFAULT_AGENT.to_string())
+    }
+
+    /// Record the start of an execution attempt; returns the attempt number
+    pub fn begin_attempt(&mut self) -> u32 {
+        let attempt = self.execution_history.len() as u32 + 1;
+        self.execution_history.push(TaskExecutionRecord {
+            attempt,
+            agent: Some(self.agent_id()),
+            started_at: crate::clock::unix_secs(),
+            ended_at: None,
+            result: ExecutionResult::Running,
+            duration: None,
+            output: None,
+            error: None,
+        });
+        attempt
+    }
+
+    /// Close the running execution attempt, if any
+    pub fn end_attempt(&mut self, result: ExecutionResult, error: Option<String>) -> bool {
+        let now = crate::clock::unix_secs();
+        self.end_attempt_at(result, error, now)
+    }
+
+    /// Close the running execution attempt with an explicit end time
+    pub fn end_attempt_at(
+        &mut self,
+        result: ExecutionResult,
+        error: Option<String>,
+        ended_at: u64,
+    ) -> bool {
+        match self.execution_history.last_mut() {
+            Some(record) if record.result == ExecutionResult::Running => {
+                let ended_at = ended_at.max(record.started_at);
+                record.ended_at = Some(ended_at);
+                record.duration = Some(ended_at - record.started_at);
+                record.result = result;
+                record.error = error;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Check if task has reached a final state
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.status,
+            TaskStatus::Completed | TaskStatus::Cancelled | TaskStatus::Failed
+        )
+    }
+
     /// Check if task is in progress
     pub fn is_in_progress(&self) -> bool {
         matches!(self.status, TaskStatus::InProgress)
@@ -363,6 +448,10 @@ pub struct TaskExecution {
     /// Execution timeout in seconds
     pub timeout: Option<u64>,
 
+    /// Shell running `command` (e.g. `bash`); the default `sh` when unset
+    #[serde(default)]
+    pub shell: Option<String>,
+
     /// Retry configuration
     pub retry_config: RetryConfig,
 
@@ -380,6 +469,7 @@ impl Default for TaskExecution {
             working_directory: None,
             environment: HashMap::new(),
             timeout: None,
+            shell: None,
             retry_config: RetryConfig::default(),
             auto_execute: false,
             mode: ExecutionMode::Manual,
@@ -387,6 +477,36 @@ impl Default for TaskExecution {
     }
 }
 
+impl TaskExecution {
+    /// Shell running the command
+    pub fn shell(&self) -> &str {
+        self.shell.as_deref().unwrap_or(DEFAULT_SHELL)
+    }
+}
+
+/// Working directory of a task, which must stay inside the workspace root
+///
+/// Relative directories are taken from `root`; the result is canonical.
+pub fn resolve_working_dir(root: &Path, dir: &str) -> Result<PathBuf, String> {
+    let root = root
+        .canonicalize()
+        .map_err(|e| format!("Workspace root {} is not accessible: {e}", root.display()))?;
+    let resolved = root
+        .join(dir)
+        .canonicalize()
+        .map_err(|e| format!("Working directory {dir} is not accessible: {e}"))?;
+    if !resolved.starts_with(&root) {
+        return Err(format!(
+            "Working directory {dir} is outside the workspace root {}",
+            root.display()
+        ));
+    }
+    if !resolved.is_dir() {
+        return Err(format!("Working directory {dir} is not a directory"));
+    }
+    Ok(resolved)
+}
+
 /// Task retry configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RetryConfig {
@@ -431,7 +551,7 @@ pub enum ExecutionMode {
 }
 
 /// Task note/comment
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TaskNote {
     /// Note unique identifier
     pub id: String,
@@ -447,11 +567,15 @@ pub struct TaskNote {
 }
 
 /// Task execution record
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TaskExecutionRecord {
     /// Execution attempt number
     pub attempt: u32,
 
+    /// Agent that ran this attempt
+    #[serde(default)]
+    pub agent: Option<String>,
+
     /// Execution start time
     pub started_at: u64,
 
@@ -486,6 +610,12 @@ pub enum ExecutionResult {
     /// Execution was cancelled
     Cancelled,
 
+    /// Execution was stopped by pausing the task
+    Paused,
+
+    /// Execution was cut short by a daemon restart
+    Interrupted,
+
     /// Execution is still running
     Running,
 }
@@ -557,6 +687,32 @@ pub enum SortOrder {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_duration_with_clock_skew() {
+        let mut task = Task::new("Skewed".to_string(), TaskCategory::Development);
+        // Started while the clock was ahead
+        task.started_at = Some(crate::clock::unix_secs() + 3600);
+        task.update_status(TaskStatus::Completed);
+        assert_eq!(task.actual_duration, Some(0));
+    }
+
+    #[test]
+    fn test_working_dir_stays_inside_workspace_root() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir(root.path().join("api")).unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let canonical = root.path().canonicalize().unwrap();
+
+        assert_eq!(
+            resolve_working_dir(root.path(), "api").unwrap(),
+            canonical.join("api")
+        );
+        assert_eq!(resolve_working_dir(root.path(), ".").unwrap(), canonical);
+        assert!(resolve_working_dir(root.path(), "..").is_err());
+        assert!(resolve_working_dir(root.path(), "missing").is_err());
+        assert!(resolve_working_dir(root.path(), &outside.path().to_string_lossy()).is_err());
+    }
+
     #[test]
     fn test_task_creation() {
         let task = Task::new("Test Task".to_string(), TaskCategory::Development);
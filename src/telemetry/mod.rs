@@ -0,0 +1,385 @@
+// WezTerm Multi-Process Development Framework - Usage Telemetry
+// Opt-in, anonymous usage statistics sent to a configured endpoint
+//
+// Reports only ever contain counts, the framework version, the platform and
+// a random installation ID. Every payload is checked against an allow-list
+// before it leaves the process, so workspace names, task titles, commands or
+// paths can never be included.
+
+use crate::config::TelemetryConfig;
+use crate::logging::LogContext;
+use crate::{log_info, log_warn, Message};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Payload schema version
+pub const TELEMETRY_SCHEMA_VERSION: u32 = 1;
+
+/// Features whose usage is counted; counters for any other name are ignored
+pub const TRACKED_FEATURES: &[&str] = &[
+    "workspace_create",
+    "workspace_manage",
+    "process_spawn",
+    "task_queue",
+    "task_control",
+    "template_list",
+    "template_create",
+    "maintenance",
+    "upgrade",
+];
+
+/// Keys allowed to carry string values in a report
+const STRING_FIELDS: &[&str] = &["installation_id", "version", "os", "arch"];
+
+/// Telemetry feature name counted for an IPC message
+pub fn usage_feature(message: &Message) -> Option<&'static str> {
+    match message {
+        Message::WorkspaceCreate { .. } => Some("workspace_create"),
+        Message::WorkspaceDelete { .. }
+        | Message::WorkspaceRename { .. }
+        | Message::WorkspaceSwitch { .. } => Some("workspace_manage"),
+        Message::ProcessSpawn { .. } => Some("process_spawn"),
+        Message::TaskQueue { .. } => Some("task_queue"),
+        Message::TaskCancel { .. } | Message::TaskPause { .. } | Message::TaskResume { .. } => {
+            Some("task_control")
+        }
+        Message::TemplateList => Some("template_list"),
+        Message::TemplateCreate { .. } => Some("template_create"),
+        Message::MaintenanceSet { .. } => Some("maintenance"),
+        Message::FocusSet { .. } => Some("focus"),
+        Message::NoteAdd { .. } => Some("note"),
+        Message::Upgrade { .. } => Some("upgrade"),
+        _ => None,
+    }
+}
+
+/// Feature usage counters shared across the process
+#[derive(Debug)]
+pub struct UsageCounters {
+    counters: Vec<AtomicU64>,
+}
+
+impl Default for UsageCounters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UsageCounters {
+    pub fn new() -> Self {
+        Self {
+            counters: TRACKED_FEATURES.iter().map(|_| AtomicU64::new(0)).collect(),
+        }
+    }
+
+    /// Count one use of a tracked feature
+    pub fn record(&self, feature: &str) {
+        if let Some(index) = TRACKED_FEATURES.iter().position(|f| *f == feature) {
+            self.counters[index].fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Current counts keyed by feature name
+    pub fn snapshot(&self) -> BTreeMap<String, u64> {
+        TRACKED_FEATURES
+            .iter()
+            .zip(&self.counters)
+            .map(|(name, counter)| (name.to_string(), counter.load(Ordering::Relaxed)))
+            .collect()
+    }
+}
+
+/// Aggregate counts gathered from the running framework
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageTotals {
+    pub workspaces: usize,
+    pub tasks: usize,
+    pub templates: usize,
+}
+
+/// Anonymous usage report
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TelemetryReport {
+    pub schema_version: u32,
+    pub installation_id: String,
+    pub version: String,
+    pub os: String,
+    pub arch: String,
+    pub totals: UsageTotals,
+    pub features: BTreeMap<String, u64>,
+}
+
+impl TelemetryReport {
+    /// Build a report from counts only
+    pub fn new(installation_id: String, totals: UsageTotals, counters: &UsageCounters) -> Self {
+        Self {
+            schema_version: TELEMETRY_SCHEMA_VERSION,
+            installation_id,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            totals,
+            features: counters.snapshot(),
+        }
+    }
+
+    /// Serialize the report, refusing anything outside the allow-list
+    pub fn to_payload(&self) -> Result<serde_json::Value, String> {
+        let payload = serde_json::to_value(self).map_err(|e| e.to_string())?;
+        ensure_redacted(&payload)?;
+        Ok(payload)
+    }
+}
+
+/// Check that a payload only contains counts and allow-listed strings
+pub fn ensure_redacted(payload: &serde_json::Value) -> Result<(), String> {
+    let object = payload
+        .as_object()
+        .ok_or("Telemetry payload must be an object")?;
+
+    for (key, value) in object {
+        match value {
+            serde_json::Value::Number(_) => {}
+            serde_json::Value::String(s) if STRING_FIELDS.contains(&key.as_str()) => {
+                if key == "installation_id" && uuid::Uuid::parse_str(s).is_err() {
+                    return Err("installation_id must be a random UUID".to_string());
+                }
+            }
+            serde_json::Value::Object(fields) if key == "totals" || key == "features" => {
+                for (field, count) in fields {
+                    if key == "features" && !TRACKED_FEATURES.contains(&field.as_str()) {
+                        return Err(format!("Untracked feature in payload: {field}"));
+                    }
+                    if !count.is_number() {
+                        return Err(format!("Non-numeric value for {key}.{field}"));
+                    }
+                }
+            }
+            _ => return Err(format!("Field not allowed in telemetry payload: {key}")),
+        }
+    }
+
+    Ok(())
+}
+
+/// Load or create the random installation ID
+///
+/// The ID is not derived from anything on the machine and can be reset by
+/// deleting the file.
+pub fn installation_id() -> String {
+    let path = installation_id_path();
+
+    if let Ok(existing) = std::fs::read_to_string(&path) {
+        let existing = existing.trim();
+        if uuid::Uuid::parse_str(existing).is_ok() {
+            return existing.to_string();
+        }
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Err(e) = std::fs::write(&path, &id) {
+        log::warn!("Failed to store telemetry installation id: {e}");
+    }
+    id
+}
+
+fn installation_id_path() -> PathBuf {
+    let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push("wezterm-parallel");
+    path.push("telemetry-id");
+    path
+}
+
+/// Telemetry state shared by the IPC server and the reporter
+pub struct Telemetry {
+    config: TelemetryConfig,
+    installation_id: String,
+    counters: UsageCounters,
+}
+
+impl Telemetry {
+    /// Create telemetry state; the installation ID is only persisted once opted in
+    pub fn new(config: TelemetryConfig) -> Self {
+        let installation_id = if config.enabled {
+            installation_id()
+        } else {
+            uuid::Uuid::new_v4().to_string()
+        };
+
+        Self {
+            config,
+            installation_id,
+            counters: UsageCounters::new(),
+        }
+    }
+
+    /// Telemetry configuration
+    pub fn config(&self) -> &TelemetryConfig {
+        &self.config
+    }
+
+    /// Count one use of a feature; no-op unless telemetry is enabled
+    pub fn record(&self, feature: &str) {
+        if self.config.enabled {
+            self.counters.record(feature);
+        }
+    }
+
+    /// Build the report that would be sent for the given totals
+    pub fn report(&self, totals: UsageTotals) -> TelemetryReport {
+        TelemetryReport::new(self.installation_id.clone(), totals, &self.counters)
+    }
+}
+
+/// Sends reports to the configured endpoint
+pub struct TelemetryReporter {
+    config: TelemetryConfig,
+    client: reqwest::Client,
+}
+
+impl TelemetryReporter {
+    pub fn new(config: TelemetryConfig) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Whether reports will be sent
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled && self.config.endpoint.is_some()
+    }
+
+    /// Send one report
+    pub async fn send(&self, report: &TelemetryReport) -> Result<(), String> {
+        let endpoint = match (&self.config.endpoint, self.config.enabled) {
+            (Some(endpoint), true) => endpoint,
+            _ => return Err("Telemetry is disabled".to_string()),
+        };
+        let payload = report.to_payload()?;
+
+        let response = self
+            .client
+            .post(endpoint)
+            .timeout(Duration::from_secs(10))
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("Telemetry endpoint returned {}", response.status()))
+        }
+    }
+
+    /// Periodically send reports built by `collect`
+    pub fn spawn<F, Fut>(self, collect: F) -> Option<tokio::task::JoinHandle<()>>
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = TelemetryReport> + Send,
+    {
+        if !self.is_enabled() {
+            return None;
+        }
+
+        let interval = Duration::from_secs(self.config.interval_hours.max(1) * 3600);
+        let context = LogContext::new("telemetry", "reporter_start").with_metadata(
+            "interval_hours",
+            serde_json::json!(self.config.interval_hours),
+        );
+        log_info!(context, "Anonymous usage telemetry enabled");
+
+        Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            // Skip the immediate first tick so nothing is sent right at startup
+            ticker.tick().await;
+
+            loop {
+                ticker.tick().await;
+                let report = collect().await;
+                if let Err(e) = self.send(&report).await {
+                    let context = LogContext::new("telemetry", "report_failed");
+                    log_warn!(context, "Failed to send telemetry report: {}", e);
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_report() -> TelemetryReport {
+        let counters = UsageCounters::new();
+        counters.record("task_queue");
+        counters.record("task_queue");
+        counters.record("workspace_create");
+        counters.record("my-secret-workspace");
+
+        TelemetryReport::new(
+            uuid::Uuid::new_v4().to_string(),
+            UsageTotals {
+                workspaces: 2,
+                tasks: 5,
+                templates: 3,
+            },
+            &counters,
+        )
+    }
+
+    #[test]
+    fn test_counters_ignore_untracked_features() {
+        let report = sample_report();
+        assert_eq!(report.features["task_queue"], 2);
+        assert_eq!(report.features["workspace_create"], 1);
+        assert!(!report.features.contains_key("my-secret-workspace"));
+    }
+
+    #[test]
+    fn test_payload_passes_redaction() {
+        let payload = sample_report().to_payload().unwrap();
+        assert_eq!(payload["totals"]["tasks"], 5);
+        assert_eq!(payload["schema_version"], TELEMETRY_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_redaction_rejects_free_text() {
+        let mut payload = sample_report().to_payload().unwrap();
+        payload["workspace"] = serde_json::json!("my-project");
+        assert!(ensure_redacted(&payload).is_err());
+
+        let mut payload = sample_report().to_payload().unwrap();
+        payload["features"]["/home/user/project"] = serde_json::json!(1);
+        assert!(ensure_redacted(&payload).is_err());
+
+        let mut payload = sample_report().to_payload().unwrap();
+        payload["installation_id"] = serde_json::json!("user@example.com");
+        assert!(ensure_redacted(&payload).is_err());
+    }
+
+    #[test]
+    fn test_disabled_telemetry_collects_nothing() {
+        let telemetry = Telemetry::new(TelemetryConfig::default());
+        telemetry.record("task_queue");
+
+        let report = telemetry.report(UsageTotals::default());
+        assert!(report.features.values().all(|count| *count == 0));
+    }
+
+    #[tokio::test]
+    async fn test_disabled_reporter_sends_nothing() {
+        let reporter = TelemetryReporter::new(TelemetryConfig::default());
+        assert!(!reporter.is_enabled());
+        assert!(reporter.send(&sample_report()).await.is_err());
+        assert!(reporter.spawn(|| async { sample_report() }).is_none());
+    }
+}
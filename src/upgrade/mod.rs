@@ -0,0 +1,526 @@
+// WezTerm Multi-Process Development Framework - In-Place Upgrade
+// Stages a new binary, hands the running instance's state over to it and
+// rolls back when the successor does not come up healthy
+//
+// Listening sockets are taken over by path: the successor binds the IPC
+// socket path (replacing the old socket file) and retries the dashboard port
+// until the previous instance has released it.
+
+use crate::client::IpcClient;
+use crate::Message;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Environment variable pointing a successor process at its handoff file
+pub const HANDOFF_ENV: &str = "WEZTERM_PARALLEL_HANDOFF";
+
+/// Binary name reported by `--version`
+const BINARY_NAME: &str = "wezterm-parallel";
+
+/// Progress of a handoff between two instances
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HandoffState {
+    /// Successor spawned, not ready yet
+    Pending,
+    /// Successor is serving requests
+    Ready,
+    /// Successor did not come up; previous instance kept running
+    Failed,
+    /// Successor was unhealthy and the previous binary was restored
+    RolledBack,
+}
+
+/// Handoff record shared between the outgoing and incoming instance
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpgradeHandoff {
+    /// Handoff progress
+    pub state: HandoffState,
+
+    /// Version of the outgoing instance
+    pub previous_version: String,
+
+    /// Version reported by the new binary
+    pub target_version: String,
+
+    /// Path the binary is installed at
+    pub binary: PathBuf,
+
+    /// Backup of the previous binary used for rollback
+    pub previous_binary: Option<PathBuf>,
+
+    /// PID of the outgoing instance
+    pub previous_pid: u32,
+
+    /// PID of the successor once spawned
+    pub successor_pid: Option<u32>,
+
+    /// Socket the successor serves on until it holds the instance lock and
+    /// moves it into place
+    #[serde(default)]
+    pub successor_socket: Option<PathBuf>,
+
+    /// Maintenance reason active during the upgrade
+    pub maintenance_reason: Option<String>,
+
+    /// Time the upgrade was requested
+    pub requested_at: u64,
+
+    /// Time of the last state change
+    pub updated_at: u64,
+
+    /// Failure description, if any
+    pub error: Option<String>,
+}
+
+impl UpgradeHandoff {
+    /// Default handoff file location
+    pub fn default_path() -> PathBuf {
+        let mut path = dirs::config_dir().unwrap_or_else(|| {
+            log::warn!("設定ディレクトリが取得できません。カレントディレクトリを使用します。");
+            PathBuf::from(".")
+        });
+        path.push("wezterm-parallel");
+        path.push("upgrade-handoff.json");
+        path
+    }
+
+    /// Handoff file passed to this process by its predecessor, if any
+    pub fn path_from_env() -> Option<PathBuf> {
+        std::env::var_os(HANDOFF_ENV).map(PathBuf::from)
+    }
+
+    /// Load a handoff record
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let json = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&json).map_err(|e| e.to_string())
+    }
+
+    /// Write the record atomically
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        let temp_path = path.with_extension("json.tmp");
+        fs::write(&temp_path, json).map_err(|e| e.to_string())?;
+        fs::rename(&temp_path, path).map_err(|e| e.to_string())
+    }
+
+    /// Update the state and persist the record
+    pub fn transition(
+        &mut self,
+        path: &Path,
+        state: HandoffState,
+        error: Option<String>,
+    ) -> Result<(), String> {
+        self.state = state;
+        self.error = error;
+        self.updated_at = now_secs();
+        self.save(path)
+    }
+}
+
+/// Copy or download a binary into `staged_path` and make it executable
+///
+/// Downloads must use https and come with the expected SHA-256 of the
+/// binary; a local copy is checked when `sha256` is given. Nothing is left
+/// at `staged_path` when the check fails.
+pub async fn stage_binary(
+    source: &str,
+    staged_path: &Path,
+    sha256: Option<&str>,
+) -> Result<(), String> {
+    if source.starts_with("http://") {
+        return Err(format!("Refusing to download {source} over plain http"));
+    }
+    let download = source.starts_with("https://");
+    if download && sha256.is_none() {
+        return Err("Downloaded binaries need their expected SHA-256 (--sha256)".to_string());
+    }
+
+    if let Some(parent) = staged_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    if download {
+        let response = reqwest::get(source)
+            .await
+            .map_err(|e| format!("Download failed: {e}"))?;
+        if !response.status().is_success() {
+            return Err(format!("Download failed: HTTP {}", response.status()));
+        }
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| format!("Download failed: {e}"))?;
+        fs::write(staged_path, &bytes).map_err(|e| e.to_string())?;
+    } else {
+        fs::copy(source, staged_path).map_err(|e| format!("Failed to copy {source}: {e}"))?;
+    }
+
+    if let Some(expected) = sha256 {
+        if let Err(e) = verify_sha256(staged_path, expected) {
+            let _ = fs::remove_file(staged_path);
+            return Err(e);
+        }
+    }
+    set_executable(staged_path)
+}
+
+/// Hex SHA-256 of a file
+pub fn sha256_file(path: &Path) -> Result<String, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    Ok(Sha256::digest(&bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+/// Fail unless the file at `path` has the hex SHA-256 `expected`
+pub fn verify_sha256(path: &Path, expected: &str) -> Result<(), String> {
+    let actual = sha256_file(path)?;
+    if actual.eq_ignore_ascii_case(expected.trim()) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Checksum mismatch for {}: expected {}, got {actual}",
+            path.display(),
+            expected.trim()
+        ))
+    }
+}
+
+/// Run `--version` on a binary and return the reported version
+pub fn probe_version(binary: &Path) -> Result<String, String> {
+    let output = Command::new(binary)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to run {}: {e}", binary.display()))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "{} --version exited with {}",
+            binary.display(),
+            output.status
+        ));
+    }
+
+    parse_version_output(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| format!("{} is not a {BINARY_NAME} binary", binary.display()))
+}
+
+/// Extract the version from `wezterm-parallel <version>` output
+fn parse_version_output(output: &str) -> Option<String> {
+    let mut parts = output.split_whitespace();
+    if parts.next()? != BINARY_NAME {
+        return None;
+    }
+    parts.next().map(|v| v.to_string())
+}
+
+/// Move the staged binary into place, keeping the current one as `<target>.prev`
+pub fn install_binary(staged_path: &Path, target: &Path) -> Result<PathBuf, String> {
+    let backup = backup_path(target);
+    fs::copy(target, &backup).map_err(|e| format!("Failed to back up current binary: {e}"))?;
+    fs::rename(staged_path, target).map_err(|e| format!("Failed to install new binary: {e}"))?;
+    Ok(backup)
+}
+
+/// Put the backed up binary back in place
+pub fn restore_binary(backup: &Path, target: &Path) -> Result<(), String> {
+    fs::rename(backup, target).map_err(|e| format!("Failed to restore previous binary: {e}"))
+}
+
+/// Backup location for the binary at `target`
+pub fn backup_path(target: &Path) -> PathBuf {
+    let mut name = target.file_name().unwrap_or_default().to_os_string();
+    name.push(".prev");
+    target.with_file_name(name)
+}
+
+/// Start a detached instance of `binary` that picks up the given handoff file
+pub fn spawn_successor(binary: &Path, handoff_path: &Path) -> Result<u32, String> {
+    let child = Command::new(binary)
+        .env(HANDOFF_ENV, handoff_path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {e}", binary.display()))?;
+    Ok(child.id())
+}
+
+/// Install the staged binary over `target`, record the handoff and start the successor
+///
+/// Called by the outgoing instance once it has drained. The staged binary is
+/// checked against `sha256`, when given, before it is run at all.
+pub fn begin_handoff(
+    staged_path: &Path,
+    target: &Path,
+    handoff_path: &Path,
+    maintenance_reason: Option<String>,
+    sha256: Option<&str>,
+) -> Result<(UpgradeHandoff, u32), String> {
+    if let Some(expected) = sha256 {
+        verify_sha256(staged_path, expected)?;
+    }
+    let target_version = probe_version(staged_path)?;
+    let backup = install_binary(staged_path, target)?;
+
+    let now = now_secs();
+    let handoff = UpgradeHandoff {
+        state: HandoffState::Pending,
+        previous_version: env!("CARGO_PKG_VERSION").to_string(),
+        target_version,
+        binary: target.to_path_buf(),
+        previous_binary: Some(backup.clone()),
+        previous_pid: std::process::id(),
+        successor_pid: None,
+        successor_socket: None,
+        maintenance_reason,
+        requested_at: now,
+        updated_at: now,
+        error: None,
+    };
+
+    let result = handoff
+        .save(handoff_path)
+        .and_then(|_| spawn_successor(target, handoff_path));
+
+    match result {
+        Ok(pid) => Ok((handoff, pid)),
+        Err(e) => {
+            // Leave the old binary in place if the successor never started
+            if let Err(restore_error) = restore_binary(&backup, target) {
+                log::error!("{restore_error}");
+            }
+            Err(e)
+        }
+    }
+}
+
+/// Wait until the handoff leaves the pending state or the timeout expires
+pub async fn wait_for_handoff(path: &Path, timeout: Duration) -> Option<UpgradeHandoff> {
+    let deadline = tokio::time::Instant::now() + timeout;
+
+    loop {
+        if let Ok(handoff) = UpgradeHandoff::load(path) {
+            if handoff.state != HandoffState::Pending {
+                return Some(handoff);
+            }
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return None;
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+}
+
+/// Wait until the instance listening on `socket` answers a ping
+pub async fn probe_health(socket: &Path, timeout: Duration) -> Result<(), String> {
+    let client = IpcClient::new(socket).with_timeout(Duration::from_secs(2));
+    let deadline = tokio::time::Instant::now() + timeout;
+
+    loop {
+        let error = match client.call(Message::Ping).await {
+            Ok(Message::Pong) => return Ok(()),
+            Ok(other) => format!("unexpected reply {other:?}"),
+            Err(e) => e.message,
+        };
+        if tokio::time::Instant::now() >= deadline {
+            return Err(format!("Health check failed: {error}"));
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+}
+
+/// Ask a process to terminate (SIGTERM)
+#[cfg(unix)]
+pub fn terminate(pid: u32) -> Result<(), String> {
+    let pid = libc::pid_t::try_from(pid).map_err(|_| format!("Invalid PID {pid}"))?;
+    // SAFETY: kill has no memory safety requirements
+    if unsafe { libc::kill(pid, libc::SIGTERM) } == 0 {
+        Ok(())
+    } else {
+        Err(format!(
+            "Failed to terminate {pid}: {}",
+            std::io::Error::last_os_error()
+        ))
+    }
+}
+
+#[cfg(not(unix))]
+pub fn terminate(pid: u32) -> Result<(), String> {
+    Err(format!("Terminating {pid} is only supported on Unix"))
+}
+
+/// Whether a process with this PID exists
+#[cfg(unix)]
+pub fn is_running(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: signal 0 only checks that the process exists
+    let result = unsafe { libc::kill(pid, 0) };
+    result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+pub fn is_running(_pid: u32) -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755)).map_err(|e| e.to_string())
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path) -> Result<(), String> {
+    Ok(())
+}
+
+/// Current time in seconds
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn sample_handoff() -> UpgradeHandoff {
+        UpgradeHandoff {
+            state: HandoffState::Pending,
+            previous_version: "0.3.0".to_string(),
+            target_version: "0.3.1".to_string(),
+            binary: PathBuf::from("/usr/local/bin/wezterm-parallel"),
+            previous_binary: None,
+            previous_pid: 1,
+            successor_pid: None,
+            successor_socket: None,
+            maintenance_reason: None,
+            requested_at: now_secs(),
+            updated_at: now_secs(),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_version_output() {
+        assert_eq!(
+            parse_version_output("wezterm-parallel 0.3.1\n"),
+            Some("0.3.1".to_string())
+        );
+        assert_eq!(parse_version_output("other-tool 1.0"), None);
+        assert_eq!(parse_version_output(""), None);
+    }
+
+    #[test]
+    fn test_handoff_roundtrip_and_transition() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("handoff.json");
+
+        let mut handoff = sample_handoff();
+        handoff.save(&path).unwrap();
+        assert_eq!(UpgradeHandoff::load(&path).unwrap(), handoff);
+
+        handoff
+            .transition(&path, HandoffState::Failed, Some("boom".to_string()))
+            .unwrap();
+        let loaded = UpgradeHandoff::load(&path).unwrap();
+        assert_eq!(loaded.state, HandoffState::Failed);
+        assert_eq!(loaded.error.as_deref(), Some("boom"));
+    }
+
+    #[test]
+    fn test_install_and_restore_binary() {
+        let dir = TempDir::new().unwrap();
+        let target = dir.path().join("wezterm-parallel");
+        let staged = dir.path().join("wezterm-parallel.new");
+        fs::write(&target, "old").unwrap();
+        fs::write(&staged, "new").unwrap();
+
+        let backup = install_binary(&staged, &target).unwrap();
+        assert_eq!(backup, dir.path().join("wezterm-parallel.prev"));
+        assert_eq!(fs::read_to_string(&target).unwrap(), "new");
+
+        restore_binary(&backup, &target).unwrap();
+        assert_eq!(fs::read_to_string(&target).unwrap(), "old");
+        assert!(!backup.exists());
+    }
+
+    #[tokio::test]
+    async fn test_stage_binary_from_path() {
+        let dir = TempDir::new().unwrap();
+        let source = dir.path().join("source");
+        let staged = dir.path().join("staging").join("wezterm-parallel.new");
+        fs::write(&source, "binary").unwrap();
+
+        stage_binary(source.to_str().unwrap(), &staged, None)
+            .await
+            .unwrap();
+        assert_eq!(fs::read_to_string(&staged).unwrap(), "binary");
+    }
+
+    #[tokio::test]
+    async fn test_stage_binary_checks_transport_and_checksum() {
+        let dir = TempDir::new().unwrap();
+        let staged = dir.path().join("wezterm-parallel.new");
+        let abc = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+        // Rejected before anything is fetched
+        let error = stage_binary("http://example.com/wp", &staged, Some(abc))
+            .await
+            .unwrap_err();
+        assert!(error.contains("plain http"), "{error}");
+        let error = stage_binary("https://example.com/wp", &staged, None)
+            .await
+            .unwrap_err();
+        assert!(error.contains("SHA-256"), "{error}");
+
+        let source = dir.path().join("source");
+        fs::write(&source, "abc").unwrap();
+        let source = source.to_str().unwrap();
+        let error = stage_binary(source, &staged, Some(&"0".repeat(64)))
+            .await
+            .unwrap_err();
+        assert!(error.contains("Checksum mismatch"), "{error}");
+        assert!(!staged.exists());
+
+        stage_binary(source, &staged, Some(&abc.to_uppercase()))
+            .await
+            .unwrap();
+        assert_eq!(sha256_file(&staged).unwrap(), abc);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_handoff_times_out_while_pending() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("handoff.json");
+        sample_handoff().save(&path).unwrap();
+
+        assert!(wait_for_handoff(&path, Duration::from_millis(300))
+            .await
+            .is_none());
+
+        let mut handoff = sample_handoff();
+        handoff
+            .transition(&path, HandoffState::Ready, None)
+            .unwrap();
+        let finished = wait_for_handoff(&path, Duration::from_millis(300))
+            .await
+            .unwrap();
+        assert_eq!(finished.state, HandoffState::Ready);
+    }
+}
@@ -0,0 +1,240 @@
+// WezTerm Multi-Process Development Framework - Daemon IPC Integration Test
+// Starts the framework binary in a private home and drives it over its socket
+
+#![cfg(target_os = "linux")]
+
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+use wezterm_parallel::client::IpcClient;
+use wezterm_parallel::ipc::transport::SOCKET_NAME;
+use wezterm_parallel::Message;
+
+/// Running framework instance, stopped with SIGTERM when dropped
+struct Daemon {
+    child: Child,
+    home: TempDir,
+}
+
+impl Daemon {
+    fn start() -> Self {
+        let home = TempDir::new().unwrap();
+        let run_dir = home.path().join("run");
+        std::fs::create_dir(&run_dir).unwrap();
+        let child = Command::new(env!("CARGO_BIN_EXE_wezterm-parallel"))
+            .current_dir(home.path())
+            .env("HOME", home.path())
+            .env("XDG_RUNTIME_DIR", &run_dir)
+            .env("XDG_CONFIG_HOME", home.path().join(".config"))
+            .env("XDG_DATA_HOME", home.path().join(".local/share"))
+            .env_remove("XDG_CACHE_HOME")
+            .env_remove("WEZTERM_MULTI_DEV_SOCKET_PATH")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+        Self { child, home }
+    }
+
+    fn socket_path(&self) -> PathBuf {
+        self.home.path().join("run").join(SOCKET_NAME)
+    }
+
+    async fn client(&mut self) -> IpcClient {
+        let client = IpcClient::new(self.socket_path());
+        let deadline = Instant::now() + Duration::from_secs(60);
+        loop {
+            if client.call(Message::Ping).await.is_ok() {
+                return client;
+            }
+            if let Some(status) = self.child.try_wait().unwrap() {
+                panic!("daemon exited during startup: {status}");
+            }
+            assert!(Instant::now() < deadline, "daemon did not start");
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+
+    fn root(&self) -> &Path {
+        self.home.path()
+    }
+}
+
+impl Drop for Daemon {
+    fn drop(&mut self) {
+        unsafe {
+            libc::kill(self.child.id() as libc::pid_t, libc::SIGTERM);
+        }
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Instant::now() < deadline {
+            if let Ok(Some(_)) = self.child.try_wait() {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[tokio::test]
+async fn test_process_spawn_starts_a_managed_process() {
+    let mut daemon = Daemon::start();
+    let client = daemon.client().await;
+
+    client
+        .call(Message::WorkspaceCreate {
+            name: "dev".to_string(),
+            template: "basic".to_string(),
+            project_dir: Some(daemon.root().display().to_string()),
+            variables: Default::default(),
+        })
+        .await
+        .unwrap();
+
+    let process_id = match client
+        .call(Message::ProcessSpawn {
+            workspace: "dev".to_string(),
+            command: "sleep 30".to_string(),
+        })
+        .await
+        .unwrap()
+    {
+        Message::StatusUpdate { process_id, .. } => process_id,
+        other => panic!("unexpected reply {other:?}"),
+    };
+    assert!(process_id.starts_with("dev-sleep-"), "{process_id}");
+
+    // Known to the process manager, not only recorded in the workspace
+    match client
+        .call(Message::ProcessEnv {
+            process_id: process_id.clone(),
+        })
+        .await
+        .unwrap()
+    {
+        Message::ProcessEnvResponse { .. } => {}
+        other => panic!("unexpected reply {other:?}"),
+    }
+    match client
+        .call(Message::ProcessList {
+            workspace: Some("dev".to_string()),
+        })
+        .await
+        .unwrap()
+    {
+        Message::ProcessListResponse { processes } => {
+            let process = processes.iter().find(|p| p.id == process_id).unwrap();
+            assert_eq!(process.command, "sleep 30");
+        }
+        other => panic!("unexpected reply {other:?}"),
+    }
+
+    // Commands that cannot be started are reported, not acknowledged
+    let error = client
+        .call(Message::ProcessSpawn {
+            workspace: "dev".to_string(),
+            command: "/nonexistent/program".to_string(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(error.component, "process_manager");
+}
+
+#[tokio::test]
+async fn test_process_log_tail_follows_process_output() {
+    use std::os::unix::fs::PermissionsExt;
+    use wezterm_parallel::process::TailFilter;
+
+    let mut daemon = Daemon::start();
+    let client = daemon.client().await;
+
+    let script = daemon.root().join("emit.sh");
+    std::fs::write(
+        &script,
+        "#!/bin/sh\necho tail-marker\necho tail-failure >&2\nexec sleep 30\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    client
+        .call(Message::WorkspaceCreate {
+            name: "dev".to_string(),
+            template: "basic".to_string(),
+            project_dir: Some(daemon.root().display().to_string()),
+            variables: Default::default(),
+        })
+        .await
+        .unwrap();
+    let process_id = match client
+        .call(Message::ProcessSpawn {
+            workspace: "dev".to_string(),
+            command: script.display().to_string(),
+        })
+        .await
+        .unwrap()
+    {
+        Message::StatusUpdate { process_id, .. } => process_id,
+        other => panic!("unexpected reply {other:?}"),
+    };
+
+    // The output reaches the process's log file, which the tail follows
+    let mut cursor = None;
+    let mut lines = Vec::new();
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while lines.len() < 2 {
+        assert!(Instant::now() < deadline, "output not tailed: {lines:?}");
+        match client
+            .call(Message::ProcessLogTail {
+                process_id: process_id.clone(),
+                filter: TailFilter::default(),
+                cursor,
+                limit: None,
+            })
+            .await
+            .unwrap()
+        {
+            Message::ProcessLogTailResponse { chunk, .. } => {
+                lines.extend(chunk.lines);
+                cursor = Some(chunk.cursor);
+            }
+            other => panic!("unexpected reply {other:?}"),
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    // The two streams are read concurrently, so their lines may interleave either way
+    assert!(
+        lines
+            .iter()
+            .any(|line| line.ends_with("[STDOUT] tail-marker")),
+        "{lines:?}"
+    );
+    assert!(
+        lines
+            .iter()
+            .any(|line| line.ends_with("[STDERR] tail-failure")),
+        "{lines:?}"
+    );
+
+    // Stderr is logged as errors
+    match client
+        .call(Message::ProcessLogTail {
+            process_id,
+            filter: TailFilter {
+                level: Some("error".to_string()),
+                ..Default::default()
+            },
+            cursor: None,
+            limit: None,
+        })
+        .await
+        .unwrap()
+    {
+        Message::ProcessLogTailResponse { chunk, .. } => {
+            assert_eq!(chunk.lines.len(), 1, "{:?}", chunk.lines);
+            assert!(chunk.lines[0].ends_with("[STDERR] tail-failure"));
+        }
+        other => panic!("unexpected reply {other:?}"),
+    }
+}
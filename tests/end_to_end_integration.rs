@@ -46,6 +46,7 @@ async fn test_integrated_workspace_process_management() {
     let config = ProcessConfig {
         claude_code_binary: "echo".to_string(),
         max_processes: 10,
+        max_processes_per_workspace: None,
         health_check_interval_secs: 1,
         restart_delay_secs: 1,
         max_restart_attempts: 3,
@@ -53,6 +54,9 @@ async fn test_integrated_workspace_process_management() {
         default_restart_policy: wezterm_parallel::process::manager::RestartPolicy::OnFailure,
         environment_vars: std::collections::HashMap::new(),
         working_directory: None,
+        watchdog: Default::default(),
+        circuit_breaker: Default::default(),
+        secrets: Default::default(),
     };
 
     let (process_manager, _event_receiver) = ProcessManager::new(config);
@@ -95,6 +99,7 @@ async fn test_websocket_dashboard_integration() {
         auth_enabled: false,
         auth_token: None,
         compression: false,
+        language: Default::default(),
     };
 
     let (websocket_server, _metrics_tx) = WebSocketServer::new(config);
@@ -122,6 +127,7 @@ async fn test_metrics_update_flow() {
         auth_enabled: false,
         auth_token: None,
         compression: false,
+        language: Default::default(),
     };
 
     let (websocket_server, metrics_tx) = WebSocketServer::new(config);
@@ -137,6 +143,7 @@ async fn test_metrics_update_flow() {
         workspaces: Vec::new(),
         framework: Some(wezterm_parallel::metrics::FrameworkMetrics::new()),
         update_type: wezterm_parallel::dashboard::UpdateType::Full,
+        version: None,
     };
 
     let result = metrics_tx.send(update).await;
@@ -155,13 +162,15 @@ async fn test_message_serialization_compatibility() {
     let workspace_msg = Message::WorkspaceCreate {
         name: "test-workspace".to_string(),
         template: "basic".to_string(),
+        project_dir: None,
+        variables: Default::default(),
     };
 
     let serialized = serde_json::to_string(&workspace_msg).unwrap();
     let deserialized: Message = serde_json::from_str(&serialized).unwrap();
 
     match deserialized {
-        Message::WorkspaceCreate { name, template } => {
+        Message::WorkspaceCreate { name, template, .. } => {
             assert_eq!(name, "test-workspace");
             assert_eq!(template, "basic");
         }
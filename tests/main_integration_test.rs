@@ -65,6 +65,13 @@ async fn test_main_process_ipc_server() {
             metrics_enabled: true,
             cleanup_interval: 600,
             max_task_history: 1000,
+            timezone: Default::default(),
+            budgets: Vec::new(),
+            language: Default::default(),
+            artifacts_dir: None,
+            ids: Default::default(),
+            latency_budgets: Vec::new(),
+            preemption: Default::default(),
         };
         let task_manager = Arc::new(TaskManager::new(task_config));
         let template_engine = Arc::new(tokio::sync::Mutex::new(TemplateEngine::new()));
@@ -128,6 +135,13 @@ async fn test_main_process_message_handling() {
         metrics_enabled: true,
         cleanup_interval: 600,
         max_task_history: 1000,
+        timezone: Default::default(),
+        budgets: Vec::new(),
+        language: Default::default(),
+        artifacts_dir: None,
+        ids: Default::default(),
+        latency_budgets: Vec::new(),
+        preemption: Default::default(),
     };
     let task_manager = Arc::new(TaskManager::new(task_config));
     let template_engine = Arc::new(tokio::sync::Mutex::new(TemplateEngine::new()));
@@ -156,6 +170,8 @@ async fn test_main_process_message_handling() {
     let create_message = Message::WorkspaceCreate {
         name: "test-workspace".to_string(),
         template: "default".to_string(),
+        project_dir: None,
+        variables: Default::default(),
     };
     let response = handle_message_test(
         create_message,
@@ -182,6 +198,11 @@ async fn test_main_process_message_handling() {
         id: "test-task-1".to_string(),
         priority: 5,
         command: "test command".to_string(),
+        working_dir: None,
+        env: Default::default(),
+        shell: None,
+        timeout_secs: None,
+        assignee: None,
     };
     let response = handle_message_test(
         task_queue_message,
@@ -210,7 +231,7 @@ async fn handle_message_test(
             log_info!(ping_context, "Ping received, responding with Pong");
             Message::Pong
         }
-        Message::WorkspaceCreate { name, template } => {
+        Message::WorkspaceCreate { name, template, .. } => {
             let create_context = LogContext::new("ipc", "workspace_create_request")
                 .with_entity_id(&name)
                 .with_metadata("template", serde_json::json!(template));
@@ -253,6 +274,7 @@ async fn handle_message_test(
             id,
             priority,
             command,
+            ..
         } => {
             let queue_context = LogContext::new("ipc", "task_queue_request")
                 .with_entity_id(&id)
@@ -370,6 +392,7 @@ async fn test_main_process_performance_initialization() {
         gc_interval_secs: 300,
         cpu_limit_percent: 80.0,
         memory_limit_mb: 512,
+        cache: Default::default(),
     };
 
     // Test startup optimizer
@@ -381,10 +404,8 @@ async fn test_main_process_performance_initialization() {
     assert!(result.is_ok());
 
     // Test performance manager
-    let perf_manager = Arc::new(std::sync::Mutex::new(PerformanceManager::new(
-        perf_config.clone(),
-    )));
-    assert!(!perf_manager.lock().unwrap().generate_report().is_empty());
+    let perf_manager = Arc::new(PerformanceManager::new(perf_config.clone()));
+    assert!(!perf_manager.generate_report(Default::default()).is_empty());
 
     // Test memory monitor
     let mut memory_monitor = MemoryMonitor::new(perf_config.memory_limit_mb);
@@ -425,6 +446,7 @@ async fn test_main_process_websocket_dashboard() {
         auth_enabled: false,
         auth_token: None,
         compression: true,
+        language: Default::default(),
     };
 
     let task_config = TaskConfig {
@@ -437,6 +459,13 @@ async fn test_main_process_websocket_dashboard() {
         metrics_enabled: true,
         cleanup_interval: 600,
         max_task_history: 1000,
+        timezone: Default::default(),
+        budgets: Vec::new(),
+        language: Default::default(),
+        artifacts_dir: None,
+        ids: Default::default(),
+        latency_budgets: Vec::new(),
+        preemption: Default::default(),
     };
 
     let task_manager = Arc::new(TaskManager::new(task_config));
@@ -80,6 +80,7 @@ async fn test_template_create_delete_messages() {
     let create_response = Message::TemplateCreateResponse {
         success: true,
         error: None,
+        warnings: Vec::new(),
     };
 
     let json = serde_json::to_string(&create_response).unwrap();
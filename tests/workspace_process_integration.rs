@@ -276,6 +276,7 @@ fn create_test_process_config() -> ProcessConfig {
     ProcessConfig {
         claude_code_binary: "echo".to_string(), // Use echo for testing instead of actual claude-code
         max_processes: 16,
+        max_processes_per_workspace: None,
         health_check_interval_secs: 1,
         restart_delay_secs: 1,
         max_restart_attempts: 3,
@@ -283,5 +284,8 @@ fn create_test_process_config() -> ProcessConfig {
         default_restart_policy: RestartPolicy::OnFailure,
         environment_vars: HashMap::new(),
         working_directory: None,
+        watchdog: Default::default(),
+        circuit_breaker: Default::default(),
+        secrets: Default::default(),
     }
 }